@@ -0,0 +1,41 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+pragma solidity ^0.7.0;
+
+pragma experimental ABIEncoderV2;
+
+import "./Governance.sol";
+
+/// @title Operator multicall contract
+/// @notice Allows the operator to perform several `commitBlocks`, `proveBlocks` and `executeBlocks`
+/// calls within a single transaction, saving the base transaction cost.
+/// @dev This contract must be registered as an active validator in the `Governance` contract.
+/// @author Matter Labs
+contract OperatorMulticall {
+    /// @notice zkSync contract (proxy) the calls are forwarded to
+    address public immutable zkSync;
+
+    /// @notice Governance contract that contains the list of the active validators
+    Governance public immutable governance;
+
+    constructor(address _zkSync, Governance _governance) {
+        zkSync = _zkSync;
+        governance = _governance;
+    }
+
+    /// @notice Forwards the calls to the zkSync contract one by one, reverting all of them if any call fails
+    /// @param _calls ABI-encoded calls of the zkSync contract
+    function aggregate(bytes[] calldata _calls) external {
+        governance.requireActiveValidator(msg.sender);
+
+        for (uint256 i = 0; i < _calls.length; ++i) {
+            (bool success, bytes memory returnData) = zkSync.call(_calls[i]);
+            if (!success) {
+                // Bubble up the revert reason of the failed call
+                assembly {
+                    revert(add(returnData, 32), mload(returnData))
+                }
+            }
+        }
+    }
+}
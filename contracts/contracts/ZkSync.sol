@@ -9,6 +9,7 @@ import "./SafeMath.sol";
 import "./SafeMathUInt128.sol";
 import "./SafeCast.sol";
 import "./Utils.sol";
+import "./IERC20Permit.sol";
 
 import "./Storage.sol";
 import "./Config.sol";
@@ -224,6 +225,41 @@ contract ZkSync is UpgradeableMaster, Storage, Config, Events, ReentrancyGuard {
         uint104 _amount,
         address _zkSyncAddress
     ) external nonReentrant {
+        transferAndRegisterDeposit(_token, _amount, _zkSyncAddress);
+    }
+
+    /// @notice Deposit ERC20 token supporting EIP-2612 to Layer 2 without a separate approve transaction
+    /// @dev Resulting priority operation is an ordinary deposit, so it's processed by the server the same way
+    /// @param _token Token address
+    /// @param _amount Token amount
+    /// @param _zkSyncAddress Receiver Layer 2 address
+    /// @param _deadline Permit signature deadline
+    /// @param _v Permit signature `v` component
+    /// @param _r Permit signature `r` component
+    /// @param _s Permit signature `s` component
+    function depositERC20WithPermit(
+        IERC20 _token,
+        uint104 _amount,
+        address _zkSyncAddress,
+        uint256 _deadline,
+        uint8 _v,
+        bytes32 _r,
+        bytes32 _s
+    ) external nonReentrant {
+        // Permit can be front-run by anyone who has seen the signature, in that case the allowance is already set
+        // and the failure is ignored. If the allowance is not enough, `transferFrom` below will revert.
+        try
+            IERC20Permit(address(_token)).permit(msg.sender, address(this), _amount, _deadline, _v, _r, _s)
+        {} catch {}
+        transferAndRegisterDeposit(_token, _amount, _zkSyncAddress);
+    }
+
+    /// @notice Transfer ERC20 tokens from user into contract, validate it, register deposit
+    function transferAndRegisterDeposit(
+        IERC20 _token,
+        uint104 _amount,
+        address _zkSyncAddress
+    ) internal {
         require(_zkSyncAddress != SPECIAL_ACCOUNT_ADDRESS, "P");
         requireActive();
 
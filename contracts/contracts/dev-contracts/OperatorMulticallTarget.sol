@@ -0,0 +1,25 @@
+// SPDX-License-Identifier: UNLICENSED
+
+pragma solidity ^0.7.0;
+
+/// @dev Stand-in for the zkSync contract recording the calls forwarded by the `OperatorMulticall`
+contract OperatorMulticallTarget {
+    /// @dev Values passed to the successful calls, in order
+    uint256[] public values;
+
+    /// @dev Senders of the successful calls, in order
+    address[] public senders;
+
+    function record(uint256 _value) external {
+        values.push(_value);
+        senders.push(msg.sender);
+    }
+
+    function fail() external pure {
+        revert("target failed");
+    }
+
+    function callsCount() external view returns (uint256) {
+        return values.length;
+    }
+}
@@ -43,7 +43,7 @@ async function main() {
 
     const erc20 = await deployContract(
         deployWallet,
-        readContractCode('dev-contracts/TestnetERC20Token'),
+        readContractCode('dev-contracts/TestnetERC20PermitToken'),
         ['Matter Labs Trial Token', 'MLTT', 18],
         { gasLimit: 5000000 }
     );
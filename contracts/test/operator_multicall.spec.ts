@@ -0,0 +1,59 @@
+import { expect, use } from 'chai';
+import { solidity } from 'ethereum-waffle';
+import { Contract, Signer } from 'ethers';
+
+import * as hardhat from 'hardhat';
+
+use(solidity);
+
+describe('OperatorMulticall unit tests', function () {
+    this.timeout(50000);
+
+    let multicall: Contract;
+    let target: Contract;
+    let validator: Signer;
+    let user: Signer;
+
+    beforeEach(async () => {
+        [validator, user] = await hardhat.ethers.getSigners();
+
+        const governanceFactory = await hardhat.ethers.getContractFactory('TestGovernance');
+        const governance = await governanceFactory.deploy();
+        await governance.initialize(
+            hardhat.ethers.utils.defaultAbiCoder.encode(['address'], [await validator.getAddress()])
+        );
+        await governance.setValidator(await validator.getAddress(), true);
+
+        const targetFactory = await hardhat.ethers.getContractFactory('OperatorMulticallTarget');
+        target = await targetFactory.deploy();
+
+        const multicallFactory = await hardhat.ethers.getContractFactory('OperatorMulticall');
+        multicall = await multicallFactory.deploy(target.address, governance.address);
+    });
+
+    function recordCall(value: number): string {
+        return target.interface.encodeFunctionData('record', [value]);
+    }
+
+    it('Forwards the calls in order', async () => {
+        await multicall.aggregate([recordCall(1), recordCall(2), recordCall(3)]);
+
+        expect(await target.callsCount()).to.eq(3);
+        for (let i = 0; i < 3; i++) {
+            expect(await target.values(i)).to.eq(i + 1);
+            // The zkSync contract sees the multicall contract as the validator.
+            expect(await target.senders(i)).to.eq(multicall.address);
+        }
+    });
+
+    it('Reverts all the calls with the reason of the failed one', async () => {
+        const failCall = target.interface.encodeFunctionData('fail');
+        await expect(multicall.aggregate([recordCall(1), failCall, recordCall(2)])).to.be.revertedWith('target failed');
+        expect(await target.callsCount()).to.eq(0);
+    });
+
+    it('Is only callable by the active validator', async () => {
+        await expect(multicall.connect(user).aggregate([recordCall(1)])).to.be.revertedWith('1h');
+        expect(await target.callsCount()).to.eq(0);
+    });
+});
@@ -170,6 +170,7 @@ describe('ZK priority queue ops unit tests', function () {
 
     let zksyncContract;
     let tokenContract;
+    let permitTokenContract;
     before(async () => {
         [wallet] = await hardhat.ethers.getSigners();
         const contracts = readProductionContracts();
@@ -182,11 +183,45 @@ describe('ZK priority queue ops unit tests', function () {
         tokenContract = await tokenContractFactory.deploy('Matter Labs Trial Token', 'MLTT', 18);
         await tokenContract.mint(wallet.address, parseEther('1000000'));
 
+        const permitTokenContractFactory = await hardhat.ethers.getContractFactory('TestnetERC20PermitToken');
+        permitTokenContract = await permitTokenContractFactory.deploy('Matter Labs Permit Token', 'MLPT', 18);
+        await permitTokenContract.mint(wallet.address, parseEther('1000000'));
+
         const govContract = deployer.governanceContract(wallet);
         await govContract.addToken(tokenContract.address);
-    });
+        await govContract.addToken(permitTokenContract.address);
+    });
+
+    async function signPermit(value: BigNumber, deadline: BigNumber): Promise<ethers.Signature> {
+        const nonce = await permitTokenContract.nonces(wallet.address);
+        const { chainId } = await wallet.provider.getNetwork();
+        const signature = await wallet._signTypedData(
+            {
+                name: await permitTokenContract.name(),
+                version: '1',
+                chainId,
+                verifyingContract: permitTokenContract.address
+            },
+            {
+                Permit: [
+                    { name: 'owner', type: 'address' },
+                    { name: 'spender', type: 'address' },
+                    { name: 'value', type: 'uint256' },
+                    { name: 'nonce', type: 'uint256' },
+                    { name: 'deadline', type: 'uint256' }
+                ]
+            },
+            { owner: wallet.address, spender: zksyncContract.address, value, nonce, deadline }
+        );
+        return ethers.utils.splitSignature(signature);
+    }
 
-    async function performDeposit(to: Address, token: TokenAddress, depositAmount: BigNumber) {
+    async function performDeposit(
+        to: Address,
+        token: TokenAddress,
+        depositAmount: BigNumber,
+        permit?: { deadline: BigNumber; signature: ethers.Signature }
+    ) {
         const openedRequests = await zksyncContract.getTotalOpenPriorityRequests();
         const depositOwner = wallet.address;
 
@@ -195,6 +230,17 @@ describe('ZK priority queue ops unit tests', function () {
             tx = await zksyncContract.depositETH(depositOwner, {
                 value: depositAmount
             });
+        } else if (permit) {
+            const { v, r, s } = permit.signature;
+            tx = await zksyncContract.depositERC20WithPermit(
+                token,
+                depositAmount,
+                depositOwner,
+                permit.deadline,
+                v,
+                r,
+                s
+            );
         } else {
             tx = await zksyncContract.depositERC20(token, depositAmount, depositOwner);
         }
@@ -264,6 +310,48 @@ describe('ZK priority queue ops unit tests', function () {
         await performDeposit(ethers.Wallet.createRandom().address, tokenAddress, depositAmount);
     });
 
+    it('success ERC20 deposits with permit', async () => {
+        const tokenAddress = permitTokenContract.address;
+        const depositAmount = parseEther('1.0');
+        const deadline = ethers.constants.MaxUint256;
+
+        const balanceBefore = await permitTokenContract.balanceOf(zksyncContract.address);
+        const signature = await signPermit(depositAmount, deadline);
+        await performDeposit(wallet.address, tokenAddress, depositAmount, { deadline, signature });
+        const balanceAfter = await permitTokenContract.balanceOf(zksyncContract.address);
+        expect(balanceAfter.sub(balanceBefore), 'deposited amount').eq(depositAmount);
+        expect(await permitTokenContract.allowance(wallet.address, zksyncContract.address), 'allowance').eq(0);
+    });
+
+    it('ERC20 deposit with front-run permit', async () => {
+        const tokenAddress = permitTokenContract.address;
+        const depositAmount = parseEther('1.0');
+        const deadline = ethers.constants.MaxUint256;
+
+        // Someone submits the permit from the deposit transaction before it's mined.
+        const signature = await signPermit(depositAmount, deadline);
+        const { v, r, s } = signature;
+        await (
+            await permitTokenContract.permit(wallet.address, zksyncContract.address, depositAmount, deadline, v, r, s)
+        ).wait();
+
+        await performDeposit(wallet.address, tokenAddress, depositAmount, { deadline, signature });
+        expect(await permitTokenContract.allowance(wallet.address, zksyncContract.address), 'allowance').eq(0);
+    });
+
+    it('ERC20 deposit with invalid permit fails', async () => {
+        const tokenAddress = permitTokenContract.address;
+        const depositAmount = parseEther('1.0');
+        const deadline = ethers.constants.MaxUint256;
+
+        // Signature permits to spend less than the deposit amount.
+        const signature = await signPermit(depositAmount.div(2), deadline);
+        const { revertReason } = await getCallRevertReason(
+            async () => await performDeposit(wallet.address, tokenAddress, depositAmount, { deadline, signature })
+        );
+        expect(revertReason, 'revert reason').eq('ERC20: transfer amount exceeds allowance');
+    });
+
     it('success FullExit request', async () => {
         zksyncContract.connect(wallet);
         const accountId = 1;
@@ -0,0 +1,188 @@
+//! Preconditions of the blocks revert.
+//!
+//! The revert is only safe when none of the reverted blocks is executed on the contract and
+//! the Ethereum sender is stopped, i.e. there are no operator transactions in flight that may
+//! be mined after the revert.
+
+use anyhow::{ensure, format_err};
+use web3::{contract::Options, types::U256};
+use zksync_eth_client::EthereumGateway;
+use zksync_storage::StorageProcessor;
+use zksync_types::BlockNumber;
+
+/// Returns the value of the contract counter of the blocks, e.g. `totalBlocksCommitted`.
+pub async fn contract_total_blocks(
+    client: &EthereumGateway,
+    counter: &str,
+) -> anyhow::Result<BlockNumber> {
+    let total: U256 = client
+        .call_main_contract_function(counter, (), None, Options::default(), None)
+        .await
+        .map_err(|e| format_err!("Failed to query contract {}: {}", counter, e))?;
+    Ok(BlockNumber(total.as_u32()))
+}
+
+// TODO: don't use anyhow (ZKS-588)
+/// Checks that the blocks following `last_correct_block` can be reverted in storage.
+pub async fn check_storage(
+    storage: &mut StorageProcessor<'_>,
+    last_correct_block: BlockNumber,
+) -> anyhow::Result<()> {
+    let last_committed_block = storage
+        .chain()
+        .block_schema()
+        .get_last_committed_block()
+        .await?;
+    let last_verified_block = storage
+        .chain()
+        .block_schema()
+        .get_last_verified_confirmed_block()
+        .await?;
+    println!(
+        "Last committed block {} verified {}",
+        last_committed_block, last_verified_block
+    );
+
+    let unconfirmed_operations = storage
+        .ethereum_schema()
+        .load_unconfirmed_operations()
+        .await?
+        .len();
+    check_storage_state(
+        last_correct_block,
+        last_committed_block,
+        last_verified_block,
+        unconfirmed_operations,
+    )
+}
+
+fn check_storage_state(
+    last_correct_block: BlockNumber,
+    last_committed_block: BlockNumber,
+    last_verified_block: BlockNumber,
+    unconfirmed_operations: usize,
+) -> anyhow::Result<()> {
+    ensure!(
+        last_correct_block < last_committed_block,
+        "There are no blocks after {} to revert",
+        last_correct_block
+    );
+    ensure!(
+        last_verified_block <= last_correct_block,
+        "Some blocks to revert are already verified"
+    );
+    ensure!(
+        unconfirmed_operations == 0,
+        "Ethereum sender has {} unconfirmed operations, wait until they're confirmed and stop it",
+        unconfirmed_operations
+    );
+    Ok(())
+}
+
+// TODO: don't use anyhow (ZKS-588)
+/// Checks that the blocks following `last_correct_block` can be reverted on the contract.
+pub async fn check_contract(
+    client: &EthereumGateway,
+    last_correct_block: BlockNumber,
+) -> anyhow::Result<()> {
+    let total_executed = contract_total_blocks(client, "totalBlocksExecuted").await?;
+    let total_committed = contract_total_blocks(client, "totalBlocksCommitted").await?;
+    let current_nonce = client.current_nonce().await?;
+    let pending_nonce = client.pending_nonce().await?;
+    check_contract_state(
+        last_correct_block,
+        total_executed,
+        total_committed,
+        current_nonce,
+        pending_nonce,
+    )
+}
+
+fn check_contract_state(
+    last_correct_block: BlockNumber,
+    total_executed: BlockNumber,
+    total_committed: BlockNumber,
+    current_nonce: U256,
+    pending_nonce: U256,
+) -> anyhow::Result<()> {
+    ensure!(
+        total_executed <= last_correct_block,
+        "Block {} is already executed on the contract",
+        total_executed
+    );
+    ensure!(
+        last_correct_block < total_committed,
+        "There are no blocks after {} to revert on the contract, last committed block is {}",
+        last_correct_block,
+        total_committed
+    );
+    // The transactions of the operator which are not mined yet may be mined after the revert.
+    ensure!(
+        current_nonce == pending_nonce,
+        "Operator has pending transactions (nonce {}, pending nonce {}), stop the Ethereum sender and wait until they're mined",
+        current_nonce,
+        pending_nonce
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn storage_preconditions() {
+        // Blocks 6..=10 are committed, 1..=5 are verified.
+        assert!(check_storage_state(BlockNumber(5), BlockNumber(10), BlockNumber(5), 0).is_ok());
+        assert!(check_storage_state(BlockNumber(7), BlockNumber(10), BlockNumber(5), 0).is_ok());
+
+        // Nothing to revert.
+        assert!(check_storage_state(BlockNumber(10), BlockNumber(10), BlockNumber(5), 0).is_err());
+        // Verified blocks can't be reverted.
+        assert!(check_storage_state(BlockNumber(4), BlockNumber(10), BlockNumber(5), 0).is_err());
+        // Ethereum sender is not quiesced.
+        assert!(check_storage_state(BlockNumber(5), BlockNumber(10), BlockNumber(5), 2).is_err());
+    }
+
+    #[test]
+    fn contract_preconditions() {
+        let nonce = U256::from(3);
+        // Blocks 6..=10 are committed, 1..=5 are executed.
+        assert!(check_contract_state(
+            BlockNumber(5),
+            BlockNumber(5),
+            BlockNumber(10),
+            nonce,
+            nonce
+        )
+        .is_ok());
+
+        // Executed blocks can't be reverted.
+        assert!(check_contract_state(
+            BlockNumber(4),
+            BlockNumber(5),
+            BlockNumber(10),
+            nonce,
+            nonce
+        )
+        .is_err());
+        // Nothing to revert.
+        assert!(check_contract_state(
+            BlockNumber(10),
+            BlockNumber(5),
+            BlockNumber(10),
+            nonce,
+            nonce
+        )
+        .is_err());
+        // Operator transactions are in flight.
+        assert!(check_contract_state(
+            BlockNumber(5),
+            BlockNumber(5),
+            BlockNumber(10),
+            nonce,
+            nonce + 1
+        )
+        .is_err());
+    }
+}
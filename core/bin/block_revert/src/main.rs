@@ -7,116 +7,26 @@ use web3::{
     contract::Options,
     types::{TransactionReceipt, U256, U64},
 };
-use zksync_config::{ContractsConfig, ETHClientConfig, ETHSenderConfig};
+use zksync_config::{ContractsConfig, DBConfig, ETHClientConfig, ETHSenderConfig};
 use zksync_eth_client::EthereumGateway;
 use zksync_storage::StorageProcessor;
 use zksync_types::{aggregated_operations::stored_block_info, block::Block, BlockNumber, H256};
 
+use crate::report::ConsistencyReport;
+
+mod checks;
+mod report;
+
 // TODO: don't use anyhow (ZKS-588)
 async fn revert_blocks_in_storage(
     storage: &mut StorageProcessor<'_>,
     last_block: BlockNumber,
 ) -> anyhow::Result<()> {
-    let mut transaction = storage.start_transaction().await?;
-
-    transaction
-        .chain()
-        .mempool_schema()
-        .return_executed_txs_to_mempool(last_block)
-        .await?;
-    println!("`mempool_txs`, `executed_transactions` tables are updated");
-    transaction
-        .chain()
-        .state_schema()
-        .clear_current_nonce_table(last_block)
-        .await?;
-    println!("`committed_nonce` table is updated");
-    transaction
-        .chain()
-        .block_schema()
-        .remove_blocks(last_block)
-        .await?;
-    println!("`block` table is cleaned");
-    transaction
+    storage
         .chain()
         .block_schema()
-        .remove_pending_block()
-        .await?;
-    println!("`pending_block` table is cleaned");
-    transaction
-        .chain()
-        .tree_cache_schema_bincode()
-        .remove_new_account_tree_cache(last_block)
-        .await?;
-    println!("`account_tree_cache` table is cleaned");
-
-    transaction
-        .chain()
-        .state_schema()
-        .remove_account_balance_updates(last_block)
-        .await?;
-    println!("`account_balance_updates` table is cleaned");
-    transaction
-        .chain()
-        .state_schema()
-        .remove_account_creates(last_block)
-        .await?;
-    println!("`account_creates` table is cleaned");
-    transaction
-        .chain()
-        .state_schema()
-        .remove_account_pubkey_updates(last_block)
-        .await?;
-    println!("`account_pubkey_updates` table is cleaned");
-
-    transaction
-        .chain()
-        .state_schema()
-        .remove_mint_nft_updates(last_block)
-        .await?;
-    println!("`mint_nft_updates` table is cleaned");
-
-    transaction
-        .chain()
-        .operations_schema()
-        .remove_eth_unprocessed_aggregated_ops()
-        .await?;
-    println!("`eth_unprocessed_aggregated_ops` table is cleaned");
-    transaction
-        .chain()
-        .operations_schema()
-        .remove_aggregate_operations_and_bindings(last_block)
-        .await?;
-    println!("`aggregate_operations`, `eth_aggregated_ops_binding`, `eth_tx_hashes`, `eth_operations` tables are cleaned");
-
-    transaction
-        .prover_schema()
-        .remove_witnesses(last_block)
-        .await?;
-    println!("`block_witness` table is cleaned");
-    transaction
-        .prover_schema()
-        .remove_proofs(last_block)
+        .revert_blocks(last_block)
         .await?;
-    println!("`proofs` table is cleaned");
-    transaction
-        .prover_schema()
-        .remove_aggregated_proofs(last_block)
-        .await?;
-    println!("`aggregated_proofs` table is cleaned");
-    transaction
-        .prover_schema()
-        .remove_prover_jobs(last_block)
-        .await?;
-    println!("`prover_job_queue` table is cleaned");
-
-    transaction
-        .ethereum_schema()
-        .update_eth_parameters(last_block)
-        .await?;
-    println!("`eth_parameters` table is updated");
-
-    transaction.commit().await?;
 
     println!("Blocks were reverted in storage");
     Ok(())
@@ -211,6 +121,11 @@ enum Command {
 #[derive(Debug, StructOpt)]
 #[structopt(name = "zkSync block revert tool", author = "Matter Labs")]
 #[structopt(about = "Tool to revert blocks in zkSync network on contract and/or in storage")]
+#[structopt(
+    after_help = "The Ethereum sender must be stopped and none of the blocks to revert may be executed. \
+                  Blocks are reverted on contract first, then the storage (including the account tree cache) \
+                  is rolled back in a single database transaction and the consistency report is printed."
+)]
 struct Opt {
     /// Last correct block, tool reverts blocks with numbers greater than this field.
     #[structopt(long)]
@@ -225,6 +140,13 @@ struct Opt {
 // TODO: don't use anyhow (ZKS-588)
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    zksync_config::file::load_config_files()?;
+    zksync_config::run_validate_config_mode(|| {
+        ContractsConfig::from_env();
+        ETHClientConfig::from_env();
+        ETHSenderConfig::from_env();
+        DBConfig::from_env().validate()
+    });
     let opt = Opt::from_args();
 
     let key_without_prefix = opt
@@ -246,28 +168,31 @@ async fn main() -> anyhow::Result<()> {
         contracts.contract_addr,
     );
 
+    let last_block = BlockNumber(opt.last_correct_block);
     let last_commited_block = storage
         .chain()
         .block_schema()
         .get_last_committed_confirmed_block()
         .await?;
-    let last_verified_block = storage
-        .chain()
-        .block_schema()
-        .get_last_verified_confirmed_block()
-        .await?;
 
-    println!(
-        "Last committed block {} verified {}",
-        &last_commited_block, &last_verified_block
-    );
-    ensure!(
-        *last_verified_block <= opt.last_correct_block,
-        "Some blocks to revert are already verified"
-    );
+    // Nothing is reverted unless all the preconditions are met.
+    if matches!(opt.command, Command::All | Command::Storage) {
+        checks::check_storage(&mut storage, last_block).await?;
+    }
+    if matches!(opt.command, Command::All | Command::Contract) {
+        checks::check_contract(&client, last_block).await?;
+        let contract_committed_block =
+            checks::contract_total_blocks(&client, "totalBlocksCommitted").await?;
+        ensure!(
+            contract_committed_block == last_commited_block,
+            "Last committed block on the contract is {}, but {} in storage",
+            contract_committed_block,
+            last_commited_block
+        );
+    }
 
-    let blocks_to_revert = *last_commited_block - opt.last_correct_block;
-    let last_block = BlockNumber(opt.last_correct_block);
+    // Contract is checked to have blocks after the last correct one.
+    let blocks_to_revert = last_commited_block.saturating_sub(opt.last_correct_block);
 
     match opt.command {
         Command::All => {
@@ -288,5 +213,19 @@ async fn main() -> anyhow::Result<()> {
         }
     }
 
+    let report = ConsistencyReport::collect(
+        &mut storage,
+        &client,
+        last_block,
+        matches!(opt.command, Command::All | Command::Storage),
+        matches!(opt.command, Command::All | Command::Contract),
+    )
+    .await?;
+    println!("{}", report);
+    ensure!(
+        report.problems().is_empty(),
+        "Storage or contract state is inconsistent after the revert"
+    );
+
     Ok(())
 }
@@ -0,0 +1,290 @@
+//! Consistency report printed after the revert.
+
+use std::fmt;
+
+use zksync_eth_client::EthereumGateway;
+use zksync_storage::StorageProcessor;
+use zksync_types::{aggregated_operations::AggregatedActionType, BlockNumber};
+
+use crate::checks::contract_total_blocks;
+
+/// State of the storage and the contract after the revert.
+#[derive(Debug)]
+pub struct ConsistencyReport {
+    last_correct_block: BlockNumber,
+    last_committed_block: BlockNumber,
+    last_verified_block: BlockNumber,
+    has_pending_block: bool,
+    last_account_tree_cache_block: Option<BlockNumber>,
+    last_aggregated_block: BlockNumber,
+    unconfirmed_eth_operations: usize,
+    mempool_size: u32,
+    contract_committed_block: Option<BlockNumber>,
+    storage_reverted: bool,
+    contract_reverted: bool,
+}
+
+impl ConsistencyReport {
+    // TODO: don't use anyhow (ZKS-588)
+    pub async fn collect(
+        storage: &mut StorageProcessor<'_>,
+        client: &EthereumGateway,
+        last_correct_block: BlockNumber,
+        storage_reverted: bool,
+        contract_reverted: bool,
+    ) -> anyhow::Result<Self> {
+        let last_committed_block = storage
+            .chain()
+            .block_schema()
+            .get_last_committed_block()
+            .await?;
+        let last_verified_block = storage
+            .chain()
+            .block_schema()
+            .get_last_verified_confirmed_block()
+            .await?;
+        let has_pending_block = storage
+            .chain()
+            .block_schema()
+            .load_pending_block()
+            .await?
+            .is_some();
+        let last_account_tree_cache_block = storage
+            .chain()
+            .tree_cache_schema_bincode()
+            .get_last_block_with_account_tree_cache()
+            .await?;
+
+        let mut last_aggregated_block = BlockNumber(0);
+        for action in [
+            AggregatedActionType::CommitBlocks,
+            AggregatedActionType::CreateProofBlocks,
+            AggregatedActionType::PublishProofBlocksOnchain,
+            AggregatedActionType::ExecuteBlocks,
+        ] {
+            let block = storage
+                .chain()
+                .operations_schema()
+                .get_last_affected_block_by_aggregated_action(action)
+                .await?;
+            last_aggregated_block = last_aggregated_block.max(block);
+        }
+
+        let unconfirmed_eth_operations = storage
+            .ethereum_schema()
+            .load_unconfirmed_operations()
+            .await?
+            .len();
+        let mempool_size = storage.chain().mempool_schema().get_mempool_size().await?;
+
+        // The report is still useful if the contract is not available.
+        let contract_committed_block =
+            match contract_total_blocks(client, "totalBlocksCommitted").await {
+                Ok(block) => Some(block),
+                Err(err) => {
+                    println!("Cannot check the contract state: {}", err);
+                    None
+                }
+            };
+
+        Ok(Self {
+            last_correct_block,
+            last_committed_block,
+            last_verified_block,
+            has_pending_block,
+            last_account_tree_cache_block,
+            last_aggregated_block,
+            unconfirmed_eth_operations,
+            mempool_size,
+            contract_committed_block,
+            storage_reverted,
+            contract_reverted,
+        })
+    }
+
+    /// Returns the list of the inconsistencies found after the revert.
+    /// Only the reverted parts are expected to be rolled back to the last correct block.
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.storage_reverted {
+            self.storage_problems(&mut problems);
+        }
+        if let (true, Some(block)) = (self.contract_reverted, self.contract_committed_block) {
+            if block != self.last_correct_block {
+                problems.push(format!(
+                    "last committed block on the contract is {}, expected {}",
+                    block, self.last_correct_block
+                ));
+            }
+        }
+        problems
+    }
+
+    fn storage_problems(&self, problems: &mut Vec<String>) {
+        if self.last_committed_block != self.last_correct_block {
+            problems.push(format!(
+                "last committed block in storage is {}, expected {}",
+                self.last_committed_block, self.last_correct_block
+            ));
+        }
+        if self.has_pending_block {
+            problems.push("pending block is not removed".to_string());
+        }
+        if let Some(block) = self.last_account_tree_cache_block {
+            if block > self.last_correct_block {
+                problems.push(format!("account tree cache is stored for block {}", block));
+            }
+        }
+        if self.last_aggregated_block > self.last_correct_block {
+            problems.push(format!(
+                "aggregated operations affect block {}",
+                self.last_aggregated_block
+            ));
+        }
+        if self.unconfirmed_eth_operations != 0 {
+            problems.push(format!(
+                "{} Ethereum operations are not confirmed",
+                self.unconfirmed_eth_operations
+            ));
+        }
+    }
+}
+
+impl fmt::Display for ConsistencyReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let optional = |block: Option<BlockNumber>| {
+            block.map_or_else(|| "-".to_string(), |block| block.to_string())
+        };
+
+        writeln!(f, "Post-revert consistency report:")?;
+        writeln!(
+            f,
+            "  last correct block:          {}",
+            self.last_correct_block
+        )?;
+        writeln!(
+            f,
+            "  last committed block:        {}",
+            self.last_committed_block
+        )?;
+        writeln!(
+            f,
+            "  last verified block:         {}",
+            self.last_verified_block
+        )?;
+        writeln!(
+            f,
+            "  pending block:               {}",
+            self.has_pending_block
+        )?;
+        writeln!(
+            f,
+            "  last account tree cache:     {}",
+            optional(self.last_account_tree_cache_block)
+        )?;
+        writeln!(
+            f,
+            "  last aggregated block:       {}",
+            self.last_aggregated_block
+        )?;
+        writeln!(
+            f,
+            "  unconfirmed Ethereum ops:    {}",
+            self.unconfirmed_eth_operations
+        )?;
+        writeln!(f, "  transactions in mempool:     {}", self.mempool_size)?;
+        writeln!(
+            f,
+            "  last committed on contract:  {}",
+            optional(self.contract_committed_block)
+        )?;
+
+        let problems = self.problems();
+        if problems.is_empty() {
+            write!(f, "Storage and contract are consistent")
+        } else {
+            writeln!(f, "Inconsistencies found:")?;
+            for problem in problems {
+                writeln!(f, "  - {}", problem)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn consistent_report() -> ConsistencyReport {
+        ConsistencyReport {
+            last_correct_block: BlockNumber(5),
+            last_committed_block: BlockNumber(5),
+            last_verified_block: BlockNumber(5),
+            has_pending_block: false,
+            last_account_tree_cache_block: Some(BlockNumber(5)),
+            last_aggregated_block: BlockNumber(5),
+            unconfirmed_eth_operations: 0,
+            mempool_size: 10,
+            contract_committed_block: Some(BlockNumber(5)),
+            storage_reverted: true,
+            contract_reverted: true,
+        }
+    }
+
+    #[test]
+    fn consistent_state() {
+        let report = consistent_report();
+        assert!(report.problems().is_empty());
+        assert!(report
+            .to_string()
+            .ends_with("Storage and contract are consistent"));
+
+        // Unknown contract state isn't reported as a problem.
+        let report = ConsistencyReport {
+            contract_committed_block: None,
+            ..consistent_report()
+        };
+        assert!(report.problems().is_empty());
+    }
+
+    #[test]
+    fn storage_inconsistencies() {
+        let report = ConsistencyReport {
+            last_committed_block: BlockNumber(6),
+            has_pending_block: true,
+            last_account_tree_cache_block: Some(BlockNumber(7)),
+            last_aggregated_block: BlockNumber(6),
+            unconfirmed_eth_operations: 1,
+            ..consistent_report()
+        };
+        assert_eq!(report.problems().len(), 5);
+        assert!(report.to_string().contains("Inconsistencies found:"));
+
+        // Storage isn't checked if it wasn't reverted.
+        let report = ConsistencyReport {
+            storage_reverted: false,
+            ..report
+        };
+        assert!(report.problems().is_empty());
+    }
+
+    #[test]
+    fn contract_inconsistencies() {
+        let report = ConsistencyReport {
+            contract_committed_block: Some(BlockNumber(6)),
+            ..consistent_report()
+        };
+        assert_eq!(
+            report.problems(),
+            vec!["last committed block on the contract is 6, expected 5".to_string()]
+        );
+
+        // Contract isn't checked if it wasn't reverted.
+        let report = ConsistencyReport {
+            contract_reverted: false,
+            ..report
+        };
+        assert!(report.problems().is_empty());
+    }
+}
@@ -45,6 +45,7 @@ pub fn rollup_ops_blocks_from_bytes(input_data: Vec<u8>) -> Result<RollupOpsBloc
             fee_account,
             timestamp: None,
             previous_block_root_hash: H256::default(),
+            root_hash: None,
             contract_version: None,
         };
         Ok(block)
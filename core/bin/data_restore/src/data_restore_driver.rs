@@ -1,6 +1,7 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, fmt, time::Duration};
 
 // External deps
+use futures::{stream, StreamExt};
 use web3::{
     contract::Contract,
     types::{H160, H256},
@@ -13,7 +14,8 @@ use zksync_crypto::{
     Fr,
 };
 use zksync_types::{
-    Account, AccountId, AccountMap, AccountUpdate, BlockNumber, SerialId, Token, TokenKind,
+    block::Block, Account, AccountId, AccountMap, AccountUpdate, BlockNumber, SerialId, Token,
+    TokenKind,
 };
 
 // Local deps
@@ -21,9 +23,13 @@ use crate::{
     contract::{get_genesis_account, ZkSyncDeployedContract},
     eth_tx_helpers::get_ethereum_transaction,
     events_state::EventsState,
+    logs_fetcher::LogsFetcher,
+    progress::{ProgressSnapshot, RestoreProgress},
     rollup_ops::RollupOpsBlock,
     storage_interactor::StorageInteractor,
     tree_state::TreeState,
+    ETH_REQUESTS_CONCURRENCY, EVENTS_UPDATE_RETRY_INTERVAL, MAX_EVENTS_UPDATE_ATTEMPTS,
+    MAX_LOGS_RANGE_SIZE, NEW_BLOCKS_POLL_INTERVAL,
 };
 
 /// Storage state update:
@@ -37,6 +43,24 @@ pub enum StorageUpdateState {
     Operations,
 }
 
+/// Difference between the root hash of the block computed by the driver and the one committed to the contract.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RootMismatch {
+    pub block_number: BlockNumber,
+    pub computed_root: H256,
+    pub committed_root: H256,
+}
+
+impl fmt::Display for RootMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Root hash of the block {} is {:?}, while {:?} is committed to the contract",
+            self.block_number, self.computed_root, self.committed_root
+        )
+    }
+}
+
 /// Data restore driver is a high level interface for all restoring components.
 /// It is actually a finite state machine, that has following states:
 /// - Empty - The state is new
@@ -69,8 +93,13 @@ pub struct DataRestoreDriver<T: Transport> {
     pub tree_state: TreeState,
     /// The step distance of viewing events in the ethereum blocks
     pub eth_blocks_step: u64,
+    /// Fetcher of the contract logs. Its concurrency also limits the amount of
+    /// the Ethereum transactions fetched at once.
+    pub logs_fetcher: LogsFetcher,
     /// The distance to the last ethereum block
     pub end_eth_blocks_offset: u64,
+    /// Delay before the retry of the failed update of the events state
+    pub events_update_retry_interval: Duration,
     /// Finite mode flag. In finite mode, driver will only work until
     /// amount of restored blocks will become equal to amount of known
     /// verified blocks. After that, it will stop.
@@ -81,6 +110,14 @@ pub struct DataRestoreDriver<T: Transport> {
     /// Serial id of the last priority operation processed by the driver. It's necessary to manually
     /// keep track of it since it's impossible to restore it from the contract.
     pub last_priority_op_serial_id: SerialId,
+    /// Audit mode flag. In audit mode, driver stops on the first block whose computed
+    /// root hash differs from the committed one.
+    pub audit_mode: bool,
+    /// The first block whose computed root hash differs from the committed one.
+    pub root_mismatch: Option<RootMismatch>,
+    /// Blocks whose root hash can't be checked, since it's not committed to the contract
+    /// (the blocks committed by the contracts preceding version 4).
+    pub unverified_blocks: Vec<BlockNumber>,
 }
 
 impl<T: Transport> DataRestoreDriver<T> {
@@ -130,13 +167,41 @@ impl<T: Transport> DataRestoreDriver<T> {
             events_state,
             tree_state,
             eth_blocks_step,
+            logs_fetcher: LogsFetcher::new(
+                ETH_REQUESTS_CONCURRENCY,
+                MAX_LOGS_RANGE_SIZE.min(eth_blocks_step),
+            ),
             end_eth_blocks_offset,
+            events_update_retry_interval: EVENTS_UPDATE_RETRY_INTERVAL,
             finite_mode,
             final_hash,
             last_priority_op_serial_id: 0,
+            audit_mode: false,
+            root_mismatch: None,
+            unverified_blocks: Vec::new(),
         }
     }
 
+    /// Enables the audit mode: driver stops on the first block whose computed root hash
+    /// differs from the committed one, reporting it in `root_mismatch`.
+    pub fn with_audit_mode(mut self) -> Self {
+        self.audit_mode = true;
+        self
+    }
+
+    /// Sets the max amount of the concurrent requests to the Ethereum node.
+    pub fn with_eth_requests_concurrency(mut self, concurrency: usize) -> Self {
+        self.logs_fetcher =
+            LogsFetcher::new(concurrency, MAX_LOGS_RANGE_SIZE.min(self.eth_blocks_step));
+        self
+    }
+
+    /// Sets the delay before the retry of the failed update of the events state.
+    pub fn with_events_update_retry_interval(mut self, interval: Duration) -> Self {
+        self.events_update_retry_interval = interval;
+        self
+    }
+
     /// Sets the 'genesis' state.
     /// Tree with inserted genesis account will be created.
     /// Used when restore driver is restarted.
@@ -341,11 +406,38 @@ impl<T: Transport> DataRestoreDriver<T> {
     pub async fn run_state_update(&mut self, interactor: &mut StorageInteractor<'_>) {
         let mut last_watched_block: u64 = self.events_state.last_watched_eth_block_number;
         let mut final_hash_was_found = false;
+        let progress = RestoreProgress::new(last_watched_block);
+        let mut failed_events_updates = 0;
         loop {
             vlog::info!("Last watched ethereum block: {:?}", last_watched_block);
 
-            // Update events
-            if self.update_events_state(interactor).await {
+            // Update events. The progress is stored after every step, so the failed one
+            // is retried from the last stored checkpoint.
+            let has_new_events = match self.update_events_state(interactor).await {
+                Ok(has_new_events) => {
+                    failed_events_updates = 0;
+                    has_new_events
+                }
+                Err(err) => {
+                    failed_events_updates += 1;
+                    if failed_events_updates == MAX_EVENTS_UPDATE_ATTEMPTS {
+                        panic!(
+                            "Failed to update the events state {} times in a row, the restore can be resumed from the Ethereum block {}: {}",
+                            failed_events_updates,
+                            self.events_state.last_watched_eth_block_number,
+                            err
+                        );
+                    }
+                    vlog::warn!(
+                        "Failed to update the events state, retrying from the Ethereum block {}: {}",
+                        self.events_state.last_watched_eth_block_number,
+                        err
+                    );
+                    tokio::time::sleep(self.events_update_retry_interval).await;
+                    continue;
+                }
+            };
+            if has_new_events {
                 // Update operations
                 let new_ops_blocks = self.update_operations_state(interactor).await;
 
@@ -356,6 +448,11 @@ impl<T: Transport> DataRestoreDriver<T> {
                     self.update_tree_state(&mut transaction, new_ops_blocks)
                         .await;
 
+                    if self.audit_mode && self.root_mismatch.is_some() {
+                        transaction.commit().await;
+                        break;
+                    }
+
                     let total_verified_blocks =
                         self.zksync_contract.get_total_verified_blocks().await;
 
@@ -404,20 +501,42 @@ impl<T: Transport> DataRestoreDriver<T> {
 
             if last_watched_block == self.events_state.last_watched_eth_block_number {
                 vlog::info!("sleep block");
-                std::thread::sleep(std::time::Duration::from_secs(5));
+                tokio::time::sleep(NEW_BLOCKS_POLL_INTERVAL).await;
             } else {
                 last_watched_block = self.events_state.last_watched_eth_block_number;
+                self.report_progress(&progress).await;
             }
         }
     }
 
+    /// Reports the progress of the restore after the step over the Ethereum blocks.
+    async fn report_progress(&self, progress: &RestoreProgress) {
+        let last_watched_eth_block = self.events_state.last_watched_eth_block_number;
+        let last_eth_block = EventsState::get_last_block_number(&self.web3)
+            .await
+            .unwrap_or(last_watched_eth_block);
+        let total_verified_blocks = self.zksync_contract.get_total_verified_blocks().await;
+
+        progress.report(ProgressSnapshot {
+            last_watched_eth_block,
+            last_eth_block,
+            decoded_events: self.events_state.decoded_events,
+            restored_block: self.tree_state.block_number,
+            total_verified_blocks,
+        });
+    }
+
     /// Updates events state, saves new blocks, tokens events and the last watched eth block number in storage
     /// Returns bool flag, true if there are new block events
-    async fn update_events_state(&mut self, interactor: &mut StorageInteractor<'_>) -> bool {
+    async fn update_events_state(
+        &mut self,
+        interactor: &mut StorageInteractor<'_>,
+    ) -> anyhow::Result<bool> {
         let (block_events, token_events, priority_op_data, last_watched_eth_block_number) = self
             .events_state
             .update_events_state(
                 &self.web3,
+                &self.logs_fetcher,
                 &self.zksync_contract,
                 &self.governance_contract,
                 &self.contract_upgrade_eth_blocks,
@@ -425,8 +544,7 @@ impl<T: Transport> DataRestoreDriver<T> {
                 self.end_eth_blocks_offset,
                 self.init_contract_version,
             )
-            .await
-            .expect("Updating events state: cant update events state");
+            .await?;
         interactor
             .save_events_state(
                 &block_events,
@@ -436,7 +554,7 @@ impl<T: Transport> DataRestoreDriver<T> {
             )
             .await;
 
-        !block_events.is_empty()
+        Ok(!block_events.is_empty())
     }
 
     /// Updates tree state from the new Rollup operations blocks, saves it in storage
@@ -468,9 +586,14 @@ impl<T: Transport> DataRestoreDriver<T> {
                     &mut self.last_priority_op_serial_id,
                 )
                 .expect("Updating tree state: cant update tree from operations");
+            self.check_root_hash(&block, op_block.root_hash);
             blocks.push(block);
             updates.push(acc_updates);
             count += 1;
+
+            if self.audit_mode && self.root_mismatch.is_some() {
+                break;
+            }
         }
 
         let mut transaction = interactor.start_transaction().await;
@@ -503,6 +626,28 @@ impl<T: Transport> DataRestoreDriver<T> {
         vlog::debug!("Updated state");
     }
 
+    /// Compares the root hash of the restored block with the one committed to the contract,
+    /// recording the first mismatch. Blocks without the committed root hash are recorded as unverified.
+    pub(crate) fn check_root_hash(&mut self, block: &Block, committed_root: Option<H256>) {
+        let committed_root = match committed_root {
+            Some(root_hash) => root_hash,
+            None => {
+                self.unverified_blocks.push(block.block_number);
+                return;
+            }
+        };
+        let computed_root = block.get_eth_encoded_root();
+        if computed_root != committed_root && self.root_mismatch.is_none() {
+            let mismatch = RootMismatch {
+                block_number: block.block_number,
+                computed_root,
+                committed_root,
+            };
+            vlog::error!("{}", mismatch);
+            self.root_mismatch = Some(mismatch);
+        }
+    }
+
     /// Gets new operations blocks from events, updates rollup operations stored state.
     /// Returns new rollup operations blocks
     async fn update_operations_state(
@@ -519,43 +664,52 @@ impl<T: Transport> DataRestoreDriver<T> {
     }
 
     /// Returns operations blocks from verified op blocks events.
+    ///
+    /// Ethereum transactions of the events are fetched concurrently, and the operations
+    /// of every transaction are decoded while the following ones are still being fetched.
     pub async fn get_new_operation_blocks_from_events(&mut self) -> Vec<RollupOpsBlock> {
-        let mut blocks = Vec::new();
-
-        let mut last_event_tx_hash = None;
-        // The HashMap from block_num to the RollupOpsBlock data for the tx represented by last_event_tx_hash.
-        // It is used as a cache to reuse the fetched data.
-        let mut last_tx_blocks = HashMap::new();
-
         // TODO (ZKS-722): either due to Ethereum node lag or unknown
         // bug in the events state, we have to additionally filter out
         // already processed rollup blocks.
         let mut last_processed_block = self.tree_state.block_number;
+        let mut events = Vec::new();
         for event in self
             .events_state
             .get_only_verified_committed_events()
-            .iter()
+            .into_iter()
             .filter(|bl| bl.block_num > self.tree_state.block_number)
         {
             // For some reasons, we have a bug where event state contains duplicates for blocks
             if last_processed_block >= event.block_num {
                 continue;
             }
-            // We use an aggregated block in contracts, which means that several BlockEvent can include the same tx_hash,
-            // but for correct restore we need to generate RollupBlocks from this tx only once.
-            // These blocks go one after the other, and checking only the last transaction hash is safe.
-
-            // If the previous tx hash does not exist or it is not equal to the current one, we should
-            // re-fetch the blocks for the new tx hash.
-            if !last_event_tx_hash
-                .map(|tx| tx == event.transaction_hash)
-                .unwrap_or_default()
-            {
-                let blocks = RollupOpsBlock::get_rollup_ops_blocks(&self.web3, event)
-                    .await
-                    .expect("Cant get new operation blocks from events");
+            last_processed_block = event.block_num;
+            events.push(event);
+        }
 
-                last_tx_blocks = blocks
+        // We use an aggregated block in contracts, which means that several BlockEvent can include the same tx_hash,
+        // but for correct restore we need to generate RollupBlocks from this tx only once.
+        // These blocks go one after the other, so each transaction is fetched for the first event of such a group,
+        // and its blocks are used for the whole group.
+        let mut tx_events = events.clone();
+        tx_events.dedup_by_key(|event| event.transaction_hash);
+
+        let web3 = &self.web3;
+        let mut tx_blocks = stream::iter(tx_events)
+            .map(|event| async move { RollupOpsBlock::get_rollup_ops_blocks(web3, &event).await })
+            .buffered(self.logs_fetcher.concurrency());
+
+        let mut blocks = Vec::with_capacity(events.len());
+        // The HashMap from block_num to the RollupOpsBlock data for the tx of the current group of events.
+        let mut last_tx_blocks = HashMap::new();
+        let mut last_event_tx_hash = None;
+        for event in events {
+            if last_event_tx_hash != Some(event.transaction_hash) {
+                last_tx_blocks = tx_blocks
+                    .next()
+                    .await
+                    .expect("Transaction of the event wasn't fetched")
+                    .expect("Cant get new operation blocks from events")
                     .into_iter()
                     .map(|block| (block.block_num, block))
                     .collect();
@@ -564,7 +718,6 @@ impl<T: Transport> DataRestoreDriver<T> {
 
             if let Some(rollup_block) = last_tx_blocks.remove(&event.block_num) {
                 blocks.push(rollup_block);
-                last_processed_block = event.block_num;
             } else {
                 panic!("Block not found")
             }
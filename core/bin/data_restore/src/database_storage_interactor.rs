@@ -68,6 +68,15 @@ impl<'a> DatabaseStorageInteractor<'a> {
             .expect("Сant make u256 block_number in get_last_watched_block_number_from_storage")
     }
 
+    /// Returns the last watched ethereum block number of the restore checkpoint, if any.
+    pub async fn get_restore_checkpoint(&mut self) -> Option<u64> {
+        self.storage
+            .data_restore_schema()
+            .get_last_watched_block_number()
+            .await
+            .expect("Cant load last watched block number")
+    }
+
     pub async fn save_rollup_ops(&mut self, blocks: &[RollupOpsBlock]) {
         let mut ops = Vec::with_capacity(blocks.len());
 
@@ -260,6 +269,7 @@ impl<'a> DatabaseStorageInteractor<'a> {
             verified_events,
             last_watched_eth_block_number,
             priority_op_data,
+            decoded_events: 0,
         }
     }
 
@@ -4,9 +4,7 @@ use std::convert::TryFrom;
 // External deps
 use anyhow::format_err;
 use web3::contract::Contract;
-use web3::types::{
-    BlockNumber as Web3BlockNumber, FilterBuilder, Log, Transaction, H256, U256, U64,
-};
+use web3::types::{BlockNumber as Web3BlockNumber, FilterBuilder, Log, Transaction, H256, U256};
 use web3::{Transport, Web3};
 // Workspace deps
 use zksync_contracts::upgrade_gatekeeper;
@@ -15,6 +13,7 @@ use zksync_types::{Address, BlockNumber, NewTokenEvent, PriorityOp, SerialId};
 use crate::contract::{ZkSyncContractVersion, ZkSyncDeployedContract};
 use crate::eth_tx_helpers::get_block_number_from_ethereum_transaction;
 use crate::events::{BlockEvent, EventType};
+use crate::logs_fetcher::LogsFetcher;
 
 /// Rollup contract events states description
 #[derive(Debug, Clone)]
@@ -30,6 +29,8 @@ pub struct EventsState {
     /// fetching fields which are not present in public data
     /// such as Ethereum transaction hash.
     pub priority_op_data: HashMap<SerialId, PriorityOp>,
+    /// Amount of the contract events decoded since the start, not stored
+    pub decoded_events: u64,
 }
 
 impl std::default::Default for EventsState {
@@ -40,6 +41,7 @@ impl std::default::Default for EventsState {
             verified_events: Vec::new(),
             last_watched_eth_block_number: 0,
             priority_op_data: HashMap::new(),
+            decoded_events: 0,
         }
     }
 }
@@ -82,6 +84,7 @@ impl EventsState {
     /// # Arguments
     ///
     /// * `web3` - Web3 provider url
+    /// * `logs_fetcher` - Fetcher of the contract logs
     /// * `zksync_contract` - Rollup contract
     /// * `governance_contract` - Governance contract
     /// * `contract_upgrade_eth_blocks` - Ethereum blocks that include correct UpgradeComplete events
@@ -93,6 +96,7 @@ impl EventsState {
     pub async fn update_events_state<T: Transport>(
         &mut self,
         web3: &Web3<T>,
+        logs_fetcher: &LogsFetcher,
         zksync_contract: &ZkSyncDeployedContract<T>,
         governance_contract: &(ethabi::Contract, Contract<T>),
         contract_upgrade_eth_blocks: &[u64],
@@ -105,6 +109,7 @@ impl EventsState {
         let (events, token_events, priority_op_data, to_block_number) =
             EventsState::get_new_events_and_last_watched_block(
                 web3,
+                logs_fetcher,
                 zksync_contract,
                 governance_contract,
                 self.last_watched_eth_block_number,
@@ -119,6 +124,9 @@ impl EventsState {
         // events emitted by the Upgrade GateKeeper. Should be provided by the
         // config.
         self.last_watched_eth_block_number = to_block_number;
+        self.decoded_events += (events.iter().map(|(_, logs)| logs.len()).sum::<usize>()
+            + token_events.len()
+            + priority_op_data.len()) as u64;
         for (zksync_contract, block_events) in events {
             self.update_blocks_state(
                 zksync_contract,
@@ -161,6 +169,7 @@ impl EventsState {
     /// # Arguments
     ///
     /// * `web3` - Web3 provider url
+    /// * `logs_fetcher` - Fetcher of the contract logs
     /// * `zksync_contract` - Rollup contract
     /// * `governance_contract` - Governance contract
     /// * `last_watched_block_number` - the current last watched eth block
@@ -170,6 +179,7 @@ impl EventsState {
     #[allow(clippy::needless_lifetimes)] // Cargo clippy gives a false positive warning on needless_lifetimes there, so can be allowed.
     async fn get_new_events_and_last_watched_block<'a, T: Transport>(
         web3: &Web3<T>,
+        logs_fetcher: &LogsFetcher,
         zksync_contract: &'a ZkSyncDeployedContract<T>,
         governance_contract: &(ethabi::Contract, Contract<T>),
         last_watched_block_number: u64,
@@ -199,30 +209,31 @@ impl EventsState {
             from_block_number_u64 + eth_blocks_step
         };
 
-        let token_logs = EventsState::get_token_added_logs(
-            web3,
-            governance_contract,
-            Web3BlockNumber::Number(from_block_number_u64.into()),
-            Web3BlockNumber::Number(to_block_number_u64.into()),
-        )
-        .await?;
-        let mut logs = vec![];
-        let block_logs = EventsState::get_block_logs(
-            web3,
-            zksync_contract,
-            Web3BlockNumber::Number(from_block_number_u64.into()),
-            Web3BlockNumber::Number(to_block_number_u64.into()),
-        )
-        .await?;
-        logs.push((zksync_contract, block_logs));
-
-        let priority_op_data = EventsState::get_priority_operations_logs(
-            web3,
-            zksync_contract,
-            from_block_number_u64.into(),
-            to_block_number_u64.into(),
-        )
-        .await?;
+        // All kinds of logs are fetched concurrently.
+        let (token_logs, block_logs, priority_op_data) = futures::try_join!(
+            EventsState::get_token_added_logs(
+                web3,
+                logs_fetcher,
+                governance_contract,
+                from_block_number_u64,
+                to_block_number_u64,
+            ),
+            EventsState::get_block_logs(
+                web3,
+                logs_fetcher,
+                zksync_contract,
+                from_block_number_u64,
+                to_block_number_u64,
+            ),
+            EventsState::get_priority_operations_logs(
+                web3,
+                logs_fetcher,
+                zksync_contract,
+                from_block_number_u64,
+                to_block_number_u64,
+            ),
+        )?;
+        let logs = vec![(zksync_contract, block_logs)];
 
         Ok((logs, token_logs, priority_op_data, to_block_number_u64))
     }
@@ -259,98 +270,42 @@ impl EventsState {
         Ok(result)
     }
 
-    async fn get_priority_operations_logs_inner<T: Transport>(
-        web3: &Web3<T>,
-        contract: &ZkSyncDeployedContract<T>,
-        from: U64,
-        to: U64,
-    ) -> anyhow::Result<Vec<PriorityOp>> {
-        let priority_op_topic = contract
-            .abi
-            .event("NewPriorityRequest")
-            .expect("main contract abi error")
-            .signature();
-        let filter = FilterBuilder::default()
-            .address(vec![contract.web3_contract.address()])
-            .from_block(Web3BlockNumber::Number(from))
-            .to_block(Web3BlockNumber::Number(to))
-            .topics(Some(vec![priority_op_topic]), None, None, None)
-            .build();
-
-        let logs = web3.eth().logs(filter).await?;
-        logs.into_iter()
-            .map(|event| {
-                PriorityOp::try_from(event)
-                    .map_err(|e| format_err!("Failed to parse event log from ETH: {:?}", e))
-            })
-            .collect()
-    }
-
     /// Returns priority operations logs emitted by the zkSync contract.
     ///
     /// # Arguments
     ///
     /// * `web3` - Web3 provider.
+    /// * `logs_fetcher` - Fetcher of the contract logs
     /// * `contract` - zkSync contract.
-    /// * `start` - start of the block range
-    /// * `end` - end of the block range (inclusive).
+    /// * `from` - start of the block range
+    /// * `to` - end of the block range (inclusive).
     ///
     async fn get_priority_operations_logs<T: Transport>(
         web3: &Web3<T>,
+        logs_fetcher: &LogsFetcher,
         contract: &ZkSyncDeployedContract<T>,
-        start: U64,
-        end: U64,
+        from: u64,
+        to: u64,
     ) -> Result<Vec<PriorityOp>, anyhow::Error> {
-        const LIMIT_ERR: &str = "query returned more than";
-        let mut from_number = start;
-        let mut to_number = end;
-
-        let mut priority_operations = Vec::new();
-
-        loop {
-            if from_number > end {
-                return Ok(priority_operations);
-            }
+        let priority_op_topic = contract
+            .abi
+            .event("NewPriorityRequest")
+            .expect("main contract abi error")
+            .signature();
 
-            let result = EventsState::get_priority_operations_logs_inner(
+        logs_fetcher
+            .fetch_logs(
                 web3,
-                contract,
-                from_number,
-                to_number,
+                contract.web3_contract.address(),
+                vec![priority_op_topic],
+                from,
+                to,
+                |event| {
+                    PriorityOp::try_from(event)
+                        .map_err(|e| format_err!("Failed to parse event log from ETH: {:?}", e))
+                },
             )
-            .await;
-            let range_diff = to_number - from_number;
-
-            match result {
-                Ok(mut operations) => {
-                    // Successfully processed block range.
-                    priority_operations.append(&mut operations);
-
-                    from_number = to_number + 1;
-                    to_number = (from_number + range_diff).min(end);
-
-                    continue;
-                }
-                Err(err) => {
-                    if err.to_string().contains(LIMIT_ERR) {
-                        if to_number <= from_number || to_number - from_number == 1.into() {
-                            return Err(format_err!(
-                                "Ethereum node failed to return logs for a single block: {}",
-                                err
-                            ));
-                        }
-
-                        // Shorten the block range.
-                        to_number = from_number + (range_diff / 2u64);
-
-                        continue;
-                    } else {
-                        // Non-recoverable error.
-                        return Err(err);
-                    }
-                }
-            }
-        }
+            .await
     }
 
     /// Returns new added token logs
@@ -358,37 +313,38 @@ impl EventsState {
     /// # Arguments
     ///
     /// * `web3` - Web3 provider url
+    /// * `logs_fetcher` - Fetcher of the contract logs
     /// * `contract` - Governance contract
     /// * `from` - From ethereum block number
-    /// * `to` - To ethereum block number
+    /// * `to` - To ethereum block number (inclusive)
     ///
     async fn get_token_added_logs<T: Transport>(
         web3: &Web3<T>,
+        logs_fetcher: &LogsFetcher,
         contract: &(ethabi::Contract, Contract<T>),
-        from: Web3BlockNumber,
-        to: Web3BlockNumber,
+        from: u64,
+        to: u64,
     ) -> Result<Vec<NewTokenEvent>, anyhow::Error> {
         let new_token_event_topic = contract
             .0
             .event("NewToken")
             .expect("Governance contract abi error")
             .signature();
-        let filter = FilterBuilder::default()
-            .address(vec![contract.1.address()])
-            .from_block(from)
-            .to_block(to)
-            .topics(Some(vec![new_token_event_topic]), None, None, None)
-            .build();
 
-        web3.eth()
-            .logs(filter)
-            .await?
-            .into_iter()
-            .map(|event| {
-                NewTokenEvent::try_from(event)
-                    .map_err(|e| format_err!("Failed to parse NewToken event log from ETH: {}", e))
-            })
-            .collect()
+        logs_fetcher
+            .fetch_logs(
+                web3,
+                contract.1.address(),
+                vec![new_token_event_topic],
+                from,
+                to,
+                |event| {
+                    NewTokenEvent::try_from(event).map_err(|e| {
+                        format_err!("Failed to parse NewToken event log from ETH: {}", e)
+                    })
+                },
+            )
+            .await
     }
 
     /// Returns the contract logs that occurred on the specified blocks
@@ -396,15 +352,17 @@ impl EventsState {
     /// # Arguments
     ///
     /// * `web3` - Web3 provider url
+    /// * `logs_fetcher` - Fetcher of the contract logs
     /// * `contract` - Specified contract
     /// * `from_block_number` - Start ethereum block number
-    /// * `to_block_number` - End ethereum block number
+    /// * `to_block_number` - End ethereum block number (inclusive)
     ///
     async fn get_block_logs<T: Transport>(
         web3: &Web3<T>,
+        logs_fetcher: &LogsFetcher,
         contract: &ZkSyncDeployedContract<T>,
-        from_block_number: Web3BlockNumber,
-        to_block_number: Web3BlockNumber,
+        from_block_number: u64,
+        to_block_number: u64,
     ) -> Result<Vec<Log>, anyhow::Error> {
         let block_verified_topic = contract
             .abi
@@ -427,20 +385,16 @@ impl EventsState {
         let topics_vec: Vec<H256> =
             vec![block_verified_topic, block_comitted_topic, reverted_topic];
 
-        let filter = FilterBuilder::default()
-            .address(vec![contract.web3_contract.address()])
-            .from_block(from_block_number)
-            .to_block(to_block_number)
-            .topics(Some(topics_vec), None, None, None)
-            .build();
-
-        let result = web3
-            .eth()
-            .logs(filter)
+        logs_fetcher
+            .fetch_logs(
+                web3,
+                contract.web3_contract.address(),
+                topics_vec,
+                from_block_number,
+                to_block_number,
+                Ok,
+            )
             .await
-            .map_err(|e| format_err!("No new logs: {}", e))?;
-
-        Ok(result)
     }
 
     /// Updates committed and verified blocks state by extending their arrays
@@ -0,0 +1,437 @@
+// Built-in deps
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+// External deps
+use futures::future::BoxFuture;
+use rand::Rng;
+use tokio::{
+    sync::Mutex,
+    time::{sleep_until, timeout, Instant},
+};
+use web3::{
+    error::TransportError,
+    rpc::{Call, Value},
+    RequestId, Transport,
+};
+
+/// Parts of the errors returned by the Ethereum nodes when the request is throttled.
+const RATE_LIMIT_ERRORS: &[&str] = &["429", "too many requests", "rate limit"];
+
+fn is_rate_limit_error(err: &web3::Error) -> bool {
+    match err {
+        web3::Error::Transport(TransportError::Code(code)) => *code == 429,
+        _ => {
+            let message = err.to_string().to_lowercase();
+            RATE_LIMIT_ERRORS
+                .iter()
+                .any(|limit| message.contains(limit))
+        }
+    }
+}
+
+/// Errors that may disappear if the request is repeated, possibly to another endpoint.
+/// The rest of the errors, e.g. the invalid request ones, are returned to the caller as is.
+fn is_transient_error(err: &web3::Error) -> bool {
+    matches!(
+        err,
+        web3::Error::Transport(_)
+            | web3::Error::Io(_)
+            | web3::Error::Unreachable
+            | web3::Error::InvalidResponse(_)
+    ) || is_rate_limit_error(err)
+}
+
+/// Spreads the requests to the endpoint in time, so they don't exceed its rate limit.
+#[derive(Debug)]
+struct RateLimiter {
+    /// Min interval between the requests, no limit if not set.
+    interval: Option<Duration>,
+    /// Moment when the next request is allowed.
+    next_request: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    fn new(requests_per_second: Option<u32>) -> Self {
+        Self {
+            interval: requests_per_second.map(|requests| Duration::from_secs(1) / requests.max(1)),
+            next_request: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Waits until the request is allowed, reserving the slot for it.
+    async fn acquire(&self) {
+        let request_at = {
+            let mut next_request = self.next_request.lock().await;
+            let request_at = (*next_request).max(Instant::now());
+            *next_request = request_at + self.interval.unwrap_or_default();
+            request_at
+        };
+        sleep_until(request_at).await;
+    }
+
+    /// Postpones the following requests, once the endpoint reports they are too frequent.
+    async fn throttle(&self, delay: Duration) {
+        let mut next_request = self.next_request.lock().await;
+        *next_request = (*next_request).max(Instant::now() + delay);
+    }
+}
+
+struct Endpoint<T> {
+    transport: T,
+    rate_limiter: RateLimiter,
+}
+
+impl<T> fmt::Debug for Endpoint<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Endpoint URLs often contain the API keys, so the transport isn't printed.
+        f.debug_struct("Endpoint")
+            .field("rate_limiter", &self.rate_limiter)
+            .finish()
+    }
+}
+
+#[derive(Debug)]
+struct Inner<T> {
+    endpoints: Vec<Endpoint<T>>,
+    /// Index of the endpoint that served the last request, the next ones are sent to it first.
+    active: AtomicUsize,
+    max_retries: usize,
+    retry_interval: Duration,
+    /// Time the endpoint has to respond, the request is sent to the next one afterwards.
+    request_timeout: Duration,
+}
+
+/// Web3 transport sending the requests to several Ethereum nodes.
+///
+/// Requests are sent to the active endpoint, every endpoint not exceeding its own rate limit.
+/// If the endpoint fails with the transient error (e.g. it's unreachable or throttles the requests),
+/// the request is sent to the next one, which becomes active once it succeeds. The endpoint that
+/// doesn't respond within the request timeout is treated as the failed one. Once all of the
+/// endpoints have failed, the request is retried after the exponentially growing delay with
+/// a random jitter, so the concurrent requests don't hit the nodes at the same moment.
+#[derive(Debug)]
+pub struct FailoverTransport<T> {
+    inner: Arc<Inner<T>>,
+}
+
+impl<T> Clone for FailoverTransport<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T> FailoverTransport<T> {
+    /// Returns the transport sending the requests to the given endpoints, the first one is active initially.
+    ///
+    /// # Arguments
+    ///
+    /// * `transports` - Transports of the endpoints
+    /// * `requests_per_second` - Max amount of the requests to every endpoint per second, not limited if not set
+    /// * `max_retries` - How many times the request is retried once all of the endpoints have failed
+    /// * `retry_interval` - Delay before the first retry, doubled for every following one
+    /// * `request_timeout` - Time every endpoint has to respond before the request is sent to the next one
+    ///
+    pub fn new(
+        transports: Vec<T>,
+        requests_per_second: Option<u32>,
+        max_retries: usize,
+        retry_interval: Duration,
+        request_timeout: Duration,
+    ) -> Self {
+        assert!(!transports.is_empty(), "at least one endpoint is required");
+        let endpoints = transports
+            .into_iter()
+            .map(|transport| Endpoint {
+                transport,
+                rate_limiter: RateLimiter::new(requests_per_second),
+            })
+            .collect();
+        Self {
+            inner: Arc::new(Inner {
+                endpoints,
+                active: AtomicUsize::new(0),
+                max_retries,
+                retry_interval,
+                request_timeout,
+            }),
+        }
+    }
+
+    /// Index of the endpoint the next request is sent to first.
+    pub fn active_endpoint(&self) -> usize {
+        self.inner.active.load(Ordering::Relaxed)
+    }
+}
+
+impl<T: Transport + Send + Sync + 'static> Inner<T>
+where
+    T::Out: Send,
+{
+    /// Delay before the retry, random within the upper half of the exponentially growing interval.
+    fn retry_delay(&self, retry: usize) -> Duration {
+        let delay = self.retry_interval * 2u32.saturating_pow(retry as u32).min(64);
+        delay / 2 + delay.mul_f64(rand::thread_rng().gen_range(0.0..0.5))
+    }
+
+    async fn send(&self, id: RequestId, request: Call) -> web3::Result<Value> {
+        let endpoints = self.endpoints.len();
+        let mut last_error = web3::Error::Unreachable;
+
+        for retry in 0..=self.max_retries {
+            if retry > 0 {
+                let delay = self.retry_delay(retry - 1);
+                vlog::warn!(
+                    "All the Ethereum endpoints have failed: {}, retrying in {:?} ({}/{})",
+                    last_error,
+                    delay,
+                    retry,
+                    self.max_retries
+                );
+                tokio::time::sleep(delay).await;
+            }
+
+            let active = self.active.load(Ordering::Relaxed);
+            for index in (active..endpoints).chain(0..active) {
+                let endpoint = &self.endpoints[index];
+                endpoint.rate_limiter.acquire().await;
+
+                let response = timeout(
+                    self.request_timeout,
+                    endpoint.transport.send(id, request.clone()),
+                )
+                .await
+                .unwrap_or_else(|_| {
+                    Err(web3::Error::Transport(TransportError::Message(format!(
+                        "no response within {:?}",
+                        self.request_timeout
+                    ))))
+                });
+                match response {
+                    Ok(response) => {
+                        if self.active.swap(index, Ordering::Relaxed) != index {
+                            vlog::warn!("Switched to the Ethereum endpoint #{}", index);
+                        }
+                        return Ok(response);
+                    }
+                    Err(err) if is_transient_error(&err) => {
+                        vlog::debug!("Ethereum endpoint #{} has failed: {}", index, err);
+                        if is_rate_limit_error(&err) {
+                            endpoint
+                                .rate_limiter
+                                .throttle(self.retry_delay(retry))
+                                .await;
+                        }
+                        last_error = err;
+                    }
+                    Err(err) => return Err(err),
+                }
+            }
+        }
+        Err(last_error)
+    }
+}
+
+impl<T: Transport + Send + Sync + 'static> Transport for FailoverTransport<T>
+where
+    T::Out: Send,
+{
+    type Out = BoxFuture<'static, web3::Result<Value>>;
+
+    fn prepare(&self, method: &str, params: Vec<Value>) -> (RequestId, Call) {
+        self.inner.endpoints[self.active_endpoint()]
+            .transport
+            .prepare(method, params)
+    }
+
+    fn send(&self, id: RequestId, request: Call) -> Self::Out {
+        let inner = self.inner.clone();
+        Box::pin(async move { inner.send(id, request).await })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::future;
+    use std::sync::atomic::AtomicU32;
+    use web3::Web3;
+
+    const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+    /// Transport failing the first `failures` requests with the given error.
+    #[derive(Debug, Clone)]
+    struct FlakyTransport {
+        failures: Arc<AtomicU32>,
+        error: fn() -> web3::Error,
+        requests: Arc<AtomicU32>,
+        /// Whether the transport never responds to the requests.
+        hangs: bool,
+    }
+
+    impl FlakyTransport {
+        fn new(failures: u32, error: fn() -> web3::Error) -> Self {
+            Self {
+                failures: Arc::new(AtomicU32::new(failures)),
+                error,
+                requests: Default::default(),
+                hangs: false,
+            }
+        }
+
+        fn hanging() -> Self {
+            Self {
+                hangs: true,
+                ..Self::new(0, unreachable_error)
+            }
+        }
+
+        fn requests(&self) -> u32 {
+            self.requests.load(Ordering::Relaxed)
+        }
+    }
+
+    impl Transport for FlakyTransport {
+        type Out = BoxFuture<'static, web3::Result<Value>>;
+
+        fn prepare(&self, method: &str, params: Vec<Value>) -> (RequestId, Call) {
+            (1, web3::helpers::build_request(1, method, params))
+        }
+
+        fn send(&self, _id: RequestId, _request: Call) -> Self::Out {
+            self.requests.fetch_add(1, Ordering::Relaxed);
+            if self.hangs {
+                return Box::pin(future::pending());
+            }
+            let failed = self
+                .failures
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |failures| {
+                    failures.checked_sub(1)
+                })
+                .is_ok();
+            Box::pin(future::ready(if failed {
+                Err((self.error)())
+            } else {
+                Ok(Value::String("0x80".into()))
+            }))
+        }
+    }
+
+    fn unreachable_error() -> web3::Error {
+        web3::Error::Transport(TransportError::Code(502))
+    }
+
+    fn rate_limit_error() -> web3::Error {
+        web3::Error::Transport(TransportError::Code(429))
+    }
+
+    fn invalid_request_error() -> web3::Error {
+        web3::Error::Rpc(jsonrpc_core::Error::invalid_request())
+    }
+
+    async fn block_number<T: Transport>(transport: T) -> web3::Result<u64> {
+        Ok(Web3::new(transport).eth().block_number().await?.as_u64())
+    }
+
+    /// Checks that the requests are sent to the next endpoint once the active one fails.
+    #[tokio::test]
+    async fn failover_to_the_next_endpoint() {
+        let first = FlakyTransport::new(u32::MAX, unreachable_error);
+        let second = FlakyTransport::new(0, unreachable_error);
+        let transport = FailoverTransport::new(
+            vec![first.clone(), second.clone()],
+            None,
+            0,
+            Duration::from_millis(1),
+            REQUEST_TIMEOUT,
+        );
+
+        assert_eq!(block_number(transport.clone()).await.unwrap(), 0x80);
+        assert_eq!(transport.active_endpoint(), 1);
+        // The following requests are sent to the endpoint that works.
+        assert_eq!(block_number(transport.clone()).await.unwrap(), 0x80);
+        assert_eq!((first.requests(), second.requests()), (1, 2));
+    }
+
+    /// Checks that the request is retried once all of the endpoints fail, unless the error is permanent.
+    #[tokio::test]
+    async fn retry_after_all_the_endpoints_fail() {
+        let first = FlakyTransport::new(2, rate_limit_error);
+        let second = FlakyTransport::new(2, unreachable_error);
+        let transport = FailoverTransport::new(
+            vec![first.clone(), second.clone()],
+            Some(1000),
+            2,
+            Duration::from_millis(1),
+            REQUEST_TIMEOUT,
+        );
+        assert_eq!(block_number(transport.clone()).await.unwrap(), 0x80);
+        assert_eq!((first.requests(), second.requests()), (3, 2));
+
+        let transport = FailoverTransport::new(
+            vec![FlakyTransport::new(u32::MAX, unreachable_error)],
+            None,
+            2,
+            Duration::from_millis(1),
+            REQUEST_TIMEOUT,
+        );
+        assert!(block_number(transport).await.is_err());
+
+        let endpoint = FlakyTransport::new(1, invalid_request_error);
+        let transport = FailoverTransport::new(
+            vec![endpoint.clone(), FlakyTransport::new(0, unreachable_error)],
+            None,
+            2,
+            Duration::from_millis(1),
+            REQUEST_TIMEOUT,
+        );
+        assert!(block_number(transport).await.is_err());
+        assert_eq!(endpoint.requests(), 1);
+    }
+
+    /// Checks that the endpoint that doesn't respond is treated as the failed one and the request is sent to the next one.
+    #[tokio::test]
+    async fn failover_from_the_unresponsive_endpoint() {
+        let first = FlakyTransport::hanging();
+        let second = FlakyTransport::new(0, unreachable_error);
+        let transport = FailoverTransport::new(
+            vec![first.clone(), second.clone()],
+            None,
+            0,
+            Duration::from_millis(1),
+            Duration::from_millis(50),
+        );
+
+        let response = tokio::time::timeout(REQUEST_TIMEOUT, block_number(transport.clone()))
+            .await
+            .expect("request wasn't failed over to the next endpoint");
+        assert_eq!(response.unwrap(), 0x80);
+        assert_eq!(transport.active_endpoint(), 1);
+        assert_eq!((first.requests(), second.requests()), (1, 1));
+
+        // Once all of the endpoints hang, the request fails instead of waiting forever.
+        let transport = FailoverTransport::new(
+            vec![first.clone()],
+            None,
+            1,
+            Duration::from_millis(1),
+            Duration::from_millis(50),
+        );
+        let err = tokio::time::timeout(REQUEST_TIMEOUT, block_number(transport))
+            .await
+            .expect("request to the unresponsive endpoint wasn't timed out")
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            web3::Error::Transport(TransportError::Message(_))
+        ));
+        assert_eq!(first.requests(), 3);
+    }
+}
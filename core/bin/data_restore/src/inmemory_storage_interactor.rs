@@ -244,6 +244,7 @@ impl InMemoryStorageInteractor {
             id,
             address,
             eth_block_number: _,
+            eth_tx_hash: _,
         } in tokens
         {
             inner.tokens.insert(
@@ -263,6 +264,16 @@ impl InMemoryStorageInteractor {
         inner.storage_state = StorageUpdateState::Events;
     }
 
+    pub async fn get_restore_checkpoint(&mut self) -> Option<u64> {
+        let inner = self.inner.borrow();
+        // Genesis block is always stored before any other state.
+        if inner.last_watched_block == 0 {
+            None
+        } else {
+            Some(inner.last_watched_block)
+        }
+    }
+
     pub async fn save_genesis_tree_state(
         &mut self,
         genesis_updates: &[(AccountId, AccountUpdate)],
@@ -286,6 +297,7 @@ impl InMemoryStorageInteractor {
             verified_events,
             last_watched_eth_block_number: inner.last_watched_block,
             priority_op_data: Default::default(),
+            decoded_events: 0,
         }
     }
 
@@ -4,7 +4,10 @@ pub mod database_storage_interactor;
 pub mod eth_tx_helpers;
 pub mod events;
 pub mod events_state;
+pub mod failover_transport;
 pub mod inmemory_storage_interactor;
+pub mod logs_fetcher;
+pub mod progress;
 pub mod rollup_ops;
 pub mod storage_interactor;
 pub mod tree_state;
@@ -12,12 +15,28 @@ pub mod tree_state;
 #[cfg(test)]
 mod tests;
 
+use std::time::Duration;
+
 use crate::storage_interactor::StorageInteractor;
 use zksync_types::{tokens::get_genesis_token_list, TokenId};
 
 // How many blocks we will process at once.
-pub const ETH_BLOCKS_STEP: u64 = 10_000;
+pub const ETH_BLOCKS_STEP: u64 = 100_000;
+// Max amount of blocks requested in a single `eth_getLogs` call.
+pub const MAX_LOGS_RANGE_SIZE: u64 = 10_000;
+// How many requests to the Ethereum node are sent concurrently.
+pub const ETH_REQUESTS_CONCURRENCY: usize = 8;
 pub const END_ETH_BLOCKS_OFFSET: u64 = 40;
+// Delay before the first retry of the request failed by all the Ethereum nodes.
+pub const ETH_REQUEST_RETRY_INTERVAL: Duration = Duration::from_secs(1);
+// Time the Ethereum node has to respond before the request is sent to the next one.
+pub const ETH_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+// Delay before the retry of the failed update of the events state.
+pub const EVENTS_UPDATE_RETRY_INTERVAL: Duration = Duration::from_secs(5);
+// How many times in a row the update of the events state may fail before the restore is stopped.
+pub const MAX_EVENTS_UPDATE_ATTEMPTS: u32 = 10;
+// Delay before checking the Ethereum node for the new blocks.
+pub const NEW_BLOCKS_POLL_INTERVAL: Duration = Duration::from_secs(5);
 
 pub async fn add_tokens_to_storage(interactor: &mut StorageInteractor<'_>, eth_network: &str) {
     let genesis_tokens = get_genesis_token_list(eth_network).expect("Initial token list not found");
@@ -0,0 +1,289 @@
+// Built-in deps
+use std::sync::atomic::{AtomicU64, Ordering};
+// External deps
+use anyhow::format_err;
+use futures::{stream, StreamExt, TryStreamExt};
+use web3::types::{BlockNumber as Web3BlockNumber, FilterBuilder, Log, H256};
+use web3::{Transport, Web3};
+// Workspace deps
+use zksync_types::Address;
+
+/// Parts of the errors returned by the Ethereum nodes when the block range of `eth_getLogs`
+/// contains too many logs.
+const LIMIT_ERRORS: &[&str] = &[
+    "query returned more than",
+    "response size exceeded",
+    "block range is too wide",
+    "range limit exceeded",
+];
+
+fn is_limit_error(err: &web3::Error) -> bool {
+    let message = err.to_string();
+    LIMIT_ERRORS.iter().any(|limit| message.contains(limit))
+}
+
+/// Fetches the contract logs by splitting the block range into the smaller ones
+/// requested concurrently.
+///
+/// The size of the requested ranges adapts to the limits of the Ethereum node: it's halved
+/// every time the node refuses to return the logs of the range and doubled after every
+/// successful request, up to the `max_range_size`. The size is shared between the fetches,
+/// so the limit discovered by one of them is respected by the following ones.
+#[derive(Debug)]
+pub struct LogsFetcher {
+    /// Max amount of the concurrent requests.
+    concurrency: usize,
+    /// Size of the next requested block range.
+    range_size: AtomicU64,
+    max_range_size: u64,
+}
+
+impl LogsFetcher {
+    /// Returns a fetcher starting with the `max_range_size` ranges.
+    ///
+    /// # Arguments
+    ///
+    /// * `concurrency` - Max amount of the concurrent requests
+    /// * `max_range_size` - Max amount of blocks in the requested range
+    ///
+    pub fn new(concurrency: usize, max_range_size: u64) -> Self {
+        assert!(
+            concurrency > 0,
+            "at least one concurrent request is required"
+        );
+        assert!(max_range_size > 0, "block range can't be empty");
+        Self {
+            concurrency,
+            range_size: AtomicU64::new(max_range_size),
+            max_range_size,
+        }
+    }
+
+    /// Max amount of the concurrent requests.
+    pub fn concurrency(&self) -> usize {
+        self.concurrency
+    }
+
+    /// Size of the next requested block range.
+    pub fn range_size(&self) -> u64 {
+        self.range_size.load(Ordering::Relaxed)
+    }
+
+    fn shrink_range(&self, failed_range_size: u64) {
+        let range_size = (failed_range_size / 2).max(1);
+        self.range_size.fetch_min(range_size, Ordering::Relaxed);
+    }
+
+    fn grow_range(&self) {
+        let max_range_size = self.max_range_size;
+        let _ = self
+            .range_size
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |range_size| {
+                Some(range_size.saturating_mul(2).min(max_range_size))
+            });
+    }
+
+    /// Returns the logs of the contract with any of the given topics emitted in the blocks
+    /// from `from` to `to` (inclusive), ordered by the block number.
+    ///
+    /// Every range is decoded with `decode` as soon as it's fetched, while the following
+    /// ranges are still being requested.
+    ///
+    /// # Arguments
+    ///
+    /// * `web3` - Web3 provider
+    /// * `address` - Contract address
+    /// * `topics` - Topics of the logs, any of them matches
+    /// * `from` - Start of the block range
+    /// * `to` - End of the block range (inclusive)
+    /// * `decode` - Decoding of the fetched logs
+    ///
+    pub async fn fetch_logs<T, R, F>(
+        &self,
+        web3: &Web3<T>,
+        address: Address,
+        topics: Vec<H256>,
+        from: u64,
+        to: u64,
+        decode: F,
+    ) -> anyhow::Result<Vec<R>>
+    where
+        T: Transport,
+        F: Fn(Log) -> anyhow::Result<R>,
+    {
+        // Ranges are produced lazily, so every new range uses the size adapted
+        // by the responses received by the moment.
+        let ranges = stream::unfold(from, |range_start| async move {
+            if range_start > to {
+                return None;
+            }
+            let range_end = range_start.saturating_add(self.range_size() - 1).min(to);
+            Some(((range_start, range_end), range_end + 1))
+        });
+
+        let decode = &decode;
+        let topics = &topics;
+        let chunks: Vec<Vec<R>> = ranges
+            .map(|(range_start, range_end)| async move {
+                self.fetch_range(web3, address, topics, range_start, range_end)
+                    .await?
+                    .into_iter()
+                    .map(decode)
+                    .collect::<anyhow::Result<Vec<R>>>()
+            })
+            .buffered(self.concurrency)
+            .try_collect()
+            .await?;
+
+        Ok(chunks.into_iter().flatten().collect())
+    }
+
+    /// Fetches the logs of the range, splitting it further if the node refuses to return them at once.
+    async fn fetch_range<T: Transport>(
+        &self,
+        web3: &Web3<T>,
+        address: Address,
+        topics: &[H256],
+        from: u64,
+        to: u64,
+    ) -> anyhow::Result<Vec<Log>> {
+        let mut logs = Vec::new();
+        let mut range_start = from;
+        let mut range_size = to - from + 1;
+
+        while range_start <= to {
+            let range_end = range_start.saturating_add(range_size - 1).min(to);
+            let filter = FilterBuilder::default()
+                .address(vec![address])
+                .from_block(Web3BlockNumber::Number(range_start.into()))
+                .to_block(Web3BlockNumber::Number(range_end.into()))
+                .topics(Some(topics.to_vec()), None, None, None)
+                .build();
+
+            match web3.eth().logs(filter).await {
+                Ok(range_logs) => {
+                    logs.extend(range_logs);
+                    range_start = range_end + 1;
+                    self.grow_range();
+                }
+                Err(err) if is_limit_error(&err) => {
+                    if range_end == range_start {
+                        return Err(format_err!(
+                            "Ethereum node failed to return logs for a single block: {}",
+                            err
+                        ));
+                    }
+                    range_size = (range_end - range_start + 1) / 2;
+                    self.shrink_range(range_end - range_start + 1);
+                    vlog::debug!(
+                        "Too many logs in the blocks {}-{}, the range is shortened to {} blocks",
+                        range_start,
+                        range_end,
+                        range_size
+                    );
+                }
+                Err(err) => return Err(format_err!("No new logs: {}", err)),
+            }
+        }
+        Ok(logs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::future;
+    use serde_json::{json, Value};
+    use web3::{types::Bytes, RequestId};
+
+    use crate::tests::utils::{create_log, u32_to_32bytes};
+
+    /// Transport returning a log per block, unless more than `max_range` blocks are requested.
+    #[derive(Debug, Clone)]
+    struct RangeLimitedTransport {
+        max_range: u64,
+    }
+
+    impl RangeLimitedTransport {
+        fn get_logs(&self, filter: &Value) -> Result<Value, web3::Error> {
+            let block = |key: &str| {
+                u64::from_str_radix(filter[key].as_str().unwrap().trim_start_matches("0x"), 16)
+                    .unwrap()
+            };
+            let (from, to) = (block("fromBlock"), block("toBlock"));
+            if to - from + 1 > self.max_range {
+                return Err(web3::Error::Rpc(jsonrpc_core::Error {
+                    code: jsonrpc_core::ErrorCode::ServerError(-32005),
+                    message: "query returned more than 10000 results".into(),
+                    data: None,
+                }));
+            }
+
+            let logs: Vec<Log> = (from..=to)
+                .map(|block| {
+                    create_log(
+                        Address::zero(),
+                        H256::zero(),
+                        vec![],
+                        Bytes(vec![]),
+                        block as u32,
+                        u32_to_32bytes(block as u32).into(),
+                    )
+                })
+                .collect();
+            Ok(json!(logs))
+        }
+    }
+
+    impl Transport for RangeLimitedTransport {
+        type Out = future::Ready<Result<Value, web3::Error>>;
+
+        fn prepare(&self, method: &str, params: Vec<Value>) -> (RequestId, jsonrpc_core::Call) {
+            (
+                1,
+                jsonrpc_core::Call::MethodCall(jsonrpc_core::MethodCall {
+                    jsonrpc: Some(jsonrpc_core::Version::V2),
+                    method: method.to_string(),
+                    params: jsonrpc_core::Params::Array(params),
+                    id: jsonrpc_core::Id::Num(1),
+                }),
+            )
+        }
+
+        fn send(&self, _id: RequestId, request: jsonrpc_core::Call) -> Self::Out {
+            future::ready(match request {
+                jsonrpc_core::Call::MethodCall(jsonrpc_core::MethodCall {
+                    method,
+                    params: jsonrpc_core::Params::Array(params),
+                    ..
+                }) if method == "eth_getLogs" => self.get_logs(&params[0]),
+                _ => Err(web3::Error::Unreachable),
+            })
+        }
+    }
+
+    /// Checks that the logs of all the blocks are fetched in order despite the node limits,
+    /// and the range size adapts to them.
+    #[tokio::test]
+    async fn fetch_logs_with_range_limit() {
+        let web3 = Web3::new(RangeLimitedTransport { max_range: 7 });
+        let fetcher = LogsFetcher::new(4, 100);
+
+        let blocks = fetcher
+            .fetch_logs(&web3, Address::zero(), vec![H256::zero()], 10, 250, |log| {
+                Ok(log.block_number.unwrap().as_u64())
+            })
+            .await
+            .unwrap();
+        assert_eq!(blocks, (10..=250).collect::<Vec<_>>());
+        assert!(fetcher.range_size() <= 14);
+
+        // A single block exceeding the limit can't be fetched.
+        let web3 = Web3::new(RangeLimitedTransport { max_range: 0 });
+        let err = fetcher
+            .fetch_logs(&web3, Address::zero(), vec![H256::zero()], 1, 10, Ok)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("single block"));
+    }
+}
@@ -3,6 +3,7 @@ use structopt::StructOpt;
 use web3::transports::Http;
 use zksync_config::configs::{ChainConfig, ContractsConfig as EnvContractsConfig, ETHClientConfig};
 use zksync_crypto::convert::FeConvert;
+use zksync_prometheus_exporter::{run_prometheus_exporter, GlobalLabels};
 use zksync_storage::ConnectionPool;
 use zksync_types::{Address, H256};
 
@@ -10,8 +11,9 @@ use web3::Web3;
 use zksync_data_restore::contract::ZkSyncDeployedContract;
 use zksync_data_restore::{
     add_tokens_to_storage, data_restore_driver::DataRestoreDriver,
-    database_storage_interactor::DatabaseStorageInteractor, storage_interactor::StorageInteractor,
-    END_ETH_BLOCKS_OFFSET, ETH_BLOCKS_STEP,
+    database_storage_interactor::DatabaseStorageInteractor, failover_transport::FailoverTransport,
+    inmemory_storage_interactor::InMemoryStorageInteractor, storage_interactor::StorageInteractor,
+    END_ETH_BLOCKS_OFFSET, ETH_BLOCKS_STEP, ETH_REQUEST_RETRY_INTERVAL, ETH_REQUEST_TIMEOUT,
 };
 use zksync_types::network::Network;
 
@@ -22,11 +24,13 @@ use zksync_types::network::Network;
     rename_all = "snake_case"
 )]
 struct Opt {
-    /// Restores data with provided genesis (zero) block
+    /// Restores data with provided genesis (zero) block. If the database contains
+    /// the progress of the interrupted restore, it's continued instead
     #[structopt(long)]
     genesis: bool,
 
-    /// Continues data restoring
+    /// Continues data restoring. Restore is continued without this flag as well,
+    /// once the database contains its progress
     #[structopt(long = "continue", name = "continue")]
     continue_mode: bool,
 
@@ -34,17 +38,44 @@ struct Opt {
     #[structopt(long)]
     finite: bool,
 
+    /// Restore the state in memory until the last verified block and check the root hash of every block
+    /// against the committed one, without using the database. Exits with an error on the first mismatch,
+    /// or with the exit code 2 if the root hashes of some blocks are not committed and can't be checked
+    #[structopt(long)]
+    audit: bool,
+
     /// Expected tree root hash after restoring. This argument is ignored if mode is not `finite`
     #[structopt(long)]
     final_hash: Option<String>,
 
-    /// Sets the web3 API to be used to interact with the Ethereum blockchain
+    /// Sets the web3 API to be used to interact with the Ethereum blockchain. Can be repeated
+    /// to provide the fallback endpoints, used once the previous ones fail
     #[structopt(long = "web3", name = "web3")]
-    web3_url: Option<String>,
+    web3_url: Vec<String>,
 
     /// Provides a path to the configuration file for data restore
     #[structopt(long = "config", name = "config")]
     config_path: Option<String>,
+
+    /// Check the configuration consistency and print the effective configuration instead of restoring the data
+    #[structopt(long)]
+    validate_config: bool,
+
+    /// Max amount of the concurrent requests to the Ethereum node
+    #[structopt(long, default_value = "8")]
+    eth_requests_concurrency: usize,
+
+    /// Max amount of the requests to every Ethereum node per second, not limited by default
+    #[structopt(long)]
+    eth_requests_per_second: Option<u32>,
+
+    /// How many times the request is retried once all of the Ethereum nodes have failed it
+    #[structopt(long, default_value = "8")]
+    eth_request_retries: usize,
+
+    /// Exposes the restore progress metrics in the Prometheus format on the given port
+    #[structopt(long)]
+    metrics_port: Option<u16>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -79,20 +110,78 @@ impl ContractsConfig {
     }
 }
 
+/// Restores the state in memory and checks the root hash of every executed block
+/// against the one committed to the contract. Exits with an error on the first mismatch,
+/// the blocks without the committed root hash are reported as unverified.
+async fn audit(mut driver: DataRestoreDriver<FailoverTransport<Http>>, config: &ContractsConfig) {
+    vlog::info!("Auditing the committed root hashes, the database is not used");
+    let mut interactor = StorageInteractor::InMemory(InMemoryStorageInteractor::new());
+    add_tokens_to_storage(&mut interactor, &config.eth_network.to_string()).await;
+    driver
+        .set_genesis_state_from_eth(&mut interactor, config.genesis_tx_hash)
+        .await;
+
+    driver.run_state_update(&mut interactor).await;
+
+    if let Some(mismatch) = driver.root_mismatch {
+        vlog::error!("Audit failed: {}", mismatch);
+        std::process::exit(1);
+    }
+
+    let total_blocks = *driver.tree_state.block_number as usize;
+    let unverified_blocks = &driver.unverified_blocks;
+    if let (Some(first), Some(last)) = (unverified_blocks.first(), unverified_blocks.last()) {
+        vlog::warn!(
+            "Audit incomplete: root hashes of {} out of {} executed blocks (from {} to {}) are not committed to the contract and were not checked, \
+             root hashes of the other {} blocks match the committed ones, final root hash: {:?}",
+            unverified_blocks.len(),
+            total_blocks,
+            first,
+            last,
+            total_blocks - unverified_blocks.len(),
+            driver.tree_state.root_hash()
+        );
+        std::process::exit(2);
+    }
+    vlog::info!(
+        "Audit passed: root hashes of all the {} executed blocks match the committed ones, final root hash: {:?}",
+        total_blocks,
+        driver.tree_state.root_hash()
+    );
+}
+
 #[tokio::main]
 async fn main() {
-    vlog::info!("Restoring zkSync state from the contract");
-    let _vlog_guard = vlog::init();
-    let connection_pool = ConnectionPool::new(Some(1));
-
+    zksync_config::file::load_config_files().expect("failed to load the configuration files");
     let opt = Opt::from_args();
+    if opt.validate_config {
+        let is_valid = zksync_config::print_and_validate(|| ChainConfig::from_env().validate());
+        std::process::exit(if is_valid { 0 } else { 1 });
+    }
 
-    let web3_url = opt.web3_url.unwrap_or_else(|| {
-        let config_opts = ETHClientConfig::from_env();
-        config_opts.web3_url()
-    });
+    vlog::info!("Restoring zkSync state from the contract");
+    let _vlog_guard = vlog::init();
+    if let Some(port) = opt.metrics_port {
+        let global_labels = GlobalLabels::new("data_restore", ChainConfig::from_env().eth.network);
+        run_prometheus_exporter(port, global_labels);
+    }
 
-    let transport = Http::new(&web3_url).expect("failed to start web3 transport");
+    let web3_urls = if opt.web3_url.is_empty() {
+        ETHClientConfig::from_env().web3_url
+    } else {
+        opt.web3_url
+    };
+    let transports = web3_urls
+        .iter()
+        .map(|url| Http::new(url).expect("failed to start web3 transport"))
+        .collect();
+    let transport = FailoverTransport::new(
+        transports,
+        opt.eth_requests_per_second,
+        opt.eth_request_retries,
+        ETH_REQUEST_RETRY_INTERVAL,
+        ETH_REQUEST_TIMEOUT,
+    );
 
     let config = opt
         .config_path
@@ -101,14 +190,14 @@ async fn main() {
 
     vlog::info!("Using the following config: {:#?}", config);
 
-    let finite_mode = opt.finite;
+    // Audit is over once all the executed blocks are checked.
+    let finite_mode = opt.finite || opt.audit;
     let final_hash = if finite_mode {
         opt.final_hash
             .map(|value| FeConvert::from_hex(&value).expect("Can't parse the final hash"))
     } else {
         None
     };
-    let storage = connection_pool.access_storage().await.unwrap();
     let web3 = Web3::new(transport);
     let contract = ZkSyncDeployedContract::version4(web3.eth(), config.contract_addr);
     let mut driver = DataRestoreDriver::new(
@@ -121,11 +210,29 @@ async fn main() {
         finite_mode,
         final_hash,
         contract,
-    );
+    )
+    .with_eth_requests_concurrency(opt.eth_requests_concurrency);
+
+    if opt.audit {
+        audit(driver.with_audit_mode(), &config).await;
+        return;
+    }
 
+    let connection_pool = ConnectionPool::new(Some(1));
+    let storage = connection_pool.access_storage().await.unwrap();
     let mut interactor = StorageInteractor::Database(DatabaseStorageInteractor::new(storage));
+    // Progress of the interrupted restore is stored in the database, so it's resumed
+    // from the checkpoint instead of being started from the genesis again.
+    let checkpoint = interactor.get_restore_checkpoint().await;
+    if let Some(last_watched_eth_block) = checkpoint {
+        vlog::info!(
+            "Resuming the restore from the checkpoint at the Ethereum block {}",
+            last_watched_eth_block
+        );
+    }
+
     // If genesis is argument is present - there will be fetching contracts creation transactions to get first eth block and genesis acc address
-    if opt.genesis {
+    if opt.genesis && checkpoint.is_none() {
         // We have to load pre-defined tokens into the database before restoring state,
         // since these tokens do not have a corresponding Ethereum events.
         add_tokens_to_storage(&mut interactor, &config.eth_network.to_string()).await;
@@ -135,7 +242,9 @@ async fn main() {
             .await;
     }
 
-    if opt.continue_mode && driver.load_state_from_storage(&mut interactor).await {
+    if (opt.continue_mode || checkpoint.is_some())
+        && driver.load_state_from_storage(&mut interactor).await
+    {
         std::process::exit(0);
     }
 
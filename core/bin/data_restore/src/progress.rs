@@ -0,0 +1,140 @@
+// Built-in deps
+use std::time::{Duration, Instant};
+// Workspace deps
+use zksync_types::BlockNumber;
+
+/// State of the restore at the end of the step.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProgressSnapshot {
+    /// Last Ethereum block whose events are processed.
+    pub last_watched_eth_block: u64,
+    /// Latest block of the Ethereum network.
+    pub last_eth_block: u64,
+    /// Amount of the contract events decoded since the start.
+    pub decoded_events: u64,
+    /// Last zkSync block applied to the tree.
+    pub restored_block: BlockNumber,
+    /// Amount of the zkSync blocks verified on the contract.
+    pub total_verified_blocks: u32,
+}
+
+/// Keeps track of the restore progress, reporting it to the logs and the metrics.
+///
+/// The estimated time is extrapolated from the speed of processing the Ethereum blocks
+/// since the start, so it becomes accurate after a few steps.
+#[derive(Debug)]
+pub struct RestoreProgress {
+    started_at: Instant,
+    /// Ethereum block the restore was (re)started from.
+    start_eth_block: u64,
+}
+
+impl RestoreProgress {
+    pub fn new(start_eth_block: u64) -> Self {
+        Self {
+            started_at: Instant::now(),
+            start_eth_block,
+        }
+    }
+
+    /// Share of the verified blocks applied to the tree, in percents.
+    pub fn tree_progress(snapshot: &ProgressSnapshot) -> f64 {
+        if snapshot.total_verified_blocks == 0 {
+            return 100.0;
+        }
+        (f64::from(*snapshot.restored_block) / f64::from(snapshot.total_verified_blocks) * 100.0)
+            .min(100.0)
+    }
+
+    /// Expected time until all of the Ethereum blocks are processed,
+    /// unknown until there's any progress since the start.
+    pub fn eta(&self, snapshot: &ProgressSnapshot, elapsed: Duration) -> Option<Duration> {
+        let processed = snapshot
+            .last_watched_eth_block
+            .checked_sub(self.start_eth_block)
+            .filter(|processed| *processed > 0)?;
+        let remaining = snapshot
+            .last_eth_block
+            .saturating_sub(snapshot.last_watched_eth_block);
+        Some(elapsed.mul_f64(remaining as f64 / processed as f64))
+    }
+
+    /// Reports the restore progress to the logs and the metrics.
+    pub fn report(&self, snapshot: ProgressSnapshot) {
+        let tree_progress = Self::tree_progress(&snapshot);
+        let eta = self.eta(&snapshot, self.started_at.elapsed());
+
+        vlog::info!(
+            "Restore progress: Ethereum block {} of {}, events decoded: {}, block {} of {} verified restored ({:.2}%), ETA: {}",
+            snapshot.last_watched_eth_block,
+            snapshot.last_eth_block,
+            snapshot.decoded_events,
+            *snapshot.restored_block,
+            snapshot.total_verified_blocks,
+            tree_progress,
+            eta.map(|eta| format!("{}s", eta.as_secs()))
+                .unwrap_or_else(|| "unknown".to_string())
+        );
+
+        metrics::gauge!(
+            "data_restore.last_watched_eth_block",
+            snapshot.last_watched_eth_block as f64
+        );
+        metrics::gauge!(
+            "data_restore.last_eth_block",
+            snapshot.last_eth_block as f64
+        );
+        metrics::gauge!(
+            "data_restore.decoded_events",
+            snapshot.decoded_events as f64
+        );
+        metrics::gauge!(
+            "data_restore.restored_block",
+            f64::from(*snapshot.restored_block)
+        );
+        metrics::gauge!(
+            "data_restore.total_verified_blocks",
+            f64::from(snapshot.total_verified_blocks)
+        );
+        metrics::gauge!("data_restore.tree_progress", tree_progress);
+        if let Some(eta) = eta {
+            metrics::gauge!("data_restore.eta_seconds", eta.as_secs_f64());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(last_watched_eth_block: u64, restored_block: u32) -> ProgressSnapshot {
+        ProgressSnapshot {
+            last_watched_eth_block,
+            last_eth_block: 1000,
+            decoded_events: 0,
+            restored_block: BlockNumber(restored_block),
+            total_verified_blocks: 40,
+        }
+    }
+
+    #[test]
+    fn progress_estimation() {
+        let progress = RestoreProgress::new(200);
+        let elapsed = Duration::from_secs(60);
+
+        // No progress since the start, nothing to extrapolate.
+        assert_eq!(progress.eta(&snapshot(200, 0), elapsed), None);
+        // A quarter of the remaining blocks took a minute, so three more are expected.
+        assert_eq!(
+            progress.eta(&snapshot(400, 10), elapsed),
+            Some(Duration::from_secs(180))
+        );
+        assert_eq!(
+            progress.eta(&snapshot(1000, 40), elapsed),
+            Some(Duration::ZERO)
+        );
+
+        assert_eq!(RestoreProgress::tree_progress(&snapshot(400, 10)), 25.0);
+        assert_eq!(RestoreProgress::tree_progress(&snapshot(1000, 40)), 100.0);
+    }
+}
@@ -20,6 +20,9 @@ pub struct RollupOpsBlock {
     pub timestamp: Option<u64>,
     /// Previous block root hash.
     pub previous_block_root_hash: H256,
+    /// Root hash of the block committed to the contract. Not known for the blocks
+    /// committed to the contracts older than v4 and the blocks loaded from the database.
+    pub root_hash: Option<H256>,
     /// zkSync contract version for the given block.
     /// Used to obtain block chunk sizes. Stored in the database
     /// in the corresponding block event.
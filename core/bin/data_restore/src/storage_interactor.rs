@@ -163,6 +163,13 @@ impl StorageInteractor<'_> {
         storage_interact!(self.save_special_token(token))
     }
 
+    /// Returns the last watched ethereum block number of the stored restore progress,
+    /// or `None` if the restore wasn't started yet. Restore can be resumed from it
+    /// with `DataRestoreDriver::load_state_from_storage`.
+    pub async fn get_restore_checkpoint(&mut self) -> Option<u64> {
+        storage_interact!(self.get_restore_checkpoint())
+    }
+
     /// Returns Rollup contract events state from storage
     pub async fn get_block_events_state_from_storage(&mut self) -> EventsState {
         storage_interact!(self.get_block_events_state_from_storage())
@@ -277,6 +284,7 @@ pub fn stored_ops_block_into_ops_block(op_block: StoredRollupOpsBlock) -> Rollup
             .previous_block_root_hash
             .map(|h| H256::from_slice(&h))
             .unwrap_or_default(),
+        root_hash: None,
         contract_version: Some(
             ZkSyncContractVersion::try_from(op_block.contract_version as u32)
                 .expect("invalid contract version in the database"),
@@ -1,7 +1,7 @@
 pub(crate) mod utils;
 
 use std::cmp::max;
-use std::{collections::HashMap, future::Future};
+use std::{collections::HashMap, future::Future, time::Duration};
 
 use chrono::Utc;
 use futures::future;
@@ -196,6 +196,8 @@ pub(crate) struct Web3Transport {
     transactions: HashMap<String, Transaction>,
     logs: HashMap<String, Vec<Log>>,
     last_block: u32,
+    /// Whether all the requests fail, as if the Ethereum node is down.
+    unavailable: bool,
 }
 
 impl Web3Transport {
@@ -204,8 +206,17 @@ impl Web3Transport {
             transactions: HashMap::default(),
             logs: HashMap::default(),
             last_block: 0,
+            unavailable: false,
         }
     }
+
+    fn unavailable() -> Self {
+        Self {
+            unavailable: true,
+            ..Self::new()
+        }
+    }
+
     fn push_transactions(&mut self, transactions: Vec<Transaction>) {
         for transaction in transactions {
             self.last_block = max(transaction.block_number.unwrap().as_u32(), self.last_block);
@@ -259,7 +270,9 @@ impl Transport for Web3Transport {
 
     fn send(&self, _id: RequestId, request: jsonrpc_core::Call) -> Self::Out {
         Box::new(future::ready({
-            if let jsonrpc_core::Call::MethodCall(req) = request {
+            if self.unavailable {
+                Err(web3::Error::Unreachable)
+            } else if let jsonrpc_core::Call::MethodCall(req) = request {
                 let mut params = if let Params::Array(params) = req.params {
                     params
                 } else {
@@ -445,6 +458,12 @@ async fn test_run_state_update(mut storage: StorageProcessor<'_>) {
 
     assert_eq!(driver.events_state.committed_events.len(), events.len());
 
+    // Restore progress is stored as the checkpoint to resume from.
+    assert_eq!(
+        interactor.get_restore_checkpoint().await,
+        Some(driver.events_state.last_watched_eth_block_number)
+    );
+
     // Nullify the state of driver
     let eth = Eth::new(transport.clone());
 
@@ -466,6 +485,69 @@ async fn test_run_state_update(mut storage: StorageProcessor<'_>) {
     assert_eq!(*driver.tree_state.block_number, 2)
 }
 
+/// Checks that the audit reports the blocks without the committed root hash as unverified
+/// and records the first mismatching block.
+#[test]
+fn audit_root_hash_check() {
+    let transport = Web3Transport::new();
+    let eth = Eth::new(transport.clone());
+    let mut driver = DataRestoreDriver::new(
+        Web3::new(transport),
+        [1u8; 20].into(),
+        Vec::new(),
+        4,
+        ETH_BLOCKS_STEP,
+        END_ETH_BLOCKS_OFFSET,
+        true,
+        None,
+        ZkSyncDeployedContract::version4(eth, [1u8; 20].into()),
+    )
+    .with_audit_mode();
+
+    let blocks: Vec<_> = (1..=4)
+        .map(|number| create_block(BlockNumber(number), Vec::new()))
+        .collect();
+    let committed_root = blocks[0].get_eth_encoded_root();
+
+    driver.check_root_hash(&blocks[0], Some(committed_root));
+    driver.check_root_hash(&blocks[1], None);
+    assert_eq!(driver.root_mismatch, None);
+    assert_eq!(driver.unverified_blocks, vec![BlockNumber(2)]);
+
+    // Only the first mismatch is recorded.
+    let wrong_root = H256::repeat_byte(1);
+    driver.check_root_hash(&blocks[2], Some(wrong_root));
+    driver.check_root_hash(&blocks[3], Some(wrong_root));
+    let mismatch = driver.root_mismatch.unwrap();
+    assert_eq!(mismatch.block_number, BlockNumber(3));
+    assert_eq!(mismatch.computed_root, blocks[2].get_eth_encoded_root());
+    assert_eq!(mismatch.committed_root, wrong_root);
+    assert_eq!(driver.unverified_blocks, vec![BlockNumber(2)]);
+}
+
+/// Checks that the failed updates of the events state are retried a limited amount of times.
+#[tokio::test]
+#[should_panic(expected = "Failed to update the events state")]
+async fn events_update_retries_are_bounded() {
+    let transport = Web3Transport::unavailable();
+    let eth = Eth::new(transport.clone());
+    let mut driver = DataRestoreDriver::new(
+        Web3::new(transport),
+        [1u8; 20].into(),
+        Vec::new(),
+        4,
+        ETH_BLOCKS_STEP,
+        END_ETH_BLOCKS_OFFSET,
+        true,
+        None,
+        ZkSyncDeployedContract::version4(eth, [1u8; 20].into()),
+    )
+    .with_events_update_retry_interval(Duration::from_millis(1));
+
+    let mut interactor = StorageInteractor::InMemory(InMemoryStorageInteractor::new());
+    driver.run_state_update(&mut interactor).await;
+}
+
 // TODO: Find a way to restore this test (ZKS-694)
 #[tokio::test]
 #[ignore]
@@ -627,6 +627,7 @@ mod test {
             fee_account: AccountId(0),
             timestamp: None,
             previous_block_root_hash: Default::default(),
+            root_hash: None,
             contract_version: None,
         };
 
@@ -654,6 +655,7 @@ mod test {
             fee_account: AccountId(0),
             timestamp: None,
             previous_block_root_hash: Default::default(),
+            root_hash: None,
             contract_version: None,
         };
 
@@ -682,6 +684,7 @@ mod test {
             fee_account: AccountId(0),
             timestamp: None,
             previous_block_root_hash: Default::default(),
+            root_hash: None,
             contract_version: None,
         };
 
@@ -710,6 +713,7 @@ mod test {
             fee_account: AccountId(0),
             timestamp: None,
             previous_block_root_hash: Default::default(),
+            root_hash: None,
             contract_version: None,
         };
 
@@ -738,6 +742,7 @@ mod test {
             fee_account: AccountId(0),
             timestamp: None,
             previous_block_root_hash: Default::default(),
+            root_hash: None,
             contract_version: None,
         };
 
@@ -764,6 +769,7 @@ mod test {
             fee_account: AccountId(0),
             timestamp: None,
             previous_block_root_hash: Default::default(),
+            root_hash: None,
             contract_version: None,
         };
 
@@ -790,6 +796,7 @@ mod test {
             fee_account: AccountId(1),
             timestamp: None,
             previous_block_root_hash: Default::default(),
+            root_hash: None,
             contract_version: None,
         };
         // This transaction have to be deleted, do not uncomment. Delete it after removing the corresponding code        // let tx6 = Close {
@@ -997,6 +1004,7 @@ mod test {
             fee_account: AccountId(0),
             timestamp: None,
             previous_block_root_hash: Default::default(),
+            root_hash: None,
             contract_version: None,
         };
 
@@ -0,0 +1,193 @@
+use std::{
+    fs::{self, File},
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{ensure, format_err};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use structopt::StructOpt;
+use zksync_storage::{backup::BACKUP_TABLES, StorageProcessor};
+
+/// Version of the backup format, increased on incompatible changes of the layout.
+const BACKUP_VERSION: u32 = 2;
+/// Name of the file describing the backup.
+const MANIFEST_FILE: &str = "manifest.json";
+/// Amount of rows loaded from or inserted into the database at once.
+const BATCH_SIZE: usize = 1000;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TableManifest {
+    name: String,
+    rows: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Manifest {
+    version: u32,
+    created_at: DateTime<Utc>,
+    tables: Vec<TableManifest>,
+}
+
+fn table_file(dir: &Path, table: &str) -> PathBuf {
+    dir.join(format!("{}.jsonl", table))
+}
+
+async fn export(storage: &mut StorageProcessor<'_>, dir: &Path) -> anyhow::Result<()> {
+    fs::create_dir_all(dir)?;
+    ensure!(
+        !dir.join(MANIFEST_FILE).exists(),
+        "Directory {} already contains a backup",
+        dir.display()
+    );
+
+    let mut transaction = storage.start_transaction().await?;
+    transaction.backup_schema().begin_snapshot().await?;
+
+    let mut tables = Vec::new();
+    for &table in BACKUP_TABLES {
+        let mut writer = BufWriter::new(File::create(table_file(dir, table))?);
+        let mut rows = 0u64;
+
+        transaction.backup_schema().open_table(table).await?;
+        loop {
+            let batch = transaction.backup_schema().fetch_rows(BATCH_SIZE).await?;
+            if batch.is_empty() {
+                break;
+            }
+            for row in &batch {
+                serde_json::to_writer(&mut writer, row)?;
+                writer.write_all(b"\n")?;
+            }
+            rows += batch.len() as u64;
+        }
+        writer.flush()?;
+
+        println!("`{}` table is exported, {} rows", table, rows);
+        tables.push(TableManifest {
+            name: table.to_string(),
+            rows,
+        });
+    }
+    transaction.commit().await?;
+
+    // Manifest is written last, so an interrupted export is never mistaken for a complete one.
+    let manifest = Manifest {
+        version: BACKUP_VERSION,
+        created_at: Utc::now(),
+        tables,
+    };
+    serde_json::to_writer_pretty(File::create(dir.join(MANIFEST_FILE))?, &manifest)?;
+    println!("Backup is saved to {}", dir.display());
+    Ok(())
+}
+
+async fn restore(storage: &mut StorageProcessor<'_>, dir: &Path) -> anyhow::Result<()> {
+    let manifest: Manifest = serde_json::from_reader(BufReader::new(
+        File::open(dir.join(MANIFEST_FILE))
+            .map_err(|err| format_err!("Unable to open the backup manifest: {}", err))?,
+    ))?;
+    ensure!(
+        manifest.version == BACKUP_VERSION,
+        "Unsupported backup version {}, expected {}",
+        manifest.version,
+        BACKUP_VERSION
+    );
+    for table in &manifest.tables {
+        ensure!(
+            BACKUP_TABLES.contains(&table.name.as_str()),
+            "Backup contains unknown table `{}`",
+            table.name
+        );
+    }
+
+    let mut transaction = storage.start_transaction().await?;
+    for table in &manifest.tables {
+        let rows = transaction.backup_schema().count_rows(&table.name).await?;
+        ensure!(
+            rows == 0,
+            "`{}` table is not empty, backup can only be restored into a fresh database",
+            table.name
+        );
+    }
+
+    for table in &manifest.tables {
+        let reader = BufReader::new(File::open(table_file(dir, &table.name))?);
+        let mut batch = Vec::with_capacity(BATCH_SIZE);
+        for line in reader.lines() {
+            batch.push(serde_json::from_str::<Value>(&line?)?);
+            if batch.len() == BATCH_SIZE {
+                transaction
+                    .backup_schema()
+                    .insert_rows(&table.name, std::mem::take(&mut batch))
+                    .await?;
+            }
+        }
+        if !batch.is_empty() {
+            transaction
+                .backup_schema()
+                .insert_rows(&table.name, batch)
+                .await?;
+        }
+        transaction
+            .backup_schema()
+            .reset_sequences(&table.name)
+            .await?;
+
+        let rows = transaction.backup_schema().count_rows(&table.name).await?;
+        ensure!(
+            rows as u64 == table.rows,
+            "`{}` table has {} rows after the restore, {} expected",
+            table.name,
+            rows,
+            table.rows
+        );
+        println!("`{}` table is restored, {} rows", table.name, rows);
+    }
+    transaction.commit().await?;
+
+    println!(
+        "Backup created at {} is restored from {}",
+        manifest.created_at,
+        dir.display()
+    );
+    Ok(())
+}
+
+#[derive(Debug, StructOpt)]
+enum Command {
+    /// Exports the consistent snapshot of the rollup tables to the directory
+    Export {
+        #[structopt(parse(from_os_str))]
+        dir: PathBuf,
+    },
+    /// Restores the rollup tables from the directory into the empty database
+    Restore {
+        #[structopt(parse(from_os_str))]
+        dir: PathBuf,
+    },
+}
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "zkSync database backup tool", author = "Matter Labs")]
+#[structopt(
+    about = "Tool to export the rollup-critical tables of zkSync database and restore them into a fresh database"
+)]
+struct Opt {
+    #[structopt(subcommand)]
+    command: Command,
+}
+
+// TODO: don't use anyhow (ZKS-588)
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let opt = Opt::from_args();
+    let mut storage = StorageProcessor::establish_connection().await?;
+
+    match opt.command {
+        Command::Export { dir } => export(&mut storage, &dir).await,
+        Command::Restore { dir } => restore(&mut storage, &dir).await,
+    }
+}
@@ -0,0 +1,103 @@
+//! Client of the core private and admin APIs.
+
+use std::time::{Duration, UNIX_EPOCH};
+
+use anyhow::format_err;
+use jsonwebtoken::{encode, EncodingKey, Header};
+use reqwest::{Method, RequestBuilder, Response};
+use serde::{de::DeserializeOwned, Serialize};
+
+use zksync_config::configs::api::{AdminApiConfig, PrivateApiConfig};
+
+/// Lifetime of the admin API access tokens issued by the CLI.
+const AUTH_TOKEN_LIFETIME: Duration = Duration::from_secs(60);
+/// Time the core has to respond, so the CLI doesn't hang once the core is unresponsive.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Serialize)]
+struct PayloadAuthToken {
+    /// Subject (whom auth token refers to).
+    sub: String,
+    /// Expiration time (as UTC timestamp).
+    exp: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct CoreApiClient {
+    client: reqwest::Client,
+    private_url: String,
+    admin_url: String,
+    admin_secret: String,
+}
+
+impl CoreApiClient {
+    pub fn from_env() -> anyhow::Result<Self> {
+        let admin_config = AdminApiConfig::from_env();
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()?;
+        Ok(Self {
+            client,
+            private_url: PrivateApiConfig::from_env().url,
+            admin_url: admin_config.url,
+            admin_secret: admin_config.secret_auth,
+        })
+    }
+
+    /// Sends the request to the private API, which is not authorized.
+    pub async fn private<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+    ) -> anyhow::Result<Option<T>> {
+        let request = self
+            .client
+            .request(method, format!("{}{}", self.private_url, path));
+        Self::send(request).await
+    }
+
+    /// Sends the request with the optional JSON `body` to the admin API.
+    pub async fn admin<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<serde_json::Value>,
+    ) -> anyhow::Result<Option<T>> {
+        let mut request = self
+            .client
+            .request(method, format!("{}{}", self.admin_url, path))
+            .bearer_auth(self.auth_token()?);
+        if let Some(body) = body {
+            request = request.json(&body);
+        }
+        Self::send(request).await
+    }
+
+    fn auth_token(&self) -> anyhow::Result<String> {
+        let exp = UNIX_EPOCH.elapsed()? + AUTH_TOKEN_LIFETIME;
+        let payload = PayloadAuthToken {
+            sub: "Authorization".to_string(),
+            exp: exp.as_secs() as usize,
+        };
+        Ok(encode(
+            &Header::default(),
+            &payload,
+            &EncodingKey::from_secret(self.admin_secret.as_ref()),
+        )?)
+    }
+
+    /// Returns the deserialized response, or `None` if it's empty.
+    async fn send<T: DeserializeOwned>(request: RequestBuilder) -> anyhow::Result<Option<T>> {
+        let response: Response = request.send().await?;
+        let status = response.status();
+        let body = response.text().await?;
+        if !status.is_success() {
+            return Err(format_err!("Request failed with {}: {}", status, body));
+        }
+        if body.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(serde_json::from_str(&body)?))
+        }
+    }
+}
@@ -0,0 +1,199 @@
+//! Fee policy updates.
+//!
+//! The fee policy is a part of the API servers configuration which is reloaded on `SIGHUP`.
+//! The updated values are stored in the `operator_overrides.toml` file in the configuration
+//! directory (`ZKSYNC_CONFIG_PATH`), so they're applied on top of the other config files
+//! on reload and survive the restarts.
+
+use std::{env, fs, path::PathBuf};
+
+use anyhow::{bail, ensure, format_err};
+use structopt::StructOpt;
+use toml::{value::Table, Value};
+
+use zksync_config::file::CONFIG_PATH_VAR;
+
+/// Name of the file with the values set by the CLI.
+const OVERRIDES_FILE: &str = "operator_overrides.toml";
+
+/// Settings of the fee policy that can be changed without restarting the API servers.
+const FEE_POLICY_KEYS: &[&str] = &[
+    "api.common.fee_free_accounts",
+    "api.common.enforce_pubkey_change_fee",
+    "api.common.max_number_of_transactions_per_batch",
+    "api.common.max_number_of_authors_per_batch",
+    "api.common.subsidy_name",
+    "api.common.max_subsidy_usd_scaled",
+    "api.common.subsidized_ips",
+    "fee_ticker.fast_processing_coeff",
+    "fee_ticker.scale_fee_percent",
+    "fee_ticker.subsidy_cpk_price_usd_scaled",
+    "fee_ticker.unconditionally_valid_tokens",
+];
+
+#[derive(Debug, StructOpt)]
+pub enum FeePolicyCommand {
+    /// Shows the effective fee policy
+    Show,
+    /// Sets the fee policy value, e.g. `api.common.fee_free_accounts [1, 2]`
+    Set { key: String, value: String },
+    /// Removes the value set by `set`, so the one from the other config files is used
+    Unset { key: String },
+}
+
+pub fn run(command: FeePolicyCommand) -> anyhow::Result<()> {
+    match command {
+        FeePolicyCommand::Show => {
+            for key in FEE_POLICY_KEYS {
+                let value = env::var(variable_name(key)).unwrap_or_else(|_| "-".to_string());
+                println!("{} = {}", key, value);
+            }
+        }
+        FeePolicyCommand::Set { key, value } => {
+            check_key(&key)?;
+            let mut overrides = load_overrides()?;
+            insert(&mut overrides, &key, parse_value(&value));
+            save_overrides(&overrides)?;
+            print_apply_hint(&key);
+        }
+        FeePolicyCommand::Unset { key } => {
+            check_key(&key)?;
+            let mut overrides = load_overrides()?;
+            ensure!(
+                remove(&mut overrides, &key),
+                "{} is not set by the operator",
+                key
+            );
+            save_overrides(&overrides)?;
+            print_apply_hint(&key);
+        }
+    }
+    Ok(())
+}
+
+fn check_key(key: &str) -> anyhow::Result<()> {
+    ensure!(
+        FEE_POLICY_KEYS.contains(&key),
+        "{} is not a fee policy setting, supported ones are: {}",
+        key,
+        FEE_POLICY_KEYS.join(", ")
+    );
+    Ok(())
+}
+
+/// Name of the environment variable corresponding to the config key.
+fn variable_name(key: &str) -> String {
+    key.replace('.', "_").to_uppercase()
+}
+
+fn print_apply_hint(key: &str) {
+    println!(
+        "{} is updated, send SIGHUP to the API servers to apply it. \
+         Note that the value is ignored if `{}` is set in the servers environment.",
+        key,
+        variable_name(key)
+    );
+}
+
+fn overrides_path() -> anyhow::Result<PathBuf> {
+    let config_path = env::var_os(CONFIG_PATH_VAR)
+        .map(PathBuf::from)
+        .ok_or_else(|| format_err!("{} is not set", CONFIG_PATH_VAR))?;
+    if !config_path.is_dir() {
+        bail!(
+            "{} must point to the directory with the config files",
+            CONFIG_PATH_VAR
+        );
+    }
+    Ok(config_path.join(OVERRIDES_FILE))
+}
+
+fn load_overrides() -> anyhow::Result<Table> {
+    let path = overrides_path()?;
+    if !path.exists() {
+        return Ok(Table::new());
+    }
+    Ok(toml::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn save_overrides(overrides: &Table) -> anyhow::Result<()> {
+    fs::write(overrides_path()?, toml::to_string(overrides)?)?;
+    Ok(())
+}
+
+/// Parses the value as TOML (numbers, booleans, arrays), falling back to the plain string.
+fn parse_value(value: &str) -> Value {
+    toml::from_str::<Table>(&format!("value = {}", value))
+        .ok()
+        .and_then(|mut table| table.remove("value"))
+        .unwrap_or_else(|| Value::String(value.to_string()))
+}
+
+fn insert(table: &mut Table, key: &str, value: Value) {
+    match key.split_once('.') {
+        Some((name, rest)) => {
+            let nested = table
+                .entry(name)
+                .or_insert_with(|| Value::Table(Table::new()));
+            if !nested.is_table() {
+                *nested = Value::Table(Table::new());
+            }
+            insert(nested.as_table_mut().unwrap(), rest, value);
+        }
+        None => {
+            table.insert(key.to_string(), value);
+        }
+    }
+}
+
+fn remove(table: &mut Table, key: &str) -> bool {
+    match key.split_once('.') {
+        Some((name, rest)) => match table.get_mut(name).and_then(Value::as_table_mut) {
+            Some(nested) => remove(nested, rest),
+            None => false,
+        },
+        None => table.remove(key).is_some(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overrides_table() {
+        let mut table = Table::new();
+        insert(
+            &mut table,
+            "api.common.fee_free_accounts",
+            parse_value("[1, 2]"),
+        );
+        insert(
+            &mut table,
+            "api.common.subsidy_name",
+            parse_value("Partner"),
+        );
+        insert(
+            &mut table,
+            "fee_ticker.scale_fee_percent",
+            parse_value("150"),
+        );
+
+        let expected: Table = toml::from_str(
+            r#"
+[api.common]
+fee_free_accounts = [1, 2]
+subsidy_name = "Partner"
+
+[fee_ticker]
+scale_fee_percent = 150
+"#,
+        )
+        .unwrap();
+        assert_eq!(table, expected);
+
+        assert!(remove(&mut table, "api.common.subsidy_name"));
+        assert!(!remove(&mut table, "api.common.subsidy_name"));
+        assert!(!remove(&mut table, "api.rest.port"));
+    }
+}
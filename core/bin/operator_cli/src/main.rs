@@ -0,0 +1,56 @@
+//! zkSync operator CLI.
+//!
+//! Covers the common administrative tasks, so they don't require the direct database access:
+//! the changes go through the core private and admin APIs, while the read-only inspection
+//! queries the storage directly.
+
+use structopt::StructOpt;
+
+use crate::{
+    client::CoreApiClient, fee_policy::FeePolicyCommand, mempool::MempoolCommand,
+    priority_ops::PriorityOpsDeadlines, prover::ProverStatus, tokens::TokensCommand,
+};
+
+mod client;
+mod fee_policy;
+mod mempool;
+mod priority_ops;
+mod prover;
+mod tokens;
+
+#[derive(Debug, StructOpt)]
+enum Command {
+    /// Token management
+    Tokens(TokensCommand),
+    /// Fee policy of the API servers
+    FeePolicy(FeePolicyCommand),
+    /// Mempool inspection and clean up
+    Mempool(MempoolCommand),
+    /// Status of the prover jobs queue
+    Prover(ProverStatus),
+    /// Deadlines of the priority operations waiting to be executed
+    PriorityOps(PriorityOpsDeadlines),
+}
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "zksync-operator", author = "Matter Labs")]
+#[structopt(about = "Tool for the common administrative tasks of the zkSync operator")]
+struct Opt {
+    #[structopt(subcommand)]
+    command: Command,
+}
+
+// TODO: don't use anyhow (ZKS-588)
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    zksync_config::file::load_config_files()?;
+    let opt = Opt::from_args();
+
+    match opt.command {
+        Command::Tokens(command) => tokens::run(command, &CoreApiClient::from_env()?).await,
+        Command::FeePolicy(command) => fee_policy::run(command),
+        Command::Mempool(command) => mempool::run(command, &CoreApiClient::from_env()?).await,
+        Command::Prover(command) => prover::run(command).await,
+        Command::PriorityOps(command) => priority_ops::run(command).await,
+    }
+}
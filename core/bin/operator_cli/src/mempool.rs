@@ -0,0 +1,63 @@
+//! Mempool inspection and clean up.
+
+use anyhow::format_err;
+use reqwest::Method;
+use structopt::StructOpt;
+
+use zksync_storage::StorageProcessor;
+use zksync_types::tx::TxHash;
+
+use crate::client::CoreApiClient;
+
+#[derive(Debug, StructOpt)]
+pub enum MempoolCommand {
+    /// Shows the state of the server components: mempool size, pending block, transactions acceptance pause
+    Status,
+    /// Shows the queued transaction
+    Show { tx_hash: TxHash },
+    /// Removes the queued transaction (along with its batch) from the mempool
+    Remove { tx_hash: TxHash },
+    /// Removes the already executed transactions from the mempool
+    Recheck,
+}
+
+pub async fn run(command: MempoolCommand, client: &CoreApiClient) -> anyhow::Result<()> {
+    match command {
+        MempoolCommand::Status => {
+            let state: serde_json::Value = client
+                .admin(Method::GET, "/state", None)
+                .await?
+                .ok_or_else(|| format_err!("Empty response"))?;
+            println!("{}", serde_json::to_string_pretty(&state)?);
+        }
+        MempoolCommand::Show { tx_hash } => {
+            let mut storage = StorageProcessor::establish_connection().await?;
+            let tx = storage
+                .chain()
+                .mempool_schema()
+                .get_tx(tx_hash.as_ref())
+                .await?
+                .ok_or_else(|| format_err!("Transaction is not found in the mempool"))?;
+            println!("{}", serde_json::to_string_pretty(&tx)?);
+        }
+        MempoolCommand::Remove { tx_hash } => {
+            let removed: serde_json::Value = client
+                .admin(
+                    Method::DELETE,
+                    &format!("/mempool/txs/{}", tx_hash.to_string()),
+                    None,
+                )
+                .await?
+                .ok_or_else(|| format_err!("Empty response"))?;
+            println!("Removed transactions: {}", removed["removed"]);
+        }
+        MempoolCommand::Recheck => {
+            let removed: serde_json::Value = client
+                .admin(Method::POST, "/mempool/recheck", None)
+                .await?
+                .ok_or_else(|| format_err!("Empty response"))?;
+            println!("Removed transactions: {}", removed["removed"]);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,52 @@
+//! Deadlines of the priority operations waiting to be executed.
+
+use structopt::StructOpt;
+use web3::{transports::Http, Web3};
+
+use zksync_config::ETHClientConfig;
+use zksync_storage::StorageProcessor;
+
+#[derive(Debug, StructOpt)]
+pub struct PriorityOpsDeadlines {
+    /// Operations with less Ethereum blocks left until the deadline are marked as urgent
+    #[structopt(long, default_value = "1000")]
+    warn_blocks: u64,
+}
+
+pub async fn run(command: PriorityOpsDeadlines) -> anyhow::Result<()> {
+    let mut storage = StorageProcessor::establish_connection().await?;
+    let priority_ops = storage
+        .chain()
+        .mempool_schema()
+        .get_confirmed_priority_ops()
+        .await?;
+
+    let web3 = Web3::new(Http::new(&ETHClientConfig::from_env().web3_url())?);
+    let current_block = web3.eth().block_number().await?.as_u64();
+
+    println!(
+        "{} priority operations are waiting to be executed, current Ethereum block is {}",
+        priority_ops.len(),
+        current_block
+    );
+    for op in priority_ops {
+        let blocks_left = op.deadline_block.saturating_sub(current_block);
+        let mark = if blocks_left == 0 {
+            "EXPIRED"
+        } else if blocks_left < command.warn_blocks {
+            "URGENT"
+        } else {
+            ""
+        };
+        println!(
+            "  #{} {} eth tx {:?}: deadline block {}, {} blocks left {}",
+            op.serial_id,
+            op.data.variance_name(),
+            op.eth_hash,
+            op.deadline_block,
+            blocks_left,
+            mark
+        );
+    }
+    Ok(())
+}
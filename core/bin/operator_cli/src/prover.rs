@@ -0,0 +1,58 @@
+//! Prover queue status.
+
+use std::time::Duration;
+
+use structopt::StructOpt;
+
+use zksync_storage::StorageProcessor;
+
+#[derive(Debug, StructOpt)]
+pub struct ProverStatus {
+    /// Window (in seconds) of the recently proven jobs used to calculate the average proving time
+    #[structopt(long, default_value = "3600")]
+    window_secs: u64,
+}
+
+pub async fn run(command: ProverStatus) -> anyhow::Result<()> {
+    let mut storage = StorageProcessor::establish_connection().await?;
+    let stats = storage
+        .prover_schema()
+        .load_prover_queue_stats(Duration::from_secs(command.window_secs))
+        .await?;
+    let in_progress_jobs = storage.prover_schema().load_in_progress_jobs().await?;
+
+    println!(
+        "{:<20}  {:>8}  {:>8}  {:>11}  {:>17}",
+        "job type", "size", "pending", "in progress", "avg proving time"
+    );
+    for stats in stats {
+        let average_proving_time = stats
+            .average_proving_time
+            .map_or_else(|| "-".to_string(), |secs| format!("{:.0}s", secs));
+        println!(
+            "{:<20}  {:>8}  {:>8}  {:>11}  {:>17}",
+            stats.job_type,
+            stats.job_size,
+            stats.pending_jobs,
+            stats.in_progress_jobs,
+            average_proving_time
+        );
+    }
+
+    if !in_progress_jobs.is_empty() {
+        println!();
+        println!("Jobs in progress:");
+        for job in in_progress_jobs {
+            println!(
+                "  #{} {} blocks {}-{} by {} (last heartbeat at {})",
+                job.id,
+                job.job_type,
+                job.first_block,
+                job.last_block,
+                job.updated_by,
+                job.updated_at
+            );
+        }
+    }
+    Ok(())
+}
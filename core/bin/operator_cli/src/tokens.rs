@@ -0,0 +1,102 @@
+//! Token management: listing the tokens and pausing them.
+
+use anyhow::format_err;
+use reqwest::Method;
+use structopt::StructOpt;
+
+use zksync_storage::StorageProcessor;
+use zksync_types::TokenLike;
+
+use crate::client::CoreApiClient;
+
+#[derive(Debug, StructOpt)]
+pub enum TokensCommand {
+    /// Lists the tokens along with their pause status
+    List {
+        /// Show only the paused tokens
+        #[structopt(long)]
+        paused: bool,
+    },
+    /// Shows the token details and its market volume used to decide whether it's suitable for fees
+    Show {
+        /// Token id, address or symbol
+        token: String,
+    },
+    /// Pauses the token: new transfers and swaps of it are rejected, withdrawals are allowed
+    Pause { id: u32 },
+    /// Unpauses the token
+    Unpause { id: u32 },
+}
+
+pub async fn run(command: TokensCommand, client: &CoreApiClient) -> anyhow::Result<()> {
+    match command {
+        TokensCommand::List { paused } => {
+            let mut storage = StorageProcessor::establish_connection().await?;
+            let paused_tokens = storage.tokens_schema().load_paused_tokens().await?;
+            let mut tokens: Vec<_> = storage
+                .tokens_schema()
+                .load_tokens()
+                .await?
+                .into_iter()
+                .map(|(_, token)| token)
+                .filter(|token| !paused || paused_tokens.contains(&token.id))
+                .collect();
+            tokens.sort_unstable_by_key(|token| token.id);
+
+            println!(
+                "{:>6}  {:<10}  {:<42}  {:>8}  paused",
+                "id", "symbol", "address", "decimals"
+            );
+            for token in tokens {
+                println!(
+                    "{:>6}  {:<10}  {:<42}  {:>8}  {}",
+                    *token.id,
+                    token.symbol,
+                    format!("{:?}", token.address),
+                    token.decimals,
+                    paused_tokens.contains(&token.id)
+                );
+            }
+        }
+        TokensCommand::Show { token } => {
+            let mut storage = StorageProcessor::establish_connection().await?;
+            let token = storage
+                .tokens_schema()
+                .get_token(TokenLike::parse(&token))
+                .await?
+                .ok_or_else(|| format_err!("Token {} is not found", token))?;
+            let paused = storage
+                .tokens_schema()
+                .load_paused_tokens()
+                .await?
+                .contains(&token.id);
+            let market_volume = storage
+                .tokens_schema()
+                .get_token_market_volume(token.id)
+                .await?;
+
+            println!("{}", serde_json::to_string_pretty(&token)?);
+            println!("paused: {}", paused);
+            match market_volume {
+                Some(volume) => println!(
+                    "market volume: {} (updated at {})",
+                    volume.market_volume, volume.last_updated
+                ),
+                None => println!("market volume: unknown"),
+            }
+        }
+        TokensCommand::Pause { id } => {
+            client
+                .private::<()>(Method::POST, &format!("/tokens/{}/pause", id))
+                .await?;
+            println!("Token {} is paused", id);
+        }
+        TokensCommand::Unpause { id } => {
+            client
+                .private::<()>(Method::POST, &format!("/tokens/{}/unpause", id))
+                .await?;
+            println!("Token {} is unpaused", id);
+        }
+    }
+    Ok(())
+}
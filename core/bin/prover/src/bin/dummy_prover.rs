@@ -3,6 +3,6 @@ use zksync_prover::dummy_prover::DummyProver;
 
 #[tokio::main]
 async fn main() {
-    let run_prometheus_exporter = false;
+    let run_prometheus_exporter = true;
     main_for_prover_impl::<DummyProver>(run_prometheus_exporter).await;
 }
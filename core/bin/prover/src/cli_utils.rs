@@ -3,12 +3,14 @@ use std::time::Duration;
 // External deps
 use structopt::StructOpt;
 // Workspace deps
-use zksync_config::configs::ProverConfig as EnvProverConfig;
+use zksync_config::configs::{api::LogFilterApiConfig, ProverConfig as EnvProverConfig};
+use zksync_types::network::Network;
 use zksync_utils::{get_env, parse_env};
 // Local deps
-use crate::{client, prover_work_cycle, ProverConfig, ProverImpl, ShutdownRequest};
-use zksync_config::configs::api::PrometheusConfig;
-use zksync_prometheus_exporter::run_prometheus_exporter;
+use crate::{client, prover_work_cycle, ApiClient, ProverConfig, ProverImpl, ShutdownRequest};
+use zksync_prometheus_exporter::{run_prometheus_exporter, GlobalLabels};
+use zksync_prover_utils::api::{SetupKeyState, SetupKeysReport};
+use zksync_prover_utils::setup_keys::{parse_setup_keys_checksums, prepare_universal_setup_keys};
 
 fn api_client_from_env() -> client::ApiClient {
     let server_api_url = parse_env("API_PROVER_URL");
@@ -33,6 +35,14 @@ pub async fn main_for_prover_impl<PROVER>(run_prometheus: bool)
 where
     PROVER: ProverImpl + Send + Sync + 'static,
 {
+    zksync_config::file::load_config_files().expect("failed to load the configuration files");
+    zksync_config::run_validate_config_mode(|| {
+        EnvProverConfig::from_env();
+        <PROVER as ProverImpl>::Config::from_env();
+        api_client_from_env();
+        LogFilterApiConfig::from_env();
+        Vec::new()
+    });
     let opt = Opt::from_args();
     let worker_name = opt.worker_name;
 
@@ -40,9 +50,11 @@ where
     let prover_options = EnvProverConfig::from_env();
     let prover_config = <PROVER as ProverImpl>::Config::from_env();
     let api_client = api_client_from_env();
+    let log_filter_config = LogFilterApiConfig::from_env();
     let prover = PROVER::create_from_config(prover_config);
 
     let _vlog_guard = vlog::init();
+    zksync_http_utils::log_filter::start_log_filter_server(log_filter_config.bind_addr());
 
     vlog::info!("creating prover, worker name: {}", worker_name);
 
@@ -69,10 +81,14 @@ where
     }
 
     if run_prometheus {
-        let prom_config = PrometheusConfig::from_env();
-        run_prometheus_exporter(prom_config.port);
+        // Only the network is required from the chain config, so the rest of it isn't loaded.
+        let network: Network = parse_env("CHAIN_ETH_NETWORK");
+        let global_labels = GlobalLabels::new("prover", network);
+        run_prometheus_exporter(prover_options.prover.prometheus_port, global_labels);
     }
 
+    check_setup_keys(&prover, &api_client, &prover_options, &worker_name).await;
+
     prover_work_cycle(
         prover,
         api_client,
@@ -82,3 +98,58 @@ where
     )
     .await;
 }
+
+/// Checks (and downloads if allowed) the universal setup keys required by the prover and reports
+/// their state to the server. Panics if any of the keys is not ready, since the prover won't be
+/// able to create proofs without them.
+async fn check_setup_keys<PROVER: ProverImpl>(
+    prover: &PROVER,
+    api_client: &client::ApiClient,
+    prover_options: &EnvProverConfig,
+    worker_name: &str,
+) {
+    let powers = prover.universal_setup_powers();
+    if powers.is_empty() {
+        return;
+    }
+
+    let checksums = parse_setup_keys_checksums(&prover_options.prover.setup_keys_checksums)
+        .expect("Failed to parse setup keys checksums");
+    let download_from_network = parse_env("MISC_PROVER_DOWNLOAD_SETUP");
+    let keys = tokio::task::spawn_blocking(move || {
+        prepare_universal_setup_keys(&powers, &checksums, download_from_network)
+    })
+    .await
+    .expect("Setup keys check panicked");
+
+    for key in &keys {
+        vlog::info!(
+            "Universal setup key 2^{}: {:?} (checksum verified: {}, downloaded: {})",
+            key.power_of_two,
+            key.state,
+            key.checksum_verified,
+            key.downloaded
+        );
+    }
+    let not_ready = keys
+        .iter()
+        .filter(|key| key.state != SetupKeyState::Ready)
+        .map(|key| key.power_of_two)
+        .collect::<Vec<_>>();
+
+    api_client
+        .report_setup_keys(SetupKeysReport {
+            prover_name: worker_name.to_string(),
+            keys,
+        })
+        .await
+        .map_err(|e| vlog::warn!("Failed to report setup keys: {}", e))
+        .unwrap_or_default();
+
+    if !not_ready.is_empty() {
+        panic!(
+            "Universal setup keys are missing or corrupted for powers of two: {:?}",
+            not_ready
+        );
+    }
+}
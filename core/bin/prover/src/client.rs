@@ -10,7 +10,8 @@ use vlog::*;
 // Workspace deps
 use crate::auth_utils::AuthTokenGenerator;
 use zksync_prover_utils::api::{
-    ProverInputRequest, ProverInputResponse, ProverOutputRequest, ProverStopped, WorkingOn,
+    ProverInputRequest, ProverInputResponse, ProverOutputRequest, ProverStopped, SetupKeysReport,
+    WorkingOn,
 };
 
 #[derive(Debug, Clone)]
@@ -19,6 +20,7 @@ pub struct ApiClient {
     working_on_url: Url,
     publish_url: Url,
     stopped_url: Url,
+    setup_keys_url: Url,
     // Client keeps connection pool inside, so it is recommended to reuse it (see docstring for reqwest::Client).
     http_client: reqwest::Client,
     // A generator that create the authentication token upon request to any endpoint.
@@ -41,6 +43,7 @@ impl ApiClient {
             working_on_url: base_url.join("/working_on").unwrap(),
             publish_url: base_url.join("/publish").unwrap(),
             stopped_url: base_url.join("/stopped").unwrap(),
+            setup_keys_url: base_url.join("/setup_keys").unwrap(),
             http_client,
             auth_token_generator,
         }
@@ -196,4 +199,25 @@ impl crate::ApiClient for ApiClient {
 
         self.with_retries(operation).await
     }
+
+    async fn report_setup_keys(&self, report: SetupKeysReport) -> anyhow::Result<()> {
+        let operation = || async {
+            let response = self
+                .http_client
+                .post(self.setup_keys_url.clone())
+                .bearer_auth(&self.get_encoded_token()?)
+                .json(&report)
+                .send()
+                .await
+                .map_err(|e| Transient(format_err!("failed to send setup_keys request: {}", e)))?;
+
+            if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+                return Err(Permanent(format_err!("authorization error")));
+            }
+
+            Ok(())
+        };
+
+        self.with_retries(operation).await
+    }
 }
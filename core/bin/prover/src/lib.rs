@@ -11,7 +11,7 @@ use std::sync::{
     atomic::{AtomicBool, AtomicI32, Ordering},
     Arc,
 };
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::sync::oneshot;
 // External deps
 use zksync_crypto::rand::{
@@ -22,7 +22,7 @@ use zksync_crypto::rand::{
 use zksync_config::ProverConfig as EnvProverConfig;
 use zksync_prover_utils::api::{
     JobRequestData, JobResultData, ProverInputRequest, ProverInputRequestAuxData,
-    ProverInputResponse, ProverOutputRequest,
+    ProverInputResponse, ProverOutputRequest, SetupKeysReport,
 };
 
 const ABSENT_PROVER_ID: i32 = -1;
@@ -83,6 +83,11 @@ pub trait ProverImpl {
         Default::default()
         // TODO: Add the ability to define different config (ZKS-283).
     }
+    /// Powers of two of the universal setup keys required to create proofs.
+    /// The keys are checked on startup, so a prover with missing or corrupted keys fails early.
+    fn universal_setup_powers(&self) -> Vec<u32> {
+        Vec::new()
+    }
     /// Resource heavy operation
     fn create_proof(&self, data: JobRequestData) -> anyhow::Result<JobResultData>;
 }
@@ -92,6 +97,17 @@ pub trait ApiClient: Debug {
     async fn working_on(&self, job_id: i32, prover_name: &str) -> anyhow::Result<()>;
     async fn publish(&self, data: ProverOutputRequest) -> anyhow::Result<()>;
     async fn prover_stopped(&self, prover_name: String) -> anyhow::Result<()>;
+    async fn report_setup_keys(&self, report: SetupKeysReport) -> anyhow::Result<()>;
+}
+
+/// Returns the type and the size of the proof job, used to label the proving metrics.
+/// Proving time depends on the block size for single proofs and on the amount of
+/// aggregated proofs for the aggregated ones.
+pub fn proof_job_labels(job_data: &JobRequestData) -> (&'static str, usize) {
+    match job_data {
+        JobRequestData::BlockProof(_, block_size) => ("single_proof", *block_size),
+        JobRequestData::AggregatedBlockProof(proofs) => ("aggregated_proof", proofs.len()),
+    }
 }
 
 async fn compute_proof_no_blocking<PROVER>(
@@ -177,6 +193,7 @@ pub async fn prover_work_cycle<PROVER, CLIENT>(
             data: job_data,
             first_block,
             last_block,
+            job_priority,
         } = prover_input_response;
         let job_data = if let Some(job_data) = job_data {
             job_data
@@ -185,10 +202,11 @@ pub async fn prover_work_cycle<PROVER, CLIENT>(
         };
 
         vlog::info!(
-            "got job id: {}, blocks: [{}, {}]",
+            "got job id: {}, blocks: [{}, {}], priority: {}",
             job_id,
             first_block,
-            last_block
+            last_block,
+            job_priority
         );
 
         let heartbeat_future_handle = heartbeat_future_handle(
@@ -198,6 +216,8 @@ pub async fn prover_work_cycle<PROVER, CLIENT>(
             prover_options.prover.heartbeat_interval(),
         )
         .fuse();
+        let (job_type, job_size) = proof_job_labels(&job_data);
+        let proving_start = Instant::now();
         let compute_proof_future = compute_proof_no_blocking(prover, job_data).fuse();
 
         pin_mut!(heartbeat_future_handle, compute_proof_future);
@@ -210,11 +230,20 @@ pub async fn prover_work_cycle<PROVER, CLIENT>(
 
         let (ret_prover, proof) = futures::select! {
             comp_proof = compute_proof_future => {
-                comp_proof.expect("Failed to compute proof")
+                comp_proof.unwrap_or_else(|err| {
+                    metrics::increment_counter!("prover.failed_proofs", "type" => job_type);
+                    panic!("Failed to compute proof: {}", err)
+                })
             },
             _ = heartbeat_future_handle => unreachable!(),
         };
         prover = ret_prover;
+        metrics::histogram!(
+            "prover.proof_generation",
+            proving_start.elapsed(),
+            "type" => job_type,
+            "size" => job_size.to_string()
+        );
 
         client
             .publish(ProverOutputRequest {
@@ -224,7 +253,10 @@ pub async fn prover_work_cycle<PROVER, CLIENT>(
                 data: proof,
             })
             .await
-            .map_err(|e| vlog::warn!("Failed to publish proof: {}", e))
+            .map_err(|e| {
+                metrics::increment_counter!("prover.failed_publications");
+                vlog::warn!("Failed to publish proof: {}", e)
+            })
             .unwrap_or_default();
 
         vlog::info!(
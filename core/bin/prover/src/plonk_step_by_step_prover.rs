@@ -31,6 +31,8 @@ pub struct PlonkStepByStepProverConfig {
     pub block_sizes: Vec<usize>,
     pub download_setup_from_network: bool,
     pub aggregated_proof_sizes_with_setup_pow: Vec<(usize, u32)>,
+    /// Setup powers needed to prove blocks of `block_sizes`.
+    pub block_sizes_setup_pow: Vec<u32>,
 }
 
 impl ProverConfig for PlonkStepByStepProverConfig {
@@ -40,12 +42,26 @@ impl ProverConfig for PlonkStepByStepProverConfig {
         let aggregated_proof_sizes_with_setup_pow = env_config
             .circuit
             .supported_aggregated_proof_sizes_with_setup_pow();
+        let block_sizes_setup_pow = env_config
+            .circuit
+            .supported_block_chunks_sizes
+            .iter()
+            .zip(
+                env_config
+                    .circuit
+                    .supported_block_chunks_sizes_setup_powers
+                    .iter(),
+            )
+            .filter(|(size, _)| env_config.state_keeper.block_chunk_sizes.contains(size))
+            .map(|(_, setup_pow)| *setup_pow as u32)
+            .collect();
 
         Self {
             download_setup_from_network: parse_env("MISC_PROVER_DOWNLOAD_SETUP"),
             all_block_sizes: env_config.circuit.supported_block_chunks_sizes,
             block_sizes: env_config.state_keeper.block_chunk_sizes,
             aggregated_proof_sizes_with_setup_pow,
+            block_sizes_setup_pow,
         }
     }
 }
@@ -173,6 +189,24 @@ impl ProverImpl for PlonkStepByStepProver {
         Ok(proof)
     }
 
+    fn universal_setup_powers(&self) -> Vec<u32> {
+        let mut powers = self
+            .config
+            .block_sizes_setup_pow
+            .iter()
+            .cloned()
+            .chain(
+                self.config
+                    .aggregated_proof_sizes_with_setup_pow
+                    .iter()
+                    .map(|(_, setup_pow)| *setup_pow),
+            )
+            .collect::<Vec<_>>();
+        powers.sort_unstable();
+        powers.dedup();
+        powers
+    }
+
     fn create_from_config(config: PlonkStepByStepProverConfig) -> Self {
         assert!(!config.block_sizes.is_empty());
         PlonkStepByStepProver {
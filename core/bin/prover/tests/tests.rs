@@ -15,6 +15,7 @@ use zksync_config::configs::{ChainConfig, ProverConfig};
 use zksync_crypto::{
     circuit::{account::CircuitAccount, CircuitAccountTree},
     pairing::ff::PrimeField,
+    proof::SingleProof,
     Fr,
 };
 use zksync_prover::dummy_prover::{DummyProver, DummyProverConfig};
@@ -23,7 +24,7 @@ use zksync_prover::plonk_step_by_step_prover::{
 };
 use zksync_prover::{ProverImpl, ShutdownRequest};
 use zksync_prover_utils::api::{
-    JobRequestData, ProverInputRequest, ProverInputResponse, ProverOutputRequest,
+    JobRequestData, ProverInputRequest, ProverInputResponse, ProverOutputRequest, SetupKeysReport,
 };
 use zksync_types::{
     block::smallest_block_size_for_chunks, operations::DepositOp, Account, AccountId, Address,
@@ -57,14 +58,24 @@ impl Default for MockProverConfigs {
                 cycle_wait: 500,
                 request_timeout: 1,
                 die_after_proof: false,
+                setup_keys_checksums: Vec::new(),
+                prometheus_port: 3313,
             },
             core: zksync_config::configs::prover::Core {
                 gone_timeout: 2,
                 idle_provers: 1,
+                aggregated_proof_deadline: 3600,
+                aggregated_proof_preemption_window: 600,
             },
             witness_generator: zksync_config::configs::prover::WitnessGenerator {
                 prepare_data_interval: 5000,
                 witness_generators: 2,
+                lease_timeout: 60000,
+            },
+            exit_proof_generator: zksync_config::configs::prover::ExitProofGenerator {
+                workers: 1,
+                poll_interval: 1000,
+                lease_timeout: 60000,
             },
         };
 
@@ -145,6 +156,26 @@ fn test_data_for_prover() -> JobRequestData {
     JobRequestData::BlockProof(prover_data, 10)
 }
 
+/// Checks that the proving metrics are labeled by the block size of the single proofs
+/// and by the amount of proofs in the aggregated ones.
+#[test]
+fn test_proof_job_labels() {
+    assert_eq!(
+        zksync_prover::proof_job_labels(&test_data_for_prover()),
+        ("single_proof", 10)
+    );
+
+    let aggregated = JobRequestData::AggregatedBlockProof(vec![
+        (SingleProof::default(), 10),
+        (SingleProof::default(), 32),
+        (SingleProof::default(), 10),
+    ]);
+    assert_eq!(
+        zksync_prover::proof_job_labels(&aggregated),
+        ("aggregated_proof", 3)
+    );
+}
+
 #[tokio::test]
 async fn test_shutdown_request() {
     let MockProverConfigs {
@@ -276,6 +307,7 @@ impl zksync_prover::ApiClient for MockApiClient {
             first_block: BlockNumber(1),
             last_block: BlockNumber(1),
             data: Some(test_data_for_prover()),
+            job_priority: 0,
         };
 
         Ok(response)
@@ -299,4 +331,8 @@ impl zksync_prover::ApiClient for MockApiClient {
     async fn prover_stopped(&self, _: String) -> anyhow::Result<()> {
         Ok(())
     }
+
+    async fn report_setup_keys(&self, _: SetupKeysReport) -> anyhow::Result<()> {
+        Ok(())
+    }
 }
@@ -1,5 +1,4 @@
-use futures::{channel::mpsc, executor::block_on, SinkExt, StreamExt};
-use std::cell::RefCell;
+use futures::channel::mpsc;
 use std::str::FromStr;
 
 use structopt::StructOpt;
@@ -11,19 +10,36 @@ use zksync_core::{genesis_init, run_core, wait_for_tasks};
 use zksync_eth_client::EthereumGateway;
 use zksync_forced_exit_requests::run_forced_exit_requests_actors;
 use zksync_gateway_watcher::run_gateway_watcher_if_multiplexed;
-use zksync_witness_generator::run_prover_server;
+use zksync_witness_generator::{exit_proof_generator::run_exit_proof_generator, run_prover_server};
 
+use tokio::signal::unix::{signal, SignalKind};
 use tokio::task::JoinHandle;
-use zksync_config::configs::api::{PrivateApiConfig, PrometheusConfig, TokenConfig};
+use zksync_api::api_server::{
+    settings::{ReloadableSettings, SharedTxPolicy, TxPolicy},
+    shutdown::ApiShutdown,
+    state_freshness::StateFreshness,
+};
+use zksync_config::configs::api::{
+    LogFilterApiConfig, PrivateApiConfig, PrometheusConfig, TokenConfig,
+};
 use zksync_config::{
     configs::api::{CommonApiConfig, JsonRpcConfig, ProverApiConfig, RestApiConfig, Web3Config},
-    ChainConfig, ContractsConfig, DBConfig, ETHClientConfig, ETHSenderConfig, ETHWatchConfig,
-    ForcedExitRequestsConfig, GatewayWatcherConfig, ProverConfig, TickerConfig, ZkSyncConfig,
+    AlertingConfig, ArchiverConfig, ChainConfig, ContractsConfig, DBConfig, ETHClientConfig,
+    ETHSenderConfig, ETHWatchConfig, EventPublisherConfig, ForcedExitRequestsConfig,
+    GatewayWatcherConfig, ProverConfig, TickerConfig, TokenHoldersConfig, TokenMetadataConfig,
+    ZkSyncConfig,
 };
+use zksync_core::alerter::run_alerter;
+use zksync_core::archiver::run_archiver;
+use zksync_core::event_publisher::run_event_publisher;
 use zksync_core::rejected_tx_cleaner::run_rejected_tx_cleaner;
+use zksync_core::state_pruner::run_state_pruner;
+use zksync_core::token_holders::run_token_holders_updater;
+use zksync_core::token_metadata::run_token_metadata_updater;
 use zksync_mempool::run_mempool_tx_handler;
-use zksync_prometheus_exporter::{run_operation_counter, run_prometheus_exporter};
+use zksync_prometheus_exporter::{run_operation_counter, run_prometheus_exporter, GlobalLabels};
 use zksync_storage::ConnectionPool;
+use zksync_types::tx::PackedEthSignature;
 
 const DEFAULT_CHANNEL_CAPACITY: usize = 32_768;
 
@@ -52,6 +68,13 @@ pub enum Component {
     Prometheus,
     PrometheusPeriodicMetrics,
     RejectedTaskCleaner,
+    StatePruner,
+    Archiver,
+    EventPublisher,
+    Alerter,
+    TokenMetadata,
+    TokenHolders,
+    ExitProofGenerator,
 }
 
 impl FromStr for Component {
@@ -70,6 +93,13 @@ impl FromStr for Component {
             "fetchers" => Ok(Component::Fetchers),
             "core" => Ok(Component::Core),
             "rejected-task-cleaner" => Ok(Component::RejectedTaskCleaner),
+            "state-pruner" => Ok(Component::StatePruner),
+            "archiver" => Ok(Component::Archiver),
+            "event-publisher" => Ok(Component::EventPublisher),
+            "alerter" => Ok(Component::Alerter),
+            "token-metadata" => Ok(Component::TokenMetadata),
+            "token-holders" => Ok(Component::TokenHolders),
+            "exit-proof-generator" => Ok(Component::ExitProofGenerator),
             "prometheus-periodic-metrics" => Ok(Component::PrometheusPeriodicMetrics),
             other => Err(format!("{} is not a valid component name", other)),
         }
@@ -98,6 +128,41 @@ impl Default for ComponentsToRun {
     }
 }
 
+impl ComponentsToRun {
+    /// Returns the value of the `component` label of the metrics: the main components run by the process.
+    fn metrics_component_label(&self) -> String {
+        let mut labels = Vec::new();
+        let is_api = |component: &Component| {
+            matches!(
+                component,
+                Component::RestApi
+                    | Component::Web3Api
+                    | Component::RpcApi
+                    | Component::RpcWebSocketApi
+            )
+        };
+        if self.0.iter().any(is_api) {
+            labels.push("api");
+        }
+        for (component, label) in &[
+            (Component::Core, "core"),
+            (Component::EthSender, "eth_sender"),
+            (Component::WitnessGenerator, "witness_generator"),
+            (Component::ForcedExit, "forced_exit"),
+        ] {
+            if self.0.contains(component) {
+                labels.push(*label);
+            }
+        }
+
+        if labels.is_empty() {
+            "server".to_string()
+        } else {
+            labels.join(",")
+        }
+    }
+}
+
 impl FromStr for ComponentsToRun {
     type Err = String;
 
@@ -122,11 +187,19 @@ struct Opt {
         default_value = "rest-api,web3-api,rpc-api,rpc-websocket-api,eth-sender,witness-generator,forced-exit,prometheus,core,rejected-task-cleaner,fetchers,prometheus-periodic-metrics"
     )]
     components: ComponentsToRun,
+    /// Check the configuration consistency and print the effective configuration instead of launching the server
+    #[structopt(long)]
+    validate_config: bool,
 }
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    zksync_config::file::load_config_files()?;
     let opt = Opt::from_args();
+    if opt.validate_config {
+        let is_valid = zksync_config::print_and_validate(|| ZkSyncConfig::from_env().validate());
+        std::process::exit(if is_valid { 0 } else { 1 });
+    }
     let mut _vlog_guard = None;
     let server_mode = if opt.genesis {
         ServerCommand::Genesis
@@ -151,24 +224,39 @@ async fn main() -> anyhow::Result<()> {
 }
 
 async fn run_server(components: &ComponentsToRun) {
-    let connection_pool = ConnectionPool::new(None);
-    let read_only_connection_pool = ConnectionPool::new_readonly_pool(None);
-    let (stop_signal_sender, mut stop_signal_receiver) = mpsc::channel(256);
+    let db_config = DBConfig::from_env();
+    zksync_storage::query_log::set_slow_query_threshold(db_config.slow_query_threshold());
+    let mut connection_pool = ConnectionPool::new(None);
+    let mut read_only_connection_pool = ConnectionPool::new_readonly_pool(None)
+        .with_primary_fallback(&connection_pool, db_config.replica_max_lag())
+        .with_max_pending_checkouts(db_config.max_pending_checkouts);
+    let archive = zksync_object_store::archive_from_env();
+    if let Some(archive) = &archive {
+        connection_pool = connection_pool.with_archive(archive.clone());
+        read_only_connection_pool = read_only_connection_pool.with_archive(archive.clone());
+    }
+    // Slow queries are reported along with the component which made them.
+    let component_pool = |component| connection_pool.clone().with_component(component);
+    let read_only_component_pool =
+        |component| read_only_connection_pool.clone().with_component(component);
+    let api_shutdown = ApiShutdown::new();
+    let mut reloadable_settings = None;
 
     let mut tasks = vec![];
 
     if components.0.contains(&Component::Web3Api) {
         // Run web3 api
         tasks.push(zksync_api::api_server::web3::start_rpc_server(
-            connection_pool.clone(),
+            read_only_component_pool("api"),
             &Web3Config::from_env(),
             &TokenConfig::from_env(),
+            api_shutdown.clone(),
         ));
     }
 
     if components.0.contains(&Component::Fetchers) {
         // Run price fetchers
-        let mut price_tasks = run_price_updaters(connection_pool.clone());
+        let mut price_tasks = run_price_updaters(component_pool("fetchers"));
         tasks.append(&mut price_tasks);
     }
 
@@ -185,51 +273,67 @@ async fn run_server(components: &ComponentsToRun) {
         let gateway_watcher_config = GatewayWatcherConfig::from_env();
 
         // Run eth multiplexer
-        if let Some(task) =
-            run_gateway_watcher_if_multiplexed(eth_gateway.clone(), &gateway_watcher_config)
-        {
+        if let Some(task) = run_gateway_watcher_if_multiplexed(
+            eth_gateway.clone(),
+            &gateway_watcher_config,
+            ETHClientConfig::from_env().web3_ws_url,
+        ) {
             tasks.push(task);
         }
 
+        let contracts_config = ContractsConfig::from_env();
+        let common_config = CommonApiConfig::from_env();
+
         // Run signer
         let (sign_check_sender, sign_check_receiver) = mpsc::channel(DEFAULT_CHANNEL_CAPACITY);
         tasks.push(zksync_api::signature_checker::start_sign_checker(
             eth_gateway,
             sign_check_receiver,
+            &common_config,
         ));
-
-        let contracts_config = ContractsConfig::from_env();
-        let common_config = CommonApiConfig::from_env();
         let token_config = TokenConfig::from_env();
         let chain_config = ChainConfig::from_env();
         let fee_ticker_config = TickerConfig::from_env();
-        let ticker_info = Box::new(TickerInfo::new(read_only_connection_pool.clone()));
+        let ticker_info = Box::new(TickerInfo::new(read_only_component_pool("api")));
 
         let ticker = FeeTicker::new_with_default_validator(
             ticker_info,
             fee_ticker_config,
             chain_config.max_blocks_to_aggregate(),
-            read_only_connection_pool.clone(),
+            read_only_component_pool("api"),
         );
+        let tx_policy = SharedTxPolicy::new(TxPolicy::from_config(&common_config));
+        reloadable_settings = Some(ReloadableSettings::new(tx_policy.clone(), ticker.clone()));
+        let state_freshness = StateFreshness::new(&common_config);
+        if let Some(task) = state_freshness
+            .clone()
+            .start_checker(read_only_component_pool("api"))
+        {
+            tasks.push(task);
+        }
 
         if components.0.contains(&Component::RpcWebSocketApi) {
             let (mempool_tx_request_sender, mempool_tx_request_receiver) =
                 mpsc::channel(DEFAULT_CHANNEL_CAPACITY);
             tasks.push(run_mempool_tx_handler(
-                connection_pool.clone(),
+                component_pool("api"),
                 mempool_tx_request_receiver,
                 chain_config.state_keeper.block_chunk_sizes.clone(),
+                chain_config.state_keeper.mempool_capacity,
             ));
             tasks.push(zksync_api::api_server::rpc_subscriptions::start_ws_server(
-                read_only_connection_pool.clone(),
+                read_only_component_pool("api"),
                 sign_check_sender.clone(),
                 ticker.clone(),
                 &common_config,
                 &token_config,
+                tx_policy.clone(),
                 &JsonRpcConfig::from_env(),
                 chain_config.state_keeper.miniblock_iteration_interval(),
                 mempool_tx_request_sender,
-                eth_watch_config.confirmations_for_eth_event,
+                eth_watch_config.deposit_confirmations(),
+                state_freshness.clone(),
+                api_shutdown.clone(),
             ));
         }
 
@@ -237,19 +341,23 @@ async fn run_server(components: &ComponentsToRun) {
             let (mempool_tx_request_sender, mempool_tx_request_receiver) =
                 mpsc::channel(DEFAULT_CHANNEL_CAPACITY);
             tasks.push(run_mempool_tx_handler(
-                connection_pool.clone(),
+                component_pool("api"),
                 mempool_tx_request_receiver,
                 chain_config.state_keeper.block_chunk_sizes.clone(),
+                chain_config.state_keeper.mempool_capacity,
             ));
             tasks.push(zksync_api::api_server::rpc_server::start_rpc_server(
-                read_only_connection_pool.clone(),
+                read_only_component_pool("api"),
                 sign_check_sender.clone(),
                 ticker.clone(),
                 &JsonRpcConfig::from_env(),
                 &common_config,
                 &token_config,
+                tx_policy.clone(),
                 mempool_tx_request_sender,
-                eth_watch_config.confirmations_for_eth_event,
+                eth_watch_config.deposit_confirmations(),
+                state_freshness.clone(),
+                api_shutdown.clone(),
             ));
         }
 
@@ -257,26 +365,30 @@ async fn run_server(components: &ComponentsToRun) {
             let (mempool_tx_request_sender, mempool_tx_request_receiver) =
                 mpsc::channel(DEFAULT_CHANNEL_CAPACITY);
             tasks.push(run_mempool_tx_handler(
-                connection_pool.clone(),
+                component_pool("api"),
                 mempool_tx_request_receiver,
                 chain_config.state_keeper.block_chunk_sizes,
+                chain_config.state_keeper.mempool_capacity,
             ));
             let private_config = PrivateApiConfig::from_env();
             tasks.push(zksync_api::api_server::rest::start_server_thread_detached(
-                read_only_connection_pool.clone(),
-                connection_pool.clone(),
+                read_only_component_pool("api"),
+                component_pool("api"),
                 RestApiConfig::from_env().bind_addr(),
                 contracts_config.contract_addr,
                 ticker,
                 sign_check_sender,
+                tx_policy,
                 mempool_tx_request_sender,
                 private_config.url,
+                state_freshness,
+                api_shutdown.clone(),
             ));
         }
     }
 
     if components.0.contains(&Component::EthSender) {
-        tasks.push(run_eth_sender(connection_pool.clone()))
+        tasks.push(run_eth_sender(component_pool("eth_sender")))
     }
 
     if components.0.contains(&Component::Core) {
@@ -284,8 +396,8 @@ async fn run_server(components: &ComponentsToRun) {
 
         tasks.append(
             &mut run_core(
-                connection_pool.clone(),
-                read_only_connection_pool.clone(),
+                component_pool("core"),
+                read_only_component_pool("core"),
                 &ZkSyncConfig::from_env(),
                 eth_gateway.clone(),
             )
@@ -295,47 +407,151 @@ async fn run_server(components: &ComponentsToRun) {
     }
 
     if components.0.contains(&Component::WitnessGenerator) {
-        tasks.push(run_witness_generator(connection_pool.clone()))
+        tasks.push(run_witness_generator(component_pool("witness_generator")))
     }
 
     if components.0.contains(&Component::Prometheus) {
         // Run prometheus data exporter.
         let config = PrometheusConfig::from_env();
-        let prometheus_task_handle = run_prometheus_exporter(config.port);
+        let global_labels = GlobalLabels::new(
+            components.metrics_component_label(),
+            ChainConfig::from_env().eth.network,
+        );
+        let prometheus_task_handle = run_prometheus_exporter(config.port, global_labels);
         tasks.push(prometheus_task_handle);
         // We can run them only with active prometheus
         if components.0.contains(&Component::PrometheusPeriodicMetrics) {
-            let counter_task_handle = run_operation_counter(read_only_connection_pool.clone());
+            let counter_task_handle = run_operation_counter(read_only_component_pool("prometheus"));
             tasks.push(counter_task_handle);
         }
     }
 
     if components.0.contains(&Component::ForcedExit) {
-        tasks.append(&mut run_forced_exit(connection_pool.clone()));
+        tasks.append(&mut run_forced_exit(component_pool("forced_exit")));
     }
 
     if components.0.contains(&Component::RejectedTaskCleaner) {
         let config = DBConfig::from_env();
-        tasks.push(run_rejected_tx_cleaner(&config, connection_pool));
+        tasks.push(run_rejected_tx_cleaner(
+            &config,
+            component_pool("rejected_task_cleaner"),
+        ));
+    }
+
+    if components.0.contains(&Component::StatePruner) {
+        let config = DBConfig::from_env();
+        tasks.push(run_state_pruner(&config, component_pool("state_pruner")));
+    }
+
+    if components.0.contains(&Component::EventPublisher) {
+        let config = EventPublisherConfig::from_env();
+        tasks.push(run_event_publisher(
+            config,
+            component_pool("event_publisher"),
+        ));
+    }
+
+    if components.0.contains(&Component::Alerter) {
+        let eth_gateway = EthereumGateway::from_config(
+            &ETHClientConfig::from_env(),
+            &ETHSenderConfig::from_env(),
+            ContractsConfig::from_env().contract_addr,
+        );
+        tasks.push(run_alerter(
+            AlertingConfig::from_env(),
+            component_pool("alerter"),
+            eth_gateway,
+        ));
+    }
+
+    if components.0.contains(&Component::TokenMetadata) {
+        let eth_client_config = ETHClientConfig::from_env();
+        let eth_gateway = EthereumGateway::from_config(
+            &eth_client_config,
+            &ETHSenderConfig::from_env(),
+            ContractsConfig::from_env().contract_addr,
+        );
+        tasks.push(run_token_metadata_updater(
+            TokenMetadataConfig::from_env(),
+            eth_client_config.chain_id,
+            component_pool("token_metadata"),
+            eth_gateway,
+        ));
+    }
+
+    if components.0.contains(&Component::TokenHolders) {
+        tasks.push(run_token_holders_updater(
+            TokenHoldersConfig::from_env(),
+            component_pool("token_holders"),
+        ));
+    }
+
+    if components.0.contains(&Component::ExitProofGenerator) {
+        tasks.push(run_exit_proof_generator(
+            component_pool("exit_proof_generator"),
+            ProverConfig::from_env().exit_proof_generator,
+        ));
+    }
+
+    if components.0.contains(&Component::Archiver) {
+        // Archived rows can only be read back by the components having access to the archive.
+        let store = archive.expect("Archiver requires the archive to be enabled");
+        tasks.push(run_archiver(
+            &ArchiverConfig::from_env(),
+            component_pool("archiver"),
+            store,
+        ));
     }
 
+    // The private core API and the prover API serve the log filter endpoints themselves.
+    if !components.0.contains(&Component::Core)
+        && !components.0.contains(&Component::WitnessGenerator)
     {
-        let stop_signal_sender = RefCell::new(stop_signal_sender.clone());
-        ctrlc::set_handler(move || {
-            let mut sender = stop_signal_sender.borrow_mut();
-            block_on(sender.send(true)).expect("Ctrl+C signal send");
-        })
-        .expect("Error setting Ctrl+C handler");
+        tasks.push(zksync_http_utils::log_filter::start_log_filter_server(
+            LogFilterApiConfig::from_env().bind_addr(),
+        ));
     }
 
-    tokio::select! {
-        _ = async { wait_for_tasks(tasks).await } => {
-            panic!("One if the actors is not supposed to finish its execution")
-        },
-        _ = async { stop_signal_receiver.next().await } => {
-            vlog::warn!("Stop signal received, shutting down");
+    let mut interrupt = signal(SignalKind::interrupt()).expect("Error setting SIGINT handler");
+    let mut terminate = signal(SignalKind::terminate()).expect("Error setting SIGTERM handler");
+    let mut hangup = signal(SignalKind::hangup()).expect("Error setting SIGHUP handler");
+
+    let tasks = wait_for_tasks(tasks);
+    tokio::pin!(tasks);
+    loop {
+        tokio::select! {
+            _ = &mut tasks => {
+                panic!("One if the actors is not supposed to finish its execution")
+            },
+            _ = hangup.recv() => {
+                reload_settings(reloadable_settings.as_ref());
+            },
+            _ = interrupt.recv() => break,
+            _ = terminate.recv() => break,
         }
-    };
+    }
+
+    vlog::warn!("Stop signal received, shutting down");
+    api_shutdown
+        .shutdown(CommonApiConfig::from_env().shutdown_timeout())
+        .await;
+}
+
+/// Re-reads the configuration files and applies the settings that can be changed without restart.
+fn reload_settings(settings: Option<&ReloadableSettings>) {
+    vlog::info!("SIGHUP received, reloading the configuration");
+    match zksync_config::file::reload_config_files() {
+        Ok(changed) => vlog::info!("Changed configuration variables: {:?}", changed),
+        Err(err) => {
+            vlog::error!("Failed to reload the configuration files: {}", err);
+            return;
+        }
+    }
+    if let Some(settings) = settings {
+        if let Err(err) = settings.reload() {
+            vlog::error!("Failed to apply the reloaded API settings: {}", err);
+        }
+    }
 }
 
 pub fn run_forced_exit(connection_pool: ConnectionPool) -> Vec<JoinHandle<()>> {
@@ -352,6 +568,7 @@ pub fn run_forced_exit(connection_pool: ConnectionPool) -> Vec<JoinHandle<()>> {
         connection_pool.clone(),
         mempool_tx_request_receiver,
         chain_config.state_keeper.block_chunk_sizes,
+        chain_config.state_keeper.mempool_capacity,
     );
     let forced_exit_task = run_forced_exit_requests_actors(
         connection_pool,
@@ -382,8 +599,29 @@ pub fn run_eth_sender(connection_pool: ConnectionPool) -> JoinHandle<()> {
         &eth_sender_config,
         contracts.contract_addr,
     );
+    let additional_operators = eth_sender_config
+        .sender
+        .additional_operator_private_keys
+        .iter()
+        .map(|private_key| {
+            let address = PackedEthSignature::address_from_private_key(private_key)
+                .expect("Invalid private key of the additional operator account");
+            let gateway = EthereumGateway::from_config_for_operator(
+                &eth_client_config,
+                address,
+                *private_key,
+                contracts.contract_addr,
+            );
+            (address, gateway)
+        })
+        .collect();
 
-    zksync_eth_sender::run_eth_sender(connection_pool, eth_gateway, eth_sender_config)
+    zksync_eth_sender::run_eth_sender(
+        connection_pool,
+        eth_gateway,
+        additional_operators,
+        eth_sender_config,
+    )
 }
 
 pub fn run_price_updaters(connection_pool: ConnectionPool) -> Vec<JoinHandle<()>> {
@@ -17,6 +17,8 @@ struct Opt {
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    zksync_config::file::load_config_files()?;
+    zksync_config::run_validate_config_mode(|| DBConfig::from_env().validate());
     let opt = Opt::from_args();
 
     let mut storage = StorageProcessor::establish_connection().await?;
@@ -0,0 +1,361 @@
+//! Optional screening of the addresses involved in the incoming transactions.
+//!
+//! Before being accepted into the mempool, senders and recipients of the transactions
+//! can be checked against a static denylist file and/or an external screening service.
+//! If no screening source is configured, every address is allowed.
+//!
+//! Every address is checked by every source concurrently, and each check is bounded by
+//! the `API_COMMON_SCREENING_TIMEOUT_MS` timeout.
+
+// Built-in uses
+use std::{
+    collections::HashSet,
+    fs,
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+// External uses
+use anyhow::Context;
+use futures::{stream::FuturesUnordered, StreamExt};
+use serde::Deserialize;
+use tokio::time::timeout;
+
+// Workspace uses
+use zksync_config::configs::api::CommonApiConfig;
+use zksync_types::{Address, ZkSyncTx};
+
+// Local uses
+use crate::api_server::tx_sender::SubmitError;
+
+/// Source of the information about the denied addresses.
+#[async_trait::async_trait]
+pub trait AddressScreener: Send + Sync {
+    async fn is_denied(&self, address: Address) -> anyhow::Result<bool>;
+}
+
+/// Denylist loaded from a file on startup.
+///
+/// File is expected to contain one address per line. Empty lines and lines
+/// starting with `#` are ignored.
+#[derive(Debug, Clone, Default)]
+pub struct StaticDenylist {
+    addresses: HashSet<Address>,
+}
+
+impl StaticDenylist {
+    pub fn new(addresses: impl IntoIterator<Item = Address>) -> Self {
+        Self {
+            addresses: addresses.into_iter().collect(),
+        }
+    }
+
+    pub fn load(path: &str) -> anyhow::Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Unable to read the denylist file {}", path))?;
+        Self::parse(&contents)
+    }
+
+    fn parse(contents: &str) -> anyhow::Result<Self> {
+        let addresses = contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| {
+                Address::from_str(line.trim_start_matches("0x"))
+                    .with_context(|| format!("Incorrect address in the denylist: {}", line))
+            })
+            .collect::<anyhow::Result<HashSet<_>>>()?;
+
+        Ok(Self { addresses })
+    }
+}
+
+#[async_trait::async_trait]
+impl AddressScreener for StaticDenylist {
+    async fn is_denied(&self, address: Address) -> anyhow::Result<bool> {
+        Ok(self.addresses.contains(&address))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ScreeningResponse {
+    denied: bool,
+}
+
+/// External screening service.
+///
+/// Service is requested with `GET <url>/<address>` and must respond with `{ "denied": bool }`.
+#[derive(Debug, Clone)]
+pub struct ScreeningService {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl ScreeningService {
+    pub fn new(url: String, timeout: Duration) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .expect("Unable to create the screening service client");
+        Self {
+            client,
+            url: url.trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl AddressScreener for ScreeningService {
+    async fn is_denied(&self, address: Address) -> anyhow::Result<bool> {
+        let response: ScreeningResponse = self
+            .client
+            .get(&format!("{}/{:?}", self.url, address))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        Ok(response.denied)
+    }
+}
+
+/// Screening stage of the transactions acceptance.
+#[derive(Clone)]
+pub struct AddressScreening {
+    screeners: Vec<Arc<dyn AddressScreener>>,
+    /// Time given to a single check of the address by one of the screeners.
+    timeout: Duration,
+}
+
+impl std::fmt::Debug for AddressScreening {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AddressScreening")
+            .field("screeners", &self.screeners.len())
+            .field("timeout", &self.timeout)
+            .finish()
+    }
+}
+
+impl AddressScreening {
+    pub fn new(screeners: Vec<Arc<dyn AddressScreener>>, timeout: Duration) -> Self {
+        Self { screeners, timeout }
+    }
+
+    pub fn from_config(config: &CommonApiConfig) -> Self {
+        let mut screeners: Vec<Arc<dyn AddressScreener>> = Vec::new();
+        if let Some(path) = &config.screening_denylist_path {
+            let denylist = StaticDenylist::load(path).expect("Unable to load address denylist");
+            screeners.push(Arc::new(denylist));
+        }
+        if let Some(url) = &config.screening_service_url {
+            screeners.push(Arc::new(ScreeningService::new(
+                url.clone(),
+                config.screening_timeout(),
+            )));
+        }
+
+        Self::new(screeners, config.screening_timeout())
+    }
+
+    /// Checks all the senders and recipients of the transactions.
+    ///
+    /// If the screening source is not available or doesn't respond in time, transactions
+    /// are rejected, so no address can bypass the screening.
+    pub async fn check_txs<'a>(
+        &self,
+        txs: impl IntoIterator<Item = &'a ZkSyncTx>,
+    ) -> Result<(), SubmitError> {
+        if self.screeners.is_empty() {
+            return Ok(());
+        }
+
+        let start = Instant::now();
+        let addresses: HashSet<_> = txs.into_iter().flat_map(screened_addresses).collect();
+        let mut checks: FuturesUnordered<_> = addresses
+            .into_iter()
+            .flat_map(|address| {
+                self.screeners
+                    .iter()
+                    .map(move |screener| self.check_address(screener.as_ref(), address))
+            })
+            .collect();
+
+        // Denied address is reported even if some of the other checks have failed.
+        let mut failure = None;
+        while let Some(result) = checks.next().await {
+            match result {
+                Ok(Some(address)) => {
+                    metrics::increment_counter!("api.address_screening.denied");
+                    return Err(SubmitError::AddressDenied(address));
+                }
+                Ok(None) => {}
+                Err(err) => failure = Some(err),
+            }
+        }
+        if let Some(err) = failure {
+            return Err(SubmitError::Internal(err));
+        }
+
+        metrics::histogram!("api.address_screening", start.elapsed());
+        Ok(())
+    }
+
+    /// Returns the address if it's denied by the screener.
+    async fn check_address(
+        &self,
+        screener: &dyn AddressScreener,
+        address: Address,
+    ) -> anyhow::Result<Option<Address>> {
+        let result = match timeout(self.timeout, screener.is_denied(address)).await {
+            Ok(result) => result,
+            Err(_) => Err(anyhow::format_err!(
+                "Screening didn't finish in {:?}",
+                self.timeout
+            )),
+        };
+        match result {
+            Ok(denied) => Ok(Some(address).filter(|_| denied)),
+            Err(err) => {
+                metrics::increment_counter!("api.address_screening.errors");
+                vlog::warn!("Address screening failed for {:?}: {}", address, err);
+                Err(err)
+            }
+        }
+    }
+}
+
+/// Returns the senders and recipients of the transaction.
+fn screened_addresses(tx: &ZkSyncTx) -> Vec<Address> {
+    match tx {
+        // New public key hash is not an address.
+        ZkSyncTx::ChangePubKey(tx) => vec![tx.account],
+        ZkSyncTx::Swap(tx) => vec![
+            tx.submitter_address,
+            tx.orders.0.recipient_address,
+            tx.orders.1.recipient_address,
+        ],
+        _ => vec![tx.account()]
+            .into_iter()
+            .chain(tx.to_account())
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::Barrier;
+    use zksync_types::{AccountId, Nonce, TokenId, Transfer};
+
+    const TIMEOUT: Duration = Duration::from_secs(1);
+
+    fn tx(to: Address) -> ZkSyncTx {
+        ZkSyncTx::Transfer(Box::new(Transfer::new(
+            AccountId(0),
+            Address::from_low_u64_be(10),
+            to,
+            TokenId(0),
+            1u64.into(),
+            1u64.into(),
+            Nonce(0),
+            Default::default(),
+            None,
+        )))
+    }
+
+    /// Allows every address once all the expected checks are running at the same time.
+    struct ConcurrentScreener(Barrier);
+
+    #[async_trait::async_trait]
+    impl AddressScreener for ConcurrentScreener {
+        async fn is_denied(&self, _address: Address) -> anyhow::Result<bool> {
+            self.0.wait().await;
+            Ok(false)
+        }
+    }
+
+    /// Never responds.
+    struct StuckScreener;
+
+    #[async_trait::async_trait]
+    impl AddressScreener for StuckScreener {
+        async fn is_denied(&self, _address: Address) -> anyhow::Result<bool> {
+            futures::future::pending().await
+        }
+    }
+
+    #[test]
+    fn parse_denylist() {
+        let contents = "
+            # Comment line
+            0x0000000000000000000000000000000000000001
+
+            0000000000000000000000000000000000000002
+        ";
+        let denylist = StaticDenylist::parse(contents).unwrap();
+        assert_eq!(
+            denylist.addresses,
+            vec![Address::from_low_u64_be(1), Address::from_low_u64_be(2)]
+                .into_iter()
+                .collect()
+        );
+
+        assert!(StaticDenylist::parse("not an address").is_err());
+    }
+
+    #[tokio::test]
+    async fn denied_address_rejected() {
+        let denied = Address::from_low_u64_be(1);
+        let denylist: Arc<dyn AddressScreener> = Arc::new(StaticDenylist::new(vec![denied]));
+        let screening = AddressScreening::new(vec![denylist], TIMEOUT);
+
+        assert!(screening
+            .check_txs(&[tx(Address::from_low_u64_be(2))])
+            .await
+            .is_ok());
+        assert!(matches!(
+            screening.check_txs(&[tx(denied)]).await,
+            Err(SubmitError::AddressDenied(address)) if address == denied
+        ));
+        // Empty screening allows everything.
+        assert!(AddressScreening::new(vec![], TIMEOUT)
+            .check_txs(&[tx(denied)])
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn checks_run_concurrently() {
+        // Two addresses of the transaction checked by two screeners, the barrier is only passed
+        // if all four checks run at the same time.
+        let screener: Arc<dyn AddressScreener> = Arc::new(ConcurrentScreener(Barrier::new(4)));
+        let screening = AddressScreening::new(vec![screener.clone(), screener], TIMEOUT);
+
+        assert!(screening
+            .check_txs(&[tx(Address::from_low_u64_be(2))])
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn stuck_check_times_out() {
+        let denied = Address::from_low_u64_be(1);
+        let stuck: Arc<dyn AddressScreener> = Arc::new(StuckScreener);
+        let screening = AddressScreening::new(vec![stuck.clone()], TIMEOUT);
+        assert!(matches!(
+            screening.check_txs(&[tx(denied)]).await,
+            Err(SubmitError::Internal(_))
+        ));
+
+        // Denial is reported without waiting for the stuck checks.
+        let denylist: Arc<dyn AddressScreener> = Arc::new(StaticDenylist::new(vec![denied]));
+        let screening = AddressScreening::new(vec![stuck, denylist], Duration::from_secs(60));
+        assert!(matches!(
+            screening.check_txs(&[tx(denied)]).await,
+            Err(SubmitError::AddressDenied(address)) if address == denied
+        ));
+    }
+}
@@ -0,0 +1,108 @@
+//! Backpressure of the API servers on the saturated database connection pool.
+//!
+//! Once too many requests already wait for a database connection (see
+//! `ConnectionPool::with_max_pending_checkouts`), the new HTTP requests are rejected with
+//! `503 Service Unavailable` right away instead of being queued behind them, so the clients
+//! can retry later or be routed to another server by the load balancer.
+
+// External uses
+use jsonrpc_http_server::{RequestMiddleware, RequestMiddlewareAction, Response};
+
+// Workspace uses
+use zksync_storage::ConnectionPool;
+
+/// Message of the rejected requests.
+pub(crate) const OVERLOADED_MESSAGE: &str = "Server is overloaded, please retry later";
+
+/// Returns `true` if the request to the `api` served by the `pool` should be rejected.
+pub(crate) fn should_reject(pool: &ConnectionPool, api: &'static str) -> bool {
+    let reject = pool.is_saturated();
+    if reject {
+        metrics::increment_counter!("api.backpressure.rejected", "api" => api);
+    }
+    reject
+}
+
+/// JSON-RPC HTTP server middleware rejecting the requests while the pool is saturated
+/// and passing the rest to the `inner` middleware.
+pub(crate) struct BackpressureMiddleware<M> {
+    pool: ConnectionPool,
+    api: &'static str,
+    inner: M,
+}
+
+impl<M: RequestMiddleware> BackpressureMiddleware<M> {
+    pub fn new(pool: ConnectionPool, api: &'static str, inner: M) -> Self {
+        Self { pool, api, inner }
+    }
+}
+
+impl<M: RequestMiddleware> RequestMiddleware for BackpressureMiddleware<M> {
+    fn on_request(&self, request: hyper::Request<hyper::Body>) -> RequestMiddlewareAction {
+        if should_reject(&self.pool, self.api) {
+            return Response::service_unavailable(OVERLOADED_MESSAGE).into();
+        }
+        self.inner.on_request(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyper::{Body, Request, StatusCode};
+    use std::time::Duration;
+
+    /// Middleware letting all the requests through.
+    struct PassThrough;
+
+    impl RequestMiddleware for PassThrough {
+        fn on_request(&self, request: Request<Body>) -> RequestMiddlewareAction {
+            request.into()
+        }
+    }
+
+    fn is_passed_through(action: &RequestMiddlewareAction) -> bool {
+        matches!(action, RequestMiddlewareAction::Proceed { .. })
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn rejects_requests_while_pool_is_saturated() -> anyhow::Result<()> {
+        let pool = ConnectionPool::new(Some(1)).with_max_pending_checkouts(1);
+        let middleware = BackpressureMiddleware::new(pool.clone(), "test", PassThrough);
+
+        let action = middleware.on_request(Request::new(Body::empty()));
+        assert!(is_passed_through(&action));
+
+        // Take the only connection and make another task wait for it.
+        let storage = pool.access_storage().await?;
+        let waiting_pool = pool.clone();
+        let waiting = tokio::spawn(async move { waiting_pool.access_storage().await.map(drop) });
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while !pool.is_saturated() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await?;
+
+        match middleware.on_request(Request::new(Body::empty())) {
+            RequestMiddlewareAction::Respond { response, .. } => {
+                assert_eq!(response.await?.status(), StatusCode::SERVICE_UNAVAILABLE);
+            }
+            RequestMiddlewareAction::Proceed { .. } => {
+                panic!("Request is passed through while the pool is saturated")
+            }
+        }
+
+        // Requests are served again once the pool is drained.
+        drop(storage);
+        waiting.await??;
+        let action = middleware.on_request(Request::new(Body::empty()));
+        assert!(is_passed_through(&action));
+
+        Ok(())
+    }
+}
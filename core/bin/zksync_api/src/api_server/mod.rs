@@ -3,13 +3,21 @@
 //! `mod rest` - api is used for block explorer.
 //! `mod rpc_server` - JSON rpc via HTTP (for request reply functions)
 //! `mod rpc_subscriptions` - JSON rpc via WebSocket (for request reply functions and subscriptions)
+//! `mod settings` - settings reloaded on `SIGHUP` without restarting the servers
+//! `mod shutdown` - graceful shutdown of the servers
+//! `mod state_freshness` - circuit breaker of the account state endpoints while the block production is stalled
 
+pub mod address_screening;
+mod backpressure;
 mod event_notify;
 pub mod forced_exit_checker;
 mod helpers;
 pub mod rest;
 pub mod rpc_server;
 pub mod rpc_subscriptions;
+pub mod settings;
+pub mod shutdown;
+pub mod state_freshness;
 mod tx_sender;
 pub mod web3;
 
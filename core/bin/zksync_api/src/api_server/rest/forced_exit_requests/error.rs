@@ -86,6 +86,7 @@ pub enum SumbitErrorCode {
     IncorrectTx = 104,
     TxAdd = 105,
     InappropriateFeeToken = 106,
+    AddressDenied = 107,
 
     Internal = 110,
     CommunicationCoreServer = 111,
@@ -101,6 +102,7 @@ impl SumbitErrorCode {
             SubmitError::IncorrectTx(_) => Self::IncorrectTx,
             SubmitError::TxAdd(_) => Self::TxAdd,
             SubmitError::InappropriateFeeToken => Self::InappropriateFeeToken,
+            SubmitError::AddressDenied(_) => Self::AddressDenied,
             SubmitError::MempoolCommunication(_) => Self::CommunicationCoreServer,
             SubmitError::Internal(_) => Self::Internal,
             SubmitError::Other(_) => Self::Other,
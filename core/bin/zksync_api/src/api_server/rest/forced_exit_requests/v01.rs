@@ -12,10 +12,11 @@ use bigdecimal::{BigDecimal, FromPrimitive};
 use chrono::{Duration, Utc};
 use num::{bigint::ToBigInt, BigUint};
 use std::time::Instant;
-use std::{convert::TryInto, ops::Add};
+use std::{collections::HashSet, convert::TryInto, ops::Add};
 // Workspace uses
 pub use zksync_api_client::rest::forced_exit_requests::{
-    ForcedExitRegisterRequest, ForcedExitRequestStatus,
+    ForcedExitFeeQuote, ForcedExitRegisterRequest, ForcedExitRequestProgressInfo,
+    ForcedExitRequestStatus,
 };
 
 use zksync_api_client::rest::forced_exit_requests::ConfigInfo;
@@ -26,13 +27,16 @@ use zksync_types::{
         ForcedExitEligibilityResponse, ForcedExitRequest, ForcedExitRequestId,
         SaveForcedExitRequestQuery,
     },
-    Address, TokenLike,
+    Address, TokenId, TokenLike,
 };
 
 // Local uses
 use super::{error::ApiError, JsonResult};
 use crate::api_server::forced_exit_checker::ForcedExitAccountAgeChecker;
 
+/// Maximum number of the requests returned for a single target.
+const MAX_REQUESTS_BY_TARGET: u32 = 100;
+
 /// Shared data between `/api/forced_exit_requests/v0.1/` endpoints.
 pub struct ApiForcedExitRequestsData {
     pub(crate) connection_pool: ConnectionPool,
@@ -105,19 +109,41 @@ pub async fn submit_request(
         .map_err(warn_err)
         .map_err(ApiError::internal)?;
 
-    if params.tokens.len() > data.max_tokens_per_request as usize {
+    // The tokens of all the targets are paid for and limited together
+    let exits: Vec<(Address, TokenId)> = std::iter::once((params.target, &params.tokens))
+        .chain(
+            params
+                .additional_targets
+                .iter()
+                .map(|target| (target.target, &target.tokens)),
+        )
+        .flat_map(|(target, tokens)| tokens.iter().map(move |token| (target, *token)))
+        .collect();
+
+    if exits.len() > data.max_tokens_per_request as usize {
         return Err(ApiError::bad_request(
             "Maximum number of tokens per ForcedExit request exceeded",
         ));
     }
 
-    data.forced_exit_checker
-        .validate_forced_exit(&mut storage, params.target)
-        .await
-        .map_err(ApiError::from)?;
+    // The transactions are sent in a batch, so a repeated exit would fail all of them
+    if exits.iter().collect::<HashSet<_>>().len() != exits.len() {
+        return Err(ApiError::bad_request(
+            "Each token of a target can be exited only once per ForcedExit request",
+        ));
+    }
+
+    let targets = std::iter::once(params.target)
+        .chain(params.additional_targets.iter().map(|target| target.target));
+    for target in targets {
+        data.forced_exit_checker
+            .validate_forced_exit(&mut storage, target)
+            .await
+            .map_err(ApiError::from)?;
+    }
 
     let price_of_one_exit = BigDecimal::from(data.price_per_token);
-    let price_of_request = price_of_one_exit * BigDecimal::from_usize(params.tokens.len()).unwrap();
+    let price_of_request = price_of_one_exit * BigDecimal::from_usize(exits.len()).unwrap();
 
     let user_fee = params.price_in_wei.to_bigint().unwrap();
     let user_fee = BigDecimal::from(user_fee);
@@ -130,7 +156,7 @@ pub async fn submit_request(
 
     let mut tokens_schema = storage.tokens_schema();
 
-    for token_id in params.tokens.iter() {
+    for (_, token_id) in exits.iter() {
         // The result is going nowhere.
         // This is simply to make sure that the tokens
         // that were supplied do indeed exist
@@ -149,6 +175,7 @@ pub async fn submit_request(
         .store_request(SaveForcedExitRequestQuery {
             target: params.target,
             tokens: params.tokens.clone(),
+            additional_targets: params.additional_targets.clone(),
             price_in_wei: params.price_in_wei.clone(),
             created_at,
             valid_until,
@@ -192,6 +219,80 @@ pub async fn get_request_by_id(
     }
 }
 
+pub async fn get_fee_quote(
+    data: web::Data<ApiForcedExitRequestsData>,
+    tokens_count: web::Path<u8>,
+) -> JsonResult<ForcedExitFeeQuote> {
+    let start = Instant::now();
+    let tokens_count = *tokens_count;
+
+    if tokens_count == 0 || tokens_count > data.max_tokens_per_request {
+        return Err(ApiError::bad_request(
+            "The number of tokens should be between 1 and the maximum number of tokens per request",
+        ));
+    }
+
+    let price_per_token = BigUint::from(data.price_per_token as u64);
+    let price_in_wei = &price_per_token * BigUint::from(tokens_count);
+
+    metrics::histogram!("api", start.elapsed(), "type" => "v01", "endpoint_name" => "get_forced_exit_fee_quote");
+    Ok(Json(ForcedExitFeeQuote {
+        tokens_count,
+        price_per_token,
+        price_in_wei,
+    }))
+}
+
+pub async fn get_request_progress(
+    data: web::Data<ApiForcedExitRequestsData>,
+    request_id: web::Path<ForcedExitRequestId>,
+) -> JsonResult<ForcedExitRequestProgressInfo> {
+    let start = Instant::now();
+
+    let mut storage = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(warn_err)
+        .map_err(ApiError::internal)?;
+
+    let request = storage
+        .forced_exit_requests_schema()
+        .get_request_by_id(*request_id)
+        .await
+        .map_err(ApiError::internal)?
+        .ok_or_else(|| ApiError::not_found("Request with such id does not exist"))?;
+
+    metrics::histogram!("api", start.elapsed(), "type" => "v01", "endpoint_name" => "get_forced_exit_request_progress");
+    Ok(Json(ForcedExitRequestProgressInfo {
+        progress: request.progress(Utc::now()),
+        request,
+    }))
+}
+
+pub async fn get_requests_by_target(
+    data: web::Data<ApiForcedExitRequestsData>,
+    target: web::Path<Address>,
+) -> JsonResult<Vec<ForcedExitRequest>> {
+    let start = Instant::now();
+
+    let mut storage = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(warn_err)
+        .map_err(ApiError::internal)?;
+
+    let requests = storage
+        .forced_exit_requests_schema()
+        .get_requests_by_target(*target, MAX_REQUESTS_BY_TARGET)
+        .await
+        .map_err(ApiError::internal)?;
+
+    metrics::histogram!("api", start.elapsed(), "type" => "v01", "endpoint_name" => "get_forced_exit_requests_by_target");
+    Ok(Json(requests))
+}
+
 // Checks if the account is eligible for forced_exit in terms of
 // existing enough time
 pub async fn check_account_eligibility(
@@ -234,7 +335,13 @@ pub fn api_scope(
     if config.enabled {
         scope
             .route("/submit", web::post().to(submit_request))
+            .route("/fee/{tokens_count}", web::get().to(get_fee_quote))
             .route("/requests/{id}", web::get().to(get_request_by_id))
+            .route("/requests/{id}/status", web::get().to(get_request_progress))
+            .route(
+                "/targets/{target}/requests",
+                web::get().to(get_requests_by_target),
+            )
             .route(
                 "/checks/eligibility/{account}",
                 web::get().to(check_account_eligibility),
@@ -254,7 +361,10 @@ mod tests {
     use zksync_api_client::rest::client::Client;
     use zksync_config::{ForcedExitRequestsConfig, ZkSyncConfig};
     use zksync_storage::ConnectionPool;
-    use zksync_types::{Address, TokenId};
+    use zksync_types::{
+        forced_exit_requests::{ForcedExitRequestProgress, ForcedExitTarget},
+        Address, TokenId,
+    };
 
     use super::*;
     use crate::api_server::{
@@ -329,6 +439,7 @@ mod tests {
         let register_request = ForcedExitRegisterRequest {
             target: Address::from_str("c0f97CC918C9d6fA4E9fc6be61a6a06589D199b2").unwrap(),
             tokens: vec![TokenId(0)],
+            additional_targets: vec![],
             price_in_wei: BigUint::from_str("1212").unwrap(),
         };
 
@@ -401,6 +512,7 @@ mod tests {
         let register_request = ForcedExitRegisterRequest {
             target: Address::from_str("c0f97CC918C9d6fA4E9fc6be61a6a06589D199b2").unwrap(),
             tokens,
+            additional_targets: vec![],
             price_in_wei,
         };
 
@@ -444,6 +556,7 @@ mod tests {
         let fe_request = ForcedExitRegisterRequest {
             target,
             tokens: tokens.clone(),
+            additional_targets: vec![],
             price_in_wei: price_in_wei.clone(),
         };
 
@@ -453,6 +566,93 @@ mod tests {
         assert_eq!(submit_result.tokens, tokens);
         assert_eq!(submit_result.target, target);
 
+        let quote = client.get_forced_exit_fee_quote(tokens.len() as u8).await?;
+        assert_eq!(quote.price_in_wei, price_in_wei);
+        client
+            .get_forced_exit_fee_quote(max_tokens_per_request + 1)
+            .await
+            .expect_err("Api quotes the requests exceeding the limit on the number of tokens");
+
+        let progress_info = client
+            .get_forced_exit_request_progress(submit_result.id)
+            .await?;
+        assert_eq!(
+            progress_info.progress,
+            ForcedExitRequestProgress::AwaitingPayment
+        );
+        assert_eq!(progress_info.request, submit_result);
+
+        let target_requests = client.get_forced_exit_requests_by_target(target).await?;
+        assert!(target_requests.contains(&submit_result));
+
+        server.stop().await;
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn test_forced_exit_requests_submit_batch() -> anyhow::Result<()> {
+        let price_per_token: i64 = 1000000000000000000;
+        let server_config = get_test_config_from_forced_exit_requests(ForcedExitRequestsConfig {
+            max_tokens_per_request: 3,
+            price_per_token,
+            ..ForcedExitRequestsConfig::from_env()
+        });
+
+        let (client, server) = TestServer::from_config(server_config).await?;
+
+        let target = Address::from_str("c0f97CC918C9d6fA4E9fc6be61a6a06589D199b2").unwrap();
+        let additional_target =
+            Address::from_str("2a0a81e257a2f5d6ed4f07b81dbda09f107bd027").unwrap();
+        let additional_targets = vec![ForcedExitTarget {
+            target: additional_target,
+            tokens: vec![TokenId(0), TokenId(1)],
+        }];
+
+        // The fee has to cover the tokens of all the targets
+        let fe_request = ForcedExitRegisterRequest {
+            target,
+            tokens: vec![TokenId(0)],
+            additional_targets: additional_targets.clone(),
+            price_in_wei: BigUint::from_i64(price_per_token).unwrap(),
+        };
+        client
+            .submit_forced_exit_request(fe_request)
+            .await
+            .expect_err("Api does not take the additional targets into account in the fee");
+
+        // Every token of a target can be exited only once
+        let fe_request = ForcedExitRegisterRequest {
+            target,
+            tokens: vec![TokenId(0)],
+            additional_targets: vec![ForcedExitTarget {
+                target,
+                tokens: vec![TokenId(0)],
+            }],
+            price_in_wei: BigUint::from_i64(price_per_token).unwrap().mul(2u32),
+        };
+        client
+            .submit_forced_exit_request(fe_request)
+            .await
+            .expect_err("Api accepts the repeated exits");
+
+        let fe_request = ForcedExitRegisterRequest {
+            target,
+            tokens: vec![TokenId(0)],
+            additional_targets: additional_targets.clone(),
+            price_in_wei: BigUint::from_i64(price_per_token).unwrap().mul(3u32),
+        };
+        let submit_result = client.submit_forced_exit_request(fe_request).await?;
+        assert_eq!(submit_result.additional_targets, additional_targets);
+
+        let target_requests = client
+            .get_forced_exit_requests_by_target(additional_target)
+            .await?;
+        assert!(target_requests.contains(&submit_result));
+
         server.stop().await;
         Ok(())
     }
@@ -1,7 +1,15 @@
-use actix_cors::Cors;
-use actix_web::{web, App, HttpResponse, HttpServer};
-use futures::channel::mpsc;
+use actix_web::{
+    dev::Service,
+    http::{header, HeaderName, HeaderValue},
+    web, App, HttpResponse, HttpServer,
+};
+use futures::{
+    channel::mpsc,
+    future::{self, Either},
+    FutureExt, TryFutureExt,
+};
 use std::net::SocketAddr;
+use vlog::Instrument;
 use zksync_storage::ConnectionPool;
 use zksync_types::{SequentialTxId, H160};
 
@@ -10,12 +18,19 @@ use zksync_utils::panic_notify::{spawn_panic_handler, ThreadPanicNotify};
 use self::v01::api_decl::ApiV01;
 use crate::signature_checker::VerifySignatureRequest;
 
-use super::tx_sender::TxSender;
+use super::{
+    backpressure::{should_reject, OVERLOADED_MESSAGE},
+    settings::SharedTxPolicy,
+    shutdown::ApiShutdown,
+    state_freshness::StateFreshness,
+    tx_sender::TxSender,
+};
 
 use crate::api_server::rest::network_status::SharedNetworkStatus;
 use crate::fee_ticker::FeeTicker;
 use tokio::task::JoinHandle;
 use zksync_config::ZkSyncConfig;
+use zksync_http_utils::cors::cors;
 use zksync_mempool::MempoolTransactionRequest;
 
 mod forced_exit_requests;
@@ -24,14 +39,31 @@ pub mod network_status;
 mod v01;
 pub mod v02;
 
+/// Header with the ID of the request, which is attached to the logs emitted while processing it.
+/// The ID provided by the client (or the load balancer) is reused, otherwise a new one is generated.
+const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Prefixes of the endpoints serving the account state, which are disabled while it's stale.
+const ACCOUNT_STATE_PATHS: &[&str] = &["/api/v0.1/account/", "/api/v0.2/accounts/"];
+
+fn serves_account_state(path: &str) -> bool {
+    ACCOUNT_STATE_PATHS
+        .iter()
+        .any(|prefix| path.starts_with(prefix))
+}
+
 async fn start_server(
     api_v01: ApiV01,
     fee_ticker: FeeTicker,
     sign_verifier: mpsc::Sender<VerifySignatureRequest>,
+    tx_policy: SharedTxPolicy,
     bind_to: SocketAddr,
     mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
+    state_freshness: StateFreshness,
+    shutdown: ApiShutdown,
 ) {
-    HttpServer::new(move || {
+    let shutdown_timeout = api_v01.config.api.common.shutdown_timeout_sec;
+    let server = HttpServer::new(move || {
         let api_v01 = api_v01.clone();
         // This api stores forced exit requests, it's necessary to use main database connection
         let forced_exit_requests_api_scope = forced_exit_requests::api_scope(
@@ -52,19 +84,58 @@ async fn start_server(
                 fee_ticker.clone(),
                 &api_v01.config.api.common,
                 &api_v01.config.api.token_config,
+                tx_policy.clone(),
                 mempool_tx_sender.clone(),
             );
             v02::api_scope(tx_sender, &api_v01.config, api_v01.network_status.clone())
         };
+        let pool = api_v01.connection_pool.clone();
+        let state_freshness = state_freshness.clone();
         App::new()
-            .wrap(
-                Cors::default()
-                    .send_wildcard()
-                    .max_age(3600)
-                    .allow_any_origin()
-                    .allow_any_header()
-                    .allow_any_method(),
-            )
+            // Requests are rejected before reaching the handlers, but still get the CORS headers.
+            .wrap_fn(move |req, srv| {
+                let staleness = state_freshness
+                    .staleness()
+                    .filter(|_| serves_account_state(req.path()));
+                match staleness {
+                    Some(staleness) => {
+                        let retry_after = state_freshness.retry_after().as_secs().to_string();
+                        let response = HttpResponse::ServiceUnavailable()
+                            .insert_header((header::RETRY_AFTER, retry_after))
+                            .body(staleness.to_string());
+                        Either::Left(future::ready(Ok(req.into_response(response))))
+                    }
+                    None => Either::Right(srv.call(req)),
+                }
+            })
+            .wrap_fn(move |req, srv| {
+                if should_reject(&pool, "rest") {
+                    let response = HttpResponse::ServiceUnavailable().body(OVERLOADED_MESSAGE);
+                    Either::Left(future::ready(Ok(req.into_response(response))))
+                } else {
+                    Either::Right(srv.call(req))
+                }
+            })
+            .wrap(cors(&api_v01.config.api.cors))
+            .wrap_fn(|req, srv| {
+                let request_id = req
+                    .headers()
+                    .get(REQUEST_ID_HEADER)
+                    .and_then(|id| id.to_str().ok())
+                    .map(String::from)
+                    .unwrap_or_else(vlog::new_request_id);
+                let span = vlog::request_span(&request_id);
+                srv.call(req)
+                    .map_ok(move |mut response| {
+                        if let Ok(value) = HeaderValue::from_str(&request_id) {
+                            response
+                                .headers_mut()
+                                .insert(HeaderName::from_static(REQUEST_ID_HEADER), value);
+                        }
+                        response
+                    })
+                    .instrument(span)
+            })
             .service(api_v01.into_scope())
             .service(forced_exit_requests_api_scope)
             .service(api_v02_scope)
@@ -77,12 +148,16 @@ async fn start_server(
     .workers(super::THREADS_PER_SERVER)
     .bind(bind_to)
     .unwrap()
-    .shutdown_timeout(60)
+    // The server is stopped by the `shutdown` registry along with the other API servers.
+    .disable_signals()
+    .shutdown_timeout(shutdown_timeout)
     .keep_alive(10)
     .client_timeout(60000)
-    .run()
-    .await
-    .expect("REST API server has crashed");
+    .run();
+
+    let server_handle = server.clone();
+    shutdown.register("REST", move || server_handle.stop(true).boxed());
+    server.await.expect("REST API server has crashed");
 }
 
 /// Start HTTP REST API
@@ -95,8 +170,11 @@ pub fn start_server_thread_detached(
     contract_address: H160,
     fee_ticker: FeeTicker,
     sign_verifier: mpsc::Sender<VerifySignatureRequest>,
+    tx_policy: SharedTxPolicy,
     mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
     core_address: String,
+    state_freshness: StateFreshness,
+    shutdown: ApiShutdown,
 ) -> JoinHandle<()> {
     let (handler, panic_sender) = spawn_panic_handler();
 
@@ -131,8 +209,11 @@ pub fn start_server_thread_detached(
                     api_v01,
                     fee_ticker,
                     sign_verifier,
+                    tx_policy,
                     listen_addr,
                     mempool_tx_sender.clone(),
+                    state_freshness,
+                    shutdown,
                 )
                 .await;
             });
@@ -7,19 +7,22 @@ use std::time::Instant;
 
 // External uses
 use actix_web::{web, Scope};
+use num::Zero;
 
 // Workspace uses
 use zksync_api_types::v02::{
     account::{Account, AccountAddressOrId, AccountState, IncomingAccountTxsQuery},
+    exit_proof::{ApiExitProofJob, ExitProofJobStatus},
     pagination::{
         parse_query, AccountTxsRequest, ApiEither, Paginated, PaginationQuery, PendingOpsRequest,
+        WithdrawalsRequest,
     },
     transaction::{Transaction, TxHashSerializeWrapper},
 };
 use zksync_crypto::params::{MIN_NFT_TOKEN_ID, NFT_TOKEN_ID_VAL};
 use zksync_storage::{ConnectionPool, StorageProcessor};
 use zksync_token_db_cache::TokenDBCache;
-use zksync_types::{tx::TxHash, AccountId, Address, BlockNumber, SerialId, TokenLike};
+use zksync_types::{tx::TxHash, AccountId, Address, BlockNumber, SerialId, TokenId, TokenLike};
 
 // Local uses
 use super::{
@@ -29,6 +32,10 @@ use super::{
 };
 use crate::{api_server::helpers::get_depositing, api_try, fee_ticker::PriceError};
 
+/// Maximal amount of the exit proofs waiting for the generation, new requests are rejected
+/// once it's reached.
+const MAX_QUEUED_EXIT_PROOFS: u64 = 1000;
+
 /// Shared data between `api/v02/accounts` endpoints.
 #[derive(Clone)]
 struct ApiAccountData {
@@ -231,6 +238,51 @@ impl ApiAccountData {
         result
     }
 
+    async fn account_state_at_block(
+        &self,
+        account_id: AccountId,
+        block_number: BlockNumber,
+    ) -> Result<Option<Account>, Error> {
+        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
+        let mut transaction = storage.start_transaction().await.map_err(Error::storage)?;
+        let last_finalized_block = transaction
+            .chain()
+            .block_schema()
+            .get_last_verified_confirmed_block()
+            .await
+            .map_err(Error::storage)?;
+        if block_number > last_finalized_block {
+            return Err(Error::from(InvalidDataError::BlockNotFinalized));
+        }
+        // Account diffs preceding the earliest state snapshot may be pruned.
+        let first_snapshot_block = transaction
+            .chain()
+            .pruning_schema()
+            .get_first_state_snapshot_block()
+            .await
+            .map_err(Error::storage)?;
+        if matches!(first_snapshot_block, Some(first_block) if block_number < first_block) {
+            return Err(Error::from(InvalidDataError::BlockStatePruned));
+        }
+
+        let (last_update_block, account) = transaction
+            .chain()
+            .account_schema()
+            .account_state_at_block(account_id, block_number)
+            .await
+            .map_err(Error::storage)?;
+        let result = if let Some(account) = account {
+            Ok(Some(
+                self.api_account(account, account_id, last_update_block, &mut transaction)
+                    .await?,
+            ))
+        } else {
+            Ok(None)
+        };
+        transaction.commit().await.map_err(Error::storage)?;
+        result
+    }
+
     async fn account_full_info(
         &self,
         address: Address,
@@ -323,6 +375,25 @@ impl ApiAccountData {
         storage.paginate_checked(&new_query).await
     }
 
+    /// Withdrawals are matched by their L1 recipient, so the withdrawals sent by the other
+    /// accounts to the address are listed as well.
+    async fn account_withdrawals(
+        &self,
+        query: PaginationQuery<ApiEither<TxHash>>,
+        address: Address,
+    ) -> Result<Paginated<Transaction, TxHashSerializeWrapper>, Error> {
+        let new_query = PaginationQuery {
+            from: WithdrawalsRequest {
+                address,
+                tx_hash: query.from,
+            },
+            limit: query.limit,
+            direction: query.direction,
+        };
+        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
+        storage.paginate_checked(&new_query).await
+    }
+
     /// Pending deposits can be matched only with addresses,
     /// while pending full exits can be matched only with account ids.
     /// If the account isn't created yet it doesn't have an id
@@ -345,6 +416,91 @@ impl ApiAccountData {
         let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
         storage.paginate_checked(&new_query).await
     }
+
+    /// Exit proofs are generated against the state of the last finalized block,
+    /// so the proof is requested again once the new block is finalized.
+    /// Only the tokens the account has a finalized balance of can be exited.
+    async fn request_exit_proof(
+        &self,
+        account_id: AccountId,
+        token_like: TokenLike,
+    ) -> Result<ApiExitProofJob, Error> {
+        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
+        let (token_id, block_number) = self.exit_proof_target(&mut storage, token_like).await?;
+
+        let account = storage
+            .chain()
+            .account_schema()
+            .last_verified_state_for_account(account_id)
+            .await
+            .map_err(Error::storage)?
+            .ok_or_else(|| Error::from(InvalidDataError::AccountNotFound))?;
+        if account.get_balance(token_id).is_zero() {
+            return Err(Error::from(InvalidDataError::NothingToExit));
+        }
+
+        // Existing jobs are returned as is, so the limit applies only to the new ones.
+        if let Some(job) = storage
+            .exit_proofs_schema()
+            .get_exit_proof_job(account_id, token_id, block_number)
+            .await
+            .map_err(Error::storage)?
+        {
+            if job.status != ExitProofJobStatus::Failed {
+                return Ok(job);
+            }
+        }
+        let queued = storage
+            .exit_proofs_schema()
+            .count_queued_exit_proof_jobs()
+            .await
+            .map_err(Error::storage)?;
+        if queued >= MAX_QUEUED_EXIT_PROOFS {
+            return Err(Error::from(InvalidDataError::ExitProofQueueFull));
+        }
+
+        storage
+            .exit_proofs_schema()
+            .request_exit_proof(account_id, token_id, block_number)
+            .await
+            .map_err(Error::storage)
+    }
+
+    async fn exit_proof(
+        &self,
+        account_id: AccountId,
+        token_like: TokenLike,
+    ) -> Result<Option<ApiExitProofJob>, Error> {
+        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
+        let (token_id, block_number) = self.exit_proof_target(&mut storage, token_like).await?;
+        storage
+            .exit_proofs_schema()
+            .get_exit_proof_job(account_id, token_id, block_number)
+            .await
+            .map_err(Error::storage)
+    }
+
+    /// Returns the token id along with the last finalized block the exit proof is generated against.
+    async fn exit_proof_target(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        token_like: TokenLike,
+    ) -> Result<(TokenId, BlockNumber), Error> {
+        let token_id = self
+            .tokens
+            .get_token(storage, token_like.clone())
+            .await
+            .map_err(Error::storage)?
+            .ok_or_else(|| Error::from(PriceError::token_not_found(token_like)))?
+            .id;
+        let block_number = storage
+            .chain()
+            .block_schema()
+            .get_last_verified_confirmed_block()
+            .await
+            .map_err(Error::storage)?;
+        Ok((token_id, block_number))
+    }
 }
 
 async fn account_committed_info(
@@ -379,6 +535,25 @@ async fn account_finalized_info(
     res
 }
 
+async fn account_state_at_block(
+    data: web::Data<ApiAccountData>,
+    path: web::Path<(String, BlockNumber)>,
+) -> ApiResult<Option<Account>> {
+    let start = Instant::now();
+    let (account_id_or_address, block_number) = path.into_inner();
+    let address_or_id = api_try!(data.parse_account_id_or_address(&account_id_or_address));
+    let account_id = api_try!(data.get_id_by_address_or_id(address_or_id).await);
+    let res = if let Some(account_id) = account_id {
+        data.account_state_at_block(account_id, block_number)
+            .await
+            .into()
+    } else {
+        ApiResult::Ok(None)
+    };
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "account_state_at_block");
+    res
+}
+
 async fn account_full_info(
     data: web::Data<ApiAccountData>,
     account_id_or_address: web::Path<String>,
@@ -451,6 +626,61 @@ async fn account_pending_txs(
     res
 }
 
+async fn account_withdrawals(
+    data: web::Data<ApiAccountData>,
+    account_id_or_address: web::Path<String>,
+    web::Query(query): web::Query<PaginationQuery<String>>,
+) -> ApiResult<Paginated<Transaction, TxHashSerializeWrapper>> {
+    let start = Instant::now();
+    let query = api_try!(parse_query(query).map_err(Error::from));
+    let address_or_id = api_try!(data.parse_account_id_or_address(&account_id_or_address));
+    let address = api_try!(data.get_address_by_address_or_id(address_or_id).await);
+    let res = data.account_withdrawals(query, address).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "account_withdrawals");
+    res
+}
+
+async fn request_exit_proof(
+    data: web::Data<ApiAccountData>,
+    path: web::Path<(String, String)>,
+) -> ApiResult<ApiExitProofJob> {
+    let start = Instant::now();
+    let (account_id_or_address, token_like) = path.into_inner();
+    let address_or_id = api_try!(data.parse_account_id_or_address(&account_id_or_address));
+    let account_id =
+        api_try!(data
+            .get_id_by_address_or_id(address_or_id)
+            .await
+            .and_then(|account_id| {
+                account_id.ok_or_else(|| Error::from(InvalidDataError::AccountNotFound))
+            }));
+    let res = data
+        .request_exit_proof(account_id, TokenLike::parse(&token_like))
+        .await
+        .into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "request_exit_proof");
+    res
+}
+
+async fn exit_proof(
+    data: web::Data<ApiAccountData>,
+    path: web::Path<(String, String)>,
+) -> ApiResult<Option<ApiExitProofJob>> {
+    let start = Instant::now();
+    let (account_id_or_address, token_like) = path.into_inner();
+    let address_or_id = api_try!(data.parse_account_id_or_address(&account_id_or_address));
+    let account_id = api_try!(data.get_id_by_address_or_id(address_or_id).await);
+    let res = if let Some(account_id) = account_id {
+        data.exit_proof(account_id, TokenLike::parse(&token_like))
+            .await
+            .into()
+    } else {
+        ApiResult::Ok(None)
+    };
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "exit_proof");
+    res
+}
+
 pub fn api_scope(
     pool: ConnectionPool,
     tokens: TokenDBCache,
@@ -468,6 +698,10 @@ pub fn api_scope(
             "{account_id_or_address}/finalized",
             web::get().to(account_finalized_info),
         )
+        .route(
+            "{account_id_or_address}/finalized/{block_number}",
+            web::get().to(account_state_at_block),
+        )
         .route("{account_id_or_address}", web::get().to(account_full_info))
         .route(
             "{account_id_or_address}/transactions",
@@ -477,13 +711,23 @@ pub fn api_scope(
             "{account_id_or_address}/transactions/pending",
             web::get().to(account_pending_txs),
         )
+        .route(
+            "{account_id_or_address}/withdrawals",
+            web::get().to(account_withdrawals),
+        )
+        .service(
+            web::resource("{account_id_or_address}/exitProof/{token_like}")
+                .route(web::get().to(exit_proof))
+                .route(web::post().to(request_exit_proof)),
+        )
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::api_server::rest::v02::{
-        test_utils::{deserialize_response_result, TestServerConfig},
+        error::ErrorCode,
+        test_utils::{deserialize_response_result, TestServerConfig, EXECUTED_BLOCKS_COUNT},
         SharedData,
     };
     use num::BigUint;
@@ -534,7 +778,7 @@ mod tests {
                         TokenDBCache::new(
                             cfg.config.api.token_config.invalidate_token_cache_period(),
                         ),
-                        cfg.config.eth_watch.confirmations_for_eth_event,
+                        cfg.config.eth_watch.deposit_confirmations(),
                     )
                 },
                 Some(shared_data),
@@ -545,7 +789,7 @@ mod tests {
                 Self {
                     api_server,
                     pool,
-                    confirmations_for_eth_event: cfg.config.eth_watch.confirmations_for_eth_event,
+                    confirmations_for_eth_event: cfg.config.eth_watch.deposit_confirmations(),
                 },
             ))
         }
@@ -611,6 +855,55 @@ mod tests {
             .await?;
         let account_finalized_info: Option<Account> = deserialize_response_result(response)?;
 
+        let response = client
+            .account_state_at_block(&account_id.to_string(), BlockNumber(EXECUTED_BLOCKS_COUNT))
+            .await?;
+        let _: Option<Account> = deserialize_response_result(response)?;
+        let response = client
+            .account_state_at_block(
+                &account_id.to_string(),
+                BlockNumber(EXECUTED_BLOCKS_COUNT + 1),
+            )
+            .await?;
+        let error = serde_json::from_value::<Error>(response.error.unwrap()).unwrap();
+        assert_eq!(error.code, ErrorCode::BlockNotFinalized);
+
+        let response = client.exit_proof(&account_id.to_string(), "ETH").await?;
+        let job: Option<ApiExitProofJob> = deserialize_response_result(response)?;
+        assert_eq!(job, None);
+        let response = client
+            .request_exit_proof(&account_id.to_string(), "ETH")
+            .await?;
+        // Only the tokens with the finalized balance can be exited.
+        let has_finalized_eth = account_finalized_info
+            .as_ref()
+            .and_then(|account| account.balances.get("ETH"))
+            .map_or(false, |balance| !balance.0.is_zero());
+        if has_finalized_eth {
+            let requested_job: ApiExitProofJob = deserialize_response_result(response)?;
+            assert_eq!(requested_job.account_id, account_id);
+            assert_eq!(requested_job.token_id, TokenId(0));
+            assert_eq!(requested_job.status, ExitProofJobStatus::Queued);
+            let response = client.exit_proof(&format!("{:?}", address), "ETH").await?;
+            let job: Option<ApiExitProofJob> = deserialize_response_result(response)?;
+            assert_eq!(job, Some(requested_job.clone()));
+
+            // Repeated requests return the same job.
+            let response = client
+                .request_exit_proof(&account_id.to_string(), "ETH")
+                .await?;
+            let job: ApiExitProofJob = deserialize_response_result(response)?;
+            assert_eq!(job.id, requested_job.id);
+        } else {
+            let error = serde_json::from_value::<Error>(response.error.unwrap()).unwrap();
+            let expected_code = if account_finalized_info.is_some() {
+                ErrorCode::NothingToExit
+            } else {
+                ErrorCode::AccountNotFound
+            };
+            assert_eq!(error.code, expected_code);
+        }
+
         {
             let mut storage = server.pool.access_storage().await?;
             storage
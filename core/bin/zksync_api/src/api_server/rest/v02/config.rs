@@ -33,7 +33,7 @@ impl ApiConfigData {
             network: config.chain.eth.network,
             contract: config.contracts.contract_addr,
             gov_contract: config.contracts.governance_addr,
-            deposit_confirmations: config.eth_watch.confirmations_for_eth_event,
+            deposit_confirmations: config.eth_watch.deposit_confirmations(),
             zksync_version: ZksyncVersion::ContractV4,
         }
     }
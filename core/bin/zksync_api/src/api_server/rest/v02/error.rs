@@ -27,6 +27,13 @@ pub enum ErrorCode {
     PaginationLimitTooBig = 206,
     QueryDeserializationError = 207,
     InvalidNFTTokenId = 208,
+    InvalidFactoryRegistrationSignature = 209,
+    FactoryCreatorAddressMismatch = 210,
+    FactoryRegistrationDeprecated = 211,
+    BlockNotFinalized = 212,
+    BlockStatePruned = 213,
+    NothingToExit = 214,
+    ExitProofQueueFull = 215,
     StorageError = 300,
     TokenNotFound = 500,
     ExternalApiError = 501,
@@ -39,6 +46,7 @@ pub enum ErrorCode {
     InappropriateFeeToken = 606,
     CommunicationCoreServer = 607,
     Toggle2FAError = 608,
+    AddressDenied = 609,
     Other = 60_000,
 }
 
@@ -103,6 +111,20 @@ pub enum InvalidDataError {
     PaginationLimitTooBig,
     #[error("NFT token ID should be greater than or equal to {}", MIN_NFT_TOKEN_ID)]
     InvalidNFTTokenId,
+    #[error("Factory registration is not signed by the creator")]
+    InvalidFactoryRegistrationSignature,
+    #[error("Creator account has another address")]
+    FactoryCreatorAddressMismatch,
+    #[error("Factory registration is deprecated")]
+    FactoryRegistrationDeprecated,
+    #[error("Block is not finalized yet")]
+    BlockNotFinalized,
+    #[error("Account state of the block is pruned")]
+    BlockStatePruned,
+    #[error("Account has no finalized balance of the token")]
+    NothingToExit,
+    #[error("Too many exit proofs are queued, try again later")]
+    ExitProofQueueFull,
 }
 
 impl ApiError for InvalidDataError {
@@ -120,6 +142,15 @@ impl ApiError for InvalidDataError {
             Self::TransactionNotFound => ErrorCode::TransactionNotFound,
             Self::PaginationLimitTooBig => ErrorCode::PaginationLimitTooBig,
             Self::InvalidNFTTokenId => ErrorCode::InvalidNFTTokenId,
+            Self::InvalidFactoryRegistrationSignature => {
+                ErrorCode::InvalidFactoryRegistrationSignature
+            }
+            Self::FactoryCreatorAddressMismatch => ErrorCode::FactoryCreatorAddressMismatch,
+            Self::FactoryRegistrationDeprecated => ErrorCode::FactoryRegistrationDeprecated,
+            Self::BlockNotFinalized => ErrorCode::BlockNotFinalized,
+            Self::BlockStatePruned => ErrorCode::BlockStatePruned,
+            Self::NothingToExit => ErrorCode::NothingToExit,
+            Self::ExitProofQueueFull => ErrorCode::ExitProofQueueFull,
         }
     }
 }
@@ -197,6 +228,7 @@ impl ApiError for SubmitError {
             Self::IncorrectTx(_) => ErrorCode::IncorrectTx,
             Self::TxAdd(_) => ErrorCode::TxAddError,
             Self::InappropriateFeeToken => ErrorCode::InappropriateFeeToken,
+            Self::AddressDenied(_) => ErrorCode::AddressDenied,
             Self::MempoolCommunication(_) => ErrorCode::CommunicationCoreServer,
             Self::Internal(_) => ErrorCode::InternalError,
             Self::Toggle2FA(_) => ErrorCode::Toggle2FAError,
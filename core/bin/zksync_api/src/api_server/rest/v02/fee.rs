@@ -107,6 +107,7 @@ mod tests {
         },
         SharedData,
     };
+    use crate::api_server::settings::{SharedTxPolicy, TxPolicy};
     use crate::fee_ticker::validator::cache::TokenInMemoryCache;
     use chrono::Utc;
     use futures::channel::mpsc;
@@ -171,6 +172,7 @@ mod tests {
                     dummy_fee_ticker(&prices, Some(cache.clone())),
                     &cfg.config.api.common,
                     &cfg.config.api.token_config,
+                    SharedTxPolicy::new(TxPolicy::from_config(&cfg.config.api.common)),
                     mempool_tx_request_sender.clone(),
                 ))
             },
@@ -46,7 +46,7 @@ pub(crate) fn api_scope(
         .service(account::api_scope(
             tx_sender.pool.clone(),
             tx_sender.tokens.clone(),
-            zk_config.eth_watch.confirmations_for_eth_event,
+            zk_config.eth_watch.deposit_confirmations(),
         ))
         .service(block::api_scope(
             tx_sender.pool.clone(),
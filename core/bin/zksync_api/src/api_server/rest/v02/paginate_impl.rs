@@ -7,9 +7,10 @@ use zksync_api_types::{
     v02::{
         block::BlockInfo,
         pagination::{
-            AccountTxsRequest, ApiEither, BlockAndTxHash, Paginated, PaginationQuery,
-            PendingOpsRequest,
+            AccountTxsRequest, ApiEither, BlockAndTxHash, NFTFactoriesRequest, Paginated,
+            PaginationQuery, PendingOpsRequest, WithdrawalsRequest,
         },
+        token::NFTFactoryRegistration,
         transaction::{Transaction, TxHashSerializeWrapper},
     },
     Either,
@@ -267,6 +268,75 @@ impl Paginate<AccountTxsRequest> for StorageProcessor<'_> {
     }
 }
 
+#[async_trait::async_trait]
+impl Paginate<WithdrawalsRequest> for StorageProcessor<'_> {
+    type OutputObj = Transaction;
+    type OutputId = TxHashSerializeWrapper;
+
+    async fn paginate(
+        &mut self,
+        query: &PaginationQuery<WithdrawalsRequest>,
+    ) -> Result<Paginated<Transaction, TxHashSerializeWrapper>, Error> {
+        let mut transaction = self.start_transaction().await.map_err(Error::storage)?;
+
+        let tx_hash = match query.from.tx_hash.inner {
+            Either::Left(tx_hash) => tx_hash,
+            Either::Right(_) => {
+                if let Some(tx_hash) = transaction
+                    .chain()
+                    .operations_ext_schema()
+                    .get_last_withdrawal_tx_hash(query.from.address)
+                    .await
+                    .map_err(Error::storage)?
+                {
+                    tx_hash
+                } else {
+                    return Ok(Paginated::new(
+                        Vec::new(),
+                        Default::default(),
+                        query.limit,
+                        query.direction,
+                        0,
+                    ));
+                }
+            }
+        };
+
+        let query = PaginationQuery {
+            from: WithdrawalsRequest {
+                tx_hash: ApiEither::from(tx_hash),
+                ..query.from
+            },
+            limit: query.limit,
+            direction: query.direction,
+        };
+
+        let txs = transaction
+            .chain()
+            .operations_ext_schema()
+            .get_withdrawals_to_address(&query)
+            .await
+            .map_err(Error::storage)?
+            .ok_or_else(|| Error::from(InvalidDataError::TransactionNotFound))?;
+        let count = transaction
+            .chain()
+            .operations_ext_schema()
+            .get_withdrawals_count(query.from.address)
+            .await
+            .map_err(Error::storage)?;
+
+        transaction.commit().await.map_err(Error::storage)?;
+
+        Ok(Paginated::new(
+            txs,
+            TxHashSerializeWrapper(tx_hash),
+            query.limit,
+            query.direction,
+            count,
+        ))
+    }
+}
+
 #[async_trait::async_trait]
 impl Paginate<PendingOpsRequest> for StorageProcessor<'_> {
     type OutputObj = Transaction;
@@ -338,3 +408,50 @@ impl Paginate<PendingOpsRequest> for StorageProcessor<'_> {
         ))
     }
 }
+
+#[async_trait::async_trait]
+impl Paginate<NFTFactoriesRequest> for StorageProcessor<'_> {
+    type OutputObj = NFTFactoryRegistration;
+    type OutputId = i64;
+
+    async fn paginate(
+        &mut self,
+        query: &PaginationQuery<NFTFactoriesRequest>,
+    ) -> Result<Paginated<NFTFactoryRegistration, i64>, Error> {
+        let mut transaction = self.start_transaction().await.map_err(Error::storage)?;
+
+        let creator_id = Some(query.from.creator_id);
+        let (count, last_id) = transaction
+            .tokens_schema()
+            .get_nft_factory_registrations_count(creator_id, false)
+            .await
+            .map_err(Error::storage)?;
+        let registration_id = match query.from.registration_id.inner {
+            Either::Left(registration_id) => registration_id,
+            Either::Right(_) => last_id.unwrap_or_default(),
+        };
+
+        let query = PaginationQuery {
+            from: registration_id,
+            limit: query.limit,
+            direction: query.direction,
+        };
+        let registrations = transaction
+            .tokens_schema()
+            .load_nft_factory_registrations(creator_id, false, &query)
+            .await
+            .map_err(Error::storage)?
+            .into_iter()
+            .map(NFTFactoryRegistration::from)
+            .collect();
+        transaction.commit().await.map_err(Error::storage)?;
+
+        Ok(Paginated::new(
+            registrations,
+            query.from,
+            query.limit,
+            query.direction,
+            count,
+        ))
+    }
+}
@@ -36,7 +36,7 @@ use zksync_types::{
     aggregated_operations::AggregatedActionType,
     helpers::{apply_updates, closest_packable_fee_amount, closest_packable_token_amount},
     operations::{ChangePubKeyOp, TransferToNewOp},
-    prover::ProverJobType,
+    prover::{ProverJobPriorityPolicy, ProverJobType},
     tx::ChangePubKeyType,
     AccountId, AccountMap, AccountUpdate, Address, BlockNumber, Deposit, DepositOp,
     ExecutedOperations, ExecutedPriorityOp, ExecutedTx, FullExit, FullExitOp, MintNFTOp, Nonce,
@@ -500,6 +500,7 @@ impl TestServerConfig {
                     100,
                     100u32.into(),
                     Default::default(),
+                    None,
                 )
                 .await?;
             storage
@@ -535,13 +536,17 @@ impl TestServerConfig {
                     .await?;
 
                 // Get job id.
+                let priority_policy = ProverJobPriorityPolicy {
+                    aggregated_proof_deadline: std::time::Duration::from_secs(600),
+                    preemption_window: std::time::Duration::from_secs(120),
+                };
                 let stored_job_id = ProverSchema(&mut storage)
-                    .get_idle_prover_job_from_job_queue()
+                    .get_idle_prover_job_from_job_queue(&priority_policy)
                     .await?
                     .unwrap()
                     .job_id;
                 let stored_aggregated_job_id = ProverSchema(&mut storage)
-                    .get_idle_prover_job_from_job_queue()
+                    .get_idle_prover_job_from_job_queue(&priority_policy)
                     .await?
                     .unwrap()
                     .job_id;
@@ -586,6 +591,7 @@ impl TestServerConfig {
                         100,
                         100u32.into(),
                         Default::default(),
+                        None,
                     )
                     .await?;
                 let eth_tx_hash = dummy_ethereum_tx_hash(id);
@@ -627,6 +633,7 @@ impl TestServerConfig {
                         100,
                         100u32.into(),
                         Default::default(),
+                        None,
                     )
                     .await?;
                 storage
@@ -6,7 +6,7 @@ use std::time::Instant;
 
 // External uses
 use actix_web::{
-    web::{self},
+    web::{self, Json},
     Scope,
 };
 use bigdecimal::{BigDecimal, Zero};
@@ -14,14 +14,19 @@ use num::{rational::Ratio, BigUint, FromPrimitive};
 
 // Workspace uses
 use zksync_api_types::v02::{
-    pagination::{parse_query, ApiEither, Paginated, PaginationQuery},
-    token::{ApiNFT, ApiToken, TokenPrice},
+    pagination::{parse_query, ApiEither, NFTFactoriesRequest, Paginated, PaginationQuery},
+    token::{
+        ApiNFT, ApiToken, ApiTokenHolders, ApiTokenMetadata, NFTFactoryRegistration,
+        RegisterNFTFactoryRequest, TokenListingRejection, TokenPrice,
+    },
 };
 use zksync_config::ZkSyncConfig;
 use zksync_crypto::params::MIN_NFT_TOKEN_ID;
 use zksync_storage::{ConnectionPool, StorageProcessor};
 use zksync_token_db_cache::TokenDBCache;
-use zksync_types::{tx::TxHash, AccountId, Token, TokenId, TokenLike};
+use zksync_types::{
+    register_factory::register_factory_message, tx::TxHash, AccountId, Token, TokenId, TokenLike,
+};
 
 // Local uses
 use super::{
@@ -158,6 +163,7 @@ impl ApiTokenData {
             .await
             .map_err(Error::storage)
     }
+
     // TODO: take `currency` as enum. (ZKS-628)
     async fn token_price_in(
         &self,
@@ -235,6 +241,38 @@ async fn token_price(
     })
 }
 
+async fn token_metadata(
+    data: web::Data<ApiTokenData>,
+    token_like_string: web::Path<String>,
+) -> ApiResult<Option<ApiTokenMetadata>> {
+    let start = Instant::now();
+    let token = api_try!(data.token(TokenLike::parse(&token_like_string)).await);
+    let mut storage = api_try!(data.pool.access_storage().await.map_err(Error::storage));
+    let metadata = api_try!(storage
+        .tokens_schema()
+        .get_token_metadata(token.id)
+        .await
+        .map_err(Error::storage));
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "token_metadata");
+    ApiResult::Ok(metadata.map(Into::into))
+}
+
+async fn token_holders(
+    data: web::Data<ApiTokenData>,
+    token_like_string: web::Path<String>,
+) -> ApiResult<Option<ApiTokenHolders>> {
+    let start = Instant::now();
+    let token = api_try!(data.token(TokenLike::parse(&token_like_string)).await);
+    let mut storage = api_try!(data.pool.access_storage().await.map_err(Error::storage));
+    let holders = api_try!(storage
+        .tokens_schema()
+        .get_token_holders(token.id)
+        .await
+        .map_err(Error::storage));
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "token_holders");
+    ApiResult::Ok(holders)
+}
+
 async fn get_nft(
     data: web::Data<ApiTokenData>,
     id: web::Path<TokenId>,
@@ -288,6 +326,101 @@ async fn get_nft_id_by_tx_hash(
     ApiResult::Ok(nft_id)
 }
 
+async fn nft_factories(
+    data: web::Data<ApiTokenData>,
+    creator_id: web::Path<AccountId>,
+    web::Query(query): web::Query<PaginationQuery<String>>,
+) -> ApiResult<Paginated<NFTFactoryRegistration, i64>> {
+    let start = Instant::now();
+    let query = api_try!(parse_query(query).map_err(Error::from));
+    let query = PaginationQuery {
+        from: NFTFactoriesRequest {
+            creator_id: *creator_id,
+            registration_id: query.from,
+        },
+        limit: query.limit,
+        direction: query.direction,
+    };
+    let mut storage = api_try!(data.pool.access_storage().await.map_err(Error::storage));
+    let res = storage.paginate_checked(&query).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "nft_factories");
+    res
+}
+
+/// Registers the factory signed by the creator. The signature is checked the same way
+/// the contract does, so the registration can be submitted to the contract as is.
+async fn register_nft_factory(
+    data: web::Data<ApiTokenData>,
+    Json(request): Json<RegisterNFTFactoryRequest>,
+) -> ApiResult<NFTFactoryRegistration> {
+    let start = Instant::now();
+    let message = register_factory_message(
+        request.creator_id,
+        request.creator_address,
+        request.factory_address,
+    );
+    let signer = request.signature.signature_recover_signer(&message);
+    if !matches!(signer, Ok(signer) if signer == request.creator_address) {
+        return Error::from(InvalidDataError::InvalidFactoryRegistrationSignature).into();
+    }
+
+    let mut storage = api_try!(data.pool.access_storage().await.map_err(Error::storage));
+    // The contract only accepts the factories of the existing creators.
+    let creator_address = api_try!(storage
+        .chain()
+        .account_schema()
+        .account_address_by_id(request.creator_id)
+        .await
+        .map_err(Error::storage));
+    match creator_address {
+        None => return Error::from(InvalidDataError::AccountNotFound).into(),
+        Some(address) if address != request.creator_address => {
+            return Error::from(InvalidDataError::FactoryCreatorAddressMismatch).into()
+        }
+        Some(_) => {}
+    }
+
+    let id = api_try!(storage
+        .tokens_schema()
+        .store_nft_factory_registration(
+            request.creator_id,
+            request.creator_address,
+            request.factory_address,
+            &request.signature.serialize_packed(),
+        )
+        .await
+        .map_err(Error::storage));
+    let registration = api_try!(storage
+        .tokens_schema()
+        .load_nft_factory_registration(id)
+        .await
+        .map_err(Error::storage))
+    .expect("registration is just stored");
+    // The registration message has no nonce, so a deprecated factory can't be restored by the creator.
+    if registration.deprecated_at.is_some() {
+        return Error::from(InvalidDataError::FactoryRegistrationDeprecated).into();
+    }
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "register_nft_factory");
+    ApiResult::Ok(registration.into())
+}
+
+/// Max amount of the listing rejections returned at once.
+const MAX_LISTING_REJECTIONS: u32 = 100;
+
+async fn listing_rejections(
+    data: web::Data<ApiTokenData>,
+) -> ApiResult<Vec<TokenListingRejection>> {
+    let start = Instant::now();
+    let mut storage = api_try!(data.pool.access_storage().await.map_err(Error::storage));
+    let rejections = api_try!(storage
+        .tokens_schema()
+        .load_listing_rejections(MAX_LISTING_REJECTIONS)
+        .await
+        .map_err(Error::storage));
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "listing_rejections");
+    ApiResult::Ok(rejections.into_iter().map(Into::into).collect())
+}
+
 pub fn api_scope(
     config: &ZkSyncConfig,
     pool: ConnectionPool,
@@ -299,17 +432,22 @@ pub fn api_scope(
     web::scope("tokens")
         .app_data(web::Data::new(data))
         .route("", web::get().to(token_pagination))
+        .route("listing_rejections", web::get().to(listing_rejections))
         .route("{token_like}", web::get().to(token_info))
         .route(
             "{token_like}/priceIn/{currency}",
             web::get().to(token_price),
         )
+        .route("{token_like}/metadata", web::get().to(token_metadata))
+        .route("{token_like}/holders", web::get().to(token_holders))
         .route("nft/{id}", web::get().to(get_nft))
         .route("nft/{id}/owner", web::get().to(get_nft_owner))
         .route(
             "nft_id_by_tx_hash/{tx_hash}",
             web::get().to(get_nft_id_by_tx_hash),
         )
+        .route("nft_factories", web::post().to(register_nft_factory))
+        .route("nft_factories/{creator_id}", web::get().to(nft_factories))
 }
 
 #[cfg(test)]
@@ -320,7 +458,9 @@ mod tests {
         SharedData,
     };
     use zksync_api_types::v02::{pagination::PaginationDirection, ApiVersion};
-    use zksync_types::{Address, BlockNumber, ZkSyncTx};
+    use zksync_types::{
+        tx::PackedEthSignature, AccountUpdate, Address, BlockNumber, Nonce, ZkSyncTx, H256,
+    };
 
     async fn is_token_enabled_for_fees(
         storage: &mut StorageProcessor<'_>,
@@ -456,6 +596,11 @@ mod tests {
         let response = client.token_price(&token_like, "333").await?;
         assert!(response.error.is_some());
 
+        // Holders aren't collected in the test data.
+        let response = client.token_holders(&token_like).await?;
+        let holders: Option<ApiTokenHolders> = deserialize_response_result(response)?;
+        assert!(holders.is_none());
+
         let nft_id = TokenId(65542);
         let response = client.nft_by_id(nft_id).await?;
         let nft: ApiNFT = deserialize_response_result(response)?;
@@ -500,6 +645,85 @@ mod tests {
         let nft_id: Option<TokenId> = deserialize_response_result(response)?;
         assert!(nft_id.is_some());
 
+        let rejected_address = Address::random();
+        {
+            let mut storage = cfg.pool.access_storage().await?;
+            storage
+                .tokens_schema()
+                .store_listing_rejection(TokenId(1000), rejected_address, "symbol is taken", None)
+                .await?;
+        }
+        let response = client.token_listing_rejections().await?;
+        let rejections: Vec<TokenListingRejection> = deserialize_response_result(response)?;
+        assert_eq!(rejections[0].token_id, TokenId(1000));
+        assert_eq!(rejections[0].address, rejected_address);
+        assert_eq!(rejections[0].reason, "symbol is taken");
+
+        let private_key = H256::random();
+        let creator_address = PackedEthSignature::address_from_private_key(&private_key)?;
+        let factory_address = Address::random();
+        let message = register_factory_message(AccountId(1000), creator_address, factory_address);
+        let mut request = RegisterNFTFactoryRequest {
+            creator_id: AccountId(1000),
+            creator_address,
+            factory_address,
+            signature: PackedEthSignature::sign(&private_key, &message)?,
+        };
+        // The creator account doesn't exist yet.
+        let response = client.register_nft_factory(&request).await?;
+        assert!(deserialize_response_result::<NFTFactoryRegistration>(response).is_err());
+        {
+            let mut storage = cfg.pool.access_storage().await?;
+            let updates = vec![(
+                AccountId(1000),
+                AccountUpdate::Create {
+                    address: creator_address,
+                    nonce: Nonce(0),
+                },
+            )];
+            storage
+                .chain()
+                .state_schema()
+                .commit_state_update(BlockNumber(1000), &updates, 0)
+                .await?;
+        }
+
+        request.signature = PackedEthSignature::sign(&H256::random(), &message)?;
+        let response = client.register_nft_factory(&request).await?;
+        assert!(deserialize_response_result::<NFTFactoryRegistration>(response).is_err());
+
+        request.signature = PackedEthSignature::sign(&private_key, &message)?;
+        let response = client.register_nft_factory(&request).await?;
+        let registration: NFTFactoryRegistration = deserialize_response_result(response)?;
+        assert_eq!(registration.factory_address, factory_address);
+        assert!(!registration.registered_on_chain);
+
+        let query = PaginationQuery {
+            from: ApiEither::from(registration.id),
+            limit: 10,
+            direction: PaginationDirection::Older,
+        };
+        let response = client.nft_factories(AccountId(1000), &query).await?;
+        let registrations: Paginated<NFTFactoryRegistration, i64> =
+            deserialize_response_result(response)?;
+        assert_eq!(registrations.list, vec![registration.clone()]);
+        assert_eq!(registrations.pagination.count, 1);
+
+        // The deprecated registration can't be restored by replaying the signature.
+        {
+            let mut storage = cfg.pool.access_storage().await?;
+            storage
+                .tokens_schema()
+                .deprecate_nft_factory_registration(registration.id)
+                .await?;
+        }
+        let response = client.register_nft_factory(&request).await?;
+        assert!(deserialize_response_result::<NFTFactoryRegistration>(response).is_err());
+        let response = client.nft_factories(AccountId(1000), &query).await?;
+        let registrations: Paginated<NFTFactoryRegistration, i64> =
+            deserialize_response_result(response)?;
+        assert!(registrations.list.is_empty());
+
         server.stop().await;
         Ok(())
     }
@@ -11,9 +11,9 @@ use actix_web::{
 // Workspace uses
 use zksync_api_types::{
     v02::transaction::{
-        ApiTxBatch, IncomingTxBatch, L1Receipt, L1Transaction, Receipt, SubmitBatchResponse,
-        Toggle2FA, Toggle2FAResponse, Transaction, TransactionData, TxData, TxHashSerializeWrapper,
-        TxInBlockStatus,
+        ApiTxBatch, CancelTx, CancelTxResponse, IncomingTxBatch, L1Receipt, L1Transaction, Receipt,
+        SignatureVerification, SignedData, SubmitBatchResponse, Toggle2FA, Toggle2FAResponse,
+        Transaction, TransactionData, TxData, TxHashSerializeWrapper, TxInBlockStatus,
     },
     TxWithSignature,
 };
@@ -225,6 +225,36 @@ async fn toggle_2fa(
     response.into()
 }
 
+async fn cancel_tx(
+    data: web::Data<ApiTransactionData>,
+    Json(cancel_tx): Json<CancelTx>,
+) -> ApiResult<CancelTxResponse> {
+    let start = Instant::now();
+    let response = data
+        .tx_sender
+        .cancel_tx(cancel_tx)
+        .await
+        .map_err(Error::from);
+
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "cancel_tx");
+    response.into()
+}
+
+async fn verify_signature(
+    data: web::Data<ApiTransactionData>,
+    Json(signed_data): Json<SignedData>,
+) -> ApiResult<SignatureVerification> {
+    let start = Instant::now();
+    let response = data
+        .tx_sender
+        .verify_signatures(signed_data)
+        .await
+        .map_err(Error::from);
+
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "verify_signature");
+    response.into()
+}
+
 async fn get_batch(
     data: web::Data<ApiTransactionData>,
     batch_hash: web::Path<TxHash>,
@@ -246,6 +276,8 @@ pub fn api_scope(tx_sender: TxSender) -> Scope {
         .route("/batches", web::post().to(submit_batch))
         .route("/batches/{batch_hash}", web::get().to(get_batch))
         .route("/toggle2FA", web::post().to(toggle_2fa))
+        .route("/cancel", web::post().to(cancel_tx))
+        .route("/verifySignature", web::post().to(verify_signature))
 }
 
 #[cfg(test)]
@@ -258,6 +290,7 @@ mod tests {
         },
         SharedData,
     };
+    use crate::api_server::settings::{SharedTxPolicy, TxPolicy};
     use crate::fee_ticker::validator::cache::TokenInMemoryCache;
     use chrono::Utc;
     use futures::{channel::mpsc, StreamExt};
@@ -266,17 +299,19 @@ mod tests {
     use std::str::FromStr;
     use tokio::task::JoinHandle;
     use zksync_api_types::v02::{
-        transaction::{L2Receipt, TxHashSerializeWrapper},
+        transaction::{L2Receipt, SignatureCheck, TxHashSerializeWrapper},
         ApiVersion,
     };
     use zksync_mempool::MempoolTransactionRequest;
+    use zksync_test_account::ZkSyncAccount;
     use zksync_types::{
         tokens::{Token, TokenMarketVolume},
         tx::{
-            EthBatchSignData, EthBatchSignatures, PackedEthSignature, TxEthSignature,
-            TxEthSignatureVariant,
+            error::TxAddError, EthBatchSignData, EthBatchSignatures, PackedEthSignature,
+            TxEthSignature, TxEthSignatureVariant,
         },
-        Address, BlockNumber, SignedZkSyncTx, TokenId, TokenKind, TokenLike,
+        AccountId, AccountUpdate, Address, BlockNumber, Nonce, SignedZkSyncTx, TokenId, TokenKind,
+        TokenLike, ZkSyncTx,
     };
 
     fn submit_txs_loopback() -> (mpsc::Sender<MempoolTransactionRequest>, JoinHandle<()>) {
@@ -291,9 +326,15 @@ mod tests {
                     MempoolTransactionRequest::NewPriorityOps(_, _, resp) => {
                         resp.send(Ok(())).unwrap_or_default()
                     }
+                    MempoolTransactionRequest::RemovePriorityOps(_, resp) => {
+                        resp.send(Ok(())).unwrap_or_default()
+                    }
                     MempoolTransactionRequest::NewTxsBatch(_, _, resp) => {
                         resp.send(Ok(())).unwrap_or_default()
                     }
+                    MempoolTransactionRequest::CancelTx(_, _, _, resp) => {
+                        resp.send(Ok(())).unwrap_or_default()
+                    }
                 }
             }
         });
@@ -352,6 +393,7 @@ mod tests {
                     dummy_fee_ticker(&prices, Some(cache.clone())),
                     &cfg.config.api.common,
                     &cfg.config.api.token_config,
+                    SharedTxPolicy::new(TxPolicy::from_config(&cfg.config.api.common)),
                     sender.clone(),
                 ))
             },
@@ -365,6 +407,35 @@ mod tests {
         let tx_hash: TxHash = deserialize_response_result(response)?;
         assert_eq!(tx.hash(), tx_hash);
 
+        let response = client
+            .verify_signature(SignedData::Tx(TxWithSignature {
+                tx: tx.clone(),
+                signature: TxEthSignatureVariant::Single(None),
+            }))
+            .await?;
+        let verification: SignatureVerification = deserialize_response_result(response)?;
+        assert_eq!(verification.account, tx.account());
+        assert_eq!(verification.signer_pub_key_hash, tx.verify_signature());
+        assert!(verification.signer_pub_key_hash.is_some());
+        // The transaction is `ChangePubKey`, so its ECDSA authorization data is checked.
+        assert_eq!(verification.eth_signature, Some(SignatureCheck::valid()));
+
+        let mut tampered_tx = tx.clone();
+        if let ZkSyncTx::ChangePubKey(change_pk) = &mut tampered_tx {
+            *change_pk.nonce += 1;
+        }
+        let response = client
+            .verify_signature(SignedData::Tx(TxWithSignature {
+                tx: tampered_tx,
+                signature: TxEthSignatureVariant::Single(None),
+            }))
+            .await?;
+        let verification: SignatureVerification = deserialize_response_result(response)?;
+        assert_eq!(
+            verification.eth_signature.map(|check| check.valid),
+            Some(false)
+        );
+
         let TestTransactions { acc, txs } = TestServerConfig::gen_zk_txs(1_00);
         let eth = Token::new(TokenId(0), Default::default(), "ETH", 18, TokenKind::ERC20);
         let (good_batch, expected_tx_hashes): (Vec<_>, Vec<_>) = txs
@@ -493,6 +564,26 @@ mod tests {
         let tx_data: Option<TxData> = deserialize_response_result(response)?;
         assert_eq!(tx_data.unwrap().tx.tx_hash, pending_tx_hash);
 
+        // Cancellations signed too long ago are rejected, so they can't be replayed.
+        let eth_private_key = acc
+            .try_get_eth_private_key()
+            .expect("Should have ETH private key");
+        let sign = |message: &[u8]| {
+            TxEthSignature::EthereumSignature(
+                PackedEthSignature::sign(eth_private_key, message).unwrap(),
+            )
+        };
+        let mut cancel_tx = CancelTx {
+            tx_hash: pending_tx_hash,
+            account_id: AccountId(0xf00d),
+            nonce: Nonce(0),
+            timestamp: Utc::now() - chrono::Duration::hours(1),
+            signature: sign(&[]),
+        };
+        cancel_tx.signature = sign(cancel_tx.get_ethereum_sign_message().as_bytes());
+        let response = client.cancel_tx(cancel_tx).await?;
+        assert!(response.error.is_some());
+
         let tx = TestServerConfig::gen_zk_txs(1_u64).txs[0].0.clone();
         let response = client.tx_data(tx.hash()).await?;
         let tx_data: Option<TxData> = deserialize_response_result(response)?;
@@ -502,4 +593,166 @@ mod tests {
         task.abort();
         Ok(())
     }
+
+    /// Checks that the batch in which one account pays the fee for the others is accepted
+    /// only if it's signed by every author and the sponsor can pay the fee.
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn sponsored_batch() -> anyhow::Result<()> {
+        let (sender, task) = submit_txs_loopback();
+
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+        };
+        let eth = Token::new(TokenId(0), Default::default(), "ETH", 18, TokenKind::ERC20);
+        let mut tokens = HashMap::new();
+        tokens.insert(TokenLike::Id(TokenId(0)), eth.clone());
+        let mut market = HashMap::new();
+        market.insert(
+            TokenId(0),
+            TokenMarketVolume {
+                market_volume: Ratio::from_integer(BigUint::from(400u32)),
+                last_updated: Utc::now(),
+            },
+        );
+        let cache = TokenInMemoryCache::new()
+            .with_tokens(tokens)
+            .with_market(market);
+        let prices = vec![(TokenLike::Id(TokenId(0)), 10500_u64.into())];
+
+        let (client, server) = cfg.start_server(
+            move |cfg: &TestServerConfig| {
+                api_scope(TxSender::new(
+                    cfg.pool.clone(),
+                    dummy_sign_verifier(),
+                    dummy_fee_ticker(&prices, Some(cache.clone())),
+                    &cfg.config.api.common,
+                    &cfg.config.api.token_config,
+                    SharedTxPolicy::new(TxPolicy::from_config(&cfg.config.api.common)),
+                    sender.clone(),
+                ))
+            },
+            Some(shared_data),
+        );
+
+        let user = ZkSyncAccount::rand();
+        user.set_account_id(Some(AccountId(0xbeef0)));
+        let sponsor = ZkSyncAccount::rand();
+        sponsor.set_account_id(Some(AccountId(0xbeef1)));
+        let fee = BigUint::from(1000u32);
+
+        // The user transfers the tokens without paying the fee, the sponsor pays it
+        // with the zero-amount transfer to itself.
+        let (user_transfer, _) = user.sign_transfer(
+            TokenId(0),
+            "ETH",
+            10u32.into(),
+            0u32.into(),
+            &Address::random(),
+            None,
+            false,
+            Default::default(),
+        );
+        let (fee_transfer, _) = sponsor.sign_transfer(
+            TokenId(0),
+            "ETH",
+            0u32.into(),
+            fee.clone(),
+            &sponsor.address,
+            None,
+            false,
+            Default::default(),
+        );
+        let batch: Vec<_> = vec![ZkSyncTx::from(user_transfer), ZkSyncTx::from(fee_transfer)]
+            .into_iter()
+            .map(|tx| TxWithSignature {
+                tx,
+                signature: TxEthSignatureVariant::Single(None),
+            })
+            .collect();
+        let message = EthBatchSignData::get_batch_sign_message(
+            batch
+                .iter()
+                .map(|tx| (tx.tx.clone(), eth.clone(), tx.tx.account()))
+                .collect(),
+        );
+        let batch_signatures = EthBatchSignatures::Multi(
+            [&user, &sponsor]
+                .iter()
+                .map(|account| {
+                    let eth_private_key = account
+                        .try_get_eth_private_key()
+                        .expect("Should have ETH private key");
+                    TxEthSignature::EthereumSignature(
+                        PackedEthSignature::sign(eth_private_key, &message).unwrap(),
+                    )
+                })
+                .collect(),
+        );
+
+        // The user has to sign the whole batch, not only the transfer.
+        let response = client.submit_batch(batch.clone(), None).await?;
+        let error = serde_json::from_value::<Error>(response.error.unwrap()).unwrap();
+        assert_eq!(
+            error,
+            Error::from(SubmitError::TxAdd(TxAddError::MissingEthSignature))
+        );
+
+        // The sponsor account doesn't have the funds to pay the fee.
+        let response = client
+            .submit_batch(batch.clone(), Some(batch_signatures.clone()))
+            .await?;
+        let error = serde_json::from_value::<Error>(response.error.unwrap()).unwrap();
+        assert_eq!(
+            error,
+            Error::from(SubmitError::TxAdd(TxAddError::SponsorBalanceTooLow))
+        );
+
+        {
+            let mut storage = cfg.pool.access_storage().await?;
+            let updates = vec![
+                (
+                    AccountId(0xbeef1),
+                    AccountUpdate::Create {
+                        address: sponsor.address,
+                        nonce: Nonce(0),
+                    },
+                ),
+                (
+                    AccountId(0xbeef1),
+                    AccountUpdate::UpdateBalance {
+                        old_nonce: Nonce(0),
+                        new_nonce: Nonce(0),
+                        balance_update: (TokenId(0), BigUint::from(0u32), fee),
+                    },
+                ),
+            ];
+            storage
+                .chain()
+                .state_schema()
+                .commit_state_update(BlockNumber(1000), &updates, 0)
+                .await?;
+        }
+
+        let response = client
+            .submit_batch(batch.clone(), Some(batch_signatures))
+            .await?;
+        let submit_batch_response: SubmitBatchResponse = deserialize_response_result(response)?;
+        let tx_hashes: Vec<_> = batch.iter().map(|tx| tx.tx.hash()).collect();
+        assert_eq!(
+            submit_batch_response.batch_hash,
+            TxHash::batch_hash(&tx_hashes)
+        );
+
+        server.stop().await;
+        task.abort();
+        Ok(())
+    }
 }
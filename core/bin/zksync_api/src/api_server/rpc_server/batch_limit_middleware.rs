@@ -0,0 +1,116 @@
+// Built-in uses
+use std::future::Future;
+
+// External uses
+use jsonrpc_core::{
+    futures::future::{self, Either},
+    middleware::{self, Middleware},
+    Error, ErrorCode, Metadata, Request, Response, Version,
+};
+
+/// Middleware that bounds the size of JSON-RPC 2.0 batch requests.
+///
+/// Batches themselves are handled by the `jsonrpc_core` library: every call in the array is
+/// processed and the responses are returned in the same order as the calls. However, without
+/// any limit a single HTTP request or WS message could force the server to perform an arbitrary
+/// amount of work, so batches larger than `max_batch_size` are rejected as a whole.
+#[derive(Debug, Clone, Copy)]
+pub struct BatchLimitMiddleware {
+    max_batch_size: usize,
+}
+
+impl BatchLimitMiddleware {
+    pub fn new(max_batch_size: usize) -> Self {
+        Self { max_batch_size }
+    }
+
+    fn check_request(&self, request: &Request) -> Result<(), Error> {
+        if let Request::Batch(calls) = request {
+            metrics::histogram!("api.rpc.batch_request_size", calls.len() as f64);
+            if calls.len() > self.max_batch_size {
+                return Err(Error {
+                    code: ErrorCode::InvalidRequest,
+                    message: format!(
+                        "Batch request is too big: {} calls provided, at most {} allowed",
+                        calls.len(),
+                        self.max_batch_size
+                    ),
+                    data: None,
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<M: Metadata> Middleware<M> for BatchLimitMiddleware {
+    type Future = future::Ready<Option<Response>>;
+    type CallFuture = middleware::NoopCallFuture;
+
+    fn on_request<F, X>(&self, request: Request, meta: M, next: F) -> Either<Self::Future, X>
+    where
+        F: Fn(Request, M) -> X + Send + Sync,
+        X: Future<Output = Option<Response>> + Send + 'static,
+    {
+        match self.check_request(&request) {
+            Ok(()) => Either::Right(next(request, meta)),
+            Err(error) => Either::Left(future::ready(Some(Response::from(
+                error,
+                Some(Version::V2),
+            )))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonrpc_core::{MetaIoHandler, Value};
+
+    fn io_handler(max_batch_size: usize) -> MetaIoHandler<(), BatchLimitMiddleware> {
+        let mut io = MetaIoHandler::with_middleware(BatchLimitMiddleware::new(max_batch_size));
+        io.add_sync_method("echo", |params: jsonrpc_core::Params| {
+            params.parse::<(u64,)>().map(|(value,)| Value::from(value))
+        });
+        io
+    }
+
+    /// Checks that batches within the limit are processed and responses keep the order of calls.
+    #[tokio::test]
+    async fn batch_within_limit() {
+        let io = io_handler(3);
+        let request = r#"[
+            {"jsonrpc":"2.0","method":"echo","params":[3],"id":1},
+            {"jsonrpc":"2.0","method":"echo","params":[2],"id":2},
+            {"jsonrpc":"2.0","method":"echo","params":[1],"id":3}
+        ]"#;
+
+        let response = io.handle_request(request, ()).await.unwrap();
+        assert_eq!(
+            response,
+            r#"[{"jsonrpc":"2.0","result":3,"id":1},{"jsonrpc":"2.0","result":2,"id":2},{"jsonrpc":"2.0","result":1,"id":3}]"#
+        );
+    }
+
+    /// Checks that too big batches are rejected as a whole, while single calls are not affected.
+    #[tokio::test]
+    async fn batch_over_limit() {
+        let io = io_handler(1);
+        let request = r#"[
+            {"jsonrpc":"2.0","method":"echo","params":[1],"id":1},
+            {"jsonrpc":"2.0","method":"echo","params":[2],"id":2}
+        ]"#;
+
+        let response = io.handle_request(request, ()).await.unwrap();
+        let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+        assert_eq!(
+            response["error"]["code"],
+            Value::from(ErrorCode::InvalidRequest.code())
+        );
+
+        let request = r#"{"jsonrpc":"2.0","method":"echo","params":[1],"id":1}"#;
+        let response = io.handle_request(request, ()).await.unwrap();
+        assert_eq!(response, r#"{"jsonrpc":"2.0","result":1,"id":1}"#);
+    }
+}
@@ -11,6 +11,13 @@ pub enum RpcErrorCodes {
     IncorrectTx = 103,
     FeeTooLow = 104,
     InappropriateFeeToken = 105,
+    ReplacementFeeTooLow = 106,
+    ReplacementNotPossible = 107,
+    MempoolIsFull = 108,
+    TokenPaused = 109,
+    TxAcceptancePaused = 110,
+    TxNotQueued = 111,
+    CancellationNotPossible = 112,
 
     MissingEthSignature = 200,
     EIP1271SignatureVerificationFail = 201,
@@ -22,6 +29,8 @@ pub enum RpcErrorCodes {
     OperationsLimitReached = 302,
     UnsupportedFastProcessing = 303,
     Toggle2FA = 304,
+    AddressDenied = 305,
+    StaleState = 306,
 }
 
 impl From<TxAddError> for RpcErrorCodes {
@@ -31,6 +40,7 @@ impl From<TxAddError> for RpcErrorCodes {
             TxAddError::IncorrectTx(_) => Self::IncorrectTx,
             TxAddError::TxFeeTooLow => Self::FeeTooLow,
             TxAddError::TxBatchFeeTooLow => Self::FeeTooLow,
+            TxAddError::SponsorBalanceTooLow => Self::FeeTooLow,
             TxAddError::MissingEthSignature => Self::MissingEthSignature,
             TxAddError::EIP1271SignatureVerificationFail => Self::EIP1271SignatureVerificationFail,
             TxAddError::IncorrectEthSignature => Self::IncorrectEthSignature,
@@ -41,6 +51,13 @@ impl From<TxAddError> for RpcErrorCodes {
             TxAddError::BatchTooBig => Self::Other,
             TxAddError::BatchWithdrawalsOverload => Self::Other,
             TxAddError::EthSignaturesLimitExceeded => Self::Other,
+            TxAddError::ReplacementFeeTooLow => Self::ReplacementFeeTooLow,
+            TxAddError::ReplacementNotPossible => Self::ReplacementNotPossible,
+            TxAddError::MempoolIsFull => Self::MempoolIsFull,
+            TxAddError::TokenPaused(_) => Self::TokenPaused,
+            TxAddError::TxAcceptancePaused => Self::TxAcceptancePaused,
+            TxAddError::TxNotQueued => Self::TxNotQueued,
+            TxAddError::CancellationNotPossible => Self::CancellationNotPossible,
         }
     }
 }
@@ -87,6 +104,11 @@ impl From<SubmitError> for jsonrpc_core::Error {
                 message: inner.to_string(),
                 data: None,
             },
+            SubmitError::AddressDenied(_) => Self {
+                code: RpcErrorCodes::AddressDenied.into(),
+                message: inner.to_string(),
+                data: None,
+            },
             SubmitError::MempoolCommunication(reason) => Self {
                 code: RpcErrorCodes::Other.into(),
                 message: "Error communicating core server".to_string(),
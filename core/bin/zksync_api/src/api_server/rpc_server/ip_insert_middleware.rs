@@ -17,7 +17,7 @@ const METADATA_PARAM_NAME: &str = "extracted_request_metadata";
 /// header of HTTP request. This header IP inserted by Cloudflare and users can never set it by themselves.
 ///
 /// IpInsertMiddleWare is the middleware that gets the value of the `CF-Connecting-IP` header of the HTTP request and appends it as the last
-/// parameter of the JSON-RPC call.
+/// parameter of the JSON-RPC call. For batch requests, the IP is appended to every call of the batch.
 pub struct IpInsertMiddleWare;
 
 /// Structure that is used to describe the minimum and the maximum number
@@ -50,6 +50,7 @@ fn get_call_with_ip_if_needed(
         ("tx_submit", MethodWithIpDescription::new(1, 4)),
         ("submit_txs_batch", MethodWithIpDescription::new(1, 3)),
         ("get_tx_fee", MethodWithIpDescription::new(3, 4)),
+        ("get_tx_fee_batch", MethodWithIpDescription::new(3, 4)),
         (
             "get_txs_batch_fee_in_wei",
             MethodWithIpDescription::new(3, 4),
@@ -108,6 +109,26 @@ fn get_call_with_ip_if_needed(
     }
 }
 
+/// Applies `get_call_with_ip_if_needed` to every method call of the JSON-RPC request, be it a single call or a batch.
+fn get_request_with_ip_if_needed(
+    request: jsonrpc_core::Request,
+    ip: Option<String>,
+) -> jsonrpc_core::Request {
+    let insert_ip = |call: jsonrpc_core::Call| match call {
+        jsonrpc_core::Call::MethodCall(call) => {
+            jsonrpc_core::Call::MethodCall(get_call_with_ip_if_needed(call, ip.clone()))
+        }
+        call => call,
+    };
+
+    match request {
+        jsonrpc_core::Request::Single(call) => jsonrpc_core::Request::Single(insert_ip(call)),
+        jsonrpc_core::Request::Batch(calls) => {
+            jsonrpc_core::Request::Batch(calls.into_iter().map(insert_ip).collect())
+        }
+    }
+}
+
 /// Given the HTTP body of the JSON-RPC request and the IP of the user, inserts the information about it
 /// in the call (if needed) and returns the bytes of the new body.
 /// If the IP supplied is None, the method makes sure that the user could not pass the IP
@@ -119,12 +140,12 @@ async fn insert_ip_if_needed(body: hyper::Body, ip: Option<String>) -> hyper::Re
         body_bytes.extend(bytes?.into_iter());
     }
 
-    let call: std::result::Result<jsonrpc_core::MethodCall, _> =
+    let request: std::result::Result<jsonrpc_core::Request, _> =
         serde_json::from_slice(&body_bytes);
 
-    if let Ok(call) = call {
-        let new_call = get_call_with_ip_if_needed(call, ip);
-        let new_body_bytes = serde_json::to_vec(&new_call);
+    if let Ok(request) = request {
+        let new_request = get_request_with_ip_if_needed(request, ip);
+        let new_body_bytes = serde_json::to_vec(&new_request);
         if let Ok(s) = new_body_bytes {
             body_bytes = s;
         }
@@ -299,6 +320,54 @@ mod tests {
         );
     }
 
+    #[test]
+    fn insert_ip_batch_test() {
+        let calls = vec![
+            jsonrpc_core::Call::MethodCall(get_method_call(
+                "tx_submit".to_owned(),
+                Params::Array(vec![
+                    Value::String("serialized_transfer".to_owned()),
+                    Value::String("some_signature".to_owned()),
+                    Value::Bool(true),
+                    Value::String("override_ip".to_owned()),
+                ]),
+            )),
+            jsonrpc_core::Call::MethodCall(get_method_call(
+                "account_info".to_owned(),
+                Params::Array(vec![Value::String("address".to_owned())]),
+            )),
+        ];
+
+        let request = get_request_with_ip_if_needed(
+            jsonrpc_core::Request::Batch(calls),
+            Some(IP.to_owned()),
+        );
+        let calls = match request {
+            jsonrpc_core::Request::Batch(calls) => calls,
+            jsonrpc_core::Request::Single(_) => panic!("Batch request turned into a single one"),
+        };
+        let params: Vec<_> = calls
+            .into_iter()
+            .map(|call| match call {
+                jsonrpc_core::Call::MethodCall(call) => call.params,
+                _ => panic!("Unexpected call type"),
+            })
+            .collect();
+
+        assert_eq!(
+            params,
+            vec![
+                Params::Array(vec![
+                    Value::String("serialized_transfer".to_owned()),
+                    Value::String("some_signature".to_owned()),
+                    Value::Bool(true),
+                    json!({ "ip": IP }),
+                ]),
+                Params::Array(vec![Value::String("address".to_owned())]),
+            ]
+        );
+    }
+
     #[test]
     fn insert_ip_incorrect_call_test() {
         // We do not attempt to add the IP to the methods which don't need metadata
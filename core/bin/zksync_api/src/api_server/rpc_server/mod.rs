@@ -3,7 +3,7 @@ use std::time::Instant;
 
 // External uses
 use futures::channel::mpsc;
-use jsonrpc_core::{Error, IoHandler, MetaIoHandler, Metadata, Middleware, Result};
+use jsonrpc_core::{Error, MetaIoHandler, Metadata, Middleware, Result};
 use jsonrpc_http_server::ServerBuilder;
 use tokio::task::JoinHandle;
 
@@ -22,6 +22,7 @@ use zksync_utils::panic_notify::{spawn_panic_handler, ThreadPanicNotify};
 // Local uses
 use crate::{signature_checker::VerifySignatureRequest, utils::shared_lru_cache::AsyncLruCache};
 
+mod batch_limit_middleware;
 pub mod error;
 mod ip_insert_middleware;
 mod rpc_impl;
@@ -29,9 +30,13 @@ mod rpc_trait;
 pub mod types;
 
 pub use self::rpc_trait::Rpc;
-use self::types::*;
-use super::tx_sender::TxSender;
+use self::{error::RpcErrorCodes, types::*};
+use super::{
+    backpressure::BackpressureMiddleware, settings::SharedTxPolicy, shutdown::ApiShutdown,
+    state_freshness::StateFreshness, tx_sender::TxSender,
+};
 use crate::fee_ticker::FeeTicker;
+pub(crate) use batch_limit_middleware::BatchLimitMiddleware;
 use ip_insert_middleware::IpInsertMiddleWare;
 use zksync_mempool::MempoolTransactionRequest;
 
@@ -44,6 +49,7 @@ pub struct RpcApp {
     pub confirmations_for_eth_event: u64,
 
     tx_sender: TxSender,
+    state_freshness: StateFreshness,
 }
 
 impl RpcApp {
@@ -53,8 +59,10 @@ impl RpcApp {
         ticker: FeeTicker,
         config: &CommonApiConfig,
         token_config: &TokenConfig,
+        tx_policy: SharedTxPolicy,
         confirmations_for_eth_event: u64,
         mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
+        state_freshness: StateFreshness,
     ) -> Self {
         let api_requests_caches_size = config.caches_size;
 
@@ -64,6 +72,7 @@ impl RpcApp {
             ticker,
             config,
             token_config,
+            tx_policy,
             mempool_tx_sender,
         );
 
@@ -75,6 +84,7 @@ impl RpcApp {
             confirmations_for_eth_event,
 
             tx_sender,
+            state_freshness,
         }
     }
 
@@ -92,6 +102,21 @@ impl RpcApp {
             .map_err(|_| Error::internal_error())
     }
 
+    /// Fails if the account state is stale, so the outdated balances are not served.
+    fn check_state_freshness(&self) -> Result<()> {
+        match self.state_freshness.staleness() {
+            Some(staleness) => Err(Error {
+                code: RpcErrorCodes::StaleState.into(),
+                message: staleness.to_string(),
+                data: Some(serde_json::json!({
+                    "staleness": staleness,
+                    "retryAfter": self.state_freshness.retry_after().as_secs(),
+                })),
+            }),
+            None => Ok(()),
+        }
+    }
+
     // cache access functions
     async fn get_executed_priority_operation(
         &self,
@@ -264,32 +289,44 @@ pub fn start_rpc_server(
     config: &JsonRpcConfig,
     common_api_config: &CommonApiConfig,
     token_config: &TokenConfig,
+    tx_policy: SharedTxPolicy,
     mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
     confirmations_for_eth_event: u64,
+    state_freshness: StateFreshness,
+    shutdown: ApiShutdown,
 ) -> JoinHandle<()> {
     let addr = config.http_bind_addr();
+    let max_batch_request_size = config.max_batch_request_size;
+    let backpressure =
+        BackpressureMiddleware::new(connection_pool.clone(), "rpc", IpInsertMiddleWare);
     let rpc_app = RpcApp::new(
         connection_pool,
         sign_verify_request_sender,
         ticker,
         common_api_config,
         token_config,
+        tx_policy,
         confirmations_for_eth_event,
         mempool_tx_sender,
+        state_freshness,
     );
 
     let (handler, panic_sender) = spawn_panic_handler();
     std::thread::spawn(move || {
         let _panic_sentinel = ThreadPanicNotify(panic_sender);
-        let mut io = IoHandler::new();
+        let mut io: MetaIoHandler<(), _> =
+            MetaIoHandler::with_middleware(BatchLimitMiddleware::new(max_batch_request_size));
         rpc_app.extend(&mut io);
 
         let server = ServerBuilder::new(io)
             .threads(super::THREADS_PER_SERVER)
-            .request_middleware(IpInsertMiddleWare {})
+            .request_middleware(backpressure)
             .start_http(&addr)
             .unwrap();
+        let close_handle = server.close_handle();
+        let stopped = shutdown.register_jsonrpc("JSON RPC", move || close_handle.close());
         server.wait();
+        stopped.send(()).ok();
     });
     handler
 }
@@ -297,7 +334,32 @@ pub fn start_rpc_server(
 #[cfg(test)]
 mod test {
     use serde::{Deserialize, Serialize};
-    use zksync_types::TxFeeTypes;
+    use zksync_storage::test_data::dummy_ethereum_tx_hash;
+    use zksync_types::{Deposit, PriorityOp, TokenId, TxFeeTypes, ZkSyncPriorityOp, H256};
+
+    use super::*;
+    use crate::api_server::{
+        rest::v02::test_utils::{
+            dummy_fee_ticker, dummy_sign_verifier, TestServerConfig, VERIFIED_OP_SERIAL_ID,
+        },
+        settings::TxPolicy,
+    };
+
+    const CONFIRMATIONS_FOR_ETH_EVENT: u64 = 10;
+
+    fn test_rpc_app(cfg: &TestServerConfig) -> RpcApp {
+        RpcApp::new(
+            cfg.pool.clone(),
+            dummy_sign_verifier(),
+            dummy_fee_ticker(&[], None),
+            &cfg.config.api.common,
+            &cfg.config.api.token_config,
+            SharedTxPolicy::new(TxPolicy::from_config(&cfg.config.api.common)),
+            CONFIRMATIONS_FOR_ETH_EVENT,
+            mpsc::channel(10).0,
+            StateFreshness::new(&cfg.config.api.common),
+        )
+    }
 
     #[test]
     fn tx_fee_type_serialization() {
@@ -327,4 +389,78 @@ mod test {
             assert_eq!(query, de);
         }
     }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn ethop_info_by_hash() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+        let rpc_app = test_rpc_app(&cfg);
+
+        // The operation executed in the verified block.
+        let status = rpc_app
+            .clone()
+            ._impl_ethop_info_by_hash(dummy_ethereum_tx_hash(VERIFIED_OP_SERIAL_ID as i64))
+            .await?
+            .expect("Executed operation is not found");
+        assert_eq!(status.serial_id, VERIFIED_OP_SERIAL_ID);
+        assert_eq!(status.eth_block, 10);
+        assert_eq!(
+            status.expected_accept_block,
+            10 + CONFIRMATIONS_FOR_ETH_EVENT
+        );
+        assert!(status.executed);
+        let block = status.block.expect("Executed operation has no block");
+        assert_eq!(block.block_number, 2);
+        assert!(block.committed);
+        assert!(block.verified);
+
+        // The operation which is still in the mempool.
+        let pending_eth_hash = H256::repeat_byte(0x11);
+        cfg.pool
+            .access_storage()
+            .await?
+            .chain()
+            .mempool_schema()
+            .insert_priority_ops(
+                &[PriorityOp {
+                    serial_id: 1000,
+                    data: ZkSyncPriorityOp::Deposit(Deposit {
+                        from: Default::default(),
+                        token: TokenId(0),
+                        amount: 100u32.into(),
+                        to: Default::default(),
+                    }),
+                    deadline_block: 0,
+                    eth_hash: pending_eth_hash,
+                    eth_block: 25,
+                    eth_block_index: Some(1),
+                }],
+                false,
+            )
+            .await?;
+        let status = rpc_app
+            .clone()
+            ._impl_ethop_info_by_hash(pending_eth_hash)
+            .await?
+            .expect("Pending operation is not found");
+        assert_eq!(status.serial_id, 1000);
+        assert_eq!(
+            status.expected_accept_block,
+            25 + CONFIRMATIONS_FOR_ETH_EVENT
+        );
+        assert!(!status.executed);
+        assert!(status.block.is_none());
+
+        // Unknown L1 transaction.
+        assert!(rpc_app
+            ._impl_ethop_info_by_hash(H256::repeat_byte(0x22))
+            .await?
+            .is_none());
+
+        Ok(())
+    }
 }
@@ -2,6 +2,7 @@ use std::collections::HashMap;
 use std::time::Instant;
 // External uses
 use bigdecimal::BigDecimal;
+use chrono::Utc;
 use jsonrpc_core::{Error, Result};
 // Workspace uses
 use zksync_api_types::{
@@ -15,7 +16,7 @@ use zksync_api_types::{
 use zksync_crypto::params::MIN_NFT_TOKEN_ID;
 use zksync_types::{
     tx::{EthBatchSignatures, TxEthSignatureVariant, TxHash},
-    AccountId, Address, Fee, Token, TokenId, TokenLike, TotalFee, TxFeeTypes, ZkSyncTx,
+    AccountId, Address, Fee, Token, TokenId, TokenLike, TotalFee, TxFeeTypes, ZkSyncTx, H256,
 };
 // Local uses
 use crate::{
@@ -27,9 +28,13 @@ use crate::{
 
 use super::{types::*, RpcApp};
 
+/// Maximum number of tokens for which the fee can be requested in one `get_tx_fee_batch` call.
+const MAX_TOKENS_IN_FEE_REQUEST: usize = 50;
+
 impl RpcApp {
     pub async fn _impl_account_info(self, address: Address) -> Result<AccountInfoResp> {
         let start = Instant::now();
+        self.check_state_freshness()?;
 
         let account_state = self.get_account_state(address).await?;
 
@@ -107,6 +112,97 @@ impl RpcApp {
         Ok(result)
     }
 
+    pub async fn _impl_scheduled_txs(self, address: Address) -> Result<Vec<ScheduledTxResp>> {
+        let start = Instant::now();
+        let mut storage = self.access_storage().await?;
+
+        let account_id = storage
+            .chain()
+            .account_schema()
+            .account_id_by_address(address)
+            .await
+            .map_err(|err| {
+                vlog::warn!("Internal Server Error: '{}'; input: {:?}", err, address);
+                Error::internal_error()
+            })?;
+
+        let result = if let Some(account_id) = account_id {
+            storage
+                .chain()
+                .mempool_schema()
+                .get_scheduled_txs(account_id, Utc::now().timestamp() as u64)
+                .await
+                .map_err(|err| {
+                    vlog::warn!("Internal Server Error: '{}'; input: {:?}", err, address);
+                    Error::internal_error()
+                })?
+                .into_iter()
+                .map(|tx| ScheduledTxResp {
+                    tx_hash: tx.hash(),
+                    valid_from: tx.tx.valid_from(),
+                    tx: tx.tx,
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        metrics::histogram!("api", start.elapsed(), "type" => "rpc", "endpoint_name" => "scheduled_txs");
+        Ok(result)
+    }
+
+    pub async fn _impl_ethop_info_by_hash(self, eth_hash: H256) -> Result<Option<ETHOpStatusResp>> {
+        let start = Instant::now();
+        let mut storage = self.access_storage().await?;
+
+        let executed_op = storage
+            .chain()
+            .operations_schema()
+            .get_executed_priority_operation_by_eth_hash(eth_hash.as_bytes())
+            .await
+            .map_err(|err| {
+                vlog::warn!("Internal Server Error: '{}'; input: {:?}", err, eth_hash);
+                Error::internal_error()
+            })?;
+
+        let result = if let Some(executed_op) = executed_op {
+            let block = self.get_block_info(executed_op.block_number).await?;
+            let eth_block = executed_op.eth_block as u64;
+            Some(ETHOpStatusResp {
+                serial_id: executed_op.priority_op_serialid as u64,
+                eth_block,
+                expected_accept_block: eth_block + self.confirmations_for_eth_event,
+                executed: true,
+                block: Some(BlockInfo {
+                    block_number: executed_op.block_number,
+                    committed: true,
+                    verified: block.map(|b| b.verified_at.is_some()).unwrap_or_default(),
+                }),
+            })
+        } else {
+            let pending_op = storage
+                .chain()
+                .mempool_schema()
+                .get_pending_operation_by_hash(eth_hash)
+                .await
+                .map_err(|err| {
+                    vlog::warn!("Internal Server Error: '{}'; input: {:?}", err, eth_hash);
+                    Error::internal_error()
+                })?;
+
+            pending_op.map(|op| ETHOpStatusResp {
+                serial_id: op.serial_id,
+                eth_block: op.eth_block,
+                expected_accept_block: op.eth_block + self.confirmations_for_eth_event,
+                executed: false,
+                block: None,
+            })
+        };
+
+        metrics::histogram!("api", start.elapsed(), "type" => "rpc", "endpoint_name" => "ethop_info_by_hash");
+        Ok(result)
+    }
+
     pub async fn _impl_get_confirmations_for_eth_op_amount(self) -> Result<u64> {
         Ok(self.confirmations_for_eth_event)
     }
@@ -317,6 +413,67 @@ impl RpcApp {
         Ok(fee)
     }
 
+    pub async fn _impl_get_tx_fee_batch(
+        self,
+        tx_type: ApiTxFeeTypes,
+        address: Address,
+        tokens: Vec<TokenLike>,
+        extracted_request_metadata: Option<RequestMetadata>,
+    ) -> Result<Vec<Fee>> {
+        let start = Instant::now();
+        if tokens.is_empty() || tokens.len() > MAX_TOKENS_IN_FEE_REQUEST {
+            return Err(Error {
+                code: RpcErrorCodes::IncorrectTx.into(),
+                message: format!(
+                    "Number of tokens must be between 1 and {}",
+                    MAX_TOKENS_IN_FEE_REQUEST
+                ),
+                data: None,
+            });
+        }
+
+        for token in &tokens {
+            let token_allowed = self
+                .tx_sender
+                .ticker
+                .token_allowed_for_fees(token.clone())
+                .await
+                .map_err(SubmitError::Internal)?;
+            if !token_allowed {
+                return Err(SubmitError::InappropriateFeeToken.into());
+            }
+        }
+
+        let results = self
+            .tx_sender
+            .ticker
+            .get_fee_in_tokens_from_ticker_in_wei(tx_type.into(), tokens, address)
+            .await
+            .map_err(SubmitError::Internal)?;
+
+        let mut fees = Vec::with_capacity(results.len());
+        for result in results {
+            let should_subsidize_cpk = self
+                .tx_sender
+                .should_subsidize_cpk(
+                    &result.normal_fee.total_fee,
+                    &result.subsidized_fee.total_fee,
+                    &result.subsidy_size_usd,
+                    extracted_request_metadata.clone(),
+                )
+                .await?;
+
+            fees.push(if should_subsidize_cpk {
+                result.subsidized_fee
+            } else {
+                result.normal_fee
+            });
+        }
+
+        metrics::histogram!("api", start.elapsed(), "type" => "rpc", "endpoint_name" => "get_tx_fee_batch");
+        Ok(fees)
+    }
+
     pub async fn _impl_get_txs_batch_fee_in_wei(
         self,
         tx_types: Vec<ApiTxFeeTypes>,
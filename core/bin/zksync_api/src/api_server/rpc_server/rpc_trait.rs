@@ -16,7 +16,7 @@ use zksync_api_types::{
 use zksync_crypto::params::ZKSYNC_VERSION;
 use zksync_types::{
     tx::{EthBatchSignatures, TxEthSignatureVariant, TxHash},
-    AccountId, Address, Fee, Token, TokenId, TokenLike, TotalFee, ZkSyncTx,
+    AccountId, Address, Fee, Token, TokenId, TokenLike, TotalFee, ZkSyncTx, H256,
 };
 
 // Local uses
@@ -39,9 +39,16 @@ pub trait Rpc {
     #[rpc(name = "ethop_info", returns = "ETHOpInfoResp")]
     fn ethop_info(&self, serial_id: u32) -> BoxFutureResult<ETHOpInfoResp>;
 
+    #[rpc(name = "ethop_info_by_hash", returns = "Option<ETHOpStatusResp>")]
+    fn ethop_info_by_hash(&self, eth_hash: H256) -> BoxFutureResult<Option<ETHOpStatusResp>>;
+
     #[rpc(name = "tx_info", returns = "ETHOpInfoResp")]
     fn tx_info(&self, hash: TxHash) -> BoxFutureResult<TransactionInfoResp>;
 
+    /// Returns the transactions of the account that can't be executed yet because of their `valid_from` field.
+    #[rpc(name = "scheduled_txs", returns = "Vec<ScheduledTxResp>")]
+    fn scheduled_txs(&self, address: Address) -> BoxFutureResult<Vec<ScheduledTxResp>>;
+
     #[rpc(name = "tx_submit", returns = "TxHash")]
     fn tx_submit(
         &self,
@@ -76,6 +83,16 @@ pub trait Rpc {
         extracted_request_metadata: Option<RequestMetadata>,
     ) -> BoxFutureResult<Fee>;
 
+    /// Returns the fee for a single operation in each of the provided tokens.
+    #[rpc(name = "get_tx_fee_batch", returns = "Vec<Fee>")]
+    fn get_tx_fee_batch(
+        &self,
+        tx_type: ApiTxFeeTypes,
+        address: Address,
+        tokens: Vec<TokenLike>,
+        extracted_request_metadata: Option<RequestMetadata>,
+    ) -> BoxFutureResult<Vec<Fee>>;
+
     // _addresses argument is left for the backward compatibility.
     #[rpc(name = "get_txs_batch_fee_in_wei", returns = "TotalFee")]
     fn get_txs_batch_fee_in_wei(
@@ -121,10 +138,18 @@ impl Rpc for RpcApp {
         spawn!(self._impl_ethop_info(serial_id))
     }
 
+    fn ethop_info_by_hash(&self, eth_hash: H256) -> BoxFutureResult<Option<ETHOpStatusResp>> {
+        spawn!(self._impl_ethop_info_by_hash(eth_hash))
+    }
+
     fn tx_info(&self, hash: TxHash) -> BoxFutureResult<TransactionInfoResp> {
         spawn!(self._impl_tx_info(hash))
     }
 
+    fn scheduled_txs(&self, address: Address) -> BoxFutureResult<Vec<ScheduledTxResp>> {
+        spawn!(self._impl_scheduled_txs(address))
+    }
+
     // Important: the last parameter should have name `meta` and be of type `RequestMetadata`
     fn tx_submit(
         &self,
@@ -165,6 +190,17 @@ impl Rpc for RpcApp {
         spawn!(self._impl_get_tx_fee(tx_type, address, token_like, meta))
     }
 
+    // Important: the last parameter should have name `meta` and be of type `RequestMetadata`
+    fn get_tx_fee_batch(
+        &self,
+        tx_type: ApiTxFeeTypes,
+        address: Address,
+        tokens: Vec<TokenLike>,
+        meta: Option<RequestMetadata>,
+    ) -> BoxFutureResult<Vec<Fee>> {
+        spawn!(self._impl_get_tx_fee_batch(tx_type, address, tokens, meta))
+    }
+
     // Important: the last parameter should have name `meta` and be of type `RequestMetadata`
     fn get_txs_batch_fee_in_wei(
         &self,
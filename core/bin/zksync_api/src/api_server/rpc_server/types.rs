@@ -12,7 +12,7 @@ use zksync_api_types::v02::{
 use zksync_crypto::params::{MIN_NFT_TOKEN_ID, NFT_TOKEN_ID_VAL};
 use zksync_storage::StorageProcessor;
 use zksync_token_db_cache::TokenDBCache;
-use zksync_types::{Account, AccountId, Address, Nonce, PubKeyHash, TokenId};
+use zksync_types::{tx::TxHash, Account, AccountId, Address, Nonce, PubKeyHash, TokenId, ZkSyncTx};
 use zksync_utils::BigUintSerdeWrapper;
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -119,6 +119,28 @@ pub struct ETHOpInfoResp {
     pub block: Option<BlockInfo>,
 }
 
+/// Status of the priority operation found by the hash of the L1 transaction that created it.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ETHOpStatusResp {
+    pub serial_id: u64,
+    pub eth_block: u64,
+    /// Number of the Ethereum block after which the operation gets enough confirmations
+    /// to be processed by the server.
+    pub expected_accept_block: u64,
+    pub executed: bool,
+    pub block: Option<BlockInfo>,
+}
+
+/// Transaction waiting in the mempool until its `valid_from` timestamp passes.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledTxResp {
+    pub tx_hash: TxHash,
+    pub tx: ZkSyncTx,
+    pub valid_from: u64,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct ContractAddressResp {
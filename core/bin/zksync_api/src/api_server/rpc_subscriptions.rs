@@ -20,7 +20,13 @@ use zksync_utils::panic_notify::{spawn_panic_handler, ThreadPanicNotify};
 use crate::fee_ticker::FeeTicker;
 use crate::{
     api_server::event_notify::{start_sub_notifier, EventNotifierRequest, EventSubscribeRequest},
-    api_server::rpc_server::types::{ETHOpInfoResp, ResponseAccountState, TransactionInfoResp},
+    api_server::rpc_server::{
+        types::{ETHOpInfoResp, ResponseAccountState, TransactionInfoResp},
+        BatchLimitMiddleware,
+    },
+    api_server::{
+        settings::SharedTxPolicy, shutdown::ApiShutdown, state_freshness::StateFreshness,
+    },
     signature_checker::VerifySignatureRequest,
 };
 
@@ -184,12 +190,16 @@ pub fn start_ws_server(
     ticker: FeeTicker,
     common_config: &CommonApiConfig,
     token_config: &TokenConfig,
+    tx_policy: SharedTxPolicy,
     config: &JsonRpcConfig,
     miniblock_iteration_interval: Duration,
     mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
     confirmations_for_eth_event: u64,
+    state_freshness: StateFreshness,
+    shutdown: ApiShutdown,
 ) -> JoinHandle<()> {
     let addr = config.ws_bind_addr();
+    let max_batch_request_size = config.max_batch_request_size;
 
     let (event_sub_sender, event_sub_receiver) = mpsc::channel(2048);
 
@@ -207,15 +217,19 @@ pub fn start_ws_server(
         ticker,
         common_config,
         token_config,
+        tx_policy,
         confirmations_for_eth_event,
         mempool_tx_sender,
+        state_freshness,
     );
 
     let (handler, panic_sender) = spawn_panic_handler();
 
     std::thread::spawn(move || {
         let _panic_sentinel = ThreadPanicNotify(panic_sender);
-        let mut io = PubSubHandler::new(MetaIoHandler::default());
+        let mut io = PubSubHandler::new(MetaIoHandler::with_middleware(
+            BatchLimitMiddleware::new(max_batch_request_size),
+        ));
 
         req_rpc_app.extend(&mut io);
 
@@ -231,7 +245,11 @@ pub fn start_ws_server(
         .start(&addr)
         .expect("Unable to start RPC ws server");
 
+        // Closing the server sends the close frame with the `1001 Going Away` code to every client.
+        let close_handle = server.close_handle();
+        let stopped = shutdown.register_jsonrpc("JSON RPC WebSocket", move || close_handle.close());
         server.wait().expect("rpc ws server start");
+        stopped.send(()).ok();
     });
     handler
 }
@@ -0,0 +1,120 @@
+//! Settings of the API servers that can be changed while the server is running.
+//!
+//! On `SIGHUP` the server re-reads the configuration files and applies the reloadable settings:
+//! the limits, fee-free accounts and subsidies of the submitted transactions, the fee scaling
+//! and the list of the tokens that are always accepted for paying fees.
+//! The rest of the configuration is only applied on restart.
+
+// Built-in uses
+use std::{
+    collections::HashSet,
+    iter::FromIterator,
+    sync::{Arc, RwLock},
+};
+// External uses
+use num::{rational::Ratio, BigUint};
+// Workspace uses
+use zksync_config::{configs::api::CommonApiConfig, TickerConfig};
+use zksync_types::AccountId;
+// Local uses
+use crate::fee_ticker::FeeTicker;
+
+/// Limits and subsidies applied to the submitted transactions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TxPolicy {
+    /// List of account IDs that do not have to pay fees for operations.
+    pub fee_free_accounts: HashSet<AccountId>,
+    pub enforce_pubkey_change_fee: bool,
+    // Limit the number of both transactions and Ethereum signatures per batch.
+    pub max_number_of_transactions_per_batch: usize,
+    pub max_number_of_authors_per_batch: usize,
+
+    pub current_subsidy_type: String,
+    pub max_subsidy_usd: Ratio<BigUint>,
+    pub subsidized_ips: HashSet<String>,
+}
+
+impl TxPolicy {
+    pub fn from_config(config: &CommonApiConfig) -> Self {
+        Self {
+            fee_free_accounts: HashSet::from_iter(config.fee_free_accounts.clone()),
+            enforce_pubkey_change_fee: config.enforce_pubkey_change_fee,
+            max_number_of_transactions_per_batch: config.max_number_of_transactions_per_batch
+                as usize,
+            max_number_of_authors_per_batch: config.max_number_of_authors_per_batch as usize,
+            current_subsidy_type: config.subsidy_name.clone(),
+            max_subsidy_usd: config.max_subsidy_usd(),
+            subsidized_ips: config.subsidized_ips.iter().cloned().collect(),
+        }
+    }
+}
+
+/// Transactions policy shared by the API servers, the changes are visible through all the clones.
+#[derive(Debug, Clone)]
+pub struct SharedTxPolicy(Arc<RwLock<Arc<TxPolicy>>>);
+
+impl SharedTxPolicy {
+    pub fn new(policy: TxPolicy) -> Self {
+        Self(Arc::new(RwLock::new(Arc::new(policy))))
+    }
+
+    /// Returns the policy currently in use.
+    pub fn get(&self) -> Arc<TxPolicy> {
+        self.0.read().unwrap().clone()
+    }
+
+    /// Replaces the policy, it's applied to the transactions submitted afterwards.
+    pub fn set(&self, policy: TxPolicy) {
+        *self.0.write().unwrap() = Arc::new(policy);
+    }
+}
+
+/// Reloadable settings of the API servers run by the process.
+#[derive(Clone)]
+pub struct ReloadableSettings {
+    tx_policy: SharedTxPolicy,
+    fee_ticker: FeeTicker,
+}
+
+impl ReloadableSettings {
+    pub fn new(tx_policy: SharedTxPolicy, fee_ticker: FeeTicker) -> Self {
+        Self {
+            tx_policy,
+            fee_ticker,
+        }
+    }
+
+    /// Loads the settings from the environment and applies them.
+    /// Nothing is changed if any of the configs can't be loaded.
+    pub fn reload(&self) -> anyhow::Result<()> {
+        let common_config = CommonApiConfig::try_from_env()?;
+        let ticker_config = TickerConfig::try_from_env()?;
+
+        let tx_policy = TxPolicy::from_config(&common_config);
+        if *self.tx_policy.get() != tx_policy {
+            vlog::info!("Transactions policy is changed to {:?}", tx_policy);
+            self.tx_policy.set(tx_policy);
+        }
+        self.fee_ticker.reload(&ticker_config);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shared_tx_policy() {
+        let mut policy = TxPolicy::from_config(&CommonApiConfig::from_env());
+        let shared = SharedTxPolicy::new(policy.clone());
+        let cloned = shared.clone();
+
+        policy.max_number_of_transactions_per_batch += 1;
+        policy.fee_free_accounts.insert(AccountId(42));
+        shared.set(policy.clone());
+
+        // The change is visible through the clones.
+        assert_eq!(*cloned.get(), policy);
+    }
+}
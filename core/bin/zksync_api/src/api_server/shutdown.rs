@@ -0,0 +1,97 @@
+//! Graceful shutdown of the API servers.
+//!
+//! Every server registers the function stopping it once it's started. On shutdown the servers
+//! stop accepting new connections and are given some time to finish the requests in progress,
+//! the WebSocket subscriptions are closed with the `1001 Going Away` code, so the clients know
+//! that they should reconnect.
+
+// Built-in uses
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+// External uses
+use futures::{
+    channel::oneshot,
+    future::{join_all, BoxFuture},
+    FutureExt,
+};
+
+type StopServer = Box<dyn FnOnce() -> BoxFuture<'static, ()> + Send>;
+
+/// Registry of the running API servers, shared by all of them.
+#[derive(Clone, Default)]
+pub struct ApiShutdown {
+    servers: Arc<Mutex<Vec<(&'static str, StopServer)>>>,
+}
+
+impl ApiShutdown {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the running `server`. The future returned by `stop` must resolve
+    /// once the server has finished the requests in progress.
+    pub fn register<F>(&self, server: &'static str, stop: F)
+    where
+        F: FnOnce() -> BoxFuture<'static, ()> + Send + 'static,
+    {
+        self.servers.lock().unwrap().push((server, Box::new(stop)));
+    }
+
+    /// Registers the JSON-RPC server stopped by the `close` function. The returned sender must be
+    /// notified once the server is stopped, i.e. when waiting for the server returns.
+    pub(crate) fn register_jsonrpc<F>(&self, server: &'static str, close: F) -> oneshot::Sender<()>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        let (stopped_sender, stopped_receiver) = oneshot::channel();
+        self.register(server, move || {
+            close();
+            stopped_receiver.map(drop).boxed()
+        });
+        stopped_sender
+    }
+
+    /// Stops all the registered servers and waits until they're stopped, but no longer than `timeout`.
+    pub async fn shutdown(&self, timeout: Duration) {
+        let servers = std::mem::take(&mut *self.servers.lock().unwrap());
+        let stopped = servers.into_iter().map(|(server, stop)| async move {
+            match tokio::time::timeout(timeout, stop()).await {
+                Ok(()) => vlog::info!("{} API server is stopped", server),
+                Err(_) => vlog::warn!(
+                    "{} API server didn't finish the requests in progress in {:?}",
+                    server,
+                    timeout
+                ),
+            }
+        });
+        join_all(stopped).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn shutdown_waits_for_servers() {
+        let shutdown = ApiShutdown::new();
+
+        let (stopped_sender, stopped_receiver) = oneshot::channel();
+        shutdown.register("fast", move || {
+            async move {
+                stopped_sender.send(()).unwrap();
+            }
+            .boxed()
+        });
+        // The server which never stops doesn't block the shutdown of the others.
+        shutdown.register("stuck", || futures::future::pending().boxed());
+
+        shutdown.shutdown(Duration::from_millis(100)).await;
+        stopped_receiver.await.unwrap();
+
+        // Servers are stopped only once.
+        assert!(shutdown.servers.lock().unwrap().is_empty());
+    }
+}
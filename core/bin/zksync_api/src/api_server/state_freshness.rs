@@ -0,0 +1,183 @@
+//! Circuit breaker of the API endpoints serving the account state.
+//!
+//! Account balances and nonces served by the API are only as fresh as the last block. If the block
+//! production stalls while there are transactions waiting in the mempool, the API would silently
+//! serve the outdated state, so the affected endpoints are switched off until the blocks are produced
+//! again: REST responds with `503 Service Unavailable` and the `Retry-After` header, while the JSON RPC
+//! methods fail with the `StaleState` error code.
+//!
+//! Lag of the database replica doesn't make the state stale, since the read queries are routed to
+//! the primary database while the replica lags (see `ConnectionPool::with_primary_fallback`).
+
+// Built-in uses
+use std::{
+    fmt,
+    sync::{Arc, RwLock},
+    time::{Duration, UNIX_EPOCH},
+};
+
+// External uses
+use serde::Serialize;
+use tokio::{task::JoinHandle, time};
+
+// Workspace uses
+use zksync_config::configs::api::CommonApiConfig;
+use zksync_storage::ConnectionPool;
+use zksync_types::BlockNumber;
+
+/// How often the state freshness is checked.
+const CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Description of the stale state, reported to the clients.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Staleness {
+    pub last_block: BlockNumber,
+    pub last_block_age_sec: u64,
+    pub mempool_size: u32,
+}
+
+impl fmt::Display for Staleness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Account state is stale: block {} was created {} seconds ago, {} transactions are waiting to be processed",
+            self.last_block, self.last_block_age_sec, self.mempool_size
+        )
+    }
+}
+
+/// Shared result of the last state freshness check.
+#[derive(Debug, Clone)]
+pub struct StateFreshness {
+    max_block_age: Option<Duration>,
+    retry_after: Duration,
+    staleness: Arc<RwLock<Option<Staleness>>>,
+}
+
+impl StateFreshness {
+    pub fn new(config: &CommonApiConfig) -> Self {
+        Self {
+            max_block_age: config.max_block_age(),
+            retry_after: config.stale_state_retry_after(),
+            staleness: Arc::default(),
+        }
+    }
+
+    /// Returns the description of the stale state, or `None` if the state is fresh.
+    pub fn staleness(&self) -> Option<Staleness> {
+        self.staleness.read().unwrap().clone()
+    }
+
+    /// Time after which the clients should retry the rejected requests.
+    pub fn retry_after(&self) -> Duration {
+        self.retry_after
+    }
+
+    /// Spawns the task checking the state freshness. Does nothing if the check is disabled.
+    pub fn start_checker(self, pool: ConnectionPool) -> Option<JoinHandle<()>> {
+        let max_block_age = self.max_block_age?;
+        Some(tokio::spawn(async move {
+            let mut timer = time::interval(CHECK_INTERVAL);
+            loop {
+                timer.tick().await;
+                match self.check(&pool, max_block_age).await {
+                    Ok(staleness) => self.update(staleness),
+                    Err(err) => vlog::warn!("Unable to check the state freshness: {}", err),
+                }
+            }
+        }))
+    }
+
+    // TODO: don't use anyhow (ZKS-588)
+    async fn check(
+        &self,
+        pool: &ConnectionPool,
+        max_block_age: Duration,
+    ) -> anyhow::Result<Option<Staleness>> {
+        let mut storage = pool.access_storage().await?;
+        let last_block = storage
+            .chain()
+            .block_schema()
+            .get_last_saved_block()
+            .await?;
+        let timestamp = storage
+            .chain()
+            .block_schema()
+            .get_storage_block(last_block)
+            .await?
+            .and_then(|block| block.timestamp)
+            .unwrap_or_default();
+        // The transactions scheduled for the future or reverted ones are not expected to be
+        // included into the next block, so they don't make the state stale.
+        let now = UNIX_EPOCH.elapsed()?;
+        let mempool_size = storage
+            .chain()
+            .mempool_schema()
+            .get_executable_mempool_size(now.as_secs())
+            .await?;
+
+        let block_age = now.saturating_sub(Duration::from_secs(timestamp.max(0) as u64));
+        Ok(evaluate(max_block_age, last_block, block_age, mempool_size))
+    }
+
+    fn update(&self, staleness: Option<Staleness>) {
+        metrics::gauge!("api.stale_state", staleness.is_some() as u8 as f64);
+
+        let mut current = self.staleness.write().unwrap();
+        match (current.is_some(), &staleness) {
+            (false, Some(staleness)) => {
+                vlog::error!("{}, the affected API endpoints are disabled", staleness)
+            }
+            (true, None) => {
+                vlog::info!("Account state is fresh again, the API endpoints are enabled")
+            }
+            _ => {}
+        }
+        *current = staleness;
+    }
+}
+
+/// The state is stale if there are executable transactions to process, but the last block is too old.
+/// An idle network doesn't produce blocks, so the age of the last block alone means nothing.
+fn evaluate(
+    max_block_age: Duration,
+    last_block: BlockNumber,
+    block_age: Duration,
+    mempool_size: u32,
+) -> Option<Staleness> {
+    if mempool_size == 0 || block_age <= max_block_age {
+        return None;
+    }
+    Some(Staleness {
+        last_block,
+        last_block_age_sec: block_age.as_secs(),
+        mempool_size,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stale_state() {
+        let max_age = Duration::from_secs(300);
+        let block = BlockNumber(10);
+
+        // Idle network.
+        assert_eq!(evaluate(max_age, block, Duration::from_secs(3600), 0), None);
+        // Block production keeps up.
+        assert_eq!(evaluate(max_age, block, Duration::from_secs(60), 5), None);
+        assert_eq!(evaluate(max_age, block, max_age, 5), None);
+        // Block production is stalled.
+        assert_eq!(
+            evaluate(max_age, block, Duration::from_secs(301), 5),
+            Some(Staleness {
+                last_block: block,
+                last_block_age_sec: 301,
+                mempool_size: 5,
+            })
+        );
+    }
+}
@@ -1,7 +1,6 @@
 //! Helper module to submit transactions into the zkSync Network.
 
 // Built-in uses
-use std::iter::FromIterator;
 use std::{
     collections::{HashMap, HashSet},
     fmt::Display,
@@ -10,7 +9,7 @@ use std::{
 
 // External uses
 use bigdecimal::BigDecimal;
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
 use futures::{
     channel::{mpsc, oneshot},
     prelude::*,
@@ -21,8 +20,12 @@ use num::{bigint::ToBigInt, BigUint, Zero};
 use thiserror::Error;
 
 // Workspace uses
+use vlog::Instrument;
 use zksync_api_types::{
-    v02::transaction::{SubmitBatchResponse, Toggle2FA, Toggle2FAResponse, TxHashSerializeWrapper},
+    v02::transaction::{
+        CancelTx, CancelTxResponse, SignatureCheck, SignatureVerification, SignedData,
+        SignedMessage, SubmitBatchResponse, Toggle2FA, Toggle2FAResponse, TxHashSerializeWrapper,
+    },
     TxWithSignature,
 };
 use zksync_storage::misc::records::Subsidy;
@@ -33,7 +36,7 @@ use zksync_types::{
         EthBatchSignData, EthBatchSignatures, EthSignData, Order, SignedZkSyncTx, TxEthSignature,
         TxEthSignatureVariant, TxHash,
     },
-    AccountId, Address, PubKeyHash, Token, TokenId, TokenLike, TxFeeTypes, ZkSyncTx, H160,
+    Account, AccountId, Address, PubKeyHash, Token, TokenId, TokenLike, TxFeeTypes, ZkSyncTx, H160,
 };
 use zksync_utils::{
     big_decimal_to_ratio, biguint_to_big_decimal, ratio_to_scaled_u64, scaled_big_decimal_to_ratio,
@@ -41,11 +44,15 @@ use zksync_utils::{
 
 // Local uses
 use crate::{
-    api_server::forced_exit_checker::{ForcedExitAccountAgeChecker, ForcedExitChecker},
+    api_server::{
+        address_screening::AddressScreening,
+        forced_exit_checker::{ForcedExitAccountAgeChecker, ForcedExitChecker},
+        settings::SharedTxPolicy,
+    },
     fee_ticker::{ResponseBatchFee, ResponseFee, TokenPriceRequestType},
     signature_checker::{
-        BatchRequest, OrderRequest, RequestData, Toggle2FARequest, TxRequest, VerifiedTx,
-        VerifySignatureRequest,
+        BatchRequest, MessageRequest, OrderRequest, RequestData, Toggle2FARequest, TxRequest,
+        VerifiedTx, VerifySignatureRequest,
     },
     tx_error::Toggle2FAError,
     utils::block_details_cache::BlockDetailsCache,
@@ -69,17 +76,11 @@ pub struct TxSender {
     pub tokens: TokenDBCache,
 
     pub forced_exit_checker: ForcedExitChecker,
+    /// Screening of the transactions senders and recipients.
+    pub address_screening: AddressScreening,
     pub blocks: BlockDetailsCache,
-    /// List of account IDs that do not have to pay fees for operations.
-    pub fee_free_accounts: HashSet<AccountId>,
-    pub enforce_pubkey_change_fee: bool,
-    // Limit the number of both transactions and Ethereum signatures per batch.
-    pub max_number_of_transactions_per_batch: usize,
-    pub max_number_of_authors_per_batch: usize,
-
-    pub current_subsidy_type: String,
-    pub max_subsidy_usd: Ratio<BigUint>,
-    pub subsidized_ips: HashSet<String>,
+    /// Limits and subsidies of the transactions, can be reloaded while the server is running.
+    pub policy: SharedTxPolicy,
 }
 
 #[derive(Debug, Error)]
@@ -96,6 +97,8 @@ pub enum SubmitError {
     TxAdd(#[from] TxAddError),
     #[error("Chosen token is not suitable for paying fees.")]
     InappropriateFeeToken,
+    #[error("Address {0:?} is not allowed to transact.")]
+    AddressDenied(Address),
     // Not all TxAddErrors would apply to Toggle2FA, but
     // it is helpful to re-use IncorrectEthSignature and DbError
     #[error("Failed to toggle 2FA: {0}.")]
@@ -148,12 +151,9 @@ impl TxSender {
         ticker: FeeTicker,
         config: &CommonApiConfig,
         token_config: &TokenConfig,
+        policy: SharedTxPolicy,
         mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
     ) -> Self {
-        let max_number_of_transactions_per_batch =
-            config.max_number_of_transactions_per_batch as usize;
-        let max_number_of_authors_per_batch = config.max_number_of_authors_per_batch as usize;
-
         Self {
             mempool_tx_sender,
             pool: connection_pool,
@@ -163,15 +163,9 @@ impl TxSender {
             forced_exit_checker: ForcedExitChecker::new(
                 config.forced_exit_minimum_account_age_secs,
             ),
-            enforce_pubkey_change_fee: config.enforce_pubkey_change_fee,
+            address_screening: AddressScreening::from_config(config),
             blocks: BlockDetailsCache::new(config.caches_size),
-
-            fee_free_accounts: HashSet::from_iter(config.fee_free_accounts.clone()),
-            max_number_of_transactions_per_batch,
-            max_number_of_authors_per_batch,
-            current_subsidy_type: config.subsidy_name.clone(),
-            max_subsidy_usd: config.max_subsidy_usd(),
-            subsidized_ips: config.subsidized_ips.clone().into_iter().collect(),
+            policy,
         }
     }
 
@@ -213,6 +207,227 @@ impl TxSender {
             .unwrap_or(EthAccountType::Owned))
     }
 
+    async fn committed_account(&self, id: AccountId) -> Result<Option<Account>, SubmitError> {
+        let (_, committed) = self
+            .pool
+            .access_storage()
+            .await
+            .map_err(SubmitError::internal)?
+            .chain()
+            .account_schema()
+            .last_committed_state_for_account(id)
+            .await
+            .map_err(SubmitError::internal)?;
+        Ok(committed)
+    }
+
+    /// Checks the signatures of the transaction or the message for the account without submitting
+    /// anything, so the signing integrations can be debugged.
+    pub async fn verify_signatures(
+        &self,
+        data: SignedData,
+    ) -> Result<SignatureVerification, SubmitError> {
+        match data {
+            SignedData::Tx(TxWithSignature { tx, signature }) => {
+                self.verify_tx_signatures(tx, signature).await
+            }
+            SignedData::Message(message) => self.verify_message_signatures(message).await,
+        }
+    }
+
+    async fn verify_tx_signatures(
+        &self,
+        tx: ZkSyncTx,
+        signature: TxEthSignatureVariant,
+    ) -> Result<SignatureVerification, SubmitError> {
+        let account_id = tx.account_id().or(Err(SubmitError::AccountCloseDisabled))?;
+        let account = self.committed_account(account_id).await?;
+        let address = match (&tx, &account) {
+            // `ForcedExit` is signed by the initiator rather than the target account.
+            (ZkSyncTx::ForcedExit(_), Some(account)) => account.address,
+            _ => tx.account(),
+        };
+
+        let expected_signer = match (&tx, &account) {
+            (_, None) => Err(format!("Account {} doesn't exist", account_id)),
+            (_, Some(account)) if account.address != address => Err(format!(
+                "Account {} doesn't belong to {:?}",
+                account_id, address
+            )),
+            // `ChangePubKey` is signed with the new key.
+            (ZkSyncTx::ChangePubKey(change_pk), _) => Ok(change_pk.new_pk_hash),
+            (_, Some(account)) => signing_key(account),
+        };
+        let signer = tx.verify_signature();
+
+        let account_type = self
+            .get_sender_type(account_id)
+            .await
+            .map_err(|_| SubmitError::TxAdd(TxAddError::DbError))?;
+        let token = self.token_info_from_id(tx.token_id()).await?;
+        let message = tx
+            .get_ethereum_sign_message(token.clone())
+            .map(String::into_bytes);
+        let eth_signature_required = message.is_some() && eth_signature_required(account_type, &tx);
+
+        let eth_signature = match (signature.tx_signature().clone(), message) {
+            // `ChangePubKey` is authorized by its own data rather than the Eth signature of the request.
+            _ if matches!(tx, ZkSyncTx::ChangePubKey(_)) => {
+                Some(self.check_change_pubkey_auth(tx, address, token).await?)
+            }
+            (Some(_), _) if matches!(account_type, EthAccountType::CREATE2) => Some(
+                SignatureCheck::invalid("Eth signature from CREATE2 account not expected"),
+            ),
+            (Some(signature), Some(message)) => {
+                let sign_data = EthSignData { signature, message };
+                Some(
+                    self.check_tx_eth_signature(tx, address, token, Some(sign_data))
+                        .await?,
+                )
+            }
+            (Some(_), None) => Some(SignatureCheck::invalid(
+                "Transaction doesn't have a message to be signed by the Eth signature",
+            )),
+            (None, _) => None,
+        };
+
+        Ok(SignatureVerification {
+            account: address,
+            account_pub_key_hash: account
+                .as_ref()
+                .and_then(|account| signing_key(account).ok()),
+            signer_pub_key_hash: signer,
+            signature: Some(check_signer(signer, expected_signer)),
+            eth_signature_required,
+            eth_signature,
+        })
+    }
+
+    /// Checks the Ethereum authorization of the `ChangePubKey` transaction: the ECDSA signature
+    /// or the CREATE2 data it contains, or the onchain authorization via the contract.
+    async fn check_change_pubkey_auth(
+        &self,
+        tx: ZkSyncTx,
+        sender: Address,
+        token: Token,
+    ) -> Result<SignatureCheck, SubmitError> {
+        if let ZkSyncTx::ChangePubKey(change_pk) = &tx {
+            if !change_pk.is_onchain() {
+                return Ok(if change_pk.is_eth_auth_data_valid() {
+                    SignatureCheck::valid()
+                } else {
+                    SignatureCheck::invalid("ChangePubKey authorization data is invalid")
+                });
+            }
+        }
+        self.check_tx_eth_signature(tx, sender, token, None).await
+    }
+
+    async fn check_tx_eth_signature(
+        &self,
+        tx: ZkSyncTx,
+        sender: Address,
+        token: Token,
+        sign_data: Option<EthSignData>,
+    ) -> Result<SignatureCheck, SubmitError> {
+        let (response, receiver) = oneshot::channel();
+        let request = VerifySignatureRequest {
+            data: RequestData::Tx(TxRequest {
+                tx: SignedZkSyncTx {
+                    tx,
+                    eth_sign_data: sign_data,
+                    created_at: Utc::now(),
+                },
+                sender,
+                token,
+            }),
+            response,
+        };
+
+        match send_verify_request_and_recv(request, self.sign_verify_requests.clone(), receiver)
+            .await
+        {
+            Ok(_) => Ok(SignatureCheck::valid()),
+            Err(SubmitError::TxAdd(
+                err @ (TxAddError::IncorrectEthSignature | TxAddError::ChangePkNotAuthorized),
+            )) => Ok(SignatureCheck::invalid(err)),
+            // The Eth authorization is checked before the transaction correctness, so the rest of
+            // the errors mean it's correct. The zkSync signature is checked separately.
+            Err(SubmitError::TxAdd(_)) => Ok(SignatureCheck::valid()),
+            Err(err) => Err(err),
+        }
+    }
+
+    async fn verify_message_signatures(
+        &self,
+        message: SignedMessage,
+    ) -> Result<SignatureVerification, SubmitError> {
+        let account_id = self
+            .pool
+            .access_storage()
+            .await
+            .map_err(SubmitError::internal)?
+            .chain()
+            .account_schema()
+            .account_id_by_address(message.account)
+            .await
+            .map_err(SubmitError::internal)?;
+        let account = match account_id {
+            Some(account_id) => self.committed_account(account_id).await?,
+            None => None,
+        };
+        let expected_signer = match &account {
+            Some(account) => signing_key(account),
+            None => Err(format!("Account {:?} doesn't exist", message.account)),
+        };
+
+        let signer = message.signature.as_ref().map(|signature| {
+            signature
+                .verify_musig(&message.message)
+                .map(|pub_key| PubKeyHash::from_pubkey(&pub_key))
+        });
+
+        let eth_signature = match message.eth_signature {
+            Some(signature) => {
+                let (response, receiver) = oneshot::channel();
+                let request = VerifySignatureRequest {
+                    data: RequestData::Message(MessageRequest {
+                        sign_data: EthSignData {
+                            signature,
+                            message: message.message,
+                        },
+                        sender: message.account,
+                    }),
+                    response,
+                };
+                let check = match send_verify_request_and_recv(
+                    request,
+                    self.sign_verify_requests.clone(),
+                    receiver,
+                )
+                .await
+                {
+                    Ok(_) => SignatureCheck::valid(),
+                    Err(SubmitError::TxAdd(err)) => SignatureCheck::invalid(err),
+                    Err(err) => return Err(err),
+                };
+                Some(check)
+            }
+            None => None,
+        };
+
+        Ok(SignatureVerification {
+            account: message.account,
+            account_pub_key_hash: account
+                .as_ref()
+                .and_then(|account| signing_key(account).ok()),
+            signer_pub_key_hash: signer.flatten(),
+            signature: signer.map(|signer| check_signer(signer, expected_signer)),
+            eth_signature_required: false,
+            eth_signature,
+        })
+    }
+
     pub async fn toggle_2fa(
         &self,
         toggle_2fa: Toggle2FA,
@@ -259,18 +474,7 @@ impl TxSender {
         &self,
         toggle_2fa: Toggle2FA,
     ) -> Result<(), SubmitError> {
-        let current_time = Utc::now();
-        let request_time = toggle_2fa.timestamp;
-        let validness_interval = Duration::minutes(VALIDNESS_INTERVAL_MINUTES);
-
-        if current_time - validness_interval > request_time
-            || current_time + validness_interval < request_time
-        {
-            return Err(SubmitError::InvalidParams(format!(
-                "Timestamp differs by more than {} minutes",
-                VALIDNESS_INTERVAL_MINUTES
-            )));
-        }
+        check_request_timestamp(toggle_2fa.timestamp)?;
 
         let message = toggle_2fa.get_ethereum_sign_message().into_bytes();
 
@@ -295,6 +499,48 @@ impl TxSender {
         Ok(())
     }
 
+    /// Removes the queued transaction from the mempool on behalf of the account owner,
+    /// so the nonce of the transaction can be used by a new one.
+    pub async fn cancel_tx(&self, cancel_tx: CancelTx) -> Result<CancelTxResponse, SubmitError> {
+        check_request_timestamp(cancel_tx.timestamp)?;
+
+        let message = cancel_tx.get_ethereum_sign_message().into_bytes();
+        let signer = self
+            .get_address_by_id(cancel_tx.account_id)
+            .await
+            .or(Err(SubmitError::TxAdd(TxAddError::DbError)))?;
+
+        let (sender, receiver) = oneshot::channel();
+        let request = VerifySignatureRequest {
+            data: RequestData::Message(MessageRequest {
+                sign_data: EthSignData {
+                    signature: cancel_tx.signature,
+                    message,
+                },
+                sender: signer,
+            }),
+            response: sender,
+        };
+        send_verify_request_and_recv(request, self.sign_verify_requests.clone(), receiver).await?;
+
+        let (sender, receiver) = oneshot::channel();
+        let item = MempoolTransactionRequest::CancelTx(
+            cancel_tx.tx_hash,
+            cancel_tx.account_id,
+            cancel_tx.nonce,
+            sender,
+        );
+        let mut mempool_sender = self.mempool_tx_sender.clone();
+        mempool_sender
+            .send(item)
+            .await
+            .map_err(SubmitError::mempool_communication)?;
+
+        receiver.await.map_err(SubmitError::internal)??;
+
+        Ok(CancelTxResponse { success: true })
+    }
+
     async fn verify_order_eth_signature(
         &self,
         order: &Order,
@@ -368,15 +614,16 @@ impl TxSender {
             return Err(SubmitError::UnsupportedFastProcessing);
         }
 
-        if let ZkSyncTx::Withdraw(withdraw) = &mut tx {
-            if withdraw.fast {
-                // We set `fast` field ourselves, so we have to check that user did not set it themselves.
-                return Err(SubmitError::IncorrectTx(
-                    "'fast' field of Withdraw transaction must not be set manually.".to_string(),
-                ));
-            }
-
-            withdraw.fast = fast_processing;
+        // We set `fast` field ourselves, so we have to check that user did not set it themselves.
+        if tx.is_fast_processing() {
+            return Err(SubmitError::IncorrectTx(
+                "'fast' field of Withdraw transaction must not be set manually.".to_string(),
+            ));
+        }
+        match &mut tx {
+            ZkSyncTx::Withdraw(withdraw) => withdraw.fast = fast_processing,
+            ZkSyncTx::WithdrawNFT(withdraw) => withdraw.fast = fast_processing,
+            _ => {}
         }
 
         let result = self
@@ -400,17 +647,18 @@ impl TxSender {
         &self,
         new_subsidy_usd: Ratio<BigUint>,
     ) -> Result<bool, anyhow::Error> {
+        let policy = self.policy.get();
         let subsidized_already = self
             .pool
             .access_storage()
             .await?
             .misc_schema()
-            .get_total_used_subsidy_for_type(&self.current_subsidy_type)
+            .get_total_used_subsidy_for_type(&policy.current_subsidy_type)
             .await?;
         let subsidized_already_usd = scaled_big_decimal_to_ratio(subsidized_already)?;
 
-        let result = if self.max_subsidy_usd > subsidized_already_usd {
-            &self.max_subsidy_usd - &subsidized_already_usd >= new_subsidy_usd
+        let result = if policy.max_subsidy_usd > subsidized_already_usd {
+            &policy.max_subsidy_usd - &subsidized_already_usd >= new_subsidy_usd
         } else {
             false
         };
@@ -426,7 +674,7 @@ impl TxSender {
         extracted_request_metadata: Option<RequestMetadata>,
     ) -> Result<bool, SubmitError> {
         let should_subsidize_ip = if let Some(meta) = extracted_request_metadata {
-            self.subsidized_ips.contains(&meta.ip)
+            self.policy.get().subsidized_ips.contains(&meta.ip)
         } else {
             false
         };
@@ -467,7 +715,7 @@ impl TxSender {
             token_id,
             token_amount: biguint_to_big_decimal(subsidized_fee),
             full_cost_token: biguint_to_big_decimal(normal_fee),
-            subsidy_type: self.current_subsidy_type.clone(),
+            subsidy_type: self.policy.get().current_subsidy_type.clone(),
             tx_hash: hash,
         };
 
@@ -486,6 +734,18 @@ impl TxSender {
         tx: ZkSyncTx,
         signature: TxEthSignatureVariant,
         extracted_request_metadata: Option<RequestMetadata>,
+    ) -> Result<TxHash, SubmitError> {
+        let span = vlog::tx_span("tx_sender.submit_tx", tx.hash().as_ref());
+        self.submit_tx_inner(tx, signature, extracted_request_metadata)
+            .instrument(span)
+            .await
+    }
+
+    async fn submit_tx_inner(
+        &self,
+        tx: ZkSyncTx,
+        signature: TxEthSignatureVariant,
+        extracted_request_metadata: Option<RequestMetadata>,
     ) -> Result<TxHash, SubmitError> {
         let labels = vec![
             ("stage", "api".to_string()),
@@ -499,6 +759,11 @@ impl TxSender {
             return Err(SubmitError::AccountCloseDisabled);
         }
 
+        self.address_screening
+            .check_txs(std::iter::once(&tx))
+            .await?;
+        self.check_paused_tokens(std::iter::once(&tx)).await?;
+
         if let ZkSyncTx::ForcedExit(forced_exit) = &tx {
             self.check_forced_exit(forced_exit).await?;
         }
@@ -509,9 +774,10 @@ impl TxSender {
             .get_ethereum_sign_message(token.clone())
             .map(String::into_bytes);
 
+        let policy = self.policy.get();
         let is_whitelisted_initiator = tx
             .account_id()
-            .map(|account_id| self.fee_free_accounts.contains(&account_id))
+            .map(|account_id| policy.fee_free_accounts.contains(&account_id))
             .unwrap_or(false);
 
         let tx_fee_info = if !is_whitelisted_initiator {
@@ -526,7 +792,7 @@ impl TxSender {
 
         if let Some((tx_type, token, address, provided_fee)) = tx_fee_info {
             let should_enforce_fee = !matches!(tx_type, TxFeeTypes::ChangePubKey { .. })
-                || self.enforce_pubkey_change_fee;
+                || policy.enforce_pubkey_change_fee;
 
             let fee_allowed = self.ticker.token_allowed_for_fees(token.clone()).await?;
 
@@ -645,7 +911,8 @@ impl TxSender {
         // Even though this is going to be checked on the Mempool part,
         // we don't want to verify huge batches as long as this operation
         // is expensive.
-        if txs.len() > self.max_number_of_transactions_per_batch {
+        let policy = self.policy.get();
+        if txs.len() > policy.max_number_of_transactions_per_batch {
             return Err(SubmitError::TxAdd(TxAddError::BatchTooBig));
         }
 
@@ -659,7 +926,7 @@ impl TxSender {
         }
 
         // Same check but in terms of signatures.
-        if eth_signatures.len() > self.max_number_of_authors_per_batch {
+        if eth_signatures.len() > policy.max_number_of_authors_per_batch {
             return Err(SubmitError::TxAdd(TxAddError::EthSignaturesLimitExceeded));
         }
 
@@ -667,6 +934,12 @@ impl TxSender {
             return Err(SubmitError::AccountCloseDisabled);
         }
 
+        self.address_screening
+            .check_txs(txs.iter().map(|tx| &tx.tx))
+            .await?;
+        self.check_paused_tokens(txs.iter().map(|tx| &tx.tx))
+            .await?;
+
         // Checking fees data
         let mut provided_total_usd_fee = BigDecimal::from(0);
         let mut transaction_types = vec![];
@@ -837,6 +1110,17 @@ impl TxSender {
             tx_sender_types.push(self.get_tx_sender_type(tx).await?);
         }
 
+        check_create2_change_pubkey_order(&txs, &tx_senders)?;
+
+        if !sponsored_authors(&txs, &tx_senders).is_empty() {
+            // Authors of the sponsored transactions rely on the fee paid by others, so they have
+            // to agree on the whole batch by signing its message rather than only their transactions.
+            if eth_signatures.is_empty() {
+                return Err(SubmitError::TxAdd(TxAddError::MissingEthSignature));
+            }
+            self.check_sponsors_balances(&txs).await?;
+        }
+
         let batch_sign_data = if !eth_signatures.is_empty() {
             // User provided at least one signature for the whole batch.
             // In this case each sender cannot be CREATE2.
@@ -951,6 +1235,43 @@ impl TxSender {
             .await
     }
 
+    /// Rejects the transfers and the swaps of the paused tokens, the rest of the transactions
+    /// (e.g. withdrawals) are allowed.
+    async fn check_paused_tokens<'a>(
+        &self,
+        txs: impl IntoIterator<Item = &'a ZkSyncTx>,
+    ) -> Result<(), SubmitError> {
+        let tokens: Vec<_> = txs
+            .into_iter()
+            .flat_map(ZkSyncTx::pausable_tokens)
+            .collect();
+        if tokens.is_empty() {
+            return Ok(());
+        }
+
+        let paused_tokens = match self.tokens.try_get_paused_tokens_from_cache().await {
+            Some(paused_tokens) => paused_tokens,
+            None => {
+                let mut storage = self
+                    .pool
+                    .access_storage()
+                    .await
+                    .map_err(SubmitError::internal)?;
+                self.tokens
+                    .get_paused_tokens(&mut storage)
+                    .await
+                    .map_err(SubmitError::internal)?
+            }
+        };
+        match tokens
+            .into_iter()
+            .find(|token| paused_tokens.contains(token))
+        {
+            Some(token) => Err(SubmitError::TxAdd(TxAddError::TokenPaused(token))),
+            None => Ok(()),
+        }
+    }
+
     /// Returns a message that user has to sign to send the transaction.
     /// If the transaction doesn't need a message signature, returns `None`.
     /// If any error is encountered during the message generation, returns `jsonrpc_core::Error`.
@@ -986,6 +1307,46 @@ impl TxSender {
         })
     }
 
+    /// Checks that the accounts paying the fee of the sponsored batch have enough committed
+    /// balance to pay it, so the batch isn't accepted just to fail in the state keeper.
+    async fn check_sponsors_balances(&self, txs: &[TxWithSignature]) -> Result<(), SubmitError> {
+        let mut fees = HashMap::<(AccountId, TokenId), BigUint>::new();
+        for tx in txs {
+            if let Some((_, token, _, fee)) = tx.tx.get_fee_info() {
+                if fee.is_zero() {
+                    continue;
+                }
+                let account_id = tx
+                    .tx
+                    .account_id()
+                    .or(Err(SubmitError::AccountCloseDisabled))?;
+                let token = self.token_info_from_id(token).await?;
+                *fees.entry((account_id, token.id)).or_default() += fee;
+            }
+        }
+
+        let mut storage = self
+            .pool
+            .access_storage()
+            .await
+            .or(Err(SubmitError::TxAdd(TxAddError::DbError)))?;
+        for ((account_id, token_id), fee) in fees {
+            let (_, account) = storage
+                .chain()
+                .account_schema()
+                .last_committed_state_for_account(account_id)
+                .await
+                .or(Err(SubmitError::TxAdd(TxAddError::DbError)))?;
+            let balance = account
+                .map(|account| account.get_balance(token_id))
+                .unwrap_or_default();
+            if balance < fee {
+                return Err(SubmitError::TxAdd(TxAddError::SponsorBalanceTooLow));
+            }
+        }
+        Ok(())
+    }
+
     /// Resolves the token from the database.
     pub(crate) async fn token_info_from_id(
         &self,
@@ -1031,6 +1392,68 @@ async fn send_verify_request_and_recv(
         .map_err(SubmitError::TxAdd)
 }
 
+/// Rejects the signed requests made too long ago (or in the future), so they can't be replayed.
+fn check_request_timestamp(request_time: DateTime<Utc>) -> Result<(), SubmitError> {
+    let current_time = Utc::now();
+    let validness_interval = Duration::minutes(VALIDNESS_INTERVAL_MINUTES);
+
+    if current_time - validness_interval > request_time
+        || current_time + validness_interval < request_time
+    {
+        return Err(SubmitError::InvalidParams(format!(
+            "Timestamp differs by more than {} minutes",
+            VALIDNESS_INTERVAL_MINUTES
+        )));
+    }
+    Ok(())
+}
+
+/// Returns `true` if the transaction of the account with the given type must have
+/// the Ethereum signature of its message (if it has one).
+fn eth_signature_required(account_type: EthAccountType, tx: &ZkSyncTx) -> bool {
+    match (account_type, tx) {
+        (EthAccountType::CREATE2, _) => false,
+        (EthAccountType::No2FA(_), ZkSyncTx::ChangePubKey(_)) => true,
+        (EthAccountType::No2FA(hash), _) => {
+            if let Some(not_checked_hash) = hash {
+                let tx_pub_key_hash = PubKeyHash::from_pubkey(&tx.signature().pub_key.0);
+
+                tx_pub_key_hash != not_checked_hash
+            } else {
+                false
+            }
+        }
+
+        _ => true,
+    }
+}
+
+/// Returns the key the account signs the transactions with, or the reason it can't sign them.
+fn signing_key(account: &Account) -> Result<PubKeyHash, String> {
+    if account.pub_key_hash == PubKeyHash::zero() {
+        Err("Signing key of the account is not set".to_string())
+    } else {
+        Ok(account.pub_key_hash)
+    }
+}
+
+/// Checks that the zkSync signature was made with the expected key.
+fn check_signer(
+    signer: Option<PubKeyHash>,
+    expected_signer: Result<PubKeyHash, String>,
+) -> SignatureCheck {
+    match (signer, expected_signer) {
+        (None, _) => SignatureCheck::invalid("L2 signature is incorrect"),
+        (Some(_), Err(reason)) => SignatureCheck::invalid(reason),
+        (Some(signer), Ok(expected)) if signer != expected => SignatureCheck::invalid(format!(
+            "L2 signature is made with the key {} instead of {}",
+            signer.as_hex(),
+            expected.as_hex()
+        )),
+        (Some(_), Ok(_)) => SignatureCheck::valid(),
+    }
+}
+
 /// Send a request for Ethereum signature verification and wait for the response.
 /// If `msg_to_sign` is not `None`, then the signature must be present.
 async fn verify_tx_info_message_signature(
@@ -1051,23 +1474,7 @@ async fn verify_tx_info_message_signature(
         ));
     }
 
-    let should_check_eth_signature = match (account_type, tx) {
-        (EthAccountType::CREATE2, _) => false,
-        (EthAccountType::No2FA(_), ZkSyncTx::ChangePubKey(_)) => true,
-        (EthAccountType::No2FA(hash), _) => {
-            if let Some(not_checked_hash) = hash {
-                let tx_pub_key_hash = PubKeyHash::from_pubkey(&tx.signature().pub_key.0);
-
-                tx_pub_key_hash != not_checked_hash
-            } else {
-                false
-            }
-        }
-
-        _ => true,
-    };
-
-    let eth_sign_data = match (msg_to_sign, should_check_eth_signature) {
+    let eth_sign_data = match (msg_to_sign, eth_signature_required(account_type, tx)) {
         (Some(message), true) => {
             let signature = signature.ok_or(SubmitError::TxAdd(TxAddError::MissingEthSignature))?;
             Some(EthSignData { signature, message })
@@ -1188,6 +1595,62 @@ async fn verify_txs_batch_signature(
     send_verify_request_and_recv(request, req_channel, receiver).await
 }
 
+/// Checks that the CREATE2 `ChangePubKey` submitted within the batch precedes the other
+/// transactions of its account, since those can only be authorized after the CREATE2 wallet
+/// gets its signing key and don't carry the Ethereum signatures.
+fn check_create2_change_pubkey_order(
+    txs: &[TxWithSignature],
+    senders: &[Address],
+) -> Result<(), SubmitError> {
+    let is_create2_change_pubkey = |tx: &TxWithSignature| match &tx.tx {
+        ZkSyncTx::ChangePubKey(tx) => tx
+            .eth_auth_data
+            .as_ref()
+            .map_or(false, |auth_data| auth_data.is_create2()),
+        _ => false,
+    };
+    let create2_senders: HashSet<_> = txs
+        .iter()
+        .zip(senders)
+        .filter(|(tx, _)| is_create2_change_pubkey(tx))
+        .map(|(_, sender)| *sender)
+        .collect();
+
+    let mut authorized = HashSet::new();
+    for (tx, sender) in txs.iter().zip(senders) {
+        if is_create2_change_pubkey(tx) {
+            authorized.insert(*sender);
+        } else if create2_senders.contains(sender) && !authorized.contains(sender) {
+            return Err(SubmitError::IncorrectTx(format!(
+                "CREATE2 ChangePubKey of the account {:?} must precede its other transactions in the batch",
+                sender
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Returns the authors of the sponsored batch transactions, i.e. the ones that don't pay
+/// the fee for any of their transactions while the other authors of the batch do.
+fn sponsored_authors(txs: &[TxWithSignature], senders: &[Address]) -> Vec<Address> {
+    let mut authors = Vec::new();
+    let mut fee_payers = HashSet::new();
+    for (tx, &sender) in txs.iter().zip(senders) {
+        if !authors.contains(&sender) {
+            authors.push(sender);
+        }
+        if matches!(tx.tx.get_fee_info(), Some((_, _, _, fee)) if !fee.is_zero()) {
+            fee_payers.insert(sender);
+        }
+    }
+
+    if fee_payers.is_empty() {
+        return Vec::new();
+    }
+    authors.retain(|author| !fee_payers.contains(author));
+    authors
+}
+
 /// Scales the fee provided by user up to check whether the provided fee is enough to cover our expenses for
 /// maintaining the protocol.
 ///
@@ -1225,6 +1688,8 @@ fn scale_user_fee_up(provided_total_usd_fee: BigDecimal) -> BigDecimal {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use zksync_types::tx::{ChangePubKey, ChangePubKeyCREATE2Data, ChangePubKeyEthAuthData};
+    use zksync_types::{Nonce, Transfer, H256};
 
     #[test]
     fn test_scaling_user_fee_by_two() {
@@ -1255,4 +1720,117 @@ mod tests {
 
         assert_eq!(provided_fee_scaled_by_five_percent, scaled_fee);
     }
+
+    #[test]
+    fn test_sponsored_authors() {
+        let transfer = |from: Address, fee: u32| TxWithSignature {
+            tx: ZkSyncTx::from(Transfer::new(
+                AccountId(1),
+                from,
+                Address::repeat_byte(0xff),
+                TokenId(0),
+                100u32.into(),
+                fee.into(),
+                Nonce(0),
+                Default::default(),
+                None,
+            )),
+            signature: TxEthSignatureVariant::Single(None),
+        };
+        let (user, other_user, sponsor) = (
+            Address::repeat_byte(1),
+            Address::repeat_byte(2),
+            Address::repeat_byte(3),
+        );
+
+        // Nobody pays the fee, so nobody is sponsored.
+        let txs = vec![transfer(user, 0), transfer(sponsor, 0)];
+        assert!(sponsored_authors(&txs, &[user, sponsor]).is_empty());
+        // Every author pays for itself.
+        let txs = vec![transfer(user, 10), transfer(sponsor, 10)];
+        assert!(sponsored_authors(&txs, &[user, sponsor]).is_empty());
+
+        let txs = vec![
+            transfer(user, 0),
+            transfer(other_user, 0),
+            transfer(user, 0),
+            transfer(sponsor, 30),
+        ];
+        assert_eq!(
+            sponsored_authors(&txs, &[user, other_user, user, sponsor]),
+            vec![user, other_user]
+        );
+        // The author paying the fee for any of its transactions is not sponsored.
+        let txs = vec![transfer(user, 0), transfer(user, 10), transfer(sponsor, 20)];
+        assert!(sponsored_authors(&txs, &[user, user, sponsor]).is_empty());
+    }
+
+    #[test]
+    fn test_create2_change_pubkey_order() {
+        let create2_data = ChangePubKeyCREATE2Data {
+            creator_address: Address::repeat_byte(0xaa),
+            salt_arg: H256::repeat_byte(1),
+            code_hash: H256::repeat_byte(2),
+        };
+        let key = PubKeyHash { data: [1; 20] };
+        let wallet = create2_data.get_address(&key);
+        let other = Address::repeat_byte(1);
+        let mut change_pubkey = ChangePubKey::new(
+            AccountId(1),
+            wallet,
+            key,
+            TokenId(0),
+            0u32.into(),
+            Nonce(0),
+            Default::default(),
+            None,
+            None,
+        );
+        change_pubkey.eth_auth_data = Some(ChangePubKeyEthAuthData::CREATE2(create2_data));
+        let change_pubkey = TxWithSignature {
+            tx: ZkSyncTx::from(change_pubkey),
+            signature: TxEthSignatureVariant::Single(None),
+        };
+        let transfer = |from: Address| TxWithSignature {
+            tx: ZkSyncTx::from(Transfer::new(
+                AccountId(1),
+                from,
+                Address::repeat_byte(0xff),
+                TokenId(0),
+                100u32.into(),
+                0u32.into(),
+                Nonce(1),
+                Default::default(),
+                None,
+            )),
+            signature: TxEthSignatureVariant::Single(None),
+        };
+
+        // The wallet gets its key first and then uses it within the same batch.
+        let txs = vec![transfer(other), change_pubkey.clone(), transfer(wallet)];
+        assert!(check_create2_change_pubkey_order(&txs, &[other, wallet, wallet]).is_ok());
+        // Batches without the CREATE2 ChangePubKey are not affected.
+        let txs = vec![transfer(wallet), transfer(other)];
+        assert!(check_create2_change_pubkey_order(&txs, &[wallet, other]).is_ok());
+
+        let txs = vec![transfer(wallet), change_pubkey, transfer(other)];
+        assert!(matches!(
+            check_create2_change_pubkey_order(&txs, &[wallet, wallet, other]),
+            Err(SubmitError::IncorrectTx(_))
+        ));
+    }
+
+    #[test]
+    fn test_check_signer() {
+        let key = PubKeyHash { data: [1; 20] };
+        let other_key = PubKeyHash { data: [2; 20] };
+
+        assert_eq!(check_signer(Some(key), Ok(key)), SignatureCheck::valid());
+        assert!(!check_signer(None, Ok(key)).valid);
+        assert!(!check_signer(Some(other_key), Ok(key)).valid);
+        assert_eq!(
+            check_signer(Some(key), Err("Account 1 doesn't exist".to_string())),
+            SignatureCheck::invalid("Account 1 doesn't exist")
+        );
+    }
 }
@@ -2,13 +2,14 @@
 // External uses
 
 use jsonrpc_core::{Error, IoHandler, MetaIoHandler, Metadata, Middleware, Result};
-use jsonrpc_http_server::ServerBuilder;
+use jsonrpc_http_server::{RequestMiddlewareAction, ServerBuilder};
 // Workspace uses
 
 use zksync_storage::{ConnectionPool, StorageProcessor};
 use zksync_utils::panic_notify::{spawn_panic_handler, ThreadPanicNotify};
 // Local uses
 use self::{calls::CallsHelper, logs::LogsHelper, rpc_trait::Web3Rpc};
+use super::{backpressure::BackpressureMiddleware, shutdown::ApiShutdown};
 
 use tokio::task::JoinHandle;
 use zksync_config::configs::api::{TokenConfig, Web3Config};
@@ -65,9 +66,15 @@ pub fn start_rpc_server(
     connection_pool: ConnectionPool,
     web3_config: &Web3Config,
     token_config: &TokenConfig,
+    shutdown: ApiShutdown,
 ) -> JoinHandle<()> {
     let addr = web3_config.bind_addr();
 
+    let backpressure = BackpressureMiddleware::new(
+        connection_pool.clone(),
+        "web3",
+        |request: hyper::Request<hyper::Body>| RequestMiddlewareAction::from(request),
+    );
     let rpc_app = Web3RpcApp::new(connection_pool, web3_config, token_config);
     let (handler, panic_sender) = spawn_panic_handler();
 
@@ -79,9 +86,13 @@ pub fn start_rpc_server(
 
         let server = ServerBuilder::new(io)
             .threads(super::THREADS_PER_SERVER)
+            .request_middleware(backpressure)
             .start_http(&addr)
             .unwrap();
+        let close_handle = server.close_handle();
+        let stopped = shutdown.register_jsonrpc("Web3", move || close_handle.close());
         server.wait();
+        stopped.send(()).ok();
     });
     handler
 }
@@ -2,18 +2,33 @@
 //!
 //! Implements coinmarketcap API for tokens deployed using `deploy-dev-erc20`
 //! Prices are randomly distributed around base values estimated from real world prices.
+//!
+//! Tokens are loaded from `etc/tokens/*.json`. With `--discover-tokens` the tokens added to the
+//! governance contract of the local chain are discovered and kept up to date as well, so the list
+//! doesn't depend on `etc/tokens/localhost.json` staying in sync with the deployed tokens.
+//!
+//! Any cross-origin request is allowed unless restricted by the `DEV_TICKER_CORS_*` variables
+//! (e.g. `DEV_TICKER_CORS_ALLOWED_ORIGINS`), which override the fields of the permissive policy.
 
-use actix_cors::Cors;
 use actix_web::{middleware, web, App, HttpRequest, HttpResponse, HttpServer, Result};
 use bigdecimal::BigDecimal;
 use chrono::{SecondsFormat, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use std::{collections::HashMap, fs::read_to_string, path::Path};
+use std::{collections::HashMap, fs::read_to_string, path::Path, sync::RwLock};
 use std::{convert::TryFrom, time::Duration};
 use structopt::StructOpt;
+use web3::{
+    contract::{Contract, Options},
+    transports::Http,
+    types::{BlockNumber, FilterBuilder, U256},
+    Web3,
+};
+use zksync_config::configs::api::{CorsConfig, CorsOverrides};
+use zksync_contracts::{erc20_contract, governance_contract};
 use zksync_crypto::rand::{thread_rng, Rng};
-use zksync_types::Address;
+use zksync_http_utils::cors::cors;
+use zksync_types::{Address, NewTokenEvent};
 
 #[derive(Debug, Serialize, Deserialize)]
 struct CoinMarketCapTokenQuery {
@@ -52,7 +67,7 @@ macro_rules! make_sloppy {
 
 async fn handle_coinmarketcap_token_price_query(
     query: web::Query<CoinMarketCapTokenQuery>,
-    _data: web::Data<Vec<TokenData>>,
+    _data: web::Data<Tokens>,
 ) -> Result<HttpResponse> {
     let symbol = query.symbol.clone();
     let base_price = match symbol.as_str() {
@@ -88,7 +103,7 @@ async fn handle_coinmarketcap_token_price_query(
     Ok(HttpResponse::Ok().json(resp))
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Clone)]
 struct Token {
     pub address: Address,
     // While never used directly, it is better to keep this field here so that it is easy to know what fields are
@@ -106,46 +121,165 @@ struct TokenData {
     platforms: HashMap<String, Address>,
 }
 
+impl From<Token> for TokenData {
+    fn from(token: Token) -> Self {
+        let symbol = token.symbol.to_lowercase();
+        let mut platforms = HashMap::new();
+        platforms.insert(String::from("ethereum"), token.address);
+        let id = match symbol.as_str() {
+            "eth" => String::from("ethereum"),
+            "wbtc" => String::from("wrapped-bitcoin"),
+            "bat" => String::from("basic-attention-token"),
+            _ => symbol.clone(),
+        };
+
+        TokenData {
+            id,
+            symbol: symbol.clone(),
+            name: symbol,
+            platforms,
+        }
+    }
+}
+
+/// Tokens served by the ticker, updated by the tokens discovery.
+type Tokens = RwLock<Vec<TokenData>>;
+
 fn load_tokens(path: impl AsRef<Path>) -> Vec<TokenData> {
     if let Ok(text) = read_to_string(path) {
         let tokens: Vec<Token> = serde_json::from_str(&text).unwrap();
-        let tokens_data: Vec<TokenData> = tokens
-            .into_iter()
-            .map(|token| {
-                let symbol = token.symbol.to_lowercase();
-                let mut platforms = HashMap::new();
-                platforms.insert(String::from("ethereum"), token.address);
-                let id = match symbol.as_str() {
-                    "eth" => String::from("ethereum"),
-                    "wbtc" => String::from("wrapped-bitcoin"),
-                    "bat" => String::from("basic-attention-token"),
-                    _ => symbol.clone(),
-                };
-
-                TokenData {
-                    id,
-                    symbol: symbol.clone(),
-                    name: symbol,
-                    platforms,
-                }
-            })
-            .collect();
-        tokens_data
+        tokens.into_iter().map(TokenData::from).collect()
     } else {
         Vec::new()
     }
 }
 
+fn load_all_tokens() -> Vec<TokenData> {
+    let localhost_tokens = load_tokens(&"etc/tokens/localhost.json");
+    let rinkeby_tokens = load_tokens(&"etc/tokens/rinkeby.json");
+    let ropsten_tokens = load_tokens(&"etc/tokens/ropsten.json");
+    let goerli_tokens = load_tokens(&"etc/tokens/goerli.json");
+    localhost_tokens
+        .into_iter()
+        .chain(rinkeby_tokens.into_iter())
+        .chain(ropsten_tokens.into_iter())
+        .chain(goerli_tokens.into_iter())
+        .collect()
+}
+
+/// Loads the ERC-20 tokens added to the governance contract along with their symbols and decimals.
+// TODO: don't use anyhow (ZKS-588)
+async fn discover_tokens(
+    web3: &Web3<Http>,
+    governance_addr: Address,
+) -> anyhow::Result<Vec<Token>> {
+    let new_token_topic = governance_contract()
+        .event("NewToken")
+        .expect("Governance contract abi error")
+        .signature();
+    let filter = FilterBuilder::default()
+        .address(vec![governance_addr])
+        .topics(Some(vec![new_token_topic]), None, None, None)
+        .from_block(BlockNumber::Earliest)
+        .to_block(BlockNumber::Latest)
+        .build();
+
+    let mut tokens = Vec::new();
+    for log in web3.eth().logs(filter).await? {
+        let event = NewTokenEvent::try_from(log)?;
+        // Tokens without the metadata are skipped, the others are still served.
+        match load_token_metadata(web3, event.address).await {
+            Ok(token) => tokens.push(token),
+            Err(err) => vlog::warn!(
+                "Cannot load the metadata of the token {:?}: {}",
+                event.address,
+                err
+            ),
+        }
+    }
+    Ok(tokens)
+}
+
+// TODO: don't use anyhow (ZKS-588)
+async fn load_token_metadata(web3: &Web3<Http>, address: Address) -> anyhow::Result<Token> {
+    let contract = Contract::new(web3.eth(), address, erc20_contract());
+    let symbol: String = contract
+        .query("symbol", (), None, Options::default(), None)
+        .await?;
+    let decimals: U256 = contract
+        .query("decimals", (), None, Options::default(), None)
+        .await?;
+    Ok(Token {
+        address,
+        decimals: decimals.as_u32() as u8,
+        symbol,
+    })
+}
+
+/// Periodically updates the `tokens` with the discovered ones.
+async fn run_tokens_discovery(
+    web3: Web3<Http>,
+    governance_addr: Address,
+    interval: Duration,
+    tokens: web::Data<Tokens>,
+) {
+    let file_tokens = load_all_tokens();
+
+    let mut timer = tokio::time::interval(interval);
+    let mut known_tokens = 0;
+    loop {
+        timer.tick().await;
+        let discovered = match discover_tokens(&web3, governance_addr).await {
+            Ok(discovered) => discovered,
+            Err(err) => {
+                vlog::warn!("Tokens discovery failed: {}", err);
+                continue;
+            }
+        };
+        if discovered.len() != known_tokens {
+            vlog::info!(
+                "Discovered {} tokens: {}",
+                discovered.len(),
+                discovered
+                    .iter()
+                    .map(|token| token.symbol.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+            known_tokens = discovered.len();
+        }
+
+        let discovered = discovered.into_iter().map(TokenData::from).collect();
+        *tokens.write().unwrap() = merge_tokens(&file_tokens, discovered);
+    }
+}
+
+/// Returns the tokens from the files along with the discovered ones, which replace
+/// the tokens from the files with the same address.
+fn merge_tokens(file_tokens: &[TokenData], discovered: Vec<TokenData>) -> Vec<TokenData> {
+    file_tokens
+        .iter()
+        .filter(|token| {
+            !discovered
+                .iter()
+                .any(|discovered| discovered.platforms == token.platforms)
+        })
+        .cloned()
+        .chain(discovered)
+        .collect()
+}
+
 async fn handle_coingecko_token_list(
     _req: HttpRequest,
-    data: web::Data<Vec<TokenData>>,
+    data: web::Data<Tokens>,
 ) -> Result<HttpResponse> {
-    Ok(HttpResponse::Ok().json((*data.into_inner()).clone()))
+    let tokens = data.read().unwrap().clone();
+    Ok(HttpResponse::Ok().json(tokens))
 }
 
 async fn handle_coingecko_token_price_query(
     req: HttpRequest,
-    _data: web::Data<Vec<TokenData>>,
+    _data: web::Data<Tokens>,
 ) -> Result<HttpResponse> {
     let coin_id = req.match_info().get("coin_id");
     let base_price = match coin_id {
@@ -167,20 +301,10 @@ async fn handle_coingecko_token_price_query(
     Ok(HttpResponse::Ok().json(resp))
 }
 
-fn main_scope(sloppy_mode: bool) -> actix_web::Scope {
-    let localhost_tokens = load_tokens(&"etc/tokens/localhost.json");
-    let rinkeby_tokens = load_tokens(&"etc/tokens/rinkeby.json");
-    let ropsten_tokens = load_tokens(&"etc/tokens/ropsten.json");
-    let goerli_tokens = load_tokens(&"etc/tokens/goerli.json");
-    let data: Vec<TokenData> = localhost_tokens
-        .into_iter()
-        .chain(rinkeby_tokens.into_iter())
-        .chain(ropsten_tokens.into_iter())
-        .chain(goerli_tokens.into_iter())
-        .collect();
+fn main_scope(sloppy_mode: bool, data: web::Data<Tokens>) -> actix_web::Scope {
     if sloppy_mode {
         web::scope("")
-            .app_data(web::Data::new(data))
+            .app_data(data)
             .route(
                 "/cryptocurrency/quotes/latest",
                 web::get().to(make_sloppy!(handle_coinmarketcap_token_price_query)),
@@ -195,7 +319,7 @@ fn main_scope(sloppy_mode: bool) -> actix_web::Scope {
             )
     } else {
         web::scope("")
-            .app_data(web::Data::new(data))
+            .app_data(data)
             .route(
                 "/cryptocurrency/quotes/latest",
                 web::get().to(handle_coinmarketcap_token_price_query),
@@ -215,7 +339,7 @@ fn main_scope(sloppy_mode: bool) -> actix_web::Scope {
 ///
 /// Implements coinmarketcap API for tokens deployed using `deploy-dev-erc20`
 /// Prices are randomly distributed around base values estimated from real world prices.
-#[derive(Debug, StructOpt, Clone, Copy)]
+#[derive(Debug, StructOpt, Clone)]
 struct FeeTickerOpts {
     /// Activate "sloppy" mode.
     ///
@@ -224,6 +348,23 @@ struct FeeTickerOpts {
     /// and will randomly return errors for 5% of requests.
     #[structopt(long)]
     sloppy: bool,
+    /// Discover the tokens added to the governance contract of the local chain.
+    /// Requires the compiled contracts in `$ZKSYNC_HOME/contracts/artifacts`.
+    #[structopt(long)]
+    discover_tokens: bool,
+    /// Web3 URL of the local Ethereum node used for the tokens discovery.
+    #[structopt(
+        long,
+        env = "ETH_CLIENT_WEB3_URL",
+        default_value = "http://127.0.0.1:8545"
+    )]
+    web3_url: String,
+    /// Address of the governance contract, required for the tokens discovery.
+    #[structopt(long, env = "CONTRACTS_GOVERNANCE_ADDR")]
+    governance_addr: Option<Address>,
+    /// Interval (in seconds) between the tokens discovery runs.
+    #[structopt(long, default_value = "30")]
+    discovery_interval_sec: u64,
 }
 
 #[actix_web::main]
@@ -235,11 +376,32 @@ async fn main() -> std::io::Result<()> {
         vlog::info!("Fee ticker server will run in a sloppy mode.");
     }
 
+    let cors_config =
+        CorsConfig::permissive().with_overrides(CorsOverrides::from_env("DEV_TICKER_CORS_"));
+
+    let tokens = web::Data::new(Tokens::new(load_all_tokens()));
+    if opts.discover_tokens {
+        vlog::info!("Fee ticker server will discover the tokens of the local chain.");
+        let governance_addr = opts
+            .governance_addr
+            .expect("Governance contract address is required for the tokens discovery");
+        // `ETH_CLIENT_WEB3_URL` may contain several URLs, the first one is used.
+        let web3_url = opts.web3_url.split(',').next().unwrap_or_default();
+        let web3 = Web3::new(Http::new(web3_url).expect("Invalid web3 URL"));
+        tokio::spawn(run_tokens_discovery(
+            web3,
+            governance_addr,
+            Duration::from_secs(opts.discovery_interval_sec),
+            tokens.clone(),
+        ));
+    }
+
+    let sloppy = opts.sloppy;
     HttpServer::new(move || {
         App::new()
-            .wrap(Cors::default().allow_any_origin().max_age(3600))
+            .wrap(cors(&cors_config))
             .wrap(middleware::Logger::default())
-            .service(main_scope(opts.sloppy))
+            .service(main_scope(sloppy, tokens.clone()))
     })
     .bind("0.0.0.0:9876")
     .unwrap()
@@ -247,3 +409,60 @@ async fn main() -> std::io::Result<()> {
     .run()
     .await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test;
+
+    fn token(symbol: &str, address: u64) -> TokenData {
+        TokenData::from(Token {
+            address: Address::from_low_u64_be(address),
+            decimals: 18,
+            symbol: symbol.to_string(),
+        })
+    }
+
+    fn symbols(tokens: &[TokenData]) -> Vec<&str> {
+        tokens.iter().map(|token| token.symbol.as_str()).collect()
+    }
+
+    #[test]
+    fn discovered_tokens_replace_the_file_ones() {
+        let file_tokens = vec![token("ETH", 0), token("DAI", 1), token("BAT", 2)];
+
+        // Nothing is discovered yet.
+        let tokens = merge_tokens(&file_tokens, Vec::new());
+        assert_eq!(symbols(&tokens), vec!["eth", "dai", "bat"]);
+
+        // The redeployed `DAI` has the new address, `BAT` is redeployed at the same address.
+        let discovered = vec![token("DAI", 3), token("BAT2", 2)];
+        let tokens = merge_tokens(&file_tokens, discovered);
+        assert_eq!(symbols(&tokens), vec!["eth", "dai", "dai", "bat2"]);
+        assert_eq!(tokens[2].platforms["ethereum"], Address::from_low_u64_be(3));
+    }
+
+    /// Returns the ids of the tokens served by the ticker.
+    async fn served_token_ids(tokens: web::Data<Tokens>) -> Vec<String> {
+        let app = test::init_service(App::new().service(main_scope(false, tokens))).await;
+        let request = test::TestRequest::get()
+            .uri("/api/v3/coins/list")
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        let tokens: Vec<TokenData> = test::read_body_json(response).await;
+        tokens.into_iter().map(|token| token.id).collect()
+    }
+
+    #[actix_rt::test]
+    async fn token_list_serves_the_discovered_tokens() {
+        let tokens = web::Data::new(Tokens::new(vec![token("ETH", 0)]));
+        assert_eq!(served_token_ids(tokens.clone()).await, vec!["ethereum"]);
+
+        // The list is updated by the discovery.
+        *tokens.write().unwrap() = merge_tokens(&[token("ETH", 0)], vec![token("wBTC", 1)]);
+        assert_eq!(
+            served_token_ids(tokens).await,
+            vec!["ethereum", "wrapped-bitcoin"]
+        );
+    }
+}
@@ -2,6 +2,8 @@
 //! onchain `ChangePubKey` authorization or EIP1271 signature
 //! verification.
 
+use std::time::{Duration, Instant};
+
 use web3::{contract::Options, types::Address};
 use zksync_contracts::eip1271_contract;
 use zksync_eth_client::ethereum_gateway::EthereumGateway;
@@ -10,18 +12,47 @@ use zksync_types::{
     {Nonce, PubKeyHash},
 };
 
+use crate::utils::shared_lru_cache::SharedLruCache;
+
 /// isValidSignature return value according to EIP1271 standard
 /// bytes4(keccak256("isValidSignature(bytes32,bytes)")
 pub const EIP1271_SUCCESS_RETURN_VALUE: [u8; 4] = [0x16, 0x26, 0xba, 0x7e];
 
+/// Amount of the addresses which code presence is cached.
+const CONTRACT_CODE_CACHE_SIZE: usize = 100_000;
+/// The address without the code may get a contract deployed later (e.g. a counterfactual wallet),
+/// so the absence of the code is cached for a limited time only.
+const NO_CODE_CACHE_TTL: Duration = Duration::from_secs(10 * 60);
+
 #[derive(Clone)]
 pub struct EthereumChecker {
     client: EthereumGateway,
+    /// Whether the address has a contract deployed, along with the moment it was checked.
+    contract_code: SharedLruCache<Address, (bool, Instant)>,
 }
 
 impl EthereumChecker {
     pub fn new(client: EthereumGateway) -> Self {
-        Self { client }
+        Self {
+            client,
+            contract_code: SharedLruCache::new(CONTRACT_CODE_CACHE_SIZE),
+        }
+    }
+
+    /// Checks whether there is a contract deployed at the address, the result is cached.
+    async fn has_code(&self, address: Address) -> Result<bool, anyhow::Error> {
+        match self.contract_code.get(&address) {
+            Some((true, _)) => return Ok(true),
+            Some((false, checked_at)) if checked_at.elapsed() < NO_CODE_CACHE_TTL => {
+                return Ok(false)
+            }
+            _ => {}
+        }
+
+        let has_code = self.client.has_code(address).await?;
+        self.contract_code
+            .insert(address, (has_code, Instant::now()));
+        Ok(has_code)
     }
 
     /// Transforms the message into an array expected by EIP-1271 standard.
@@ -40,6 +71,11 @@ impl EthereumChecker {
         message: &[u8],
         signature: EIP1271Signature,
     ) -> Result<bool, anyhow::Error> {
+        // Only the contracts can validate the signatures, so the call is not made for
+        // the externally owned accounts.
+        if !self.has_code(address).await? {
+            return Ok(false);
+        }
         let sign_message = Self::get_sign_message(message);
 
         let call_result = self
@@ -124,7 +160,7 @@ mod tests {
             transport,
             zksync_contract(),
             Default::default(),
-            PrivateKeySigner::new(Default::default()),
+            PrivateKeySigner::new(Default::default()).into(),
             Default::default(),
             0,
             1.0,
@@ -144,6 +180,28 @@ mod tests {
         assert!(result, "Signature is incorrect");
     }
 
+    /// Checks that the signatures of the accounts without the contract code are rejected
+    /// without calling the contract, and the code presence is requested once.
+    #[tokio::test]
+    async fn eip1271_requires_contract_code() {
+        let eth_checker = EthereumChecker::new(EthereumGateway::Mock(Default::default()));
+        let address = Address::repeat_byte(1);
+        for _ in 0..2 {
+            let result = eth_checker
+                .is_eip1271_signature_correct(
+                    address,
+                    b"hello-world",
+                    EIP1271Signature(vec![0; 65]),
+                )
+                .await
+                .unwrap();
+            assert!(!result);
+        }
+
+        let code_requests = eth_checker.client.get_mock().unwrap().code_requests().await;
+        assert_eq!(code_requests, vec![address]);
+    }
+
     /// This test checks that the actual signature data taken from
     /// mainnet / Argent smart wallet is valid in our codebase.
     #[test]
@@ -8,6 +8,7 @@ use std::collections::{HashMap, HashSet};
 use std::convert::TryFrom;
 use std::fmt::Display;
 use std::iter::FromIterator;
+use std::sync::{Arc, RwLock};
 use std::time::Duration;
 
 // External deps
@@ -40,8 +41,8 @@ pub use crate::fee_ticker::ticker_info::{FeeTickerInfo, TickerInfo};
 use crate::fee_ticker::validator::FeeTokenValidator;
 use crate::fee_ticker::{
     ticker_api::{
-        coingecko::CoinGeckoAPI, coinmarkercap::CoinMarketCapAPI, FeeTickerAPI, TickerApi,
-        CONNECTION_TIMEOUT,
+        coingecko::CoinGeckoAPI, coinmarkercap::CoinMarketCapAPI, price_alerts::PriceAlerts,
+        FeeTickerAPI, TickerApi, TokenPriceAPI, CONNECTION_TIMEOUT,
     },
     validator::{watcher::UniswapTokenWatcher, MarketUpdater},
 };
@@ -192,7 +193,8 @@ impl PriceError {
 #[derive(Clone)]
 pub struct FeeTicker {
     info: Box<dyn FeeTickerInfo>,
-    config: TickerConfig,
+    // Shared by all the clones of the ticker, so the reloaded config is applied to all of them.
+    config: Arc<RwLock<Arc<TickerConfig>>>,
     validator: FeeTokenValidator,
 }
 
@@ -222,21 +224,31 @@ pub fn run_updaters(
         .build()
         .expect("Failed to build reqwest::Client");
     let (price_source, base_url) = config.price_source();
+    let reference_source = config.reference_price_source();
+    let price_alerts = PriceAlerts::new(config);
     let price_updater = match price_source {
-        TokenPriceSource::CoinMarketCap => {
-            let token_price_api =
-                CoinMarketCapAPI::new(client, base_url.parse().expect("Correct CoinMarketCap url"));
+        TokenPriceSource::CoinMarketCap => tokio::spawn(async move {
+            let token_price_api = CoinMarketCapAPI::new(
+                client.clone(),
+                base_url.parse().expect("Correct CoinMarketCap url"),
+            );
+            let reference_api = reference_price_api(client, reference_source).await;
+            let ticker_api = TickerApi::new(db_pool, token_price_api, price_source)
+                .with_price_alerts(price_alerts, reference_api);
 
-            let ticker_api = TickerApi::new(db_pool, token_price_api);
-            tokio::spawn(ticker_api.keep_price_updated())
-        }
+            ticker_api.keep_price_updated().await;
+        }),
 
         TokenPriceSource::CoinGecko => tokio::spawn(async move {
-            let token_price_api =
-                CoinGeckoAPI::new(client, base_url.parse().expect("Correct CoinGecko url"))
-                    .await
-                    .expect("failed to init CoinGecko client");
-            let ticker_api = TickerApi::new(db_pool, token_price_api);
+            let token_price_api = CoinGeckoAPI::new(
+                client.clone(),
+                base_url.parse().expect("Correct CoinGecko url"),
+            )
+            .await
+            .expect("failed to init CoinGecko client");
+            let reference_api = reference_price_api(client, reference_source).await;
+            let ticker_api = TickerApi::new(db_pool, token_price_api, price_source)
+                .with_price_alerts(price_alerts, reference_api);
 
             ticker_api.keep_price_updated().await;
         }),
@@ -245,6 +257,32 @@ pub fn run_updaters(
     tasks
 }
 
+/// Creates the API of the provider the prices are cross-checked with. The prices are not cross-checked
+/// if the provider can't be initialized, since it's not required to update them.
+async fn reference_price_api(
+    client: reqwest::Client,
+    source: Option<(TokenPriceSource, String)>,
+) -> Option<(TokenPriceSource, Box<dyn TokenPriceAPI + Send + Sync>)> {
+    let (source, base_url) = source?;
+    let base_url = base_url
+        .parse()
+        .expect("Correct reference price provider url");
+    let api: Box<dyn TokenPriceAPI + Send + Sync> = match source {
+        TokenPriceSource::CoinMarketCap => Box::new(CoinMarketCapAPI::new(client, base_url)),
+        TokenPriceSource::CoinGecko => match CoinGeckoAPI::new(client, base_url).await {
+            Ok(api) => Box::new(api),
+            Err(err) => {
+                vlog::error!(
+                    "Failed to init CoinGecko client, the prices are not cross-checked: {}",
+                    err
+                );
+                return None;
+            }
+        },
+    };
+    Some((source, api))
+}
+
 impl FeeTicker {
     pub fn new(
         info: Box<dyn FeeTickerInfo>,
@@ -253,7 +291,7 @@ impl FeeTicker {
     ) -> Self {
         Self {
             info,
-            config,
+            config: Arc::new(RwLock::new(Arc::new(config))),
             validator,
         }
     }
@@ -284,6 +322,27 @@ impl FeeTicker {
         );
         Self::new(info, ticker_config, validator)
     }
+
+    /// Applies the reloadable part of the config: the gas cost of the operations, the fee scaling,
+    /// the price of the subsidized `ChangePubKey` and the list of the tokens always accepted for fees.
+    pub fn reload(&self, config: &zksync_config::TickerConfig) {
+        let mut ticker_config = TickerConfig::clone(&self.config());
+        ticker_config.gas_cost_tx = GasOperationsCost::from_constants(config.fast_processing_coeff);
+        ticker_config.scale_fee_coefficient = Ratio::new(
+            BigUint::from(config.scale_fee_percent),
+            BigUint::from(100u32),
+        );
+        ticker_config.subsidy_cpk_price_usd = config.subsidy_cpk_price_usd();
+        *self.config.write().unwrap() = Arc::new(ticker_config);
+
+        self.validator.set_unconditionally_valid(HashSet::from_iter(
+            config.unconditionally_valid_tokens.clone(),
+        ));
+    }
+
+    fn config(&self) -> Arc<TickerConfig> {
+        self.config.read().unwrap().clone()
+    }
 }
 
 impl FeeTicker {
@@ -329,19 +388,84 @@ impl FeeTicker {
         recipient: Address,
     ) -> Result<ResponseFee, anyhow::Error> {
         let start = Instant::now();
-        let zkp_cost_chunk = self.config.zkp_cost_chunk_usd.clone();
         let token = self.info.get_token(token).await?;
 
         let gas_price_wei = self.info.get_gas_price_wei().await?;
-        let scale_gas_price = Self::risk_gas_price_estimate(gas_price_wei.clone());
         let wei_price_usd = self.wei_price_usd().await?;
-        let token_usd_risk = self.token_usd_risk(&token).await?;
+        let (fee_type, gas_tx_amount, op_chunks) = self.gas_tx_amount(tx_type, recipient).await?;
+
+        let fee = self
+            .fee_in_token(
+                &token,
+                fee_type,
+                &gas_tx_amount,
+                &op_chunks,
+                &gas_price_wei,
+                &wei_price_usd,
+            )
+            .await?;
 
+        metrics::histogram!("ticker.get_fee_from_ticker_in_wei", start.elapsed());
+        Ok(fee)
+    }
+
+    /// Calculates the fee for a single operation in each of the provided tokens.
+    ///
+    /// The gas price, the price of ETH and the operation cost are fetched only once,
+    /// so all the returned fees are based on the same market data.
+    /// Fees are returned in the same order as the tokens.
+    pub async fn get_fee_in_tokens_from_ticker_in_wei(
+        &self,
+        tx_type: TxFeeTypes,
+        tokens: Vec<TokenLike>,
+        recipient: Address,
+    ) -> Result<Vec<ResponseFee>, anyhow::Error> {
+        let start = Instant::now();
+
+        let gas_price_wei = self.info.get_gas_price_wei().await?;
+        let wei_price_usd = self.wei_price_usd().await?;
         let (fee_type, gas_tx_amount, op_chunks) = self.gas_tx_amount(tx_type, recipient).await?;
 
-        let zkp_fee = (zkp_cost_chunk * op_chunks) * &token_usd_risk;
+        let mut fees = Vec::with_capacity(tokens.len());
+        for token in tokens {
+            let token = self.info.get_token(token).await?;
+            let fee = self
+                .fee_in_token(
+                    &token,
+                    fee_type,
+                    &gas_tx_amount,
+                    &op_chunks,
+                    &gas_price_wei,
+                    &wei_price_usd,
+                )
+                .await?;
+            fees.push(fee);
+        }
+
+        metrics::histogram!(
+            "ticker.get_fee_in_tokens_from_ticker_in_wei",
+            start.elapsed()
+        );
+        Ok(fees)
+    }
+
+    /// Converts the cost of an operation into the fee denominated in the given token.
+    async fn fee_in_token(
+        &self,
+        token: &Token,
+        fee_type: OutputFeeType,
+        gas_tx_amount: &BigUint,
+        op_chunks: &BigUint,
+        gas_price_wei: &BigUint,
+        wei_price_usd: &Ratio<BigUint>,
+    ) -> Result<ResponseFee, anyhow::Error> {
+        let zkp_cost_chunk = self.config().zkp_cost_chunk_usd.clone();
+        let scale_gas_price = Self::risk_gas_price_estimate(gas_price_wei.clone());
+        let token_usd_risk = self.token_usd_risk(token).await?;
+
+        let zkp_fee = (zkp_cost_chunk * op_chunks.clone()) * &token_usd_risk;
         let mut normal_gas_fee =
-            (&wei_price_usd * gas_tx_amount.clone() * scale_gas_price.clone()) * &token_usd_risk;
+            (wei_price_usd * gas_tx_amount.clone() * scale_gas_price) * &token_usd_risk;
 
         // Increase fee only for L2 operations
         if matches!(
@@ -351,14 +475,14 @@ impl FeeTicker {
                 | OutputFeeType::MintNFT
                 | OutputFeeType::Swap
         ) {
-            normal_gas_fee *= self.config.scale_fee_coefficient.clone();
+            normal_gas_fee *= self.config().scale_fee_coefficient.clone();
         }
 
         let normal_fee = Fee::new(
             fee_type,
             zkp_fee,
             normal_gas_fee,
-            gas_tx_amount,
+            gas_tx_amount.clone(),
             gas_price_wei.clone(),
         );
 
@@ -371,7 +495,7 @@ impl FeeTicker {
             // assumes that the token's price is > 0
             let token_price = big_decimal_to_ratio(&token_price).unwrap();
             let full_amount = self
-                .config
+                .config()
                 .subsidy_cpk_price_usd
                 .checked_div(&token_price)
                 .unwrap();
@@ -397,7 +521,6 @@ impl FeeTicker {
             });
         }
 
-        metrics::histogram!("ticker.get_fee_from_ticker_in_wei", start.elapsed());
         Ok(ResponseFee {
             normal_fee: normal_fee.clone(),
             subsidized_fee: normal_fee,
@@ -411,7 +534,7 @@ impl FeeTicker {
         txs: Vec<(TxFeeTypes, Address)>,
     ) -> anyhow::Result<ResponseBatchFee> {
         let start = Instant::now();
-        let zkp_cost_chunk = self.config.zkp_cost_chunk_usd.clone();
+        let zkp_cost_chunk = self.config().zkp_cost_chunk_usd.clone();
 
         let token = self.info.get_token(token).await?;
 
@@ -457,7 +580,7 @@ impl FeeTicker {
             // This would mean that the final subsidized fee is zero. However, this is a very rare ocasion
             Ratio::from(BigUint::zero())
         } else {
-            &self.config.subsidy_cpk_price_usd / denom_part
+            &self.config().subsidy_cpk_price_usd / denom_part
         };
 
         for (tx_type, recipient) in txs {
@@ -471,7 +594,7 @@ impl FeeTicker {
                     | OutputFeeType::Swap
                     | OutputFeeType::MintNFT
             ) {
-                self.config.scale_fee_coefficient.clone() * gas_tx_amount
+                self.config().scale_fee_coefficient.clone() * gas_tx_amount
             } else {
                 gas_tx_amount.into()
             };
@@ -533,7 +656,7 @@ impl FeeTicker {
     pub async fn token_usd_risk(&self, token: &Token) -> anyhow::Result<Ratio<BigUint>> {
         let start = Instant::now();
         let token_risk_factor = self
-            .config
+            .config()
             .tokens_risk_factors
             .get(&token.id)
             .cloned()
@@ -588,7 +711,7 @@ impl FeeTicker {
         ) {
             self.calculate_fast_withdrawal_gas_cost(op_chunks).await?
         } else {
-            self.config
+            self.config()
                 .gas_cost_tx
                 .standard_cost
                 .get(&fee_type)
@@ -619,17 +742,17 @@ impl FeeTicker {
         // We have to calculate how much from base price for operations has already paid in blocks and add remain cost to fast withdrawal operation
         let commit_cost = calculate_cost(
             GasCounter::BASE_COMMIT_BLOCKS_TX_COST,
-            self.config.max_blocks_to_aggregate,
+            self.config().max_blocks_to_aggregate,
             future_blocks.blocks_to_commit,
         );
         let execute_cost = calculate_cost(
             GasCounter::BASE_EXECUTE_BLOCKS_TX_COST,
-            self.config.max_blocks_to_aggregate,
+            self.config().max_blocks_to_aggregate,
             future_blocks.blocks_to_execute,
         );
         let proof_cost = calculate_cost(
             GasCounter::BASE_PROOF_BLOCKS_TX_COST,
-            self.config.max_blocks_to_aggregate,
+            self.config().max_blocks_to_aggregate,
             future_blocks.blocks_to_prove,
         );
         metrics::histogram!("ticker.calculate_fast_withdrawal_gas_cost", start.elapsed());
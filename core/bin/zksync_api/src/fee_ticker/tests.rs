@@ -679,6 +679,64 @@ fn test_ticker_formula() {
     }
 }
 
+/// Checks that the fees returned for several tokens at once match the fees requested one by one.
+#[test]
+fn test_fee_in_tokens() {
+    let validator = FeeTokenValidator::new(
+        TokenInMemoryCache::new(),
+        chrono::Duration::seconds(100),
+        BigDecimal::from(100),
+        Default::default(),
+    );
+
+    let config = get_test_ticker_config();
+    let ticker = FeeTicker::new(Box::new(MockTickerInfo::default()), config, validator);
+
+    let tokens = vec![TestToken::eth(), TestToken::hex(), TestToken::expensive()];
+    let tx_types = vec![
+        TxFeeTypes::Transfer,
+        TxFeeTypes::Withdraw,
+        TxFeeTypes::ChangePubKey(ChangePubKeyFeeTypeArg::ContractsV4Version(
+            ChangePubKeyType::CREATE2,
+        )),
+    ];
+
+    for tx_type in tx_types {
+        let fees = block_on(ticker.get_fee_in_tokens_from_ticker_in_wei(
+            tx_type,
+            tokens.iter().map(|token| token.id.into()).collect(),
+            Address::default(),
+        ))
+        .unwrap();
+        assert_eq!(fees.len(), tokens.len());
+
+        for (token, fee) in tokens.iter().zip(fees) {
+            let expected_fee = block_on(ticker.get_fee_from_ticker_in_wei(
+                tx_type,
+                token.id.into(),
+                Address::default(),
+            ))
+            .unwrap();
+            assert_eq!(fee.normal_fee.total_fee, expected_fee.normal_fee.total_fee);
+            assert_eq!(
+                fee.subsidized_fee.total_fee,
+                expected_fee.subsidized_fee.total_fee
+            );
+        }
+    }
+
+    // A single token which is not acceptable for fees fails the whole request.
+    block_on(ticker.get_fee_in_tokens_from_ticker_in_wei(
+        TxFeeTypes::Transfer,
+        vec![
+            TestToken::eth().id.into(),
+            TestToken::zero_price().id.into(),
+        ],
+        Address::default(),
+    ))
+    .unwrap_err();
+}
+
 // It's temporary solution while zero-price tokens marked as allowed for fee
 #[test]
 fn test_zero_price_token_fee() {
@@ -3,15 +3,19 @@ use super::PriceError;
 use anyhow::format_err;
 use async_trait::async_trait;
 use chrono::Utc;
-use num::rational::Ratio;
+use num::{rational::Ratio, BigUint};
 
 use std::time::{Duration, Instant};
 
+use zksync_config::configs::ticker::TokenPriceSource;
 use zksync_storage::ConnectionPool;
 use zksync_types::{Token, TokenId, TokenPrice};
 
+use self::price_alerts::PriceAlerts;
+
 pub mod coingecko;
 pub mod coinmarkercap;
+pub mod price_alerts;
 
 const UPDATE_PRICE_INTERVAL_SECS: u64 = 10 * 60;
 /// The limit of time we are willing to wait for response.
@@ -30,21 +34,44 @@ pub trait FeeTickerAPI {
     async fn keep_price_updated(self);
 }
 
-#[derive(Debug, Clone)]
 pub(super) struct TickerApi<T: TokenPriceAPI> {
     db_pool: ConnectionPool,
 
     token_price_api: T,
+    /// Source of the `token_price_api` prices.
+    price_source: TokenPriceSource,
+    /// Provider the prices are cross-checked with.
+    reference_api: Option<(TokenPriceSource, Box<dyn TokenPriceAPI + Send + Sync>)>,
+    price_alerts: PriceAlerts,
 }
 
 impl<T: TokenPriceAPI> TickerApi<T> {
-    pub fn new(db_pool: ConnectionPool, token_price_api: T) -> Self {
+    pub fn new(
+        db_pool: ConnectionPool,
+        token_price_api: T,
+        price_source: TokenPriceSource,
+    ) -> Self {
         Self {
             db_pool,
             token_price_api,
+            price_source,
+            reference_api: None,
+            price_alerts: PriceAlerts::default(),
         }
     }
 
+    /// Enables the alerts on the suspicious prices. The prices are cross-checked with `reference_api`
+    /// if it's set.
+    pub fn with_price_alerts(
+        mut self,
+        price_alerts: PriceAlerts,
+        reference_api: Option<(TokenPriceSource, Box<dyn TokenPriceAPI + Send + Sync>)>,
+    ) -> Self {
+        self.price_alerts = price_alerts;
+        self.reference_api = reference_api;
+        self
+    }
+
     async fn get_all_tokens(&self) -> Result<Vec<Token>, PriceError> {
         let mut storage = self
             .db_pool
@@ -77,6 +104,56 @@ impl<T: TokenPriceAPI> TickerApi<T> {
 
         Ok(())
     }
+    async fn load_stored_price(&self, token_id: TokenId) -> Result<Option<TokenPrice>, PriceError> {
+        let mut storage = self
+            .db_pool
+            .access_storage()
+            .await
+            .map_err(PriceError::db_error)?;
+        storage
+            .tokens_schema()
+            .get_historical_ticker_price(token_id)
+            .await
+            .map_err(PriceError::db_error)
+    }
+    /// Alerts if the new price of the token is suspicious. Never fails, the price is stored anyway.
+    async fn check_price(&self, token: &Token, price: &Ratio<BigUint>) {
+        if self.price_alerts.checks_price_change() {
+            match self.load_stored_price(token.id).await {
+                Ok(Some(previous)) => {
+                    self.price_alerts
+                        .check_price_change(token, &previous.usd_price, price)
+                        .await
+                }
+                Ok(None) => {}
+                Err(err) => vlog::warn!(
+                    "Can't load the previous price of token {}: {}",
+                    token.symbol,
+                    err
+                ),
+            }
+        }
+
+        if let Some((reference_source, reference_api)) = &self.reference_api {
+            match reference_api.get_price(token).await {
+                Ok(reference_price) => {
+                    let prices = [
+                        (self.price_source, price.clone()),
+                        (*reference_source, reference_price.usd_price),
+                    ];
+                    self.price_alerts.check_providers(token, &prices).await;
+                }
+                // The token isn't listed by the reference provider, nothing to compare with.
+                Err(PriceError::TokenNotFound(_)) => {}
+                Err(err) => vlog::warn!(
+                    "Can't get the reference price of token {} from {:?}: {}",
+                    token.symbol,
+                    reference_source,
+                    err
+                ),
+            }
+        }
+    }
     async fn update_price(&self, token: &Token) -> Result<(), PriceError> {
         let start = Instant::now();
         let api_price = match self.token_price_api.get_price(token).await {
@@ -89,6 +166,7 @@ impl<T: TokenPriceAPI> TickerApi<T> {
             },
             Err(e) => return Err(e),
         };
+        self.check_price(token, &api_price.usd_price).await;
 
         self.update_stored_value(token.id, api_price.clone())
             .await
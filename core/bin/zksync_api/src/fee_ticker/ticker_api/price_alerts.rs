@@ -0,0 +1,227 @@
+//! Alerts on the suspicious token prices.
+//!
+//! The prices reported by the provider are checked before being stored: the price is suspicious
+//! if it changed too much since the previous update, or if the providers disagree about it.
+//! Such prices are still stored, since the fees would be calculated using the outdated prices
+//! otherwise, but the alert is logged, counted in the `ticker.price_alert` metric and sent
+//! to the webhook if it's configured.
+
+// Built-in uses
+use std::{collections::HashSet, sync::Mutex};
+// External uses
+use num::{rational::Ratio, BigUint, ToPrimitive};
+// Workspace uses
+use zksync_config::{configs::ticker::TokenPriceSource, TickerConfig};
+use zksync_notifier::AlertNotifier;
+use zksync_types::{Token, TokenId};
+use zksync_utils::ratio_to_big_decimal;
+
+/// Precision of the prices converted for the comparison.
+const PRICE_PRECISION: usize = 18;
+
+/// Checks of the token prices, all of them are disabled by default.
+#[derive(Default)]
+pub(crate) struct PriceAlerts {
+    /// Max change of the price since the previous update, in percent.
+    max_price_change: Option<f64>,
+    /// Max deviation of the provider price from the median price, in percent.
+    max_provider_deviation: Option<f64>,
+    notifier: AlertNotifier,
+    /// Tokens the providers currently disagree about, so the alert is sent once until they agree again.
+    diverged_tokens: Mutex<HashSet<TokenId>>,
+}
+
+impl PriceAlerts {
+    pub fn new(config: &TickerConfig) -> Self {
+        let mut notifier = AlertNotifier::default();
+        if let Some(url) = &config.price_alert_webhook_url {
+            let url = url.parse().expect("invalid price alert webhook URL");
+            notifier = notifier.with_slack(url);
+        }
+        Self {
+            max_price_change: enabled(config.max_price_change_percent),
+            max_provider_deviation: enabled(config.max_provider_deviation_percent),
+            notifier,
+            diverged_tokens: Mutex::default(),
+        }
+    }
+
+    /// Returns `true` if the price change since the previous update is checked.
+    pub fn checks_price_change(&self) -> bool {
+        self.max_price_change.is_some()
+    }
+
+    /// Alerts if the token price changed too much since the previous update.
+    pub async fn check_price_change(
+        &self,
+        token: &Token,
+        previous: &Ratio<BigUint>,
+        new: &Ratio<BigUint>,
+    ) {
+        let max_change = match self.max_price_change {
+            Some(max_change) => max_change,
+            None => return,
+        };
+        let (previous, new) = (to_f64(previous), to_f64(new));
+        match price_change_percent(previous, new) {
+            Some(change) if change > max_change => {
+                metrics::increment_counter!(
+                    "ticker.price_alert",
+                    "kind" => "price_change",
+                    "token" => token.symbol.clone()
+                );
+                self.alert(format!(
+                    "Price of {} changed by {:.2}% since the previous update: {} USD -> {} USD",
+                    token.symbol, change, previous, new
+                ))
+                .await;
+            }
+            _ => {}
+        }
+    }
+
+    /// Alerts if any of the providers deviates too much from the median price reported by all of them.
+    pub async fn check_providers(
+        &self,
+        token: &Token,
+        prices: &[(TokenPriceSource, Ratio<BigUint>)],
+    ) {
+        let max_deviation = match self.max_provider_deviation {
+            Some(max_deviation) => max_deviation,
+            None => return,
+        };
+        let prices: Vec<_> = prices
+            .iter()
+            .map(|(source, price)| (*source, to_f64(price)))
+            .collect();
+        let outliers = provider_outliers(&prices, max_deviation);
+
+        let newly_diverged = {
+            let mut diverged_tokens = self.diverged_tokens.lock().unwrap();
+            if outliers.is_empty() {
+                if diverged_tokens.remove(&token.id) {
+                    vlog::info!("Price providers agree on {} again", token.symbol);
+                }
+                return;
+            }
+            diverged_tokens.insert(token.id)
+        };
+
+        metrics::increment_counter!(
+            "ticker.price_alert",
+            "kind" => "provider_deviation",
+            "token" => token.symbol.clone()
+        );
+        let deviations = outliers
+            .iter()
+            .map(|(source, deviation)| format!("{:?} by {:.2}%", source, deviation))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let prices = prices
+            .iter()
+            .map(|(source, price)| format!("{:?} = {} USD", source, price))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let text = format!(
+            "Price providers disagree on {}: {}, deviation from the median price: {}",
+            token.symbol, prices, deviations
+        );
+        // The disagreement usually persists for a while, so it's only sent once.
+        if newly_diverged {
+            self.alert(text).await;
+        } else {
+            vlog::warn!("{}", text);
+        }
+    }
+
+    async fn alert(&self, text: String) {
+        vlog::warn!("{}", text);
+        if let Err(err) = self.notifier.send_alert(&text).await {
+            vlog::error!("Unable to send the price alert: {}", err);
+        }
+    }
+}
+
+fn enabled(percent: f64) -> Option<f64> {
+    if percent > 0.0 {
+        Some(percent)
+    } else {
+        None
+    }
+}
+
+fn to_f64(price: &Ratio<BigUint>) -> f64 {
+    ratio_to_big_decimal(price, PRICE_PRECISION)
+        .to_f64()
+        .unwrap_or_default()
+}
+
+/// Change of the price in percent, `None` if there is no previous price to compare with.
+fn price_change_percent(previous: f64, new: f64) -> Option<f64> {
+    if previous <= 0.0 {
+        return None;
+    }
+    Some((new - previous).abs() / previous * 100.0)
+}
+
+/// Returns the providers deviating from the median price by more than `max_deviation` percent
+/// along with their deviation. Zero prices are reported for the tokens the provider doesn't list,
+/// so they're ignored.
+fn provider_outliers(
+    prices: &[(TokenPriceSource, f64)],
+    max_deviation: f64,
+) -> Vec<(TokenPriceSource, f64)> {
+    let mut listed: Vec<_> = prices.iter().filter(|(_, price)| *price > 0.0).collect();
+    if listed.len() < 2 {
+        return Vec::new();
+    }
+    listed.sort_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap());
+    let middle = listed.len() / 2;
+    let median = if listed.len() % 2 == 0 {
+        (listed[middle - 1].1 + listed[middle].1) / 2.0
+    } else {
+        listed[middle].1
+    };
+
+    listed
+        .into_iter()
+        .map(|(source, price)| (*source, (price - median).abs() / median * 100.0))
+        .filter(|(_, deviation)| *deviation > max_deviation)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn price_change() {
+        assert_eq!(price_change_percent(0.0, 10.0), None);
+        assert_eq!(price_change_percent(10.0, 10.0), Some(0.0));
+        assert_eq!(price_change_percent(10.0, 12.5), Some(25.0));
+        assert_eq!(price_change_percent(10.0, 5.0), Some(50.0));
+        assert_eq!(price_change_percent(10.0, 0.0), Some(100.0));
+    }
+
+    #[test]
+    fn providers_deviation() {
+        let gecko = TokenPriceSource::CoinGecko;
+        let cmc = TokenPriceSource::CoinMarketCap;
+
+        // Providers agree.
+        assert!(provider_outliers(&[(gecko, 100.0), (cmc, 104.0)], 5.0).is_empty());
+        // Token isn't listed by one of the providers.
+        assert!(provider_outliers(&[(gecko, 100.0), (cmc, 0.0)], 5.0).is_empty());
+        // Providers disagree, both of them deviate from the median price equally.
+        assert_eq!(
+            provider_outliers(&[(gecko, 90.0), (cmc, 110.0)], 5.0),
+            vec![(gecko, 10.0), (cmc, 10.0)]
+        );
+    }
+
+    #[test]
+    fn conversion() {
+        let price = Ratio::new(BigUint::from(3u32), BigUint::from(2u32));
+        assert_eq!(to_f64(&price), 1.5);
+    }
+}
@@ -7,6 +7,7 @@ pub mod watcher;
 // Built-in uses
 use std::{
     collections::HashSet,
+    sync::{Arc, RwLock},
     time::{Duration, Instant},
 };
 
@@ -96,7 +97,7 @@ impl<W: TokenWatcher> MarketUpdater<W> {
 #[derive(Debug, Clone)]
 pub struct FeeTokenValidator {
     // Storage for unconditionally valid tokens, such as ETH
-    unconditionally_valid: HashSet<Address>,
+    unconditionally_valid: Arc<RwLock<HashSet<Address>>>,
     tokens_cache: TokenCacheWrapper,
     available_time: chrono::Duration,
     liquidity_volume: BigDecimal,
@@ -110,18 +111,28 @@ impl FeeTokenValidator {
         unconditionally_valid: HashSet<Address>,
     ) -> Self {
         Self {
-            unconditionally_valid,
+            unconditionally_valid: Arc::new(RwLock::new(unconditionally_valid)),
             tokens_cache: cache.into(),
             available_time,
             liquidity_volume,
         }
     }
 
+    /// Replaces the list of the unconditionally valid tokens for all the clones of the validator.
+    pub(crate) fn set_unconditionally_valid(&self, tokens: HashSet<Address>) {
+        *self.unconditionally_valid.write().unwrap() = tokens;
+    }
+
     /// Returns `true` if token can be used to pay fees.
     pub(crate) async fn token_allowed(&self, token: TokenLike) -> anyhow::Result<bool> {
         let token = self.resolve_token(token).await?;
         if let Some(token) = token {
-            if self.unconditionally_valid.contains(&token.address) {
+            if self
+                .unconditionally_valid
+                .read()
+                .unwrap()
+                .contains(&token.address)
+            {
                 return Ok(true);
             }
             self.check_token(token).await
@@ -283,5 +294,15 @@ mod tests {
         assert!(dai_allowed);
         assert!(!phnx_allowed);
         assert!(eth_allowed);
+
+        // The reloaded list of the unconditionally valid tokens is applied to all the clones.
+        validator
+            .clone()
+            .set_unconditionally_valid(vec![phnx_token_address].into_iter().collect());
+        let phnx_allowed = validator
+            .token_allowed(TokenLike::Address(phnx_token_address))
+            .await
+            .unwrap();
+        assert!(phnx_allowed);
     }
 }
@@ -3,9 +3,15 @@
 //! Main routine of this module operates a multithreaded event loop,
 //! which is used to spawn concurrent tasks to efficiently check the
 //! transactions signatures.
+//! Amount of concurrently running checks is adjusted dynamically depending
+//! on the amount of requests waiting for a check.
 
 // Built-in uses
 use std::collections::HashSet;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
 use std::time::Instant;
 
 // External uses
@@ -13,12 +19,16 @@ use futures::{
     channel::{mpsc, oneshot},
     StreamExt,
 };
-use tokio::task::JoinHandle;
+use tokio::{sync::Semaphore, task::JoinHandle};
 
 // Workspace uses
+use zksync_config::configs::api::CommonApiConfig;
 use zksync_eth_client::EthereumGateway;
 use zksync_types::{
-    tx::{error::TxAddError, EthBatchSignData, EthSignData, TxEthSignature},
+    tx::{
+        error::TxAddError, EIP1271Signature, EIP712Message, EthBatchSignData, EthSignData,
+        TxEthSignature,
+    },
     Address, Order, SignedZkSyncTx, Token, ZkSyncTx,
 };
 // Local uses
@@ -33,6 +43,7 @@ pub enum TxVariant {
     Batch(Vec<SignedZkSyncTx>, Option<EthBatchSignData>),
     Order(Box<Order>),
     Toggle2FA,
+    Message,
 }
 
 /// Wrapper on a `TxVariant` which guarantees that (a batch of)
@@ -71,6 +82,7 @@ impl VerifiedTx {
             TxVariant::Batch(_, _) => panic!("called `unwrap_tx` on a `Batch` value"),
             TxVariant::Order(_) => panic!("called `unwrap_tx` on an `Order` value"),
             TxVariant::Toggle2FA => panic!("called `unwrap_tx` on an `Toggle2FA` value"),
+            TxVariant::Message => panic!("called `unwrap_tx` on a `Message` value"),
         }
     }
 
@@ -81,6 +93,7 @@ impl VerifiedTx {
             TxVariant::Tx(_) => panic!("called `unwrap_batch` on a `Tx` value"),
             TxVariant::Order(_) => panic!("called `unwrap_batch` on an `Order` value"),
             TxVariant::Toggle2FA => panic!("called `unwrap_batch` on an `Toggle2FA` value"),
+            TxVariant::Message => panic!("called `unwrap_batch` on a `Message` value"),
         }
     }
 }
@@ -143,6 +156,18 @@ async fn verify_eth_signature(
                 return Err(TxAddError::IncorrectEthSignature);
             }
         }
+        RequestData::Message(request) => {
+            let signature_correct = verify_ethereum_signature(
+                &request.sign_data.signature,
+                &request.sign_data.message,
+                request.sender,
+                eth_checker,
+            )
+            .await;
+            if !signature_correct {
+                return Err(TxAddError::IncorrectEthSignature);
+            }
+        }
     }
 
     Ok(())
@@ -156,21 +181,87 @@ async fn verify_ethereum_signature(
     sender_address: Address,
     eth_checker: &EthereumChecker,
 ) -> bool {
-    let signer_account = match eth_signature {
-        TxEthSignature::EthereumSignature(packed_signature) => {
-            packed_signature.signature_recover_signer(message)
+    if let TxEthSignature::EthereumSignature(packed_signature) = eth_signature {
+        let signer_account = packed_signature.signature_recover_signer(message);
+        if matches!(signer_account, Ok(address) if address == sender_address) {
+            return true;
         }
-        TxEthSignature::EIP1271Signature(signature) => {
-            return eth_checker
-                .is_eip1271_signature_correct(sender_address, message, signature.clone())
-                .await
-                .expect("Unable to check EIP1271 signature")
+    }
+    verify_eip1271_signature(eth_signature, message, sender_address, eth_checker).await
+}
+
+/// Checks that the EIP-712 typed data was signed by an expected address.
+///
+/// Only the ECDSA signatures are accepted: smart contract wallets validate
+/// the human-readable messages.
+fn verify_eip712_signature(
+    eth_signature: &TxEthSignature,
+    message: &EIP712Message,
+    sender_address: Address,
+) -> bool {
+    match eth_signature {
+        TxEthSignature::EthereumSignature(packed_signature) => matches!(
+            packed_signature.signature_recover_signer_from_raw(&message.signing_hash()),
+            Ok(address) if address == sender_address
+        ),
+        TxEthSignature::EIP1271Signature(_) => false,
+    }
+}
+
+/// Asks the sender contract to validate the signature according to EIP-1271.
+async fn verify_eip1271_signature(
+    eth_signature: &TxEthSignature,
+    message: &[u8],
+    sender_address: Address,
+    eth_checker: &EthereumChecker,
+) -> bool {
+    let signature = match eth_signature {
+        // Smart contract wallets (e.g. Argent) may provide an ECDSA signature made by one of
+        // the wallet owners. Such a signature can't be matched with the sender address,
+        // so we ask the sender contract to validate it according to EIP-1271.
+        // The contract is only called if there is a code deployed at the sender address.
+        TxEthSignature::EthereumSignature(packed_signature) => {
+            EIP1271Signature(packed_signature.serialize_packed().to_vec())
         }
+        TxEthSignature::EIP1271Signature(signature) => signature.clone(),
     };
-    match signer_account {
-        Ok(address) => address == sender_address,
-        Err(_) => false,
-    }
+    eth_checker
+        .is_eip1271_signature_correct(sender_address, message, signature)
+        .await
+        .unwrap_or_else(|err| {
+            vlog::warn!("Unable to check EIP1271 signature: {}", err);
+            false
+        })
+}
+
+/// Recovers the signers of all the ECDSA signatures of the batch at once.
+///
+/// Every signature is recovered only once for each message, independently of the amount of
+/// senders in the batch. Recovery is CPU-bound, so it's performed on the blocking threads pool.
+async fn recover_batch_signers(
+    signatures: &[TxEthSignature],
+    messages: Vec<Vec<u8>>,
+) -> HashSet<Address> {
+    let packed_signatures: Vec<_> = signatures
+        .iter()
+        .filter_map(|signature| match signature {
+            TxEthSignature::EthereumSignature(packed_signature) => Some(packed_signature.clone()),
+            TxEthSignature::EIP1271Signature(_) => None,
+        })
+        .collect();
+
+    tokio::task::spawn_blocking(move || {
+        packed_signatures
+            .iter()
+            .flat_map(|signature| {
+                messages
+                    .iter()
+                    .filter_map(move |message| signature.signature_recover_signer(message).ok())
+            })
+            .collect()
+    })
+    .await
+    .expect("Signers recovery task panicked")
 }
 
 async fn verify_eth_signature_single_tx(
@@ -206,7 +297,7 @@ async fn verify_eth_signature_single_tx(
             verify_ethereum_signature(signature, &sign_data.message, sender_address, eth_checker)
                 .await;
         if !signature_correct {
-            let old_message = tx.get_old_ethereum_sign_message(token);
+            let old_message = tx.get_old_ethereum_sign_message(token.clone());
             if let Some(message) = old_message {
                 signature_correct = verify_ethereum_signature(
                     signature,
@@ -217,6 +308,12 @@ async fn verify_eth_signature_single_tx(
                 .await;
             }
         }
+        if !signature_correct {
+            // Wallets that only expose the typed data signing sign the EIP-712 alternative.
+            if let Some(message) = tx.get_eip712_message(token) {
+                signature_correct = verify_eip712_signature(signature, &message, sender_address);
+            }
+        }
         if !signature_correct {
             return Err(TxAddError::IncorrectEthSignature);
         }
@@ -246,27 +343,32 @@ async fn verify_eth_signature_txs_batch(
         false => None,
     };
 
+    let mut messages = vec![batch_sign_data.message.clone()];
+    messages.extend(old_message.clone());
+    let recovered_signers = recover_batch_signers(&batch_sign_data.signatures, messages).await;
+
     for sender in senders {
         if signers.contains(sender) {
             continue;
         }
+        if recovered_signers.contains(sender) {
+            signers.insert(sender);
+            continue;
+        }
         // All possible signers are cached already and this sender didn't match any of them.
         if signers.len() == batch_sign_data.signatures.len() {
             return Err(TxAddError::IncorrectEthSignature);
         }
+        // Sender is not an owner of any ECDSA signature, so it can only be a smart contract wallet.
         // This block will set the `sender_correct` variable to `true` at the first match.
         let mut sender_correct = false;
         for signature in &batch_sign_data.signatures {
-            let mut signature_correct = verify_ethereum_signature(
-                signature,
-                &batch_sign_data.message,
-                *sender,
-                eth_checker,
-            )
-            .await;
+            let mut signature_correct =
+                verify_eip1271_signature(signature, &batch_sign_data.message, *sender, eth_checker)
+                    .await;
             if !signature_correct {
                 if let Some(old_message) = &old_message {
-                    signature_correct = verify_ethereum_signature(
+                    signature_correct = verify_eip1271_signature(
                         signature,
                         old_message.as_slice(),
                         *sender,
@@ -301,6 +403,9 @@ fn verify_tx_correctness(tx: &mut TxVariant) -> Result<(), TxAddError> {
             tx.tx.check_correctness()?;
         }
         TxVariant::Batch(batch, _) => {
+            // Signatures of the valid batch are verified at once and cached, otherwise
+            // the invalid ones are found by the per-transaction checks.
+            ZkSyncTx::verify_signatures_batch(batch.iter_mut().map(|tx| &mut tx.tx));
             for tx in batch.iter_mut() {
                 tx.tx.check_correctness()?;
             }
@@ -308,7 +413,7 @@ fn verify_tx_correctness(tx: &mut TxVariant) -> Result<(), TxAddError> {
         TxVariant::Order(order) => order
             .check_correctness()
             .map_err(|err| TxAddError::IncorrectTx(TransactionError::OrderError(err)))?,
-        TxVariant::Toggle2FA => {} // There is no data to check correctness of
+        TxVariant::Toggle2FA | TxVariant::Message => {} // There is no data to check correctness of
     }
     Ok(())
 }
@@ -346,6 +451,13 @@ pub struct Toggle2FARequest {
     pub sender: Address,
 }
 
+/// Request to check the Ethereum signature of an arbitrary message.
+#[derive(Debug)]
+pub struct MessageRequest {
+    pub sign_data: EthSignData,
+    pub sender: Address,
+}
+
 /// Request for the signature check.
 #[derive(Debug)]
 pub struct VerifySignatureRequest {
@@ -360,6 +472,7 @@ pub enum RequestData {
     Batch(BatchRequest),
     Order(OrderRequest),
     Toggle2FA(Toggle2FARequest),
+    Message(MessageRequest),
 }
 
 impl RequestData {
@@ -371,32 +484,277 @@ impl RequestData {
             }
             RequestData::Order(request) => TxVariant::Order(request.order.clone()),
             RequestData::Toggle2FA(_) => TxVariant::Toggle2FA,
+            RequestData::Message(_) => TxVariant::Message,
         }
     }
 }
 
+/// Pool of the signature check workers.
+///
+/// Every worker is represented by a semaphore permit. The pool is resized to the current load,
+/// i.e. the amount of busy workers and queued requests, whenever a new request arrives. Only idle
+/// workers are removed. Pool size always stays within the configured bounds.
+#[derive(Debug)]
+struct WorkersPool {
+    permits: Arc<Semaphore>,
+    /// Amount of requests waiting for a worker.
+    queued: Arc<AtomicUsize>,
+    workers: usize,
+    min_workers: usize,
+    max_workers: usize,
+}
+
+impl WorkersPool {
+    fn new(min_workers: usize, max_workers: usize) -> Self {
+        assert!(
+            min_workers > 0 && min_workers <= max_workers,
+            "Incorrect signature checker pool bounds: min {}, max {}",
+            min_workers,
+            max_workers
+        );
+
+        Self {
+            permits: Arc::new(Semaphore::new(min_workers)),
+            queued: Arc::new(AtomicUsize::new(0)),
+            workers: min_workers,
+            min_workers,
+            max_workers,
+        }
+    }
+
+    /// Adjusts the amount of workers to the current load and the `incoming` requests,
+    /// which are not counted as queued yet.
+    fn rescale(&mut self, incoming: usize) {
+        let queued = self.queued.load(Ordering::SeqCst);
+        let busy_workers = self
+            .workers
+            .saturating_sub(self.permits.available_permits());
+        let target = (busy_workers + queued + incoming).clamp(self.min_workers, self.max_workers);
+
+        if target > self.workers {
+            self.permits.add_permits(target - self.workers);
+            self.workers = target;
+        } else {
+            // Only idle workers can be removed, busy ones will be removed on the next rescale.
+            while self.workers > target {
+                match self.permits.try_acquire() {
+                    Ok(permit) => permit.forget(),
+                    Err(_) => break,
+                }
+                self.workers -= 1;
+            }
+        }
+
+        metrics::gauge!("signature_checker.workers", self.workers as f64);
+        metrics::gauge!("signature_checker.queue_depth", queued as f64);
+    }
+}
+
 /// Main routine of the concurrent signature checker.
 /// See the module documentation for details.
 pub fn start_sign_checker(
     client: EthereumGateway,
     input: mpsc::Receiver<VerifySignatureRequest>,
+    config: &CommonApiConfig,
 ) -> JoinHandle<()> {
     let eth_checker = EthereumChecker::new(client);
+    let pool = WorkersPool::new(
+        config.sign_checker_min_workers,
+        config.sign_checker_max_workers,
+    );
 
     /// Basically it receives the requests through the channel and verifies signatures,
     /// notifying the request sender about the check result.
     async fn checker_routine(
         mut input: mpsc::Receiver<VerifySignatureRequest>,
         eth_checker: EthereumChecker,
+        mut pool: WorkersPool,
     ) {
         while let Some(VerifySignatureRequest { data, response }) = input.next().await {
+            pool.rescale(1);
+            pool.queued.fetch_add(1, Ordering::SeqCst);
+
             let eth_checker = eth_checker.clone();
+            let permits = pool.permits.clone();
+            let queued = pool.queued.clone();
             tokio::spawn(async move {
+                let _permit = permits
+                    .acquire_owned()
+                    .await
+                    .expect("Workers semaphore is never closed");
+                queued.fetch_sub(1, Ordering::SeqCst);
+
                 let resp = VerifiedTx::verify(data, &eth_checker).await;
 
                 response.send(resp).unwrap_or_default();
             });
         }
     }
-    tokio::spawn(checker_routine(input, eth_checker))
+    tokio::spawn(checker_routine(input, eth_checker, pool))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_types::{
+        tx::PackedEthSignature, AccountId, Nonce, TokenId, TokenKind, Transfer, H256,
+    };
+
+    /// Checks that the batch in which one account pays the fee for another is accepted only
+    /// if both of them signed the message of the whole batch.
+    #[tokio::test]
+    async fn sponsored_batch_signatures() {
+        let eth_checker = EthereumChecker::new(EthereumGateway::Mock(Default::default()));
+        let token = Token::new(TokenId(0), Address::zero(), "ETH", 18, TokenKind::ERC20);
+        let user_key = H256::repeat_byte(7);
+        let user = PackedEthSignature::address_from_private_key(&user_key).unwrap();
+        let sponsor_key = H256::repeat_byte(8);
+        let sponsor = PackedEthSignature::address_from_private_key(&sponsor_key).unwrap();
+
+        let user_transfer = Transfer::new(
+            AccountId(1),
+            user,
+            Address::repeat_byte(2),
+            TokenId(0),
+            1000u32.into(),
+            0u32.into(),
+            Nonce(0),
+            Default::default(),
+            None,
+        );
+        let fee_transfer = Transfer::new(
+            AccountId(2),
+            sponsor,
+            sponsor,
+            TokenId(0),
+            0u32.into(),
+            10u32.into(),
+            Nonce(0),
+            Default::default(),
+            None,
+        );
+        let txs: Vec<SignedZkSyncTx> = vec![user_transfer, fee_transfer]
+            .into_iter()
+            .map(|tx| ZkSyncTx::from(tx).into())
+            .collect();
+        let senders = [user, sponsor];
+        let message = EthBatchSignData::get_batch_sign_message(
+            txs.iter()
+                .map(|tx| (tx.tx.clone(), token.clone(), tx.tx.account()))
+                .collect(),
+        );
+        let sign = |private_key: &H256, message: &[u8]| {
+            TxEthSignature::EthereumSignature(
+                PackedEthSignature::sign(private_key, message).unwrap(),
+            )
+        };
+        let verify = |signatures: Vec<TxEthSignature>| {
+            let batch_sign_data = EthBatchSignData {
+                signatures,
+                message: message.clone(),
+            };
+            let eth_checker = &eth_checker;
+            let txs = &txs;
+            async move {
+                verify_eth_signature_txs_batch(txs, &senders, &batch_sign_data, eth_checker).await
+            }
+        };
+
+        let result = verify(vec![
+            sign(&user_key, &message),
+            sign(&sponsor_key, &message),
+        ])
+        .await;
+        assert!(result.is_ok());
+
+        // The user didn't agree on the batch.
+        let result = verify(vec![sign(&sponsor_key, &message)]).await;
+        assert!(matches!(result, Err(TxAddError::IncorrectEthSignature)));
+
+        // The user signed their transfer only, not knowing who pays the fee for it.
+        let user_message = txs[0]
+            .tx
+            .get_ethereum_sign_message(token.clone())
+            .unwrap()
+            .into_bytes();
+        let result = verify(vec![
+            sign(&user_key, &user_message),
+            sign(&sponsor_key, &message),
+        ])
+        .await;
+        assert!(matches!(result, Err(TxAddError::IncorrectEthSignature)));
+    }
+
+    /// Checks that the signature of the EIP-712 message is accepted for its signer only.
+    #[test]
+    fn eip712_signature() {
+        let private_key = H256::repeat_byte(7);
+        let sender = PackedEthSignature::address_from_private_key(&private_key).unwrap();
+        let transfer = Transfer::new(
+            AccountId(1),
+            sender,
+            Address::repeat_byte(2),
+            TokenId(0),
+            1000u32.into(),
+            10u32.into(),
+            Nonce(0),
+            Default::default(),
+            None,
+        );
+        let token = Token::new(TokenId(0), Address::zero(), "ETH", 18, TokenKind::ERC20);
+        let message = ZkSyncTx::from(transfer).get_eip712_message(token).unwrap();
+
+        let signature = TxEthSignature::EthereumSignature(
+            PackedEthSignature::sign_raw(&private_key, &message.signing_hash()).unwrap(),
+        );
+        assert!(verify_eip712_signature(&signature, &message, sender));
+        assert!(!verify_eip712_signature(
+            &signature,
+            &message,
+            Address::repeat_byte(3)
+        ));
+
+        // The human-readable message signature doesn't match the typed data.
+        let signature = TxEthSignature::EthereumSignature(
+            PackedEthSignature::sign(&private_key, b"Nonce: 0").unwrap(),
+        );
+        assert!(!verify_eip712_signature(&signature, &message, sender));
+    }
+
+    /// Checks that the pool grows with the queue depth and shrinks back once it's drained.
+    #[test]
+    fn workers_pool_rescale() {
+        let mut pool = WorkersPool::new(2, 8);
+
+        // Load fits into the minimal pool.
+        pool.queued.store(1, Ordering::SeqCst);
+        pool.rescale(1);
+        assert_eq!(pool.workers, 2);
+
+        // Pool grows, but not above the maximum.
+        pool.queued.store(4, Ordering::SeqCst);
+        pool.rescale(1);
+        assert_eq!(pool.workers, 5);
+        pool.queued.store(20, Ordering::SeqCst);
+        pool.rescale(1);
+        assert_eq!(pool.workers, 8);
+
+        // Pool shrinks once the queue is drained, the new request is served by an idle worker.
+        let busy = pool.permits.clone().try_acquire_many_owned(3).unwrap();
+        pool.queued.store(0, Ordering::SeqCst);
+        pool.rescale(1);
+        assert_eq!(pool.workers, 4);
+        assert_eq!(pool.permits.available_permits(), 1);
+
+        // Busy workers are not removed.
+        pool.rescale(0);
+        assert_eq!(pool.workers, 3);
+        pool.rescale(0);
+        assert_eq!(pool.workers, 3);
+
+        drop(busy);
+        pool.rescale(1);
+        assert_eq!(pool.workers, 2);
+        assert_eq!(pool.permits.available_permits(), 2);
+    }
 }
@@ -0,0 +1,353 @@
+//! zkSync core admin API server.
+//!
+//! This file contains the endpoints for the operator actions: pausing the acceptance
+//! of the new transactions, sealing the pending block, cleaning up the mempool and
+//! inspecting the state of the server components.
+//!
+//! Every request must be authorized with the JWT signed by the secret from the
+//! `API_ADMIN_SECRET_AUTH` variable. This API must not be available from outside of the cluster.
+//! Cross-origin requests are governed by the `api.cors` policy with the `api.admin.cors` overrides.
+
+use std::thread;
+
+use actix_web::dev::ServiceRequest;
+use actix_web::{web, App, HttpResponse, HttpServer};
+use actix_web_httpauth::{
+    extractors::{
+        bearer::{BearerAuth, Config},
+        AuthenticationError,
+    },
+    middleware::HttpAuthentication,
+};
+use futures::{channel::mpsc, StreamExt};
+use jsonwebtoken::errors::Error as JwtError;
+use jsonwebtoken::{decode, DecodingKey, Validation};
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinHandle;
+
+use zksync_config::configs::{
+    api::{AdminApiConfig, CorsConfig},
+    chain::BlockSealCriteria,
+};
+use zksync_http_utils::cors::cors;
+use zksync_storage::{misc::records::TxAcceptancePause, ConnectionPool, StorageProcessor};
+use zksync_types::{tx::TxHash, BlockNumber};
+use zksync_utils::panic_notify::ThreadPanicNotify;
+
+use crate::{committer::AggregatedProofSizes, state_keeper::ForceSeal};
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PayloadAuthToken {
+    /// Subject (whom auth token refers to).
+    sub: String,
+    /// Expiration time (as UTC timestamp).
+    exp: usize,
+}
+
+#[derive(Debug, Clone)]
+struct AppState {
+    connection_pool: ConnectionPool,
+    secret_auth: String,
+    force_seal: ForceSeal,
+    seal_criteria: BlockSealCriteria,
+    aggregated_proof_sizes: AggregatedProofSizes,
+}
+
+impl AppState {
+    async fn access_storage(&self) -> actix_web::Result<StorageProcessor<'_>> {
+        self.connection_pool
+            .access_storage()
+            .await
+            .map_err(actix_web::error::ErrorInternalServerError)
+    }
+}
+
+struct AuthTokenValidator<'a> {
+    decoding_key: DecodingKey<'a>,
+}
+
+impl<'a> AuthTokenValidator<'a> {
+    fn new(secret: &'a str) -> Self {
+        Self {
+            decoding_key: DecodingKey::from_secret(secret.as_ref()),
+        }
+    }
+
+    /// Checks whether the secret key and the authorization token match.
+    fn validate_auth_token(&self, token: &str) -> Result<(), JwtError> {
+        decode::<PayloadAuthToken>(token, &self.decoding_key, &Validation::default())?;
+
+        Ok(())
+    }
+
+    async fn validator(
+        &self,
+        req: ServiceRequest,
+        credentials: BearerAuth,
+    ) -> actix_web::Result<ServiceRequest> {
+        let config = req.app_data::<Config>().cloned().unwrap_or_default();
+
+        self.validate_auth_token(credentials.token())
+            .map_err(|_| AuthenticationError::from(config))?;
+
+        Ok(req)
+    }
+}
+
+/// Summary of the block being formed by the state keeper, as it's stored in the database.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PendingBlockInfo {
+    number: BlockNumber,
+    chunks_left: usize,
+    iteration: usize,
+    success_operations: usize,
+    failed_txs: usize,
+    timestamp: u64,
+}
+
+/// State of the server components.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ComponentsState {
+    tx_acceptance_pause: Option<TxAcceptancePause>,
+    mempool_size: u32,
+    pending_block: Option<PendingBlockInfo>,
+    force_seal_requested: bool,
+    last_committed_block: BlockNumber,
+    last_verified_block: BlockNumber,
+    seal_criteria: BlockSealCriteria,
+    aggregated_proof_sizes: Vec<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PauseRequest {
+    reason: String,
+}
+
+/// Result of the mempool clean up.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RemovedTxs {
+    removed: u64,
+}
+
+/// Returns the state of the server components.
+#[actix_web::get("/state")]
+async fn components_state(data: web::Data<AppState>) -> actix_web::Result<HttpResponse> {
+    let mut storage = data.access_storage().await?;
+
+    let tx_acceptance_pause = storage
+        .misc_schema()
+        .load_tx_acceptance_pause()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let mempool_size = storage
+        .chain()
+        .mempool_schema()
+        .get_mempool_size()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let pending_block = storage
+        .chain()
+        .block_schema()
+        .load_pending_block()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?
+        .map(|block| PendingBlockInfo {
+            number: block.number,
+            chunks_left: block.chunks_left,
+            iteration: block.pending_block_iteration,
+            success_operations: block.success_operations.len(),
+            failed_txs: block.failed_txs.len(),
+            timestamp: block.timestamp,
+        });
+    let last_committed_block = storage
+        .chain()
+        .block_schema()
+        .get_last_committed_block()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let last_verified_block = storage
+        .chain()
+        .block_schema()
+        .get_last_verified_confirmed_block()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(ComponentsState {
+        tx_acceptance_pause,
+        mempool_size,
+        pending_block,
+        force_seal_requested: data.force_seal.is_requested(),
+        last_committed_block,
+        last_verified_block,
+        seal_criteria: data.seal_criteria,
+        aggregated_proof_sizes: data.aggregated_proof_sizes.get(),
+    }))
+}
+
+/// Pauses the acceptance of the new transactions, the priority operations are still processed.
+#[actix_web::post("/tx_acceptance/pause")]
+async fn pause_tx_acceptance(
+    data: web::Data<AppState>,
+    request: web::Json<PauseRequest>,
+) -> actix_web::Result<HttpResponse> {
+    let mut storage = data.access_storage().await?;
+    let is_paused = storage
+        .misc_schema()
+        .pause_tx_acceptance(&request.reason)
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    if is_paused {
+        vlog::warn!(
+            "Acceptance of the transactions is paused: {}",
+            request.reason
+        );
+    }
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Resumes the acceptance of the new transactions.
+#[actix_web::post("/tx_acceptance/resume")]
+async fn resume_tx_acceptance(data: web::Data<AppState>) -> actix_web::Result<HttpResponse> {
+    let mut storage = data.access_storage().await?;
+    let is_resumed = storage
+        .misc_schema()
+        .resume_tx_acceptance()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    if is_resumed {
+        vlog::info!("Acceptance of the transactions is resumed");
+    }
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Requests the state keeper to seal the pending block on the next miniblock iteration.
+/// The request is ignored if the pending block is empty.
+#[actix_web::post("/block/seal")]
+async fn force_seal(data: web::Data<AppState>) -> actix_web::Result<HttpResponse> {
+    data.force_seal.request();
+    vlog::info!("Sealing of the pending block is requested");
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Removes the already executed transactions from the mempool.
+#[actix_web::post("/mempool/recheck")]
+async fn recheck_mempool(data: web::Data<AppState>) -> actix_web::Result<HttpResponse> {
+    let mut storage = data.access_storage().await?;
+    let mut mempool_schema = storage.chain().mempool_schema();
+
+    let size_before = mempool_schema
+        .get_mempool_size()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    mempool_schema
+        .collect_garbage()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let size_after = mempool_schema
+        .get_mempool_size()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    // Transactions can be added concurrently, so the difference may be negative.
+    let removed = size_before.saturating_sub(size_after) as u64;
+    vlog::info!("Mempool is rechecked, {} transactions are removed", removed);
+    Ok(HttpResponse::Ok().json(RemovedTxs { removed }))
+}
+
+/// Removes the queued transaction from the mempool. If the transaction belongs to a batch,
+/// the whole batch is removed.
+#[actix_web::delete("/mempool/txs/{tx_hash}")]
+async fn remove_queued_tx(
+    data: web::Data<AppState>,
+    tx_hash: web::Path<String>,
+) -> actix_web::Result<HttpResponse> {
+    let tx_hash: TxHash = tx_hash.parse().map_err(actix_web::error::ErrorBadRequest)?;
+
+    let mut storage = data.access_storage().await?;
+    let removed = storage
+        .chain()
+        .mempool_schema()
+        .remove_queued_tx(tx_hash)
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    if removed == 0 {
+        return Err(actix_web::error::ErrorNotFound(
+            "Queued transaction with the given hash is not found",
+        ));
+    }
+    vlog::info!(
+        "Queued transaction {} is removed from the mempool along with {} others",
+        tx_hash.to_string(),
+        removed - 1
+    );
+    Ok(HttpResponse::Ok().json(RemovedTxs { removed }))
+}
+
+pub fn start_admin_core_api(
+    connection_pool: ConnectionPool,
+    force_seal: ForceSeal,
+    seal_criteria: BlockSealCriteria,
+    aggregated_proof_sizes: AggregatedProofSizes,
+    config: AdminApiConfig,
+    cors_config: CorsConfig,
+) -> JoinHandle<()> {
+    let (panic_sender, mut panic_receiver) = mpsc::channel(1);
+
+    thread::Builder::new()
+        .name("core-admin-api".to_string())
+        .spawn(move || {
+            let _panic_sentinel = ThreadPanicNotify(panic_sender.clone());
+            let actix_runtime = actix_rt::System::new();
+
+            actix_runtime.block_on(async move {
+                let app_state = AppState {
+                    connection_pool,
+                    secret_auth: config.secret_auth.clone(),
+                    force_seal,
+                    seal_criteria,
+                    aggregated_proof_sizes,
+                };
+
+                HttpServer::new(move || {
+                    let auth = HttpAuthentication::bearer(move |req, credentials| async {
+                        let secret_auth = req
+                            .app_data::<web::Data<AppState>>()
+                            .expect("failed get AppState upon receipt of the authentication token")
+                            .secret_auth
+                            .clone();
+                        AuthTokenValidator::new(&secret_auth)
+                            .validator(req, credentials)
+                            .await
+                    });
+
+                    // Preflight requests don't have the credentials, so they're handled before the authorization.
+                    App::new()
+                        .wrap(auth)
+                        .wrap(actix_web::middleware::Logger::default())
+                        .wrap(cors(&cors_config))
+                        .app_data(web::Data::new(app_state.clone()))
+                        .service(components_state)
+                        .service(pause_tx_acceptance)
+                        .service(resume_tx_acceptance)
+                        .service(force_seal)
+                        .service(recheck_mempool)
+                        .service(remove_queued_tx)
+                })
+                .bind(&config.bind_addr())
+                .expect("failed to bind")
+                .run()
+                .await
+            })
+        })
+        .expect("failed to start admin API server");
+    tokio::spawn(async move {
+        panic_receiver.next().await.unwrap();
+    })
+}
@@ -0,0 +1,388 @@
+//! The alerter notifies the operators about the anomalies of the server in the configured
+//! channels (Slack and/or Telegram), so they're not only visible to the ones watching the dashboards.
+//!
+//! The conditions are checked periodically against the database and the Ethereum node:
+//! the committed blocks waiting for the proofs for too long, the priority operations approaching
+//! their deadline, the Ethereum transactions that are not confirmed for too long and the token prices
+//! that are not updated anymore. The alert is repeated while its condition persists, and the operators
+//! are notified once it's resolved.
+
+// Built-in uses
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    time::Instant,
+};
+// External uses
+use chrono::{DateTime, Utc};
+use tokio::{task::JoinHandle, time};
+// Workspace deps
+use zksync_config::AlertingConfig;
+use zksync_eth_client::EthereumGateway;
+use zksync_notifier::AlertNotifier;
+use zksync_storage::{ConnectionPool, StorageProcessor};
+use zksync_types::BlockNumber;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AlertKind {
+    ProofLag,
+    PriorityOpDeadline,
+    StuckEthTx,
+    StalePrices,
+}
+
+impl fmt::Display for AlertKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            AlertKind::ProofLag => "proof generation lag",
+            AlertKind::PriorityOpDeadline => "priority operation deadline",
+            AlertKind::StuckEthTx => "stuck Ethereum transaction",
+            AlertKind::StalePrices => "stale token prices",
+        };
+        f.write_str(name)
+    }
+}
+
+/// State of the server the alert conditions are checked against.
+#[derive(Debug, Clone)]
+pub struct HealthSnapshot {
+    pub last_committed_block: BlockNumber,
+    pub last_proven_block: BlockNumber,
+    /// Latest Ethereum block, `None` if the node is unavailable.
+    pub eth_block: Option<u64>,
+    /// Earliest deadline of the priority operations waiting to be executed.
+    pub earliest_priority_op_deadline: Option<u64>,
+    /// Creation time of the oldest Ethereum operation that isn't confirmed yet.
+    pub oldest_unconfirmed_eth_tx: Option<DateTime<Utc>>,
+    /// Time of the latest update of the token prices.
+    pub last_price_update: Option<DateTime<Utc>>,
+    pub now: DateTime<Utc>,
+}
+
+impl HealthSnapshot {
+    async fn load(
+        storage: &mut StorageProcessor<'_>,
+        eth_gateway: &EthereumGateway,
+    ) -> anyhow::Result<Self> {
+        let eth_block = match eth_gateway.block_number().await {
+            Ok(block) => Some(block.as_u64()),
+            Err(e) => {
+                vlog::warn!("Alerter couldn't get the latest Ethereum block: {}", e);
+                None
+            }
+        };
+        let mut block_schema = storage.chain().block_schema();
+        let last_committed_block = block_schema.get_last_committed_block().await?;
+        let last_proven_block = block_schema.get_last_proven_block().await?;
+
+        Ok(Self {
+            last_committed_block,
+            last_proven_block,
+            eth_block,
+            earliest_priority_op_deadline: storage
+                .chain()
+                .mempool_schema()
+                .get_earliest_priority_op_deadline()
+                .await?,
+            oldest_unconfirmed_eth_tx: storage
+                .ethereum_schema()
+                .get_oldest_unconfirmed_operation_time()
+                .await?,
+            last_price_update: storage
+                .tokens_schema()
+                .get_last_ticker_price_update()
+                .await?,
+            now: Utc::now(),
+        })
+    }
+}
+
+/// Returns the alerts raised for the snapshot along with their messages.
+pub fn check_alerts(
+    snapshot: &HealthSnapshot,
+    config: &AlertingConfig,
+) -> Vec<(AlertKind, String)> {
+    let mut alerts = Vec::new();
+
+    let proof_lag = snapshot
+        .last_committed_block
+        .saturating_sub(*snapshot.last_proven_block);
+    if proof_lag > config.max_proof_lag_blocks {
+        alerts.push((
+            AlertKind::ProofLag,
+            format!(
+                "{} committed blocks are waiting for the proofs, the last proven block is {}",
+                proof_lag, *snapshot.last_proven_block
+            ),
+        ));
+    }
+
+    if let (Some(deadline), Some(eth_block)) =
+        (snapshot.earliest_priority_op_deadline, snapshot.eth_block)
+    {
+        let blocks_left = deadline.saturating_sub(eth_block);
+        if blocks_left < config.min_priority_op_deadline_blocks {
+            alerts.push((
+                AlertKind::PriorityOpDeadline,
+                format!(
+                    "Priority operation must be executed in {} Ethereum blocks (deadline block {})",
+                    blocks_left, deadline
+                ),
+            ));
+        }
+    }
+
+    if let Some(created_at) = snapshot.oldest_unconfirmed_eth_tx {
+        let age = (snapshot.now - created_at).to_std().unwrap_or_default();
+        if age > config.max_eth_tx_age() {
+            alerts.push((
+                AlertKind::StuckEthTx,
+                format!(
+                    "Ethereum operation is not confirmed for {} minutes",
+                    age.as_secs() / 60
+                ),
+            ));
+        }
+    }
+
+    if let Some(last_update) = snapshot.last_price_update {
+        let age = (snapshot.now - last_update).to_std().unwrap_or_default();
+        if age > config.max_price_age() {
+            alerts.push((
+                AlertKind::StalePrices,
+                format!(
+                    "Token prices are not updated for {} minutes, the price sources may be failing",
+                    age.as_secs() / 60
+                ),
+            ));
+        }
+    }
+
+    alerts
+}
+
+/// Returns the alerts whose conditions can't be checked against the snapshot,
+/// they're neither raised nor resolved until the missing data is available again.
+pub fn unchecked_alerts(snapshot: &HealthSnapshot) -> Vec<AlertKind> {
+    let mut unchecked = Vec::new();
+    if snapshot.eth_block.is_none() {
+        unchecked.push(AlertKind::PriorityOpDeadline);
+    }
+    unchecked
+}
+
+struct Alerter {
+    config: AlertingConfig,
+    notifier: AlertNotifier,
+    /// Alerts whose conditions persist along with the time they were last sent.
+    raised: HashMap<AlertKind, Instant>,
+}
+
+impl Alerter {
+    fn new(config: AlertingConfig) -> Self {
+        let mut notifier = AlertNotifier::default();
+        if let Some(url) = &config.slack_webhook_url {
+            let url = url.parse().expect("invalid Slack webhook URL");
+            notifier = notifier.with_slack(url);
+        }
+        if let (Some(bot_token), Some(chat_id)) =
+            (&config.telegram_bot_token, &config.telegram_chat_id)
+        {
+            notifier = notifier.with_telegram(bot_token.clone(), chat_id.clone());
+        }
+        if !notifier.has_channels() {
+            vlog::warn!("No alerting channels are configured, the alerts are only logged");
+        }
+
+        Self {
+            config,
+            notifier,
+            raised: HashMap::new(),
+        }
+    }
+
+    async fn send(&self, text: &str) {
+        vlog::warn!("Alert: {}", text);
+        if let Err(e) = self.notifier.send_alert(text).await {
+            vlog::error!("Can't send the alert: {:?}", e);
+        }
+    }
+
+    /// Removes and returns the raised alerts that are neither raised again nor unchecked.
+    fn take_resolved(
+        &mut self,
+        alerts: &[(AlertKind, String)],
+        unchecked: &[AlertKind],
+    ) -> Vec<AlertKind> {
+        let kinds: HashSet<_> = alerts
+            .iter()
+            .map(|(kind, _)| kind)
+            .chain(unchecked)
+            .copied()
+            .collect();
+
+        let resolved: Vec<_> = self
+            .raised
+            .keys()
+            .filter(|kind| !kinds.contains(kind))
+            .copied()
+            .collect();
+        for kind in &resolved {
+            self.raised.remove(kind);
+        }
+        resolved
+    }
+
+    /// Sends the new alerts and repeats the ones that are due, notifies about the resolved ones.
+    async fn notify(&mut self, alerts: Vec<(AlertKind, String)>, unchecked: Vec<AlertKind>) {
+        let now = Instant::now();
+
+        for kind in self.take_resolved(&alerts, &unchecked) {
+            self.send(&format!("Resolved: {}", kind)).await;
+        }
+
+        for (kind, message) in alerts {
+            let due = match self.raised.get(&kind) {
+                Some(sent_at) => now.duration_since(*sent_at) >= self.config.repeat_interval(),
+                None => true,
+            };
+            if due {
+                self.raised.insert(kind, now);
+                self.send(&format!("{}: {}", kind, message)).await;
+            }
+        }
+        metrics::gauge!("alerter.raised_alerts", self.raised.len() as f64);
+    }
+}
+
+#[must_use]
+pub fn run_alerter(
+    config: AlertingConfig,
+    db_pool: ConnectionPool,
+    eth_gateway: EthereumGateway,
+) -> JoinHandle<()> {
+    let mut timer = time::interval(config.check_interval());
+    let mut alerter = Alerter::new(config);
+
+    tokio::spawn(async move {
+        loop {
+            timer.tick().await;
+            let mut storage = match db_pool.access_storage().await {
+                Ok(storage) => storage,
+                Err(e) => {
+                    vlog::error!("Alerter couldn't access the database: {:?}", e);
+                    continue;
+                }
+            };
+            match HealthSnapshot::load(&mut storage, &eth_gateway).await {
+                Ok(snapshot) => {
+                    let alerts = check_alerts(&snapshot, &alerter.config);
+                    let unchecked = unchecked_alerts(&snapshot);
+                    alerter.notify(alerts, unchecked).await;
+                }
+                Err(e) => vlog::error!("Can't check the alert conditions {:?}", e),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn config() -> AlertingConfig {
+        AlertingConfig {
+            check_interval: 60,
+            repeat_interval: 3600,
+            max_proof_lag_blocks: 50,
+            min_priority_op_deadline_blocks: 1000,
+            max_eth_tx_age: 1800,
+            max_price_age: 600,
+            slack_webhook_url: None,
+            telegram_bot_token: None,
+            telegram_chat_id: None,
+        }
+    }
+
+    fn healthy_snapshot() -> HealthSnapshot {
+        let now = Utc::now();
+        HealthSnapshot {
+            last_committed_block: BlockNumber(100),
+            last_proven_block: BlockNumber(90),
+            eth_block: Some(10_000),
+            earliest_priority_op_deadline: Some(20_000),
+            oldest_unconfirmed_eth_tx: Some(now - Duration::minutes(5)),
+            last_price_update: Some(now - Duration::minutes(1)),
+            now,
+        }
+    }
+
+    fn alert_kinds(snapshot: &HealthSnapshot) -> Vec<AlertKind> {
+        check_alerts(snapshot, &config())
+            .into_iter()
+            .map(|(kind, _)| kind)
+            .collect()
+    }
+
+    #[test]
+    fn alert_conditions() {
+        let snapshot = healthy_snapshot();
+        assert!(alert_kinds(&snapshot).is_empty());
+
+        let mut snapshot = healthy_snapshot();
+        snapshot.last_proven_block = BlockNumber(40);
+        snapshot.earliest_priority_op_deadline = Some(10_500);
+        assert_eq!(
+            alert_kinds(&snapshot),
+            vec![AlertKind::ProofLag, AlertKind::PriorityOpDeadline]
+        );
+        // The deadline isn't checked while the Ethereum node is unavailable.
+        snapshot.eth_block = None;
+        assert_eq!(alert_kinds(&snapshot), vec![AlertKind::ProofLag]);
+
+        let mut snapshot = healthy_snapshot();
+        snapshot.oldest_unconfirmed_eth_tx = Some(snapshot.now - Duration::hours(1));
+        snapshot.last_price_update = Some(snapshot.now - Duration::hours(1));
+        assert_eq!(
+            alert_kinds(&snapshot),
+            vec![AlertKind::StuckEthTx, AlertKind::StalePrices]
+        );
+        // Nothing to check if there are no pending operations and prices.
+        snapshot.oldest_unconfirmed_eth_tx = None;
+        snapshot.last_price_update = None;
+        assert!(alert_kinds(&snapshot).is_empty());
+    }
+
+    #[test]
+    fn unchecked_alerts_are_not_resolved() {
+        let mut alerter = Alerter::new(config());
+        let mut snapshot = healthy_snapshot();
+        snapshot.earliest_priority_op_deadline = Some(10_500);
+        let alerts = check_alerts(&snapshot, &alerter.config);
+        assert!(alerter
+            .take_resolved(&alerts, &unchecked_alerts(&snapshot))
+            .is_empty());
+        alerter
+            .raised
+            .insert(AlertKind::PriorityOpDeadline, Instant::now());
+
+        // The Ethereum node is unavailable, the deadline alert stays raised.
+        snapshot.eth_block = None;
+        let alerts = check_alerts(&snapshot, &alerter.config);
+        assert!(alerts.is_empty());
+        assert!(alerter
+            .take_resolved(&alerts, &unchecked_alerts(&snapshot))
+            .is_empty());
+        assert!(alerter.raised.contains_key(&AlertKind::PriorityOpDeadline));
+
+        // The node is back and the deadline is far away.
+        let snapshot = healthy_snapshot();
+        let alerts = check_alerts(&snapshot, &alerter.config);
+        assert_eq!(
+            alerter.take_resolved(&alerts, &unchecked_alerts(&snapshot)),
+            vec![AlertKind::PriorityOpDeadline]
+        );
+        assert!(alerter.raised.is_empty());
+    }
+}
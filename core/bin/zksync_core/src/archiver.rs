@@ -0,0 +1,66 @@
+//! The archiver is responsible for moving the rows of the old executed blocks from the database
+//! to the object storage: the events and the pubdata of the committed blocks.
+//!
+//! Rows are archived by the ranges of the configured amount of blocks, every range is stored
+//! as a single object of gzipped JSON lines. Rows of the configurable amount of the latest
+//! executed blocks are always kept in the database, so the event listener never misses them.
+//! Archived rows remain accessible through the connections having access to the archive,
+//! see `ConnectionPool::with_archive`.
+
+// Built-in uses
+use std::sync::Arc;
+// External uses
+use tokio::{task::JoinHandle, time};
+// Workspace deps
+use zksync_config::ArchiverConfig;
+use zksync_storage::{archive::ObjectStore, ConnectionPool, StorageProcessor};
+use zksync_types::BlockNumber;
+
+#[must_use]
+pub fn run_archiver(
+    config: &ArchiverConfig,
+    db_pool: ConnectionPool,
+    store: Arc<dyn ObjectStore>,
+) -> JoinHandle<()> {
+    let retention_blocks = config.retention_blocks;
+    let blocks_per_object = config.blocks_per_object;
+    let mut timer = time::interval(config.archive_interval());
+
+    tokio::spawn(async move {
+        loop {
+            timer.tick().await;
+            let mut storage = db_pool
+                .access_storage()
+                .await
+                .expect("archiver couldn't access the database");
+            if let Err(e) = archive_blocks(
+                &mut storage,
+                store.as_ref(),
+                retention_blocks,
+                blocks_per_object,
+            )
+            .await
+            {
+                vlog::error!("Can't archive the old blocks {:?}", e);
+            }
+        }
+    })
+}
+
+async fn archive_blocks(
+    storage: &mut StorageProcessor<'_>,
+    store: &dyn ObjectStore,
+    retention_blocks: u32,
+    blocks_per_object: u32,
+) -> anyhow::Result<()> {
+    let last_executed_block = storage
+        .chain()
+        .block_schema()
+        .get_last_verified_confirmed_block()
+        .await?;
+    let archive_up_to = BlockNumber(last_executed_block.saturating_sub(retention_blocks));
+    storage
+        .archive_schema()
+        .archive_blocks(store, archive_up_to, blocks_per_object)
+        .await
+}
@@ -22,6 +22,7 @@ async fn main() {
         Some(eth_watcher_config.request_per_task_limit()),
         Some(eth_watcher_config.task_limit()),
     )
+    .with_new_heads_subscription(eth_client_config.web3_ws_url.clone())
     .run()
     .await;
 }
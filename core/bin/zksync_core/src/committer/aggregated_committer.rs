@@ -17,11 +17,65 @@ use zksync_types::{
     BlockNumber, U256,
 };
 
+/// Amount of wei in one gwei.
+const WEI_IN_GWEI: u64 = 1_000_000_000;
+
+/// Gas price aware choice of the aggregated operations size.
+///
+/// The base cost of the aggregated operation transaction is paid once, so it's shared by the aggregated blocks.
+/// With the configured cost target the operations are created as soon as there are enough blocks to keep
+/// the base cost per block below the target: more blocks are batched when the gas is expensive, while the blocks
+/// are sent sooner when it's cheap.
+#[derive(Debug, Clone, Copy)]
+struct AggregationCost {
+    gas_price: Option<U256>,
+    cost_target_gwei: u64,
+}
+
+impl AggregationCost {
+    async fn load(
+        storage: &mut StorageProcessor<'_>,
+        config: &ChainConfig,
+    ) -> anyhow::Result<Self> {
+        let cost_target_gwei = config.state_keeper.aggregation_cost_target_gwei;
+        let gas_price = if cost_target_gwei > 0 {
+            storage.ethereum_schema().load_average_gas_price().await?
+        } else {
+            None
+        };
+        Ok(Self {
+            gas_price,
+            cost_target_gwei,
+        })
+    }
+
+    /// Returns the amount of blocks which is enough to create the operation with the given base transaction cost.
+    /// It's `max_blocks` if the criterion is disabled or the gas price is not known yet.
+    fn blocks_to_aggregate(&self, base_tx_cost: usize, max_blocks: usize) -> usize {
+        let gas_price = match self.gas_price {
+            Some(gas_price) if self.cost_target_gwei > 0 => gas_price,
+            _ => return max_blocks,
+        };
+        let cost_target = U256::from(self.cost_target_gwei) * U256::from(WEI_IN_GWEI);
+        let tx_cost = gas_price.saturating_mul(U256::from(base_tx_cost));
+        let (mut blocks, remainder) = tx_cost.div_mod(cost_target);
+        if !remainder.is_zero() {
+            blocks += U256::one();
+        }
+        if blocks >= U256::from(max_blocks) {
+            max_blocks
+        } else {
+            max(blocks.as_usize(), 1)
+        }
+    }
+}
+
 fn create_new_commit_operation(
     last_committed_block: &Block,
     new_blocks: &[Block],
     current_time: DateTime<Utc>,
     max_blocks_to_commit: usize,
+    enough_blocks_to_commit: usize,
     block_commit_deadline: Duration,
     max_gas_for_tx: U256,
     fast_processing: bool,
@@ -50,7 +104,7 @@ fn create_new_commit_operation(
 
     let should_commit_blocks = any_block_commit_deadline_triggered
         || gas_limit_reached_for_blocks
-        || new_blocks.len() == max_blocks_to_commit
+        || new_blocks.len() >= enough_blocks_to_commit
         || fast_processing;
     if !should_commit_blocks {
         return None;
@@ -76,6 +130,7 @@ fn create_new_commit_operation(
 fn create_new_create_proof_operation(
     new_blocks_with_proofs: &[Block],
     available_aggregate_proof_sizes: &[usize],
+    enough_blocks_to_prove: usize,
     current_time: DateTime<Utc>,
     block_verify_deadline: Duration,
     _max_gas_for_tx: U256,
@@ -102,24 +157,30 @@ fn create_new_create_proof_operation(
             })
     };
 
-    let can_create_max_aggregate_proof = new_blocks_with_proofs.len() >= max_aggregate_size;
+    let can_create_aggregate_proof = new_blocks_with_proofs.len() >= enough_blocks_to_prove;
 
     let should_create_aggregate_proof =
-        any_block_verify_deadline_triggered || can_create_max_aggregate_proof || fast_processing;
+        any_block_verify_deadline_triggered || can_create_aggregate_proof || fast_processing;
 
     if !should_create_aggregate_proof {
         return None;
     }
 
-    // get max possible aggregate size
-    let aggregate_proof_size = available_aggregate_proof_sizes
-        .iter()
-        .rev()
-        .find(|aggregate_size| {
-            *aggregate_size >= &std::cmp::min(new_blocks_with_proofs.len(), max_aggregate_size)
-        })
-        .cloned()
-        .expect("failed to find correct aggregate proof size");
+    let blocks_count = std::cmp::min(new_blocks_with_proofs.len(), max_aggregate_size);
+    let aggregate_proof_size = if enough_blocks_to_prove < max_aggregate_size {
+        // Smaller proofs are created faster, so the gas price allows to skip the padding.
+        available_aggregate_proof_sizes
+            .iter()
+            .find(|aggregate_size| **aggregate_size >= blocks_count)
+    } else {
+        // get max possible aggregate size
+        available_aggregate_proof_sizes
+            .iter()
+            .rev()
+            .find(|aggregate_size| **aggregate_size >= blocks_count)
+    }
+    .cloned()
+    .expect("failed to find correct aggregate proof size");
 
     let blocks = new_blocks_with_proofs
         .iter()
@@ -151,6 +212,7 @@ fn create_execute_blocks_operation(
     proven_non_executed_block: &[Block],
     current_time: DateTime<Utc>,
     max_blocks_to_execute: usize,
+    enough_blocks_to_execute: usize,
     block_execute_deadline: Duration,
     max_gas_for_tx: U256,
     fast_processing: bool,
@@ -178,7 +240,7 @@ fn create_execute_blocks_operation(
 
     let should_execute_blocks = any_block_execute_deadline_triggered
         || gas_limit_reached_for_blocks
-        || proven_non_executed_block.len() == max_blocks_to_execute
+        || proven_non_executed_block.len() >= enough_blocks_to_execute
         || fast_processing;
     if !should_execute_blocks {
         return None;
@@ -224,6 +286,7 @@ async fn is_fast_processing_requested(
 async fn create_aggregated_commits_storage(
     storage: &mut StorageProcessor<'_>,
     config: &ChainConfig,
+    aggregation_cost: AggregationCost,
 ) -> anyhow::Result<bool> {
     let mut transaction = storage.start_transaction().await?;
     let last_aggregate_committed_block = OperationsSchema(&mut transaction)
@@ -253,6 +316,10 @@ async fn create_aggregated_commits_storage(
         &new_blocks,
         Utc::now(),
         config.state_keeper.max_aggregated_blocks_to_commit,
+        aggregation_cost.blocks_to_aggregate(
+            GasCounter::BASE_COMMIT_BLOCKS_TX_COST,
+            config.state_keeper.max_aggregated_blocks_to_commit,
+        ),
         config.state_keeper.block_commit_deadline(),
         config.state_keeper.max_aggregated_tx_gas.into(),
         fast_processing_requested,
@@ -276,6 +343,8 @@ async fn create_aggregated_commits_storage(
 async fn create_aggregated_prover_task_storage(
     storage: &mut StorageProcessor<'_>,
     config: &ChainConfig,
+    aggregated_proof_sizes: &[usize],
+    aggregation_cost: AggregationCost,
 ) -> anyhow::Result<bool> {
     let mut transaction = storage.start_transaction().await?;
     let last_aggregate_committed_block = OperationsSchema(&mut transaction)
@@ -309,9 +378,12 @@ async fn create_aggregated_prover_task_storage(
     let fast_processing_requested =
         is_fast_processing_requested(&mut transaction, &blocks_with_proofs).await?;
 
+    let max_aggregate_size = aggregated_proof_sizes.last().cloned().unwrap_or_default();
     let create_proof_operation = create_new_create_proof_operation(
         &blocks_with_proofs,
-        &config.state_keeper.aggregated_proof_sizes,
+        aggregated_proof_sizes,
+        aggregation_cost
+            .blocks_to_aggregate(GasCounter::BASE_PROOF_BLOCKS_TX_COST, max_aggregate_size),
         Utc::now(),
         config.state_keeper.block_prove_deadline(),
         config.state_keeper.max_aggregated_tx_gas.into(),
@@ -404,6 +476,7 @@ async fn create_aggregated_publish_proof_operation_storage(
 async fn create_aggregated_execute_operation_storage(
     storage: &mut StorageProcessor<'_>,
     config: &ChainConfig,
+    aggregation_cost: AggregationCost,
 ) -> anyhow::Result<bool> {
     let mut transaction = storage.start_transaction().await?;
     let last_aggregate_executed_block = OperationsSchema(&mut transaction)
@@ -434,6 +507,10 @@ async fn create_aggregated_execute_operation_storage(
         &blocks,
         Utc::now(),
         config.state_keeper.max_aggregated_blocks_to_execute,
+        aggregation_cost.blocks_to_aggregate(
+            GasCounter::BASE_EXECUTE_BLOCKS_TX_COST,
+            config.state_keeper.max_aggregated_blocks_to_execute,
+        ),
         config.state_keeper.block_execute_deadline(),
         config.state_keeper.max_aggregated_tx_gas.into(),
         fast_processing_requested,
@@ -457,11 +534,21 @@ async fn create_aggregated_execute_operation_storage(
 pub async fn create_aggregated_operations_storage(
     storage: &mut StorageProcessor<'_>,
     config: &ChainConfig,
+    aggregated_proof_sizes: &[usize],
 ) -> anyhow::Result<()> {
-    while create_aggregated_commits_storage(storage, config).await? {}
-    while create_aggregated_prover_task_storage(storage, config).await? {}
+    let aggregation_cost = AggregationCost::load(storage, config).await?;
+
+    while create_aggregated_commits_storage(storage, config, aggregation_cost).await? {}
+    while create_aggregated_prover_task_storage(
+        storage,
+        config,
+        aggregated_proof_sizes,
+        aggregation_cost,
+    )
+    .await?
+    {}
     while create_aggregated_publish_proof_operation_storage(storage).await? {}
-    while create_aggregated_execute_operation_storage(storage, config).await? {}
+    while create_aggregated_execute_operation_storage(storage, config, aggregation_cost).await? {}
 
     Ok(())
 }
@@ -475,3 +562,36 @@ fn log_aggregated_op_creation(aggregated_op: &AggregatedOperation) {
         last
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blocks_to_aggregate() {
+        let gwei = |amount: u64| Some(U256::from(amount) * U256::from(WEI_IN_GWEI));
+        let cost = |gas_price, cost_target_gwei| AggregationCost {
+            gas_price,
+            cost_target_gwei,
+        };
+
+        // Criterion is disabled or the gas price is unknown.
+        assert_eq!(cost(gwei(100), 0).blocks_to_aggregate(450_000, 5), 5);
+        assert_eq!(cost(None, 10_000_000).blocks_to_aggregate(450_000, 5), 5);
+        // Base cost is 4_500_000 gwei, so 2 blocks keep it below the target.
+        assert_eq!(cost(gwei(10), 3_000_000).blocks_to_aggregate(450_000, 5), 2);
+        assert_eq!(cost(gwei(10), 2_250_000).blocks_to_aggregate(450_000, 5), 2);
+        // Cheap gas.
+        assert_eq!(cost(gwei(1), 3_000_000).blocks_to_aggregate(450_000, 5), 1);
+        assert_eq!(
+            cost(Some(U256::zero()), 1).blocks_to_aggregate(450_000, 5),
+            1
+        );
+        // Expensive gas.
+        assert_eq!(
+            cost(gwei(1000), 3_000_000).blocks_to_aggregate(450_000, 5),
+            5
+        );
+        assert_eq!(cost(Some(U256::MAX), 1).blocks_to_aggregate(450_000, 5), 5);
+    }
+}
@@ -0,0 +1,94 @@
+//! Aggregated proof sizes that can be changed while the server is running.
+
+// Built-in uses
+use std::sync::{Arc, RwLock};
+// External uses
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum InvalidAggregatedProofSizes {
+    #[error("At least one aggregated proof size should be provided")]
+    Empty,
+    #[error("Aggregated proof sizes should be sorted in strictly increasing order")]
+    NotIncreasing,
+    #[error("Aggregated proof size {0} has no setup and verification keys")]
+    Unsupported(usize),
+}
+
+/// Sizes of the aggregated proofs the committer is allowed to create.
+///
+/// Provers support every size from the circuit config, so any subset of them can be
+/// chosen at runtime without redeploying provers.
+#[derive(Debug, Clone)]
+pub struct AggregatedProofSizes {
+    /// Sizes for which there are setup keys and the verification key in the contract.
+    supported: Arc<Vec<usize>>,
+    current: Arc<RwLock<Vec<usize>>>,
+}
+
+impl AggregatedProofSizes {
+    /// Creates a new object, panics if `sizes` are not valid.
+    pub fn new(sizes: Vec<usize>, supported: Vec<usize>) -> Self {
+        let supported = Arc::new(supported);
+        Self::validate(&sizes, &supported).expect("Incorrect aggregated proof sizes in config");
+
+        Self {
+            supported,
+            current: Arc::new(RwLock::new(sizes)),
+        }
+    }
+
+    /// Returns the currently used aggregated proof sizes.
+    pub fn get(&self) -> Vec<usize> {
+        self.current.read().unwrap().clone()
+    }
+
+    /// Replaces the used aggregated proof sizes, the new sizes are applied to
+    /// the next aggregated proof created by the committer.
+    pub fn set(&self, sizes: Vec<usize>) -> Result<(), InvalidAggregatedProofSizes> {
+        Self::validate(&sizes, &self.supported)?;
+        vlog::info!("Aggregated proof sizes are changed to {:?}", sizes);
+        *self.current.write().unwrap() = sizes;
+        Ok(())
+    }
+
+    fn validate(sizes: &[usize], supported: &[usize]) -> Result<(), InvalidAggregatedProofSizes> {
+        if sizes.is_empty() {
+            return Err(InvalidAggregatedProofSizes::Empty);
+        }
+        if sizes.windows(2).any(|pair| pair[0] >= pair[1]) {
+            return Err(InvalidAggregatedProofSizes::NotIncreasing);
+        }
+        if let Some(size) = sizes.iter().find(|size| !supported.contains(size)) {
+            return Err(InvalidAggregatedProofSizes::Unsupported(*size));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aggregated_proof_sizes_validation() {
+        let sizes = AggregatedProofSizes::new(vec![1, 5], vec![1, 5, 10, 20]);
+        assert_eq!(sizes.get(), vec![1, 5]);
+
+        assert_eq!(sizes.set(vec![]), Err(InvalidAggregatedProofSizes::Empty));
+        assert_eq!(
+            sizes.set(vec![5, 1]),
+            Err(InvalidAggregatedProofSizes::NotIncreasing)
+        );
+        assert_eq!(
+            sizes.set(vec![1, 7]),
+            Err(InvalidAggregatedProofSizes::Unsupported(7))
+        );
+        assert_eq!(sizes.get(), vec![1, 5]);
+
+        // The change is visible through the clones.
+        let cloned = sizes.clone();
+        sizes.set(vec![10, 20]).unwrap();
+        assert_eq!(cloned.get(), vec![10, 20]);
+    }
+}
@@ -16,12 +16,15 @@ use zksync_types::{
 };
 
 mod aggregated_committer;
+mod aggregated_proof_sizes;
+
+pub use self::aggregated_proof_sizes::{AggregatedProofSizes, InvalidAggregatedProofSizes};
 
 // In this component, the most interesting part of the database is decimals,
 // Usually we don't change them, so we can invalidate the cache once an hour.
 const TOKEN_INVALIDATE_CACHE: Duration = Duration::from_secs(60 * 60);
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum CommitRequest {
     PendingBlock((PendingBlock, AppliedUpdatesRequest)),
     RemoveRevertedBlock(BlockNumber),
@@ -191,6 +194,16 @@ async fn seal_incomplete_block(
         .expect("committer must commit the pending block into db");
 
     vlog::info!("seal incomplete block #{}", block.block_number);
+    for tx in block
+        .block_transactions
+        .iter()
+        .filter_map(ExecutedOperations::get_executed_tx)
+    {
+        vlog::tx_event(
+            "committer.seal_incomplete_block",
+            tx.signed_tx.hash().as_ref(),
+        );
+    }
 
     let block_number = block.block_number;
 
@@ -281,7 +294,11 @@ async fn finish_block(request: BlockFinishRequest, pool: &ConnectionPool) {
     metrics::histogram!("committer.finish_block", start.elapsed());
 }
 
-async fn poll_for_new_proofs_task(pool: ConnectionPool, config: ChainConfig) {
+async fn poll_for_new_proofs_task(
+    pool: ConnectionPool,
+    config: ChainConfig,
+    aggregated_proof_sizes: AggregatedProofSizes,
+) {
     let mut timer = time::interval(PROOF_POLL_INTERVAL);
     loop {
         timer.tick().await;
@@ -291,19 +308,39 @@ async fn poll_for_new_proofs_task(pool: ConnectionPool, config: ChainConfig) {
             .await
             .expect("db connection failed for committer");
 
-        aggregated_committer::create_aggregated_operations_storage(&mut storage, &config)
-            .await
-            .map_err(|e| vlog::error!("Failed to create aggregated operation: {}", e))
-            .unwrap_or_default();
+        aggregated_committer::create_aggregated_operations_storage(
+            &mut storage,
+            &config,
+            &aggregated_proof_sizes.get(),
+        )
+        .await
+        .map_err(|e| vlog::error!("Failed to create aggregated operation: {}", e))
+        .unwrap_or_default();
     }
 }
 
+/// Spawns the task storing the blocks proposed by the state keeper without creating
+/// the aggregated operations for them. The task stops once the requests sender is dropped.
+#[cfg(feature = "testkit")]
+#[must_use]
+pub fn run_block_committer(
+    rx_for_ops: Receiver<CommitRequest>,
+    pool: ConnectionPool,
+) -> JoinHandle<()> {
+    tokio::spawn(handle_new_commit_task(rx_for_ops, pool))
+}
+
 #[must_use]
 pub fn run_committer(
     rx_for_ops: Receiver<CommitRequest>,
     pool: ConnectionPool,
     config: ChainConfig,
+    aggregated_proof_sizes: AggregatedProofSizes,
 ) -> JoinHandle<()> {
     tokio::spawn(handle_new_commit_task(rx_for_ops, pool.clone()));
-    tokio::spawn(poll_for_new_proofs_task(pool, config))
+    tokio::spawn(poll_for_new_proofs_task(
+        pool,
+        config,
+        aggregated_proof_sizes,
+    ))
 }
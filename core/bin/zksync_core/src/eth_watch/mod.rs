@@ -1,12 +1,20 @@
 //! Ethereum watcher polls the Ethereum node for new events
 //! such as PriorityQueue events or NewToken events.
+//!
+//! The node is polled once a new block is received via the `newHeads` subscription if the WebSocket
+//! URL of the node is configured, otherwise (or if the subscription fails) the new blocks are polled.
 //! New events are accepted to the zkSync network once they have the sufficient amount of confirmations.
 //!
 //! Poll interval is configured using the `ETH_POLL_INTERVAL` constant.
-//! Number of confirmations is configured using the `CONFIRMATIONS_FOR_ETH_EVENT` environment variable.
+//! Number of confirmations is configured using the `CONFIRMATIONS_FOR_ETH_EVENT` environment variable,
+//! and may be overridden for the specific kinds of events (deposits, full exits and governance events).
+//!
+//! Hashes of the recent blocks containing the confirmed priority operations are tracked, and if any
+//! of them changes (meaning that the reorg was deeper than the confirmation margin), the affected
+//! priority operations are removed from the mempool and the blocks are scanned again.
 
 // Built-in deps
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::time::{Duration, Instant};
 
 // External uses
@@ -23,9 +31,14 @@ use web3::types::BlockNumber;
 
 use zksync_config::{ContractsConfig, ETHWatchConfig};
 use zksync_crypto::params::PRIORITY_EXPIRATION;
-use zksync_eth_client::ethereum_gateway::EthereumGateway;
+use zksync_eth_client::{
+    ethereum_gateway::EthereumGateway,
+    new_heads::{follow_new_heads, NewHead},
+};
 use zksync_mempool::MempoolTransactionRequest;
-use zksync_types::{NewTokenEvent, PriorityOp, RegisterNFTFactoryEvent, SerialId};
+use zksync_types::{
+    NewTokenEvent, PriorityOp, RegisterNFTFactoryEvent, SerialId, ZkSyncPriorityOp, H256,
+};
 
 // Local deps
 use self::{client::EthClient, eth_state::ETHState, received_ops::sift_outdated_ops};
@@ -41,6 +54,10 @@ mod tests;
 /// before repeating the request.
 const RATE_LIMIT_DELAY: Duration = Duration::from_secs(30);
 
+/// Depth (in blocks below the latest one) within which the blocks containing the confirmed
+/// priority operations are checked for reorgs.
+const REORG_TRACKING_DEPTH: u64 = 64;
+
 /// Ethereum Watcher operating mode.
 ///
 /// Normally Ethereum watcher will always poll the Ethereum node upon request,
@@ -58,7 +75,12 @@ pub enum WatcherMode {
 
 #[derive(Debug)]
 pub enum EthWatchRequest {
+    /// Requests the latest block number from the node and processes the new blocks.
     PollETHNode,
+    /// Processes the new blocks up to the given one, received via the `newHeads` subscription.
+    NewEthBlock {
+        number: u64,
+    },
     GetNewTokens {
         last_eth_block: Option<u64>,
         resp: oneshot::Sender<Vec<NewTokenEvent>>,
@@ -67,6 +89,9 @@ pub enum EthWatchRequest {
         last_eth_block: Option<u64>,
         resp: oneshot::Sender<Vec<RegisterNFTFactoryEvent>>,
     },
+    GetLastEthBlock {
+        resp: oneshot::Sender<u64>,
+    },
 }
 
 #[derive(Debug, Error)]
@@ -77,12 +102,55 @@ fn is_missing_priority_op_error(error: &anyhow::Error) -> bool {
     error.is::<MissingPriorityOpError>()
 }
 
+/// Amounts of confirmations required for the different kinds of Ethereum events.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventConfirmations {
+    pub deposit: u64,
+    pub full_exit: u64,
+    /// Confirmations for the new tokens and NFT factories registration events.
+    pub governance: u64,
+}
+
+impl EventConfirmations {
+    /// Requires the same amount of confirmations for all the events.
+    pub fn uniform(confirmations: u64) -> Self {
+        Self {
+            deposit: confirmations,
+            full_exit: confirmations,
+            governance: confirmations,
+        }
+    }
+
+    pub fn from_config(config: &ETHWatchConfig) -> Self {
+        Self {
+            deposit: config.deposit_confirmations(),
+            full_exit: config.full_exit_confirmations(),
+            governance: config.governance_event_confirmations(),
+        }
+    }
+
+    /// Amount of confirmations for the given priority operation.
+    pub fn for_priority_op(&self, op: &PriorityOp) -> u64 {
+        match op.data {
+            ZkSyncPriorityOp::Deposit(_) => self.deposit,
+            ZkSyncPriorityOp::FullExit(_) => self.full_exit,
+        }
+    }
+
+    /// Maximum amount of confirmations among all the priority operations.
+    pub fn max_for_priority_ops(&self) -> u64 {
+        self.deposit.max(self.full_exit)
+    }
+}
+
 pub struct EthWatch<W: EthClient> {
     client: W,
     mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
     eth_state: ETHState,
     /// All ethereum events are accepted after sufficient confirmations to eliminate risk of block reorg.
-    number_of_confirmations_for_event: u64,
+    confirmations: EventConfirmations,
+    /// Hashes of the recent blocks containing the confirmed priority operations.
+    tracked_blocks: BTreeMap<u64, H256>,
     mode: WatcherMode,
 }
 
@@ -90,14 +158,15 @@ impl<W: EthClient> EthWatch<W> {
     pub fn new(
         client: W,
         mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
-        number_of_confirmations_for_event: u64,
+        confirmations: EventConfirmations,
     ) -> Self {
         Self {
             client,
             mempool_tx_sender,
             eth_state: ETHState::default(),
             mode: WatcherMode::Working,
-            number_of_confirmations_for_event,
+            confirmations,
+            tracked_blocks: BTreeMap::new(),
         }
     }
 
@@ -106,34 +175,15 @@ impl<W: EthClient> EthWatch<W> {
         self.eth_state = new_state;
     }
 
-    async fn get_unconfirmed_ops(
-        &mut self,
-        current_ethereum_block: u64,
-    ) -> anyhow::Result<Vec<PriorityOp>> {
-        // We want to scan the interval of blocks from the latest one up to the oldest one which may
-        // have unconfirmed priority ops.
-        // `+ 1` is added because if we subtract number of confirmations, we'll obtain the last block
-        // which has operations that must be processed. So, for the unconfirmed operations, we must
-        // start from the block next to it.
-        let block_from_number =
-            current_ethereum_block.saturating_sub(self.number_of_confirmations_for_event) + 1;
-        let block_from = BlockNumber::Number(block_from_number.into());
-        let block_to = BlockNumber::Latest;
-
-        self.client
-            .get_priority_op_events(block_from, block_to)
-            .await
-    }
-
     async fn process_new_blocks(&mut self, last_ethereum_block: u64) -> anyhow::Result<()> {
         debug_assert!(self.eth_state.last_ethereum_block() < last_ethereum_block);
         debug_assert!(self.eth_state.last_ethereum_block() < last_ethereum_block);
 
         // We have to process every block between the current and previous known values.
         // This is crucial since `eth_watch` may enter the backoff mode in which it will skip many blocks.
-        // Note that we don't have to add `number_of_confirmations_for_event` here, because the check function takes
+        // Note that we don't have to add the number of confirmations here, because the check function takes
         // care of it on its own. Here we calculate "how many blocks should we watch", and the offsets with respect
-        // to the `number_of_confirmations_for_event` are calculated by `update_eth_state`.
+        // to the number of confirmations are calculated by `update_eth_state`.
         let mut next_priority_op_id = self.eth_state.next_priority_op_id();
         let previous_ethereum_block = self.eth_state.last_ethereum_block();
         let block_difference = last_ethereum_block.saturating_sub(previous_ethereum_block);
@@ -219,25 +269,34 @@ impl<W: EthClient> EthWatch<W> {
         current_ethereum_block: u64,
         unprocessed_blocks_amount: u64,
     ) -> anyhow::Result<ETHState> {
-        let new_block_with_accepted_events =
-            current_ethereum_block.saturating_sub(self.number_of_confirmations_for_event);
-        let previous_block_with_accepted_events =
-            new_block_with_accepted_events.saturating_sub(unprocessed_blocks_amount);
-
-        let unconfirmed_queue = self.get_unconfirmed_ops(current_ethereum_block).await?;
-        let priority_queue = self
+        // Priority operations of different kinds require different amounts of confirmations,
+        // so we scan the blocks starting from the oldest one which may have the operations
+        // not processed yet up to the latest one, and split the operations afterwards.
+        let previous_block_with_priority_ops = current_ethereum_block
+            .saturating_sub(self.confirmations.max_for_priority_ops())
+            .saturating_sub(unprocessed_blocks_amount);
+        let priority_ops = self
             .client
             .get_priority_op_events(
-                BlockNumber::Number(previous_block_with_accepted_events.into()),
-                BlockNumber::Number(new_block_with_accepted_events.into()),
+                BlockNumber::Number(previous_block_with_priority_ops.into()),
+                BlockNumber::Latest,
             )
             .await?;
+        let (priority_queue, unconfirmed_queue) =
+            self.split_confirmed_ops(priority_ops, current_ethereum_block);
+        self.track_blocks(&priority_queue, current_ethereum_block)
+            .await?;
         let priority_queue_map: HashMap<u64, _> = priority_queue
             .iter()
             .cloned()
             .map(|priority_op| (priority_op.serial_id, priority_op.into()))
             .collect();
 
+        let new_block_with_accepted_events =
+            current_ethereum_block.saturating_sub(self.confirmations.governance);
+        let previous_block_with_accepted_events =
+            new_block_with_accepted_events.saturating_sub(unprocessed_blocks_amount);
+
         let new_tokens = self
             .client
             .get_new_tokens_events(
@@ -258,8 +317,8 @@ impl<W: EthClient> EthWatch<W> {
         new_priority_op_ids.sort_unstable();
         vlog::debug!(
             "Updating eth state: block_range=[{},{}], new_priority_ops={:?}",
-            previous_block_with_accepted_events,
-            new_block_with_accepted_events,
+            previous_block_with_priority_ops,
+            current_ethereum_block,
             new_priority_op_ids
         );
 
@@ -299,6 +358,130 @@ impl<W: EthClient> EthWatch<W> {
         Ok(state)
     }
 
+    /// Splits the priority operations into the confirmed and unconfirmed ones.
+    ///
+    /// Since the priority operations are executed in order, the operation is only considered
+    /// confirmed if all the preceding operations are confirmed as well, e.g. a deposit following
+    /// the full exit waits for the full exit confirmations.
+    fn split_confirmed_ops(
+        &self,
+        mut priority_ops: Vec<PriorityOp>,
+        current_ethereum_block: u64,
+    ) -> (Vec<PriorityOp>, Vec<PriorityOp>) {
+        priority_ops.sort_by_key(|op| op.serial_id);
+        let confirmed_count = priority_ops
+            .iter()
+            .take_while(|op| {
+                op.eth_block + self.confirmations.for_priority_op(op) <= current_ethereum_block
+            })
+            .count();
+        let unconfirmed_queue = priority_ops.split_off(confirmed_count);
+        (priority_ops, unconfirmed_queue)
+    }
+
+    /// Remembers the hashes of the recent blocks containing the confirmed priority operations.
+    async fn track_blocks(
+        &mut self,
+        confirmed_ops: &[PriorityOp],
+        current_ethereum_block: u64,
+    ) -> anyhow::Result<()> {
+        let oldest_tracked_block = current_ethereum_block.saturating_sub(REORG_TRACKING_DEPTH);
+        self.tracked_blocks = self.tracked_blocks.split_off(&oldest_tracked_block);
+
+        for op in confirmed_ops {
+            if op.eth_block < oldest_tracked_block
+                || self.tracked_blocks.contains_key(&op.eth_block)
+            {
+                continue;
+            }
+            if let Some(hash) = self.client.block_hash(op.eth_block).await? {
+                self.tracked_blocks.insert(op.eth_block, hash);
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks whether the tracked blocks were reverted by the reorg and rolls back
+    /// the affected priority operations.
+    ///
+    /// Since the reorg changes all the blocks following the fork point, it's enough to check
+    /// the latest tracked block to detect it.
+    async fn check_reorg(&mut self) -> anyhow::Result<()> {
+        let (&latest_block, &latest_hash) = match self.tracked_blocks.iter().next_back() {
+            Some(tracked_block) => tracked_block,
+            None => return Ok(()),
+        };
+        if self.client.block_hash(latest_block).await? == Some(latest_hash) {
+            return Ok(());
+        }
+
+        // Find the oldest of the reverted blocks.
+        let mut reverted_block = latest_block;
+        let tracked_blocks: Vec<_> = self.tracked_blocks.clone().into_iter().rev().collect();
+        for (number, hash) in tracked_blocks {
+            if self.client.block_hash(number).await? == Some(hash) {
+                break;
+            }
+            reverted_block = number;
+        }
+        self.rollback(reverted_block).await
+    }
+
+    /// Removes the priority operations received starting from the given block from the
+    /// mempool and the state, so the blocks are scanned again on the next update.
+    async fn rollback(&mut self, reverted_block: u64) -> anyhow::Result<()> {
+        let (priority_queue, reverted_ops): (HashMap<_, _>, HashMap<_, _>) = self
+            .eth_state
+            .priority_queue()
+            .clone()
+            .into_iter()
+            .partition(|(_, op)| op.as_ref().eth_block < reverted_block);
+        let mut reverted_ids: Vec<SerialId> = reverted_ops
+            .keys()
+            .copied()
+            .chain(
+                self.eth_state
+                    .unconfirmed_queue()
+                    .iter()
+                    .map(|op| op.serial_id),
+            )
+            .collect();
+        reverted_ids.sort_unstable();
+
+        vlog::error!(
+            "Ethereum reorg deeper than the confirmation margin is detected starting from the block {}, \
+            rolling back priority operations {:?}",
+            reverted_block,
+            reverted_ids
+        );
+        metrics::increment_counter!("eth_watcher.deep_reorg");
+
+        let (sender, receiver) = oneshot::channel();
+        self.mempool_tx_sender
+            .send(MempoolTransactionRequest::RemovePriorityOps(
+                reverted_ids,
+                sender,
+            ))
+            .await?;
+        receiver.await.expect("Mempool actor was dropped")?;
+
+        self.tracked_blocks
+            .retain(|number, _| *number < reverted_block);
+        let last_ethereum_block = reverted_block
+            .saturating_sub(1)
+            .min(self.eth_state.last_ethereum_block());
+        let new_state = ETHState::new(
+            last_ethereum_block,
+            last_ethereum_block,
+            Vec::new(),
+            priority_queue,
+            self.eth_state.new_tokens().to_vec(),
+            self.eth_state.new_register_nft_factory_events().to_vec(),
+        );
+        self.set_new_state(new_state);
+        Ok(())
+    }
+
     fn get_register_factory_event(
         &self,
         last_block_number: Option<u64>,
@@ -332,15 +515,39 @@ impl<W: EthClient> EthWatch<W> {
     async fn poll_eth_node(&mut self) -> anyhow::Result<()> {
         let start = Instant::now();
         let last_block_number = self.client.block_number().await?;
+        self.process_latest_block(last_block_number).await?;
+
+        metrics::histogram!("eth_watcher.poll_eth_node", start.elapsed());
+        Ok(())
+    }
 
+    /// Processes the blocks up to the given latest one, if there are any new blocks.
+    async fn process_latest_block(&mut self, last_block_number: u64) -> anyhow::Result<()> {
         if last_block_number > self.eth_state.last_ethereum_block() {
+            self.check_reorg().await?;
             self.process_new_blocks(last_block_number).await?;
         }
-
-        metrics::histogram!("eth_watcher.poll_eth_node", start.elapsed());
         Ok(())
     }
 
+    fn handle_poll_error(&mut self, error: anyhow::Error) {
+        if self.is_backoff_requested(&error) {
+            vlog::warn!(
+                "Rate limit was reached, as reported by Ethereum node. \
+                Entering the backoff mode"
+            );
+            self.enter_backoff_mode();
+        } else if is_missing_priority_op_error(&error) {
+            vlog::warn!("{}\nEntering the backoff mode", error);
+            // Wait for some time and try to fetch new logs again.
+            self.enter_backoff_mode();
+        } else {
+            // Some unexpected kind of error, we won't shutdown the node because of it,
+            // but rather expect node administrators to handle the situation.
+            vlog::error!("Failed to process new blocks {}", error);
+        }
+    }
+
     // TODO try to move it to eth client
     fn is_backoff_requested(&self, error: &anyhow::Error) -> bool {
         error.to_string().contains("429 Too Many Requests")
@@ -411,25 +618,20 @@ impl<W: EthClient> EthWatch<W> {
                         continue;
                     }
 
-                    let poll_result = self.poll_eth_node().await;
-
-                    if let Err(error) = poll_result {
-                        if self.is_backoff_requested(&error) {
-                            vlog::warn!(
-                                "Rate limit was reached, as reported by Ethereum node. \
-                                Entering the backoff mode"
-                            );
-                            self.enter_backoff_mode();
-                        } else if is_missing_priority_op_error(&error) {
-                            vlog::warn!("{}\nEntering the backoff mode", error);
-                            // Wait for some time and try to fetch new logs again.
-                            self.enter_backoff_mode();
-                        } else {
-                            // Some unexpected kind of error, we won't shutdown the node because of it,
-                            // but rather expect node administrators to handle the situation.
-                            vlog::error!("Failed to process new blocks {}", error);
-                        }
+                    if let Err(error) = self.poll_eth_node().await {
+                        self.handle_poll_error(error);
+                    }
+                }
+                EthWatchRequest::NewEthBlock { number } => {
+                    if !self.polling_allowed() {
+                        continue;
+                    }
+
+                    let start = Instant::now();
+                    if let Err(error) = self.process_latest_block(number).await {
+                        self.handle_poll_error(error);
                     }
+                    metrics::histogram!("eth_watcher.poll_eth_node", start.elapsed());
                 }
                 EthWatchRequest::GetNewTokens {
                     last_eth_block,
@@ -444,6 +646,9 @@ impl<W: EthClient> EthWatch<W> {
                     resp.send(self.get_register_factory_event(last_eth_block))
                         .ok();
                 }
+                EthWatchRequest::GetLastEthBlock { resp } => {
+                    resp.send(self.eth_state.last_ethereum_block()).ok();
+                }
             }
         }
     }
@@ -455,6 +660,7 @@ pub async fn start_eth_watch(
     eth_gateway: EthereumGateway,
     contract_config: &ContractsConfig,
     eth_watcher_config: &ETHWatchConfig,
+    web3_ws_url: Option<String>,
     mempool_req_sender: mpsc::Sender<MempoolTransactionRequest>,
 ) -> JoinHandle<()> {
     let eth_client = EthHttpClient::new(
@@ -466,24 +672,31 @@ pub async fn start_eth_watch(
     let mut eth_watch = EthWatch::new(
         eth_client,
         mempool_req_sender,
-        eth_watcher_config.confirmations_for_eth_event,
+        EventConfirmations::from_config(eth_watcher_config),
     );
 
     eth_watch.restore_from_eth_using_latest_block_number().await;
 
     tokio::spawn(eth_watch.run(eth_req_receiver));
 
-    let poll_interval = eth_watcher_config.poll_interval();
+    // Blocks received via the subscription are processed right away, the node is only polled
+    // for the latest block while the subscription is not available.
+    let (mut new_heads, follower_task) =
+        follow_new_heads(web3_ws_url, eth_watcher_config.poll_interval());
     tokio::spawn(async move {
-        let mut timer = time::interval(poll_interval);
-
-        loop {
-            timer.tick().await;
+        while let Some(new_head) = new_heads.recv().await {
+            let request = match new_head {
+                NewHead::Block(number) => EthWatchRequest::NewEthBlock { number },
+                NewHead::PollTick => EthWatchRequest::PollETHNode,
+            };
             eth_req_sender
                 .clone()
-                .send(EthWatchRequest::PollETHNode)
+                .send(request)
                 .await
                 .expect("ETH watch receiver dropped");
         }
+        // The follower only stops if it panics, the watcher is useless without the new blocks.
+        let result = follower_task.await;
+        panic!("New Ethereum blocks follower stopped: {:?}", result);
     })
 }
@@ -1,5 +1,5 @@
 use std::cmp::max;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 use web3::types::{Address, BlockNumber};
@@ -9,17 +9,21 @@ use zksync_types::{
     SerialId, TokenId, ZkSyncPriorityOp, H256,
 };
 
-use futures::channel::mpsc;
-use futures::StreamExt;
+use futures::channel::{mpsc, oneshot};
+use futures::{SinkExt, StreamExt};
 use tokio::sync::RwLock;
 use zksync_mempool::MempoolTransactionRequest;
 
 use super::is_missing_priority_op_error;
-use crate::eth_watch::{client::EthClient, EthWatch};
+use crate::eth_watch::{client::EthClient, EthWatch, EthWatchRequest, EventConfirmations};
 
 struct FakeEthClientData {
     priority_ops: HashMap<u64, Vec<PriorityOp>>,
     last_block_number: u64,
+    /// Blocks replaced by the reorg, their hashes differ from the original ones.
+    reorged_blocks: HashSet<u64>,
+    /// How many times the latest block number was requested.
+    block_number_requests: usize,
 }
 
 impl FakeEthClientData {
@@ -27,6 +31,8 @@ impl FakeEthClientData {
         Self {
             priority_ops: Default::default(),
             last_block_number: 0,
+            reorged_blocks: Default::default(),
+            block_number_requests: 0,
         }
     }
 
@@ -70,6 +76,16 @@ impl FakeEthClient {
         let mut inner = self.inner.write().await;
         inner.last_block_number = block_number;
     }
+
+    /// Replaces the blocks starting from the given one, including the operations within them.
+    async fn reorg(&mut self, from_block: u64, ops: &[PriorityOp]) {
+        let mut inner = self.inner.write().await;
+        inner.priority_ops.retain(|block, _| *block < from_block);
+        for number in from_block..=inner.last_block_number {
+            inner.reorged_blocks.insert(number);
+        }
+        inner.add_operations(ops);
+    }
 }
 
 #[async_trait::async_trait]
@@ -108,7 +124,22 @@ impl EthClient for FakeEthClient {
     }
 
     async fn block_number(&self) -> Result<u64, anyhow::Error> {
-        Ok(self.inner.read().await.last_block_number)
+        let mut inner = self.inner.write().await;
+        inner.block_number_requests += 1;
+        Ok(inner.last_block_number)
+    }
+
+    async fn block_hash(&self, number: u64) -> Result<Option<H256>, anyhow::Error> {
+        let inner = self.inner.read().await;
+        if number > inner.last_block_number {
+            return Ok(None);
+        }
+        let salt = if inner.reorged_blocks.contains(&number) {
+            u32::MAX as u64
+        } else {
+            0
+        };
+        Ok(Some(H256::from_low_u64_be(number + salt)))
     }
 
     async fn get_auth_fact(
@@ -132,7 +163,7 @@ fn create_watcher<T: EthClient>(
     client: T,
     mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
 ) -> EthWatch<T> {
-    EthWatch::new(client, mempool_tx_sender, 1)
+    EthWatch::new(client, mempool_tx_sender, EventConfirmations::uniform(1))
 }
 
 async fn fake_mempool(
@@ -151,7 +182,15 @@ async fn fake_mempool(
                 }
                 channel.send(Ok(())).unwrap_or_default()
             }
+            MempoolTransactionRequest::RemovePriorityOps(serial_ids, channel) => {
+                let mut lock = data.write().await;
+                for serial_id in serial_ids {
+                    lock.remove(&serial_id);
+                }
+                channel.send(Ok(())).unwrap_or_default()
+            }
             MempoolTransactionRequest::NewTxsBatch(_, _, _) => unreachable!(),
+            MempoolTransactionRequest::CancelTx(_, _, _, _) => unreachable!(),
         }
     }
 }
@@ -239,7 +278,7 @@ async fn test_operation_queues() {
 }
 
 /// This test simulates the situation when eth watch module did not poll Ethereum node for some time
-/// (e.g. because of rate limit) and skipped more blocks than `number_of_confirmations_for_event`.
+/// (e.g. because of rate limit) and skipped more blocks than the number of confirmations.
 #[tokio::test]
 async fn test_operation_queues_time_lag() {
     let mut client = FakeEthClient::new();
@@ -546,3 +585,210 @@ async fn test_serial_id_gaps() {
     assert_eq!(watcher.eth_state.last_ethereum_block_backup(), 0);
     assert_eq!(watcher.eth_state.last_ethereum_block(), 3);
 }
+
+/// Checks that the priority operations of different kinds are confirmed according to their
+/// own confirmations requirements, and the operations are only confirmed in order.
+#[tokio::test]
+async fn test_confirmations_per_op_type() {
+    let mut client = FakeEthClient::new();
+    let (sender, receiver) = mpsc::channel(10);
+    let data = Arc::new(RwLock::new(HashMap::new()));
+    tokio::spawn(fake_mempool(receiver, data.clone()));
+
+    let deposit = ZkSyncPriorityOp::Deposit(Deposit {
+        from: Default::default(),
+        token: TokenId(0),
+        amount: Default::default(),
+        to: Default::default(),
+    });
+    let full_exit = ZkSyncPriorityOp::FullExit(FullExit {
+        account_id: AccountId(0),
+        eth_address: Default::default(),
+        token: TokenId(0),
+        is_legacy: false,
+    });
+    client
+        .add_operations(&[
+            PriorityOp {
+                serial_id: 0,
+                data: deposit.clone(),
+                deadline_block: 0,
+                eth_hash: [2; 32].into(),
+                eth_block: 2,
+                eth_block_index: Some(1),
+            },
+            PriorityOp {
+                serial_id: 1,
+                data: full_exit,
+                deadline_block: 0,
+                eth_hash: [3; 32].into(),
+                eth_block: 3,
+                eth_block_index: Some(1),
+            },
+            PriorityOp {
+                serial_id: 2,
+                data: deposit,
+                deadline_block: 0,
+                eth_hash: [4; 32].into(),
+                eth_block: 4,
+                eth_block_index: Some(1),
+            },
+        ])
+        .await;
+    client.set_last_block_number(5).await;
+
+    let confirmations = EventConfirmations {
+        deposit: 1,
+        full_exit: 3,
+        governance: 1,
+    };
+    let mut watcher = EthWatch::new(client.clone(), sender, confirmations);
+    watcher.poll_eth_node().await.unwrap();
+
+    // The last deposit has enough confirmations, but it has to wait for the full exit.
+    let priority_queue = watcher.eth_state.priority_queue();
+    assert_eq!(priority_queue.len(), 1);
+    priority_queue.get(&0).unwrap();
+    let unconfirmed_ids: Vec<_> = watcher
+        .eth_state
+        .unconfirmed_queue()
+        .iter()
+        .map(|op| op.serial_id)
+        .collect();
+    assert_eq!(unconfirmed_ids, vec![1, 2]);
+
+    client.set_last_block_number(6).await;
+    watcher.poll_eth_node().await.unwrap();
+    assert_eq!(watcher.eth_state.priority_queue().len(), 3);
+    assert!(watcher.eth_state.unconfirmed_queue().is_empty());
+    let reader = data.read().await;
+    assert!(reader.values().all(|(_, confirmed)| *confirmed));
+}
+
+/// Checks that the priority operations reverted by the reorg deeper than the confirmation
+/// margin are removed, and the replacing operations are received instead.
+#[tokio::test]
+async fn test_deep_reorg_rollback() {
+    let mut client = FakeEthClient::new();
+    let (sender, receiver) = mpsc::channel(10);
+    let data = Arc::new(RwLock::new(HashMap::new()));
+    tokio::spawn(fake_mempool(receiver, data.clone()));
+
+    let deposit = ZkSyncPriorityOp::Deposit(Deposit {
+        from: Default::default(),
+        token: TokenId(0),
+        amount: Default::default(),
+        to: Default::default(),
+    });
+    client
+        .add_operations(&[
+            PriorityOp {
+                serial_id: 0,
+                data: deposit.clone(),
+                deadline_block: 0,
+                eth_hash: [2; 32].into(),
+                eth_block: 2,
+                eth_block_index: Some(1),
+            },
+            PriorityOp {
+                serial_id: 1,
+                data: deposit.clone(),
+                deadline_block: 0,
+                eth_hash: [3; 32].into(),
+                eth_block: 3,
+                eth_block_index: Some(1),
+            },
+        ])
+        .await;
+    client.set_last_block_number(5).await;
+
+    let mut watcher = create_watcher(client.clone(), sender);
+    watcher.poll_eth_node().await.unwrap();
+    assert_eq!(watcher.eth_state.priority_queue().len(), 2);
+    let tracked_blocks: Vec<_> = watcher.tracked_blocks.keys().copied().collect();
+    assert_eq!(tracked_blocks, vec![2, 3]);
+
+    // The confirmed operation is replaced by the reorg.
+    let replacing_op = PriorityOp {
+        serial_id: 1,
+        data: deposit,
+        deadline_block: 0,
+        eth_hash: [4; 32].into(),
+        eth_block: 4,
+        eth_block_index: Some(1),
+    };
+    client.reorg(3, &[replacing_op.clone()]).await;
+    client.set_last_block_number(6).await;
+    watcher.poll_eth_node().await.unwrap();
+
+    let priority_queue = watcher.eth_state.priority_queue();
+    assert_eq!(priority_queue.len(), 2);
+    assert_eq!(
+        priority_queue.get(&1).unwrap().as_ref().eth_hash,
+        replacing_op.eth_hash
+    );
+    let tracked_blocks: Vec<_> = watcher.tracked_blocks.keys().copied().collect();
+    assert_eq!(tracked_blocks, vec![2, 4]);
+
+    let reader = data.read().await;
+    let (op, confirmed) = reader.get(&1).unwrap();
+    assert_eq!(op.eth_hash, replacing_op.eth_hash);
+    assert!(confirmed);
+}
+
+/// Checks that the new blocks received from the blocks follower are processed without requesting
+/// the latest block number again, while the polling requests still request it.
+#[tokio::test]
+async fn test_new_eth_block_requests() {
+    let mut client = FakeEthClient::new();
+    let (sender, receiver) = mpsc::channel(10);
+    let data = Arc::new(RwLock::new(HashMap::new()));
+    tokio::spawn(fake_mempool(receiver, data.clone()));
+
+    let deposit = |serial_id, eth_block| PriorityOp {
+        serial_id,
+        data: ZkSyncPriorityOp::Deposit(Deposit {
+            from: Default::default(),
+            token: TokenId(0),
+            amount: Default::default(),
+            to: Default::default(),
+        }),
+        deadline_block: 0,
+        eth_hash: H256::from_low_u64_be(serial_id),
+        eth_block,
+        eth_block_index: Some(1),
+    };
+    client.add_operations(&[deposit(0, 2)]).await;
+
+    let (mut requests, requests_receiver) = mpsc::channel(10);
+    tokio::spawn(create_watcher(client.clone(), sender).run(requests_receiver));
+    let last_eth_block = |mut requests: mpsc::Sender<EthWatchRequest>| async move {
+        let (resp, receiver) = oneshot::channel();
+        requests
+            .send(EthWatchRequest::GetLastEthBlock { resp })
+            .await
+            .unwrap();
+        receiver.await.unwrap()
+    };
+
+    requests
+        .send(EthWatchRequest::NewEthBlock { number: 2 })
+        .await
+        .unwrap();
+    assert_eq!(last_eth_block(requests.clone()).await, 2);
+    assert!(data.read().await.contains_key(&0));
+    assert_eq!(client.inner.read().await.block_number_requests, 0);
+
+    // Stale block numbers are ignored.
+    requests
+        .send(EthWatchRequest::NewEthBlock { number: 1 })
+        .await
+        .unwrap();
+    assert_eq!(last_eth_block(requests.clone()).await, 2);
+
+    client.add_operations(&[deposit(1, 3)]).await;
+    requests.send(EthWatchRequest::PollETHNode).await.unwrap();
+    assert_eq!(last_eth_block(requests.clone()).await, 3);
+    assert!(data.read().await.contains_key(&1));
+    assert_eq!(client.inner.read().await.block_number_requests, 1);
+}
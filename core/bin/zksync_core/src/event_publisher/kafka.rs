@@ -0,0 +1,62 @@
+// Built-in uses
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+// External uses
+use async_trait::async_trait;
+use kafka::producer::{Producer, Record, RequiredAcks};
+// Local uses
+use super::{EventMessage, MessageBroker};
+
+const ACK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Kafka producer, the messages are acknowledged by all of the in-sync replicas.
+pub struct KafkaBroker {
+    producer: Arc<Mutex<Producer>>,
+}
+
+impl KafkaBroker {
+    pub fn new(hosts: Vec<String>) -> anyhow::Result<Self> {
+        let producer = Producer::from_hosts(hosts)
+            .with_ack_timeout(ACK_TIMEOUT)
+            .with_required_acks(RequiredAcks::All)
+            .create()?;
+        Ok(Self {
+            producer: Arc::new(Mutex::new(producer)),
+        })
+    }
+}
+
+#[async_trait]
+impl MessageBroker for KafkaBroker {
+    async fn publish(&self, messages: Vec<EventMessage>) -> anyhow::Result<()> {
+        let producer = self.producer.clone();
+        // The producer is blocking, so it's not run on the async workers.
+        tokio::task::spawn_blocking(move || {
+            let records: Vec<_> = messages
+                .iter()
+                .map(|message| {
+                    Record::from_key_value(
+                        &message.topic,
+                        message.key.as_bytes(),
+                        &message.payload[..],
+                    )
+                })
+                .collect();
+            for result in producer.lock().unwrap().send_all(&records)? {
+                for partition in result.partition_confirms {
+                    if let Err(code) = partition.offset {
+                        anyhow::bail!(
+                            "Kafka didn't accept the messages of `{}`: {:?}",
+                            result.topic,
+                            code
+                        );
+                    }
+                }
+            }
+            Ok(())
+        })
+        .await?
+    }
+}
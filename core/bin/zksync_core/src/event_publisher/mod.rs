@@ -0,0 +1,286 @@
+//! The event publisher streams the block and transaction events to the message broker
+//! (Kafka or NATS), so the downstream pipelines can consume the rollup without polling the API.
+//!
+//! Events are read from the `events` table in the order of their ids and published to the
+//! `<prefix>.blocks` and `<prefix>.transactions` topics, see `docs/event_publisher.md` for
+//! the schema of the messages. The id of the last published event is stored in the database
+//! once the broker acknowledges the batch, so the events are delivered at least once: the batch
+//! interrupted by a failure is published again, and consumers are expected to deduplicate the
+//! messages by the `event_id`. For NATS, the messages are published to JetStream, which acknowledges
+//! them once they are stored by the stream. Events that can't be decoded or exceed the message size
+//! limit are logged and skipped. On the first start, only the events emitted after it are published.
+
+// Built-in uses
+use std::convert::TryFrom;
+// External uses
+use async_trait::async_trait;
+use serde::Serialize;
+use tokio::{task::JoinHandle, time};
+// Workspace deps
+use zksync_config::{configs::event_publisher::EventPublisherBackend, EventPublisherConfig};
+use zksync_storage::{event::records::StoredEvent, ConnectionPool, StorageProcessor};
+use zksync_types::event::{transaction::TransactionStatus, EventData, EventId, ZkSyncEvent};
+// Local uses
+use self::{kafka::KafkaBroker, nats::NatsBroker};
+
+mod kafka;
+mod nats;
+
+/// Message to be published to the broker.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventMessage {
+    /// Id of the published event, used by NATS to deduplicate the messages.
+    pub event_id: u64,
+    pub topic: String,
+    /// Key of the message, the messages with the same key are kept in order by Kafka.
+    pub key: String,
+    pub payload: Vec<u8>,
+}
+
+#[async_trait]
+pub trait MessageBroker: Send + Sync {
+    /// Publishes the messages in order, returns once all of them are acknowledged by the broker.
+    async fn publish(&self, messages: Vec<EventMessage>) -> anyhow::Result<()>;
+}
+
+/// Published representation of the event: the event itself along with its id.
+#[derive(Debug, Serialize)]
+struct PublishedEvent<'a> {
+    event_id: u64,
+    #[serde(flatten)]
+    event: &'a ZkSyncEvent,
+}
+
+/// Returns the message of the event, or `None` if the event isn't published:
+/// account events and the transactions that are not executed yet are skipped.
+pub fn event_message(
+    topic_prefix: &str,
+    event: &ZkSyncEvent,
+) -> anyhow::Result<Option<EventMessage>> {
+    let (topic, key) = match &event.data {
+        EventData::Block(block_event) => {
+            ("blocks", block_event.block_details.block_number.to_string())
+        }
+        EventData::Transaction(tx_event) if tx_event.status != TransactionStatus::Queued => {
+            ("transactions", tx_event.tx_hash.clone())
+        }
+        _ => return Ok(None),
+    };
+    let payload = serde_json::to_vec(&PublishedEvent {
+        event_id: *event.id,
+        event,
+    })?;
+    Ok(Some(EventMessage {
+        event_id: *event.id,
+        topic: format!("{}.{}", topic_prefix, topic),
+        key,
+        payload,
+    }))
+}
+
+/// Returns the messages of the events to be published.
+///
+/// Events that can't be decoded or published are skipped, so they don't stall the delivery
+/// of the following events: otherwise the batch would fail over and over again.
+fn batch_messages(events: Vec<StoredEvent>, config: &EventPublisherConfig) -> Vec<EventMessage> {
+    let mut messages = Vec::new();
+    for event in events {
+        let event_id = event.id;
+        let message = ZkSyncEvent::try_from(event)
+            .map_err(anyhow::Error::from)
+            .and_then(|event| event_message(&config.topic_prefix, &event));
+        let error = match message {
+            Ok(Some(message)) if message.payload.len() > config.max_message_size => format!(
+                "message of {} bytes exceeds the limit of {} bytes",
+                message.payload.len(),
+                config.max_message_size
+            ),
+            Ok(message) => {
+                messages.extend(message);
+                continue;
+            }
+            Err(err) => err.to_string(),
+        };
+        vlog::error!("Event {} is not published: {}", event_id, error);
+        metrics::increment_counter!("event_publisher.skipped_events");
+    }
+    messages
+}
+
+/// Name of the publisher the offset is stored for.
+fn publisher_name(config: &EventPublisherConfig) -> String {
+    let backend = match config.backend {
+        EventPublisherBackend::Kafka => "kafka",
+        EventPublisherBackend::Nats => "nats",
+    };
+    format!("{}:{}", backend, config.topic_prefix)
+}
+
+/// Publishes the events emitted since the last published one, batch by batch.
+async fn publish_new_events(
+    storage: &mut StorageProcessor<'_>,
+    broker: &dyn MessageBroker,
+    config: &EventPublisherConfig,
+) -> anyhow::Result<()> {
+    let publisher = publisher_name(config);
+    let mut offset = match storage
+        .event_schema()
+        .get_publisher_offset(&publisher)
+        .await?
+    {
+        Some(offset) => offset,
+        None => {
+            let offset = storage
+                .event_schema()
+                .get_last_event_id()
+                .await?
+                .unwrap_or(EventId(0));
+            storage
+                .event_schema()
+                .store_publisher_offset(&publisher, offset)
+                .await?;
+            offset
+        }
+    };
+
+    loop {
+        let events = storage
+            .event_schema()
+            .fetch_events_batch(offset, config.batch_size)
+            .await?;
+        let last_event_id = match events.last() {
+            Some(event) => EventId(event.id as u64),
+            None => return Ok(()),
+        };
+        let messages = batch_messages(events, config);
+
+        let published = messages.len();
+        broker.publish(messages).await?;
+        storage
+            .event_schema()
+            .store_publisher_offset(&publisher, last_event_id)
+            .await?;
+        metrics::counter!("event_publisher.published_events", published as u64);
+        offset = last_event_id;
+    }
+}
+
+#[must_use]
+pub fn run_event_publisher(
+    config: EventPublisherConfig,
+    db_pool: ConnectionPool,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let broker: Box<dyn MessageBroker> = match config.backend {
+            EventPublisherBackend::Kafka => Box::new(
+                KafkaBroker::new(config.servers.clone()).expect("failed to connect to Kafka"),
+            ),
+            EventPublisherBackend::Nats => Box::new(
+                NatsBroker::connect(&config.servers)
+                    .await
+                    .expect("failed to connect to NATS"),
+            ),
+        };
+        let mut timer = time::interval(config.poll_interval());
+
+        loop {
+            timer.tick().await;
+            let mut storage = db_pool
+                .access_storage()
+                .await
+                .expect("event publisher couldn't access the database");
+            if let Err(e) = publish_new_events(&mut storage, broker.as_ref(), &config).await {
+                vlog::error!("Can't publish the new events {:?}", e);
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+    use zksync_storage::event::{get_event_type, EventType};
+    use zksync_types::{
+        event::{
+            account::AccountStateChangeStatus,
+            block::BlockStatus,
+            test_data::{get_account_event, get_block_event, get_transaction_event},
+            transaction::TransactionType,
+        },
+        AccountId, TokenId,
+    };
+
+    #[test]
+    fn event_messages() {
+        let mut event = get_block_event(BlockStatus::Finalized);
+        event.id = EventId(7);
+        let message = event_message("zksync", &event).unwrap().unwrap();
+        assert_eq!(message.topic, "zksync.blocks");
+        assert_eq!(message.key, "0");
+        let payload: Value = serde_json::from_slice(&message.payload).unwrap();
+        assert_eq!(payload["event_id"], 7);
+        assert_eq!(payload["type"], "block");
+        assert_eq!(payload["data"]["status"], "finalized");
+
+        let event = get_transaction_event(
+            TransactionType::Transfer,
+            AccountId(1),
+            TokenId(0),
+            TransactionStatus::Committed,
+        );
+        let message = event_message("zksync", &event).unwrap().unwrap();
+        assert_eq!(message.topic, "zksync.transactions");
+        let payload: Value = serde_json::from_slice(&message.payload).unwrap();
+        assert_eq!(payload["data"]["tx_hash"], message.key);
+
+        // Queued transactions and account events are not published.
+        let event = get_transaction_event(
+            TransactionType::Transfer,
+            AccountId(1),
+            TokenId(0),
+            TransactionStatus::Queued,
+        );
+        assert_eq!(event_message("zksync", &event).unwrap(), None);
+        let event = get_account_event(AccountId(1), None, AccountStateChangeStatus::Committed);
+        assert_eq!(event_message("zksync", &event).unwrap(), None);
+    }
+
+    fn stored_event(id: i64, event: &ZkSyncEvent) -> StoredEvent {
+        let event_data = match &event.data {
+            EventData::Account(data) => serde_json::to_value(data),
+            EventData::Block(data) => serde_json::to_value(data),
+            EventData::Transaction(data) => serde_json::to_value(data),
+        };
+        StoredEvent {
+            id,
+            block_number: *event.block_number as i64,
+            event_type: get_event_type(event),
+            event_data: event_data.unwrap(),
+        }
+    }
+
+    #[test]
+    fn poison_events_are_skipped() {
+        let mut config = EventPublisherConfig {
+            backend: EventPublisherBackend::Nats,
+            servers: Vec::new(),
+            topic_prefix: "zksync".into(),
+            batch_size: 10,
+            poll_interval: 1000,
+            max_message_size: 1 << 20,
+        };
+        let event = get_block_event(BlockStatus::Committed);
+        let mut events: Vec<_> = (1..=3).map(|id| stored_event(id, &event)).collect();
+        // Data of the event doesn't match its type.
+        events[1].event_type = EventType::Transaction;
+
+        let messages = batch_messages(events.clone(), &config);
+        let event_ids: Vec<_> = messages.iter().map(|message| message.event_id).collect();
+        assert_eq!(event_ids, vec![1, 3]);
+
+        // Messages exceeding the size limit are skipped as well.
+        config.max_message_size = messages[0].payload.len() - 1;
+        assert!(batch_messages(events, &config).is_empty());
+    }
+}
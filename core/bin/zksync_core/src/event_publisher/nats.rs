@@ -0,0 +1,56 @@
+// Built-in uses
+use std::{sync::Arc, time::Duration};
+// External uses
+use anyhow::format_err;
+use async_trait::async_trait;
+use nats::jetstream::{JetStream, PublishOptions};
+// Local uses
+use super::{EventMessage, MessageBroker};
+
+const ACK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// NATS JetStream publisher, the messages are published to the subjects named after the topics
+/// and acknowledged by the stream storing them. The stream has to be created beforehand.
+pub struct NatsBroker {
+    jetstream: Arc<JetStream>,
+}
+
+impl NatsBroker {
+    pub async fn connect(servers: &[String]) -> anyhow::Result<Self> {
+        let servers = servers.join(",");
+        // JetStream API of the client is blocking, so it's not run on the async workers.
+        let connection = tokio::task::spawn_blocking(move || nats::connect(&servers)).await??;
+        Ok(Self {
+            jetstream: Arc::new(nats::jetstream::new(connection)),
+        })
+    }
+}
+
+#[async_trait]
+impl MessageBroker for NatsBroker {
+    async fn publish(&self, messages: Vec<EventMessage>) -> anyhow::Result<()> {
+        let jetstream = self.jetstream.clone();
+        tokio::task::spawn_blocking(move || {
+            for message in messages {
+                // The stream drops the messages with the same id published within its duplicate window.
+                let options = PublishOptions {
+                    id: Some(message.event_id.to_string()),
+                    timeout: Some(ACK_TIMEOUT),
+                    ..Default::default()
+                };
+                jetstream
+                    .publish_with_options(&message.topic, &message.payload, &options)
+                    .map_err(|err| {
+                        format_err!(
+                            "NATS didn't acknowledge the event {} published to `{}`: {}",
+                            message.event_id,
+                            message.topic,
+                            err
+                        )
+                    })?;
+            }
+            Ok(())
+        })
+        .await?
+    }
+}
@@ -1,7 +1,7 @@
 use crate::register_factory_handler::run_register_factory_handler;
 use crate::state_keeper::ZkSyncStateInitParams;
 use crate::{
-    committer::run_committer,
+    committer::{run_committer, AggregatedProofSizes},
     eth_watch::start_eth_watch,
     state_keeper::{start_root_hash_calculator, start_state_keeper, ZkSyncStateKeeper},
     token_handler::run_token_handler,
@@ -16,15 +16,22 @@ use zksync_types::{tokens::get_genesis_token_list, Token, TokenId, TokenKind};
 
 const DEFAULT_CHANNEL_CAPACITY: usize = 32_768;
 
+pub mod alerter;
+pub mod archiver;
 pub mod committer;
 pub mod eth_watch;
+pub mod event_publisher;
 pub mod register_factory_handler;
 pub mod rejected_tx_cleaner;
 pub mod state_keeper;
+pub mod state_pruner;
 pub mod token_handler;
+pub mod token_holders;
+pub mod token_metadata;
 pub mod tx_event_emitter;
 
-mod genesis;
+mod admin_api;
+pub mod genesis;
 mod private_api;
 
 /// Waits for any of the tokio tasks to be finished.
@@ -87,6 +94,7 @@ pub async fn genesis_init(config: &ChainConfig) {
 /// - block proposer, module to create block proposals for state keeper.
 /// - committer, module to store pending and completed blocks into the database.
 /// - private Core API server.
+/// - admin API server for the operator actions.
 pub async fn run_core(
     connection_pool: ConnectionPool,
     read_only_connection_pool: ConnectionPool,
@@ -108,6 +116,16 @@ pub async fn run_core(
         connection_pool.clone(),
         mempool_tx_request_receiver,
         config.chain.state_keeper.block_chunk_sizes.clone(),
+        config.chain.state_keeper.mempool_capacity,
+    );
+
+    let aggregated_proof_sizes = AggregatedProofSizes::new(
+        config.chain.state_keeper.aggregated_proof_sizes.clone(),
+        config
+            .chain
+            .circuit
+            .supported_aggregated_proof_sizes
+            .clone(),
     );
 
     // Run health check api for core
@@ -115,6 +133,8 @@ pub async fn run_core(
         connection_pool.clone(),
         read_only_connection_pool,
         eth_gateway.clone(),
+        config.chain.state_keeper.seal_criteria(),
+        aggregated_proof_sizes.clone(),
         config.api.private.clone(),
     );
 
@@ -125,6 +145,7 @@ pub async fn run_core(
         eth_gateway.clone(),
         &config.contracts,
         &config.eth_watch,
+        config.eth_client.web3_ws_url.clone(),
         mempool_tx_request_sender.clone(),
     )
     .await;
@@ -146,14 +167,23 @@ pub async fn run_core(
         proposed_blocks_sender,
         mempool_block_request_sender,
         config.chain.state_keeper.block_chunk_sizes.clone(),
-        config.chain.state_keeper.miniblock_iterations as usize,
-        config.chain.state_keeper.fast_block_miniblock_iterations as usize,
+        config.chain.state_keeper.seal_criteria(),
+        eth_watch_req_sender.clone(),
         processed_tx_events_sender,
     );
 
     // Execute reverted blocks before start
     state_keeper.execute_reverted_blocks().await;
 
+    let admin_api_task = admin_api::start_admin_core_api(
+        connection_pool.clone(),
+        state_keeper.force_seal_handle(),
+        config.chain.state_keeper.seal_criteria(),
+        aggregated_proof_sizes.clone(),
+        config.api.admin.clone(),
+        config.api.admin_cors.clone(),
+    );
+
     let state_keeper_task = start_state_keeper(
         state_keeper,
         config.chain.state_keeper.miniblock_iteration_interval(),
@@ -165,6 +195,7 @@ pub async fn run_core(
         proposed_blocks_receiver,
         connection_pool.clone(),
         config.chain.clone(),
+        aggregated_proof_sizes,
     );
 
     // Start mempool.
@@ -205,6 +236,7 @@ pub async fn run_core(
         mempool_block_handler_task,
         mempool_tx_handler_task,
         private_api_task,
+        admin_api_task,
     ];
 
     Ok(task_futures)
@@ -14,13 +14,22 @@ use actix_web::{web, App, HttpResponse, HttpServer};
 use futures::{channel::mpsc, StreamExt};
 use tokio::sync::RwLock;
 use tokio::task::JoinHandle;
-use zksync_api_types::CoreStatus;
+use zksync_api_types::{
+    v02::{
+        pagination::{PaginationQuery, MAX_LIMIT},
+        token::NFTFactoryRegistration,
+    },
+    CoreStatus,
+};
 
-use zksync_config::configs::api::PrivateApiConfig;
+use zksync_config::configs::{api::PrivateApiConfig, chain::BlockSealCriteria};
 use zksync_eth_client::EthereumGateway;
 use zksync_storage::ConnectionPool;
+use zksync_types::{ethereum::EthTxReplacement, TokenId, TokenLike};
 use zksync_utils::panic_notify::ThreadPanicNotify;
 
+use crate::committer::AggregatedProofSizes;
+
 const STATUS_INVALIDATION_PERIOD: Duration = Duration::from_secs(60);
 
 #[derive(Debug)]
@@ -29,6 +38,8 @@ struct AppState {
     read_only_connection_pool: ConnectionPool,
     eth_client: EthereumGateway,
     status_cache: RwLock<Option<(CoreStatus, Instant)>>,
+    seal_criteria: BlockSealCriteria,
+    aggregated_proof_sizes: AggregatedProofSizes,
 }
 
 /// Health check.
@@ -63,10 +74,196 @@ async fn status(data: web::Data<AppState>) -> actix_web::Result<HttpResponse> {
     Ok(HttpResponse::Ok().json(response))
 }
 
+/// Returns the criteria currently used by the state keeper to seal blocks.
+#[actix_web::get("/seal_criteria")]
+async fn seal_criteria(data: web::Data<AppState>) -> actix_web::Result<HttpResponse> {
+    Ok(HttpResponse::Ok().json(data.seal_criteria))
+}
+
+/// Returns the sizes of aggregated proofs the committer is allowed to create.
+#[actix_web::get("/aggregated_proof_sizes")]
+async fn aggregated_proof_sizes(data: web::Data<AppState>) -> actix_web::Result<HttpResponse> {
+    Ok(HttpResponse::Ok().json(data.aggregated_proof_sizes.get()))
+}
+
+/// Changes the sizes of aggregated proofs the committer is allowed to create.
+/// Only sizes that have setup keys can be chosen.
+#[actix_web::post("/aggregated_proof_sizes")]
+async fn set_aggregated_proof_sizes(
+    data: web::Data<AppState>,
+    sizes: web::Json<Vec<usize>>,
+) -> actix_web::Result<HttpResponse> {
+    data.aggregated_proof_sizes
+        .set(sizes.into_inner())
+        .map_err(actix_web::error::ErrorBadRequest)?;
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Requests the Ethereum sender to replace the transaction of the ongoing Ethereum operation:
+/// either resend it with the increased fee (`"resend"`) or replace it with a self-send
+/// transaction at the same nonce (`"cancel"`).
+#[actix_web::post("/eth_operations/{id}/replace")]
+async fn replace_eth_tx(
+    data: web::Data<AppState>,
+    eth_op_id: web::Path<i64>,
+    action: web::Json<EthTxReplacement>,
+) -> actix_web::Result<HttpResponse> {
+    let mut storage = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let is_requested = storage
+        .ethereum_schema()
+        .request_eth_tx_replacement(eth_op_id.into_inner(), action.into_inner())
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    if !is_requested {
+        return Err(actix_web::error::ErrorNotFound(
+            "Unconfirmed Ethereum operation with the given id is not found",
+        ));
+    }
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Returns the ids of the paused tokens.
+#[actix_web::get("/tokens/paused")]
+async fn paused_tokens(data: web::Data<AppState>) -> actix_web::Result<HttpResponse> {
+    let mut storage = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let mut paused_tokens: Vec<_> = storage
+        .tokens_schema()
+        .load_paused_tokens()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?
+        .into_iter()
+        .collect();
+    paused_tokens.sort_unstable();
+
+    Ok(HttpResponse::Ok().json(paused_tokens))
+}
+
+/// Pauses the token: new transfers and swaps of it are rejected, while the withdrawals are allowed.
+#[actix_web::post("/tokens/{id}/pause")]
+async fn pause_token(
+    data: web::Data<AppState>,
+    token_id: web::Path<u32>,
+) -> actix_web::Result<HttpResponse> {
+    set_token_paused(&data, TokenId(token_id.into_inner()), true).await
+}
+
+/// Unpauses the token, so it can be transferred and swapped again.
+#[actix_web::post("/tokens/{id}/unpause")]
+async fn unpause_token(
+    data: web::Data<AppState>,
+    token_id: web::Path<u32>,
+) -> actix_web::Result<HttpResponse> {
+    set_token_paused(&data, TokenId(token_id.into_inner()), false).await
+}
+
+async fn set_token_paused(
+    data: &AppState,
+    token_id: TokenId,
+    paused: bool,
+) -> actix_web::Result<HttpResponse> {
+    let mut storage = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let token = storage
+        .tokens_schema()
+        .get_token(TokenLike::Id(token_id))
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    if token.is_none() {
+        return Err(actix_web::error::ErrorNotFound(
+            "Token with the given id is not found",
+        ));
+    }
+
+    let mut tokens_schema = storage.tokens_schema();
+    let result = if paused {
+        tokens_schema.pause_token(token_id).await
+    } else {
+        tokens_schema.unpause_token(token_id).await
+    };
+    if result.map_err(actix_web::error::ErrorInternalServerError)? {
+        vlog::info!(
+            "Token {} is {}",
+            token_id,
+            if paused { "paused" } else { "unpaused" }
+        );
+    }
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Returns the page of all the NFT factory registrations, including the deprecated ones.
+#[actix_web::get("/nft_factories")]
+async fn nft_factories(
+    data: web::Data<AppState>,
+    web::Query(query): web::Query<PaginationQuery<i64>>,
+) -> actix_web::Result<HttpResponse> {
+    if query.limit > MAX_LIMIT {
+        return Err(actix_web::error::ErrorBadRequest(format!(
+            "Limit should be less than or equal to {}",
+            MAX_LIMIT
+        )));
+    }
+    let mut storage = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let registrations: Vec<NFTFactoryRegistration> = storage
+        .tokens_schema()
+        .load_nft_factory_registrations(None, true, &query)
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?
+        .into_iter()
+        .map(Into::into)
+        .collect();
+
+    Ok(HttpResponse::Ok().json(registrations))
+}
+
+/// Deprecates the NFT factory registration, e.g. once the factory turns out to be malicious,
+/// so it's no longer served by the public API.
+#[actix_web::post("/nft_factories/{id}/deprecate")]
+async fn deprecate_nft_factory(
+    data: web::Data<AppState>,
+    id: web::Path<i64>,
+) -> actix_web::Result<HttpResponse> {
+    let mut storage = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let is_deprecated = storage
+        .tokens_schema()
+        .deprecate_nft_factory_registration(id.into_inner())
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    if !is_deprecated {
+        return Err(actix_web::error::ErrorNotFound(
+            "Active NFT factory registration with the given id is not found",
+        ));
+    }
+    Ok(HttpResponse::Ok().finish())
+}
+
 pub fn start_private_core_api(
     connection_pool: ConnectionPool,
     read_only_connection_pool: ConnectionPool,
     eth_client: EthereumGateway,
+    seal_criteria: BlockSealCriteria,
+    aggregated_proof_sizes: AggregatedProofSizes,
     config: PrivateApiConfig,
 ) -> JoinHandle<()> {
     let (panic_sender, mut panic_receiver) = mpsc::channel(1);
@@ -85,6 +282,8 @@ pub fn start_private_core_api(
                         read_only_connection_pool: read_only_connection_pool.clone(),
                         eth_client: eth_client.clone(),
                         status_cache: Default::default(),
+                        seal_criteria,
+                        aggregated_proof_sizes: aggregated_proof_sizes.clone(),
                     };
 
                     // By calling `register_data` instead of `data` we're avoiding double
@@ -94,6 +293,16 @@ pub fn start_private_core_api(
                         .app_data(web::Data::new(app_state))
                         .app_data(web::JsonConfig::default().limit(2usize.pow(32)))
                         .service(status)
+                        .service(seal_criteria)
+                        .service(aggregated_proof_sizes)
+                        .service(set_aggregated_proof_sizes)
+                        .service(replace_eth_tx)
+                        .service(paused_tokens)
+                        .service(pause_token)
+                        .service(unpause_token)
+                        .service(nft_factories)
+                        .service(deprecate_nft_factory)
+                        .configure(zksync_http_utils::log_filter::configure)
                 })
                 .bind(&config.bind_addr())
                 .expect("failed to bind")
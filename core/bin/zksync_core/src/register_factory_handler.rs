@@ -10,10 +10,36 @@ use tokio::task::JoinHandle;
 // Workspace uses
 use zksync_config::TokenHandlerConfig;
 use zksync_storage::{ConnectionPool, StorageProcessor};
-use zksync_types::RegisterNFTFactoryEvent;
+use zksync_types::{Address, RegisterNFTFactoryEvent};
 // Local uses
 use crate::eth_watch::EthWatchRequest;
 
+/// Max amount of the stored factories registered for the accounts that don't exist yet.
+/// Registering the factory requires only the creator's signature, so the amount of
+/// such registrations is not limited on L1.
+const MAX_PENDING_FACTORIES: u32 = 10_000;
+
+/// What should be done with the factory registered on L1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FactoryResolution {
+    /// Creator account exists, factory can be used for the withdrawals.
+    Store,
+    /// Creator account doesn't exist yet, factory is stored once it's created.
+    Pending,
+    /// Creator account has another address, contract will never use the factory.
+    Skip(Address),
+}
+
+impl FactoryResolution {
+    fn new(factory: &RegisterNFTFactoryEvent, creator_address: Option<Address>) -> Self {
+        match creator_address {
+            Some(address) if address == factory.creator_address => Self::Store,
+            Some(address) => Self::Skip(address),
+            None => Self::Pending,
+        }
+    }
+}
+
 /// Handle events about registering factories for minting tokens
 #[derive(Debug)]
 struct NFTFactoryHandler {
@@ -53,6 +79,8 @@ impl NFTFactoryHandler {
         receiver.await.expect("Err response from eth watch")
     }
 
+    /// Stores the factories of the existing creators. Factories of the creators that don't exist yet
+    /// are stored as pending, the previously pending ones are stored once their creators are created.
     async fn save_register_factory(
         &self,
         storage: &mut StorageProcessor<'_>,
@@ -60,37 +88,81 @@ impl NFTFactoryHandler {
     ) -> anyhow::Result<()> {
         let mut transaction = storage.start_transaction().await?;
 
-        let factories = {
-            let mut factories = vec![];
-            let mut account_schema = transaction.chain().account_schema();
-            for factory in register_nft_factory_events {
-                // If account does not exists skip factory
-                if let Some(account_id) = account_schema
-                    .account_id_by_address(factory.creator_address)
+        let pending_factories = transaction
+            .tokens_schema()
+            .load_pending_nft_factories()
+            .await?;
+        for factory in pending_factories {
+            let resolution = Self::resolve_factory(&mut transaction, &factory).await?;
+            if resolution == FactoryResolution::Pending {
+                continue;
+            }
+            transaction
+                .tokens_schema()
+                .remove_pending_nft_factory(factory.creator_id, factory.creator_address)
+                .await?;
+            Self::apply_resolution(&mut transaction, &factory, resolution).await?;
+        }
+
+        for factory in register_nft_factory_events {
+            let resolution = Self::resolve_factory(&mut transaction, &factory).await?;
+            Self::apply_resolution(&mut transaction, &factory, resolution).await?;
+        }
+
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    async fn resolve_factory(
+        storage: &mut StorageProcessor<'_>,
+        factory: &RegisterNFTFactoryEvent,
+    ) -> anyhow::Result<FactoryResolution> {
+        let creator_address = storage
+            .chain()
+            .account_schema()
+            .account_address_by_id(factory.creator_id)
+            .await?;
+        Ok(FactoryResolution::new(factory, creator_address))
+    }
+
+    async fn apply_resolution(
+        storage: &mut StorageProcessor<'_>,
+        factory: &RegisterNFTFactoryEvent,
+        resolution: FactoryResolution,
+    ) -> anyhow::Result<()> {
+        match resolution {
+            FactoryResolution::Store => {
+                storage
+                    .tokens_schema()
+                    .store_nft_factory(
+                        factory.creator_id,
+                        factory.creator_address,
+                        factory.factory_address,
+                    )
                     .await?
-                {
-                    factories.push((account_id, factory))
-                } else {
+            }
+            FactoryResolution::Pending => {
+                let is_stored = storage
+                    .tokens_schema()
+                    .store_pending_nft_factory(factory, MAX_PENDING_FACTORIES)
+                    .await?;
+                if !is_stored {
+                    metrics::increment_counter!(
+                        "register_factory_handler.dropped_pending_factories"
+                    );
                     vlog::warn!(
-                        "Cant register factory, creator {:?} does not exist",
-                        &factory.creator_address
-                    )
+                        "Cant register factory {:?}, too many factories are waiting for their creators",
+                        factory.factory_address
+                    );
                 }
             }
-            factories
-        };
-
-        let mut token_schema = transaction.tokens_schema();
-        for (account_id, nft_factory) in factories {
-            token_schema
-                .store_nft_factory(
-                    account_id,
-                    nft_factory.creator_address,
-                    nft_factory.factory_address,
-                )
-                .await?
+            FactoryResolution::Skip(address) => vlog::warn!(
+                "Cant register factory, creator {} has address {:?} instead of {:?}",
+                *factory.creator_id,
+                address,
+                factory.creator_address
+            ),
         }
-        transaction.commit().await?;
         Ok(())
     }
 
@@ -100,12 +172,8 @@ impl NFTFactoryHandler {
             timer.tick().await;
 
             let register_nft_factory_events = self.load_register_nft_factory_events().await;
-
-            self.last_eth_block = register_nft_factory_events
-                .iter()
-                .map(|event| event.eth_block)
-                .max()
-                .or(self.last_eth_block);
+            let last_eth_block =
+                next_last_eth_block(self.last_eth_block, &register_nft_factory_events);
 
             let mut storage = self
                 .connection_pool
@@ -113,13 +181,31 @@ impl NFTFactoryHandler {
                 .await
                 .expect("db connection failed for token handler");
 
-            self.save_register_factory(&mut storage, register_nft_factory_events)
+            // The events are requested again on the next iteration if they are not saved.
+            if let Err(err) = self
+                .save_register_factory(&mut storage, register_nft_factory_events)
                 .await
-                .expect("failed to add register tokens to the database");
+            {
+                vlog::error!("Failed to save the registered NFT factories: {}", err);
+                continue;
+            }
+            self.last_eth_block = last_eth_block;
         }
     }
 }
 
+/// Returns the last Ethereum block the registered factories are processed for.
+fn next_last_eth_block(
+    last_eth_block: Option<u64>,
+    events: &[RegisterNFTFactoryEvent],
+) -> Option<u64> {
+    events
+        .iter()
+        .map(|event| event.eth_block)
+        .max()
+        .max(last_eth_block)
+}
+
 #[must_use]
 pub fn run_register_factory_handler(
     db_pool: ConnectionPool,
@@ -132,3 +218,51 @@ pub fn run_register_factory_handler(
         handler.run().await
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_types::AccountId;
+
+    fn factory_event(creator_address: Address, eth_block: u64) -> RegisterNFTFactoryEvent {
+        RegisterNFTFactoryEvent {
+            factory_address: Address::random(),
+            creator_id: AccountId(1),
+            creator_address,
+            eth_block,
+        }
+    }
+
+    #[test]
+    fn factory_resolution() {
+        let creator_address = Address::random();
+        let factory = factory_event(creator_address, 1);
+
+        assert_eq!(
+            FactoryResolution::new(&factory, Some(creator_address)),
+            FactoryResolution::Store
+        );
+        assert_eq!(
+            FactoryResolution::new(&factory, None),
+            FactoryResolution::Pending
+        );
+        let other_address = Address::random();
+        assert_eq!(
+            FactoryResolution::new(&factory, Some(other_address)),
+            FactoryResolution::Skip(other_address)
+        );
+    }
+
+    #[test]
+    fn last_eth_block() {
+        let events = vec![
+            factory_event(Address::random(), 5),
+            factory_event(Address::random(), 7),
+        ];
+
+        assert_eq!(next_last_eth_block(None, &[]), None);
+        assert_eq!(next_last_eth_block(Some(3), &[]), Some(3));
+        assert_eq!(next_last_eth_block(None, &events), Some(7));
+        assert_eq!(next_last_eth_block(Some(10), &events), Some(10));
+    }
+}
@@ -10,6 +10,7 @@ use futures::{
 use tokio::task::JoinHandle;
 use tokio::time;
 // Workspace uses
+use zksync_config::configs::chain::BlockSealCriteria;
 use zksync_mempool::{GetBlockRequest, MempoolBlocksRequest, ProposedBlock};
 use zksync_state::state::{OpSuccess, ZkSyncState};
 use zksync_types::{
@@ -19,25 +20,28 @@ use zksync_types::{
     },
     gas_counter::GasCounter,
     mempool::SignedTxVariant,
-    tx::ZkSyncTx,
-    Address, PriorityOp, SignedZkSyncTx,
+    Address, PriorityOp, SignedZkSyncTx, ZkSyncTx,
 };
 // Local uses
 use self::{
     pending_block::PendingBlock,
     root_hash_calculator::{BlockRootHashJob, RootHashCalculator},
     types::{ApplyOutcome, StateKeeperConfig},
-    utils::system_time_timestamp,
+    utils::{
+        report_chunks_utilization, report_op_execution, report_tx_queue_latency,
+        system_time_timestamp,
+    },
 };
 use crate::{
     committer::{BlockCommitRequest, CommitRequest},
+    eth_watch::EthWatchRequest,
     tx_event_emitter::ProcessedOperations,
 };
 
 pub use self::{
     init_params::ZkSyncStateInitParams,
     root_hash_calculator::{start_root_hash_calculator, BlockRootHashJobQueue},
-    types::StateKeeperTestkitRequest,
+    types::{ForceSeal, StateKeeperTestkitRequest},
 };
 
 mod init_params;
@@ -59,6 +63,9 @@ pub struct ZkSyncStateKeeper {
 
     tx_for_commitments: mpsc::Sender<CommitRequest>,
     tx_for_mempool: mpsc::Sender<MempoolBlocksRequest>,
+    /// Channel used to request the last known Ethereum block, so the deadlines of the
+    /// priority operations in the pending block can be monitored.
+    eth_watch_req: mpsc::Sender<EthWatchRequest>,
     /// Channel used for sending queued transaction events. Required since state keeper
     /// has no access to the database.
     processed_tx_events_sender: mpsc::Sender<ProcessedOperations>,
@@ -70,6 +77,9 @@ pub struct ZkSyncStateKeeper {
     /// Queue of reverted blocks
     /// They will be executed before the start of the StateKeeper
     reverted_blocks: VecDeque<IncompleteBlock>,
+
+    /// Request to seal the pending block set by the operator via the admin API.
+    force_seal: ForceSeal,
 }
 
 impl ZkSyncStateKeeper {
@@ -83,8 +93,8 @@ impl ZkSyncStateKeeper {
         tx_for_commitments: mpsc::Sender<CommitRequest>,
         tx_for_mempool: mpsc::Sender<MempoolBlocksRequest>,
         available_block_chunk_sizes: Vec<usize>,
-        max_miniblock_iterations: usize,
-        fast_miniblock_iterations: usize,
+        seal_criteria: BlockSealCriteria,
+        eth_watch_req: mpsc::Sender<EthWatchRequest>,
         processed_tx_events_sender: mpsc::Sender<ProcessedOperations>,
     ) -> (Self, RootHashCalculator) {
         // We need two copies of state:
@@ -128,12 +138,8 @@ impl ZkSyncStateKeeper {
             .get_account_by_address(&fee_account_address)
             .expect("Fee account should be present in the account tree");
 
-        let config = StateKeeperConfig::new(
-            fee_account_id,
-            available_block_chunk_sizes,
-            max_miniblock_iterations,
-            fast_miniblock_iterations,
-        );
+        let config =
+            StateKeeperConfig::new(fee_account_id, available_block_chunk_sizes, seal_criteria);
 
         let pending_block = {
             // Keeper starts with the NEXT block
@@ -154,10 +160,12 @@ impl ZkSyncStateKeeper {
 
             tx_for_commitments,
             tx_for_mempool,
+            eth_watch_req,
             processed_tx_events_sender,
 
             root_hash_queue,
             reverted_blocks: initial_state.reverted_blocks.clone(),
+            force_seal: ForceSeal::default(),
         };
         keeper.initialize(initial_state.pending_block);
 
@@ -243,6 +251,11 @@ impl ZkSyncStateKeeper {
         metrics::histogram!("state_keeper.initialize", start.elapsed());
     }
 
+    /// Returns the handle to request sealing of the pending block.
+    pub fn force_seal_handle(&self) -> ForceSeal {
+        self.force_seal.clone()
+    }
+
     pub async fn execute_reverted_blocks(&mut self) {
         while let Some(block) = self.reverted_blocks.pop_front() {
             self.execute_incomplete_block(block).await;
@@ -403,7 +416,19 @@ impl ZkSyncStateKeeper {
             }
         }
 
-        let mut tx_queue = proposed_block.txs.into_iter().collect::<VecDeque<_>>();
+        // Signatures of the proposed transactions are verified at once, so the transactions
+        // execution uses the cached signers.
+        let mut proposed_txs = proposed_block.txs;
+        ZkSyncTx::verify_signatures_batch(proposed_txs.iter_mut().flat_map(
+            |variant| match variant {
+                SignedTxVariant::Tx(tx) => vec![&mut tx.tx],
+                SignedTxVariant::Batch(batch) => {
+                    batch.txs.iter_mut().map(|tx| &mut tx.tx).collect()
+                }
+            },
+        ));
+
+        let mut tx_queue = proposed_txs.into_iter().collect::<VecDeque<_>>();
         while let Some(variant) = tx_queue.pop_front() {
             match &variant {
                 SignedTxVariant::Tx(tx) => {
@@ -454,16 +479,21 @@ impl ZkSyncStateKeeper {
         // Iteration is complete, increment it in the pending block.
         self.pending_block.increment_iteration();
 
-        // If pending block contains withdrawals we seal it faster
-        let max_miniblock_iterations = if self.pending_block.fast_processing_required {
-            self.config.fast_miniblock_iterations
-        } else {
-            self.config.max_miniblock_iterations
-        };
-
         // Check whether we should seal this block and start processing the next one, or we just need
         // to persist the pending block.
-        if self.pending_block.should_seal(max_miniblock_iterations) {
+        let should_seal = self.pending_block.should_seal(
+            &self.config.seal_criteria,
+            self.config.max_block_size(),
+            system_time_timestamp(),
+        );
+        // Empty block is never sealed on request, the request is dropped instead.
+        let force_seal =
+            self.force_seal.take() && !self.pending_block.success_operations.is_empty();
+        if force_seal {
+            vlog::info!("Sealing block #{} on request", *self.pending_block.number);
+        }
+
+        if should_seal || force_seal || self.priority_op_deadline_approaching().await {
             self.seal_pending_block().await;
         } else {
             // State keeper may process empty blocks (or blocks containing rejected transactions only), and it's an
@@ -478,6 +508,47 @@ impl ZkSyncStateKeeper {
         metrics::histogram!("state_keeper.execute_proposed_block", start.elapsed());
     }
 
+    /// Checks whether any priority operation in the pending block is close to its deadline on Ethereum.
+    /// Missing the deadline activates the exodus mode, so such blocks are sealed regardless of the other criteria.
+    async fn priority_op_deadline_approaching(&mut self) -> bool {
+        let margin = self.config.seal_criteria.priority_op_deadline_margin;
+        if margin == 0 {
+            return false;
+        }
+        let deadline = match self.pending_block.earliest_priority_op_deadline() {
+            Some(deadline) => deadline,
+            None => return false,
+        };
+
+        let (resp, receiver) = oneshot::channel();
+        if self
+            .eth_watch_req
+            .send(EthWatchRequest::GetLastEthBlock { resp })
+            .await
+            .is_err()
+        {
+            return false;
+        }
+        let last_eth_block = match receiver.await {
+            Ok(block) => block,
+            Err(_) => return false,
+        };
+
+        if last_eth_block + margin < deadline {
+            return false;
+        }
+
+        vlog::error!(
+            "Priority operation in block {} is approaching its deadline: last Ethereum block {}, deadline block {}. \
+             Sealing the block",
+            *self.pending_block.number,
+            last_eth_block,
+            deadline
+        );
+        metrics::increment_counter!("state_keeper.priority_op_deadline_seal");
+        true
+    }
+
     // Err if there is no space in current block
     fn apply_priority_op(&mut self, priority_op: &PriorityOp) -> ApplyOutcome<ExecutedOperations> {
         let start = Instant::now();
@@ -526,6 +597,7 @@ impl ZkSyncStateKeeper {
         );
 
         metrics::histogram!("state_keeper.apply_priority_op", start.elapsed());
+        report_op_execution(start.elapsed(), priority_op.data.variance_name());
         ApplyOutcome::Included(exec_result)
     }
 
@@ -580,9 +652,17 @@ impl ZkSyncStateKeeper {
             return ApplyOutcome::NotIncluded;
         }
 
+        // Fast withdrawals in the batch speed up the block processing the same way as the single ones.
+        if txs.iter().any(|tx| tx.tx.is_fast_processing()) {
+            self.pending_block.fast_processing_required = true;
+        }
+
         let all_updates = self
             .state
             .execute_txs_batch(txs, self.pending_block.timestamp);
+        for tx in txs {
+            report_tx_queue_latency(tx.created_at, tx.tx.variance_name());
+        }
 
         for (tx, tx_updates) in txs.iter().zip(all_updates) {
             match tx_updates {
@@ -648,6 +728,7 @@ impl ZkSyncStateKeeper {
 
     fn apply_tx(&mut self, tx: &SignedZkSyncTx) -> ApplyOutcome<ExecutedOperations> {
         let start = Instant::now();
+        let _span = vlog::tx_span("state_keeper.apply_tx", tx.hash().as_ref()).entered();
         let chunks_needed = self.state.chunks_for_tx(tx);
 
         // If we can't add the tx to the block due to the size limit, we return this tx,
@@ -673,11 +754,9 @@ impl ZkSyncStateKeeper {
             }
         }
 
-        if let ZkSyncTx::Withdraw(tx) = &tx.tx {
-            // Check if we should mark this block as requiring fast processing.
-            if tx.fast {
-                self.pending_block.fast_processing_required = true;
-            }
+        // Check if we should mark this block as requiring fast processing.
+        if tx.tx.is_fast_processing() {
+            self.pending_block.fast_processing_required = true;
         }
 
         let tx_updates = self
@@ -734,9 +813,16 @@ impl ZkSyncStateKeeper {
         };
 
         metrics::histogram!("state_keeper.apply_tx", start.elapsed());
+        report_op_execution(start.elapsed(), tx.tx.variance_name());
+        report_tx_queue_latency(tx.created_at, tx.tx.variance_name());
         ApplyOutcome::Included(exec_result)
     }
 
+    /// Returns the number of chunks of the pending block occupied by the executed operations.
+    fn used_chunks(&self) -> usize {
+        self.config.max_block_size() - self.pending_block.chunks_left
+    }
+
     /// Finalizes the pending block, transforming it into a full block.
     async fn seal_pending_block(&mut self) {
         let start = Instant::now();
@@ -797,6 +883,8 @@ impl ZkSyncStateKeeper {
             self.pending_block.timestamp,
         );
 
+        report_chunks_utilization(self.used_chunks(), block.block_chunks_size);
+
         // Update the fields of the new pending block.
         let block_metadata = BlockMetadata {
             fast_processing: self.pending_block.fast_processing_required,
@@ -1,5 +1,6 @@
 // External uses
 // Workspace uses
+use zksync_config::configs::chain::BlockSealCriteria;
 use zksync_state::state::CollectedFee;
 use zksync_types::{
     block::{ExecutedOperations, ExecutedTx, PendingBlock as SendablePendingBlock},
@@ -77,9 +78,76 @@ impl PendingBlock {
         self.failed_txs.is_empty() && self.success_operations.is_empty()
     }
 
-    pub(super) fn should_seal(&self, miniblock_iterations: usize) -> bool {
+    /// Checks whether the block satisfies any of the sealing criteria.
+    ///
+    /// Criteria other than the miniblock iterations are only checked for blocks that contain
+    /// at least one successful operation, since there is no point in sealing an empty block.
+    pub(super) fn should_seal(
+        &self,
+        criteria: &BlockSealCriteria,
+        max_block_size: usize,
+        current_timestamp: u64,
+    ) -> bool {
+        if self.chunks_left == 0 {
+            return true;
+        }
+
+        // If pending block contains withdrawals we seal it faster.
+        let miniblock_iterations = if self.fast_processing_required {
+            criteria.fast_miniblock_iterations
+        } else {
+            criteria.miniblock_iterations
+        };
         // `>=` in condition since iterations start with 0.
-        self.chunks_left == 0 || self.pending_block_iteration >= miniblock_iterations
+        if self.pending_block_iteration >= miniblock_iterations {
+            return true;
+        }
+
+        if self.success_operations.is_empty() {
+            return false;
+        }
+
+        let used_chunks = max_block_size.saturating_sub(self.chunks_left);
+        if used_chunks * 100 >= max_block_size * criteria.chunks_utilization_percent as usize {
+            return true;
+        }
+        if criteria.max_block_age_secs != 0
+            && current_timestamp.saturating_sub(self.timestamp) >= criteria.max_block_age_secs
+        {
+            return true;
+        }
+        if criteria.max_pending_withdrawals != 0
+            && self.withdrawals_count() >= criteria.max_pending_withdrawals
+        {
+            return true;
+        }
+        if criteria.gas_budget != 0
+            && self.gas_counter.commit_gas_limit() >= criteria.gas_budget.into()
+        {
+            return true;
+        }
+
+        false
+    }
+
+    /// Returns the earliest Ethereum deadline block among the priority operations in the block.
+    pub(super) fn earliest_priority_op_deadline(&self) -> Option<u64> {
+        self.success_operations
+            .iter()
+            .filter_map(|op| match op {
+                ExecutedOperations::PriorityOp(op) => Some(op.priority_op.deadline_block),
+                ExecutedOperations::Tx(_) => None,
+            })
+            .min()
+    }
+
+    /// Returns the amount of operations in the block that have to be processed on L1.
+    fn withdrawals_count(&self) -> usize {
+        self.success_operations
+            .iter()
+            .filter_map(ExecutedOperations::get_executed_op)
+            .filter(|op| op.is_processable_onchain_operation())
+            .count()
     }
 
     pub(super) fn add_successful_execution(
@@ -162,7 +230,8 @@ impl PendingBlock {
 mod tests {
     use chrono::prelude::*;
     use zksync_types::{
-        AccountId, AccountUpdate, Address, Nonce, SignedZkSyncTx, TokenId, Transfer, ZkSyncTx,
+        block::ExecutedPriorityOp, AccountId, AccountUpdate, Address, Deposit, DepositOp, Nonce,
+        PriorityOp, SignedZkSyncTx, TokenId, Transfer, ZkSyncOp, ZkSyncPriorityOp, ZkSyncTx,
     };
 
     use super::*;
@@ -171,6 +240,10 @@ mod tests {
     const CHUNKS_PER_BLOCK: usize = 100;
     const MAX_ITERATIONS: usize = 2;
 
+    fn seal_criteria() -> BlockSealCriteria {
+        BlockSealCriteria::with_iterations(MAX_ITERATIONS, MAX_ITERATIONS)
+    }
+
     fn pending_block() -> PendingBlock {
         // Fields that aren't interesting in the testing context.
         let unprocessed_priority_op_before = 0;
@@ -215,6 +288,33 @@ mod tests {
         }))
     }
 
+    /// Creates a mock executed deposit with the given Ethereum deadline block.
+    fn mock_executed_priority_op(deadline_block: u64) -> ExecutedOperations {
+        let deposit = Deposit {
+            from: Address::default(),
+            token: TokenId(0),
+            amount: 100u64.into(),
+            to: Address::default(),
+        };
+
+        ExecutedOperations::PriorityOp(Box::new(ExecutedPriorityOp {
+            priority_op: PriorityOp {
+                serial_id: 0,
+                data: ZkSyncPriorityOp::Deposit(deposit.clone()),
+                deadline_block,
+                eth_hash: Default::default(),
+                eth_block: 0,
+                eth_block_index: None,
+            },
+            op: ZkSyncOp::Deposit(Box::new(DepositOp {
+                priority_op: deposit,
+                account_id: AccountId(0),
+            })),
+            block_index: 0,
+            created_at: Utc::now(),
+        }))
+    }
+
     /// Creates all the fields to call `add_successfull_execution`.
     fn prepare_successful_execution() -> (
         usize,
@@ -255,7 +355,7 @@ mod tests {
         // Methods testing on the empty block.
         assert!(pending_block.is_empty(), "Block should be empty");
         assert!(
-            !pending_block.should_seal(MAX_ITERATIONS),
+            !pending_block.should_seal(&seal_criteria(), CHUNKS_PER_BLOCK, 0),
             "Should no seal empty block with no enough iterations"
         );
 
@@ -284,7 +384,7 @@ mod tests {
         );
 
         assert!(
-            !pending_block.should_seal(MAX_ITERATIONS),
+            !pending_block.should_seal(&seal_criteria(), CHUNKS_PER_BLOCK, 0),
             "Block should not be sealed after 1 iteration"
         );
 
@@ -295,7 +395,7 @@ mod tests {
         );
 
         assert!(
-            pending_block.should_seal(MAX_ITERATIONS),
+            pending_block.should_seal(&seal_criteria(), CHUNKS_PER_BLOCK, 0),
             "Block should be sealed after 2 iteration"
         );
 
@@ -382,4 +482,70 @@ mod tests {
             pending_block.account_updates.len(),
         )
     }
+
+    /// Checks that the configurable sealing criteria are applied to the non-empty block.
+    #[test]
+    fn seal_criteria_applied() {
+        let mut pending_block = pending_block();
+
+        // Age criterion must not affect the empty block.
+        let criteria = BlockSealCriteria {
+            max_block_age_secs: 10,
+            ..seal_criteria()
+        };
+        assert!(!pending_block.should_seal(&criteria, CHUNKS_PER_BLOCK, 100));
+
+        let (chunks, updates, fee, exec_result) = prepare_successful_execution();
+        pending_block.add_successful_execution(chunks, updates, fee, exec_result);
+
+        assert!(!pending_block.should_seal(&criteria, CHUNKS_PER_BLOCK, 9));
+        assert!(pending_block.should_seal(&criteria, CHUNKS_PER_BLOCK, 10));
+
+        // 2 chunks out of 100 are used.
+        let criteria = BlockSealCriteria {
+            chunks_utilization_percent: 3,
+            ..seal_criteria()
+        };
+        assert!(!pending_block.should_seal(&criteria, CHUNKS_PER_BLOCK, 0));
+        let criteria = BlockSealCriteria {
+            chunks_utilization_percent: 2,
+            ..seal_criteria()
+        };
+        assert!(pending_block.should_seal(&criteria, CHUNKS_PER_BLOCK, 0));
+
+        // Mock operation is a failed transfer, so it's not a withdrawal.
+        let criteria = BlockSealCriteria {
+            max_pending_withdrawals: 1,
+            ..seal_criteria()
+        };
+        assert!(!pending_block.should_seal(&criteria, CHUNKS_PER_BLOCK, 0));
+
+        // Even an empty block has the base commit cost.
+        let criteria = BlockSealCriteria {
+            gas_budget: 1,
+            ..seal_criteria()
+        };
+        assert!(pending_block.should_seal(&criteria, CHUNKS_PER_BLOCK, 0));
+    }
+
+    /// Checks that the earliest deadline among the priority operations in the block is reported.
+    #[test]
+    fn earliest_priority_op_deadline() {
+        let mut pending_block = pending_block();
+        assert_eq!(pending_block.earliest_priority_op_deadline(), None);
+
+        let (chunks, updates, fee, exec_result) = prepare_successful_execution();
+        pending_block.add_successful_execution(chunks, updates, fee, exec_result);
+        assert_eq!(pending_block.earliest_priority_op_deadline(), None);
+
+        for deadline in [200, 100, 300] {
+            pending_block.add_successful_execution(
+                DepositOp::CHUNKS,
+                Vec::new(),
+                None,
+                mock_executed_priority_op(deadline),
+            );
+        }
+        assert_eq!(pending_block.earliest_priority_op_deadline(), Some(100));
+    }
 }
@@ -35,12 +35,31 @@ impl<'a, 'b> StateRestoreDb for StateRestoreStorage<'a, 'b> {
     }
 
     async fn load_last_cached_block(&mut self) -> Option<BlockNumber> {
-        self.storage
+        let cached_block = self
+            .storage
             .chain()
             .tree_cache_schema_bincode()
             .get_last_block_with_account_tree_cache()
             .await
-            .expect("Can't load the last block with cache")
+            .expect("Can't load the last block with cache")?;
+        // The cache can't be brought up to date once the diffs following it are pruned.
+        let first_snapshot_block = self
+            .storage
+            .chain()
+            .pruning_schema()
+            .get_first_state_snapshot_block()
+            .await
+            .expect("Can't load the first state snapshot block");
+        match first_snapshot_block {
+            Some(first_snapshot_block) if cached_block < first_snapshot_block => {
+                vlog::warn!(
+                    "Account tree cache of the block {} precedes the pruned blocks, it is ignored",
+                    cached_block
+                );
+                None
+            }
+            _ => Some(cached_block),
+        }
     }
 
     async fn load_state_diff(
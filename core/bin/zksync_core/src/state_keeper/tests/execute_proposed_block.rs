@@ -1,8 +1,8 @@
 use chrono::Utc;
 use zksync_mempool::ProposedBlock;
 use zksync_types::{
-    mempool::SignedTxVariant, AccountId, BlockNumber, Nonce, SignedZkSyncTx, TokenId, Transfer,
-    ZkSyncTx,
+    mempool::{SignedTxVariant, SignedTxsBatch},
+    AccountId, BlockNumber, Nonce, SignedZkSyncTx, TokenId, Transfer, ZkSyncTx,
 };
 
 use super::utils::*;
@@ -236,6 +236,41 @@ async fn fast_withdrawal() {
     tester.assert_sealed().await;
 }
 
+/// Checks that fast withdrawal inside a batch causes block to be sealed faster as well.
+#[tokio::test]
+async fn fast_withdrawal_in_batch() {
+    const MAX_ITERATIONS: usize = 100;
+    const FAST_ITERATIONS: usize = 0; // Seal block right after fast withdrawal.
+
+    let mut tester = StateKeeperTester::new(20, MAX_ITERATIONS, FAST_ITERATIONS);
+    let transfer =
+        create_account_and_transfer(&mut tester, TokenId(0), AccountId(1), 200u32, 100u32);
+    let withdraw = create_account_and_fast_withdrawal(
+        &mut tester,
+        TokenId(0),
+        AccountId(2),
+        200u32,
+        145u32,
+        Default::default(),
+    );
+
+    let proposed_block = ProposedBlock {
+        priority_ops: Vec::new(),
+        txs: vec![SignedTxVariant::Batch(SignedTxsBatch {
+            txs: vec![transfer, withdraw],
+            batch_id: 1,
+            eth_signatures: Vec::new(),
+        })],
+    };
+
+    tester
+        .state_keeper
+        .execute_proposed_block(proposed_block)
+        .await;
+
+    tester.assert_sealed().await;
+}
+
 /// Checks the following things:
 /// 1. if proposed block is empty, no pending block is yielded from the state keeper.
 /// 2. if there were no successful operations in the block, pending block iteration is not incremented after empty or rejected-only updates.
@@ -565,3 +600,37 @@ async fn transfers_with_different_timestamps() {
         })
         .await;
 }
+
+/// Checks that the pending block is sealed on request, and the empty block is not.
+#[tokio::test]
+async fn force_seal() {
+    let mut tester = StateKeeperTester::new(20, 10, 10);
+    let force_seal = tester.state_keeper.force_seal_handle();
+    let empty_block = || ProposedBlock {
+        txs: Vec::new(),
+        priority_ops: Vec::new(),
+    };
+
+    // Request for the empty block is dropped.
+    force_seal.request();
+    tester
+        .state_keeper
+        .execute_proposed_block(empty_block())
+        .await;
+    assert!(!force_seal.is_requested());
+
+    apply_single_transfer(&mut tester).await;
+    tester
+        .assert_pending_with(|block| assert_eq!(block.success_operations.len(), 1))
+        .await;
+
+    force_seal.request();
+    tester
+        .state_keeper
+        .execute_proposed_block(empty_block())
+        .await;
+    tester
+        .assert_sealed_with(|block| assert_eq!(block.block_transactions.len(), 1))
+        .await;
+    assert!(!force_seal.is_requested());
+}
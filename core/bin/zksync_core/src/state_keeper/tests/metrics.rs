@@ -0,0 +1,59 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use zksync_types::{AccountId, TokenId, WithdrawOp};
+
+use super::utils::*;
+use crate::state_keeper::utils::{chunks_utilization, tx_queue_latency};
+
+/// Checks that only the chunks of the executed operations are counted as used.
+#[test]
+fn used_chunks() {
+    let mut tester = StateKeeperTester::new(6, 1, 1);
+    assert_eq!(tester.state_keeper.used_chunks(), 0);
+
+    let withdraw = create_account_and_withdrawal(
+        &mut tester,
+        TokenId(0),
+        AccountId(1),
+        200u32,
+        145u32,
+        Default::default(),
+    );
+    assert!(tester.state_keeper.apply_tx(&withdraw).is_included());
+    assert_eq!(tester.state_keeper.used_chunks(), WithdrawOp::CHUNKS);
+
+    // Failed transactions don't occupy the chunks.
+    let withdraw = create_account_and_withdrawal(
+        &mut tester,
+        TokenId(0),
+        AccountId(2),
+        100u32,
+        145u32,
+        Default::default(),
+    );
+    assert!(tester.state_keeper.apply_tx(&withdraw).is_included());
+    assert_eq!(tester.state_keeper.used_chunks(), WithdrawOp::CHUNKS);
+}
+
+/// Checks that the queue latency is measured from the transaction creation and is never negative.
+#[test]
+fn tx_queue_latency_is_not_negative() {
+    let now = Utc::now();
+    assert_eq!(
+        tx_queue_latency(now - chrono::Duration::seconds(5), now),
+        Duration::from_secs(5)
+    );
+    assert_eq!(
+        tx_queue_latency(now + chrono::Duration::seconds(5), now),
+        Duration::default()
+    );
+}
+
+/// Checks that the utilization is the share of the used chunks in the block.
+#[test]
+fn block_chunks_utilization() {
+    assert_eq!(chunks_utilization(3, 6), Some(0.5));
+    assert_eq!(chunks_utilization(6, 6), Some(1.0));
+    assert_eq!(chunks_utilization(0, 0), None);
+}
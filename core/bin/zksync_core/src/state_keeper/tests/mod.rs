@@ -1,11 +1,13 @@
 use super::{ZkSyncStateInitParams, ZkSyncStateKeeper};
 use futures::channel::mpsc;
+use zksync_config::configs::chain::BlockSealCriteria;
 use zksync_types::{AccountId, H160, *};
 
 mod apply_priority_op;
 mod apply_tx;
 mod execute_proposed_block;
 mod gas_limit;
+mod metrics;
 mod pending_block;
 mod utils;
 
@@ -20,6 +22,7 @@ fn test_create_incorrect_state_keeper() {
     let (events_sender, _events_receiver) = mpsc::channel(CHANNEL_SIZE);
     let (request_tx, _request_rx) = mpsc::channel(CHANNEL_SIZE);
     let (response_tx, _response_rx) = mpsc::channel(CHANNEL_SIZE);
+    let (eth_watch_req_tx, _eth_watch_req_rx) = mpsc::channel(CHANNEL_SIZE);
 
     let fee_collector = Account::default_with_address(&H160::random());
 
@@ -35,8 +38,8 @@ fn test_create_incorrect_state_keeper() {
         request_tx,
         response_tx,
         vec![1, 2, 2], // `available_block_chunk_sizes` must be strictly increasing.
-        MAX_ITERATIONS,
-        FAST_ITERATIONS,
+        BlockSealCriteria::with_iterations(MAX_ITERATIONS, FAST_ITERATIONS),
+        eth_watch_req_tx,
         events_sender,
     );
 }
@@ -3,6 +3,7 @@ use crate::state_keeper::{CommitRequest, ZkSyncStateInitParams, ZkSyncStateKeepe
 use chrono::Utc;
 use futures::{channel::mpsc, stream::StreamExt};
 use num::BigUint;
+use zksync_config::configs::chain::BlockSealCriteria;
 use zksync_crypto::{
     priv_key_from_fs,
     rand::{Rng, SeedableRng, XorShiftRng},
@@ -27,6 +28,7 @@ impl StateKeeperTester {
         let (events_sender, _events_receiver) = mpsc::channel(CHANNEL_SIZE);
         let (request_tx, _request_rx) = mpsc::channel(CHANNEL_SIZE);
         let (response_tx, response_rx) = mpsc::channel(CHANNEL_SIZE);
+        let (eth_watch_req_tx, _eth_watch_req_rx) = mpsc::channel(CHANNEL_SIZE);
 
         let fee_collector = Account::default_with_address(&H160::random());
 
@@ -41,8 +43,8 @@ impl StateKeeperTester {
             response_tx,
             request_tx,
             vec![available_chunk_size],
-            max_iterations,
-            fast_iterations,
+            BlockSealCriteria::with_iterations(max_iterations, fast_iterations),
+            eth_watch_req_tx,
             events_sender,
         );
 
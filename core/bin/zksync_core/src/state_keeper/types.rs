@@ -1,7 +1,13 @@
+// Built-in uses
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
 // External uses
 use futures::channel::oneshot;
 use itertools::Itertools;
 // Workspace uses
+use zksync_config::configs::chain::BlockSealCriteria;
 use zksync_mempool::ProposedBlock;
 use zksync_types::{Account, AccountId, Address};
 // Local uses
@@ -41,13 +47,34 @@ impl<T> ApplyOutcome<T> {
     }
 }
 
+/// Operator request to seal the pending block regardless of the seal criteria.
+/// The clones of the handle share the same request.
+#[derive(Debug, Clone, Default)]
+pub struct ForceSeal(Arc<AtomicBool>);
+
+impl ForceSeal {
+    /// Requests to seal the pending block at the end of the next miniblock iteration.
+    pub fn request(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    /// Checks whether the request is not processed by the state keeper yet.
+    pub fn is_requested(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    /// Resets the request, returns whether it was set.
+    pub(super) fn take(&self) -> bool {
+        self.0.swap(false, Ordering::SeqCst)
+    }
+}
+
 /// Constant configuration parameters needed by state keeper to work.
 #[derive(Debug)]
 pub(super) struct StateKeeperConfig {
     pub(super) fee_account_id: AccountId,
     pub(super) available_block_chunk_sizes: Vec<usize>,
-    pub(super) max_miniblock_iterations: usize,
-    pub(super) fast_miniblock_iterations: usize,
+    pub(super) seal_criteria: BlockSealCriteria,
     max_block_size: usize,
 }
 
@@ -55,8 +82,7 @@ impl StateKeeperConfig {
     pub(super) fn new(
         fee_account_id: AccountId,
         available_block_chunk_sizes: Vec<usize>,
-        max_miniblock_iterations: usize,
-        fast_miniblock_iterations: usize,
+        seal_criteria: BlockSealCriteria,
     ) -> Self {
         // Ensure that available block chunk sizes are sorted and not empty.
         assert!(
@@ -79,8 +105,7 @@ impl StateKeeperConfig {
         Self {
             fee_account_id,
             available_block_chunk_sizes,
-            max_miniblock_iterations,
-            fast_miniblock_iterations,
+            seal_criteria,
             max_block_size,
         }
     }
@@ -97,7 +122,11 @@ mod tests {
     /// Checks that config can be created if provided values are correct.
     #[test]
     fn create_config() {
-        let config = StateKeeperConfig::new(AccountId(0), vec![1, 2, 3], 10, 20);
+        let config = StateKeeperConfig::new(
+            AccountId(0),
+            vec![1, 2, 3],
+            BlockSealCriteria::with_iterations(10, 20),
+        );
         assert_eq!(config.max_block_size, 3);
     }
 
@@ -106,7 +135,11 @@ mod tests {
     #[should_panic(expected = "Block chunk sizes are not in order")]
     fn config_chunks_out_of_order() {
         let incorrect_chunks = vec![3, 1, 2];
-        let _config = StateKeeperConfig::new(AccountId(0), incorrect_chunks, 10, 20);
+        let _config = StateKeeperConfig::new(
+            AccountId(0),
+            incorrect_chunks,
+            BlockSealCriteria::with_iterations(10, 20),
+        );
     }
 
     /// Checks that if chunk sizes are empty, it will panic.
@@ -114,6 +147,10 @@ mod tests {
     #[should_panic(expected = "Block chunk sizes are empty")]
     fn config_chunks_empty() {
         let incorrect_chunks = vec![];
-        let _config = StateKeeperConfig::new(AccountId(0), incorrect_chunks, 10, 20);
+        let _config = StateKeeperConfig::new(
+            AccountId(0),
+            incorrect_chunks,
+            BlockSealCriteria::with_iterations(10, 20),
+        );
     }
 }
@@ -1,4 +1,6 @@
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use chrono::{DateTime, Utc};
 
 pub(super) fn system_time_timestamp() -> u64 {
     SystemTime::now()
@@ -6,3 +8,37 @@ pub(super) fn system_time_timestamp() -> u64 {
         .expect("failed to get system time")
         .as_secs()
 }
+
+/// Returns the time the transaction spent waiting in the mempool before being executed.
+/// The clocks of the API servers accepting the transactions may differ, so the latency is never negative.
+pub(super) fn tx_queue_latency(created_at: DateTime<Utc>, now: DateTime<Utc>) -> Duration {
+    (now - created_at).to_std().unwrap_or_default()
+}
+
+/// Returns the share of chunks of the sealed block that are occupied by operations.
+pub(super) fn chunks_utilization(used_chunks: usize, block_chunks_size: usize) -> Option<f64> {
+    if block_chunks_size == 0 {
+        return None;
+    }
+    Some(used_chunks as f64 / block_chunks_size as f64)
+}
+
+/// Reports the time the transaction spent waiting in the mempool before being executed.
+pub(super) fn report_tx_queue_latency(created_at: DateTime<Utc>, tx_type: String) {
+    let latency = tx_queue_latency(created_at, Utc::now());
+    metrics::histogram!("state_keeper.tx_queue_latency", latency, "type" => tx_type);
+}
+
+/// Reports the share of chunks of the sealed block that are occupied by operations.
+pub(super) fn report_chunks_utilization(used_chunks: usize, block_chunks_size: usize) {
+    metrics::histogram!("state_keeper.block_chunks_used", used_chunks as f64);
+    metrics::histogram!("state_keeper.block_chunks_size", block_chunks_size as f64);
+    if let Some(utilization) = chunks_utilization(used_chunks, block_chunks_size) {
+        metrics::histogram!("state_keeper.block_chunks_utilization", utilization);
+    }
+}
+
+/// Reports the time spent on the execution of a single operation of the given type.
+pub(super) fn report_op_execution(elapsed: Duration, op_type: String) {
+    metrics::histogram!("state_keeper.execute_op", elapsed, "type" => op_type);
+}
@@ -0,0 +1,57 @@
+//! The pruner is responsible for removing the per-block data of the old executed blocks
+//! from the database: account balance and public key diffs and block witnesses.
+//!
+//! Such data is only needed to restore the state of the non-executed blocks, so it's kept
+//! only for the configurable amount of the latest executed blocks. Before any data is removed,
+//! the full state after the last executed block is stored as a snapshot (once per configured
+//! amount of blocks). The data is only pruned up to a snapshot, which along with the retained diffs
+//! is sufficient to restore the state of any following block, see `PruningSchema::prune_executed_blocks`.
+
+// External uses
+use tokio::{task::JoinHandle, time};
+// Workspace deps
+use zksync_config::DBConfig;
+use zksync_storage::{ConnectionPool, StorageProcessor};
+
+#[must_use]
+pub fn run_state_pruner(config: &DBConfig, db_pool: ConnectionPool) -> JoinHandle<()> {
+    let retention_blocks = config.pruning_retention_blocks;
+    let snapshot_interval = config.state_snapshot_interval;
+    let mut timer = time::interval(config.state_pruner_interval());
+
+    tokio::spawn(async move {
+        loop {
+            timer.tick().await;
+            let mut storage = db_pool
+                .access_storage()
+                .await
+                .expect("state pruner couldn't access the database");
+            if let Err(e) = prune(&mut storage, retention_blocks, snapshot_interval).await {
+                vlog::error!("Can't prune the old blocks data {:?}", e);
+            }
+        }
+    })
+}
+
+async fn prune(
+    storage: &mut StorageProcessor<'_>,
+    retention_blocks: u32,
+    snapshot_interval: u32,
+) -> anyhow::Result<()> {
+    let pruned = storage
+        .chain()
+        .pruning_schema()
+        .prune_executed_blocks(retention_blocks, snapshot_interval)
+        .await?;
+    if let Some((last_pruned_block, pruned)) = pruned {
+        metrics::counter!("state_pruner.pruned_rows", pruned.balance_updates, "table" => "account_balance_updates");
+        metrics::counter!("state_pruner.pruned_rows", pruned.pubkey_updates, "table" => "account_pubkey_updates");
+        metrics::counter!("state_pruner.pruned_rows", pruned.witnesses, "table" => "block_witness");
+        vlog::debug!(
+            "Pruned the data of the blocks up to {}: {:?}",
+            last_pruned_block,
+            pruned
+        );
+    }
+    Ok(())
+}
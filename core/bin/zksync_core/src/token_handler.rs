@@ -3,7 +3,13 @@
 //!
 //! To set the name and the decimals parameter for the token, a match is searched for with the
 //! token list (which is taken from the environment). If the token address is not found in the
-//! trusted token list, then the default values are used (name = "ERC20-{id}", decimals = 18).
+//! trusted token list, the token is listed automatically: the listing fee payment is looked up
+//! in the transaction that added the token (if the fee is configured), and the symbol and the decimals
+//! are read from the token contract and validated. Every fee transfer pays for a single token. Tokens
+//! failing the validation are recorded to the listing rejections and stored with the default values
+//! (name = "ERC20-{id}", decimals = 18), so the operations with them are still processed, but they are
+//! not announced. If the validation can't be completed because of the Ethereum node errors, the tokens
+//! are processed again on the next iteration.
 
 // Built-in deps
 use std::collections::HashMap;
@@ -12,14 +18,18 @@ use futures::{
     channel::{mpsc, oneshot},
     SinkExt,
 };
+use thiserror::Error;
 use tokio::task::JoinHandle;
 // Workspace uses
 use zksync_config::TokenHandlerConfig;
 use zksync_notifier::Notifier;
-use zksync_storage::{tokens::StoreTokenError, ConnectionPool, StorageProcessor};
+use zksync_storage::{
+    tokens::{StoreTokenError, TokensSchema},
+    ConnectionPool, StorageProcessor,
+};
 use zksync_types::{
     tokens::{NewTokenEvent, Token, TokenInfo},
-    Address, TokenId, TokenKind, U256,
+    Address, Log, TokenId, TokenKind, TokenLike, H256, U256,
 };
 // Local uses
 use crate::eth_watch::EthWatchRequest;
@@ -27,6 +37,101 @@ use web3::contract::Options;
 use zksync_contracts::erc20_contract;
 use zksync_eth_client::EthereumGateway;
 
+/// Max length of the symbol of the token listed automatically.
+const MAX_SYMBOL_LENGTH: usize = 10;
+/// Max decimals of the token listed automatically, as many as Ether has.
+const MAX_DECIMALS: u8 = 18;
+
+/// Reason for the token not to be listed automatically.
+#[derive(Debug, Clone, PartialEq, Error)]
+enum ListingRejection {
+    #[error("listing fee is not paid")]
+    FeeNotPaid,
+    #[error("failed to read the token metadata: {0}")]
+    MetadataUnavailable(String),
+    #[error("symbol {0:?} must be up to 10 alphanumeric characters")]
+    InvalidSymbol(String),
+    #[error("symbol {0} is already taken")]
+    SymbolTaken(String),
+    #[error("decimals {0} exceed 18")]
+    InvalidDecimals(U256),
+}
+
+/// Payment expected along with the token added to the contract.
+#[derive(Debug, Clone, Copy)]
+struct ListingFee {
+    token: Address,
+    treasury: Address,
+    amount: U256,
+}
+
+impl ListingFee {
+    fn from_config(config: &TokenHandlerConfig) -> Option<Self> {
+        match (
+            config.listing_fee_token,
+            config.listing_fee_treasury,
+            config.listing_fee(),
+        ) {
+            (Some(token), Some(treasury), Some(amount)) => Some(Self {
+                token,
+                treasury,
+                amount,
+            }),
+            (None, None, None) => None,
+            _ => panic!("The listing fee token, treasury and amount must be set together"),
+        }
+    }
+
+    /// Returns the log indices of the fee transfers to the treasury among the logs of the transaction.
+    fn payments(&self, logs: &[Log]) -> Vec<u64> {
+        let transfer_topic = erc20_contract()
+            .event("Transfer")
+            .expect("ERC20 contract abi error")
+            .signature();
+        logs.iter()
+            .enumerate()
+            .filter(|(_, log)| {
+                log.address == self.token
+                    && log.topics.len() == 3
+                    && log.topics[0] == transfer_topic
+                    && Address::from_slice(&log.topics[2].as_bytes()[12..]) == self.treasury
+                    && U256::from_big_endian(&log.data.0) >= self.amount
+            })
+            .map(|(idx, log)| log.log_index.map_or(idx as u64, |index| index.as_u64()))
+            .collect()
+    }
+}
+
+/// Checks whether the contract call failed because of the contract itself (e.g. it's reverted
+/// or returned malformed data), so repeating the call won't change the result.
+fn is_contract_error(err: &anyhow::Error) -> bool {
+    match err.downcast_ref::<web3::contract::Error>() {
+        Some(web3::contract::Error::InvalidOutputType(_)) | Some(web3::contract::Error::Abi(_)) => {
+            true
+        }
+        Some(web3::contract::Error::Api(web3::Error::Rpc(err))) => {
+            // Rate limit errors are reported by some nodes as the RPC errors.
+            let message = err.message.to_lowercase();
+            !(message.contains("rate limit") || message.contains("too many requests"))
+        }
+        _ => false,
+    }
+}
+
+/// Checks the symbol and the decimals read from the token contract, returns the decimals if they are valid.
+fn validate_metadata(symbol: &str, decimals: U256) -> Result<u8, ListingRejection> {
+    if symbol.is_empty()
+        || symbol.len() > MAX_SYMBOL_LENGTH
+        || !symbol.chars().all(|c| c.is_ascii_alphanumeric())
+    {
+        return Err(ListingRejection::InvalidSymbol(symbol.to_string()));
+    }
+    if decimals > U256::from(MAX_DECIMALS) {
+        return Err(ListingRejection::InvalidDecimals(decimals));
+    }
+    Ok(decimals.as_u32() as u8)
+}
+
 struct TokenHandler {
     connection_pool: ConnectionPool,
     poll_interval: std::time::Duration,
@@ -35,6 +140,7 @@ struct TokenHandler {
     token_list: HashMap<Address, TokenInfo>,
     last_eth_block: Option<u64>,
     notifier: Option<Notifier>,
+    listing_fee: Option<ListingFee>,
 }
 
 impl TokenHandler {
@@ -45,6 +151,7 @@ impl TokenHandler {
         config: TokenHandlerConfig,
     ) -> Self {
         let poll_interval = config.poll_interval();
+        let listing_fee = ListingFee::from_config(&config);
         let token_list = config
             .token_list()
             .into_iter()
@@ -60,6 +167,7 @@ impl TokenHandler {
             token_list,
             poll_interval,
             notifier,
+            listing_fee,
             last_eth_block: None, // TODO: Maybe load last viewed Ethereum block number for TokenHandler from DB (ZKS-518).
             eth_watcher_req,
         }
@@ -79,8 +187,10 @@ impl TokenHandler {
         receiver.await.expect("Err response from eth watch")
     }
 
-    async fn is_contract_erc20(&self, address: Address) -> bool {
-        self.eth_client
+    /// Errors are returned only if the check can't be completed, e.g. the Ethereum node is unavailable.
+    async fn is_contract_erc20(&self, address: Address) -> anyhow::Result<bool> {
+        let balance = self
+            .eth_client
             .call_contract_function::<U256, _, _, _>(
                 "balanceOf",
                 address,
@@ -90,8 +200,109 @@ impl TokenHandler {
                 address,
                 erc20_contract(),
             )
-            .await
-            .is_ok()
+            .await;
+        match balance {
+            Ok(_) => Ok(true),
+            Err(err) if is_contract_error(&err) => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Consumes the unused listing fee transfer of the transaction that added the token.
+    /// Returns `false` if there is no such transfer.
+    async fn consume_listing_fee(
+        &self,
+        token_schema: &mut TokensSchema<'_, '_>,
+        listing_fee: &ListingFee,
+        token_event: &NewTokenEvent,
+    ) -> anyhow::Result<bool> {
+        // The transaction is mined, so the missing receipt means the node is lagging behind.
+        let receipt = self
+            .eth_client
+            .tx_receipt(token_event.eth_tx_hash)
+            .await?
+            .ok_or_else(|| {
+                anyhow::format_err!(
+                    "Receipt of the transaction {:?} adding the token {} is not found",
+                    token_event.eth_tx_hash,
+                    token_event.id
+                )
+            })?;
+        for log_index in listing_fee.payments(&receipt.logs) {
+            if token_schema
+                .consume_listing_fee_payment(token_event.eth_tx_hash, log_index, token_event.id)
+                .await?
+            {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    async fn load_token_metadata(&self, address: Address) -> anyhow::Result<(String, U256)> {
+        let symbol: String = self
+            .eth_client
+            .call_contract_function(
+                "symbol",
+                (),
+                None,
+                Options::default(),
+                None,
+                address,
+                erc20_contract(),
+            )
+            .await?;
+        let decimals: U256 = self
+            .eth_client
+            .call_contract_function(
+                "decimals",
+                (),
+                None,
+                Options::default(),
+                None,
+                address,
+                erc20_contract(),
+            )
+            .await?;
+        Ok((symbol, decimals))
+    }
+
+    /// Validates the token that is not in the trusted list, returns its symbol and decimals if it can be listed.
+    /// Errors are returned only if the validation can't be completed, e.g. the Ethereum node is unavailable.
+    async fn validate_listing(
+        &self,
+        token_schema: &mut TokensSchema<'_, '_>,
+        token_event: &NewTokenEvent,
+    ) -> anyhow::Result<Result<(String, u8), ListingRejection>> {
+        if let Some(listing_fee) = &self.listing_fee {
+            if !self
+                .consume_listing_fee(token_schema, listing_fee, token_event)
+                .await?
+            {
+                return Ok(Err(ListingRejection::FeeNotPaid));
+            }
+        }
+
+        let (symbol, decimals) = match self.load_token_metadata(token_event.address).await {
+            Ok(metadata) => metadata,
+            Err(err) if is_contract_error(&err) => {
+                return Ok(Err(ListingRejection::MetadataUnavailable(err.to_string())))
+            }
+            Err(err) => return Err(err),
+        };
+        let decimals = match validate_metadata(&symbol, decimals) {
+            Ok(decimals) => decimals,
+            Err(rejection) => return Ok(Err(rejection)),
+        };
+        if token_schema
+            .get_token(TokenLike::Symbol(symbol.clone()))
+            .await?
+            .is_some()
+        {
+            return Ok(Err(ListingRejection::SymbolTaken(symbol)));
+        }
+
+        Ok(Ok((symbol, decimals)))
     }
 
     async fn save_new_tokens(
@@ -115,7 +326,7 @@ impl TokenHandler {
             let default_symbol = format!("ERC20-{}", token_event.id);
             let default_decimals = 18;
 
-            let is_erc20 = self.is_contract_erc20(token_event.address).await;
+            let is_erc20 = self.is_contract_erc20(token_event.address).await?;
             let token_kind = if is_erc20 {
                 TokenKind::ERC20
             } else {
@@ -169,14 +380,52 @@ impl TokenHandler {
                     }
                 }
                 None => {
-                    // Token with default parameters.
-                    let token = Token::new(
-                        token_event.id,
-                        token_event.address,
-                        &default_symbol,
-                        default_decimals,
-                        token_kind,
-                    );
+                    let listing = self
+                        .validate_listing(&mut token_schema, &token_event)
+                        .await?;
+                    let token = match listing {
+                        Ok((symbol, decimals)) => Token::new(
+                            token_event.id,
+                            token_event.address,
+                            &symbol,
+                            decimals,
+                            token_kind,
+                        ),
+                        Err(rejection) => {
+                            vlog::warn!(
+                                "Token {} ({:?}) is not listed: {}",
+                                token_event.id,
+                                token_event.address,
+                                rejection
+                            );
+                            token_schema
+                                .store_listing_rejection(
+                                    token_event.id,
+                                    token_event.address,
+                                    &rejection.to_string(),
+                                    Some(token_event.eth_tx_hash),
+                                )
+                                .await?;
+                            metrics::increment_counter!("token_handler.rejected_listings");
+
+                            // Token with default parameters, so the operations with it are processed.
+                            let token = Token::new(
+                                token_event.id,
+                                token_event.address,
+                                &default_symbol,
+                                default_decimals,
+                                token_kind,
+                            );
+                            match token_schema.store_token(token).await {
+                                Ok(..) => (),
+                                Err(StoreTokenError::Other(anyhow_err)) => return Err(anyhow_err),
+                                Err(StoreTokenError::TokenAlreadyExistsError(err)) => {
+                                    vlog::warn!("failed to store token in database: {}", err)
+                                }
+                            }
+                            continue;
+                        }
+                    };
                     let try_insert_token = token_schema.store_token(token.clone()).await;
                     match try_insert_token {
                         Ok(..) => (),
@@ -205,7 +454,7 @@ impl TokenHandler {
             let new_tokens_events = self.load_new_token_events().await;
 
             // Ether is a standard token, so we can assume that at least the last token ID is zero.
-            self.last_eth_block = new_tokens_events
+            let last_eth_block = new_tokens_events
                 .iter()
                 .map(|token| token.eth_block_number)
                 .max()
@@ -217,10 +466,16 @@ impl TokenHandler {
                 .await
                 .expect("db connection failed for token handler");
 
-            let new_tokens = self
-                .save_new_tokens(&mut storage, new_tokens_events)
-                .await
-                .expect("failed to add tokens to the database");
+            // The listing can't be validated while the Ethereum node is unavailable,
+            // so the events are processed again on the next iteration.
+            let new_tokens = match self.save_new_tokens(&mut storage, new_tokens_events).await {
+                Ok(new_tokens) => new_tokens,
+                Err(err) => {
+                    vlog::error!("Failed to add tokens to the database: {}", err);
+                    continue;
+                }
+            };
+            self.last_eth_block = last_eth_block;
 
             // Send a notification that the token has been successfully added to the database.
             if let Some(notifier) = &self.notifier {
@@ -252,3 +507,85 @@ pub fn run_token_handler(
         token_handler.run().await
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use web3::types::Bytes;
+
+    fn transfer_log(token: Address, to: Address, amount: U256) -> Log {
+        let mut data = [0u8; 32];
+        amount.to_big_endian(&mut data);
+        Log {
+            address: token,
+            topics: vec![
+                erc20_contract().event("Transfer").unwrap().signature(),
+                H256::from(Address::random()),
+                H256::from(to),
+            ],
+            data: Bytes(data.to_vec()),
+            block_hash: None,
+            block_number: None,
+            transaction_hash: None,
+            transaction_index: None,
+            log_index: None,
+            transaction_log_index: None,
+            log_type: None,
+            removed: None,
+        }
+    }
+
+    #[test]
+    fn listing_validation() {
+        let fee = ListingFee {
+            token: Address::random(),
+            treasury: Address::random(),
+            amount: U256::from(100),
+        };
+        let payment = transfer_log(fee.token, fee.treasury, U256::from(100));
+        assert_eq!(fee.payments(&[payment.clone()]), vec![0]);
+        assert!(fee
+            .payments(&[transfer_log(fee.token, fee.treasury, U256::from(99))])
+            .is_empty());
+        assert!(fee
+            .payments(&[transfer_log(fee.token, Address::random(), fee.amount)])
+            .is_empty());
+        assert!(fee
+            .payments(&[transfer_log(Address::random(), fee.treasury, fee.amount)])
+            .is_empty());
+
+        // Payments are identified by the log index, every one of them can be consumed separately.
+        let mut indexed_payment = payment.clone();
+        indexed_payment.log_index = Some(7.into());
+        let unrelated = transfer_log(Address::random(), fee.treasury, fee.amount);
+        assert_eq!(
+            fee.payments(&[unrelated, payment, indexed_payment]),
+            vec![1, 7]
+        );
+
+        let contract_error = |err| anyhow::Error::from(web3::contract::Error::Api(err));
+        assert!(is_contract_error(&contract_error(web3::Error::Rpc(
+            jsonrpc_core::Error::invalid_params("execution reverted")
+        ))));
+        assert!(!is_contract_error(&contract_error(web3::Error::Rpc(
+            jsonrpc_core::Error::invalid_params("rate limit exceeded")
+        ))));
+        assert!(!is_contract_error(&contract_error(
+            web3::Error::Unreachable
+        )));
+
+        assert_eq!(validate_metadata("USDC", U256::from(6)), Ok(6));
+        assert_eq!(
+            validate_metadata("ERC20-1", U256::from(18)),
+            Err(ListingRejection::InvalidSymbol("ERC20-1".to_string()))
+        );
+        assert_eq!(
+            validate_metadata("", U256::from(18)),
+            Err(ListingRejection::InvalidSymbol(String::new()))
+        );
+        assert_eq!(
+            validate_metadata("BIG", U256::from(19)),
+            Err(ListingRejection::InvalidDecimals(U256::from(19)))
+        );
+    }
+}
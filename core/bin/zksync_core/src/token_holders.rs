@@ -0,0 +1,52 @@
+//! Token holders job periodically counts the accounts holding every token in the finalized state
+//! and groups their balances into buckets, so the token issuers don't have to scrape the whole
+//! account set. The results are served by the tokens API.
+
+// Built-in uses
+use std::{str::FromStr, time::Instant};
+// External uses
+use bigdecimal::BigDecimal;
+use tokio::{task::JoinHandle, time};
+// Workspace uses
+use zksync_config::TokenHoldersConfig;
+use zksync_storage::{ConnectionPool, StorageProcessor};
+
+/// Min balances of the distribution buckets in the token units.
+const BALANCE_THRESHOLDS: &[&str] = &["0", "0.01", "1", "100", "10000", "1000000"];
+
+async fn update_token_holders(storage: &mut StorageProcessor<'_>) -> anyhow::Result<()> {
+    let start = Instant::now();
+    let thresholds = BALANCE_THRESHOLDS
+        .iter()
+        .map(|threshold| BigDecimal::from_str(threshold))
+        .collect::<Result<Vec<_>, _>>()?;
+    storage
+        .tokens_schema()
+        .update_token_holders(&thresholds)
+        .await?;
+
+    metrics::histogram!("token_holders.update", start.elapsed());
+    vlog::debug!("Token holders are updated in {:?}", start.elapsed());
+    Ok(())
+}
+
+#[must_use]
+pub fn run_token_holders_updater(
+    config: TokenHoldersConfig,
+    db_pool: ConnectionPool,
+) -> JoinHandle<()> {
+    let mut timer = time::interval(config.update_interval());
+
+    tokio::spawn(async move {
+        loop {
+            timer.tick().await;
+            let mut storage = db_pool
+                .access_storage()
+                .await
+                .expect("token holders job couldn't access the database");
+            if let Err(e) = update_token_holders(&mut storage).await {
+                vlog::error!("Failed to update the token holders: {:?}", e);
+            }
+        }
+    })
+}
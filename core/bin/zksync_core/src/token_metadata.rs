@@ -0,0 +1,249 @@
+//! Token metadata updater enriches the tokens known to the network with their metadata.
+//!
+//! Name and decimals are read from the token contracts, so the decimals the network uses can be
+//! verified against the on-chain ones. Logos and descriptions are taken from the curated token list
+//! in the Uniswap token list format, if one is configured. The metadata is served by the tokens API,
+//! so the wallets don't have to maintain their own token lists.
+
+// Built-in uses
+use std::{collections::HashMap, time::Duration};
+// External uses
+use serde::Deserialize;
+use tokio::{task::JoinHandle, time};
+use web3::contract::Options;
+// Workspace uses
+use zksync_config::TokenMetadataConfig;
+use zksync_contracts::erc20_contract;
+use zksync_eth_client::EthereumGateway;
+use zksync_storage::ConnectionPool;
+use zksync_types::{tokens::TokenMetadata, Address, Token, U256};
+
+/// Timeout for loading the curated token list.
+const TOKEN_LIST_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Deserialize)]
+struct TokenList {
+    tokens: Vec<TokenListEntry>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TokenListEntry {
+    chain_id: u64,
+    address: Address,
+    name: String,
+    #[serde(default, rename = "logoURI")]
+    logo_uri: Option<String>,
+    #[serde(default)]
+    extensions: TokenListExtensions,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+struct TokenListExtensions {
+    #[serde(default)]
+    description: Option<String>,
+}
+
+/// Parses the token list, skipping the tokens of the other networks.
+fn parse_token_list(
+    contents: &str,
+    chain_id: u64,
+) -> anyhow::Result<HashMap<Address, TokenListEntry>> {
+    let list: TokenList = serde_json::from_str(contents)?;
+    Ok(list
+        .tokens
+        .into_iter()
+        .filter(|entry| entry.chain_id == chain_id)
+        .map(|entry| (entry.address, entry))
+        .collect())
+}
+
+/// Combines the metadata read from the token contract with the one of the curated list.
+/// The on-chain name takes precedence over the listed one.
+fn merge_metadata(
+    onchain_name: Option<String>,
+    onchain_decimals: Option<u8>,
+    entry: Option<&TokenListEntry>,
+) -> TokenMetadata {
+    TokenMetadata {
+        name: onchain_name.or_else(|| entry.map(|entry| entry.name.clone())),
+        onchain_decimals,
+        logo_url: entry.and_then(|entry| entry.logo_uri.clone()),
+        description: entry.and_then(|entry| entry.extensions.description.clone()),
+    }
+}
+
+struct TokenMetadataUpdater {
+    config: TokenMetadataConfig,
+    chain_id: u64,
+    db_pool: ConnectionPool,
+    eth_gateway: EthereumGateway,
+    http_client: reqwest::Client,
+}
+
+impl TokenMetadataUpdater {
+    async fn load_token_list(&self) -> anyhow::Result<HashMap<Address, TokenListEntry>> {
+        let url = match &self.config.token_list_url {
+            Some(url) => url,
+            None => return Ok(HashMap::new()),
+        };
+        let contents = self
+            .http_client
+            .get(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        parse_token_list(&contents, self.chain_id)
+    }
+
+    /// Reads the name and the decimals from the token contract, either is `None`
+    /// if the contract doesn't implement the corresponding method.
+    async fn load_onchain_metadata(&self, token: &Token) -> (Option<String>, Option<u8>) {
+        // Ether has no contract.
+        if token.address == Address::zero() {
+            return (Some("Ether".to_string()), Some(18));
+        }
+
+        let name = self
+            .eth_gateway
+            .call_contract_function::<String, _, _, _>(
+                "name",
+                (),
+                None,
+                Options::default(),
+                None,
+                token.address,
+                erc20_contract(),
+            )
+            .await
+            .ok();
+        let decimals = self
+            .eth_gateway
+            .call_contract_function::<U256, _, _, _>(
+                "decimals",
+                (),
+                None,
+                Options::default(),
+                None,
+                token.address,
+                erc20_contract(),
+            )
+            .await
+            .ok()
+            .filter(|decimals| *decimals <= U256::from(u8::MAX))
+            .map(|decimals| decimals.as_u32() as u8);
+        (name, decimals)
+    }
+
+    async fn update_metadata(&self) -> anyhow::Result<()> {
+        let token_list = self.load_token_list().await.unwrap_or_else(|err| {
+            vlog::warn!("Failed to load the curated token list: {}", err);
+            HashMap::new()
+        });
+
+        let mut storage = self.db_pool.access_storage().await?;
+        let tokens = storage.tokens_schema().load_tokens().await?;
+        for token in tokens.values() {
+            let (name, decimals) = self.load_onchain_metadata(token).await;
+            if matches!(decimals, Some(decimals) if decimals != token.decimals) {
+                vlog::warn!(
+                    "Token {} ({}) has {} decimals known to the network, but {:?} on-chain",
+                    token.id,
+                    token.symbol,
+                    token.decimals,
+                    decimals
+                );
+            }
+
+            // Values which failed to load don't overwrite the previously stored ones.
+            let metadata = merge_metadata(name, decimals, token_list.get(&token.address));
+            storage
+                .tokens_schema()
+                .store_token_metadata(token.id, &metadata)
+                .await?;
+        }
+
+        metrics::gauge!("token_metadata.updated_tokens", tokens.len() as f64);
+        Ok(())
+    }
+}
+
+#[must_use]
+pub fn run_token_metadata_updater(
+    config: TokenMetadataConfig,
+    chain_id: u64,
+    db_pool: ConnectionPool,
+    eth_gateway: EthereumGateway,
+) -> JoinHandle<()> {
+    let mut timer = time::interval(config.update_interval());
+    let updater = TokenMetadataUpdater {
+        config,
+        chain_id,
+        db_pool,
+        eth_gateway,
+        http_client: reqwest::Client::builder()
+            .timeout(TOKEN_LIST_TIMEOUT)
+            .build()
+            .expect("failed to build the HTTP client"),
+    };
+
+    tokio::spawn(async move {
+        loop {
+            timer.tick().await;
+            if let Err(e) = updater.update_metadata().await {
+                vlog::error!("Failed to update the token metadata: {:?}", e);
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_list_metadata() {
+        let usdc: Address = "a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".parse().unwrap();
+        let contents = r#"{
+            "name": "Curated",
+            "tokens": [
+                {
+                    "chainId": 1,
+                    "address": "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48",
+                    "name": "USD Coin",
+                    "symbol": "USDC",
+                    "decimals": 6,
+                    "logoURI": "https://example.com/usdc.png",
+                    "extensions": { "description": "Fully reserved stablecoin" }
+                },
+                {
+                    "chainId": 4,
+                    "address": "0xeb8f08a975ab53e34d8a0330e0d34de942c95926",
+                    "name": "USD Coin",
+                    "symbol": "USDC",
+                    "decimals": 6
+                }
+            ]
+        }"#;
+        let token_list = parse_token_list(contents, 1).unwrap();
+        assert_eq!(token_list.len(), 1);
+
+        let metadata = merge_metadata(None, Some(6), token_list.get(&usdc));
+        assert_eq!(
+            metadata,
+            TokenMetadata {
+                name: Some("USD Coin".to_string()),
+                onchain_decimals: Some(6),
+                logo_url: Some("https://example.com/usdc.png".to_string()),
+                description: Some("Fully reserved stablecoin".to_string()),
+            }
+        );
+
+        // On-chain name takes precedence, unlisted tokens have no logo.
+        let metadata = merge_metadata(Some("USDC".to_string()), None, None);
+        assert_eq!(metadata.name.as_deref(), Some("USDC"));
+        assert_eq!(metadata.logo_url, None);
+    }
+}
@@ -3,10 +3,11 @@ use std::collections::VecDeque;
 use std::str::FromStr;
 // External uses
 use num::BigUint;
-use zksync_basic_types::{H256, U256};
+use zksync_basic_types::{Address, BlockNumber, H256, U256};
 // Workspace uses
+use zksync_crypto::proof::AggregatedProof;
 use zksync_storage::{ConnectionPool, StorageProcessor};
-use zksync_types::ethereum::{ETHOperation, EthOpId, InsertedOperationResponse};
+use zksync_types::ethereum::{ETHOperation, EthOpId, EthTxReplacement, InsertedOperationResponse};
 // Local uses
 use super::transactions::ETHStats;
 use zksync_types::aggregated_operations::{AggregatedActionType, AggregatedOperation};
@@ -47,7 +48,34 @@ pub(super) trait DatabaseInterface {
         operations_id: Vec<i64>,
     ) -> anyhow::Result<()>;
 
+    /// Loads the aggregated proof for the given blocks range.
+    async fn load_aggregated_proof(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        first_block: BlockNumber,
+        last_block: BlockNumber,
+    ) -> anyhow::Result<Option<AggregatedProof>>;
+
+    /// Moves the invalid aggregated proof for the given blocks range to the quarantine,
+    /// so that it's generated again.
+    async fn quarantine_aggregated_proof(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        first_block: BlockNumber,
+        last_block: BlockNumber,
+    ) -> anyhow::Result<()>;
+
+    /// Replaces the stored aggregated operation with the updated one.
+    async fn update_aggregated_operation(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        id: i64,
+        operation: &AggregatedOperation,
+    ) -> anyhow::Result<()>;
+
     /// Saves a new unconfirmed operation to the database.
+    /// `sender` is the additional operator account, `None` stands for the main one.
+    #[allow(clippy::too_many_arguments)]
     async fn save_new_eth_tx(
         &self,
         connection: &mut StorageProcessor<'_>,
@@ -56,8 +84,49 @@ pub(super) trait DatabaseInterface {
         deadline_block: i64,
         used_gas_price: U256,
         raw_tx: Vec<u8>,
+        sender: Option<Address>,
     ) -> anyhow::Result<InsertedOperationResponse>;
 
+    /// Binds the operation sent within the multicall transaction of the stored Ethereum operation.
+    async fn add_bundled_operation(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        eth_op_id: i64,
+        op: (i64, AggregatedOperation),
+    ) -> anyhow::Result<()>;
+
+    /// Stores the initial nonce of the additional operator account, unless it's already stored.
+    async fn initialize_operator_nonce(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        address: Address,
+        nonce: i64,
+    ) -> anyhow::Result<()>;
+
+    /// Loads the next nonce of the operator account without updating it.
+    /// `sender` is the additional operator account, `None` stands for the main one.
+    async fn load_next_nonce(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        sender: Option<Address>,
+    ) -> anyhow::Result<i64>;
+
+    /// Overwrites the next nonce of the operator account.
+    async fn set_next_nonce(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        sender: Option<Address>,
+        nonce: i64,
+    ) -> anyhow::Result<()>;
+
+    /// Assigns a new nonce to the unconfirmed Ethereum operation.
+    async fn update_eth_tx_nonce(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        eth_op_id: EthOpId,
+        nonce: i64,
+    ) -> anyhow::Result<()>;
+
     /// Adds a tx hash entry associated with some Ethereum operation to the database.
     async fn add_hash_entry(
         &self,
@@ -83,6 +152,50 @@ pub(super) trait DatabaseInterface {
         op: &ETHOperation,
     ) -> anyhow::Result<()>;
 
+    /// Removes the pending transaction replacement requests and returns them.
+    async fn take_replacement_requests(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Vec<(EthOpId, EthTxReplacement)>>;
+
+    /// Stores the hash of the transaction cancelling the operation.
+    async fn set_cancel_tx_hash(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        eth_op_id: EthOpId,
+        hash: &H256,
+    ) -> anyhow::Result<()>;
+
+    /// Marks an operation as completed by the cancelling transaction, so the associated
+    /// zkSync operations are considered unprocessed.
+    async fn cancel_operation(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        hash: &H256,
+        op: &ETHOperation,
+    ) -> anyhow::Result<()>;
+
+    /// Stores the gas used by the confirmed transaction of the aggregated operation of the given size.
+    async fn save_gas_usage(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        action_type: AggregatedActionType,
+        size: i64,
+        gas_used: i64,
+    ) -> anyhow::Result<()>;
+
+    /// Loads the gas used by at most `limit` latest confirmed transactions of the given type
+    /// with the operation size within the `[min_size, max_size]` range, as pairs of the
+    /// operation size and the gas used.
+    async fn load_gas_usage(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        action_type: AggregatedActionType,
+        min_size: i64,
+        max_size: i64,
+        limit: i64,
+    ) -> anyhow::Result<Vec<(i64, i64)>>;
+
     /// Loads the stored Ethereum operations stats.
     async fn load_stats(&self, connection: &mut StorageProcessor<'_>) -> anyhow::Result<ETHStats>;
 
@@ -177,6 +290,47 @@ impl DatabaseInterface for Database {
         Ok(())
     }
 
+    async fn load_aggregated_proof(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        first_block: BlockNumber,
+        last_block: BlockNumber,
+    ) -> anyhow::Result<Option<AggregatedProof>> {
+        let proof = connection
+            .prover_schema()
+            .load_aggregated_proof(first_block, last_block)
+            .await?;
+
+        Ok(proof)
+    }
+
+    async fn quarantine_aggregated_proof(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        first_block: BlockNumber,
+        last_block: BlockNumber,
+    ) -> anyhow::Result<()> {
+        connection
+            .prover_schema()
+            .quarantine_aggregated_proof(first_block, last_block)
+            .await?;
+        Ok(())
+    }
+
+    async fn update_aggregated_operation(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        id: i64,
+        operation: &AggregatedOperation,
+    ) -> anyhow::Result<()> {
+        connection
+            .chain()
+            .operations_schema()
+            .update_aggregated_operation(id, operation)
+            .await?;
+        Ok(())
+    }
+
     async fn save_new_eth_tx(
         &self,
         connection: &mut StorageProcessor<'_>,
@@ -185,6 +339,7 @@ impl DatabaseInterface for Database {
         deadline_block: i64,
         used_gas_price: U256,
         raw_tx: Vec<u8>,
+        sender: Option<Address>,
     ) -> anyhow::Result<InsertedOperationResponse> {
         let result = connection
             .ethereum_schema()
@@ -194,12 +349,69 @@ impl DatabaseInterface for Database {
                 deadline_block,
                 BigUint::from_str(&used_gas_price.to_string()).unwrap(),
                 raw_tx,
+                sender,
             )
             .await?;
 
         Ok(result)
     }
 
+    async fn initialize_operator_nonce(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        address: Address,
+        nonce: i64,
+    ) -> anyhow::Result<()> {
+        Ok(connection
+            .ethereum_schema()
+            .initialize_operator_nonce(address, nonce)
+            .await?)
+    }
+
+    async fn load_next_nonce(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        sender: Option<Address>,
+    ) -> anyhow::Result<i64> {
+        Ok(connection.ethereum_schema().load_next_nonce(sender).await?)
+    }
+
+    async fn set_next_nonce(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        sender: Option<Address>,
+        nonce: i64,
+    ) -> anyhow::Result<()> {
+        Ok(connection
+            .ethereum_schema()
+            .set_next_nonce(sender, nonce)
+            .await?)
+    }
+
+    async fn update_eth_tx_nonce(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        eth_op_id: EthOpId,
+        nonce: i64,
+    ) -> anyhow::Result<()> {
+        Ok(connection
+            .ethereum_schema()
+            .update_eth_tx_nonce(eth_op_id, nonce)
+            .await?)
+    }
+
+    async fn add_bundled_operation(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        eth_op_id: i64,
+        op: (i64, AggregatedOperation),
+    ) -> anyhow::Result<()> {
+        Ok(connection
+            .ethereum_schema()
+            .add_bundled_operation(eth_op_id, op)
+            .await?)
+    }
+
     async fn add_hash_entry(
         &self,
         connection: &mut StorageProcessor<'_>,
@@ -259,57 +471,60 @@ impl DatabaseInterface for Database {
     ) -> anyhow::Result<()> {
         let mut transaction = connection.start_transaction().await?;
 
-        match &op.op {
-            Some((_, AggregatedOperation::CommitBlocks(op))) => {
-                let (first_block, last_block) = op.block_range();
-
-                self.set_metrics(&op.blocks, "L1_commit".to_string()).await;
-                transaction
-                    .chain()
-                    .operations_schema()
-                    .confirm_aggregated_operations(
-                        first_block,
-                        last_block,
-                        AggregatedActionType::CommitBlocks,
-                    )
-                    .await?;
-            }
-            Some((_, AggregatedOperation::PublishProofBlocksOnchain(op))) => {
-                let (first_block, last_block) = op.block_range();
-                self.set_metrics(&op.blocks, "L1_publish_proof".to_string())
-                    .await;
-                transaction
-                    .chain()
-                    .operations_schema()
-                    .confirm_aggregated_operations(
-                        first_block,
-                        last_block,
-                        AggregatedActionType::PublishProofBlocksOnchain,
-                    )
-                    .await?;
-            }
-            Some((_, AggregatedOperation::ExecuteBlocks(op))) => {
-                let (first_block, last_block) = op.block_range();
-                self.set_metrics(&op.blocks, "L1_execute".to_string()).await;
-                for block in &op.blocks {
+        // The multicall transaction confirms all the operations sent within it.
+        for (_, aggregated_op) in op.op.iter().chain(&op.bundled_ops) {
+            match aggregated_op {
+                AggregatedOperation::CommitBlocks(op) => {
+                    let (first_block, last_block) = op.block_range();
+
+                    self.set_metrics(&op.blocks, "L1_commit".to_string()).await;
+                    transaction
+                        .chain()
+                        .operations_schema()
+                        .confirm_aggregated_operations(
+                            first_block,
+                            last_block,
+                            AggregatedActionType::CommitBlocks,
+                        )
+                        .await?;
+                }
+                AggregatedOperation::PublishProofBlocksOnchain(op) => {
+                    let (first_block, last_block) = op.block_range();
+                    self.set_metrics(&op.blocks, "L1_publish_proof".to_string())
+                        .await;
                     transaction
                         .chain()
-                        .state_schema()
-                        .apply_state_update(block.block_number)
+                        .operations_schema()
+                        .confirm_aggregated_operations(
+                            first_block,
+                            last_block,
+                            AggregatedActionType::PublishProofBlocksOnchain,
+                        )
                         .await?;
                 }
+                AggregatedOperation::ExecuteBlocks(op) => {
+                    let (first_block, last_block) = op.block_range();
+                    self.set_metrics(&op.blocks, "L1_execute".to_string()).await;
+                    for block in &op.blocks {
+                        transaction
+                            .chain()
+                            .state_schema()
+                            .apply_state_update(block.block_number)
+                            .await?;
+                    }
 
-                transaction
-                    .chain()
-                    .operations_schema()
-                    .confirm_aggregated_operations(
-                        first_block,
-                        last_block,
-                        AggregatedActionType::ExecuteBlocks,
-                    )
-                    .await?;
+                    transaction
+                        .chain()
+                        .operations_schema()
+                        .confirm_aggregated_operations(
+                            first_block,
+                            last_block,
+                            AggregatedActionType::ExecuteBlocks,
+                        )
+                        .await?;
+                }
+                _ => {}
             }
-            _ => {}
         }
 
         transaction.ethereum_schema().confirm_eth_tx(hash).await?;
@@ -318,6 +533,67 @@ impl DatabaseInterface for Database {
         Ok(())
     }
 
+    async fn take_replacement_requests(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Vec<(EthOpId, EthTxReplacement)>> {
+        Ok(connection
+            .ethereum_schema()
+            .take_eth_tx_replacement_requests()
+            .await?)
+    }
+
+    async fn set_cancel_tx_hash(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        eth_op_id: EthOpId,
+        hash: &H256,
+    ) -> anyhow::Result<()> {
+        Ok(connection
+            .ethereum_schema()
+            .set_cancel_tx_hash(eth_op_id, hash)
+            .await?)
+    }
+
+    async fn cancel_operation(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        hash: &H256,
+        op: &ETHOperation,
+    ) -> anyhow::Result<()> {
+        Ok(connection
+            .ethereum_schema()
+            .cancel_eth_tx(op.id, hash)
+            .await?)
+    }
+
+    async fn save_gas_usage(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        action_type: AggregatedActionType,
+        size: i64,
+        gas_used: i64,
+    ) -> anyhow::Result<()> {
+        Ok(connection
+            .ethereum_schema()
+            .save_gas_usage(action_type, size, gas_used)
+            .await?)
+    }
+
+    async fn load_gas_usage(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        action_type: AggregatedActionType,
+        min_size: i64,
+        max_size: i64,
+        limit: i64,
+    ) -> anyhow::Result<Vec<(i64, i64)>> {
+        Ok(connection
+            .ethereum_schema()
+            .load_gas_usage(action_type, min_size, max_size, limit)
+            .await?)
+    }
+
     async fn load_stats(&self, connection: &mut StorageProcessor<'_>) -> anyhow::Result<ETHStats> {
         let stats = connection.ethereum_schema().load_stats().await?;
         Ok(stats.into())
@@ -356,6 +632,12 @@ impl Database {
                 ];
 
                 metrics::histogram!("process_tx", tx.elapsed(), &labels);
+                if let Some(tx) = tx.get_executed_tx() {
+                    vlog::tx_event(
+                        &format!("eth_sender.{}", stage),
+                        tx.signed_tx.hash().as_ref(),
+                    );
+                }
             }
             let labels = vec![("stage", stage.clone())];
             metrics::histogram!("process_block", block.elapsed(), &labels)
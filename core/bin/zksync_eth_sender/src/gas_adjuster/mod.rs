@@ -4,8 +4,10 @@ use std::{collections::VecDeque, marker::PhantomData, time::Instant};
 use zksync_basic_types::U256;
 use zksync_eth_client::EthereumGateway;
 // Local deps
+use self::oracle::{GasPriceOracle, NodeOracle};
 use crate::database::DatabaseInterface;
 
+pub(crate) mod oracle;
 mod parameters;
 
 #[cfg(test)]
@@ -28,6 +30,8 @@ pub(super) struct GasAdjuster<DB: DatabaseInterface> {
     last_price_renewal: Instant,
     /// Timestamp of the last sample added to the `statistics`.
     last_sample_added: Instant,
+    /// Source of the network gas price.
+    oracle: Box<dyn GasPriceOracle>,
     _db: PhantomData<DB>,
 }
 
@@ -45,11 +49,18 @@ impl<DB: DatabaseInterface> GasAdjuster<DB> {
             statistics: GasStatistics::new(gas_price_limit),
             last_price_renewal: Instant::now(),
             last_sample_added: Instant::now(),
+            oracle: Box::new(NodeOracle),
 
             _db: PhantomData,
         }
     }
 
+    /// Replaces the default source of the network gas price (`eth_gasPrice` of the node).
+    pub fn with_oracle(mut self, oracle: Box<dyn GasPriceOracle>) -> Self {
+        self.oracle = oracle;
+        self
+    }
+
     async fn get_suggested_price(
         &self,
         ethereum: &EthereumGateway,
@@ -59,7 +70,7 @@ impl<DB: DatabaseInterface> GasAdjuster<DB> {
             return Ok(price);
         }
 
-        let network_price = ethereum.get_gas_price().await?;
+        let network_price = self.oracle.gas_price(ethereum).await?;
         let scaled_price = if let Some(old_price) = old_tx_gas_price {
             // Stuck transaction, scale it up.
             self.scale_up(old_price, network_price)
@@ -99,7 +110,7 @@ impl<DB: DatabaseInterface> GasAdjuster<DB> {
     pub async fn keep_updated(&mut self, ethereum: &EthereumGateway, db: &DB) {
         if self.last_sample_added.elapsed() >= parameters::sample_adding_interval() {
             // Report the current price to be gathered by the statistics module.
-            match ethereum.get_gas_price().await {
+            match self.oracle.gas_price(ethereum).await {
                 Ok(network_price) => {
                     self.statistics.add_sample(network_price);
 
@@ -0,0 +1,130 @@
+//! `oracle` module provides the sources of the network gas price for `GasAdjuster`.
+//!
+//! Currently the following oracles are provided:
+//! - `NodeOracle`: `eth_gasPrice` of the Ethereum node.
+//! - `HttpOracle`: external gas price oracle accessed via HTTP.
+//! - `PercentileOracle`: base fee of the next block increased by the median of the
+//!   priority fees paid at the configured percentile within the recent blocks.
+
+// Built-in deps
+use std::time::Duration;
+// External deps
+use anyhow::format_err;
+use async_trait::async_trait;
+use zksync_basic_types::U256;
+use zksync_config::configs::eth_sender::{
+    GasPriceOracle as GasPriceOracleConfig, GasPriceOracleKind,
+};
+use zksync_eth_client::{ethereum_gateway::FeeHistory, EthereumGateway};
+
+/// Timeout for the requests to the external gas price oracle.
+const HTTP_ORACLE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Source of the current network gas price.
+#[async_trait]
+pub(crate) trait GasPriceOracle: std::fmt::Debug + Send + Sync {
+    /// Returns the gas price in wei suitable for the transaction to be mined soon.
+    async fn gas_price(&self, ethereum: &EthereumGateway) -> anyhow::Result<U256>;
+}
+
+/// Creates the gas price oracle according to the configuration.
+pub(crate) fn gas_price_oracle(config: &GasPriceOracleConfig) -> Box<dyn GasPriceOracle> {
+    match config.kind {
+        GasPriceOracleKind::Node => Box::new(NodeOracle),
+        GasPriceOracleKind::Http => Box::new(HttpOracle {
+            client: reqwest::Client::new(),
+            url: config.http_url.clone(),
+            json_pointer: config.http_json_pointer.clone(),
+            price_multiplier: config.http_price_multiplier,
+        }),
+        GasPriceOracleKind::Percentile => Box::new(PercentileOracle {
+            blocks: config.percentile_blocks,
+            percentile: config.percentile,
+        }),
+    }
+}
+
+/// Oracle that uses the gas price suggested by the Ethereum node.
+#[derive(Debug, Default)]
+pub(crate) struct NodeOracle;
+
+#[async_trait]
+impl GasPriceOracle for NodeOracle {
+    async fn gas_price(&self, ethereum: &EthereumGateway) -> anyhow::Result<U256> {
+        ethereum.get_gas_price().await
+    }
+}
+
+/// Oracle that requests the gas price from an external HTTP service.
+#[derive(Debug)]
+pub(crate) struct HttpOracle {
+    client: reqwest::Client,
+    url: String,
+    json_pointer: String,
+    price_multiplier: f64,
+}
+
+#[async_trait]
+impl GasPriceOracle for HttpOracle {
+    async fn gas_price(&self, _ethereum: &EthereumGateway) -> anyhow::Result<U256> {
+        let response: serde_json::Value = self
+            .client
+            .get(&self.url)
+            .timeout(HTTP_ORACLE_TIMEOUT)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let price = response
+            .pointer(&self.json_pointer)
+            .and_then(|value| value.as_f64().or_else(|| value.as_str()?.parse().ok()))
+            .ok_or_else(|| {
+                format_err!(
+                    "Gas price oracle response has no numeric value at '{}'",
+                    self.json_pointer
+                )
+            })?;
+        price_to_wei(price, self.price_multiplier)
+    }
+}
+
+/// Oracle that estimates the gas price from the fee history of the recent blocks.
+#[derive(Debug)]
+pub(crate) struct PercentileOracle {
+    blocks: u64,
+    percentile: f64,
+}
+
+#[async_trait]
+impl GasPriceOracle for PercentileOracle {
+    async fn gas_price(&self, ethereum: &EthereumGateway) -> anyhow::Result<U256> {
+        let history = ethereum.fee_history(self.blocks, self.percentile).await?;
+        estimate_from_fee_history(&history)
+            .ok_or_else(|| format_err!("Fee history contains no blocks"))
+    }
+}
+
+/// Converts the price reported by the external oracle into wei.
+pub(crate) fn price_to_wei(price: f64, multiplier: f64) -> anyhow::Result<U256> {
+    let wei = (price * multiplier).round();
+    if !wei.is_finite() || wei < 0.0 {
+        anyhow::bail!("Invalid gas price reported by the oracle: {}", price);
+    }
+    Ok(U256::from(wei as u128))
+}
+
+/// Sums up the base fee of the next block and the median of the priority fees
+/// paid within the recent blocks.
+pub(crate) fn estimate_from_fee_history(history: &FeeHistory) -> Option<U256> {
+    let next_base_fee = *history.base_fee_per_gas.last()?;
+
+    let mut priority_fees = history.priority_fees.clone();
+    priority_fees.sort();
+    let priority_fee = priority_fees
+        .get(priority_fees.len() / 2)
+        .copied()
+        .unwrap_or_default();
+
+    Some(next_base_fee + priority_fee)
+}
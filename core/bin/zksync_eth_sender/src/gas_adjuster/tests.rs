@@ -1,9 +1,15 @@
 // Built-in uses
 // Workspace uses
 use zksync_basic_types::U256;
+use zksync_config::configs::eth_sender::{GasPriceOracle, GasPriceOracleKind};
+use zksync_eth_client::ethereum_gateway::FeeHistory;
 // Local uses
 use crate::{
-    gas_adjuster::{parameters::limit_scale_factor, GasStatistics},
+    gas_adjuster::{
+        oracle::{estimate_from_fee_history, gas_price_oracle, price_to_wei},
+        parameters::limit_scale_factor,
+        GasStatistics,
+    },
     tests::mock::{default_eth_sender, MockDatabase},
     DatabaseInterface, GasAdjuster,
 };
@@ -378,3 +384,66 @@ async fn gas_price_limit_preservation() {
         assert_eq!(new_limit, price_limit.into());
     }
 }
+
+/// Checks that the percentile oracle suggests the base fee of the next block increased
+/// by the median of the recent priority fees.
+#[test]
+fn percentile_oracle_estimation() {
+    let history = FeeHistory {
+        base_fee_per_gas: vec![90.into(), 95.into(), 100.into()],
+        priority_fees: vec![7.into(), 2.into(), 3.into()],
+    };
+    assert_eq!(estimate_from_fee_history(&history), Some(103.into()));
+
+    let no_priority_fees = FeeHistory {
+        base_fee_per_gas: vec![100.into()],
+        priority_fees: Vec::new(),
+    };
+    assert_eq!(
+        estimate_from_fee_history(&no_priority_fees),
+        Some(100.into())
+    );
+
+    assert_eq!(estimate_from_fee_history(&FeeHistory::default()), None);
+}
+
+/// Checks that the prices reported by the external oracle are converted into wei.
+#[test]
+fn http_oracle_price_conversion() {
+    // `ethgasstation` reports prices in tenths of gwei.
+    assert_eq!(
+        price_to_wei(425.0, 100_000_000.0).unwrap(),
+        U256::from(42_500_000_000u64)
+    );
+    assert_eq!(price_to_wei(1.5, 1.0).unwrap(), U256::from(2));
+    assert!(price_to_wei(-1.0, 1.0).is_err());
+    assert!(price_to_wei(f64::NAN, 1.0).is_err());
+}
+
+/// Checks that `GasAdjuster` uses the configured oracle as the source of the network price.
+#[tokio::test]
+async fn custom_oracle() {
+    let (mut ethereum, db) = eth_and_db_clients().await;
+    ethereum
+        .get_mut_mock()
+        .unwrap()
+        .set_gas_price(1000.into())
+        .await
+        .unwrap();
+
+    let config = GasPriceOracle {
+        kind: GasPriceOracleKind::Percentile,
+        http_url: Default::default(),
+        http_json_pointer: Default::default(),
+        http_price_multiplier: 1.0,
+        percentile_blocks: 20,
+        percentile: 60.0,
+    };
+    let mut gas_adjuster: GasAdjuster<MockDatabase> = GasAdjuster::new(&db)
+        .await
+        .with_oracle(gas_price_oracle(&config));
+
+    // Mock fee history has no priority fees and the base fee equal to the node gas price.
+    let price = gas_adjuster.get_gas_price(&ethereum, None).await.unwrap();
+    assert_eq!(price, 1000.into());
+}
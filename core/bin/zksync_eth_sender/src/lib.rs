@@ -4,24 +4,28 @@
 //! every transaction is executed successfully and confirmed.
 
 // Built-in deps
-use std::collections::VecDeque;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::time::{Duration, Instant};
 // External uses
 use anyhow::format_err;
+use ethabi::{ParamType, Token};
 use tokio::{task::JoinHandle, time};
 use web3::{
     contract::Options,
-    types::{TransactionReceipt, H256, U256},
+    types::{Address, TransactionReceipt, H256, U256},
 };
 // Workspace uses
-use zksync_config::ETHSenderConfig;
-use zksync_eth_client::{EthereumGateway, SignedCallResult};
-use zksync_storage::ConnectionPool;
-use zksync_types::ethereum::ETHOperation;
+use zksync_config::{configs::eth_sender::Sender, ETHSenderConfig};
+use zksync_eth_client::{ethereum_gateway::CallOutcome, EthereumGateway, SignedCallResult};
+use zksync_prover_utils::aggregated_proofs::AggregatedProofVerifier;
+use zksync_storage::{ConnectionPool, StorageProcessor};
+use zksync_types::ethereum::{ETHOperation, EthOpId, EthTxReplacement};
 // Local uses
 use self::{
     database::{Database, DatabaseInterface},
     gas_adjuster::GasAdjuster,
+    operator_keys::OperatorKeys,
+    proof_verifier::ProofVerifier,
     transactions::*,
     tx_queue::{TxData, TxQueue, TxQueueBuilder},
 };
@@ -32,6 +36,8 @@ use zksync_types::{
 
 mod database;
 mod gas_adjuster;
+mod operator_keys;
+mod proof_verifier;
 mod transactions;
 mod tx_queue;
 
@@ -42,6 +48,19 @@ mod tests;
 const RATE_LIMIT_BACKOFF_PERIOD: Duration = Duration::from_secs(30);
 /// Rate limit error will contain this response code
 const RATE_LIMIT_HTTP_CODE: &str = "429";
+/// Type of the EIP-1559 transactions.
+const EIP1559_TX_TYPE: u64 = 2;
+/// Additional gas reserved for forwarding every call within the multicall transaction.
+const MULTICALL_GAS_PER_CALL: u64 = 10_000;
+/// Gas limit of the self-send transaction cancelling the operation or filling the nonce gap.
+const SELF_SEND_TX_GAS_LIMIT: u64 = 21_000;
+/// Interval between the reconciliations of the stored nonces with the chain.
+const NONCE_RECONCILIATION_INTERVAL: Duration = Duration::from_secs(60);
+/// Maximum deviation (in percent) of the size of the previous operations considered similar
+/// to the operation which gas limit is estimated.
+const GAS_USAGE_SIZE_TOLERANCE: i64 = 25;
+/// Maximum amount of the previous transactions the gas limit is estimated from.
+const GAS_USAGE_SAMPLES: i64 = 20;
 
 /// `TxCheckMode` enum determines the policy on the obtaining the tx status.
 /// The latest sent transaction can be pending (we're still waiting for it),
@@ -102,6 +121,47 @@ enum TxCheckMode {
 /// 2. Withdraw operations (only if both commit/verify for the same block operations were sent).
 /// 3. Commit operations.
 ///
+/// # Transaction replacement
+///
+/// Operator may request (via the private API) to replace the transaction of the ongoing
+/// operation without waiting for the deadline block:
+///
+/// - `resend` sends the transaction again with the increased fee.
+/// - `cancel` sends a self-send transaction with the increased fee at the same nonce. Once it's
+///   confirmed, the zkSync operations are returned to the queue and sent again later.
+///   Only the latest sent operation can be cancelled, and no new operations are sent until
+///   the cancellation is completed, since they would be reverted by the contract.
+///
+/// # Nonce reconciliation
+///
+/// On startup and then periodically the stored nonces of the operator accounts are compared
+/// with the nonces known to the Ethereum node (including the pending transactions):
+///
+/// - Nonces used by the transactions sent from the operator account bypassing `ETHSender`
+///   are skipped.
+/// - Operations which nonce was used by such a transaction are sent again along with the following
+///   pending operations of the account, so that they're still executed in order: every operation
+///   takes the nonce of the next one (replacing its pending transaction) and the last one takes
+///   a new nonce.
+/// - Unused nonces preceding the sent transactions are filled with self-send transactions,
+///   otherwise the node would never include the following transactions.
+/// - Unused nonces following the latest sent transaction are reused.
+///
+/// # Dry-run mode
+///
+/// If `dry_run` is enabled in the configuration, the transactions are built and simulated
+/// via `eth_call` instead of being broadcasted, and the results are reported to the log.
+/// The database is not modified in this mode, so the simulated operations are kept
+/// unprocessed and will be sent once the mode is disabled.
+///
+/// # Local proof verification
+///
+/// If `verify_proofs_locally` is enabled in the configuration, the aggregated proof of every
+/// `proveBlocks` operation is checked against the verification key before the transaction is sent.
+/// An invalid proof is moved to the quarantine and its prover job is returned to the queue.
+/// Until the proof is generated again, the operation and the following `proveBlocks` and
+/// `executeBlocks` operations are kept unprocessed, while the blocks are still committed.
+///
 /// # Failure policy
 ///
 /// By default, `ETHSender` expects no transactions to fail, and thus upon a failure it will
@@ -119,12 +179,27 @@ struct ETHSender<DB: DatabaseInterface> {
     tx_queue: TxQueue,
     /// Utility for managing the gas price for transactions.
     gas_adjuster: GasAdjuster<DB>,
+    /// Operator accounts used to send the transactions.
+    operator_keys: OperatorKeys,
+    /// Transaction replacements requested for the ongoing operations.
+    replacement_requests: HashMap<EthOpId, EthTxReplacement>,
+    /// Timestamp of the last nonces reconciliation.
+    last_nonce_reconciliation: Option<Instant>,
+    /// Identifiers of the operations already simulated in the dry-run mode.
+    dry_run_ops: HashSet<i64>,
+    /// Verifier of the aggregated proofs, set if they're verified locally.
+    proof_verifier: Option<Box<dyn ProofVerifier>>,
     /// Settings for the `ETHSender`.
     options: ETHSenderConfig,
 }
 
 impl<DB: DatabaseInterface> ETHSender<DB> {
-    pub async fn new(options: ETHSenderConfig, db: DB, ethereum: EthereumGateway) -> Self {
+    pub async fn new(
+        options: ETHSenderConfig,
+        db: DB,
+        ethereum: EthereumGateway,
+        additional_operators: Vec<(Address, EthereumGateway)>,
+    ) -> Self {
         let mut connection = db
             .acquire_connection()
             .await
@@ -134,37 +209,75 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
             .await
             .expect("Unable create database transaction");
 
-        db.restore_unprocessed_operations(&mut transaction)
-            .await
-            .expect("Can't restore unprocessed operations");
-
-        let ongoing_ops = db
-            .load_unconfirmed_operations(&mut transaction)
-            .await
-            .expect("Can't restore state");
-
-        let operations_id = ongoing_ops
-            .iter()
-            .filter_map(|eth_op| eth_op.op.as_ref())
-            .map(|aggregated_op| aggregated_op.0)
-            .collect::<Vec<_>>();
-        db.remove_unprocessed_operations(&mut transaction, operations_id)
-            .await
-            .expect("Failed remove unprocessed operations");
+        // Nothing is sent in the dry-run mode, so the sent operations are not tracked
+        // and the database is left intact.
+        let ongoing_ops = if options.sender.dry_run {
+            VecDeque::new()
+        } else {
+            db.restore_unprocessed_operations(&mut transaction)
+                .await
+                .expect("Can't restore unprocessed operations");
+
+            let ongoing_ops = db
+                .load_unconfirmed_operations(&mut transaction)
+                .await
+                .expect("Can't restore state");
+
+            let operations_id = ongoing_ops
+                .iter()
+                .flat_map(|eth_op| eth_op.op.iter().chain(&eth_op.bundled_ops))
+                .map(|aggregated_op| aggregated_op.0)
+                .collect::<Vec<_>>();
+            db.remove_unprocessed_operations(&mut transaction, operations_id)
+                .await
+                .expect("Failed remove unprocessed operations");
+            ongoing_ops
+        };
 
         let stats = db
             .load_stats(&mut transaction)
             .await
             .expect("Failed loading ETH operations stats");
 
+        // Every operation sent within the multicall transaction occupies its own slot.
+        let sent_pending_txs = ongoing_ops
+            .iter()
+            .map(|eth_op| 1 + eth_op.bundled_ops.len())
+            .sum();
         let tx_queue = TxQueueBuilder::new(options.sender.max_txs_in_flight as usize)
-            .with_sent_pending_txs(ongoing_ops.len())
+            .with_sent_pending_txs(sent_pending_txs)
             .with_commit_operations_count(stats.last_committed_block)
             .with_verify_operations_count(stats.last_verified_block)
             .with_execute_operations_count(stats.last_executed_block)
             .build();
 
-        let gas_adjuster = GasAdjuster::new(&db).await;
+        let gas_adjuster =
+            GasAdjuster::new(&db)
+                .await
+                .with_oracle(gas_adjuster::oracle::gas_price_oracle(
+                    &options.gas_price_oracle,
+                ));
+
+        let operator_keys = OperatorKeys::new(
+            ethereum.clone(),
+            additional_operators,
+            options.sender.operator_low_balance_threshold.into(),
+        );
+        // Nonces of the additional accounts are only taken from the node when the account
+        // is used for the first time, afterwards they're managed by the database.
+        for key in operator_keys.additional() {
+            let address = key
+                .address
+                .expect("Additional operator must have an address");
+            let nonce = key
+                .ethereum
+                .pending_nonce()
+                .await
+                .expect("Can't load the nonce of the operator account");
+            db.initialize_operator_nonce(&mut transaction, address, nonce.as_u64() as i64)
+                .await
+                .expect("Can't initialize the nonce of the operator account");
+        }
 
         transaction
             .commit()
@@ -178,6 +291,14 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
             ethereum,
             tx_queue,
             gas_adjuster,
+            operator_keys,
+            replacement_requests: HashMap::new(),
+            last_nonce_reconciliation: None,
+            dry_run_ops: HashSet::new(),
+            proof_verifier: options
+                .sender
+                .verify_proofs_locally
+                .then(|| Box::new(AggregatedProofVerifier::default()) as Box<dyn ProofVerifier>),
             options,
         }
     }
@@ -197,12 +318,18 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
             }
 
             if self.options.sender.is_enabled {
+                // Repair the nonces before sending the new transactions.
+                if !self.options.sender.dry_run {
+                    self.keep_nonces_reconciled().await;
+                }
                 // ...and proceed them.
                 last_used_block = self.proceed_next_operations(last_used_block).await;
                 // Update the gas adjuster to maintain the up-to-date max gas price limit.
                 self.gas_adjuster
                     .keep_updated(&self.ethereum, &self.db)
                     .await;
+                // Track the balances of the operator accounts.
+                self.operator_keys.keep_updated().await;
             }
         }
     }
@@ -214,7 +341,12 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
         let mut connection = self.db.acquire_connection().await?;
         let mut transaction = connection.start_transaction().await?;
 
-        let new_operations = self.db.load_new_operations(&mut transaction).await?;
+        let mut new_operations = self.db.load_new_operations(&mut transaction).await?;
+        if self.options.sender.dry_run {
+            // Simulated operations are kept unprocessed in the database.
+            let dry_run_ops = &mut self.dry_run_ops;
+            new_operations.retain(|(id, _)| dry_run_ops.insert(*id));
+        }
 
         if !new_operations.is_empty() {
             vlog::info!("Loaded {} new operations", new_operations.len());
@@ -222,12 +354,19 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
             vlog::debug!("No new operations are loaded from the database");
         }
 
+        if self.proof_verifier.is_some() {
+            self.hold_unverified_operations(&mut transaction, &mut new_operations)
+                .await?;
+        }
+
         // let's mark the operations as successful processed.
         // So that next time you do not add them to the queue again.
-        let operations_id = new_operations.iter().map(|(id, _)| *id).collect::<Vec<_>>();
-        self.db
-            .remove_unprocessed_operations(&mut transaction, operations_id)
-            .await?;
+        if !self.options.sender.dry_run {
+            let operations_id = new_operations.iter().map(|(id, _)| *id).collect::<Vec<_>>();
+            self.db
+                .remove_unprocessed_operations(&mut transaction, operations_id)
+                .await?;
+        }
 
         transaction.commit().await?;
         drop(connection);
@@ -240,6 +379,109 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
         Ok(())
     }
 
+    /// Checks the proofs of the loaded `proveBlocks` operations in order. Once the proof is
+    /// not available or invalid, this operation and the following operations except the commit
+    /// ones are removed from the list, so that they're kept unprocessed in the database.
+    async fn hold_unverified_operations(
+        &mut self,
+        connection: &mut StorageProcessor<'_>,
+        operations: &mut Vec<(i64, AggregatedOperation)>,
+    ) -> anyhow::Result<()> {
+        for idx in 0..operations.len() {
+            let (id, operation) = &mut operations[idx];
+            if self
+                .verify_proof_locally(connection, *id, operation)
+                .await?
+            {
+                continue;
+            }
+
+            let (held_ops, commit_ops) = operations
+                .split_off(idx)
+                .into_iter()
+                .partition::<Vec<_>, _>(|(_, op)| !op.is_commit());
+            operations.extend(commit_ops);
+            // Held operations must be simulated again once they're ready.
+            for (id, _) in held_ops {
+                self.dry_run_ops.remove(&id);
+            }
+            break;
+        }
+        Ok(())
+    }
+
+    /// Checks the aggregated proof of the `proveBlocks` operation against the verification key.
+    /// Operations of other types are skipped.
+    ///
+    /// Returns `false` if the proof is not available or invalid, the invalid proof is moved
+    /// to the quarantine to be generated again. If the proof was generated again after
+    /// the quarantine, the operation is updated to send the new one.
+    async fn verify_proof_locally(
+        &mut self,
+        connection: &mut StorageProcessor<'_>,
+        id: i64,
+        operation: &mut AggregatedOperation,
+    ) -> anyhow::Result<bool> {
+        let (first_block, last_block) = match operation {
+            AggregatedOperation::PublishProofBlocksOnchain(op) => {
+                match (op.blocks.first(), op.blocks.last()) {
+                    (Some(first), Some(last)) => (first.block_number, last.block_number),
+                    _ => return Ok(true),
+                }
+            }
+            _ => return Ok(true),
+        };
+
+        let proof = match self
+            .db
+            .load_aggregated_proof(connection, first_block, last_block)
+            .await?
+        {
+            Some(proof) => proof,
+            None => {
+                vlog::warn!(
+                    "Aggregated proof for blocks {}-{} is not available yet, proveBlocks transaction is postponed",
+                    first_block,
+                    last_block
+                );
+                return Ok(false);
+            }
+        };
+
+        let start = Instant::now();
+        let is_valid = self
+            .proof_verifier
+            .as_mut()
+            .expect("Proof verifier is not set")
+            .verify(&proof)?;
+        metrics::histogram!("eth_sender.verify_proof_locally", start.elapsed());
+
+        if !is_valid {
+            vlog::error!(
+                "Aggregated proof for blocks {}-{} does not match the verification key, it's quarantined to be generated again",
+                first_block,
+                last_block
+            );
+            metrics::increment_counter!("eth_sender.invalid_proofs");
+            self.db
+                .quarantine_aggregated_proof(connection, first_block, last_block)
+                .await?;
+            return Ok(false);
+        }
+
+        let encoded_proof = proof.serialize_aggregated_proof();
+        if let AggregatedOperation::PublishProofBlocksOnchain(op) = operation {
+            if op.proof == encoded_proof {
+                return Ok(true);
+            }
+            op.proof = encoded_proof;
+        }
+        self.db
+            .update_aggregated_operation(connection, id, operation)
+            .await?;
+        Ok(true)
+    }
+
     /// This method does two main things:
     ///
     /// 1. Pops all the available transactions from the `TxQueue` and sends them.
@@ -250,6 +492,12 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
     async fn proceed_next_operations(&mut self, last_used_block: u64) -> u64 {
         let start = Instant::now();
 
+        if self.options.sender.dry_run {
+            self.simulate_ready_operations().await;
+            metrics::histogram!("eth_sender.proceed_next_operations", start.elapsed());
+            return last_used_block;
+        }
+
         let current_block = match self.ethereum.block_number().await {
             Ok(current_block) => current_block.as_u64(),
             Err(e) => {
@@ -258,17 +506,49 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
             }
         };
 
-        while let Some(tx) = self.tx_queue.pop_front() {
-            if let Err(e) = self.initialize_operation(tx.clone(), current_block).await {
+        if let Err(e) = self.load_replacement_requests().await {
+            Self::process_error(e).await;
+        }
+
+        // New operations are not sent until the pending cancellation is completed,
+        // otherwise they would follow the cancelled operation.
+        let is_cancelling = self
+            .replacement_requests
+            .values()
+            .any(|action| *action == EthTxReplacement::Cancel)
+            || self
+                .ongoing_ops
+                .iter()
+                .any(|op| op.cancel_tx_hash.is_some());
+        let mut ready_txs = Vec::new();
+        if !is_cancelling {
+            while let Some(tx) = self.tx_queue.pop_front() {
+                ready_txs.push(tx);
+            }
+        }
+
+        let groups = match self.group_txs(ready_txs.clone()).await {
+            Ok(groups) => groups,
+            Err(e) => {
                 Self::process_error(e).await;
-                // Return the unperformed operation to the queue, since failing the
-                // operation initialization means that it was not stored in the database.
-                if let Err(err_message) = self.tx_queue.return_popped(tx) {
-                    panic!(
-                        "Failed return previous sent operation to the queue: {}",
-                        err_message
-                    );
-                }
+                // Operations will be sent on the next iteration.
+                self.return_unperformed_txs(ready_txs);
+                Vec::new()
+            }
+        };
+        let mut groups = groups.into_iter();
+        while let Some(group) = groups.next() {
+            if let Err(e) = self
+                .initialize_operation(group.clone(), current_block)
+                .await
+            {
+                Self::process_error(e).await;
+                // Return the unperformed operations to the queue, since failing the
+                // operation initialization means that they were not stored in the database.
+                // The following operations are returned as well to preserve the order.
+                let unperformed: Vec<_> = std::iter::once(group).chain(groups).flatten().collect();
+                self.return_unperformed_txs(unperformed);
+                break;
             }
         }
 
@@ -283,30 +563,50 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
 
             // Commit the next operations (if any).
             while let Some(mut current_op) = self.ongoing_ops.pop_front() {
+                let mut replacement = self.replacement_requests.remove(&current_op.id);
+                if replacement == Some(EthTxReplacement::Cancel) && !self.ongoing_ops.is_empty() {
+                    vlog::warn!(
+                        "Only the latest sent operation can be cancelled, ignoring the cancellation of <ETH Operation id: {}>",
+                        current_op.id
+                    );
+                    replacement = None;
+                }
+
                 // We perform a commitment step here. In case of error, we suppose that this is some
                 // network issue which won't appear the next time, so we report the situation to the
                 // log and consider the operation pending (meaning that we won't process it on this
                 // step, but will try to do so on the next one).
                 let commitment = match self
-                    .perform_commitment_step(&mut current_op, current_block)
+                    .perform_commitment_step(&mut current_op, current_block, replacement)
                     .await
                 {
                     Ok(commitment) => commitment,
                     Err(e) => {
                         Self::process_error(e).await;
+                        // The replacement will be retried on the next step.
+                        if let Some(replacement) = replacement {
+                            self.replacement_requests.insert(current_op.id, replacement);
+                        }
                         OperationCommitment::Pending
                     }
                 };
 
                 match commitment {
                     OperationCommitment::Committed => {
-                        // Free a slot for the next tx in the queue.
-                        self.tx_queue.report_commitment();
+                        // Free the slots for the next txs in the queue, one for every
+                        // operation sent within the transaction.
+                        for _ in 0..=current_op.bundled_ops.len() {
+                            self.tx_queue.report_commitment();
+                        }
                     }
                     OperationCommitment::Pending => {
                         // Poll this operation on the next iteration.
                         new_ongoing_ops.push_back(current_op);
                     }
+                    OperationCommitment::Cancelled => {
+                        // Return the operations to the queue to send them again.
+                        self.return_cancelled_operations(current_op);
+                    }
                 }
             }
             assert!(
@@ -315,12 +615,119 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
             );
             // Store the ongoing operations for the next round.
             self.ongoing_ops = new_ongoing_ops;
+
+            // Requests for the operations that are not ongoing anymore can't be performed.
+            let ongoing_ops = &self.ongoing_ops;
+            self.replacement_requests.retain(|id, action| {
+                let is_ongoing = ongoing_ops.iter().any(|op| op.id == *id);
+                if !is_ongoing {
+                    vlog::warn!(
+                        "Ignoring the {} request for <ETH Operation id: {}>, since it's not ongoing",
+                        action.to_string(),
+                        id
+                    );
+                }
+                is_ongoing
+            });
+
+            // Age of the stuck transactions is the amount of blocks passed since the latest
+            // transaction of the operation was sent.
+            let max_stuck_tx_age = self
+                .ongoing_ops
+                .iter()
+                .filter(|op| op.is_stuck(current_block))
+                .map(|op| {
+                    current_block + self.options.sender.expected_wait_time_block
+                        - op.last_deadline_block
+                })
+                .max()
+                .unwrap_or_default();
+            metrics::gauge!("eth_sender.stuck_tx_age", max_stuck_tx_age as f64);
         }
 
         metrics::histogram!("eth_sender.proceed_next_operations", start.elapsed());
         current_block
     }
 
+    /// Simulates the transactions of the operations ready to be sent and reports the results
+    /// to the log. The operations are considered committed right away, so the dependent
+    /// operations are simulated as well.
+    async fn simulate_ready_operations(&mut self) {
+        let mut ready_txs = Vec::new();
+        while let Some(tx) = self.tx_queue.pop_front() {
+            ready_txs.push(tx);
+        }
+
+        let groups = match self.group_txs(ready_txs.clone()).await {
+            Ok(groups) => groups,
+            Err(e) => {
+                Self::process_error(e).await;
+                self.return_unperformed_txs(ready_txs);
+                return;
+            }
+        };
+        for group in groups {
+            if let Err(e) = self.simulate_group(&group).await {
+                Self::process_error(e).await;
+            }
+            for _ in &group {
+                self.tx_queue.report_commitment();
+            }
+        }
+    }
+
+    /// Simulates the transaction performing the given operations. Transactions combining
+    /// several operations are simulated against the multicall contract.
+    async fn simulate_group(&self, group: &[TxData]) -> anyhow::Result<()> {
+        let description = Self::operations_description(group.iter().map(|tx| &tx.operation));
+
+        let outcome = if let [tx] = group {
+            let gas_limit = self.gas_limit_for_aggregated_op(&tx.operation.1).await?;
+            self.ethereum
+                .simulate_tx(tx.raw.clone(), Some(gas_limit))
+                .await?
+        } else {
+            let multicall_addr = self.options.sender.multicall_contract_addr.ok_or_else(|| {
+                format_err!(
+                    "Multicall contract is not configured, can't simulate the transaction of operations {}",
+                    description
+                )
+            })?;
+            let mut gas_limit = U256::zero();
+            for tx in group {
+                gas_limit += self.gas_limit_for_aggregated_op(&tx.operation.1).await?
+                    + U256::from(MULTICALL_GAS_PER_CALL);
+            }
+            let gas_limit = gas_limit.min(U256::from(self.options.sender.max_gas_limit));
+            self.ethereum
+                .simulate_tx_for_addr(
+                    Self::encode_multicall(group),
+                    multicall_addr,
+                    Some(gas_limit),
+                )
+                .await?
+        };
+
+        match outcome {
+            CallOutcome::Success(_) => {
+                vlog::info!(
+                    "Dry run: transaction for operations {} would succeed",
+                    description
+                );
+                metrics::increment_counter!("eth_sender.dry_run", "result" => "success");
+            }
+            CallOutcome::Reverted(reason) => {
+                vlog::warn!(
+                    "Dry run: transaction for operations {} would be reverted: {}",
+                    description,
+                    reason
+                );
+                metrics::increment_counter!("eth_sender.dry_run", "result" => "reverted");
+            }
+        }
+        Ok(())
+    }
+
     async fn process_error(err: anyhow::Error) {
         vlog::warn!("Error while trying to complete uncommitted op: {}", err);
         if err.to_string().contains(RATE_LIMIT_HTTP_CODE) {
@@ -335,14 +742,377 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
         }
     }
 
+    /// Reconciles the stored nonces with the chain if the reconciliation interval has passed.
+    async fn keep_nonces_reconciled(&mut self) {
+        if let Some(last_reconciliation) = self.last_nonce_reconciliation {
+            if last_reconciliation.elapsed() < NONCE_RECONCILIATION_INTERVAL {
+                return;
+            }
+        }
+        self.last_nonce_reconciliation = Some(Instant::now());
+
+        if let Err(e) = self.reconcile_nonces().await {
+            Self::process_error(e).await;
+        }
+    }
+
+    /// Compares the stored nonces of the operator accounts with the chain and repairs
+    /// the divergences, see "Nonce reconciliation" section for details.
+    async fn reconcile_nonces(&mut self) -> anyhow::Result<()> {
+        let start = Instant::now();
+        let current_block = self.ethereum.block_number().await?.as_u64();
+
+        let senders: Vec<_> = std::iter::once(None)
+            .chain(self.operator_keys.additional().map(|key| key.address))
+            .collect();
+        for sender in senders {
+            self.reconcile_account_nonce(sender, current_block).await?;
+        }
+
+        metrics::histogram!("eth_sender.reconcile_nonces", start.elapsed());
+        Ok(())
+    }
+
+    /// Reconciles the stored nonce of the operator account and the nonces of its ongoing
+    /// operations with the chain.
+    async fn reconcile_account_nonce(
+        &mut self,
+        sender: Option<Address>,
+        current_block: u64,
+    ) -> anyhow::Result<()> {
+        let ethereum = self.operator_keys.gateway(sender)?.clone();
+        let mined_nonce = ethereum.current_nonce().await?.as_u64() as i64;
+        let pending_nonce = ethereum.pending_nonce().await?.as_u64() as i64;
+        let account = sender
+            .map(|address| format!("{:#x}", address))
+            .unwrap_or_else(|| "main".to_string());
+
+        // The nonce of the operation is used by another transaction if it's already mined,
+        // while none of the operation transactions is. Operations being cancelled are
+        // handled by the cancellation.
+        let mut lost_ops = Vec::new();
+        for (idx, op) in self.ongoing_ops.iter().enumerate() {
+            if op.sender != sender
+                || op.cancel_tx_hash.is_some()
+                || op.nonce.as_u64() as i64 >= mined_nonce
+            {
+                continue;
+            }
+            let mut is_mined = false;
+            for tx_hash in &op.used_tx_hashes {
+                if self
+                    .ethereum
+                    .get_tx_status(*tx_hash, Some(current_block))
+                    .await?
+                    .is_some()
+                {
+                    is_mined = true;
+                    break;
+                }
+            }
+            if !is_mined {
+                lost_ops.push(idx);
+            }
+        }
+
+        let sent_nonces: HashSet<i64> = self
+            .ongoing_ops
+            .iter()
+            .enumerate()
+            .filter(|(idx, op)| op.sender == sender && !lost_ops.contains(idx))
+            .map(|(_, op)| op.nonce.as_u64() as i64)
+            .collect();
+        let last_sent_nonce = sent_nonces
+            .iter()
+            .copied()
+            .filter(|nonce| *nonce >= pending_nonce)
+            .max();
+        // Nonces of the transactions unknown to the node that are not used by any operation.
+        let gap: Vec<i64> = match last_sent_nonce {
+            Some(last_sent_nonce) => (pending_nonce..last_sent_nonce)
+                .filter(|nonce| !sent_nonces.contains(nonce))
+                .collect(),
+            None => Vec::new(),
+        };
+
+        let mut connection = self.db.acquire_connection().await?;
+        let mut transaction = connection.start_transaction().await?;
+
+        let stored_nonce = self.db.load_next_nonce(&mut transaction, sender).await?;
+        let mut next_nonce = last_sent_nonce.map_or(pending_nonce, |nonce| nonce + 1);
+        if next_nonce != stored_nonce {
+            vlog::warn!(
+                "Stored nonce {} of the operator account {} diverged from the chain (mined nonce: {}, pending nonce: {}), using nonce {}",
+                stored_nonce,
+                account,
+                mined_nonce,
+                pending_nonce,
+                next_nonce
+            );
+            metrics::increment_counter!("eth_sender.nonce_repairs", "account" => account.clone(), "kind" => "stored_nonce");
+        }
+
+        // The pending operations following the lost ones are re-queued as well: otherwise they
+        // would be executed before the lost operations and reverted by the contract.
+        let mut requeued_ops: Vec<usize> = match lost_ops
+            .iter()
+            .map(|idx| self.ongoing_ops[*idx].nonce)
+            .min()
+        {
+            Some(first_lost_nonce) => self
+                .ongoing_ops
+                .iter()
+                .enumerate()
+                .filter(|(idx, op)| {
+                    lost_ops.contains(idx)
+                        || (op.sender == sender
+                            && op.cancel_tx_hash.is_none()
+                            && op.nonce > first_lost_nonce
+                            && op.nonce.as_u64() as i64 >= mined_nonce)
+                })
+                .map(|(idx, _)| idx)
+                .collect(),
+            None => Vec::new(),
+        };
+        requeued_ops.sort_by_key(|idx| self.ongoing_ops[*idx].nonce);
+        // Nonces of the pending re-queued operations are reused in order, the lost operations
+        // take the new nonces instead.
+        let mut requeued_nonces: Vec<(i64, U256)> = requeued_ops
+            .iter()
+            .filter(|idx| !lost_ops.contains(idx))
+            .map(|idx| {
+                let op = &self.ongoing_ops[*idx];
+                (op.nonce.as_u64() as i64, op.last_used_gas_price)
+            })
+            .collect();
+        for _ in 0..lost_ops.len() {
+            requeued_nonces.push((next_nonce, U256::zero()));
+            next_nonce += 1;
+        }
+
+        let mut new_txs = Vec::new();
+        for (idx, (nonce, replaced_gas_price)) in requeued_ops.into_iter().zip(requeued_nonces) {
+            let mut op = self.ongoing_ops[idx].clone();
+            if lost_ops.contains(&idx) {
+                vlog::warn!(
+                    "Nonce {} of <ETH Operation id: {}> was used by another transaction of the operator account {}, sending it with nonce {}",
+                    op.nonce,
+                    op.id,
+                    account,
+                    nonce
+                );
+                metrics::increment_counter!("eth_sender.nonce_repairs", "account" => account.clone(), "kind" => "lost_operation");
+            } else {
+                vlog::warn!(
+                    "<ETH Operation id: {}> follows the operation with a lost nonce, sending it with nonce {} instead of {}",
+                    op.id,
+                    nonce,
+                    op.nonce
+                );
+            }
+
+            op.nonce = nonce.into();
+            // The transaction replacing the pending one must have a higher gas price.
+            op.last_used_gas_price = op.last_used_gas_price.max(replaced_gas_price);
+            let deadline_block = self.get_deadline_block(current_block);
+            let new_tx = self.create_supplement_tx(deadline_block, &mut op).await?;
+
+            self.db
+                .update_eth_tx_nonce(&mut transaction, op.id, op.nonce.as_u64() as i64)
+                .await?;
+            self.db
+                .update_eth_tx(
+                    &mut transaction,
+                    op.id,
+                    deadline_block as i64,
+                    new_tx.gas_price,
+                )
+                .await?;
+            self.db
+                .add_hash_entry(&mut transaction, op.id, &new_tx.hash)
+                .await?;
+
+            self.ongoing_ops[idx] = op;
+            new_txs.push(new_tx);
+        }
+
+        if let (Some(first), Some(last)) = (gap.first(), gap.last()) {
+            vlog::warn!(
+                "Nonces {}-{} of the operator account {} are not used by any transaction, filling the gap with self-send transactions",
+                first,
+                last,
+                account
+            );
+            metrics::increment_counter!("eth_sender.nonce_repairs", "account" => account, "kind" => "gap");
+
+            let gas_price = self
+                .gas_adjuster
+                .get_gas_price(&self.ethereum, None)
+                .await?;
+            let operator_address = sender.unwrap_or(self.options.sender.operator_commit_eth_addr);
+            for nonce in gap {
+                let mut options = Options {
+                    nonce: Some(nonce.into()),
+                    gas: Some(SELF_SEND_TX_GAS_LIMIT.into()),
+                    ..Default::default()
+                };
+                Self::set_fee_options(&mut options, &self.options.sender, gas_price, 0);
+                let filler_tx = ethereum
+                    .sign_prepared_tx_for_addr(Vec::new(), operator_address, options)
+                    .await?;
+                new_txs.push(filler_tx);
+            }
+        }
+
+        if next_nonce != stored_nonce {
+            self.db
+                .set_next_nonce(&mut transaction, sender, next_nonce)
+                .await?;
+        }
+        transaction.commit().await?;
+
+        for tx in new_txs {
+            vlog::info!(
+                "Nonce reconciliation: sending tx {}",
+                self.eth_tx_description(&tx)
+            );
+            if let Err(e) = self.ethereum.send_raw_tx(tx.raw_tx).await {
+                // Transactions of the operations are resent once considered stuck,
+                // while the gap is filled again on the next reconciliation.
+                vlog::warn!("Error while sending the transaction: {}", e);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the popped transactions that were not sent to the queue, preserving their order.
+    fn return_unperformed_txs(&mut self, txs: Vec<TxData>) {
+        for tx in txs.into_iter().rev() {
+            if let Err(err_message) = self.tx_queue.return_popped(tx) {
+                panic!(
+                    "Failed return previous sent operation to the queue: {}",
+                    err_message
+                );
+            }
+        }
+    }
+
+    /// Splits the transactions ready to be sent into groups, each of them is sent as a single
+    /// multicall transaction as long as the combined gas limit and calldata size fit into the
+    /// configured limits. If the multicall contract is not configured, every transaction
+    /// forms a group of its own.
+    async fn group_txs(&self, txs: Vec<TxData>) -> anyhow::Result<Vec<Vec<TxData>>> {
+        if self.options.sender.multicall_contract_addr.is_none() {
+            return Ok(txs.into_iter().map(|tx| vec![tx]).collect());
+        }
+
+        let max_gas = U256::from(self.options.sender.multicall_max_gas);
+        let max_calldata_size = self.options.sender.multicall_max_calldata_size as usize;
+
+        let mut groups: Vec<Vec<TxData>> = Vec::new();
+        let (mut group_gas, mut group_calldata_size) = (U256::zero(), 0);
+        for tx in txs {
+            let gas = self.gas_limit_for_aggregated_op(&tx.operation.1).await?
+                + U256::from(MULTICALL_GAS_PER_CALL);
+            let calldata_size = tx.raw.len();
+
+            match groups.last_mut() {
+                Some(group)
+                    if group_gas + gas <= max_gas
+                        && group_calldata_size + calldata_size <= max_calldata_size =>
+                {
+                    group.push(tx);
+                    group_gas += gas;
+                    group_calldata_size += calldata_size;
+                }
+                // A transaction exceeding the limits on its own is still sent directly.
+                _ => {
+                    groups.push(vec![tx]);
+                    group_gas = gas;
+                    group_calldata_size = calldata_size;
+                }
+            }
+        }
+        Ok(groups)
+    }
+
+    /// Encodes the `aggregate` call of the multicall contract performing the given transactions.
+    fn encode_multicall(txs: &[TxData]) -> Vec<u8> {
+        let calls = txs.iter().map(|tx| Token::Bytes(tx.raw.clone())).collect();
+        let mut data =
+            ethabi::short_signature("aggregate", &[ParamType::Array(Box::new(ParamType::Bytes))])
+                .to_vec();
+        data.extend(ethabi::encode(&[Token::Array(calls)]));
+        data
+    }
+
+    /// Loads the transaction replacements requested via the private API.
+    async fn load_replacement_requests(&mut self) -> anyhow::Result<()> {
+        let mut connection = self.db.acquire_connection().await?;
+        let requests = self.db.take_replacement_requests(&mut connection).await?;
+        for (eth_op_id, action) in requests {
+            vlog::info!(
+                "Requested {} of the transaction for <ETH Operation id: {}>",
+                action.to_string(),
+                eth_op_id
+            );
+            self.replacement_requests.insert(eth_op_id, action);
+        }
+        Ok(())
+    }
+
+    /// Returns the zkSync operations of the cancelled Ethereum operation to the queue.
+    fn return_cancelled_operations(&mut self, op: ETHOperation) {
+        let operations = op.op.into_iter().chain(op.bundled_ops);
+        let txs: Vec<_> = operations
+            .map(|operation| {
+                let raw_tx = self.operation_to_raw_tx(&operation.1);
+                TxData::from_operation(operation, raw_tx)
+            })
+            .collect();
+
+        // The latest popped operation must be returned first.
+        for tx in txs.into_iter().rev() {
+            if let Err(err_message) = self.tx_queue.return_popped(tx) {
+                panic!(
+                    "Failed return cancelled operation to the queue: {}",
+                    err_message
+                );
+            }
+        }
+    }
+
     /// Stores the new operation in the database and sends the corresponding transaction.
-    async fn initialize_operation(&mut self, tx: TxData, current_block: u64) -> anyhow::Result<()> {
+    /// If several transactions are provided, they are combined into a single multicall transaction.
+    async fn initialize_operation(
+        &mut self,
+        txs: Vec<TxData>,
+        current_block: u64,
+    ) -> anyhow::Result<()> {
+        let (tx, bundled_txs) = txs
+            .split_first()
+            .expect("Operation must contain at least one transaction");
+        let raw_tx = if bundled_txs.is_empty() {
+            tx.raw.clone()
+        } else {
+            Self::encode_multicall(&txs)
+        };
+        let bundled_ops: Vec<_> = bundled_txs.iter().map(|tx| tx.operation.clone()).collect();
+
         let deadline_block = self.get_deadline_block(current_block);
         let gas_price = self
             .gas_adjuster
             .get_gas_price(&self.ethereum, None)
             .await?;
 
+        // Operations must be sent from the same account until all of them are confirmed,
+        // otherwise they may be mined out of order.
+        let sender = match self.ongoing_ops.back() {
+            Some(ongoing_op) => ongoing_op.sender,
+            None => self.operator_keys.select(),
+        };
+
         let mut connection = self.db.acquire_connection().await?;
         let mut transaction = connection.start_transaction().await?;
 
@@ -358,25 +1128,41 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
                     Some(tx.operation.clone()),
                     deadline_block as i64,
                     gas_price,
-                    tx.raw.clone(),
+                    raw_tx.clone(),
+                    sender,
                 )
                 .await?;
+            for op in &bundled_ops {
+                self.db
+                    .add_bundled_operation(&mut transaction, assigned_data.id, op.clone())
+                    .await?;
+            }
 
             let mut new_op = ETHOperation {
                 id: assigned_data.id,
                 op_type: tx.op_type,
-                op: Some(tx.operation),
+                op: Some(tx.operation.clone()),
+                bundled_ops,
                 nonce: assigned_data.nonce,
                 last_deadline_block: deadline_block,
                 last_used_gas_price: gas_price,
                 used_tx_hashes: vec![], // No hash yet, will be added below.
-                encoded_tx_data: tx.raw,
+                encoded_tx_data: raw_tx,
                 confirmed: false,
                 final_hash: None,
+                sender,
+                cancel_tx_hash: None,
             };
 
             // Sign the transaction.
-            let signed_tx = Self::sign_new_tx(&self.ethereum, &new_op).await?;
+            let gas_limit = self.gas_limit_for_op(&new_op).await?;
+            let signed_tx = Self::sign_new_tx(
+                self.operator_keys.gateway(sender)?,
+                &self.options.sender,
+                &new_op,
+                gas_limit,
+            )
+            .await?;
 
             // With signed tx, update the hash in the operation entry and in the db.
             new_op.used_tx_hashes.push(signed_tx.hash);
@@ -423,17 +1209,30 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
     /// Helper method to obtain the string representation of the zkSync operation.
     /// Intended to be used for log entries.
     fn zksync_operation_description(&self, operation: &ETHOperation) -> String {
-        if let Some((id, op)) = &operation.op {
-            let (first_block, last_block) = op.get_block_range();
-            format!(
-                "<id {}; action: {}; blocks: {}-{}>",
-                id,
-                op.get_action_type().to_string(),
-                first_block,
-                last_block
-            )
-        } else {
+        Self::operations_description(operation.op.iter().chain(&operation.bundled_ops))
+    }
+
+    /// Helper method to obtain the string representation of the given zkSync operations.
+    fn operations_description<'a>(
+        operations: impl Iterator<Item = &'a (i64, AggregatedOperation)>,
+    ) -> String {
+        let descriptions: Vec<_> = operations
+            .map(|(id, op)| {
+                let (first_block, last_block) = op.get_block_range();
+                format!(
+                    "<id {}; action: {}; blocks: {}-{}>",
+                    id,
+                    op.get_action_type().to_string(),
+                    first_block,
+                    last_block
+                )
+            })
+            .collect();
+
+        if descriptions.is_empty() {
             "<not applicable>".into()
+        } else {
+            descriptions.join(", ")
         }
     }
 
@@ -447,6 +1246,7 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
         &mut self,
         op: &mut ETHOperation,
         current_block: u64,
+        replacement: Option<EthTxReplacement>,
     ) -> anyhow::Result<OperationCommitment> {
         let start = Instant::now();
         assert!(
@@ -454,11 +1254,32 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
             "OperationETHState should have at least one transaction"
         );
 
+        // Check whether the cancelling transaction was included instead of the operation one.
+        if let Some(cancel_tx_hash) = op.cancel_tx_hash {
+            let outcome = self
+                .check_transaction_state(TxCheckMode::Latest, op, cancel_tx_hash, current_block)
+                .await?;
+            if outcome == TxCheckOutcome::Committed {
+                let mut connection = self.db.acquire_connection().await?;
+                self.db
+                    .cancel_operation(&mut connection, &cancel_tx_hash, op)
+                    .await?;
+
+                vlog::info!(
+                    "Cancelled: [ETH Operation <id: {}, type: {:?}>. Tx hash: <{:#x}>. ZKSync operation: {}]",
+                    op.id, op.op_type, cancel_tx_hash, self.zksync_operation_description(op),
+                );
+                metrics::increment_counter!("eth_sender.cancelled_operations");
+                return Ok(OperationCommitment::Cancelled);
+            }
+        }
+
         // Check statuses of existing transactions.
         // Go through every transaction in a loop. We will exit this method early
         // if there will be discovered a pending or successfully committed transaction.
         for (idx, tx_hash) in op.used_tx_hashes.iter().enumerate() {
-            let mode = if idx == op.used_tx_hashes.len() - 1 {
+            // The latest transaction is considered stuck if its replacement was requested.
+            let mode = if idx == op.used_tx_hashes.len() - 1 && replacement.is_none() {
                 TxCheckMode::Latest
             } else {
                 TxCheckMode::Old
@@ -508,6 +1329,8 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
                     self.db
                         .confirm_operation(&mut transaction, tx_hash, op)
                         .await?;
+                    self.save_gas_usage(&mut transaction, op, *tx_hash, current_block)
+                        .await?;
                     transaction.commit().await?;
                     return Ok(OperationCommitment::Committed);
                 }
@@ -530,8 +1353,16 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
         }
 
         // Reaching this point will mean that the latest transaction got stuck.
-        // We should create another tx based on it, and send it.
         let deadline_block = self.get_deadline_block(current_block);
+        if replacement == Some(EthTxReplacement::Cancel) {
+            return self.cancel_stuck_tx(deadline_block, op).await;
+        }
+        if op.cancel_tx_hash.is_some() {
+            // Resending the operation transaction would replace the cancelling one.
+            return Ok(OperationCommitment::Pending);
+        }
+
+        // We should create another tx based on it, and send it.
         // Raw tx contents are the same for every transaction, so we just
         // create a new one from the old one with updated parameters.
         let new_tx = self.create_supplement_tx(deadline_block, op).await?;
@@ -563,6 +1394,87 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
         Ok(OperationCommitment::Pending)
     }
 
+    /// Stores the gas used by the confirmed transaction as a basis for the gas limit estimation.
+    /// Multicall transactions are skipped, since the gas used by every combined operation is unknown.
+    async fn save_gas_usage(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        op: &ETHOperation,
+        tx_hash: H256,
+        current_block: u64,
+    ) -> anyhow::Result<()> {
+        let aggregated_op = match &op.op {
+            Some((_, aggregated_op)) if op.bundled_ops.is_empty() => aggregated_op,
+            _ => return Ok(()),
+        };
+        let gas_used = self
+            .ethereum
+            .get_tx_status(tx_hash, Some(current_block))
+            .await?
+            .and_then(|status| status.gas_used);
+
+        if let Some(gas_used) = gas_used {
+            self.db
+                .save_gas_usage(
+                    connection,
+                    aggregated_op.get_action_type(),
+                    Self::aggregated_op_size(aggregated_op),
+                    gas_used.as_u64() as i64,
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Replaces the stuck transaction of the operation with a self-send transaction
+    /// at the same nonce, so the operation may be sent again later.
+    async fn cancel_stuck_tx(
+        &mut self,
+        deadline_block: u64,
+        op: &mut ETHOperation,
+    ) -> anyhow::Result<OperationCommitment> {
+        let mut tx_options = self.tx_options_from_stuck_tx(op).await?;
+        tx_options.gas = Some(SELF_SEND_TX_GAS_LIMIT.into());
+
+        let operator_address = op
+            .sender
+            .unwrap_or(self.options.sender.operator_commit_eth_addr);
+        let cancel_tx = self
+            .operator_keys
+            .gateway(op.sender)?
+            .sign_prepared_tx_for_addr(Vec::new(), operator_address, tx_options)
+            .await?;
+
+        op.last_deadline_block = deadline_block;
+        op.last_used_gas_price = cancel_tx.gas_price;
+        op.cancel_tx_hash = Some(cancel_tx.hash);
+
+        // Cancelling transaction should be persisted in the DB *before* sending it.
+        let mut connection = self.db.acquire_connection().await?;
+        let mut transaction = connection.start_transaction().await?;
+        self.db
+            .update_eth_tx(
+                &mut transaction,
+                op.id,
+                deadline_block as i64,
+                cancel_tx.gas_price,
+            )
+            .await?;
+        self.db
+            .set_cancel_tx_hash(&mut transaction, op.id, &cancel_tx.hash)
+            .await?;
+
+        vlog::info!(
+            "Stuck tx processing: cancelling op, eth_op_id: {}; ETH tx: {}",
+            op.id,
+            self.eth_tx_description(&cancel_tx),
+        );
+        self.ethereum.send_raw_tx(cancel_tx.raw_tx).await?;
+        transaction.commit().await?;
+
+        Ok(OperationCommitment::Pending)
+    }
+
     /// Handles a transaction execution failure by reporting the issue to the log
     /// and terminating the node.
     async fn failure_handler(&self, receipt: &TransactionReceipt) -> ! {
@@ -637,13 +1549,11 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
     /// Creates a new Ethereum operation.
     async fn sign_new_tx(
         ethereum: &EthereumGateway,
+        sender: &Sender,
         op: &ETHOperation,
+        gas_limit: U256,
     ) -> anyhow::Result<SignedCallResult> {
         let tx_options = {
-            // We set the gas limit for commit / verify operations as pre-calculated estimation.
-            // This estimation is a higher bound based on a pre-calculated cost of every operation in the block.
-            let gas_limit = Self::gas_limit_for_op(op);
-
             assert!(
                 gas_limit > 0.into(),
                 "Proposed gas limit for operation is 0; operation: {:?}",
@@ -656,29 +1566,155 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
                 gas_limit
             );
 
-            Options {
+            let mut options = Options {
                 nonce: Some(op.nonce),
-                gas_price: Some(op.last_used_gas_price),
                 gas: Some(gas_limit),
-                max_fee_per_gas: None,
-                max_priority_fee_per_gas: None,
                 ..Default::default()
-            }
+            };
+            Self::set_fee_options(&mut options, sender, op.last_used_gas_price, 0);
+            options
         };
 
-        let signed_tx = ethereum
-            .sign_prepared_tx(op.encoded_tx_data.clone(), tx_options)
-            .await?;
+        Self::sign_tx(ethereum, sender, op, tx_options).await
+    }
 
-        Ok(signed_tx)
+    /// Signs the transaction of the operation. Transactions combining several operations
+    /// are sent to the multicall contract, the rest are sent to the zkSync contract.
+    async fn sign_tx(
+        ethereum: &EthereumGateway,
+        sender: &Sender,
+        op: &ETHOperation,
+        options: Options,
+    ) -> anyhow::Result<SignedCallResult> {
+        let data = op.encoded_tx_data.clone();
+        if op.bundled_ops.is_empty() {
+            return ethereum.sign_prepared_tx(data, options).await;
+        }
+
+        let multicall_addr = sender.multicall_contract_addr.ok_or_else(|| {
+            format_err!(
+                "Multicall contract is not configured, can't sign the transaction of <ETH Operation id: {}>",
+                op.id
+            )
+        })?;
+        ethereum
+            .sign_prepared_tx_for_addr(data, multicall_addr, options)
+            .await
+    }
+
+    /// Sets the fee of the transaction depending on the configured transaction type.
+    ///
+    /// For EIP-1559 transactions `gas_price` is used as the max fee per gas, and the priority
+    /// fee is increased by 15% for each of the `replacements` of the stuck transaction, since
+    /// nodes require both fees to be bumped to accept the replacement.
+    fn set_fee_options(
+        options: &mut Options,
+        sender: &Sender,
+        gas_price: U256,
+        replacements: usize,
+    ) {
+        if !sender.use_eip1559 {
+            options.gas_price = Some(gas_price);
+            return;
+        }
+
+        let mut priority_fee = U256::from(sender.max_priority_fee_per_gas);
+        for _ in 0..replacements {
+            priority_fee = priority_fee * U256::from(115) / U256::from(100);
+        }
+        options.transaction_type = Some(EIP1559_TX_TYPE.into());
+        options.max_priority_fee_per_gas = Some(priority_fee);
+        options.max_fee_per_gas = Some(std::cmp::max(gas_price, priority_fee));
     }
 
     /// Calculates the gas limit for transaction to be send, depending on the type of operation.
-    fn gas_limit_for_op(op: &ETHOperation) -> U256 {
-        let (_, op) = op
+    /// For the multicall transaction the gas limits of all the combined operations are summed up.
+    async fn gas_limit_for_op(&self, op: &ETHOperation) -> anyhow::Result<U256> {
+        let (_, first_op) = op
             .op
             .as_ref()
             .expect("Operation not found - can't compute gas limit");
+        if op.bundled_ops.is_empty() {
+            return self.gas_limit_for_aggregated_op(first_op).await;
+        }
+
+        let mut gas_limit = U256::zero();
+        for op in std::iter::once(first_op).chain(op.bundled_ops.iter().map(|(_, op)| op)) {
+            gas_limit +=
+                self.gas_limit_for_aggregated_op(op).await? + U256::from(MULTICALL_GAS_PER_CALL);
+        }
+        Ok(gas_limit.min(U256::from(self.options.sender.max_gas_limit)))
+    }
+
+    /// Calculates the gas limit for the call of the zkSync contract performing the operation.
+    ///
+    /// The gas used by the previous transactions of similar size is scaled to the size of the
+    /// operation, and the configured safety margin is added on top of it. If there are no such
+    /// transactions, the pre-calculated estimation is used. In both cases the gas limit doesn't
+    /// exceed the configured hard cap.
+    async fn gas_limit_for_aggregated_op(&self, op: &AggregatedOperation) -> anyhow::Result<U256> {
+        let size = Self::aggregated_op_size(op);
+        let mut connection = self.db.acquire_connection().await?;
+        let gas_usage = self
+            .db
+            .load_gas_usage(
+                &mut connection,
+                op.get_action_type(),
+                size * (100 - GAS_USAGE_SIZE_TOLERANCE) / 100,
+                size * (100 + GAS_USAGE_SIZE_TOLERANCE) / 100,
+                GAS_USAGE_SAMPLES,
+            )
+            .await?;
+
+        let gas_limit =
+            Self::estimate_gas_limit(size, &gas_usage, self.options.sender.gas_limit_margin)
+                .unwrap_or_else(|| Self::precalculated_gas_limit(op));
+        Ok(gas_limit.min(U256::from(self.options.sender.max_gas_limit)))
+    }
+
+    /// Scales the gas used by the previous transactions to the size of the operation
+    /// and increases the maximum of the obtained values by the safety margin.
+    /// Returns `None` if there are no previous transactions.
+    fn estimate_gas_limit(size: i64, gas_usage: &[(i64, i64)], margin: f64) -> Option<U256> {
+        let max_gas_used = gas_usage
+            .iter()
+            .filter(|(used_size, _)| *used_size > 0)
+            .map(|(used_size, gas_used)| {
+                // Round up to never underestimate.
+                (*gas_used as u128 * size as u128 + *used_size as u128 - 1) / *used_size as u128
+            })
+            .max()?;
+
+        // Margin is applied in basis points to keep the estimation precise.
+        let margin = (margin * 10_000.0).round() as u128;
+        let gas_limit = (max_gas_used * (10_000 + margin) + 9_999) / 10_000;
+        Some(U256::from(gas_limit))
+    }
+
+    /// Returns the size of the operation the gas used by its transaction mostly depends on:
+    /// the total amount of chunks of the committed or executed blocks, or the amount
+    /// of the proven blocks.
+    fn aggregated_op_size(op: &AggregatedOperation) -> i64 {
+        let size: usize = match op {
+            AggregatedOperation::CommitBlocks(commit) => commit
+                .blocks
+                .iter()
+                .map(|block| block.block_chunks_size)
+                .sum(),
+            AggregatedOperation::ExecuteBlocks(execute) => execute
+                .blocks
+                .iter()
+                .map(|block| block.block_chunks_size)
+                .sum(),
+            AggregatedOperation::PublishProofBlocksOnchain(proof) => proof.blocks.len(),
+            AggregatedOperation::CreateProofBlocks(create_proof) => create_proof.blocks.len(),
+        };
+        size as i64
+    }
+
+    /// Pre-calculated estimation of the gas limit, which is a higher bound based on
+    /// a pre-calculated cost of every operation in the block.
+    fn precalculated_gas_limit(op: &AggregatedOperation) -> U256 {
         match op {
             AggregatedOperation::CommitBlocks(commit) => {
                 GasCounter::commit_gas_limit_aggregated(&commit.blocks)
@@ -704,8 +1740,13 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
     ) -> anyhow::Result<SignedCallResult> {
         let tx_options = self.tx_options_from_stuck_tx(stuck_tx).await?;
 
-        let raw_tx = stuck_tx.encoded_tx_data.clone();
-        let signed_tx = self.ethereum.sign_prepared_tx(raw_tx, tx_options).await?;
+        let signed_tx = Self::sign_tx(
+            self.operator_keys.gateway(stuck_tx.sender)?,
+            &self.options.sender,
+            stuck_tx,
+            tx_options,
+        )
+        .await?;
 
         stuck_tx.last_deadline_block = deadline_block;
         stuck_tx.last_used_gas_price = signed_tx.gas_price;
@@ -727,7 +1768,7 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
             .get_gas_price(&self.ethereum, Some(old_tx_gas_price))
             .await?;
         let nonce = stuck_tx.nonce;
-        let gas_limit = Self::gas_limit_for_op(stuck_tx);
+        let gas_limit = self.gas_limit_for_op(stuck_tx).await?;
 
         assert!(
             gas_limit > 0.into(),
@@ -744,11 +1785,18 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
             gas_limit,
         );
 
-        Ok(Options::with(move |opt| {
-            opt.gas_price = Some(new_gas_price);
+        let mut options = Options::with(move |opt| {
             opt.nonce = Some(nonce);
             opt.gas = Some(gas_limit);
-        }))
+        });
+        Self::set_fee_options(
+            &mut options,
+            &self.options.sender,
+            new_gas_price,
+            stuck_tx.used_tx_hashes.len(),
+        );
+
+        Ok(options)
     }
 
     /// Encodes the operation data to the Ethereum tx payload (not signs it!).
@@ -796,16 +1844,21 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
     }
 }
 
+/// Runs the Ethereum sender.
+///
+/// `additional_operators` are the gateways signing the transactions with the keys of
+/// the additional operator accounts, along with the addresses of these accounts.
 #[must_use]
 pub fn run_eth_sender(
     pool: ConnectionPool,
     eth_gateway: EthereumGateway,
+    additional_operators: Vec<(Address, EthereumGateway)>,
     options: ETHSenderConfig,
 ) -> JoinHandle<()> {
     let db = Database::new(pool);
 
     tokio::spawn(async move {
-        let eth_sender = ETHSender::new(options, db, eth_gateway).await;
+        let eth_sender = ETHSender::new(options, db, eth_gateway, additional_operators).await;
 
         eth_sender.run().await
     })
@@ -0,0 +1,143 @@
+//! `operator_keys` module manages the pool of the operator accounts used to send
+//! the Ethereum transactions.
+//!
+//! Since the zkSync contract accepts the commit, prove and execute operations only in
+//! order, the transactions of the ongoing operations must be sent from the same account
+//! to be ordered by nonce. Thus the account may only be changed once all the previously
+//! sent operations are confirmed. At this point the account with the highest known ETH balance
+//! is selected, the main account is preferred if the balances are equal or not loaded yet.
+//! The same account is used as long as it stays the richest one, so the other accounts
+//! take over when it runs low on ETH.
+
+// Built-in deps
+use std::time::{Duration, Instant};
+// External uses
+use web3::types::{Address, U256};
+// Workspace uses
+use zksync_eth_client::EthereumGateway;
+
+/// Interval between the updates of the operator accounts balances.
+const BALANCE_UPDATE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Operator account capable of signing the Ethereum transactions.
+#[derive(Debug, Clone)]
+pub(crate) struct OperatorKey {
+    /// Address of the additional operator account, `None` for the main one.
+    pub address: Option<Address>,
+    /// Gateway signing the transactions with the account key.
+    pub ethereum: EthereumGateway,
+    /// Last known ETH balance of the account.
+    pub balance: Option<U256>,
+}
+
+/// Pool of the operator accounts.
+#[derive(Debug)]
+pub(crate) struct OperatorKeys {
+    /// Main operator account goes first, followed by the additional ones.
+    keys: Vec<OperatorKey>,
+    /// Balance below which the account is considered running low on ETH.
+    low_balance_threshold: U256,
+    /// Timestamp of the last balances update.
+    last_balance_update: Option<Instant>,
+}
+
+impl OperatorKeys {
+    pub fn new(
+        main: EthereumGateway,
+        additional: Vec<(Address, EthereumGateway)>,
+        low_balance_threshold: U256,
+    ) -> Self {
+        let main = OperatorKey {
+            address: None,
+            ethereum: main,
+            balance: None,
+        };
+        let additional = additional
+            .into_iter()
+            .map(|(address, ethereum)| OperatorKey {
+                address: Some(address),
+                ethereum,
+                balance: None,
+            });
+
+        Self {
+            keys: std::iter::once(main).chain(additional).collect(),
+            low_balance_threshold,
+            last_balance_update: None,
+        }
+    }
+
+    /// Returns the additional operator accounts.
+    pub fn additional(&self) -> impl Iterator<Item = &OperatorKey> {
+        self.keys.iter().skip(1)
+    }
+
+    /// Returns the gateway signing the transactions of the given operator account.
+    pub fn gateway(&self, address: Option<Address>) -> anyhow::Result<&EthereumGateway> {
+        self.keys
+            .iter()
+            .find(|key| key.address == address)
+            .map(|key| &key.ethereum)
+            .ok_or_else(|| {
+                anyhow::format_err!(
+                    "Operator account {:?} is not configured, can't sign the transaction",
+                    address
+                )
+            })
+    }
+
+    /// Selects the account with the highest known balance to send the new operation from.
+    /// Must only be used when there are no ongoing operations.
+    pub fn select(&self) -> Option<Address> {
+        // `max_by_key` returns the last of the equal elements,
+        // so the main account wins the ties (e.g. if balances are not loaded yet).
+        self.keys
+            .iter()
+            .rev()
+            .max_by_key(|key| key.balance.unwrap_or_default())
+            .and_then(|key| key.address)
+    }
+
+    /// Updates the balances of the operator accounts if the update interval has passed
+    /// and reports the accounts running low on ETH.
+    pub async fn keep_updated(&mut self) {
+        if let Some(last_update) = self.last_balance_update {
+            if last_update.elapsed() < BALANCE_UPDATE_INTERVAL {
+                return;
+            }
+        }
+        self.last_balance_update = Some(Instant::now());
+
+        for key in &mut self.keys {
+            let address = key
+                .address
+                .map(|address| format!("{:#x}", address))
+                .unwrap_or_else(|| "main".to_string());
+            let balance = match key.ethereum.sender_eth_balance().await {
+                Ok(balance) => balance,
+                Err(err) => {
+                    vlog::warn!(
+                        "Cannot load the balance of the operator account {}: {}",
+                        address,
+                        err
+                    );
+                    continue;
+                }
+            };
+            key.balance = Some(balance);
+
+            // Balance in gwei is precise enough for the metrics.
+            let balance_gwei = (balance / U256::exp10(9)).low_u64() as f64;
+            metrics::gauge!("eth_sender.operator_balance", balance_gwei, "account" => address.clone());
+            if balance < self.low_balance_threshold {
+                vlog::error!(
+                    "Operator account {} runs low on ETH: balance is {} wei, threshold is {} wei",
+                    address,
+                    balance,
+                    self.low_balance_threshold
+                );
+                metrics::increment_counter!("eth_sender.operator_low_balance", "account" => address);
+            }
+        }
+    }
+}
@@ -0,0 +1,18 @@
+//! Local verification of the aggregated proofs before they're sent to Ethereum.
+
+// Workspace uses
+use zksync_crypto::proof::AggregatedProof;
+use zksync_prover_utils::aggregated_proofs::AggregatedProofVerifier;
+
+/// Checks the aggregated proof of the `proveBlocks` operation, so that the transaction
+/// which would be reverted by the contract is not sent.
+pub(crate) trait ProofVerifier: Send {
+    /// Returns `false` if the proof doesn't match the verification key.
+    fn verify(&mut self, proof: &AggregatedProof) -> anyhow::Result<bool>;
+}
+
+impl ProofVerifier for AggregatedProofVerifier {
+    fn verify(&mut self, proof: &AggregatedProof) -> anyhow::Result<bool> {
+        AggregatedProofVerifier::verify(self, proof)
+    }
+}
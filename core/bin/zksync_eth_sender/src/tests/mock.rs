@@ -1,21 +1,29 @@
 //! Mocking utilities for tests.
 
 // Built-in deps
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::convert::TryFrom;
 // External uses
 use tokio::sync::RwLock;
 use web3::contract::Options;
-use zksync_basic_types::{BlockNumber, H256, U256};
+use zksync_basic_types::{Address, BlockNumber, H256, U256};
 // Workspace uses
-use zksync_config::configs::eth_sender::{ETHSenderConfig, GasLimit, Sender};
+use zksync_config::configs::eth_sender::{
+    ETHSenderConfig, GasLimit, GasPriceOracle, GasPriceOracleKind, Sender,
+};
+use zksync_crypto::{
+    ff::{Field, PrimeField},
+    proof::AggregatedProof,
+    Fr,
+};
 use zksync_eth_client::EthereumGateway;
 use zksync_storage::{ethereum::records::ETHParams, StorageProcessor};
 use zksync_types::aggregated_operations::{AggregatedActionType, AggregatedOperation};
-use zksync_types::ethereum::{ETHOperation, EthOpId, InsertedOperationResponse};
+use zksync_types::ethereum::{ETHOperation, EthOpId, EthTxReplacement, InsertedOperationResponse};
 // Local uses
 use super::ETHSender;
 use crate::database::DatabaseInterface;
+use crate::proof_verifier::ProofVerifier;
 use crate::transactions::ETHStats;
 use zksync_eth_client::clients::mock::MockEthereum;
 
@@ -25,7 +33,14 @@ pub(crate) struct MockDatabase {
     eth_operations: RwLock<Vec<ETHOperation>>,
     aggregated_operations: RwLock<Vec<(i64, AggregatedOperation)>>,
     unprocessed_operations: RwLock<Vec<(i64, AggregatedOperation)>>,
+    replacement_requests: RwLock<Vec<(EthOpId, EthTxReplacement)>>,
+    /// Nonces overwritten via `set_next_nonce`, otherwise the nonce is the number of
+    /// the operations sent from the account.
+    next_nonces: RwLock<HashMap<Option<Address>, i64>>,
+    gas_usage: RwLock<Vec<(AggregatedActionType, i64, i64)>>,
     eth_parameters: RwLock<ETHParams>,
+    aggregated_proofs: RwLock<HashMap<(BlockNumber, BlockNumber), AggregatedProof>>,
+    quarantined_proofs: RwLock<Vec<(BlockNumber, BlockNumber)>>,
 }
 
 impl MockDatabase {
@@ -40,7 +55,12 @@ impl MockDatabase {
             eth_operations: RwLock::new(eth_operations),
             aggregated_operations: RwLock::new(aggregated_operations),
             unprocessed_operations: RwLock::new(unprocessed_operations),
+            replacement_requests: Default::default(),
+            next_nonces: Default::default(),
+            gas_usage: Default::default(),
             eth_parameters: RwLock::new(eth_parameters),
+            aggregated_proofs: Default::default(),
+            quarantined_proofs: Default::default(),
         }
     }
 
@@ -68,6 +88,42 @@ impl MockDatabase {
         Ok(())
     }
 
+    /// Simulates the prover storing the aggregated proof for the blocks range.
+    pub async fn store_aggregated_proof(
+        &self,
+        first_block: BlockNumber,
+        last_block: BlockNumber,
+        proof: AggregatedProof,
+    ) {
+        self.aggregated_proofs
+            .write()
+            .await
+            .insert((first_block, last_block), proof);
+    }
+
+    /// Returns the blocks ranges which proofs were quarantined.
+    pub async fn quarantined_proofs(&self) -> Vec<(BlockNumber, BlockNumber)> {
+        self.quarantined_proofs.read().await.clone()
+    }
+
+    /// Returns the stored aggregated operation.
+    pub async fn aggregated_operation(&self, id: i64) -> Option<AggregatedOperation> {
+        self.aggregated_operations
+            .read()
+            .await
+            .iter()
+            .find(|(op_id, _)| *op_id == id)
+            .map(|(_, op)| op.clone())
+    }
+
+    /// Simulates the request to replace the transaction sent via the private API.
+    pub async fn request_replacement(&self, eth_op_id: EthOpId, action: EthTxReplacement) {
+        self.replacement_requests
+            .write()
+            .await
+            .push((eth_op_id, action));
+    }
+
     /// Ensures that the provided transaction is stored in the database and not confirmed yet.
     pub async fn assert_stored(&self, tx: &ETHOperation) {
         let eth_operations = self.eth_operations.read().await;
@@ -139,6 +195,51 @@ impl DatabaseInterface for MockDatabase {
         Ok(())
     }
 
+    async fn load_aggregated_proof(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+        first_block: BlockNumber,
+        last_block: BlockNumber,
+    ) -> anyhow::Result<Option<AggregatedProof>> {
+        let proofs = self.aggregated_proofs.read().await;
+        Ok(proofs.get(&(first_block, last_block)).cloned())
+    }
+
+    async fn quarantine_aggregated_proof(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+        first_block: BlockNumber,
+        last_block: BlockNumber,
+    ) -> anyhow::Result<()> {
+        let mut proofs = self.aggregated_proofs.write().await;
+        if proofs.remove(&(first_block, last_block)).is_some() {
+            self.quarantined_proofs
+                .write()
+                .await
+                .push((first_block, last_block));
+        }
+        Ok(())
+    }
+
+    async fn update_aggregated_operation(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+        id: i64,
+        operation: &AggregatedOperation,
+    ) -> anyhow::Result<()> {
+        let mut aggregated_operations = self.aggregated_operations.write().await;
+        let mut unprocessed_operations = self.unprocessed_operations.write().await;
+        for (op_id, op) in aggregated_operations
+            .iter_mut()
+            .chain(unprocessed_operations.iter_mut())
+        {
+            if *op_id == id {
+                *op = operation.clone();
+            }
+        }
+        Ok(())
+    }
+
     async fn update_gas_price_params(
         &self,
         _connection: &mut StorageProcessor<'_>,
@@ -199,22 +300,25 @@ impl DatabaseInterface for MockDatabase {
 
     async fn save_new_eth_tx(
         &self,
-        _connection: &mut StorageProcessor<'_>,
+        connection: &mut StorageProcessor<'_>,
         op_type: AggregatedActionType,
         op: Option<(i64, AggregatedOperation)>,
         deadline_block: i64,
         used_gas_price: U256,
         encoded_tx_data: Vec<u8>,
+        sender: Option<Address>,
     ) -> anyhow::Result<InsertedOperationResponse> {
+        let nonce = self.load_next_nonce(connection, sender).await?;
+        self.next_nonces.write().await.insert(sender, nonce + 1);
         let mut eth_operations = self.eth_operations.write().await;
         let id = eth_operations.len() as i64;
-        let nonce = eth_operations.len();
 
         // Store with the assigned ID.
         let eth_operation = ETHOperation {
             id,
             op_type,
             op,
+            bundled_ops: Vec::new(),
             nonce: nonce.into(),
             last_deadline_block: deadline_block as u64,
             last_used_gas_price: used_gas_price,
@@ -222,6 +326,8 @@ impl DatabaseInterface for MockDatabase {
             encoded_tx_data,
             confirmed: false,
             final_hash: None,
+            sender,
+            cancel_tx_hash: None,
         };
 
         eth_operations.push(eth_operation);
@@ -234,6 +340,83 @@ impl DatabaseInterface for MockDatabase {
         Ok(response)
     }
 
+    async fn initialize_operator_nonce(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+        _address: Address,
+        _nonce: i64,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn load_next_nonce(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+        sender: Option<Address>,
+    ) -> anyhow::Result<i64> {
+        if let Some(nonce) = self.next_nonces.read().await.get(&sender) {
+            return Ok(*nonce);
+        }
+        let nonce = self
+            .eth_operations
+            .read()
+            .await
+            .iter()
+            .filter(|eth_op| eth_op.sender == sender)
+            .count();
+        Ok(nonce as i64)
+    }
+
+    async fn set_next_nonce(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+        sender: Option<Address>,
+        nonce: i64,
+    ) -> anyhow::Result<()> {
+        self.next_nonces.write().await.insert(sender, nonce);
+        Ok(())
+    }
+
+    async fn update_eth_tx_nonce(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+        eth_op_id: EthOpId,
+        nonce: i64,
+    ) -> anyhow::Result<()> {
+        let mut eth_operations = self.eth_operations.write().await;
+        let eth_op = eth_operations
+            .iter_mut()
+            .find(|eth_op| eth_op.id == eth_op_id && !eth_op.confirmed);
+
+        if let Some(eth_op) = eth_op {
+            eth_op.nonce = nonce.into();
+        } else {
+            panic!("Attempt to update tx that is not unconfirmed");
+        }
+
+        Ok(())
+    }
+
+    async fn add_bundled_operation(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+        eth_op_id: i64,
+        op: (i64, AggregatedOperation),
+    ) -> anyhow::Result<()> {
+        let mut eth_operations = self.eth_operations.write().await;
+        let eth_op = eth_operations
+            .iter_mut()
+            .find(|eth_op| eth_op.id == eth_op_id && !eth_op.confirmed);
+
+        if let Some(eth_op) = eth_op {
+            eth_op.bundled_ops.push(op);
+        } else {
+            panic!("Attempt to update tx that is not unconfirmed");
+        }
+
+        Ok(())
+    }
+
     /// Adds a tx hash entry associated with some Ethereum operation to the database.
     async fn add_hash_entry(
         &self,
@@ -302,6 +485,54 @@ impl DatabaseInterface for MockDatabase {
         Ok(())
     }
 
+    async fn take_replacement_requests(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Vec<(EthOpId, EthTxReplacement)>> {
+        let requests = std::mem::take(&mut *self.replacement_requests.write().await);
+        Ok(requests)
+    }
+
+    async fn set_cancel_tx_hash(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+        eth_op_id: EthOpId,
+        hash: &H256,
+    ) -> anyhow::Result<()> {
+        let mut eth_operations = self.eth_operations.write().await;
+        let eth_op = eth_operations
+            .iter_mut()
+            .find(|eth_op| eth_op.id == eth_op_id && !eth_op.confirmed);
+
+        if let Some(eth_op) = eth_op {
+            eth_op.cancel_tx_hash = Some(*hash);
+        } else {
+            panic!("Attempt to update tx that is not unconfirmed");
+        }
+
+        Ok(())
+    }
+
+    async fn cancel_operation(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+        hash: &H256,
+        op: &ETHOperation,
+    ) -> anyhow::Result<()> {
+        let mut eth_operations = self.eth_operations.write().await;
+        let eth_op = eth_operations
+            .iter_mut()
+            .find(|eth_op| eth_op.id == op.id && !eth_op.confirmed)
+            .expect("Request to cancel operation that was not stored");
+
+        eth_op.confirmed = true;
+        eth_op.final_hash = Some(*hash);
+        eth_op.op = None;
+        eth_op.bundled_ops.clear();
+
+        Ok(())
+    }
+
     async fn load_gas_price_limit(
         &self,
         _connection: &mut StorageProcessor<'_>,
@@ -312,6 +543,43 @@ impl DatabaseInterface for MockDatabase {
         Ok(gas_price_limit)
     }
 
+    async fn save_gas_usage(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+        action_type: AggregatedActionType,
+        size: i64,
+        gas_used: i64,
+    ) -> anyhow::Result<()> {
+        self.gas_usage
+            .write()
+            .await
+            .push((action_type, size, gas_used));
+        Ok(())
+    }
+
+    async fn load_gas_usage(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+        action_type: AggregatedActionType,
+        min_size: i64,
+        max_size: i64,
+        limit: i64,
+    ) -> anyhow::Result<Vec<(i64, i64)>> {
+        let usage = self
+            .gas_usage
+            .read()
+            .await
+            .iter()
+            .rev()
+            .filter(|(op_type, size, _)| {
+                *op_type == action_type && (min_size..=max_size).contains(size)
+            })
+            .map(|(_, size, gas_used)| (*size, *gas_used))
+            .take(limit as usize)
+            .collect();
+        Ok(usage)
+    }
+
     async fn load_stats(&self, _connection: &mut StorageProcessor<'_>) -> anyhow::Result<ETHStats> {
         let eth_parameters = self.eth_parameters.read().await;
         let eth_stats = ETHStats {
@@ -340,9 +608,11 @@ impl DatabaseInterface for MockDatabase {
 
             // Consider an operation that affects sequential blocks.
             let maybe_operation = eth_operations.iter().find(|eth_operation| {
-                let op_block_range = eth_operation.op.as_ref().unwrap().1.get_block_range();
-
-                op_block_range.1 == first_block - 1
+                eth_operation
+                    .op
+                    .iter()
+                    .chain(&eth_operation.bundled_ops)
+                    .any(|(_, op)| op.get_block_range().1 == first_block - 1)
             });
 
             let operation = match maybe_operation {
@@ -417,6 +687,30 @@ pub(crate) async fn restored_eth_sender(
     .await
 }
 
+/// Proof verifier accepting the proofs with the non-zero aggregated input.
+pub(crate) struct MockProofVerifier;
+
+impl ProofVerifier for MockProofVerifier {
+    fn verify(&mut self, proof: &AggregatedProof) -> anyhow::Result<bool> {
+        Ok(proof
+            .proof
+            .inputs
+            .first()
+            .map_or(false, |input| !input.is_zero()))
+    }
+}
+
+/// Creates the aggregated proof with the given aggregated input,
+/// which is only accepted by `MockProofVerifier` if the input is not zero.
+pub(crate) fn gen_aggregated_proof(aggregated_input: u64) -> AggregatedProof {
+    let mut proof = AggregatedProof::default();
+    proof
+        .proof
+        .inputs
+        .push(Fr::from_str(&aggregated_input.to_string()).unwrap());
+    proof
+}
+
 /// Helper method for configurable creation of `ETHSender`.
 async fn build_eth_sender(
     max_txs_in_flight: u64,
@@ -440,8 +734,21 @@ async fn build_eth_sender(
             wait_confirmations: super::WAIT_CONFIRMATIONS,
             tx_poll_period: 0,
             is_enabled: true,
+            verify_proofs_locally: false,
+            use_eip1559: false,
+            max_priority_fee_per_gas: 0,
             operator_commit_eth_addr: Default::default(),
             operator_private_key: Default::default(),
+            additional_operator_private_keys: Vec::new(),
+            operator_low_balance_threshold: 0,
+            remote_signer_url: None,
+            remote_signer_timeout: 10,
+            multicall_contract_addr: None,
+            multicall_max_gas: 8000000,
+            multicall_max_calldata_size: 120000,
+            gas_limit_margin: 0.2f64,
+            max_gas_limit: 15000000,
+            dry_run: false,
         },
         gas_price_limit: GasLimit {
             default: 1000,
@@ -449,9 +756,17 @@ async fn build_eth_sender(
             update_interval: 15,
             scale_factor: 1.0f64,
         },
+        gas_price_oracle: GasPriceOracle {
+            kind: GasPriceOracleKind::Node,
+            http_url: Default::default(),
+            http_json_pointer: Default::default(),
+            http_price_multiplier: 1.0f64,
+            percentile_blocks: 20,
+            percentile: 60.0f64,
+        },
     };
 
-    ETHSender::new(options, db, ethereum).await
+    ETHSender::new(options, db, ethereum, Vec::new()).await
 }
 
 /// Behaves the same as `ETHSender::sign_new_tx`, but does not affect nonce.
@@ -482,6 +797,7 @@ pub(crate) async fn create_signed_tx(
         id,
         op_type,
         op: Some(aggregated_operation.clone()),
+        bundled_ops: Vec::new(),
         nonce: signed_tx.nonce,
         last_deadline_block: deadline_block,
         last_used_gas_price: signed_tx.gas_price,
@@ -489,5 +805,7 @@ pub(crate) async fn create_signed_tx(
         encoded_tx_data: raw_tx,
         confirmed: false,
         final_hash: None,
+        sender: None,
+        cancel_tx_hash: None,
     }
 }
@@ -1,11 +1,22 @@
 // Local uses
 use self::mock::{
     concurrent_eth_sender, create_signed_tx, default_eth_parameters, default_eth_sender,
-    restored_eth_sender,
+    gen_aggregated_proof, restored_eth_sender, MockDatabase, MockProofVerifier,
+};
+use super::{
+    database::DatabaseInterface, operator_keys::OperatorKeys, transactions::TxCheckOutcome,
+    tx_queue::TxData, ETHSender, TxCheckMode,
+};
+use web3::contract::Options;
+use web3::types::{Address, U256, U64};
+use zksync_eth_client::{
+    clients::mock::MockEthereum, ethereum_gateway::ExecutedTxStatus, EthereumGateway,
+};
+use zksync_types::{
+    aggregated_operations::{AggregatedActionType, AggregatedOperation},
+    ethereum::EthTxReplacement,
+    BlockNumber,
 };
-use super::{transactions::TxCheckOutcome, ETHSender, TxCheckMode};
-use web3::types::U64;
-use zksync_eth_client::ethereum_gateway::ExecutedTxStatus;
 
 const EXPECTED_WAIT_TIME_BLOCKS: u64 = 30;
 const WAIT_CONFIRMATIONS: u64 = 3;
@@ -34,6 +45,71 @@ async fn deadline_block() {
     );
 }
 
+/// Creates a mock gateway of the operator account with the given ETH balance.
+async fn operator_gateway(balance: u64) -> EthereumGateway {
+    let mut ethereum = MockEthereum::default();
+    ethereum.set_sender_balance(balance.into()).await;
+    EthereumGateway::Mock(ethereum)
+}
+
+/// Checks that the operator account with the highest balance is selected for the new operations.
+#[tokio::test]
+async fn operator_keys_selection() {
+    let first = Address::repeat_byte(1);
+    let second = Address::repeat_byte(2);
+    let mut operator_keys = OperatorKeys::new(
+        operator_gateway(100).await,
+        vec![
+            (first, operator_gateway(300).await),
+            (second, operator_gateway(200).await),
+        ],
+        150.into(),
+    );
+
+    // Main account is used until the balances are known.
+    assert_eq!(operator_keys.select(), None);
+
+    operator_keys.keep_updated().await;
+    assert_eq!(operator_keys.select(), Some(first));
+
+    assert!(operator_keys.gateway(None).is_ok());
+    assert!(operator_keys.gateway(Some(second)).is_ok());
+    assert!(operator_keys
+        .gateway(Some(Address::repeat_byte(3)))
+        .is_err());
+}
+
+/// Checks that transaction fee is set according to the configured transaction type.
+#[tokio::test]
+async fn fee_options() {
+    let eth_sender = default_eth_sender().await;
+    let mut sender = eth_sender.options.sender.clone();
+    let gas_price = U256::from(100_000);
+
+    // Legacy transactions only use the gas price.
+    let mut options = Options::default();
+    ETHSender::<MockDatabase>::set_fee_options(&mut options, &sender, gas_price, 1);
+    assert_eq!(options.gas_price, Some(gas_price));
+    assert_eq!(options.transaction_type, None);
+    assert_eq!(options.max_fee_per_gas, None);
+
+    sender.use_eip1559 = true;
+    sender.max_priority_fee_per_gas = 1000;
+
+    let mut options = Options::default();
+    ETHSender::<MockDatabase>::set_fee_options(&mut options, &sender, gas_price, 0);
+    assert_eq!(options.gas_price, None);
+    assert_eq!(options.transaction_type, Some(U64::from(2)));
+    assert_eq!(options.max_fee_per_gas, Some(gas_price));
+    assert_eq!(options.max_priority_fee_per_gas, Some(U256::from(1000)));
+
+    // Priority fee is bumped for every replacement and never exceeds the max fee.
+    let mut options = Options::default();
+    ETHSender::<MockDatabase>::set_fee_options(&mut options, &sender, U256::from(1200), 2);
+    assert_eq!(options.max_priority_fee_per_gas, Some(U256::from(1322)));
+    assert_eq!(options.max_fee_per_gas, Some(U256::from(1322)));
+}
+
 /// Checks that received transaction response is reduced to the
 /// `TxCheckOutcome` correctly.
 ///
@@ -78,6 +154,7 @@ async fn transaction_state() {
         confirmations: WAIT_CONFIRMATIONS,
         success: true,
         receipt: None,
+        gas_used: None,
     };
     eth_sender
         .ethereum
@@ -91,6 +168,7 @@ async fn transaction_state() {
         confirmations: WAIT_CONFIRMATIONS - 1,
         success: true,
         receipt: None,
+        gas_used: None,
     };
     eth_sender
         .ethereum
@@ -104,6 +182,7 @@ async fn transaction_state() {
         confirmations: WAIT_CONFIRMATIONS,
         success: false,
         receipt: Some(Default::default()),
+        gas_used: None,
     };
     eth_sender
         .ethereum
@@ -117,6 +196,7 @@ async fn transaction_state() {
         confirmations: WAIT_CONFIRMATIONS - 1,
         success: false,
         receipt: Some(Default::default()),
+        gas_used: None,
     };
     eth_sender
         .ethereum
@@ -972,3 +1052,585 @@ async fn concurrent_operations_order() {
         }
     }
 }
+
+/// Checks that the operations ready to be sent at the same time are combined into
+/// a single multicall transaction, which confirms all of them at once.
+#[tokio::test]
+async fn multicall_operations() {
+    const MAX_TXS_IN_FLIGHT: u64 = 3;
+    let mut eth_sender = concurrent_eth_sender(MAX_TXS_IN_FLIGHT).await;
+    eth_sender.options.sender.multicall_contract_addr = Some(Address::repeat_byte(0x42));
+
+    let operations = vec![
+        test_data::commit_blocks_operation(0),
+        test_data::publish_proof_blocks_onchain_operations(0),
+        test_data::execute_blocks_operations(0),
+    ];
+    for operation in &operations {
+        eth_sender
+            .db
+            .send_aggregated_operation(operation.clone())
+            .await
+            .unwrap();
+    }
+    eth_sender.load_new_operations().await.unwrap();
+    eth_sender.proceed_next_operations(0).await;
+
+    // All the operations are sent within a single transaction.
+    assert_eq!(eth_sender.ongoing_ops.len(), 1);
+    let eth_op = eth_sender.ongoing_ops[0].clone();
+    assert_eq!(eth_op.op.as_ref().unwrap().0, operations[0].0);
+    let bundled_ids: Vec<_> = eth_op.bundled_ops.iter().map(|(id, _)| *id).collect();
+    assert_eq!(bundled_ids, vec![operations[1].0, operations[2].0]);
+
+    let txs: Vec<_> = operations
+        .iter()
+        .map(|op| TxData::from_operation(op.clone(), eth_sender.operation_to_raw_tx(&op.1)))
+        .collect();
+    assert_eq!(
+        eth_op.encoded_tx_data,
+        ETHSender::<MockDatabase>::encode_multicall(&txs)
+    );
+
+    let tx_hash = eth_op.used_tx_hashes[0];
+    eth_sender.db.assert_stored(&eth_op).await;
+    eth_sender
+        .ethereum
+        .get_mock()
+        .unwrap()
+        .assert_sent(&tx_hash.as_bytes().to_vec())
+        .await;
+    eth_sender
+        .ethereum
+        .get_mut_mock()
+        .unwrap()
+        .add_successfull_execution(tx_hash, WAIT_CONFIRMATIONS)
+        .await;
+
+    eth_sender.proceed_next_operations(0).await;
+
+    let mut expected_op = eth_op;
+    expected_op.confirmed = true;
+    expected_op.final_hash = Some(tx_hash);
+    eth_sender.db.assert_confirmed(&expected_op).await;
+    assert!(eth_sender.ongoing_ops.is_empty());
+
+    // Slots of all the combined operations are freed.
+    for idx in 1..=MAX_TXS_IN_FLIGHT as usize {
+        eth_sender
+            .db
+            .send_aggregated_operation(test_data::commit_blocks_operation(idx))
+            .await
+            .unwrap();
+    }
+    eth_sender.load_new_operations().await.unwrap();
+    eth_sender.proceed_next_operations(0).await;
+    assert_eq!(eth_sender.ongoing_ops.len(), 1);
+    assert_eq!(
+        eth_sender.ongoing_ops[0].bundled_ops.len() + 1,
+        MAX_TXS_IN_FLIGHT as usize
+    );
+}
+
+/// Checks that the transactions are combined within the configured gas and calldata limits.
+#[tokio::test]
+async fn multicall_limits() {
+    let mut eth_sender = default_eth_sender().await;
+    let txs: Vec<_> = (0..3)
+        .map(|idx| {
+            let op = test_data::commit_blocks_operation(idx);
+            TxData::from_operation(op.clone(), eth_sender.operation_to_raw_tx(&op.1))
+        })
+        .collect();
+
+    // Without the multicall contract every transaction is sent separately.
+    let groups = eth_sender.group_txs(txs.clone()).await.unwrap();
+    assert_eq!(groups.len(), 3);
+
+    eth_sender.options.sender.multicall_contract_addr = Some(Address::repeat_byte(0x42));
+    eth_sender.options.sender.multicall_max_gas = u64::MAX;
+    eth_sender.options.sender.multicall_max_calldata_size = u64::MAX;
+    let groups = eth_sender.group_txs(txs.clone()).await.unwrap();
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[0], txs);
+
+    // Calldata limit allows only two transactions to be combined.
+    let calldata_size = txs[0].raw.len() + txs[1].raw.len();
+    eth_sender.options.sender.multicall_max_calldata_size = calldata_size as u64;
+    let groups = eth_sender.group_txs(txs.clone()).await.unwrap();
+    assert_eq!(groups.len(), 2);
+    assert_eq!(groups[0].len(), 2);
+
+    // Transactions exceeding the gas limit on their own are sent separately.
+    eth_sender.options.sender.multicall_max_calldata_size = u64::MAX;
+    eth_sender.options.sender.multicall_max_gas = 1;
+    let groups = eth_sender.group_txs(txs).await.unwrap();
+    assert_eq!(groups.len(), 3);
+}
+
+/// Checks that the requested replacements of the ongoing operation transaction are performed:
+/// resending with the increased fee and cancellation returning the operation to the queue.
+#[tokio::test]
+async fn transaction_replacement() {
+    let mut eth_sender = default_eth_sender().await;
+
+    let operations = vec![
+        test_data::commit_blocks_operation(0),
+        test_data::commit_blocks_operation(1),
+    ];
+    eth_sender
+        .db
+        .send_aggregated_operation(operations[0].clone())
+        .await
+        .unwrap();
+    eth_sender.load_new_operations().await.unwrap();
+    eth_sender.proceed_next_operations(0).await;
+    assert_eq!(eth_sender.ongoing_ops[0].used_tx_hashes.len(), 1);
+    let eth_op_id = eth_sender.ongoing_ops[0].id;
+
+    // The transaction is resent without waiting for the deadline block.
+    eth_sender
+        .db
+        .request_replacement(eth_op_id, EthTxReplacement::Resend)
+        .await;
+    eth_sender.proceed_next_operations(0).await;
+    let eth_op = eth_sender.ongoing_ops[0].clone();
+    assert_eq!(eth_op.used_tx_hashes.len(), 2);
+    assert!(eth_op.last_used_gas_price > U256::zero());
+    eth_sender
+        .ethereum
+        .get_mock()
+        .unwrap()
+        .assert_sent(&eth_op.used_tx_hashes[1].as_bytes().to_vec())
+        .await;
+
+    // No new operations are sent while the cancellation is pending.
+    eth_sender
+        .db
+        .send_aggregated_operation(operations[1].clone())
+        .await
+        .unwrap();
+    eth_sender.load_new_operations().await.unwrap();
+    eth_sender
+        .db
+        .request_replacement(eth_op_id, EthTxReplacement::Cancel)
+        .await;
+    eth_sender.proceed_next_operations(0).await;
+    assert_eq!(eth_sender.ongoing_ops.len(), 1);
+    let cancel_tx_hash = eth_sender.ongoing_ops[0]
+        .cancel_tx_hash
+        .expect("Cancelling transaction must be sent");
+    eth_sender
+        .ethereum
+        .get_mock()
+        .unwrap()
+        .assert_sent(&cancel_tx_hash.as_bytes().to_vec())
+        .await;
+
+    eth_sender.proceed_next_operations(0).await;
+    assert_eq!(eth_sender.ongoing_ops.len(), 1);
+    assert_eq!(eth_sender.ongoing_ops[0].id, eth_op_id);
+
+    // Once the cancelling transaction is confirmed, the operation is sent again.
+    eth_sender
+        .ethereum
+        .get_mut_mock()
+        .unwrap()
+        .add_successfull_execution(cancel_tx_hash, WAIT_CONFIRMATIONS)
+        .await;
+    eth_sender.proceed_next_operations(0).await;
+    assert!(eth_sender.ongoing_ops.is_empty());
+    let mut cancelled_op = eth_op;
+    cancelled_op.confirmed = true;
+    eth_sender.db.assert_confirmed(&cancelled_op).await;
+
+    eth_sender.proceed_next_operations(0).await;
+    assert_eq!(eth_sender.ongoing_ops.len(), 1);
+    let resent_op = &eth_sender.ongoing_ops[0];
+    assert_ne!(resent_op.id, eth_op_id);
+    assert_eq!(resent_op.op.as_ref().unwrap().0, operations[0].0);
+}
+
+/// Checks that the stored nonce and the nonces of the ongoing operations are repaired
+/// once they've diverged from the chain.
+#[tokio::test]
+async fn nonce_reconciliation() {
+    let mut eth_sender = default_eth_sender().await;
+    let operations = vec![
+        test_data::commit_blocks_operation(0),
+        test_data::commit_blocks_operation(1),
+    ];
+
+    // Nonces used by the transactions sent bypassing `ETHSender` are skipped.
+    eth_sender
+        .ethereum
+        .get_mut_mock()
+        .unwrap()
+        .set_nonces(5.into(), 5.into())
+        .await;
+    eth_sender.reconcile_nonces().await.unwrap();
+    eth_sender
+        .db
+        .send_aggregated_operation(operations[0].clone())
+        .await
+        .unwrap();
+    eth_sender.load_new_operations().await.unwrap();
+    eth_sender.proceed_next_operations(0).await;
+    assert_eq!(eth_sender.ongoing_ops[0].nonce, 5.into());
+
+    // The operation which nonce was used by another transaction is sent with a new nonce.
+    eth_sender
+        .ethereum
+        .get_mut_mock()
+        .unwrap()
+        .set_nonces(6.into(), 6.into())
+        .await;
+    eth_sender.reconcile_nonces().await.unwrap();
+    let eth_op = eth_sender.ongoing_ops[0].clone();
+    assert_eq!(eth_op.nonce, 6.into());
+    assert_eq!(eth_op.used_tx_hashes.len(), 2);
+    eth_sender
+        .ethereum
+        .get_mock()
+        .unwrap()
+        .assert_sent(&eth_op.used_tx_hashes[1].as_bytes().to_vec())
+        .await;
+    eth_sender.db.assert_stored(&eth_op).await;
+
+    // Unused nonces preceding the sent transaction are filled with self-send transactions.
+    let mut connection = eth_sender.db.acquire_connection().await.unwrap();
+    eth_sender
+        .db
+        .set_next_nonce(&mut connection, None, 9)
+        .await
+        .unwrap();
+    drop(connection);
+    eth_sender
+        .db
+        .send_aggregated_operation(operations[1].clone())
+        .await
+        .unwrap();
+    eth_sender.load_new_operations().await.unwrap();
+    eth_sender.proceed_next_operations(0).await;
+    assert_eq!(eth_sender.ongoing_ops[1].nonce, 9.into());
+
+    eth_sender
+        .ethereum
+        .get_mut_mock()
+        .unwrap()
+        .set_nonces(6.into(), 7.into())
+        .await;
+    eth_sender.reconcile_nonces().await.unwrap();
+    let gas_price = eth_sender
+        .gas_adjuster
+        .get_gas_price(&eth_sender.ethereum, None)
+        .await
+        .unwrap();
+    for nonce in 7..9 {
+        let options = Options {
+            nonce: Some(nonce.into()),
+            gas_price: Some(gas_price),
+            ..Default::default()
+        };
+        let filler_tx = eth_sender
+            .ethereum
+            .sign_prepared_tx(Vec::new(), options)
+            .await
+            .unwrap();
+        eth_sender
+            .ethereum
+            .get_mock()
+            .unwrap()
+            .assert_sent(&filler_tx.hash.as_bytes().to_vec())
+            .await;
+    }
+
+    // Unused nonces following the latest sent transaction are reused.
+    let mut connection = eth_sender.db.acquire_connection().await.unwrap();
+    eth_sender
+        .db
+        .set_next_nonce(&mut connection, None, 15)
+        .await
+        .unwrap();
+    drop(connection);
+    eth_sender.reconcile_nonces().await.unwrap();
+    let mut connection = eth_sender.db.acquire_connection().await.unwrap();
+    let next_nonce = eth_sender
+        .db
+        .load_next_nonce(&mut connection, None)
+        .await
+        .unwrap();
+    assert_eq!(next_nonce, 10);
+}
+
+/// Checks that the pending operations following the one with a lost nonce are re-queued,
+/// so that the operations are still sent in order.
+#[tokio::test]
+async fn nonce_reconciliation_keeps_order() {
+    let mut eth_sender = concurrent_eth_sender(2).await;
+    let operations = vec![
+        test_data::commit_blocks_operation(0),
+        test_data::commit_blocks_operation(1),
+    ];
+
+    eth_sender
+        .ethereum
+        .get_mut_mock()
+        .unwrap()
+        .set_nonces(5.into(), 5.into())
+        .await;
+    eth_sender.reconcile_nonces().await.unwrap();
+    for operation in &operations {
+        eth_sender
+            .db
+            .send_aggregated_operation(operation.clone())
+            .await
+            .unwrap();
+    }
+    eth_sender.load_new_operations().await.unwrap();
+    eth_sender.proceed_next_operations(0).await;
+    assert_eq!(eth_sender.ongoing_ops[0].nonce, 5.into());
+    assert_eq!(eth_sender.ongoing_ops[1].nonce, 6.into());
+    let replaced_gas_price = eth_sender.ongoing_ops[1].last_used_gas_price;
+
+    // Nonce 5 is used by another transaction, while the second operation is still pending.
+    eth_sender
+        .ethereum
+        .get_mut_mock()
+        .unwrap()
+        .set_nonces(6.into(), 7.into())
+        .await;
+    eth_sender.reconcile_nonces().await.unwrap();
+
+    let first_op = eth_sender.ongoing_ops[0].clone();
+    let second_op = eth_sender.ongoing_ops[1].clone();
+    assert_eq!(first_op.op.as_ref().unwrap().0, operations[0].0);
+    assert_eq!(first_op.nonce, 6.into());
+    assert!(first_op.last_used_gas_price > replaced_gas_price);
+    assert_eq!(second_op.op.as_ref().unwrap().0, operations[1].0);
+    assert_eq!(second_op.nonce, 7.into());
+    for op in &[first_op, second_op] {
+        assert_eq!(op.used_tx_hashes.len(), 2);
+        eth_sender
+            .ethereum
+            .get_mock()
+            .unwrap()
+            .assert_sent(&op.used_tx_hashes[1].as_bytes().to_vec())
+            .await;
+        eth_sender.db.assert_stored(op).await;
+    }
+
+    let mut connection = eth_sender.db.acquire_connection().await.unwrap();
+    let next_nonce = eth_sender
+        .db
+        .load_next_nonce(&mut connection, None)
+        .await
+        .unwrap();
+    assert_eq!(next_nonce, 8);
+}
+
+/// Checks that the gas limit is estimated from the gas used by the confirmed transactions
+/// of similar size, falling back to the pre-calculated estimation.
+#[tokio::test]
+async fn gas_limit_estimation() {
+    let mut eth_sender = default_eth_sender().await;
+    let operation = test_data::commit_blocks_operation(0);
+    // Test blocks contain 50 chunks.
+    assert_eq!(
+        ETHSender::<MockDatabase>::aggregated_op_size(&operation.1),
+        50
+    );
+
+    // There are no confirmed transactions yet.
+    let gas_limit = eth_sender
+        .gas_limit_for_aggregated_op(&operation.1)
+        .await
+        .unwrap();
+    assert_eq!(
+        gas_limit,
+        ETHSender::<MockDatabase>::precalculated_gas_limit(&operation.1)
+    );
+
+    // Gas used by the confirmed transaction is stored.
+    eth_sender
+        .db
+        .send_aggregated_operation(operation.clone())
+        .await
+        .unwrap();
+    eth_sender.load_new_operations().await.unwrap();
+    eth_sender.proceed_next_operations(0).await;
+    let tx_hash = eth_sender.ongoing_ops[0].used_tx_hashes[0];
+    let response = ExecutedTxStatus {
+        confirmations: WAIT_CONFIRMATIONS,
+        success: true,
+        receipt: None,
+        gas_used: Some(300_000.into()),
+    };
+    eth_sender
+        .ethereum
+        .get_mut_mock()
+        .unwrap()
+        .add_execution(&tx_hash, &response)
+        .await;
+    eth_sender.proceed_next_operations(0).await;
+    assert!(eth_sender.ongoing_ops.is_empty());
+
+    // The configured margin is 20%.
+    let gas_limit = eth_sender
+        .gas_limit_for_aggregated_op(&operation.1)
+        .await
+        .unwrap();
+    assert_eq!(gas_limit, 360_000.into());
+
+    // Gas used is scaled to the size of the operation, while the operations of another type
+    // or of different size are not considered.
+    let mut connection = eth_sender.db.acquire_connection().await.unwrap();
+    for (action_type, size, gas_used) in vec![
+        (AggregatedActionType::CommitBlocks, 40, 400_000),
+        (AggregatedActionType::CommitBlocks, 100, 5_000_000),
+        (AggregatedActionType::ExecuteBlocks, 50, 5_000_000),
+    ] {
+        eth_sender
+            .db
+            .save_gas_usage(&mut connection, action_type, size, gas_used)
+            .await
+            .unwrap();
+    }
+    drop(connection);
+    let gas_limit = eth_sender
+        .gas_limit_for_aggregated_op(&operation.1)
+        .await
+        .unwrap();
+    assert_eq!(gas_limit, 600_000.into());
+
+    // Gas limit never exceeds the hard cap.
+    eth_sender.options.sender.max_gas_limit = 500_000;
+    let gas_limit = eth_sender
+        .gas_limit_for_aggregated_op(&operation.1)
+        .await
+        .unwrap();
+    assert_eq!(gas_limit, 500_000.into());
+}
+
+/// Checks that in the dry-run mode the transactions are only simulated: nothing is sent
+/// or stored, and the simulated operations are not loaded again.
+#[tokio::test]
+async fn dry_run() {
+    const MAX_TXS_IN_FLIGHT: u64 = 3;
+    let mut eth_sender = concurrent_eth_sender(MAX_TXS_IN_FLIGHT).await;
+    eth_sender.options.sender.dry_run = true;
+
+    let operations = vec![
+        test_data::commit_blocks_operation(0),
+        test_data::publish_proof_blocks_onchain_operations(0),
+        test_data::execute_blocks_operations(0),
+    ];
+    for operation in &operations {
+        eth_sender
+            .db
+            .send_aggregated_operation(operation.clone())
+            .await
+            .unwrap();
+    }
+    eth_sender.load_new_operations().await.unwrap();
+    eth_sender.proceed_next_operations(0).await;
+
+    // Every operation is simulated in order, but none of them is sent.
+    assert!(eth_sender.ongoing_ops.is_empty());
+    let expected_txs: Vec<_> = operations
+        .iter()
+        .map(|op| eth_sender.operation_to_raw_tx(&op.1))
+        .collect();
+    let mock = eth_sender.ethereum.get_mock().unwrap();
+    assert_eq!(mock.simulated_txs().await, expected_txs);
+
+    // Operations are kept unprocessed in the database, but not simulated again.
+    let mut connection = eth_sender.db.acquire_connection().await.unwrap();
+    let unprocessed = eth_sender
+        .db
+        .load_new_operations(&mut connection)
+        .await
+        .unwrap();
+    assert_eq!(unprocessed.len(), operations.len());
+    drop(connection);
+
+    eth_sender.load_new_operations().await.unwrap();
+    eth_sender.proceed_next_operations(0).await;
+    let mock = eth_sender.ethereum.get_mock().unwrap();
+    assert_eq!(mock.simulated_txs().await.len(), operations.len());
+}
+
+/// Returns the identifiers of the operations kept unprocessed in the database.
+async fn unprocessed_operation_ids(db: &MockDatabase) -> Vec<i64> {
+    let mut connection = db.acquire_connection().await.unwrap();
+    db.load_new_operations(&mut connection)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|(id, _)| id)
+        .collect()
+}
+
+/// Checks that the invalid aggregated proof is quarantined, and the `proveBlocks` operation
+/// with the following `executeBlocks` one are held until the proof is generated again,
+/// while the commit operations are still processed.
+#[tokio::test]
+async fn invalid_proof_quarantined() {
+    let mut eth_sender = default_eth_sender().await;
+    eth_sender.proof_verifier = Some(Box::new(MockProofVerifier));
+
+    let commit_op = test_data::commit_blocks_operation(0);
+    let proof_op = test_data::publish_proof_blocks_onchain_operations(0);
+    let execute_op = test_data::execute_blocks_operations(0);
+    let next_commit_op = test_data::commit_blocks_operation(1);
+    let operations = vec![
+        commit_op,
+        proof_op.clone(),
+        execute_op.clone(),
+        next_commit_op,
+    ];
+    for operation in &operations {
+        eth_sender
+            .db
+            .send_aggregated_operation(operation.clone())
+            .await
+            .unwrap();
+    }
+    let blocks = (BlockNumber(1), BlockNumber(1));
+    eth_sender
+        .db
+        .store_aggregated_proof(blocks.0, blocks.1, gen_aggregated_proof(0))
+        .await;
+
+    // The invalid proof is quarantined, and only the commit operations are processed.
+    eth_sender.load_new_operations().await.unwrap();
+    assert_eq!(eth_sender.db.quarantined_proofs().await, vec![blocks]);
+    assert_eq!(
+        unprocessed_operation_ids(&eth_sender.db).await,
+        vec![proof_op.0, execute_op.0]
+    );
+
+    // Operations are held while the proof is being generated again.
+    eth_sender.load_new_operations().await.unwrap();
+    assert_eq!(eth_sender.db.quarantined_proofs().await, vec![blocks]);
+    assert_eq!(
+        unprocessed_operation_ids(&eth_sender.db).await,
+        vec![proof_op.0, execute_op.0]
+    );
+
+    // Once the valid proof is stored, it replaces the one of the operation.
+    let proof = gen_aggregated_proof(1);
+    let encoded_proof = proof.serialize_aggregated_proof();
+    eth_sender
+        .db
+        .store_aggregated_proof(blocks.0, blocks.1, proof)
+        .await;
+    eth_sender.load_new_operations().await.unwrap();
+    assert!(unprocessed_operation_ids(&eth_sender.db).await.is_empty());
+    match eth_sender.db.aggregated_operation(proof_op.0).await {
+        Some(AggregatedOperation::PublishProofBlocksOnchain(op)) => {
+            assert_eq!(op.proof, encoded_proof)
+        }
+        op => panic!("Unexpected operation: {:?}", op),
+    }
+}
@@ -51,6 +51,8 @@ pub enum TxCheckOutcome {
 pub enum OperationCommitment {
     Committed,
     Pending,
+    /// Operation transaction was replaced by the cancelling one.
+    Cancelled,
 }
 
 impl Default for OperationCommitment {
@@ -1,10 +1,12 @@
 //! The `zksync_event_listener` is a stand-alone server-application responsible for
 //! fetching new events that happen in the zkSync network from the database
-//! and streaming them to the connected WebSocket clients.
+//! and streaming them to the connected WebSocket clients. If enabled, the events are also
+//! delivered to the webhooks registered by the integrators, see the [`webhooks`] module.
 
 // Built-in uses
 // Workspace uses
-use zksync_config::ZkSyncConfig;
+use zksync_config::{WebhooksConfig, ZkSyncConfig};
+use zksync_storage::ConnectionPool;
 // External uses
 use actix::prelude::*;
 use actix_web::{web, App, Error, HttpRequest, HttpResponse, HttpServer};
@@ -19,10 +21,15 @@ pub mod listener;
 pub mod messages;
 pub mod monitor;
 pub mod subscriber;
+pub mod webhooks;
+
+const WEBHOOKS_DB_POOL_SIZE: u32 = 2;
+const REPLAY_DB_POOL_SIZE: u32 = 4;
 
 #[derive(Debug)]
 struct AppState {
     server_monitor: Addr<ServerMonitor>,
+    db_pool: ConnectionPool,
 }
 
 async fn ws_index(
@@ -30,7 +37,8 @@ async fn ws_index(
     stream: web::Payload,
     data: web::Data<AppState>,
 ) -> Result<HttpResponse, Error> {
-    ws::start(Subscriber::new(data.server_monitor.clone()), &req, stream)
+    let subscriber = Subscriber::new(data.server_monitor.clone(), data.db_pool.clone());
+    ws::start(subscriber, &req, stream)
 }
 
 pub async fn run_event_server(config: ZkSyncConfig) {
@@ -40,14 +48,40 @@ pub async fn run_event_server(config: ZkSyncConfig) {
         .unwrap()
         .start();
 
+    // Webhooks config is optional, the event server runs without them if it's absent.
+    // Events of the old blocks may be archived, so the replayed events are read back from the archive.
+    let archive = zksync_object_store::archive_from_env();
+    let new_pool = |size| {
+        let pool = ConnectionPool::new(Some(size));
+        match &archive {
+            Some(archive) => pool.with_archive(archive.clone()),
+            None => pool,
+        }
+    };
+
+    let webhooks = WebhooksConfig::from_env_if_enabled().map(|webhooks_config| {
+        let webhooks_pool = new_pool(WEBHOOKS_DB_POOL_SIZE);
+        let api_token = webhooks_config.api_token.clone();
+        webhooks::run_webhook_dispatcher(webhooks_pool.clone(), webhooks_config);
+        (webhooks_pool, api_token)
+    });
+
     let state = web::Data::new(AppState {
         server_monitor: monitor.clone(),
+        db_pool: new_pool(REPLAY_DB_POOL_SIZE),
     });
 
     let server = HttpServer::new(move || {
-        App::new()
+        let app = App::new()
             .app_data(state.clone())
-            .route("/", web::get().to(ws_index))
+            .route("/", web::get().to(ws_index));
+        match &webhooks {
+            Some((webhooks_pool, api_token)) => app.service(webhooks::api::api_scope(
+                webhooks_pool.clone(),
+                api_token.clone(),
+            )),
+            None => app,
+        }
     })
     .bind(config.event_listener.ws_bind_addr())
     .unwrap()
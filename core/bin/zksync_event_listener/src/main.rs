@@ -1,11 +1,18 @@
-use zksync_config::ZkSyncConfig;
+use zksync_config::{configs::api::LogFilterApiConfig, ZkSyncConfig};
 use zksync_event_listener::run_event_server;
 
 fn main() {
+    zksync_config::file::load_config_files().expect("failed to load the configuration files");
+    zksync_config::run_validate_config_mode(|| ZkSyncConfig::from_env().validate());
     let _vlog_guard = vlog::init();
     let config = ZkSyncConfig::from_env();
 
     let sys = actix_web::rt::System::new();
 
-    sys.block_on(run_event_server(config));
+    sys.block_on(async move {
+        zksync_http_utils::log_filter::start_log_filter_server(
+            LogFilterApiConfig::from_env().bind_addr(),
+        );
+        run_event_server(config).await
+    });
 }
@@ -8,13 +8,15 @@ use zksync_types::{
     AccountId, TokenId,
 };
 // Local uses
+use super::deserialize_one_or_many;
 
 #[derive(Debug, Clone, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct AccountFilter {
     pub accounts: Option<HashSet<AccountId>>,
     pub tokens: Option<HashSet<TokenId>>,
-    pub status: Option<AccountStateChangeStatus>,
+    #[serde(default, deserialize_with = "deserialize_one_or_many")]
+    pub status: Option<HashSet<AccountStateChangeStatus>>,
 }
 
 impl AccountFilter {
@@ -23,8 +25,8 @@ impl AccountFilter {
             EventData::Account(account_event) => account_event,
             _ => return false,
         };
-        if let Some(status) = &self.status {
-            if account_event.status != *status {
+        if let Some(statuses) = &self.status {
+            if !statuses.contains(&account_event.status) {
                 return false;
             }
         }
@@ -112,7 +114,12 @@ mod tests {
         assert!(account_filter.matches(&event));
 
         // Finally, add a status filter.
-        account_filter.status = Some(AccountStateChangeStatus::Committed);
+        account_filter.status = Some(
+            [AccountStateChangeStatus::Committed]
+                .iter()
+                .copied()
+                .collect(),
+        );
         // No match.
         assert!(!account_filter.matches(&event));
         // Correct status.
@@ -1,14 +1,17 @@
 // Built-in uses
+use std::collections::HashSet;
 // External uses
 use serde::Deserialize;
 // Workspace uses
 use zksync_types::event::{block::*, EventData, ZkSyncEvent};
 // Local uses
+use super::deserialize_one_or_many;
 
 #[derive(Debug, Clone, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct BlockFilter {
-    pub status: Option<BlockStatus>,
+    #[serde(default, deserialize_with = "deserialize_one_or_many")]
+    pub status: Option<HashSet<BlockStatus>>,
 }
 
 impl BlockFilter {
@@ -17,8 +20,8 @@ impl BlockFilter {
             EventData::Block(block_event) => block_event,
             _ => return false,
         };
-        if let Some(block_status) = &self.status {
-            if block_event.status != *block_status {
+        if let Some(statuses) = &self.status {
+            if !statuses.contains(&block_event.status) {
                 return false;
             }
         }
@@ -45,12 +48,24 @@ mod tests {
         }
         // Only match committed blocks.
         let block_filter = BlockFilter {
-            status: Some(BlockStatus::Committed),
+            status: Some([BlockStatus::Committed].iter().copied().collect()),
         };
         let block_event = get_block_event(BlockStatus::Committed);
         assert!(block_filter.matches(&block_event));
         // Should be filtered out.
         let block_event = get_block_event(BlockStatus::Finalized);
         assert!(!block_filter.matches(&block_event));
+        // Match both committed and finalized blocks.
+        let block_filter = BlockFilter {
+            status: Some(
+                [BlockStatus::Committed, BlockStatus::Finalized]
+                    .iter()
+                    .copied()
+                    .collect(),
+            ),
+        };
+        assert!(block_filter.matches(&block_event));
+        let block_event = get_block_event(BlockStatus::Reverted);
+        assert!(!block_filter.matches(&block_event));
     }
 }
@@ -1,6 +1,7 @@
 // Built-in uses
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
+use std::hash::Hash;
 // Workspace uses
 use zksync_storage::event::{get_event_type, EventType};
 use zksync_types::event::ZkSyncEvent;
@@ -17,6 +18,29 @@ mod transaction;
 #[cfg(test)]
 mod tests;
 
+/// Either a single value or a list of them.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum OneOrMany<T: Eq + Hash> {
+    One(T),
+    Many(HashSet<T>),
+}
+
+/// Deserializes the filter field accepting both a single value and a list of them,
+/// e.g. `"status": "committed"` as well as `"status": ["committed", "finalized"]`.
+fn deserialize_one_or_many<'de, D, T>(deserializer: D) -> Result<Option<HashSet<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + Eq + Hash,
+{
+    Ok(
+        Option::<OneOrMany<T>>::deserialize(deserializer)?.map(|value| match value {
+            OneOrMany::One(value) => std::iter::once(value).collect(),
+            OneOrMany::Many(values) => values,
+        }),
+    )
+}
+
 #[derive(Debug, Clone)]
 pub enum EventFilter {
     Account(AccountFilter),
@@ -62,6 +62,11 @@ fn test_filters_deserialize() {
                 "accounts": [1, 2, 3]
             }
         }"#,
+        r#"{
+            "block": {
+                "status": ["committed", "verified"]
+            }
+        }"#,
     ];
     for (i, input) in INVALID.iter().enumerate() {
         let result = serde_json::from_str::<SubscriberFilters>(input);
@@ -103,6 +108,14 @@ fn test_filters_deserialize() {
             },
             "transaction": {}
         }"#,
+        r#"{
+            "block": {
+                "status": ["committed", "finalized"]
+            },
+            "transaction": {
+                "status": ["finalized", "rejected"]
+            }
+        }"#,
     ];
     for (i, input) in VALID.iter().enumerate() {
         let result = serde_json::from_str::<SubscriberFilters>(input);
@@ -200,4 +213,27 @@ fn test_subscriber_filters() {
     assert!(filters.matches(&account_event));
     assert!(filters.matches(&block_event));
     assert!(filters.matches(&tx_event));
+
+    // Several statuses are accepted at once.
+    let input = r#"{
+        "transaction": {
+            "status": ["committed", "finalized"]
+        }
+    }"#;
+    let filters = deserialize_valid(input);
+    assert!(filters.matches(&tx_event));
+    let finalized_tx_event = get_transaction_event(
+        TransactionType::Transfer,
+        AccountId(0),
+        TokenId(0),
+        TransactionStatus::Finalized,
+    );
+    assert!(filters.matches(&finalized_tx_event));
+    let rejected_tx_event = get_transaction_event(
+        TransactionType::Transfer,
+        AccountId(0),
+        TokenId(0),
+        TransactionStatus::Rejected,
+    );
+    assert!(!filters.matches(&rejected_tx_event));
 }
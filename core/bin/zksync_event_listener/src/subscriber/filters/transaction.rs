@@ -8,6 +8,7 @@ use zksync_types::{
     AccountId, TokenId,
 };
 // Local uses
+use super::deserialize_one_or_many;
 
 #[derive(Debug, Clone, Deserialize)]
 #[serde(deny_unknown_fields)]
@@ -15,7 +16,8 @@ pub struct TransactionFilter {
     pub types: Option<HashSet<TransactionType>>,
     pub accounts: Option<HashSet<AccountId>>,
     pub tokens: Option<HashSet<TokenId>>,
-    pub status: Option<TransactionStatus>,
+    #[serde(default, deserialize_with = "deserialize_one_or_many")]
+    pub status: Option<HashSet<TransactionStatus>>,
 }
 
 impl TransactionFilter {
@@ -24,8 +26,8 @@ impl TransactionFilter {
             EventData::Transaction(tx_event) => tx_event,
             _ => return false,
         };
-        if let Some(status) = &self.status {
-            if tx_event.status != *status {
+        if let Some(statuses) = &self.status {
+            if !statuses.contains(&tx_event.status) {
                 return false;
             }
         }
@@ -93,7 +95,7 @@ mod tests {
             assert!(tx_filter.matches(&event));
         }
         // Add status filter.
-        tx_filter.status = Some(TransactionStatus::Rejected);
+        tx_filter.status = Some([TransactionStatus::Rejected].iter().copied().collect());
         // Committed transaction doesn't match.
         assert!(!tx_filter.matches(&event));
         // Change the status.
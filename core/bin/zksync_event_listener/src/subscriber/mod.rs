@@ -1,34 +1,179 @@
 // Built-in uses
+use std::convert::TryFrom;
 // External uses
 use actix::prelude::*;
 use actix_web_actors::ws;
+use serde::Serialize;
 // Workspace uses
+use zksync_storage::ConnectionPool;
+use zksync_types::event::{EventId, ZkSyncEvent};
 // Local uses
 use crate::messages::{NewEvents, RegisterSubscriber, RemoveSubscriber, Shutdown};
 use crate::monitor::ServerMonitor;
 use filters::SubscriberFilters;
+use pending::PendingEvents;
+use request::{ReplayFrom, SubscriptionRequest};
 
-mod filters;
+pub(crate) mod filters;
+mod pending;
+mod request;
+
+/// Max amount of the events loaded from the database at once during the replay.
+const REPLAY_BATCH_SIZE: u32 = 1000;
+/// Max amount of the new events buffered while the stored ones are replayed.
+const MAX_PENDING_EVENTS: usize = 10_000;
+
+/// The event sent to the client along with its id, so the client
+/// can resume the stream from it after the reconnect.
+#[derive(Debug, Serialize)]
+struct SubscriberEvent<'a> {
+    event_id: EventId,
+    #[serde(flatten)]
+    event: &'a ZkSyncEvent,
+}
 
 /// The WebSocket actor. Created for each connected client.
 #[derive(Debug)]
 pub struct Subscriber {
     /// Subscriber's events interests. Remain `None` until the client
     /// sends JSON-serialized map of filters. Before that, all incoming
-    /// events will be ignored.
+    /// events will be ignored. The client may send the new filters at any
+    /// time, they replace the previous ones.
     filters: Option<SubscriberFilters>,
     /// The address of the [`ServerMonitor`] for registering.
     monitor: Addr<ServerMonitor>,
+    /// Pool of connections to the database, used to replay the stored events.
+    db_pool: ConnectionPool,
+    /// New events received while the stored ones are replayed.
+    /// They are sent once the replay is finished, `None` if there's no replay.
+    pending_events: Option<PendingEvents>,
+    /// The id of the last event processed for the client. Since the replayed events
+    /// overlap with the new ones, the events up to this one are skipped.
+    last_event_id: Option<EventId>,
 }
 
 impl Subscriber {
-    pub fn new(monitor: Addr<ServerMonitor>) -> Self {
+    pub fn new(monitor: Addr<ServerMonitor>, db_pool: ConnectionPool) -> Self {
         Self {
             filters: None,
             monitor,
+            db_pool,
+            pending_events: None,
+            last_event_id: None,
         }
     }
 
+    /// Applies the subscription request of the client, the new filters replace the previous ones.
+    /// Returns the point the stored events should be replayed from, if any.
+    fn subscribe(&mut self, request: SubscriptionRequest) -> Option<ReplayFrom> {
+        self.filters = Some(request.filters);
+        // The replay that is already in progress continues with the new filters.
+        let replay_from = request
+            .replay_from
+            .filter(|_| self.pending_events.is_none())?;
+        // The replayed events are sent even if the client has received them before.
+        self.last_event_id = None;
+        self.pending_events = Some(PendingEvents::new(MAX_PENDING_EVENTS));
+        Some(replay_from)
+    }
+
+    /// Sends the events matching the filters to the client,
+    /// skipping the ones that are already processed.
+    fn send_events<'a>(
+        &mut self,
+        events: impl IntoIterator<Item = &'a ZkSyncEvent>,
+        ctx: &mut <Self as Actor>::Context,
+    ) {
+        let filters = match &self.filters {
+            Some(filters) => filters,
+            None => return,
+        };
+        for event in events {
+            if matches!(self.last_event_id, Some(id) if event.id <= id) {
+                continue;
+            }
+            self.last_event_id = Some(event.id);
+            if !filters.matches(event) {
+                continue;
+            }
+            let event = SubscriberEvent {
+                event_id: event.id,
+                event,
+            };
+            let json = serde_json::to_string(&event).unwrap();
+            ctx.text(json);
+        }
+    }
+
+    /// Sends the stored events to the client batch by batch, and then the new
+    /// events received in the meantime. Once it's done, the new events are sent
+    /// to the client right away.
+    fn replay(&mut self, from: ReplayFrom, ctx: &mut <Self as Actor>::Context) {
+        let pool = self.db_pool.clone();
+        async move {
+            let mut storage = pool.access_storage().await?;
+            let from = match from {
+                ReplayFrom::EventId(event_id) => Some(event_id),
+                ReplayFrom::BlockNumber(block_number) => storage
+                    .event_schema()
+                    .get_first_event_id_since_block(block_number)
+                    .await?
+                    .map(|event_id| EventId(event_id.saturating_sub(1))),
+            };
+            let events = match from {
+                Some(from) => storage
+                    .event_schema()
+                    .fetch_events_batch(from, REPLAY_BATCH_SIZE)
+                    .await?
+                    .into_iter()
+                    .map(ZkSyncEvent::try_from)
+                    .collect::<Result<_, _>>()?,
+                None => Vec::new(),
+            };
+            Ok(events)
+        }
+        .into_actor(self)
+        .map(|result: anyhow::Result<Vec<ZkSyncEvent>>, act, ctx| {
+            let events = match result {
+                Ok(events) => events,
+                Err(err) => {
+                    vlog::error!("Couldn't replay the events, reason: {:?}", err);
+                    let reason = Some(ws::CloseReason {
+                        code: ws::CloseCode::Error,
+                        description: Some("couldn't replay the events".to_string()),
+                    });
+                    ctx.close(reason);
+                    return act.shutdown(ctx);
+                }
+            };
+            act.send_events(&events, ctx);
+            match events.last() {
+                Some(event) if events.len() == REPLAY_BATCH_SIZE as usize => {
+                    act.replay(ReplayFrom::EventId(event.id), ctx);
+                }
+                _ => {
+                    let replay_from = act
+                        .pending_events
+                        .as_mut()
+                        .and_then(PendingEvents::take_replay_from);
+                    if let Some(from) = replay_from {
+                        // Some of the new events were dropped, they are read from the database.
+                        act.replay(ReplayFrom::EventId(from), ctx);
+                        return;
+                    }
+                    // The replay has caught up with the new events.
+                    let pending_events = act
+                        .pending_events
+                        .take()
+                        .map(PendingEvents::into_events)
+                        .unwrap_or_default();
+                    act.send_events(&pending_events, ctx);
+                }
+            }
+        })
+        .spawn(ctx);
+    }
+
     /// Remove the subscriber's address from the monitor's set and stop
     /// the execution context completely. Should be called instead of
     /// `ctx.stop()`.
@@ -84,14 +229,13 @@ impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for Subscriber {
         match msg {
             Ok(ws::Message::Ping(msg)) => ctx.pong(&msg),
             Ok(ws::Message::Text(text)) => {
-                // If the client already registered his interests,
-                // ignore the message, otherwise, try to parse the text.
-                if self.filters.is_some() {
-                    return;
-                }
-                match serde_json::from_str(&text) {
-                    Ok(filters) => {
-                        self.filters = Some(filters);
+                // The client (re)registers his interests, the events
+                // are filtered by the new ones starting from the next batch.
+                match SubscriptionRequest::parse(&text) {
+                    Ok(request) => {
+                        if let Some(from) = self.subscribe(request) {
+                            self.replay(from, ctx);
+                        }
                     }
                     Err(err) => {
                         // The client provided invalid JSON, give
@@ -132,17 +276,11 @@ impl Handler<NewEvents> for Subscriber {
     type Result = ();
 
     fn handle(&mut self, msg: NewEvents, ctx: &mut Self::Context) {
-        let filters = match &self.filters {
-            Some(filters) => filters,
-            None => return,
-        };
-        for event in msg.0.as_ref() {
-            if !filters.matches(event) {
-                continue;
-            }
-            let json = serde_json::to_string(&event).unwrap();
-            ctx.text(json);
+        if let Some(pending_events) = &mut self.pending_events {
+            pending_events.push(&msg.0);
+            return;
         }
+        self.send_events(msg.0.as_ref(), ctx);
     }
 }
 
@@ -159,3 +297,71 @@ impl Handler<Shutdown> for Subscriber {
         ctx.stop();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_types::{
+        event::{block::BlockStatus, test_data::*, transaction::*},
+        AccountId, BlockNumber, TokenId,
+    };
+
+    fn subscription(text: &str) -> SubscriptionRequest {
+        SubscriptionRequest::parse(text).unwrap()
+    }
+
+    fn matches(subscriber: &Subscriber, event: &ZkSyncEvent) -> bool {
+        subscriber.filters.as_ref().unwrap().matches(event)
+    }
+
+    /// Checks that the filters sent by the client replace the previous ones
+    /// and the replay is started once.
+    #[actix::test]
+    async fn test_filters_update() {
+        let monitor = ServerMonitor::new().start();
+        let mut subscriber = Subscriber::new(monitor, ConnectionPool::new(Some(1)));
+        let committed_block = get_block_event(BlockStatus::Committed);
+        let finalized_block = get_block_event(BlockStatus::Finalized);
+        let tx_event = get_transaction_event(
+            TransactionType::Transfer,
+            AccountId(0),
+            TokenId(0),
+            TransactionStatus::Committed,
+        );
+
+        let replay_from =
+            subscriber.subscribe(subscription(r#"{ "block": { "status": "committed" } }"#));
+        assert_eq!(replay_from, None);
+        assert!(matches(&subscriber, &committed_block));
+        assert!(!matches(&subscriber, &finalized_block));
+        assert!(!matches(&subscriber, &tx_event));
+
+        // New filters replace the previous ones rather than extend them.
+        let replay_from = subscriber.subscribe(subscription(r#"{ "transaction": {} }"#));
+        assert_eq!(replay_from, None);
+        assert!(!matches(&subscriber, &committed_block));
+        assert!(matches(&subscriber, &tx_event));
+
+        // The replay is started for the subscription requesting it.
+        let replay_from = subscriber.subscribe(subscription(
+            r#"{
+                "filters": { "block": {} },
+                "replay_from": { "block_number": 10 }
+            }"#,
+        ));
+        assert_eq!(replay_from, Some(ReplayFrom::BlockNumber(BlockNumber(10))));
+        assert!(subscriber.pending_events.is_some());
+        assert!(matches(&subscriber, &finalized_block));
+
+        // The replay in progress isn't restarted, but the events are filtered by the new filters.
+        let replay_from = subscriber.subscribe(subscription(
+            r#"{
+                "filters": { "transaction": {} },
+                "replay_from": { "event_id": 1 }
+            }"#,
+        ));
+        assert_eq!(replay_from, None);
+        assert!(!matches(&subscriber, &finalized_block));
+        assert!(matches(&subscriber, &tx_event));
+    }
+}
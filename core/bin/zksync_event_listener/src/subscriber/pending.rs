@@ -0,0 +1,93 @@
+// Built-in uses
+// External uses
+// Workspace uses
+use zksync_types::event::{EventId, ZkSyncEvent};
+// Local uses
+
+/// New events received by the subscriber while the stored ones are replayed.
+///
+/// The amount of the buffered events is limited. Since every new event is already
+/// stored in the database, the events that don't fit are dropped and replayed
+/// from the database again instead.
+#[derive(Debug)]
+pub struct PendingEvents {
+    events: Vec<ZkSyncEvent>,
+    capacity: usize,
+    /// The id preceding the first dropped event, the replay is continued from it.
+    replay_from: Option<EventId>,
+}
+
+impl PendingEvents {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            events: Vec::new(),
+            capacity,
+            replay_from: None,
+        }
+    }
+
+    /// Buffers the new events. Once the capacity is exceeded, all the buffered
+    /// events are dropped, so they have to be replayed from the database.
+    pub fn push(&mut self, events: &[ZkSyncEvent]) {
+        if self.events.len() + events.len() <= self.capacity {
+            self.events.extend(events.iter().cloned());
+            return;
+        }
+        if let Some(first) = self.events.first().or_else(|| events.first()) {
+            self.replay_from
+                .get_or_insert(EventId(first.id.saturating_sub(1)));
+        }
+        self.events.clear();
+    }
+
+    /// Returns the id the replay should be continued from if any events
+    /// were dropped since the previous call.
+    pub fn take_replay_from(&mut self) -> Option<EventId> {
+        self.replay_from.take()
+    }
+
+    pub fn into_events(self) -> Vec<ZkSyncEvent> {
+        self.events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_types::event::{block::BlockStatus, test_data::get_block_event};
+
+    fn events(ids: impl IntoIterator<Item = u64>) -> Vec<ZkSyncEvent> {
+        ids.into_iter()
+            .map(|id| {
+                let mut event = get_block_event(BlockStatus::Committed);
+                event.id = EventId(id);
+                event
+            })
+            .collect()
+    }
+
+    fn ids(events: &[ZkSyncEvent]) -> Vec<u64> {
+        events.iter().map(|event| *event.id).collect()
+    }
+
+    #[test]
+    fn test_pending_events() {
+        let mut pending = PendingEvents::new(3);
+        pending.push(&events(1..=2));
+        pending.push(&events(3..=3));
+        assert_eq!(pending.take_replay_from(), None);
+
+        // The overflowed buffer is dropped entirely, the replay is continued
+        // from the first dropped event.
+        pending.push(&events(4..=4));
+        assert_eq!(pending.take_replay_from(), Some(EventId(0)));
+        assert_eq!(pending.take_replay_from(), None);
+
+        // Events are buffered again after the drop.
+        pending.push(&events(5..=6));
+        pending.push(&events(7..=10));
+        pending.push(&events(11..=12));
+        assert_eq!(pending.take_replay_from(), Some(EventId(4)));
+        assert_eq!(ids(&pending.into_events()), vec![11, 12]);
+    }
+}
@@ -0,0 +1,108 @@
+// Built-in uses
+// External uses
+use serde::Deserialize;
+use serde_json::Value;
+// Workspace uses
+use zksync_types::{event::EventId, BlockNumber};
+// Local uses
+use super::filters::SubscriberFilters;
+
+/// The point in the history the events are replayed from.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReplayFrom {
+    /// Start from the first event of the block.
+    BlockNumber(BlockNumber),
+    /// Start from the event following the given one,
+    /// e.g. the last event received before the disconnect.
+    EventId(EventId),
+}
+
+/// The message sent by the client to subscribe to the events.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SubscriptionRequest {
+    pub filters: SubscriberFilters,
+    /// If set, the matching events stored in the database are sent
+    /// before the new ones.
+    #[serde(default)]
+    pub replay_from: Option<ReplayFrom>,
+}
+
+impl SubscriptionRequest {
+    /// Parses the client's message. Besides the full request, a bare map of filters
+    /// is accepted, it subscribes to the new events only.
+    pub fn parse(text: &str) -> serde_json::Result<Self> {
+        let value: Value = serde_json::from_str(text)?;
+        if value.get("filters").is_some() {
+            serde_json::from_value(value)
+        } else {
+            Ok(Self {
+                filters: serde_json::from_value(value)?,
+                replay_from: None,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subscription_request() {
+        let request = SubscriptionRequest::parse(r#"{ "block": {} }"#).unwrap();
+        assert_eq!(request.replay_from, None);
+
+        let request = SubscriptionRequest::parse(
+            r#"{
+                "filters": { "block": {} },
+                "replay_from": { "block_number": 10 }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            request.replay_from,
+            Some(ReplayFrom::BlockNumber(BlockNumber(10)))
+        );
+
+        let request = SubscriptionRequest::parse(
+            r#"{
+                "filters": {},
+                "replay_from": { "event_id": 1500 }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            request.replay_from,
+            Some(ReplayFrom::EventId(EventId(1500)))
+        );
+
+        const INVALID: &[&str] = &[
+            // Unknown replay start.
+            r#"{
+                "filters": {},
+                "replay_from": { "block": 10 }
+            }"#,
+            // Unknown fields.
+            r#"{
+                "filters": {},
+                "replay": { "block_number": 10 }
+            }"#,
+            // Invalid filters.
+            r#"{
+                "filters": { "blocks": {} }
+            }"#,
+            r#"{
+                "replay_from": { "block_number": 10 }
+            }"#,
+        ];
+        for (i, input) in INVALID.iter().enumerate() {
+            assert!(
+                SubscriptionRequest::parse(input).is_err(),
+                "Input #{} is supposed to be invalid",
+                i
+            );
+        }
+    }
+}
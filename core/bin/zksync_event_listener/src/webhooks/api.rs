@@ -0,0 +1,188 @@
+//! Management API of the webhooks, available to the holders of the configured token only.
+
+// Built-in uses
+// External uses
+use actix_web::{error, web, HttpRequest, HttpResponse, Scope};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+// Workspace uses
+use zksync_storage::ConnectionPool;
+// Local uses
+use crate::subscriber::filters::SubscriberFilters;
+
+#[derive(Debug, Clone)]
+struct ApiState {
+    db_pool: ConnectionPool,
+    api_token: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WebhookRequest {
+    /// URL the events are `POST`ed to.
+    pub url: String,
+    /// Secret of the HMAC signature of the delivered payloads.
+    pub secret: String,
+    /// Filters of the events in the format of the WebSocket subscriptions,
+    /// the empty ones match all of the events.
+    pub filters: Value,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WebhookResponse {
+    pub id: i64,
+}
+
+/// Compares the tokens in constant time. Tokens are hashed first, so the length
+/// of the expected one is not leaked either.
+fn tokens_match(provided: &str, expected: &str) -> bool {
+    let provided = Sha256::digest(provided.as_bytes());
+    let expected = Sha256::digest(expected.as_bytes());
+    provided
+        .iter()
+        .zip(expected.iter())
+        .fold(0u8, |acc, (lhs, rhs)| acc | (lhs ^ rhs))
+        == 0
+}
+
+fn check_token(req: &HttpRequest, api_token: &str) -> actix_web::Result<()> {
+    let token = req
+        .headers()
+        .get("Authorization")
+        .and_then(|header| header.to_str().ok())
+        .and_then(|header| header.strip_prefix("Bearer "));
+    match token {
+        Some(token) if tokens_match(token, api_token) => Ok(()),
+        _ => Err(error::ErrorUnauthorized("invalid API token")),
+    }
+}
+
+async fn add_webhook(
+    req: HttpRequest,
+    state: web::Data<ApiState>,
+    body: web::Json<WebhookRequest>,
+) -> actix_web::Result<HttpResponse> {
+    check_token(&req, &state.api_token)?;
+    let body = body.into_inner();
+    let url = reqwest::Url::parse(&body.url).map_err(error::ErrorBadRequest)?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(error::ErrorBadRequest("only HTTP(S) URLs are supported"));
+    }
+    if body.secret.is_empty() {
+        return Err(error::ErrorBadRequest("secret can't be empty"));
+    }
+    serde_json::from_value::<SubscriberFilters>(body.filters.clone())
+        .map_err(error::ErrorBadRequest)?;
+
+    let id = state
+        .db_pool
+        .access_storage()
+        .await
+        .map_err(error::ErrorInternalServerError)?
+        .webhooks_schema()
+        .add_webhook(url.as_str(), &body.secret, &body.filters)
+        .await
+        .map_err(error::ErrorInternalServerError)?;
+    Ok(HttpResponse::Ok().json(WebhookResponse { id }))
+}
+
+async fn remove_webhook(
+    req: HttpRequest,
+    state: web::Data<ApiState>,
+    id: web::Path<i64>,
+) -> actix_web::Result<HttpResponse> {
+    check_token(&req, &state.api_token)?;
+    let is_removed = state
+        .db_pool
+        .access_storage()
+        .await
+        .map_err(error::ErrorInternalServerError)?
+        .webhooks_schema()
+        .deactivate_webhook(id.into_inner())
+        .await
+        .map_err(error::ErrorInternalServerError)?;
+    if !is_removed {
+        return Err(error::ErrorNotFound("webhook is not found"));
+    }
+    Ok(HttpResponse::Ok().finish())
+}
+
+async fn dead_letters(
+    req: HttpRequest,
+    state: web::Data<ApiState>,
+    id: web::Path<i64>,
+) -> actix_web::Result<HttpResponse> {
+    check_token(&req, &state.api_token)?;
+    let dead_letters = state
+        .db_pool
+        .access_storage()
+        .await
+        .map_err(error::ErrorInternalServerError)?
+        .webhooks_schema()
+        .load_dead_letters(id.into_inner())
+        .await
+        .map_err(error::ErrorInternalServerError)?;
+    Ok(HttpResponse::Ok().json(dead_letters))
+}
+
+async fn retry_dead_letter(
+    req: HttpRequest,
+    state: web::Data<ApiState>,
+    path: web::Path<(i64, i64)>,
+) -> actix_web::Result<HttpResponse> {
+    check_token(&req, &state.api_token)?;
+    let (webhook_id, id) = path.into_inner();
+    let is_retried = state
+        .db_pool
+        .access_storage()
+        .await
+        .map_err(error::ErrorInternalServerError)?
+        .webhooks_schema()
+        .retry_dead_letter(webhook_id, id)
+        .await
+        .map_err(error::ErrorInternalServerError)?;
+    if !is_retried {
+        return Err(error::ErrorNotFound("dead delivery is not found"));
+    }
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Returns the `/webhooks` scope of the management API.
+///
+/// - `POST /webhooks` registers the webhook.
+/// - `DELETE /webhooks/{id}` stops the deliveries to the webhook.
+/// - `GET /webhooks/{id}/dead_letters` lists the deliveries that ran out of attempts.
+/// - `POST /webhooks/{webhook_id}/dead_letters/{id}/retry` puts the dead delivery back to the queue.
+pub fn api_scope(db_pool: ConnectionPool, api_token: String) -> Scope {
+    web::scope("/webhooks")
+        .app_data(web::Data::new(ApiState { db_pool, api_token }))
+        .route("", web::post().to(add_webhook))
+        .route("/{id}", web::delete().to(remove_webhook))
+        .route("/{id}/dead_letters", web::get().to(dead_letters))
+        .route(
+            "/{webhook_id}/dead_letters/{id}/retry",
+            web::post().to(retry_dead_letter),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::test::TestRequest;
+
+    use super::*;
+
+    #[test]
+    fn api_token_check() {
+        let request = |header: &str| {
+            TestRequest::default()
+                .insert_header(("Authorization", header))
+                .to_http_request()
+        };
+
+        assert!(check_token(&request("Bearer secret-token"), "secret-token").is_ok());
+        assert!(check_token(&request("Bearer secret-toke"), "secret-token").is_err());
+        assert!(check_token(&request("Bearer secret-token!"), "secret-token").is_err());
+        assert!(check_token(&request("secret-token"), "secret-token").is_err());
+        assert!(check_token(&TestRequest::default().to_http_request(), "secret-token").is_err());
+    }
+}
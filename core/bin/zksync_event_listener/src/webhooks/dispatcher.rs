@@ -0,0 +1,212 @@
+// Built-in uses
+use std::convert::TryFrom;
+// External uses
+use chrono::Utc;
+use futures_util::future::join_all;
+use hmac::{Hmac, Mac, NewMac};
+use sha2::Sha256;
+// Workspace uses
+use zksync_config::WebhooksConfig;
+use zksync_storage::{webhooks::records::PendingWebhookDelivery, ConnectionPool};
+use zksync_types::event::{EventId, ZkSyncEvent};
+// Local uses
+use crate::subscriber::filters::SubscriberFilters;
+
+/// Header containing the HMAC-SHA256 signature of the delivered payload.
+pub const SIGNATURE_HEADER: &str = "X-ZkSync-Signature";
+/// Header containing the id of the delivery, the same for all of its attempts.
+pub const DELIVERY_HEADER: &str = "X-ZkSync-Delivery";
+
+/// Returns the hex-encoded HMAC-SHA256 signature of the payload.
+pub fn sign_payload(secret: &str, payload: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(payload);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// Moves the new events to the delivery queues of the matching webhooks
+/// and sends the due deliveries.
+#[derive(Debug)]
+pub struct WebhookDispatcher {
+    db_pool: ConnectionPool,
+    client: reqwest::Client,
+    config: WebhooksConfig,
+}
+
+impl WebhookDispatcher {
+    pub fn new(db_pool: ConnectionPool, config: WebhooksConfig) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(config.request_timeout())
+            .build()
+            .expect("failed to create the webhooks HTTP client");
+        Self {
+            db_pool,
+            client,
+            config,
+        }
+    }
+
+    /// Enqueues up to `batch_size` events emitted since the last check for the webhooks whose filters
+    /// they match. Returns the amount of the processed events.
+    ///
+    /// Events that can't be decoded are skipped, so they don't block the following ones.
+    pub async fn enqueue_new_events(&self) -> anyhow::Result<usize> {
+        let mut storage = self.db_pool.access_storage().await?;
+        let webhooks = storage.webhooks_schema().load_active_webhooks().await?;
+        let from = match webhooks.iter().map(|webhook| webhook.last_event_id).min() {
+            Some(from) => EventId(from as u64),
+            None => return Ok(0),
+        };
+        let stored_events = storage
+            .event_schema()
+            .fetch_events_batch(from, self.config.batch_size)
+            .await?;
+        let last_event_id = match stored_events.last() {
+            Some(event) => EventId(event.id as u64),
+            None => return Ok(0),
+        };
+        let events_count = stored_events.len();
+        let events: Vec<_> = stored_events
+            .into_iter()
+            .filter_map(|event| {
+                let id = event.id;
+                let payload = ZkSyncEvent::try_from(event).and_then(|event| {
+                    let payload = serde_json::to_value(&event)?;
+                    Ok((event, payload))
+                });
+                match payload {
+                    Ok(payload) => Some(payload),
+                    Err(err) => {
+                        metrics::increment_counter!("event_listener.webhooks.invalid_events");
+                        vlog::error!("Skipping the event {} that can't be delivered: {}", id, err);
+                        None
+                    }
+                }
+            })
+            .collect();
+
+        for webhook in webhooks {
+            // Filters are validated on the registration, the webhook with the invalid ones
+            // is deactivated, otherwise it would hold back the events of the others.
+            let filters: SubscriberFilters = match serde_json::from_value(webhook.filters) {
+                Ok(filters) => filters,
+                Err(err) => {
+                    vlog::error!(
+                        "Deactivating the webhook {} with the invalid filters: {}",
+                        webhook.id,
+                        err
+                    );
+                    storage
+                        .webhooks_schema()
+                        .deactivate_webhook(webhook.id)
+                        .await?;
+                    continue;
+                }
+            };
+            let deliveries: Vec<_> = events
+                .iter()
+                .filter(|(event, _)| {
+                    *event.id > webhook.last_event_id as u64 && filters.matches(event)
+                })
+                .map(|(event, payload)| (event.id, payload.clone()))
+                .collect();
+            storage
+                .webhooks_schema()
+                .enqueue_deliveries(webhook.id, &deliveries, last_event_id)
+                .await?;
+        }
+        Ok(events_count)
+    }
+
+    /// Sends the due deliveries concurrently, scheduling the retries of the failed ones.
+    ///
+    /// Deliveries are claimed for twice the request timeout, so the concurrent dispatchers
+    /// don't send them twice.
+    pub async fn send_pending_deliveries(&self) -> anyhow::Result<()> {
+        let mut storage = self.db_pool.access_storage().await?;
+        let claimed_until =
+            Utc::now() + chrono::Duration::from_std(self.config.request_timeout() * 2)?;
+        let deliveries = storage
+            .webhooks_schema()
+            .claim_pending_deliveries(i64::from(self.config.batch_size), claimed_until)
+            .await?;
+        let results = join_all(deliveries.iter().map(|delivery| self.send(delivery))).await;
+
+        for (delivery, result) in deliveries.iter().zip(results) {
+            match result {
+                Ok(()) => {
+                    metrics::increment_counter!("event_listener.webhooks.delivered");
+                    storage
+                        .webhooks_schema()
+                        .mark_delivered(delivery.id)
+                        .await?;
+                }
+                Err(err) => {
+                    let attempts = delivery.attempts as u32 + 1;
+                    let next_attempt_at = self
+                        .config
+                        .retry_delay(attempts)
+                        .map(|delay| Utc::now() + chrono::Duration::from_std(delay).unwrap());
+                    if next_attempt_at.is_none() {
+                        metrics::increment_counter!("event_listener.webhooks.dead_letters");
+                        vlog::warn!(
+                            "Delivery {} of the webhook {} ran out of attempts: {}",
+                            delivery.id,
+                            delivery.webhook_id,
+                            err
+                        );
+                    }
+                    storage
+                        .webhooks_schema()
+                        .record_failed_attempt(delivery.id, &err.to_string(), next_attempt_at)
+                        .await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes the deliveries sent earlier than the retention period ago.
+    pub async fn prune_delivered(&self) -> anyhow::Result<()> {
+        let delivered_before =
+            Utc::now() - chrono::Duration::from_std(self.config.delivered_retention())?;
+        let pruned = self
+            .db_pool
+            .access_storage()
+            .await?
+            .webhooks_schema()
+            .prune_delivered(delivered_before)
+            .await?;
+        metrics::counter!("event_listener.webhooks.pruned", pruned);
+        Ok(())
+    }
+
+    async fn send(&self, delivery: &PendingWebhookDelivery) -> anyhow::Result<()> {
+        let payload = serde_json::to_vec(&delivery.payload)?;
+        let signature = sign_payload(&delivery.secret, &payload);
+        self.client
+            .post(&delivery.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(SIGNATURE_HEADER, format!("sha256={}", signature))
+            .header(DELIVERY_HEADER, delivery.id.to_string())
+            .body(payload)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn payload_signature() {
+        assert_eq!(
+            sign_payload("key", b"The quick brown fox jumps over the lazy dog"),
+            "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+        );
+    }
+}
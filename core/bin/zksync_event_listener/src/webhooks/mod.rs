@@ -0,0 +1,60 @@
+//! Webhooks deliver the events to the URLs registered by the integrators, so they
+//! don't have to keep the WebSocket connection or poll the API.
+//!
+//! Webhooks are registered through the management API along with the HMAC secret and
+//! the filters in the format of the WebSocket subscriptions. Every new event matching
+//! the filters is put into the persistent delivery queue of the webhook and `POST`ed to
+//! its URL with the `X-ZkSync-Signature: sha256=<hex>` header containing the HMAC-SHA256
+//! signature of the body. Failed deliveries are retried with the exponential backoff and
+//! moved to the dead letters once out of attempts, where they can be inspected and retried.
+//! Delivered payloads are pruned once they are older than the configured retention period.
+
+// Built-in uses
+use std::time::{Duration, Instant};
+// External uses
+use tokio::time;
+// Workspace uses
+use zksync_config::WebhooksConfig;
+use zksync_storage::ConnectionPool;
+// Local uses
+use dispatcher::WebhookDispatcher;
+
+pub mod api;
+pub mod dispatcher;
+
+/// How often the delivered payloads older than the retention period are pruned.
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Runs the dispatcher of the webhook deliveries on the current `actix` system.
+pub fn run_webhook_dispatcher(db_pool: ConnectionPool, config: WebhooksConfig) {
+    let mut timer = time::interval(config.poll_interval());
+    let batch_size = config.batch_size as usize;
+    let dispatcher = WebhookDispatcher::new(db_pool, config);
+
+    actix::spawn(async move {
+        let mut last_prune: Option<Instant> = None;
+        loop {
+            timer.tick().await;
+            // Events are enqueued in batches until the backlog is processed.
+            loop {
+                match dispatcher.enqueue_new_events().await {
+                    Ok(processed) if processed == batch_size => continue,
+                    Ok(_) => break,
+                    Err(err) => {
+                        vlog::error!("Failed to enqueue the webhook deliveries: {}", err);
+                        break;
+                    }
+                }
+            }
+            if let Err(err) = dispatcher.send_pending_deliveries().await {
+                vlog::error!("Failed to send the webhook deliveries: {}", err);
+            }
+            if last_prune.map_or(true, |last_prune| last_prune.elapsed() >= PRUNE_INTERVAL) {
+                last_prune = Some(Instant::now());
+                if let Err(err) = dispatcher.prune_delivered().await {
+                    vlog::error!("Failed to prune the webhook deliveries: {}", err);
+                }
+            }
+        }
+    });
+}
@@ -1,4 +1,4 @@
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use futures::{
     channel::{mpsc, oneshot},
     SinkExt,
@@ -7,9 +7,9 @@ use num::Zero;
 
 use zksync_storage::{chain::operations_ext::records::TxReceiptResponse, ConnectionPool};
 use zksync_types::{
-    forced_exit_requests::{ForcedExitRequest, ForcedExitRequestId},
+    forced_exit_requests::{DormantBalance, ForcedExitRequest, ForcedExitRequestId},
     tx::TxHash,
-    AccountId, Nonce,
+    Account, AccountId, Address, Nonce,
 };
 
 use zksync_api::api_server::forced_exit_checker::{ForcedExitAccountAgeChecker, ForcedExitChecker};
@@ -25,6 +25,7 @@ pub trait CoreInteractionWrapper {
     async fn get_nonce(&self, account_id: AccountId) -> anyhow::Result<Option<Nonce>>;
     async fn get_unconfirmed_requests(&self) -> anyhow::Result<Vec<ForcedExitRequest>>;
     async fn set_fulfilled_at(&self, id: i64) -> anyhow::Result<()>;
+    async fn set_payment_received_at(&self, id: i64) -> anyhow::Result<()>;
     async fn set_fulfilled_by(
         &self,
         id: ForcedExitRequestId,
@@ -43,6 +44,16 @@ pub trait CoreInteractionWrapper {
         deleting_threshold: chrono::Duration,
     ) -> anyhow::Result<()>;
     async fn check_forced_exit_request(&self, request: &ForcedExitRequest) -> anyhow::Result<bool>;
+    async fn get_committed_account(&self, account_id: AccountId)
+        -> anyhow::Result<Option<Account>>;
+    async fn get_dormant_balances(
+        &self,
+        idle_since: DateTime<Utc>,
+        max_balance_usd: f64,
+        excluded: &[Address],
+        limit: u32,
+    ) -> anyhow::Result<Vec<DormantBalance>>;
+    async fn send_tx(&mut self, tx: SignedZkSyncTx) -> anyhow::Result<TxHash>;
 }
 
 #[derive(Clone)]
@@ -102,6 +113,17 @@ impl CoreInteractionWrapper for MempoolCoreInteractionWrapper {
         Ok(())
     }
 
+    async fn set_payment_received_at(&self, id: i64) -> anyhow::Result<()> {
+        let mut storage = self.connection_pool.access_storage().await?;
+        let mut fe_schema = storage.forced_exit_requests_schema();
+
+        fe_schema.set_payment_received_at(id, Utc::now()).await?;
+
+        vlog::info!("Payment for ForcedExit request with id {} was received", id);
+
+        Ok(())
+    }
+
     async fn set_fulfilled_by(
         &self,
         id: ForcedExitRequestId,
@@ -181,26 +203,81 @@ impl CoreInteractionWrapper for MempoolCoreInteractionWrapper {
 
     async fn check_forced_exit_request(&self, request: &ForcedExitRequest) -> anyhow::Result<bool> {
         let mut storage = self.connection_pool.access_storage().await?;
-        let target = request.target;
-        let eligible = self
-            .forced_exit_checker
-            .check_forced_exit(&mut storage, target)
+
+        // The transactions are sent in a batch, so the request is possible
+        // only if every target can be exited
+        let targets = std::iter::once(request.target).chain(
+            request
+                .additional_targets
+                .iter()
+                .map(|target| target.target),
+        );
+        for target in targets {
+            let eligible = self
+                .forced_exit_checker
+                .check_forced_exit(&mut storage, target)
+                .await?;
+
+            let mut account_schema = storage.chain().account_schema();
+
+            let target_state = account_schema.account_state_by_address(target).await?;
+            let target_nonce = target_state.committed.map(|state| state.1.nonce);
+
+            let possible = match target_nonce {
+                // The forced exit is possible is the account is eligile (existed for long enough)
+                // and its nonce is zero
+                Some(nonce) => nonce.is_zero() && eligible,
+                // The account does exist. The ForcedExit can not be applied to account
+                // which does not exist in the network
+                None => false,
+            };
+            if !possible {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    async fn get_committed_account(
+        &self,
+        account_id: AccountId,
+    ) -> anyhow::Result<Option<Account>> {
+        let mut storage = self.connection_pool.access_storage().await?;
+        let account = storage
+            .chain()
+            .account_schema()
+            .last_committed_state_for_account(account_id)
+            .await?
+            .1;
+
+        Ok(account)
+    }
+
+    async fn get_dormant_balances(
+        &self,
+        idle_since: DateTime<Utc>,
+        max_balance_usd: f64,
+        excluded: &[Address],
+        limit: u32,
+    ) -> anyhow::Result<Vec<DormantBalance>> {
+        let mut storage = self.connection_pool.access_storage().await?;
+        let balances = storage
+            .forced_exit_requests_schema()
+            .get_dormant_balances(idle_since, max_balance_usd, excluded, limit)
             .await?;
 
-        let mut account_schema = storage.chain().account_schema();
+        Ok(balances)
+    }
 
-        let target_state = account_schema.account_state_by_address(target).await?;
-        let target_nonce = target_state.committed.map(|state| state.1.nonce);
-
-        if let Some(nonce) = target_nonce {
-            // The forced exit is possible is the account is eligile (existed for long enough)
-            // and its nonce is zero
-            let possible = nonce.is_zero() && eligible;
-            Ok(possible)
-        } else {
-            // The account does exist. The ForcedExit can not be applied to account
-            // which does not exist in the network
-            Ok(false)
-        }
+    async fn send_tx(&mut self, tx: SignedZkSyncTx) -> anyhow::Result<TxHash> {
+        let tx_hash = tx.hash();
+
+        let (sender, receiver) = oneshot::channel();
+        let item = MempoolTransactionRequest::NewTx(Box::new(tx), sender);
+        self.mempool_tx_sender.send(item).await?;
+        receiver.await??;
+
+        Ok(tx_hash)
     }
 }
@@ -0,0 +1,311 @@
+use std::{sync::Arc, time::Duration};
+
+use chrono::Utc;
+use tokio::time;
+
+use zksync_config::ForcedExitRequestsConfig;
+use zksync_types::{forced_exit_requests::DormantBalance, tx::TxHash, AccountId, PubKeyHash};
+
+use crate::{
+    core_interaction_wrapper::CoreInteractionWrapper,
+    forced_exit_sender::{build_signed_forced_exit, SenderAccountLock},
+    utils::{read_signing_key, Engine, PrivateKey},
+};
+
+/// The maximum time to wait for the sent exits to be committed.
+const COMMIT_TIMEOUT: Duration = Duration::from_secs(120);
+const COMMIT_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Withdraws the small balances of the dormant accounts to L1 using the ForcedExit
+/// sender account, so the funds aren't left in the accounts nobody uses.
+///
+/// An account is dormant if it has no signing key and wasn't updated for the configured
+/// amount of days. Only the balances worth at most the configured threshold are withdrawn
+/// and the opted out accounts are never touched. Note that the exited accounts stay in
+/// the tree, only their balances are moved to L1.
+pub struct DormantAccountsExiter<T: CoreInteractionWrapper> {
+    core_interaction_wrapper: T,
+    config: ForcedExitRequestsConfig,
+    forced_exit_sender_account_id: AccountId,
+    sender_private_key: PrivateKey<Engine>,
+    sender_account_lock: SenderAccountLock,
+}
+
+impl<T: CoreInteractionWrapper> DormantAccountsExiter<T> {
+    /// Creates the exiter sending the transactions from the ForcedExit sender account.
+    /// The `sender_account_lock` must be shared with the other users of the account,
+    /// so the nonces of their transactions don't clash.
+    pub fn new(
+        core_interaction_wrapper: T,
+        config: ForcedExitRequestsConfig,
+        forced_exit_sender_account_id: AccountId,
+        sender_account_lock: SenderAccountLock,
+    ) -> anyhow::Result<Self> {
+        let sender_private_key = config
+            .sender_private_key
+            .strip_prefix("0x")
+            .unwrap_or(&config.sender_private_key);
+        let sender_private_key = hex::decode(sender_private_key)
+            .map_err(|err| anyhow::anyhow!("Decoding private key failed: {}", err))?;
+        let sender_private_key = read_signing_key(&sender_private_key)?;
+
+        Ok(Self {
+            core_interaction_wrapper,
+            config,
+            forced_exit_sender_account_id,
+            sender_private_key,
+            sender_account_lock,
+        })
+    }
+
+    /// Withdraws the dust balances of the dormant accounts once per the configured check interval.
+    pub async fn run(mut self) {
+        let mut timer = time::interval(self.config.dormant_accounts_check_interval());
+        loop {
+            timer.tick().await;
+
+            match self.exit_dormant_accounts().await {
+                Ok(exits) => vlog::info!("Sent {} ForcedExits for the dormant accounts", exits),
+                // The failed exits are retried on the next check
+                Err(err) => vlog::warn!(
+                    "An error occured when exiting the dormant accounts: {}",
+                    err
+                ),
+            }
+        }
+    }
+
+    /// Sends the ForcedExit transactions for the dust balances of the dormant accounts
+    /// and waits until they are committed. Returns the amount of the sent transactions.
+    pub async fn exit_dormant_accounts(&mut self) -> anyhow::Result<usize> {
+        let idle_since =
+            Utc::now() - chrono::Duration::from_std(self.config.dormant_account_idle_time())?;
+        let balances = self
+            .core_interaction_wrapper
+            .get_dormant_balances(
+                idle_since,
+                self.config.dormant_balance_threshold_usd,
+                &self.config.dormant_accounts_opt_out,
+                self.config.dormant_accounts_batch_size,
+            )
+            .await?;
+        metrics::gauge!(
+            "forced_exit_requests.dormant_accounts.candidates",
+            balances.len() as f64
+        );
+
+        // The requests are fulfilled from the same account, so they have to wait until
+        // the exits are committed to not reuse the nonces.
+        let sender_account_lock = Arc::clone(&self.sender_account_lock);
+        let _guard = sender_account_lock.lock().await;
+
+        let mut sent_txs = Vec::new();
+        let sending_result = self.send_exits(balances, &mut sent_txs).await;
+
+        if let Some(&tx_hash) = sent_txs.last() {
+            self.wait_until_committed(tx_hash).await?;
+        }
+        sending_result?;
+
+        Ok(sent_txs.len())
+    }
+
+    async fn send_exits(
+        &mut self,
+        balances: Vec<DormantBalance>,
+        sent_txs: &mut Vec<TxHash>,
+    ) -> anyhow::Result<()> {
+        if balances.is_empty() {
+            return Ok(());
+        }
+        let mut nonce = self
+            .core_interaction_wrapper
+            .get_nonce(self.forced_exit_sender_account_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("ForcedExit sender account does not exist"))?;
+
+        for balance in balances {
+            if !self.is_still_dormant(&balance).await? {
+                continue;
+            }
+
+            let tx = build_signed_forced_exit(
+                self.forced_exit_sender_account_id,
+                &self.sender_private_key,
+                nonce,
+                balance.target,
+                balance.token,
+            );
+            sent_txs.push(self.core_interaction_wrapper.send_tx(tx).await?);
+            *nonce += 1;
+
+            metrics::increment_counter!("forced_exit_requests.dormant_accounts.exits");
+            vlog::info!(
+                "Sent ForcedExit for the dormant account {} balance of token {}",
+                balance.target,
+                balance.token
+            );
+        }
+
+        Ok(())
+    }
+
+    // The dormant balances are loaded from the verified state, so the account
+    // could have been used since then
+    async fn is_still_dormant(&self, balance: &DormantBalance) -> anyhow::Result<bool> {
+        let account = self
+            .core_interaction_wrapper
+            .get_committed_account(balance.account_id)
+            .await?;
+
+        Ok(account.map_or(false, |account| {
+            account.pub_key_hash == PubKeyHash::default()
+                && account.get_balance(balance.token) == balance.balance
+        }))
+    }
+
+    async fn wait_until_committed(&self, tx_hash: TxHash) -> anyhow::Result<()> {
+        let success = time::timeout(COMMIT_TIMEOUT, self.poll_receipt(tx_hash))
+            .await
+            .map_err(|_| {
+                anyhow::anyhow!(
+                    "ForcedExit transaction {} was not committed in time",
+                    tx_hash
+                )
+            })??;
+        anyhow::ensure!(success, "ForcedExit transaction {} failed", tx_hash);
+
+        Ok(())
+    }
+
+    async fn poll_receipt(&self, tx_hash: TxHash) -> anyhow::Result<bool> {
+        let mut timer = time::interval(COMMIT_POLL_INTERVAL);
+        loop {
+            timer.tick().await;
+            if let Some(receipt) = self.core_interaction_wrapper.get_receipt(tx_hash).await? {
+                return Ok(receipt.success);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use num::BigUint;
+
+    use zksync_types::{Account, Address, TokenId, ZkSyncTx};
+
+    use super::*;
+    use crate::test::MockCoreInteractionWrapper;
+
+    fn dormant_balance(account_id: u32, target: Address) -> DormantBalance {
+        DormantBalance {
+            account_id: AccountId(account_id),
+            target,
+            token: TokenId(1),
+            balance: BigUint::from(100u32),
+        }
+    }
+
+    fn account(target: Address, balance: u32) -> Account {
+        let mut account = Account::default_with_address(&target);
+        account.set_balance(TokenId(1), BigUint::from(balance));
+        account
+    }
+
+    #[tokio::test]
+    async fn test_dormant_accounts_exiter() {
+        let config = ForcedExitRequestsConfig::from_env();
+        let core_interaction_wrapper = MockCoreInteractionWrapper::default();
+
+        let targets: Vec<_> = (0..3).map(|_| Address::random()).collect();
+        // The second account received funds and the third one set the signing key
+        // since the verified state was loaded.
+        let mut signed_account = account(targets[2], 100);
+        signed_account.pub_key_hash = PubKeyHash { data: [1; 20] };
+        *core_interaction_wrapper.accounts.lock().unwrap() = vec![
+            (AccountId(1), account(targets[0], 100)),
+            (AccountId(2), account(targets[1], 200)),
+            (AccountId(3), signed_account),
+        ];
+        *core_interaction_wrapper.dormant_balances.lock().unwrap() = targets
+            .iter()
+            .enumerate()
+            .map(|(id, &target)| dormant_balance(id as u32 + 1, target))
+            .collect();
+
+        let mut exiter = DormantAccountsExiter::new(
+            core_interaction_wrapper,
+            config,
+            AccountId(12),
+            SenderAccountLock::default(),
+        )
+        .unwrap();
+        assert_eq!(exiter.exit_dormant_accounts().await.unwrap(), 1);
+
+        let sent_targets: Vec<_> = exiter
+            .core_interaction_wrapper
+            .sent_txs
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|tx| match &tx.tx {
+                ZkSyncTx::ForcedExit(tx) => (tx.initiator_account_id, tx.target, tx.token),
+                _ => panic!("Only ForcedExit transactions should be sent"),
+            })
+            .collect();
+        assert_eq!(sent_targets, vec![(AccountId(12), targets[0], TokenId(1))]);
+    }
+
+    #[tokio::test]
+    async fn test_dormant_accounts_exiter_waits_for_sender_account() {
+        let config = ForcedExitRequestsConfig::from_env();
+        let core_interaction_wrapper = MockCoreInteractionWrapper::default();
+        let target = Address::random();
+        *core_interaction_wrapper.accounts.lock().unwrap() =
+            vec![(AccountId(1), account(target, 100))];
+        *core_interaction_wrapper.dormant_balances.lock().unwrap() =
+            vec![dormant_balance(1, target)];
+
+        let sender_account_lock = SenderAccountLock::default();
+        let mut exiter = DormantAccountsExiter::new(
+            core_interaction_wrapper,
+            config,
+            AccountId(12),
+            Arc::clone(&sender_account_lock),
+        )
+        .unwrap();
+
+        // The account is busy fulfilling a request, so no exits are sent meanwhile.
+        let guard = sender_account_lock.lock().await;
+        let exit = exiter.exit_dormant_accounts();
+        tokio::pin!(exit);
+        assert!(time::timeout(Duration::from_millis(100), &mut exit)
+            .await
+            .is_err());
+        drop(guard);
+        assert_eq!(exit.await.unwrap(), 1);
+    }
+
+    #[test]
+    fn test_dormant_accounts_exiter_invalid_key() {
+        // Not a hex string, an empty key and the one out of the field range.
+        for key in &[
+            "0xnothex".to_string(),
+            "0x".to_string(),
+            format!("0x{}", "ff".repeat(32)),
+        ] {
+            let config = ForcedExitRequestsConfig {
+                sender_private_key: key.clone(),
+                ..ForcedExitRequestsConfig::from_env()
+            };
+            assert!(DormantAccountsExiter::new(
+                MockCoreInteractionWrapper::default(),
+                config,
+                AccountId(12),
+                SenderAccountLock::default(),
+            )
+            .is_err());
+        }
+    }
+}
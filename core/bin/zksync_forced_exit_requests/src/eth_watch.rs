@@ -28,6 +28,7 @@ use zksync_types::forced_exit_requests::FundsReceivedEvent;
 use super::prepare_forced_exit_sender::prepare_forced_exit_sender_account;
 use crate::{
     core_interaction_wrapper::{CoreInteractionWrapper, MempoolCoreInteractionWrapper},
+    dormant_accounts::DormantAccountsExiter,
     forced_exit_sender::MempoolForcedExitSender,
 };
 
@@ -424,6 +425,22 @@ pub fn run_forced_exit_contract_watcher(
             "Unexpected error while trying to wait for unconfirmed forced_exit transactions",
         );
 
+        // Exiting the dormant accounts waits for the sent transactions to be committed,
+        // so it runs separately from the events polling sharing the sender account lock.
+        if config.dormant_accounts_enabled {
+            match DormantAccountsExiter::new(
+                core_interaction_wrapper.clone(),
+                config.clone(),
+                id,
+                forced_exit_sender.sender_account_lock(),
+            ) {
+                Ok(dormant_accounts_exiter) => {
+                    tokio::spawn(dormant_accounts_exiter.run());
+                }
+                Err(err) => vlog::error!("Failed to start exiting the dormant accounts: {}", err),
+            }
+        }
+
         let contract_watcher = ForcedExitContractWatcher::new(
             core_interaction_wrapper,
             config,
@@ -570,12 +587,14 @@ mod test {
             id: 1,
             target: Address::random(),
             tokens: vec![TokenId(0)],
+            additional_targets: vec![],
             price_in_wei: BigUint::from_i64(12).unwrap(),
             valid_until: Utc::now().sub(week),
             // Outdated by far
             created_at: Utc::now().sub(week).sub(three_days),
             fulfilled_at: None,
             fulfilled_by: None,
+            payment_received_at: None,
         };
 
         add_request(
@@ -632,6 +651,7 @@ mod test {
             id: 1,
             target: Address::random(),
             tokens: vec![TokenId(0)],
+            additional_targets: vec![],
             price_in_wei: BigUint::from_i64(12).unwrap(),
             // does not matter in these tests
             valid_until: Utc::now(),
@@ -639,6 +659,7 @@ mod test {
             created_at: Utc::now().sub(chrono::Duration::milliseconds(1)),
             fulfilled_at: None,
             fulfilled_by: None,
+            payment_received_at: None,
         }]);
 
         watcher
@@ -657,6 +678,7 @@ mod test {
             id: 1,
             target: Address::random(),
             tokens: vec![TokenId(0)],
+            additional_targets: vec![],
             price_in_wei: BigUint::from_i64(12).unwrap(),
             // does not matter in these tests
             valid_until: Utc::now(),
@@ -664,6 +686,7 @@ mod test {
             created_at: Utc::now().sub(chrono::Duration::weeks(1)),
             fulfilled_at: None,
             fulfilled_by: None,
+            payment_received_at: None,
         }]);
 
         watcher
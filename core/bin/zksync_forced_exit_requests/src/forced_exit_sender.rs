@@ -1,8 +1,8 @@
-use std::ops::AddAssign;
+use std::{ops::AddAssign, sync::Arc};
 
 use chrono::{DateTime, Utc};
 use num::BigUint;
-use tokio::time;
+use tokio::{sync::Mutex, time};
 
 use zksync_config::ForcedExitRequestsConfig;
 
@@ -22,6 +22,36 @@ use crate::utils::read_signing_key;
 // We try to process a request 3 times before sending warnings in the console
 const PROCESSING_ATTEMPTS: u32 = 3;
 
+/// Held while the transactions of the ForcedExit sender account are sent and committed,
+/// so the requests and the other users of the account don't send them with the same nonces.
+pub type SenderAccountLock = Arc<Mutex<()>>;
+
+/// Builds the zero fee ForcedExit transaction sent by the ForcedExit sender account.
+pub fn build_signed_forced_exit(
+    sender_id: AccountId,
+    sender_private_key: &PrivateKey<Engine>,
+    nonce: Nonce,
+    target: Address,
+    token: TokenId,
+) -> SignedZkSyncTx {
+    let tx = ForcedExit::new_signed(
+        sender_id,
+        target,
+        token,
+        BigUint::from(0u32),
+        nonce,
+        TimeRange::default(),
+        sender_private_key,
+    )
+    .expect("Failed to create signed ForcedExit transaction");
+
+    SignedZkSyncTx {
+        tx: ZkSyncTx::ForcedExit(Box::new(tx)),
+        eth_sign_data: None,
+        created_at: Utc::now(),
+    }
+}
+
 #[async_trait::async_trait]
 pub trait ForcedExitSender {
     async fn process_request(&mut self, amount: BigUint, submission_time: DateTime<Utc>);
@@ -32,6 +62,7 @@ pub struct MempoolForcedExitSender<T: CoreInteractionWrapper> {
     config: ForcedExitRequestsConfig,
     forced_exit_sender_account_id: AccountId,
     sender_private_key: PrivateKey<Engine>,
+    sender_account_lock: SenderAccountLock,
 }
 
 #[async_trait::async_trait]
@@ -76,31 +107,28 @@ impl<T: CoreInteractionWrapper> MempoolForcedExitSender<T> {
             config,
             forced_exit_sender_account_id,
             sender_private_key,
+            sender_account_lock: SenderAccountLock::default(),
         }
     }
 
+    /// Returns the lock to be shared with the other senders of the ForcedExit sender account transactions.
+    pub fn sender_account_lock(&self) -> SenderAccountLock {
+        Arc::clone(&self.sender_account_lock)
+    }
+
     pub fn build_forced_exit(
         &self,
         nonce: Nonce,
         target: Address,
         token: TokenId,
     ) -> SignedZkSyncTx {
-        let tx = ForcedExit::new_signed(
+        build_signed_forced_exit(
             self.forced_exit_sender_account_id,
+            &self.sender_private_key,
+            nonce,
             target,
             token,
-            BigUint::from(0u32),
-            nonce,
-            TimeRange::default(),
-            &self.sender_private_key,
         )
-        .expect("Failed to create signed ForcedExit transaction");
-
-        SignedZkSyncTx {
-            tx: ZkSyncTx::ForcedExit(Box::new(tx)),
-            eth_sign_data: None,
-            created_at: Utc::now(),
-        }
     }
 
     pub async fn build_transactions(
@@ -116,8 +144,8 @@ impl<T: CoreInteractionWrapper> MempoolForcedExitSender<T> {
 
         let mut transactions: Vec<SignedZkSyncTx> = vec![];
 
-        for token in fe_request.tokens.into_iter() {
-            transactions.push(self.build_forced_exit(sender_nonce, fe_request.target, token));
+        for (target, token) in fe_request.exits() {
+            transactions.push(self.build_forced_exit(sender_nonce, target, token));
             sender_nonce.add_assign(1);
         }
 
@@ -238,6 +266,12 @@ impl<T: CoreInteractionWrapper> MempoolForcedExitSender<T> {
             return Ok(());
         };
 
+        self.core_interaction_wrapper
+            .set_payment_received_at(id)
+            .await?;
+
+        let sender_account_lock = self.sender_account_lock();
+        let _guard = sender_account_lock.lock().await;
         let txs = self.build_transactions(fe_request.clone()).await?;
 
         // Right before sending the transactions we must check if the request is possible at all
@@ -270,6 +304,7 @@ mod test {
     };
 
     use zksync_config::ForcedExitRequestsConfig;
+    use zksync_types::forced_exit_requests::{ForcedExitRequestProgress, ForcedExitTarget};
 
     use super::*;
     use crate::test::{add_request, MockCoreInteractionWrapper};
@@ -309,11 +344,13 @@ mod test {
                 id: 12,
                 target: Address::random(),
                 tokens: vec![TokenId(1)],
+                additional_targets: vec![],
                 price_in_wei: BigUint::from_str("10000000000").unwrap(),
                 valid_until: Utc::now().add(day),
                 created_at: Utc::now(),
                 fulfilled_by: None,
                 fulfilled_at: None,
+                payment_received_at: None,
             },
         );
 
@@ -377,5 +414,76 @@ mod test {
                 .len(),
             1
         );
+
+        let request = forced_exit_sender
+            .core_interaction_wrapper
+            .get_request_by_id(12)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(request.payment_received_at.is_some());
+        assert_eq!(
+            request.progress(Utc::now()),
+            ForcedExitRequestProgress::Completed
+        );
+    }
+
+    #[tokio::test]
+    async fn test_forced_exit_sender_batch() {
+        let day = chrono::Duration::days(1);
+
+        let forced_exit_requests = ForcedExitRequestsConfig {
+            // There must be 10 digits in id
+            digits_in_id: 10,
+            ..ForcedExitRequestsConfig::from_env()
+        };
+
+        let mut forced_exit_sender = get_test_forced_exit_sender(Some(forced_exit_requests));
+
+        let target = Address::random();
+        let additional_target = Address::random();
+        add_request(
+            &forced_exit_sender.core_interaction_wrapper.requests,
+            ForcedExitRequest {
+                id: 13,
+                target,
+                tokens: vec![TokenId(1)],
+                additional_targets: vec![ForcedExitTarget {
+                    target: additional_target,
+                    tokens: vec![TokenId(1), TokenId(2)],
+                }],
+                price_in_wei: BigUint::from_str("30000000000").unwrap(),
+                valid_until: Utc::now().add(day),
+                created_at: Utc::now(),
+                fulfilled_by: None,
+                fulfilled_at: None,
+                payment_received_at: None,
+            },
+        );
+
+        // A single payment covers the exits of all the targets
+        forced_exit_sender
+            .process_request(BigUint::from_str("30000000013").unwrap(), Utc::now())
+            .await;
+
+        let sent_targets: Vec<_> = forced_exit_sender
+            .core_interaction_wrapper
+            .sent_txs
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|tx| match &tx.tx {
+                ZkSyncTx::ForcedExit(tx) => (tx.target, tx.token),
+                _ => panic!("Only ForcedExit transactions should be sent"),
+            })
+            .collect();
+        assert_eq!(
+            sent_targets,
+            vec![
+                (target, TokenId(1)),
+                (additional_target, TokenId(1)),
+                (additional_target, TokenId(2))
+            ]
+        );
     }
 }
@@ -8,6 +8,7 @@ use zksync_config::configs::api::CommonApiConfig;
 use zksync_mempool::MempoolTransactionRequest;
 
 mod core_interaction_wrapper;
+pub mod dormant_accounts;
 pub mod eth_watch;
 pub mod forced_exit_sender;
 pub mod prepare_forced_exit_sender;
@@ -1,12 +1,12 @@
 use std::{ops::Sub, sync::Mutex};
 
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use zksync_storage::chain::operations_ext::records::TxReceiptResponse;
 use zksync_types::Nonce;
 use zksync_types::{
-    forced_exit_requests::{ForcedExitRequest, ForcedExitRequestId},
+    forced_exit_requests::{DormantBalance, ForcedExitRequest, ForcedExitRequestId},
     tx::TxHash,
-    AccountId, SignedZkSyncTx,
+    Account, AccountId, Address, SignedZkSyncTx,
 };
 
 use super::core_interaction_wrapper::CoreInteractionWrapper;
@@ -18,6 +18,8 @@ pub struct MockCoreInteractionWrapper {
     pub sent_txs: Mutex<Vec<SignedZkSyncTx>>,
     // It is easier when keeping track of the deleted txs
     pub deleted_requests: Mutex<Vec<ForcedExitRequest>>,
+    pub accounts: Mutex<Vec<(AccountId, Account)>>,
+    pub dormant_balances: Mutex<Vec<DormantBalance>>,
 }
 
 impl Default for MockCoreInteractionWrapper {
@@ -36,6 +38,8 @@ impl Default for MockCoreInteractionWrapper {
             }),
             sent_txs: Mutex::new(vec![]),
             deleted_requests: Mutex::new(vec![]),
+            accounts: Mutex::new(vec![]),
+            dormant_balances: Mutex::new(vec![]),
         }
     }
 }
@@ -90,6 +94,14 @@ impl CoreInteractionWrapper for MockCoreInteractionWrapper {
 
         Ok(())
     }
+    async fn set_payment_received_at(&self, id: i64) -> anyhow::Result<()> {
+        let index = self.get_request_index_by_id(id)?;
+        let mut requests = self.lock_requests();
+
+        requests[index].payment_received_at = Some(Utc::now());
+
+        Ok(())
+    }
     async fn set_fulfilled_by(
         &self,
         id: ForcedExitRequestId,
@@ -168,6 +180,40 @@ impl CoreInteractionWrapper for MockCoreInteractionWrapper {
         // For tests it is better to just return true all the time
         Ok(true)
     }
+
+    async fn get_committed_account(
+        &self,
+        account_id: AccountId,
+    ) -> anyhow::Result<Option<Account>> {
+        let accounts = self.accounts.lock().unwrap();
+        let account = accounts
+            .iter()
+            .find(|(id, _)| *id == account_id)
+            .map(|(_, account)| account.clone());
+
+        Ok(account)
+    }
+
+    async fn get_dormant_balances(
+        &self,
+        _idle_since: DateTime<Utc>,
+        _max_balance_usd: f64,
+        _excluded: &[Address],
+        limit: u32,
+    ) -> anyhow::Result<Vec<DormantBalance>> {
+        // The balances are expected to be dormant, small enough and not opted out already
+        let balances = self.dormant_balances.lock().unwrap();
+        let balances = balances.iter().take(limit as usize).cloned().collect();
+
+        Ok(balances)
+    }
+
+    async fn send_tx(&mut self, tx: SignedZkSyncTx) -> anyhow::Result<TxHash> {
+        let tx_hash = tx.hash();
+        self.lock_sent_txs().push(tx);
+
+        Ok(tx_hash)
+    }
 }
 
 pub fn add_request(requests: &Mutex<Vec<ForcedExitRequest>>, new_request: ForcedExitRequest) {
@@ -17,9 +17,9 @@ pub type Fs = <Engine as JubjubEngine>::Fs;
 pub fn read_signing_key(private_key: &[u8]) -> anyhow::Result<PrivateKey<Engine>> {
     let mut fs_repr = FsRepr::default();
     fs_repr.read_be(private_key)?;
-    Ok(PrivateKey::<Engine>(
-        Fs::from_repr(fs_repr).expect("couldn't read private key from repr"),
-    ))
+    let private_key = Fs::from_repr(fs_repr)
+        .map_err(|err| anyhow::anyhow!("couldn't read private key from repr: {:?}", err))?;
+    Ok(PrivateKey::<Engine>(private_key))
 }
 
 pub fn extract_id_from_amount(amount: BigUint, digits_in_id: u32) -> (i64, BigUint) {
@@ -5,13 +5,17 @@
 
 // Built-in
 use std::clone::Clone;
+use std::time::Duration;
 // Workspace uses
 use zksync_crypto::proof::{AggregatedProof, SingleProof};
-use zksync_storage::{ConnectionPool, StorageProcessor};
+use zksync_storage::{
+    prover::records::{InProgressProverJob, ProverQueueStats},
+    ConnectionPool, StorageProcessor,
+};
 use zksync_types::{
     aggregated_operations::{AggregatedActionType, AggregatedOperation},
     block::Block,
-    prover::{ProverJob, ProverJobType},
+    prover::{ProverJob, ProverJobPriorityPolicy, ProverJobType},
     AccountMap, AccountUpdates, BlockNumber,
 };
 // Local uses
@@ -109,12 +113,38 @@ impl DatabaseInterface for Database {
     async fn mark_stale_jobs_as_idle(
         &self,
         connection: &mut StorageProcessor<'_>,
+        stale_timeout: Duration,
     ) -> anyhow::Result<()> {
-        connection.prover_schema().mark_stale_jobs_as_idle().await?;
+        connection
+            .prover_schema()
+            .mark_stale_jobs_as_idle(stale_timeout)
+            .await?;
 
         Ok(())
     }
 
+    async fn load_in_progress_jobs(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Vec<InProgressProverJob>> {
+        let jobs = connection.prover_schema().load_in_progress_jobs().await?;
+
+        Ok(jobs)
+    }
+
+    async fn load_prover_queue_stats(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        proving_time_window: Duration,
+    ) -> anyhow::Result<Vec<ProverQueueStats>> {
+        let stats = connection
+            .prover_schema()
+            .load_prover_queue_stats(proving_time_window)
+            .await?;
+
+        Ok(stats)
+    }
+
     async fn load_last_verified_block(
         &self,
         connection: &mut StorageProcessor<'_>,
@@ -154,10 +184,11 @@ impl DatabaseInterface for Database {
     async fn load_idle_prover_job_from_job_queue(
         &self,
         connection: &mut StorageProcessor<'_>,
+        priority_policy: &ProverJobPriorityPolicy,
     ) -> anyhow::Result<Option<ProverJob>> {
         let proof = connection
             .prover_schema()
-            .get_idle_prover_job_from_job_queue()
+            .get_idle_prover_job_from_job_queue(priority_policy)
             .await?;
 
         Ok(proof)
@@ -279,7 +310,50 @@ impl DatabaseInterface for Database {
     ) -> anyhow::Result<()> {
         connection
             .prover_schema()
-            .store_witness(block, witness)
+            .replace_witness(block, witness)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn try_acquire_witness_lease(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        block: BlockNumber,
+        worker: &str,
+        lease_timeout: Duration,
+    ) -> anyhow::Result<bool> {
+        let acquired = connection
+            .prover_schema()
+            .try_acquire_witness_lease(block, worker, lease_timeout)
+            .await?;
+
+        Ok(acquired)
+    }
+
+    async fn heartbeat_witness_lease(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        block: BlockNumber,
+        worker: &str,
+    ) -> anyhow::Result<bool> {
+        let prolonged = connection
+            .prover_schema()
+            .heartbeat_witness_lease(block, worker)
+            .await?;
+
+        Ok(prolonged)
+    }
+
+    async fn release_witness_lease(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        block: BlockNumber,
+        worker: &str,
+    ) -> anyhow::Result<()> {
+        connection
+            .prover_schema()
+            .release_witness_lease(block, worker)
             .await?;
 
         Ok(())
@@ -6,13 +6,17 @@
 // Built-in
 use std::clone::Clone;
 use std::marker::{Send, Sync};
+use std::time::Duration;
 // Workspace uses
 use zksync_crypto::proof::{AggregatedProof, SingleProof};
-use zksync_storage::StorageProcessor;
+use zksync_storage::{
+    prover::records::{InProgressProverJob, ProverQueueStats},
+    StorageProcessor,
+};
 use zksync_types::aggregated_operations::{AggregatedActionType, AggregatedOperation};
 use zksync_types::{
     block::Block,
-    prover::{ProverJob, ProverJobType},
+    prover::{ProverJob, ProverJobPriorityPolicy, ProverJobType},
     AccountMap, AccountUpdates, BlockNumber,
 };
 
@@ -62,8 +66,22 @@ pub trait DatabaseInterface: Send + Sync + Clone + 'static {
     async fn mark_stale_jobs_as_idle(
         &self,
         connection: &mut StorageProcessor<'_>,
+        stale_timeout: Duration,
     ) -> anyhow::Result<()>;
 
+    async fn load_in_progress_jobs(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Vec<InProgressProverJob>>;
+
+    /// Loads the prover jobs statistics grouped by the job type and size, the average proving
+    /// time is calculated for jobs proven within `proving_time_window`.
+    async fn load_prover_queue_stats(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        proving_time_window: Duration,
+    ) -> anyhow::Result<Vec<ProverQueueStats>>;
+
     async fn load_last_verified_block(
         &self,
         connection: &mut StorageProcessor<'_>,
@@ -83,6 +101,7 @@ pub trait DatabaseInterface: Send + Sync + Clone + 'static {
     async fn load_idle_prover_job_from_job_queue(
         &self,
         connection: &mut StorageProcessor<'_>,
+        priority_policy: &ProverJobPriorityPolicy,
     ) -> anyhow::Result<Option<ProverJob>>;
 
     async fn record_prover_is_working(
@@ -135,6 +154,7 @@ pub trait DatabaseInterface: Send + Sync + Clone + 'static {
         tree_cache_binary: Vec<u8>,
     ) -> anyhow::Result<()>;
 
+    /// Stores witness for a block, replacing the outdated one if it exists.
     async fn store_witness(
         &self,
         connection: &mut StorageProcessor<'_>,
@@ -142,6 +162,31 @@ pub trait DatabaseInterface: Send + Sync + Clone + 'static {
         witness: serde_json::Value,
     ) -> anyhow::Result<()>;
 
+    /// Tries to claim the block for witness generation by `worker`.
+    /// Returns `false` if the block is claimed by another alive worker.
+    async fn try_acquire_witness_lease(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        block: BlockNumber,
+        worker: &str,
+        lease_timeout: Duration,
+    ) -> anyhow::Result<bool>;
+
+    /// Notifies that `worker` is still building the witness for the block.
+    async fn heartbeat_witness_lease(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        block: BlockNumber,
+        worker: &str,
+    ) -> anyhow::Result<bool>;
+
+    async fn release_witness_lease(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        block: BlockNumber,
+        worker: &str,
+    ) -> anyhow::Result<()>;
+
     async fn pending_jobs_count(
         &self,
         connection: &mut StorageProcessor<'_>,
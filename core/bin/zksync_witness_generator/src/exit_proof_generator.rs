@@ -0,0 +1,326 @@
+//! Generator of the exit proofs requested via the API.
+//!
+//! In the exodus mode users withdraw their funds from the contract using the exit proofs generated
+//! against the last verified state. The API queues the requested proofs in the database, and this
+//! component takes them from the queue and generates them with the configured amount of workers.
+//! The account tree of the verified state is built once and shared by all the workers, which lock it
+//! only to prepare the circuit witness. Jobs in progress are kept alive by heartbeats, so the jobs of a
+//! crashed generator are returned to the queue without interfering with the other running instances.
+
+// Built-in deps
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+// External deps
+use anyhow::format_err;
+use tokio::sync::Semaphore;
+use tokio::task::JoinHandle;
+use tokio::time;
+// Workspace deps
+use zksync_api_types::v02::exit_proof::{ApiExitProofJob, ExitProofData, StoredBlockInfo};
+use zksync_config::configs::prover::ExitProofGenerator as ExitProofGeneratorConfig;
+use zksync_crypto::params::MIN_NFT_TOKEN_ID;
+use zksync_prover_utils::exit_proof::ExitProofTree;
+use zksync_storage::{ConnectionPool, StorageProcessor};
+use zksync_types::{AccountId, BlockNumber, TokenLike};
+
+/// Everything required to generate the proof for the job without accessing the database.
+struct ExitProofInput {
+    job: ApiExitProofJob,
+    tree: Arc<ExitProofTree>,
+    proof_data: ExitProofData,
+}
+
+struct ExitProofGenerator {
+    pool: ConnectionPool,
+    config: ExitProofGeneratorConfig,
+    /// Account tree of the last verified block the proofs were requested for.
+    tree: Option<(BlockNumber, Arc<ExitProofTree>)>,
+    /// Identifiers of the jobs being generated by this instance.
+    jobs_in_progress: Arc<Mutex<HashSet<u64>>>,
+}
+
+impl ExitProofGenerator {
+    async fn run(mut self) {
+        // Heartbeats are sent by a separate task, since building the tree may take longer than the lease.
+        tokio::spawn(Self::send_heartbeats(
+            self.pool.clone(),
+            self.jobs_in_progress.clone(),
+            self.config.lease_timeout() / 4,
+        ));
+
+        let workers = Arc::new(Semaphore::new(self.config.workers.max(1)));
+        let mut timer = time::interval(self.config.poll_interval());
+        loop {
+            timer.tick().await;
+            match self.return_stale_jobs().await {
+                Ok(0) => {}
+                Ok(returned) => {
+                    vlog::info!(
+                        "{} stale exit proof jobs were returned to the queue",
+                        returned
+                    )
+                }
+                Err(err) => {
+                    vlog::warn!("Unable to return the exit proof jobs to the queue: {}", err)
+                }
+            }
+
+            // Take the jobs from the queue while there are idle workers.
+            while let Ok(worker) = workers.clone().try_acquire_owned() {
+                let job = match self.take_next_job().await {
+                    Ok(Some(job)) => job,
+                    Ok(None) => break,
+                    Err(err) => {
+                        vlog::warn!("Unable to take the exit proof job: {}", err);
+                        break;
+                    }
+                };
+                let job_id = job.id;
+                self.jobs_in_progress.lock().unwrap().insert(job_id);
+                let input = match self.prepare_input(job).await {
+                    Ok(input) => input,
+                    Err(err) => {
+                        Self::store_result(&self.pool, job_id, Err(err)).await;
+                        self.jobs_in_progress.lock().unwrap().remove(&job_id);
+                        continue;
+                    }
+                };
+
+                let pool = self.pool.clone();
+                let jobs_in_progress = self.jobs_in_progress.clone();
+                tokio::spawn(async move {
+                    let result = tokio::task::spawn_blocking(move || generate_proof(input))
+                        .await
+                        .map_err(|err| format_err!("Exit proof worker panicked: {}", err))
+                        .and_then(|result| result);
+                    Self::store_result(&pool, job_id, result).await;
+                    jobs_in_progress.lock().unwrap().remove(&job_id);
+                    drop(worker);
+                });
+            }
+        }
+    }
+
+    async fn send_heartbeats(
+        pool: ConnectionPool,
+        jobs_in_progress: Arc<Mutex<HashSet<u64>>>,
+        interval: Duration,
+    ) {
+        let mut timer = time::interval(interval);
+        loop {
+            timer.tick().await;
+            let job_ids: Vec<_> = jobs_in_progress.lock().unwrap().iter().copied().collect();
+            if job_ids.is_empty() {
+                continue;
+            }
+            let sent = async {
+                let mut storage = pool.access_storage().await?;
+                storage
+                    .exit_proofs_schema()
+                    .heartbeat_exit_proof_jobs(&job_ids)
+                    .await
+            };
+            if let Err(err) = sent.await {
+                vlog::warn!("Unable to prolong the exit proof jobs lease: {}", err);
+            }
+        }
+    }
+
+    // TODO: don't use anyhow (ZKS-588)
+    async fn return_stale_jobs(&self) -> anyhow::Result<u64> {
+        let mut storage = self.pool.access_storage().await?;
+        storage
+            .exit_proofs_schema()
+            .return_stale_exit_proof_jobs(self.config.lease_timeout())
+            .await
+    }
+
+    async fn take_next_job(&self) -> anyhow::Result<Option<ApiExitProofJob>> {
+        let mut storage = self.pool.access_storage().await?;
+        storage
+            .exit_proofs_schema()
+            .take_next_exit_proof_job()
+            .await
+    }
+
+    async fn store_result(
+        pool: &ConnectionPool,
+        job_id: u64,
+        result: anyhow::Result<ExitProofData>,
+    ) {
+        let stored = async {
+            let mut storage = pool.access_storage().await?;
+            match &result {
+                Ok(proof) => {
+                    metrics::increment_counter!("exit_proof_generator.jobs", "result" => "done");
+                    storage
+                        .exit_proofs_schema()
+                        .store_exit_proof(job_id, proof)
+                        .await
+                }
+                Err(err) => {
+                    metrics::increment_counter!("exit_proof_generator.jobs", "result" => "failed");
+                    vlog::warn!("Exit proof job {} failed: {}", job_id, err);
+                    storage
+                        .exit_proofs_schema()
+                        .mark_exit_proof_job_failed(job_id, &err.to_string())
+                        .await
+                }
+            }
+        };
+        if let Err(err) = stored.await {
+            vlog::error!(
+                "Unable to store the result of the exit proof job {}: {}",
+                job_id,
+                err
+            );
+        }
+    }
+
+    async fn prepare_input(&mut self, job: ApiExitProofJob) -> anyhow::Result<ExitProofInput> {
+        let start = Instant::now();
+        let mut storage = self.pool.access_storage().await?;
+        let tree = self.load_tree(&mut storage, job.block_number).await?;
+        let block = storage
+            .chain()
+            .block_schema()
+            .get_block(job.block_number)
+            .await?
+            .ok_or_else(|| format_err!("Block {} is not found", job.block_number))?;
+
+        let owner = storage
+            .chain()
+            .account_schema()
+            .account_address_by_id(job.account_id)
+            .await?
+            .ok_or_else(|| format_err!("Account {} is not found", *job.account_id))?;
+        let token = storage
+            .tokens_schema()
+            .get_token(TokenLike::Id(job.token_id))
+            .await?
+            .ok_or_else(|| format_err!("Token {} is not found", *job.token_id))?;
+
+        let (nft_creator_id, nft_creator_address, nft_serial_id, nft_content_hash) =
+            if *job.token_id < MIN_NFT_TOKEN_ID {
+                // The placeholder creator address should be the address of the account with id 0.
+                let creator_address = storage
+                    .chain()
+                    .account_schema()
+                    .account_address_by_id(AccountId(0))
+                    .await?
+                    .ok_or_else(|| format_err!("Account with id 0 does not exist"))?;
+                (AccountId(0), creator_address, 0, Default::default())
+            } else {
+                let nft = storage
+                    .tokens_schema()
+                    .get_nft(job.token_id)
+                    .await?
+                    .ok_or_else(|| format_err!("NFT {} is not found", *job.token_id))?;
+                (
+                    nft.creator_id,
+                    nft.creator_address,
+                    nft.serial_id,
+                    nft.content_hash,
+                )
+            };
+
+        let proof_data = ExitProofData {
+            stored_block_info: StoredBlockInfo::from_block(&block),
+            owner,
+            account_id: job.account_id,
+            token_id: job.token_id,
+            // Amount and proof are set once the proof is generated.
+            amount: Default::default(),
+            nft_creator_id,
+            nft_creator_address,
+            nft_serial_id,
+            nft_content_hash,
+            proof: Default::default(),
+            token_address: token.address,
+        };
+        metrics::histogram!("exit_proof_generator", start.elapsed(), "stage" => "prepare_input");
+        Ok(ExitProofInput {
+            job,
+            tree,
+            proof_data,
+        })
+    }
+
+    /// Returns the account tree of the `block`, which must be the last verified block.
+    async fn load_tree(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        block: BlockNumber,
+    ) -> anyhow::Result<Arc<ExitProofTree>> {
+        if let Some((tree_block, tree)) = &self.tree {
+            if *tree_block == block {
+                return Ok(tree.clone());
+            }
+        }
+        // The previous tree is dropped once the workers using it are done.
+        self.tree = None;
+
+        let start = Instant::now();
+        let (verified_block, accounts) =
+            storage.chain().state_schema().load_verified_state().await?;
+        if verified_block != block {
+            return Err(format_err!(
+                "State of the block {} is not available anymore, the last verified block is {}. \
+                 Request the proof again",
+                block,
+                verified_block
+            ));
+        }
+        // Building the tree is CPU-bound, so it shouldn't block the runtime.
+        let tree = tokio::task::spawn_blocking(move || ExitProofTree::new(accounts)).await?;
+        let tree = Arc::new(tree);
+        self.tree = Some((block, tree.clone()));
+        metrics::histogram!("exit_proof_generator", start.elapsed(), "stage" => "load_tree");
+        Ok(tree)
+    }
+}
+
+fn generate_proof(input: ExitProofInput) -> anyhow::Result<ExitProofData> {
+    let start = Instant::now();
+    let ExitProofInput {
+        job,
+        tree,
+        mut proof_data,
+    } = input;
+    vlog::info!(
+        "Generating the exit proof for the account {}, token {} (job {})",
+        *job.account_id,
+        *job.token_id,
+        job.id
+    );
+
+    let witness = tree.exit_circuit_witness(
+        job.account_id,
+        proof_data.owner,
+        job.token_id,
+        proof_data.nft_creator_id,
+        proof_data.nft_serial_id,
+        proof_data.nft_content_hash,
+    )?;
+    let (proof, amount) = witness.prove()?;
+    proof_data.proof = proof;
+    proof_data.amount = amount.into();
+
+    metrics::histogram!("exit_proof_generator", start.elapsed(), "stage" => "generate_proof");
+    Ok(proof_data)
+}
+
+/// Runs the generator of the exit proofs requested via the API.
+pub fn run_exit_proof_generator(
+    pool: ConnectionPool,
+    config: ExitProofGeneratorConfig,
+) -> JoinHandle<()> {
+    let generator = ExitProofGenerator {
+        pool,
+        config,
+        tree: None,
+        jobs_in_progress: Default::default(),
+    };
+    tokio::spawn(generator.run())
+}
@@ -1,7 +1,8 @@
 // Built-in
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 // External
 use actix_web::dev::ServiceRequest;
 use actix_web::{web, App, HttpResponse, HttpServer};
@@ -25,13 +26,14 @@ use zksync_circuit::serialization::ProverData;
 use zksync_config::configs::api::ProverApiConfig;
 use zksync_prover_utils::api::{
     JobRequestData, JobResultData, ProverInputRequest, ProverInputResponse, ProverOutputRequest,
-    WorkingOn,
+    SetupKeyStatus, SetupKeysReport, WorkingOn,
 };
 use zksync_types::aggregated_operations::{
     AggregatedActionType, AggregatedOperation, BlocksCreateProofOperation,
 };
 use zksync_types::prover::{
-    ProverJobType, AGGREGATED_PROOF_JOB_PRIORITY, SINGLE_PROOF_JOB_PRIORITY,
+    ProverJobPriorityPolicy, ProverJobType, AGGREGATED_PROOF_JOB_PRIORITY,
+    SINGLE_PROOF_JOB_PRIORITY,
 };
 use zksync_types::BlockNumber;
 use zksync_utils::panic_notify::{spawn_panic_handler, ThreadPanicNotify};
@@ -41,6 +43,7 @@ mod tests;
 
 pub mod database;
 mod database_interface;
+pub mod exit_proof_generator;
 mod scaler;
 mod witness_generator;
 
@@ -57,10 +60,19 @@ struct AppState<DB: DatabaseInterface> {
     secret_auth: String,
     database: DB,
     scaler_oracle: Arc<RwLock<ScalerOracle<DB>>>,
+    /// Universal setup keys states reported by the provers on startup.
+    setup_keys: Arc<RwLock<HashMap<String, Vec<SetupKeyStatus>>>>,
+    priority_policy: ProverJobPriorityPolicy,
 }
 
 impl<DB: DatabaseInterface> AppState<DB> {
-    pub fn new(secret_auth: String, database: DB, idle_provers: u32) -> Self {
+    pub fn new(
+        secret_auth: String,
+        database: DB,
+        idle_provers: u32,
+        setup_keys: Arc<RwLock<HashMap<String, Vec<SetupKeyStatus>>>>,
+        priority_policy: ProverJobPriorityPolicy,
+    ) -> Self {
         let scaler_oracle = Arc::new(RwLock::new(ScalerOracle::new(
             database.clone(),
             idle_provers,
@@ -70,6 +82,8 @@ impl<DB: DatabaseInterface> AppState<DB> {
             secret_auth,
             database,
             scaler_oracle,
+            setup_keys,
+            priority_policy,
         }
     }
 
@@ -129,7 +143,7 @@ async fn get_job<DB: DatabaseInterface>(
     let mut storage = data.access_storage().await?;
     let ret = data
         .database
-        .load_idle_prover_job_from_job_queue(&mut storage)
+        .load_idle_prover_job_from_job_queue(&mut storage, &data.priority_policy)
         .await
         .map_err(|e| {
             vlog::warn!("could not get next unverified commit operation: {}", e);
@@ -145,6 +159,7 @@ async fn get_job<DB: DatabaseInterface>(
                 serde_json::from_value(prover_job.job_data)
                     .expect("Failed to parse prover job from db"),
             ),
+            job_priority: prover_job.job_priority,
         }))
     } else {
         Ok(HttpResponse::Ok().json(ProverInputResponse {
@@ -152,6 +167,7 @@ async fn get_job<DB: DatabaseInterface>(
             first_block: BlockNumber(0),
             last_block: BlockNumber(0),
             data: None,
+            job_priority: 0,
         }))
     }
 }
@@ -253,6 +269,45 @@ async fn stopped<DB: DatabaseInterface>(
     Ok(HttpResponse::Ok().finish())
 }
 
+async fn report_setup_keys<DB: DatabaseInterface>(
+    data: web::Data<AppState<DB>>,
+    r: web::Json<SetupKeysReport>,
+) -> actix_web::Result<HttpResponse> {
+    let report = r.into_inner();
+    if report.prover_name.is_empty() {
+        return Err(actix_web::error::ErrorBadRequest("empty name"));
+    }
+
+    vlog::info!(
+        "Prover instance '{}' reported setup keys: {:?}",
+        report.prover_name,
+        report.keys
+    );
+    data.setup_keys
+        .write()
+        .await
+        .insert(report.prover_name, report.keys);
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+async fn setup_keys<DB: DatabaseInterface>(
+    data: web::Data<AppState<DB>>,
+) -> actix_web::Result<HttpResponse> {
+    let response: Vec<_> = data
+        .setup_keys
+        .read()
+        .await
+        .iter()
+        .map(|(prover_name, keys)| SetupKeysReport {
+            prover_name: prover_name.clone(),
+            keys: keys.clone(),
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
 /// Input of the `/scaler/replicas` endpoint.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RequiredReplicasInput {
@@ -284,9 +339,66 @@ async fn required_replicas<DB: DatabaseInterface>(
     Ok(HttpResponse::Ok().json(response))
 }
 
+async fn queue_stats<DB: DatabaseInterface>(
+    data: web::Data<AppState<DB>>,
+) -> actix_web::Result<HttpResponse> {
+    let mut oracle = data.scaler_oracle.write().await;
+
+    let response = oracle.queue_stats().await.map_err(|e| {
+        vlog::warn!("failed to load prover queue stats: {}", e);
+        actix_web::error::ErrorInternalServerError("storage layer error")
+    })?;
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Prover job being proven at the moment, an item of the `/api/internal/prover/in_progress_jobs` output.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InProgressJob {
+    job_id: i32,
+    job_type: String,
+    first_block: BlockNumber,
+    last_block: BlockNumber,
+    /// Name of the prover that sent the last heartbeat for the job.
+    prover_name: String,
+    /// Amount of seconds passed since the last heartbeat for the job.
+    /// Jobs are returned to the queue once it exceeds the prover gone timeout.
+    seconds_since_heartbeat: i64,
+}
+
+async fn in_progress_jobs<DB: DatabaseInterface>(
+    data: web::Data<AppState<DB>>,
+) -> actix_web::Result<HttpResponse> {
+    let mut storage = data.access_storage().await?;
+    let jobs = data
+        .database
+        .load_in_progress_jobs(&mut storage)
+        .await
+        .map_err(|e| {
+            vlog::warn!("failed to load in progress prover jobs: {}", e);
+            actix_web::error::ErrorInternalServerError("storage layer error")
+        })?;
+
+    let now = chrono::Utc::now();
+    let response: Vec<_> = jobs
+        .into_iter()
+        .map(|job| InProgressJob {
+            job_id: job.id,
+            job_type: job.job_type,
+            first_block: BlockNumber(job.first_block as u32),
+            last_block: BlockNumber(job.last_block as u32),
+            prover_name: job.updated_by,
+            seconds_since_heartbeat: (now - job.updated_at).num_seconds(),
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
 async fn update_prover_job_queue_loop<DB: DatabaseInterface>(
     database: DB,
     prepare_data_interval: Duration,
+    stale_job_timeout: Duration,
 ) {
     // We use `prepare_data_interval` as timeout in this function to align creating prover jobs
     // with witness generator routine.
@@ -295,7 +407,7 @@ async fn update_prover_job_queue_loop<DB: DatabaseInterface>(
     loop {
         interval.tick().await;
 
-        update_prover_job_queue(database.clone())
+        update_prover_job_queue(database.clone(), stale_job_timeout)
             .await
             .unwrap_or_else(|e| {
                 vlog::warn!("Failed to update prover job queue: {}", e);
@@ -303,7 +415,10 @@ async fn update_prover_job_queue_loop<DB: DatabaseInterface>(
     }
 }
 
-async fn update_prover_job_queue<DB: DatabaseInterface>(database: DB) -> anyhow::Result<()> {
+async fn update_prover_job_queue<DB: DatabaseInterface>(
+    database: DB,
+    stale_job_timeout: Duration,
+) -> anyhow::Result<()> {
     let mut connection = database.acquire_connection().await?;
     {
         let next_single_block_to_add = database
@@ -381,7 +496,12 @@ async fn update_prover_job_queue<DB: DatabaseInterface>(database: DB) -> anyhow:
                 .await?;
         }
     }
-    database.mark_stale_jobs_as_idle(&mut connection).await?;
+    database
+        .mark_stale_jobs_as_idle(&mut connection, stale_job_timeout)
+        .await?;
+
+    let pending_jobs = database.pending_jobs_count(&mut connection).await?;
+    metrics::gauge!("prover_server.pending_jobs", pending_jobs as f64);
 
     Ok(())
 }
@@ -405,6 +525,7 @@ pub fn run_prover_server<DB: DatabaseInterface>(
                 tokio::spawn(update_prover_job_queue_loop(
                     database.clone(),
                     witness_generator_opts.prepare_data_interval(),
+                    core_opts.gone_timeout(),
                 ));
 
                 let last_verified_block = {
@@ -420,6 +541,17 @@ pub fn run_prover_server<DB: DatabaseInterface>(
                         as usize
                 };
 
+                // Witness generators of different instances sharing the database are
+                // distinguished by the process id and the startup time.
+                let instance_id = format!(
+                    "{}_{}",
+                    std::process::id(),
+                    SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .expect("Current time is before the UNIX epoch")
+                        .as_millis()
+                );
+
                 // Start pool maintainer threads.
                 for offset in 0..witness_generator_opts.witness_generators {
                     let start_block = (last_verified_block + offset + 1) as u32;
@@ -434,15 +566,24 @@ pub fn run_prover_server<DB: DatabaseInterface>(
                         witness_generator_opts.prepare_data_interval(),
                         BlockNumber(start_block),
                         BlockNumber(block_step),
+                        format!("witness_generator_{}_{}", instance_id, offset),
+                        witness_generator_opts.lease_timeout(),
                     );
                     pool_maintainer.start(panic_sender.clone());
                 }
                 // Start HTTP server.
                 let secret_auth = prover_api_opts.secret_auth.clone();
                 let idle_provers = core_opts.idle_provers;
+                let priority_policy = core_opts.job_priority_policy();
+                let setup_keys = Arc::new(RwLock::new(HashMap::new()));
                 HttpServer::new(move || {
-                    let app_state =
-                        AppState::new(secret_auth.clone(), database.clone(), idle_provers);
+                    let app_state = AppState::new(
+                        secret_auth.clone(),
+                        database.clone(),
+                        idle_provers,
+                        setup_keys.clone(),
+                        priority_policy,
+                    );
 
                     let auth = HttpAuthentication::bearer(move |req, credentials| async {
                         let secret_auth = req
@@ -465,10 +606,24 @@ pub fn run_prover_server<DB: DatabaseInterface>(
                         .route("/working_on", web::post().to(working_on::<DB>))
                         .route("/publish", web::post().to(publish::<DB>))
                         .route("/stopped", web::post().to(stopped::<DB>))
+                        .route("/setup_keys", web::post().to(report_setup_keys::<DB>))
                         .route(
                             "/api/internal/prover/replicas",
                             web::post().to(required_replicas::<DB>),
                         )
+                        .route(
+                            "/api/internal/prover/in_progress_jobs",
+                            web::get().to(in_progress_jobs::<DB>),
+                        )
+                        .route(
+                            "/api/internal/prover/queue",
+                            web::get().to(queue_stats::<DB>),
+                        )
+                        .route(
+                            "/api/internal/prover/setup_keys",
+                            web::get().to(setup_keys::<DB>),
+                        )
+                        .configure(zksync_http_utils::log_filter::configure)
                 })
                 .bind(&prover_api_opts.bind_addr())
                 .expect("failed to bind")
@@ -1,7 +1,86 @@
 //! Module with utilities for prover scaler service.
 
+// Built-in deps
+use std::time::Duration;
+// External deps
+use serde::{Deserialize, Serialize};
 // Workspace deps
 use crate::database_interface::DatabaseInterface;
+use zksync_storage::prover::records::ProverQueueStats;
+
+/// Only jobs proven within this window are used to calculate the average proving time,
+/// so the estimation follows changes of the prover hardware and the block sizes.
+const PROVING_TIME_WINDOW: Duration = Duration::from_secs(60 * 60);
+
+/// Prover jobs of the same type and size, an item of the `/api/internal/prover/queue` output.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProverQueueItem {
+    pub job_type: String,
+    /// Block chunks for single proofs and amount of blocks for aggregated ones.
+    pub job_size: u64,
+    pub pending_jobs: u64,
+    pub in_progress_jobs: u64,
+    /// Average proving time of the recently proven jobs in seconds.
+    pub average_proving_time: Option<f64>,
+}
+
+/// Output of the `/api/internal/prover/queue` endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProverQueueOutput {
+    pub jobs: Vec<ProverQueueItem>,
+    /// Estimated time to prove all the pending and in progress jobs by the provers
+    /// working at the moment, in seconds. `None` if there were no recently proven jobs.
+    pub estimated_drain_time: Option<f64>,
+}
+
+impl From<Vec<ProverQueueStats>> for ProverQueueOutput {
+    fn from(stats: Vec<ProverQueueStats>) -> Self {
+        let jobs: Vec<_> = stats
+            .into_iter()
+            .map(|item| ProverQueueItem {
+                job_type: item.job_type,
+                job_size: item.job_size as u64,
+                pending_jobs: item.pending_jobs as u64,
+                in_progress_jobs: item.in_progress_jobs as u64,
+                average_proving_time: item.average_proving_time,
+            })
+            .collect();
+        let estimated_drain_time = estimate_drain_time(&jobs);
+
+        Self {
+            jobs,
+            estimated_drain_time,
+        }
+    }
+}
+
+/// Estimates time needed to prove all the queued jobs. Jobs of sizes that were not proven
+/// recently are assumed to take the average proving time among all the sizes.
+fn estimate_drain_time(jobs: &[ProverQueueItem]) -> Option<f64> {
+    let known_times: Vec<_> = jobs
+        .iter()
+        .filter_map(|item| item.average_proving_time)
+        .collect();
+    let fallback_time = if known_times.is_empty() {
+        None
+    } else {
+        Some(known_times.iter().sum::<f64>() / known_times.len() as f64)
+    };
+
+    let mut total_time = 0f64;
+    for item in jobs {
+        let jobs_count = item.pending_jobs + item.in_progress_jobs;
+        if jobs_count == 0 {
+            continue;
+        }
+        total_time += jobs_count as f64 * item.average_proving_time.or(fallback_time)?;
+    }
+    // Every prover works on a single job at a time.
+    let provers: u64 = jobs.iter().map(|item| item.in_progress_jobs).sum();
+
+    Some(total_time / std::cmp::max(provers, 1) as f64)
+}
+
 /// Scaler oracle provides information for prover scaler
 /// service about required amount of provers for server
 /// to operate optimally.
@@ -31,4 +110,15 @@ impl<DB: DatabaseInterface> ScalerOracle<DB> {
 
         Ok(provers_required)
     }
+
+    /// Returns the prover jobs queue depth by the job type and size and the estimated time to prove it.
+    pub async fn queue_stats(&mut self) -> anyhow::Result<ProverQueueOutput> {
+        let mut storage = self.db.acquire_connection().await?;
+        let stats = self
+            .db
+            .load_prover_queue_stats(&mut storage, PROVING_TIME_WINDOW)
+            .await?;
+
+        Ok(stats.into())
+    }
 }
@@ -1,8 +1,9 @@
 // Built-in
 use std::clone::Clone;
+use std::collections::HashMap;
 use std::str::FromStr;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 // External uses
 use chrono::Utc;
 use tokio::sync::RwLock;
@@ -11,12 +12,14 @@ use tokio::time::sleep;
 use zksync_crypto::params::account_tree_depth;
 use zksync_crypto::proof::{AggregatedProof, SingleProof};
 use zksync_storage::chain::tree_cache::records::AccountTreeCache;
-use zksync_storage::prover::records::{StorageBlockWitness, StorageProverJobQueue, StoredProof};
+use zksync_storage::prover::records::{
+    InProgressProverJob, ProverQueueStats, StorageBlockWitness, StorageProverJobQueue, StoredProof,
+};
 use zksync_storage::StorageProcessor;
 use zksync_types::{
     aggregated_operations::{AggregatedActionType, AggregatedOperation},
     block::Block,
-    prover::{ProverJob, ProverJobStatus, ProverJobType},
+    prover::{ProverJob, ProverJobPriorityPolicy, ProverJobStatus, ProverJobType},
     AccountId, AccountMap, AccountTree, AccountUpdates, Address, BlockNumber,
 };
 // Local uses
@@ -31,6 +34,8 @@ pub struct MockDatabase {
     blocks: Arc<RwLock<Vec<Block>>>,
     account_tree_cache: Arc<RwLock<AccountTreeCache>>,
     accounts_state: Arc<RwLock<(u32, AccountMap)>>,
+    /// Holder of the lease and the time of its last heartbeat for each block.
+    witness_leases: Arc<RwLock<HashMap<BlockNumber, (String, Instant)>>>,
 }
 
 impl MockDatabase {
@@ -49,6 +54,7 @@ impl MockDatabase {
                 tree_cache_binary,
             })),
             accounts_state: Arc::new(RwLock::new((0, accounts))),
+            witness_leases: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
@@ -109,6 +115,7 @@ impl DatabaseInterface for MockDatabase {
             id,
             job_priority,
             job_data,
+            proving_started_at: None,
         };
 
         prover_job_queue.1.push(new_job);
@@ -135,6 +142,57 @@ impl DatabaseInterface for MockDatabase {
         Ok(BlockNumber(block_number as u32))
     }
 
+    async fn try_acquire_witness_lease(
+        &self,
+        _: &mut StorageProcessor<'_>,
+        block: BlockNumber,
+        worker: &str,
+        lease_timeout: Duration,
+    ) -> anyhow::Result<bool> {
+        let mut witness_leases = self.witness_leases.write().await;
+        let available = match witness_leases.get(&block) {
+            Some((holder, heartbeat_at)) => {
+                holder == worker || heartbeat_at.elapsed() >= lease_timeout
+            }
+            None => true,
+        };
+        if available {
+            witness_leases.insert(block, (worker.to_string(), Instant::now()));
+        }
+
+        Ok(available)
+    }
+
+    async fn heartbeat_witness_lease(
+        &self,
+        _: &mut StorageProcessor<'_>,
+        block: BlockNumber,
+        worker: &str,
+    ) -> anyhow::Result<bool> {
+        let mut witness_leases = self.witness_leases.write().await;
+        match witness_leases.get_mut(&block) {
+            Some((holder, heartbeat_at)) if holder == worker => {
+                *heartbeat_at = Instant::now();
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    async fn release_witness_lease(
+        &self,
+        _: &mut StorageProcessor<'_>,
+        block: BlockNumber,
+        worker: &str,
+    ) -> anyhow::Result<()> {
+        let mut witness_leases = self.witness_leases.write().await;
+        if matches!(witness_leases.get(&block), Some((holder, _)) if holder == worker) {
+            witness_leases.remove(&block);
+        }
+
+        Ok(())
+    }
+
     async fn pending_jobs_count(&self, _: &mut StorageProcessor<'_>) -> anyhow::Result<u32> {
         let count = self
             .prover_job_queue
@@ -171,12 +229,19 @@ impl DatabaseInterface for MockDatabase {
         Ok(single_proof)
     }
 
-    async fn mark_stale_jobs_as_idle(&self, _: &mut StorageProcessor<'_>) -> anyhow::Result<()> {
+    async fn mark_stale_jobs_as_idle(
+        &self,
+        _: &mut StorageProcessor<'_>,
+        stale_timeout: Duration,
+    ) -> anyhow::Result<()> {
         let now = Utc::now();
+        let stale_timeout = chrono::Duration::from_std(stale_timeout)?;
         let prover_job_queue = &mut self.prover_job_queue.write().await.1;
 
         for job in prover_job_queue.iter_mut() {
-            if now - job.updated_at > chrono::Duration::seconds(10) {
+            if job.job_status == ProverJobStatus::InProgress.to_number()
+                && now - job.updated_at >= stale_timeout
+            {
                 job.job_status = ProverJobStatus::Idle.to_number();
                 job.updated_at = now;
                 job.updated_by = "server_clean_idle".to_string();
@@ -186,6 +251,94 @@ impl DatabaseInterface for MockDatabase {
         Ok(())
     }
 
+    async fn load_in_progress_jobs(
+        &self,
+        _: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Vec<InProgressProverJob>> {
+        let jobs = self
+            .prover_job_queue
+            .read()
+            .await
+            .1
+            .iter()
+            .filter(|job| job.job_status == ProverJobStatus::InProgress.to_number())
+            .map(|job| InProgressProverJob {
+                id: job.id,
+                job_type: job.job_type.clone(),
+                first_block: job.first_block,
+                last_block: job.last_block,
+                updated_by: job.updated_by.clone(),
+                updated_at: job.updated_at,
+            })
+            .collect();
+
+        Ok(jobs)
+    }
+
+    async fn load_prover_queue_stats(
+        &self,
+        _: &mut StorageProcessor<'_>,
+        proving_time_window: Duration,
+    ) -> anyhow::Result<Vec<ProverQueueStats>> {
+        let now = Utc::now();
+        let proving_time_window = chrono::Duration::from_std(proving_time_window)?;
+        let blocks = self.blocks.read().await;
+
+        let mut stats: Vec<ProverQueueStats> = Vec::new();
+        let mut proving_times: HashMap<(String, i64), Vec<f64>> = HashMap::new();
+        for job in self.prover_job_queue.read().await.1.iter() {
+            let done = job.job_status == ProverJobStatus::Done.to_number();
+            if done && now - job.updated_at > proving_time_window {
+                continue;
+            }
+            let job_size = if job.job_type == ProverJobType::SingleProof.to_string() {
+                blocks
+                    .iter()
+                    .find(|block| *block.block_number as i64 == job.first_block)
+                    .map(|block| block.block_chunks_size as i64)
+                    .unwrap_or_default()
+            } else {
+                job.last_block - job.first_block + 1
+            };
+
+            let index = match stats
+                .iter()
+                .position(|item| item.job_type == job.job_type && item.job_size == job_size)
+            {
+                Some(index) => index,
+                None => {
+                    stats.push(ProverQueueStats {
+                        job_type: job.job_type.clone(),
+                        job_size,
+                        pending_jobs: 0,
+                        in_progress_jobs: 0,
+                        average_proving_time: None,
+                    });
+                    stats.len() - 1
+                }
+            };
+            if job.job_status == ProverJobStatus::Idle.to_number() {
+                stats[index].pending_jobs += 1;
+            } else if job.job_status == ProverJobStatus::InProgress.to_number() {
+                stats[index].in_progress_jobs += 1;
+            } else if let Some(proving_started_at) = job.proving_started_at {
+                proving_times
+                    .entry((job.job_type.clone(), job_size))
+                    .or_default()
+                    .push((job.updated_at - proving_started_at).num_milliseconds() as f64 / 1000.0);
+            }
+        }
+
+        for item in stats.iter_mut() {
+            if let Some(times) = proving_times.get(&(item.job_type.clone(), item.job_size)) {
+                item.average_proving_time = Some(times.iter().sum::<f64>() / times.len() as f64);
+            }
+        }
+        stats.sort_by(|a, b| (&a.job_type, a.job_size).cmp(&(&b.job_type, b.job_size)));
+
+        Ok(stats)
+    }
+
     async fn load_last_verified_block(
         &self,
         _: &mut StorageProcessor<'_>,
@@ -224,23 +377,33 @@ impl DatabaseInterface for MockDatabase {
     async fn load_idle_prover_job_from_job_queue(
         &self,
         _: &mut StorageProcessor<'_>,
+        priority_policy: &ProverJobPriorityPolicy,
     ) -> anyhow::Result<Option<ProverJob>> {
+        let now = Utc::now();
+        let job_priority = |job: &StorageProverJobQueue| {
+            let job_type = job.job_type.parse().expect("Incorrect job type");
+            priority_policy.job_priority(&job_type, job.job_priority, job.created_at, now)
+        };
+
         let prover_job_queue = &mut self.prover_job_queue.write().await.1;
         let idle_prover_job = prover_job_queue
             .iter_mut()
             .filter(|job| job.job_status == ProverJobStatus::Idle.to_number())
-            .max_by_key(|job| (job.job_priority, job.id));
+            .min_by_key(|job| (job_priority(job), job.id));
 
         let prover_job = if let Some(job) = idle_prover_job {
+            let job_priority = job_priority(job);
             job.job_status = ProverJobStatus::InProgress.to_number();
             job.updated_at = Utc::now();
             job.updated_by = "server_give_job".to_string();
+            job.proving_started_at = Some(job.updated_at);
 
             Some(ProverJob::new(
                 job.id,
                 BlockNumber(job.first_block as u32),
                 BlockNumber(job.last_block as u32),
                 job.job_data.clone(),
+                job_priority,
             ))
         } else {
             None
@@ -381,16 +544,11 @@ impl DatabaseInterface for MockDatabase {
     ) -> anyhow::Result<()> {
         let witness_str = serde_json::to_string(&witness).expect("Failed to serialize witness");
         let mut block_witness = self.block_witness.write().await;
-        let is_block_not_saved_yet = !block_witness
-            .iter()
-            .any(|witness| witness.block == *block as i64);
-
-        if is_block_not_saved_yet {
-            block_witness.push(StorageBlockWitness {
-                block: *block as i64,
-                witness: witness_str,
-            });
-        }
+        block_witness.retain(|witness| witness.block != *block as i64);
+        block_witness.push(StorageBlockWitness {
+            block: *block as i64,
+            witness: witness_str,
+        });
 
         Ok(())
     }
@@ -1,2 +1,4 @@
 mod mock;
 mod prover_server;
+mod scaler;
+mod witness_cache;
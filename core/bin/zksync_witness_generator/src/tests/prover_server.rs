@@ -7,13 +7,15 @@ use num::BigUint;
 use zksync_config::{
     configs::{
         api::ProverApiConfig,
-        prover::{Core, Prover, WitnessGenerator},
+        prover::{Core, ExitProofGenerator, Prover, WitnessGenerator},
     },
     ProverConfig,
 };
 use zksync_crypto::franklin_crypto::bellman::pairing::ff::{PrimeField, PrimeFieldRepr};
 use zksync_prover::{client, ApiClient};
-use zksync_prover_utils::api::ProverInputRequest;
+use zksync_prover_utils::api::{
+    ProverInputRequest, SetupKeyState, SetupKeyStatus, SetupKeysReport,
+};
 use zksync_types::{block::Block, AccountId, BlockNumber, TokenId, H256};
 // Local deps
 use super::mock::MockDatabase;
@@ -39,14 +41,24 @@ impl Default for MockProverOptions {
                 cycle_wait: 500,
                 request_timeout: 10,
                 die_after_proof: false,
+                setup_keys_checksums: Vec::new(),
+                prometheus_port: 3313,
             },
             core: Core {
                 gone_timeout: 60000,
                 idle_provers: 1,
+                aggregated_proof_deadline: 3600,
+                aggregated_proof_preemption_window: 600,
             },
             witness_generator: WitnessGenerator {
                 prepare_data_interval: 500,
                 witness_generators: 1,
+                lease_timeout: 60000,
+            },
+            exit_proof_generator: ExitProofGenerator {
+                workers: 1,
+                poll_interval: 1000,
+                lease_timeout: 60000,
             },
         };
 
@@ -153,6 +165,19 @@ async fn test_api_client_simple_simulation(prover_name: &str, database: MockData
         .unwrap();
     assert!(job.data.is_some());
 
+    client
+        .report_setup_keys(SetupKeysReport {
+            prover_name: prover_name.to_string(),
+            keys: vec![SetupKeyStatus {
+                power_of_two: 20,
+                state: SetupKeyState::Ready,
+                checksum_verified: false,
+                downloaded: false,
+            }],
+        })
+        .await
+        .unwrap();
+
     let mut storage = database.acquire_connection().await.unwrap();
     let witness = database
         .load_witness(&mut storage, BlockNumber(1))
@@ -0,0 +1,50 @@
+// Workspace deps
+use zksync_storage::prover::records::ProverQueueStats;
+use zksync_types::prover::ProverJobType;
+// Local deps
+use crate::scaler::ProverQueueOutput;
+
+fn queue_item(
+    job_type: ProverJobType,
+    job_size: i64,
+    pending_jobs: i64,
+    in_progress_jobs: i64,
+    average_proving_time: Option<f64>,
+) -> ProverQueueStats {
+    ProverQueueStats {
+        job_type: job_type.to_string(),
+        job_size,
+        pending_jobs,
+        in_progress_jobs,
+        average_proving_time,
+    }
+}
+
+/// Checks the estimation of the time needed to prove all the queued jobs.
+#[test]
+fn queue_drain_time() {
+    // Empty queue is drained immediately.
+    let output = ProverQueueOutput::from(Vec::new());
+    assert_eq!(output.estimated_drain_time, Some(0.0));
+
+    // Nothing is known about the proving time.
+    let output =
+        ProverQueueOutput::from(vec![queue_item(ProverJobType::SingleProof, 26, 2, 0, None)]);
+    assert_eq!(output.estimated_drain_time, None);
+
+    // 3 jobs by 10 seconds and 2 jobs by 40 seconds proven by 2 provers.
+    let output = ProverQueueOutput::from(vec![
+        queue_item(ProverJobType::AggregatedProof, 4, 1, 1, Some(40.0)),
+        queue_item(ProverJobType::SingleProof, 26, 2, 1, Some(10.0)),
+    ]);
+    assert_eq!(output.jobs.len(), 2);
+    assert_eq!(output.estimated_drain_time, Some(55.0));
+
+    // Sizes without the recent proofs use the average time of other sizes.
+    let output = ProverQueueOutput::from(vec![
+        queue_item(ProverJobType::SingleProof, 26, 0, 1, Some(10.0)),
+        queue_item(ProverJobType::SingleProof, 78, 1, 0, None),
+        queue_item(ProverJobType::SingleProof, 182, 0, 0, Some(30.0)),
+    ]);
+    assert_eq!(output.estimated_drain_time, Some(30.0));
+}
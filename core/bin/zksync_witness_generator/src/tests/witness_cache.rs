@@ -0,0 +1,67 @@
+// Built-in deps
+use std::time::Duration;
+// Workspace deps
+use zksync_circuit::serialization::ProverData;
+use zksync_crypto::Fr;
+use zksync_types::BlockNumber;
+// Local deps
+use super::{mock::MockDatabase, prover_server::get_test_block};
+use crate::{
+    witness_generator::{BlockInfo, WitnessGenerator},
+    DatabaseInterface,
+};
+
+/// Checks that the stored witness is reused while it matches the root hash of the block,
+/// and is rebuilt once it doesn't.
+#[tokio::test]
+async fn stale_witness_is_rebuilt() -> anyhow::Result<()> {
+    let database = MockDatabase::new();
+    let block = get_test_block().await;
+    let block_number = block.block_number;
+    database.add_block(block.clone()).await;
+
+    let witness_generator = WitnessGenerator::new(
+        database.clone(),
+        Duration::from_millis(100),
+        BlockNumber(1),
+        BlockNumber(1),
+        "test_worker".to_string(),
+        Duration::from_secs(60),
+    );
+
+    // There is no witness for the block yet.
+    let block_info = witness_generator.should_work_on_block(block_number).await?;
+    assert!(matches!(block_info, BlockInfo::NoWitness(_)));
+
+    // The built witness is reused.
+    witness_generator.prepare_witness_and_save_it(block).await?;
+    let block_info = witness_generator.should_work_on_block(block_number).await?;
+    assert!(matches!(block_info, BlockInfo::WithWitness));
+
+    // The witness doesn't match the block after its revert and re-creation.
+    let mut storage = database.acquire_connection().await?;
+    let witness = database
+        .load_witness(&mut storage, block_number)
+        .await?
+        .expect("witness is stored");
+    let mut stale_witness: ProverData = serde_json::from_value(witness)?;
+    stale_witness.new_root = Fr::default();
+    database
+        .store_witness(
+            &mut storage,
+            block_number,
+            serde_json::to_value(stale_witness)?,
+        )
+        .await?;
+    let block_info = witness_generator.should_work_on_block(block_number).await?;
+    assert!(matches!(block_info, BlockInfo::NoWitness(_)));
+
+    // Witness which can't be deserialized is rebuilt as well.
+    database
+        .store_witness(&mut storage, block_number, serde_json::json!("corrupted"))
+        .await?;
+    let block_info = witness_generator.should_work_on_block(block_number).await?;
+    assert!(matches!(block_info, BlockInfo::NoWitness(_)));
+
+    Ok(())
+}
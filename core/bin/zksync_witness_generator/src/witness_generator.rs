@@ -3,6 +3,7 @@ use std::time::Instant;
 use std::{thread, time};
 // External
 use futures::channel::mpsc;
+use serde::Deserialize;
 use tokio::time::sleep;
 use zksync_crypto::merkle_tree::parallel_smt::SparseMerkleTreeSerializableCacheBN256;
 // Workspace deps
@@ -20,6 +21,11 @@ use zksync_utils::panic_notify::ThreadPanicNotify;
 ///
 /// This will generate and store in db witnesses for blocks with indexes
 /// start_block, start_block + block_step, start_block + 2*block_step, ...
+///
+/// Several witness generator instances may share the database. Before building a witness,
+/// the block is claimed with a lease which is kept alive by heartbeats. Blocks claimed
+/// by another instance are skipped and rechecked later, so they are picked up again
+/// if the lease holder dies before storing the witness.
 pub struct WitnessGenerator<DB: DatabaseInterface> {
     /// Connection to the database.
     database: DB,
@@ -28,10 +34,15 @@ pub struct WitnessGenerator<DB: DatabaseInterface> {
 
     start_block: BlockNumber,
     block_step: BlockNumber,
+
+    /// Unique name of this witness generator used to hold block leases.
+    worker: String,
+    /// Time after which the lease of the worker that stopped sending heartbeats expires.
+    lease_timeout: time::Duration,
 }
 
 #[derive(Debug)]
-enum BlockInfo {
+pub(crate) enum BlockInfo {
     NotReadyBlock,
     WithWitness,
     NoWitness(Block),
@@ -44,12 +55,16 @@ impl<DB: DatabaseInterface> WitnessGenerator<DB> {
         rounds_interval: time::Duration,
         start_block: BlockNumber,
         block_step: BlockNumber,
+        worker: String,
+        lease_timeout: time::Duration,
     ) -> Self {
         Self {
             database,
             rounds_interval,
             start_block,
             block_step,
+            worker,
+            lease_timeout,
         }
     }
 
@@ -72,7 +87,7 @@ impl<DB: DatabaseInterface> WitnessGenerator<DB> {
     }
 
     /// Returns status of witness for block with index block_number
-    async fn should_work_on_block(
+    pub(crate) async fn should_work_on_block(
         &self,
         block_number: BlockNumber,
     ) -> Result<BlockInfo, anyhow::Error> {
@@ -88,10 +103,20 @@ impl<DB: DatabaseInterface> WitnessGenerator<DB> {
                 .database
                 .load_witness(&mut transaction, block_number)
                 .await?;
-            if witness.is_none() {
-                BlockInfo::NoWitness(block)
-            } else {
-                BlockInfo::WithWitness
+            match witness {
+                Some(witness) if Self::is_witness_actual(&witness, &block) => {
+                    BlockInfo::WithWitness
+                }
+                Some(_) => {
+                    // The witness was built for a block that has been reverted since then.
+                    vlog::warn!(
+                        "Stored witness for block {} doesn't match its root hash, rebuilding it",
+                        block_number
+                    );
+                    metrics::increment_counter!("witness_generator.stale_witness");
+                    BlockInfo::NoWitness(block)
+                }
+                None => BlockInfo::NoWitness(block),
             }
         } else {
             BlockInfo::NotReadyBlock
@@ -101,6 +126,15 @@ impl<DB: DatabaseInterface> WitnessGenerator<DB> {
         Ok(block_info)
     }
 
+    /// Checks that the stored witness was built for the current state of the block,
+    /// so it can be reused instead of being rebuilt.
+    fn is_witness_actual(witness: &serde_json::Value, block: &Block) -> bool {
+        match ProverData::deserialize(witness) {
+            Ok(prover_data) => prover_data.new_root == block.new_root_hash,
+            Err(_) => false,
+        }
+    }
+
     async fn load_account_tree(
         &self,
         block: BlockNumber,
@@ -216,7 +250,7 @@ impl<DB: DatabaseInterface> WitnessGenerator<DB> {
         Ok(circuit_account_tree)
     }
 
-    async fn prepare_witness_and_save_it(&self, block: Block) -> anyhow::Result<()> {
+    pub(crate) async fn prepare_witness_and_save_it(&self, block: Block) -> anyhow::Result<()> {
         let fn_start = Instant::now();
         let mut storage = self.database.acquire_connection().await?;
 
@@ -248,6 +282,110 @@ impl<DB: DatabaseInterface> WitnessGenerator<DB> {
         Ok(())
     }
 
+    /// Claims the block for this witness generator, returns `false` if
+    /// another witness generator is already working on it.
+    async fn try_acquire_lease(&self, block_number: BlockNumber) -> anyhow::Result<bool> {
+        let mut storage = self.database.acquire_connection().await?;
+        self.database
+            .try_acquire_witness_lease(&mut storage, block_number, &self.worker, self.lease_timeout)
+            .await
+    }
+
+    /// Keeps the lease on the block alive until the task is aborted.
+    async fn keep_lease_alive(
+        database: DB,
+        block_number: BlockNumber,
+        worker: String,
+        heartbeat_interval: time::Duration,
+    ) {
+        loop {
+            sleep(heartbeat_interval).await;
+            let result = match database.acquire_connection().await {
+                Ok(mut storage) => {
+                    database
+                        .heartbeat_witness_lease(&mut storage, block_number, &worker)
+                        .await
+                }
+                Err(err) => Err(err),
+            };
+            match result {
+                Ok(true) => {}
+                Ok(false) => vlog::warn!(
+                    "Witness generator {} lost the lease for block {}",
+                    worker,
+                    block_number
+                ),
+                Err(err) => vlog::warn!(
+                    "Witness generator {} failed to prolong the lease for block {}: {}",
+                    worker,
+                    block_number,
+                    err
+                ),
+            }
+        }
+    }
+
+    /// Prepares the witness for the leased block, sending heartbeats while it's being built.
+    /// The lease is released afterwards regardless of the result.
+    async fn prepare_leased_witness(&self, block: Block) -> anyhow::Result<()> {
+        let block_number = block.block_number;
+        let heartbeat = tokio::spawn(Self::keep_lease_alive(
+            self.database.clone(),
+            block_number,
+            self.worker.clone(),
+            self.lease_timeout / 3,
+        ));
+        let result = self.prepare_witness_and_save_it(block).await;
+        heartbeat.abort();
+
+        let mut storage = self.database.acquire_connection().await?;
+        self.database
+            .release_witness_lease(&mut storage, block_number, &self.worker)
+            .await?;
+        result
+    }
+
+    /// Claims and processes the block if it still has no witness.
+    /// Returns `true` if there is nothing left to do with this block.
+    async fn try_process_block(&self, block_info: BlockInfo) -> anyhow::Result<bool> {
+        let block = match block_info {
+            BlockInfo::NoWitness(block) => block,
+            BlockInfo::WithWitness => return Ok(true),
+            BlockInfo::NotReadyBlock => return Ok(false),
+        };
+        if !self.try_acquire_lease(block.block_number).await? {
+            return Ok(false);
+        }
+        self.prepare_leased_witness(block).await?;
+        Ok(true)
+    }
+
+    /// Rechecks the blocks that were claimed by other witness generators,
+    /// taking over the ones whose leases have expired.
+    async fn process_foreign_blocks(&self, foreign_blocks: &mut Vec<BlockNumber>) {
+        let mut still_foreign = Vec::new();
+        for block_number in foreign_blocks.drain(..) {
+            let result = match self.should_work_on_block(block_number).await {
+                Ok(block_info) => self.try_process_block(block_info).await,
+                Err(err) => Err(err),
+            };
+            match result {
+                Ok(true) => {}
+                Ok(false) => still_foreign.push(block_number),
+                Err(err) => {
+                    vlog::warn!(
+                        "Witness generator {} failed to recheck block {}: {}",
+                        self.worker,
+                        block_number,
+                        err
+                    );
+                    still_foreign.push(block_number);
+                }
+            }
+        }
+        *foreign_blocks = still_foreign;
+    }
+
     /// Returns next block for generating witness
     fn next_witness_block(
         current_block: BlockNumber,
@@ -275,10 +413,15 @@ impl<DB: DatabaseInterface> WitnessGenerator<DB> {
         metrics::register_counter!("witness_generator.cache_access", "type" => "hit");
         metrics::register_counter!("witness_generator.cache_access", "type" => "off_by_1");
         metrics::register_counter!("witness_generator.cache_access", "type" => "miss");
+        metrics::register_counter!("witness_generator.failures");
 
         let mut current_block = self.start_block;
+        // Blocks skipped because they were leased by another witness generator.
+        let mut foreign_blocks = Vec::new();
         loop {
             sleep(self.rounds_interval).await;
+            self.process_foreign_blocks(&mut foreign_blocks).await;
+
             let should_work = match self.should_work_on_block(current_block).await {
                 Ok(should_work) => should_work,
                 Err(err) => {
@@ -288,12 +431,19 @@ impl<DB: DatabaseInterface> WitnessGenerator<DB> {
             };
 
             let next_block = Self::next_witness_block(current_block, self.block_step, &should_work);
-            if let BlockInfo::NoWitness(block) = should_work {
-                let block_number = block.block_number;
-                if let Err(err) = self.prepare_witness_and_save_it(block).await {
-                    vlog::warn!("Witness generator ({},{}) failed to prepare witness for block: {}, err: {}",
-                        self.start_block, self.block_step, block_number, err);
-                    continue; // Retry the same block on the next iteration.
+            if let BlockInfo::NoWitness(_) = should_work {
+                match self.try_process_block(should_work).await {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        // Another witness generator works on this block, check it again later.
+                        foreign_blocks.push(current_block);
+                    }
+                    Err(err) => {
+                        metrics::increment_counter!("witness_generator.failures");
+                        vlog::warn!("Witness generator ({},{}) failed to prepare witness for block: {}, err: {}",
+                            self.start_block, self.block_step, current_block, err);
+                        continue; // Retry the same block on the next iteration.
+                    }
                 }
             }
 
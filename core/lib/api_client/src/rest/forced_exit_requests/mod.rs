@@ -6,7 +6,12 @@
 use serde::{Deserialize, Serialize};
 
 // Workspace uses
-use zksync_types::{forced_exit_requests::ForcedExitRequest, Address, TokenId};
+use zksync_types::{
+    forced_exit_requests::{
+        ForcedExitRequest, ForcedExitRequestId, ForcedExitRequestProgress, ForcedExitTarget,
+    },
+    Address, TokenId,
+};
 use zksync_utils::BigUintSerdeAsRadix10Str;
 
 use num::BigUint;
@@ -37,12 +42,33 @@ pub enum ForcedExitRequestStatus {
 pub struct ForcedExitRegisterRequest {
     pub target: Address,
     pub tokens: Vec<TokenId>,
+    // Other accounts to be exited with the same payment
+    #[serde(default)]
+    pub additional_targets: Vec<ForcedExitTarget>,
     // Even though the price is constant, we still need to specify it,
     // since the price might change (with config)
     #[serde(with = "BigUintSerdeAsRadix10Str")]
     pub price_in_wei: BigUint,
 }
 
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ForcedExitFeeQuote {
+    pub tokens_count: u8,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub price_per_token: BigUint,
+    // The exact amount to be specified in the request and paid to the contract
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub price_in_wei: BigUint,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ForcedExitRequestProgressInfo {
+    pub progress: ForcedExitRequestProgress,
+    pub request: ForcedExitRequest,
+}
+
 const FORCED_EXIT_REQUESTS_SCOPE: &str = "/api/forced_exit_requests/v0.1/";
 
 impl Client {
@@ -61,4 +87,37 @@ impl Client {
             .send()
             .await
     }
+
+    pub async fn get_forced_exit_fee_quote(
+        &self,
+        tokens_count: u8,
+    ) -> ClientResult<ForcedExitFeeQuote> {
+        self.get_with_scope(FORCED_EXIT_REQUESTS_SCOPE, &format!("fee/{}", tokens_count))
+            .send()
+            .await
+    }
+
+    pub async fn get_forced_exit_request_progress(
+        &self,
+        id: ForcedExitRequestId,
+    ) -> ClientResult<ForcedExitRequestProgressInfo> {
+        self.get_with_scope(
+            FORCED_EXIT_REQUESTS_SCOPE,
+            &format!("requests/{}/status", id),
+        )
+        .send()
+        .await
+    }
+
+    pub async fn get_forced_exit_requests_by_target(
+        &self,
+        target: Address,
+    ) -> ClientResult<Vec<ForcedExitRequest>> {
+        self.get_with_scope(
+            FORCED_EXIT_REQUESTS_SCOPE,
+            &format!("targets/{:?}/requests", target),
+        )
+        .send()
+        .await
+    }
 }
@@ -4,7 +4,7 @@ use zksync_api_types::v02::{
     pagination::{ApiEither, PaginationQuery},
     Response,
 };
-use zksync_types::{tx::TxHash, SerialId};
+use zksync_types::{tx::TxHash, BlockNumber, SerialId};
 
 impl Client {
     pub async fn account_info(
@@ -20,6 +20,22 @@ impl Client {
         .await
     }
 
+    pub async fn account_state_at_block(
+        &self,
+        account_id_or_address: &str,
+        block_number: BlockNumber,
+    ) -> Result<Response> {
+        self.get_with_scope(
+            super::API_V02_SCOPE,
+            &format!(
+                "accounts/{}/finalized/{}",
+                account_id_or_address, block_number
+            ),
+        )
+        .send()
+        .await
+    }
+
     pub async fn account_full_info(&self, account_id_or_address: &str) -> Result<Response> {
         self.get_with_scope(
             super::API_V02_SCOPE,
@@ -56,4 +72,51 @@ impl Client {
         .send()
         .await
     }
+
+    pub async fn account_withdrawals(
+        &self,
+        pagination_query: &PaginationQuery<ApiEither<TxHash>>,
+        address: &str,
+    ) -> Result<Response> {
+        self.get_with_scope(
+            super::API_V02_SCOPE,
+            &format!("accounts/{}/withdrawals", address),
+        )
+        .query(pagination_query)
+        .send()
+        .await
+    }
+
+    /// Queues the exit proof generation for the account and token against the last finalized state.
+    pub async fn request_exit_proof(
+        &self,
+        account_id_or_address: &str,
+        token_like: &str,
+    ) -> Result<Response> {
+        self.post_with_scope(
+            super::API_V02_SCOPE,
+            &format!(
+                "accounts/{}/exitProof/{}",
+                account_id_or_address, token_like
+            ),
+        )
+        .send()
+        .await
+    }
+
+    pub async fn exit_proof(
+        &self,
+        account_id_or_address: &str,
+        token_like: &str,
+    ) -> Result<Response> {
+        self.get_with_scope(
+            super::API_V02_SCOPE,
+            &format!(
+                "accounts/{}/exitProof/{}",
+                account_id_or_address, token_like
+            ),
+        )
+        .send()
+        .await
+    }
 }
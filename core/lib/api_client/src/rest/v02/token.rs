@@ -1,9 +1,10 @@
 use crate::rest::client::{Client, Result};
 use zksync_api_types::v02::{
     pagination::{ApiEither, PaginationQuery},
+    token::RegisterNFTFactoryRequest,
     Response,
 };
-use zksync_types::{tx::TxHash, TokenId, TokenLike};
+use zksync_types::{tx::TxHash, AccountId, TokenId, TokenLike};
 
 impl Client {
     pub async fn token_pagination(
@@ -31,6 +32,18 @@ impl Client {
         .await
     }
 
+    pub async fn token_metadata(&self, token: &TokenLike) -> Result<Response> {
+        self.get_with_scope(super::API_V02_SCOPE, &format!("tokens/{}/metadata", token))
+            .send()
+            .await
+    }
+
+    pub async fn token_holders(&self, token: &TokenLike) -> Result<Response> {
+        self.get_with_scope(super::API_V02_SCOPE, &format!("tokens/{}/holders", token))
+            .send()
+            .await
+    }
+
     pub async fn nft_by_id(&self, id: TokenId) -> Result<Response> {
         self.get_with_scope(super::API_V02_SCOPE, &format!("tokens/nft/{}", id))
             .send()
@@ -51,4 +64,34 @@ impl Client {
         .send()
         .await
     }
+
+    pub async fn token_listing_rejections(&self) -> Result<Response> {
+        self.get_with_scope(super::API_V02_SCOPE, "tokens/listing_rejections")
+            .send()
+            .await
+    }
+
+    pub async fn nft_factories(
+        &self,
+        creator_id: AccountId,
+        pagination_query: &PaginationQuery<ApiEither<i64>>,
+    ) -> Result<Response> {
+        self.get_with_scope(
+            super::API_V02_SCOPE,
+            &format!("tokens/nft_factories/{}", creator_id),
+        )
+        .query(&pagination_query)
+        .send()
+        .await
+    }
+
+    pub async fn register_nft_factory(
+        &self,
+        request: &RegisterNFTFactoryRequest,
+    ) -> Result<Response> {
+        self.post_with_scope(super::API_V02_SCOPE, "tokens/nft_factories")
+            .body(request)
+            .send()
+            .await
+    }
 }
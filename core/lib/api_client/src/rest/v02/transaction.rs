@@ -1,6 +1,9 @@
 use crate::rest::client::{Client, Result};
 use zksync_api_types::{
-    v02::{transaction::IncomingTxBatch, Response},
+    v02::{
+        transaction::{CancelTx, IncomingTxBatch, SignedData},
+        Response,
+    },
     TxWithSignature,
 };
 use zksync_types::tx::{EthBatchSignatures, TxEthSignatureVariant, TxHash, ZkSyncTx};
@@ -28,6 +31,20 @@ impl Client {
             .await
     }
 
+    pub async fn verify_signature(&self, data: SignedData) -> Result<Response> {
+        self.post_with_scope(super::API_V02_SCOPE, "transactions/verifySignature")
+            .body(&data)
+            .send()
+            .await
+    }
+
+    pub async fn cancel_tx(&self, cancel_tx: CancelTx) -> Result<Response> {
+        self.post_with_scope(super::API_V02_SCOPE, "transactions/cancel")
+            .body(&cancel_tx)
+            .send()
+            .await
+    }
+
     pub async fn tx_status(&self, tx_hash: TxHash) -> Result<Response> {
         self.get_with_scope(
             super::API_V02_SCOPE,
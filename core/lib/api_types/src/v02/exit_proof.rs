@@ -0,0 +1,107 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+use zksync_crypto::proof::EncodedSingleProof;
+use zksync_types::{block::Block, AccountId, Address, BlockNumber, TokenId, H256};
+use zksync_utils::BigUintSerdeWrapper;
+
+/// Block info stored in the zkSync contract, required to perform the exit in the exodus mode.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredBlockInfo {
+    pub block_number: BlockNumber,
+    pub priority_operations: u64,
+    pub pending_onchain_operations_hash: H256,
+    pub timestamp: u64,
+    pub state_hash: H256,
+    pub commitment: H256,
+}
+
+impl StoredBlockInfo {
+    pub fn from_block(block: &Block) -> Self {
+        Self {
+            block_number: block.block_number,
+            priority_operations: block.number_of_processed_prior_ops(),
+            pending_onchain_operations_hash: block.get_onchain_operations_block_info().1,
+            timestamp: block.timestamp,
+            state_hash: block.get_eth_encoded_root(),
+            commitment: block.block_commitment,
+        }
+    }
+}
+
+/// Input data of the exit transaction on the zkSync contract.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExitProofData {
+    pub stored_block_info: StoredBlockInfo,
+    pub owner: Address,
+    pub account_id: AccountId,
+    pub token_id: TokenId,
+    pub amount: BigUintSerdeWrapper,
+    pub nft_creator_id: AccountId,
+    pub nft_creator_address: Address,
+    pub nft_serial_id: u32,
+    pub nft_content_hash: H256,
+    pub proof: EncodedSingleProof,
+    pub token_address: Address,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ExitProofJobStatus {
+    Queued,
+    InProgress,
+    Done,
+    Failed,
+}
+
+impl ExitProofJobStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Queued => "queued",
+            Self::InProgress => "inProgress",
+            Self::Done => "done",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+impl fmt::Display for ExitProofJobStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ExitProofJobStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "queued" => Ok(Self::Queued),
+            "inProgress" => Ok(Self::InProgress),
+            "done" => Ok(Self::Done),
+            "failed" => Ok(Self::Failed),
+            other => Err(format!("Unknown exit proof job status: {}", other)),
+        }
+    }
+}
+
+/// Job generating the exit proof for the account and token against the state of the last verified block.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiExitProofJob {
+    pub id: u64,
+    pub account_id: AccountId,
+    pub token_id: TokenId,
+    pub block_number: BlockNumber,
+    pub status: ExitProofJobStatus,
+    /// Position of the job in the queue, set only for the queued jobs.
+    pub queue_position: Option<u64>,
+    /// Present once the job is done.
+    pub proof: Option<ExitProofData>,
+    /// Reason of the failure, present if the job is failed.
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
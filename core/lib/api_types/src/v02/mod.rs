@@ -6,6 +6,7 @@ use zksync_types::network::Network;
 
 pub mod account;
 pub mod block;
+pub mod exit_proof;
 pub mod fee;
 pub mod pagination;
 pub mod status;
@@ -139,3 +139,16 @@ pub struct AccountTxsRequest {
     pub token: Option<TokenId>,
     pub second_address: Option<Address>,
 }
+
+#[derive(Debug, Serialize)]
+pub struct NFTFactoriesRequest {
+    pub creator_id: AccountId,
+    pub registration_id: ApiEither<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WithdrawalsRequest {
+    /// L1 address the withdrawals are sent to.
+    pub address: Address,
+    pub tx_hash: ApiEither<TxHash>,
+}
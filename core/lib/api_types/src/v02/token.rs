@@ -1,6 +1,7 @@
 use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use zksync_types::{AccountId, Address, Token, TokenId, H256};
+use zksync_types::{tx::PackedEthSignature, AccountId, Address, Token, TokenId, H256};
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
@@ -22,6 +23,17 @@ pub struct TokenPrice {
     pub price: BigDecimal,
 }
 
+/// Token added to the contract that failed the listing validation.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenListingRejection {
+    pub token_id: TokenId,
+    pub address: Address,
+    pub reason: String,
+    pub eth_tx_hash: Option<H256>,
+    pub created_at: DateTime<Utc>,
+}
+
 impl ApiToken {
     pub fn from_token_and_eligibility(token: Token, eligibility: bool) -> Self {
         ApiToken {
@@ -73,3 +85,69 @@ pub struct ApiNFT {
     pub current_factory: Address,
     pub withdrawn_factory: Option<Address>,
 }
+
+/// Factory registration submitted by the NFT creator.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisterNFTFactoryRequest {
+    pub creator_id: AccountId,
+    pub creator_address: Address,
+    pub factory_address: Address,
+    /// Creator's signature of the registration message, the contract accepts the same one.
+    pub signature: PackedEthSignature,
+}
+
+/// Factory registered by the NFT creator, along with the data to verify its authenticity.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct NFTFactoryRegistration {
+    pub id: i64,
+    pub creator_id: AccountId,
+    pub creator_address: Address,
+    pub factory_address: Address,
+    /// Registration message signed by the creator.
+    pub message: String,
+    pub signature: PackedEthSignature,
+    /// Whether the factory is registered on the contract as well, so it's used for the withdrawals.
+    pub registered_on_chain: bool,
+    pub created_at: DateTime<Utc>,
+    pub deprecated_at: Option<DateTime<Utc>>,
+}
+
+/// Metadata of the token, collected from the token contract and the curated token list.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiTokenMetadata {
+    pub token_id: TokenId,
+    pub name: Option<String>,
+    /// Decimals known to the zkSync network.
+    pub decimals: u8,
+    /// Decimals reported by the token contract.
+    pub onchain_decimals: Option<u8>,
+    /// Whether the decimals known to the network match the ones of the token contract.
+    pub decimals_verified: bool,
+    pub logo_url: Option<String>,
+    pub description: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Amount of the accounts holding the token in the finalized state and the distribution of their balances.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiTokenHolders {
+    pub token_id: TokenId,
+    /// Amount of the accounts with the non-zero balance of the token.
+    pub holders: u64,
+    /// Balance buckets ordered by the min balance, the buckets without holders are omitted.
+    pub distribution: Vec<TokenHoldersBucket>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Holders with the balance not less than `min_balance` and less than the min balance of the next bucket.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenHoldersBucket {
+    /// Min balance of the bucket in the token units (i.e. taking the token decimals into account).
+    pub min_balance: BigDecimal,
+    pub holders: u64,
+}
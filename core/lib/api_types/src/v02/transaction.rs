@@ -6,9 +6,9 @@ use serde::{Deserialize, Serialize};
 use zksync_types::{
     tx::{
         ChangePubKey, Close, EthBatchSignatures, ForcedExit, MintNFT, Swap, Transfer,
-        TxEthSignature, TxHash, Withdraw, WithdrawNFT,
+        TxEthSignature, TxHash, TxSignature, Withdraw, WithdrawNFT,
     },
-    AccountId, Address, BlockNumber, EthBlockId, PubKeyHash, SerialId, TokenId, ZkSyncOp,
+    AccountId, Address, BlockNumber, EthBlockId, Nonce, PubKeyHash, SerialId, TokenId, ZkSyncOp,
     ZkSyncPriorityOp, H256,
 };
 use zksync_utils::{BigUintSerdeAsRadix10Str, ZeroPrefixHexSerde};
@@ -296,3 +296,100 @@ impl Toggle2FA {
 pub struct Toggle2FAResponse {
     pub success: bool,
 }
+
+/// Request of the account owner to remove their transaction from the mempool before it's executed.
+/// The request must be signed by the Ethereum key of the account.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelTx {
+    pub tx_hash: TxHash,
+    pub account_id: AccountId,
+    pub nonce: Nonce,
+    #[serde(with = "ts_milliseconds")]
+    pub timestamp: DateTime<Utc>,
+    pub signature: TxEthSignature,
+}
+
+impl CancelTx {
+    pub fn get_ethereum_sign_message(&self) -> String {
+        format!(
+            "Cancel the zkSync transaction {}.\n\
+            Nonce: {}\n\
+            Timestamp: {}",
+            self.tx_hash.to_string(),
+            *self.nonce,
+            self.timestamp.timestamp_millis()
+        )
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CancelTxResponse {
+    pub success: bool,
+}
+
+/// Data the signatures are verified for, without submitting it to the server.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum SignedData {
+    /// Transaction along with its Ethereum signature, same as the submitted one.
+    Tx(TxWithSignature),
+    /// Arbitrary message signed by the account.
+    Message(SignedMessage),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedMessage {
+    /// Account the message is expected to be signed by.
+    pub account: Address,
+    #[serde(with = "ZeroPrefixHexSerde")]
+    pub message: Vec<u8>,
+    /// zkSync signature of the message made with the signing key of the account.
+    pub signature: Option<TxSignature>,
+    /// Ethereum signature of the message made by the account owner.
+    pub eth_signature: Option<TxEthSignature>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SignatureCheck {
+    pub valid: bool,
+    /// Reason the signature is invalid.
+    pub error: Option<String>,
+}
+
+impl SignatureCheck {
+    pub fn valid() -> Self {
+        Self {
+            valid: true,
+            error: None,
+        }
+    }
+
+    pub fn invalid(error: impl ToString) -> Self {
+        Self {
+            valid: false,
+            error: Some(error.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SignatureVerification {
+    /// Account the data is expected to be signed by.
+    pub account: Address,
+    /// Signing key of the account, `None` if the account doesn't exist or its signing key is not set.
+    pub account_pub_key_hash: Option<PubKeyHash>,
+    /// Key the zkSync signature was made with, `None` if the signature is incorrect.
+    pub signer_pub_key_hash: Option<PubKeyHash>,
+    /// Result of the zkSync signature check, `None` if the signature is not provided.
+    pub signature: Option<SignatureCheck>,
+    /// `true` if the transaction is rejected without the Ethereum signature, i.e. 2FA is enabled
+    /// for the account. Always `false` for the messages.
+    pub eth_signature_required: bool,
+    /// Result of the Ethereum signature check, `None` if the signature is not provided.
+    /// For `ChangePubKey` it's the check of the Ethereum authorization of the new key.
+    pub eth_signature: Option<SignatureCheck>,
+}
@@ -30,7 +30,7 @@ use crate::{
     element::CircuitElement,
     operation::{OperationBranch, OperationBranchWitness},
     utils::boolean_or,
-    witness::utils::{apply_leaf_operation, get_audits},
+    witness::utils::{get_audits, get_leaf_witness},
 };
 
 #[derive(Clone)]
@@ -237,7 +237,7 @@ impl<'a, E: RescueEngine> Circuit<E> for ZkSyncExitCircuit<'a, E> {
 }
 
 pub fn create_exit_circuit_with_public_input(
-    account_tree: &mut CircuitAccountTree,
+    account_tree: &CircuitAccountTree,
     account_id: AccountId,
     token_id: TokenId,
     nft_creator_id: AccountId,
@@ -249,27 +249,16 @@ pub fn create_exit_circuit_with_public_input(
     let token_id_fe = Fr::from_str(&token_id.to_string()).unwrap();
     let serial_id_fe = Fr::from_str(&nft_serial_id.to_string()).unwrap();
     let root_hash = account_tree.root_hash();
-    let (account_witness, _, balance, _) =
-        apply_leaf_operation(account_tree, *account_id, *token_id as u32, |_| {}, |_| {});
+    let (account_witness, balance) = get_leaf_witness(account_tree, *account_id, *token_id as u32);
     let (audit_path, audit_balance_path) = get_audits(account_tree, *account_id, *token_id as u32);
 
-    let (special_account_witness, _, special_account_balance, _) = apply_leaf_operation(
-        account_tree,
-        NFT_STORAGE_ACCOUNT_ID.0,
-        *token_id as u32,
-        |_| {},
-        |_| {},
-    );
+    let (special_account_witness, special_account_balance) =
+        get_leaf_witness(account_tree, NFT_STORAGE_ACCOUNT_ID.0, *token_id as u32);
     let (special_account_audit_path, special_account_audit_balance_path) =
         get_audits(account_tree, NFT_STORAGE_ACCOUNT_ID.0, *token_id as u32);
 
-    let (creator_account_witness, _, creator_account_balance, _) = apply_leaf_operation(
-        account_tree,
-        *nft_creator_id,
-        *token_id as u32,
-        |_| {},
-        |_| {},
-    );
+    let (creator_account_witness, creator_account_balance) =
+        get_leaf_witness(account_tree, *nft_creator_id, *token_id as u32);
     let (creator_account_audit_path, creator_account_audit_balance_path) =
         get_audits(account_tree, *nft_creator_id, *token_id as u32);
 
@@ -297,10 +286,10 @@ pub fn create_exit_circuit_with_public_input(
         &creator_account_address_fe,
         ACCOUNT_ID_BIT_WIDTH,
     );
+    // The creator is not set for the fungible tokens, the empty account is used instead.
     let creator_address = account_tree
         .get(*nft_creator_id)
-        .expect("nft creator id account should be in the tree")
-        .address;
+        .map_or_else(Fr::zero, |account| account.address);
     append_be_fixed_width(&mut pubdata_commitment, &creator_address, ADDRESS_WIDTH);
     append_be_fixed_width(&mut pubdata_commitment, &serial_id_fe, SERIAL_ID_WIDTH);
     let content_hash_as_vec: Vec<Option<Fr>> = nft_content_hash
@@ -405,7 +394,7 @@ mod test {
         circuit_account_tree.insert(*test_account_id, CircuitAccount::from(test_account));
 
         let zksync_exit_circuit = create_exit_circuit_with_public_input(
-            &mut circuit_account_tree,
+            &circuit_account_tree,
             test_account_id,
             token_id,
             Default::default(),
@@ -479,7 +468,7 @@ mod test {
         );
 
         let zksync_exit_circuit = create_exit_circuit_with_public_input(
-            &mut circuit_account_tree,
+            &circuit_account_tree,
             test_account_id,
             token_id,
             test_account_id,
@@ -432,6 +432,23 @@ pub fn get_audits(
     (audit_account, audit_balance)
 }
 
+/// Returns the witness of the account and its balance of the token without changing the tree.
+/// The missing account is represented by the empty one.
+pub fn get_leaf_witness(
+    tree: &CircuitAccountTree,
+    account_address: u32,
+    token: u32,
+) -> (AccountWitness<Bn256>, Fr) {
+    let default_account = CircuitAccount::default();
+    let account = tree.get(account_address).unwrap_or(&default_account);
+    let balance = account
+        .subtree
+        .get(token)
+        .map_or_else(Fr::zero, |balance| balance.value);
+
+    (AccountWitness::from_circuit_account(account), balance)
+}
+
 pub fn apply_leaf_operation<Fa: Fn(&mut CircuitAccount<Bn256>), Fb: Fn(&mut Balance<Bn256>)>(
     tree: &mut CircuitAccountTree,
     account_address: u32,
@@ -0,0 +1,97 @@
+// Built-in uses
+use std::time::Duration;
+// External uses
+use serde::Deserialize;
+// Local uses
+use crate::envy_load;
+
+/// Configuration of the alerter notifying the operators about the anomalies of the server.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct AlertingConfig {
+    /// How often the conditions are checked.
+    /// Value in seconds.
+    pub check_interval: u64,
+    /// How often the alert is repeated while the condition persists.
+    /// Value in seconds.
+    pub repeat_interval: u64,
+    /// Max amount of the committed blocks waiting for the proofs.
+    pub max_proof_lag_blocks: u32,
+    /// Min amount of the Ethereum blocks left until the deadline of the oldest priority operation.
+    pub min_priority_op_deadline_blocks: u64,
+    /// Max age of the Ethereum transaction that isn't confirmed yet.
+    /// Value in seconds.
+    pub max_eth_tx_age: u64,
+    /// Max time since the last update of the token prices.
+    /// Value in seconds.
+    pub max_price_age: u64,
+    /// Incoming webhook of the Slack channel the alerts are sent to.
+    pub slack_webhook_url: Option<String>,
+    /// Token of the Telegram bot sending the alerts.
+    pub telegram_bot_token: Option<String>,
+    /// Id of the Telegram chat the alerts are sent to.
+    pub telegram_chat_id: Option<String>,
+}
+
+impl AlertingConfig {
+    pub fn from_env() -> Self {
+        envy_load!("alerting", "ALERTING_")
+    }
+
+    /// Converts `self.check_interval` into `Duration`.
+    pub fn check_interval(&self) -> Duration {
+        Duration::from_secs(self.check_interval)
+    }
+
+    /// Converts `self.repeat_interval` into `Duration`.
+    pub fn repeat_interval(&self) -> Duration {
+        Duration::from_secs(self.repeat_interval)
+    }
+
+    /// Converts `self.max_eth_tx_age` into `Duration`.
+    pub fn max_eth_tx_age(&self) -> Duration {
+        Duration::from_secs(self.max_eth_tx_age)
+    }
+
+    /// Converts `self.max_price_age` into `Duration`.
+    pub fn max_price_age(&self) -> Duration {
+        Duration::from_secs(self.max_price_age)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configs::test_utils::set_env;
+
+    fn expected_config() -> AlertingConfig {
+        AlertingConfig {
+            check_interval: 60,
+            repeat_interval: 3600,
+            max_proof_lag_blocks: 50,
+            min_priority_op_deadline_blocks: 1000,
+            max_eth_tx_age: 1800,
+            max_price_age: 600,
+            slack_webhook_url: Some("https://hooks.slack.com/services/T0/B0/X".into()),
+            telegram_bot_token: None,
+            telegram_chat_id: None,
+        }
+    }
+
+    #[test]
+    fn from_env() {
+        let config = r#"
+ALERTING_CHECK_INTERVAL="60"
+ALERTING_REPEAT_INTERVAL="3600"
+ALERTING_MAX_PROOF_LAG_BLOCKS="50"
+ALERTING_MIN_PRIORITY_OP_DEADLINE_BLOCKS="1000"
+ALERTING_MAX_ETH_TX_AGE="1800"
+ALERTING_MAX_PRICE_AGE="600"
+ALERTING_SLACK_WEBHOOK_URL="https://hooks.slack.com/services/T0/B0/X"
+        "#;
+        set_env(config);
+
+        let actual = AlertingConfig::from_env();
+        assert_eq!(actual, expected_config());
+        assert_eq!(actual.check_interval(), Duration::from_secs(60));
+    }
+}
@@ -29,7 +29,13 @@ pub struct ApiConfig {
     pub prover: ProverApiConfig,
     /// Configuration options for the Prometheus exporter.
     pub prometheus: PrometheusConfig,
+    /// Configuration options for the log filter server of the components without the private API.
+    pub log_filter: LogFilterApiConfig,
     pub token_config: TokenConfig,
+    /// CORS policy of the public HTTP APIs.
+    pub cors: CorsConfig,
+    /// CORS policy of the admin API, i.e. the public one with the admin overrides applied.
+    pub admin_cors: CorsConfig,
 }
 
 impl ApiConfig {
@@ -43,7 +49,10 @@ impl ApiConfig {
             private: envy_load!("private", "API_PRIVATE_"),
             prover: envy_load!("prover", "API_PROVER_"),
             prometheus: envy_load!("prometheus", "API_PROMETHEUS_"),
+            log_filter: envy_load!("log_filter", "API_LOG_FILTER_"),
             token_config: envy_load!("token", "API_TOKEN_"),
+            cors: CorsConfig::from_env(),
+            admin_cors: CorsConfig::admin_from_env(),
         }
     }
 }
@@ -56,6 +65,28 @@ impl CommonApiConfig {
     pub fn from_env() -> Self {
         envy_load!("common", "API_COMMON_")
     }
+
+    /// Loads the config without panicking, used to reload it while the server is running.
+    pub fn try_from_env() -> Result<Self, envy::Error> {
+        envy::prefixed("API_COMMON_").from_env()
+    }
+
+    pub fn screening_timeout(&self) -> Duration {
+        Duration::from_millis(self.screening_timeout_ms)
+    }
+
+    pub fn shutdown_timeout(&self) -> Duration {
+        Duration::from_secs(self.shutdown_timeout_sec)
+    }
+
+    /// Returns `None` if the staleness of the account state isn't checked.
+    pub fn max_block_age(&self) -> Option<Duration> {
+        Some(Duration::from_secs(self.max_block_age_sec)).filter(|age| !age.is_zero())
+    }
+
+    pub fn stale_state_retry_after(&self) -> Duration {
+        Duration::from_secs(self.stale_state_retry_after_sec)
+    }
 }
 
 impl AdminApiConfig {
@@ -99,6 +130,16 @@ impl PrometheusConfig {
     }
 }
 
+impl LogFilterApiConfig {
+    pub fn from_env() -> Self {
+        envy_load!("log_filter", "API_LOG_FILTER_")
+    }
+
+    pub fn bind_addr(&self) -> SocketAddr {
+        SocketAddr::new("0.0.0.0".parse().unwrap(), self.port)
+    }
+}
+
 // Common configuration options for the API
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct CommonApiConfig {
@@ -122,6 +163,27 @@ pub struct CommonApiConfig {
 
     /// The name of current subsidy. It is needed to conveniently fetch historical data regarding subsidies for different partners
     pub subsidy_name: String,
+
+    /// Minimal amount of concurrently running signature checks.
+    pub sign_checker_min_workers: usize,
+    /// Maximum amount of concurrently running signature checks, reached when the requests queue grows.
+    pub sign_checker_max_workers: usize,
+
+    /// Path to the file with addresses not allowed to send or receive transactions.
+    pub screening_denylist_path: Option<String>,
+    /// URL of the external service used to screen the transactions senders and recipients.
+    pub screening_service_url: Option<String>,
+    /// Time given to a single screening check, the transaction is rejected if the check takes longer.
+    pub screening_timeout_ms: u64,
+
+    /// Time given to the API servers to finish the requests in progress on shutdown.
+    pub shutdown_timeout_sec: u64,
+
+    /// Maximum age of the last block while there are transactions waiting in the mempool, after which
+    /// the account state served by the API is considered stale. Zero value disables the check.
+    pub max_block_age_sec: u64,
+    /// Value of the `Retry-After` header of the requests rejected while the account state is stale.
+    pub stale_state_retry_after_sec: u64,
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -210,6 +272,8 @@ pub struct JsonRpcConfig {
     pub ws_port: u16,
     /// URL to access WebSocket RPC server.
     pub ws_url: String,
+    /// Maximum number of calls allowed in a single JSON-RPC batch request.
+    pub max_batch_request_size: usize,
 }
 
 impl JsonRpcConfig {
@@ -239,12 +303,103 @@ impl Web3Config {
     }
 }
 
+/// CORS policy of an HTTP API server.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct CorsConfig {
+    /// Origins allowed to make the cross-origin requests, `*` allows any origin.
+    /// Empty list forbids the cross-origin requests.
+    pub allowed_origins: Vec<String>,
+    /// Headers allowed in the cross-origin requests, `*` allows any header.
+    pub allowed_headers: Vec<String>,
+    /// Time (in seconds) for which the browsers may cache the preflight responses.
+    pub max_age_sec: u64,
+    /// Whether the cross-origin requests may include the credentials (cookies, authorization headers).
+    pub allow_credentials: bool,
+}
+
+/// Overrides of the CORS policy for a particular API scope, unset values are taken from the base policy.
+#[derive(Debug, Deserialize, Clone, PartialEq, Default)]
+pub struct CorsOverrides {
+    pub allowed_origins: Option<Vec<String>>,
+    pub allowed_headers: Option<Vec<String>>,
+    pub max_age_sec: Option<u64>,
+    pub allow_credentials: Option<bool>,
+}
+
+impl CorsConfig {
+    /// Policy of the public HTTP APIs.
+    pub fn from_env() -> Self {
+        envy_load!("cors", "API_CORS_")
+    }
+
+    /// Policy of the admin API.
+    pub fn admin_from_env() -> Self {
+        Self::from_env().with_overrides(CorsOverrides::from_env("API_ADMIN_CORS_"))
+    }
+
+    /// Policy allowing any cross-origin request, used by the development servers.
+    pub fn permissive() -> Self {
+        Self {
+            allowed_origins: vec!["*".into()],
+            allowed_headers: vec!["*".into()],
+            max_age_sec: 3600,
+            allow_credentials: false,
+        }
+    }
+
+    pub fn with_overrides(self, overrides: CorsOverrides) -> Self {
+        Self {
+            allowed_origins: overrides.allowed_origins.unwrap_or(self.allowed_origins),
+            allowed_headers: overrides.allowed_headers.unwrap_or(self.allowed_headers),
+            max_age_sec: overrides.max_age_sec.unwrap_or(self.max_age_sec),
+            allow_credentials: overrides
+                .allow_credentials
+                .unwrap_or(self.allow_credentials),
+        }
+    }
+
+    pub fn allows_any_origin(&self) -> bool {
+        self.allowed_origins.iter().any(|origin| origin == "*")
+    }
+
+    pub fn allows_any_header(&self) -> bool {
+        self.allowed_headers.iter().any(|header| header == "*")
+    }
+
+    /// Returns the problems of the policy, `name` is the config section it's loaded from.
+    pub fn validate(&self, name: &str) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.allow_credentials && self.allows_any_origin() {
+            problems.push(format!(
+                "{}: credentials must not be allowed for any origin, list the allowed origins explicitly",
+                name
+            ));
+        }
+        problems
+    }
+}
+
+impl CorsOverrides {
+    /// Loads the overrides set by the variables with the `prefix`, e.g. `API_ADMIN_CORS_`.
+    pub fn from_env(prefix: &str) -> Self {
+        envy_load!("cors overrides", prefix)
+    }
+}
+
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct PrometheusConfig {
     /// Port to which the Prometheus exporter server is listening.
     pub port: u16,
 }
 
+/// Internal server changing the log filter of the running component, see `zksync_http_utils::log_filter`.
+/// The components serving the private core API or the prover API expose the log filter there instead.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct LogFilterApiConfig {
+    /// Port to which the log filter server is listening.
+    pub port: u16,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -263,6 +418,14 @@ mod tests {
                 subsidized_ips: vec!["127.0.0.1".to_owned()],
                 max_subsidy_usd_scaled: 20000,
                 subsidy_name: String::from("PartnerName"),
+                sign_checker_min_workers: 4,
+                sign_checker_max_workers: 64,
+                screening_denylist_path: Some("etc/denylist.txt".into()),
+                screening_service_url: None,
+                screening_timeout_ms: 2000,
+                shutdown_timeout_sec: 30,
+                max_block_age_sec: 300,
+                stale_state_retry_after_sec: 30,
             },
             admin: AdminApiConfig {
                 port: 8080,
@@ -278,6 +441,7 @@ mod tests {
                 http_url: "http://127.0.0.1:3030".into(),
                 ws_port: 3031,
                 ws_url: "ws://127.0.0.1:3031".into(),
+                max_batch_request_size: 100,
             },
             web3: Web3Config {
                 port: 3002,
@@ -295,9 +459,22 @@ mod tests {
                 secret_auth: "sample".into(),
             },
             prometheus: PrometheusConfig { port: 3312 },
+            log_filter: LogFilterApiConfig { port: 8091 },
             token_config: TokenConfig {
                 invalidate_token_cache_period_sec: 10,
             },
+            cors: CorsConfig {
+                allowed_origins: vec!["https://wallet.zksync.io".into()],
+                allowed_headers: vec!["*".into()],
+                max_age_sec: 3600,
+                allow_credentials: false,
+            },
+            admin_cors: CorsConfig {
+                allowed_origins: vec!["https://admin.zksync.io".into()],
+                allowed_headers: vec!["authorization".into(), "content-type".into()],
+                max_age_sec: 3600,
+                allow_credentials: true,
+            },
         }
     }
 
@@ -313,6 +490,13 @@ API_COMMON_MAX_SUBSIDY_USD_SCALED=20000
 API_COMMON_SUBSIDY_NAME=PartnerName
 API_COMMON_MAX_NUMBER_OF_TRANSACTIONS_PER_BATCH=200
 API_COMMON_MAX_NUMBER_OF_AUTHORS_PER_BATCH=10
+API_COMMON_SIGN_CHECKER_MIN_WORKERS=4
+API_COMMON_SIGN_CHECKER_MAX_WORKERS=64
+API_COMMON_SCREENING_DENYLIST_PATH="etc/denylist.txt"
+API_COMMON_SCREENING_TIMEOUT_MS="2000"
+API_COMMON_SHUTDOWN_TIMEOUT_SEC="30"
+API_COMMON_MAX_BLOCK_AGE_SEC="300"
+API_COMMON_STALE_STATE_RETRY_AFTER_SEC="30"
 API_TOKEN_INVALIDATE_TOKEN_CACHE_PERIOD_SEC="10"
 API_ADMIN_PORT="8080"
 API_ADMIN_URL="http://127.0.0.1:8080"
@@ -323,6 +507,7 @@ API_JSON_RPC_HTTP_PORT="3030"
 API_JSON_RPC_HTTP_URL="http://127.0.0.1:3030"
 API_JSON_RPC_WS_PORT="3031"
 API_JSON_RPC_WS_URL="ws://127.0.0.1:3031"
+API_JSON_RPC_MAX_BATCH_REQUEST_SIZE="100"
 API_WEB3_PORT="3002"
 API_WEB3_URL="http://127.0.0.1:3002"
 API_WEB3_CHAIN_ID="240"
@@ -333,6 +518,14 @@ API_PROVER_PORT="8088"
 API_PROVER_URL="http://127.0.0.1:8088"
 API_PROVER_SECRET_AUTH="sample"
 API_PROMETHEUS_PORT="3312"
+API_LOG_FILTER_PORT="8091"
+API_CORS_ALLOWED_ORIGINS="https://wallet.zksync.io"
+API_CORS_ALLOWED_HEADERS="*"
+API_CORS_MAX_AGE_SEC="3600"
+API_CORS_ALLOW_CREDENTIALS="false"
+API_ADMIN_CORS_ALLOWED_ORIGINS="https://admin.zksync.io"
+API_ADMIN_CORS_ALLOWED_HEADERS="authorization,content-type"
+API_ADMIN_CORS_ALLOW_CREDENTIALS="true"
         "#;
         set_env(config);
 
@@ -371,4 +564,22 @@ API_PROMETHEUS_PORT="3312"
             SocketAddr::new(bind_broadcast_addr, config.web3.port)
         );
     }
+
+    #[test]
+    fn cors_validation() {
+        let config = CorsConfig::permissive();
+        assert!(config.validate("api.cors").is_empty());
+
+        let config = config.with_overrides(CorsOverrides {
+            allow_credentials: Some(true),
+            ..CorsOverrides::default()
+        });
+        assert_eq!(config.validate("api.cors").len(), 1);
+
+        let config = config.with_overrides(CorsOverrides {
+            allowed_origins: Some(vec!["https://wallet.zksync.io".into()]),
+            ..CorsOverrides::default()
+        });
+        assert!(config.validate("api.cors").is_empty());
+    }
 }
@@ -0,0 +1,90 @@
+// Built-in uses
+use std::time::Duration;
+// External uses
+use serde::Deserialize;
+// Local uses
+use crate::envy_load;
+
+/// Configuration of the archiver moving the old rows to the object storage.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct ArchiverConfig {
+    /// Whether the archive is used. Once the rows are archived, the archive must stay enabled
+    /// for every component reading them back.
+    pub enabled: bool,
+    /// URL of the S3-compatible object storage.
+    pub object_store_url: String,
+    /// Region of the object storage.
+    pub object_store_region: String,
+    /// Bucket to store the archived rows in.
+    pub object_store_bucket: String,
+    /// Amount of the latest executed blocks whose rows are kept in the database.
+    pub retention_blocks: u32,
+    /// Amount of blocks whose rows are archived into a single object.
+    pub blocks_per_object: u32,
+    /// How often the archiver checks for the blocks to archive.
+    /// Value in seconds.
+    pub archive_interval: u64,
+}
+
+impl ArchiverConfig {
+    pub fn from_env() -> Self {
+        envy_load!("archiver", "ARCHIVER_")
+    }
+
+    /// Loads the config if the archive is enabled. The config is optional,
+    /// so `None` is returned if it's absent.
+    pub fn from_env_if_enabled() -> Option<Self> {
+        let enabled = std::env::var("ARCHIVER_ENABLED")
+            .ok()
+            .and_then(|enabled| enabled.parse().ok())
+            .unwrap_or(false);
+        enabled.then(Self::from_env)
+    }
+
+    /// Converts `self.archive_interval` into `Duration`.
+    pub fn archive_interval(&self) -> Duration {
+        Duration::from_secs(self.archive_interval)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configs::test_utils::set_env;
+
+    fn expected_config() -> ArchiverConfig {
+        ArchiverConfig {
+            enabled: true,
+            object_store_url: "http://127.0.0.1:9000".into(),
+            object_store_region: "us-east-1".into(),
+            object_store_bucket: "zksync-archive".into(),
+            retention_blocks: 100000,
+            blocks_per_object: 1000,
+            archive_interval: 3600,
+        }
+    }
+
+    #[test]
+    fn from_env() {
+        let config = r#"
+ARCHIVER_ENABLED="true"
+ARCHIVER_OBJECT_STORE_URL="http://127.0.0.1:9000"
+ARCHIVER_OBJECT_STORE_REGION="us-east-1"
+ARCHIVER_OBJECT_STORE_BUCKET="zksync-archive"
+ARCHIVER_RETENTION_BLOCKS="100000"
+ARCHIVER_BLOCKS_PER_OBJECT="1000"
+ARCHIVER_ARCHIVE_INTERVAL="3600"
+        "#;
+        set_env(config);
+
+        let actual = ArchiverConfig::from_env();
+        assert_eq!(actual, expected_config());
+        assert_eq!(
+            ArchiverConfig::from_env_if_enabled(),
+            Some(expected_config())
+        );
+
+        set_env(r#"ARCHIVER_ENABLED="false""#);
+        assert_eq!(ArchiverConfig::from_env_if_enabled(), None);
+    }
+}
@@ -1,5 +1,5 @@
 /// External uses
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 /// Built-in uses
 use std::time::Duration;
 // Local uses
@@ -26,6 +26,74 @@ impl ChainConfig {
             state_keeper: envy_load!("state_keeper", "CHAIN_STATE_KEEPER_"),
         }
     }
+    /// Checks the consistency of the circuit and state keeper parameters,
+    /// returns the descriptions of the found problems.
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        let circuit = &self.circuit;
+        let state_keeper = &self.state_keeper;
+
+        if circuit.supported_block_chunks_sizes.len()
+            != circuit.supported_block_chunks_sizes_setup_powers.len()
+        {
+            problems.push(
+                "Each supported block chunks size must have the corresponding setup power".into(),
+            );
+        }
+        if circuit.supported_aggregated_proof_sizes.len()
+            != circuit.supported_aggregated_proof_sizes_setup_power2.len()
+        {
+            problems.push(
+                "Each supported aggregated proof size must have the corresponding setup power"
+                    .into(),
+            );
+        }
+
+        if state_keeper.block_chunk_sizes.is_empty() {
+            problems.push("At least one block chunks size must be provided".into());
+        }
+        for size in &state_keeper.block_chunk_sizes {
+            if !circuit.supported_block_chunks_sizes.contains(size) {
+                problems.push(format!(
+                    "Block chunks size {} is not supported by the circuit, such blocks will never be proven",
+                    size
+                ));
+            }
+        }
+
+        if state_keeper.aggregated_proof_sizes.is_empty() {
+            problems.push("At least one aggregated proof size must be provided".into());
+        }
+        if state_keeper
+            .aggregated_proof_sizes
+            .windows(2)
+            .any(|pair| pair[0] >= pair[1])
+        {
+            problems
+                .push("Aggregated proof sizes must be sorted in strictly increasing order".into());
+        }
+        for size in &state_keeper.aggregated_proof_sizes {
+            if !circuit.supported_aggregated_proof_sizes.contains(size) {
+                problems.push(format!(
+                    "Aggregated proof size {} has no setup and verification keys",
+                    size
+                ));
+            }
+        }
+
+        if state_keeper.max_aggregated_blocks_to_commit == 0
+            || state_keeper.max_aggregated_blocks_to_execute == 0
+        {
+            problems.push("Maximum amounts of aggregated blocks must be positive".into());
+        }
+        if state_keeper.seal_chunks_utilization_percent > 100 {
+            problems
+                .push("Chunks utilization percent to seal the block must not exceed 100".into());
+        }
+
+        problems
+    }
+
     pub fn max_blocks_to_aggregate(&self) -> u32 {
         std::cmp::max(
             self.state_keeper.max_aggregated_blocks_to_commit,
@@ -94,6 +162,64 @@ pub struct StateKeeper {
     pub block_prove_deadline: u64,
     pub block_execute_deadline: u64,
     pub max_aggregated_tx_gas: usize,
+    /// Target L1 cost (in gwei) of the base part of the aggregated operation transaction per aggregated block.
+    /// The base cost is paid once per transaction, so the operations are created as soon as there are enough
+    /// blocks to keep the cost per block below the target: more blocks are aggregated when the gas is expensive,
+    /// and less when it's cheap, so the blocks are committed, proven and executed sooner. The deadlines and
+    /// the max amounts of the aggregated blocks are still applied. `0` disables the criterion, so the max amount
+    /// of blocks is always awaited.
+    pub aggregation_cost_target_gwei: u64,
+    /// Maximum amount of transactions in the mempool. When it's reached, transactions paying
+    /// the lowest fee per chunk are evicted in favor of the new ones.
+    pub mempool_capacity: usize,
+    /// Maximum age of the pending block in seconds before sealing it. `0` disables the criterion.
+    pub seal_block_max_age_secs: u64,
+    /// Percentage of the block chunks which should be used to seal the block.
+    pub seal_chunks_utilization_percent: u8,
+    /// Amount of withdrawals in the pending block to seal it. `0` disables the criterion.
+    pub seal_max_pending_withdrawals: usize,
+    /// Amount of the commit gas the pending block can use before sealing it. `0` disables the criterion.
+    pub seal_gas_budget: u64,
+    /// Amount of Ethereum blocks left before the deadline of a priority operation in the pending block
+    /// at which the block is sealed. `0` disables the criterion.
+    pub seal_priority_op_deadline_margin: u64,
+}
+
+/// Set of criteria used by the state keeper to decide when the pending block should be sealed.
+///
+/// Block is sealed as soon as at least one of the enabled criteria is met.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct BlockSealCriteria {
+    /// Maximum amount of miniblock iterations before sealing the block.
+    pub miniblock_iterations: usize,
+    /// Maximum amount of miniblock iterations in case of block containing a fast withdrawal request.
+    pub fast_miniblock_iterations: usize,
+    /// Maximum age of the pending block in seconds. `0` disables the criterion.
+    pub max_block_age_secs: u64,
+    /// Percentage of the block chunks which should be used to seal the block.
+    pub chunks_utilization_percent: u8,
+    /// Maximum amount of withdrawals in the pending block. `0` disables the criterion.
+    pub max_pending_withdrawals: usize,
+    /// Maximum commit gas the pending block can use. `0` disables the criterion.
+    pub gas_budget: u64,
+    /// Amount of Ethereum blocks before the deadline of a priority operation in the pending block
+    /// at which the block is sealed. `0` disables the criterion.
+    pub priority_op_deadline_margin: u64,
+}
+
+impl BlockSealCriteria {
+    /// Criteria that seal the block only when it's full or enough miniblock iterations passed.
+    pub fn with_iterations(miniblock_iterations: usize, fast_miniblock_iterations: usize) -> Self {
+        Self {
+            miniblock_iterations,
+            fast_miniblock_iterations,
+            max_block_age_secs: 0,
+            chunks_utilization_percent: 100,
+            max_pending_withdrawals: 0,
+            gas_budget: 0,
+            priority_op_deadline_margin: 0,
+        }
+    }
 }
 
 impl StateKeeper {
@@ -102,6 +228,19 @@ impl StateKeeper {
         Duration::from_millis(self.miniblock_iteration_interval)
     }
 
+    /// Collects the block sealing parameters into `BlockSealCriteria`.
+    pub fn seal_criteria(&self) -> BlockSealCriteria {
+        BlockSealCriteria {
+            miniblock_iterations: self.miniblock_iterations as usize,
+            fast_miniblock_iterations: self.fast_block_miniblock_iterations as usize,
+            max_block_age_secs: self.seal_block_max_age_secs,
+            chunks_utilization_percent: self.seal_chunks_utilization_percent,
+            max_pending_withdrawals: self.seal_max_pending_withdrawals,
+            gas_budget: self.seal_gas_budget,
+            priority_op_deadline_margin: self.seal_priority_op_deadline_margin,
+        }
+    }
+
     pub fn block_commit_deadline(&self) -> Duration {
         Duration::from_secs(self.block_commit_deadline)
     }
@@ -147,10 +286,31 @@ mod tests {
                 block_prove_deadline: 3_000,
                 block_execute_deadline: 4_000,
                 max_aggregated_tx_gas: 4_000_000,
+                aggregation_cost_target_gwei: 2_000_000,
+                mempool_capacity: 100_000,
+                seal_block_max_age_secs: 30,
+                seal_chunks_utilization_percent: 90,
+                seal_max_pending_withdrawals: 20,
+                seal_gas_budget: 3_000_000,
+                seal_priority_op_deadline_margin: 1_000,
             },
         }
     }
 
+    #[test]
+    fn validate() {
+        assert!(expected_config().validate().is_empty());
+
+        let mut config = expected_config();
+        config.state_keeper.block_chunk_sizes = vec![6, 32];
+        config.state_keeper.aggregated_proof_sizes = vec![5, 1, 7];
+        config
+            .circuit
+            .supported_aggregated_proof_sizes_setup_power2
+            .pop();
+        assert_eq!(config.validate().len(), 4);
+    }
+
     #[test]
     fn from_env() {
         let config = r#"
@@ -175,6 +335,13 @@ CHAIN_STATE_KEEPER_BLOCK_COMMIT_DEADLINE="300"
 CHAIN_STATE_KEEPER_BLOCK_PROVE_DEADLINE="3000"
 CHAIN_STATE_KEEPER_BLOCK_EXECUTE_DEADLINE="4000"
 CHAIN_STATE_KEEPER_MAX_AGGREGATED_TX_GAS="4000000"
+CHAIN_STATE_KEEPER_AGGREGATION_COST_TARGET_GWEI="2000000"
+CHAIN_STATE_KEEPER_MEMPOOL_CAPACITY="100000"
+CHAIN_STATE_KEEPER_SEAL_BLOCK_MAX_AGE_SECS="30"
+CHAIN_STATE_KEEPER_SEAL_CHUNKS_UTILIZATION_PERCENT="90"
+CHAIN_STATE_KEEPER_SEAL_MAX_PENDING_WITHDRAWALS="20"
+CHAIN_STATE_KEEPER_SEAL_GAS_BUDGET="3000000"
+CHAIN_STATE_KEEPER_SEAL_PRIORITY_OP_DEADLINE_MARGIN="1000"
         "#;
         set_env(config);
 
@@ -191,5 +358,17 @@ CHAIN_STATE_KEEPER_MAX_AGGREGATED_TX_GAS="4000000"
             config.state_keeper.miniblock_iteration_interval(),
             Duration::from_millis(config.state_keeper.miniblock_iteration_interval)
         );
+        assert_eq!(
+            config.state_keeper.seal_criteria(),
+            BlockSealCriteria {
+                miniblock_iterations: 10,
+                fast_miniblock_iterations: 5,
+                max_block_age_secs: 30,
+                chunks_utilization_percent: 90,
+                max_pending_withdrawals: 20,
+                gas_budget: 3_000_000,
+                priority_op_deadline_margin: 1_000,
+            }
+        );
     }
 }
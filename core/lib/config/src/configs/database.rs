@@ -18,6 +18,18 @@ pub struct DBConfig {
     pub rejected_transactions_max_age: u64,
     /// Sleep time (in hours) of the actor responsible for deleting failed transactions from the database.
     pub rejected_transactions_cleaner_interval: u64,
+    /// Max lag (in seconds) of the database replica after which the API read queries are routed to the primary.
+    pub replica_max_lag: u64,
+    /// Queries taking longer than this amount of milliseconds are logged, 0 disables the log.
+    pub slow_query_threshold: u64,
+    /// Amount of the latest executed blocks which account diffs and witnesses are kept by the state pruner.
+    pub pruning_retention_blocks: u32,
+    /// State pruner stores the full state snapshot once per this amount of executed blocks.
+    pub state_snapshot_interval: u32,
+    /// Sleep time (in seconds) of the state pruner.
+    pub state_pruner_interval: u64,
+    /// API requests are rejected while this amount of requests already wait for a database connection, 0 disables the limit.
+    pub max_pending_checkouts: usize,
 }
 
 impl DBConfig {
@@ -27,6 +39,15 @@ impl DBConfig {
         envy_load!("database", "DATABASE_")
     }
 
+    /// Checks the consistency of the configuration, returns the descriptions of the found problems.
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.pool_size == 0 {
+            problems.push("Database connection pool size must be positive".into());
+        }
+        problems
+    }
+
     pub fn rejected_transactions_max_age(&self) -> time::Duration {
         time::Duration::from_secs(self.rejected_transactions_max_age * Self::SECS_PER_HOUR)
     }
@@ -34,6 +55,22 @@ impl DBConfig {
     pub fn rejected_transactions_cleaner_interval(&self) -> time::Duration {
         time::Duration::from_secs(self.rejected_transactions_cleaner_interval)
     }
+
+    pub fn replica_max_lag(&self) -> time::Duration {
+        time::Duration::from_secs(self.replica_max_lag)
+    }
+
+    /// Threshold of the slow queries log, `None` if the log is disabled.
+    pub fn slow_query_threshold(&self) -> Option<time::Duration> {
+        match self.slow_query_threshold {
+            0 => None,
+            threshold => Some(time::Duration::from_millis(threshold)),
+        }
+    }
+
+    pub fn state_pruner_interval(&self) -> time::Duration {
+        time::Duration::from_secs(self.state_pruner_interval)
+    }
 }
 
 #[cfg(test)]
@@ -47,6 +84,12 @@ mod tests {
             url: "postgres://postgres@localhost/plasma".into(),
             rejected_transactions_max_age: 336,
             rejected_transactions_cleaner_interval: 24,
+            replica_max_lag: 10,
+            slow_query_threshold: 1000,
+            pruning_retention_blocks: 10000,
+            state_snapshot_interval: 1000,
+            state_pruner_interval: 600,
+            max_pending_checkouts: 100,
         }
     }
 
@@ -57,10 +100,27 @@ DATABASE_POOL_SIZE="10"
 DATABASE_URL="postgres://postgres@localhost/plasma"
 DATABASE_REJECTED_TRANSACTIONS_MAX_AGE="336"
 DATABASE_REJECTED_TRANSACTIONS_CLEANER_INTERVAL="24"
+DATABASE_REPLICA_MAX_LAG="10"
+DATABASE_SLOW_QUERY_THRESHOLD="1000"
+DATABASE_PRUNING_RETENTION_BLOCKS="10000"
+DATABASE_STATE_SNAPSHOT_INTERVAL="1000"
+DATABASE_STATE_PRUNER_INTERVAL="600"
+DATABASE_MAX_PENDING_CHECKOUTS="100"
         "#;
         set_env(config);
 
         let actual = DBConfig::from_env();
         assert_eq!(actual, expected_config());
     }
+
+    #[test]
+    fn slow_query_threshold() {
+        let mut config = expected_config();
+        assert_eq!(
+            config.slow_query_threshold(),
+            Some(time::Duration::from_secs(1))
+        );
+        config.slow_query_threshold = 0;
+        assert_eq!(config.slow_query_threshold(), None);
+    }
 }
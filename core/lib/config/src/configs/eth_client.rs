@@ -14,6 +14,9 @@ pub struct ETHClientConfig {
     pub gas_price_factor: f64,
     /// Address of the Ethereum node API.
     pub web3_url: Vec<String>,
+    /// Address of the Ethereum node WebSocket API used to subscribe to the new blocks.
+    /// If not set, the new blocks are polled.
+    pub web3_ws_url: Option<String>,
 }
 
 impl ETHClientConfig {
@@ -43,6 +46,7 @@ mod tests {
                 "http://127.0.0.1:8545".into(),
                 "http://127.0.0.1:8546".into(),
             ],
+            web3_ws_url: Some("ws://127.0.0.1:8546".into()),
         }
     }
 
@@ -52,6 +56,7 @@ mod tests {
 ETH_CLIENT_CHAIN_ID="9"
 ETH_CLIENT_GAS_PRICE_FACTOR="1"
 ETH_CLIENT_WEB3_URL="http://127.0.0.1:8545,http://127.0.0.1:8546"
+ETH_CLIENT_WEB3_WS_URL="ws://127.0.0.1:8546"
         "#;
         set_env(config);
 
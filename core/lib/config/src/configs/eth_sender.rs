@@ -14,6 +14,8 @@ pub struct ETHSenderConfig {
     pub sender: Sender,
     /// Options related to the `gas_adjuster` submodule.
     pub gas_price_limit: GasLimit,
+    /// Options of the source for the network gas price used by the `gas_adjuster` submodule.
+    pub gas_price_oracle: GasPriceOracle,
 }
 
 impl ETHSenderConfig {
@@ -24,16 +26,51 @@ impl ETHSenderConfig {
                 "eth_sender.gas_price_limit",
                 "ETH_SENDER_GAS_PRICE_LIMIT_"
             ),
+            gas_price_oracle: envy_load!(
+                "eth_sender.gas_price_oracle",
+                "ETH_SENDER_GAS_PRICE_ORACLE_"
+            ),
         }
     }
+
+    /// Checks the consistency of the configuration, returns the descriptions of the found problems.
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.sender.max_txs_in_flight == 0 {
+            problems
+                .push("Ethereum sender must be allowed to send at least one transaction".into());
+        }
+        if self.sender.is_enabled
+            && self.sender.remote_signer_url.is_none()
+            && self.sender.operator_private_key.is_zero()
+        {
+            problems.push(
+                "Either the operator private key or the remote signer URL must be provided".into(),
+            );
+        }
+        problems
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct Sender {
     /// Private key of the operator account.
+    /// Not required if the transactions are signed by the remote signer.
+    #[serde(default)]
     pub operator_private_key: H256,
     /// Address of the operator account.
     pub operator_commit_eth_addr: Address,
+    /// Private keys of the additional operator accounts.
+    /// Once the ongoing operations are confirmed, the new ones are sent from the account with the highest ETH balance.
+    pub additional_operator_private_keys: Vec<H256>,
+    /// ETH balance of the operator account in wei below which the low balance alert is reported.
+    pub operator_low_balance_threshold: u64,
+    /// URL of the remote signer (e.g. Web3Signer) exposing the `eth_signTransaction` method.
+    /// If set, transactions of the operator account are signed remotely instead of using
+    /// the `operator_private_key`.
+    pub remote_signer_url: Option<String>,
+    /// Timeout of the requests to the remote signer in seconds.
+    pub remote_signer_timeout: u64,
     /// mount of confirmations required to consider L1 transaction committed.
     pub wait_confirmations: u64,
     /// Amount of blocks we will wait before considering L1 transaction stuck.
@@ -44,6 +81,29 @@ pub struct Sender {
     pub max_txs_in_flight: u64,
     /// Whether sender should interact with L1 or not.
     pub is_enabled: bool,
+    /// Whether aggregated proofs should be verified locally before sending `proveBlocks` transactions.
+    pub verify_proofs_locally: bool,
+    /// Whether EIP-1559 (type 2) transactions should be sent instead of the legacy ones.
+    /// Must only be enabled for networks that passed the London hard fork.
+    pub use_eip1559: bool,
+    /// Priority fee per gas for EIP-1559 transactions in wei.
+    /// It is increased for every replacement of a stuck transaction.
+    pub max_priority_fee_per_gas: u64,
+    /// Address of the `OperatorMulticall` contract. If set, the operations ready to be sent
+    /// at the same time are combined into a single transaction calling this contract.
+    pub multicall_contract_addr: Option<Address>,
+    /// Maximum total gas limit of the operations combined into a single multicall transaction.
+    pub multicall_max_gas: u64,
+    /// Maximum total calldata size in bytes of the operations combined into a single multicall transaction.
+    pub multicall_max_calldata_size: u64,
+    /// Safety margin added to the gas limit estimated from the gas used by the previous
+    /// transactions of similar size, e.g. `0.2` stands for 20%.
+    pub gas_limit_margin: f64,
+    /// Hard cap for the gas limit of the transactions.
+    pub max_gas_limit: u64,
+    /// Whether the transactions should only be simulated via `eth_call` and logged
+    /// instead of being broadcasted.
+    pub dry_run: bool,
 }
 
 impl Sender {
@@ -51,6 +111,11 @@ impl Sender {
     pub fn tx_poll_period(&self) -> Duration {
         Duration::from_secs(self.tx_poll_period)
     }
+
+    /// Converts `self.remote_signer_timeout` into `Duration`.
+    pub fn remote_signer_timeout(&self) -> Duration {
+        Duration::from_secs(self.remote_signer_timeout)
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -77,6 +142,34 @@ impl GasLimit {
     }
 }
 
+/// Source of the network gas price.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum GasPriceOracleKind {
+    /// `eth_gasPrice` of the Ethereum node.
+    Node,
+    /// External HTTP gas price oracle.
+    Http,
+    /// Percentile of the priority fees paid within the recent blocks.
+    Percentile,
+}
+
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct GasPriceOracle {
+    /// Source of the network gas price.
+    pub kind: GasPriceOracleKind,
+    /// URL of the external oracle, used by the `http` oracle.
+    pub http_url: String,
+    /// JSON pointer (RFC 6901) to the gas price within the external oracle response.
+    pub http_json_pointer: String,
+    /// Multiplier converting the price reported by the external oracle into wei.
+    pub http_price_multiplier: f64,
+    /// Amount of the recent blocks considered by the `percentile` oracle.
+    pub percentile_blocks: u64,
+    /// Percentile of the priority fees within each block, used by the `percentile` oracle.
+    pub percentile: f64,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -90,10 +183,26 @@ mod tests {
                 tx_poll_period: 3,
                 max_txs_in_flight: 3,
                 is_enabled: true,
+                verify_proofs_locally: true,
+                use_eip1559: true,
+                max_priority_fee_per_gas: 1500000000,
                 operator_private_key: hash(
                     "27593fea79697e947890ecbecce7901b0008345e5d7259710d0dd5e500d040be",
                 ),
                 operator_commit_eth_addr: addr("de03a0B5963f75f1C8485B355fF6D30f3093BDE7"),
+                additional_operator_private_keys: vec![
+                    hash("8d0d8b3b4c7a5b6b0e8bc6e0a3e7f1fbd6cd3b1e5b7f7d2d1f8a3c7b2a1e4f09"),
+                    hash("2a1e4f098d0d8b3b4c7a5b6b0e8bc6e0a3e7f1fbd6cd3b1e5b7f7d2d1f8a3c7b"),
+                ],
+                operator_low_balance_threshold: 1000000000000000000,
+                remote_signer_url: Some("http://127.0.0.1:9000".into()),
+                remote_signer_timeout: 10,
+                multicall_contract_addr: Some(addr("5E6D086F5eC079ADFF4FB3774CDf3e8D6a34F7E9")),
+                multicall_max_gas: 8000000,
+                multicall_max_calldata_size: 120000,
+                gas_limit_margin: 0.2f64,
+                max_gas_limit: 15000000,
+                dry_run: false,
             },
             gas_price_limit: GasLimit {
                 default: 400000000000,
@@ -101,6 +210,14 @@ mod tests {
                 sample_interval: 15,
                 scale_factor: 1.0f64,
             },
+            gas_price_oracle: GasPriceOracle {
+                kind: GasPriceOracleKind::Http,
+                http_url: "https://ethgasstation.info/api/ethgasAPI.json".into(),
+                http_json_pointer: "/fast".into(),
+                http_price_multiplier: 100000000f64,
+                percentile_blocks: 20,
+                percentile: 60f64,
+            },
         }
     }
 
@@ -112,12 +229,31 @@ ETH_SENDER_SENDER_EXPECTED_WAIT_TIME_BLOCK="30"
 ETH_SENDER_SENDER_TX_POLL_PERIOD="3"
 ETH_SENDER_SENDER_MAX_TXS_IN_FLIGHT="3"
 ETH_SENDER_SENDER_IS_ENABLED="true"
+ETH_SENDER_SENDER_VERIFY_PROOFS_LOCALLY="true"
+ETH_SENDER_SENDER_USE_EIP1559="true"
+ETH_SENDER_SENDER_MAX_PRIORITY_FEE_PER_GAS="1500000000"
 ETH_SENDER_SENDER_OPERATOR_PRIVATE_KEY="0x27593fea79697e947890ecbecce7901b0008345e5d7259710d0dd5e500d040be"
 ETH_SENDER_SENDER_OPERATOR_COMMIT_ETH_ADDR="0xde03a0B5963f75f1C8485B355fF6D30f3093BDE7"
+ETH_SENDER_SENDER_ADDITIONAL_OPERATOR_PRIVATE_KEYS="0x8d0d8b3b4c7a5b6b0e8bc6e0a3e7f1fbd6cd3b1e5b7f7d2d1f8a3c7b2a1e4f09,0x2a1e4f098d0d8b3b4c7a5b6b0e8bc6e0a3e7f1fbd6cd3b1e5b7f7d2d1f8a3c7b"
+ETH_SENDER_SENDER_OPERATOR_LOW_BALANCE_THRESHOLD="1000000000000000000"
+ETH_SENDER_SENDER_REMOTE_SIGNER_URL="http://127.0.0.1:9000"
+ETH_SENDER_SENDER_REMOTE_SIGNER_TIMEOUT="10"
+ETH_SENDER_SENDER_MULTICALL_CONTRACT_ADDR="0x5E6D086F5eC079ADFF4FB3774CDf3e8D6a34F7E9"
+ETH_SENDER_SENDER_MULTICALL_MAX_GAS="8000000"
+ETH_SENDER_SENDER_MULTICALL_MAX_CALLDATA_SIZE="120000"
+ETH_SENDER_SENDER_GAS_LIMIT_MARGIN="0.2"
+ETH_SENDER_SENDER_MAX_GAS_LIMIT="15000000"
+ETH_SENDER_SENDER_DRY_RUN="false"
 ETH_SENDER_GAS_PRICE_LIMIT_DEFAULT="400000000000"
 ETH_SENDER_GAS_PRICE_LIMIT_UPDATE_INTERVAL="150"
 ETH_SENDER_GAS_PRICE_LIMIT_SAMPLE_INTERVAL="15"
 ETH_SENDER_GAS_PRICE_LIMIT_SCALE_FACTOR="1"
+ETH_SENDER_GAS_PRICE_ORACLE_KIND="http"
+ETH_SENDER_GAS_PRICE_ORACLE_HTTP_URL="https://ethgasstation.info/api/ethgasAPI.json"
+ETH_SENDER_GAS_PRICE_ORACLE_HTTP_JSON_POINTER="/fast"
+ETH_SENDER_GAS_PRICE_ORACLE_HTTP_PRICE_MULTIPLIER="100000000"
+ETH_SENDER_GAS_PRICE_ORACLE_PERCENTILE_BLOCKS="20"
+ETH_SENDER_GAS_PRICE_ORACLE_PERCENTILE="60"
         "#;
         set_env(config);
 
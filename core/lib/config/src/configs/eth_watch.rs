@@ -11,6 +11,15 @@ pub struct ETHWatchConfig {
     /// Amount of confirmations for the priority operation to be processed.
     /// In production this should be a non-zero value because of block reverts.
     pub confirmations_for_eth_event: u64,
+    /// Amount of confirmations for the deposits.
+    /// If not set, `confirmations_for_eth_event` is used.
+    pub confirmations_for_deposit: Option<u64>,
+    /// Amount of confirmations for the full exits.
+    /// If not set, `confirmations_for_eth_event` is used.
+    pub confirmations_for_full_exit: Option<u64>,
+    /// Amount of confirmations for the governance events (new tokens and NFT factories).
+    /// If not set, `confirmations_for_eth_event` is used.
+    pub confirmations_for_governance_event: Option<u64>,
     /// How often we want to poll the Ethereum node.
     /// Value in milliseconds.
     pub eth_node_poll_interval: u64,
@@ -25,6 +34,24 @@ impl ETHWatchConfig {
     pub fn poll_interval(&self) -> Duration {
         Duration::from_millis(self.eth_node_poll_interval)
     }
+
+    /// Amount of confirmations for the deposits to be processed.
+    pub fn deposit_confirmations(&self) -> u64 {
+        self.confirmations_for_deposit
+            .unwrap_or(self.confirmations_for_eth_event)
+    }
+
+    /// Amount of confirmations for the full exits to be processed.
+    pub fn full_exit_confirmations(&self) -> u64 {
+        self.confirmations_for_full_exit
+            .unwrap_or(self.confirmations_for_eth_event)
+    }
+
+    /// Amount of confirmations for the governance events to be processed.
+    pub fn governance_event_confirmations(&self) -> u64 {
+        self.confirmations_for_governance_event
+            .unwrap_or(self.confirmations_for_eth_event)
+    }
 }
 
 #[cfg(test)]
@@ -35,6 +62,9 @@ mod tests {
     fn expected_config() -> ETHWatchConfig {
         ETHWatchConfig {
             confirmations_for_eth_event: 0,
+            confirmations_for_deposit: Some(5),
+            confirmations_for_full_exit: None,
+            confirmations_for_governance_event: None,
             eth_node_poll_interval: 300,
         }
     }
@@ -43,6 +73,7 @@ mod tests {
     fn from_env() {
         let config = r#"
 ETH_WATCH_CONFIRMATIONS_FOR_ETH_EVENT="0"
+ETH_WATCH_CONFIRMATIONS_FOR_DEPOSIT="5"
 ETH_WATCH_ETH_NODE_POLL_INTERVAL="300"
         "#;
         set_env(config);
@@ -60,5 +91,10 @@ ETH_WATCH_ETH_NODE_POLL_INTERVAL="300"
             config.poll_interval(),
             Duration::from_millis(config.eth_node_poll_interval)
         );
+        assert_eq!(config.deposit_confirmations(), 5);
+        assert_eq!(
+            config.full_exit_confirmations(),
+            config.confirmations_for_eth_event
+        );
     }
 }
@@ -0,0 +1,78 @@
+// Built-in uses
+use std::time::Duration;
+// External uses
+use serde::Deserialize;
+// Local uses
+use crate::envy_load;
+
+/// Message broker the events are published to.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum EventPublisherBackend {
+    Kafka,
+    Nats,
+}
+
+/// Configuration of the publisher of the block and transaction events to the message broker.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct EventPublisherConfig {
+    /// Message broker the events are published to.
+    pub backend: EventPublisherBackend,
+    /// Addresses of the broker servers, e.g. `host:9092` for Kafka or `nats://host:4222` for NATS.
+    pub servers: Vec<String>,
+    /// Prefix of the topics (subjects for NATS), the events are published
+    /// to `<prefix>.blocks` and `<prefix>.transactions`.
+    pub topic_prefix: String,
+    /// Max amount of the events published at once.
+    pub batch_size: u32,
+    /// How often the new events are checked.
+    /// Value in milliseconds.
+    pub poll_interval: u64,
+    /// Max size of the published message in bytes, the events with the bigger messages are skipped.
+    /// Has to match the limit of the broker: `max_payload` for NATS, `message.max.bytes` for Kafka.
+    pub max_message_size: usize,
+}
+
+impl EventPublisherConfig {
+    pub fn from_env() -> Self {
+        envy_load!("event_publisher", "EVENT_PUBLISHER_")
+    }
+
+    /// Converts `self.poll_interval` into `Duration`.
+    pub fn poll_interval(&self) -> Duration {
+        Duration::from_millis(self.poll_interval)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configs::test_utils::set_env;
+
+    fn expected_config() -> EventPublisherConfig {
+        EventPublisherConfig {
+            backend: EventPublisherBackend::Kafka,
+            servers: vec!["127.0.0.1:9092".into(), "127.0.0.1:9093".into()],
+            topic_prefix: "zksync".into(),
+            batch_size: 1000,
+            poll_interval: 1000,
+            max_message_size: 1048576,
+        }
+    }
+
+    #[test]
+    fn from_env() {
+        let config = r#"
+EVENT_PUBLISHER_BACKEND="kafka"
+EVENT_PUBLISHER_SERVERS="127.0.0.1:9092,127.0.0.1:9093"
+EVENT_PUBLISHER_TOPIC_PREFIX="zksync"
+EVENT_PUBLISHER_BATCH_SIZE="1000"
+EVENT_PUBLISHER_POLL_INTERVAL="1000"
+EVENT_PUBLISHER_MAX_MESSAGE_SIZE="1048576"
+        "#;
+        set_env(config);
+
+        let actual = EventPublisherConfig::from_env();
+        assert_eq!(actual, expected_config());
+    }
+}
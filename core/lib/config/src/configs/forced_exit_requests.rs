@@ -28,6 +28,12 @@ struct ForcedExitRequestsInternalConfig {
     pub expiration_period: u64,
     pub blocks_check_amount: u64,
     pub eth_node_poll_interval: u64,
+    pub dormant_accounts_enabled: bool,
+    pub dormant_account_idle_days: u64,
+    pub dormant_balance_threshold_usd: f64,
+    pub dormant_accounts_check_interval: u64,
+    pub dormant_accounts_batch_size: u32,
+    pub dormant_accounts_opt_out: Vec<Address>,
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -45,6 +51,12 @@ pub struct ForcedExitRequestsConfig {
     pub expiration_period: u64,
     pub blocks_check_amount: u64,
     pub eth_node_poll_interval: u64,
+    pub dormant_accounts_enabled: bool,
+    pub dormant_account_idle_days: u64,
+    pub dormant_balance_threshold_usd: f64,
+    pub dormant_accounts_check_interval: u64,
+    pub dormant_accounts_batch_size: u32,
+    pub dormant_accounts_opt_out: Vec<Address>,
 }
 
 // Checks that in no way the price will overlap with the requests id space
@@ -87,10 +99,25 @@ impl ForcedExitRequestsConfig {
             expiration_period: config.expiration_period,
             blocks_check_amount: config.blocks_check_amount,
             eth_node_poll_interval: config.eth_node_poll_interval,
+            dormant_accounts_enabled: config.dormant_accounts_enabled,
+            dormant_account_idle_days: config.dormant_account_idle_days,
+            dormant_balance_threshold_usd: config.dormant_balance_threshold_usd,
+            dormant_accounts_check_interval: config.dormant_accounts_check_interval,
+            dormant_accounts_batch_size: config.dormant_accounts_batch_size,
+            dormant_accounts_opt_out: config.dormant_accounts_opt_out,
         }
     }
 
     pub fn poll_interval(&self) -> Duration {
         Duration::from_millis(self.eth_node_poll_interval)
     }
+
+    /// Minimum time without any updates after which the account is considered dormant.
+    pub fn dormant_account_idle_time(&self) -> Duration {
+        Duration::from_secs(self.dormant_account_idle_days * 24 * 60 * 60)
+    }
+
+    pub fn dormant_accounts_check_interval(&self) -> Duration {
+        Duration::from_secs(self.dormant_accounts_check_interval)
+    }
 }
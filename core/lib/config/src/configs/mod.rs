@@ -1,14 +1,18 @@
 // Public re-exports
 pub use self::{
-    api::ApiConfig, chain::ChainConfig, contracts::ContractsConfig, database::DBConfig,
+    alerting::AlertingConfig, api::ApiConfig, archiver::ArchiverConfig, chain::ChainConfig,
+    contracts::ContractsConfig, database::DBConfig,
     dev_liquidity_token_watcher::DevLiquidityTokenWatcherConfig, eth_client::ETHClientConfig,
     eth_sender::ETHSenderConfig, eth_watch::ETHWatchConfig, event_listener::EventListenerConfig,
-    forced_exit_requests::ForcedExitRequestsConfig, gateway_watcher::GatewayWatcherConfig,
-    misc::MiscConfig, prover::ProverConfig, ticker::TickerConfig,
-    token_handler::TokenHandlerConfig,
+    event_publisher::EventPublisherConfig, forced_exit_requests::ForcedExitRequestsConfig,
+    gateway_watcher::GatewayWatcherConfig, misc::MiscConfig, prover::ProverConfig,
+    ticker::TickerConfig, token_handler::TokenHandlerConfig, token_holders::TokenHoldersConfig,
+    token_metadata::TokenMetadataConfig, webhooks::WebhooksConfig,
 };
 
+pub mod alerting;
 pub mod api;
+pub mod archiver;
 pub mod chain;
 pub mod contracts;
 pub mod database;
@@ -17,12 +21,16 @@ pub mod eth_client;
 pub mod eth_sender;
 pub mod eth_watch;
 pub mod event_listener;
+pub mod event_publisher;
 pub mod forced_exit_requests;
 pub mod gateway_watcher;
 pub mod misc;
 pub mod prover;
 pub mod ticker;
 pub mod token_handler;
+pub mod token_holders;
+pub mod token_metadata;
+pub mod webhooks;
 
 #[cfg(test)]
 pub(crate) mod test_utils;
@@ -2,6 +2,8 @@
 use std::time::Duration;
 // External uses
 use serde::Deserialize;
+// Workspace uses
+use zksync_types::prover::ProverJobPriorityPolicy;
 // Local uses
 use crate::envy_load;
 
@@ -11,6 +13,7 @@ pub struct ProverConfig {
     pub prover: Prover,
     pub core: Core,
     pub witness_generator: WitnessGenerator,
+    pub exit_proof_generator: ExitProofGenerator,
 }
 
 impl ProverConfig {
@@ -19,6 +22,10 @@ impl ProverConfig {
             prover: envy_load!("prover.prover", "PROVER_PROVER_"),
             core: envy_load!("prover.core", "PROVER_CORE_"),
             witness_generator: envy_load!("prover.witness_generator", "PROVER_WITNESS_GENERATOR_"),
+            exit_proof_generator: envy_load!(
+                "prover.exit_proof_generator",
+                "PROVER_EXIT_PROOF_GENERATOR_"
+            ),
         }
     }
 }
@@ -34,6 +41,11 @@ pub struct Prover {
     pub request_timeout: u64,
     /// Flag for dying after proving cycle
     pub die_after_proof: bool,
+    /// Expected sha256 checksums of the universal setup keys in the `<power_of_two>:<checksum>` form.
+    /// Keys without a checksum are only checked to be present.
+    pub setup_keys_checksums: Vec<String>,
+    /// Port of the prometheus exporter of the prover.
+    pub prometheus_port: u16,
 }
 
 impl Prover {
@@ -57,9 +69,15 @@ impl Prover {
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct Core {
     /// Timeout to consider prover gone in ms.
+    /// Jobs of the provers that didn't send a heartbeat for this time are given to other provers.
     pub gone_timeout: u64,
     /// Amount of provers in the cluser if there is no pending jobs.
     pub idle_provers: u32,
+    /// Time in seconds since the aggregated proof job is added within which the proof has to be generated.
+    pub aggregated_proof_deadline: u64,
+    /// Aggregated proof jobs this close to their deadline (in seconds) are handed out
+    /// before the pending single block proof jobs.
+    pub aggregated_proof_preemption_window: u64,
 }
 
 impl Core {
@@ -67,6 +85,14 @@ impl Core {
     pub fn gone_timeout(&self) -> Duration {
         Duration::from_millis(self.gone_timeout)
     }
+
+    /// Returns the policy of handing out the prover jobs.
+    pub fn job_priority_policy(&self) -> ProverJobPriorityPolicy {
+        ProverJobPriorityPolicy {
+            aggregated_proof_deadline: Duration::from_secs(self.aggregated_proof_deadline),
+            preemption_window: Duration::from_secs(self.aggregated_proof_preemption_window),
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -75,6 +101,9 @@ pub struct WitnessGenerator {
     pub prepare_data_interval: u64,
     /// Amount of witness generator threads.
     pub witness_generators: usize,
+    /// Time after which a block claimed by a witness generator that stopped sending
+    /// heartbeats can be taken over by another one, in ms.
+    pub lease_timeout: u64,
 }
 
 impl WitnessGenerator {
@@ -82,6 +111,35 @@ impl WitnessGenerator {
     pub fn prepare_data_interval(&self) -> Duration {
         Duration::from_millis(self.prepare_data_interval)
     }
+
+    /// Converts `self.lease_timeout` into `Duration`.
+    pub fn lease_timeout(&self) -> Duration {
+        Duration::from_millis(self.lease_timeout)
+    }
+}
+
+/// Settings of the generator of the exit proofs requested via the API.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct ExitProofGenerator {
+    /// Amount of proofs generated in parallel.
+    pub workers: usize,
+    /// Interval of checking the queue for the new exit proof jobs in ms.
+    pub poll_interval: u64,
+    /// Time in ms after which a job in progress without heartbeats from its generator
+    /// is returned to the queue.
+    pub lease_timeout: u64,
+}
+
+impl ExitProofGenerator {
+    /// Converts `self.poll_interval` into `Duration`.
+    pub fn poll_interval(&self) -> Duration {
+        Duration::from_millis(self.poll_interval)
+    }
+
+    /// Converts `self.lease_timeout` into `Duration`.
+    pub fn lease_timeout(&self) -> Duration {
+        Duration::from_millis(self.lease_timeout)
+    }
 }
 
 #[cfg(test)]
@@ -96,14 +154,24 @@ mod tests {
                 cycle_wait: 500,
                 request_timeout: 10,
                 die_after_proof: false,
+                setup_keys_checksums: vec!["20:3a7c5d".to_string(), "21:b0f04e".to_string()],
+                prometheus_port: 3313,
             },
             core: Core {
                 gone_timeout: 60000,
                 idle_provers: 1,
+                aggregated_proof_deadline: 3600,
+                aggregated_proof_preemption_window: 600,
             },
             witness_generator: WitnessGenerator {
                 prepare_data_interval: 500,
                 witness_generators: 2,
+                lease_timeout: 60000,
+            },
+            exit_proof_generator: ExitProofGenerator {
+                workers: 2,
+                poll_interval: 1000,
+                lease_timeout: 60000,
             },
         }
     }
@@ -115,10 +183,18 @@ PROVER_PROVER_HEARTBEAT_INTERVAL="1000"
 PROVER_PROVER_CYCLE_WAIT="500"
 PROVER_PROVER_REQUEST_TIMEOUT="10"
 PROVER_PROVER_DIE_AFTER_PROOF=false
+PROVER_PROVER_SETUP_KEYS_CHECKSUMS="20:3a7c5d,21:b0f04e"
+PROVER_PROVER_PROMETHEUS_PORT="3313"
 PROVER_CORE_GONE_TIMEOUT="60000"
 PROVER_CORE_IDLE_PROVERS="1"
+PROVER_CORE_AGGREGATED_PROOF_DEADLINE="3600"
+PROVER_CORE_AGGREGATED_PROOF_PREEMPTION_WINDOW="600"
 PROVER_WITNESS_GENERATOR_PREPARE_DATA_INTERVAL="500"
 PROVER_WITNESS_GENERATOR_WITNESS_GENERATORS="2"
+PROVER_WITNESS_GENERATOR_LEASE_TIMEOUT="60000"
+PROVER_EXIT_PROOF_GENERATOR_WORKERS="2"
+PROVER_EXIT_PROOF_GENERATOR_POLL_INTERVAL="1000"
+PROVER_EXIT_PROOF_GENERATOR_LEASE_TIMEOUT="60000"
         "#;
         set_env(config);
 
@@ -148,10 +224,29 @@ PROVER_WITNESS_GENERATOR_WITNESS_GENERATORS="2"
             config.core.gone_timeout(),
             Duration::from_millis(config.core.gone_timeout)
         );
+        assert_eq!(
+            config.core.job_priority_policy(),
+            ProverJobPriorityPolicy {
+                aggregated_proof_deadline: Duration::from_secs(3600),
+                preemption_window: Duration::from_secs(600),
+            }
+        );
 
         assert_eq!(
             config.witness_generator.prepare_data_interval(),
             Duration::from_millis(config.witness_generator.prepare_data_interval)
         );
+        assert_eq!(
+            config.witness_generator.lease_timeout(),
+            Duration::from_millis(config.witness_generator.lease_timeout)
+        );
+        assert_eq!(
+            config.exit_proof_generator.poll_interval(),
+            Duration::from_millis(config.exit_proof_generator.poll_interval)
+        );
+        assert_eq!(
+            config.exit_proof_generator.lease_timeout(),
+            Duration::from_millis(config.exit_proof_generator.lease_timeout)
+        );
     }
 }
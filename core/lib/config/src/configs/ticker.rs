@@ -40,6 +40,15 @@ pub struct TickerConfig {
     pub number_of_ticker_actors: u8,
     /// Subsidized price for ChangePubKey in cents scaled by SUBSIDY_USD_AMOUNTS_SCALE
     pub subsidy_cpk_price_usd_scaled: u64,
+    /// Max change of the token price since the previous update (in percent), after which the alert is raised.
+    /// Zero value disables the check.
+    pub max_price_change_percent: f64,
+    /// Max deviation of the price provider from the median price of all providers (in percent), after which
+    /// the alert is raised. Once set, the prices are also requested from the other provider to cross-check them.
+    /// Zero value disables the check.
+    pub max_provider_deviation_percent: f64,
+    /// Slack-compatible webhook the price alerts are sent to, the alerts are only logged if not set.
+    pub price_alert_webhook_url: Option<String>,
 }
 
 impl TickerConfig {
@@ -51,6 +60,11 @@ impl TickerConfig {
         envy_load!("fee_ticker", "FEE_TICKER_")
     }
 
+    /// Loads the config without panicking, used to reload it while the server is running.
+    pub fn try_from_env() -> Result<Self, envy::Error> {
+        envy::prefixed("FEE_TICKER_").from_env()
+    }
+
     /// Returns the token price source type and the corresponding API URL.
     pub fn price_source(&self) -> (TokenPriceSource, String) {
         let url = match self.token_price_source {
@@ -59,6 +73,24 @@ impl TickerConfig {
         };
         (self.token_price_source, url)
     }
+
+    /// Returns the price source used to cross-check the prices of the main one and the corresponding
+    /// API URL, or `None` if the check is disabled.
+    pub fn reference_price_source(&self) -> Option<(TokenPriceSource, String)> {
+        if self.max_provider_deviation_percent <= 0.0 {
+            return None;
+        }
+        let source = match self.token_price_source {
+            TokenPriceSource::CoinGecko => (
+                TokenPriceSource::CoinMarketCap,
+                self.coinmarketcap_base_url.clone(),
+            ),
+            TokenPriceSource::CoinMarketCap => {
+                (TokenPriceSource::CoinGecko, self.coingecko_base_url.clone())
+            }
+        };
+        Some(source)
+    }
 }
 
 #[cfg(test)]
@@ -80,6 +112,9 @@ mod tests {
             token_market_update_time: 120,
             number_of_ticker_actors: 4,
             subsidy_cpk_price_usd_scaled: 100,
+            max_price_change_percent: 25.0,
+            max_provider_deviation_percent: 0.0,
+            price_alert_webhook_url: Some("https://hooks.slack.com/services/test".into()),
         }
     }
 
@@ -99,6 +134,9 @@ FEE_TICKER_NUMBER_OF_TICKER_ACTORS="4"
 FEE_TICKER_SUBSIDIZED_TOKENS_LIMITS=156
 FEE_TICKER_SCALE_FEE_PERCENT=100
 FEE_TICKER_SUBSIDY_CPK_PRICE_USD_SCALED=100
+FEE_TICKER_MAX_PRICE_CHANGE_PERCENT=25
+FEE_TICKER_MAX_PROVIDER_DEVIATION_PERCENT=0
+FEE_TICKER_PRICE_ALERT_WEBHOOK_URL="https://hooks.slack.com/services/test"
         "#;
         set_env(config);
 
@@ -128,5 +166,14 @@ FEE_TICKER_SUBSIDY_CPK_PRICE_USD_SCALED=100
             config.price_source(),
             (TokenPriceSource::CoinMarketCap, COINMARKETCAP_URL.into())
         );
+
+        config.max_provider_deviation_percent = 0.0;
+        assert_eq!(config.reference_price_source(), None);
+
+        config.max_provider_deviation_percent = 10.0;
+        assert_eq!(
+            config.reference_price_source(),
+            Some((TokenPriceSource::CoinGecko, COINGECKO_URL.into()))
+        );
     }
 }
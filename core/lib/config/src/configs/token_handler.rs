@@ -4,7 +4,7 @@ use std::time::Duration;
 // External uses
 use serde::Deserialize;
 // Workspace uses
-use zksync_types::TokenInfo;
+use zksync_types::{Address, TokenInfo, U256};
 // Local uses
 use crate::envy_load;
 
@@ -17,6 +17,12 @@ pub struct TokenHandlerConfig {
     pub poll_interval: u64,
     /// Link to MatterMost channel for token list notification.
     pub webhook_url: String,
+    /// Token the listing fee is paid in. If not set, the listing fee payments are not verified.
+    pub listing_fee_token: Option<Address>,
+    /// Address receiving the listing fees.
+    pub listing_fee_treasury: Option<Address>,
+    /// Min listing fee in the smallest units of the fee token, as a decimal string.
+    pub listing_fee: Option<String>,
 }
 
 impl TokenHandlerConfig {
@@ -33,6 +39,13 @@ impl TokenHandlerConfig {
         Duration::from_secs(self.poll_interval)
     }
 
+    /// Parses self.listing_fee, `None` if the fee is not set.
+    pub fn listing_fee(&self) -> Option<U256> {
+        self.listing_fee.as_ref().map(|fee| {
+            U256::from_dec_str(fee).expect("TOKEN_HANDLER_LISTING_FEE must be a decimal number")
+        })
+    }
+
     pub fn token_list(&self) -> Vec<TokenInfo> {
         let token_list_name = self.token_list_file();
         let path = format!("./etc/token-lists/{}.json", token_list_name);
@@ -45,13 +58,16 @@ impl TokenHandlerConfig {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::configs::test_utils::set_env;
+    use crate::configs::test_utils::{addr, set_env};
 
     fn expected_config() -> TokenHandlerConfig {
         TokenHandlerConfig {
             token_list_name: "localhost".to_string(),
             poll_interval: 1,
             webhook_url: "http://127.0.0.1".to_string(),
+            listing_fee_token: Some(addr("38A2fDc11f526Ddd5a607C1F251C065f40fBF2f7")),
+            listing_fee_treasury: Some(addr("de03a0B5963f75f1C8485B355fF6D30f3093BDE7")),
+            listing_fee: Some("1000000000000000000".to_string()),
         }
     }
 
@@ -61,11 +77,15 @@ mod tests {
 TOKEN_HANDLER_POLL_INTERVAL=1
 TOKEN_HANDLER_WEBHOOK_URL="http://127.0.0.1"
 TOKEN_HANDLER_TOKEN_LIST_NAME="localhost"
+TOKEN_HANDLER_LISTING_FEE_TOKEN="0x38A2fDc11f526Ddd5a607C1F251C065f40fBF2f7"
+TOKEN_HANDLER_LISTING_FEE_TREASURY="0xde03a0B5963f75f1C8485B355fF6D30f3093BDE7"
+TOKEN_HANDLER_LISTING_FEE="1000000000000000000"
         "#;
         set_env(config);
 
         let actual_config = TokenHandlerConfig::from_env();
         let expected_config = expected_config();
         assert_eq!(actual_config, expected_config);
+        assert_eq!(actual_config.listing_fee(), Some(U256::exp10(18)));
     }
 }
@@ -0,0 +1,49 @@
+// Built-in uses
+use std::time::Duration;
+// External uses
+use serde::Deserialize;
+// Local uses
+use crate::envy_load;
+
+/// Configuration of the job collecting the holders of the tokens.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct TokenHoldersConfig {
+    /// How often the holders of the tokens are recalculated.
+    /// Value in seconds.
+    pub update_interval: u64,
+}
+
+impl TokenHoldersConfig {
+    pub fn from_env() -> Self {
+        envy_load!("token_holders", "TOKEN_HOLDERS_")
+    }
+
+    /// Converts `self.update_interval` into `Duration`.
+    pub fn update_interval(&self) -> Duration {
+        Duration::from_secs(self.update_interval)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configs::test_utils::set_env;
+
+    fn expected_config() -> TokenHoldersConfig {
+        TokenHoldersConfig {
+            update_interval: 3600,
+        }
+    }
+
+    #[test]
+    fn from_env() {
+        let config = r#"
+TOKEN_HOLDERS_UPDATE_INTERVAL="3600"
+        "#;
+        set_env(config);
+
+        let actual = TokenHoldersConfig::from_env();
+        assert_eq!(actual, expected_config());
+        assert_eq!(actual.update_interval(), Duration::from_secs(3600));
+    }
+}
@@ -0,0 +1,54 @@
+// Built-in uses
+use std::time::Duration;
+// External uses
+use serde::Deserialize;
+// Local uses
+use crate::envy_load;
+
+/// Configuration of the job enriching the tokens with the metadata.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct TokenMetadataConfig {
+    /// How often the metadata of the tokens is updated.
+    /// Value in seconds.
+    pub update_interval: u64,
+    /// URL of the curated token list in the Uniswap token list format, the logos and the descriptions
+    /// of the tokens are taken from it. If not set, only the on-chain metadata is collected.
+    pub token_list_url: Option<String>,
+}
+
+impl TokenMetadataConfig {
+    pub fn from_env() -> Self {
+        envy_load!("token_metadata", "TOKEN_METADATA_")
+    }
+
+    /// Converts `self.update_interval` into `Duration`.
+    pub fn update_interval(&self) -> Duration {
+        Duration::from_secs(self.update_interval)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configs::test_utils::set_env;
+
+    fn expected_config() -> TokenMetadataConfig {
+        TokenMetadataConfig {
+            update_interval: 3600,
+            token_list_url: Some("https://tokens.example.com/list.json".into()),
+        }
+    }
+
+    #[test]
+    fn from_env() {
+        let config = r#"
+TOKEN_METADATA_UPDATE_INTERVAL="3600"
+TOKEN_METADATA_TOKEN_LIST_URL="https://tokens.example.com/list.json"
+        "#;
+        set_env(config);
+
+        let actual = TokenMetadataConfig::from_env();
+        assert_eq!(actual, expected_config());
+        assert_eq!(actual.update_interval(), Duration::from_secs(3600));
+    }
+}
@@ -0,0 +1,138 @@
+// Built-in uses
+use std::time::Duration;
+// External uses
+use serde::Deserialize;
+// Local uses
+use crate::envy_load;
+
+/// Configuration of the webhooks delivering the events to the integrators.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct WebhooksConfig {
+    /// Whether the event server delivers the events to the registered webhooks.
+    pub enabled: bool,
+    /// Token expected in the `Authorization: Bearer` header of the webhooks management requests.
+    pub api_token: String,
+    /// How often the new events and the pending deliveries are checked.
+    /// Value in milliseconds.
+    pub poll_interval: u64,
+    /// Max amount of the events enqueued and of the deliveries sent at once.
+    pub batch_size: u32,
+    /// Timeout of the delivery request.
+    /// Value in seconds.
+    pub request_timeout: u64,
+    /// Amount of the delivery attempts, after which it's moved to the dead letters.
+    pub max_attempts: u32,
+    /// Delay before the first retry of the delivery, doubled for every following one.
+    /// Value in seconds.
+    pub retry_interval: u64,
+    /// Max delay between the delivery attempts.
+    /// Value in seconds.
+    pub max_retry_interval: u64,
+    /// How long the delivered payloads are kept before being pruned.
+    /// Value in hours.
+    pub delivered_retention: u64,
+}
+
+impl WebhooksConfig {
+    pub fn from_env() -> Self {
+        envy_load!("webhooks", "WEBHOOKS_")
+    }
+
+    /// Loads the config if the webhooks are enabled. The config is optional,
+    /// so `None` is returned if it's absent.
+    pub fn from_env_if_enabled() -> Option<Self> {
+        let enabled = std::env::var("WEBHOOKS_ENABLED")
+            .ok()
+            .and_then(|enabled| enabled.parse().ok())
+            .unwrap_or(false);
+        enabled.then(Self::from_env)
+    }
+
+    /// Converts `self.poll_interval` into `Duration`.
+    pub fn poll_interval(&self) -> Duration {
+        Duration::from_millis(self.poll_interval)
+    }
+
+    /// Converts `self.request_timeout` into `Duration`.
+    pub fn request_timeout(&self) -> Duration {
+        Duration::from_secs(self.request_timeout)
+    }
+
+    /// Converts `self.delivered_retention` into `Duration`.
+    pub fn delivered_retention(&self) -> Duration {
+        Duration::from_secs(self.delivered_retention * 60 * 60)
+    }
+
+    /// Delay before the next attempt of the delivery that has failed `attempts` times,
+    /// `None` if it's out of attempts.
+    pub fn retry_delay(&self, attempts: u32) -> Option<Duration> {
+        if attempts >= self.max_attempts {
+            return None;
+        }
+        let multiplier = 2u64.saturating_pow(attempts.saturating_sub(1));
+        let delay = self.retry_interval.saturating_mul(multiplier);
+        Some(Duration::from_secs(delay.min(self.max_retry_interval)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configs::test_utils::set_env;
+
+    fn expected_config() -> WebhooksConfig {
+        WebhooksConfig {
+            enabled: true,
+            api_token: "sample".into(),
+            poll_interval: 1000,
+            batch_size: 100,
+            request_timeout: 10,
+            max_attempts: 10,
+            retry_interval: 10,
+            max_retry_interval: 3600,
+            delivered_retention: 168,
+        }
+    }
+
+    #[test]
+    fn from_env() {
+        let config = r#"
+WEBHOOKS_ENABLED="true"
+WEBHOOKS_API_TOKEN="sample"
+WEBHOOKS_POLL_INTERVAL="1000"
+WEBHOOKS_BATCH_SIZE="100"
+WEBHOOKS_REQUEST_TIMEOUT="10"
+WEBHOOKS_MAX_ATTEMPTS="10"
+WEBHOOKS_RETRY_INTERVAL="10"
+WEBHOOKS_MAX_RETRY_INTERVAL="3600"
+WEBHOOKS_DELIVERED_RETENTION="168"
+        "#;
+        set_env(config);
+
+        let actual = WebhooksConfig::from_env();
+        assert_eq!(actual, expected_config());
+        assert_eq!(
+            WebhooksConfig::from_env_if_enabled(),
+            Some(expected_config())
+        );
+
+        set_env(r#"WEBHOOKS_ENABLED="false""#);
+        assert_eq!(WebhooksConfig::from_env_if_enabled(), None);
+    }
+
+    #[test]
+    fn retry_delay() {
+        let config = expected_config();
+        assert_eq!(config.retry_delay(1), Some(Duration::from_secs(10)));
+        assert_eq!(config.retry_delay(3), Some(Duration::from_secs(40)));
+        assert_eq!(config.retry_delay(9), Some(Duration::from_secs(2560)));
+        // The delay is capped.
+        let config = WebhooksConfig {
+            max_attempts: 100,
+            ..expected_config()
+        };
+        assert_eq!(config.retry_delay(20), Some(Duration::from_secs(3600)));
+        // No more attempts.
+        assert_eq!(expected_config().retry_delay(10), None);
+    }
+}
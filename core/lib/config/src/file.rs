@@ -0,0 +1,283 @@
+//! Loading of the configuration from the TOML files.
+//!
+//! The configuration is layered: the defaults declared in the config structures are overridden
+//! by the TOML files, which are in turn overridden by the environment variables. The TOML files have
+//! the same layout as the ones in `etc/env`, and every value is mapped to the environment variable
+//! the same way the `zk config compile` command does it: `[chain.state_keeper] block_chunk_sizes=[10, 32]`
+//! becomes `CHAIN_STATE_KEEPER_BLOCK_CHUNK_SIZES="10,32"`.
+//!
+//! The files can be re-read while the binary is running (see `reload_config_files`), which only updates
+//! the variables that were set from the files, so the explicitly set variables still take precedence.
+
+// Built-in uses
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    env, fs, io,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+// External uses
+use once_cell::sync::OnceCell;
+use thiserror::Error;
+use toml::{value::Table, Value};
+
+/// Environment variable with the path to the TOML configuration file
+/// or to the directory with the TOML configuration files, e.g. `etc/env/dev`.
+pub const CONFIG_PATH_VAR: &str = "ZKSYNC_CONFIG_PATH";
+
+/// Prefixes of the environment variables which belong to the zkSync configuration.
+const CONFIG_PREFIXES: &[&str] = &[
+    "ALERTING_",
+    "API_",
+    "ARCHIVER_",
+    "CHAIN_",
+    "CONTRACTS_",
+    "DATABASE_",
+    "DEV_LIQUIDITY_TOKEN_WATCHER_",
+    "ETH_CLIENT_",
+    "ETH_SENDER_",
+    "ETH_WATCH_",
+    "EVENT_LISTENER_",
+    "EVENT_PUBLISHER_",
+    "FEE_TICKER_",
+    "FORCED_EXIT_REQUESTS_",
+    "GATEWAY_WATCHER_",
+    "MISC_",
+    "NFT_FACTORY_",
+    "PROVER_",
+    "TOKEN_HANDLER_",
+    "TOKEN_METADATA_",
+    "WEBHOOKS_",
+];
+
+/// Parts of the variable names which values must not be printed.
+const SECRET_MARKERS: &[&str] = &[
+    "PRIVATE_KEY",
+    "SECRET",
+    "PASSWORD",
+    "API_KEY",
+    "TOKEN",
+    "DATABASE_URL",
+    "DATABASE_REPLICA_URL",
+    "WEB3_URL",
+];
+
+/// Names of the variables set from the configuration files.
+static FILE_VARIABLES: OnceCell<Mutex<BTreeSet<String>>> = OnceCell::new();
+
+#[derive(Debug, Error)]
+pub enum ConfigFileError {
+    #[error("Cannot read the config file {0}: {1}")]
+    Read(PathBuf, io::Error),
+    #[error("Cannot parse the config file {0}: {1}")]
+    Parse(PathBuf, toml::de::Error),
+}
+
+/// Loads the configuration files from the path set by the `ZKSYNC_CONFIG_PATH` variable (if any)
+/// and sets the environment variables which are not set yet, so the variables set explicitly
+/// take precedence over the files.
+///
+/// Must be called at the very start of the binary, before any configuration is loaded.
+/// Returns the amount of variables set from the files.
+pub fn load_config_files() -> Result<usize, ConfigFileError> {
+    let path = match env::var_os(CONFIG_PATH_VAR) {
+        Some(path) => PathBuf::from(path),
+        None => return Ok(0),
+    };
+
+    let mut file_variables = FILE_VARIABLES.get_or_init(Default::default).lock().unwrap();
+    let mut applied = 0;
+    for (name, value) in config_file_variables(&path)? {
+        if env::var_os(&name).is_none() {
+            env::set_var(&name, value);
+            file_variables.insert(name);
+            applied += 1;
+        }
+    }
+    Ok(applied)
+}
+
+/// Re-reads the configuration files and updates the variables that are not set explicitly,
+/// i.e. the ones set from the files by `load_config_files` or by the previous reload.
+/// The variables removed from the files keep their values.
+///
+/// Returns the names of the changed variables.
+pub fn reload_config_files() -> Result<Vec<String>, ConfigFileError> {
+    let path = match env::var_os(CONFIG_PATH_VAR) {
+        Some(path) => PathBuf::from(path),
+        None => return Ok(Vec::new()),
+    };
+
+    let mut file_variables = FILE_VARIABLES.get_or_init(Default::default).lock().unwrap();
+    let mut changed = Vec::new();
+    for (name, value) in config_file_variables(&path)? {
+        let current = env::var(&name).ok();
+        if current.is_some() && !file_variables.contains(&name) {
+            continue;
+        }
+        if current.as_ref() != Some(&value) {
+            env::set_var(&name, value);
+            changed.push(name.clone());
+        }
+        file_variables.insert(name);
+    }
+    Ok(changed)
+}
+
+/// Collects the environment variables defined by the TOML file or by all the TOML files in the directory.
+pub fn config_file_variables(path: &Path) -> Result<BTreeMap<String, String>, ConfigFileError> {
+    let files = if path.is_dir() {
+        let entries = fs::read_dir(path).map_err(|err| ConfigFileError::Read(path.into(), err))?;
+        let mut files = Vec::new();
+        for entry in entries {
+            let file = entry
+                .map_err(|err| ConfigFileError::Read(path.into(), err))?
+                .path();
+            if file.extension().map_or(false, |ext| ext == "toml") {
+                files.push(file);
+            }
+        }
+        // Files are applied in a predictable order, so the duplicated values are resolved the same way.
+        files.sort();
+        files
+    } else {
+        vec![path.to_path_buf()]
+    };
+
+    let mut variables = BTreeMap::new();
+    for file in files {
+        let contents =
+            fs::read_to_string(&file).map_err(|err| ConfigFileError::Read(file.clone(), err))?;
+        let table: Table =
+            toml::from_str(&contents).map_err(|err| ConfigFileError::Parse(file.clone(), err))?;
+        collect_variables("", &table, &mut variables);
+    }
+    Ok(variables)
+}
+
+fn collect_variables(prefix: &str, table: &Table, variables: &mut BTreeMap<String, String>) {
+    for (key, value) in table {
+        let name = format!("{}{}", prefix, key.to_uppercase());
+        match value {
+            Value::Table(nested) => collect_variables(&format!("{}_", name), nested, variables),
+            Value::Array(values) => {
+                let values: Vec<_> = values.iter().map(value_to_string).collect();
+                variables.insert(name, values.join(","));
+            }
+            value => {
+                variables.insert(name, value_to_string(value));
+            }
+        }
+    }
+}
+
+fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::String(value) => value.clone(),
+        value => value.to_string(),
+    }
+}
+
+/// Returns the configuration variables currently set in the environment,
+/// with the values of the secrets (private keys, passwords, etc.) masked.
+pub fn effective_variables() -> BTreeMap<String, String> {
+    env::vars()
+        .filter(|(name, _)| {
+            CONFIG_PREFIXES
+                .iter()
+                .any(|prefix| name.starts_with(prefix))
+        })
+        .map(|(name, value)| {
+            if SECRET_MARKERS.iter().any(|marker| name.contains(marker)) {
+                (name, "<hidden>".to_string())
+            } else {
+                (name, value)
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toml_to_variables() {
+        let table: Table = toml::from_str(
+            r#"
+zksync_action="dont_ask"
+
+[chain.state_keeper]
+block_chunk_sizes=[10, 32, 72]
+miniblock_iteration_interval=200
+fee_account_addr="0xde03a0B5963f75f1C8485B355fF6D30f3093BDE7"
+
+[eth_sender.sender]
+is_enabled=true
+gas_limit_margin=0.2
+"#,
+        )
+        .unwrap();
+
+        let mut variables = BTreeMap::new();
+        collect_variables("", &table, &mut variables);
+
+        let expected: BTreeMap<String, String> = vec![
+            ("ZKSYNC_ACTION", "dont_ask"),
+            ("CHAIN_STATE_KEEPER_BLOCK_CHUNK_SIZES", "10,32,72"),
+            ("CHAIN_STATE_KEEPER_MINIBLOCK_ITERATION_INTERVAL", "200"),
+            (
+                "CHAIN_STATE_KEEPER_FEE_ACCOUNT_ADDR",
+                "0xde03a0B5963f75f1C8485B355fF6D30f3093BDE7",
+            ),
+            ("ETH_SENDER_SENDER_IS_ENABLED", "true"),
+            ("ETH_SENDER_SENDER_GAS_LIMIT_MARGIN", "0.2"),
+        ]
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+        assert_eq!(variables, expected);
+    }
+
+    #[test]
+    fn reload_keeps_explicit_variables() {
+        let file =
+            env::temp_dir().join(format!("zksync_config_reload_{}.toml", std::process::id()));
+        fs::write(&file, "[test_reload]\nfrom_file=1\nexplicit=1\n").unwrap();
+        env::set_var("TEST_RELOAD_EXPLICIT", "0");
+        env::set_var(CONFIG_PATH_VAR, &file);
+
+        load_config_files().unwrap();
+        assert_eq!(env::var("TEST_RELOAD_FROM_FILE").unwrap(), "1");
+        assert_eq!(env::var("TEST_RELOAD_EXPLICIT").unwrap(), "0");
+
+        fs::write(&file, "[test_reload]\nfrom_file=2\nexplicit=2\n").unwrap();
+        let changed = reload_config_files().unwrap();
+        assert_eq!(changed, vec!["TEST_RELOAD_FROM_FILE".to_string()]);
+        assert_eq!(env::var("TEST_RELOAD_FROM_FILE").unwrap(), "2");
+        assert_eq!(env::var("TEST_RELOAD_EXPLICIT").unwrap(), "0");
+
+        env::remove_var(CONFIG_PATH_VAR);
+        fs::remove_file(&file).unwrap();
+    }
+
+    #[test]
+    fn secrets_are_hidden() {
+        env::set_var("ALERTING_TELEGRAM_BOT_TOKEN", "123:secret");
+        env::set_var("ALERTING_CHECK_INTERVAL", "60");
+
+        let variables = effective_variables();
+        assert_eq!(variables["ALERTING_TELEGRAM_BOT_TOKEN"], "<hidden>");
+        assert_eq!(variables["ALERTING_CHECK_INTERVAL"], "60");
+    }
+
+    #[test]
+    fn base_config_files_are_parsed() {
+        let home = env::var("ZKSYNC_HOME").expect("ZKSYNC_HOME variable must be set");
+        let variables =
+            config_file_variables(&Path::new(&home).join("etc/env/base")).expect("invalid configs");
+
+        assert_eq!(variables["MISC_LOG_FORMAT"], "plain");
+        assert!(variables.contains_key("CHAIN_STATE_KEEPER_BLOCK_CHUNK_SIZES"));
+    }
+}
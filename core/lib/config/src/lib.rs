@@ -1,11 +1,15 @@
+use std::{env, panic, process};
+
 pub use crate::configs::{
-    ApiConfig, ChainConfig, ContractsConfig, DBConfig, DevLiquidityTokenWatcherConfig,
-    ETHClientConfig, ETHSenderConfig, ETHWatchConfig, EventListenerConfig,
-    ForcedExitRequestsConfig, GatewayWatcherConfig, MiscConfig, ProverConfig, TickerConfig,
-    TokenHandlerConfig,
+    AlertingConfig, ApiConfig, ArchiverConfig, ChainConfig, ContractsConfig, DBConfig,
+    DevLiquidityTokenWatcherConfig, ETHClientConfig, ETHSenderConfig, ETHWatchConfig,
+    EventListenerConfig, EventPublisherConfig, ForcedExitRequestsConfig, GatewayWatcherConfig,
+    MiscConfig, ProverConfig, TickerConfig, TokenHandlerConfig, TokenHoldersConfig,
+    TokenMetadataConfig, WebhooksConfig,
 };
 
 pub mod configs;
+pub mod file;
 pub mod test_config;
 
 #[derive(Debug, Clone)]
@@ -43,4 +47,91 @@ impl ZkSyncConfig {
             forced_exit_requests: ForcedExitRequestsConfig::from_env(),
         }
     }
+
+    /// Checks the consistency of the configuration, returns the descriptions of the found problems.
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = self.chain.validate();
+
+        problems.extend(self.api.cors.validate("api.cors"));
+        problems.extend(self.api.admin_cors.validate("api.admin.cors"));
+        problems.extend(self.db.validate());
+        problems.extend(self.eth_sender.validate());
+        problems
+    }
+}
+
+/// Runs the configuration loader, returning the message of its panic as an error.
+/// The `from_env` constructors panic on the missing or malformed variables, which must be reported
+/// as the configuration problems by the `--validate-config` mode instead of aborting it.
+pub fn try_load<T>(load: impl FnOnce() -> T + panic::UnwindSafe) -> Result<T, String> {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let result = panic::catch_unwind(load);
+    panic::set_hook(default_hook);
+
+    result.map_err(|payload| {
+        payload
+            .downcast_ref::<String>()
+            .cloned()
+            .or_else(|| payload.downcast_ref::<&str>().map(|msg| msg.to_string()))
+            .unwrap_or_else(|| "Cannot load the configuration".to_string())
+    })
+}
+
+/// Implements the `--validate-config` mode of the binaries: prints the effective configuration variables,
+/// then loads and checks the configuration used by the binary via `validate` and prints the found problems.
+/// The configuration that can't be loaded is reported as a problem as well.
+///
+/// Returns `true` if the configuration is valid.
+pub fn print_and_validate(validate: impl FnOnce() -> Vec<String> + panic::UnwindSafe) -> bool {
+    println!("Effective configuration:");
+    for (name, value) in file::effective_variables() {
+        println!("{}={}", name, value);
+    }
+
+    let problems = try_load(validate).unwrap_or_else(|err| vec![err]);
+    if problems.is_empty() {
+        println!("Configuration is valid");
+    } else {
+        println!("Configuration is invalid:");
+        for problem in &problems {
+            println!("- {}", problem);
+        }
+    }
+    problems.is_empty()
+}
+
+/// Handles the `--validate-config` flag for the binaries with the required arguments, which can't be
+/// omitted by their argument parsers: if the flag is passed, validates the configuration via
+/// `print_and_validate` and exits with the corresponding code.
+pub fn run_validate_config_mode(validate: impl FnOnce() -> Vec<String> + panic::UnwindSafe) {
+    if env::args().skip(1).any(|arg| arg == "--validate-config") {
+        let is_valid = print_and_validate(validate);
+        process::exit(if is_valid { 0 } else { 1 });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_config_is_reported() {
+        assert_eq!(try_load(|| 42), Ok(42));
+        assert_eq!(
+            try_load(|| -> u32 { panic!("Cannot load config <{}>: {}", "db", "missing url") }),
+            Err("Cannot load config <db>: missing url".to_string())
+        );
+
+        #[derive(Debug, serde::Deserialize)]
+        struct TestConfig {
+            missing_value: u64,
+        }
+        env::remove_var("TEST_VALIDATE_MISSING_VALUE");
+        assert!(!print_and_validate(|| {
+            let config: TestConfig = envy_load!("test", "TEST_VALIDATE_");
+            vec![format!("{:?}", config)]
+        }));
+        assert!(print_and_validate(Vec::new));
+    }
 }
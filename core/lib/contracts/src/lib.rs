@@ -13,6 +13,8 @@ const GOVERNANCE_CONTRACT_FILE: &str =
     "contracts/artifacts/cache/solpp-generated-contracts/Governance.sol/Governance.json";
 const IERC20_CONTRACT_FILE: &str =
     "contracts/artifacts/cache/solpp-generated-contracts/IERC20.sol/IERC20.json";
+const IERC20_PERMIT_CONTRACT_FILE: &str =
+    "contracts/artifacts/cache/solpp-generated-contracts/IERC20Permit.sol/IERC20Permit.json";
 const IEIP1271_CONTRACT_FILE: &str =
     "contracts/artifacts/cache/solpp-generated-contracts/dev-contracts/IEIP1271.sol/IEIP1271.json";
 const UPGRADE_GATEKEEPER_CONTRACT_FILE: &str =
@@ -91,6 +93,15 @@ pub fn erc20_contract() -> Contract {
     Contract::load(abi_string.as_bytes()).expect("erc20 contract abi")
 }
 
+pub fn erc20_permit_contract() -> Contract {
+    let abi_string = read_file_to_json_value(IERC20_PERMIT_CONTRACT_FILE)
+        .expect("couldn't read IERC20_PERMIT_CONTRACT_FILE")
+        .get("abi")
+        .expect("couldn't get abi from IERC20_PERMIT_CONTRACT_FILE")
+        .to_string();
+    Contract::load(abi_string.as_bytes()).expect("erc20 permit contract abi")
+}
+
 pub fn eip1271_contract() -> Contract {
     let abi_string = read_file_to_json_value(IEIP1271_CONTRACT_FILE)
         .expect("couldn't read IEIP1271_CONTRACT_FILE")
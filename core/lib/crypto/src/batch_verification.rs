@@ -0,0 +1,177 @@
+//! Batch verification of the transaction signatures.
+//!
+//! Instead of checking `h * (s_i * G - R_i - c_i * X_i) = 0` for every signature separately,
+//! the random linear combination of these equations is checked at once:
+//!
+//! `h * ((sum z_i * s_i) * G - sum (z_i * R_i + z_i * c_i * X_i)) = 0`
+//!
+//! The sum is calculated as a single multi-scalar multiplication sharing the point doublings,
+//! which makes the verification of a large amount of signatures several times faster.
+//! If the batch check fails, at least one of the signatures is invalid, but there is no way to
+//! determine which one without verifying them one by one.
+
+use crate::franklin_crypto::{
+    bellman::pairing::ff::{Field, PrimeField},
+    eddsa::Signature,
+    jubjub::{edwards, Unknown},
+};
+use rand::{thread_rng, Rng};
+use rayon::prelude::*;
+
+use crate::{
+    musig::{musig_challenge, point_generator_mul},
+    params::JUBJUB_PARAMS,
+    primitives::rescue_hash_tx_msg,
+    Engine, Fs, PublicKey,
+};
+
+type Point = edwards::Point<Engine, Unknown>;
+
+/// Width of the window of the multi-scalar multiplication in bits.
+/// Must divide 64, so that no window crosses the boundary of the scalar limbs.
+const WINDOW_BITS: usize = 4;
+
+/// Minimal amount of the signatures handled by a single thread.
+const MIN_CHUNK_SIZE: usize = 32;
+
+/// Calculates `sum scalars_i * bases_i` via the Straus method.
+fn multiexp(bases: &[Point], scalars: &[Fs]) -> Point {
+    let tables: Vec<Vec<Point>> = bases
+        .iter()
+        .map(|base| {
+            let mut table = Vec::with_capacity(1 << WINDOW_BITS);
+            table.push(Point::zero());
+            for i in 1..(1 << WINDOW_BITS) {
+                let next = table[i - 1].add(base, &JUBJUB_PARAMS);
+                table.push(next);
+            }
+            table
+        })
+        .collect();
+    let reprs: Vec<_> = scalars.iter().map(|scalar| scalar.into_repr()).collect();
+
+    let windows = (Fs::NUM_BITS as usize + WINDOW_BITS - 1) / WINDOW_BITS;
+    let mut acc = Point::zero();
+    for window in (0..windows).rev() {
+        for _ in 0..WINDOW_BITS {
+            acc = acc.double(&JUBJUB_PARAMS);
+        }
+        for (table, repr) in tables.iter().zip(&reprs) {
+            let bit = window * WINDOW_BITS;
+            let digit = (repr.as_ref()[bit / 64] >> (bit % 64)) as usize & ((1 << WINDOW_BITS) - 1);
+            if digit != 0 {
+                acc = acc.add(&table[digit], &JUBJUB_PARAMS);
+            }
+        }
+    }
+    acc
+}
+
+/// Calculates `sum z_i * s_i` and `sum (z_i * R_i + z_i * c_i * X_i)` for the chunk of signatures.
+fn combine_chunk(signatures: &[(&PublicKey, &Signature<Engine>, &[u8])]) -> (Fs, Point) {
+    let mut rng = thread_rng();
+    let mut s_sum = Fs::zero();
+    let mut bases = Vec::with_capacity(signatures.len() * 2);
+    let mut scalars = Vec::with_capacity(signatures.len() * 2);
+
+    for (pubkey, signature, msg) in signatures {
+        let challenge = musig_challenge(pubkey, &signature.r, &rescue_hash_tx_msg(msg));
+        let z: Fs = rng.gen();
+
+        let mut z_s = signature.s;
+        z_s.mul_assign(&z);
+        s_sum.add_assign(&z_s);
+
+        let mut z_c = challenge;
+        z_c.mul_assign(&z);
+        bases.push(signature.r.clone());
+        scalars.push(z);
+        bases.push(pubkey.0.clone());
+        scalars.push(z_c);
+    }
+
+    (s_sum, multiexp(&bases, &scalars))
+}
+
+/// Verifies the signatures of the messages all at once. The messages are hashed the same way
+/// as in `TxSignature::verify_musig`.
+///
+/// Returns `true` only if all the signatures are valid. An empty batch is considered valid.
+pub fn batch_verify_musig_rescue(signatures: &[(&PublicKey, &Signature<Engine>, &[u8])]) -> bool {
+    let chunk_size = std::cmp::max(
+        MIN_CHUNK_SIZE,
+        (signatures.len() + rayon::current_num_threads() - 1) / rayon::current_num_threads(),
+    );
+    let (s_sum, points_sum) = signatures.par_chunks(chunk_size).map(combine_chunk).reduce(
+        || (Fs::zero(), Point::zero()),
+        |(mut s_acc, points_acc), (s, points)| {
+            s_acc.add_assign(&s);
+            (s_acc, points_acc.add(&points, &JUBJUB_PARAMS))
+        },
+    );
+
+    point_generator_mul(s_sum)
+        .add(&points_sum.negate(), &JUBJUB_PARAMS)
+        .mul_by_cofactor(&JUBJUB_PARAMS)
+        == Point::zero()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        franklin_crypto::{eddsa::Seed, jubjub::FixedGenerators},
+        params::RESCUE_PARAMS,
+        priv_key_from_fs, public_key_from_private,
+    };
+    use rand::{SeedableRng, XorShiftRng};
+
+    fn sign(rng: &mut XorShiftRng, msg: &[u8]) -> (PublicKey, Signature<Engine>) {
+        let private_key = priv_key_from_fs(rng.gen());
+        let hashed_msg = rescue_hash_tx_msg(msg);
+        let seed = Seed::deterministic_seed(&private_key, &hashed_msg);
+        let signature = private_key.musig_rescue_sign(
+            &hashed_msg,
+            &seed,
+            FixedGenerators::SpendingKeyGenerator,
+            &RESCUE_PARAMS,
+            &JUBJUB_PARAMS,
+        );
+        (public_key_from_private(&private_key), signature)
+    }
+
+    #[test]
+    fn multiexp_matches_separate_multiplications() {
+        let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+        let bases: Vec<Point> = (0..5).map(|_| point_generator_mul(rng.gen())).collect();
+        let scalars: Vec<Fs> = (0..5).map(|_| rng.gen()).collect();
+
+        let expected = bases
+            .iter()
+            .zip(&scalars)
+            .fold(Point::zero(), |acc, (base, scalar)| {
+                acc.add(&base.mul(*scalar, &JUBJUB_PARAMS), &JUBJUB_PARAMS)
+            });
+        assert!(multiexp(&bases, &scalars) == expected);
+    }
+
+    #[test]
+    fn batch_verification() {
+        let mut rng = XorShiftRng::from_seed([5, 6, 7, 8]);
+        let msgs: Vec<Vec<u8>> = (0..100u8).map(|i| vec![i; 40]).collect();
+        let signed: Vec<_> = msgs.iter().map(|msg| sign(&mut rng, msg)).collect();
+
+        let mut batch: Vec<_> = signed
+            .iter()
+            .zip(&msgs)
+            .map(|((pubkey, signature), msg)| (pubkey, signature, msg.as_slice()))
+            .collect();
+        assert!(batch_verify_musig_rescue(&batch));
+        assert!(batch_verify_musig_rescue(&batch[..1]));
+        assert!(batch_verify_musig_rescue(&[]));
+
+        // A single signature of the wrong message invalidates the whole batch.
+        batch[42].2 = msgs[43].as_slice();
+        assert!(!batch_verify_musig_rescue(&batch));
+    }
+}
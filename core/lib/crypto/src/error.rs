@@ -19,3 +19,35 @@ pub enum ConversionError {
     #[error("Cannot convert into prime field value: {0}")]
     PrimeFieldDecodingError(#[from] ff::PrimeFieldDecodingError),
 }
+
+#[derive(Debug, Error, PartialEq)]
+pub enum MusigError {
+    #[error("At least two participants are required")]
+    NotEnoughParticipants,
+    #[error("Participant position {0} is out of range")]
+    InvalidPosition(usize),
+    #[error("Private key doesn't match the public key at the participant position")]
+    PublicKeyMismatch,
+    #[error(
+        "Values count doesn't match the participants count. Actual: {actual}, expected: {expected}"
+    )]
+    ParticipantsMismatch { expected: usize, actual: usize },
+    #[error("Nonce commitments were not received")]
+    CommitmentsNotReceived,
+    #[error("Nonces were not received")]
+    NoncesNotReceived,
+    #[error("Nonce of the participant {0} doesn't match its commitment")]
+    CommitmentMismatch(usize),
+    #[error("Nonce has already been used")]
+    NonceUsed,
+    #[error("Partial signature of the participant {0} is invalid")]
+    InvalidPartialSignature(usize),
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum KeyDerivationError {
+    #[error("Seed too short, must be at least 32 bytes long")]
+    SeedTooShort,
+    #[error("Invalid derivation path: {0}")]
+    InvalidPath(String),
+}
@@ -0,0 +1,159 @@
+//! Deterministic derivation of the zkSync private keys.
+//!
+//! A single seed (e.g. the one obtained from the Ethereum signature of the zkSync account access
+//! message) may be used to derive any amount of the private keys via the derivation path
+//! `m/i_1/i_2/.../i_n`, where every index is a decimal `u32` number.
+//!
+//! The seed of every path level is calculated from the seed of the previous level as
+//! `sha256(KEY_DERIVATION_MESSAGE || parent_seed || index)`, where the index is encoded as
+//! 4 big-endian bytes. The private key is obtained from the resulting seed via
+//! [`private_key_from_seed`]. The path `m` corresponds to the private key of the seed itself.
+
+use sha2::{Digest, Sha256};
+
+use crate::{
+    error::KeyDerivationError,
+    franklin_crypto::bellman::pairing::ff::{PrimeField, PrimeFieldRepr},
+    priv_key_from_fs, Fs, PrivateKey,
+};
+
+/// Message prepended to the parent seed during the derivation of the child seed.
+pub const KEY_DERIVATION_MESSAGE: &[u8] = b"zkSync key derivation";
+
+/// Minimal length of the seed in bytes.
+pub const MIN_SEED_LEN: usize = 32;
+
+fn sha256_bytes(input: &[u8]) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    hasher.input(input);
+    hasher.result().to_vec()
+}
+
+/// Generates a new `PrivateKey` from seed using a deterministic algorithm:
+/// seed is hashed via `sha256` hash (twice), and the output treated as a `PrivateKey`.
+/// If the obtained value doesn't have a correct value to be a `PrivateKey`, hashing operation is applied
+/// repeatedly to the previous output, until the value can be interpreted as a `PrivateKey`.
+pub fn private_key_from_seed(seed: &[u8]) -> Result<PrivateKey, KeyDerivationError> {
+    if seed.len() < MIN_SEED_LEN {
+        return Err(KeyDerivationError::SeedTooShort);
+    }
+
+    let mut effective_seed = sha256_bytes(seed);
+
+    loop {
+        let raw_priv_key = sha256_bytes(&effective_seed);
+        let mut fs_repr = <Fs as PrimeField>::Repr::default();
+        fs_repr
+            .read_be(&raw_priv_key[..])
+            .expect("failed to read raw_priv_key");
+        match Fs::from_repr(fs_repr) {
+            Ok(fs) => return Ok(priv_key_from_fs(fs)),
+            Err(_) => {
+                effective_seed = raw_priv_key;
+            }
+        }
+    }
+}
+
+/// Parses the derivation path in the `m/i_1/.../i_n` format.
+pub fn parse_derivation_path(path: &str) -> Result<Vec<u32>, KeyDerivationError> {
+    let mut segments = path.split('/');
+    if segments.next() != Some("m") {
+        return Err(KeyDerivationError::InvalidPath(path.to_owned()));
+    }
+
+    segments
+        .map(|segment| {
+            // `u32::from_str` accepts the leading `+`, which is not a part of the format.
+            let is_number =
+                !segment.is_empty() && segment.bytes().all(|byte| byte.is_ascii_digit());
+            match segment.parse() {
+                Ok(index) if is_number => Ok(index),
+                _ => Err(KeyDerivationError::InvalidPath(path.to_owned())),
+            }
+        })
+        .collect()
+}
+
+/// Derives the seed of the child key with the given index.
+pub fn derive_child_seed(seed: &[u8], index: u32) -> Vec<u8> {
+    let mut input = Vec::with_capacity(KEY_DERIVATION_MESSAGE.len() + seed.len() + 4);
+    input.extend_from_slice(KEY_DERIVATION_MESSAGE);
+    input.extend_from_slice(seed);
+    input.extend_from_slice(&index.to_be_bytes());
+    sha256_bytes(&input)
+}
+
+/// Derives the private key of the given path from the seed.
+pub fn derive_private_key(seed: &[u8], path: &[u32]) -> Result<PrivateKey, KeyDerivationError> {
+    if seed.len() < MIN_SEED_LEN {
+        return Err(KeyDerivationError::SeedTooShort);
+    }
+
+    let seed = path.iter().fold(seed.to_vec(), |seed, index| {
+        derive_child_seed(&seed, *index)
+    });
+    private_key_from_seed(&seed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::convert::FeConvert;
+
+    #[test]
+    fn derivation_path() {
+        assert_eq!(parse_derivation_path("m").unwrap(), Vec::<u32>::new());
+        assert_eq!(parse_derivation_path("m/0/1").unwrap(), vec![0, 1]);
+        assert_eq!(
+            parse_derivation_path("m/4294967295").unwrap(),
+            vec![u32::MAX]
+        );
+
+        for path in &[
+            "",
+            "0/1",
+            "m/",
+            "m/1/",
+            "m//1",
+            "m/+1",
+            "m/1'",
+            "m/4294967296",
+        ] {
+            assert_eq!(
+                parse_derivation_path(path).unwrap_err(),
+                KeyDerivationError::InvalidPath(path.to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn derived_keys() {
+        let seed = [1u8; 32];
+        let test_vectors = [
+            (
+                "m",
+                "0caa064a83a3e78f8469fca9b0d65b8982389e99d39c1dba2bc85a89863c8123",
+            ),
+            (
+                "m/0/1",
+                "04e50a78f4dd1a21f1da456036d37a876f3f41d69bc505c2504492b9511209f0",
+            ),
+            (
+                "m/2147483655",
+                "0c1c2cfe00d11a1bc6af2722630afee644d8a2f67a43cf73eecb4f20b10d63eb",
+            ),
+        ];
+
+        for (path, expected) in &test_vectors {
+            let path = parse_derivation_path(path).unwrap();
+            let private_key = derive_private_key(&seed, &path).unwrap();
+            assert_eq!(private_key.0.to_hex(), *expected);
+        }
+
+        assert_eq!(
+            derive_private_key(&[1u8; 31], &[0]).unwrap_err(),
+            KeyDerivationError::SeedTooShort
+        );
+    }
+}
@@ -19,10 +19,13 @@ mod crypto_exports {
 
 pub use crypto_exports::*;
 
+pub mod batch_verification;
 pub mod circuit;
 pub mod convert;
 pub mod error;
+pub mod key_derivation;
 pub mod merkle_tree;
+pub mod musig;
 pub mod params;
 pub mod primitives;
 pub mod proof;
@@ -0,0 +1,445 @@
+//! MuSig multi-party signing.
+//!
+//! Allows several parties to cooperatively produce a single Schnorr signature which is
+//! indistinguishable from the one created by `PrivateKey::musig_rescue_sign` for the aggregated
+//! public key. Since the public key is a part of the Fiat-Shamir challenge, the resulting
+//! signature is verified by the circuit the same way as any other transaction signature.
+//!
+//! The protocol consists of three rounds:
+//!
+//! 1. Every party creates a [`MusigSigner`] and broadcasts its nonce commitment.
+//! 2. Once all the commitments are received, every party reveals its nonce.
+//! 3. Once all the nonces are received and checked against the commitments, every party
+//!    broadcasts its partial signature of the message, which can then be aggregated by anyone.
+//!
+//! The nonce is generated randomly for every signer and can be used only once, so a new signer
+//! is required for every message.
+
+use crate::franklin_crypto::{
+    bellman::pairing::ff::{Field, PrimeField, PrimeFieldRepr},
+    circuit::multipack,
+    eddsa::Signature,
+    jubjub::{edwards, FixedGenerators, JubjubParams, Unknown},
+    rescue::StatefulRescue,
+};
+use rand::Rng;
+
+use crate::{
+    error::MusigError,
+    merkle_tree::hasher::Hasher,
+    params::{JUBJUB_PARAMS, RESCUE_HASHER, RESCUE_PARAMS},
+    primitives::{rescue_hash_tx_msg, BitConvert, GetBitsFixed},
+    public_key_from_private, Engine, Fr, Fs, PrivateKey, PublicKey,
+};
+
+/// Point of the Jubjub curve used as a nonce of the signature.
+pub type MusigNonce = edwards::Point<Engine, Unknown>;
+
+/// Length of the signed message in bytes, same as in the circuit.
+const SIGN_MESSAGE_LEN: usize = 32;
+
+/// Converts little endian bits into the field element.
+/// The number of bits must not exceed the capacity of the field.
+fn fs_from_le_bits(mut bits: Vec<bool>) -> Fs {
+    assert!(bits.len() <= Fs::CAPACITY as usize);
+    bits.resize(256, false);
+
+    let mut repr = <Fs as PrimeField>::Repr::default();
+    repr.read_le(&BitConvert::into_bytes(bits)[..])
+        .expect("repr has exactly 32 bytes");
+    Fs::from_repr(repr).expect("value is less than the field modulus")
+}
+
+fn fr_into_le_bytes(value: &Fr) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(32);
+    value
+        .into_repr()
+        .write_le(&mut bytes)
+        .expect("writing to vec never fails");
+    bytes
+}
+
+pub(crate) fn point_generator_mul(scalar: Fs) -> MusigNonce {
+    JUBJUB_PARAMS
+        .generator(FixedGenerators::SpendingKeyGenerator)
+        .mul(scalar, &JUBJUB_PARAMS)
+        .into()
+}
+
+/// Calculates the aggregation coefficients of the public keys, i.e. `a_i = H(L, X_i)`,
+/// where `L` is the list of all the public keys.
+pub fn musig_coefficients(pubkeys: &[PublicKey]) -> Vec<Fs> {
+    let keys_elements: Vec<Fr> = pubkeys
+        .iter()
+        .flat_map(|pubkey| {
+            let (x, y) = pubkey.0.into_xy();
+            vec![x, y]
+        })
+        .collect();
+
+    pubkeys
+        .iter()
+        .map(|pubkey| {
+            let (x, y) = pubkey.0.into_xy();
+            let mut input = keys_elements.clone();
+            input.extend_from_slice(&[x, y]);
+            let hash = RESCUE_HASHER.hash_elements(input);
+            fs_from_le_bits(hash.get_bits_le_fixed(Fs::CAPACITY as usize))
+        })
+        .collect()
+}
+
+/// Aggregates the public keys of the signing parties into the single public key `X = sum(a_i * X_i)`,
+/// which verifies the aggregated signature.
+///
+/// The order of the keys matters, so all the parties must agree on it.
+pub fn aggregate_public_keys(pubkeys: &[PublicKey]) -> Result<PublicKey, MusigError> {
+    if pubkeys.len() < 2 {
+        return Err(MusigError::NotEnoughParticipants);
+    }
+
+    let aggregated = pubkeys
+        .iter()
+        .zip(musig_coefficients(pubkeys))
+        .fold(edwards::Point::zero(), |acc: MusigNonce, (pubkey, a)| {
+            acc.add(&pubkey.0.mul(a, &JUBJUB_PARAMS), &JUBJUB_PARAMS)
+        });
+
+    Ok(PublicKey(aggregated))
+}
+
+/// Calculates the commitment to the nonce, which is published before the nonce itself.
+pub fn nonce_commitment(nonce: &MusigNonce) -> Fr {
+    let (x, y) = nonce.into_xy();
+    RESCUE_HASHER.hash_elements(vec![x, y])
+}
+
+/// Calculates the Fiat-Shamir challenge of the signature for the already hashed message
+/// (see `rescue_hash_tx_msg`). Mirrors the computation of the circuit.
+pub fn musig_challenge(pubkey: &PublicKey, r: &MusigNonce, hashed_msg: &[u8]) -> Fs {
+    assert!(hashed_msg.len() <= SIGN_MESSAGE_LEN);
+    let mut msg = hashed_msg.to_vec();
+    msg.resize(SIGN_MESSAGE_LEN, 0);
+
+    let mut input = fr_into_le_bytes(&pubkey.0.into_xy().0);
+    input.extend(fr_into_le_bytes(&r.into_xy().0));
+    input.extend(msg);
+
+    let packed = multipack::compute_multipacking::<Engine>(&BitConvert::from_be_bytes(&input));
+    let mut sponge = StatefulRescue::<Engine>::new(&RESCUE_PARAMS);
+    sponge.specialize(packed.len() as u8);
+    sponge.absorb(&packed);
+    let s0 = sponge.squeeze_out_single();
+    let s1 = sponge.squeeze_out_single();
+
+    let take_bits = (Fs::CAPACITY / 2) as usize;
+    let mut bits = s0.get_bits_le_fixed(take_bits);
+    bits.extend(s1.get_bits_le_fixed(take_bits));
+    fs_from_le_bits(bits)
+}
+
+/// One of the parties of the MuSig signing session.
+pub struct MusigSigner {
+    private_key: PrivateKey,
+    position: usize,
+    pubkeys: Vec<PublicKey>,
+    coefficients: Vec<Fs>,
+    aggregated_pubkey: PublicKey,
+    nonce_secret: Option<Fs>,
+    nonce: MusigNonce,
+    commitments: Option<Vec<Fr>>,
+    nonces: Option<Vec<MusigNonce>>,
+}
+
+impl std::fmt::Debug for MusigSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MusigSigner")
+            .field("position", &self.position)
+            .finish()
+    }
+}
+
+impl MusigSigner {
+    /// Creates a signer of the party with the given private key.
+    /// `position` is the index of the party public key in the list of all the public keys.
+    pub fn new<R: Rng>(
+        private_key: PrivateKey,
+        pubkeys: Vec<PublicKey>,
+        position: usize,
+        rng: &mut R,
+    ) -> Result<Self, MusigError> {
+        let aggregated_pubkey = aggregate_public_keys(&pubkeys)?;
+        let own_pubkey = pubkeys
+            .get(position)
+            .ok_or(MusigError::InvalidPosition(position))?;
+        if own_pubkey.0 != public_key_from_private(&private_key).0 {
+            return Err(MusigError::PublicKeyMismatch);
+        }
+
+        let nonce_secret: Fs = rng.gen();
+        Ok(Self {
+            private_key,
+            position,
+            coefficients: musig_coefficients(&pubkeys),
+            pubkeys,
+            aggregated_pubkey,
+            nonce_secret: Some(nonce_secret),
+            nonce: point_generator_mul(nonce_secret),
+            commitments: None,
+            nonces: None,
+        })
+    }
+
+    /// Returns the aggregated public key of the session.
+    pub fn aggregated_pubkey(&self) -> &PublicKey {
+        &self.aggregated_pubkey
+    }
+
+    /// Returns the commitment to the nonce of the party to be published in the first round.
+    pub fn nonce_commitment(&self) -> Fr {
+        nonce_commitment(&self.nonce)
+    }
+
+    /// Receives the nonce commitments of all the parties, including the own one.
+    /// Returns the nonce of the party to be published in the second round.
+    pub fn receive_commitments(&mut self, commitments: Vec<Fr>) -> Result<MusigNonce, MusigError> {
+        self.check_length(commitments.len())?;
+        if commitments[self.position] != self.nonce_commitment() {
+            return Err(MusigError::CommitmentMismatch(self.position));
+        }
+
+        self.commitments = Some(commitments);
+        Ok(self.nonce.clone())
+    }
+
+    /// Receives the nonces of all the parties, including the own one, and checks them against
+    /// the received commitments.
+    pub fn receive_nonces(&mut self, nonces: Vec<MusigNonce>) -> Result<(), MusigError> {
+        let commitments = self
+            .commitments
+            .as_ref()
+            .ok_or(MusigError::CommitmentsNotReceived)?;
+        self.check_length(nonces.len())?;
+        for (i, (nonce, commitment)) in nonces.iter().zip(commitments).enumerate() {
+            if nonce_commitment(nonce) != *commitment {
+                return Err(MusigError::CommitmentMismatch(i));
+            }
+        }
+
+        self.nonces = Some(nonces);
+        Ok(())
+    }
+
+    /// Creates the partial signature of the message to be published in the third round.
+    /// The message is hashed the same way as in `TxSignature::sign_musig`.
+    ///
+    /// The nonce is used only once, so the second call returns an error.
+    pub fn sign(&mut self, msg: &[u8]) -> Result<Fs, MusigError> {
+        let r = self.aggregated_nonce()?;
+        let nonce_secret = self.nonce_secret.take().ok_or(MusigError::NonceUsed)?;
+        let challenge = musig_challenge(&self.aggregated_pubkey, &r, &rescue_hash_tx_msg(msg));
+
+        // s_i = r_i + c * a_i * x_i
+        let mut s = challenge;
+        s.mul_assign(&self.coefficients[self.position]);
+        s.mul_assign(&self.private_key.0);
+        s.add_assign(&nonce_secret);
+        Ok(s)
+    }
+
+    /// Checks the partial signatures of all the parties and aggregates them into the signature,
+    /// which is valid for the aggregated public key.
+    pub fn aggregate_signatures(
+        &self,
+        msg: &[u8],
+        partial_signatures: &[Fs],
+    ) -> Result<Signature<Engine>, MusigError> {
+        let r = self.aggregated_nonce()?;
+        let nonces = self.nonces.as_ref().ok_or(MusigError::NoncesNotReceived)?;
+        self.check_length(partial_signatures.len())?;
+        let challenge = musig_challenge(&self.aggregated_pubkey, &r, &rescue_hash_tx_msg(msg));
+
+        let mut s = Fs::zero();
+        for (i, partial_signature) in partial_signatures.iter().enumerate() {
+            // s_i * G == R_i + (c * a_i) * X_i
+            let mut scalar = challenge;
+            scalar.mul_assign(&self.coefficients[i]);
+            let expected = nonces[i].add(
+                &self.pubkeys[i].0.mul(scalar, &JUBJUB_PARAMS),
+                &JUBJUB_PARAMS,
+            );
+            if point_generator_mul(*partial_signature) != expected {
+                return Err(MusigError::InvalidPartialSignature(i));
+            }
+            s.add_assign(partial_signature);
+        }
+
+        Ok(Signature { r, s })
+    }
+
+    fn aggregated_nonce(&self) -> Result<MusigNonce, MusigError> {
+        let nonces = self.nonces.as_ref().ok_or(MusigError::NoncesNotReceived)?;
+        Ok(nonces
+            .iter()
+            .fold(edwards::Point::zero(), |acc: MusigNonce, nonce| {
+                acc.add(nonce, &JUBJUB_PARAMS)
+            }))
+    }
+
+    fn check_length(&self, actual: usize) -> Result<(), MusigError> {
+        if actual != self.pubkeys.len() {
+            return Err(MusigError::ParticipantsMismatch {
+                expected: self.pubkeys.len(),
+                actual,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{franklin_crypto::eddsa::Seed, priv_key_from_fs};
+    use rand::{SeedableRng, XorShiftRng};
+
+    fn verify(pubkey: &PublicKey, msg: &[u8], signature: &Signature<Engine>) -> bool {
+        pubkey.verify_musig_rescue(
+            &rescue_hash_tx_msg(msg),
+            signature,
+            FixedGenerators::SpendingKeyGenerator,
+            &RESCUE_PARAMS,
+            &JUBJUB_PARAMS,
+        )
+    }
+
+    fn run_session(
+        private_keys: Vec<PrivateKey>,
+        msg: &[u8],
+        rng: &mut XorShiftRng,
+    ) -> (PublicKey, Signature<Engine>) {
+        let pubkeys: Vec<_> = private_keys.iter().map(public_key_from_private).collect();
+        let mut signers: Vec<_> = private_keys
+            .into_iter()
+            .enumerate()
+            .map(|(i, key)| MusigSigner::new(key, pubkeys.clone(), i, rng).unwrap())
+            .collect();
+
+        let commitments: Vec<_> = signers.iter().map(|s| s.nonce_commitment()).collect();
+        let nonces: Vec<_> = signers
+            .iter_mut()
+            .map(|s| s.receive_commitments(commitments.clone()).unwrap())
+            .collect();
+        for signer in &mut signers {
+            signer.receive_nonces(nonces.clone()).unwrap();
+        }
+        let partial_signatures: Vec<_> = signers.iter_mut().map(|s| s.sign(msg).unwrap()).collect();
+
+        let signature = signers[0]
+            .aggregate_signatures(msg, &partial_signatures)
+            .unwrap();
+        (signers[0].aggregated_pubkey().clone(), signature)
+    }
+
+    /// Checks that the challenge matches the one of the single party signature.
+    #[test]
+    fn challenge_matches_single_signature() {
+        let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+        let private_key = priv_key_from_fs(rng.gen());
+        let pubkey = public_key_from_private(&private_key);
+        let hashed_msg = rescue_hash_tx_msg(b"single signature");
+
+        let seed = Seed::deterministic_seed(&private_key, &hashed_msg);
+        let signature = private_key.musig_rescue_sign(
+            &hashed_msg,
+            &seed,
+            FixedGenerators::SpendingKeyGenerator,
+            &RESCUE_PARAMS,
+            &JUBJUB_PARAMS,
+        );
+
+        let challenge = musig_challenge(&pubkey, &signature.r, &hashed_msg);
+        let expected = signature
+            .r
+            .add(&pubkey.0.mul(challenge, &JUBJUB_PARAMS), &JUBJUB_PARAMS);
+        assert!(point_generator_mul(signature.s) == expected);
+    }
+
+    #[test]
+    fn aggregated_signature_is_valid() {
+        let mut rng = XorShiftRng::from_seed([5, 6, 7, 8]);
+        let msg = b"multi-party signature";
+
+        for participants in 2..=3 {
+            let private_keys = (0..participants)
+                .map(|_| priv_key_from_fs(rng.gen()))
+                .collect();
+            let (pubkey, signature) = run_session(private_keys, msg, &mut rng);
+            assert!(verify(&pubkey, msg, &signature));
+            assert!(!verify(&pubkey, b"another message", &signature));
+        }
+    }
+
+    #[test]
+    fn protocol_violations() {
+        let mut rng = XorShiftRng::from_seed([9, 10, 11, 12]);
+        let private_keys: Vec<_> = (0..2).map(|_| priv_key_from_fs(rng.gen())).collect();
+        let pubkeys: Vec<_> = private_keys.iter().map(public_key_from_private).collect();
+
+        assert_eq!(
+            aggregate_public_keys(&pubkeys[..1]).unwrap_err(),
+            MusigError::NotEnoughParticipants
+        );
+        assert_eq!(
+            MusigSigner::new(
+                priv_key_from_fs(private_keys[0].0),
+                pubkeys.clone(),
+                1,
+                &mut rng
+            )
+            .unwrap_err(),
+            MusigError::PublicKeyMismatch
+        );
+
+        let mut first = MusigSigner::new(
+            priv_key_from_fs(private_keys[0].0),
+            pubkeys.clone(),
+            0,
+            &mut rng,
+        )
+        .unwrap();
+        let mut second =
+            MusigSigner::new(priv_key_from_fs(private_keys[1].0), pubkeys, 1, &mut rng).unwrap();
+        assert_eq!(
+            first.sign(b"msg").unwrap_err(),
+            MusigError::NoncesNotReceived
+        );
+
+        let commitments = vec![first.nonce_commitment(), second.nonce_commitment()];
+        let first_nonce = first.receive_commitments(commitments.clone()).unwrap();
+        let second_nonce = second.receive_commitments(commitments).unwrap();
+
+        // The nonce is changed after the commitment was published.
+        let wrong_nonce = first_nonce.add(&first_nonce, &JUBJUB_PARAMS);
+        assert_eq!(
+            second
+                .receive_nonces(vec![wrong_nonce, second_nonce.clone()])
+                .unwrap_err(),
+            MusigError::CommitmentMismatch(0)
+        );
+
+        let nonces = vec![first_nonce, second_nonce];
+        first.receive_nonces(nonces.clone()).unwrap();
+        second.receive_nonces(nonces).unwrap();
+
+        let first_signature = first.sign(b"msg").unwrap();
+        assert_eq!(first.sign(b"msg").unwrap_err(), MusigError::NonceUsed);
+        let second_signature = second.sign(b"another msg").unwrap();
+        assert_eq!(
+            first
+                .aggregate_signatures(b"msg", &[first_signature, second_signature])
+                .unwrap_err(),
+            MusigError::InvalidPartialSignature(1)
+        );
+    }
+}
@@ -0,0 +1,229 @@
+//! Health scoring of the Ethereum providers used by `MultiplexerEthereumClient`.
+//!
+//! Every provider is scored separately for every class of requests, since e.g. a provider
+//! rate-limiting the heavy `eth_getLogs` requests may still serve the plain reads well.
+//! The score is the average latency of the successful requests increased proportionally
+//! to the recent error rate, the lower the better. Providers which have not served any request
+//! of the class yet have no score and are tried after the scored ones. After several consecutive
+//! failures the provider is put on cooldown and is only used when the other providers fail as well.
+
+// Built-in deps
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Weight of the latest request in the moving averages of the latency and the error rate.
+const EWMA_WEIGHT: f64 = 0.2;
+/// Score multiplier for the provider failing all the requests.
+const ERROR_RATE_PENALTY: f64 = 10.0;
+/// Amount of consecutive failures after which the provider is put on cooldown.
+const COOLDOWN_FAILURES: u32 = 3;
+/// Period during which the failing provider is used as the last resort only.
+const COOLDOWN_PERIOD: Duration = Duration::from_secs(30);
+
+/// Class of the requests the providers are scored for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestClass {
+    /// Plain state queries: blocks, balances, nonces, receipts, contract calls.
+    Read,
+    /// Logs queries, which are much heavier and often limited by the providers.
+    Logs,
+    /// Broadcasting and signing of the transactions.
+    Send,
+}
+
+impl RequestClass {
+    pub const ALL: [RequestClass; 3] = [RequestClass::Read, RequestClass::Logs, RequestClass::Send];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RequestClass::Read => "read",
+            RequestClass::Logs => "logs",
+            RequestClass::Send => "send",
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl fmt::Display for RequestClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Health of the provider for a single request class.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ClassHealth {
+    /// Moving average of the successful requests latency in milliseconds.
+    pub latency_ms: f64,
+    /// Moving average of the share of the failed requests.
+    pub error_rate: f64,
+    /// Amount of the successful requests.
+    pub successes: u64,
+    /// Amount of failures since the last successful request.
+    pub consecutive_failures: u32,
+    /// Moment until which the provider is on cooldown.
+    pub cooldown_until: Option<Instant>,
+}
+
+impl ClassHealth {
+    /// Whether the provider has served any request, i.e. whether its score is known.
+    pub fn has_succeeded(&self) -> bool {
+        self.successes > 0
+    }
+
+    /// Score of the provider, the lower the better. Meaningful only if the provider has succeeded.
+    pub fn score(&self) -> f64 {
+        self.latency_ms * (1.0 + ERROR_RATE_PENALTY * self.error_rate)
+    }
+
+    /// Whether the provider is on cooldown at the given moment.
+    pub fn is_cooling_down(&self, now: Instant) -> bool {
+        self.cooldown_until
+            .map(|until| now < until)
+            .unwrap_or_default()
+    }
+
+    fn report_success(&mut self, latency: Duration) {
+        let latency_ms = latency.as_secs_f64() * 1000.0;
+        self.latency_ms = if self.successes == 0 {
+            latency_ms
+        } else {
+            ewma(self.latency_ms, latency_ms)
+        };
+        self.error_rate = ewma(self.error_rate, 0.0);
+        self.successes += 1;
+        self.consecutive_failures = 0;
+        self.cooldown_until = None;
+    }
+
+    fn report_failure(&mut self, now: Instant) {
+        self.error_rate = ewma(self.error_rate, 1.0);
+        self.consecutive_failures += 1;
+        if self.consecutive_failures >= COOLDOWN_FAILURES {
+            self.cooldown_until = Some(now + COOLDOWN_PERIOD);
+        }
+    }
+}
+
+/// Health of the provider for all the request classes.
+#[derive(Debug, Default)]
+pub struct ProviderHealth {
+    classes: Mutex<[ClassHealth; 3]>,
+}
+
+impl ProviderHealth {
+    pub fn get(&self, class: RequestClass) -> ClassHealth {
+        self.classes.lock().unwrap()[class.index()]
+    }
+
+    pub fn report_success(&self, class: RequestClass, latency: Duration) {
+        self.classes.lock().unwrap()[class.index()].report_success(latency);
+    }
+
+    pub fn report_failure(&self, class: RequestClass) {
+        self.classes.lock().unwrap()[class.index()].report_failure(Instant::now());
+    }
+}
+
+/// Returns the order in which the providers should be tried for the request of the given class.
+///
+/// The providers on cooldown go last. The preferred provider (e.g. the one with the longest
+/// chain according to the gateway watcher) goes first among the rest, followed by the others
+/// ordered by their score. Providers which have never succeeded go after the scored ones.
+/// Providers keep their original order in case of a tie.
+pub fn providers_order(
+    health: &[ClassHealth],
+    preferred: usize,
+    now: Instant,
+) -> impl Iterator<Item = usize> {
+    let mut order: Vec<_> = (0..health.len()).collect();
+    order.sort_by(|&lhs, &rhs| {
+        let key = |idx: usize| {
+            (
+                health[idx].is_cooling_down(now),
+                idx != preferred,
+                !health[idx].has_succeeded(),
+            )
+        };
+        key(lhs).cmp(&key(rhs)).then_with(|| {
+            health[lhs]
+                .score()
+                .partial_cmp(&health[rhs].score())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })
+    });
+    order.into_iter()
+}
+
+fn ewma(average: f64, value: f64) -> f64 {
+    average * (1.0 - EWMA_WEIGHT) + value * EWMA_WEIGHT
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn health(latency_ms: f64, error_rate: f64) -> ClassHealth {
+        ClassHealth {
+            latency_ms,
+            error_rate,
+            successes: 1,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn providers_ordering() {
+        let now = Instant::now();
+        let mut providers = vec![health(100.0, 0.0), health(50.0, 0.0), health(60.0, 0.5)];
+
+        // Preferred provider goes first, the rest are ordered by score.
+        let order: Vec<_> = providers_order(&providers, 0, now).collect();
+        assert_eq!(order, vec![0, 1, 2]);
+        let order: Vec<_> = providers_order(&providers, 2, now).collect();
+        assert_eq!(order, vec![2, 1, 0]);
+
+        // Providers on cooldown are used as the last resort.
+        for _ in 0..COOLDOWN_FAILURES {
+            providers[0].report_failure(now);
+        }
+        let order: Vec<_> = providers_order(&providers, 0, now).collect();
+        assert_eq!(order, vec![1, 2, 0]);
+        let order: Vec<_> = providers_order(&providers, 0, now + COOLDOWN_PERIOD).collect();
+        assert_eq!(order[0], 0);
+
+        // Successful request ends the cooldown.
+        providers[0].report_success(Duration::from_millis(100));
+        assert!(!providers[0].is_cooling_down(now));
+        assert_eq!(providers[0].consecutive_failures, 0);
+    }
+    #[test]
+    fn providers_without_successes_go_last() {
+        let now = Instant::now();
+        let mut providers = vec![
+            ClassHealth::default(),
+            health(100.0, 0.0),
+            health(50.0, 0.5),
+        ];
+
+        // Unknown provider doesn't outrank the scored ones despite its zero latency.
+        let order: Vec<_> = providers_order(&providers, 1, now).collect();
+        assert_eq!(order, vec![1, 2, 0]);
+
+        // Failures alone don't make the provider scored.
+        providers[0].report_failure(now);
+        assert!(!providers[0].has_succeeded());
+        let order: Vec<_> = providers_order(&providers, 2, now).collect();
+        assert_eq!(order, vec![2, 1, 0]);
+
+        // Once the provider succeeds, it is ranked by its score.
+        providers[0].report_success(Duration::from_millis(10));
+        assert!(providers[0].has_succeeded());
+        let order: Vec<_> = providers_order(&providers, 1, now).collect();
+        assert_eq!(order, vec![1, 0, 2]);
+    }
+}
@@ -22,7 +22,9 @@ use web3::{
 // Workspace uses
 use zksync_eth_signer::{raw_ethereum_tx::RawTransaction, EthereumSigner};
 
-use crate::ethereum_gateway::{ExecutedTxStatus, FailureInfo, SignedCallResult};
+use crate::ethereum_gateway::{
+    CallOutcome, ExecutedTxStatus, FailureInfo, FeeHistory, SignedCallResult,
+};
 /// Gas limit value to be used in transaction if for some reason
 /// gas limit was not set for it.
 ///
@@ -153,6 +155,37 @@ impl<S: EthereumSigner> ETHDirectClient<S> {
         Ok(network_gas_price)
     }
 
+    pub async fn fee_history(
+        &self,
+        block_count: u64,
+        reward_percentile: f64,
+    ) -> Result<FeeHistory, anyhow::Error> {
+        #[cfg(feature = "with-metrics")]
+        let start = Instant::now();
+        let history = self
+            .inner
+            .web3
+            .eth()
+            .fee_history(
+                block_count.into(),
+                BlockNumber::Latest,
+                Some(vec![reward_percentile]),
+            )
+            .await?;
+        let priority_fees = history
+            .reward
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|rewards| rewards.first().copied())
+            .collect();
+        #[cfg(feature = "with-metrics")]
+        metrics::histogram!("eth_client.direct.fee_history", start.elapsed());
+        Ok(FeeHistory {
+            base_fee_per_gas: history.base_fee_per_gas,
+            priority_fees,
+        })
+    }
+
     pub async fn sign_prepared_tx(
         &self,
         data: Vec<u8>,
@@ -171,10 +204,11 @@ impl<S: EthereumSigner> ETHDirectClient<S> {
         #[cfg(feature = "with-metrics")]
         let start = Instant::now();
 
-        // fetch current gas_price
-        let gas_price = match options.gas_price {
-            Some(gas_price) => gas_price,
-            None => self.get_gas_price().await?,
+        // fetch current gas_price, for EIP-1559 transactions the max fee per gas is used instead.
+        let gas_price = match (options.max_fee_per_gas, options.gas_price) {
+            (Some(max_fee_per_gas), _) => max_fee_per_gas,
+            (None, Some(gas_price)) => gas_price,
+            (None, None) => self.get_gas_price().await?,
         };
 
         let nonce = match options.nonce {
@@ -200,16 +234,16 @@ impl<S: EthereumSigner> ETHDirectClient<S> {
         // form and sign tx
         let tx = RawTransaction {
             chain_id: self.inner.chain_id,
-            transaction_type: None,
-            access_list: None,
-            max_fee_per_gas: Default::default(),
+            transaction_type: options.transaction_type,
+            access_list: options.access_list,
+            max_fee_per_gas: options.max_fee_per_gas,
             nonce,
             to: Some(contract_addr),
             value: options.value.unwrap_or_default(),
             gas_price,
             gas,
             data,
-            max_priority_fee_per_gas: Default::default(),
+            max_priority_fee_per_gas: options.max_priority_fee_per_gas,
         };
 
         let signed_tx = self.inner.eth_signer.sign_transaction(tx).await?;
@@ -259,6 +293,47 @@ impl<S: EthereumSigner> ETHDirectClient<S> {
         Ok(receipt)
     }
 
+    pub async fn simulate_tx(
+        &self,
+        data: Vec<u8>,
+        gas: Option<U256>,
+    ) -> Result<CallOutcome, anyhow::Error> {
+        self.simulate_tx_for_addr(data, self.inner.contract_addr, gas)
+            .await
+    }
+
+    pub async fn simulate_tx_for_addr(
+        &self,
+        data: Vec<u8>,
+        contract_addr: H160,
+        gas: Option<U256>,
+    ) -> Result<CallOutcome, anyhow::Error> {
+        #[cfg(feature = "with-metrics")]
+        let start = Instant::now();
+        let call_request = web3::types::CallRequest {
+            from: Some(self.inner.sender_account),
+            to: Some(contract_addr),
+            gas,
+            gas_price: None,
+            value: None,
+            data: Some(data.into()),
+            transaction_type: None,
+            access_list: None,
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
+        };
+
+        let outcome = match self.inner.web3.eth().call(call_request, None).await {
+            Ok(output) => CallOutcome::Success(output.0),
+            // Node reports the reverted call as an RPC error containing the revert reason.
+            Err(web3::Error::Rpc(e)) => CallOutcome::Reverted(e.message),
+            Err(e) => return Err(e.into()),
+        };
+        #[cfg(feature = "with-metrics")]
+        metrics::histogram!("eth_client.direct.simulate_tx", start.elapsed());
+        Ok(outcome)
+    }
+
     pub async fn failure_reason(
         &self,
         tx_hash: H256,
@@ -346,6 +421,15 @@ impl<S: EthereumSigner> ETHDirectClient<S> {
         self.eth_balance(self.inner.sender_account).await
     }
 
+    pub async fn has_code(&self, address: Address) -> Result<bool, anyhow::Error> {
+        #[cfg(feature = "with-metrics")]
+        let start = Instant::now();
+        let code = self.inner.web3.eth().code(address, None).await?;
+        #[cfg(feature = "with-metrics")]
+        metrics::histogram!("eth_client.direct.has_code", start.elapsed());
+        Ok(!code.0.is_empty())
+    }
+
     pub async fn allowance(
         &self,
         token_address: Address,
@@ -442,6 +526,7 @@ impl<S: EthereumSigner> ETHDirectClient<S> {
                 };
                 let confirmations = current_block.saturating_sub(tx_block_number.as_u64());
                 let success = status.as_u64() == 1;
+                let gas_used = receipt.as_ref().and_then(|receipt| receipt.gas_used);
 
                 // Set the receipt only for failures.
                 let receipt = if success {
@@ -454,6 +539,7 @@ impl<S: EthereumSigner> ETHDirectClient<S> {
                     confirmations,
                     success,
                     receipt,
+                    gas_used,
                 }))
             }
             _ => Ok(None),
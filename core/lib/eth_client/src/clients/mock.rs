@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use anyhow::Error;
 use ethabi::{Address, Contract};
@@ -7,24 +7,38 @@ use tokio::sync::RwLock;
 use web3::contract::tokens::{Detokenize, Tokenize};
 use web3::contract::Options;
 use web3::transports::Http;
-use web3::types::{BlockId, Filter, Log, Transaction, U64};
+use web3::types::{Block, BlockId, Filter, Log, Transaction, U64};
 
 use zksync_types::{TransactionReceipt, H160, H256, U256};
 
 use crate::{
-    ethereum_gateway::{ExecutedTxStatus, FailureInfo},
+    ethereum_gateway::{CallOutcome, ExecutedTxStatus, FailureInfo, FeeHistory},
     SignedCallResult,
 };
 
-#[derive(Debug)]
-struct MockEthereumInner {
+/// State of the mocked chain, modified via any clone of the client.
+#[derive(Debug, Clone, Copy)]
+struct MockChainState {
     block_number: u64,
     gas_price: U256,
+    sender_balance: U256,
+}
+
+#[derive(Debug)]
+struct MockEthereumInner {
+    chain: Mutex<MockChainState>,
     tx_statuses: Arc<RwLock<HashMap<H256, ExecutedTxStatus>>>,
     sent_txs: Arc<RwLock<HashSet<Vec<u8>>>>,
+    /// Data of the transactions simulated via `eth_call`, in order.
+    simulated_txs: Arc<RwLock<Vec<Vec<u8>>>>,
+    /// Nonces of the latest mined block and of the pending block.
+    nonces: Arc<RwLock<(U256, U256)>>,
+    /// Addresses which code was requested, in order. The mock has no contracts deployed.
+    code_requests: Arc<RwLock<Vec<Address>>>,
 }
 
 /// Mock Ethereum client is capable of recording all the incoming requests for the further analysis.
+/// The clones of the client share the mocked chain.
 #[derive(Debug, Default, Clone)]
 pub struct MockEthereum {
     inner: Arc<MockEthereumInner>,
@@ -33,15 +47,29 @@ pub struct MockEthereum {
 impl Default for MockEthereumInner {
     fn default() -> Self {
         Self {
-            block_number: 1,
-            gas_price: 100.into(),
+            chain: Mutex::new(MockChainState {
+                block_number: 1,
+                gas_price: 100.into(),
+                sender_balance: U256::exp10(18),
+            }),
             tx_statuses: Default::default(),
             sent_txs: Default::default(),
+            simulated_txs: Default::default(),
+            nonces: Default::default(),
+            code_requests: Default::default(),
         }
     }
 }
 
 impl MockEthereum {
+    fn chain(&self) -> MockChainState {
+        *self.inner.chain.lock().unwrap()
+    }
+
+    fn update_chain(&self, update: impl FnOnce(&mut MockChainState)) {
+        update(&mut self.inner.chain.lock().unwrap());
+    }
+
     /// A fake `sha256` hasher, which calculates an `std::hash` instead.
     /// This is done for simplicity and it's also much faster.
     pub fn fake_sha256(data: &[u8]) -> H256 {
@@ -65,6 +93,11 @@ impl MockEthereum {
         );
     }
 
+    /// Returns the data of the simulated transactions.
+    pub async fn simulated_txs(&self) -> Vec<Vec<u8>> {
+        self.inner.simulated_txs.read().await.clone()
+    }
+
     /// Adds an response for the sent transaction for `ETHSender` to receive.
     pub async fn add_execution(&mut self, hash: &H256, status: &ExecutedTxStatus) {
         self.inner
@@ -77,24 +110,26 @@ impl MockEthereum {
     /// Increments the blocks by a provided `confirmations` and marks the sent transaction
     /// as a success.
     pub async fn add_successfull_execution(&mut self, tx_hash: H256, confirmations: u64) {
-        Arc::get_mut(&mut self.inner).unwrap().block_number += confirmations;
+        self.update_chain(|chain| chain.block_number += confirmations);
 
         let status = ExecutedTxStatus {
             confirmations,
             success: true,
             receipt: None,
+            gas_used: None,
         };
         self.inner.tx_statuses.write().await.insert(tx_hash, status);
     }
 
     /// Same as `add_successfull_execution`, but marks the transaction as a failure.
     pub async fn add_failed_execution(&mut self, hash: &H256, confirmations: u64) {
-        Arc::get_mut(&mut self.inner).unwrap().block_number += confirmations;
+        self.update_chain(|chain| chain.block_number += confirmations);
 
         let status = ExecutedTxStatus {
             confirmations,
             success: false,
             receipt: Some(Default::default()),
+            gas_used: None,
         };
         self.inner.tx_statuses.write().await.insert(*hash, status);
     }
@@ -107,21 +142,58 @@ impl MockEthereum {
     }
 
     pub async fn block_number(&self) -> anyhow::Result<U64> {
-        Ok(self.inner.block_number.into())
+        Ok(self.chain().block_number.into())
+    }
+
+    pub async fn block(&self, _id: BlockId) -> anyhow::Result<Option<Block<H256>>> {
+        Ok(None)
     }
 
     pub async fn set_block_number(&mut self, val: U64) -> anyhow::Result<U64> {
-        Arc::get_mut(&mut self.inner).unwrap().block_number = val.as_u64();
-        Ok(self.inner.block_number.into())
+        self.update_chain(|chain| chain.block_number = val.as_u64());
+        Ok(val)
     }
 
     pub async fn get_gas_price(&self) -> anyhow::Result<U256> {
-        Ok(self.inner.gas_price)
+        Ok(self.chain().gas_price)
+    }
+
+    /// Returns the fee history where every block has the base fee equal to the current gas price
+    /// and no priority fees.
+    pub async fn fee_history(
+        &self,
+        block_count: u64,
+        _reward_percentile: f64,
+    ) -> anyhow::Result<FeeHistory> {
+        let chain = self.chain();
+        let block_count = block_count.min(chain.block_number) as usize;
+        Ok(FeeHistory {
+            base_fee_per_gas: vec![chain.gas_price; block_count + 1],
+            priority_fees: vec![U256::zero(); block_count],
+        })
     }
 
     pub async fn set_gas_price(&mut self, val: U256) -> anyhow::Result<U256> {
-        Arc::get_mut(&mut self.inner).unwrap().gas_price = val;
-        Ok(self.inner.gas_price)
+        self.update_chain(|chain| chain.gas_price = val);
+        Ok(val)
+    }
+
+    pub async fn simulate_tx(
+        &self,
+        data: Vec<u8>,
+        _gas: Option<U256>,
+    ) -> Result<CallOutcome, anyhow::Error> {
+        self.inner.simulated_txs.write().await.push(data);
+        Ok(CallOutcome::Success(Vec::new()))
+    }
+
+    pub async fn simulate_tx_for_addr(
+        &self,
+        data: Vec<u8>,
+        _contract_addr: H160,
+        gas: Option<U256>,
+    ) -> Result<CallOutcome, anyhow::Error> {
+        self.simulate_tx(data, gas).await
     }
 
     pub async fn send_raw_tx(&self, tx: Vec<u8>) -> Result<H256, anyhow::Error> {
@@ -137,7 +209,10 @@ impl MockEthereum {
         raw_tx: Vec<u8>,
         options: Options,
     ) -> anyhow::Result<SignedCallResult> {
-        let gas_price = options.gas_price.unwrap_or(self.inner.gas_price);
+        let gas_price = options
+            .max_fee_per_gas
+            .or(options.gas_price)
+            .unwrap_or_else(|| self.chain().gas_price);
         let nonce = options.nonce.expect("Nonce must be set for every tx");
 
         // Nonce and gas_price are appended to distinguish the same transactions
@@ -165,24 +240,36 @@ impl MockEthereum {
     }
 
     pub async fn pending_nonce(&self) -> Result<U256, Error> {
-        unreachable!()
+        Ok(self.inner.nonces.read().await.1)
     }
 
     pub async fn current_nonce(&self) -> Result<U256, Error> {
-        unreachable!()
+        Ok(self.inner.nonces.read().await.0)
+    }
+
+    /// Sets the account nonces based on the latest mined and the pending blocks.
+    /// The nonces are shared between the clones of the client.
+    pub async fn set_nonces(&mut self, current: U256, pending: U256) {
+        *self.inner.nonces.write().await = (current, pending);
     }
 
     pub async fn sender_eth_balance(&self) -> Result<U256, Error> {
-        unreachable!()
+        Ok(self.chain().sender_balance)
+    }
+
+    pub async fn set_sender_balance(&mut self, val: U256) {
+        self.update_chain(|chain| chain.sender_balance = val);
     }
 
+    /// The contract address does not affect the mock transaction, so it's
+    /// signed the same way as the transaction to the main contract.
     pub async fn sign_prepared_tx_for_addr(
         &self,
-        _data: Vec<u8>,
+        data: Vec<u8>,
         _contract_addr: H160,
-        _options: Options,
+        options: Options,
     ) -> Result<SignedCallResult, Error> {
-        unreachable!()
+        self.sign_prepared_tx(data, options).await
     }
 
     pub async fn tx_receipt(&self, _tx_hash: H256) -> Result<Option<TransactionReceipt>, Error> {
@@ -193,6 +280,16 @@ impl MockEthereum {
         unreachable!()
     }
 
+    pub async fn has_code(&self, address: Address) -> Result<bool, Error> {
+        self.inner.code_requests.write().await.push(address);
+        Ok(false)
+    }
+
+    /// Returns the addresses which code was requested, in order.
+    pub async fn code_requests(&self) -> Vec<Address> {
+        self.inner.code_requests.read().await.clone()
+    }
+
     pub async fn contract_balance(
         &self,
         _token_address: Address,
@@ -270,3 +367,25 @@ impl MockEthereum {
         unreachable!()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checks that the chain state set via one clone of the client is observed by the others.
+    #[tokio::test]
+    async fn clones_share_chain() {
+        let mut ethereum = MockEthereum::default();
+        let clone = ethereum.clone();
+
+        ethereum.set_sender_balance(42.into()).await;
+        ethereum.set_gas_price(7.into()).await.unwrap();
+        ethereum
+            .add_successfull_execution(H256::repeat_byte(1), 5)
+            .await;
+
+        assert_eq!(clone.sender_eth_balance().await.unwrap(), 42.into());
+        assert_eq!(clone.get_gas_price().await.unwrap(), 7.into());
+        assert_eq!(clone.block_number().await.unwrap(), 6.into());
+    }
+}
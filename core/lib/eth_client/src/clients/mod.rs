@@ -1,3 +1,4 @@
+pub mod health;
 pub mod http_client;
 pub mod mock;
 pub mod multiplexer;
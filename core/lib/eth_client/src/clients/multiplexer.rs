@@ -1,21 +1,27 @@
 use ethabi::Contract;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::Instant;
 use web3::{
     contract::tokens::{Detokenize, Tokenize},
     contract::Options,
     transports::Http,
-    types::{Address, BlockId, Filter, Log, Transaction, U64},
+    types::{Address, Block, BlockId, Filter, Log, Transaction, U64},
 };
-use zksync_eth_signer::PrivateKeySigner;
+use zksync_eth_signer::OperatorSigner;
 use zksync_types::{TransactionReceipt, H160, H256, U256};
 
-use crate::ethereum_gateway::{ExecutedTxStatus, FailureInfo, SignedCallResult};
+use super::health::{providers_order, ProviderHealth, RequestClass};
+use crate::ethereum_gateway::{
+    CallOutcome, ExecutedTxStatus, FailureInfo, FeeHistory, SignedCallResult,
+};
 use crate::ETHDirectClient;
 
 #[derive(Debug, Default)]
 struct MultiplexerEthereumClientInner {
-    clients: Vec<(String, ETHDirectClient<PrivateKeySigner>)>,
+    clients: Vec<(String, ETHDirectClient<OperatorSigner>)>,
+    /// Health of the clients, in the same order as `clients`.
+    health: Vec<ProviderHealth>,
     preferred: AtomicUsize,
 }
 
@@ -24,12 +30,41 @@ pub struct MultiplexerEthereumClient {
     inner: Arc<MultiplexerEthereumClientInner>,
 }
 
+/// Performs the request using the clients in the order of their health for the request class,
+/// failing over to the next client in case of error.
 macro_rules! multiple_call {
-    ($self:expr, $func:ident($($attr:expr),*)) => {
-        for (name, client) in $self.clients() {
+    ($self:expr, $class:ident, $func:ident($($attr:expr),*)) => {
+        let class = RequestClass::$class;
+        for (attempt, idx) in $self.clients_order(class).enumerate() {
+            let (name, client) = &$self.inner.clients[idx];
+            let health = &$self.inner.health[idx];
+            let start = Instant::now();
             match client.$func($($attr.clone()),*).await {
-                Ok(res) => return Ok(res),
-                Err(err) => vlog::error!("Error in interface: {}, {} ", name, err),
+                Ok(res) => {
+                    health.report_success(class, start.elapsed());
+                    #[cfg(feature = "with-metrics")]
+                    {
+                        let labels = [("provider", name.clone()), ("class", class.to_string())];
+                        metrics::histogram!("eth_client.multiplexed.request", start.elapsed(), &labels);
+                        metrics::increment_counter!("eth_client.multiplexed.served", &labels);
+                        if attempt > 0 {
+                            metrics::increment_counter!("eth_client.multiplexed.failover", &labels);
+                        }
+                    }
+                    #[cfg(not(feature = "with-metrics"))]
+                    let _ = attempt;
+                    return Ok(res);
+                }
+                Err(err) => {
+                    health.report_failure(class);
+                    vlog::error!("Error in interface: {}, {} ", name, err);
+                    #[cfg(feature = "with-metrics")]
+                    metrics::increment_counter!(
+                        "eth_client.multiplexed.failed",
+                        "provider" => name.clone(),
+                        "class" => class.to_string()
+                    );
+                }
             }
         }
         anyhow::bail!("All interfaces was wrong please try again")
@@ -44,12 +79,11 @@ impl MultiplexerEthereumClient {
     pub fn add_client(
         &mut self,
         name: String,
-        client: ETHDirectClient<PrivateKeySigner>,
+        client: ETHDirectClient<OperatorSigner>,
     ) -> &mut Self {
-        Arc::get_mut(&mut self.inner)
-            .unwrap()
-            .clients
-            .push((name, client));
+        let inner = Arc::get_mut(&mut self.inner).unwrap();
+        inner.clients.push((name, client));
+        inner.health.push(ProviderHealth::default());
         self
     }
 
@@ -61,7 +95,7 @@ impl MultiplexerEthereumClient {
         }
     }
 
-    pub fn clients(&self) -> impl Iterator<Item = (&str, &ETHDirectClient<PrivateKeySigner>)> {
+    pub fn clients(&self) -> impl Iterator<Item = (&str, &ETHDirectClient<OperatorSigner>)> {
         let preferred = self.inner.preferred.load(Ordering::Relaxed);
         self.inner
             .clients
@@ -78,6 +112,50 @@ impl MultiplexerEthereumClient {
             .map(|(name, client)| (name.as_str(), client))
     }
 
+    /// Returns the indices of the clients in the order they should be tried
+    /// for the request of the given class.
+    fn clients_order(&self, class: RequestClass) -> impl Iterator<Item = usize> {
+        let health: Vec<_> = self
+            .inner
+            .health
+            .iter()
+            .map(|health| health.get(class))
+            .collect();
+        let preferred = self.inner.preferred.load(Ordering::Relaxed);
+        providers_order(&health, preferred, Instant::now())
+    }
+
+    /// Reports the health of the clients: moving averages of the latency and the error rate
+    /// for every request class.
+    pub fn report_health(&self) {
+        for ((name, _), health) in self.inner.clients.iter().zip(&self.inner.health) {
+            for class in RequestClass::ALL.iter().copied() {
+                let class_health = health.get(class);
+                if class_health.is_cooling_down(Instant::now()) {
+                    vlog::warn!(
+                        "Ethereum Gateway `{}` is on cooldown for the {} requests",
+                        name,
+                        class
+                    );
+                }
+                #[cfg(feature = "with-metrics")]
+                {
+                    let labels = [("provider", name.clone()), ("class", class.to_string())];
+                    metrics::gauge!(
+                        "eth_client.multiplexed.latency_ms",
+                        class_health.latency_ms,
+                        &labels
+                    );
+                    metrics::gauge!(
+                        "eth_client.multiplexed.error_rate",
+                        class_health.error_rate,
+                        &labels
+                    );
+                }
+            }
+        }
+    }
+
     pub fn create_contract(
         &self,
         address: Address,
@@ -91,23 +169,35 @@ impl MultiplexerEthereumClient {
     }
 
     pub async fn pending_nonce(&self) -> Result<U256, anyhow::Error> {
-        multiple_call!(self, pending_nonce());
+        multiple_call!(self, Read, pending_nonce());
     }
 
     pub async fn current_nonce(&self) -> Result<U256, anyhow::Error> {
-        multiple_call!(self, current_nonce());
+        multiple_call!(self, Read, current_nonce());
     }
 
     pub async fn block_number(&self) -> Result<U64, anyhow::Error> {
-        multiple_call!(self, block_number());
+        multiple_call!(self, Read, block_number());
+    }
+
+    pub async fn block(&self, id: BlockId) -> Result<Option<Block<H256>>, anyhow::Error> {
+        multiple_call!(self, Read, block(id));
     }
 
     pub async fn get_gas_price(&self) -> Result<U256, anyhow::Error> {
-        multiple_call!(self, get_gas_price());
+        multiple_call!(self, Read, get_gas_price());
+    }
+
+    pub async fn fee_history(
+        &self,
+        block_count: u64,
+        reward_percentile: f64,
+    ) -> Result<FeeHistory, anyhow::Error> {
+        multiple_call!(self, Read, fee_history(block_count, reward_percentile));
     }
 
     pub async fn sender_eth_balance(&self) -> Result<U256, anyhow::Error> {
-        multiple_call!(self, sender_eth_balance());
+        multiple_call!(self, Read, sender_eth_balance());
     }
 
     pub async fn sign_prepared_tx(
@@ -115,7 +205,7 @@ impl MultiplexerEthereumClient {
         data: Vec<u8>,
         options: Options,
     ) -> Result<SignedCallResult, anyhow::Error> {
-        multiple_call!(self, sign_prepared_tx(data, options));
+        multiple_call!(self, Send, sign_prepared_tx(data, options));
     }
 
     pub async fn sign_prepared_tx_for_addr(
@@ -126,30 +216,52 @@ impl MultiplexerEthereumClient {
     ) -> Result<SignedCallResult, anyhow::Error> {
         multiple_call!(
             self,
+            Send,
             sign_prepared_tx_for_addr(data, contract_addr, options)
         );
     }
 
+    pub async fn simulate_tx(
+        &self,
+        data: Vec<u8>,
+        gas: Option<U256>,
+    ) -> Result<CallOutcome, anyhow::Error> {
+        multiple_call!(self, Read, simulate_tx(data, gas));
+    }
+
+    pub async fn simulate_tx_for_addr(
+        &self,
+        data: Vec<u8>,
+        contract_addr: H160,
+        gas: Option<U256>,
+    ) -> Result<CallOutcome, anyhow::Error> {
+        multiple_call!(self, Read, simulate_tx_for_addr(data, contract_addr, gas));
+    }
+
     pub async fn send_raw_tx(&self, tx: Vec<u8>) -> Result<H256, anyhow::Error> {
-        multiple_call!(self, send_raw_tx(tx));
+        multiple_call!(self, Send, send_raw_tx(tx));
     }
 
     pub async fn tx_receipt(
         &self,
         tx_hash: H256,
     ) -> Result<Option<TransactionReceipt>, anyhow::Error> {
-        multiple_call!(self, tx_receipt(tx_hash));
+        multiple_call!(self, Read, tx_receipt(tx_hash));
     }
 
     pub async fn failure_reason(
         &self,
         tx_hash: H256,
     ) -> Result<Option<FailureInfo>, anyhow::Error> {
-        multiple_call!(self, failure_reason(tx_hash));
+        multiple_call!(self, Read, failure_reason(tx_hash));
     }
 
     pub async fn eth_balance(&self, address: Address) -> Result<U256, anyhow::Error> {
-        multiple_call!(self, eth_balance(address));
+        multiple_call!(self, Read, eth_balance(address));
+    }
+
+    pub async fn has_code(&self, address: Address) -> Result<bool, anyhow::Error> {
+        multiple_call!(self, Read, has_code(address));
     }
 
     pub async fn allowance(
@@ -157,7 +269,7 @@ impl MultiplexerEthereumClient {
         token_address: Address,
         erc20_abi: Contract,
     ) -> Result<U256, anyhow::Error> {
-        multiple_call!(self, allowance(token_address, erc20_abi));
+        multiple_call!(self, Read, allowance(token_address, erc20_abi));
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -179,6 +291,7 @@ impl MultiplexerEthereumClient {
     {
         multiple_call!(
             self,
+            Read,
             call_contract_function(func, params, from, options, block, token_address, erc20_abi)
         );
     }
@@ -199,6 +312,7 @@ impl MultiplexerEthereumClient {
     {
         multiple_call!(
             self,
+            Read,
             call_main_contract_function(func, params, from, options, block)
         );
     }
@@ -208,11 +322,11 @@ impl MultiplexerEthereumClient {
         hash: H256,
         current_block: Option<u64>,
     ) -> Result<Option<ExecutedTxStatus>, anyhow::Error> {
-        multiple_call!(self, get_tx_status(hash, current_block));
+        multiple_call!(self, Read, get_tx_status(hash, current_block));
     }
 
     pub async fn logs(&self, filter: Filter) -> anyhow::Result<Vec<Log>> {
-        multiple_call!(self, logs(filter));
+        multiple_call!(self, Logs, logs(filter));
     }
 
     pub fn encode_tx_data<P: Tokenize + Clone>(&self, func: &str, params: P) -> Vec<u8> {
@@ -224,6 +338,6 @@ impl MultiplexerEthereumClient {
     }
 
     pub async fn get_tx(&self, hash: H256) -> Result<Option<Transaction>, anyhow::Error> {
-        multiple_call!(self, get_tx(hash));
+        multiple_call!(self, Read, get_tx(hash));
     }
 }
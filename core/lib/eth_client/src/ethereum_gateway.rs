@@ -1,12 +1,12 @@
 use web3::contract::tokens::{Detokenize, Tokenize};
 use web3::contract::{Contract, Options};
 use web3::transports::Http;
-use web3::types::{Address, BlockId, Filter, Log, Transaction, U64};
+use web3::types::{Address, Block, BlockId, Filter, Log, Transaction, U64};
 
 use std::fmt::Debug;
 use zksync_config::{ETHClientConfig, ETHSenderConfig};
 use zksync_contracts::zksync_contract;
-use zksync_eth_signer::PrivateKeySigner;
+use zksync_eth_signer::{JsonRpcSigner, OperatorSigner, PrivateKeySigner};
 use zksync_types::{TransactionReceipt, H160, H256, U256};
 
 use crate::clients::mock::MockEthereum;
@@ -21,6 +21,15 @@ pub struct SignedCallResult {
     pub hash: H256,
 }
 
+/// Fees paid within the recent Ethereum blocks, as reported by `eth_feeHistory`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FeeHistory {
+    /// Base fee per gas of every requested block, followed by the base fee of the next block.
+    pub base_fee_per_gas: Vec<U256>,
+    /// Priority fee per gas at the requested percentile for every requested block.
+    pub priority_fees: Vec<U256>,
+}
+
 /// State of the executed Ethereum transaction.
 #[derive(Debug, Clone)]
 pub struct ExecutedTxStatus {
@@ -31,7 +40,18 @@ pub struct ExecutedTxStatus {
     /// Receipt for a transaction. Will be set to `Some` only if the transaction
     /// failed during execution.
     pub receipt: Option<TransactionReceipt>,
+    /// Amount of gas used by the transaction, if reported by the node.
+    pub gas_used: Option<U256>,
+}
+/// Outcome of the transaction simulated via `eth_call`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CallOutcome {
+    /// Transaction would succeed, returning the given data.
+    Success(Vec<u8>),
+    /// Transaction would be reverted with the given reason.
+    Reverted(String),
 }
+
 /// Information about transaction failure.
 #[derive(Debug, Clone)]
 pub struct FailureInfo {
@@ -43,7 +63,7 @@ pub struct FailureInfo {
 
 #[derive(Debug, Clone)]
 pub enum EthereumGateway {
-    Direct(ETHDirectClient<PrivateKeySigner>),
+    Direct(ETHDirectClient<OperatorSigner>),
     Multiplexed(MultiplexerEthereumClient),
     Mock(MockEthereum),
 }
@@ -53,6 +73,46 @@ impl EthereumGateway {
         eth_client_config: &ETHClientConfig,
         eth_sender_config: &ETHSenderConfig,
         main_contract: Address,
+    ) -> Self {
+        let sender = &eth_sender_config.sender;
+        let signer = match &sender.remote_signer_url {
+            Some(url) => {
+                JsonRpcSigner::with_address(url.as_str(), sender.operator_commit_eth_addr, None)
+                    .with_timeout(sender.remote_signer_timeout())
+                    .into()
+            }
+            None => PrivateKeySigner::new(sender.operator_private_key).into(),
+        };
+
+        Self::from_config_with_signer(
+            eth_client_config,
+            sender.operator_commit_eth_addr,
+            signer,
+            main_contract,
+        )
+    }
+
+    /// Creates a gateway signing the transactions with the given operator account.
+    pub fn from_config_for_operator(
+        eth_client_config: &ETHClientConfig,
+        operator_address: Address,
+        operator_private_key: H256,
+        main_contract: Address,
+    ) -> Self {
+        Self::from_config_with_signer(
+            eth_client_config,
+            operator_address,
+            PrivateKeySigner::new(operator_private_key).into(),
+            main_contract,
+        )
+    }
+
+    /// Creates a gateway signing the transactions of the operator account with the given signer.
+    pub fn from_config_with_signer(
+        eth_client_config: &ETHClientConfig,
+        operator_address: Address,
+        signer: OperatorSigner,
+        main_contract: Address,
     ) -> Self {
         if eth_client_config.web3_url.len() == 1 {
             let transport = web3::transports::Http::new(&eth_client_config.web3_url()).unwrap();
@@ -60,8 +120,8 @@ impl EthereumGateway {
             EthereumGateway::Direct(ETHDirectClient::new(
                 transport,
                 zksync_contract(),
-                eth_sender_config.sender.operator_commit_eth_addr,
-                PrivateKeySigner::new(eth_sender_config.sender.operator_private_key),
+                operator_address,
+                signer,
                 main_contract,
                 eth_client_config.chain_id,
                 eth_client_config.gas_price_factor,
@@ -77,8 +137,8 @@ impl EthereumGateway {
                     ETHDirectClient::new(
                         transport,
                         contract.clone(),
-                        eth_sender_config.sender.operator_commit_eth_addr,
-                        PrivateKeySigner::new(eth_sender_config.sender.operator_private_key),
+                        operator_address,
+                        signer.clone(),
                         main_contract,
                         eth_client_config.chain_id,
                         eth_client_config.gas_price_factor,
@@ -123,9 +183,23 @@ impl EthereumGateway {
         delegate_call!(self.block_number())
     }
 
+    pub async fn block(&self, id: BlockId) -> Result<Option<Block<H256>>, anyhow::Error> {
+        delegate_call!(self.block(id))
+    }
+
     pub async fn get_gas_price(&self) -> Result<U256, anyhow::Error> {
         delegate_call!(self.get_gas_price())
     }
+
+    /// Returns the fee history of the `block_count` latest blocks with the priority fees
+    /// taken at the `reward_percentile` of every block.
+    pub async fn fee_history(
+        &self,
+        block_count: u64,
+        reward_percentile: f64,
+    ) -> Result<FeeHistory, anyhow::Error> {
+        delegate_call!(self.fee_history(block_count, reward_percentile))
+    }
     /// Returns the account balance.
     pub async fn sender_eth_balance(&self) -> Result<U256, anyhow::Error> {
         delegate_call!(self.sender_eth_balance())
@@ -152,6 +226,27 @@ impl EthereumGateway {
         delegate_call!(self.sign_prepared_tx_for_addr(data, contract_addr, options))
     }
 
+    /// Simulates the transaction of the operator account calling the main contract
+    /// with the given data via `eth_call`, nothing is broadcasted.
+    pub async fn simulate_tx(
+        &self,
+        data: Vec<u8>,
+        gas: Option<U256>,
+    ) -> Result<CallOutcome, anyhow::Error> {
+        delegate_call!(self.simulate_tx(data, gas))
+    }
+
+    /// Simulates the transaction of the operator account calling the given contract
+    /// with the given data via `eth_call`, nothing is broadcasted.
+    pub async fn simulate_tx_for_addr(
+        &self,
+        data: Vec<u8>,
+        contract_addr: H160,
+        gas: Option<U256>,
+    ) -> Result<CallOutcome, anyhow::Error> {
+        delegate_call!(self.simulate_tx_for_addr(data, contract_addr, gas))
+    }
+
     /// Sends the transaction to the Ethereum blockchain.
     /// Transaction is expected to be encoded as the byte sequence.
     pub async fn send_raw_tx(&self, tx: Vec<u8>) -> Result<H256, anyhow::Error> {
@@ -178,6 +273,11 @@ impl EthereumGateway {
         delegate_call!(self.eth_balance(address))
     }
 
+    /// Checks whether there is a contract deployed at the address.
+    pub async fn has_code(&self, address: Address) -> Result<bool, anyhow::Error> {
+        delegate_call!(self.has_code(address))
+    }
+
     pub async fn allowance(
         &self,
         token_address: Address,
@@ -1,5 +1,6 @@
 pub mod clients;
 pub mod ethereum_gateway;
+pub mod new_heads;
 pub use clients::http_client::ETHDirectClient;
 pub use clients::multiplexer::MultiplexerEthereumClient;
 pub use ethereum_gateway::{EthereumGateway, SignedCallResult};
@@ -0,0 +1,204 @@
+//! Following of the new Ethereum blocks.
+//!
+//! Block numbers are received via the `newHeads` WebSocket subscription if the WebSocket URL
+//! of the node is configured. If the subscription is not available, breaks or stalls, the poll ticks
+//! are emitted instead until the subscription is re-established, so the node is polled by the consumer.
+
+// Built-in deps
+use std::time::{Duration, Instant};
+// External uses
+use anyhow::format_err;
+use futures::StreamExt;
+use tokio::{sync::mpsc, task::JoinHandle, time};
+
+/// Capacity of the channel with the new heads.
+const NEW_HEADS_CHANNEL_CAPACITY: usize = 32;
+/// For how long the poll ticks are emitted before trying to subscribe again.
+const RESUBSCRIBE_DELAY: Duration = Duration::from_secs(60);
+/// Time without the new blocks after which the subscription is considered stalled.
+/// Ethereum blocks are produced every 12 seconds, so it's a few missed blocks.
+const SUBSCRIPTION_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Event of the new Ethereum blocks follower.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NewHead {
+    /// Number of the new block received via the subscription.
+    Block(u64),
+    /// Poll interval elapsed while the subscription is not available, the node has to be polled.
+    PollTick,
+}
+
+/// Spawns the task sending the new Ethereum blocks to the returned channel.
+///
+/// Blocks are followed via the `newHeads` subscription of the `ws_url` node. While the subscription
+/// is not available, `NewHead::PollTick` is sent every `poll_interval` instead.
+/// Every block number is sent once, but the numbers are not guaranteed to be consecutive,
+/// e.g. after the reorg. The task stops once the receiver is dropped.
+pub fn follow_new_heads(
+    ws_url: Option<String>,
+    poll_interval: Duration,
+) -> (mpsc::Receiver<NewHead>, JoinHandle<()>) {
+    let follower = NewHeadsFollower {
+        ws_url,
+        poll_interval,
+        subscription_timeout: SUBSCRIPTION_TIMEOUT,
+        resubscribe_delay: RESUBSCRIBE_DELAY,
+        last_number: None,
+    };
+    follower.spawn()
+}
+
+struct NewHeadsFollower {
+    ws_url: Option<String>,
+    poll_interval: Duration,
+    subscription_timeout: Duration,
+    resubscribe_delay: Duration,
+    /// Number of the last sent block, so the number isn't sent again after resubscribing.
+    last_number: Option<u64>,
+}
+
+impl NewHeadsFollower {
+    fn spawn(mut self) -> (mpsc::Receiver<NewHead>, JoinHandle<()>) {
+        let (sender, receiver) = mpsc::channel(NEW_HEADS_CHANNEL_CAPACITY);
+        let task = tokio::spawn(async move {
+            loop {
+                if let Some(ws_url) = self.ws_url.clone() {
+                    match self.subscribe(&ws_url, &sender).await {
+                        Ok(()) if sender.is_closed() => return,
+                        Ok(()) => vlog::warn!("`newHeads` subscription was closed by the node"),
+                        Err(err) => vlog::warn!("`newHeads` subscription failed: {}", err),
+                    }
+                    #[cfg(feature = "with-metrics")]
+                    metrics::increment_counter!("eth_client.new_heads.subscription_failure");
+                }
+
+                // Without the subscription the node is polled, periodically trying to subscribe again.
+                let deadline = self
+                    .ws_url
+                    .as_ref()
+                    .map(|_| Instant::now() + self.resubscribe_delay);
+                if !self.tick(&sender, deadline).await {
+                    return;
+                }
+            }
+        });
+        (receiver, task)
+    }
+
+    /// Sends the numbers of the blocks received via the subscription until it's closed either
+    /// by the node or by dropping the receiver. Fails if no blocks are received for too long.
+    async fn subscribe(
+        &mut self,
+        ws_url: &str,
+        sender: &mpsc::Sender<NewHead>,
+    ) -> anyhow::Result<()> {
+        let timeout = self.subscription_timeout;
+        let subscription = async {
+            let transport = web3::transports::WebSocket::new(ws_url).await?;
+            let web3 = web3::Web3::new(transport);
+            let new_heads = web3.eth_subscribe().subscribe_new_heads().await?;
+            Ok::<_, anyhow::Error>(new_heads)
+        };
+        let mut new_heads = time::timeout(timeout, subscription)
+            .await
+            .map_err(|_| format_err!("subscription wasn't established in {:?}", timeout))??;
+        vlog::info!("Subscribed to the new Ethereum blocks via `{}`", ws_url);
+
+        loop {
+            let head = match time::timeout(timeout, new_heads.next()).await {
+                Ok(Some(head)) => head?,
+                Ok(None) => return Ok(()),
+                Err(_) => anyhow::bail!("no new blocks were received in {:?}", timeout),
+            };
+            let number = match head.number {
+                Some(number) => number.as_u64(),
+                // Pending blocks have no number.
+                None => continue,
+            };
+            if self.last_number == Some(number) {
+                continue;
+            }
+            self.last_number = Some(number);
+            if sender.send(NewHead::Block(number)).await.is_err() {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Sends the poll ticks until the deadline. Returns `false` if the receiver was dropped.
+    async fn tick(&mut self, sender: &mpsc::Sender<NewHead>, deadline: Option<Instant>) -> bool {
+        let mut timer = time::interval(self.poll_interval);
+        while deadline.map_or(true, |deadline| Instant::now() < deadline) {
+            timer.tick().await;
+            if sender.send(NewHead::PollTick).await.is_err() {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    fn follower(ws_url: Option<String>) -> NewHeadsFollower {
+        NewHeadsFollower {
+            ws_url,
+            poll_interval: Duration::from_millis(10),
+            subscription_timeout: Duration::from_millis(100),
+            resubscribe_delay: Duration::from_secs(60),
+            last_number: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn node_is_polled_without_subscription() {
+        let (mut new_heads, task) = follower(None).spawn();
+
+        for _ in 0..3 {
+            assert_eq!(new_heads.recv().await, Some(NewHead::PollTick));
+        }
+
+        drop(new_heads);
+        time::timeout(Duration::from_secs(1), task)
+            .await
+            .expect("Follower didn't stop")
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn stalled_subscription_falls_back_to_polling() {
+        // The node accepts the connections but never completes the handshake.
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let ws_url = format!("ws://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let mut connections = Vec::new();
+            while let Ok((connection, _)) = listener.accept().await {
+                connections.push(connection);
+            }
+        });
+
+        let (mut new_heads, _) = follower(Some(ws_url)).spawn();
+        let head = time::timeout(Duration::from_secs(5), new_heads.recv())
+            .await
+            .expect("Follower didn't fall back to polling");
+        assert_eq!(head, Some(NewHead::PollTick));
+    }
+
+    #[tokio::test]
+    async fn unavailable_subscription_falls_back_to_polling() {
+        // Nothing listens on the port once the listener is dropped.
+        let ws_url = {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            format!("ws://{}", listener.local_addr().unwrap())
+        };
+
+        let (mut new_heads, _) = follower(Some(ws_url)).spawn();
+        let head = time::timeout(Duration::from_secs(5), new_heads.recv())
+            .await
+            .expect("Follower didn't fall back to polling");
+        assert_eq!(head, Some(NewHead::PollTick));
+    }
+}
@@ -4,10 +4,21 @@ use crate::EthereumSigner;
 use crate::RawTransaction;
 
 use jsonrpc_core::types::response::Output;
-use zksync_types::tx::{PackedEthSignature, TxEthSignature};
+use zksync_types::tx::{EIP712Message, PackedEthSignature, TxEthSignature};
 use zksync_types::Address;
 
 use serde_json::Value;
+use std::time::Duration;
+
+/// Default timeout of the requests to the signer, long enough for the signer to ask the user for the confirmation.
+const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+
+fn http_client(timeout: Duration) -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(timeout)
+        .build()
+        .expect("failed to build the HTTP client")
+}
 
 pub fn is_signature_from_address(
     signature: &PackedEthSignature,
@@ -84,6 +95,31 @@ impl EthereumSigner for JsonRpcSigner {
         }
     }
 
+    /// Signs the typed data via the `eth_signTypedData_v4` method.
+    async fn sign_typed_data(
+        &self,
+        message: &EIP712Message,
+    ) -> Result<TxEthSignature, SignerError> {
+        let request = JsonRpcRequest::sign_typed_data(self.address()?, message);
+        let ret = self
+            .post(&request)
+            .await
+            .map_err(|err| SignerError::SigningFailed(err.to_string()))?;
+        let signature: PackedEthSignature = serde_json::from_value(ret)
+            .map_err(|err| SignerError::SigningFailed(err.to_string()))?;
+
+        let signer = signature
+            .signature_recover_signer_from_raw(&message.signing_hash())
+            .map_err(|err| SignerError::RecoverAddress(err.to_string()))?;
+        if signer == self.address()? {
+            Ok(TxEthSignature::EthereumSignature(signature))
+        } else {
+            Err(SignerError::SigningFailed(
+                "Invalid signature from JsonRpcSigner".to_string(),
+            ))
+        }
+    }
+
     /// Signs and returns the RLP-encoded transaction.
     async fn sign_transaction(&self, raw_tx: RawTransaction) -> Result<Vec<u8>, SignerError> {
         let msg = JsonRpcRequest::sign_transaction(self.address()?, raw_tx);
@@ -123,7 +159,7 @@ impl JsonRpcSigner {
     ) -> Result<Self, SignerError> {
         let mut signer = Self {
             rpc_addr: rpc_addr.into(),
-            client: reqwest::Client::new(),
+            client: http_client(DEFAULT_REQUEST_TIMEOUT),
             address: None,
             signer_type,
         };
@@ -153,6 +189,27 @@ impl JsonRpcSigner {
         Ok(signer)
     }
 
+    /// Creates a signer for the known address without any requests to the server.
+    /// `signer_type` must be specified for the messages to be signed.
+    pub fn with_address(
+        rpc_addr: impl Into<String>,
+        address: Address,
+        signer_type: Option<SignerType>,
+    ) -> Self {
+        Self {
+            rpc_addr: rpc_addr.into(),
+            client: http_client(DEFAULT_REQUEST_TIMEOUT),
+            address: Some(address),
+            signer_type,
+        }
+    }
+
+    /// Sets the timeout of the requests to the signer.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.client = http_client(timeout);
+        self
+    }
+
     /// Get Ethereum address.
     pub fn address(&self) -> Result<Address, SignerError> {
         self.address.ok_or(SignerError::DefineAddress)
@@ -290,7 +347,11 @@ impl JsonRpcSigner {
 mod messages {
     use crate::RawTransaction;
     use hex::encode;
-    use zksync_types::Address;
+    use web3::types::U64;
+    use zksync_types::{tx::EIP712Message, Address};
+
+    /// Type of the EIP-1559 transactions.
+    const EIP1559_TX_TYPE: u64 = 2;
 
     #[derive(Debug, Serialize, Deserialize)]
     pub struct JsonRpcRequest {
@@ -336,32 +397,53 @@ mod messages {
             Self::create("eth_sign", params)
         }
 
+        /// Signs the EIP-712 typed data, passed as a JSON string.
+        pub fn sign_typed_data(address: Address, message: &EIP712Message) -> Self {
+            let params = vec![
+                serde_json::to_value(address).expect("serialization fail"),
+                serde_json::to_value(message.to_json().to_string()).expect("serialization fail"),
+            ];
+            Self::create("eth_signTypedData_v4", params)
+        }
+
         /// Signs a transaction that can be submitted to the network.
         /// The address to sign with must be unlocked.
         pub fn sign_transaction(from: Address, tx_data: RawTransaction) -> Self {
             let mut params = Vec::new();
 
+            let mut tx = serde_json::json!({
+                "from": serde_json::to_value(from).expect("serialization fail"),
+                "gas": serde_json::to_value(tx_data.gas).expect("serialization fail"),
+                "value": serde_json::to_value(tx_data.value).expect("serialization fail"),
+                "data": serde_json::to_value(format!("0x{}", encode(tx_data.data))).expect("serialization fail"),
+                "nonce": serde_json::to_value(tx_data.nonce).expect("serialization fail"),
+            });
             // Parameter `To` is optional, so we add it only if it is not None
-            let tx = if let Some(to) = tx_data.to {
-                serde_json::json!({
-                    "from": serde_json::to_value(from).expect("serialization fail"),
-                    "to": serde_json::to_value(to).expect("serialization fail"),
-                    "gas": serde_json::to_value(tx_data.gas).expect("serialization fail"),
-                    "gasPrice": serde_json::to_value(tx_data.gas_price).expect("serialization fail"),
-                    "value": serde_json::to_value(tx_data.value).expect("serialization fail"),
-                    "data": serde_json::to_value(format!("0x{}", encode(tx_data.data))).expect("serialization fail"),
-                    "nonce": serde_json::to_value(tx_data.nonce).expect("serialization fail"),
-                })
-            } else {
-                serde_json::json!({
-                    "from": serde_json::to_value(from).expect("serialization fail"),
-                    "gas": serde_json::to_value(tx_data.gas).expect("serialization fail"),
-                    "gasPrice": serde_json::to_value(tx_data.gas_price).expect("serialization fail"),
-                    "value": serde_json::to_value(tx_data.value).expect("serialization fail"),
-                    "data": serde_json::to_value(format!("0x{}", encode(tx_data.data))).expect("serialization fail"),
-                    "nonce": serde_json::to_value(tx_data.nonce).expect("serialization fail"),
-                })
-            };
+            if let Some(to) = tx_data.to {
+                tx["to"] = serde_json::to_value(to).expect("serialization fail");
+            }
+            // Chain ID is optional for the signers that have it configured.
+            if tx_data.chain_id != 0 {
+                tx["chainId"] =
+                    serde_json::to_value(U64::from(tx_data.chain_id)).expect("serialization fail");
+            }
+            // EIP-1559 transactions have the fee caps instead of the gas price.
+            match (tx_data.max_fee_per_gas, tx_data.max_priority_fee_per_gas) {
+                (Some(max_fee_per_gas), Some(max_priority_fee_per_gas))
+                    if tx_data.transaction_type == Some(EIP1559_TX_TYPE.into()) =>
+                {
+                    tx["type"] = serde_json::to_value(U64::from(EIP1559_TX_TYPE))
+                        .expect("serialization fail");
+                    tx["maxFeePerGas"] =
+                        serde_json::to_value(max_fee_per_gas).expect("serialization fail");
+                    tx["maxPriorityFeePerGas"] =
+                        serde_json::to_value(max_priority_fee_per_gas).expect("serialization fail");
+                }
+                _ => {
+                    tx["gasPrice"] =
+                        serde_json::to_value(tx_data.gas_price).expect("serialization fail");
+                }
+            }
             params.push(tx);
             Self::create("eth_signTransaction", params)
         }
@@ -375,9 +457,10 @@ mod tests {
     use jsonrpc_core::{Failure, Id, Output, Success, Version};
     use parity_crypto::publickey::{Generator, KeyPair, Random};
     use serde_json::json;
+    use std::time::Duration;
 
     use zksync_types::{
-        tx::{PackedEthSignature, TxEthSignature},
+        tx::{EIP712Message, EIP712Value, PackedEthSignature, TxEthSignature},
         Address,
     };
 
@@ -501,4 +584,84 @@ mod tests {
         assert_ne!(transaction_signature.len(), 0);
         abort_handle.abort();
     }
+
+    /// Checks that the request to the signer that doesn't respond fails after the timeout.
+    #[actix_rt::test]
+    async fn stalled_signer_timeout() {
+        // The signer accepts the connections but never responds.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = format!("http://{}/", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let mut connections = Vec::new();
+            while let Ok((connection, _)) = listener.accept().await {
+                connections.push(connection);
+            }
+        });
+
+        let client = JsonRpcSigner::with_address(address, Address::repeat_byte(1), None)
+            .with_timeout(Duration::from_millis(100));
+        let raw_tx = RawTransaction {
+            chain_id: 0,
+            transaction_type: None,
+            access_list: None,
+            max_fee_per_gas: Default::default(),
+            nonce: Default::default(),
+            to: None,
+            value: Default::default(),
+            gas_price: Default::default(),
+            gas: Default::default(),
+            data: vec![],
+            max_priority_fee_per_gas: Default::default(),
+        };
+        let result = tokio::time::timeout(Duration::from_secs(5), client.sign_transaction(raw_tx))
+            .await
+            .expect("Request to the signer didn't time out");
+        assert!(result.is_err());
+    }
+
+    /// Checks that EIP-1559 transactions are passed to the signer with the fee caps.
+    #[test]
+    fn sign_eip1559_transaction_request() {
+        let raw_tx = RawTransaction {
+            chain_id: 9,
+            transaction_type: Some(2.into()),
+            access_list: None,
+            max_fee_per_gas: Some(100.into()),
+            nonce: 1.into(),
+            to: Some(Address::repeat_byte(1)),
+            value: Default::default(),
+            gas_price: 100.into(),
+            gas: 21000.into(),
+            data: vec![],
+            max_priority_fee_per_gas: Some(2.into()),
+        };
+        let request = JsonRpcRequest::sign_transaction(Address::repeat_byte(2), raw_tx);
+        let tx = &request.params[0];
+
+        assert_eq!(tx["chainId"], json!("0x9"));
+        assert_eq!(tx["type"], json!("0x2"));
+        assert_eq!(tx["maxFeePerGas"], json!("0x64"));
+        assert_eq!(tx["maxPriorityFeePerGas"], json!("0x2"));
+        assert!(tx.get("gasPrice").is_none());
+    }
+
+    /// Checks that the typed data is passed to the signer as a JSON string.
+    #[test]
+    fn sign_typed_data_request() {
+        let message = EIP712Message {
+            primary_type: "Withdraw",
+            fields: vec![("nonce", EIP712Value::Uint32(3))],
+        };
+        let request = JsonRpcRequest::sign_typed_data(Address::repeat_byte(2), &message);
+        assert_eq!(request.method, "eth_signTypedData_v4");
+
+        let typed_data: serde_json::Value =
+            serde_json::from_str(request.params[1].as_str().unwrap()).unwrap();
+        assert_eq!(typed_data["primaryType"], json!("Withdraw"));
+        assert_eq!(
+            typed_data["types"]["Withdraw"],
+            json!([{ "name": "nonce", "type": "uint32" }])
+        );
+        assert_eq!(typed_data["message"]["nonce"], json!(3));
+    }
 }
@@ -0,0 +1,981 @@
+//! Signer backed by the Ledger hardware wallet running the Ethereum application.
+//!
+//! Messages are adapted to what the device is able to display:
+//!
+//! - EIP-712 typed data is sent field by field, so the device displays the domain and every
+//!   field of the 2FA message (the signer asks the SDK to use the typed data for the 2FA messages,
+//!   see [`EthereumSigner::prefers_typed_data`]). The application versions not supporting it
+//!   display the domain separator and the message hash instead.
+//! - Messages signed via the `personal_sign` command are displayed as text if they consist of
+//!   the printable ASCII characters, otherwise as their hash. The binary `ChangePubKey` message
+//!   is verified by the contract as is, so the device can only display its hash.
+//! - Transactions are parsed by the device and displayed field by field.
+
+use std::{fmt, ops::Range, sync::Arc};
+
+use web3::signing::Signature;
+use zksync_types::{
+    tx::{
+        EIP712Message, EIP712Value, PackedEthSignature, TxEthSignature, EIP712_DOMAIN_NAME,
+        EIP712_DOMAIN_VERSION,
+    },
+    Address, H256,
+};
+
+use crate::raw_ethereum_tx::{RawTransaction, Transaction};
+use crate::{EthereumSigner, SignerError};
+
+#[cfg(feature = "ledger")]
+pub use hid::HidTransport;
+
+const CLA: u8 = 0xe0;
+const INS_GET_ADDRESS: u8 = 0x02;
+const INS_SIGN_TRANSACTION: u8 = 0x04;
+const INS_SIGN_PERSONAL_MESSAGE: u8 = 0x08;
+const INS_SIGN_EIP712: u8 = 0x0c;
+const INS_EIP712_STRUCT_DEFINITION: u8 = 0x1a;
+const INS_EIP712_STRUCT_IMPLEMENTATION: u8 = 0x1c;
+const P1_FIRST_CHUNK: u8 = 0x00;
+const P1_NEXT_CHUNK: u8 = 0x80;
+/// The `EIP-712` command signing the domain separator and the message hash.
+const P2_EIP712_HASHED: u8 = 0x00;
+/// The `EIP-712` command signing the typed data sent beforehand.
+const P2_EIP712_FULL: u8 = 0x01;
+/// Struct name of the struct definition and the struct implementation commands.
+const P2_EIP712_STRUCT_NAME: u8 = 0x00;
+/// Struct field of the struct definition and the struct implementation commands.
+const P2_EIP712_STRUCT_FIELD: u8 = 0xff;
+/// Size of the chunks the payloads are split into, the same as the one of the Ledger libraries.
+const MAX_CHUNK_SIZE: usize = 150;
+const SW_OK: u16 = 0x9000;
+const SW_USER_REJECTED: u16 = 0x6985;
+const SW_INS_NOT_SUPPORTED: u16 = 0x6d00;
+
+/// Type descriptions of the EIP-712 struct fields.
+const EIP712_TYPE_UINT: u8 = 2;
+const EIP712_TYPE_ADDRESS: u8 = 3;
+const EIP712_TYPE_STRING: u8 = 5;
+/// Flag of the type description followed by the type size.
+const EIP712_TYPE_SIZE_FLAG: u8 = 0x40;
+
+/// Derivation path of the first Ledger Live Ethereum account.
+pub const DEFAULT_DERIVATION_PATH: &str = "m/44'/60'/0'/0/0";
+
+/// Channel to exchange the APDU commands with the device.
+pub trait LedgerTransport: Send + Sync + fmt::Debug {
+    /// Sends the APDU command and returns the response along with its status word.
+    fn exchange(&self, apdu: &[u8]) -> Result<Vec<u8>, SignerError>;
+}
+
+#[derive(Debug, Clone)]
+pub struct LedgerSigner {
+    transport: Arc<dyn LedgerTransport>,
+    derivation_path: Vec<u32>,
+}
+
+impl LedgerSigner {
+    /// Creates the signer for the account with the given BIP-32 derivation path,
+    /// e.g. [`DEFAULT_DERIVATION_PATH`].
+    pub fn new(
+        transport: Arc<dyn LedgerTransport>,
+        derivation_path: &str,
+    ) -> Result<Self, SignerError> {
+        Ok(Self {
+            transport,
+            derivation_path: parse_derivation_path(derivation_path)?,
+        })
+    }
+
+    /// Connects to the first Ledger device found via HID.
+    #[cfg(feature = "ledger")]
+    pub fn connect(derivation_path: &str) -> Result<Self, SignerError> {
+        Self::new(Arc::new(HidTransport::open()?), derivation_path)
+    }
+
+    fn encoded_path(&self) -> Vec<u8> {
+        let mut path = Vec::with_capacity(1 + 4 * self.derivation_path.len());
+        path.push(self.derivation_path.len() as u8);
+        for index in &self.derivation_path {
+            path.extend_from_slice(&index.to_be_bytes());
+        }
+        path
+    }
+
+    /// Sends the payload split into the chunks the device accepts, the device responds
+    /// with the signature after the last one. The last `tail_len` bytes are sent
+    /// in the same chunk.
+    async fn exchange_chunked(
+        &self,
+        ins: u8,
+        payload: Vec<u8>,
+        tail_len: usize,
+    ) -> Result<Vec<u8>, SignerError> {
+        let transport = self.transport.clone();
+        run_blocking(move || {
+            let mut response = Vec::new();
+            for (i, range) in payload_chunks(payload.len(), tail_len).enumerate() {
+                let p1 = if i == 0 {
+                    P1_FIRST_CHUNK
+                } else {
+                    P1_NEXT_CHUNK
+                };
+                response = exchange(transport.as_ref(), ins, p1, 0x00, &payload[range])?;
+            }
+            Ok(response)
+        })
+        .await
+    }
+}
+
+#[async_trait::async_trait]
+impl EthereumSigner for LedgerSigner {
+    /// Signs the message with the `personal_sign` command, the device adds
+    /// the `\x19Ethereum Signed Message:\n` prefix itself.
+    async fn sign_message(&self, message: &[u8]) -> Result<TxEthSignature, SignerError> {
+        let mut payload = self.encoded_path();
+        payload.extend_from_slice(&(message.len() as u32).to_be_bytes());
+        payload.extend_from_slice(message);
+
+        let response = self
+            .exchange_chunked(INS_SIGN_PERSONAL_MESSAGE, payload, 0)
+            .await?;
+        packed_signature(&response).map(TxEthSignature::EthereumSignature)
+    }
+
+    /// Sends the typed data to be displayed by the device and signs it. The application
+    /// versions not supporting the typed data sign the domain separator and the message hash.
+    async fn sign_typed_data(
+        &self,
+        message: &EIP712Message,
+    ) -> Result<TxEthSignature, SignerError> {
+        let transport = self.transport.clone();
+        let path = self.encoded_path();
+        let message = message.clone();
+        let response = run_blocking(move || {
+            if send_typed_data(transport.as_ref(), &message)? {
+                exchange(
+                    transport.as_ref(),
+                    INS_SIGN_EIP712,
+                    0x00,
+                    P2_EIP712_FULL,
+                    &path,
+                )
+            } else {
+                let mut payload = path;
+                payload.extend_from_slice(EIP712Message::domain_separator().as_bytes());
+                payload.extend_from_slice(message.struct_hash().as_bytes());
+                exchange(
+                    transport.as_ref(),
+                    INS_SIGN_EIP712,
+                    0x00,
+                    P2_EIP712_HASHED,
+                    &payload,
+                )
+            }
+        })
+        .await?;
+        packed_signature(&response).map(TxEthSignature::EthereumSignature)
+    }
+
+    /// Signs and returns the RLP-encoded transaction.
+    async fn sign_transaction(&self, raw_tx: RawTransaction) -> Result<Vec<u8>, SignerError> {
+        let chain_id = raw_tx.chain_id;
+        let gas_price = match raw_tx.max_fee_per_gas {
+            Some(val) => val,
+            None => raw_tx.gas_price,
+        };
+        let tx = Transaction {
+            to: raw_tx.to,
+            nonce: raw_tx.nonce,
+            gas: raw_tx.gas,
+            gas_price,
+            value: raw_tx.value,
+            data: raw_tx.data,
+            transaction_type: raw_tx.transaction_type,
+            access_list: raw_tx.access_list.unwrap_or_default(),
+            max_priority_fee_per_gas: raw_tx.max_priority_fee_per_gas.unwrap_or_default(),
+        };
+
+        // The device detects the end of the legacy transaction by its EIP-155 fields,
+        // so they must not be split between the chunks.
+        let tail_len = if tx.is_legacy() {
+            rlp::encode(&chain_id).len() + 2
+        } else {
+            0
+        };
+        let mut payload = self.encoded_path();
+        payload.extend_from_slice(&tx.encode(chain_id, None));
+
+        let response = self
+            .exchange_chunked(INS_SIGN_TRANSACTION, payload, tail_len)
+            .await?;
+        let (v, r, s) = parse_signature(&response)?;
+
+        let v = if tx.is_legacy() {
+            legacy_v(chain_id, v)
+        } else {
+            v as u64
+        };
+        Ok(tx.encode(chain_id, Some(&Signature { v, r, s })))
+    }
+
+    async fn get_address(&self) -> Result<Address, SignerError> {
+        let transport = self.transport.clone();
+        let path = self.encoded_path();
+        let response =
+            run_blocking(move || exchange(transport.as_ref(), INS_GET_ADDRESS, 0x00, 0x00, &path))
+                .await?;
+        parse_address(&response)
+    }
+
+    /// The device displays the fields of the typed data, while the human-readable
+    /// 2FA messages contain the line breaks and are displayed as their hash.
+    fn prefers_typed_data(&self) -> bool {
+        true
+    }
+}
+
+/// Runs the blocking exchange with the device on a separate thread,
+/// since the device waits for the user confirmation.
+async fn run_blocking<T, F>(exchange: F) -> Result<T, SignerError>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, SignerError> + Send + 'static,
+{
+    let (sender, receiver) = futures::channel::oneshot::channel();
+    std::thread::spawn(move || {
+        let _ = sender.send(exchange());
+    });
+    receiver.await.map_err(|_| {
+        SignerError::SigningFailed("Ledger exchange terminated unexpectedly".to_string())
+    })?
+}
+
+/// Parses the derivation path in the `m/44'/60'/0'/0/0` format.
+fn parse_derivation_path(path: &str) -> Result<Vec<u32>, SignerError> {
+    let invalid_path = || SignerError::CustomError(format!("Invalid derivation path: {}", path));
+
+    let mut parts = path.split('/');
+    if parts.next() != Some("m") {
+        return Err(invalid_path());
+    }
+    let indices = parts
+        .map(|part| {
+            let (index, hardened) = match part.strip_suffix('\'') {
+                Some(index) => (index, true),
+                None => (part, false),
+            };
+            let index: u32 = index.parse().map_err(|_| invalid_path())?;
+            if index >= 0x8000_0000 {
+                return Err(invalid_path());
+            }
+            Ok(if hardened { index | 0x8000_0000 } else { index })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    // The device accepts at most 10 path elements.
+    if indices.is_empty() || indices.len() > 10 {
+        return Err(invalid_path());
+    }
+    Ok(indices)
+}
+
+/// Splits the payload into the chunks of at most `MAX_CHUNK_SIZE` bytes,
+/// except for the last one which is extended to include the whole tail.
+fn payload_chunks(len: usize, tail_len: usize) -> impl Iterator<Item = Range<usize>> {
+    let tail_start = len.saturating_sub(tail_len);
+    let mut start = 0;
+    std::iter::from_fn(move || {
+        if start >= len {
+            return None;
+        }
+        let mut end = usize::min(start + MAX_CHUNK_SIZE, len);
+        if tail_len > 0 && end > tail_start {
+            end = len;
+        }
+        let chunk = start..end;
+        start = end;
+        Some(chunk)
+    })
+}
+
+/// Sends the APDU command and returns the response data along with the status word.
+fn exchange_raw(
+    transport: &dyn LedgerTransport,
+    ins: u8,
+    p1: u8,
+    p2: u8,
+    data: &[u8],
+) -> Result<(Vec<u8>, u16), SignerError> {
+    let mut apdu = Vec::with_capacity(5 + data.len());
+    apdu.extend_from_slice(&[CLA, ins, p1, p2, data.len() as u8]);
+    apdu.extend_from_slice(data);
+
+    let mut response = transport.exchange(&apdu)?;
+    if response.len() < 2 {
+        return Err(SignerError::CustomError(
+            "Malformed Ledger response".to_string(),
+        ));
+    }
+    let status = response.split_off(response.len() - 2);
+    Ok((response, u16::from_be_bytes([status[0], status[1]])))
+}
+
+fn exchange(
+    transport: &dyn LedgerTransport,
+    ins: u8,
+    p1: u8,
+    p2: u8,
+    data: &[u8],
+) -> Result<Vec<u8>, SignerError> {
+    let (response, status) = exchange_raw(transport, ins, p1, p2, data)?;
+    check_status(response, status)
+}
+
+fn check_status(response: Vec<u8>, status: u16) -> Result<Vec<u8>, SignerError> {
+    match status {
+        SW_OK => Ok(response),
+        SW_USER_REJECTED => Err(SignerError::SigningFailed(
+            "Rejected on the Ledger device".to_string(),
+        )),
+        status => Err(SignerError::CustomError(format!(
+            "Ledger error, status word {:#06x}",
+            status
+        ))),
+    }
+}
+
+/// Structs of the typed data: the zkSync domain and the message itself.
+fn typed_data_structs(message: &EIP712Message) -> Vec<(&str, Vec<(&str, EIP712Value)>)> {
+    let domain = vec![
+        ("name", EIP712Value::String(EIP712_DOMAIN_NAME.to_string())),
+        (
+            "version",
+            EIP712Value::String(EIP712_DOMAIN_VERSION.to_string()),
+        ),
+    ];
+    vec![
+        ("EIP712Domain", domain),
+        (message.primary_type, message.fields.clone()),
+    ]
+}
+
+/// Encodes the definition of the struct field: its type description and name.
+fn encode_field_definition(name: &str, value: &EIP712Value) -> Vec<u8> {
+    let mut data = match value {
+        EIP712Value::Address(_) => vec![EIP712_TYPE_ADDRESS],
+        EIP712Value::String(_) => vec![EIP712_TYPE_STRING],
+        EIP712Value::Uint32(_) => vec![EIP712_TYPE_SIZE_FLAG | EIP712_TYPE_UINT, 4],
+    };
+    data.push(name.len() as u8);
+    data.extend_from_slice(name.as_bytes());
+    data
+}
+
+/// Encodes the value of the struct field prefixed with its length.
+fn encode_field_value(value: &EIP712Value) -> Vec<u8> {
+    let value = match value {
+        EIP712Value::Address(address) => address.as_bytes().to_vec(),
+        EIP712Value::String(string) => string.as_bytes().to_vec(),
+        // Integers are sent without the leading zeros.
+        EIP712Value::Uint32(value) => {
+            let bytes = value.to_be_bytes();
+            let leading_zeros = bytes.iter().take_while(|&&byte| byte == 0).count();
+            bytes[leading_zeros.min(3)..].to_vec()
+        }
+    };
+    let mut data = (value.len() as u16).to_be_bytes().to_vec();
+    data.extend_from_slice(&value);
+    data
+}
+
+/// Sends the definitions and the values of the typed data structs. Returns `false` if
+/// the application doesn't support the typed data, so only its hashes can be signed.
+fn send_typed_data(
+    transport: &dyn LedgerTransport,
+    message: &EIP712Message,
+) -> Result<bool, SignerError> {
+    let structs = typed_data_structs(message);
+    for (i, (name, fields)) in structs.iter().enumerate() {
+        let (response, status) = exchange_raw(
+            transport,
+            INS_EIP712_STRUCT_DEFINITION,
+            0x00,
+            P2_EIP712_STRUCT_NAME,
+            name.as_bytes(),
+        )?;
+        if i == 0 && status == SW_INS_NOT_SUPPORTED {
+            return Ok(false);
+        }
+        check_status(response, status)?;
+        for (field, value) in fields {
+            exchange(
+                transport,
+                INS_EIP712_STRUCT_DEFINITION,
+                0x00,
+                P2_EIP712_STRUCT_FIELD,
+                &encode_field_definition(field, value),
+            )?;
+        }
+    }
+    for (name, fields) in &structs {
+        exchange(
+            transport,
+            INS_EIP712_STRUCT_IMPLEMENTATION,
+            0x00,
+            P2_EIP712_STRUCT_NAME,
+            name.as_bytes(),
+        )?;
+        for (_, value) in fields {
+            exchange(
+                transport,
+                INS_EIP712_STRUCT_IMPLEMENTATION,
+                0x00,
+                P2_EIP712_STRUCT_FIELD,
+                &encode_field_value(value),
+            )?;
+        }
+    }
+    Ok(true)
+}
+
+/// Parses the `v || r || s` signature returned by the device.
+fn parse_signature(response: &[u8]) -> Result<(u8, H256, H256), SignerError> {
+    if response.len() != 65 {
+        return Err(SignerError::SigningFailed(format!(
+            "Unexpected Ledger signature length {}",
+            response.len()
+        )));
+    }
+    Ok((
+        response[0],
+        H256::from_slice(&response[1..33]),
+        H256::from_slice(&response[33..65]),
+    ))
+}
+
+/// Converts the `v || r || s` signature returned by the device to the packed one.
+fn packed_signature(response: &[u8]) -> Result<PackedEthSignature, SignerError> {
+    let (v, r, s) = parse_signature(response)?;
+
+    let mut packed = [0u8; 65];
+    packed[..32].copy_from_slice(r.as_bytes());
+    packed[32..64].copy_from_slice(s.as_bytes());
+    packed[64] = v;
+    PackedEthSignature::deserialize_packed(&packed)
+        .map_err(|err| SignerError::SigningFailed(err.to_string()))
+}
+
+/// The device returns the lowest byte of the EIP-155 `v` only, so it has to be
+/// restored for the chain ids above 109.
+fn legacy_v(chain_id: u64, v: u8) -> u64 {
+    let base = chain_id * 2 + 35;
+    let recovery_id = (v as u64 + 256 - (base & 0xff)) & 0xff;
+    base + recovery_id
+}
+
+/// Parses the `GET_ADDRESS` response: the public key and the hex address,
+/// both prefixed with their length.
+fn parse_address(response: &[u8]) -> Result<Address, SignerError> {
+    let pub_key_len = *response.first().ok_or(SignerError::DefineAddress)? as usize;
+    let address_len = *response
+        .get(1 + pub_key_len)
+        .ok_or(SignerError::DefineAddress)? as usize;
+    let address = response
+        .get(2 + pub_key_len..2 + pub_key_len + address_len)
+        .ok_or(SignerError::DefineAddress)?;
+    std::str::from_utf8(address)
+        .ok()
+        .and_then(|address| address.parse().ok())
+        .ok_or(SignerError::DefineAddress)
+}
+
+#[cfg(feature = "ledger")]
+mod hid {
+    use std::sync::Mutex;
+    use std::time::{Duration, Instant};
+
+    use hidapi::{HidApi, HidDevice};
+
+    use super::LedgerTransport;
+    use crate::SignerError;
+
+    const LEDGER_VENDOR_ID: u16 = 0x2c97;
+    const LEDGER_USAGE_PAGE: u16 = 0xffa0;
+    const LEDGER_CHANNEL: u16 = 0x0101;
+    const TAG_APDU: u8 = 0x05;
+    const PACKET_SIZE: usize = 64;
+    /// Size of the header of the first response packet: the channel, the tag,
+    /// the sequence number and the response length.
+    const FIRST_PACKET_HEADER_SIZE: usize = 7;
+    /// Size of the header of the next response packets: the channel, the tag and the sequence number.
+    const PACKET_HEADER_SIZE: usize = 5;
+    /// Time given to the user to confirm the command on the device.
+    const RESPONSE_TIMEOUT: Duration = Duration::from_secs(120);
+
+    /// Transport to the Ledger device connected via USB.
+    pub struct HidTransport {
+        device: Mutex<HidDevice>,
+    }
+
+    impl std::fmt::Debug for HidTransport {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "HidTransport")
+        }
+    }
+
+    impl HidTransport {
+        /// Opens the first connected Ledger device.
+        pub fn open() -> Result<Self, SignerError> {
+            let api = HidApi::new().map_err(hid_error)?;
+            let info = api
+                .device_list()
+                .find(|info| {
+                    info.vendor_id() == LEDGER_VENDOR_ID
+                        && (info.usage_page() == LEDGER_USAGE_PAGE || info.interface_number() == 0)
+                })
+                .ok_or_else(|| SignerError::CustomError("No Ledger device found".to_string()))?;
+            let device = info.open_device(&api).map_err(hid_error)?;
+            Ok(Self {
+                device: Mutex::new(device),
+            })
+        }
+
+        fn write_apdu(device: &HidDevice, apdu: &[u8]) -> Result<(), SignerError> {
+            let mut data = Vec::with_capacity(2 + apdu.len());
+            data.extend_from_slice(&(apdu.len() as u16).to_be_bytes());
+            data.extend_from_slice(apdu);
+
+            for (sequence, chunk) in data.chunks(PACKET_SIZE - PACKET_HEADER_SIZE).enumerate() {
+                // The leading zero is the HID report id.
+                let mut packet = vec![0u8];
+                packet.extend_from_slice(&LEDGER_CHANNEL.to_be_bytes());
+                packet.push(TAG_APDU);
+                packet.extend_from_slice(&(sequence as u16).to_be_bytes());
+                packet.extend_from_slice(chunk);
+                packet.resize(PACKET_SIZE + 1, 0);
+                device.write(&packet).map_err(hid_error)?;
+            }
+            Ok(())
+        }
+
+        fn read_response(device: &HidDevice) -> Result<Vec<u8>, SignerError> {
+            let deadline = Instant::now() + RESPONSE_TIMEOUT;
+            let mut response = Vec::new();
+            let mut expected_len = 0;
+            let mut sequence = 0u16;
+            loop {
+                let timeout = deadline.saturating_duration_since(Instant::now());
+                let mut packet = [0u8; PACKET_SIZE];
+                let read = device
+                    .read_timeout(&mut packet, timeout.as_millis() as i32)
+                    .map_err(hid_error)?;
+                if read == 0 {
+                    return Err(hid_error(format!("no response in {:?}", RESPONSE_TIMEOUT)));
+                }
+
+                let header_size = if sequence == 0 {
+                    FIRST_PACKET_HEADER_SIZE
+                } else {
+                    PACKET_HEADER_SIZE
+                };
+                if read < header_size
+                    || packet[..2] != LEDGER_CHANNEL.to_be_bytes()
+                    || packet[2] != TAG_APDU
+                    || packet[3..5] != sequence.to_be_bytes()
+                {
+                    return Err(hid_error("Malformed HID packet"));
+                }
+                if sequence == 0 {
+                    expected_len = u16::from_be_bytes([packet[5], packet[6]]) as usize;
+                }
+                response.extend_from_slice(&packet[header_size..read]);
+
+                if response.len() >= expected_len {
+                    response.truncate(expected_len);
+                    return Ok(response);
+                }
+                sequence += 1;
+            }
+        }
+    }
+
+    impl LedgerTransport for HidTransport {
+        fn exchange(&self, apdu: &[u8]) -> Result<Vec<u8>, SignerError> {
+            let device = self.device.lock().expect("Ledger device lock is poisoned");
+            Self::write_apdu(&device, apdu)?;
+            Self::read_response(&device)
+        }
+    }
+
+    fn hid_error(err: impl ToString) -> SignerError {
+        SignerError::CustomError(format!("Ledger HID error: {}", err.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::PrivateKeySigner;
+    use parity_crypto::Keccak256;
+    use web3::types::U64;
+    use zksync_types::U256;
+
+    /// Typed data struct received by the device: its name, the field types and names
+    /// and the encoded values.
+    #[derive(Debug, Default)]
+    struct MockStruct {
+        name: String,
+        fields: Vec<(String, String)>,
+        values: Vec<[u8; 32]>,
+    }
+
+    impl MockStruct {
+        fn hash(&self) -> [u8; 32] {
+            let members: Vec<_> = self
+                .fields
+                .iter()
+                .map(|(type_name, name)| format!("{} {}", type_name, name))
+                .collect();
+            let encoded_type = format!("{}({})", self.name, members.join(","));
+            let mut bytes = encoded_type.as_bytes().keccak256().to_vec();
+            for value in &self.values {
+                bytes.extend_from_slice(value);
+            }
+            bytes.keccak256()
+        }
+    }
+
+    #[derive(Debug, Default)]
+    struct MockState {
+        /// Data of the chunked command received so far.
+        payload: Vec<u8>,
+        /// Typed data structs in the order of their definitions.
+        structs: Vec<MockStruct>,
+        /// Index of the struct the values are currently sent for.
+        implemented_struct: usize,
+    }
+
+    /// Emulates the device with the Ethereum application holding the given key.
+    #[derive(Debug)]
+    struct MockLedger {
+        private_key: H256,
+        /// Whether the application predates the typed data support.
+        hashed_eip712_only: bool,
+        state: Mutex<MockState>,
+    }
+
+    impl MockLedger {
+        fn new(private_key: H256) -> Self {
+            Self {
+                private_key,
+                hashed_eip712_only: false,
+                state: Default::default(),
+            }
+        }
+
+        /// Returns the signature in the `v || r || s` format of the device.
+        fn sign_raw(&self, hash: &[u8]) -> Vec<u8> {
+            let signature =
+                PackedEthSignature::sign_raw(&self.private_key, &H256::from_slice(hash)).unwrap();
+            let packed = signature.serialize_packed();
+            let mut response = vec![packed[64]];
+            response.extend_from_slice(&packed[..64]);
+            response
+        }
+
+        fn sign_transaction(&self, tx: &[u8]) -> Vec<u8> {
+            // The typed transactions start with their type.
+            let legacy = tx[0] >= 0xc0;
+            let rlp_tx = if legacy { tx } else { &tx[1..] };
+            let info = rlp::Rlp::new(rlp_tx).payload_info().unwrap();
+            if info.header_len + info.value_len > rlp_tx.len() {
+                // Waiting for the next chunk.
+                return vec![];
+            }
+
+            let mut signature = self.sign_raw(&tx.keccak256());
+            let recovery_id = signature[0] - 27;
+            signature[0] = if legacy {
+                let chain_id: u64 = rlp::Rlp::new(rlp_tx).val_at(6).unwrap();
+                ((chain_id * 2 + 35 + recovery_id as u64) & 0xff) as u8
+            } else {
+                recovery_id
+            };
+            signature
+        }
+
+        fn process(&self, ins: u8, p2: u8, state: &mut MockState) -> Result<Vec<u8>, u16> {
+            let payload = state.payload.clone();
+            // Skip the derivation path.
+            let data = || &payload[1 + 4 * payload[0] as usize..];
+            let response = match (ins, p2) {
+                (INS_GET_ADDRESS, _) => {
+                    let address =
+                        PackedEthSignature::address_from_private_key(&self.private_key).unwrap();
+                    let address = hex::encode(address.as_bytes());
+                    let mut response = vec![65];
+                    response.extend_from_slice(&[4u8; 65]);
+                    response.push(address.len() as u8);
+                    response.extend_from_slice(address.as_bytes());
+                    response
+                }
+                (INS_SIGN_TRANSACTION, _) => self.sign_transaction(data()),
+                (INS_SIGN_PERSONAL_MESSAGE, _) => {
+                    let data = data();
+                    let message_len =
+                        u32::from_be_bytes([data[0], data[1], data[2], data[3]]) as usize;
+                    if data.len() - 4 < message_len {
+                        vec![]
+                    } else {
+                        let signature =
+                            PackedEthSignature::sign(&self.private_key, &data[4..]).unwrap();
+                        let packed = signature.serialize_packed();
+                        let mut response = vec![packed[64]];
+                        response.extend_from_slice(&packed[..64]);
+                        response
+                    }
+                }
+                (INS_SIGN_EIP712, P2_EIP712_HASHED) => {
+                    let mut signed_bytes = b"\x19\x01".to_vec();
+                    signed_bytes.extend_from_slice(&data()[..64]);
+                    self.sign_raw(&signed_bytes.keccak256())
+                }
+                (INS_SIGN_EIP712, P2_EIP712_FULL) => {
+                    if state.structs.len() != 2 || state.implemented_struct != 1 {
+                        return Err(0x6a80);
+                    }
+                    let mut signed_bytes = b"\x19\x01".to_vec();
+                    signed_bytes.extend_from_slice(&state.structs[0].hash());
+                    signed_bytes.extend_from_slice(&state.structs[1].hash());
+                    self.sign_raw(&signed_bytes.keccak256())
+                }
+                (INS_EIP712_STRUCT_DEFINITION, _) if self.hashed_eip712_only => {
+                    return Err(SW_INS_NOT_SUPPORTED)
+                }
+                (INS_EIP712_STRUCT_DEFINITION, P2_EIP712_STRUCT_NAME) => {
+                    state.structs.push(MockStruct {
+                        name: String::from_utf8(payload.clone()).unwrap(),
+                        ..Default::default()
+                    });
+                    vec![]
+                }
+                (INS_EIP712_STRUCT_DEFINITION, P2_EIP712_STRUCT_FIELD) => {
+                    let (type_name, name) = match payload[0] {
+                        EIP712_TYPE_ADDRESS => ("address".to_string(), &payload[2..]),
+                        EIP712_TYPE_STRING => ("string".to_string(), &payload[2..]),
+                        type_desc if type_desc == EIP712_TYPE_SIZE_FLAG | EIP712_TYPE_UINT => {
+                            (format!("uint{}", payload[1] as usize * 8), &payload[3..])
+                        }
+                        _ => return Err(0x6a80),
+                    };
+                    let name = String::from_utf8(name.to_vec()).unwrap();
+                    let current = state.structs.last_mut().ok_or(0x6a80_u16)?;
+                    current.fields.push((type_name, name));
+                    vec![]
+                }
+                (INS_EIP712_STRUCT_IMPLEMENTATION, P2_EIP712_STRUCT_NAME) => {
+                    let name = String::from_utf8(payload.clone()).unwrap();
+                    state.implemented_struct = state
+                        .structs
+                        .iter()
+                        .position(|s| s.name == name)
+                        .ok_or(0x6a80_u16)?;
+                    vec![]
+                }
+                (INS_EIP712_STRUCT_IMPLEMENTATION, P2_EIP712_STRUCT_FIELD) => {
+                    let value = &payload[2..];
+                    assert_eq!(
+                        value.len(),
+                        u16::from_be_bytes([payload[0], payload[1]]) as usize
+                    );
+                    let current = &mut state.structs[state.implemented_struct];
+                    let encoded = match current.fields[current.values.len()].0.as_str() {
+                        "string" => value.keccak256(),
+                        _ => {
+                            let mut word = [0u8; 32];
+                            word[32 - value.len()..].copy_from_slice(value);
+                            word
+                        }
+                    };
+                    current.values.push(encoded);
+                    vec![]
+                }
+                _ => return Err(SW_INS_NOT_SUPPORTED),
+            };
+            Ok(response)
+        }
+    }
+
+    impl LedgerTransport for MockLedger {
+        fn exchange(&self, apdu: &[u8]) -> Result<Vec<u8>, SignerError> {
+            assert_eq!(apdu[0], CLA);
+            assert_eq!(apdu[4] as usize, apdu.len() - 5);
+            let (ins, p1, p2, data) = (apdu[1], apdu[2], apdu[3], &apdu[5..]);
+
+            let mut state = self.state.lock().unwrap();
+            if p1 == P1_FIRST_CHUNK {
+                state.payload.clear();
+            }
+            state.payload.extend_from_slice(data);
+
+            let (mut response, status) = match self.process(ins, p2, &mut state) {
+                Ok(response) => (response, SW_OK),
+                Err(status) => (vec![], status),
+            };
+            response.extend_from_slice(&status.to_be_bytes());
+            Ok(response)
+        }
+    }
+
+    fn ledger_signer(private_key: H256) -> LedgerSigner {
+        LedgerSigner::new(
+            Arc::new(MockLedger::new(private_key)),
+            DEFAULT_DERIVATION_PATH,
+        )
+        .unwrap()
+    }
+
+    fn raw_tx(chain_id: u64, transaction_type: u64, data: Vec<u8>) -> RawTransaction {
+        RawTransaction {
+            nonce: U256::from(1u32),
+            to: Some(Address::repeat_byte(1)),
+            gas: U256::from(21000u32),
+            gas_price: U256::from(1u32),
+            value: U256::from(1u32),
+            data,
+            chain_id,
+            transaction_type: Some(U64::from(transaction_type)),
+            access_list: None,
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
+        }
+    }
+
+    #[test]
+    fn derivation_path() {
+        assert_eq!(
+            parse_derivation_path(DEFAULT_DERIVATION_PATH).unwrap(),
+            vec![0x8000_002c, 0x8000_003c, 0x8000_0000, 0, 0]
+        );
+        assert!(parse_derivation_path("44'/60'/0'").is_err());
+        assert!(parse_derivation_path("m/44'/x").is_err());
+        assert!(parse_derivation_path("m/2147483648").is_err());
+    }
+
+    #[test]
+    fn legacy_v_restoration() {
+        assert_eq!(legacy_v(1, 37), 37);
+        assert_eq!(legacy_v(1, 38), 38);
+        // 280 * 2 + 35 = 595, its lowest byte is 83.
+        assert_eq!(legacy_v(280, 84), 596);
+    }
+
+    #[test]
+    fn chunks() {
+        let chunks: Vec<_> = payload_chunks(320, 0).collect();
+        assert_eq!(chunks, vec![0..150, 150..300, 300..320]);
+        // The tail is never split, even if it makes the last chunk longer.
+        let chunks: Vec<_> = payload_chunks(303, 5).collect();
+        assert_eq!(chunks, vec![0..150, 150..303]);
+        let chunks: Vec<_> = payload_chunks(300, 3).collect();
+        assert_eq!(chunks, vec![0..150, 150..300]);
+        assert_eq!(payload_chunks(0, 0).count(), 0);
+    }
+
+    #[tokio::test]
+    async fn get_address() {
+        let private_key = H256::repeat_byte(5);
+        let signer = ledger_signer(private_key);
+        assert_eq!(
+            signer.get_address().await.unwrap(),
+            PackedEthSignature::address_from_private_key(&private_key).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn sign_message() {
+        let private_key = H256::repeat_byte(5);
+        let signer = ledger_signer(private_key);
+        let pk_signer = PrivateKeySigner::new(private_key);
+
+        // The long message is sent in several chunks.
+        for message in [b"Transfer 1.0 ETH".to_vec(), vec![0xab; 600]] {
+            assert_eq!(
+                signer.sign_message(&message).await.unwrap(),
+                pk_signer.sign_message(&message).await.unwrap()
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn sign_typed_data() {
+        let private_key = H256::repeat_byte(5);
+        let pk_signer = PrivateKeySigner::new(private_key);
+        let mut hashed_only_ledger = MockLedger::new(private_key);
+        hashed_only_ledger.hashed_eip712_only = true;
+        let signers = vec![
+            ledger_signer(private_key),
+            LedgerSigner::new(Arc::new(hashed_only_ledger), DEFAULT_DERIVATION_PATH).unwrap(),
+        ];
+
+        let message = EIP712Message {
+            primary_type: "Transfer",
+            fields: vec![
+                ("amount", EIP712Value::String("1.0 ETH".to_string())),
+                ("to", EIP712Value::Address(Address::repeat_byte(1))),
+                ("nonce", EIP712Value::Uint32(0)),
+                ("accountId", EIP712Value::Uint32(0x0102_0304)),
+            ],
+        };
+        for signer in signers {
+            assert!(signer.prefers_typed_data());
+            assert_eq!(
+                signer.sign_typed_data(&message).await.unwrap(),
+                pk_signer.sign_typed_data(&message).await.unwrap()
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn sign_transaction() {
+        let private_key = H256::repeat_byte(5);
+        let signer = ledger_signer(private_key);
+        let pk_signer = PrivateKeySigner::new(private_key);
+
+        // Legacy transactions with `v` fitting into a byte and not, the typed transaction,
+        // and the one sent in several chunks.
+        let txs = vec![
+            raw_tx(9, 0, vec![]),
+            raw_tx(280, 0, vec![]),
+            raw_tx(9, 2, vec![]),
+            raw_tx(9, 0, vec![0xab; 300]),
+            raw_tx(9, 2, vec![0xab; 300]),
+        ];
+        for tx in txs {
+            assert_eq!(
+                signer.sign_transaction(tx.clone()).await.unwrap(),
+                pk_signer.sign_transaction(tx).await.unwrap()
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn user_rejection() {
+        #[derive(Debug)]
+        struct RejectingLedger;
+
+        impl LedgerTransport for RejectingLedger {
+            fn exchange(&self, _apdu: &[u8]) -> Result<Vec<u8>, SignerError> {
+                Ok(SW_USER_REJECTED.to_be_bytes().to_vec())
+            }
+        }
+
+        let signer = LedgerSigner::new(Arc::new(RejectingLedger), DEFAULT_DERIVATION_PATH).unwrap();
+        assert!(matches!(
+            signer.sign_transaction(raw_tx(9, 0, vec![])).await,
+            Err(SignerError::SigningFailed(_))
+        ));
+    }
+}
@@ -3,21 +3,34 @@ extern crate serde_derive;
 
 use async_trait::async_trait;
 use error::SignerError;
-use zksync_types::tx::TxEthSignature;
+use zksync_types::tx::{EIP712Message, TxEthSignature};
 use zksync_types::Address;
 
 pub use json_rpc_signer::JsonRpcSigner;
+pub use ledger_signer::LedgerSigner;
+pub use operator_signer::OperatorSigner;
 pub use pk_signer::PrivateKeySigner;
 pub use raw_ethereum_tx::RawTransaction;
 
 pub mod error;
 pub mod json_rpc_signer;
+pub mod ledger_signer;
+pub mod operator_signer;
 pub mod pk_signer;
 pub mod raw_ethereum_tx;
 
 #[async_trait]
 pub trait EthereumSigner: Send + Sync + Clone {
     async fn sign_message(&self, message: &[u8]) -> Result<TxEthSignature, SignerError>;
+    /// Signs the EIP-712 typed data.
+    async fn sign_typed_data(&self, message: &EIP712Message)
+        -> Result<TxEthSignature, SignerError>;
     async fn sign_transaction(&self, raw_tx: RawTransaction) -> Result<Vec<u8>, SignerError>;
     async fn get_address(&self) -> Result<Address, SignerError>;
+
+    /// Whether the 2FA messages are to be signed as the EIP-712 typed data by default,
+    /// e.g. since the signer is able to display them in the readable form only.
+    fn prefers_typed_data(&self) -> bool {
+        false
+    }
 }
@@ -0,0 +1,61 @@
+use crate::{EthereumSigner, JsonRpcSigner, PrivateKeySigner, RawTransaction, SignerError};
+
+use zksync_types::tx::{EIP712Message, TxEthSignature};
+use zksync_types::Address;
+
+/// Signer of the server-side Ethereum transactions (e.g. the operator ones).
+///
+/// The key is either kept locally, or the transactions are signed by a remote signer
+/// exposing the `eth_signTransaction` JSON RPC method (e.g. Web3Signer, which in turn
+/// can be backed by a cloud KMS or an HSM).
+#[derive(Debug, Clone)]
+pub enum OperatorSigner {
+    PrivateKey(PrivateKeySigner),
+    JsonRpc(JsonRpcSigner),
+}
+
+#[async_trait::async_trait]
+impl EthereumSigner for OperatorSigner {
+    async fn sign_message(&self, message: &[u8]) -> Result<TxEthSignature, SignerError> {
+        match self {
+            Self::PrivateKey(signer) => signer.sign_message(message).await,
+            Self::JsonRpc(signer) => signer.sign_message(message).await,
+        }
+    }
+
+    async fn sign_typed_data(
+        &self,
+        message: &EIP712Message,
+    ) -> Result<TxEthSignature, SignerError> {
+        match self {
+            Self::PrivateKey(signer) => signer.sign_typed_data(message).await,
+            Self::JsonRpc(signer) => signer.sign_typed_data(message).await,
+        }
+    }
+
+    async fn sign_transaction(&self, raw_tx: RawTransaction) -> Result<Vec<u8>, SignerError> {
+        match self {
+            Self::PrivateKey(signer) => signer.sign_transaction(raw_tx).await,
+            Self::JsonRpc(signer) => signer.sign_transaction(raw_tx).await,
+        }
+    }
+
+    async fn get_address(&self) -> Result<Address, SignerError> {
+        match self {
+            Self::PrivateKey(signer) => signer.get_address().await,
+            Self::JsonRpc(signer) => signer.get_address().await,
+        }
+    }
+}
+
+impl From<PrivateKeySigner> for OperatorSigner {
+    fn from(signer: PrivateKeySigner) -> Self {
+        Self::PrivateKey(signer)
+    }
+}
+
+impl From<JsonRpcSigner> for OperatorSigner {
+    fn from(signer: JsonRpcSigner) -> Self {
+        Self::JsonRpc(signer)
+    }
+}
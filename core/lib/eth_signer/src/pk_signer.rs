@@ -3,7 +3,7 @@ use crate::{EthereumSigner, SignerError};
 
 use secp256k1::SecretKey;
 
-use zksync_types::tx::{PackedEthSignature, TxEthSignature};
+use zksync_types::tx::{EIP712Message, PackedEthSignature, TxEthSignature};
 use zksync_types::{Address, H256};
 
 #[derive(Clone)]
@@ -39,6 +39,17 @@ impl EthereumSigner for PrivateKeySigner {
         Ok(TxEthSignature::EthereumSignature(pack))
     }
 
+    /// Signs the EIP-712 typed data hash:
+    /// sign(keccak256("\x19\x01" + domainSeparator + hashStruct(message))).
+    async fn sign_typed_data(
+        &self,
+        message: &EIP712Message,
+    ) -> Result<TxEthSignature, SignerError> {
+        let pack = PackedEthSignature::sign_raw(&self.private_key, &message.signing_hash())
+            .map_err(|err| SignerError::SigningFailed(err.to_string()))?;
+        Ok(TxEthSignature::EthereumSignature(pack))
+    }
+
     /// Signs and returns the RLP-encoded transaction.
     async fn sign_transaction(&self, raw_tx: RawTransaction) -> Result<Vec<u8>, SignerError> {
         let key = SecretKey::from_slice(self.private_key.as_bytes()).unwrap();
@@ -160,7 +160,15 @@ impl Transaction {
         }
     }
 
-    fn encode(&self, chain_id: u64, signature: Option<&Signature>) -> Vec<u8> {
+    /// Whether the transaction is a legacy one, i.e. its signature uses the EIP-155 `v`.
+    pub(crate) fn is_legacy(&self) -> bool {
+        matches!(
+            self.transaction_type.map(|t| t.as_u64()),
+            Some(LEGACY_TX_ID) | None
+        )
+    }
+
+    pub(crate) fn encode(&self, chain_id: u64, signature: Option<&Signature>) -> Vec<u8> {
         match self.transaction_type.map(|t| t.as_u64()) {
             Some(LEGACY_TX_ID) | None => {
                 let stream = self.encode_legacy(chain_id, signature);
@@ -187,10 +195,7 @@ impl Transaction {
 
     /// Sign and return a raw signed transaction.
     pub fn sign(self, sign: impl signing::Key, chain_id: u64) -> SignedTransaction {
-        let adjust_v_value = matches!(
-            self.transaction_type.map(|t| t.as_u64()),
-            Some(LEGACY_TX_ID) | None
-        );
+        let adjust_v_value = self.is_legacy();
 
         let encoded = self.encode(chain_id, None);
 
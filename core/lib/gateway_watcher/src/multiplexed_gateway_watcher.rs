@@ -4,14 +4,15 @@ use std::collections::HashMap;
 use std::time::{Duration, Instant};
 use thiserror::Error;
 use tokio::{task::JoinHandle, time};
-use tokio_stream::wrappers::IntervalStream;
+use tokio_stream::wrappers::{IntervalStream, ReceiverStream};
 use web3::types::{Block, BlockId, BlockNumber, H256, U64};
 
 use zksync_config::GatewayWatcherConfig;
-use zksync_eth_client::{EthereumGateway, MultiplexerEthereumClient};
+use zksync_eth_client::{new_heads::follow_new_heads, EthereumGateway, MultiplexerEthereumClient};
 use zksync_utils::retry_opt;
 
-/// Watcher which checks multiplexed client's gateways once within specified interval.
+/// Watcher which checks multiplexed client's gateways once within specified interval,
+/// or on every new block if the `newHeads` subscription is enabled.
 pub struct MultiplexedGatewayWatcher {
     /// Multiplexed client to be verified.
     client: MultiplexerEthereumClient,
@@ -25,6 +26,8 @@ pub struct MultiplexedGatewayWatcher {
     req_per_task_limit: Option<usize>,
     /// How many tasks are allowed to simultaneously make requests.
     task_limit: Option<usize>,
+    /// WebSocket URL of the node to follow the new blocks from.
+    new_heads_ws_url: Option<String>,
 }
 
 const MAX_BLOCK_NUMBER_DIFFERENCE: u64 = 1;
@@ -65,14 +68,32 @@ impl MultiplexedGatewayWatcher {
             req_timeout,
             req_per_task_limit,
             task_limit,
+            new_heads_ws_url: None,
         }
     }
 
+    /// Makes the watcher check the gateways on every new block received via the `newHeads`
+    /// subscription of the `ws_url` node. The interval is used for polling if the subscription fails.
+    pub fn with_new_heads_subscription(mut self, ws_url: Option<String>) -> Self {
+        self.new_heads_ws_url = ws_url;
+        self
+    }
+
     /// Starts actor.
     pub async fn run(self) {
         vlog::info!("Ethereum Gateway Watcher started");
 
-        IntervalStream::new(time::interval(self.interval))
+        let ticks = match self.new_heads_ws_url.clone() {
+            Some(ws_url) => {
+                let (new_heads, _) = follow_new_heads(Some(ws_url), self.interval);
+                ReceiverStream::new(new_heads).map(drop).boxed()
+            }
+            None => IntervalStream::new(time::interval(self.interval))
+                .map(drop)
+                .boxed(),
+        };
+
+        ticks
             .for_each_concurrent(self.task_limit, |_| self.check_client_gateways())
             .await
     }
@@ -198,6 +219,9 @@ impl MultiplexedGatewayWatcher {
                 }
             }
         }
+
+        // Report the health of the gateways collected while serving the requests.
+        self.client.report_health();
     }
 }
 
@@ -210,6 +234,7 @@ impl MultiplexedGatewayWatcher {
 pub fn run_multiplexed_gateway_watcher(
     eth_gateway: EthereumGateway,
     config: &GatewayWatcherConfig,
+    new_heads_ws_url: Option<String>,
 ) -> JoinHandle<()> {
     let gateway_watcher = MultiplexedGatewayWatcher::new(
         eth_gateway,
@@ -218,7 +243,8 @@ pub fn run_multiplexed_gateway_watcher(
         config.request_timeout(),
         Some(config.request_per_task_limit()),
         Some(config.task_limit()),
-    );
+    )
+    .with_new_heads_subscription(new_heads_ws_url);
 
     tokio::spawn(gateway_watcher.run())
 }
@@ -228,9 +254,14 @@ pub fn run_multiplexed_gateway_watcher(
 pub fn run_gateway_watcher_if_multiplexed(
     eth_gateway: EthereumGateway,
     config: &GatewayWatcherConfig,
+    new_heads_ws_url: Option<String>,
 ) -> Option<JoinHandle<()>> {
     if eth_gateway.is_multiplexed() {
-        Some(run_multiplexed_gateway_watcher(eth_gateway, config))
+        Some(run_multiplexed_gateway_watcher(
+            eth_gateway,
+            config,
+            new_heads_ws_url,
+        ))
     } else {
         None
     }
@@ -0,0 +1,49 @@
+//! CORS middleware of the HTTP servers built from the configured policy.
+
+// External uses
+use actix_cors::Cors;
+
+// Workspace uses
+use zksync_config::configs::api::CorsConfig;
+
+/// Creates the CORS middleware enforcing the `config` policy. Any HTTP method is allowed.
+pub fn cors(config: &CorsConfig) -> Cors {
+    let mut cors = Cors::default()
+        .allow_any_method()
+        .max_age(config.max_age_sec as usize);
+
+    if config.allows_any_origin() {
+        cors = cors.allow_any_origin();
+        // Wildcard can't be used in the responses to the requests with credentials.
+        if !config.allow_credentials {
+            cors = cors.send_wildcard();
+        }
+    } else {
+        for origin in config
+            .allowed_origins
+            .iter()
+            .filter(|origin| !origin.is_empty())
+        {
+            cors = cors.allowed_origin(origin);
+        }
+    }
+
+    if config.allows_any_header() {
+        cors = cors.allow_any_header();
+    } else {
+        let headers: Vec<_> = config
+            .allowed_headers
+            .iter()
+            .map(String::as_str)
+            .filter(|header| !header.is_empty())
+            .collect();
+        if !headers.is_empty() {
+            cors = cors.allowed_headers(headers);
+        }
+    }
+
+    if config.allow_credentials {
+        cors = cors.supports_credentials();
+    }
+    cors
+}
@@ -0,0 +1,4 @@
+//! Building blocks shared by the HTTP servers of the zkSync components.
+
+pub mod cors;
+pub mod log_filter;
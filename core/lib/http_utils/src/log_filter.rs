@@ -0,0 +1,88 @@
+//! Endpoints reading and replacing the log filter of the component at runtime.
+//!
+//! They're meant to be served by the internal (or authenticated) HTTP servers only, see `configure`.
+//! The components without such a server start the dedicated one, see `start_log_filter_server`.
+
+use std::{net::SocketAddr, thread};
+
+use actix_web::{web, App, HttpResponse, HttpServer};
+use tokio::task::JoinHandle;
+use zksync_utils::panic_notify::{spawn_panic_handler, ThreadPanicNotify};
+
+/// Returns the log filter directives currently in use.
+async fn log_filter() -> HttpResponse {
+    HttpResponse::Ok().json(vlog::log_filter())
+}
+
+/// Replaces the log filter directives (in the `RUST_LOG` format, e.g. `info,zksync_core::committer=debug`),
+/// so the log levels of the modules can be changed without restarting the component.
+async fn set_log_filter(directives: web::Json<String>) -> actix_web::Result<HttpResponse> {
+    vlog::set_log_filter(&directives).map_err(actix_web::error::ErrorBadRequest)?;
+    vlog::info!("Log filter is set to {}", directives.into_inner());
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Registers the `GET /log_filter` and `POST /log_filter` endpoints.
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource("/log_filter")
+            .route(web::get().to(log_filter))
+            .route(web::post().to(set_log_filter)),
+    );
+}
+
+/// Starts the internal server with the log filter endpoints only.
+/// It must not be available from outside of the cluster.
+pub fn start_log_filter_server(bind_addr: SocketAddr) -> JoinHandle<()> {
+    let (handler, panic_sender) = spawn_panic_handler();
+
+    thread::Builder::new()
+        .name("log-filter-server".to_string())
+        .spawn(move || {
+            let _panic_sentinel = ThreadPanicNotify(panic_sender);
+            let actix_runtime = actix_rt::System::new();
+
+            actix_runtime.block_on(async move {
+                HttpServer::new(|| App::new().configure(configure))
+                    .bind(bind_addr)
+                    .expect("failed to bind")
+                    .run()
+                    .await
+            })
+        })
+        .expect("failed to start log filter server");
+
+    handler
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{http::StatusCode, test, App};
+
+    #[actix_rt::test]
+    async fn log_filter_endpoints() {
+        let app = test::init_service(App::new().configure(configure)).await;
+
+        let request = test::TestRequest::get().uri("/log_filter").to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // The logger is not initialized in the test, so the filter can't be replaced.
+        let request = test::TestRequest::post()
+            .uri("/log_filter")
+            .set_json(&"info")
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        // Invalid directives are rejected as well.
+        let request = test::TestRequest::post()
+            .uri("/log_filter")
+            .set_json(&"info,[")
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
@@ -72,6 +72,9 @@ impl MempoolBlocksHandler {
                 &self.mempool_state,
             )
             .await?;
+        // Transactions replaced or cancelled by their owners after being loaded from the database
+        // must not be executed.
+        let txs = self.mempool_state.mark_txs_proposed(txs).await?;
 
         if !priority_ops.is_empty() || !txs.is_empty() {
             vlog::debug!(
@@ -112,7 +115,14 @@ impl MempoolBlocksHandler {
         // We have to clean garbage from mempool before running the block generator.
         // Remove any possible duplicates of already executed transactions
         // from the database.
-        self.mempool_state.collect_garbage().await;
+        // The proposed transactions are only returned to the queue once the executed ones are removed.
+        let restored = match self.mempool_state.collect_garbage().await {
+            Ok(()) => self.mempool_state.restore_transactions().await,
+            Err(err) => Err(err),
+        };
+        if let Err(err) = restored {
+            vlog::error!("Failed to restore the mempool transactions: {}", err);
+        }
         while let Some(request) = self.requests.next().await {
             match request {
                 MempoolBlocksRequest::GetBlock(block) => {
@@ -0,0 +1,96 @@
+//! Fee-based priority of the mempool transactions.
+//!
+//! Fees can be paid in different tokens, so to compare transactions with each other
+//! the fee is converted to USD using the latest known token prices and divided by the
+//! number of chunks the transaction takes in the block.
+
+// Built-in uses
+use std::collections::HashMap;
+
+// External uses
+use num::{rational::Ratio, traits::Pow, BigUint, Zero};
+
+// Workspace uses
+use zksync_storage::StorageProcessor;
+use zksync_types::{
+    mempool::SignedTxVariant, tx::error::TxAddError, SignedZkSyncTx, TokenId, TokenLike,
+};
+
+/// Fee in USD paid for a single chunk of the block.
+pub(crate) type FeePriority = Ratio<BigUint>;
+
+/// Cache of the token prices, which is filled while the priorities are calculated.
+///
+/// Prices are stored per the smallest token unit, so the raw fee amount can be multiplied by them.
+#[derive(Debug, Default)]
+pub(crate) struct TokenPricesCache {
+    prices: HashMap<TokenId, Ratio<BigUint>>,
+}
+
+impl TokenPricesCache {
+    async fn unit_price(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        token_id: TokenId,
+    ) -> Result<Ratio<BigUint>, TxAddError> {
+        if let Some(price) = self.prices.get(&token_id) {
+            return Ok(price.clone());
+        }
+
+        let token = storage
+            .tokens_schema()
+            .get_token(TokenLike::Id(token_id))
+            .await
+            .map_err(|_| TxAddError::DbError)?;
+        let price = storage
+            .tokens_schema()
+            .get_historical_ticker_price(token_id)
+            .await
+            .map_err(|_| TxAddError::DbError)?;
+
+        // Tokens without a known price (e.g. the ones not acceptable for fees anymore)
+        // give no priority to the transaction.
+        let price = match (token, price) {
+            (Some(token), Some(price)) => {
+                price.usd_price / BigUint::from(10u32).pow(u32::from(token.decimals))
+            }
+            _ => Ratio::zero(),
+        };
+
+        self.prices.insert(token_id, price.clone());
+        Ok(price)
+    }
+
+    async fn fee_in_usd(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        tx: &SignedZkSyncTx,
+    ) -> Result<Ratio<BigUint>, TxAddError> {
+        match tx.tx.get_fee_info() {
+            Some((_, TokenLike::Id(token_id), _, fee)) => {
+                let price = self.unit_price(storage, token_id).await?;
+                Ok(price * fee)
+            }
+            _ => Ok(Ratio::zero()),
+        }
+    }
+
+    /// Calculates the fee paid per chunk for a single transaction or a batch.
+    ///
+    /// Minimal amount of chunks is used for the calculation, since it doesn't require
+    /// accessing the accounts tree and is precise enough to compare the transactions.
+    pub(crate) async fn fee_priority(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        element: &SignedTxVariant,
+    ) -> Result<FeePriority, TxAddError> {
+        let mut total_fee = Ratio::zero();
+        let mut total_chunks = 0usize;
+        for tx in element.get_transactions() {
+            total_fee += self.fee_in_usd(storage, &tx).await?;
+            total_chunks += tx.tx.min_chunks();
+        }
+
+        Ok(total_fee / BigUint::from(total_chunks.max(1)))
+    }
+}
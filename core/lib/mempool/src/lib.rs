@@ -27,6 +27,7 @@ pub use crate::transactions_handler::MempoolTransactionRequest;
 use crate::transactions_handler::MempoolTransactionsHandler;
 
 mod block_handler;
+mod fee_priority;
 mod mempool_transactions_queue;
 mod state;
 mod transactions_handler;
@@ -38,6 +39,7 @@ pub fn run_mempool_tx_handler(
     db_pool: ConnectionPool,
     tx_requests: mpsc::Receiver<MempoolTransactionRequest>,
     block_chunk_sizes: Vec<usize>,
+    mempool_capacity: usize,
 ) -> JoinHandle<()> {
     let mempool_state = MempoolState::new(db_pool.clone());
     let max_block_size_chunks = *block_chunk_sizes
@@ -49,6 +51,7 @@ pub fn run_mempool_tx_handler(
         mempool_state,
         requests: tx_requests,
         max_block_size_chunks,
+        mempool_capacity,
     };
     tokio::spawn(handler.run())
 }
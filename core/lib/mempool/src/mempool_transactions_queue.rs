@@ -1,13 +1,15 @@
+use crate::fee_priority::FeePriority;
 use crate::MempoolState;
-use std::cmp::Ordering;
-use std::collections::{BinaryHeap, VecDeque};
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, VecDeque};
 use zksync_types::mempool::SignedTxVariant;
 use zksync_types::tx::error::TxAddError;
-use zksync_types::PriorityOp;
+use zksync_types::{AccountId, PriorityOp};
 
 #[derive(Debug, Clone)]
 struct MempoolPendingTransaction {
     valid_from: u64,
+    fee_priority: FeePriority,
     tx: SignedTxVariant,
 }
 
@@ -48,7 +50,7 @@ pub(crate) struct MempoolTransactionsQueue {
 impl MempoolTransactionsQueue {
     pub(crate) fn new(
         l1_transactions: VecDeque<PriorityOp>,
-        l2_transactions: VecDeque<SignedTxVariant>,
+        l2_transactions: Vec<(SignedTxVariant, FeePriority)>,
     ) -> Self {
         let mut res = Self {
             ready_l2_transactions: Default::default(),
@@ -57,8 +59,8 @@ impl MempoolTransactionsQueue {
         };
         // Due to complexity of json structure in database for transactions it's easier and safer
         // to add even not ready txs to mempool and prepare them before when it's needed.
-        for tx in l2_transactions {
-            res.add_l2_transaction(tx)
+        for (tx, fee_priority) in l2_transactions {
+            res.add_l2_transaction(tx, fee_priority)
         }
         res
     }
@@ -79,7 +81,7 @@ impl MempoolTransactionsQueue {
         }
     }
 
-    fn add_l2_transaction(&mut self, tx: SignedTxVariant) {
+    fn add_l2_transaction(&mut self, tx: SignedTxVariant, fee_priority: FeePriority) {
         self.pending_l2_transactions
             .push(MempoolPendingTransaction {
                 valid_from: tx
@@ -88,10 +90,60 @@ impl MempoolTransactionsQueue {
                     .map(|tx| tx.tx.valid_from())
                     .max()
                     .unwrap_or(0),
+                fee_priority,
                 tx,
             });
     }
 
+    /// Reorders transactions so that the ones paying the higher fee per chunk go first.
+    ///
+    /// Transactions of the same account keep their relative order, so that nonces are
+    /// never executed out of order. Transactions with equal priority keep the original order.
+    fn order_by_fee_priority(
+        txs: Vec<(SignedTxVariant, FeePriority)>,
+    ) -> VecDeque<SignedTxVariant> {
+        // According to our convention in batch `fee transaction` would be the last one,
+        // so its account is considered to be the sender of the batch.
+        fn sender(tx: &SignedTxVariant) -> Option<AccountId> {
+            let tx = match tx {
+                SignedTxVariant::Tx(tx) => tx,
+                SignedTxVariant::Batch(batch) => batch.txs.last()?,
+            };
+            tx.account_id().ok()
+        }
+
+        let total_txs = txs.len();
+        let mut accounts: HashMap<
+            Option<AccountId>,
+            VecDeque<(usize, SignedTxVariant, FeePriority)>,
+        > = HashMap::new();
+        for (idx, (tx, fee_priority)) in txs.into_iter().enumerate() {
+            accounts
+                .entry(sender(&tx))
+                .or_default()
+                .push_back((idx, tx, fee_priority));
+        }
+
+        // Heap contains the first not yet selected transaction of every account.
+        let mut heap = BinaryHeap::with_capacity(accounts.len());
+        for (account, queue) in accounts.iter() {
+            let (idx, _, fee_priority) = queue.front().expect("queue can't be empty");
+            heap.push((fee_priority.clone(), Reverse(*idx), *account));
+        }
+
+        let mut result = VecDeque::with_capacity(total_txs);
+        while let Some((_, _, account)) = heap.pop() {
+            let queue = accounts.get_mut(&account).expect("account is in the map");
+            let (_, tx, _) = queue.pop_front().expect("queue can't be empty");
+            result.push_back(tx);
+
+            if let Some((idx, _, fee_priority)) = queue.front() {
+                heap.push((fee_priority.clone(), Reverse(*idx), account));
+            }
+        }
+        result
+    }
+
     fn prepare_new_ready_l2_transactions(&mut self, block_timestamp: u64) {
         // Move some pending transactions to the ready_txs queue
         let mut ready_pending_l2_operations = {
@@ -99,7 +151,8 @@ impl MempoolTransactionsQueue {
 
             while let Some(pending_tx) = self.pending_l2_transactions.peek() {
                 if pending_tx.valid_from <= block_timestamp {
-                    ready_pending_l2_operations.push(pending_tx.tx.clone());
+                    ready_pending_l2_operations
+                        .push((pending_tx.tx.clone(), pending_tx.fee_priority.clone()));
                     self.pending_l2_transactions.pop();
                 } else {
                     break;
@@ -108,7 +161,7 @@ impl MempoolTransactionsQueue {
 
             // Now transactions should be sorted by the nonce (transaction natural order)
             // According to our convention in batch `fee transaction` would be the last one, so we would use nonce from it as a key for sort
-            ready_pending_l2_operations.sort_by_key(|tx| match tx {
+            ready_pending_l2_operations.sort_by_key(|(tx, _)| match tx {
                 SignedTxVariant::Tx(tx) => tx.tx.nonce(),
                 SignedTxVariant::Batch(batch) => batch
                     .txs
@@ -118,7 +171,8 @@ impl MempoolTransactionsQueue {
                     .nonce(),
             });
 
-            VecDeque::<SignedTxVariant>::from(ready_pending_l2_operations)
+            // Transactions paying more go first.
+            Self::order_by_fee_priority(ready_pending_l2_operations)
         };
 
         self.ready_l2_transactions
@@ -202,6 +256,7 @@ impl MempoolTransactionsQueue {
 #[cfg(test)]
 mod tests {
     use chrono::Utc;
+    use num::{BigUint, Zero};
 
     use zksync_types::tx::{TimeRange, Transfer, Withdraw};
     use zksync_types::{
@@ -230,6 +285,26 @@ mod tests {
         })
     }
 
+    fn get_transfer(account_id: u32, nonce: u32) -> SignedTxVariant {
+        let transfer = Transfer::new(
+            AccountId(account_id),
+            Address::random(),
+            Address::random(),
+            TokenId(0),
+            500u32.into(),
+            20u32.into(),
+            Nonce(nonce),
+            Default::default(),
+            None,
+        );
+
+        SignedTxVariant::Tx(SignedZkSyncTx {
+            tx: ZkSyncTx::Transfer(Box::new(transfer)),
+            eth_sign_data: None,
+            created_at: Utc::now(),
+        })
+    }
+
     fn get_withdraw() -> SignedTxVariant {
         let withdraw = Withdraw::new(
             AccountId(3),
@@ -368,7 +443,7 @@ mod tests {
 
         // Insert transactions to the mempool transcations queue
         {
-            transactions_queue.add_l2_transaction(withdraw0.clone());
+            transactions_queue.add_l2_transaction(withdraw0.clone(), FeePriority::zero());
             assert_eq!(
                 transactions_queue
                     .pending_l2_transactions
@@ -379,8 +454,8 @@ mod tests {
             );
 
             // Some "random" order for trancsactions
-            transactions_queue.add_l2_transaction(transfer2.clone());
-            transactions_queue.add_l2_transaction(transfer1.clone());
+            transactions_queue.add_l2_transaction(transfer2.clone(), FeePriority::zero());
+            transactions_queue.add_l2_transaction(transfer1.clone(), FeePriority::zero());
         }
 
         // At first we should have only one transaction ready
@@ -416,4 +491,42 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_fee_priority_ordering() {
+        let priority = |value: u32| FeePriority::from_integer(BigUint::from(value));
+
+        let first_tx_a = get_transfer(1, 1);
+        let second_tx_a = get_transfer(1, 2);
+        let tx_b = get_transfer(2, 1);
+        let tx_c = get_transfer(3, 3);
+        let tx_d = get_transfer(4, 4);
+
+        let mut transactions_queue = MempoolTransactionsQueue::new(
+            Default::default(),
+            vec![
+                (first_tx_a.clone(), priority(1)),
+                (second_tx_a.clone(), priority(10)),
+                (tx_b.clone(), priority(5)),
+                (tx_c.clone(), priority(7)),
+                (tx_d.clone(), priority(5)),
+            ],
+        );
+        transactions_queue.prepare_new_ready_l2_transactions(0);
+
+        // Transactions paying more go first, but the transactions of the same account
+        // are never reordered, and the equal priorities keep the nonce order.
+        let expected_order = vec![tx_c, tx_b, tx_d, first_tx_a, second_tx_a];
+        let actual_order: Vec<_> = transactions_queue.ready_l2_transactions.into();
+        assert_eq!(
+            actual_order
+                .iter()
+                .map(|tx| tx.hashes())
+                .collect::<Vec<_>>(),
+            expected_order
+                .iter()
+                .map(|tx| tx.hashes())
+                .collect::<Vec<_>>()
+        );
+    }
 }
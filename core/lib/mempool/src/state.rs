@@ -1,14 +1,14 @@
 use std::collections::HashSet;
 use std::time::Instant;
 
-use zksync_storage::{ConnectionPool, StorageProcessor};
+use zksync_storage::{ConnectionPool, QueryResult, StorageProcessor};
 use zksync_types::{
     mempool::{SignedTxVariant, SignedTxsBatch},
     tx::{error::TxAddError, TxHash},
     Address, TransferOp, TransferToNewOp, ZkSyncTx,
 };
 
-use crate::MempoolTransactionsQueue;
+use crate::{fee_priority::TokenPricesCache, MempoolTransactionsQueue};
 
 #[derive(Debug, Clone)]
 pub(crate) struct MempoolState {
@@ -98,22 +98,88 @@ impl MempoolState {
         }
     }
 
-    pub async fn collect_garbage(&self) {
-        let mut storage = self.db_pool.access_storage().await.expect("Db error");
+    pub async fn collect_garbage(&self) -> QueryResult<()> {
+        let mut storage = self.db_pool.access_storage().await?;
         // Remove any possible duplicates of already executed transactions
         // from the database.
-        storage
+        storage.chain().mempool_schema().collect_garbage().await
+    }
+
+    /// Restores the transactions queue on startup.
+    ///
+    /// Mempool transactions are stored in the database before the response is sent
+    /// to the user, so the queue survives restarts of the server. The transactions proposed
+    /// to the state keeper but not executed before the restart are returned to the queue.
+    /// Must be called after the executed transactions are removed by the garbage collection.
+    pub async fn restore_transactions(&self) -> QueryResult<()> {
+        let mut storage = self.db_pool.access_storage().await?;
+        let mut transaction = storage.start_transaction().await?;
+
+        let returned_txs = transaction
             .chain()
             .mempool_schema()
-            .collect_garbage()
-            .await
-            .expect("Db error");
+            .restore_proposed_txs()
+            .await?;
+        let restored_txs = transaction
+            .chain()
+            .mempool_schema()
+            .get_mempool_size()
+            .await?;
+        transaction.commit().await?;
+
+        vlog::info!(
+            "Restored {} transactions in the mempool from the database, {} of them were proposed before the restart",
+            restored_txs,
+            returned_txs
+        );
+        metrics::gauge!("mempool.restored_txs", restored_txs as f64);
+        Ok(())
     }
 
     pub fn new(db_pool: ConnectionPool) -> Self {
         Self { db_pool }
     }
 
+    /// Marks the single transactions proposed for the next miniblock in the database, so their owners
+    /// can't replace or cancel them anymore. Returns the transactions to execute, i.e. without the ones which
+    /// have been replaced or cancelled since the transaction queue was loaded.
+    pub async fn mark_txs_proposed(
+        &self,
+        txs: Vec<SignedTxVariant>,
+    ) -> Result<Vec<SignedTxVariant>, TxAddError> {
+        let tx_hashes: Vec<_> = txs
+            .iter()
+            .filter_map(|tx| match tx {
+                SignedTxVariant::Tx(tx) => Some(tx.hash()),
+                SignedTxVariant::Batch(_) => None,
+            })
+            .collect();
+        if tx_hashes.is_empty() {
+            return Ok(txs);
+        }
+
+        let marked: HashSet<_> = self
+            .db_pool
+            .access_storage()
+            .await
+            .map_err(|_| TxAddError::DbError)?
+            .chain()
+            .mempool_schema()
+            .mark_txs_proposed(&tx_hashes)
+            .await
+            .map_err(|_| TxAddError::DbError)?
+            .into_iter()
+            .collect();
+
+        Ok(txs
+            .into_iter()
+            .filter(|tx| match tx {
+                SignedTxVariant::Tx(tx) => marked.contains(&tx.hash()),
+                SignedTxVariant::Batch(_) => true,
+            })
+            .collect())
+    }
+
     pub async fn get_transaction_queue(
         &self,
         executed_txs: &[TxHash],
@@ -144,7 +210,14 @@ impl MempoolState {
             .await
             .map_err(|_| TxAddError::DbError)?;
 
-        let transactions_queue = MempoolTransactionsQueue::new(priority_ops, mempool_txs);
+        let mut prices = TokenPricesCache::default();
+        let mut prioritized_txs = Vec::with_capacity(mempool_txs.len());
+        for tx in mempool_txs {
+            let fee_priority = prices.fee_priority(&mut transaction, &tx).await?;
+            prioritized_txs.push((tx, fee_priority));
+        }
+
+        let transactions_queue = MempoolTransactionsQueue::new(priority_ops, prioritized_txs);
 
         Ok(transactions_queue)
     }
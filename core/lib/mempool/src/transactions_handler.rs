@@ -1,13 +1,15 @@
 use futures::channel::{mpsc, oneshot};
 use futures::StreamExt;
+use vlog::Instrument;
 
-use zksync_storage::ConnectionPool;
+use zksync_storage::{chain::mempool::records::QueuedTx, ConnectionPool, StorageProcessor};
 use zksync_types::{
-    mempool::SignedTxsBatch,
-    tx::{error::TxAddError, TxEthSignature},
-    PriorityOp, SignedZkSyncTx,
+    mempool::{SignedTxVariant, SignedTxsBatch},
+    tx::{error::TxAddError, TxEthSignature, TxHash},
+    AccountId, Nonce, PriorityOp, SerialId, SignedZkSyncTx,
 };
 
+use crate::fee_priority::{FeePriority, TokenPricesCache};
 use crate::state::MempoolState;
 
 #[derive(Debug)]
@@ -23,6 +25,9 @@ pub enum MempoolTransactionRequest {
         bool,
         oneshot::Sender<Result<(), TxAddError>>,
     ),
+    /// Remove the priority ops which Ethereum transactions were reverted by the reorg.
+    /// Already executed priority ops can't be removed and are reported instead.
+    RemovePriorityOps(Vec<SerialId>, oneshot::Sender<Result<(), TxAddError>>),
     /// Add a new batch of transactions to the mempool. All transactions in batch must
     /// be either executed successfully, or otherwise fail all together.
     /// Invariants for each individual transaction in the batch are the same as in
@@ -32,6 +37,97 @@ pub enum MempoolTransactionRequest {
         Vec<TxEthSignature>,
         oneshot::Sender<Result<(), TxAddError>>,
     ),
+    /// Remove the queued transaction with the given hash, account ID and nonce from the mempool.
+    /// The cancellation should be previously authorized by the account owner.
+    CancelTx(
+        TxHash,
+        AccountId,
+        Nonce,
+        oneshot::Sender<Result<(), TxAddError>>,
+    ),
+}
+
+/// Rejects the transactions moving the paused tokens. The API checks the tokens as well,
+/// but it caches the paused ones, so the transactions sent right after the pause are caught here.
+async fn check_paused_tokens(
+    storage: &mut StorageProcessor<'_>,
+    txs: &[SignedZkSyncTx],
+) -> Result<(), TxAddError> {
+    let tokens: Vec<_> = txs.iter().flat_map(|tx| tx.tx.pausable_tokens()).collect();
+    if tokens.is_empty() {
+        return Ok(());
+    }
+
+    let paused_tokens = storage
+        .tokens_schema()
+        .load_paused_tokens()
+        .await
+        .map_err(|err| {
+            vlog::error!("Mempool storage access error: {}", err);
+            TxAddError::DbError
+        })?;
+    match tokens
+        .into_iter()
+        .find(|token| paused_tokens.contains(token))
+    {
+        Some(token) => Err(TxAddError::TokenPaused(token)),
+        None => Ok(()),
+    }
+}
+
+/// Rejects the transactions while the operator has paused the acceptance of the new transactions.
+async fn check_tx_acceptance_pause(storage: &mut StorageProcessor<'_>) -> Result<(), TxAddError> {
+    let pause = storage
+        .misc_schema()
+        .load_tx_acceptance_pause()
+        .await
+        .map_err(|err| {
+            vlog::error!("Mempool storage access error: {}", err);
+            TxAddError::DbError
+        })?;
+    match pause {
+        Some(_) => Err(TxAddError::TxAcceptancePaused),
+        None => Ok(()),
+    }
+}
+
+/// Checks whether the queued transaction can be replaced with the new one with the same nonce.
+///
+/// Only single transactions (not belonging to any batch) can be replaced, and the new transaction
+/// must pay the fee in the same token as the queued one, with the amount being strictly higher.
+fn check_tx_replacement(queued_tx: &QueuedTx, new_tx: &SignedZkSyncTx) -> Result<(), TxAddError> {
+    if queued_tx.batch_id.is_some() {
+        return Err(TxAddError::ReplacementNotPossible);
+    }
+
+    match (queued_tx.tx.get_fee_info(), new_tx.get_fee_info()) {
+        (Some((_, queued_token, _, queued_fee)), Some((_, new_token, _, new_fee))) => {
+            if queued_token != new_token {
+                Err(TxAddError::ReplacementNotPossible)
+            } else if new_fee <= queued_fee {
+                Err(TxAddError::ReplacementFeeTooLow)
+            } else {
+                Ok(())
+            }
+        }
+        _ => Err(TxAddError::ReplacementNotPossible),
+    }
+}
+
+/// Checks whether the queued transaction is the one the owner wants to cancel.
+///
+/// Same as with the replacement, only single transactions (not belonging to any batch) can be cancelled.
+fn check_tx_cancellation(queued_tx: Option<&QueuedTx>, tx_hash: TxHash) -> Result<(), TxAddError> {
+    match queued_tx {
+        Some(queued_tx) if queued_tx.tx.hash() == tx_hash => {
+            if queued_tx.batch_id.is_some() {
+                Err(TxAddError::CancellationNotPossible)
+            } else {
+                Ok(())
+            }
+        }
+        _ => Err(TxAddError::TxNotQueued),
+    }
 }
 
 pub(crate) struct MempoolTransactionsHandler {
@@ -39,9 +135,60 @@ pub(crate) struct MempoolTransactionsHandler {
     pub mempool_state: MempoolState,
     pub requests: mpsc::Receiver<MempoolTransactionRequest>,
     pub max_block_size_chunks: usize,
+    pub mempool_capacity: usize,
 }
 
 impl MempoolTransactionsHandler {
+    /// Makes sure there is enough space in the mempool for the new element.
+    ///
+    /// If the mempool is full, the queued transactions paying the lowest fee per chunk
+    /// are evicted, provided that the new element pays more. Fee priorities of the queued
+    /// transactions are stored on insertion, so the mempool is not re-priced here.
+    async fn ensure_capacity(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        new_element: &SignedTxVariant,
+        new_element_priority: &FeePriority,
+    ) -> Result<(), TxAddError> {
+        let mempool_size = storage
+            .chain()
+            .mempool_schema()
+            .get_mempool_size()
+            .await
+            .map_err(|_| TxAddError::DbError)? as usize;
+        let required_size = mempool_size + new_element.hashes().len();
+        if required_size <= self.mempool_capacity {
+            return Ok(());
+        }
+
+        let amount = required_size - self.mempool_capacity;
+        let txs_to_evict = storage
+            .chain()
+            .mempool_schema()
+            .load_eviction_candidates(new_element_priority, amount)
+            .await
+            .map_err(|err| {
+                vlog::error!("Mempool storage access error: {}", err);
+                TxAddError::DbError
+            })?;
+        if txs_to_evict.len() < amount {
+            return Err(TxAddError::MempoolIsFull);
+        }
+
+        storage
+            .chain()
+            .mempool_schema()
+            .remove_txs(&txs_to_evict)
+            .await
+            .map_err(|err| {
+                vlog::error!("Mempool storage access error: {}", err);
+                TxAddError::DbError
+            })?;
+        metrics::counter!("mempool.evicted_txs", txs_to_evict.len() as u64);
+
+        Ok(())
+    }
+
     async fn add_tx(&mut self, tx: SignedZkSyncTx) -> Result<(), TxAddError> {
         // Correctness should be checked by `signature_checker`, thus
         // `tx.check_correctness()` is not invoked here.
@@ -49,12 +196,14 @@ impl MempoolTransactionsHandler {
             vlog::error!("Mempool storage access error: {}", err);
             TxAddError::DbError
         })?;
+        check_tx_acceptance_pause(&mut storage).await?;
 
+        // Close operation does not exist so we will never met this error
+        let account_id = tx.account_id().map_err(|_| TxAddError::Other)?;
         let nonce = storage
             .chain()
             .account_schema()
-            // Close operation does not exist so we will never met this error
-            .estimate_nonce(tx.account_id().map_err(|_| TxAddError::Other)?)
+            .estimate_nonce(account_id)
             .await
             .map_err(|_| TxAddError::DbError)?
             .unwrap_or_default();
@@ -62,17 +211,79 @@ impl MempoolTransactionsHandler {
         if tx.nonce() < nonce {
             return Err(TxAddError::NonceMismatch);
         }
+        check_paused_tokens(&mut storage, std::slice::from_ref(&tx)).await?;
 
-        storage
+        let queued_tx = storage
             .chain()
             .mempool_schema()
-            .insert_tx(&tx)
+            .get_queued_tx_by_nonce(account_id, tx.nonce())
             .await
             .map_err(|err| {
                 vlog::error!("Mempool storage access error: {}", err);
                 TxAddError::DbError
             })?;
 
+        if let Some(queued_tx) = &queued_tx {
+            if queued_tx.tx.hash() == tx.hash() {
+                // The very same transaction is already queued, nothing to do.
+                return Ok(());
+            }
+            check_tx_replacement(queued_tx, &tx)?;
+        }
+
+        let element = SignedTxVariant::Tx(tx.clone());
+        let fee_priority = TokenPricesCache::default()
+            .fee_priority(&mut storage, &element)
+            .await?;
+        let mut transaction = storage.start_transaction().await.map_err(|err| {
+            vlog::error!("Mempool storage access error: {}", err);
+            TxAddError::DbError
+        })?;
+
+        if let Some(queued_tx) = queued_tx {
+            let replaced = transaction
+                .chain()
+                .mempool_schema()
+                .replace_tx(queued_tx.tx.hash(), &tx)
+                .await
+                .map_err(|err| {
+                    vlog::error!("Mempool storage access error: {}", err);
+                    TxAddError::DbError
+                })?;
+            // The queued transaction has been already included into a block,
+            // so its nonce is used.
+            if !replaced {
+                return Err(TxAddError::NonceMismatch);
+            }
+            metrics::increment_counter!("mempool.replaced_txs");
+        } else {
+            self.ensure_capacity(&mut transaction, &element, &fee_priority)
+                .await?;
+            transaction
+                .chain()
+                .mempool_schema()
+                .insert_tx(&tx)
+                .await
+                .map_err(|err| {
+                    vlog::error!("Mempool storage access error: {}", err);
+                    TxAddError::DbError
+                })?;
+        }
+
+        transaction
+            .chain()
+            .mempool_schema()
+            .set_tx_fee_priority(tx.hash(), &fee_priority)
+            .await
+            .map_err(|err| {
+                vlog::error!("Mempool storage access error: {}", err);
+                TxAddError::DbError
+            })?;
+        transaction.commit().await.map_err(|err| {
+            vlog::error!("Mempool storage access error: {}", err);
+            TxAddError::DbError
+        })?;
+
         let labels = vec![
             ("stage", "mempool".to_string()),
             ("name", tx.tx.variance_name()),
@@ -83,6 +294,46 @@ impl MempoolTransactionsHandler {
         Ok(())
     }
 
+    async fn cancel_tx(
+        &mut self,
+        tx_hash: TxHash,
+        account_id: AccountId,
+        nonce: Nonce,
+    ) -> Result<(), TxAddError> {
+        let mut storage = self.db_pool.access_storage().await.map_err(|err| {
+            vlog::error!("Mempool storage access error: {}", err);
+            TxAddError::DbError
+        })?;
+
+        let queued_tx = storage
+            .chain()
+            .mempool_schema()
+            .get_queued_tx_by_nonce(account_id, nonce)
+            .await
+            .map_err(|err| {
+                vlog::error!("Mempool storage access error: {}", err);
+                TxAddError::DbError
+            })?;
+        check_tx_cancellation(queued_tx.as_ref(), tx_hash)?;
+
+        let cancelled = storage
+            .chain()
+            .mempool_schema()
+            .cancel_queued_tx(tx_hash)
+            .await
+            .map_err(|err| {
+                vlog::error!("Mempool storage access error: {}", err);
+                TxAddError::DbError
+            })?;
+        // The transaction has been already proposed for a block or included into it.
+        if !cancelled {
+            return Err(TxAddError::TxNotQueued);
+        }
+        metrics::increment_counter!("mempool.cancelled_txs");
+
+        Ok(())
+    }
+
     /// Add priority operations to the mempool. For a better UX, we save unconfirmed transactions
     /// to the database. And we will move them to the real queue when they are confirmed.
     async fn add_priority_ops(
@@ -133,6 +384,41 @@ impl MempoolTransactionsHandler {
         Ok(())
     }
 
+    async fn remove_priority_ops(&mut self, serial_ids: Vec<SerialId>) -> Result<(), TxAddError> {
+        let mut storage = self.db_pool.access_storage().await.map_err(|err| {
+            vlog::error!("Mempool storage access error: {}", err);
+            TxAddError::DbError
+        })?;
+        let last_processed_priority_op = storage
+            .chain()
+            .operations_schema()
+            .get_max_priority_op_serial_id()
+            .await
+            .map_err(|_| TxAddError::DbError)?;
+
+        let (executed, pending): (Vec<_>, Vec<_>) = serial_ids
+            .into_iter()
+            .partition(|serial_id| Some(*serial_id) <= last_processed_priority_op);
+        if !executed.is_empty() {
+            vlog::error!(
+                "Priority operations {:?} reverted on Ethereum are already executed, manual intervention is required",
+                executed
+            );
+            metrics::increment_counter!("mempool.executed_priority_ops_reverted");
+        }
+
+        storage
+            .chain()
+            .mempool_schema()
+            .remove_priority_ops_from_mempool(&pending)
+            .await
+            .map_err(|err| {
+                vlog::error!("Mempool storage access error: {}", err);
+                TxAddError::DbError
+            })?;
+        Ok(())
+    }
+
     async fn add_batch(
         &mut self,
         txs: Vec<SignedZkSyncTx>,
@@ -148,6 +434,7 @@ impl MempoolTransactionsHandler {
             vlog::error!("Mempool storage access error: {}", err);
             TxAddError::DbError
         })?;
+        check_tx_acceptance_pause(&mut storage).await?;
 
         for tx in txs.iter() {
             // Correctness should be checked by `signature_checker`, thus
@@ -166,10 +453,19 @@ impl MempoolTransactionsHandler {
             }
         }
 
+        check_paused_tokens(&mut storage, &batch.txs).await?;
+
         if self.mempool_state.chunks_for_batch(&batch).await? > self.max_block_size_chunks {
             return Err(TxAddError::BatchTooBig);
         }
 
+        let element = SignedTxVariant::Batch(batch.clone());
+        let fee_priority = TokenPricesCache::default()
+            .fee_priority(&mut storage, &element)
+            .await?;
+        self.ensure_capacity(&mut storage, &element, &fee_priority)
+            .await?;
+
         for tx in &batch.txs {
             let labels = vec![
                 ("stage", "mempool".to_string()),
@@ -198,7 +494,8 @@ impl MempoolTransactionsHandler {
         while let Some(request) = self.requests.next().await {
             match request {
                 MempoolTransactionRequest::NewTx(tx, resp) => {
-                    let tx_add_result = self.add_tx(*tx).await;
+                    let span = vlog::tx_span("mempool.add_tx", tx.hash().as_ref());
+                    let tx_add_result = self.add_tx(*tx).instrument(span).await;
                     resp.send(tx_add_result).unwrap_or_default();
                 }
                 MempoolTransactionRequest::NewTxsBatch(txs, eth_signatures, resp) => {
@@ -209,7 +506,127 @@ impl MempoolTransactionsHandler {
                     let tx_add_result = self.add_priority_ops(ops, confirmed).await;
                     resp.send(tx_add_result).unwrap_or_default();
                 }
+                MempoolTransactionRequest::RemovePriorityOps(serial_ids, resp) => {
+                    let result = self.remove_priority_ops(serial_ids).await;
+                    resp.send(result).unwrap_or_default();
+                }
+                MempoolTransactionRequest::CancelTx(tx_hash, account_id, nonce, resp) => {
+                    let span = vlog::tx_span("mempool.cancel_tx", tx_hash.as_ref());
+                    let result = self
+                        .cancel_tx(tx_hash, account_id, nonce)
+                        .instrument(span)
+                        .await;
+                    resp.send(result).unwrap_or_default();
+                }
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use zksync_types::{
+        tx::{Transfer, Withdraw},
+        AccountId, Address, Nonce, TokenId, ZkSyncTx,
+    };
+
+    use super::*;
+
+    fn transfer(token: TokenId, fee: u32) -> SignedZkSyncTx {
+        let transfer = Transfer::new(
+            AccountId(42),
+            Address::repeat_byte(1),
+            Address::repeat_byte(2),
+            token,
+            100u32.into(),
+            fee.into(),
+            Nonce(7),
+            Default::default(),
+            None,
+        );
+
+        SignedZkSyncTx {
+            tx: ZkSyncTx::Transfer(Box::new(transfer)),
+            eth_sign_data: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    fn queued(tx: SignedZkSyncTx, batch_id: Option<i64>) -> QueuedTx {
+        QueuedTx { tx, batch_id }
+    }
+
+    #[test]
+    fn tx_replacement() {
+        let queued_tx = queued(transfer(TokenId(0), 10), None);
+
+        // Strictly higher fee in the same token is required.
+        assert!(check_tx_replacement(&queued_tx, &transfer(TokenId(0), 11)).is_ok());
+        assert!(matches!(
+            check_tx_replacement(&queued_tx, &transfer(TokenId(0), 10)),
+            Err(TxAddError::ReplacementFeeTooLow)
+        ));
+        assert!(matches!(
+            check_tx_replacement(&queued_tx, &transfer(TokenId(0), 9)),
+            Err(TxAddError::ReplacementFeeTooLow)
+        ));
+
+        // Fees in different tokens cannot be compared.
+        assert!(matches!(
+            check_tx_replacement(&queued_tx, &transfer(TokenId(1), 100)),
+            Err(TxAddError::ReplacementNotPossible)
+        ));
+
+        // The type of the transaction may be changed.
+        let withdraw = Withdraw::new(
+            AccountId(42),
+            Address::repeat_byte(1),
+            Address::repeat_byte(1),
+            TokenId(0),
+            100u32.into(),
+            20u32.into(),
+            Nonce(7),
+            Default::default(),
+            None,
+        );
+        let withdraw = SignedZkSyncTx {
+            tx: ZkSyncTx::Withdraw(Box::new(withdraw)),
+            eth_sign_data: None,
+            created_at: Utc::now(),
+        };
+        assert!(check_tx_replacement(&queued_tx, &withdraw).is_ok());
+
+        // Transactions from batches are never replaced.
+        let queued_batch_tx = queued(transfer(TokenId(0), 10), Some(1));
+        assert!(matches!(
+            check_tx_replacement(&queued_batch_tx, &transfer(TokenId(0), 100)),
+            Err(TxAddError::ReplacementNotPossible)
+        ));
+    }
+
+    #[test]
+    fn tx_cancellation() {
+        let queued_tx = queued(transfer(TokenId(0), 10), None);
+        assert!(check_tx_cancellation(Some(&queued_tx), queued_tx.tx.hash()).is_ok());
+
+        // Another transaction is queued with this nonce.
+        let other_tx = transfer(TokenId(0), 11);
+        assert!(matches!(
+            check_tx_cancellation(Some(&queued_tx), other_tx.hash()),
+            Err(TxAddError::TxNotQueued)
+        ));
+        assert!(matches!(
+            check_tx_cancellation(None, queued_tx.tx.hash()),
+            Err(TxAddError::TxNotQueued)
+        ));
+
+        // Transactions from batches are never cancelled.
+        let queued_batch_tx = queued(transfer(TokenId(0), 10), Some(1));
+        assert!(matches!(
+            check_tx_cancellation(Some(&queued_batch_tx), queued_batch_tx.tx.hash()),
+            Err(TxAddError::CancellationNotPossible)
+        ));
+    }
+}
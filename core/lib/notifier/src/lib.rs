@@ -1,8 +1,17 @@
+use std::time::Duration;
+
 use matter_most_notifier::MatterMostNotifier;
 use reqwest::Url;
+use slack_notifier::SlackNotifier;
+use telegram_notifier::TelegramNotifier;
 use zksync_types::tokens::Token;
 
 mod matter_most_notifier;
+mod slack_notifier;
+mod telegram_notifier;
+
+/// Timeout of the requests sending the alerts, so a hanging channel doesn't block the alerter.
+const NOTIFY_TIMEOUT: Duration = Duration::from_secs(10);
 
 pub struct Notifier {
     matter_most_notifier: MatterMostNotifier,
@@ -27,3 +36,40 @@ impl Notifier {
         Ok(())
     }
 }
+
+/// Sends the alerts to the operators in all of the configured channels.
+#[derive(Default)]
+pub struct AlertNotifier {
+    slack_notifier: Option<SlackNotifier>,
+    telegram_notifier: Option<TelegramNotifier>,
+}
+
+impl AlertNotifier {
+    pub fn with_slack(mut self, webhook_url: Url) -> Self {
+        self.slack_notifier = Some(SlackNotifier::new(webhook_url));
+        self
+    }
+
+    pub fn with_telegram(mut self, bot_token: String, chat_id: String) -> Self {
+        self.telegram_notifier = Some(TelegramNotifier::new(bot_token, chat_id));
+        self
+    }
+
+    /// Returns `false` if there are no channels to send the alerts to.
+    pub fn has_channels(&self) -> bool {
+        self.slack_notifier.is_some() || self.telegram_notifier.is_some()
+    }
+
+    /// Sends the alert to every channel, even if some of them fail.
+    /// Returns the first error if any.
+    pub async fn send_alert(&self, text: &str) -> anyhow::Result<()> {
+        let mut result = Ok(());
+        if let Some(slack_notifier) = &self.slack_notifier {
+            result = result.and(slack_notifier.send_notify(text).await);
+        }
+        if let Some(telegram_notifier) = &self.telegram_notifier {
+            result = result.and(telegram_notifier.send_notify(text).await);
+        }
+        result
+    }
+}
@@ -1,5 +1,7 @@
 use reqwest::{Client, Url};
 
+use crate::NOTIFY_TIMEOUT;
+
 pub struct MatterMostNotifier {
     webhook_url: Url,
     client: Client,
@@ -9,7 +11,10 @@ impl MatterMostNotifier {
     pub fn new(webhook_url: Url) -> Self {
         Self {
             webhook_url,
-            client: Client::new(),
+            client: Client::builder()
+                .timeout(NOTIFY_TIMEOUT)
+                .build()
+                .expect("failed to build the HTTP client"),
         }
     }
 
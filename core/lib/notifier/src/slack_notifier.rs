@@ -0,0 +1,35 @@
+use reqwest::{Client, Url};
+
+use crate::NOTIFY_TIMEOUT;
+
+pub struct SlackNotifier {
+    webhook_url: Url,
+    client: Client,
+}
+
+impl SlackNotifier {
+    pub fn new(webhook_url: Url) -> Self {
+        Self {
+            webhook_url,
+            client: Client::builder()
+                .timeout(NOTIFY_TIMEOUT)
+                .build()
+                .expect("failed to build the HTTP client"),
+        }
+    }
+
+    pub async fn send_notify(&self, text: &str) -> anyhow::Result<()> {
+        let parameters = serde_json::json!({
+            "text": serde_json::to_value(text)?,
+        });
+
+        self.client
+            .post(self.webhook_url.clone())
+            .json(&parameters)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,47 @@
+use reqwest::Client;
+
+use crate::NOTIFY_TIMEOUT;
+
+const TELEGRAM_API_URL: &str = "https://api.telegram.org";
+
+pub struct TelegramNotifier {
+    bot_token: String,
+    chat_id: String,
+    client: Client,
+}
+
+impl TelegramNotifier {
+    pub fn new(bot_token: String, chat_id: String) -> Self {
+        Self {
+            bot_token,
+            chat_id,
+            client: Client::builder()
+                .timeout(NOTIFY_TIMEOUT)
+                .build()
+                .expect("failed to build the HTTP client"),
+        }
+    }
+
+    /// The request URL contains the bot token, so it's stripped from the returned errors
+    /// to keep the token out of the logs.
+    pub async fn send_notify(&self, text: &str) -> anyhow::Result<()> {
+        let parameters = serde_json::json!({
+            "chat_id": serde_json::to_value(&self.chat_id)?,
+            "text": serde_json::to_value(text)?,
+        });
+
+        self.client
+            .post(format!(
+                "{}/bot{}/sendMessage",
+                TELEGRAM_API_URL, self.bot_token
+            ))
+            .json(&parameters)
+            .send()
+            .await
+            .map_err(reqwest::Error::without_url)?
+            .error_for_status()
+            .map_err(reqwest::Error::without_url)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,71 @@
+//! S3-compatible object storage for the rows archived from the database.
+
+// Built-in uses
+use std::{sync::Arc, time::Instant};
+// External uses
+use anyhow::{ensure, format_err};
+use async_trait::async_trait;
+use s3::{bucket::Bucket, creds::Credentials, region::Region};
+// Workspace uses
+use zksync_config::ArchiverConfig;
+use zksync_storage::archive::ObjectStore;
+
+/// Object store backed by a bucket of an S3-compatible storage, e.g. AWS S3 or MinIO.
+#[derive(Debug, Clone)]
+pub struct S3ObjectStore {
+    bucket: Bucket,
+}
+
+impl S3ObjectStore {
+    /// Creates the store for the configured bucket. Credentials are taken from
+    /// the `AWS_ACCESS_KEY_ID` and `AWS_SECRET_ACCESS_KEY` environment variables.
+    pub fn new(config: &ArchiverConfig) -> anyhow::Result<Self> {
+        let region = Region::Custom {
+            region: config.object_store_region.clone(),
+            endpoint: config.object_store_url.clone(),
+        };
+        let credentials = Credentials::from_env()
+            .map_err(|err| format_err!("Object storage credentials are not set: {}", err))?;
+        // Path-style requests are supported by all the S3-compatible storages, unlike the virtual hosts.
+        let bucket = Bucket::new_with_path_style(&config.object_store_bucket, region, credentials)
+            .map_err(|err| format_err!("Invalid object storage bucket: {}", err))?;
+        Ok(Self { bucket })
+    }
+}
+
+#[async_trait]
+impl ObjectStore for S3ObjectStore {
+    async fn put(&self, key: &str, data: Vec<u8>) -> anyhow::Result<()> {
+        let start = Instant::now();
+        let (_, code) = self
+            .bucket
+            .put_object(key, &data)
+            .await
+            .map_err(|err| format_err!("Unable to store `{}`: {}", key, err))?;
+        ensure!(code == 200, "Unable to store `{}`: status {}", key, code);
+
+        metrics::histogram!("object_store.put", start.elapsed());
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> anyhow::Result<Vec<u8>> {
+        let start = Instant::now();
+        let (data, code) = self
+            .bucket
+            .get_object(key)
+            .await
+            .map_err(|err| format_err!("Unable to load `{}`: {}", key, err))?;
+        ensure!(code == 200, "Unable to load `{}`: status {}", key, code);
+
+        metrics::histogram!("object_store.get", start.elapsed());
+        Ok(data)
+    }
+}
+
+/// Creates the object store of the archive if it's enabled, see `ArchiverConfig::from_env_if_enabled`.
+pub fn archive_from_env() -> Option<Arc<dyn ObjectStore>> {
+    ArchiverConfig::from_env_if_enabled().map(|config| {
+        let store = S3ObjectStore::new(&config).expect("Unable to access the object storage");
+        Arc::new(store) as Arc<dyn ObjectStore>
+    })
+}
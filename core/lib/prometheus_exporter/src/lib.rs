@@ -1,10 +1,16 @@
 //! This module handles metric export to the Prometheus server
+//!
+//! Every binary exposes its metrics on the `/metrics` endpoint of the port provided to
+//! `run_prometheus_exporter`. The metrics themselves are reported via the `metrics` crate macros
+//! (`counter!`, `gauge!`, `histogram!`), while the exporter attaches `GlobalLabels` to each of them.
 
+use metrics::{GaugeValue, Key, Label, Recorder, Unit};
 use metrics_exporter_prometheus::PrometheusBuilder;
 use num::rational::Ratio;
 use num::{BigUint, ToPrimitive};
 use std::collections::HashMap;
 use std::ops::Add;
+use std::sync::RwLock;
 use std::time::{Duration, Instant};
 use tokio::task::JoinHandle;
 use tokio::time::sleep;
@@ -15,6 +21,7 @@ use zksync_types::block::IncompleteBlock;
 use zksync_types::{ExecutedOperations, TokenId};
 
 const QUERY_INTERVAL: Duration = Duration::from_secs(30);
+const PROCESS_METRICS_INTERVAL: Duration = Duration::from_secs(10);
 
 pub fn run_operation_counter(connection_pool: ConnectionPool) -> JoinHandle<()> {
     tokio::spawn(async move {
@@ -58,6 +65,12 @@ async fn prometheus_exporter_iteration(connection_pool: ConnectionPool) -> Query
         .await?;
     metrics::gauge!("mempool_size", mempool_size as f64);
 
+    let prover_pending_jobs = transaction.prover_schema().pending_jobs_count().await?;
+    metrics::gauge!("prover_pending_jobs", prover_pending_jobs as f64);
+
+    let database_size = transaction.misc_schema().get_database_size().await?;
+    metrics::gauge!("database_size_bytes", database_size as f64);
+
     transaction.commit().await?;
     Ok(())
 }
@@ -111,19 +124,135 @@ fn token_amount_to_usd(amount: BigUint, decimals: u8, usd_price: Ratio<BigUint>)
     Ratio::from(amount) / BigUint::from(10u32).pow(u32::from(decimals)) * usd_price
 }
 
-pub fn run_prometheus_exporter(port: u16) -> JoinHandle<()> {
+/// Labels attached to all the metrics reported by the process, so the metrics of the different
+/// components and networks can be distinguished when they are scraped by the same Prometheus server.
+#[derive(Debug, Clone)]
+pub struct GlobalLabels {
+    /// Components run by the process, e.g. `core` or `api,eth_sender`.
+    pub component: String,
+    /// Name of the used Ethereum network, e.g. `mainnet` or `rinkeby`.
+    pub network: String,
+}
+
+impl GlobalLabels {
+    pub fn new(component: impl Into<String>, network: impl ToString) -> Self {
+        Self {
+            component: component.into(),
+            network: network.to_string(),
+        }
+    }
+
+    fn labels(&self) -> Vec<Label> {
+        vec![
+            Label::new("component", self.component.clone()),
+            Label::new("network", self.network.clone()),
+        ]
+    }
+}
+
+/// Recorder adding the global labels to every metric before passing it to the underlying recorder.
+///
+/// Keys with the global labels are built once per metric and cached, since the metrics are
+/// reported on the hot paths and the set of the reported keys is limited.
+struct LabeledRecorder<R> {
+    inner: R,
+    labels: Vec<Label>,
+    keys: RwLock<HashMap<Key, Key>>,
+}
+
+impl<R> LabeledRecorder<R> {
+    fn new(inner: R, labels: Vec<Label>) -> Self {
+        Self {
+            inner,
+            labels,
+            keys: RwLock::default(),
+        }
+    }
+
+    /// Calls `f` with the key extended by the global labels.
+    fn with_labeled<T>(&self, key: &Key, f: impl FnOnce(&Key) -> T) -> T {
+        if let Some(labeled) = self.keys.read().unwrap().get(key) {
+            return f(labeled);
+        }
+
+        let (name, mut labels) = key.clone().into_parts();
+        labels.extend(self.labels.iter().cloned());
+        let mut keys = self.keys.write().unwrap();
+        let labeled = keys
+            .entry(key.clone())
+            .or_insert_with(|| Key::from_parts(name, labels));
+        f(labeled)
+    }
+}
+
+impl<R: Recorder> Recorder for LabeledRecorder<R> {
+    fn register_counter(&self, key: &Key, unit: Option<Unit>, description: Option<&'static str>) {
+        self.with_labeled(key, |key| {
+            self.inner.register_counter(key, unit, description)
+        });
+    }
+
+    fn register_gauge(&self, key: &Key, unit: Option<Unit>, description: Option<&'static str>) {
+        self.with_labeled(key, |key| self.inner.register_gauge(key, unit, description));
+    }
+
+    fn register_histogram(&self, key: &Key, unit: Option<Unit>, description: Option<&'static str>) {
+        self.with_labeled(key, |key| {
+            self.inner.register_histogram(key, unit, description)
+        });
+    }
+
+    fn increment_counter(&self, key: &Key, value: u64) {
+        self.with_labeled(key, |key| self.inner.increment_counter(key, value));
+    }
+
+    fn update_gauge(&self, key: &Key, value: GaugeValue) {
+        self.with_labeled(key, |key| self.inner.update_gauge(key, value));
+    }
+
+    fn record_histogram(&self, key: &Key, value: f64) {
+        self.with_labeled(key, |key| self.inner.record_histogram(key, value));
+    }
+}
+
+/// Reports the metrics of the current process, so the resource usage of every component can be tracked.
+fn report_process_metrics() {
+    if let Some(memory) = std::fs::read_to_string("/proc/self/status")
+        .ok()
+        .and_then(|status| parse_resident_memory(&status))
+    {
+        metrics::gauge!("process_resident_memory_bytes", memory as f64);
+    }
+}
+
+/// Parses the resident set size of the process from the contents of `/proc/self/status`,
+/// which is only available on Linux.
+fn parse_resident_memory(status: &str) -> Option<u64> {
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    // The value is reported in kilobytes, e.g. `VmRSS:    123456 kB`.
+    let kilobytes: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kilobytes * 1024)
+}
+
+/// Installs the metrics recorder and runs the HTTP server exposing the metrics
+/// with the provided global labels on the `/metrics` endpoint.
+/// Resident memory of the process is reported along with the other metrics.
+pub fn run_prometheus_exporter(port: u16, global_labels: GlobalLabels) -> JoinHandle<()> {
     let addr = ([0, 0, 0, 0], port);
     let (recorder, exporter) = PrometheusBuilder::new()
         .listen_address(addr)
         .build_with_exporter()
         .expect("failed to install Prometheus recorder");
+    let recorder = LabeledRecorder::new(recorder, global_labels.labels());
     metrics::set_boxed_recorder(Box::new(recorder)).expect("failed to set metrics recorder");
 
     tokio::spawn(async move {
         tokio::pin!(exporter);
+        let mut process_metrics_timer = tokio::time::interval(PROCESS_METRICS_INTERVAL);
         loop {
             tokio::select! {
                 _ = &mut exporter => {}
+                _ = process_metrics_timer.tick() => report_process_metrics(),
             }
         }
     })
@@ -131,9 +260,14 @@ pub fn run_prometheus_exporter(port: u16) -> JoinHandle<()> {
 
 #[cfg(test)]
 mod tests {
-    use crate::{get_volumes, token_amount_to_usd, BigUint, ToPrimitive, TokenId};
+    use crate::{
+        get_volumes, parse_resident_memory, token_amount_to_usd, BigUint, GlobalLabels,
+        LabeledRecorder, ToPrimitive, TokenId,
+    };
     use chrono::Utc;
+    use metrics::{GaugeValue, Key, Label, Recorder, Unit};
     use num::FromPrimitive;
+    use std::sync::Mutex;
     use zksync_crypto::{
         priv_key_from_fs,
         rand::{thread_rng, Rng},
@@ -249,6 +383,81 @@ mod tests {
         }))
     }
 
+    #[test]
+    fn resident_memory() {
+        let status =
+            "Name:\tzksync_server\nVmPeak:\t  204800 kB\nVmRSS:\t  102400 kB\nThreads:\t12\n";
+        assert_eq!(parse_resident_memory(status), Some(102400 * 1024));
+        assert_eq!(parse_resident_memory("Name:\tzksync_server\n"), None);
+    }
+
+    /// Recorder remembering the keys of the reported metrics.
+    #[derive(Default)]
+    struct KeysRecorder(Mutex<Vec<Key>>);
+
+    impl KeysRecorder {
+        fn push(&self, key: &Key) {
+            self.0.lock().unwrap().push(key.clone());
+        }
+    }
+
+    impl Recorder for KeysRecorder {
+        fn register_counter(&self, key: &Key, _: Option<Unit>, _: Option<&'static str>) {
+            self.push(key);
+        }
+
+        fn register_gauge(&self, key: &Key, _: Option<Unit>, _: Option<&'static str>) {
+            self.push(key);
+        }
+
+        fn register_histogram(&self, key: &Key, _: Option<Unit>, _: Option<&'static str>) {
+            self.push(key);
+        }
+
+        fn increment_counter(&self, key: &Key, _: u64) {
+            self.push(key);
+        }
+
+        fn update_gauge(&self, key: &Key, _: GaugeValue) {
+            self.push(key);
+        }
+
+        fn record_histogram(&self, key: &Key, _: f64) {
+            self.push(key);
+        }
+    }
+
+    #[test]
+    fn labeled_recorder() {
+        let labels = GlobalLabels::new("prover", "localhost").labels();
+        let recorder = LabeledRecorder::new(KeysRecorder::default(), labels);
+
+        let key = Key::from_parts("mempool_size", vec![Label::new("kind", "queued")]);
+        recorder.update_gauge(&key, GaugeValue::Absolute(1.0));
+        recorder.increment_counter(&key, 1);
+        recorder.record_histogram(&Key::from_parts("api", Vec::<Label>::new()), 1.0);
+
+        // Keys with the global labels are only built once per metric.
+        assert_eq!(recorder.keys.read().unwrap().len(), 2);
+
+        let keys = recorder.inner.0.lock().unwrap();
+        assert_eq!(keys.len(), 3);
+        assert_eq!(keys[0], keys[1]);
+        let labels: Vec<_> = keys[0]
+            .labels()
+            .map(|label| (label.key().to_string(), label.value().to_string()))
+            .collect();
+        assert_eq!(
+            labels,
+            vec![
+                ("kind".to_string(), "queued".to_string()),
+                ("component".to_string(), "prover".to_string()),
+                ("network".to_string(), "localhost".to_string()),
+            ]
+        );
+        assert_eq!(keys[2].labels().count(), 2);
+    }
+
     #[test]
     fn test_get_volumes() {
         let txs = vec![
@@ -1,40 +1,24 @@
 //! Generate exit proof for exodus mode given account and token
 //! correct verified state should be present in the db (could be restored using `data-restore` module)
+//!
+//! Proofs for many accounts can be generated with `--batch-file`, which contains one `<address> <token>`
+//! pair per line. Proofs are generated by `--workers` threads in parallel and stored as JSON files in
+//! `--output-dir`, which can be served by any static HTTP server. Already generated proofs are skipped,
+//! so an interrupted run can be resumed by launching it again with the same arguments.
 
-use serde::Serialize;
+use std::collections::VecDeque;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 use std::time::Instant;
 use structopt::StructOpt;
+use zksync_api_types::v02::exit_proof::{ExitProofData, StoredBlockInfo};
 use zksync_crypto::params::MIN_NFT_TOKEN_ID;
-use zksync_crypto::proof::EncodedSingleProof;
+use zksync_prover_utils::exit_proof::ExitProofTree;
 use zksync_storage::{ConnectionPool, StorageProcessor};
-use zksync_types::{block::Block, AccountId, Address, BlockNumber, TokenId, TokenLike, H256, NFT};
-use zksync_utils::BigUintSerdeWrapper;
-
-#[derive(Serialize, Debug)]
-#[serde(rename_all = "camelCase")]
-struct StoredBlockInfo {
-    block_number: BlockNumber,
-    priority_operations: u64,
-    pending_onchain_operations_hash: H256,
-    timestamp: u64,
-    state_hash: H256,
-    commitment: H256,
-}
-
-impl StoredBlockInfo {
-    pub fn from_block(block: &Block) -> Self {
-        Self {
-            block_number: block.block_number,
-            priority_operations: block.number_of_processed_prior_ops(),
-            pending_onchain_operations_hash: block.get_onchain_operations_block_info().1,
-            timestamp: block.timestamp,
-            state_hash: block.get_eth_encoded_root(),
-            commitment: block.block_commitment,
-        }
-    }
-}
+use zksync_types::{AccountId, Address, TokenId, TokenLike, H256, NFT};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct NFTInfo {
     creator_id: AccountId,
     creator_address: Address,
@@ -42,22 +26,6 @@ struct NFTInfo {
     content_hash: H256,
 }
 
-#[derive(Serialize, Debug)]
-#[serde(rename_all = "camelCase")]
-struct ExitProofData {
-    stored_block_info: StoredBlockInfo,
-    owner: Address,
-    account_id: AccountId,
-    token_id: TokenId,
-    amount: BigUintSerdeWrapper,
-    nft_creator_id: AccountId,
-    nft_creator_address: Address,
-    nft_serial_id: u32,
-    nft_content_hash: H256,
-    proof: EncodedSingleProof,
-    token_address: Address,
-}
-
 #[derive(StructOpt)]
 #[structopt(
     name = "zkSync operator node",
@@ -66,12 +34,40 @@ struct ExitProofData {
 )]
 struct Opt {
     /// Account address
-    #[structopt(long)]
-    address: Address,
+    #[structopt(long, required_unless = "batch_file")]
+    address: Option<Address>,
 
     /// Token to withdraw - "ETH" or address of the ERC20 token
+    #[structopt(long, required_unless = "batch_file")]
+    token: Option<String>,
+
+    /// File with `<address> <token>` pairs to generate proofs for, one per line
     #[structopt(long)]
-    token: String,
+    batch_file: Option<PathBuf>,
+
+    /// Directory to store the proofs generated in the batch mode
+    #[structopt(long, default_value = "exit_proofs")]
+    output_dir: PathBuf,
+
+    /// Amount of proofs generated in parallel in the batch mode
+    #[structopt(long, default_value = "1")]
+    workers: usize,
+}
+
+/// Data required to generate the exit proof for a single account and token.
+#[derive(Debug, Clone)]
+struct ExitProofRequest {
+    owner: Address,
+    account_id: AccountId,
+    token_id: TokenId,
+    token_address: Address,
+    nft: Option<NFTInfo>,
+}
+
+impl ExitProofRequest {
+    fn output_file(&self, output_dir: &Path) -> PathBuf {
+        output_dir.join(format!("{:?}_{}.json", self.owner, *self.token_id))
+    }
 }
 
 async fn get_nft_info(storage: &mut StorageProcessor<'_>, nft: Option<NFT>) -> NFTInfo {
@@ -103,23 +99,11 @@ async fn get_nft_info(storage: &mut StorageProcessor<'_>, nft: Option<NFT>) -> N
     }
 }
 
-#[tokio::main]
-async fn main() {
-    vlog::init();
-
-    let opt = Opt::from_args();
-
-    let address = opt.address;
-    let token = TokenLike::parse(&opt.token);
-
-    let timer = Instant::now();
-    vlog::info!("Restoring state from db");
-    let connection_pool = ConnectionPool::new(Some(1));
-    let mut storage = connection_pool
-        .access_storage()
-        .await
-        .expect("Storage access failed");
-
+async fn prepare_request(
+    storage: &mut StorageProcessor<'_>,
+    address: Address,
+    token: TokenLike,
+) -> ExitProofRequest {
     let token_info = storage
         .tokens_schema()
         .get_token(token)
@@ -140,6 +124,167 @@ async fn main() {
         .expect("Db access fail")
         .unwrap_or_else(|| panic!("Unable to find account ID for address: {}", address));
 
+    let nft = if token_id.0 < MIN_NFT_TOKEN_ID {
+        None
+    } else {
+        let nft = storage
+            .tokens_schema()
+            .get_nft(token_id)
+            .await
+            .expect("Db access fail")
+            .expect("NFT token should exist");
+        Some(get_nft_info(storage, Some(nft)).await)
+    };
+
+    ExitProofRequest {
+        owner: address,
+        account_id,
+        token_id,
+        token_address,
+        nft,
+    }
+}
+
+fn generate_proof(
+    tree: &ExitProofTree,
+    stored_block_info: StoredBlockInfo,
+    zero_nft_info: &NFTInfo,
+    request: ExitProofRequest,
+) -> anyhow::Result<ExitProofData> {
+    let (proof, amount) = match &request.nft {
+        None => {
+            tree.create_exit_proof_fungible(request.account_id, request.owner, request.token_id)?
+        }
+        Some(nft) => tree.create_exit_proof_nft(
+            request.account_id,
+            request.owner,
+            request.token_id,
+            nft.creator_id,
+            nft.serial_id,
+            nft.content_hash,
+        )?,
+    };
+    let nft_info = request.nft.as_ref().unwrap_or(zero_nft_info);
+
+    Ok(ExitProofData {
+        stored_block_info,
+        owner: request.owner,
+        token_id: request.token_id,
+        account_id: request.account_id,
+        nft_creator_id: nft_info.creator_id,
+        nft_creator_address: nft_info.creator_address,
+        nft_serial_id: nft_info.serial_id,
+        nft_content_hash: nft_info.content_hash,
+        amount: amount.into(),
+        proof,
+        token_address: request.token_address,
+    })
+}
+
+/// Generates proofs for all the requests which don't have the output file yet.
+fn generate_proofs_batch(
+    tree: ExitProofTree,
+    stored_block_info: StoredBlockInfo,
+    zero_nft_info: NFTInfo,
+    requests: Vec<ExitProofRequest>,
+    output_dir: PathBuf,
+    workers: usize,
+) {
+    fs::create_dir_all(&output_dir).expect("Failed to create output directory");
+
+    let total = requests.len();
+    let queue: VecDeque<_> = requests
+        .into_iter()
+        .filter(|request| !request.output_file(&output_dir).exists())
+        .collect();
+    vlog::info!(
+        "Generating {} exit proofs ({} already generated) with {} workers",
+        queue.len(),
+        total - queue.len(),
+        workers
+    );
+    let queue = Arc::new(Mutex::new(queue));
+    let tree = Arc::new(tree);
+
+    let handles: Vec<_> = (0..workers.max(1))
+        .map(|_| {
+            let tree = Arc::clone(&tree);
+            let stored_block_info = stored_block_info.clone();
+            let zero_nft_info = zero_nft_info.clone();
+            let queue = queue.clone();
+            let output_dir = output_dir.clone();
+            std::thread::spawn(move || loop {
+                let request = match queue.lock().unwrap().pop_front() {
+                    Some(request) => request,
+                    None => break,
+                };
+                let output_file = request.output_file(&output_dir);
+                let result = generate_proof(
+                    &tree,
+                    stored_block_info.clone(),
+                    &zero_nft_info,
+                    request.clone(),
+                )
+                .and_then(|proof_data| {
+                    // Write to the temporary file first, so an interrupted run won't leave a broken proof.
+                    let tmp_file = output_file.with_extension("tmp");
+                    fs::write(&tmp_file, serde_json::to_string_pretty(&proof_data)?)?;
+                    fs::rename(&tmp_file, &output_file)?;
+                    Ok(())
+                });
+                match result {
+                    Ok(()) => vlog::info!("Exit proof stored: {}", output_file.display()),
+                    Err(err) => vlog::error!(
+                        "Failed to generate exit proof for {:?}, token {}: {}",
+                        request.owner,
+                        *request.token_id,
+                        err
+                    ),
+                }
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        handle.join().expect("Exit proof worker panicked");
+    }
+}
+
+fn read_batch_file(path: &Path) -> Vec<(Address, TokenLike)> {
+    fs::read_to_string(path)
+        .expect("Failed to read batch file")
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let mut parts = line.split_whitespace();
+            let (address, token) = match (parts.next(), parts.next(), parts.next()) {
+                (Some(address), Some(token), None) => (address, token),
+                _ => panic!("Incorrect batch file line: {}", line),
+            };
+            let address = address
+                .trim_start_matches("0x")
+                .parse()
+                .unwrap_or_else(|_| panic!("Incorrect address: {}", address));
+            (address, TokenLike::parse(token))
+        })
+        .collect()
+}
+
+#[tokio::main]
+async fn main() {
+    vlog::init();
+
+    let opt = Opt::from_args();
+
+    let timer = Instant::now();
+    vlog::info!("Restoring state from db");
+    let connection_pool = ConnectionPool::new(Some(1));
+    let mut storage = connection_pool
+        .access_storage()
+        .await
+        .expect("Storage access failed");
+
     let accounts = storage
         .chain()
         .state_schema()
@@ -162,53 +307,34 @@ async fn main() {
         .expect("Db access fail")
         .expect("Block not stored");
     let stored_block_info = StoredBlockInfo::from_block(&block);
+    let zero_nft_info = get_nft_info(&mut storage, None).await;
 
     vlog::info!("Restored state from db: {} s", timer.elapsed().as_secs());
 
-    let (proof, amount, nft) = if token_id.0 < MIN_NFT_TOKEN_ID {
-        let (proof, amount) = zksync_prover_utils::exit_proof::create_exit_proof_fungible(
-            accounts, account_id, address, token_id,
-        )
-        .expect("Failed to generate exit proof");
+    if let Some(batch_file) = &opt.batch_file {
+        let mut requests = Vec::new();
+        for (address, token) in read_batch_file(batch_file) {
+            requests.push(prepare_request(&mut storage, address, token).await);
+        }
+        let tree = ExitProofTree::new(accounts);
+        generate_proofs_batch(
+            tree,
+            stored_block_info,
+            zero_nft_info,
+            requests,
+            opt.output_dir,
+            opt.workers,
+        );
+        return;
+    }
 
-        (proof, amount, None)
-    } else {
-        let nft = storage
-            .tokens_schema()
-            .get_nft(token_id)
-            .await
-            .expect("Db access fail")
-            .expect("NFT token should exist");
-        let (proof, amount) = zksync_prover_utils::exit_proof::create_exit_proof_nft(
-            accounts,
-            account_id,
-            address,
-            token_id,
-            nft.creator_id,
-            nft.serial_id,
-            nft.content_hash,
-        )
+    let address = opt.address.expect("Account address is required");
+    let token = TokenLike::parse(&opt.token.expect("Token is required"));
+    let request = prepare_request(&mut storage, address, token).await;
+    let tree = ExitProofTree::new(accounts);
+    let proof_data = generate_proof(&tree, stored_block_info, &zero_nft_info, request)
         .expect("Failed to generate exit proof");
 
-        (proof, amount, Some(nft))
-    };
-
-    let nft_info = get_nft_info(&mut storage, nft).await;
-
-    let proof_data = ExitProofData {
-        stored_block_info,
-        owner: address,
-        token_id,
-        account_id,
-        nft_creator_id: nft_info.creator_id,
-        nft_creator_address: nft_info.creator_address,
-        nft_serial_id: nft_info.serial_id,
-        nft_content_hash: nft_info.content_hash,
-        amount: amount.into(),
-        proof,
-        token_address,
-    };
-
     println!("\n\n");
     println!("==========================");
     println!("Generating proof completed");
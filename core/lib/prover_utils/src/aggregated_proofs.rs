@@ -1,5 +1,6 @@
 use crate::fs_utils::get_recursive_verification_key_path;
 use crate::{get_universal_setup_monomial_form, PlonkVerificationKey};
+use std::collections::{hash_map::Entry, HashMap};
 use std::fs::File;
 use std::time::Instant;
 use zksync_crypto::bellman::pairing::{CurveAffine, Engine as EngineTrait};
@@ -13,7 +14,7 @@ use zksync_crypto::params::{RECURSIVE_CIRCUIT_NUM_INPUTS, RECURSIVE_CIRCUIT_VK_T
 use zksync_crypto::proof::{AggregatedProof, SingleProof, Vk};
 use zksync_crypto::recursive_aggregation_circuit::circuit::{
     create_recursive_circuit_setup, create_zksync_recursive_aggregate,
-    proof_recursive_aggregate_for_zksync,
+    proof_recursive_aggregate_for_zksync, RecursiveAggregationCircuitBn256,
 };
 use zksync_crypto::Engine;
 
@@ -154,3 +155,35 @@ pub fn gen_aggregate_proof(
         aggr_limbs,
     })
 }
+
+/// Verifies aggregated proofs against the recursive verification keys.
+/// Every key is read from disk once, when the proof of its size is verified for the first time.
+#[derive(Default)]
+pub struct AggregatedProofVerifier {
+    keys: HashMap<usize, VkAggregate<Engine, RecursiveAggregationCircuitBn256<'static>>>,
+}
+
+impl AggregatedProofVerifier {
+    /// Verifies aggregated proof against the recursive verification key for its size.
+    /// Returns an error if the verification key cannot be loaded.
+    pub fn verify(&mut self, proof: &AggregatedProof) -> anyhow::Result<bool> {
+        let proofs_count = proof.individual_vk_idxs.len();
+        let vk = match self.keys.entry(proofs_count) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(VkAggregate::read(File::open(
+                get_recursive_verification_key_path(proofs_count),
+            )?)?),
+        };
+
+        let start = Instant::now();
+        let is_valid = verify::<_, _, RollingKeccakTranscript<<Engine as ScalarEngine>::Fr>>(
+            vk,
+            &proof.proof,
+            None,
+        )
+        .map_err(|err| anyhow::format_err!("Failed to verify aggregated proof: {:?}", err))?;
+        metrics::histogram!("prover", start.elapsed(), "stage" => "verify_proof", "type" => "aggregated_proof");
+
+        Ok(is_valid)
+    }
+}
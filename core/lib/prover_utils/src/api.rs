@@ -21,6 +21,9 @@ pub struct ProverInputResponse {
     pub first_block: BlockNumber,
     pub last_block: BlockNumber,
     pub data: Option<JobRequestData>,
+    /// Priority of the job in the prover job queue, the lower is the more urgent.
+    #[serde(default)]
+    pub job_priority: i32,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -58,3 +61,31 @@ pub struct WorkingOn {
 pub struct ProverStopped {
     pub prover_name: String,
 }
+
+/// State of the universal setup key on the prover machine.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SetupKeyState {
+    /// Key is present on disk and matches the configured checksum (if any).
+    Ready,
+    /// Key is absent and could not be downloaded.
+    Missing,
+    /// Key is present, but its checksum differs from the configured one.
+    ChecksumMismatch,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SetupKeyStatus {
+    pub power_of_two: u32,
+    pub state: SetupKeyState,
+    /// Whether the key was checked against a configured checksum.
+    pub checksum_verified: bool,
+    /// Whether the key was downloaded during the check.
+    pub downloaded: bool,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SetupKeysReport {
+    pub prover_name: String,
+    pub keys: Vec<SetupKeyStatus>,
+}
@@ -4,61 +4,151 @@ use crate::gen_verified_proof_for_exit_circuit;
 use anyhow::format_err;
 use num::BigUint;
 use std::time::Instant;
-use zksync_circuit::exit_circuit::create_exit_circuit_with_public_input;
+use zksync_circuit::exit_circuit::{create_exit_circuit_with_public_input, ZkSyncExitCircuit};
 use zksync_crypto::circuit::account::CircuitAccount;
 use zksync_crypto::circuit::CircuitAccountTree;
 use zksync_crypto::proof::EncodedSingleProof;
+use zksync_crypto::Engine;
 use zksync_types::{AccountId, AccountMap, Address, TokenId, H256};
 
-fn create_exit_proof(
+/// Account tree prepared for the exit proofs generation.
+///
+/// Building the circuit tree for the whole state is expensive, so when proofs for many accounts
+/// are required, the tree should be built once and reused. The tree is never changed after
+/// it's built, so the workers can share it.
+pub struct ExitProofTree {
     accounts: AccountMap,
-    account_id: AccountId,
-    owner: Address,
-    token_id: TokenId,
-    nft_creator_id: AccountId,
-    nft_serial_id: u32,
-    nft_content_hash: H256,
-) -> Result<(EncodedSingleProof, BigUint), anyhow::Error> {
-    let timer = Instant::now();
-    let mut circuit_account_tree =
-        CircuitAccountTree::new(zksync_crypto::params::account_tree_depth());
-
-    let mut target_account = None;
-    for (id, account) in accounts {
-        if id == account_id {
-            target_account = Some(account.clone());
+    circuit_account_tree: CircuitAccountTree,
+}
+
+impl ExitProofTree {
+    pub fn new(accounts: AccountMap) -> Self {
+        let timer = Instant::now();
+        let mut circuit_account_tree =
+            CircuitAccountTree::new(zksync_crypto::params::account_tree_depth());
+        for (id, account) in &accounts {
+            circuit_account_tree.insert(**id, CircuitAccount::from(account.clone()));
+        }
+        vlog::info!("Exit proof tree created: {} s", timer.elapsed().as_secs());
+
+        Self {
+            accounts,
+            circuit_account_tree,
         }
-        circuit_account_tree.insert(*id, CircuitAccount::from(account));
     }
 
-    let balance = target_account
-        .map(|acc| acc.get_balance(token_id))
-        .ok_or_else(|| {
-            format_err!(
-                "Fund account not found: id: {}, address: 0x{:x}",
-                *account_id,
-                owner
-            )
-        })?;
-
-    let zksync_exit_circuit = create_exit_circuit_with_public_input(
-        &mut circuit_account_tree,
-        account_id,
-        token_id,
-        nft_creator_id,
-        nft_serial_id,
-        nft_content_hash,
-    );
-    let commitment = zksync_exit_circuit
-        .pub_data_commitment
-        .expect("Witness should contract commitment");
-    vlog::info!("Proof commitment: {:?}", commitment);
-
-    let proof = gen_verified_proof_for_exit_circuit(zksync_exit_circuit)
-        .map_err(|e| format_err!("Failed to generate proof: {}", e))?;
-
-    vlog::info!("Exit proof created: {} s", timer.elapsed().as_secs());
-    Ok((proof.serialize_single_proof(), balance))
+    /// Prepares the exit circuit for the account and token, the proof is then generated
+    /// by `ExitCircuitWitness::prove`.
+    pub fn exit_circuit_witness(
+        &self,
+        account_id: AccountId,
+        owner: Address,
+        token_id: TokenId,
+        nft_creator_id: AccountId,
+        nft_serial_id: u32,
+        nft_content_hash: H256,
+    ) -> Result<ExitCircuitWitness, anyhow::Error> {
+        let balance = self
+            .accounts
+            .get(&account_id)
+            .map(|acc| acc.get_balance(token_id))
+            .ok_or_else(|| {
+                format_err!(
+                    "Fund account not found: id: {}, address: 0x{:x}",
+                    *account_id,
+                    owner
+                )
+            })?;
+
+        let circuit = create_exit_circuit_with_public_input(
+            &self.circuit_account_tree,
+            account_id,
+            token_id,
+            nft_creator_id,
+            nft_serial_id,
+            nft_content_hash,
+        );
+        Ok(ExitCircuitWitness { circuit, balance })
+    }
+
+    fn create_exit_proof(
+        &self,
+        account_id: AccountId,
+        owner: Address,
+        token_id: TokenId,
+        nft_creator_id: AccountId,
+        nft_serial_id: u32,
+        nft_content_hash: H256,
+    ) -> Result<(EncodedSingleProof, BigUint), anyhow::Error> {
+        self.exit_circuit_witness(
+            account_id,
+            owner,
+            token_id,
+            nft_creator_id,
+            nft_serial_id,
+            nft_content_hash,
+        )?
+        .prove()
+    }
+
+    pub fn create_exit_proof_fungible(
+        &self,
+        account_id: AccountId,
+        owner: Address,
+        token_id: TokenId,
+    ) -> Result<(EncodedSingleProof, BigUint), anyhow::Error> {
+        self.create_exit_proof(
+            account_id,
+            owner,
+            token_id,
+            Default::default(),
+            Default::default(),
+            Default::default(),
+        )
+    }
+
+    pub fn create_exit_proof_nft(
+        &self,
+        account_id: AccountId,
+        owner: Address,
+        token_id: TokenId,
+        creator_id: AccountId,
+        serial_id: u32,
+        content_hash: H256,
+    ) -> Result<(EncodedSingleProof, BigUint), anyhow::Error> {
+        self.create_exit_proof(
+            account_id,
+            owner,
+            token_id,
+            creator_id,
+            serial_id,
+            content_hash,
+        )
+    }
+}
+
+/// Exit circuit prepared by `ExitProofTree` along with the exited amount.
+pub struct ExitCircuitWitness {
+    circuit: ZkSyncExitCircuit<'static, Engine>,
+    balance: BigUint,
+}
+
+impl ExitCircuitWitness {
+    /// Generates the proof, returns it along with the exited amount.
+    pub fn prove(self) -> Result<(EncodedSingleProof, BigUint), anyhow::Error> {
+        let timer = Instant::now();
+        let commitment = self
+            .circuit
+            .pub_data_commitment
+            .expect("Witness should contract commitment");
+        vlog::info!("Proof commitment: {:?}", commitment);
+
+        let proof = gen_verified_proof_for_exit_circuit(self.circuit)
+            .map_err(|e| format_err!("Failed to generate proof: {}", e))?;
+
+        vlog::info!("Exit proof created: {} s", timer.elapsed().as_secs());
+        Ok((proof.serialize_single_proof(), self.balance))
+    }
 }
 
 pub fn create_exit_proof_fungible(
@@ -67,15 +157,7 @@ pub fn create_exit_proof_fungible(
     owner: Address,
     token_id: TokenId,
 ) -> Result<(EncodedSingleProof, BigUint), anyhow::Error> {
-    create_exit_proof(
-        accounts,
-        account_id,
-        owner,
-        token_id,
-        Default::default(),
-        Default::default(),
-        Default::default(),
-    )
+    ExitProofTree::new(accounts).create_exit_proof_fungible(account_id, owner, token_id)
 }
 
 pub fn create_exit_proof_nft(
@@ -87,8 +169,7 @@ pub fn create_exit_proof_nft(
     serial_id: u32,
     content_hash: H256,
 ) -> Result<(EncodedSingleProof, BigUint), anyhow::Error> {
-    create_exit_proof(
-        accounts,
+    ExitProofTree::new(accounts).create_exit_proof_nft(
         account_id,
         owner,
         token_id,
@@ -97,3 +178,81 @@ pub fn create_exit_proof_nft(
         content_hash,
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use zksync_types::Account;
+
+    fn accounts() -> AccountMap {
+        let mut accounts = AccountMap::default();
+        for id in 1..=3u32 {
+            let mut account = Account::default_with_address(&Address::repeat_byte(id as u8));
+            account.set_balance(TokenId(0), BigUint::from(id * 100));
+            accounts.insert(AccountId(id), account);
+        }
+        accounts
+    }
+
+    fn fungible_witness(
+        tree: &ExitProofTree,
+        id: u32,
+        token_id: TokenId,
+    ) -> Result<ExitCircuitWitness, anyhow::Error> {
+        tree.exit_circuit_witness(
+            AccountId(id),
+            Address::repeat_byte(id as u8),
+            token_id,
+            Default::default(),
+            Default::default(),
+            Default::default(),
+        )
+    }
+
+    #[test]
+    fn exit_circuit_witness() {
+        let tree = ExitProofTree::new(accounts());
+
+        let witness = fungible_witness(&tree, 2, TokenId(0)).unwrap();
+        assert_eq!(witness.balance, BigUint::from(200u32));
+        assert!(witness.circuit.pub_data_commitment.is_some());
+        // The exit of the token the account doesn't have is still possible.
+        let witness = fungible_witness(&tree, 2, TokenId(1)).unwrap();
+        assert_eq!(witness.balance, BigUint::from(0u32));
+
+        assert!(fungible_witness(&tree, 4, TokenId(0)).is_err());
+    }
+
+    // The witness doesn't depend on the previously prepared ones, so the tree can be shared
+    // by the workers instead of being built for every proof.
+    #[test]
+    fn exit_circuit_witness_shared_tree() {
+        let commitment = |tree: &ExitProofTree, id: u32| {
+            fungible_witness(tree, id, TokenId(0))
+                .unwrap()
+                .circuit
+                .pub_data_commitment
+                .unwrap()
+        };
+        let expected: Vec<_> = (1..=3)
+            .map(|id| commitment(&ExitProofTree::new(accounts()), id))
+            .collect();
+
+        let tree = Arc::new(ExitProofTree::new(accounts()));
+        let handles: Vec<_> = (1..=3)
+            .map(|id| {
+                let tree = Arc::clone(&tree);
+                std::thread::spawn(move || commitment(&tree, id))
+            })
+            .collect();
+        let commitments: Vec<_> = handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect();
+        assert_eq!(commitments, expected);
+
+        assert_eq!(commitment(&tree, 1), expected[0]);
+        assert_eq!(tree.accounts, accounts());
+    }
+}
@@ -38,13 +38,19 @@ fn get_universal_setup_monomial_file_name(power_of_two: u32) -> Result<String, a
     Ok(format!("setup_2^{}.key", power_of_two))
 }
 
+/// Returns path to the universal setup in the monomial form of the given power of two.
+pub fn get_universal_setup_monomial_file_path(power_of_two: u32) -> Result<PathBuf, anyhow::Error> {
+    let setup_file_name = get_universal_setup_monomial_file_name(power_of_two)?;
+    let mut path = base_universal_setup_dir()?;
+    path.push(&setup_file_name);
+    Ok(path)
+}
+
 pub fn save_universal_setup_monomial_file<R: Read>(
     power_of_two: u32,
     mut reader: R,
 ) -> Result<(), anyhow::Error> {
-    let setup_file_name = get_universal_setup_monomial_file_name(power_of_two)?;
-    let mut path = base_universal_setup_dir()?;
-    path.push(&setup_file_name);
+    let path = get_universal_setup_monomial_file_path(power_of_two)?;
     let mut file = File::create(path)?;
     copy(&mut reader, &mut file)?;
     Ok(())
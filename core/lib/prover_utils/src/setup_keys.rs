@@ -0,0 +1,245 @@
+//! Startup check of the universal setup keys required by the prover.
+//!
+//! Keys are looked up in the local setup directory, which also serves as a cache for the
+//! downloaded keys, so every key is downloaded at most once per machine.
+//!
+//! Hashing the multi-gigabyte keys takes a while, so the checksum of every key is cached next to it
+//! (`setup_2^<power>.key.sha256`) along with the size and the modification time of the key file.
+//! The key is only hashed again once it's changed.
+
+use crate::api::{SetupKeyState, SetupKeyStatus};
+use crate::{fs_utils, network_utils};
+use anyhow::format_err;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{copy, BufReader};
+use std::path::{Path, PathBuf};
+use std::time::{Instant, UNIX_EPOCH};
+
+/// Parses checksums of the setup keys given in the `<power_of_two>:<sha256 hex>` form.
+pub fn parse_setup_keys_checksums(entries: &[String]) -> anyhow::Result<HashMap<u32, String>> {
+    entries
+        .iter()
+        .map(|entry| {
+            let (power_of_two, checksum) = entry
+                .split_once(':')
+                .ok_or_else(|| format_err!("Invalid setup key checksum entry: {}", entry))?;
+            let power_of_two = power_of_two
+                .trim()
+                .parse()
+                .map_err(|e| format_err!("Invalid setup key power of two '{}': {}", entry, e))?;
+            Ok((power_of_two, checksum.trim().to_lowercase()))
+        })
+        .collect()
+}
+
+/// Calculates sha256 checksum of the universal setup file of the given power of two.
+pub fn universal_setup_checksum(power_of_two: u32) -> anyhow::Result<String> {
+    let path = fs_utils::get_universal_setup_monomial_file_path(power_of_two)?;
+    file_checksum(&path)
+}
+
+/// Size and modification time (in nanoseconds since the epoch) of the file.
+type FileStamp = (u64, u128);
+
+fn file_stamp(path: &Path) -> anyhow::Result<FileStamp> {
+    let metadata = fs::metadata(path)?;
+    let modified = metadata.modified()?.duration_since(UNIX_EPOCH)?.as_nanos();
+    Ok((metadata.len(), modified))
+}
+
+fn checksum_cache_path(path: &Path) -> PathBuf {
+    let mut cache_path = path.as_os_str().to_owned();
+    cache_path.push(".sha256");
+    cache_path.into()
+}
+
+/// Returns the cached checksum of the file if the file hasn't changed since it was cached.
+fn cached_checksum(cache_path: &Path, stamp: FileStamp) -> Option<String> {
+    let contents = fs::read_to_string(cache_path).ok()?;
+    let mut parts = contents.split_whitespace();
+    let cached_stamp: FileStamp = (parts.next()?.parse().ok()?, parts.next()?.parse().ok()?);
+    let checksum = parts.next()?;
+    Some(checksum.to_string()).filter(|_| cached_stamp == stamp)
+}
+
+/// Calculates sha256 checksum of the file, reusing the cached one if the file hasn't changed.
+fn file_checksum(path: &Path) -> anyhow::Result<String> {
+    let stamp = file_stamp(path)?;
+    let cache_path = checksum_cache_path(path);
+    if let Some(checksum) = cached_checksum(&cache_path, stamp) {
+        return Ok(checksum);
+    }
+
+    let mut reader = BufReader::with_capacity(1 << 24, File::open(path)?);
+    let mut hasher = Sha256::new();
+    copy(&mut reader, &mut hasher)?;
+    let checksum = hex::encode(hasher.finalize());
+
+    // The file could be replaced while it was hashed, the checksum is not cached then.
+    if file_stamp(path)? == stamp {
+        let cached = format!("{} {} {}\n", stamp.0, stamp.1, checksum);
+        if let Err(e) = fs::write(&cache_path, cached) {
+            vlog::warn!("Failed to cache the checksum of {}: {}", path.display(), e);
+        }
+    }
+    Ok(checksum)
+}
+
+/// Keys without the expected checksum are only checked to be readable, so they are not hashed.
+fn check_universal_setup_key(power_of_two: u32, expected_checksum: Option<&str>) -> SetupKeyState {
+    let path = match fs_utils::get_universal_setup_monomial_file_path(power_of_two) {
+        Ok(path) => path,
+        Err(_) => return SetupKeyState::Missing,
+    };
+    check_setup_key_file(&path, expected_checksum)
+}
+
+fn check_setup_key_file(path: &Path, expected_checksum: Option<&str>) -> SetupKeyState {
+    let expected = match expected_checksum {
+        Some(expected) => expected,
+        None if File::open(path).is_ok() => return SetupKeyState::Ready,
+        None => return SetupKeyState::Missing,
+    };
+    match file_checksum(path) {
+        Err(_) => SetupKeyState::Missing,
+        Ok(checksum) if checksum != expected => SetupKeyState::ChecksumMismatch,
+        Ok(_) => SetupKeyState::Ready,
+    }
+}
+
+/// Makes sure that universal setup keys of the given powers of two are present on disk
+/// and match the configured checksums. Missing or corrupted keys are (re-)downloaded
+/// if `download_from_network` is set.
+///
+/// Keys without the configured checksum are only checked to be present and readable.
+pub fn prepare_universal_setup_keys(
+    powers_of_two: &[u32],
+    checksums: &HashMap<u32, String>,
+    download_from_network: bool,
+) -> Vec<SetupKeyStatus> {
+    powers_of_two
+        .iter()
+        .map(|&power_of_two| {
+            let start = Instant::now();
+            let expected_checksum = checksums.get(&power_of_two).map(String::as_str);
+
+            let mut downloaded = false;
+            let mut state = check_universal_setup_key(power_of_two, expected_checksum);
+            if state != SetupKeyState::Ready && download_from_network {
+                vlog::warn!(
+                    "Universal setup key 2^{} is not ready ({:?}), downloading it",
+                    power_of_two,
+                    state
+                );
+                match network_utils::download_universal_setup_monomial_form(power_of_two) {
+                    Ok(()) => {
+                        downloaded = true;
+                        state = check_universal_setup_key(power_of_two, expected_checksum);
+                    }
+                    Err(e) => {
+                        vlog::error!(
+                            "Failed to download universal setup key 2^{}: {}",
+                            power_of_two,
+                            e
+                        );
+                    }
+                }
+            }
+            metrics::histogram!("prover", start.elapsed(), "stage" => "check_setup_key");
+
+            SetupKeyStatus {
+                power_of_two,
+                state,
+                checksum_verified: expected_checksum.is_some(),
+                downloaded,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    /// sha256 of the empty string.
+    const EMPTY_CHECKSUM: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+
+    #[test]
+    fn checksum_is_cached() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("setup_2^20.key");
+        File::create(&path).unwrap();
+        let cache_path = checksum_cache_path(&path);
+        assert_eq!(cache_path, dir.path().join("setup_2^20.key.sha256"));
+
+        assert_eq!(file_checksum(&path).unwrap(), EMPTY_CHECKSUM);
+        assert!(fs::read_to_string(&cache_path)
+            .unwrap()
+            .ends_with(&format!("{}\n", EMPTY_CHECKSUM)));
+
+        // The file isn't hashed again while its size and modification time are the same.
+        let stamp = file_stamp(&path).unwrap();
+        fs::write(&cache_path, format!("{} {} cached", stamp.0, stamp.1)).unwrap();
+        assert_eq!(file_checksum(&path).unwrap(), "cached");
+        assert_eq!(
+            check_setup_key_file(&path, Some(EMPTY_CHECKSUM)),
+            SetupKeyState::ChecksumMismatch
+        );
+
+        // Changed file is hashed again.
+        File::create(&path).unwrap().write_all(b"key").unwrap();
+        let checksum = file_checksum(&path).unwrap();
+        assert_ne!(checksum, "cached");
+        assert_ne!(checksum, EMPTY_CHECKSUM);
+        assert_eq!(
+            cached_checksum(&cache_path, file_stamp(&path).unwrap()),
+            Some(checksum)
+        );
+
+        // Corrupted cache is ignored.
+        fs::write(&cache_path, "garbage").unwrap();
+        assert_eq!(
+            cached_checksum(&cache_path, file_stamp(&path).unwrap()),
+            None
+        );
+    }
+
+    #[test]
+    fn setup_key_states() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("setup_2^21.key");
+        assert_eq!(check_setup_key_file(&path, None), SetupKeyState::Missing);
+        assert_eq!(
+            check_setup_key_file(&path, Some(EMPTY_CHECKSUM)),
+            SetupKeyState::Missing
+        );
+
+        File::create(&path).unwrap();
+        // Keys without the expected checksum are not hashed.
+        assert_eq!(check_setup_key_file(&path, None), SetupKeyState::Ready);
+        assert!(!checksum_cache_path(&path).exists());
+
+        assert_eq!(
+            check_setup_key_file(&path, Some(EMPTY_CHECKSUM)),
+            SetupKeyState::Ready
+        );
+        assert_eq!(
+            check_setup_key_file(&path, Some("00")),
+            SetupKeyState::ChecksumMismatch
+        );
+    }
+
+    #[test]
+    fn parse_checksums() {
+        let entries = vec!["20:ABCD".to_string(), " 21 : ef01 ".to_string()];
+        let checksums = parse_setup_keys_checksums(&entries).unwrap();
+        assert_eq!(checksums[&20], "abcd");
+        assert_eq!(checksums[&21], "ef01");
+
+        assert!(parse_setup_keys_checksums(&["20".to_string()]).is_err());
+        assert!(parse_setup_keys_checksums(&["x:abcd".to_string()]).is_err());
+    }
+}
@@ -0,0 +1,5 @@
+ALTER TABLE mempool_txs ADD COLUMN account_id BIGINT DEFAULT NULL;
+ALTER TABLE mempool_txs ADD COLUMN nonce BIGINT DEFAULT NULL;
+
+CREATE INDEX IF NOT EXISTS mempool_txs_account_id_nonce_idx
+    ON mempool_txs (account_id, nonce);
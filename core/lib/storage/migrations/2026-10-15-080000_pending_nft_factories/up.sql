@@ -0,0 +1,9 @@
+-- Factories registered on L1 for the accounts that don't exist in zkSync yet.
+-- They are moved to the `nft_factory` table once the creator account is created.
+CREATE TABLE pending_nft_factories (
+    creator_id INTEGER NOT NULL,
+    creator_address TEXT NOT NULL,
+    factory_address TEXT NOT NULL,
+    eth_block BIGINT NOT NULL,
+    PRIMARY KEY (creator_id, creator_address)
+);
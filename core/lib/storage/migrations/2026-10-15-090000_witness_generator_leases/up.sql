@@ -0,0 +1,5 @@
+CREATE TABLE witness_generator_leases (
+    block BIGINT PRIMARY KEY,
+    worker TEXT NOT NULL,
+    heartbeat_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
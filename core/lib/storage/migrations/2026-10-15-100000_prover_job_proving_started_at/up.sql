@@ -0,0 +1 @@
+ALTER TABLE prover_job_queue ADD COLUMN proving_started_at TIMESTAMP WITH TIME ZONE;
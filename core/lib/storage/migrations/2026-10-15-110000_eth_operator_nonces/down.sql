@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS eth_operator_nonces;
+ALTER TABLE eth_operations DROP COLUMN IF EXISTS sender_address;
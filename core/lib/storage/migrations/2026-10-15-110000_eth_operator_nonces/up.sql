@@ -0,0 +1,9 @@
+-- Operator account that sent the operation, `NULL` stands for the main operator account.
+ALTER TABLE eth_operations ADD sender_address BYTEA;
+
+-- Nonces of the additional operator accounts.
+-- Nonce of the main operator account is stored in `eth_parameters`.
+CREATE TABLE eth_operator_nonces (
+    address BYTEA PRIMARY KEY,
+    nonce BIGINT NOT NULL
+);
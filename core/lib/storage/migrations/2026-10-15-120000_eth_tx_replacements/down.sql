@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS eth_tx_replacement_requests;
+ALTER TABLE eth_operations DROP COLUMN IF EXISTS cancel_tx_hash;
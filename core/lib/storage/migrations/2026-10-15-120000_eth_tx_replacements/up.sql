@@ -0,0 +1,10 @@
+-- Hash of the self-send transaction cancelling the operation at the same nonce.
+ALTER TABLE eth_operations ADD cancel_tx_hash BYTEA;
+
+-- Replacements of the stuck transactions requested via the private API.
+-- Requests are taken and performed by the Ethereum sender.
+CREATE TABLE eth_tx_replacement_requests (
+    eth_op_id BIGINT PRIMARY KEY REFERENCES eth_operations (id) ON DELETE CASCADE,
+    action TEXT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
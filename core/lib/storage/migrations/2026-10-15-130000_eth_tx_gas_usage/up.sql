@@ -0,0 +1,13 @@
+-- Gas used by the confirmed transactions of the aggregated operations, serves as a basis
+-- for the gas limit estimation of the following transactions.
+-- Size of the operation is the total amount of chunks of the committed or executed blocks,
+-- or the amount of the proven blocks.
+CREATE TABLE eth_tx_gas_usage (
+    id BIGSERIAL PRIMARY KEY,
+    action_type TEXT NOT NULL,
+    size BIGINT NOT NULL,
+    gas_used BIGINT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
+
+CREATE INDEX eth_tx_gas_usage_action_type_size_idx ON eth_tx_gas_usage (action_type, size);
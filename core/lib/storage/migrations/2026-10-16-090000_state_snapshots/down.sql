@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS state_snapshot_chunks;
+DROP TABLE IF EXISTS state_snapshots;
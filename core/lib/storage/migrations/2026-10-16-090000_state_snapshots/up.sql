@@ -0,0 +1,16 @@
+-- Full account states after the executed blocks, stored periodically by the state pruner.
+-- Every snapshot makes the per-block account diffs up to its block unnecessary for
+-- restoring the state, so they can be removed beyond the retention window.
+CREATE TABLE state_snapshots (
+    block_number BIGINT PRIMARY KEY,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
+
+-- Accounts of the snapshot split into the chunks by the ranges of their ids,
+-- so neither the snapshot nor the lookup of a single account touches the whole state.
+CREATE TABLE state_snapshot_chunks (
+    block_number BIGINT NOT NULL REFERENCES state_snapshots (block_number) ON DELETE CASCADE,
+    chunk_id BIGINT NOT NULL,
+    accounts JSONB NOT NULL,
+    PRIMARY KEY (block_number, chunk_id)
+);
@@ -0,0 +1,24 @@
+-- The legacy partition becomes the plain table again, the rows of the other partitions are moved into it.
+ALTER TABLE tx_filters DETACH PARTITION tx_filters_legacy;
+INSERT INTO tx_filters_legacy (address, token, tx_hash, sequence_number, is_priority)
+SELECT address, token, tx_hash, sequence_number, is_priority FROM tx_filters
+ON CONFLICT DO NOTHING;
+DROP TABLE tx_filters;
+
+ALTER TABLE tx_filters_legacy RENAME TO tx_filters;
+ALTER TABLE tx_filters DROP CONSTRAINT tx_filters_legacy_pkey;
+ALTER TABLE tx_filters ALTER COLUMN sequence_number DROP NOT NULL;
+ALTER TABLE tx_filters ADD CONSTRAINT tx_filters_pkey PRIMARY KEY (address, token, tx_hash);
+INSERT INTO tx_filters (address, token, tx_hash, sequence_number, is_priority)
+SELECT address, token, tx_hash, sequence_number, is_priority FROM tx_filters_unmatched
+ON CONFLICT DO NOTHING;
+DROP TABLE tx_filters_unmatched;
+ALTER INDEX tx_filters_legacy_address_idx RENAME TO tx_filters_address_idx;
+ALTER INDEX ix_tx_filters_legacy_tx_hash_address RENAME TO ix_tx_filters_tx_hash_address;
+ALTER INDEX ix_tx_filters_legacy_address_sequence_number RENAME TO ix_tx_filters_address_sequence_number;
+
+DROP FUNCTION IF EXISTS detach_tx_filters_partitions;
+DROP FUNCTION IF EXISTS create_tx_filters_partitions;
+DROP FUNCTION IF EXISTS create_tx_filters_partition;
+DROP FUNCTION IF EXISTS tx_filters_legacy_bound;
+DROP FUNCTION IF EXISTS tx_filters_partition_size;
@@ -0,0 +1,209 @@
+-- `tx_filters` is partitioned by the range of the sequence number shared by the executed transactions
+-- and priority operations, so the account history queries bounded by the sequence number only touch
+-- the relevant partitions, and the partitions with the old history can be detached for archival.
+-- Partition `tx_filters_p<N>` holds the sequence numbers in `[N * size, (N + 1) * size)`.
+--
+-- The existing rows are not copied: the old table is attached as the `tx_filters_legacy` partition
+-- holding the sequence numbers up to the end of the current range, see `tx_filters_legacy_bound`.
+-- The rows without the sequence number are moved to `tx_filters_unmatched`.
+CREATE OR REPLACE FUNCTION tx_filters_partition_size() RETURNS BIGINT
+    LANGUAGE sql IMMUTABLE
+AS $$ SELECT 10000000::BIGINT $$;
+
+-- Exclusive upper bound of the sequence numbers held by the legacy partition.
+DO
+$$
+BEGIN
+    EXECUTE format(
+        'CREATE OR REPLACE FUNCTION tx_filters_legacy_bound() RETURNS BIGINT LANGUAGE sql IMMUTABLE AS $f$ SELECT %s::BIGINT $f$',
+        ((SELECT last_value FROM executed_operations_seq_number) / tx_filters_partition_size() + 1)
+            * tx_filters_partition_size()
+    );
+END;
+$$;
+
+-- Creates the partition holding the given sequence number if it doesn't exist yet.
+-- Returns whether the partition was created.
+CREATE OR REPLACE FUNCTION create_tx_filters_partition(seq_no BIGINT) RETURNS BOOLEAN
+    LANGUAGE plpgsql
+AS
+$$
+DECLARE
+    partition_index BIGINT := seq_no / tx_filters_partition_size();
+    partition_name TEXT := format('tx_filters_p%s', partition_index);
+BEGIN
+    IF seq_no < tx_filters_legacy_bound() THEN
+        RETURN false;
+    END IF;
+    IF to_regclass(partition_name) IS NULL THEN
+        EXECUTE format(
+            'CREATE TABLE %I PARTITION OF tx_filters FOR VALUES FROM (%s) TO (%s)',
+            partition_name,
+            partition_index * tx_filters_partition_size(),
+            (partition_index + 1) * tx_filters_partition_size()
+        );
+        RETURN true;
+    END IF;
+    RETURN false;
+END;
+$$;
+
+-- Creates the partitions for the current and the next range of the sequence numbers,
+-- so the partition always exists by the time the history rows are inserted.
+-- Returns whether any partition was created.
+CREATE OR REPLACE FUNCTION create_tx_filters_partitions() RETURNS BOOLEAN
+    LANGUAGE plpgsql
+AS
+$$
+DECLARE
+    last_seq_no BIGINT := (SELECT last_value FROM executed_operations_seq_number);
+BEGIN
+    -- Both partitions have to be checked, so the results are not short-circuited.
+    RETURN (create_tx_filters_partition(last_seq_no)::INT
+        + create_tx_filters_partition(last_seq_no + tx_filters_partition_size())::INT) > 0;
+END;
+$$;
+
+-- Detaches the partitions holding only the sequence numbers less than the given one
+-- and returns their names. Detached partitions are kept as standalone tables.
+CREATE OR REPLACE FUNCTION detach_tx_filters_partitions(before_seq_no BIGINT) RETURNS SETOF TEXT
+    LANGUAGE plpgsql
+AS
+$$
+DECLARE
+    partition_name TEXT;
+    upper_bound BIGINT;
+BEGIN
+    FOR partition_name IN
+        SELECT c.relname
+        FROM pg_inherits i
+        JOIN pg_class c ON c.oid = i.inhrelid
+        WHERE i.inhparent = 'tx_filters'::regclass
+        ORDER BY c.relname
+    LOOP
+        IF partition_name = 'tx_filters_legacy' THEN
+            upper_bound := tx_filters_legacy_bound();
+        ELSE
+            upper_bound := (substring(partition_name FROM 'tx_filters_p(\d+)')::BIGINT + 1) * tx_filters_partition_size();
+        END IF;
+        IF upper_bound <= before_seq_no THEN
+            EXECUTE format('ALTER TABLE tx_filters DETACH PARTITION %I', partition_name);
+            RETURN NEXT partition_name;
+        END IF;
+    END LOOP;
+END;
+$$;
+
+-- Partition key can't be null. Sequence numbers were set for the history of the executed operations
+-- by `add_seq_no`, the remaining rows are filled from the executed operations. Rows are updated in batches
+-- of transactions, so a single statement doesn't have to hold the whole history.
+DO
+$$
+DECLARE
+    batch_size CONSTANT INTEGER := 100000;
+    last_tx_hash BYTEA := ''::BYTEA;
+    batch BYTEA[];
+BEGIN
+    LOOP
+        SELECT array_agg(tx_hash ORDER BY tx_hash) INTO batch
+        FROM (
+            SELECT DISTINCT tx_hash
+            FROM tx_filters
+            WHERE sequence_number IS NULL AND tx_hash > last_tx_hash
+            ORDER BY tx_hash
+            LIMIT batch_size
+        ) hashes;
+        EXIT WHEN batch IS NULL;
+
+        UPDATE tx_filters f
+        SET sequence_number = COALESCE(
+                (SELECT t.sequence_number FROM executed_transactions t WHERE t.tx_hash = f.tx_hash),
+                (SELECT MIN(p.sequence_number) FROM executed_priority_operations p WHERE p.tx_hash = f.tx_hash)
+            ),
+            is_priority = COALESCE(
+                f.is_priority,
+                CASE
+                    WHEN EXISTS (SELECT 1 FROM executed_transactions t WHERE t.tx_hash = f.tx_hash) THEN false
+                    WHEN EXISTS (SELECT 1 FROM executed_priority_operations p WHERE p.tx_hash = f.tx_hash) THEN true
+                END
+            )
+        WHERE f.sequence_number IS NULL AND f.tx_hash = ANY(batch);
+
+        last_tx_hash := batch[array_length(batch, 1)];
+    END LOOP;
+END;
+$$;
+
+-- Rows that don't match any executed operation can't be placed into a partition. They are kept
+-- in `tx_filters_unmatched` for the investigation and returned back by the down migration.
+CREATE TABLE tx_filters_unmatched (LIKE tx_filters);
+WITH unmatched AS (
+    DELETE FROM tx_filters WHERE sequence_number IS NULL
+    RETURNING address, token, tx_hash, sequence_number, is_priority
+)
+INSERT INTO tx_filters_unmatched (address, token, tx_hash, sequence_number, is_priority)
+SELECT address, token, tx_hash, sequence_number, is_priority FROM unmatched;
+
+DO
+$$
+DECLARE
+    unmatched BIGINT := (SELECT COUNT(*) FROM tx_filters_unmatched);
+BEGIN
+    IF unmatched > 0 THEN
+        RAISE WARNING '% rows of tx_filters don''t match any executed operation, they are moved to tx_filters_unmatched',
+            unmatched;
+    END IF;
+END;
+$$;
+
+ALTER TABLE tx_filters RENAME TO tx_filters_legacy;
+ALTER INDEX tx_filters_address_idx RENAME TO tx_filters_legacy_address_idx;
+ALTER INDEX ix_tx_filters_tx_hash_address RENAME TO ix_tx_filters_legacy_tx_hash_address;
+ALTER INDEX ix_tx_filters_address_sequence_number RENAME TO ix_tx_filters_legacy_address_sequence_number;
+-- Partition key has to be a part of the primary key. Sequence number is the same for all
+-- the rows of the transaction, so the uniqueness of the rows is not affected.
+ALTER TABLE tx_filters_legacy DROP CONSTRAINT tx_filters_pkey;
+ALTER TABLE tx_filters_legacy ALTER COLUMN sequence_number SET NOT NULL;
+ALTER TABLE tx_filters_legacy ADD CONSTRAINT tx_filters_legacy_pkey
+    PRIMARY KEY (address, token, tx_hash, sequence_number);
+
+CREATE TABLE tx_filters
+(
+    address bytea NOT NULL,
+    token INTEGER NOT NULL,
+    tx_hash bytea NOT NULL,
+    sequence_number BIGINT NOT NULL,
+    is_priority bool,
+    CONSTRAINT tx_filters_pkey PRIMARY KEY (address, token, tx_hash, sequence_number)
+) PARTITION BY RANGE (sequence_number);
+
+-- Indexes of the legacy partition are attached to the ones of the partitioned table instead of being rebuilt.
+CREATE INDEX IF NOT EXISTS tx_filters_address_idx ON tx_filters USING hash (address);
+CREATE INDEX IF NOT EXISTS ix_tx_filters_tx_hash_address ON tx_filters (tx_hash, address);
+CREATE INDEX IF NOT EXISTS ix_tx_filters_address_sequence_number ON tx_filters USING btree (address, sequence_number) include(is_priority);
+
+-- `ATTACH PARTITION` doesn't scan the legacy rows if their bound is already enforced by a validated check.
+DO
+$$
+BEGIN
+    EXECUTE format(
+        'ALTER TABLE tx_filters_legacy ADD CONSTRAINT tx_filters_legacy_bound CHECK (sequence_number < %s) NOT VALID',
+        tx_filters_legacy_bound()
+    );
+END;
+$$;
+ALTER TABLE tx_filters_legacy VALIDATE CONSTRAINT tx_filters_legacy_bound;
+
+DO
+$$
+BEGIN
+    EXECUTE format(
+        'ALTER TABLE tx_filters ATTACH PARTITION tx_filters_legacy FOR VALUES FROM (MINVALUE) TO (%s)',
+        tx_filters_legacy_bound()
+    );
+END;
+$$;
+-- The bound is enforced by the partition constraint now.
+ALTER TABLE tx_filters_legacy DROP CONSTRAINT tx_filters_legacy_bound;
+
+SELECT create_tx_filters_partitions();
@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS executed_priority_operations_full_exits_to_account_idx;
+DROP INDEX IF EXISTS executed_transactions_withdrawals_to_account_idx;
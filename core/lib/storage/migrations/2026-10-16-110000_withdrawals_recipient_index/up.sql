@@ -0,0 +1,9 @@
+-- Withdrawals are looked up by their L1 recipient regardless of the L2 account which sent them,
+-- so only the transactions moving the funds to L1 are indexed.
+CREATE INDEX IF NOT EXISTS executed_transactions_withdrawals_to_account_idx
+    ON executed_transactions (to_account, sequence_number)
+    WHERE tx->>'type' IN ('Withdraw', 'WithdrawNFT', 'ForcedExit');
+
+CREATE INDEX IF NOT EXISTS executed_priority_operations_full_exits_to_account_idx
+    ON executed_priority_operations (to_account, sequence_number)
+    WHERE operation->>'type' = 'FullExit';
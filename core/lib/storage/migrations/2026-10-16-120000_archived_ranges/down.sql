@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS events_block_number_idx;
+DROP TABLE IF EXISTS archived_ranges;
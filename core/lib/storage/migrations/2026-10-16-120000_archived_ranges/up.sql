@@ -0,0 +1,15 @@
+-- Ranges of blocks whose rows were moved from the hot tables to the object storage.
+CREATE TABLE archived_ranges (
+    table_name TEXT NOT NULL,
+    from_block BIGINT NOT NULL,
+    to_block BIGINT NOT NULL,
+    object_key TEXT NOT NULL,
+    rows BIGINT NOT NULL,
+    -- Range of the identifiers of the archived rows, `NULL` if the range has no rows.
+    min_row_id BIGINT,
+    max_row_id BIGINT,
+    archived_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+    PRIMARY KEY (table_name, from_block)
+);
+
+CREATE INDEX IF NOT EXISTS events_block_number_idx ON events (block_number);
@@ -0,0 +1,42 @@
+-- Webhooks registered by the integrators to be notified about the events.
+CREATE TABLE webhooks (
+    id BIGSERIAL PRIMARY KEY,
+    url TEXT NOT NULL,
+    -- Secret of the HMAC signature of the delivered payloads.
+    secret TEXT NOT NULL,
+    -- Filters of the events in the format of the event server subscriptions.
+    filters JSONB NOT NULL,
+    -- Events up to this id are already enqueued for the delivery.
+    last_event_id BIGINT NOT NULL,
+    is_active BOOLEAN NOT NULL DEFAULT true,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
+
+-- Queue of the events to be delivered to the webhooks.
+CREATE TABLE webhook_deliveries (
+    id BIGSERIAL PRIMARY KEY,
+    webhook_id BIGINT NOT NULL REFERENCES webhooks (id) ON DELETE CASCADE,
+    event_id BIGINT NOT NULL,
+    payload JSONB NOT NULL,
+    attempts INT NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+    last_error TEXT,
+    delivered_at TIMESTAMP WITH TIME ZONE,
+    -- Set once the delivery runs out of attempts.
+    is_dead BOOLEAN NOT NULL DEFAULT false,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+    UNIQUE (webhook_id, event_id)
+);
+
+CREATE INDEX webhook_deliveries_pending_idx ON webhook_deliveries (next_attempt_at)
+    WHERE delivered_at IS NULL AND NOT is_dead;
+-- Delivered payloads are pruned once they are older than the retention period.
+CREATE INDEX webhook_deliveries_delivered_idx ON webhook_deliveries (delivered_at)
+    WHERE delivered_at IS NOT NULL;
+
+-- Deliveries that ran out of attempts, to be inspected and retried by the operators.
+CREATE VIEW webhook_dead_letters AS
+    SELECT webhook_deliveries.*, webhooks.url
+    FROM webhook_deliveries
+    INNER JOIN webhooks ON webhooks.id = webhook_deliveries.webhook_id
+    WHERE webhook_deliveries.is_dead;
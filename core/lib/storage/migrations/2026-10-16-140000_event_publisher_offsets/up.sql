@@ -0,0 +1,6 @@
+-- Id of the last event published by every event publisher to the message broker.
+CREATE TABLE event_publisher_offsets (
+    publisher TEXT PRIMARY KEY,
+    last_event_id BIGINT NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
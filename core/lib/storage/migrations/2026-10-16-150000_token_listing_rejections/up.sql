@@ -0,0 +1,8 @@
+-- Tokens added to the governance contract that failed the listing validation.
+CREATE TABLE token_listing_rejections (
+    token_id INTEGER PRIMARY KEY,
+    address TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    eth_tx_hash BYTEA,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
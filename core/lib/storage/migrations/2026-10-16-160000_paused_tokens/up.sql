@@ -0,0 +1,5 @@
+-- Tokens that can't be transferred or swapped, while the withdrawals of them are still allowed.
+CREATE TABLE paused_tokens (
+    token_id INTEGER PRIMARY KEY REFERENCES tokens(id) ON DELETE CASCADE,
+    paused_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
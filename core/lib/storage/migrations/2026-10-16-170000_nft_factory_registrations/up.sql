@@ -0,0 +1,11 @@
+-- Factories registered by the creators via the API, along with the signed registration messages.
+-- The same signature registers the factory on the contract.
+CREATE TABLE nft_factory_registrations (
+    id BIGSERIAL PRIMARY KEY,
+    creator_id INTEGER NOT NULL,
+    creator_address TEXT NOT NULL,
+    factory_address TEXT NOT NULL,
+    signature BYTEA NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+    UNIQUE (creator_id, factory_address)
+);
@@ -0,0 +1 @@
+ALTER TABLE nft_factory_registrations DROP COLUMN IF EXISTS deprecated_at;
@@ -0,0 +1,2 @@
+-- Deprecated registrations are no longer served to the users, but are kept so they can't be restored.
+ALTER TABLE nft_factory_registrations ADD COLUMN deprecated_at TIMESTAMP WITH TIME ZONE;
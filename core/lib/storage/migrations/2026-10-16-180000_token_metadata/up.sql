@@ -0,0 +1,9 @@
+-- Metadata of the tokens collected from the token contracts and the curated token list.
+CREATE TABLE token_metadata (
+    token_id INTEGER PRIMARY KEY REFERENCES tokens(id) ON DELETE CASCADE,
+    name TEXT,
+    onchain_decimals SMALLINT,
+    logo_url TEXT,
+    description TEXT,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS forced_exit_requests_target_idx;
+ALTER TABLE forced_exit_requests DROP COLUMN payment_received_at;
@@ -0,0 +1,2 @@
+ALTER TABLE forced_exit_requests ADD COLUMN payment_received_at TIMESTAMP with time zone;
+CREATE INDEX IF NOT EXISTS forced_exit_requests_target_idx ON forced_exit_requests (target);
@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS forced_exit_requests_additional_targets_idx;
+ALTER TABLE forced_exit_requests DROP COLUMN additional_targets;
@@ -0,0 +1,4 @@
+-- Other accounts exited by the same request, as a JSON array of `{ "target": ..., "tokens": [...] }`
+ALTER TABLE forced_exit_requests ADD COLUMN additional_targets JSONB NOT NULL DEFAULT '[]';
+CREATE INDEX IF NOT EXISTS forced_exit_requests_additional_targets_idx
+    ON forced_exit_requests USING GIN (additional_targets jsonb_path_ops);
@@ -0,0 +1,6 @@
+-- Single-row table: while the row exists, the new transactions are rejected by the mempool.
+CREATE TABLE tx_acceptance_pause (
+    id BOOLEAN PRIMARY KEY DEFAULT true CHECK (id),
+    reason TEXT NOT NULL,
+    paused_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
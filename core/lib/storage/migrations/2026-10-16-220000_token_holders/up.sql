@@ -0,0 +1,9 @@
+-- Amount of the accounts holding the token in the finalized state and the distribution of their balances,
+-- collected periodically by the token holders job.
+CREATE TABLE token_holders (
+    token_id INTEGER PRIMARY KEY REFERENCES tokens(id) ON DELETE CASCADE,
+    holders BIGINT NOT NULL,
+    -- JSON array of the balance buckets `{ "minBalance": string, "holders": number }` ordered by `minBalance`.
+    distribution JSONB NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
@@ -0,0 +1 @@
+DROP TABLE IF EXISTS exit_proof_jobs;
@@ -0,0 +1,18 @@
+-- Exit proofs requested via the API and generated by the exit proof generator.
+-- The proof is generated against the state of `block_number`, which is the last verified block at the request time.
+CREATE TABLE exit_proof_jobs (
+    id BIGSERIAL PRIMARY KEY,
+    account_id BIGINT NOT NULL,
+    token_id INTEGER NOT NULL,
+    block_number BIGINT NOT NULL,
+    -- One of `queued`, `inProgress`, `done` and `failed`.
+    status TEXT NOT NULL,
+    -- Input data of the exit transaction, present once the job is done.
+    proof JSONB,
+    error TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+    UNIQUE (account_id, token_id, block_number)
+);
+
+CREATE INDEX exit_proof_jobs_status_idx ON exit_proof_jobs (status, id);
@@ -0,0 +1 @@
+ALTER TABLE mempool_txs ADD COLUMN proposed BOOL NOT NULL DEFAULT FALSE;
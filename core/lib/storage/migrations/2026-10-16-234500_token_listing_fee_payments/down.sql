@@ -0,0 +1 @@
+DROP TABLE IF EXISTS token_listing_fee_payments;
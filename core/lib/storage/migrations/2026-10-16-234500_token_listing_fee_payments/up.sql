@@ -0,0 +1,8 @@
+-- Listing fee transfers consumed by the listed tokens, so every transfer pays for a single token.
+CREATE TABLE token_listing_fee_payments (
+    eth_tx_hash BYTEA NOT NULL,
+    log_index BIGINT NOT NULL,
+    token_id INTEGER NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+    PRIMARY KEY (eth_tx_hash, log_index)
+);
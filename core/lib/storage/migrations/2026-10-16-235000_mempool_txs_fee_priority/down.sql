@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS mempool_txs_fee_priority_idx;
+ALTER TABLE mempool_txs DROP COLUMN IF EXISTS fee_priority;
@@ -0,0 +1,7 @@
+-- Fee paid per chunk of the single queued transactions, in USD. Transactions with the
+-- lowest priority are evicted first when the mempool is full.
+ALTER TABLE mempool_txs ADD COLUMN fee_priority NUMERIC DEFAULT NULL;
+
+CREATE INDEX IF NOT EXISTS mempool_txs_fee_priority_idx
+    ON mempool_txs (fee_priority)
+    WHERE fee_priority IS NOT NULL AND batch_id = 0 AND reverted = false AND proposed = false;
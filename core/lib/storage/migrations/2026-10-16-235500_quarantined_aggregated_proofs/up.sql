@@ -0,0 +1,10 @@
+-- Aggregated proofs rejected by the local verification. They're moved out of `aggregated_proofs`
+-- so that the proof is generated again, and kept for the investigation.
+CREATE TABLE IF NOT EXISTS quarantined_aggregated_proofs
+(
+    id          BIGSERIAL PRIMARY KEY,
+    first_block BIGINT                   NOT NULL,
+    last_block  BIGINT                   NOT NULL,
+    proof       JSONB                    NOT NULL,
+    created_at  TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
@@ -0,0 +1,420 @@
+// Built-in deps
+use std::{
+    collections::HashMap,
+    fmt,
+    io::{BufRead, BufReader, Write},
+    sync::Mutex,
+    time::Instant,
+};
+// External imports
+use anyhow::{ensure, format_err};
+use async_trait::async_trait;
+use chrono::Utc;
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+// Workspace imports
+use zksync_types::BlockNumber;
+// Local imports
+use crate::{event::records::StoredEvent, QueryResult, StorageProcessor};
+use records::{ArchivedOperationArguments, ArchivedRange};
+
+pub mod records;
+
+/// Name of the `events` table in the `archived_ranges`.
+pub const EVENTS_TABLE: &str = "events";
+/// Name of the `aggregate_operations` table in the `archived_ranges`. Only the arguments of
+/// the confirmed `CommitBlocks` operations, i.e. the pubdata of the committed blocks, are archived.
+pub const PUBDATA_TABLE: &str = "aggregate_operations";
+
+/// Storage of the archived rows, e.g. an S3-compatible bucket.
+#[async_trait]
+pub trait ObjectStore: fmt::Debug + Send + Sync {
+    /// Stores the object, overwriting the existing one with the same key.
+    async fn put(&self, key: &str, data: Vec<u8>) -> anyhow::Result<()>;
+
+    /// Loads the object, failing if it doesn't exist.
+    async fn get(&self, key: &str) -> anyhow::Result<Vec<u8>>;
+}
+
+/// Object store keeping the objects in memory, to be used in tests and local setups.
+#[derive(Debug, Default)]
+pub struct MemoryObjectStore {
+    objects: Mutex<HashMap<String, Vec<u8>>>,
+}
+
+#[async_trait]
+impl ObjectStore for MemoryObjectStore {
+    async fn put(&self, key: &str, data: Vec<u8>) -> anyhow::Result<()> {
+        self.objects.lock().unwrap().insert(key.to_string(), data);
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> anyhow::Result<Vec<u8>> {
+        self.objects
+            .lock()
+            .unwrap()
+            .get(key)
+            .cloned()
+            .ok_or_else(|| format_err!("Object `{}` doesn't exist", key))
+    }
+}
+
+/// Returns the key of the object containing the rows of the table in the `[from_block, to_block]` range.
+///
+/// Block numbers are zero-padded, so the keys of the table are listed in the order of the blocks.
+pub fn archive_object_key(table: &str, from_block: BlockNumber, to_block: BlockNumber) -> String {
+    format!("{}/{:010}-{:010}.jsonl.gz", table, *from_block, *to_block)
+}
+
+/// Serializes the rows into the archived format: gzipped JSON objects, one per line.
+///
+/// Such objects can be queried in place by the tools supporting the JSON lines,
+/// e.g. Amazon Athena or S3 Select.
+pub fn encode_rows<T: Serialize>(rows: &[T]) -> anyhow::Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    for row in rows {
+        serde_json::to_writer(&mut encoder, row)?;
+        encoder.write_all(b"\n")?;
+    }
+    Ok(encoder.finish()?)
+}
+
+/// Deserializes the rows encoded by `encode_rows`.
+pub fn decode_rows<T: DeserializeOwned>(data: &[u8]) -> anyhow::Result<Vec<T>> {
+    BufReader::new(GzDecoder::new(data))
+        .lines()
+        .map(|line| Ok(serde_json::from_str(&line?)?))
+        .collect()
+}
+
+/// Archive schema keeps track of the block ranges moved from the hot tables
+/// to the object storage.
+///
+/// Rows are archived by block ranges in the increasing order, so every table has
+/// all of its rows up to the last archived block in the object storage, and the rest
+/// of them in the database. The archived rows are read back by the schemas of the tables
+/// if the connection has access to the archive, see `ConnectionPool::with_archive`.
+#[derive(Debug)]
+pub struct ArchiveSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> ArchiveSchema<'a, 'c> {
+    /// Moves the rows of the blocks up to `archive_up_to` to the object store, `blocks_per_object`
+    /// blocks per object. Only the complete ranges are archived, so every object covers the same
+    /// amount of blocks.
+    pub async fn archive_blocks(
+        &mut self,
+        store: &dyn ObjectStore,
+        archive_up_to: BlockNumber,
+        blocks_per_object: u32,
+    ) -> QueryResult<()> {
+        for &table in [EVENTS_TABLE, PUBDATA_TABLE].iter() {
+            let mut last_archived_block = self.get_last_archived_block(table).await?;
+            while *last_archived_block + blocks_per_object <= *archive_up_to {
+                let from_block = last_archived_block + 1;
+                let to_block = last_archived_block + blocks_per_object;
+                let rows = if table == EVENTS_TABLE {
+                    self.archive_events(store, from_block, to_block).await?
+                } else {
+                    self.archive_pubdata(store, from_block, to_block).await?
+                };
+
+                metrics::counter!("archiver.archived_rows", rows, "table" => table);
+                vlog::info!(
+                    "Archived {} rows of `{}` of the blocks {}-{}",
+                    rows,
+                    table,
+                    from_block,
+                    to_block
+                );
+                last_archived_block = to_block;
+            }
+        }
+        Ok(())
+    }
+
+    /// Moves the events of the blocks in the `[from_block, to_block]` range to the object store.
+    /// Returns the amount of the archived events.
+    pub async fn archive_events(
+        &mut self,
+        store: &dyn ObjectStore,
+        from_block: BlockNumber,
+        to_block: BlockNumber,
+    ) -> QueryResult<u64> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+        let events = transaction
+            .event_schema()
+            .load_stored_events_in_block_range(from_block, to_block)
+            .await?;
+        let ids = events.iter().map(|event| event.id).collect::<Vec<_>>();
+        ArchiveSchema(&mut transaction)
+            .store_archived_rows(store, EVENTS_TABLE, from_block, to_block, &events, &ids)
+            .await?;
+        let removed = transaction
+            .event_schema()
+            .remove_events_in_block_range(from_block, to_block)
+            .await?;
+        ensure!(
+            removed == events.len() as u64,
+            "Events of the blocks {}-{} were changed while being archived",
+            from_block,
+            to_block
+        );
+        transaction.commit().await?;
+
+        sql_histogram!(self.0, "sql.archive.archive_events", start.elapsed());
+        Ok(removed)
+    }
+
+    /// Moves the arguments of the confirmed `CommitBlocks` operations ending in the
+    /// `[from_block, to_block]` range to the object store, the rest of the rows is kept.
+    /// Returns the amount of the archived operations.
+    pub async fn archive_pubdata(
+        &mut self,
+        store: &dyn ObjectStore,
+        from_block: BlockNumber,
+        to_block: BlockNumber,
+    ) -> QueryResult<u64> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+        let unconfirmed = sqlx::query!(
+            r#"SELECT COUNT(*) as "count!" FROM aggregate_operations
+            WHERE action_type = 'CommitBlocks' AND to_block BETWEEN $1 AND $2 AND confirmed = false"#,
+            i64::from(*from_block),
+            i64::from(*to_block)
+        )
+        .fetch_one(transaction.conn())
+        .await?
+        .count;
+        ensure!(
+            unconfirmed == 0,
+            "Blocks {}-{} have unconfirmed commit operations",
+            from_block,
+            to_block
+        );
+
+        let operations = sqlx::query_as!(
+            ArchivedOperationArguments,
+            "SELECT id, arguments FROM aggregate_operations
+            WHERE action_type = 'CommitBlocks' AND to_block BETWEEN $1 AND $2
+                AND arguments != 'null'::jsonb
+            ORDER BY id ASC",
+            i64::from(*from_block),
+            i64::from(*to_block)
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+        let ids = operations.iter().map(|op| op.id).collect::<Vec<_>>();
+        ArchiveSchema(&mut transaction)
+            .store_archived_rows(
+                store,
+                PUBDATA_TABLE,
+                from_block,
+                to_block,
+                &operations,
+                &ids,
+            )
+            .await?;
+        let archived = sqlx::query!(
+            "UPDATE aggregate_operations SET arguments = 'null'::jsonb WHERE id = ANY($1)",
+            &ids
+        )
+        .execute(transaction.conn())
+        .await?
+        .rows_affected();
+        transaction.commit().await?;
+
+        sql_histogram!(self.0, "sql.archive.archive_pubdata", start.elapsed());
+        Ok(archived)
+    }
+
+    /// Uploads the rows with the given identifiers and records the archived range.
+    /// The object is uploaded before the rows are removed, so the failure in between
+    /// only results in the object being uploaded again on the next attempt.
+    async fn store_archived_rows<T: Serialize>(
+        &mut self,
+        store: &dyn ObjectStore,
+        table: &str,
+        from_block: BlockNumber,
+        to_block: BlockNumber,
+        rows: &[T],
+        ids: &[i64],
+    ) -> QueryResult<()> {
+        let object_key = archive_object_key(table, from_block, to_block);
+        store.put(&object_key, encode_rows(rows)?).await?;
+        self.store_archived_range(
+            table,
+            from_block,
+            to_block,
+            &object_key,
+            rows.len() as u64,
+            ids.iter()
+                .min()
+                .zip(ids.iter().max())
+                .map(|(min, max)| (*min, *max)),
+        )
+        .await
+    }
+
+    /// Returns the last block whose rows of the table are archived, or zero if nothing is archived yet.
+    pub async fn get_last_archived_block(&mut self, table: &str) -> QueryResult<BlockNumber> {
+        let start = Instant::now();
+        let block = sqlx::query!(
+            "SELECT MAX(to_block) as max FROM archived_ranges WHERE table_name = $1",
+            table
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .max
+        .map(|block| BlockNumber(block as u32))
+        .unwrap_or_default();
+
+        sql_histogram!(
+            self.0,
+            "sql.archive.get_last_archived_block",
+            start.elapsed()
+        );
+        Ok(block)
+    }
+
+    /// Records that the rows of the table in the `[from_block, to_block]` range
+    /// are stored in the object with the given key.
+    async fn store_archived_range(
+        &mut self,
+        table: &str,
+        from_block: BlockNumber,
+        to_block: BlockNumber,
+        object_key: &str,
+        rows: u64,
+        row_ids: Option<(i64, i64)>,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "INSERT INTO archived_ranges (table_name, from_block, to_block, object_key, rows, min_row_id, max_row_id, archived_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+            table,
+            i64::from(*from_block),
+            i64::from(*to_block),
+            object_key,
+            rows as i64,
+            row_ids.map(|(min, _)| min),
+            row_ids.map(|(_, max)| max),
+            Utc::now(),
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        sql_histogram!(self.0, "sql.archive.store_archived_range", start.elapsed());
+        Ok(())
+    }
+
+    /// Loads the archived ranges of the table overlapping with the `[from_block, to_block]` range,
+    /// ordered by the block numbers.
+    pub async fn load_archived_ranges(
+        &mut self,
+        table: &str,
+        from_block: BlockNumber,
+        to_block: BlockNumber,
+    ) -> QueryResult<Vec<ArchivedRange>> {
+        let start = Instant::now();
+        let ranges = sqlx::query_as!(
+            ArchivedRange,
+            "SELECT * FROM archived_ranges
+            WHERE table_name = $1 AND from_block <= $3 AND to_block >= $2
+            ORDER BY from_block ASC",
+            table,
+            i64::from(*from_block),
+            i64::from(*to_block),
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        sql_histogram!(self.0, "sql.archive.load_archived_ranges", start.elapsed());
+        Ok(ranges)
+    }
+
+    /// Loads the archived ranges of the table containing the rows with the identifiers
+    /// in the `(from_id, to_id]` range, ordered by the block numbers.
+    /// The range is not bounded from above if `to_id` is `None`.
+    pub async fn load_archived_ranges_by_row_id(
+        &mut self,
+        table: &str,
+        from_id: i64,
+        to_id: Option<i64>,
+    ) -> QueryResult<Vec<ArchivedRange>> {
+        let start = Instant::now();
+        let ranges = sqlx::query_as!(
+            ArchivedRange,
+            "SELECT * FROM archived_ranges
+            WHERE table_name = $1 AND max_row_id > $2 AND ($3::bigint IS NULL OR min_row_id <= $3)
+            ORDER BY from_block ASC",
+            table,
+            from_id,
+            to_id,
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        sql_histogram!(
+            self.0,
+            "sql.archive.load_archived_ranges_by_row_id",
+            start.elapsed()
+        );
+        Ok(ranges)
+    }
+
+    /// Loads and decodes the rows of the archived objects, in the order they were archived in.
+    /// Fails if the connection has no access to the archive.
+    pub async fn load_archived_rows<T: DeserializeOwned>(
+        &mut self,
+        ranges: &[ArchivedRange],
+    ) -> QueryResult<Vec<T>> {
+        let store = self.0.archive().ok_or_else(|| {
+            format_err!("Archived rows are requested without access to the archive")
+        })?;
+        let mut rows = Vec::new();
+        for range in ranges {
+            let start = Instant::now();
+            let data = store.get(&range.object_key).await.map_err(|err| {
+                format_err!("Unable to load archived `{}`: {}", range.object_key, err)
+            })?;
+            rows.extend(decode_rows::<T>(&data)?);
+            metrics::histogram!("sql.archive.load_archived_object", start.elapsed(), "table" => range.table_name.clone());
+        }
+        Ok(rows)
+    }
+
+    /// Loads the archived events with the identifiers in the `(from_id, to_id]` range,
+    /// ordered by the identifiers. The range is not bounded from above if `to_id` is `None`.
+    pub async fn load_archived_events_by_id(
+        &mut self,
+        from_id: i64,
+        to_id: Option<i64>,
+    ) -> QueryResult<Vec<StoredEvent>> {
+        let ranges = self
+            .load_archived_ranges_by_row_id(EVENTS_TABLE, from_id, to_id)
+            .await?;
+        let mut events = self.load_archived_rows::<StoredEvent>(&ranges).await?;
+        events.retain(|event| event.id > from_id && to_id.map_or(true, |to_id| event.id <= to_id));
+        events.sort_by_key(|event| event.id);
+        Ok(events)
+    }
+
+    /// Loads the archived arguments of the aggregated operation ending at `to_block`.
+    pub async fn load_archived_operation_arguments(
+        &mut self,
+        id: i64,
+        to_block: BlockNumber,
+    ) -> QueryResult<Value> {
+        let ranges = self
+            .load_archived_ranges(PUBDATA_TABLE, to_block, to_block)
+            .await?;
+        self.load_archived_rows::<ArchivedOperationArguments>(&ranges)
+            .await?
+            .into_iter()
+            .find(|op| op.id == id)
+            .map(|op| op.arguments)
+            .ok_or_else(|| format_err!("Aggregated operation {} is not found in the archive", id))
+    }
+}
@@ -0,0 +1,26 @@
+// External imports
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::FromRow;
+// Workspace imports
+// Local imports
+
+#[derive(Debug, Clone, FromRow, PartialEq)]
+pub struct ArchivedRange {
+    pub table_name: String,
+    pub from_block: i64,
+    pub to_block: i64,
+    pub object_key: String,
+    pub rows: i64,
+    pub min_row_id: Option<i64>,
+    pub max_row_id: Option<i64>,
+    pub archived_at: DateTime<Utc>,
+}
+
+/// Arguments of the archived aggregated operation, the rest of the row is kept in the database.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, PartialEq)]
+pub struct ArchivedOperationArguments {
+    pub id: i64,
+    pub arguments: Value,
+}
@@ -0,0 +1,163 @@
+// Built-in deps
+use std::time::Instant;
+// External imports
+use anyhow::ensure;
+use serde_json::Value;
+// Workspace imports
+// Local imports
+use crate::{QueryResult, StorageProcessor};
+
+/// Tables required to restore the rollup state and restart the server on top of it, in the order
+/// they can be restored in without violating the foreign keys.
+pub const BACKUP_TABLES: &[&str] = &[
+    "server_config",
+    "tokens",
+    "nft_factory",
+    "eth_account_types",
+    "accounts",
+    "balances",
+    "nft",
+    "withdrawn_nfts_factories",
+    "account_creates",
+    "account_balance_updates",
+    "account_pubkey_updates",
+    "mint_nft_updates",
+    "blocks",
+    "block_metadata",
+    "pending_block",
+    "executed_transactions",
+    "executed_priority_operations",
+    "txs_batches_hashes",
+    "txs_batches_signatures",
+    "aggregate_operations",
+    "commit_aggregated_blocks_binding",
+    "execute_aggregated_blocks_binding",
+    "proofs",
+    "aggregated_proofs",
+    "eth_operations",
+    "eth_aggregated_ops_binding",
+    "eth_unprocessed_aggregated_ops",
+    "eth_tx_hashes",
+    "eth_parameters",
+    "account_tree_cache",
+];
+
+/// Backup schema is capable of exporting the rollup-critical tables as JSON rows
+/// and importing them back.
+///
+/// Every row is represented as a JSON object with the column names as keys, which makes
+/// the backup independent of both the `pg_dump` version and the physical layout of the tables.
+/// `NUMERIC` values are exported as strings, since JSON numbers can't hold the balances precisely.
+/// Methods accept only the names of the `BACKUP_TABLES`.
+#[derive(Debug)]
+pub struct BackupSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> BackupSchema<'a, 'c> {
+    /// Makes the current database transaction read-only and see the same snapshot of all
+    /// the tables. Must be called before any other query of the transaction.
+    pub async fn begin_snapshot(&mut self) -> QueryResult<()> {
+        sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ, READ ONLY")
+            .execute(self.0.conn())
+            .await?;
+        Ok(())
+    }
+
+    /// Returns the amount of rows in the table.
+    pub async fn count_rows(&mut self, table: &str) -> QueryResult<i64> {
+        let table = backup_table(table)?;
+        let count = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {}", table))
+            .fetch_one(self.0.conn())
+            .await?;
+        Ok(count)
+    }
+
+    /// Opens the cursor over the rows of the table, which are then loaded by `fetch_rows`.
+    /// Only one table can be read at a time within the transaction.
+    pub async fn open_table(&mut self, table: &str) -> QueryResult<()> {
+        let table = backup_table(table)?;
+        // `NUMERIC` columns are cast to text, `json_populate_recordset` parses them back on restore.
+        let columns: String = sqlx::query_scalar(
+            "SELECT string_agg(
+                CASE
+                    WHEN a.atttypid = 'numeric'::regtype THEN format('%I::text AS %I', a.attname, a.attname)
+                    WHEN a.atttypid = 'numeric[]'::regtype THEN format('%I::text[] AS %I', a.attname, a.attname)
+                    ELSE format('%I', a.attname)
+                END, ', ' ORDER BY a.attnum
+            )
+            FROM pg_attribute a
+            WHERE a.attrelid = $1::text::regclass AND a.attnum > 0 AND NOT a.attisdropped",
+        )
+        .bind(table)
+        .fetch_one(self.0.conn())
+        .await?;
+
+        sqlx::query("CLOSE ALL").execute(self.0.conn()).await?;
+        sqlx::query(&format!(
+            "DECLARE backup_rows NO SCROLL CURSOR FOR SELECT row_to_json(t) FROM (SELECT {} FROM {}) t",
+            columns, table
+        ))
+        .execute(self.0.conn())
+        .await?;
+        Ok(())
+    }
+
+    /// Loads up to `limit` next rows of the table opened by `open_table`.
+    /// Returns an empty list once all the rows are loaded.
+    pub async fn fetch_rows(&mut self, limit: usize) -> QueryResult<Vec<Value>> {
+        let start = Instant::now();
+        let rows = sqlx::query_scalar(&format!("FETCH {} FROM backup_rows", limit))
+            .fetch_all(self.0.conn())
+            .await?;
+
+        sql_histogram!(self.0, "sql.backup.fetch_rows", start.elapsed());
+        Ok(rows)
+    }
+
+    /// Inserts the rows exported by `fetch_rows` into the table.
+    pub async fn insert_rows(&mut self, table: &str, rows: Vec<Value>) -> QueryResult<()> {
+        let start = Instant::now();
+        let table = backup_table(table)?;
+        sqlx::query(&format!(
+            "INSERT INTO {0} SELECT * FROM json_populate_recordset(NULL::{0}, $1)",
+            table
+        ))
+        .bind(Value::Array(rows))
+        .execute(self.0.conn())
+        .await?;
+
+        sql_histogram!(self.0, "sql.backup.insert_rows", start.elapsed());
+        Ok(())
+    }
+
+    /// Moves the sequences generating the identifiers of the table past the restored rows.
+    pub async fn reset_sequences(&mut self, table: &str) -> QueryResult<()> {
+        let table = backup_table(table)?;
+        let columns: Vec<String> = sqlx::query_scalar(
+            "SELECT a.attname::text FROM pg_attribute a
+            WHERE a.attrelid = $1::text::regclass AND a.attnum > 0 AND NOT a.attisdropped
+                AND pg_get_serial_sequence($1::text, a.attname) IS NOT NULL",
+        )
+        .bind(table)
+        .fetch_all(self.0.conn())
+        .await?;
+
+        for column in columns {
+            sqlx::query(&format!(
+                "SELECT setval(pg_get_serial_sequence('{0}', '{1}'), COALESCE(MAX({1}), 0) + 1, false) FROM {0}",
+                table, column
+            ))
+            .execute(self.0.conn())
+            .await?;
+        }
+        Ok(())
+    }
+}
+
+fn backup_table(table: &str) -> QueryResult<&str> {
+    ensure!(
+        BACKUP_TABLES.contains(&table),
+        "Table `{}` is not backed up",
+        table
+    );
+    Ok(table)
+}
@@ -6,11 +6,12 @@ use sqlx::{types::BigDecimal, Acquire};
 // Workspace imports
 use zksync_crypto::params::{MIN_NFT_TOKEN_ID, NFT_STORAGE_ACCOUNT_ID, NFT_TOKEN_ID};
 use zksync_types::{
-    Account, AccountId, AccountUpdates, Address, BlockNumber, Nonce, PubKeyHash, TokenId,
+    Account, AccountId, AccountUpdate, AccountUpdates, Address, BlockNumber, Nonce, PubKeyHash,
+    TokenId,
 };
 // Local imports
 use self::records::*;
-use crate::chain::block::BlockSchema;
+use crate::chain::{block::BlockSchema, pruning::PruningSchema};
 use crate::diff::StorageAccountDiff;
 use crate::{QueryResult, StorageProcessor};
 
@@ -76,7 +77,7 @@ impl<'a, 'c> AccountSchema<'a, 'c> {
 
         transaction.commit().await?;
 
-        metrics::histogram!("sql.chain.state.set_account_type", start.elapsed());
+        sql_histogram!(self.0, "sql.chain.state.set_account_type", start.elapsed());
         Ok(())
     }
 
@@ -107,7 +108,11 @@ impl<'a, 'c> AccountSchema<'a, 'c> {
             verified_nonce.map(|nonce| nonce.nonce)
         };
 
-        metrics::histogram!("sql.chain.account.current_nonce", start.elapsed());
+        sql_histogram!(
+            transaction,
+            "sql.chain.account.current_nonce",
+            start.elapsed()
+        );
         Ok(current_nonce.map(|v| Nonce(v as u32)))
     }
 
@@ -150,7 +155,11 @@ impl<'a, 'c> AccountSchema<'a, 'c> {
 
         let account_type =
             db_account_type.map(|db_type| EthAccountType::from_db(db_type, pub_key_hash));
-        metrics::histogram!("sql.chain.account.account_type_by_id", start.elapsed());
+        sql_histogram!(
+            transaction,
+            "sql.chain.account.account_type_by_id",
+            start.elapsed()
+        );
         Ok(account_type)
     }
 
@@ -168,7 +177,11 @@ impl<'a, 'c> AccountSchema<'a, 'c> {
             .last_committed_state_for_account(account_id)
             .await?;
 
-        metrics::histogram!("sql.chain.account.account_state_by_id", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.chain.account.account_state_by_id",
+            start.elapsed()
+        );
         Ok(StoredAccountState {
             committed: committed_state.map(|a| (account_id, a)),
             verified: verified_state.1.map(|a| (account_id, a)),
@@ -189,7 +202,11 @@ impl<'a, 'c> AccountSchema<'a, 'c> {
         )
         .fetch_optional(self.0.conn())
         .await?;
-        metrics::histogram!("sql.chain.account.does_account_exist", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.chain.account.does_account_exist",
+            start.elapsed()
+        );
         Ok(result.is_some())
     }
 
@@ -209,7 +226,8 @@ impl<'a, 'c> AccountSchema<'a, 'c> {
             })
         };
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.account.account_state_by_address",
             start.elapsed()
         );
@@ -320,7 +338,8 @@ impl<'a, 'c> AccountSchema<'a, 'c> {
 
         transaction.commit().await?;
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.account.last_committed_state_for_account",
             start.elapsed()
         );
@@ -335,7 +354,8 @@ impl<'a, 'c> AccountSchema<'a, 'c> {
     ) -> QueryResult<Option<Account>> {
         let start = Instant::now();
         let (_, account) = self.account_and_last_block(account_id).await?;
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.account.last_verified_state_for_account",
             start.elapsed()
         );
@@ -399,7 +419,8 @@ impl<'a, 'c> AccountSchema<'a, 'c> {
         };
 
         transaction.commit().await?;
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.account.get_account_and_last_block",
             start.elapsed()
         );
@@ -426,7 +447,11 @@ impl<'a, 'c> AccountSchema<'a, 'c> {
         .await?;
 
         let account_id = result.map(|record| AccountId(record.account_id as u32));
-        metrics::histogram!("sql.chain.account.account_id_by_address", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.chain.account.account_id_by_address",
+            start.elapsed()
+        );
         Ok(account_id)
     }
 
@@ -444,7 +469,11 @@ impl<'a, 'c> AccountSchema<'a, 'c> {
         .await?;
 
         let address = result.map(|record| Address::from_slice(&record.address));
-        metrics::histogram!("sql.chain.account.account_address_by_id", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.chain.account.account_address_by_id",
+            start.elapsed()
+        );
         Ok(address)
     }
 
@@ -479,7 +508,8 @@ impl<'a, 'c> AccountSchema<'a, 'c> {
         .greatest
         .unwrap_or(block_number);
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.account.last_committed_block_with_update_for_acc",
             start.elapsed()
         );
@@ -527,7 +557,8 @@ impl<'a, 'c> AccountSchema<'a, 'c> {
             .unwrap_or_else(BigUint::zero);
 
         transaction.commit().await?;
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.account.get_account_balance_for_block",
             start.elapsed()
         );
@@ -535,6 +566,123 @@ impl<'a, 'c> AccountSchema<'a, 'c> {
         Ok(result)
     }
 
+    /// Restores the state of the account after the given block along with the number of the last
+    /// block which updated the account (or the snapshot block if it wasn't updated since the snapshot).
+    ///
+    /// The state is restored from the latest state snapshot not after the block and the account diffs
+    /// following the snapshot (or all the diffs if there is no such snapshot), so it's only correct
+    /// for the blocks starting from the earliest stored snapshot if the old blocks are pruned
+    /// (see `PruningSchema`).
+    pub async fn account_state_at_block(
+        &mut self,
+        account_id: AccountId,
+        block_number: BlockNumber,
+    ) -> QueryResult<(BlockNumber, Option<Account>)> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        let (snapshot_block, account) = PruningSchema(&mut transaction)
+            .load_account_snapshot(account_id, block_number)
+            .await?
+            .unwrap_or((BlockNumber(0), None));
+        let snapshot_block = i64::from(*snapshot_block);
+        let block_number = i64::from(*block_number);
+
+        // Only the last update of every token balance matters, since the updates store the new values.
+        // The last balance update is always among them, so the nonce is restored correctly as well.
+        let account_balance_diff = sqlx::query_as!(
+            StorageAccountUpdate,
+            "
+                SELECT DISTINCT ON (coin_id) * FROM account_balance_updates
+                WHERE account_id = $1 AND block_number > $2 AND block_number <= $3
+                ORDER BY coin_id, block_number DESC, update_order_id DESC
+            ",
+            i64::from(*account_id),
+            snapshot_block,
+            block_number
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+
+        let account_creation_diff = sqlx::query_as!(
+            StorageAccountCreation,
+            "
+                SELECT * FROM account_creates
+                WHERE account_id = $1 AND block_number > $2 AND block_number <= $3
+            ",
+            i64::from(*account_id),
+            snapshot_block,
+            block_number
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+
+        let account_pubkey_diff = sqlx::query_as!(
+            StorageAccountPubkeyUpdate,
+            "
+                SELECT * FROM account_pubkey_updates
+                WHERE account_id = $1 AND block_number > $2 AND block_number <= $3
+            ",
+            i64::from(*account_id),
+            snapshot_block,
+            block_number
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+        let mint_nft_updates = sqlx::query_as!(
+            StorageMintNFTUpdate,
+            "
+                SELECT * FROM mint_nft_updates
+                WHERE creator_account_id = $1 AND block_number > $2 AND block_number <= $3
+            ",
+            *account_id as i32,
+            snapshot_block,
+            block_number
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+
+        let mut account_diff = Vec::new();
+        account_diff.extend(
+            account_balance_diff
+                .into_iter()
+                .map(StorageAccountDiff::from),
+        );
+        account_diff.extend(
+            account_creation_diff
+                .into_iter()
+                .map(StorageAccountDiff::from),
+        );
+        account_diff.extend(
+            account_pubkey_diff
+                .into_iter()
+                .map(StorageAccountDiff::from),
+        );
+        account_diff.extend(mint_nft_updates.into_iter().map(StorageAccountDiff::from));
+        account_diff.sort_by(StorageAccountDiff::cmp_order);
+
+        let last_update_block = account_diff
+            .last()
+            .map(StorageAccountDiff::block_number)
+            .unwrap_or(snapshot_block);
+        let account_state = account_diff
+            .into_iter()
+            .map(|diff| {
+                let (_, update): (AccountId, AccountUpdate) = diff.into();
+                update
+            })
+            .fold(account, Account::apply_update);
+
+        transaction.commit().await?;
+
+        sql_histogram!(
+            self.0,
+            "sql.chain.account.account_state_at_block",
+            start.elapsed()
+        );
+        Ok((BlockNumber(last_update_block as u32), account_state))
+    }
+
     pub async fn get_account_nft_balance(&mut self, address: Address) -> QueryResult<u32> {
         let start = Instant::now();
         let mut transaction = self.0.start_transaction().await?;
@@ -570,7 +718,11 @@ impl<'a, 'c> AccountSchema<'a, 'c> {
         .unwrap_or(0) as u32;
 
         transaction.commit().await?;
-        metrics::histogram!("sql.chain.account.get_account_nft_balance", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.chain.account.get_account_nft_balance",
+            start.elapsed()
+        );
 
         Ok(balance)
     }
@@ -590,7 +742,7 @@ impl<'a, 'c> AccountSchema<'a, 'c> {
         .await?;
         let owner_id = record.map(|record| AccountId(record.account_id as u32));
 
-        metrics::histogram!("sql.chain.account.get_nft_owner", start.elapsed());
+        sql_histogram!(self.0, "sql.chain.account.get_nft_owner", start.elapsed());
         Ok(owner_id)
     }
 }
@@ -53,6 +53,16 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         let start = Instant::now();
         let mut transaction = self.0.start_transaction().await?;
 
+        // The history of the stored operations goes to the `tx_filters` partition
+        // which has to exist beforehand.
+        if !operations.is_empty() {
+            transaction
+                .chain()
+                .operations_schema()
+                .create_tx_filters_partitions()
+                .await?;
+        }
+
         for block_tx in operations.into_iter() {
             match block_tx {
                 ExecutedOperations::Tx(tx) => {
@@ -120,7 +130,11 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         }
 
         transaction.commit().await?;
-        metrics::histogram!("sql.chain.block.save_block_transactions", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.chain.block.save_block_transactions",
+            start.elapsed()
+        );
         Ok(())
     }
 
@@ -138,7 +152,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         .fetch_optional(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql.chain.block.get_storage_block", start.elapsed());
+        sql_histogram!(self.0, "sql.chain.block.get_storage_block", start.elapsed());
 
         Ok(block)
     }
@@ -179,7 +193,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
             stored_block.timestamp.unwrap_or_default() as u64,
         ));
 
-        metrics::histogram!("sql.chain.block.get_block", start.elapsed());
+        sql_histogram!(self.0, "sql.chain.block.get_block", start.elapsed());
 
         Ok(result)
     }
@@ -200,7 +214,11 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         .fetch_optional(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql.chain.block.get_block_metadata", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.chain.block.get_block_metadata",
+            start.elapsed()
+        );
 
         let result = db_result.map(|md| BlockMetadata {
             fast_processing: md.fast_processing,
@@ -221,7 +239,11 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
                 ExecutedOperations::PriorityOp(priorop) => Some(priorop.op),
             })
             .collect();
-        metrics::histogram!("sql.chain.block.get_block_operations", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.chain.block.get_block_operations",
+            start.elapsed()
+        );
         Ok(result)
     }
 
@@ -282,7 +304,11 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         .fetch_all(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql.chain.block.get_block_transactions", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.chain.block.get_block_transactions",
+            start.elapsed()
+        );
         Ok(block_txs)
     }
 
@@ -345,7 +371,11 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
             }
         });
 
-        metrics::histogram!("sql.chain.block.get_block_executed_ops", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.chain.block.get_block_executed_ops",
+            start.elapsed()
+        );
         Ok(executed_operations)
     }
 
@@ -409,7 +439,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         ).fetch_all(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql.chain.block.load_block_range", start.elapsed());
+        sql_histogram!(self.0, "sql.chain.block.load_block_range", start.elapsed());
         Ok(details)
     }
 
@@ -473,7 +503,11 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         ).fetch_all(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql.chain.block.load_block_range_asc", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.chain.block.load_block_range_asc",
+            start.elapsed()
+        );
         Ok(details)
     }
 
@@ -609,7 +643,8 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
             .ok()
             .flatten();
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.block.find_block_by_height_or_hash",
             start.elapsed()
         );
@@ -624,7 +659,11 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
             .await?
             .max
             .unwrap_or(0);
-        metrics::histogram!("sql.chain.block.get_last_committed_block", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.chain.block.get_last_committed_block",
+            start.elapsed()
+        );
         Ok(BlockNumber(count as u32))
     }
 
@@ -634,7 +673,11 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         let result = OperationsSchema(self.0)
             .get_last_block_by_aggregated_action(AggregatedActionType::CommitBlocks, None)
             .await;
-        metrics::histogram!("sql.chain.block.get_last_committed_block", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.chain.block.get_last_committed_block",
+            start.elapsed()
+        );
         result
     }
 
@@ -652,7 +695,11 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
             .await?
             .max
             .map(|block| BlockNumber(block as u32));
-        metrics::histogram!("sql.chain.block.get_last_incomplete_block", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.chain.block.get_last_incomplete_block",
+            start.elapsed()
+        );
         Ok(result)
     }
 
@@ -662,7 +709,8 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         let result = OperationsSchema(self.0)
             .get_last_block_by_aggregated_action(AggregatedActionType::CommitBlocks, Some(true))
             .await;
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.block.get_last_committed_confirmed_block",
             start.elapsed()
         );
@@ -679,7 +727,28 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         let result = OperationsSchema(self.0)
             .get_last_block_by_aggregated_action(AggregatedActionType::ExecuteBlocks, None)
             .await;
-        metrics::histogram!("sql.chain.block.get_last_verified_block", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.chain.block.get_last_verified_block",
+            start.elapsed()
+        );
+        result
+    }
+
+    /// Returns the number of last block for which proof has been sent to Ethereum.
+    pub async fn get_last_proven_block(&mut self) -> QueryResult<BlockNumber> {
+        let start = Instant::now();
+        let result = OperationsSchema(self.0)
+            .get_last_block_by_aggregated_action(
+                AggregatedActionType::PublishProofBlocksOnchain,
+                None,
+            )
+            .await;
+        sql_histogram!(
+            self.0,
+            "sql.chain.block.get_last_proven_block",
+            start.elapsed()
+        );
         result
     }
 
@@ -692,7 +761,8 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
                 Some(true),
             )
             .await;
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.block.get_last_proven_confirmed_block",
             start.elapsed()
         );
@@ -706,7 +776,8 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         let result = OperationsSchema(self.0)
             .get_last_block_by_aggregated_action(AggregatedActionType::ExecuteBlocks, Some(true))
             .await;
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.block.get_last_verified_confirmed_block",
             start.elapsed()
         );
@@ -731,7 +802,11 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         )
         .fetch_optional(self.0.conn())
         .await?;
-        metrics::histogram!("sql.chain.block.pending_block_chunks_left", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.chain.block.pending_block_chunks_left",
+            start.elapsed()
+        );
 
         Ok(maybe_block_chunks.map(|val| val.chunks_left as usize))
     }
@@ -747,7 +822,8 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         )
         .fetch_optional(self.0.conn())
         .await?;
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.block.load_storage_pending_block",
             start.elapsed()
         );
@@ -799,7 +875,11 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
 
         transaction.commit().await?;
 
-        metrics::histogram!("sql.chain.block.load_pending_block", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.chain.block.load_pending_block",
+            start.elapsed()
+        );
         Ok(Some(result))
     }
 
@@ -808,7 +888,11 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         let start = Instant::now();
         let result = self.load_storage_pending_block().await?.is_some();
 
-        metrics::histogram!("sql.chain.block.pending_block_exists", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.chain.block.pending_block_exists",
+            start.elapsed()
+        );
         Ok(result)
     }
 
@@ -855,7 +939,11 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
             .await?;
 
         transaction.commit().await?;
-        metrics::histogram!("sql.chain.block.load_pending_block", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.chain.block.load_pending_block",
+            start.elapsed()
+        );
 
         Ok(())
     }
@@ -870,7 +958,11 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         .await?
         .count;
 
-        metrics::histogram!("sql.chain.block.count_rejected_txs", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.chain.block.count_rejected_txs",
+            start.elapsed()
+        );
         Ok(count)
     }
     /// Returns the number of aggregated operations with the given `action_type` and `is_confirmed` status.
@@ -889,7 +981,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         .await?
         .count;
 
-        metrics::histogram!("sql.chain.block.count_operations", start.elapsed());
+        sql_histogram!(self.0, "sql.chain.block.count_operations", start.elapsed());
         Ok(count)
     }
 
@@ -935,7 +1027,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
 
         transaction.commit().await?;
 
-        metrics::histogram!("sql.chain.block.save_block", start.elapsed());
+        sql_histogram!(self.0, "sql.chain.block.save_block", start.elapsed());
         Ok(())
     }
 
@@ -991,7 +1083,11 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
 
         transaction.commit().await?;
 
-        metrics::histogram!("sql.chain.block.save_incomplete_block", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.chain.block.save_incomplete_block",
+            start.elapsed()
+        );
         Ok(())
     }
 
@@ -1050,7 +1146,11 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         .map(|val| val as u64)
         .unwrap_or_default();
 
-        metrics::histogram!("sql.chain.block.next_expected_serial_id", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.chain.block.next_expected_serial_id",
+            start.elapsed()
+        );
         Ok(next_expected_serial_id)
     }
 
@@ -1080,7 +1180,11 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
             }
         };
 
-        metrics::histogram!("sql.chain.block.incomplete_blocks_range", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.chain.block.incomplete_blocks_range",
+            start.elapsed()
+        );
         Ok(block_numbers)
     }
 
@@ -1098,7 +1202,8 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         .fetch_optional(self.0.conn())
         .await?;
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.block.get_storage_incomplete_block",
             start.elapsed()
         );
@@ -1152,7 +1257,8 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         .await?
         .map(|entry| FeConvert::from_bytes(&entry.root_hash).expect("Unparsable root hash"));
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.block.get_data_to_complete_block",
             start.elapsed()
         );
@@ -1178,7 +1284,11 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         .execute(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql.chain.block.save_block_metadata", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.chain.block.save_block_metadata",
+            start.elapsed()
+        );
         Ok(())
     }
 
@@ -1376,7 +1486,8 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         };
         transaction.commit().await?;
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.block.get_block_transactions_page",
             start.elapsed()
         );
@@ -1407,7 +1518,8 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         .count;
         transaction.commit().await?;
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.block.get_block_transactions_count",
             start.elapsed()
         );
@@ -1461,7 +1573,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         .execute(transaction.conn())
         .await?;
         transaction.commit().await?;
-        metrics::histogram!("sql.chain.block.remove_blocks", start.elapsed());
+        sql_histogram!(self.0, "sql.chain.block.remove_blocks", start.elapsed());
         Ok(())
     }
 
@@ -1472,7 +1584,106 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
             .execute(self.0.conn())
             .await?;
 
-        metrics::histogram!("sql.chain.block.remove_pending_block", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.chain.block.remove_pending_block",
+            start.elapsed()
+        );
+        Ok(())
+    }
+
+    /// Reverts the blocks with number greater than `last_block` along with the pending block.
+    ///
+    /// Executed transactions of the reverted blocks are returned to the mempool, the state updates,
+    /// the aggregated operations with their Ethereum transactions and the proving data are removed,
+    /// so the state keeper and the Ethereum sender restored from the database continue from `last_block`.
+    /// The blocks must be reverted on the contract beforehand.
+    pub async fn revert_blocks(&mut self, last_block: BlockNumber) -> QueryResult<()> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        transaction
+            .chain()
+            .mempool_schema()
+            .return_executed_txs_to_mempool(last_block)
+            .await?;
+        transaction
+            .chain()
+            .state_schema()
+            .clear_current_nonce_table(last_block)
+            .await?;
+        transaction
+            .chain()
+            .block_schema()
+            .remove_blocks(last_block)
+            .await?;
+        transaction
+            .chain()
+            .block_schema()
+            .remove_pending_block()
+            .await?;
+        transaction
+            .chain()
+            .tree_cache_schema_bincode()
+            .remove_new_account_tree_cache(last_block)
+            .await?;
+
+        transaction
+            .chain()
+            .state_schema()
+            .remove_account_balance_updates(last_block)
+            .await?;
+        transaction
+            .chain()
+            .state_schema()
+            .remove_account_creates(last_block)
+            .await?;
+        transaction
+            .chain()
+            .state_schema()
+            .remove_account_pubkey_updates(last_block)
+            .await?;
+        transaction
+            .chain()
+            .state_schema()
+            .remove_mint_nft_updates(last_block)
+            .await?;
+
+        transaction
+            .chain()
+            .operations_schema()
+            .remove_eth_unprocessed_aggregated_ops()
+            .await?;
+        transaction
+            .chain()
+            .operations_schema()
+            .remove_aggregate_operations_and_bindings(last_block)
+            .await?;
+
+        transaction
+            .prover_schema()
+            .remove_witnesses(last_block)
+            .await?;
+        transaction
+            .prover_schema()
+            .remove_proofs(last_block)
+            .await?;
+        transaction
+            .prover_schema()
+            .remove_aggregated_proofs(last_block)
+            .await?;
+        transaction
+            .prover_schema()
+            .remove_prover_jobs(last_block)
+            .await?;
+
+        transaction
+            .ethereum_schema()
+            .update_eth_parameters(last_block)
+            .await?;
+
+        transaction.commit().await?;
+        sql_histogram!(self.0, "sql.chain.block.revert_blocks", start.elapsed());
         Ok(())
     }
 
@@ -1518,7 +1729,8 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         .await?;
         transaction.commit().await?;
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.block.store_factories_for_block_withdraw_nfts",
             start.elapsed()
         );
@@ -1535,7 +1747,11 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
             .await?;
         let block_number = record.map(|r| BlockNumber(r.number as u32));
 
-        metrics::histogram!("sql.chain.block.get_block_number_by_hash", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.chain.block.get_block_number_by_hash",
+            start.elapsed()
+        );
         Ok(block_number)
     }
 
@@ -1569,7 +1785,8 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         .await?;
         let hashes = records.into_iter().map(|record| record.tx_hash).collect();
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.block.get_block_transactions_hashes",
             start.elapsed()
         );
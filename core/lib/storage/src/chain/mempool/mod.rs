@@ -2,6 +2,8 @@
 use std::{collections::VecDeque, convert::TryFrom, str::FromStr, time::Instant};
 // External imports
 use itertools::Itertools;
+use num::{rational::Ratio, BigUint};
+use sqlx::types::BigDecimal;
 // Workspace imports
 use zksync_api_types::v02::pagination::PaginationDirection;
 use zksync_api_types::v02::transaction::{
@@ -11,12 +13,13 @@ use zksync_types::{
     block::IncompleteBlock,
     mempool::SignedTxVariant,
     tx::{TxEthSignature, TxHash},
-    AccountId, Address, BlockNumber, ExecutedOperations, ExecutedPriorityOp, ExecutedTx,
+    AccountId, Address, BlockNumber, ExecutedOperations, ExecutedPriorityOp, ExecutedTx, Nonce,
     PriorityOp, SerialId, SignedZkSyncTx, ZkSyncPriorityOp, H256,
 };
 // Local imports
-use self::records::{MempoolPriorityOp, MempoolTx, QueuedBatchTx, RevertedBlock};
-use crate::{QueryResult, StorageProcessor};
+use self::records::{MempoolPriorityOp, MempoolTx, QueuedBatchTx, QueuedTx, RevertedBlock};
+use crate::{chain::operations::OperationsSchema, QueryResult, StorageProcessor};
+use zksync_utils::ratio_to_big_decimal;
 
 use crate::chain::operations::records::{
     StoredExecutedPriorityOperation, StoredExecutedTransaction,
@@ -24,6 +27,9 @@ use crate::chain::operations::records::{
 
 pub mod records;
 
+/// Precision of the stored fee priority, which is the fee in USD paid per chunk.
+const FEE_PRIORITY_PRECISION: usize = 18;
+
 /// Schema for persisting transactions awaiting for the execution.
 ///
 /// This schema holds the transactions that are received by the `mempool` module, but not yet have
@@ -113,7 +119,7 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
             }
         }
 
-        metrics::histogram!("sql.chain.mempool.load_txs", start.elapsed());
+        sql_histogram!(self.0, "sql.chain.mempool.load_txs", start.elapsed());
         Ok(txs.into())
     }
 
@@ -133,7 +139,11 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
         .execute(transaction.conn())
         .await?;
         transaction.commit().await?;
-        metrics::histogram!("sql.chain.mempool.remove_reverted_block", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.chain.mempool.remove_reverted_block",
+            start.elapsed()
+        );
         Ok(())
     }
 
@@ -165,14 +175,18 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
                 .eth_sign_data
                 .as_ref()
                 .map(|sd| serde_json::to_value(sd).expect("failed to encode EthSignData"));
+            let account_id = first_tx_data.account_id().ok().map(|id| *id as i64);
+            let nonce = *first_tx_data.nonce() as i64;
 
             sqlx::query!(
-                "INSERT INTO mempool_txs (tx_hash, tx, created_at, eth_sign_data)
-                VALUES ($1, $2, $3, $4)",
+                "INSERT INTO mempool_txs (tx_hash, tx, created_at, eth_sign_data, account_id, nonce)
+                VALUES ($1, $2, $3, $4, $5, $6)",
                 tx_hash,
                 tx,
                 first_tx_data.created_at,
                 eth_sign_data,
+                account_id,
+                nonce,
             )
             .execute(transaction.conn())
             .await?;
@@ -199,15 +213,19 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
                 .eth_sign_data
                 .as_ref()
                 .map(|sd| serde_json::to_value(sd).expect("failed to encode EthSignData"));
+            let account_id = tx_data.account_id().ok().map(|id| *id as i64);
+            let nonce = *tx_data.nonce() as i64;
 
             sqlx::query!(
-                "INSERT INTO mempool_txs (tx_hash, tx, created_at, eth_sign_data, batch_id)
-                VALUES ($1, $2, $3, $4, $5)",
+                "INSERT INTO mempool_txs (tx_hash, tx, created_at, eth_sign_data, batch_id, account_id, nonce)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)",
                 tx_hash,
                 tx,
                 tx_data.created_at,
                 eth_sign_data,
-                batch_id
+                batch_id,
+                account_id,
+                nonce,
             )
             .execute(transaction.conn())
             .await?;
@@ -236,7 +254,7 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
 
         transaction.commit().await?;
 
-        metrics::histogram!("sql.chain.mempool.insert_batch", start.elapsed());
+        sql_histogram!(self.0, "sql.chain.mempool.insert_batch", start.elapsed());
         Ok(batch_id)
     }
 
@@ -251,23 +269,191 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
             .eth_sign_data
             .as_ref()
             .map(|sd| serde_json::to_value(sd).expect("failed to encode EthSignData"));
+        let account_id = tx_data.account_id().ok().map(|id| *id as i64);
+        let nonce = *tx_data.nonce() as i64;
 
         sqlx::query!(
-            "INSERT INTO mempool_txs (tx_hash, tx, created_at, eth_sign_data, batch_id)
-            VALUES ($1, $2, $3, $4, $5)",
+            "INSERT INTO mempool_txs (tx_hash, tx, created_at, eth_sign_data, batch_id, account_id, nonce)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)",
             tx_hash,
             tx,
             tx_data.created_at,
             eth_sign_data,
             batch_id,
+            account_id,
+            nonce,
         )
         .execute(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql.chain.mempool.insert_tx", start.elapsed());
+        sql_histogram!(self.0, "sql.chain.mempool.insert_tx", start.elapsed());
         Ok(())
     }
 
+    /// Returns the transaction from the given account with the given nonce that awaits
+    /// for the execution in the mempool, if any.
+    /// Transactions returned to the mempool after the block revert are not taken into account.
+    pub async fn get_queued_tx_by_nonce(
+        &mut self,
+        account_id: AccountId,
+        nonce: Nonce,
+    ) -> QueryResult<Option<QueuedTx>> {
+        let start = Instant::now();
+
+        let mempool_tx = sqlx::query_as!(
+            MempoolTx,
+            "SELECT * FROM mempool_txs
+            WHERE account_id = $1 AND nonce = $2 AND reverted = false
+            ORDER BY id DESC
+            LIMIT 1",
+            i64::from(*account_id),
+            i64::from(*nonce),
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        let queued_tx = mempool_tx
+            .map(|mempool_tx| {
+                let batch_id = match mempool_tx.batch_id {
+                    0 => None,
+                    batch_id => Some(batch_id),
+                };
+                SignedZkSyncTx::try_from(mempool_tx).map(|tx| QueuedTx { tx, batch_id })
+            })
+            .transpose()?;
+
+        sql_histogram!(self.0, "sql.chain", start.elapsed(), "mempool" => "get_queued_tx_by_nonce");
+        Ok(queued_tx)
+    }
+
+    /// Returns the transactions of the given account that await for the execution in the mempool
+    /// and can't be included into a block before the given timestamp because of their `valid_from` field.
+    /// Transactions are ordered by their nonce.
+    pub async fn get_scheduled_txs(
+        &mut self,
+        account_id: AccountId,
+        timestamp: u64,
+    ) -> QueryResult<Vec<SignedZkSyncTx>> {
+        let start = Instant::now();
+
+        let mempool_txs = sqlx::query_as!(
+            MempoolTx,
+            "SELECT * FROM mempool_txs
+            WHERE account_id = $1 AND reverted = false
+            ORDER BY nonce, id",
+            i64::from(*account_id),
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        let mut scheduled_txs = Vec::new();
+        for mempool_tx in mempool_txs {
+            let tx = SignedZkSyncTx::try_from(mempool_tx)?;
+            if tx.tx.valid_from() > timestamp {
+                scheduled_txs.push(tx);
+            }
+        }
+
+        sql_histogram!(self.0, "sql.chain", start.elapsed(), "mempool" => "get_scheduled_txs");
+        Ok(scheduled_txs)
+    }
+
+    /// Replaces the single (not belonging to any batch) queued transaction with the new one.
+    /// Returns `false` if the queued transaction was not found, e.g. because it was already
+    /// included into a block and removed from the mempool.
+    pub async fn replace_tx(
+        &mut self,
+        queued_tx_hash: TxHash,
+        new_tx: &SignedZkSyncTx,
+    ) -> QueryResult<bool> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        let queued_tx_hash = hex::encode(queued_tx_hash.as_ref());
+        let removed = sqlx::query!(
+            "DELETE FROM mempool_txs
+            WHERE tx_hash = $1 AND batch_id = 0 AND reverted = false AND proposed = false",
+            &queued_tx_hash
+        )
+        .execute(transaction.conn())
+        .await?
+        .rows_affected();
+
+        let replaced = removed > 0;
+        if replaced {
+            transaction
+                .chain()
+                .mempool_schema()
+                .insert_tx(new_tx)
+                .await?;
+        }
+        transaction.commit().await?;
+
+        sql_histogram!(self.0, "sql.chain", start.elapsed(), "mempool" => "replace_tx");
+        Ok(replaced)
+    }
+
+    /// Removes the queued transaction cancelled by its owner. Transactions from batches, the ones
+    /// returned to the mempool after the block revert and the ones already proposed to the state keeper
+    /// are never removed.
+    /// Returns `false` if there is no such transaction in the mempool.
+    pub async fn cancel_queued_tx(&mut self, tx_hash: TxHash) -> QueryResult<bool> {
+        let start = Instant::now();
+        let tx_hash = hex::encode(tx_hash.as_ref());
+
+        let removed = sqlx::query!(
+            "DELETE FROM mempool_txs
+            WHERE tx_hash = $1 AND batch_id = 0 AND reverted = false AND proposed = false",
+            &tx_hash
+        )
+        .execute(self.0.conn())
+        .await?
+        .rows_affected();
+
+        sql_histogram!(self.0, "sql.chain", start.elapsed(), "mempool" => "cancel_queued_tx");
+        Ok(removed > 0)
+    }
+
+    /// Marks the transactions as proposed to the state keeper, so they can't be cancelled or replaced anymore.
+    /// Returns the hashes of the marked transactions, the ones missing in the mempool (e.g. cancelled
+    /// concurrently) must not be executed.
+    pub async fn mark_txs_proposed(&mut self, txs: &[TxHash]) -> QueryResult<Vec<TxHash>> {
+        let start = Instant::now();
+        let tx_hashes: Vec<_> = txs.iter().map(hex::encode).collect();
+
+        let marked = sqlx::query!(
+            "UPDATE mempool_txs SET proposed = true
+            WHERE tx_hash = ANY($1)
+            RETURNING tx_hash",
+            &tx_hashes
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|row| TxHash::from_str(&format!("0x{}", row.tx_hash)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+        sql_histogram!(self.0, "sql.chain", start.elapsed(), "mempool" => "mark_txs_proposed");
+        Ok(marked)
+    }
+
+    /// Returns the transactions proposed to the state keeper before the server restart, but never executed,
+    /// back to the queue, so their owners can cancel or replace them again. Must be called after the executed
+    /// transactions are removed by `collect_garbage`.
+    /// Returns the number of the returned transactions.
+    pub async fn restore_proposed_txs(&mut self) -> QueryResult<u64> {
+        let start = Instant::now();
+
+        let restored =
+            sqlx::query!("UPDATE mempool_txs SET proposed = false WHERE proposed = true")
+                .execute(self.0.conn())
+                .await?
+                .rows_affected();
+
+        sql_histogram!(self.0, "sql.chain", start.elapsed(), "mempool" => "restore_proposed_txs");
+        Ok(restored)
+    }
+
     pub async fn remove_tx(&mut self, tx: &[u8]) -> QueryResult<()> {
         let start = Instant::now();
         let tx_hash = hex::encode(tx);
@@ -280,7 +466,7 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
         .execute(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql.chain.mempool.remove_tx", start.elapsed());
+        sql_histogram!(self.0, "sql.chain.mempool.remove_tx", start.elapsed());
         Ok(())
     }
 
@@ -296,10 +482,95 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
         .execute(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql.chain.mempool.remove_txs", start.elapsed());
+        sql_histogram!(self.0, "sql.chain.mempool.remove_txs", start.elapsed());
+        Ok(())
+    }
+
+    /// Stores the fee paid per chunk by the single queued transaction, so the cheapest transactions
+    /// can be found by `load_eviction_candidates` without pricing the whole mempool.
+    pub async fn set_tx_fee_priority(
+        &mut self,
+        tx_hash: TxHash,
+        fee_priority: &Ratio<BigUint>,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        let tx_hash = hex::encode(tx_hash.as_ref());
+        let fee_priority = ratio_to_big_decimal(fee_priority, FEE_PRIORITY_PRECISION);
+
+        sqlx::query!(
+            "UPDATE mempool_txs SET fee_priority = $2
+            WHERE tx_hash = $1 AND batch_id = 0",
+            &tx_hash,
+            fee_priority
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        sql_histogram!(self.0, "sql.chain", start.elapsed(), "mempool" => "set_tx_fee_priority");
         Ok(())
     }
 
+    /// Returns up to `limit` single queued transactions paying less than `fee_priority` per chunk,
+    /// the cheapest ones first.
+    ///
+    /// Only the transactions with the highest nonce of their account are returned, otherwise the remaining
+    /// transactions of the account would never be executed because of the nonce gap. Transactions proposed
+    /// to the state keeper and the ones returned to the mempool after the block revert are never returned.
+    pub async fn load_eviction_candidates(
+        &mut self,
+        fee_priority: &Ratio<BigUint>,
+        limit: usize,
+    ) -> QueryResult<Vec<TxHash>> {
+        let start = Instant::now();
+        let fee_priority = ratio_to_big_decimal(fee_priority, FEE_PRIORITY_PRECISION);
+
+        let candidates = sqlx::query!(
+            "SELECT tx_hash FROM mempool_txs AS queued
+            WHERE fee_priority < $1 AND batch_id = 0 AND reverted = false AND proposed = false
+                AND NOT EXISTS (
+                    SELECT 1 FROM mempool_txs AS next
+                    WHERE next.account_id = queued.account_id AND next.nonce > queued.nonce
+                )
+            ORDER BY fee_priority ASC, id DESC
+            LIMIT $2",
+            fee_priority,
+            limit as i64
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|row| TxHash::from_str(&format!("0x{}", row.tx_hash)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+        sql_histogram!(self.0, "sql.chain", start.elapsed(), "mempool" => "load_eviction_candidates");
+        Ok(candidates)
+    }
+
+    /// Removes the queued transaction. If the transaction belongs to a batch, the whole batch is removed,
+    /// since the batch transactions can only be executed together.
+    /// Returns the amount of the removed transactions.
+    pub async fn remove_queued_tx(&mut self, tx_hash: TxHash) -> QueryResult<u64> {
+        let start = Instant::now();
+        let tx_hash = hex::encode(tx_hash.as_ref());
+
+        let result = sqlx::query!(
+            "DELETE FROM mempool_txs
+            WHERE tx_hash = $1 OR batch_id = (
+                SELECT batch_id FROM mempool_txs WHERE tx_hash = $1 AND batch_id <> 0
+            )",
+            &tx_hash
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        sql_histogram!(
+            self.0,
+            "sql.chain.mempool.remove_queued_tx",
+            start.elapsed()
+        );
+        Ok(result.rows_affected())
+    }
+
     /// Checks if the memory pool contains transaction with the given hash.
     pub async fn contains_tx(&mut self, tx_hash: TxHash) -> QueryResult<bool> {
         let start = Instant::now();
@@ -317,7 +588,7 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
 
         let contains = row.filter(|&counter| counter > 0).is_some();
 
-        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "contains_tx");
+        sql_histogram!(self.0, "sql.chain", start.elapsed(), "mempool" => "contains_tx");
         Ok(contains)
     }
 
@@ -327,7 +598,7 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
 
         let mempool_tx = self.get_mempool_tx(tx_hash).await?;
 
-        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "get_tx");
+        sql_histogram!(self.0, "sql.chain", start.elapsed(), "mempool" => "get_tx");
         mempool_tx
             .map(SignedZkSyncTx::try_from)
             .transpose()
@@ -349,7 +620,7 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
         .fetch_optional(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "get_tx");
+        sql_histogram!(self.0, "sql.chain", start.elapsed(), "mempool" => "get_tx");
         Ok(mempool_tx)
     }
 
@@ -399,7 +670,7 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
 
         self.remove_txs(&tx_hashes_to_remove).await?;
 
-        metrics::histogram!("sql.chain.mempool.collect_garbage", start.elapsed());
+        sql_histogram!(self.0, "sql.chain.mempool.collect_garbage", start.elapsed());
         Ok(())
     }
 
@@ -463,7 +734,7 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
             .await?;
         }
         transaction.commit().await?;
-        metrics::histogram!("sql.chain", start.elapsed(), "schema" => "mempool", "method" => "insert_priority_ops");
+        sql_histogram!(self.0, "sql.chain", start.elapsed(), "schema" => "mempool", "method" => "insert_priority_ops");
         Ok(())
     }
 
@@ -477,6 +748,21 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
         Ok(ops.into_iter().map(|op| op.into()).collect())
     }
 
+    /// Returns the earliest deadline block of the priority operations waiting
+    /// to be executed, `None` if there are no such operations.
+    pub async fn get_earliest_priority_op_deadline(&mut self) -> QueryResult<Option<u64>> {
+        let start = Instant::now();
+        let deadline_block = sqlx::query!(
+            "SELECT MIN(deadline_block) as deadline_block FROM mempool_priority_operations WHERE confirmed AND reverted = false"
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .deadline_block;
+
+        sql_histogram!(self.0, "sql.chain", start.elapsed(), "schema" => "mempool", "method" => "get_earliest_priority_op_deadline");
+        Ok(deadline_block.map(|block| block as u64))
+    }
+
     pub async fn remove_priority_op_from_mempool(&mut self, id: i64) -> QueryResult<()> {
         sqlx::query!(
             "DELETE FROM mempool_priority_operations WHERE serial_id=$1",
@@ -584,7 +870,32 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
             .await?
             .count;
 
-        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "get_mempool_size");
+        sql_histogram!(self.0, "sql.chain", start.elapsed(), "mempool" => "get_mempool_size");
+        Ok(size.unwrap_or(0) as u32)
+    }
+
+    /// Returns the amount of the transactions in the mempool that can be included into a block
+    /// at the given timestamp, i.e. not reverted and already valid according to their `valid_from` field.
+    pub async fn get_executable_mempool_size(&mut self, timestamp: u64) -> QueryResult<u32> {
+        let start = Instant::now();
+
+        // `Swap` is valid once both of its orders are.
+        let size = sqlx::query!(
+            r#"
+            SELECT COUNT(*) FROM mempool_txs
+            WHERE reverted = false AND GREATEST(
+                COALESCE((tx->>'validFrom')::numeric, 0),
+                COALESCE((tx->'orders'->0->>'validFrom')::numeric, 0),
+                COALESCE((tx->'orders'->1->>'validFrom')::numeric, 0)
+            ) <= $1
+            "#,
+            BigDecimal::from(timestamp),
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .count;
+
+        sql_histogram!(self.0, "sql.chain", start.elapsed(), "mempool" => "get_executable_mempool_size");
         Ok(size.unwrap_or(0) as u32)
     }
 
@@ -630,7 +941,7 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
             None
         };
 
-        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "get_queued_batch_info");
+        sql_histogram!(self.0, "sql.chain", start.elapsed(), "mempool" => "get_queued_batch_info");
         Ok(result)
     }
 
@@ -920,14 +1231,6 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
             )
             .execute(transaction.conn())
             .await?;
-
-            sqlx::query!(
-                "DELETE FROM tx_filters
-                WHERE tx_hash = $1",
-                &tx_hash_bytes
-            )
-            .execute(transaction.conn())
-            .await?;
         }
 
         for (op, block_number) in reverted_operations {
@@ -994,16 +1297,11 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
             )
             .execute(transaction.conn())
             .await?;
-
-            sqlx::query!(
-                "DELETE FROM tx_filters
-                WHERE tx_hash = $1",
-                &tx_hash_bytes
-            )
-            .execute(transaction.conn())
-            .await?;
         }
 
+        OperationsSchema(&mut transaction)
+            .remove_tx_filters_after_block(last_block_number)
+            .await?;
         sqlx::query!(
             r"DELETE FROM executed_priority_operations 
             WHERE block_number > $1",
@@ -1020,7 +1318,8 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
         .await?;
         transaction.commit().await?;
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.mempool.return_executed_txs_to_mempool",
             start.elapsed()
         );
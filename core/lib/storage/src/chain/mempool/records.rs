@@ -3,7 +3,7 @@ use std::convert::TryFrom;
 
 // External imports
 use chrono::{DateTime, Utc};
-use sqlx::FromRow;
+use sqlx::{types::BigDecimal, FromRow};
 
 // Workspace imports
 use zksync_types::{PriorityOp, SignedZkSyncTx, H256};
@@ -33,6 +33,14 @@ pub(crate) struct MempoolTx {
     pub next_priority_op_serial_id: Option<i64>,
     #[allow(dead_code)]
     pub reverted: bool,
+    #[allow(dead_code)]
+    pub account_id: Option<i64>,
+    #[allow(dead_code)]
+    pub nonce: Option<i64>,
+    #[allow(dead_code)]
+    pub proposed: bool,
+    #[allow(dead_code)]
+    pub fee_priority: Option<BigDecimal>,
 }
 
 impl TryFrom<MempoolTx> for SignedZkSyncTx {
@@ -50,6 +58,14 @@ impl TryFrom<MempoolTx> for SignedZkSyncTx {
     }
 }
 
+/// Transaction awaiting for the execution in the mempool.
+#[derive(Debug, Clone)]
+pub struct QueuedTx {
+    pub tx: SignedZkSyncTx,
+    /// ID of the batch the transaction belongs to, `None` for single transactions.
+    pub batch_id: Option<i64>,
+}
+
 #[derive(Debug, FromRow, PartialEq)]
 pub(crate) struct QueuedBatchTx {
     pub tx_hash: String,
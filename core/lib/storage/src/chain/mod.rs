@@ -3,6 +3,7 @@ pub mod block;
 pub mod mempool;
 pub mod operations;
 pub mod operations_ext;
+pub mod pruning;
 pub mod state;
 pub mod stats;
 pub mod tree_cache;
@@ -31,6 +32,10 @@ impl<'a, 'c> ChainIntermediator<'a, 'c> {
         operations_ext::OperationsExtSchema(self.0)
     }
 
+    pub fn pruning_schema(self) -> pruning::PruningSchema<'a, 'c> {
+        pruning::PruningSchema(self.0)
+    }
+
     pub fn state_schema(self) -> state::StateSchema<'a, 'c> {
         state::StateSchema(self.0)
     }
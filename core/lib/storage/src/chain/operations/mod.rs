@@ -44,7 +44,8 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         .max
         .unwrap_or(0);
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.operations.get_last_block_by_aggregated_action",
             start.elapsed()
         );
@@ -69,7 +70,8 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         .ok()
         .flatten();
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.operations.get_stored_aggregated_operations",
             start.elapsed()
         );
@@ -89,7 +91,8 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         )
         .fetch_optional(self.0.conn())
         .await?;
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.operations.get_executed_operation",
             start.elapsed()
         );
@@ -110,7 +113,8 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         .fetch_optional(self.0.conn())
         .await?;
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.operations.get_executed_priority_operation",
             start.elapsed()
         );
@@ -131,7 +135,8 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         .fetch_optional(self.0.conn())
         .await?;
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.operations.get_executed_priority_operation_by_eth_hash",
             start.elapsed()
         );
@@ -156,7 +161,8 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         )
         .execute(self.0.conn())
         .await?;
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.operations.confirm_aggregated_operations",
             start.elapsed()
         );
@@ -260,12 +266,118 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
             .await?;
         }
         transaction.commit().await?;
-        metrics::histogram!("sql.chain.operations.store_executed_tx", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.chain.operations.store_executed_tx",
+            start.elapsed()
+        );
         // It's almost impossible situation, but it could be triggered in tests
         let tx_duration = (Utc::now() - operation.created_at)
             .to_std()
             .unwrap_or_default();
-        metrics::histogram!("process_tx", tx_duration, "stage" => "execute");
+        sql_histogram!(self.0, "process_tx", tx_duration, "stage" => "execute");
+        Ok(())
+    }
+
+    /// Creates the `tx_filters` partitions for the current and the next range of the sequence numbers
+    /// if they don't exist yet.
+    pub async fn create_tx_filters_partitions(&mut self) -> QueryResult<()> {
+        let start = Instant::now();
+        let created = sqlx::query!(r#"SELECT create_tx_filters_partitions() AS "created!""#)
+            .fetch_one(self.0.conn())
+            .await?
+            .created;
+        if created {
+            vlog::info!("Created the new partition of the transactions history");
+        }
+
+        sql_histogram!(
+            self.0,
+            "sql.chain.operations.create_tx_filters_partitions",
+            start.elapsed()
+        );
+        Ok(())
+    }
+
+    /// Detaches the `tx_filters` partitions holding only the history preceding the given sequence number,
+    /// so they can be archived and dropped. Returns the names of the detached tables.
+    pub async fn detach_tx_filters_partitions(
+        &mut self,
+        before_sequence_number: i64,
+    ) -> QueryResult<Vec<String>> {
+        let start = Instant::now();
+        let partitions = sqlx::query!(
+            r#"SELECT detach_tx_filters_partitions($1) AS "partition!""#,
+            before_sequence_number
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|record| record.partition)
+        .collect();
+
+        sql_histogram!(
+            self.0,
+            "sql.chain.operations.detach_tx_filters_partitions",
+            start.elapsed()
+        );
+        Ok(partitions)
+    }
+
+    /// Removes the history of the operations with the given sequence numbers.
+    /// Rows are looked up by the partition key, so only the partitions holding the operations are scanned.
+    pub(crate) async fn remove_tx_filters(&mut self, sequence_numbers: &[i64]) -> QueryResult<()> {
+        let start = Instant::now();
+        let (min, max) = match (sequence_numbers.iter().min(), sequence_numbers.iter().max()) {
+            (Some(min), Some(max)) => (*min, *max),
+            _ => return Ok(()),
+        };
+        // Unlike the array, the range lets the partitions be pruned by the generic plan of the statement.
+        sqlx::query!(
+            "DELETE FROM tx_filters WHERE sequence_number BETWEEN $1 AND $2 AND sequence_number = ANY($3)",
+            min,
+            max,
+            sequence_numbers
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        sql_histogram!(
+            self.0,
+            "sql.chain.operations.remove_tx_filters",
+            start.elapsed()
+        );
+        Ok(())
+    }
+
+    /// Removes the history of the operations executed in the blocks with number greater than `last_block`.
+    pub(crate) async fn remove_tx_filters_after_block(
+        &mut self,
+        last_block: BlockNumber,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        let sequence_numbers: Vec<i64> = sqlx::query!(
+            r#"
+            SELECT sequence_number AS "sequence_number!" FROM executed_transactions
+            WHERE block_number > $1 AND sequence_number IS NOT NULL
+            UNION ALL
+            SELECT sequence_number AS "sequence_number!" FROM executed_priority_operations
+            WHERE block_number > $1 AND sequence_number IS NOT NULL
+            "#,
+            *last_block as i64
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|record| record.sequence_number)
+        .collect();
+        self.remove_tx_filters(&sequence_numbers).await?;
+
+        sql_histogram!(
+            self.0,
+            "sql.chain.operations.remove_tx_filters_after_block",
+            start.elapsed()
+        );
         Ok(())
     }
 
@@ -275,18 +387,21 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
 
         let mut transaction = self.0.start_transaction().await?;
         let offset = Utc::now() - max_age;
-        let tx_hashes: Vec<Vec<u8>> = sqlx::query!(
+        let records = sqlx::query!(
             r#"
-            SELECT tx_hash FROM executed_transactions 
+            SELECT tx_hash, sequence_number FROM executed_transactions 
             WHERE success = false AND created_at < $1 LIMIT 1000
             "#,
             offset
         )
         .fetch_all(transaction.conn())
-        .await?
-        .into_iter()
-        .map(|value| value.tx_hash)
-        .collect();
+        .await?;
+        // Transactions stored before the sequence numbers were introduced have no history.
+        let sequence_numbers: Vec<i64> = records
+            .iter()
+            .filter_map(|record| record.sequence_number)
+            .collect();
+        let tx_hashes: Vec<Vec<u8>> = records.into_iter().map(|record| record.tx_hash).collect();
 
         sqlx::query!(
             "DELETE FROM executed_transactions WHERE tx_hash = ANY ($1)",
@@ -294,16 +409,14 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         )
         .execute(transaction.conn())
         .await?;
-        sqlx::query!(
-            "DELETE FROM tx_filters WHERE tx_hash = ANY ($1)",
-            &tx_hashes
-        )
-        .execute(transaction.conn())
-        .await?;
+        OperationsSchema(&mut transaction)
+            .remove_tx_filters(&sequence_numbers)
+            .await?;
 
         transaction.commit().await?;
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.operations.remove_rejected_transactions",
             start.elapsed()
         );
@@ -372,7 +485,8 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         }
 
         transaction.commit().await?;
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.operations.store_executed_priority_op",
             start.elapsed()
         );
@@ -390,7 +504,8 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         .await?;
         let max_serial_id = max_serial_id.max.map(|record| record as u64);
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.operations.get_max_priority_op_serial_id",
             start.elapsed()
         );
@@ -437,7 +552,8 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
             None => None,
         };
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.operations.eth_withdraw_tx_for_complete_withdrawal",
             start.elapsed()
         );
@@ -465,7 +581,8 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
             .aggregated_op_final_hash(block_number)
             .await?;
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.operations.eth_withdraw_tx_for_execute_block",
             start.elapsed()
         );
@@ -492,7 +609,8 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         let eth_tx_hash =
             eth_withdraw_tx_for_execute_block.or(eth_withdraw_tx_for_complete_withdrawal);
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.operations.eth_tx_for_withdrawal",
             start.elapsed()
         );
@@ -582,6 +700,9 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         Ok(block_number)
     }
 
+    /// Loads the aggregated operation of the given type containing the block.
+    /// Arguments of the archived operations are read back from the archive,
+    /// the error is returned if the connection has no access to it.
     pub async fn get_aggregated_op_that_affects_block(
         &mut self,
         aggregated_action: AggregatedActionType,
@@ -595,16 +716,52 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
             i64::from(*block_number)
         )
         .fetch_optional(self.0.conn())
-        .await?
-        .map(|op| {
-            (
-                op.id,
-                serde_json::from_value(op.arguments).expect("unparsable aggregated op"),
-            )
-        });
+        .await?;
+
+        let aggregated_op = match aggregated_op {
+            Some(op) => {
+                let arguments = if op.arguments.is_null() {
+                    self.0
+                        .archive_schema()
+                        .load_archived_operation_arguments(op.id, BlockNumber(op.to_block as u32))
+                        .await?
+                } else {
+                    op.arguments
+                };
+                Some((
+                    op.id,
+                    serde_json::from_value(arguments).expect("unparsable aggregated op"),
+                ))
+            }
+            None => None,
+        };
         Ok(aggregated_op)
     }
 
+    /// Replaces the arguments of the stored aggregated operation, e.g. once the proof
+    /// of the `PublishProofBlocksOnchain` operation is generated again.
+    pub async fn update_aggregated_operation(
+        &mut self,
+        id: i64,
+        operation: &AggregatedOperation,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "UPDATE aggregate_operations SET arguments = $2 WHERE id = $1",
+            id,
+            serde_json::to_value(operation).expect("aggregated op serialize fail")
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        sql_histogram!(
+            self.0,
+            "sql.chain.operations.update_aggregated_operation",
+            start.elapsed()
+        );
+        Ok(())
+    }
+
     // Removes ethereum unprocessed aggregated operations
     pub async fn remove_eth_unprocessed_aggregated_ops(&mut self) -> QueryResult<()> {
         let start = Instant::now();
@@ -612,7 +769,8 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
             .execute(self.0.conn())
             .await?;
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.operations.remove_eth_unprocessed_aggregated_ops",
             start.elapsed()
         );
@@ -635,7 +793,7 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         .fetch_all(transaction.conn())
         .await?;
 
-        let hashes: Vec<Vec<u8>> = records.iter().map(|r| r.tx_hash.clone()).collect();
+        let sequence_numbers: Vec<i64> = records.iter().filter_map(|r| r.sequence_number).collect();
         {
             let ops: Vec<PriorityOp> = records.into_iter().map(|rec| rec.into()).collect();
             MempoolSchema(&mut transaction)
@@ -643,8 +801,8 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
                 .await?;
         }
 
-        sqlx::query!("DELETE FROM tx_filters WHERE tx_hash = ANY($1)", &hashes)
-            .execute(transaction.conn())
+        OperationsSchema(&mut transaction)
+            .remove_tx_filters(&sequence_numbers)
             .await?;
 
         sqlx::query!(
@@ -655,7 +813,8 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         .await?;
 
         transaction.commit().await?;
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.operations.remove_executed_priority_operations",
             start.elapsed()
         );
@@ -676,7 +835,8 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         .execute(transaction.conn())
         .await?;
 
-        metrics::histogram!(
+        sql_histogram!(
+            transaction,
             "sql.chain.operations.remove_aggregate_operations",
             start.elapsed()
         );
@@ -739,7 +899,8 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         .await?;
         transaction.commit().await?;
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.operations.remove_aggregate_operations_and_bindings",
             start.elapsed()
         );
@@ -7,7 +7,7 @@ use chrono::{DateTime, Utc};
 // Workspace imports
 use zksync_api_types::{
     v02::{
-        pagination::{AccountTxsRequest, PaginationDirection, PaginationQuery},
+        pagination::{AccountTxsRequest, PaginationDirection, PaginationQuery, WithdrawalsRequest},
         transaction::{
             ApiTxBatch, BatchStatus, Receipt, Transaction, TxData, TxHashSerializeWrapper,
             TxInBlockStatus,
@@ -85,7 +85,11 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
             Ok(None)
         };
 
-        metrics::histogram!("sql.chain.operations_ext.tx_receipt", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.chain.operations_ext.tx_receipt",
+            start.elapsed()
+        );
         result
     }
 
@@ -162,7 +166,8 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
         };
 
         transaction.commit().await?;
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.operations_ext.tx_receipt_api_v02",
             start.elapsed()
         );
@@ -242,7 +247,8 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
         };
 
         transaction.commit().await?;
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.operations_ext.tx_data_by_block_and_index_api_v02",
             start.elapsed()
         );
@@ -336,7 +342,11 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
         };
 
         transaction.commit().await?;
-        metrics::histogram!("sql.chain.operations_ext.tx_data_api_v02", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.chain.operations_ext.tx_data_api_v02",
+            start.elapsed()
+        );
         Ok(result)
     }
 
@@ -373,7 +383,8 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
             }),
         };
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.operations_ext.get_priority_op_receipt",
             start.elapsed()
         );
@@ -391,7 +402,11 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
             self.find_priority_op_by_hash(hash).await?
         };
 
-        metrics::histogram!("sql.chain.operations_ext.get_tx_by_hash", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.chain.operations_ext.get_tx_by_hash",
+            start.elapsed()
+        );
         Ok(result)
     }
 
@@ -525,7 +540,11 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
             None
         };
 
-        metrics::histogram!("sql.chain.operations_ext.find_tx_by_hash", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.chain.operations_ext.find_tx_by_hash",
+            start.elapsed()
+        );
         Ok(result)
     }
 
@@ -606,7 +625,8 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
             None
         };
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.operations_ext.find_priority_op_by_hash",
             start.elapsed()
         );
@@ -658,7 +678,8 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
         .fetch_optional(self.0.conn())
         .await?;
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.operations_ext.account_created_on",
             start.elapsed()
         );
@@ -804,7 +825,8 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
         }
 
         transaction.commit().await?;
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.operations_ext.get_account_transactions_history",
             start.elapsed()
         );
@@ -990,7 +1012,8 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
         }
 
         transaction.commit().await?;
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.operations_ext.get_account_transactions_history_from",
             start.elapsed()
         );
@@ -1105,7 +1128,8 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
         };
         transaction.commit().await?;
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.operations_ext.get_account_transactions",
             start.elapsed()
         );
@@ -1121,17 +1145,10 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
         id_from: i64,
         direction: PaginationDirection,
     ) -> QueryResult<Vec<TransactionItem>> {
-        let query_direction = match direction {
-            PaginationDirection::Newer => {
-                "WHERE sequence_number >= $4 
-                ORDER BY sequence_number 
-                LIMIT $5"
-            }
-            PaginationDirection::Older => {
-                "WHERE sequence_number <= $4
-                ORDER BY sequence_number DESC
-                LIMIT $5"
-            }
+        // The bound is applied to the history too, so only the partitions on its side are scanned.
+        let (sequence_number_bound, order) = match direction {
+            PaginationDirection::Newer => ("sequence_number >= $4", ""),
+            PaginationDirection::Older => ("sequence_number <= $4", "DESC"),
         };
 
         let token_query = if token.is_some() {
@@ -1144,10 +1161,10 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
             r#"
                 WITH tx_hashes AS (
                     SELECT DISTINCT tx_hash FROM tx_filters
-                    WHERE address = $1 {} 
+                    WHERE address = $1 AND {bound} {token}
                     INTERSECT
                     SELECT DISTINCT tx_hash FROM tx_filters
-                    WHERE address = $2 {}
+                    WHERE address = $2 AND {bound} {token}
                 )
                 SELECT                     
                     executed_transactions.tx_hash,
@@ -1163,10 +1180,13 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
                     batch_id
                 FROM tx_hashes INNER JOIN executed_transactions 
                     ON tx_hashes.tx_hash = executed_transactions.tx_hash
-                {}
-                
+                WHERE {bound}
+                ORDER BY sequence_number {order}
+                LIMIT $5
             "#,
-            token_query, token_query, query_direction
+            bound = sequence_number_bound,
+            token = token_query,
+            order = order,
         );
 
         Ok(sqlx::query_as(&query)
@@ -1304,7 +1324,8 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
         .await?;
 
         transaction.commit().await?;
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.operations_ext.get_account_last_tx_hash",
             start.elapsed()
         );
@@ -1342,7 +1363,8 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
         .fetch_optional(self.0.conn())
         .await?;
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.operations_ext.get_block_last_tx_hash",
             start.elapsed()
         );
@@ -1395,13 +1417,191 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
             .await?
             .count
         };
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.operations_ext.get_account_transactions_count",
             start.elapsed()
         );
         Ok(count as u32)
     }
 
+    /// Loads the page of the withdrawals (including forced exits and full exits) sending the funds
+    /// to the given L1 address, regardless of the L2 accounts which initiated them.
+    ///
+    /// Returns `None` if the transaction to paginate from is not found.
+    pub async fn get_withdrawals_to_address(
+        &mut self,
+        query: &PaginationQuery<WithdrawalsRequest>,
+    ) -> QueryResult<Option<Vec<Transaction>>> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+        let tx_hash = match query.from.tx_hash.inner {
+            Either::Left(tx_hash) => tx_hash,
+            Either::Right(_) => {
+                if let Some(tx_hash) = transaction
+                    .chain()
+                    .operations_ext_schema()
+                    .get_last_withdrawal_tx_hash(query.from.address)
+                    .await?
+                {
+                    tx_hash
+                } else {
+                    return Ok(Some(Vec::new()));
+                }
+            }
+        };
+        let sequence_number = transaction
+            .chain()
+            .operations_ext_schema()
+            .get_tx_sequence_number(tx_hash)
+            .await?;
+
+        let txs = if let Some(id_from) = sequence_number {
+            let query_direction = match query.direction {
+                PaginationDirection::Newer => {
+                    "WHERE sequence_number >= $2
+                    ORDER BY sequence_number
+                    LIMIT $3"
+                }
+                PaginationDirection::Older => {
+                    "WHERE sequence_number <= $2
+                    ORDER BY sequence_number DESC
+                    LIMIT $3"
+                }
+            };
+            // Filters must match the predicates of the partial indices on the recipient.
+            let raw_txs: Vec<TransactionItem> = sqlx::query_as(&format!(
+                r#"
+                WITH withdrawals AS (
+                    SELECT
+                        sequence_number,
+                        tx_hash,
+                        tx as op,
+                        block_number,
+                        created_at,
+                        success,
+                        fail_reason,
+                        Null::bytea as eth_hash,
+                        Null::bigint as priority_op_serialid,
+                        block_index,
+                        batch_id
+                    FROM executed_transactions
+                    WHERE to_account = $1 AND tx->>'type' IN ('Withdraw', 'WithdrawNFT', 'ForcedExit')
+                    UNION ALL
+                    SELECT
+                        sequence_number,
+                        tx_hash,
+                        operation as op,
+                        block_number,
+                        created_at,
+                        true as success,
+                        Null as fail_reason,
+                        eth_hash,
+                        priority_op_serialid,
+                        block_index,
+                        Null::bigint as batch_id
+                    FROM executed_priority_operations
+                    WHERE to_account = $1 AND operation->>'type' = 'FullExit'
+                )
+                SELECT * FROM withdrawals
+                {}
+                "#,
+                query_direction
+            ))
+            .bind(query.from.address.as_bytes())
+            .bind(id_from)
+            .bind(i64::from(query.limit))
+            .fetch_all(transaction.conn())
+            .await?;
+
+            let last_finalized = transaction
+                .chain()
+                .block_schema()
+                .get_last_verified_confirmed_block()
+                .await?;
+            let txs = raw_txs
+                .into_iter()
+                .map(|tx| {
+                    let is_finalized = tx.block_number as u32 <= *last_finalized;
+                    TransactionItem::transaction_from_item(tx, is_finalized)
+                })
+                .collect();
+            Some(txs)
+        } else {
+            None
+        };
+        transaction.commit().await?;
+
+        sql_histogram!(
+            self.0,
+            "sql.chain.operations_ext.get_withdrawals_to_address",
+            start.elapsed()
+        );
+        Ok(txs)
+    }
+
+    /// Returns the hash of the latest withdrawal sending the funds to the given L1 address.
+    pub async fn get_last_withdrawal_tx_hash(
+        &mut self,
+        address: Address,
+    ) -> QueryResult<Option<TxHash>> {
+        let start = Instant::now();
+        let record = sqlx::query!(
+            r#"
+                WITH withdrawals AS (
+                    SELECT tx_hash, sequence_number
+                    FROM executed_transactions
+                    WHERE to_account = $1 AND tx->>'type' IN ('Withdraw', 'WithdrawNFT', 'ForcedExit')
+                    UNION ALL
+                    SELECT tx_hash, sequence_number
+                    FROM executed_priority_operations
+                    WHERE to_account = $1 AND operation->>'type' = 'FullExit'
+                )
+                SELECT tx_hash as "tx_hash!"
+                FROM withdrawals
+                ORDER BY sequence_number DESC
+                LIMIT 1
+            "#,
+            address.as_bytes()
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        sql_histogram!(
+            self.0,
+            "sql.chain.operations_ext.get_last_withdrawal_tx_hash",
+            start.elapsed()
+        );
+        Ok(record.map(|record| TxHash::from_slice(&record.tx_hash).unwrap()))
+    }
+
+    /// Returns the amount of the withdrawals sending the funds to the given L1 address.
+    pub async fn get_withdrawals_count(&mut self, address: Address) -> QueryResult<u32> {
+        let start = Instant::now();
+        let count = sqlx::query!(
+            r#"
+                SELECT (
+                    SELECT COUNT(*) FROM executed_transactions
+                    WHERE to_account = $1 AND tx->>'type' IN ('Withdraw', 'WithdrawNFT', 'ForcedExit')
+                ) + (
+                    SELECT COUNT(*) FROM executed_priority_operations
+                    WHERE to_account = $1 AND operation->>'type' = 'FullExit'
+                ) as "count!"
+            "#,
+            address.as_bytes()
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .count;
+
+        sql_histogram!(
+            self.0,
+            "sql.chain.operations_ext.get_withdrawals_count",
+            start.elapsed()
+        );
+        Ok(count as u32)
+    }
+
     /// Returns `created_at` for `block_number` fields for transaction with given hash.
     pub async fn get_tx_sequence_number_for_block(
         &mut self,
@@ -1440,7 +1640,11 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
         .flatten();
         transaction.commit().await?;
 
-        metrics::histogram!("sql.chain.block.get_tx_sequence_number", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.chain.block.get_tx_sequence_number",
+            start.elapsed()
+        );
         Ok(result)
     }
     /// Returns `created_at` and `block_number` fields for transaction with given hash.
@@ -1474,7 +1678,11 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
         .flatten();
         transaction.commit().await?;
 
-        metrics::histogram!("sql.chain.block.get_tx_sequence_number", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.chain.block.get_tx_sequence_number",
+            start.elapsed()
+        );
         Ok(result)
     }
 
@@ -1543,7 +1751,11 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
         };
         transaction.commit().await?;
 
-        metrics::histogram!("sql.chain.block.get_in_block_batch_info", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.chain.block.get_in_block_batch_info",
+            start.elapsed()
+        );
         Ok(result)
     }
 
@@ -1567,7 +1779,7 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
         };
         transaction.commit().await?;
 
-        metrics::histogram!("sql.chain.block.get_batch_info", start.elapsed());
+        sql_histogram!(self.0, "sql.chain.block.get_batch_info", start.elapsed());
         Ok(result)
     }
 
@@ -1620,7 +1832,11 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
         .fetch_optional(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql.chain.operations_ext.tx_data_for_web3", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.chain.operations_ext.tx_data_for_web3",
+            start.elapsed()
+        );
         Ok(result)
     }
 
@@ -1683,7 +1899,8 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
             .fetch_optional(self.0.conn())
             .await?;
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.operations_ext.web3_receipt_by_hash",
             start.elapsed()
         );
@@ -1751,7 +1968,11 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
             .fetch_all(self.0.conn())
             .await?;
 
-        metrics::histogram!("sql.chain.operations_ext.web3_receipts", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.chain.operations_ext.web3_receipts",
+            start.elapsed()
+        );
         Ok(receipts)
     }
 
@@ -0,0 +1,349 @@
+// Built-in deps
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::Instant,
+};
+// External imports
+// Workspace imports
+use zksync_types::{Account, AccountId, AccountMap, BlockNumber};
+// Local imports
+use self::records::PrunedRows;
+use crate::{QueryResult, StorageProcessor};
+
+pub mod records;
+
+/// Amount of the accounts with the consecutive ids stored in a single chunk of the state snapshot.
+pub const STATE_SNAPSHOT_CHUNK_SIZE: u32 = 4096;
+
+fn snapshot_chunk_id(account_id: AccountId) -> i64 {
+    i64::from(*account_id / STATE_SNAPSHOT_CHUNK_SIZE)
+}
+
+/// Pruning schema is capable of removing the per-block data of the old executed blocks.
+///
+/// Before the data is removed, the full state of the accounts is stored as a snapshot
+/// (tables `state_snapshots` and `state_snapshot_chunks`), so the state of the snapshot block
+/// can be restored without the removed account diffs. Account creations (table `account_creates`)
+/// and NFT mints are never removed, since they are used for the address and NFT lookups.
+///
+/// The data is only pruned up to a stored snapshot and the preceding snapshots are removed,
+/// so the historical queries (e.g. the account balance at some block) are only correct
+/// for the blocks starting from the earliest stored snapshot.
+#[derive(Debug)]
+pub struct PruningSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> PruningSchema<'a, 'c> {
+    /// Stores the snapshot of the full account state after the given block,
+    /// split into the chunks of `STATE_SNAPSHOT_CHUNK_SIZE` accounts.
+    pub async fn store_state_snapshot(
+        &mut self,
+        block_number: BlockNumber,
+        accounts: &AccountMap,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        let inserted = sqlx::query!(
+            "INSERT INTO state_snapshots (block_number)
+            VALUES ($1)
+            ON CONFLICT (block_number)
+            DO NOTHING",
+            i64::from(*block_number)
+        )
+        .execute(transaction.conn())
+        .await?
+        .rows_affected();
+        // State of the executed block never changes, so the stored snapshot is kept as is.
+        if inserted > 0 {
+            let mut chunks: BTreeMap<i64, HashMap<AccountId, &Account>> = BTreeMap::new();
+            for (account_id, account) in accounts {
+                chunks
+                    .entry(snapshot_chunk_id(*account_id))
+                    .or_default()
+                    .insert(*account_id, account);
+            }
+            for (chunk_id, accounts) in chunks {
+                sqlx::query!(
+                    "INSERT INTO state_snapshot_chunks (block_number, chunk_id, accounts)
+                    VALUES ($1, $2, $3)",
+                    i64::from(*block_number),
+                    chunk_id,
+                    serde_json::to_value(accounts)?
+                )
+                .execute(transaction.conn())
+                .await?;
+            }
+        }
+        transaction.commit().await?;
+
+        sql_histogram!(
+            self.0,
+            "sql.chain.pruning.store_state_snapshot",
+            start.elapsed()
+        );
+        Ok(())
+    }
+
+    /// Returns the block number of the latest stored state snapshot.
+    pub async fn get_last_state_snapshot_block(&mut self) -> QueryResult<Option<BlockNumber>> {
+        let start = Instant::now();
+        let last_block = sqlx::query!("SELECT MAX(block_number) FROM state_snapshots")
+            .fetch_one(self.0.conn())
+            .await?
+            .max
+            .map(|block| BlockNumber(block as u32));
+
+        sql_histogram!(
+            self.0,
+            "sql.chain.pruning.get_last_state_snapshot_block",
+            start.elapsed()
+        );
+        Ok(last_block)
+    }
+
+    /// Returns the block number of the earliest stored state snapshot.
+    /// The data of the blocks up to this one may be pruned.
+    pub async fn get_first_state_snapshot_block(&mut self) -> QueryResult<Option<BlockNumber>> {
+        let start = Instant::now();
+        let first_block = sqlx::query!("SELECT MIN(block_number) FROM state_snapshots")
+            .fetch_one(self.0.conn())
+            .await?
+            .min
+            .map(|block| BlockNumber(block as u32));
+
+        sql_histogram!(
+            self.0,
+            "sql.chain.pruning.get_first_state_snapshot_block",
+            start.elapsed()
+        );
+        Ok(first_block)
+    }
+
+    /// Loads the account state from the latest snapshot stored for the block not greater than `block_number`.
+    /// The account is `None` if it didn't exist at the snapshot block.
+    pub async fn load_account_snapshot(
+        &mut self,
+        account_id: AccountId,
+        block_number: BlockNumber,
+    ) -> QueryResult<Option<(BlockNumber, Option<Account>)>> {
+        let start = Instant::now();
+        let snapshot = sqlx::query!(
+            r#"SELECT state_snapshots.block_number, state_snapshot_chunks.accounts -> $2::text AS "account?"
+            FROM state_snapshots
+            LEFT JOIN state_snapshot_chunks
+                ON state_snapshot_chunks.block_number = state_snapshots.block_number
+                AND state_snapshot_chunks.chunk_id = $3
+            WHERE state_snapshots.block_number <= $1
+            ORDER BY state_snapshots.block_number DESC
+            LIMIT 1"#,
+            i64::from(*block_number),
+            account_id.to_string(),
+            snapshot_chunk_id(account_id)
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        let result = match snapshot {
+            Some(snapshot) => {
+                let account = match snapshot.account {
+                    Some(account) => Some(serde_json::from_value(account)?),
+                    None => None,
+                };
+                Some((BlockNumber(snapshot.block_number as u32), account))
+            }
+            None => None,
+        };
+
+        sql_histogram!(
+            self.0,
+            "sql.chain.pruning.load_account_snapshot",
+            start.elapsed()
+        );
+        Ok(result)
+    }
+
+    /// Loads the latest state snapshot stored for the block not greater than `block_number`.
+    /// If `block_number` is `None`, the latest snapshot is loaded.
+    pub async fn load_state_snapshot(
+        &mut self,
+        block_number: Option<BlockNumber>,
+    ) -> QueryResult<Option<(BlockNumber, AccountMap)>> {
+        let start = Instant::now();
+        let block_number = block_number.map(|block| *block).unwrap_or(u32::MAX);
+        // Chunks are loaded along with the snapshot itself, so the snapshot removed concurrently
+        // is never observed partially.
+        let chunks = sqlx::query!(
+            r#"SELECT state_snapshots.block_number, chunk_id AS "chunk_id?", accounts AS "accounts?"
+            FROM state_snapshots
+            LEFT JOIN state_snapshot_chunks USING (block_number)
+            WHERE state_snapshots.block_number = (
+                SELECT MAX(block_number) FROM state_snapshots WHERE block_number <= $1
+            )
+            ORDER BY chunk_id ASC"#,
+            i64::from(block_number)
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        let mut result = None;
+        for chunk in chunks {
+            let (_, accounts) = result.get_or_insert_with(|| {
+                (
+                    BlockNumber(chunk.block_number as u32),
+                    AccountMap::default(),
+                )
+            });
+            if let Some(chunk_accounts) = chunk.accounts {
+                accounts.extend(serde_json::from_value::<AccountMap>(chunk_accounts)?);
+            }
+        }
+
+        sql_histogram!(
+            self.0,
+            "sql.chain.pruning.load_state_snapshot",
+            start.elapsed()
+        );
+        Ok(result)
+    }
+
+    /// Prunes the data of the executed blocks preceding the last `retention_blocks` ones.
+    ///
+    /// The snapshot of the state after the last executed block is stored once per `snapshot_interval`
+    /// blocks. The data is pruned up to the latest snapshot preceding the retained blocks, and the
+    /// snapshots before it are removed, so the state of any block starting from the earliest stored
+    /// snapshot can be restored.
+    ///
+    /// Returns the last pruned block along with the amount of the removed rows,
+    /// or `None` if there is no snapshot to prune the data up to yet.
+    pub async fn prune_executed_blocks(
+        &mut self,
+        retention_blocks: u32,
+        snapshot_interval: u32,
+    ) -> QueryResult<Option<(BlockNumber, PrunedRows)>> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        let last_executed_block = transaction
+            .chain()
+            .block_schema()
+            .get_last_verified_confirmed_block()
+            .await?;
+        let snapshot_needed = match PruningSchema(&mut transaction)
+            .get_last_state_snapshot_block()
+            .await?
+        {
+            Some(block) => *last_executed_block >= *block + snapshot_interval,
+            None => *last_executed_block > 0,
+        };
+        if snapshot_needed {
+            let (block, accounts) = transaction
+                .chain()
+                .state_schema()
+                .load_verified_state()
+                .await?;
+            PruningSchema(&mut transaction)
+                .store_state_snapshot(block, &accounts)
+                .await?;
+            vlog::info!("Stored the state snapshot for block {}", block);
+        }
+
+        let last_pruned_block = sqlx::query!(
+            "SELECT MAX(block_number) FROM state_snapshots WHERE block_number <= $1",
+            i64::from(last_executed_block.saturating_sub(retention_blocks))
+        )
+        .fetch_one(transaction.conn())
+        .await?
+        .max
+        .map(|block| BlockNumber(block as u32));
+
+        let result = match last_pruned_block {
+            Some(last_pruned_block) if *last_pruned_block > 0 => {
+                let pruned = PruningSchema(&mut transaction)
+                    .prune_block_data(last_pruned_block)
+                    .await?;
+                // Snapshots preceding the pruned blocks can't be used to restore the following states anymore.
+                PruningSchema(&mut transaction)
+                    .remove_old_state_snapshots(last_pruned_block)
+                    .await?;
+                Some((last_pruned_block, pruned))
+            }
+            _ => None,
+        };
+        transaction.commit().await?;
+
+        sql_histogram!(
+            self.0,
+            "sql.chain.pruning.prune_executed_blocks",
+            start.elapsed()
+        );
+        Ok(result)
+    }
+
+    /// Removes the state snapshots stored for the blocks with number less than `first_block`.
+    pub async fn remove_old_state_snapshots(
+        &mut self,
+        first_block: BlockNumber,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "DELETE FROM state_snapshots WHERE block_number < $1",
+            i64::from(*first_block)
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        sql_histogram!(
+            self.0,
+            "sql.chain.pruning.remove_old_state_snapshots",
+            start.elapsed()
+        );
+        Ok(())
+    }
+
+    /// Removes the account balance and public key updates along with the witnesses
+    /// of the blocks with number not greater than `last_block`.
+    ///
+    /// The caller must ensure that the blocks are executed and the state snapshot
+    /// for `last_block` or any following block is stored.
+    pub async fn prune_block_data(&mut self, last_block: BlockNumber) -> QueryResult<PrunedRows> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        let balance_updates = sqlx::query!(
+            "DELETE FROM account_balance_updates WHERE block_number <= $1",
+            i64::from(*last_block)
+        )
+        .execute(transaction.conn())
+        .await?
+        .rows_affected();
+
+        let pubkey_updates = sqlx::query!(
+            "DELETE FROM account_pubkey_updates WHERE block_number <= $1",
+            i64::from(*last_block)
+        )
+        .execute(transaction.conn())
+        .await?
+        .rows_affected();
+
+        let witnesses = sqlx::query!(
+            "DELETE FROM block_witness WHERE block <= $1",
+            i64::from(*last_block)
+        )
+        .execute(transaction.conn())
+        .await?
+        .rows_affected();
+
+        transaction.commit().await?;
+
+        sql_histogram!(
+            self.0,
+            "sql.chain.pruning.prune_block_data",
+            start.elapsed()
+        );
+        Ok(PrunedRows {
+            balance_updates,
+            pubkey_updates,
+            witnesses,
+        })
+    }
+}
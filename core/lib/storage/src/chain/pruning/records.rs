@@ -0,0 +1,11 @@
+// External imports
+// Workspace imports
+// Local imports
+
+/// Amount of the rows removed by the pruning.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PrunedRows {
+    pub balance_updates: u64,
+    pub pubkey_updates: u64,
+    pub witnesses: u64,
+}
@@ -1,6 +1,7 @@
 // Built-in deps
 use std::{cmp, collections::HashMap, time::Instant};
 // External imports
+use anyhow::ensure;
 use num::BigInt;
 use sqlx::types::BigDecimal;
 // Workspace imports
@@ -14,6 +15,7 @@ use zksync_types::{
 use crate::chain::{
     account::{records::*, restore_account},
     block::BlockSchema,
+    pruning::PruningSchema,
 };
 use crate::diff::StorageAccountDiff;
 use crate::utils::address_to_stored_string;
@@ -216,7 +218,11 @@ impl<'a, 'c> StateSchema<'a, 'c> {
 
         transaction.commit().await?;
 
-        metrics::histogram!("sql.chain.state.commit_state_update", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.chain.state.commit_state_update",
+            start.elapsed()
+        );
         Ok(())
     }
 
@@ -436,7 +442,11 @@ impl<'a, 'c> StateSchema<'a, 'c> {
 
         transaction.commit().await?;
 
-        metrics::histogram!("sql.chain.state.apply_state_update", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.chain.state.apply_state_update",
+            start.elapsed()
+        );
         Ok(())
     }
 
@@ -444,6 +454,10 @@ impl<'a, 'c> StateSchema<'a, 'c> {
     /// with a block number to which this state applies.
     /// If the provided block number is `None`, then the latest committed
     /// state will be loaded.
+    ///
+    /// The state of the block preceding the last verified one is restored from the closest
+    /// state snapshot, if there is one, rather than by reverting the diffs of all the blocks
+    /// up to the verified one. The state of the pruned blocks (see `PruningSchema`) can't be loaded.
     pub async fn load_committed_state(
         &mut self,
         block: Option<BlockNumber>,
@@ -451,30 +465,65 @@ impl<'a, 'c> StateSchema<'a, 'c> {
         let start = Instant::now();
         let mut transaction = self.0.start_transaction().await?;
 
-        let (verif_block, mut accounts) =
-            StateSchema(&mut transaction).load_verified_state().await?;
+        let first_snapshot_block = PruningSchema(&mut transaction)
+            .get_first_state_snapshot_block()
+            .await?;
+        if let (Some(block), Some(first_snapshot_block)) = (block, first_snapshot_block) {
+            ensure!(
+                block >= first_snapshot_block,
+                "State of the block {} is pruned, the earliest available state is the one of the block {}",
+                block,
+                first_snapshot_block
+            );
+        }
+
+        let verif_block = BlockSchema(&mut transaction)
+            .get_last_verified_confirmed_block()
+            .await?;
+        let snapshot = match block {
+            Some(block) if block < verif_block => {
+                PruningSchema(&mut transaction)
+                    .load_state_snapshot(Some(block))
+                    .await?
+            }
+            _ => None,
+        };
+        // The state restored from the snapshot is the one of the requested block,
+        // even if the block itself has no diffs.
+        let (from_block, mut accounts, restored_block) = match snapshot {
+            Some((snapshot_block, accounts)) => (snapshot_block, accounts, block),
+            None => {
+                let (verif_block, accounts) =
+                    StateSchema(&mut transaction).load_verified_state().await?;
+                (verif_block, accounts, None)
+            }
+        };
         vlog::debug!(
-            "Verified state block: {}, accounts: {:#?}",
-            *verif_block,
+            "Initial state block: {}, accounts: {:#?}",
+            *from_block,
             accounts
         );
 
         let state_diff = StateSchema(&mut transaction)
-            .load_state_diff(verif_block, block)
+            .load_state_diff(from_block, block)
             .await?;
 
-        // Fetch updates from blocks: verif_block +/- 1, ... , block
+        // Fetch updates from blocks: from_block +/- 1, ... , block
         let result = if let Some((block, state_diff)) = state_diff {
             vlog::debug!("Loaded state diff: {:#?}", state_diff);
             apply_updates(&mut accounts, state_diff);
-            Ok((block, accounts))
+            Ok((restored_block.unwrap_or(block), accounts))
         } else {
-            Ok((verif_block, accounts))
+            Ok((restored_block.unwrap_or(from_block), accounts))
         };
 
         transaction.commit().await?;
 
-        metrics::histogram!("sql.chain.state.load_committed_state", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.chain.state.load_committed_state",
+            start.elapsed()
+        );
         result
     }
 
@@ -524,7 +573,11 @@ impl<'a, 'c> StateSchema<'a, 'c> {
         }
 
         transaction.commit().await?;
-        metrics::histogram!("sql.chain.state.load_verified_state", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.chain.state.load_verified_state",
+            start.elapsed()
+        );
         Ok((last_block, account_map))
     }
 
@@ -665,7 +718,7 @@ impl<'a, 'c> StateSchema<'a, 'c> {
         };
 
         transaction.commit().await?;
-        metrics::histogram!("sql.chain.state.load_state_diff", start.elapsed());
+        sql_histogram!(self.0, "sql.chain.state.load_state_diff", start.elapsed());
 
         // We don't want to return an empty list to avoid the confusion, so return
         // `None` if there are no changes.
@@ -687,7 +740,7 @@ impl<'a, 'c> StateSchema<'a, 'c> {
             .await
             .map(|diff| diff.unwrap_or_default().1);
 
-        metrics::histogram!("sql.chain.state.load_state_diff", start.elapsed());
+        sql_histogram!(self.0, "sql.chain.state.load_state_diff", start.elapsed());
         result
     }
 
@@ -704,7 +757,7 @@ impl<'a, 'c> StateSchema<'a, 'c> {
         .fetch_optional(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql.token.get_mint_nft_update", start.elapsed());
+        sql_histogram!(self.0, "sql.token.get_mint_nft_update", start.elapsed());
         Ok(nft.map(|p| p.into()))
     }
 
@@ -726,7 +779,8 @@ impl<'a, 'c> StateSchema<'a, 'c> {
         .fetch_optional(self.0.conn())
         .await?;
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.state.get_mint_nft_update_by_creator_and_nonce",
             start.elapsed()
         );
@@ -748,7 +802,11 @@ impl<'a, 'c> StateSchema<'a, 'c> {
         .fetch_optional(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql.chain.state.get_nft_id_by_tx_hash", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.chain.state.get_nft_id_by_tx_hash",
+            start.elapsed()
+        );
         Ok(record.map(|r| TokenId(r.token_id as u32)))
     }
 
@@ -785,7 +843,8 @@ impl<'a, 'c> StateSchema<'a, 'c> {
         .execute(self.0.conn())
         .await?;
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.state.remove_account_balance_updates",
             start.elapsed()
         );
@@ -802,7 +861,11 @@ impl<'a, 'c> StateSchema<'a, 'c> {
         .execute(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql.chain.state.remove_account_creates", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.chain.state.remove_account_creates",
+            start.elapsed()
+        );
         Ok(())
     }
 
@@ -816,7 +879,11 @@ impl<'a, 'c> StateSchema<'a, 'c> {
         .execute(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql.chain.state.remove_mint_nft_updates", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.chain.state.remove_mint_nft_updates",
+            start.elapsed()
+        );
         Ok(())
     }
 
@@ -833,7 +900,8 @@ impl<'a, 'c> StateSchema<'a, 'c> {
         .execute(self.0.conn())
         .await?;
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.state.remove_account_pubkey_updates",
             start.elapsed()
         );
@@ -25,7 +25,11 @@ impl<'a, 'c> StatsSchema<'a, 'c> {
         .count
         .unwrap_or(0);
 
-        metrics::histogram!("sql.chain.stats.count_outstanding_proofs", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.chain.stats.count_outstanding_proofs",
+            start.elapsed()
+        );
         Ok(count as u32)
     }
 
@@ -52,7 +56,11 @@ impl<'a, 'c> StatsSchema<'a, 'c> {
         .fetch_one(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql.chain.stats.count_total_transactions", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.chain.stats.count_total_transactions",
+            start.elapsed()
+        );
         Ok((
             (tx_res.count.unwrap_or_default() + prior_ops_res.count.unwrap_or_default()) as u32,
             SequentialTxId(max(
@@ -39,7 +39,8 @@ impl<'a, 'c> TreeCacheSchemaBincode<'a, 'c> {
         .execute(self.0.conn())
         .await?;
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.tree_cache.bincode.store_account_tree_cache",
             start.elapsed()
         );
@@ -60,7 +61,8 @@ impl<'a, 'c> TreeCacheSchemaBincode<'a, 'c> {
         .await?
         .max;
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.tree_cache.bincode.get_last_block_with_account_tree_cache",
             start.elapsed()
         );
@@ -84,7 +86,8 @@ impl<'a, 'c> TreeCacheSchemaBincode<'a, 'c> {
         .fetch_optional(self.0.conn())
         .await?;
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.tree_cache.bincode.get_account_tree_cache",
             start.elapsed()
         );
@@ -115,7 +118,8 @@ impl<'a, 'c> TreeCacheSchemaBincode<'a, 'c> {
         .fetch_optional(self.0.conn())
         .await?;
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.tree_cache.bincode.get_account_tree_cache_block",
             start.elapsed()
         );
@@ -138,7 +142,8 @@ impl<'a, 'c> TreeCacheSchemaBincode<'a, 'c> {
         .execute(self.0.conn())
         .await?;
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.tree_cache.bincode.remove_new_account_tree_cache",
             start.elapsed()
         );
@@ -158,7 +163,8 @@ impl<'a, 'c> TreeCacheSchemaBincode<'a, 'c> {
         .execute(self.0.conn())
         .await?;
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.tree_cache.bincode.remove_old_account_tree_cache",
             start.elapsed()
         );
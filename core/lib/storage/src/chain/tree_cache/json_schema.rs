@@ -39,7 +39,8 @@ impl<'a, 'c> TreeCacheSchemaJSON<'a, 'c> {
         .execute(self.0.conn())
         .await?;
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.tree_cache.json.store_account_tree_cache",
             start.elapsed()
         );
@@ -59,7 +60,8 @@ impl<'a, 'c> TreeCacheSchemaJSON<'a, 'c> {
                 .await?
                 .max;
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.tree_cache.json.get_last_block_with_account_tree_cache",
             start.elapsed()
         );
@@ -85,7 +87,8 @@ impl<'a, 'c> TreeCacheSchemaJSON<'a, 'c> {
         .fetch_optional(self.0.conn())
         .await?;
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.tree_cache.json.get_account_tree_cache",
             start.elapsed()
         );
@@ -125,7 +128,8 @@ impl<'a, 'c> TreeCacheSchemaJSON<'a, 'c> {
         .fetch_optional(self.0.conn())
         .await?;
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.tree_cache.json.get_account_tree_cache_block",
             start.elapsed()
         );
@@ -151,7 +155,8 @@ impl<'a, 'c> TreeCacheSchemaJSON<'a, 'c> {
         .execute(self.0.conn())
         .await?;
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.tree_cache.json.remove_new_account_tree_cache",
             start.elapsed()
         );
@@ -171,7 +176,8 @@ impl<'a, 'c> TreeCacheSchemaJSON<'a, 'c> {
         .execute(self.0.conn())
         .await?;
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.chain.tree_cache.json.remove_old_account_tree_cache",
             start.elapsed()
         );
@@ -25,7 +25,7 @@ impl<'a, 'c> ConfigSchema<'a, 'c> {
             .fetch_one(self.0.conn())
             .await?;
 
-        metrics::histogram!("sql.load_config", start.elapsed());
+        sql_histogram!(self.0, "sql.load_config", start.elapsed());
         Ok(config)
     }
 
@@ -49,7 +49,7 @@ impl<'a, 'c> ConfigSchema<'a, 'c> {
         .execute(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql.store_config", start.elapsed());
+        sql_histogram!(self.0, "sql.store_config", start.elapsed());
         Ok(())
     }
 }
@@ -1,17 +1,23 @@
 // Built-in deps
-use std::{fmt, time::Duration, time::Instant};
+use std::{fmt, sync::Arc, time::Duration, time::Instant};
 // External imports
 use async_trait::async_trait;
-use deadpool::managed::{Manager, PoolConfig, RecycleResult, Timeouts};
+use deadpool::managed::{Manager, PoolConfig, PoolError, RecycleResult, Timeouts};
 use deadpool::Runtime;
-use sqlx::{Connection, Error as SqlxError, PgConnection};
+use sqlx::{
+    postgres::PgConnectOptions, ConnectOptions, Connection, Error as SqlxError, PgConnection,
+};
 use tokio::time;
 // Local imports
 // use self::recoverable_connection::RecoverableConnection;
-use crate::{get_database_replica_url, get_database_url, StorageProcessor};
+use self::replica::PrimaryFallback;
+use crate::{
+    archive::ObjectStore, get_database_replica_url, get_database_url, query_log, StorageProcessor,
+};
 use zksync_utils::parse_env;
 
 pub mod holder;
+mod replica;
 
 type Pool = deadpool::managed::Pool<DbPool>;
 
@@ -21,17 +27,23 @@ pub const DB_CONNECTION_RETRIES: u32 = 3;
 
 #[derive(Clone)]
 pub struct DbPool {
-    url: String,
+    options: PgConnectOptions,
 }
 
 impl DbPool {
-    fn create(url: impl Into<String>, max_size: usize) -> Pool {
+    fn create(url: impl AsRef<str>, max_size: usize) -> Pool {
         let pool_config = PoolConfig {
             max_size,
             timeouts: Timeouts::wait_millis(20_000), // wait 20 seconds before returning error
             runtime: Runtime::Tokio1,
         };
-        Pool::from_config(DbPool { url: url.into() }, pool_config)
+
+        let mut options: PgConnectOptions = url.as_ref().parse().expect("Invalid database URL");
+        options = options.application_name(query_log::component());
+        if let Some(threshold) = query_log::slow_query_threshold() {
+            options.log_slow_statements(log::LevelFilter::Warn, threshold);
+        }
+        Pool::from_config(DbPool { options }, pool_config)
     }
 }
 
@@ -40,7 +52,7 @@ impl Manager for DbPool {
     type Type = PgConnection;
     type Error = SqlxError;
     async fn create(&self) -> Result<PgConnection, SqlxError> {
-        PgConnection::connect(&self.url).await
+        PgConnection::connect_with(&self.options).await
     }
     async fn recycle(&self, obj: &mut PgConnection) -> RecycleResult<SqlxError> {
         Ok(obj.ping().await?)
@@ -52,9 +64,37 @@ impl Manager for DbPool {
 ///
 /// The size of the pool and the database URL are configured via environment
 /// variables `DATABASE_POOL_SIZE` and `DATABASE_URL` respectively.
+///
+/// The pool of the connections to the replica may fall back to the primary
+/// database while the replica lags behind it, see `with_primary_fallback`.
+///
+/// Pool can be considered saturated once too many tasks wait for a connection,
+/// see `with_max_pending_checkouts`. It's up to the caller to reject the new requests then.
+///
+/// Connections of the pool read the archived rows back if the pool has access to the archive,
+/// see `with_archive`.
+///
+/// Slow queries are reported along with the component using the pool, see `with_component`.
 #[derive(Clone)]
 pub struct ConnectionPool {
     pool: Pool,
+    primary: Option<PrimaryFallback>,
+    max_pending_checkouts: Option<usize>,
+    archive: Option<Arc<dyn ObjectStore>>,
+    component: Option<&'static str>,
+}
+
+/// Utilization of the connection pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolStatus {
+    /// Maximum amount of connections in the pool.
+    pub max_size: usize,
+    /// Amount of the currently open connections.
+    pub size: usize,
+    /// Amount of the open connections not used by anyone.
+    pub available: usize,
+    /// Amount of the tasks waiting for a connection.
+    pub pending: usize,
 }
 
 impl fmt::Debug for ConnectionPool {
@@ -73,7 +113,13 @@ impl ConnectionPool {
 
         let pool = DbPool::create(database_url, max_size as usize);
 
-        Self { pool }
+        Self {
+            pool,
+            primary: None,
+            max_pending_checkouts: None,
+            archive: None,
+            component: None,
+        }
     }
 
     /// Establishes a pool of the connections to the replica of database and
@@ -86,8 +132,63 @@ impl ConnectionPool {
 
         let pool = DbPool::create(database_url, max_size as usize);
 
-        Self { pool }
+        Self {
+            pool,
+            primary: None,
+            max_pending_checkouts: None,
+            archive: None,
+            component: None,
+        }
+    }
+
+    /// Makes the replica pool hand out the connections of the `primary` pool while the replica
+    /// lags behind the primary database for more than `max_lag`.
+    pub fn with_primary_fallback(mut self, primary: &ConnectionPool, max_lag: Duration) -> Self {
+        self.primary = Some(PrimaryFallback::new(primary.pool.clone(), max_lag));
+        self
+    }
+
+    /// Makes the pool report itself as saturated while more than `max_pending_checkouts`
+    /// tasks wait for a connection. Zero value disables the limit.
+    pub fn with_max_pending_checkouts(mut self, max_pending_checkouts: usize) -> Self {
+        self.max_pending_checkouts = Some(max_pending_checkouts).filter(|&max| max > 0);
+        self
+    }
+
+    /// Gives the connections of the pool access to the archived rows.
+    pub fn with_archive(mut self, archive: Arc<dyn ObjectStore>) -> Self {
+        self.archive = Some(archive);
+        self
     }
+
+    /// Labels the connections of the pool with the component using them, so the slow queries
+    /// of the components sharing the same pool are told apart. The clones of the pool can be
+    /// labeled independently.
+    pub fn with_component(mut self, component: &'static str) -> Self {
+        self.component = Some(component);
+        self
+    }
+
+    /// Returns the current utilization of the pool.
+    pub fn status(&self) -> PoolStatus {
+        let status = self.pool.status();
+        PoolStatus {
+            max_size: status.max_size,
+            size: status.size,
+            available: status.available.max(0) as usize,
+            pending: (-status.available).max(0) as usize,
+        }
+    }
+
+    /// Returns `true` if too many tasks already wait for a connection, so the new connection
+    /// requests would rather be rejected than queued. Always `false` if the limit isn't set.
+    pub fn is_saturated(&self) -> bool {
+        match self.max_pending_checkouts {
+            Some(max_pending_checkouts) => self.status().pending >= max_pending_checkouts,
+            None => false,
+        }
+    }
+
     /// Creates a `StorageProcessor` entity over a recoverable connection.
     /// Upon a database outage connection will block the thread until
     /// it will be able to recover the connection (or, if connection cannot
@@ -98,22 +199,51 @@ impl ConnectionPool {
     /// database access is must-have (e.g. block committer).
     pub async fn access_storage(&self) -> Result<StorageProcessor<'_>, SqlxError> {
         let start = Instant::now();
-        let connection = self.get_pooled_connection().await;
+        let mut connection = Self::get_pooled_connection(&self.pool).await;
+        if let Some(primary) = &self.primary {
+            if primary.is_replica_lagging(&mut connection).await {
+                metrics::increment_counter!("sql.replica_fallback");
+                connection = Self::get_pooled_connection(&primary.pool).await;
+            }
+        }
         metrics::histogram!("sql.connection_acquire", start.elapsed());
+        self.report_status();
+
+        let mut storage = StorageProcessor::from_pool(connection);
+        if let Some(archive) = &self.archive {
+            storage.set_archive(archive.clone());
+        }
+        if let Some(component) = self.component {
+            storage.set_component(component);
+        }
+        Ok(storage)
+    }
 
-        Ok(StorageProcessor::from_pool(connection))
+    fn report_status(&self) {
+        let status = self.status();
+        metrics::gauge!("sql.pool.size", status.size as f64);
+        metrics::gauge!("sql.pool.available", status.available as f64);
+        metrics::gauge!("sql.pool.pending", status.pending as f64);
+        metrics::gauge!(
+            "sql.pool.utilization",
+            (status.size - status.available) as f64 / status.max_size as f64
+        );
     }
 
-    async fn get_pooled_connection(&self) -> PooledConnection {
+    async fn get_pooled_connection(pool: &Pool) -> PooledConnection {
         let mut retry_count = 0;
 
         let mut one_second = time::interval(Duration::from_secs(1));
 
         while retry_count < DB_CONNECTION_RETRIES {
-            let connection = self.pool.get().await;
+            let connection = pool.get().await;
 
             match connection {
                 Ok(connection) => return connection,
+                Err(PoolError::Timeout(_)) => {
+                    metrics::increment_counter!("sql.connection_checkout_timeout");
+                    retry_count += 1;
+                }
                 Err(_) => retry_count += 1,
             }
 
@@ -123,6 +253,6 @@ impl ConnectionPool {
         }
 
         // Attempting to get the pooled connection for the last time
-        self.pool.get().await.unwrap()
+        pool.get().await.unwrap()
     }
 }
@@ -0,0 +1,181 @@
+//! Fallback of the read queries to the primary database while the replica lags behind it.
+//!
+//! Replica lag is the time since the last replayed transaction if the replica hasn't
+//! replayed all the received WAL yet, or zero otherwise (including the case when the
+//! "replica" is the primary database itself). It is checked at most once per
+//! `LAG_CHECK_INTERVAL` on the connection acquired from the replica pool.
+
+// Built-in deps
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+// External imports
+use sqlx::{Error as SqlxError, PgConnection};
+// Local imports
+use super::Pool;
+
+/// How often the replica lag is checked.
+const LAG_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Default)]
+struct LagState {
+    checked_at: Option<Instant>,
+    lagging: bool,
+}
+
+/// Primary database pool used instead of the replica one while the replica lags.
+#[derive(Clone)]
+pub(super) struct PrimaryFallback {
+    pub(super) pool: Pool,
+    max_lag: Duration,
+    state: Arc<Mutex<LagState>>,
+}
+
+impl PrimaryFallback {
+    pub(super) fn new(pool: Pool, max_lag: Duration) -> Self {
+        Self {
+            pool,
+            max_lag,
+            state: Arc::default(),
+        }
+    }
+
+    /// Returns `true` if the replica the connection belongs to lags behind the primary
+    /// for more than the allowed period.
+    pub(super) async fn is_replica_lagging(&self, replica: &mut PgConnection) -> bool {
+        if let Some(lagging) = self.recent_check() {
+            return lagging;
+        }
+
+        let lag = replica_lag(replica).await;
+        self.update_lag(lag)
+    }
+
+    /// Returns the result of the last check if it was done less than `LAG_CHECK_INTERVAL` ago.
+    fn recent_check(&self) -> Option<bool> {
+        let state = self.state.lock().unwrap();
+        state
+            .checked_at
+            .filter(|checked_at| checked_at.elapsed() < LAG_CHECK_INTERVAL)
+            .map(|_| state.lagging)
+    }
+
+    /// Records the measured replica lag and returns `true` if it exceeds the allowed one.
+    fn update_lag(&self, lag: Result<Duration, SqlxError>) -> bool {
+        // Unknown lag is considered acceptable, so a broken replica is detected
+        // by the connection errors rather than by this check.
+        let lagging = match lag {
+            Ok(lag) => {
+                metrics::histogram!("sql.replica_lag", lag);
+                lag > self.max_lag
+            }
+            Err(err) => {
+                vlog::warn!("Unable to check the replica lag: {}", err);
+                false
+            }
+        };
+
+        let mut state = self.state.lock().unwrap();
+        if lagging != state.lagging {
+            if lagging {
+                vlog::warn!(
+                    "Database replica lags for more than {:?}, read queries are routed to the primary",
+                    self.max_lag
+                );
+            } else {
+                vlog::info!("Database replica caught up, read queries are routed back to it");
+            }
+        }
+        *state = LagState {
+            checked_at: Some(Instant::now()),
+            lagging,
+        };
+        lagging
+    }
+}
+
+async fn replica_lag(replica: &mut PgConnection) -> Result<Duration, SqlxError> {
+    let lag = sqlx::query!(
+        r#"
+        SELECT (
+            CASE WHEN NOT pg_is_in_recovery() OR pg_last_wal_receive_lsn() = pg_last_wal_replay_lsn() THEN 0
+            ELSE EXTRACT(EPOCH FROM now() - pg_last_xact_replay_timestamp())
+            END
+        )::float8 AS lag
+        "#
+    )
+    .fetch_one(replica)
+    .await?
+    .lag
+    .unwrap_or_default();
+
+    Ok(Duration::from_secs_f64(lag.max(0.0)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        connection::DbPool, get_database_url, tests::db_test, QueryResult, StorageProcessor,
+    };
+
+    fn primary_fallback(max_lag: Duration) -> PrimaryFallback {
+        // Pool doesn't connect to the database until a connection is requested.
+        let pool = DbPool::create("postgres://postgres@localhost/plasma", 1);
+        PrimaryFallback::new(pool, max_lag)
+    }
+
+    /// Makes the result of the last check outdated, as if `LAG_CHECK_INTERVAL` has passed.
+    fn expire_last_check(fallback: &PrimaryFallback) {
+        let mut state = fallback.state.lock().unwrap();
+        state.checked_at = state
+            .checked_at
+            .map(|checked_at| checked_at - LAG_CHECK_INTERVAL);
+    }
+
+    /// Checks that the reads fall back to the primary while the replica lags
+    /// for more than the allowed period and return to the replica once it catches up.
+    #[test]
+    fn falls_back_while_replica_lags() {
+        let fallback = primary_fallback(Duration::from_secs(5));
+        assert_eq!(fallback.recent_check(), None);
+
+        assert!(!fallback.update_lag(Ok(Duration::from_secs(5))));
+        assert_eq!(fallback.recent_check(), Some(false));
+
+        expire_last_check(&fallback);
+        assert_eq!(fallback.recent_check(), None);
+        assert!(fallback.update_lag(Ok(Duration::from_secs(6))));
+        // The decision is reused until the next check.
+        assert_eq!(fallback.recent_check(), Some(true));
+
+        expire_last_check(&fallback);
+        assert!(!fallback.update_lag(Ok(Duration::from_secs(1))));
+        assert_eq!(fallback.recent_check(), Some(false));
+    }
+
+    /// Checks that the replica with the unknown lag keeps serving the reads.
+    #[test]
+    fn unknown_lag_is_acceptable() {
+        let fallback = primary_fallback(Duration::from_secs(5));
+        assert!(fallback.update_lag(Ok(Duration::from_secs(10))));
+
+        expire_last_check(&fallback);
+        assert!(!fallback.update_lag(Err(SqlxError::PoolTimedOut)));
+        assert_eq!(fallback.recent_check(), Some(false));
+    }
+
+    /// Checks that the primary database used as a replica doesn't lag.
+    #[db_test]
+    async fn primary_has_no_lag(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+        assert_eq!(replica_lag(storage.conn()).await?, Duration::from_secs(0));
+
+        let fallback = PrimaryFallback::new(
+            DbPool::create(get_database_url(), 1),
+            Duration::from_secs(0),
+        );
+        assert!(!fallback.is_replica_lagging(storage.conn()).await);
+        Ok(())
+    }
+}
@@ -75,7 +75,11 @@ impl<'a, 'c> DataRestoreSchema<'a, 'c> {
             .update_storage_state(new_state)
             .await?;
         transaction.commit().await?;
-        metrics::histogram!("sql.data_restore.save_block_operations", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.data_restore.save_block_operations",
+            start.elapsed()
+        );
         Ok(())
     }
 
@@ -92,7 +96,11 @@ impl<'a, 'c> DataRestoreSchema<'a, 'c> {
             .apply_state_update(BlockNumber(0))
             .await?;
         transaction.commit().await?;
-        metrics::histogram!("sql.data_restore.save_genesis_state", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.data_restore.save_genesis_state",
+            start.elapsed()
+        );
         Ok(())
     }
 
@@ -122,7 +130,11 @@ impl<'a, 'c> DataRestoreSchema<'a, 'c> {
         )
         .fetch_all(self.0.conn())
         .await?;
-        metrics::histogram!("sql.data_restore.load_rollup_ops_blocks", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.data_restore.load_rollup_ops_blocks",
+            start.elapsed()
+        );
         Ok(stored_blocks)
     }
 
@@ -145,7 +157,8 @@ impl<'a, 'c> DataRestoreSchema<'a, 'c> {
         .await?;
         transaction.commit().await?;
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.data_restore.update_last_watched_block_number",
             start.elapsed()
         );
@@ -164,13 +177,35 @@ impl<'a, 'c> DataRestoreSchema<'a, 'c> {
         .fetch_one(self.0.conn())
         .await?;
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.data_restore.load_last_watched_block_number",
             start.elapsed()
         );
         Ok(stored)
     }
 
+    /// Loads the last seen Ethereum block number, or `None` if the restore wasn't started yet.
+    pub async fn get_last_watched_block_number(&mut self) -> QueryResult<Option<u64>> {
+        let start = Instant::now();
+        let stored = sqlx::query_as!(
+            StoredLastWatchedEthBlockNumber,
+            "SELECT * FROM data_restore_last_watched_eth_block LIMIT 1",
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+        let block_number = stored
+            .map(|stored| stored.block_number.parse::<u64>())
+            .transpose()?;
+
+        sql_histogram!(
+            self.0,
+            "sql.data_restore.get_last_watched_block_number",
+            start.elapsed()
+        );
+        Ok(block_number)
+    }
+
     fn new_storage_state(&self, state: impl ToString) -> NewStorageState {
         NewStorageState {
             storage_state: state.to_string(),
@@ -221,7 +256,11 @@ impl<'a, 'c> DataRestoreSchema<'a, 'c> {
 
         transaction.commit().await?;
 
-        metrics::histogram!("sql.data_restore.save_events_state", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.data_restore.save_events_state",
+            start.elapsed()
+        );
         Ok(())
     }
 
@@ -362,7 +401,7 @@ impl<'a, 'c> DataRestoreSchema<'a, 'c> {
             .update_storage_state(new_state)
             .await?;
         transaction.commit().await?;
-        metrics::histogram!("sql.data_restore.save_rollup_ops", start.elapsed());
+        sql_histogram!(self.0, "sql.data_restore.save_rollup_ops", start.elapsed());
         Ok(())
     }
 
@@ -388,7 +427,11 @@ impl<'a, 'c> DataRestoreSchema<'a, 'c> {
         .execute(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql.data_restore.initialize_eth_stats", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.data_restore.initialize_eth_stats",
+            start.elapsed()
+        );
         Ok(())
     }
 
@@ -404,7 +447,11 @@ impl<'a, 'c> DataRestoreSchema<'a, 'c> {
         .fetch_all(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql.data_restore.load_events_state", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.data_restore.load_events_state",
+            start.elapsed()
+        );
         Ok(events)
     }
 
@@ -426,7 +473,11 @@ impl<'a, 'c> DataRestoreSchema<'a, 'c> {
         .fetch_one(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql.data_restore.load_storage_state", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.data_restore.load_storage_state",
+            start.elapsed()
+        );
         Ok(state)
     }
 
@@ -445,7 +496,11 @@ impl<'a, 'c> DataRestoreSchema<'a, 'c> {
         .await?;
         transaction.commit().await?;
 
-        metrics::histogram!("sql.data_restore.update_storage_state", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.data_restore.update_storage_state",
+            start.elapsed()
+        );
         Ok(())
     }
 
@@ -468,7 +523,11 @@ impl<'a, 'c> DataRestoreSchema<'a, 'c> {
             .await?;
         }
         transaction.commit().await?;
-        metrics::histogram!("sql.data_restore.update_block_events", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.data_restore.update_block_events",
+            start.elapsed()
+        );
         Ok(())
     }
 }
@@ -0,0 +1,259 @@
+//! Embedded Postgres server for running the database tests without a provisioned database.
+//!
+//! With the `embedded_db` feature enabled and `DATABASE_URL` not set, the storage connects to
+//! a throwaway cluster started from the locally installed Postgres binaries: nothing is downloaded,
+//! the binaries are looked up in `PG_BIN_DIR`, in the `pg_config --bindir` output or in `PATH`.
+//! The cluster lives in a temporary directory, listens on an ephemeral port and is stopped and
+//! removed when the test process exits, so the concurrent test runs don't interfere.
+//! Migrations from the `migrations` directory are applied by the `sqlx` migrator.
+//!
+//! It's not a Postgres-free backend: the schemas are built on the Postgres queries checked by `sqlx`
+//! at compile time, so the tests still need the Postgres binaries, but neither a provisioned server
+//! nor a container.
+
+// Built-in deps
+use std::{
+    borrow::Cow,
+    env, fs,
+    net::TcpListener,
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+// External imports
+use anyhow::{ensure, format_err};
+use once_cell::sync::Lazy;
+use sqlx::{
+    migrate::{Migration, MigrationType, Migrator},
+    Connection, Executor, PgConnection,
+};
+use tempfile::TempDir;
+
+/// Name of both the user and the database.
+const EMBEDDED_DB_NAME: &str = "plasma_test";
+const MIGRATIONS_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/migrations");
+/// Time given to the server to start accepting connections.
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(30);
+/// Attempts to start the server, the ephemeral port may be taken before the server binds it.
+const STARTUP_ATTEMPTS: usize = 3;
+
+/// Running server, stopped by the `atexit` hook.
+static EMBEDDED_SERVER: Lazy<Mutex<Option<EmbeddedServer>>> = Lazy::new(Default::default);
+
+static EMBEDDED_DATABASE_URL: Lazy<String> = Lazy::new(|| {
+    // Server is set up on a separate runtime, since the URL is requested from both
+    // synchronous and asynchronous contexts.
+    std::thread::spawn(|| {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(start_embedded_db())
+    })
+    .join()
+    .expect("Embedded database setup panicked")
+    .expect("Unable to start the embedded database")
+});
+
+/// Returns the URL of the migrated embedded database, starting the server if needed.
+pub fn embedded_database_url() -> String {
+    EMBEDDED_DATABASE_URL.clone()
+}
+
+/// Postgres server process along with its data directory.
+struct EmbeddedServer {
+    bin_dir: PathBuf,
+    data_dir: TempDir,
+    process: Child,
+}
+
+impl Drop for EmbeddedServer {
+    fn drop(&mut self) {
+        let stopped = Command::new(self.bin_dir.join("pg_ctl"))
+            .arg("stop")
+            .arg("-D")
+            .arg(self.data_dir.path())
+            .args(&["-m", "immediate", "-w"])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map(|status| status.success())
+            .unwrap_or_default();
+        if !stopped {
+            let _ = self.process.kill();
+        }
+        let _ = self.process.wait();
+        // The data directory is removed once the server is stopped.
+    }
+}
+
+extern "C" fn stop_embedded_server() {
+    if let Ok(mut server) = EMBEDDED_SERVER.lock() {
+        server.take();
+    }
+}
+
+async fn start_embedded_db() -> anyhow::Result<String> {
+    let bin_dir = postgres_bin_dir()?;
+    let data_dir = tempfile::Builder::new()
+        .prefix("zksync_embedded_db")
+        .tempdir()?;
+    init_cluster(&bin_dir, data_dir.path())?;
+
+    let mut attempt = 0;
+    let (process, port) = loop {
+        attempt += 1;
+        let port = ephemeral_port()?;
+        let mut process = start_server(&bin_dir, data_dir.path(), port)?;
+        match wait_for_server(&mut process, port).await {
+            Ok(()) => break (process, port),
+            Err(err) => {
+                let _ = process.kill();
+                let _ = process.wait();
+                if attempt == STARTUP_ATTEMPTS {
+                    return Err(err);
+                }
+                vlog::warn!(
+                    "Embedded database failed to start on port {}: {}",
+                    port,
+                    err
+                );
+            }
+        }
+    };
+    *EMBEDDED_SERVER.lock().unwrap() = Some(EmbeddedServer {
+        bin_dir,
+        data_dir,
+        process,
+    });
+    // Safety: the hook is a plain function not capturing any state.
+    ensure!(
+        unsafe { libc::atexit(stop_embedded_server) } == 0,
+        "Unable to register the embedded database teardown"
+    );
+    vlog::info!("Started the embedded database on port {}", port);
+
+    let mut connection = PgConnection::connect(&server_url(port, "postgres")).await?;
+    connection
+        .execute(format!("CREATE DATABASE {}", EMBEDDED_DB_NAME).as_str())
+        .await?;
+    connection.close().await?;
+
+    let database_url = server_url(port, EMBEDDED_DB_NAME);
+    let mut connection = PgConnection::connect(&database_url).await?;
+    diesel_migrator()?.run(&mut connection).await?;
+    Ok(database_url)
+}
+
+/// Looks up the directory with the Postgres binaries.
+fn postgres_bin_dir() -> anyhow::Result<PathBuf> {
+    if let Ok(dir) = env::var("PG_BIN_DIR") {
+        return Ok(dir.into());
+    }
+    if let Ok(output) = Command::new("pg_config").arg("--bindir").output() {
+        if output.status.success() {
+            return Ok(String::from_utf8(output.stdout)?.trim().into());
+        }
+    }
+    env::var_os("PATH")
+        .and_then(|paths| env::split_paths(&paths).find(|dir| dir.join("initdb").is_file()))
+        .ok_or_else(|| {
+            format_err!(
+                "Postgres binaries are not found, set PG_BIN_DIR to the directory with them"
+            )
+        })
+}
+
+fn init_cluster(bin_dir: &Path, data_dir: &Path) -> anyhow::Result<()> {
+    let output = Command::new(bin_dir.join("initdb"))
+        .arg("-D")
+        .arg(data_dir)
+        .args(&[
+            "-U",
+            EMBEDDED_DB_NAME,
+            "--auth=trust",
+            "-E",
+            "UTF8",
+            "--no-sync",
+        ])
+        .output()?;
+    ensure!(
+        output.status.success(),
+        "initdb failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    Ok(())
+}
+
+fn server_url(port: u16, database: &str) -> String {
+    format!(
+        "postgres://{}@127.0.0.1:{}/{}",
+        EMBEDDED_DB_NAME, port, database
+    )
+}
+
+/// Returns the port which is currently free.
+fn ephemeral_port() -> anyhow::Result<u16> {
+    Ok(TcpListener::bind("127.0.0.1:0")?.local_addr()?.port())
+}
+
+fn start_server(bin_dir: &Path, data_dir: &Path, port: u16) -> anyhow::Result<Child> {
+    let process = Command::new(bin_dir.join("postgres"))
+        .arg("-D")
+        .arg(data_dir)
+        .arg("-p")
+        .arg(port.to_string())
+        // Unix socket is put into the data directory to not clash with the local server.
+        .arg("-k")
+        .arg(data_dir)
+        .args(&["-h", "127.0.0.1", "-F"])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    Ok(process)
+}
+
+async fn wait_for_server(process: &mut Child, port: u16) -> anyhow::Result<()> {
+    let url = server_url(port, "postgres");
+    let started_at = Instant::now();
+    loop {
+        if let Some(status) = process.try_wait()? {
+            anyhow::bail!("Server exited with {}", status);
+        }
+        match PgConnection::connect(&url).await {
+            Ok(connection) => return Ok(connection.close().await?),
+            Err(err) if started_at.elapsed() > STARTUP_TIMEOUT => {
+                anyhow::bail!("Server didn't start in {:?}: {}", STARTUP_TIMEOUT, err)
+            }
+            Err(_) => tokio::time::sleep(Duration::from_millis(100)).await,
+        }
+    }
+}
+
+/// Builds the `sqlx` migrator from the migrations in the `diesel` layout,
+/// where every migration is a directory with the `up.sql` and `down.sql` files.
+fn diesel_migrator() -> anyhow::Result<Migrator> {
+    let mut migrations = Vec::new();
+    for entry in fs::read_dir(MIGRATIONS_DIR)? {
+        let path = entry?.path();
+        let up_sql = path.join("up.sql");
+        if !up_sql.is_file() {
+            continue;
+        }
+        // Same as `diesel`, the version is the digits of the migration timestamp.
+        let name = path.file_name().unwrap().to_string_lossy().into_owned();
+        let (timestamp, description) = name.split_at(name.find('_').unwrap_or(name.len()));
+        let version: String = timestamp.chars().filter(char::is_ascii_digit).collect();
+        migrations.push(Migration::new(
+            version.parse()?,
+            Cow::Owned(description.trim_start_matches('_').to_string()),
+            MigrationType::Simple,
+            Cow::Owned(fs::read_to_string(&up_sql)?),
+        ));
+    }
+    migrations.sort_by_key(|migration| migration.version);
+    Ok(Migrator {
+        migrations: Cow::Owned(migrations),
+    })
+}
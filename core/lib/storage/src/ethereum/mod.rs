@@ -1,5 +1,10 @@
 // Built-in deps
-use std::{collections::VecDeque, convert::TryFrom, str::FromStr, time::Instant};
+use std::{
+    collections::{HashMap, VecDeque},
+    convert::TryFrom,
+    str::FromStr,
+    time::Instant,
+};
 // External imports
 use anyhow::format_err;
 use num::{BigInt, BigUint};
@@ -7,11 +12,11 @@ use sqlx::types::BigDecimal;
 // Workspace imports
 use zksync_types::{
     aggregated_operations::{AggregatedActionType, AggregatedOperation},
-    ethereum::{ETHOperation, InsertedOperationResponse},
+    ethereum::{ETHOperation, EthTxReplacement, InsertedOperationResponse},
     event::{
         account::AccountStateChangeStatus, block::BlockStatus, transaction::TransactionStatus,
     },
-    BlockNumber, H256, U256,
+    Address, BlockNumber, H256, U256,
 };
 // Local imports
 use self::records::{ETHOperationData, ETHParams, ETHStats, ETHTxHash, StorageETHOperation};
@@ -33,11 +38,12 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
         let start = Instant::now();
         // Load the operations with the associated Ethereum transactions
         // from the database.
-        // Here we obtain a sequence of one-to-one mappings (ETH tx) -> (operation ID).
-        // Each Ethereum transaction can have no more than one associated operation, and each
-        // operation is associated with exactly one Ethereum transaction. Note that there may
-        // be ETH transactions without an operation (e.g. `completeWithdrawals` call), but for
-        // every operation always there is an ETH transaction.
+        // Here we obtain a sequence of one-to-one mappings (ETH tx) -> (first operation ID).
+        // Each operation is associated with exactly one Ethereum transaction, while the multicall
+        // transaction may be associated with several operations: the first one is joined here,
+        // and the rest are loaded for all the transactions at once. Note that there may be ETH
+        // transactions without an operation (e.g. `completeWithdrawals` call), but for every
+        // operation always there is an ETH transaction.
 
         let mut transaction = self.0.start_transaction().await?;
 
@@ -49,7 +55,10 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
                     aggregate_operations.arguments as "arguments?"
                 FROM eth_operations
                 LEFT JOIN eth_aggregated_ops_binding
-                    ON eth_aggregated_ops_binding.eth_op_id = eth_operations.id
+                    ON eth_aggregated_ops_binding.id = (
+                        SELECT MIN(id) FROM eth_aggregated_ops_binding
+                        WHERE eth_op_id = eth_operations.id
+                    )
                 LEFT JOIN aggregate_operations
                     ON aggregate_operations.id = eth_aggregated_ops_binding.op_id
                 WHERE eth_operations.confirmed = false
@@ -59,22 +68,59 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
         .fetch_all(transaction.conn())
         .await?;
 
+        // Load the stored txs hashes of all the operations ordered by their ID,
+        // so the latest added hash of every operation will be the last one in its list.
+        let mut eth_tx_hashes: HashMap<i64, Vec<ETHTxHash>> = HashMap::new();
+        let hashes = sqlx::query_as!(
+            ETHTxHash,
+            "SELECT eth_tx_hashes.* FROM eth_tx_hashes
+            INNER JOIN eth_operations ON eth_operations.id = eth_tx_hashes.eth_op_id
+            WHERE eth_operations.confirmed = false
+            ORDER BY eth_tx_hashes.id ASC"
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+        for hash in hashes {
+            eth_tx_hashes.entry(hash.eth_op_id).or_default().push(hash);
+        }
+
+        // Operations sent after the first one within the same multicall transaction.
+        let mut bundled_ops: HashMap<i64, Vec<(i64, AggregatedOperation)>> = HashMap::new();
+        let records = sqlx::query!(
+            r#"
+                SELECT bindings.eth_op_id as "eth_op_id!", aggregate_operations.id, aggregate_operations.arguments
+                FROM (
+                    SELECT eth_aggregated_ops_binding.*,
+                        ROW_NUMBER() OVER (
+                            PARTITION BY eth_aggregated_ops_binding.eth_op_id
+                            ORDER BY eth_aggregated_ops_binding.id ASC
+                        ) AS position
+                    FROM eth_aggregated_ops_binding
+                    INNER JOIN eth_operations ON eth_operations.id = eth_aggregated_ops_binding.eth_op_id
+                    WHERE eth_operations.confirmed = false
+                ) bindings
+                INNER JOIN aggregate_operations ON aggregate_operations.id = bindings.op_id
+                WHERE bindings.position > 1
+                ORDER BY bindings.id ASC
+            "#
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+        for record in records {
+            let op: AggregatedOperation = serde_json::from_value(record.arguments)
+                .expect("Incorrect serialized aggregated operation in storage");
+            bundled_ops
+                .entry(record.eth_op_id)
+                .or_default()
+                .push((record.id, op));
+        }
+
         // Create a vector for the expected output.
         let mut ops: VecDeque<ETHOperation> = VecDeque::with_capacity(eth_ops.len());
 
         // Transform the `StoredOperation` to `Operation` and `StoredETHOperation` to `ETHOperation`.
         for eth_op in eth_ops {
-            // Load the stored txs hashes ordered by their ID,
-            // so the latest added hash will be the last one in the list.
-            let eth_tx_hashes: Vec<ETHTxHash> = sqlx::query_as!(
-                ETHTxHash,
-                "SELECT * FROM eth_tx_hashes
-                WHERE eth_op_id = $1
-                ORDER BY id ASC",
-                eth_op.id
-            )
-            .fetch_all(transaction.conn())
-            .await?;
+            let eth_tx_hashes = eth_tx_hashes.remove(&eth_op.id).unwrap_or_default();
             assert!(
                 !eth_tx_hashes.is_empty(),
                 "No hashes stored for the Ethereum operation"
@@ -87,6 +133,7 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
                         .expect("Incorrect serialized aggregated operation in storage");
                 (id, op)
             });
+            let bundled_ops = bundled_ops.remove(&eth_op.id).unwrap_or_default();
 
             // Convert the fields into expected format.
             let op_type = AggregatedActionType::from_str(eth_op.op_type.as_ref())
@@ -98,11 +145,16 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
                 .map(|entry| H256::from_slice(&entry.tx_hash))
                 .collect();
             let final_hash = eth_op.final_hash.map(|hash| H256::from_slice(&hash));
+            let sender = eth_op
+                .sender_address
+                .map(|address| Address::from_slice(&address));
+            let cancel_tx_hash = eth_op.cancel_tx_hash.map(|hash| H256::from_slice(&hash));
 
             let eth_op = ETHOperation {
                 id: eth_op.id,
                 op_type,
                 op,
+                bundled_ops,
                 nonce: eth_op.nonce.into(),
                 last_deadline_block: eth_op.last_deadline_block as u64,
                 last_used_gas_price,
@@ -110,6 +162,8 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
                 encoded_tx_data: eth_op.raw_tx,
                 confirmed: eth_op.confirmed,
                 final_hash,
+                sender,
+                cancel_tx_hash,
             };
 
             ops.push_back(eth_op);
@@ -117,7 +171,11 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
 
         transaction.commit().await?;
 
-        metrics::histogram!("sql.ethereum.load_unconfirmed_operations", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.ethereum.load_unconfirmed_operations",
+            start.elapsed()
+        );
         Ok(ops)
     }
 
@@ -142,7 +200,8 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
         .execute(self.0.conn())
         .await?;
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.ethereum.restore_unprocessed_operations",
             start.elapsed()
         );
@@ -187,7 +246,11 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
             }
         }
 
-        metrics::histogram!("sql.ethereum.load_unprocessed_operations", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.ethereum.load_unprocessed_operations",
+            start.elapsed()
+        );
         Ok(operations)
     }
 
@@ -206,7 +269,8 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
         .execute(self.0.conn())
         .await?;
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.ethereum.remove_unprocessed_operations",
             start.elapsed()
         );
@@ -215,6 +279,9 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
 
     /// Stores the sent (but not confirmed yet) Ethereum transaction in the database.
     /// Returns the `ETHOperation` object containing the assigned nonce and operation ID.
+    ///
+    /// `sender` is the additional operator account sending the transaction,
+    /// `None` stands for the main operator account.
     pub async fn save_new_eth_tx(
         &mut self,
         op_type: AggregatedActionType,
@@ -222,13 +289,22 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
         last_deadline_block: i64,
         last_used_gas_price: BigUint,
         raw_tx: Vec<u8>,
+        sender: Option<Address>,
     ) -> QueryResult<InsertedOperationResponse> {
         let start = Instant::now();
         let mut transaction = self.0.start_transaction().await?;
 
         // It's important to assign nonce within the same db transaction
         // as saving the operation to avoid the state divergence.
-        let nonce = EthereumSchema(&mut transaction).get_next_nonce().await?;
+        let nonce = match sender {
+            Some(address) => {
+                EthereumSchema(&mut transaction)
+                    .get_next_operator_nonce(address)
+                    .await?
+            }
+            None => EthereumSchema(&mut transaction).get_next_nonce().await?,
+        };
+        let sender_address = sender.map(|address| address.as_bytes().to_vec());
 
         // Create and insert the operation.
 
@@ -236,11 +312,11 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
         let last_used_gas_price = BigDecimal::from(BigInt::from(last_used_gas_price));
         let eth_op_id = sqlx::query!(
             "
-                INSERT INTO eth_operations (op_type, nonce, last_deadline_block, last_used_gas_price, raw_tx)
-                VALUES ($1, $2, $3, $4, $5)
+                INSERT INTO eth_operations (op_type, nonce, last_deadline_block, last_used_gas_price, raw_tx, sender_address)
+                VALUES ($1, $2, $3, $4, $5, $6)
                 RETURNING id
             ",
-            op_type.to_string(), nonce, last_deadline_block, last_used_gas_price, raw_tx,
+            op_type.to_string(), nonce, last_deadline_block, last_used_gas_price, raw_tx, sender_address,
         )
         .fetch_one(transaction.conn())
         .await?
@@ -270,10 +346,44 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
 
         transaction.commit().await?;
 
-        metrics::histogram!("sql.ethereum.save_new_eth_tx", start.elapsed());
+        sql_histogram!(self.0, "sql.ethereum.save_new_eth_tx", start.elapsed());
         Ok(response)
     }
 
+    /// Binds the operation sent within the multicall transaction of the already stored
+    /// Ethereum operation. Operations are expected to be added in the order of sending.
+    pub async fn add_bundled_operation(
+        &mut self,
+        eth_op_id: i64,
+        operation: (i64, AggregatedOperation),
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        let (op_id, op) = operation;
+        sqlx::query!(
+            "INSERT INTO eth_aggregated_ops_binding (op_id, eth_op_id) VALUES ($1, $2)",
+            op_id,
+            eth_op_id
+        )
+        .execute(transaction.conn())
+        .await?;
+
+        // Update the stored stats.
+        EthereumSchema(&mut transaction)
+            .report_created_operation(op)
+            .await?;
+
+        transaction.commit().await?;
+
+        sql_histogram!(
+            self.0,
+            "sql.ethereum.add_bundled_operation",
+            start.elapsed()
+        );
+        Ok(())
+    }
+
     /// Returns whether the operation with the given id was confirmed.
     /// If the operation with such id does not exist, then it returns Ok(false).
     pub async fn is_aggregated_op_confirmed(&mut self, id: i64) -> QueryResult<bool> {
@@ -288,7 +398,11 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
         .map(|op| op.confirmed)
         .unwrap_or(false);
 
-        metrics::histogram!("sql.ethereum.is_aggregated_op_confirmed", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.ethereum.is_aggregated_op_confirmed",
+            start.elapsed()
+        );
         Ok(confirmed)
     }
 
@@ -303,7 +417,7 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
         .fetch_one(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql.ethereum.get_eth_op_id", start.elapsed());
+        sql_histogram!(self.0, "sql.ethereum.get_eth_op_id", start.elapsed());
         Ok(hash_entry.eth_op_id)
     }
 
@@ -318,7 +432,7 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
         )
         .execute(self.0.conn())
         .await?;
-        metrics::histogram!("sql.ethereum.add_hash_entry", start.elapsed());
+        sql_histogram!(self.0, "sql.ethereum.add_hash_entry", start.elapsed());
         Ok(())
     }
 
@@ -344,7 +458,7 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
         .execute(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql.ethereum.update_eth_tx", start.elapsed());
+        sql_histogram!(self.0, "sql.ethereum.update_eth_tx", start.elapsed());
         Ok(())
     }
 
@@ -411,7 +525,11 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
 
         transaction.commit().await?;
 
-        metrics::histogram!("sql.ethereum.report_created_operation", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.ethereum.report_created_operation",
+            start.elapsed()
+        );
         Ok(())
     }
 
@@ -441,7 +559,7 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
         .execute(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql.ethereum.update_gas_price", start.elapsed());
+        sql_histogram!(self.0, "sql.ethereum.update_gas_price", start.elapsed());
         Ok(())
     }
 
@@ -452,7 +570,7 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
         let gas_price_limit =
             U256::try_from(params.gas_price_limit).expect("Negative gas limit value stored in DB");
 
-        metrics::histogram!("sql.ethereum.load_gas_price_limit", start.elapsed());
+        sql_histogram!(self.0, "sql.ethereum.load_gas_price_limit", start.elapsed());
         Ok(gas_price_limit)
     }
 
@@ -464,16 +582,94 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
             .average_gas_price
             .map(|price| U256::try_from(price).expect("Negative average gas price stored in DB"));
 
-        metrics::histogram!("sql.ethereum.load_average_gas_price", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.ethereum.load_average_gas_price",
+            start.elapsed()
+        );
         Ok(average_gas_price)
     }
 
+    /// Stores the gas used by the confirmed transaction of the aggregated operation
+    /// of the given size.
+    pub async fn save_gas_usage(
+        &mut self,
+        action_type: AggregatedActionType,
+        size: i64,
+        gas_used: i64,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "INSERT INTO eth_tx_gas_usage (action_type, size, gas_used) VALUES ($1, $2, $3)",
+            action_type.to_string(),
+            size,
+            gas_used
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        sql_histogram!(self.0, "sql.ethereum.save_gas_usage", start.elapsed());
+        Ok(())
+    }
+
+    /// Loads the gas used by at most `limit` latest confirmed transactions of the given type
+    /// with the size of the operation within the `[min_size, max_size]` range.
+    /// Returns pairs of the operation size and the gas used.
+    pub async fn load_gas_usage(
+        &mut self,
+        action_type: AggregatedActionType,
+        min_size: i64,
+        max_size: i64,
+        limit: i64,
+    ) -> QueryResult<Vec<(i64, i64)>> {
+        let start = Instant::now();
+        let usage = sqlx::query!(
+            "SELECT size, gas_used FROM eth_tx_gas_usage
+            WHERE action_type = $1 AND size BETWEEN $2 AND $3
+            ORDER BY id DESC
+            LIMIT $4",
+            action_type.to_string(),
+            min_size,
+            max_size,
+            limit
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|row| (row.size, row.gas_used))
+        .collect();
+
+        sql_histogram!(self.0, "sql.ethereum.load_gas_usage", start.elapsed());
+        Ok(usage)
+    }
+
+    /// Returns the creation time of the oldest Ethereum operation that isn't confirmed yet,
+    /// `None` if all of the operations are confirmed.
+    pub async fn get_oldest_unconfirmed_operation_time(
+        &mut self,
+    ) -> QueryResult<Option<DateTime<Utc>>> {
+        let start = Instant::now();
+        let created_at = sqlx::query!(
+            "SELECT MIN(created_at) as created_at FROM eth_operations WHERE confirmed = false"
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .created_at;
+
+        sql_histogram!(
+            self.0,
+            "sql.ethereum.get_oldest_unconfirmed_operation_time",
+            start.elapsed()
+        );
+        Ok(created_at)
+    }
+
     /// Loads the stored Ethereum operations stats.
     pub async fn load_stats(&mut self) -> QueryResult<ETHStats> {
         let start = Instant::now();
         let params = self.load_eth_params().await?;
 
-        metrics::histogram!("sql.ethereum.load_stats", start.elapsed());
+        sql_histogram!(self.0, "sql.ethereum.load_stats", start.elapsed());
         Ok(params.into())
     }
 
@@ -482,7 +678,7 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
         let params = sqlx::query_as!(ETHParams, "SELECT * FROM eth_parameters WHERE id = true",)
             .fetch_one(self.0.conn())
             .await?;
-        metrics::histogram!("sql.ethereum.load_eth_params", start.elapsed());
+        sql_histogram!(self.0, "sql.ethereum.load_eth_params", start.elapsed());
         Ok(params)
     }
 
@@ -506,17 +702,19 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
         .execute(transaction.conn())
         .await?;
 
-        // If there is a ZKSync operation, mark it as confirmed as well.
-        let aggregated_op = sqlx::query_as!(
+        // If there are ZKSync operations, mark them as confirmed as well.
+        // There may be several operations if they were sent within a multicall transaction.
+        let aggregated_ops = sqlx::query_as!(
             StoredAggregatedOperation,
             "SELECT * FROM aggregate_operations
-                WHERE id = (SELECT op_id FROM eth_aggregated_ops_binding WHERE eth_op_id = $1)",
+                WHERE id IN (SELECT op_id FROM eth_aggregated_ops_binding WHERE eth_op_id = $1)
+                ORDER BY id ASC",
             eth_op_id,
         )
-        .fetch_optional(transaction.conn())
+        .fetch_all(transaction.conn())
         .await?;
 
-        if let Some(op) = &aggregated_op {
+        for op in &aggregated_ops {
             let (from_block, to_block) = (op.from_block as u32, op.to_block as u32);
             let action_type = AggregatedActionType::from_str(&op.action_type).unwrap();
             transaction
@@ -571,12 +769,153 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
         if let Some(time) = created_at_time {
             // It's almost impossible situation, but it could be triggered in tests
             let duration = (Utc::now() - time).to_std().unwrap_or_default();
-            metrics::histogram!("eth_operation_confirmation", duration);
+            sql_histogram!(transaction, "eth_operation_confirmation", duration);
         }
 
         transaction.commit().await?;
 
-        metrics::histogram!("sql.ethereum.confirm_eth_tx", start.elapsed());
+        sql_histogram!(self.0, "sql.ethereum.confirm_eth_tx", start.elapsed());
+        Ok(())
+    }
+
+    /// Stores the request to replace the transaction of the unconfirmed Ethereum operation.
+    /// The repeated request for the same operation overrides the previous one.
+    /// Returns `false` if there is no such unconfirmed operation.
+    pub async fn request_eth_tx_replacement(
+        &mut self,
+        eth_op_id: i64,
+        action: EthTxReplacement,
+    ) -> QueryResult<bool> {
+        let start = Instant::now();
+        let rows_affected = sqlx::query!(
+            "INSERT INTO eth_tx_replacement_requests (eth_op_id, action)
+            SELECT id, $2 FROM eth_operations WHERE id = $1 AND confirmed = false
+            ON CONFLICT (eth_op_id) DO UPDATE SET action = $2, created_at = now()",
+            eth_op_id,
+            action.to_string(),
+        )
+        .execute(self.0.conn())
+        .await?
+        .rows_affected();
+
+        sql_histogram!(
+            self.0,
+            "sql.ethereum.request_eth_tx_replacement",
+            start.elapsed()
+        );
+        Ok(rows_affected > 0)
+    }
+
+    /// Removes the pending transaction replacement requests and returns them.
+    pub async fn take_eth_tx_replacement_requests(
+        &mut self,
+    ) -> QueryResult<Vec<(i64, EthTxReplacement)>> {
+        let start = Instant::now();
+        let requests =
+            sqlx::query!("DELETE FROM eth_tx_replacement_requests RETURNING eth_op_id, action")
+                .fetch_all(self.0.conn())
+                .await?
+                .into_iter()
+                .map(|record| {
+                    let action = EthTxReplacement::from_str(&record.action)
+                        .expect("Stored replacement action must have a valid value");
+                    (record.eth_op_id, action)
+                })
+                .collect();
+
+        sql_histogram!(
+            self.0,
+            "sql.ethereum.take_eth_tx_replacement_requests",
+            start.elapsed()
+        );
+        Ok(requests)
+    }
+
+    /// Stores the hash of the transaction cancelling the Ethereum operation.
+    pub async fn set_cancel_tx_hash(&mut self, eth_op_id: i64, hash: &H256) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "UPDATE eth_operations SET cancel_tx_hash = $1 WHERE id = $2",
+            hash.as_bytes(),
+            eth_op_id
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        sql_histogram!(self.0, "sql.ethereum.set_cancel_tx_hash", start.elapsed());
+        Ok(())
+    }
+
+    /// Marks the Ethereum operation as completed by the cancelling transaction.
+    /// The associated zkSync operations are unbound from it, so they're considered
+    /// unprocessed and may be sent again.
+    ///
+    /// Only the latest sent operation can be cancelled, thus the stats are rolled back
+    /// to the blocks preceding the cancelled operations.
+    pub async fn cancel_eth_tx(&mut self, eth_op_id: i64, hash: &H256) -> QueryResult<()> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        let aggregated_ops = sqlx::query_as!(
+            StoredAggregatedOperation,
+            "SELECT * FROM aggregate_operations
+                WHERE id IN (SELECT op_id FROM eth_aggregated_ops_binding WHERE eth_op_id = $1)
+                ORDER BY id ASC",
+            eth_op_id,
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+
+        sqlx::query!(
+            "DELETE FROM eth_aggregated_ops_binding WHERE eth_op_id = $1",
+            eth_op_id
+        )
+        .execute(transaction.conn())
+        .await?;
+        sqlx::query!(
+            "UPDATE eth_operations
+                SET confirmed = $1, final_hash = $2
+                WHERE id = $3",
+            true,
+            hash.as_bytes(),
+            eth_op_id
+        )
+        .execute(transaction.conn())
+        .await?;
+
+        let mut current_stats = EthereumSchema(&mut transaction).load_eth_params().await?;
+        for op in &aggregated_ops {
+            let previous_block = op.from_block - 1;
+            match AggregatedActionType::from_str(&op.action_type).unwrap() {
+                AggregatedActionType::CommitBlocks => {
+                    current_stats.last_committed_block =
+                        current_stats.last_committed_block.min(previous_block);
+                }
+                AggregatedActionType::PublishProofBlocksOnchain => {
+                    current_stats.last_verified_block =
+                        current_stats.last_verified_block.min(previous_block);
+                }
+                AggregatedActionType::ExecuteBlocks => {
+                    current_stats.last_executed_block =
+                        current_stats.last_executed_block.min(previous_block);
+                }
+                AggregatedActionType::CreateProofBlocks => {}
+            }
+        }
+        sqlx::query!(
+            "UPDATE eth_parameters
+            SET last_committed_block = $1, last_verified_block = $2, last_executed_block = $3
+            WHERE id = true",
+            current_stats.last_committed_block,
+            current_stats.last_verified_block,
+            current_stats.last_executed_block
+        )
+        .execute(transaction.conn())
+        .await?;
+
+        transaction.commit().await?;
+
+        sql_histogram!(self.0, "sql.ethereum.cancel_eth_tx", start.elapsed());
         Ok(())
     }
 
@@ -608,10 +947,139 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
 
         transaction.commit().await?;
 
-        metrics::histogram!("sql.ethereum.get_next_nonce", start.elapsed());
+        sql_histogram!(self.0, "sql.ethereum.get_next_nonce", start.elapsed());
         Ok(old_nonce_value)
     }
 
+    /// Stores the initial nonce of the additional operator account.
+    /// Does nothing if the nonce of the account is already stored.
+    pub async fn initialize_operator_nonce(
+        &mut self,
+        address: Address,
+        nonce: i64,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "INSERT INTO eth_operator_nonces (address, nonce) VALUES ($1, $2)
+            ON CONFLICT (address) DO NOTHING",
+            address.as_bytes(),
+            nonce
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        sql_histogram!(
+            self.0,
+            "sql.ethereum.initialize_operator_nonce",
+            start.elapsed()
+        );
+        Ok(())
+    }
+
+    /// Obtains the next nonce of the additional operator account and updates the stored
+    /// value for the next invocation.
+    ///
+    /// The nonce of the account is expected to be initialized via `initialize_operator_nonce`.
+    pub async fn get_next_operator_nonce(&mut self, address: Address) -> QueryResult<i64> {
+        let start = Instant::now();
+        let nonce = sqlx::query!(
+            r#"UPDATE eth_operator_nonces
+            SET nonce = nonce + 1
+            WHERE address = $1
+            RETURNING nonce - 1 AS "nonce!""#,
+            address.as_bytes()
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .ok_or_else(|| {
+            format_err!(
+                "Nonce of the operator account {:#x} is not initialized",
+                address
+            )
+        })?
+        .nonce;
+
+        sql_histogram!(
+            self.0,
+            "sql.ethereum.get_next_operator_nonce",
+            start.elapsed()
+        );
+        Ok(nonce)
+    }
+
+    /// Loads the next nonce of the operator account without updating the stored value.
+    /// `sender` is the additional operator account, `None` stands for the main one.
+    pub async fn load_next_nonce(&mut self, sender: Option<Address>) -> QueryResult<i64> {
+        let start = Instant::now();
+        let nonce = match sender {
+            Some(address) => {
+                sqlx::query!(
+                    "SELECT nonce FROM eth_operator_nonces WHERE address = $1",
+                    address.as_bytes()
+                )
+                .fetch_optional(self.0.conn())
+                .await?
+                .ok_or_else(|| {
+                    format_err!(
+                        "Nonce of the operator account {:#x} is not initialized",
+                        address
+                    )
+                })?
+                .nonce
+            }
+            None => self.load_eth_params().await?.nonce,
+        };
+
+        sql_histogram!(self.0, "sql.ethereum.load_next_nonce", start.elapsed());
+        Ok(nonce)
+    }
+
+    /// Overwrites the next nonce of the operator account.
+    /// Used to repair the nonce bookkeeping once it has diverged from the chain.
+    pub async fn set_next_nonce(&mut self, sender: Option<Address>, nonce: i64) -> QueryResult<()> {
+        let start = Instant::now();
+        match sender {
+            Some(address) => {
+                sqlx::query!(
+                    "UPDATE eth_operator_nonces SET nonce = $1 WHERE address = $2",
+                    nonce,
+                    address.as_bytes()
+                )
+                .execute(self.0.conn())
+                .await?;
+            }
+            None => {
+                sqlx::query!(
+                    "UPDATE eth_parameters
+            SET nonce = $1
+            WHERE id = true",
+                    nonce
+                )
+                .execute(self.0.conn())
+                .await?;
+            }
+        }
+
+        sql_histogram!(self.0, "sql.ethereum.set_next_nonce", start.elapsed());
+        Ok(())
+    }
+
+    /// Assigns a new nonce to the unconfirmed Ethereum operation, e.g. once the old one
+    /// was used by another transaction of the operator account.
+    pub async fn update_eth_tx_nonce(&mut self, eth_op_id: i64, nonce: i64) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "UPDATE eth_operations SET nonce = $1 WHERE id = $2 AND confirmed = false",
+            nonce,
+            eth_op_id
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        sql_histogram!(self.0, "sql.ethereum.update_eth_tx_nonce", start.elapsed());
+        Ok(())
+    }
+
     /// Method that internally initializes the `eth_parameters` table.
     /// Since in db tests the database is empty, we must provide a possibility
     /// to initialize required db fields.
@@ -650,7 +1118,7 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
             .await?;
         }
 
-        metrics::histogram!("sql.ethereum.initialize_eth_data", start.elapsed());
+        sql_histogram!(self.0, "sql.ethereum.initialize_eth_data", start.elapsed());
         Ok(())
     }
 
@@ -666,7 +1134,8 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
         .fetch_one(self.0.conn())
         .await?
         .created_at;
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.ethereum.get_eth_operation_creation_time",
             start.elapsed()
         );
@@ -718,7 +1187,11 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
         .await?;
         transaction.commit().await?;
 
-        metrics::histogram!("sql.ethereum.update_eth_parameters", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.ethereum.update_eth_parameters",
+            start.elapsed()
+        );
         Ok(())
     }
 }
@@ -15,6 +15,8 @@ pub struct StorageETHOperation {
     pub last_deadline_block: i64,
     pub last_used_gas_price: BigDecimal,
     pub created_at: Option<DateTime<Utc>>,
+    pub sender_address: Option<Vec<u8>>,
+    pub cancel_tx_hash: Option<Vec<u8>>,
 }
 
 #[derive(Debug, Clone, FromRow, PartialEq)]
@@ -30,6 +32,8 @@ pub struct ETHOperationData {
     pub agg_op_id: Option<i64>,
     pub arguments: Option<serde_json::Value>,
     pub created_at: Option<DateTime<Utc>>,
+    pub sender_address: Option<Vec<u8>>,
+    pub cancel_tx_hash: Option<Vec<u8>>,
 }
 
 #[derive(Debug, Clone, FromRow, PartialEq)]
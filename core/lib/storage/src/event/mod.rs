@@ -16,7 +16,7 @@ use zksync_types::{
     BlockNumber,
 };
 // Local uses
-use crate::{QueryResult, StorageProcessor};
+use crate::{archive::EVENTS_TABLE, QueryResult, StorageProcessor, MAX_BLOCK_NUMBER};
 use records::StoredEvent;
 
 pub mod records;
@@ -29,8 +29,10 @@ pub use records::{get_event_type, EventType};
 /// On every insert into this table a special PostgreSQL channel gets notified
 /// about it.
 ///
-/// Note, that all events should be created solely by other `storage` methods
-/// and they are persisted in the database forever.
+/// Note, that all events should be created solely by other `storage` methods.
+/// Events of the old blocks can be moved to the object storage by the archiver.
+/// If the connection has access to the archive, the methods loading the events
+/// transparently read them back, otherwise only the events stored in the database are returned.
 #[derive(Debug)]
 pub struct EventSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
 
@@ -65,7 +67,7 @@ impl<'a, 'c> EventSchema<'a, 'c> {
         .execute(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql.event.store_event_data", start.elapsed());
+        sql_histogram!(self.0, "sql.event.store_event_data", start.elapsed());
         Ok(())
     }
 
@@ -90,10 +92,244 @@ impl<'a, 'c> EventSchema<'a, 'c> {
         .fetch_all(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql.event.fetch_new_events", start.elapsed());
+        sql_histogram!(self.0, "sql.event.fetch_new_events", start.elapsed());
         Ok(events)
     }
 
+    /// Load up to `limit` events with the `id` greater than `from`, ordered by `id`.
+    pub async fn fetch_events_batch(
+        &mut self,
+        from: EventId,
+        limit: u32,
+    ) -> QueryResult<Vec<StoredEvent>> {
+        let start = Instant::now();
+        let mut events = sqlx::query_as!(
+            StoredEvent,
+            r#"
+            SELECT
+                id,
+                block_number,
+                event_type as "event_type!: EventType",
+                event_data
+            FROM events WHERE id > $1
+            ORDER BY id ASC
+            LIMIT $2
+            "#,
+            *from as i64,
+            i64::from(limit)
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        if self.0.archive().is_some() {
+            // Archived events precede the stored ones, so only the events up to the last loaded one
+            // may be missing from the full batch.
+            let to_id = if events.len() == limit as usize {
+                events.last().map(|event| event.id)
+            } else {
+                None
+            };
+            let archived = self
+                .0
+                .archive_schema()
+                .load_archived_events_by_id(*from as i64, to_id)
+                .await?;
+            if !archived.is_empty() {
+                events.extend(archived);
+                events.sort_by_key(|event| event.id);
+                events.dedup_by_key(|event| event.id);
+                events.truncate(limit as usize);
+            }
+        }
+
+        sql_histogram!(self.0, "sql.event.fetch_events_batch", start.elapsed());
+        Ok(events)
+    }
+
+    /// Load the id of the last event published by the publisher to the message broker.
+    /// Returns `None` if the publisher hasn't published anything yet.
+    pub async fn get_publisher_offset(&mut self, publisher: &str) -> QueryResult<Option<EventId>> {
+        let start = Instant::now();
+        let offset = sqlx::query!(
+            "SELECT last_event_id FROM event_publisher_offsets WHERE publisher = $1",
+            publisher
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .map(|row| EventId(row.last_event_id as u64));
+
+        sql_histogram!(self.0, "sql.event.get_publisher_offset", start.elapsed());
+        Ok(offset)
+    }
+
+    /// Store the id of the last event published by the publisher to the message broker.
+    pub async fn store_publisher_offset(
+        &mut self,
+        publisher: &str,
+        last_event_id: EventId,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "INSERT INTO event_publisher_offsets (publisher, last_event_id)
+            VALUES ($1, $2)
+            ON CONFLICT (publisher) DO UPDATE SET last_event_id = $2, updated_at = now()",
+            publisher,
+            *last_event_id as i64
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        sql_histogram!(self.0, "sql.event.store_publisher_offset", start.elapsed());
+        Ok(())
+    }
+
+    /// Load the events of the blocks in the `[from_block, to_block]` range stored in the database,
+    /// ordered by `id`. Archived events are not included.
+    pub async fn load_stored_events_in_block_range(
+        &mut self,
+        from_block: BlockNumber,
+        to_block: BlockNumber,
+    ) -> QueryResult<Vec<StoredEvent>> {
+        let start = Instant::now();
+        let events = sqlx::query_as!(
+            StoredEvent,
+            r#"
+            SELECT
+                id,
+                block_number,
+                event_type as "event_type!: EventType",
+                event_data
+            FROM events WHERE block_number BETWEEN $1 AND $2
+            ORDER BY id ASC
+            "#,
+            i64::from(*from_block),
+            i64::from(*to_block)
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        sql_histogram!(
+            self.0,
+            "sql.event.load_stored_events_in_block_range",
+            start.elapsed()
+        );
+        Ok(events)
+    }
+
+    /// Load the events of the blocks in the `[from_block, to_block]` range ordered by `id`,
+    /// including the ones moved to the archive.
+    pub async fn load_events_in_block_range(
+        &mut self,
+        from_block: BlockNumber,
+        to_block: BlockNumber,
+    ) -> QueryResult<Vec<StoredEvent>> {
+        let start = Instant::now();
+        // Events are loaded from the database first, so the range archived concurrently is
+        // observed in both places rather than in none of them.
+        let mut events = self
+            .load_stored_events_in_block_range(from_block, to_block)
+            .await?;
+        if self.0.archive().is_some() {
+            let mut archive_schema = self.0.archive_schema();
+            let ranges = archive_schema
+                .load_archived_ranges(EVENTS_TABLE, from_block, to_block)
+                .await?;
+            let archived = archive_schema
+                .load_archived_rows::<StoredEvent>(&ranges)
+                .await?;
+            events.extend(archived.into_iter().filter(|event| {
+                (i64::from(*from_block)..=i64::from(*to_block)).contains(&event.block_number)
+            }));
+            events.sort_by_key(|event| event.id);
+            events.dedup_by_key(|event| event.id);
+        }
+
+        sql_histogram!(
+            self.0,
+            "sql.event.load_events_in_block_range",
+            start.elapsed()
+        );
+        Ok(events)
+    }
+
+    /// Remove the events of the blocks in the `[from_block, to_block]` range from the database.
+    /// Returns the amount of removed events.
+    pub async fn remove_events_in_block_range(
+        &mut self,
+        from_block: BlockNumber,
+        to_block: BlockNumber,
+    ) -> QueryResult<u64> {
+        let start = Instant::now();
+        let removed = sqlx::query!(
+            "DELETE FROM events WHERE block_number BETWEEN $1 AND $2",
+            i64::from(*from_block),
+            i64::from(*to_block)
+        )
+        .execute(self.0.conn())
+        .await?
+        .rows_affected();
+
+        sql_histogram!(
+            self.0,
+            "sql.event.remove_events_in_block_range",
+            start.elapsed()
+        );
+        Ok(removed)
+    }
+
+    /// Load the id of the first event of the blocks starting from the `block_number`,
+    /// including the archived ones. Returns `None` if there are no such events.
+    pub async fn get_first_event_id_since_block(
+        &mut self,
+        block_number: BlockNumber,
+    ) -> QueryResult<Option<EventId>> {
+        let start = Instant::now();
+        let mut id = sqlx::query!(
+            "SELECT MIN(id) as min FROM events WHERE block_number >= $1",
+            i64::from(*block_number)
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .min;
+
+        if self.0.archive().is_some() {
+            let mut archive_schema = self.0.archive_schema();
+            let ranges = archive_schema
+                .load_archived_ranges(EVENTS_TABLE, block_number, MAX_BLOCK_NUMBER)
+                .await?;
+            // Only the range containing the block has to be read, the rest of them
+            // start after the block and all of their events match.
+            let mut archived_id = None;
+            for range in ranges {
+                if range.from_block < i64::from(*block_number) {
+                    archived_id = archive_schema
+                        .load_archived_rows::<StoredEvent>(&[range])
+                        .await?
+                        .into_iter()
+                        .filter(|event| event.block_number >= i64::from(*block_number))
+                        .map(|event| event.id)
+                        .min();
+                } else {
+                    archived_id = range.min_row_id;
+                }
+                if archived_id.is_some() {
+                    break;
+                }
+            }
+            id = match (id, archived_id) {
+                (Some(id), Some(archived_id)) => Some(id.min(archived_id)),
+                (id, archived_id) => id.or(archived_id),
+            };
+        }
+
+        sql_histogram!(
+            self.0,
+            "sql.event.get_first_event_id_since_block",
+            start.elapsed()
+        );
+        Ok(id.map(|id| EventId(id as u64)))
+    }
+
     /// Load the id of the latest event in the database.
     /// Returns `None` if the `events` table is empty.
     pub async fn get_last_event_id(&mut self) -> QueryResult<Option<EventId>> {
@@ -104,7 +340,7 @@ impl<'a, 'c> EventSchema<'a, 'c> {
             .max
             .map(|id| EventId(id as u64));
 
-        metrics::histogram!("sql.event.get_last_event_id", start.elapsed());
+        sql_histogram!(self.0, "sql.event.get_last_event_id", start.elapsed());
         Ok(id)
     }
 
@@ -157,7 +393,7 @@ impl<'a, 'c> EventSchema<'a, 'c> {
             .await?;
         transaction.commit().await?;
 
-        metrics::histogram!("sql.event.store_block_event", start.elapsed());
+        sql_histogram!(self.0, "sql.event.store_block_event", start.elapsed());
         Ok(())
     }
 
@@ -199,7 +435,11 @@ impl<'a, 'c> EventSchema<'a, 'c> {
             .await?;
         transaction.commit().await?;
 
-        metrics::histogram!("sql.event.store_state_updated_event", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.event.store_state_updated_event",
+            start.elapsed()
+        );
         Ok(())
     }
 
@@ -246,7 +486,7 @@ impl<'a, 'c> EventSchema<'a, 'c> {
             .await?;
         transaction.commit().await?;
 
-        metrics::histogram!("sql.event.store_transaction_event", start.elapsed());
+        sql_histogram!(self.0, "sql.event.store_transaction_event", start.elapsed());
         Ok(())
     }
 
@@ -284,7 +524,11 @@ impl<'a, 'c> EventSchema<'a, 'c> {
             .await?;
         transaction.commit().await?;
 
-        metrics::histogram!("sql.event.store_queued_transaction_event", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.event.store_queued_transaction_event",
+            start.elapsed()
+        );
         Ok(())
     }
 }
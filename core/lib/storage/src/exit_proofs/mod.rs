@@ -0,0 +1,220 @@
+// Built-in deps
+use std::{
+    convert::TryFrom,
+    time::{Duration, Instant},
+};
+// External imports
+// Workspace imports
+use zksync_api_types::v02::exit_proof::{ApiExitProofJob, ExitProofData, ExitProofJobStatus};
+use zksync_types::{AccountId, BlockNumber, TokenId};
+// Local imports
+use self::records::DbExitProofJob;
+use crate::{QueryResult, StorageProcessor};
+
+pub mod records;
+
+/// Exit proofs schema handles the queue of the exit proofs requested via the API.
+#[derive(Debug)]
+pub struct ExitProofsSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> ExitProofsSchema<'a, 'c> {
+    /// Queues the exit proof generation for the account and token against the state of `block_number`.
+    /// If the job already exists, it's returned as is, unless it's failed: failed jobs are queued again.
+    /// Queued jobs of the same account and token for the previous blocks are superseded and removed.
+    pub async fn request_exit_proof(
+        &mut self,
+        account_id: AccountId,
+        token_id: TokenId,
+        block_number: BlockNumber,
+    ) -> QueryResult<ApiExitProofJob> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+        sqlx::query!(
+            "
+            DELETE FROM exit_proof_jobs
+            WHERE account_id = $1 AND token_id = $2 AND block_number < $3 AND status = $4
+            ",
+            i64::from(*account_id),
+            *token_id as i32,
+            i64::from(*block_number),
+            ExitProofJobStatus::Queued.as_str(),
+        )
+        .execute(transaction.conn())
+        .await?;
+        sqlx::query!(
+            "
+            INSERT INTO exit_proof_jobs (account_id, token_id, block_number, status)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (account_id, token_id, block_number) DO UPDATE
+            SET status = $4, error = NULL, updated_at = now()
+            WHERE exit_proof_jobs.status = $5
+            ",
+            i64::from(*account_id),
+            *token_id as i32,
+            i64::from(*block_number),
+            ExitProofJobStatus::Queued.as_str(),
+            ExitProofJobStatus::Failed.as_str(),
+        )
+        .execute(transaction.conn())
+        .await?;
+        let job = ExitProofsSchema(&mut transaction)
+            .get_exit_proof_job(account_id, token_id, block_number)
+            .await?
+            .expect("Exit proof job was stored in this transaction");
+        transaction.commit().await?;
+
+        sql_histogram!(self.0, "sql", start.elapsed(), "exit_proofs" => "request_exit_proof");
+        Ok(job)
+    }
+
+    /// Loads the exit proof job for the account and token against the state of `block_number`
+    /// along with its position in the queue.
+    pub async fn get_exit_proof_job(
+        &mut self,
+        account_id: AccountId,
+        token_id: TokenId,
+        block_number: BlockNumber,
+    ) -> QueryResult<Option<ApiExitProofJob>> {
+        let start = Instant::now();
+        let job = sqlx::query_as!(
+            DbExitProofJob,
+            r#"
+            SELECT id, account_id, token_id, block_number, status, proof, error,
+                CASE WHEN status = $4 THEN (
+                    SELECT COUNT(*) FROM exit_proof_jobs AS queued
+                    WHERE queued.status = $4 AND queued.id < exit_proof_jobs.id
+                ) END AS "queue_position?",
+                created_at, updated_at
+            FROM exit_proof_jobs
+            WHERE account_id = $1 AND token_id = $2 AND block_number = $3
+            "#,
+            i64::from(*account_id),
+            *token_id as i32,
+            i64::from(*block_number),
+            ExitProofJobStatus::Queued.as_str(),
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+        let job = match job {
+            Some(job) => Some(ApiExitProofJob::try_from(job)?),
+            None => None,
+        };
+
+        sql_histogram!(self.0, "sql", start.elapsed(), "exit_proofs" => "get_exit_proof_job");
+        Ok(job)
+    }
+
+    /// Takes the oldest queued job and marks it as in progress.
+    pub async fn take_next_exit_proof_job(&mut self) -> QueryResult<Option<ApiExitProofJob>> {
+        let start = Instant::now();
+        let job = sqlx::query_as!(
+            DbExitProofJob,
+            r#"
+            UPDATE exit_proof_jobs SET status = $1, updated_at = now()
+            WHERE id = (
+                SELECT id FROM exit_proof_jobs
+                WHERE status = $2
+                ORDER BY id
+                LIMIT 1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id, account_id, token_id, block_number, status, proof, error,
+                NULL::bigint AS "queue_position?", created_at, updated_at
+            "#,
+            ExitProofJobStatus::InProgress.as_str(),
+            ExitProofJobStatus::Queued.as_str(),
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+        let job = match job {
+            Some(job) => Some(ApiExitProofJob::try_from(job)?),
+            None => None,
+        };
+
+        sql_histogram!(self.0, "sql", start.elapsed(), "exit_proofs" => "take_next_exit_proof_job");
+        Ok(job)
+    }
+
+    /// Returns the amount of the jobs waiting in the queue.
+    pub async fn count_queued_exit_proof_jobs(&mut self) -> QueryResult<u64> {
+        let start = Instant::now();
+        let count = sqlx::query!(
+            r#"SELECT COUNT(*) as "count!" FROM exit_proof_jobs WHERE status = $1"#,
+            ExitProofJobStatus::Queued.as_str(),
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .count;
+
+        sql_histogram!(self.0, "sql", start.elapsed(), "exit_proofs" => "count_queued_exit_proof_jobs");
+        Ok(count as u64)
+    }
+
+    /// Prolongs the lease of the jobs being generated, so they are not returned to the queue.
+    pub async fn heartbeat_exit_proof_jobs(&mut self, ids: &[u64]) -> QueryResult<()> {
+        let start = Instant::now();
+        let ids: Vec<_> = ids.iter().map(|&id| id as i64).collect();
+        sqlx::query!(
+            "UPDATE exit_proof_jobs SET updated_at = now() WHERE id = ANY($1) AND status = $2",
+            &ids,
+            ExitProofJobStatus::InProgress.as_str(),
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        sql_histogram!(self.0, "sql", start.elapsed(), "exit_proofs" => "heartbeat_exit_proof_jobs");
+        Ok(())
+    }
+
+    /// Returns the jobs in progress which didn't receive a heartbeat for `lease_timeout`
+    /// (e.g. because their generator was restarted) to the queue.
+    pub async fn return_stale_exit_proof_jobs(
+        &mut self,
+        lease_timeout: Duration,
+    ) -> QueryResult<u64> {
+        let start = Instant::now();
+        let returned = sqlx::query!(
+            "UPDATE exit_proof_jobs SET status = $1, updated_at = now()
+            WHERE status = $2 AND updated_at <= now() - make_interval(secs => $3)",
+            ExitProofJobStatus::Queued.as_str(),
+            ExitProofJobStatus::InProgress.as_str(),
+            lease_timeout.as_secs_f64(),
+        )
+        .execute(self.0.conn())
+        .await?
+        .rows_affected();
+
+        sql_histogram!(self.0, "sql", start.elapsed(), "exit_proofs" => "return_stale_exit_proof_jobs");
+        Ok(returned)
+    }
+
+    pub async fn store_exit_proof(&mut self, id: u64, proof: &ExitProofData) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "UPDATE exit_proof_jobs SET status = $2, proof = $3, updated_at = now() WHERE id = $1",
+            id as i64,
+            ExitProofJobStatus::Done.as_str(),
+            serde_json::to_value(proof)?,
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        sql_histogram!(self.0, "sql", start.elapsed(), "exit_proofs" => "store_exit_proof");
+        Ok(())
+    }
+
+    pub async fn mark_exit_proof_job_failed(&mut self, id: u64, error: &str) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "UPDATE exit_proof_jobs SET status = $2, error = $3, updated_at = now() WHERE id = $1",
+            id as i64,
+            ExitProofJobStatus::Failed.as_str(),
+            error,
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        sql_histogram!(self.0, "sql", start.elapsed(), "exit_proofs" => "mark_exit_proof_job_failed");
+        Ok(())
+    }
+}
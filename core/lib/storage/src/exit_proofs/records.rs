@@ -0,0 +1,47 @@
+// Built-in imports
+use std::convert::TryFrom;
+// External imports
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+// Workspace imports
+use zksync_api_types::v02::exit_proof::ApiExitProofJob;
+use zksync_types::{AccountId, BlockNumber, TokenId};
+// Local imports
+
+#[derive(Debug, Clone, FromRow)]
+pub struct DbExitProofJob {
+    pub id: i64,
+    pub account_id: i64,
+    pub token_id: i32,
+    pub block_number: i64,
+    pub status: String,
+    /// JSON encoded `ExitProofData`.
+    pub proof: Option<serde_json::Value>,
+    pub error: Option<String>,
+    pub queue_position: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TryFrom<DbExitProofJob> for ApiExitProofJob {
+    type Error = anyhow::Error;
+
+    fn try_from(val: DbExitProofJob) -> Result<Self, Self::Error> {
+        let proof = match val.proof {
+            Some(proof) => Some(serde_json::from_value(proof)?),
+            None => None,
+        };
+        Ok(Self {
+            id: val.id as u64,
+            account_id: AccountId(val.account_id as u32),
+            token_id: TokenId(val.token_id as u32),
+            block_number: BlockNumber(val.block_number as u32),
+            status: val.status.parse().map_err(anyhow::Error::msg)?,
+            queue_position: val.queue_position.map(|position| position as u64),
+            proof,
+            error: val.error,
+            created_at: val.created_at,
+            updated_at: val.updated_at,
+        })
+    }
+}
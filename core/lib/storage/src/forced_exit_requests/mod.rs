@@ -1,6 +1,6 @@
 use chrono::{DateTime, Utc};
 // Built-in deps
-use num::BigInt;
+use num::{bigint::ToBigInt, BigInt};
 use sqlx::types::BigDecimal;
 use std::{ops::Sub, time::Instant};
 // External imports
@@ -8,10 +8,10 @@ use std::{ops::Sub, time::Instant};
 // Local imports
 use crate::{QueryResult, StorageProcessor};
 use zksync_types::forced_exit_requests::{
-    ForcedExitRequest, ForcedExitRequestId, SaveForcedExitRequestQuery,
+    DormantBalance, ForcedExitRequest, ForcedExitRequestId, SaveForcedExitRequestQuery,
 };
 
-use zksync_types::tx::TxHash;
+use zksync_types::{tx::TxHash, AccountId, Address, PubKeyHash, TokenId};
 
 pub mod records;
 
@@ -36,12 +36,14 @@ impl<'a, 'c> ForcedExitRequestsSchema<'a, 'c> {
         let target_str = address_to_stored_string(&request.target);
 
         let tokens = utils::vec_to_comma_list(request.tokens.clone());
+        let additional_targets = serde_json::to_value(&request.additional_targets)
+            .expect("Failed to serialize the forced exit targets");
 
         let stored_request: DbForcedExitRequest = sqlx::query_as!(
             DbForcedExitRequest,
             r#"
-            INSERT INTO forced_exit_requests ( target, tokens, price_in_wei, created_at, valid_until )
-            VALUES ( $1, $2, $3, $4, $5 )
+            INSERT INTO forced_exit_requests ( target, tokens, price_in_wei, created_at, valid_until, additional_targets )
+            VALUES ( $1, $2, $3, $4, $5, $6 )
             RETURNING *
             "#,
             target_str,
@@ -51,12 +53,17 @@ impl<'a, 'c> ForcedExitRequestsSchema<'a, 'c> {
             // However, since the valid_until is generated outside the db (using config params)
             // it was decided to set both values in the server for consistency
             request.created_at,
-            request.valid_until
+            request.valid_until,
+            additional_targets
         )
         .fetch_one(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql.forced_exit_requests.store_request", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.forced_exit_requests.store_request",
+            start.elapsed()
+        );
         Ok(stored_request.into())
     }
 
@@ -78,7 +85,8 @@ impl<'a, 'c> ForcedExitRequestsSchema<'a, 'c> {
         .await?
         .map(|r| r.into());
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.forced_exit_requests.get_request_by_id",
             start.elapsed()
         );
@@ -86,6 +94,72 @@ impl<'a, 'c> ForcedExitRequestsSchema<'a, 'c> {
         Ok(request)
     }
 
+    /// Loads the most recent requests exiting the given target, newest first.
+    pub async fn get_requests_by_target(
+        &mut self,
+        target: Address,
+        limit: u32,
+    ) -> QueryResult<Vec<ForcedExitRequest>> {
+        let start = Instant::now();
+        let target_str = address_to_stored_string(&target);
+        // Matches the requests having the target among the additional ones.
+        let additional_target = serde_json::json!([{ "target": target }]);
+
+        let requests: Vec<ForcedExitRequest> = sqlx::query_as!(
+            DbForcedExitRequest,
+            r#"
+            SELECT * FROM forced_exit_requests
+            WHERE target = $1 OR additional_targets @> $2
+            ORDER BY id DESC
+            LIMIT $3
+            "#,
+            target_str,
+            additional_target,
+            i64::from(limit)
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|r| r.into())
+        .collect();
+
+        sql_histogram!(
+            self.0,
+            "sql.forced_exit_requests.get_requests_by_target",
+            start.elapsed()
+        );
+
+        Ok(requests)
+    }
+
+    pub async fn set_payment_received_at(
+        &mut self,
+        id: ForcedExitRequestId,
+        payment_received_at: DateTime<Utc>,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+
+        sqlx::query!(
+            r#"
+            UPDATE forced_exit_requests
+                SET payment_received_at = $1
+                WHERE id = $2
+            "#,
+            payment_received_at,
+            id
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        sql_histogram!(
+            self.0,
+            "sql.forced_exit_requests.set_payment_received_at",
+            start.elapsed()
+        );
+
+        Ok(())
+    }
+
     pub async fn set_fulfilled_at(
         &mut self,
         id: ForcedExitRequestId,
@@ -105,7 +179,11 @@ impl<'a, 'c> ForcedExitRequestsSchema<'a, 'c> {
         .execute(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql.forced_exit_requests.set_fulfilled_at", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.forced_exit_requests.set_fulfilled_at",
+            start.elapsed()
+        );
 
         Ok(())
     }
@@ -130,7 +208,8 @@ impl<'a, 'c> ForcedExitRequestsSchema<'a, 'c> {
         .await?
         .map(|r| r.into());
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.forced_exit_requests.get_oldest_unfulfilled_request",
             start.elapsed()
         );
@@ -159,7 +238,11 @@ impl<'a, 'c> ForcedExitRequestsSchema<'a, 'c> {
         .execute(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql.forced_exit_requests.set_fulfilled_by", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.forced_exit_requests.set_fulfilled_by",
+            start.elapsed()
+        );
         Ok(())
     }
 
@@ -182,7 +265,8 @@ impl<'a, 'c> ForcedExitRequestsSchema<'a, 'c> {
         .map(|rec| rec.into())
         .collect();
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.forced_exit_requests.get_unconfirmed_requests",
             start.elapsed()
         );
@@ -210,11 +294,76 @@ impl<'a, 'c> ForcedExitRequestsSchema<'a, 'c> {
         .execute(self.0.conn())
         .await?;
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.forced_exit_requests.delete_old_unfulfilled_requests",
             start.elapsed()
         );
 
         Ok(())
     }
+
+    /// Loads the non-zero balances of the verified accounts without a signing key which
+    /// were not updated since `idle_since` and are worth at most `max_balance_usd`.
+    /// The balances of the tokens without a known price are never loaded.
+    pub async fn get_dormant_balances(
+        &mut self,
+        idle_since: DateTime<Utc>,
+        max_balance_usd: f64,
+        excluded: &[Address],
+        limit: u32,
+    ) -> QueryResult<Vec<DormantBalance>> {
+        let start = Instant::now();
+
+        let empty_pubkey_hash = PubKeyHash::default().data.to_vec();
+        let excluded: Vec<Vec<u8>> = excluded
+            .iter()
+            .map(|address| address.as_bytes().to_vec())
+            .collect();
+
+        let balances = sqlx::query!(
+            r#"
+            SELECT accounts.id, accounts.address, balances.coin_id, balances.balance
+            FROM balances
+            INNER JOIN accounts ON accounts.id = balances.account_id
+            INNER JOIN blocks ON blocks.number = accounts.last_block
+            INNER JOIN tokens ON tokens.id = balances.coin_id
+            INNER JOIN ticker_price ON ticker_price.token_id = balances.coin_id
+            WHERE balances.balance > 0
+                AND accounts.pubkey_hash = $1
+                AND blocks.timestamp < $2
+                AND balances.balance * ticker_price.usd_price <= $3::float8::numeric * power(10::numeric, tokens.decimals)
+                AND NOT (accounts.address = ANY($4))
+            ORDER BY accounts.id, balances.coin_id
+            LIMIT $5
+            "#,
+            empty_pubkey_hash,
+            idle_since.timestamp(),
+            max_balance_usd,
+            &excluded,
+            i64::from(limit)
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|record| DormantBalance {
+            account_id: AccountId(record.id as u32),
+            target: Address::from_slice(&record.address),
+            token: TokenId(record.coin_id as u32),
+            balance: record
+                .balance
+                .to_bigint()
+                .and_then(|balance| balance.to_biguint())
+                .expect("Stored balance must be a non-negative integer"),
+        })
+        .collect();
+
+        sql_histogram!(
+            self.0,
+            "sql.forced_exit_requests.get_dormant_balances",
+            start.elapsed()
+        );
+
+        Ok(balances)
+    }
 }
@@ -2,7 +2,11 @@ use crate::utils::{address_to_stored_string, stored_str_address_to_address};
 use chrono::{DateTime, Utc};
 use num::{bigint::ToBigInt, BigInt};
 use sqlx::types::BigDecimal;
-use zksync_types::{forced_exit_requests::ForcedExitRequest, tx::TxHash, TokenId};
+use zksync_types::{
+    forced_exit_requests::{ForcedExitRequest, ForcedExitTarget},
+    tx::TxHash,
+    TokenId,
+};
 
 use super::utils;
 
@@ -16,6 +20,8 @@ pub struct DbForcedExitRequest {
     pub created_at: DateTime<Utc>,
     pub fulfilled_by: Option<String>,
     pub fulfilled_at: Option<DateTime<Utc>>,
+    pub payment_received_at: Option<DateTime<Utc>>,
+    pub additional_targets: serde_json::Value,
 }
 
 impl From<ForcedExitRequest> for DbForcedExitRequest {
@@ -24,6 +30,8 @@ impl From<ForcedExitRequest> for DbForcedExitRequest {
 
         let tokens = utils::vec_to_comma_list(request.tokens);
         let fulfilled_by = request.fulfilled_by.map(utils::vec_to_comma_list);
+        let additional_targets = serde_json::to_value(request.additional_targets)
+            .expect("Failed to serialize the forced exit targets");
         Self {
             id: request.id,
             target: address_to_stored_string(&request.target),
@@ -33,6 +41,8 @@ impl From<ForcedExitRequest> for DbForcedExitRequest {
             created_at: request.created_at,
             fulfilled_at: request.fulfilled_at,
             fulfilled_by,
+            payment_received_at: request.payment_received_at,
+            additional_targets,
         }
     }
 }
@@ -50,16 +60,21 @@ impl From<DbForcedExitRequest> for ForcedExitRequest {
 
         let tokens: Vec<TokenId> = utils::comma_list_to_vec(val.tokens);
         let fulfilled_by: Option<Vec<TxHash>> = val.fulfilled_by.map(utils::comma_list_to_vec);
+        let additional_targets: Vec<ForcedExitTarget> =
+            serde_json::from_value(val.additional_targets)
+                .expect("Invalid forced exit targets have been stored");
 
         ForcedExitRequest {
             id: val.id,
             target: stored_str_address_to_address(&val.target),
             tokens,
+            additional_targets,
             price_in_wei,
             created_at: val.created_at,
             valid_until: val.valid_until,
             fulfilled_at: val.fulfilled_at,
             fulfilled_by,
+            payment_received_at: val.payment_received_at,
         }
     }
 }
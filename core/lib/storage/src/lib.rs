@@ -32,11 +32,15 @@
 //!
 //! There are the following sets of schemas:
 //!
+//! - archive, for tracking the rows moved to the object storage.
+//! - backup, for exporting and restoring the rollup-critical tables.
 //! - config, for the server config.
 //! - data_restore, for the data_restore crate.
 //! - ethereum, for the data associated with the Ethereum blockchain.
+//! - exit_proofs, for the queue of the exit proofs requested via the API.
 //! - prover, for the data on prover jobs, proofs, etc.
 //! - tokens, for storing and loading known tokens.
+//! - webhooks, for the webhooks registered by the integrators and their delivery queue.
 //! - chain - the biggest one, which includes several schemas for the ZKSync sidechain itself.
 //!
 //! The chain module includes the following schemas:
@@ -75,25 +79,37 @@
 #![allow(clippy::toplevel_ref_arg, clippy::suspicious_else_formatting)]
 
 // Built-in deps
-use std::env;
+use std::{env, sync::Arc};
 // External imports
 use sqlx::{postgres::Postgres, Connection, PgConnection, Transaction};
 // Workspace imports
 use zksync_types::{ActionType, BlockNumber};
 // Local imports
-use crate::connection::{holder::ConnectionHolder, PooledConnection};
+use crate::{
+    archive::ObjectStore,
+    connection::{holder::ConnectionHolder, PooledConnection},
+};
+
+// `sql_histogram!` macro has to be declared before the schema modules.
+#[macro_use]
+pub mod query_log;
 
 // mod schema;
 #[cfg(test)]
 mod tests;
 
+pub mod archive;
+pub mod backup;
 pub mod chain;
 pub mod config;
 pub mod connection;
 pub mod data_restore;
 pub mod diff;
+#[cfg(feature = "embedded_db")]
+pub mod embedded;
 pub mod ethereum;
 pub mod event;
+pub mod exit_proofs;
 pub mod forced_exit_requests;
 pub mod listener;
 pub mod misc;
@@ -101,10 +117,11 @@ pub mod prover;
 pub mod test_data;
 pub mod tokens;
 pub mod utils;
+pub mod webhooks;
 
 use forced_exit_requests::ForcedExitRequestsSchema;
 
-pub use crate::connection::ConnectionPool;
+pub use crate::connection::{ConnectionPool, PoolStatus};
 pub use sqlx::types::BigDecimal;
 pub type QueryResult<T, E = anyhow::Error> = Result<T, E>;
 
@@ -119,17 +136,32 @@ pub fn get_database_replica_url() -> String {
 }
 
 /// Obtains the database URL from the environment variable.
+///
+/// With the `embedded_db` feature, the embedded database is used if the variable is not set.
 pub fn get_database_url() -> String {
-    env::var("DATABASE_URL").expect("DATABASE_URL must be set")
+    match env::var("DATABASE_URL") {
+        Ok(url) => url,
+        #[cfg(feature = "embedded_db")]
+        Err(_) => embedded::embedded_database_url(),
+        #[cfg(not(feature = "embedded_db"))]
+        Err(_) => panic!("DATABASE_URL must be set"),
+    }
 }
 
 /// Storage processor is the main storage interaction point.
 /// It holds down the connection (either direct or pooled) to the database
 /// and provide methods to obtain different storage schemas.
+///
+/// The processor may have access to the archive of the old rows, in which case
+/// the schemas read them back from the object store, see `ArchiveSchema`.
+///
+/// The slow queries of the processor are reported along with its component, see `query_log`.
 #[derive(Debug)]
 pub struct StorageProcessor<'a> {
     conn: ConnectionHolder<'a>,
     in_transaction: bool,
+    archive: Option<Arc<dyn ObjectStore>>,
+    component: &'static str,
 }
 
 #[derive(sqlx::Type, Debug, Clone, PartialEq, Eq)]
@@ -156,16 +188,22 @@ impl<'a> StorageProcessor<'a> {
         Ok(StorageProcessor {
             conn: ConnectionHolder::Direct(connection),
             in_transaction: false,
+            archive: None,
+            component: query_log::component(),
         })
     }
 
     pub async fn start_transaction<'c: 'b, 'b>(
         &'c mut self,
     ) -> Result<StorageProcessor<'b>, anyhow::Error> {
+        let archive = self.archive.clone();
+        let component = self.component;
         let transaction = self.conn().begin().await?;
 
         let mut processor = StorageProcessor::from_transaction(transaction);
         processor.in_transaction = true;
+        processor.archive = archive;
+        processor.component = component;
 
         Ok(processor)
     }
@@ -179,6 +217,8 @@ impl<'a> StorageProcessor<'a> {
         StorageProcessor {
             conn: ConnectionHolder::Transaction(conn),
             in_transaction: true,
+            archive: None,
+            component: query_log::component(),
         }
     }
 
@@ -198,9 +238,41 @@ impl<'a> StorageProcessor<'a> {
         Self {
             conn: ConnectionHolder::Pooled(conn),
             in_transaction: false,
+            archive: None,
+            component: query_log::component(),
         }
     }
 
+    /// Returns the store of the archived rows, if the processor has access to it.
+    pub fn archive(&self) -> Option<Arc<dyn ObjectStore>> {
+        self.archive.clone()
+    }
+
+    /// Gives the processor access to the archived rows.
+    pub fn set_archive(&mut self, archive: Arc<dyn ObjectStore>) {
+        self.archive = Some(archive);
+    }
+
+    /// Returns the component the slow queries of the processor are reported with.
+    pub fn component(&self) -> &'static str {
+        self.component
+    }
+
+    /// Sets the component the slow queries of the processor are reported with.
+    pub fn set_component(&mut self, component: &'static str) {
+        self.component = component;
+    }
+
+    /// Gains access to the `Archive` schema.
+    pub fn archive_schema(&mut self) -> archive::ArchiveSchema<'_, 'a> {
+        archive::ArchiveSchema(self)
+    }
+
+    /// Gains access to the `Backup` schema.
+    pub fn backup_schema(&mut self) -> backup::BackupSchema<'_, 'a> {
+        backup::BackupSchema(self)
+    }
+
     /// Gains access to the `Chain` schemas.
     pub fn chain(&mut self) -> chain::ChainIntermediator<'_, 'a> {
         chain::ChainIntermediator(self)
@@ -221,6 +293,11 @@ impl<'a> StorageProcessor<'a> {
         ethereum::EthereumSchema(self)
     }
 
+    /// Gains access to the `ExitProofs` schema.
+    pub fn exit_proofs_schema(&mut self) -> exit_proofs::ExitProofsSchema<'_, 'a> {
+        exit_proofs::ExitProofsSchema(self)
+    }
+
     /// Gains access to the `Prover` schema.
     pub fn prover_schema(&mut self) -> prover::ProverSchema<'_, 'a> {
         prover::ProverSchema(self)
@@ -231,6 +308,11 @@ impl<'a> StorageProcessor<'a> {
         tokens::TokensSchema(self)
     }
 
+    /// Gains access to the `Webhooks` schema.
+    pub fn webhooks_schema(&mut self) -> webhooks::WebhooksSchema<'_, 'a> {
+        webhooks::WebhooksSchema(self)
+    }
+
     pub fn forced_exit_requests_schema(&mut self) -> ForcedExitRequestsSchema<'_, 'a> {
         ForcedExitRequestsSchema(self)
     }
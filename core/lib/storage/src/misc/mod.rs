@@ -4,7 +4,7 @@ use std::time::Instant;
 use sqlx::types::BigDecimal;
 // Local imports
 
-use self::records::Subsidy;
+use self::records::{Subsidy, TxAcceptancePause};
 use crate::{QueryResult, StorageProcessor};
 use num::ToPrimitive;
 
@@ -36,7 +36,7 @@ impl<'a, 'c> MiscSchema<'a, 'c> {
         .execute(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql.misc.store_subsidy", start.elapsed());
+        sql_histogram!(self.0, "sql.misc.store_subsidy", start.elapsed());
         Ok(())
     }
 
@@ -61,7 +61,63 @@ impl<'a, 'c> MiscSchema<'a, 'c> {
         // It's better move this param to actor and check it periodically
         metrics::gauge!("tx_sender.store_subsidy_data.total_subsidy", sum.to_f64().unwrap_or_default(), "type" => subsidy_type.to_string());
 
-        metrics::histogram!("sql.token.get_total_used_subsidy_for_type", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.token.get_total_used_subsidy_for_type",
+            start.elapsed()
+        );
         Ok(sum)
     }
+
+    /// Pauses the acceptance of the new transactions, the priority operations are still processed.
+    /// Returns `false` if the acceptance is already paused.
+    pub async fn pause_tx_acceptance(&mut self, reason: &str) -> QueryResult<bool> {
+        let start = Instant::now();
+        let result = sqlx::query!(
+            "INSERT INTO tx_acceptance_pause (reason) VALUES ($1) ON CONFLICT (id) DO NOTHING",
+            reason
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        sql_histogram!(self.0, "sql.misc.pause_tx_acceptance", start.elapsed());
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Resumes the acceptance of the new transactions. Returns `false` if the acceptance isn't paused.
+    pub async fn resume_tx_acceptance(&mut self) -> QueryResult<bool> {
+        let start = Instant::now();
+        let result = sqlx::query!("DELETE FROM tx_acceptance_pause")
+            .execute(self.0.conn())
+            .await?;
+
+        sql_histogram!(self.0, "sql.misc.resume_tx_acceptance", start.elapsed());
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Loads the pause of the transactions acceptance, if it's set.
+    pub async fn load_tx_acceptance_pause(&mut self) -> QueryResult<Option<TxAcceptancePause>> {
+        let start = Instant::now();
+        let pause = sqlx::query_as!(
+            TxAcceptancePause,
+            "SELECT reason, paused_at FROM tx_acceptance_pause"
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        sql_histogram!(self.0, "sql.misc.load_tx_acceptance_pause", start.elapsed());
+        Ok(pause)
+    }
+
+    /// Returns the size of the database on disk in bytes, including the indices and TOAST data.
+    pub async fn get_database_size(&mut self) -> QueryResult<u64> {
+        let start = Instant::now();
+        let size = sqlx::query!(r#"SELECT pg_database_size(current_database()) AS "size!""#)
+            .fetch_one(self.0.conn())
+            .await?
+            .size;
+
+        sql_histogram!(self.0, "sql.misc.get_database_size", start.elapsed());
+        Ok(size as u64)
+    }
 }
@@ -1,4 +1,6 @@
 // External imports
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use sqlx::types::BigDecimal;
 // Workspace imports
 // Local imports
@@ -13,3 +15,10 @@ pub struct Subsidy {
     pub full_cost_token: BigDecimal,
     pub subsidy_type: String,
 }
+
+/// Pause of the transactions acceptance set by the operator.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TxAcceptancePause {
+    pub reason: String,
+    pub paused_at: DateTime<Utc>,
+}
@@ -1,18 +1,23 @@
 // Built-in deps
-use std::time::Instant;
+use std::time::{Duration, Instant};
 // External imports
 use anyhow::format_err;
 // Workspace imports
 use zksync_types::BlockNumber;
 // Local imports
-use self::records::{StorageProverJobQueue, StoredAggregatedProof, StoredProof};
+use self::records::{
+    InProgressProverJob, ProverQueueStats, StorageProverJobQueue, StoredAggregatedProof,
+    StoredProof,
+};
 use crate::chain::operations::OperationsSchema;
 use crate::prover::records::StorageBlockWitness;
 use crate::{QueryResult, StorageProcessor};
 use chrono::{TimeZone, Utc};
 use zksync_crypto::proof::{AggregatedProof, SingleProof};
 use zksync_types::aggregated_operations::AggregatedActionType;
-use zksync_types::prover::{ProverJob, ProverJobStatus, ProverJobType};
+use zksync_types::prover::{
+    ProverJob, ProverJobPriorityPolicy, ProverJobStatus, ProverJobType, DEADLINE_JOB_PRIORITY,
+};
 
 pub mod records;
 
@@ -34,7 +39,7 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         .await?
         .count
         .unwrap_or(0) as u32;
-        metrics::histogram!("sql", start.elapsed(), "prover" => "pending_jobs_count");
+        sql_histogram!(self.0, "sql", start.elapsed(), "prover" => "pending_jobs_count");
         Ok(pending_jobs_count)
     }
 
@@ -64,27 +69,105 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
             job_data,
         ).execute(self.0.conn()).await?;
 
-        metrics::histogram!("sql", start.elapsed(), "prover" => "add_prover_job_to_job_queue");
+        sql_histogram!(self.0, "sql", start.elapsed(), "prover" => "add_prover_job_to_job_queue");
         Ok(())
     }
 
-    pub async fn mark_stale_jobs_as_idle(&mut self) -> QueryResult<()> {
+    /// Returns jobs which provers didn't send a heartbeat for `stale_timeout` back to the queue,
+    /// so they can be picked up by other provers.
+    pub async fn mark_stale_jobs_as_idle(&mut self, stale_timeout: Duration) -> QueryResult<()> {
         let start = Instant::now();
         let result = sqlx::query!(
             "UPDATE prover_job_queue SET (job_status, updated_at, updated_by) = ($1, now(), 'server_clean_idle')
-            WHERE job_status = $2 AND (now() - INTERVAL '120 seconds') >= updated_at RETURNING id",
+            WHERE job_status = $2 AND updated_at <= now() - make_interval(secs => $3) RETURNING id",
             ProverJobStatus::Idle.to_number(),
             ProverJobStatus::InProgress.to_number(),
+            stale_timeout.as_secs_f64(),
         )
         .fetch_all(self.0.conn())
         .await?;
+        if !result.is_empty() {
+            let ids: Vec<_> = result.iter().map(|job| job.id).collect();
+            vlog::warn!(
+                "Prover jobs {:?} are stale and were returned to the queue",
+                ids
+            );
+        }
         metrics::counter!("stale_jobs", result.len() as u64);
-        metrics::histogram!("sql", start.elapsed(), "prover" => "mark_stale_jobs_as_idle");
+        sql_histogram!(self.0, "sql", start.elapsed(), "prover" => "mark_stale_jobs_as_idle");
         Ok(())
     }
 
-    pub async fn get_idle_prover_job_from_job_queue(&mut self) -> QueryResult<Option<ProverJob>> {
+    /// Loads the jobs that are currently being proven along with the prover
+    /// working on them and the time of its last heartbeat.
+    pub async fn load_in_progress_jobs(&mut self) -> QueryResult<Vec<InProgressProverJob>> {
+        let start = Instant::now();
+        let jobs = sqlx::query_as!(
+            InProgressProverJob,
+            "SELECT id, job_type, first_block, last_block, updated_by, updated_at
+            FROM prover_job_queue
+            WHERE job_status = $1
+            ORDER BY id",
+            ProverJobStatus::InProgress.to_number(),
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        sql_histogram!(self.0, "sql", start.elapsed(), "prover" => "load_in_progress_jobs");
+        Ok(jobs)
+    }
+
+    /// Loads the amount of pending and in progress jobs grouped by the job type and size,
+    /// along with the average proving time of jobs proven within `proving_time_window`.
+    pub async fn load_prover_queue_stats(
+        &mut self,
+        proving_time_window: Duration,
+    ) -> QueryResult<Vec<ProverQueueStats>> {
+        let start = Instant::now();
+        let stats = sqlx::query_as!(
+            ProverQueueStats,
+            r#"
+            WITH jobs AS (
+                SELECT prover_job_queue.job_status, prover_job_queue.job_type,
+                    CASE WHEN prover_job_queue.job_type = $1 THEN COALESCE(blocks.block_size, 0)
+                        ELSE prover_job_queue.last_block - prover_job_queue.first_block + 1
+                    END AS job_size,
+                    prover_job_queue.proving_started_at, prover_job_queue.updated_at
+                FROM prover_job_queue
+                LEFT JOIN blocks ON blocks.number = prover_job_queue.first_block
+                WHERE prover_job_queue.job_status != $2
+                    OR prover_job_queue.updated_at >= now() - make_interval(secs => $3)
+            )
+            SELECT job_type, job_size as "job_size!",
+                COUNT(*) FILTER (WHERE job_status = $4) as "pending_jobs!",
+                COUNT(*) FILTER (WHERE job_status = $5) as "in_progress_jobs!",
+                AVG(EXTRACT(EPOCH FROM updated_at - proving_started_at)::float8)
+                    FILTER (WHERE job_status = $2 AND proving_started_at IS NOT NULL) as average_proving_time
+            FROM jobs
+            GROUP BY job_type, job_size
+            ORDER BY job_type, job_size
+            "#,
+            ProverJobType::SingleProof.to_string(),
+            ProverJobStatus::Done.to_number(),
+            proving_time_window.as_secs_f64(),
+            ProverJobStatus::Idle.to_number(),
+            ProverJobStatus::InProgress.to_number(),
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        sql_histogram!(self.0, "sql", start.elapsed(), "prover" => "load_prover_queue_stats");
+        Ok(stats)
+    }
+
+    /// Gives the most urgent idle job to the prover, the order of the jobs is defined by the `policy`.
+    pub async fn get_idle_prover_job_from_job_queue(
+        &mut self,
+        policy: &ProverJobPriorityPolicy,
+    ) -> QueryResult<Option<ProverJob>> {
         let start = Instant::now();
+        let now = Utc::now();
+        let preemption_threshold = policy.preemption_threshold(now);
         // Select the block to prove.
         let mut transaction = self.0.start_transaction().await?;
         sqlx::query!("LOCK TABLE prover_job_queue IN EXCLUSIVE MODE")
@@ -96,10 +179,17 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
             r#"
                 SELECT * FROM prover_job_queue
                 WHERE job_status = $1
-                ORDER BY (job_priority, id, first_block)
+                ORDER BY (
+                    CASE WHEN job_type = $2 AND created_at <= $3 THEN $4 ELSE job_priority END,
+                    id,
+                    first_block
+                )
                 LIMIT 1
             "#,
-            ProverJobStatus::Idle.to_number()
+            ProverJobStatus::Idle.to_number(),
+            ProverJobType::AggregatedProof.to_string(),
+            preemption_threshold,
+            DEADLINE_JOB_PRIORITY,
         )
         .fetch_optional(transaction.conn())
         .await?;
@@ -108,7 +198,7 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
             sqlx::query!(
                 r#"
                 UPDATE prover_job_queue
-                SET (job_status, updated_at, updated_by) = ($1, now(), 'server_give_job')
+                SET (job_status, updated_at, updated_by, proving_started_at) = ($1, now(), 'server_give_job', now())
                 WHERE id = $2;
             "#,
                 ProverJobStatus::InProgress.to_number(),
@@ -117,17 +207,22 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
             .execute(transaction.conn())
             .await?;
 
+            let job_type: ProverJobType = job.job_type.parse()?;
+            let job_priority =
+                policy.job_priority(&job_type, job.job_priority, job.created_at, now);
+
             Some(ProverJob::new(
                 job.id,
                 BlockNumber(job.first_block as u32),
                 BlockNumber(job.last_block as u32),
                 job.job_data,
+                job_priority,
             ))
         } else {
             None
         };
         transaction.commit().await?;
-        metrics::histogram!("sql", start.elapsed(), "prover" => "get_idle_prover_job_from_job_queue");
+        sql_histogram!(self.0, "sql", start.elapsed(), "prover" => "get_idle_prover_job_from_job_queue");
         Ok(prover_job)
     }
 
@@ -148,7 +243,7 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         .execute(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql", start.elapsed(), "prover" => "record_prover_is_working");
+        sql_histogram!(self.0, "sql", start.elapsed(), "prover" => "record_prover_is_working");
         Ok(())
     }
 
@@ -166,7 +261,7 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         .execute(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql", start.elapsed(), "prover" => "record_prover_stop");
+        sql_histogram!(self.0, "sql", start.elapsed(), "prover" => "record_prover_stop");
         Ok(())
     }
 
@@ -209,7 +304,7 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
             .set_block_processing_metrics(block_number, block_number, "single_proof".to_string())
             .await?;
         transaction.commit().await?;
-        metrics::histogram!("sql", start.elapsed(), "prover" => "store_proof");
+        sql_histogram!(self.0, "sql", start.elapsed(), "prover" => "store_proof");
         Ok(())
     }
 
@@ -232,7 +327,7 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
                 // It's almost impossible situation, but it could be triggered in tests
                 let duration = (Utc::now() - time).to_std().unwrap_or_default();
                 let labels = vec![("stage", stage.clone())];
-                metrics::histogram!("process_block", duration, &labels);
+                sql_histogram!(self.0, "process_block", duration, &labels);
             } else {
                 vlog::error!("Block for proof doesn't exist")
             }
@@ -280,7 +375,7 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
             .await?;
         transaction.commit().await?;
 
-        metrics::histogram!("sql", start.elapsed(), "prover" => "store_aggregated_proof");
+        sql_histogram!(self.0, "sql", start.elapsed(), "prover" => "store_aggregated_proof");
         Ok(())
     }
 
@@ -299,7 +394,7 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         .await?
         .map(|stored| serde_json::from_value(stored.proof).unwrap());
 
-        metrics::histogram!("sql", start.elapsed(), "prover" => "load_proof");
+        sql_histogram!(self.0, "sql", start.elapsed(), "prover" => "load_proof");
         Ok(proof)
     }
 
@@ -320,10 +415,53 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         .await?
         .map(|stored| serde_json::from_value(stored.proof).unwrap());
 
-        metrics::histogram!("sql", start.elapsed(), "prover" => "load_aggregated_proof");
+        sql_histogram!(self.0, "sql", start.elapsed(), "prover" => "load_aggregated_proof");
         Ok(proof)
     }
 
+    /// Moves the aggregated proof for blocks to the quarantine and returns its job to the queue,
+    /// so that the proof is generated again.
+    /// Returns `false` if there is no stored proof for these blocks.
+    pub async fn quarantine_aggregated_proof(
+        &mut self,
+        first_block: BlockNumber,
+        last_block: BlockNumber,
+    ) -> QueryResult<bool> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+        let quarantined_rows = sqlx::query!(
+            "WITH removed AS (
+                DELETE FROM aggregated_proofs WHERE first_block = $1 AND last_block = $2
+                RETURNING first_block, last_block, proof
+            )
+            INSERT INTO quarantined_aggregated_proofs (first_block, last_block, proof)
+            SELECT first_block, last_block, proof FROM removed",
+            i64::from(*first_block),
+            i64::from(*last_block)
+        )
+        .execute(transaction.conn())
+        .await?
+        .rows_affected();
+
+        if quarantined_rows != 0 {
+            sqlx::query!(
+                "UPDATE prover_job_queue
+                SET (updated_at, job_status, updated_by) = (now(), $1, 'server_quarantine_proof')
+                WHERE first_block = $2 AND last_block = $3 AND job_type = $4",
+                ProverJobStatus::Idle.to_number(),
+                i64::from(*first_block),
+                i64::from(*last_block),
+                ProverJobType::AggregatedProof.to_string()
+            )
+            .execute(transaction.conn())
+            .await?;
+        }
+        transaction.commit().await?;
+
+        sql_histogram!(self.0, "sql", start.elapsed(), "prover" => "quarantine_aggregated_proof");
+        Ok(quarantined_rows != 0)
+    }
+
     /// Stores witness for a block
     pub async fn store_witness(
         &mut self,
@@ -343,7 +481,31 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         .execute(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql", start.elapsed(), "prover" => "store_witness");
+        sql_histogram!(self.0, "sql", start.elapsed(), "prover" => "store_witness");
+        Ok(())
+    }
+
+    /// Stores witness for a block, replacing the existing one if any.
+    /// Used to overwrite witnesses that don't match the block state anymore.
+    pub async fn replace_witness(
+        &mut self,
+        block: BlockNumber,
+        witness: serde_json::Value,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        let witness_str = serde_json::to_string(&witness).expect("Failed to serialize witness");
+        sqlx::query!(
+            "INSERT INTO block_witness (block, witness)
+            VALUES ($1, $2)
+            ON CONFLICT (block)
+            DO UPDATE SET witness = $2",
+            i64::from(*block),
+            witness_str
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        sql_histogram!(self.0, "sql", start.elapsed(), "prover" => "replace_witness");
         Ok(())
     }
 
@@ -361,11 +523,81 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         .fetch_optional(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql", start.elapsed(), "prover" => "get_witness");
+        sql_histogram!(self.0, "sql", start.elapsed(), "prover" => "get_witness");
         Ok(block_witness
             .map(|w| serde_json::from_str(&w.witness).expect("Failed to deserialize witness")))
     }
 
+    /// Tries to acquire the lease on building the witness for a block.
+    /// The lease is granted if nobody holds it, if it's already held by `worker`,
+    /// or if the previous holder didn't send a heartbeat for `lease_timeout`.
+    pub async fn try_acquire_witness_lease(
+        &mut self,
+        block: BlockNumber,
+        worker: &str,
+        lease_timeout: Duration,
+    ) -> QueryResult<bool> {
+        let start = Instant::now();
+        let acquired = sqlx::query!(
+            "INSERT INTO witness_generator_leases (block, worker, heartbeat_at)
+            VALUES ($1, $2, now())
+            ON CONFLICT (block) DO UPDATE SET (worker, heartbeat_at) = ($2, now())
+            WHERE witness_generator_leases.worker = $2
+                OR witness_generator_leases.heartbeat_at <= now() - make_interval(secs => $3)
+            RETURNING block",
+            i64::from(*block),
+            worker,
+            lease_timeout.as_secs_f64(),
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .is_some();
+
+        sql_histogram!(self.0, "sql", start.elapsed(), "prover" => "try_acquire_witness_lease");
+        Ok(acquired)
+    }
+
+    /// Prolongs the witness lease held by `worker`.
+    /// Returns `false` if the lease was taken over by another worker.
+    pub async fn heartbeat_witness_lease(
+        &mut self,
+        block: BlockNumber,
+        worker: &str,
+    ) -> QueryResult<bool> {
+        let start = Instant::now();
+        let updated_rows = sqlx::query!(
+            "UPDATE witness_generator_leases SET heartbeat_at = now()
+            WHERE block = $1 AND worker = $2",
+            i64::from(*block),
+            worker,
+        )
+        .execute(self.0.conn())
+        .await?
+        .rows_affected();
+
+        sql_histogram!(self.0, "sql", start.elapsed(), "prover" => "heartbeat_witness_lease");
+        Ok(updated_rows == 1)
+    }
+
+    /// Releases the witness lease held by `worker`.
+    pub async fn release_witness_lease(
+        &mut self,
+        block: BlockNumber,
+        worker: &str,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "DELETE FROM witness_generator_leases WHERE block = $1 AND worker = $2",
+            i64::from(*block),
+            worker,
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        sql_histogram!(self.0, "sql", start.elapsed(), "prover" => "release_witness_lease");
+        Ok(())
+    }
+
     pub async fn get_last_block_prover_job_queue(
         &mut self,
         action_type: ProverJobType,
@@ -404,7 +636,7 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
             }
         };
 
-        metrics::histogram!("sql", start.elapsed(), "prover" => "get_last_block_prover_job_queue");
+        sql_histogram!(self.0, "sql", start.elapsed(), "prover" => "get_last_block_prover_job_queue");
         Ok(result)
     }
 
@@ -418,7 +650,7 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         .execute(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql", start.elapsed(), "prover" => "remove_witnesses");
+        sql_histogram!(self.0, "sql", start.elapsed(), "prover" => "remove_witnesses");
         Ok(())
     }
 
@@ -432,7 +664,7 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         .execute(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql", start.elapsed(), "prover" => "remove_proofs");
+        sql_histogram!(self.0, "sql", start.elapsed(), "prover" => "remove_proofs");
         Ok(())
     }
 
@@ -446,7 +678,7 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         .execute(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql", start.elapsed(), "prover" => "remove_aggregated_proofs");
+        sql_histogram!(self.0, "sql", start.elapsed(), "prover" => "remove_aggregated_proofs");
         Ok(())
     }
 
@@ -469,7 +701,7 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         .await?;
         transaction.commit().await?;
 
-        metrics::histogram!("sql", start.elapsed(), "prover" => "remove_prover_jobs");
+        sql_histogram!(self.0, "sql", start.elapsed(), "prover" => "remove_prover_jobs");
         Ok(())
     }
 }
@@ -68,4 +68,31 @@ pub struct StorageProverJobQueue {
     pub first_block: i64,
     pub last_block: i64,
     pub job_data: serde_json::Value,
+    /// Time when the job was given to a prover for the last time.
+    pub proving_started_at: Option<DateTime<Utc>>,
+}
+
+/// Prover job that is being proven at the moment.
+#[derive(Debug, Clone, FromRow)]
+pub struct InProgressProverJob {
+    pub id: i32,
+    pub job_type: String,
+    pub first_block: i64,
+    pub last_block: i64,
+    /// Name of the prover that sent the last heartbeat for the job.
+    pub updated_by: String,
+    /// Time of the last heartbeat for the job.
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Prover jobs statistics for jobs of the same type and size.
+#[derive(Debug, Clone, FromRow)]
+pub struct ProverQueueStats {
+    pub job_type: String,
+    /// Block chunks for single proofs and amount of blocks for aggregated ones.
+    pub job_size: i64,
+    pub pending_jobs: i64,
+    pub in_progress_jobs: i64,
+    /// Average proving time of the recently proven jobs in seconds.
+    pub average_proving_time: Option<f64>,
 }
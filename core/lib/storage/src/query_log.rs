@@ -0,0 +1,163 @@
+//! Execution time metrics and the slow queries log of the `Schema` methods.
+//!
+//! Every schema method reports its execution time via the `sql_histogram!` macro.
+//! Methods taking longer than the threshold set by `set_slow_query_threshold` are logged along
+//! with the component that called them, and the SQL statements taking longer than that are logged
+//! by `sqlx` itself under the `sqlx::query` target.
+//!
+//! The component is the one the connection pool is labeled with (see `ConnectionPool::with_component`),
+//! or the name of the binary for the unlabeled pools. The binary name is also reported to Postgres
+//! as the `application_name` of the connections, so it's visible in `pg_stat_activity` and server logs.
+
+// Built-in deps
+use std::{
+    env,
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+// External imports
+use once_cell::sync::Lazy;
+// Workspace imports
+// Local imports
+
+/// Slow queries threshold in milliseconds, zero if the slow queries log is disabled.
+static SLOW_QUERY_THRESHOLD_MS: AtomicU64 = AtomicU64::new(0);
+
+static COMPONENT: Lazy<String> = Lazy::new(|| {
+    env::current_exe()
+        .ok()
+        .and_then(|path| {
+            path.file_stem()
+                .map(|name| name.to_string_lossy().into_owned())
+        })
+        .unwrap_or_else(|| "unknown".to_string())
+});
+
+/// Records the execution time of the schema method, reporting it to the slow queries log if needed.
+/// Accepts the storage processor executing the method followed by the arguments of `metrics::histogram!`.
+macro_rules! sql_histogram {
+    ($storage:expr, $name:expr, $elapsed:expr $(, $label:expr => $value:expr)* $(,)?) => {{
+        let elapsed = $elapsed;
+        metrics::histogram!($name, elapsed $(, $label => $value)*);
+        $crate::query_log::report_query(
+            $storage.component(),
+            $name,
+            &[$(($label, $value)),*],
+            elapsed,
+        );
+    }};
+}
+
+/// Enables the slow queries log for the queries taking longer than `threshold`, `None` disables the log.
+///
+/// Statements are logged by the connections of the pools created after the call,
+/// so the threshold has to be set before the pools are created.
+pub fn set_slow_query_threshold(threshold: Option<Duration>) {
+    let threshold_ms = threshold.map_or(0, |threshold| threshold.as_millis() as u64);
+    SLOW_QUERY_THRESHOLD_MS.store(threshold_ms, Ordering::Relaxed);
+}
+
+/// Threshold after which the query is considered slow, `None` if the slow queries log is disabled.
+pub fn slow_query_threshold() -> Option<Duration> {
+    match SLOW_QUERY_THRESHOLD_MS.load(Ordering::Relaxed) {
+        0 => None,
+        threshold_ms => Some(Duration::from_millis(threshold_ms)),
+    }
+}
+
+/// Name of the binary using the database, the component of the unlabeled connection pools.
+pub fn component() -> &'static str {
+    COMPONENT.as_str()
+}
+
+/// Returns the name of the method along with its labels if it took longer than the threshold.
+fn slow_method(
+    name: &str,
+    labels: &[(&str, &str)],
+    elapsed: Duration,
+    threshold: Option<Duration>,
+) -> Option<String> {
+    if elapsed < threshold? {
+        return None;
+    }
+    let method = labels.iter().fold(name.to_string(), |method, (_, value)| {
+        format!("{}.{}", method, value)
+    });
+    Some(method)
+}
+
+#[doc(hidden)]
+pub fn report_query(component: &str, name: &str, labels: &[(&str, &str)], elapsed: Duration) {
+    let threshold = slow_query_threshold();
+    let method = match slow_method(name, labels, elapsed, threshold) {
+        Some(method) => method,
+        None => return,
+    };
+
+    vlog::warn!(
+        "Slow query `{}` called by `{}`: took {:?} (threshold {:?})",
+        method,
+        component,
+        elapsed,
+        threshold.unwrap_or_default()
+    );
+    metrics::increment_counter!(
+        "sql.slow_queries",
+        "method" => method,
+        "component" => component.to_string()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slow_methods() {
+        let threshold = Some(Duration::from_millis(100));
+        let labels = [("method", "commit"), ("status", "ok")];
+
+        assert_eq!(
+            slow_method(
+                "sql.load_config",
+                &[],
+                Duration::from_millis(150),
+                threshold
+            ),
+            Some("sql.load_config".to_string())
+        );
+        assert_eq!(
+            slow_method(
+                "sql.ethereum",
+                &labels,
+                Duration::from_millis(100),
+                threshold
+            ),
+            Some("sql.ethereum.commit.ok".to_string())
+        );
+        assert_eq!(
+            slow_method(
+                "sql.ethereum",
+                &labels,
+                Duration::from_millis(99),
+                threshold
+            ),
+            None
+        );
+        // Nothing is slow while the log is disabled.
+        assert_eq!(
+            slow_method("sql.load_config", &[], Duration::from_secs(60), None),
+            None
+        );
+    }
+
+    #[test]
+    fn slow_query_threshold_is_set() {
+        set_slow_query_threshold(Some(Duration::from_millis(1500)));
+        assert_eq!(slow_query_threshold(), Some(Duration::from_millis(1500)));
+        set_slow_query_threshold(Some(Duration::from_millis(0)));
+        assert_eq!(slow_query_threshold(), None);
+        set_slow_query_threshold(None);
+        assert_eq!(slow_query_threshold(), None);
+    }
+}
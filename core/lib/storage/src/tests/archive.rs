@@ -0,0 +1,279 @@
+// Built-in uses
+use std::sync::Arc;
+// External uses
+use serde_json::json;
+// Workspace uses
+use zksync_types::{aggregated_operations::AggregatedActionType, event::EventId, BlockNumber};
+// Local uses
+use super::db_test;
+use crate::{
+    archive::{MemoryObjectStore, EVENTS_TABLE, PUBDATA_TABLE},
+    chain::operations::OperationsSchema,
+    event::records::StoredEvent,
+    test_data::gen_unique_aggregated_operation,
+    QueryResult, StorageProcessor,
+};
+
+/// Inserts a block event for every block in the range, the events data is irrelevant for the archive.
+async fn store_events(
+    storage: &mut StorageProcessor<'_>,
+    from_block: u32,
+    to_block: u32,
+) -> QueryResult<()> {
+    for block_number in from_block..=to_block {
+        sqlx::query(
+            "INSERT INTO events (block_number, event_type, event_data) VALUES ($1, 'Block', $2)",
+        )
+        .bind(i64::from(block_number))
+        .bind(json!({ "block_number": block_number }))
+        .execute(storage.conn())
+        .await?;
+    }
+    Ok(())
+}
+
+/// Stores a `CommitBlocks` operation for every block in the range, confirming them if requested.
+async fn store_commit_operations(
+    storage: &mut StorageProcessor<'_>,
+    from_block: u32,
+    to_block: u32,
+    confirmed: bool,
+) -> QueryResult<()> {
+    let action_type = AggregatedActionType::CommitBlocks;
+    for block_number in from_block..=to_block {
+        let block_number = BlockNumber(block_number);
+        OperationsSchema(storage)
+            .store_aggregated_action(gen_unique_aggregated_operation(
+                block_number,
+                action_type,
+                100,
+            ))
+            .await?;
+        if confirmed {
+            OperationsSchema(storage)
+                .confirm_aggregated_operations(block_number, block_number, action_type)
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+fn block_numbers(events: &[StoredEvent]) -> Vec<i64> {
+    events.iter().map(|event| event.block_number).collect()
+}
+
+fn ids(events: &[StoredEvent]) -> Vec<i64> {
+    events.iter().map(|event| event.id).collect()
+}
+
+/// Checks that the archived events are removed from the database and are still loaded
+/// along with the stored ones by the connection having access to the archive.
+#[db_test]
+async fn archived_events(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let store = Arc::new(MemoryObjectStore::default());
+    store_events(&mut storage, 1, 6).await?;
+    let all_events = storage
+        .event_schema()
+        .load_events_in_block_range(BlockNumber(1), BlockNumber(6))
+        .await?;
+    assert_eq!(block_numbers(&all_events), vec![1, 2, 3, 4, 5, 6]);
+
+    assert_eq!(
+        storage
+            .archive_schema()
+            .get_last_archived_block(EVENTS_TABLE)
+            .await?,
+        BlockNumber(0)
+    );
+    for (from_block, to_block) in vec![(1, 2), (3, 4)] {
+        let archived = storage
+            .archive_schema()
+            .archive_events(
+                store.as_ref(),
+                BlockNumber(from_block),
+                BlockNumber(to_block),
+            )
+            .await?;
+        assert_eq!(archived, 2);
+    }
+    assert_eq!(
+        storage
+            .archive_schema()
+            .get_last_archived_block(EVENTS_TABLE)
+            .await?,
+        BlockNumber(4)
+    );
+
+    // Only the ranges overlapping with the requested one are loaded.
+    let ranges = storage
+        .archive_schema()
+        .load_archived_ranges(EVENTS_TABLE, BlockNumber(4), BlockNumber(6))
+        .await?;
+    assert_eq!(ranges.len(), 1);
+    assert_eq!((ranges[0].from_block, ranges[0].to_block), (3, 4));
+    assert_eq!(ranges[0].rows, 2);
+    assert_eq!(ranges[0].min_row_id, Some(all_events[2].id));
+    assert_eq!(ranges[0].max_row_id, Some(all_events[3].id));
+
+    // Without the archive only the events remaining in the database are loaded.
+    let events = storage
+        .event_schema()
+        .load_events_in_block_range(BlockNumber(1), BlockNumber(6))
+        .await?;
+    assert_eq!(block_numbers(&events), vec![5, 6]);
+
+    storage.set_archive(store);
+    let events = storage
+        .event_schema()
+        .load_events_in_block_range(BlockNumber(1), BlockNumber(6))
+        .await?;
+    assert_eq!(ids(&events), ids(&all_events));
+
+    // Rows of the archived objects outside of the requested range are skipped.
+    let events = storage
+        .event_schema()
+        .load_events_in_block_range(BlockNumber(2), BlockNumber(5))
+        .await?;
+    assert_eq!(block_numbers(&events), vec![2, 3, 4, 5]);
+
+    Ok(())
+}
+
+/// Checks that the events are replayed from the archive: the batches and the first event
+/// of the block include the archived events.
+#[db_test]
+async fn replay_archived_events(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let store = Arc::new(MemoryObjectStore::default());
+    store_events(&mut storage, 1, 6).await?;
+    let all_events = storage
+        .event_schema()
+        .load_events_in_block_range(BlockNumber(1), BlockNumber(6))
+        .await?;
+    storage
+        .archive_schema()
+        .archive_blocks(store.as_ref(), BlockNumber(5), 2)
+        .await?;
+
+    // Without the archive the archived events are not observed.
+    let events = storage
+        .event_schema()
+        .fetch_events_batch(EventId(0), 10)
+        .await?;
+    assert_eq!(block_numbers(&events), vec![5, 6]);
+    let first_id = storage
+        .event_schema()
+        .get_first_event_id_since_block(BlockNumber(2))
+        .await?;
+    assert_eq!(first_id, Some(EventId(all_events[4].id as u64)));
+
+    storage.set_archive(store);
+    // Archived and stored events are merged into the batches in the order of the ids.
+    let mut from = EventId(0);
+    let mut batches = Vec::new();
+    loop {
+        let events = storage.event_schema().fetch_events_batch(from, 4).await?;
+        match events.last() {
+            Some(event) => from = EventId(event.id as u64),
+            None => break,
+        }
+        batches.push(block_numbers(&events));
+    }
+    assert_eq!(batches, vec![vec![1, 2, 3, 4], vec![5, 6]]);
+
+    let events = storage
+        .event_schema()
+        .fetch_events_batch(EventId(all_events[1].id as u64), 2)
+        .await?;
+    assert_eq!(block_numbers(&events), vec![3, 4]);
+
+    // The block in the middle of the archived range, at the start of it and the stored one.
+    for (block_number, event) in vec![(2, 1), (3, 2), (5, 4)] {
+        let first_id = storage
+            .event_schema()
+            .get_first_event_id_since_block(BlockNumber(block_number))
+            .await?;
+        assert_eq!(first_id, Some(EventId(all_events[event].id as u64)));
+    }
+    assert_eq!(
+        storage
+            .event_schema()
+            .get_first_event_id_since_block(BlockNumber(7))
+            .await?,
+        None
+    );
+
+    Ok(())
+}
+
+/// Checks that the pubdata of the committed blocks is archived and read back
+/// by the connection having access to the archive.
+#[db_test]
+async fn archived_pubdata(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let store = Arc::new(MemoryObjectStore::default());
+    let action_type = AggregatedActionType::CommitBlocks;
+    store_commit_operations(&mut storage, 1, 5, true).await?;
+    store_commit_operations(&mut storage, 6, 6, false).await?;
+    let (id, operation) = OperationsSchema(&mut storage)
+        .get_aggregated_op_that_affects_block(action_type, BlockNumber(3))
+        .await?
+        .unwrap();
+
+    storage
+        .archive_schema()
+        .archive_blocks(store.as_ref(), BlockNumber(5), 2)
+        .await?;
+    assert_eq!(
+        storage
+            .archive_schema()
+            .get_last_archived_block(PUBDATA_TABLE)
+            .await?,
+        BlockNumber(4)
+    );
+    let ranges = storage
+        .archive_schema()
+        .load_archived_ranges(PUBDATA_TABLE, BlockNumber(1), BlockNumber(6))
+        .await?;
+    assert_eq!(
+        ranges.iter().map(|range| range.rows).collect::<Vec<_>>(),
+        vec![2, 2]
+    );
+
+    // Rows are kept, so the operation is still found, but its arguments can't be read
+    // without the archive.
+    assert!(OperationsSchema(&mut storage)
+        .get_aggregated_op_that_affects_block(action_type, BlockNumber(3))
+        .await
+        .is_err());
+    // Operations of the blocks which are not archived yet are not affected.
+    assert!(OperationsSchema(&mut storage)
+        .get_aggregated_op_that_affects_block(action_type, BlockNumber(5))
+        .await?
+        .is_some());
+
+    storage.set_archive(store.clone());
+    let (archived_id, archived_operation) = OperationsSchema(&mut storage)
+        .get_aggregated_op_that_affects_block(action_type, BlockNumber(3))
+        .await?
+        .unwrap();
+    assert_eq!(archived_id, id);
+    assert_eq!(
+        serde_json::to_value(archived_operation).unwrap(),
+        serde_json::to_value(operation).unwrap()
+    );
+
+    // Pubdata of the blocks is archived only once all of their commit operations are confirmed.
+    let result = storage
+        .archive_schema()
+        .archive_pubdata(store.as_ref(), BlockNumber(5), BlockNumber(6))
+        .await;
+    assert!(result.is_err());
+    assert_eq!(
+        storage
+            .archive_schema()
+            .get_last_archived_block(PUBDATA_TABLE)
+            .await?,
+        BlockNumber(4)
+    );
+
+    Ok(())
+}
@@ -0,0 +1,90 @@
+// Built-in imports
+// External imports
+use num::{BigUint, One};
+use serde_json::Value;
+// Workspace imports
+use zksync_types::{
+    AccountId, AccountUpdate, Address, BlockNumber, Nonce, Token, TokenId, TokenKind,
+};
+// Local imports
+use crate::tests::db_test;
+use crate::{chain::state::StateSchema, QueryResult, StorageProcessor};
+
+async fn export_table(storage: &mut StorageProcessor<'_>, table: &str) -> QueryResult<Vec<Value>> {
+    storage.backup_schema().open_table(table).await?;
+    let mut rows = Vec::new();
+    loop {
+        // Small batches to check that the rows are fetched in several steps.
+        let batch = storage.backup_schema().fetch_rows(1).await?;
+        if batch.is_empty() {
+            break;
+        }
+        rows.extend(batch);
+    }
+    Ok(rows)
+}
+
+/// Checks that the exported rows are restored without losing the precision of the balances.
+#[db_test]
+async fn backup_round_trip(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let token = Token::new(TokenId(1), Address::random(), "ABC", 18, TokenKind::ERC20);
+    storage.tokens_schema().store_or_update_token(token).await?;
+
+    // 2^200 + 1 can't be represented by a JSON number precisely.
+    let balance = (BigUint::one() << 200) + BigUint::one();
+    let updates = vec![
+        (
+            AccountId(1),
+            AccountUpdate::Create {
+                address: Address::random(),
+                nonce: Nonce(0),
+            },
+        ),
+        (
+            AccountId(1),
+            AccountUpdate::UpdateBalance {
+                old_nonce: Nonce(0),
+                new_nonce: Nonce(0),
+                balance_update: (TokenId(1), BigUint::from(0u64), balance.clone()),
+            },
+        ),
+    ];
+    StateSchema(&mut storage)
+        .commit_state_update(BlockNumber(1), &updates, 0)
+        .await?;
+    StateSchema(&mut storage)
+        .apply_state_update(BlockNumber(1))
+        .await?;
+
+    let accounts = export_table(&mut storage, "accounts").await?;
+    let balances = export_table(&mut storage, "balances").await?;
+    assert_eq!(accounts.len(), 1);
+    assert_eq!(balances.len(), 1);
+    assert_eq!(balances[0]["balance"], Value::String(balance.to_string()));
+
+    sqlx::query("DELETE FROM balances")
+        .execute(storage.conn())
+        .await?;
+    sqlx::query("DELETE FROM accounts")
+        .execute(storage.conn())
+        .await?;
+    assert_eq!(storage.backup_schema().count_rows("accounts").await?, 0);
+
+    storage
+        .backup_schema()
+        .insert_rows("accounts", accounts.clone())
+        .await?;
+    storage
+        .backup_schema()
+        .insert_rows("balances", balances.clone())
+        .await?;
+    storage.backup_schema().reset_sequences("accounts").await?;
+
+    assert_eq!(export_table(&mut storage, "accounts").await?, accounts);
+    assert_eq!(export_table(&mut storage, "balances").await?, balances);
+
+    // Only the backed up tables are accepted.
+    assert!(storage.backup_schema().count_rows("users").await.is_err());
+
+    Ok(())
+}
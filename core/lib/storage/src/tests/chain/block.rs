@@ -276,6 +276,7 @@ async fn test_find_block_by_height_or_hash(mut storage: StorageProcessor<'_>) ->
                 100,
                 100u32.into(),
                 Default::default(),
+                None,
             )
             .await?;
 
@@ -319,6 +320,7 @@ async fn test_find_block_by_height_or_hash(mut storage: StorageProcessor<'_>) ->
                         100,
                         100u32.into(),
                         Default::default(),
+                        None,
                     )
                     .await?;
                 EthereumSchema(&mut storage)
@@ -484,6 +486,7 @@ async fn test_block_page(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
                 100,
                 100u32.into(),
                 Default::default(),
+                None,
             )
             .await?;
         EthereumSchema(&mut storage)
@@ -517,6 +520,7 @@ async fn test_block_page(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
                     100,
                     100u32.into(),
                     Default::default(),
+                    None,
                 )
                 .await?;
             EthereumSchema(&mut storage)
@@ -619,6 +623,7 @@ async fn unconfirmed_transaction(mut storage: StorageProcessor<'_>) -> QueryResu
                 100,
                 100u32.into(),
                 Default::default(),
+                None,
             )
             .await?;
         EthereumSchema(&mut storage)
@@ -656,6 +661,7 @@ async fn unconfirmed_transaction(mut storage: StorageProcessor<'_>) -> QueryResu
                     100,
                     100u32.into(),
                     Default::default(),
+                    None,
                 )
                 .await?;
             EthereumSchema(&mut storage)
@@ -1139,6 +1145,97 @@ async fn test_remove_pending_block(mut storage: StorageProcessor<'_>) -> QueryRe
     Ok(())
 }
 
+/// Check that the blocks are reverted along with their operations, so the Ethereum sender
+/// restored from the database continues from the last kept block.
+#[db_test]
+async fn test_revert_blocks(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    use zksync_types::block::PendingBlock;
+
+    // Commit 4 blocks and execute the first 2 of them.
+    for block_number in 1..=4 {
+        commit_block(&mut storage, BlockNumber(block_number)).await?;
+    }
+    for block_number in 1..=2 {
+        verify_block(&mut storage, BlockNumber(block_number)).await?;
+    }
+    // Commitment of the 5th block is not sent yet, and the 6th block is pending.
+    BlockSchema(&mut storage)
+        .save_full_block(gen_sample_block(
+            BlockNumber(5),
+            BLOCK_SIZE_CHUNKS,
+            Default::default(),
+        ))
+        .await?;
+    OperationsSchema(&mut storage)
+        .store_aggregated_action(gen_unique_aggregated_operation(
+            BlockNumber(5),
+            AggregatedActionType::CommitBlocks,
+            BLOCK_SIZE_CHUNKS,
+        ))
+        .await?;
+    BlockSchema(&mut storage)
+        .save_pending_block(PendingBlock {
+            number: BlockNumber(6),
+            chunks_left: 10,
+            unprocessed_priority_op_before: 0,
+            pending_block_iteration: 1,
+            success_operations: Vec::new(),
+            failed_txs: Vec::new(),
+            timestamp: 0,
+        })
+        .await?;
+
+    // Revert the blocks with numbers greater than 3.
+    BlockSchema(&mut storage)
+        .revert_blocks(BlockNumber(3))
+        .await?;
+
+    assert_eq!(
+        BlockSchema(&mut storage).get_last_saved_block().await?,
+        BlockNumber(3)
+    );
+    assert!(BlockSchema(&mut storage)
+        .get_block(BlockNumber(4))
+        .await?
+        .is_none());
+    assert!(!BlockSchema(&mut storage).pending_block_exists().await?);
+    assert_eq!(
+        BlockSchema(&mut storage).get_last_committed_block().await?,
+        BlockNumber(3)
+    );
+    assert_eq!(
+        BlockSchema(&mut storage)
+            .get_last_committed_confirmed_block()
+            .await?,
+        BlockNumber(3)
+    );
+    // Executed blocks are not affected.
+    assert_eq!(
+        BlockSchema(&mut storage)
+            .get_last_verified_confirmed_block()
+            .await?,
+        BlockNumber(2)
+    );
+
+    // Operations of the reverted blocks are not sent again after the restart.
+    EthereumSchema(&mut storage)
+        .restore_unprocessed_operations()
+        .await?;
+    assert!(EthereumSchema(&mut storage)
+        .load_unprocessed_operations()
+        .await?
+        .is_empty());
+    assert!(EthereumSchema(&mut storage)
+        .load_unconfirmed_operations()
+        .await?
+        .is_empty());
+    let stats = EthereumSchema(&mut storage).load_stats().await?;
+    assert_eq!(stats.last_committed_block, 3);
+    assert_eq!(stats.last_executed_block, 2);
+
+    Ok(())
+}
+
 /// Checks that `get_block_transactions_page` method works correctly.
 #[db_test]
 async fn test_get_block_transactions_page(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
@@ -1,12 +1,13 @@
 // External imports
 use chrono::Utc;
+use num::{rational::Ratio, BigUint};
 // Workspace imports
 use zksync_crypto::rand::{Rng, SeedableRng, XorShiftRng};
 use zksync_types::{
     block::{Block, ExecutedOperations},
     mempool::SignedTxVariant,
     priority_ops::FullExit,
-    tx::{ChangePubKey, Transfer, TxHash, Withdraw},
+    tx::{ChangePubKey, TimeRange, Transfer, TxHash, Withdraw},
     AccountId, Address, BlockNumber, ExecutedPriorityOp, ExecutedTx, FullExitOp, Nonce, PriorityOp,
     SignedZkSyncTx, TokenId, ZkSyncOp, ZkSyncPriorityOp, ZkSyncTx, H256,
 };
@@ -225,6 +226,157 @@ async fn store_load_batch(mut storage: StorageProcessor<'_>) -> QueryResult<()>
     Ok(())
 }
 
+/// Checks that queued transactions can be found by the account ID and nonce and replaced.
+#[db_test]
+async fn replace_tx(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let txs = zksync_txs();
+    let (queued_tx, replacement) = (&txs[0], &txs[1]);
+    MempoolSchema(&mut storage).insert_tx(queued_tx).await?;
+
+    let found = MempoolSchema(&mut storage)
+        .get_queued_tx_by_nonce(queued_tx.account_id().unwrap(), queued_tx.nonce())
+        .await?
+        .expect("Queued tx is not found");
+    assert_eq!(found.tx.hash(), queued_tx.hash());
+    assert_eq!(found.batch_id, None);
+
+    // There is no tx with such a nonce.
+    assert!(MempoolSchema(&mut storage)
+        .get_queued_tx_by_nonce(queued_tx.account_id().unwrap(), queued_tx.nonce() + 1)
+        .await?
+        .is_none());
+
+    assert!(
+        MempoolSchema(&mut storage)
+            .replace_tx(queued_tx.hash(), replacement)
+            .await?
+    );
+    let txs_from_db = MempoolSchema(&mut storage).load_txs(&[]).await?;
+    assert_eq!(txs_from_db.len(), 1);
+    assert_eq!(
+        unwrap_tx(txs_from_db[0].clone()).hash(),
+        replacement.hash(),
+        "tx was not replaced"
+    );
+
+    // Replaced transaction is not in the mempool anymore.
+    assert!(
+        !MempoolSchema(&mut storage)
+            .replace_tx(queued_tx.hash(), replacement)
+            .await?
+    );
+
+    // Transactions from batches are found, but can't be replaced.
+    let batch = gen_transfers(2);
+    MempoolSchema(&mut storage)
+        .insert_batch(&batch, vec![])
+        .await?;
+    let found = MempoolSchema(&mut storage)
+        .get_queued_tx_by_nonce(batch[0].account_id().unwrap(), batch[0].nonce())
+        .await?
+        .expect("Queued batch tx is not found");
+    assert!(found.batch_id.is_some());
+    assert!(
+        !MempoolSchema(&mut storage)
+            .replace_tx(batch[0].hash(), replacement)
+            .await?
+    );
+
+    Ok(())
+}
+
+/// Checks that the transactions proposed before the server restart are returned to the queue.
+#[db_test]
+async fn restore_proposed_txs(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let txs = gen_transfers(3);
+    for tx in &txs[..2] {
+        MempoolSchema(&mut storage).insert_tx(tx).await?;
+    }
+    MempoolSchema(&mut storage)
+        .mark_txs_proposed(&[txs[0].hash()])
+        .await?;
+
+    assert_eq!(MempoolSchema(&mut storage).restore_proposed_txs().await?, 1);
+    // There is nothing to restore anymore.
+    assert_eq!(MempoolSchema(&mut storage).restore_proposed_txs().await?, 0);
+
+    // Restored transaction can be replaced again.
+    assert!(
+        MempoolSchema(&mut storage)
+            .replace_tx(txs[0].hash(), &txs[2])
+            .await?
+    );
+    let txs_from_db = MempoolSchema(&mut storage).load_txs(&[]).await?;
+    let hashes: Vec<_> = txs_from_db
+        .into_iter()
+        .map(|tx| unwrap_tx(tx).hash())
+        .collect();
+    assert_eq!(hashes, vec![txs[1].hash(), txs[2].hash()]);
+
+    Ok(())
+}
+
+/// Checks that only the transactions not valid yet are returned as scheduled ones.
+#[db_test]
+async fn scheduled_txs(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    const NOW: u64 = 1_000;
+    let account_id = AccountId(1);
+
+    let txs: Vec<_> = [(Nonce(2), NOW + 10), (Nonce(0), 0), (Nonce(1), NOW + 20)]
+        .iter()
+        .map(|&(nonce, valid_from)| {
+            let transfer = Transfer::new(
+                account_id,
+                Address::random(),
+                Address::random(),
+                TokenId(0),
+                100u32.into(),
+                10u32.into(),
+                nonce,
+                TimeRange::new(valid_from, u64::MAX),
+                None,
+            );
+            SignedZkSyncTx {
+                tx: ZkSyncTx::Transfer(Box::new(transfer)),
+                eth_sign_data: None,
+                created_at: Utc::now(),
+            }
+        })
+        .collect();
+    for tx in &txs {
+        MempoolSchema(&mut storage).insert_tx(tx).await?;
+    }
+
+    let scheduled: Vec<_> = MempoolSchema(&mut storage)
+        .get_scheduled_txs(account_id, NOW)
+        .await?
+        .into_iter()
+        .map(|tx| tx.hash())
+        .collect();
+    assert_eq!(scheduled, vec![txs[2].hash(), txs[0].hash()]);
+
+    // Once the time passes, transactions are not scheduled anymore.
+    assert_eq!(
+        MempoolSchema(&mut storage)
+            .get_scheduled_txs(account_id, NOW + 10)
+            .await?
+            .len(),
+        1
+    );
+    assert!(MempoolSchema(&mut storage)
+        .get_scheduled_txs(AccountId(2), 0)
+        .await?
+        .is_empty());
+
+    // Only the transactions valid at the moment can be executed.
+    let mut schema = MempoolSchema(&mut storage);
+    assert_eq!(schema.get_executable_mempool_size(NOW).await?, 1);
+    assert_eq!(schema.get_executable_mempool_size(NOW + 10).await?, 2);
+    assert_eq!(schema.get_executable_mempool_size(NOW + 20).await?, 3);
+
+    Ok(())
+}
+
 /// Checks that removed txs won't appear on the next load.
 #[db_test]
 async fn remove_txs(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
@@ -258,6 +410,205 @@ async fn remove_txs(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
     Ok(())
 }
 
+/// Checks that the queued transaction is removed along with the rest of its batch.
+#[db_test]
+async fn remove_queued_tx(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let txs = gen_transfers(4);
+    let (alone_txs, batch) = txs.split_at(2);
+
+    for tx in alone_txs {
+        MempoolSchema(&mut storage).insert_tx(tx).await?;
+    }
+    MempoolSchema(&mut storage)
+        .insert_batch(batch, vec![])
+        .await?;
+
+    // Removing a single transaction doesn't affect the others.
+    let removed = MempoolSchema(&mut storage)
+        .remove_queued_tx(alone_txs[0].hash())
+        .await?;
+    assert_eq!(removed, 1);
+
+    // Removing a transaction from the batch removes the whole batch.
+    let removed = MempoolSchema(&mut storage)
+        .remove_queued_tx(batch[1].hash())
+        .await?;
+    assert_eq!(removed, batch.len() as u64);
+
+    // Unknown transaction is not an error.
+    let removed = MempoolSchema(&mut storage)
+        .remove_queued_tx(batch[1].hash())
+        .await?;
+    assert_eq!(removed, 0);
+
+    let txs_from_db = MempoolSchema(&mut storage).load_txs(&[]).await?;
+    assert_eq!(txs_from_db.len(), 1);
+    assert_eq!(
+        unwrap_tx(txs_from_db[0].clone()).hash(),
+        alone_txs[1].hash()
+    );
+
+    Ok(())
+}
+
+/// Checks that only the single queued transactions can be cancelled.
+#[db_test]
+async fn cancel_queued_tx(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let txs = gen_transfers(4);
+    let (alone_txs, batch) = txs.split_at(2);
+
+    for tx in alone_txs {
+        MempoolSchema(&mut storage).insert_tx(tx).await?;
+    }
+    MempoolSchema(&mut storage)
+        .insert_batch(batch, vec![])
+        .await?;
+
+    assert!(
+        MempoolSchema(&mut storage)
+            .cancel_queued_tx(alone_txs[0].hash())
+            .await?
+    );
+    // Cancelled transaction is not in the mempool anymore.
+    assert!(
+        !MempoolSchema(&mut storage)
+            .cancel_queued_tx(alone_txs[0].hash())
+            .await?
+    );
+    // Transactions from batches can't be cancelled.
+    assert!(
+        !MempoolSchema(&mut storage)
+            .cancel_queued_tx(batch[0].hash())
+            .await?
+    );
+
+    let txs_from_db = MempoolSchema(&mut storage).load_txs(&[]).await?;
+    assert_eq!(txs_from_db.len(), 2);
+    assert_eq!(
+        unwrap_tx(txs_from_db[0].clone()).hash(),
+        alone_txs[1].hash()
+    );
+
+    Ok(())
+}
+
+/// Checks that the transactions proposed to the state keeper can't be cancelled or replaced anymore.
+#[db_test]
+async fn proposed_txs(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let txs = gen_transfers(3);
+    for tx in &txs[..2] {
+        MempoolSchema(&mut storage).insert_tx(tx).await?;
+    }
+
+    // The missing transaction is not marked.
+    let marked = MempoolSchema(&mut storage)
+        .mark_txs_proposed(&[txs[0].hash(), txs[2].hash()])
+        .await?;
+    assert_eq!(marked, vec![txs[0].hash()]);
+
+    assert!(
+        !MempoolSchema(&mut storage)
+            .cancel_queued_tx(txs[0].hash())
+            .await?
+    );
+    assert!(
+        !MempoolSchema(&mut storage)
+            .replace_tx(txs[0].hash(), &txs[2])
+            .await?
+    );
+    // Not proposed transaction can still be cancelled.
+    assert!(
+        MempoolSchema(&mut storage)
+            .cancel_queued_tx(txs[1].hash())
+            .await?
+    );
+
+    let txs_from_db = MempoolSchema(&mut storage).load_txs(&[]).await?;
+    assert_eq!(txs_from_db.len(), 1);
+    assert_eq!(unwrap_tx(txs_from_db[0].clone()).hash(), txs[0].hash());
+
+    Ok(())
+}
+
+fn transfer_from(account_id: u32, nonce: u32) -> SignedZkSyncTx {
+    let transfer = Transfer::new(
+        AccountId(account_id),
+        Address::repeat_byte(1),
+        Address::repeat_byte(2),
+        TokenId(0),
+        100u32.into(),
+        10u32.into(),
+        Nonce(nonce),
+        Default::default(),
+        None,
+    );
+
+    SignedZkSyncTx {
+        tx: ZkSyncTx::Transfer(Box::new(transfer)),
+        eth_sign_data: None,
+        created_at: Utc::now(),
+    }
+}
+
+/// Checks that only the cheapest single transactions with the highest nonce of their account
+/// are selected for the eviction.
+#[db_test]
+async fn eviction_candidates(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let priority = |value: u32| Ratio::from_integer(BigUint::from(value));
+
+    let first_tx_a = transfer_from(1, 1);
+    let second_tx_a = transfer_from(1, 2);
+    let tx_b = transfer_from(2, 1);
+    let proposed_tx = transfer_from(3, 1);
+    let queued_txs = vec![
+        (&first_tx_a, 1),
+        (&second_tx_a, 2),
+        (&tx_b, 3),
+        (&proposed_tx, 0),
+    ];
+    for (tx, value) in queued_txs {
+        MempoolSchema(&mut storage).insert_tx(tx).await?;
+        MempoolSchema(&mut storage)
+            .set_tx_fee_priority(tx.hash(), &priority(value))
+            .await?;
+    }
+    MempoolSchema(&mut storage)
+        .mark_txs_proposed(&[proposed_tx.hash()])
+        .await?;
+    // Batches are never evicted.
+    MempoolSchema(&mut storage)
+        .insert_batch(&[transfer_from(4, 1), transfer_from(5, 1)], Vec::new())
+        .await?;
+
+    // The first transaction of the account is never evicted, since it would block the next one.
+    assert_eq!(
+        MempoolSchema(&mut storage)
+            .load_eviction_candidates(&priority(5), 1)
+            .await?,
+        vec![second_tx_a.hash()]
+    );
+    assert_eq!(
+        MempoolSchema(&mut storage)
+            .load_eviction_candidates(&priority(5), 3)
+            .await?,
+        vec![second_tx_a.hash(), tx_b.hash()]
+    );
+
+    // Only the transactions paying strictly less than the new one can be evicted.
+    assert_eq!(
+        MempoolSchema(&mut storage)
+            .load_eviction_candidates(&priority(3), 3)
+            .await?,
+        vec![second_tx_a.hash()]
+    );
+    assert!(MempoolSchema(&mut storage)
+        .load_eviction_candidates(&priority(2), 1)
+        .await?
+        .is_empty());
+
+    Ok(())
+}
+
 /// Checks that already committed txs are removed by `collect_garbage` method.
 #[db_test]
 async fn collect_garbage(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
@@ -3,6 +3,7 @@ mod block;
 mod mempool;
 mod operations;
 mod operations_ext;
+mod pruning;
 mod state;
 mod tree_cache;
 
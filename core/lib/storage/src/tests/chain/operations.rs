@@ -2,8 +2,8 @@
 use chrono::{Duration, Utc};
 // Workspace imports
 use zksync_types::{
-    aggregated_operations::AggregatedActionType, Address, BlockNumber, Deposit, SequentialTxId,
-    ZkSyncPriorityOp, H256,
+    aggregated_operations::{AggregatedActionType, AggregatedOperation},
+    Address, BlockNumber, Deposit, SequentialTxId, ZkSyncPriorityOp, H256,
 };
 // Local imports
 use crate::chain::mempool::MempoolSchema;
@@ -46,6 +46,40 @@ async fn aggregated_operations(mut storage: StorageProcessor<'_>) -> QueryResult
     Ok(())
 }
 
+/// Checks that the arguments of the stored aggregated operation are replaced.
+#[db_test]
+async fn update_aggregated_operation(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let action_type = AggregatedActionType::PublishProofBlocksOnchain;
+    let mut operation = gen_unique_aggregated_operation(BlockNumber(1), action_type, 100);
+    OperationsSchema(&mut storage)
+        .store_aggregated_action(operation.clone())
+        .await?;
+    let id = OperationsSchema(&mut storage)
+        .get_stored_aggregated_operation(BlockNumber(1), action_type)
+        .await
+        .unwrap()
+        .id;
+
+    if let AggregatedOperation::PublishProofBlocksOnchain(op) = &mut operation {
+        op.proof.aggregated_input = 42.into();
+    }
+    OperationsSchema(&mut storage)
+        .update_aggregated_operation(id, &operation)
+        .await?;
+
+    let (stored_id, stored_operation) = OperationsSchema(&mut storage)
+        .get_aggregated_op_that_affects_block(action_type, BlockNumber(1))
+        .await?
+        .unwrap();
+    assert_eq!(stored_id, id);
+    assert_eq!(
+        serde_json::to_value(stored_operation).unwrap(),
+        serde_json::to_value(operation).unwrap()
+    );
+
+    Ok(())
+}
+
 /// Checks the save&load routine for executed operations.
 #[db_test]
 async fn executed_operations(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
@@ -563,3 +597,60 @@ async fn test_remove_eth_unprocessed_aggregated_ops(
 
     Ok(())
 }
+
+/// Checks that the partitions of the transactions history are detached by the sequence number.
+#[db_test]
+async fn tx_filters_partitions(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    const PARTITION_SIZE: i64 = 10_000_000;
+
+    // Partitions for the current sequence numbers are created by the migration.
+    OperationsSchema(&mut storage)
+        .create_tx_filters_partitions()
+        .await?;
+    OperationsSchema(&mut storage)
+        .store_executed_tx(NewExecutedTransaction {
+            block_number: 1,
+            tx_hash: vec![0xaa; 32],
+            tx: Default::default(),
+            operation: Default::default(),
+            from_account: Default::default(),
+            to_account: None,
+            success: true,
+            fail_reason: None,
+            block_index: Some(0),
+            primary_account_address: Default::default(),
+            nonce: Default::default(),
+            created_at: Utc::now(),
+            eth_sign_data: None,
+            batch_id: None,
+            affected_accounts: vec![Address::zero().as_bytes().to_vec()],
+            used_tokens: vec![0],
+        })
+        .await?;
+    let history_len = storage
+        .chain()
+        .operations_ext_schema()
+        .get_account_transactions_count(Address::zero(), None, None)
+        .await?;
+    assert_eq!(history_len, 1);
+
+    // The history preceding the migration is kept in the legacy partition covering the range
+    // of the sequence numbers the migration was applied in, on the fresh database it's the first one.
+    // Partition is only detached once all its sequence numbers precede the given one.
+    let detached = OperationsSchema(&mut storage)
+        .detach_tx_filters_partitions(PARTITION_SIZE - 1)
+        .await?;
+    assert!(detached.is_empty());
+    let detached = OperationsSchema(&mut storage)
+        .detach_tx_filters_partitions(PARTITION_SIZE)
+        .await?;
+    assert_eq!(detached, vec!["tx_filters_legacy".to_string()]);
+    let history_len = storage
+        .chain()
+        .operations_ext_schema()
+        .get_account_transactions_count(Address::zero(), None, None)
+        .await?;
+    assert_eq!(history_len, 0);
+
+    Ok(())
+}
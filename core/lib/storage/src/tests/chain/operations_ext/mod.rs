@@ -3,7 +3,9 @@ use std::collections::HashMap;
 // External imports
 // Workspace imports
 use zksync_api_types::v02::{
-    pagination::{AccountTxsRequest, ApiEither, PaginationDirection, PaginationQuery},
+    pagination::{
+        AccountTxsRequest, ApiEither, PaginationDirection, PaginationQuery, WithdrawalsRequest,
+    },
     transaction::{Receipt, TxInBlockStatus},
 };
 use zksync_crypto::{franklin_crypto::bellman::pairing::ff::Field, Fr};
@@ -110,7 +112,14 @@ async fn confirm_eth_op(
     let eth_tx_hash = dummy_ethereum_tx_hash(op.0);
     let response = storage
         .ethereum_schema()
-        .save_new_eth_tx(op_type, Some(op), 100, 100u32.into(), Default::default())
+        .save_new_eth_tx(
+            op_type,
+            Some(op),
+            100,
+            100u32.into(),
+            Default::default(),
+            None,
+        )
         .await?;
     storage
         .ethereum_schema()
@@ -825,6 +834,82 @@ async fn account_last_tx_hash(mut storage: StorageProcessor<'_>) -> QueryResult<
     Ok(())
 }
 
+/// Test the withdrawals lookup by the L1 recipient.
+#[db_test]
+async fn withdrawals_to_address(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let mut setup = TransactionsHistoryTestSetup::new();
+    let from = setup.from_zksync_account.address;
+    let to = setup.to_zksync_account.address;
+    setup.add_block(1);
+    setup.add_block(2);
+    commit_schema_data(&mut storage, &setup).await?;
+
+    // Withdrawals sent by `from` to `to` are found by the recipient.
+    let last_tx_hash = storage
+        .chain()
+        .operations_ext_schema()
+        .get_last_withdrawal_tx_hash(to)
+        .await?;
+    assert_eq!(last_tx_hash, Some(setup.get_tx_hash(1, 7)));
+    let count = storage
+        .chain()
+        .operations_ext_schema()
+        .get_withdrawals_count(to)
+        .await?;
+    assert_eq!(count, 4);
+
+    let txs = storage
+        .chain()
+        .operations_ext_schema()
+        .get_withdrawals_to_address(&PaginationQuery {
+            from: WithdrawalsRequest {
+                address: to,
+                tx_hash: ApiEither::from(setup.get_tx_hash(1, 7)),
+            },
+            limit: 3,
+            direction: PaginationDirection::Older,
+        })
+        .await?
+        .unwrap();
+    let hashes: Vec<TxHash> = txs.into_iter().map(|tx| tx.tx_hash).collect();
+    assert_eq!(
+        hashes,
+        vec![
+            setup.get_tx_hash(1, 7),
+            setup.get_tx_hash(1, 5),
+            setup.get_tx_hash(0, 7)
+        ]
+    );
+
+    // Full exits are withdrawals to the account owner.
+    let count = storage
+        .chain()
+        .operations_ext_schema()
+        .get_withdrawals_count(from)
+        .await?;
+    assert_eq!(count, 2);
+    let txs = storage
+        .chain()
+        .operations_ext_schema()
+        .get_withdrawals_to_address(&PaginationQuery {
+            from: WithdrawalsRequest {
+                address: from,
+                tx_hash: ApiEither::from(setup.get_tx_hash(0, 9)),
+            },
+            limit: 10,
+            direction: PaginationDirection::Newer,
+        })
+        .await?
+        .unwrap();
+    let hashes: Vec<TxHash> = txs.into_iter().map(|tx| tx.tx_hash).collect();
+    assert_eq!(
+        hashes,
+        vec![setup.get_tx_hash(0, 9), setup.get_tx_hash(1, 9)]
+    );
+
+    Ok(())
+}
+
 /// Test `get_block_last_tx_hash` method
 #[db_test]
 async fn block_last_tx_hash(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
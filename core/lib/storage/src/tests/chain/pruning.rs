@@ -0,0 +1,347 @@
+// Built-in imports
+use std::collections::HashSet;
+// External imports
+// Workspace imports
+use zksync_types::{
+    aggregated_operations::AggregatedActionType, AccountMap, AccountUpdate, BlockNumber,
+};
+// Local imports
+use super::block::apply_random_updates;
+use crate::{
+    chain::{
+        account::AccountSchema,
+        block::BlockSchema,
+        operations::OperationsSchema,
+        pruning::{PruningSchema, STATE_SNAPSHOT_CHUNK_SIZE},
+        state::StateSchema,
+    },
+    test_data::{gen_sample_block, gen_unique_aggregated_operation, BLOCK_SIZE_CHUNKS},
+    tests::{create_rng, db_test},
+    QueryResult, StorageProcessor,
+};
+
+async fn count_snapshot_chunks(
+    storage: &mut StorageProcessor<'_>,
+    block_number: BlockNumber,
+) -> QueryResult<i64> {
+    let count =
+        sqlx::query_scalar("SELECT COUNT(*) FROM state_snapshot_chunks WHERE block_number = $1")
+            .bind(i64::from(*block_number))
+            .fetch_one(storage.conn())
+            .await?;
+    Ok(count)
+}
+
+/// Marks the blocks as executed and applies their state updates, the way the executed blocks are stored.
+async fn execute_blocks(
+    storage: &mut StorageProcessor<'_>,
+    from_block: u32,
+    to_block: u32,
+) -> QueryResult<()> {
+    for block_number in (from_block..=to_block).map(BlockNumber) {
+        OperationsSchema(storage)
+            .store_aggregated_action(gen_unique_aggregated_operation(
+                block_number,
+                AggregatedActionType::ExecuteBlocks,
+                BLOCK_SIZE_CHUNKS,
+            ))
+            .await?;
+        OperationsSchema(storage)
+            .confirm_aggregated_operations(
+                block_number,
+                block_number,
+                AggregatedActionType::ExecuteBlocks,
+            )
+            .await?;
+        StateSchema(storage)
+            .apply_state_update(block_number)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Checks that the state snapshots are stored, loaded and removed correctly.
+#[db_test]
+async fn state_snapshots(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let mut rng = create_rng();
+    let (accounts_block_1, _) = apply_random_updates(AccountMap::default(), &mut rng);
+    let (accounts_block_3, _) = apply_random_updates(accounts_block_1.clone(), &mut rng);
+
+    PruningSchema(&mut storage)
+        .store_state_snapshot(BlockNumber(1), &accounts_block_1)
+        .await?;
+    PruningSchema(&mut storage)
+        .store_state_snapshot(BlockNumber(3), &accounts_block_3)
+        .await?;
+    assert_eq!(
+        PruningSchema(&mut storage)
+            .get_last_state_snapshot_block()
+            .await?,
+        Some(BlockNumber(3))
+    );
+
+    // Accounts are split into the chunks by their ids.
+    let chunks = accounts_block_3
+        .keys()
+        .map(|account_id| **account_id / STATE_SNAPSHOT_CHUNK_SIZE)
+        .collect::<HashSet<_>>();
+    assert!(chunks.len() > 1);
+    assert_eq!(
+        count_snapshot_chunks(&mut storage, BlockNumber(3)).await?,
+        chunks.len() as i64
+    );
+    // The stored snapshot is never overwritten.
+    PruningSchema(&mut storage)
+        .store_state_snapshot(BlockNumber(3), &accounts_block_1)
+        .await?;
+
+    // The latest snapshot not after the requested block is loaded.
+    assert_eq!(
+        PruningSchema(&mut storage)
+            .load_state_snapshot(None)
+            .await?,
+        Some((BlockNumber(3), accounts_block_3))
+    );
+    assert_eq!(
+        PruningSchema(&mut storage)
+            .load_state_snapshot(Some(BlockNumber(2)))
+            .await?,
+        Some((BlockNumber(1), accounts_block_1))
+    );
+    assert_eq!(
+        PruningSchema(&mut storage)
+            .load_state_snapshot(Some(BlockNumber(0)))
+            .await?,
+        None
+    );
+
+    PruningSchema(&mut storage)
+        .remove_old_state_snapshots(BlockNumber(3))
+        .await?;
+    assert_eq!(
+        PruningSchema(&mut storage)
+            .load_state_snapshot(Some(BlockNumber(2)))
+            .await?,
+        None
+    );
+    assert_eq!(
+        count_snapshot_chunks(&mut storage, BlockNumber(1)).await?,
+        0
+    );
+
+    Ok(())
+}
+
+/// Checks that only the account diffs of the pruned blocks are removed.
+#[db_test]
+async fn prune_block_data(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let mut rng = create_rng();
+    let mut accounts = AccountMap::default();
+    let mut pruned_balance_updates = 0;
+    for block_number in 1..=3 {
+        let (new_accounts, updates) = apply_random_updates(accounts, &mut rng);
+        accounts = new_accounts;
+        StateSchema(&mut storage)
+            .commit_state_update(BlockNumber(block_number), &updates, 0)
+            .await?;
+
+        if block_number <= 2 {
+            pruned_balance_updates += updates
+                .iter()
+                .filter(|(_, update)| matches!(update, AccountUpdate::UpdateBalance { .. }))
+                .count() as u64;
+        }
+    }
+
+    let pruned = PruningSchema(&mut storage)
+        .prune_block_data(BlockNumber(2))
+        .await?;
+    assert_eq!(pruned.balance_updates, pruned_balance_updates);
+
+    // Balance updates of the pruned blocks are gone, the rest are kept.
+    let block_2_diff = StateSchema(&mut storage)
+        .load_state_diff_for_block(BlockNumber(2))
+        .await?;
+    assert!(block_2_diff
+        .iter()
+        .all(|(_, update)| !matches!(update, AccountUpdate::UpdateBalance { .. })));
+    let block_3_diff = StateSchema(&mut storage)
+        .load_state_diff_for_block(BlockNumber(3))
+        .await?;
+    assert!(block_3_diff
+        .iter()
+        .any(|(_, update)| matches!(update, AccountUpdate::UpdateBalance { .. })));
+
+    // Pruning is idempotent.
+    let pruned = PruningSchema(&mut storage)
+        .prune_block_data(BlockNumber(2))
+        .await?;
+    assert_eq!(pruned, Default::default());
+
+    Ok(())
+}
+
+/// Checks that the historical account state is restored from the snapshot and the retained diffs.
+#[db_test]
+async fn account_state_at_block(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let mut rng = create_rng();
+    let mut accounts = AccountMap::default();
+    let mut states = Vec::new();
+    for block_number in 1..=3 {
+        let (new_accounts, updates) = apply_random_updates(accounts, &mut rng);
+        accounts = new_accounts;
+        StateSchema(&mut storage)
+            .commit_state_update(BlockNumber(block_number), &updates, 0)
+            .await?;
+        states.push((BlockNumber(block_number), accounts.clone()));
+    }
+
+    // State is restored from the diffs only.
+    for (block_number, accounts) in &states {
+        for (account_id, account) in accounts {
+            let (_, restored) = AccountSchema(&mut storage)
+                .account_state_at_block(*account_id, *block_number)
+                .await?;
+            assert_eq!(restored.as_ref(), Some(account));
+        }
+    }
+
+    // State is restored from the snapshot and the diffs following it.
+    PruningSchema(&mut storage)
+        .store_state_snapshot(BlockNumber(1), &states[0].1)
+        .await?;
+    PruningSchema(&mut storage)
+        .prune_block_data(BlockNumber(1))
+        .await?;
+    for (block_number, accounts) in &states {
+        for (account_id, account) in accounts {
+            let (_, restored) = AccountSchema(&mut storage)
+                .account_state_at_block(*account_id, *block_number)
+                .await?;
+            assert_eq!(restored.as_ref(), Some(account));
+        }
+    }
+
+    // Accounts created later don't exist at the earlier blocks.
+    let (block_number, accounts) = &states[2];
+    for account_id in accounts.keys() {
+        if !states[0].1.contains_key(account_id) {
+            let (last_update_block, restored) = AccountSchema(&mut storage)
+                .account_state_at_block(*account_id, BlockNumber(1))
+                .await?;
+            assert_eq!(restored, None);
+            assert_eq!(last_update_block, BlockNumber(1));
+
+            let (last_update_block, _) = AccountSchema(&mut storage)
+                .account_state_at_block(*account_id, *block_number)
+                .await?;
+            assert!(last_update_block > BlockNumber(1));
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks that the data of the executed blocks is pruned up to the stored snapshot,
+/// and the state of the following blocks is still restored.
+#[db_test]
+async fn prune_executed_blocks(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let mut rng = create_rng();
+    let mut accounts = AccountMap::default();
+    let mut states = vec![accounts.clone()];
+    let mut balance_updates = vec![0];
+    for block_number in 1..=5 {
+        let (new_accounts, updates) = apply_random_updates(accounts, &mut rng);
+        accounts = new_accounts;
+        BlockSchema(&mut storage)
+            .save_full_block(gen_sample_block(
+                BlockNumber(block_number),
+                BLOCK_SIZE_CHUNKS,
+                Default::default(),
+            ))
+            .await?;
+        StateSchema(&mut storage)
+            .commit_state_update(BlockNumber(block_number), &updates, 0)
+            .await?;
+        states.push(accounts.clone());
+        balance_updates.push(
+            updates
+                .iter()
+                .filter(|(_, update)| matches!(update, AccountUpdate::UpdateBalance { .. }))
+                .count() as u64,
+        );
+    }
+
+    // Nothing is pruned before the blocks are executed.
+    let pruned = PruningSchema(&mut storage)
+        .prune_executed_blocks(1, 2)
+        .await?;
+    assert_eq!(pruned, None);
+    assert_eq!(
+        PruningSchema(&mut storage)
+            .get_last_state_snapshot_block()
+            .await?,
+        None
+    );
+
+    // The snapshot of the last executed block is stored, but the data is only pruned
+    // up to the snapshot preceding the retained blocks.
+    execute_blocks(&mut storage, 1, 3).await?;
+    let pruned = PruningSchema(&mut storage)
+        .prune_executed_blocks(1, 2)
+        .await?;
+    assert_eq!(pruned, None);
+    assert_eq!(
+        PruningSchema(&mut storage)
+            .load_state_snapshot(None)
+            .await?,
+        Some((BlockNumber(3), states[3].clone()))
+    );
+
+    execute_blocks(&mut storage, 4, 5).await?;
+    let (last_pruned_block, pruned) = PruningSchema(&mut storage)
+        .prune_executed_blocks(1, 2)
+        .await?
+        .unwrap();
+    assert_eq!(last_pruned_block, BlockNumber(3));
+    assert_eq!(
+        pruned.balance_updates,
+        balance_updates[1..=3].iter().sum::<u64>()
+    );
+    assert_eq!(
+        PruningSchema(&mut storage)
+            .get_first_state_snapshot_block()
+            .await?,
+        Some(BlockNumber(3))
+    );
+    assert_eq!(
+        PruningSchema(&mut storage)
+            .get_last_state_snapshot_block()
+            .await?,
+        Some(BlockNumber(5))
+    );
+
+    // State of the blocks following the pruned ones is restored from the snapshot and the retained diffs.
+    for block_number in 3..=5 {
+        let (block, state) = StateSchema(&mut storage)
+            .load_committed_state(Some(BlockNumber(block_number)))
+            .await?;
+        assert_eq!(block, BlockNumber(block_number));
+        assert_eq!(state, states[block_number as usize]);
+    }
+    let (block, state) = StateSchema(&mut storage).load_committed_state(None).await?;
+    assert_eq!((block, state), (BlockNumber(5), states[5].clone()));
+    // State of the pruned blocks isn't available anymore.
+    assert!(StateSchema(&mut storage)
+        .load_committed_state(Some(BlockNumber(2)))
+        .await
+        .is_err());
+
+    // Pruning is idempotent.
+    let pruned = PruningSchema(&mut storage)
+        .prune_executed_blocks(1, 2)
+        .await?;
+    assert_eq!(pruned, Some((BlockNumber(3), Default::default())));
+
+    Ok(())
+}
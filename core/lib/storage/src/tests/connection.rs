@@ -0,0 +1,60 @@
+// Built-in deps
+use std::time::Duration;
+// Local imports
+use crate::{ConnectionPool, PoolStatus, QueryResult};
+
+/// Waits until some task waits for a connection of the pool.
+async fn wait_for_pending_checkout(pool: &ConnectionPool) {
+    tokio::time::timeout(Duration::from_secs(10), async {
+        while pool.status().pending == 0 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("No task waits for a connection");
+}
+
+/// Checks that the pool reports its utilization and becomes saturated once
+/// too many tasks wait for a connection.
+#[tokio::test]
+#[cfg_attr(not(feature = "db_test"), ignore)]
+async fn pool_saturation() -> QueryResult<()> {
+    let pool = ConnectionPool::new(Some(1)).with_max_pending_checkouts(1);
+    let unlimited_pool = pool.clone().with_max_pending_checkouts(0);
+
+    let storage = pool.access_storage().await?;
+    assert_eq!(
+        pool.status(),
+        PoolStatus {
+            max_size: 1,
+            size: 1,
+            available: 0,
+            pending: 0,
+        }
+    );
+    assert!(!pool.is_saturated());
+
+    // The only connection is taken, so the next task has to wait for it.
+    let waiting_pool = pool.clone();
+    let waiting = tokio::spawn(async move { waiting_pool.access_storage().await.map(drop) });
+    wait_for_pending_checkout(&pool).await;
+    assert_eq!(pool.status().pending, 1);
+    assert!(pool.is_saturated());
+    // Pool without the limit is never saturated.
+    assert!(!unlimited_pool.is_saturated());
+
+    drop(storage);
+    waiting.await??;
+    assert_eq!(
+        pool.status(),
+        PoolStatus {
+            max_size: 1,
+            size: 1,
+            available: 1,
+            pending: 0,
+        }
+    );
+    assert!(!pool.is_saturated());
+
+    Ok(())
+}
@@ -4,8 +4,8 @@ use std::str::FromStr;
 // Workspace imports
 use zksync_types::{
     aggregated_operations::{AggregatedActionType, AggregatedOperation},
-    ethereum::ETHOperation,
-    BlockNumber, H256, U256,
+    ethereum::{ETHOperation, EthTxReplacement},
+    Address, BlockNumber, H256, U256,
 };
 // Local imports
 use crate::test_data::{gen_unique_aggregated_operation, BLOCK_SIZE_CHUNKS};
@@ -50,6 +50,7 @@ impl EthereumTxParams {
             id: db_id,
             op_type,
             op: self.op.clone(),
+            bundled_ops: Vec::new(),
             nonce: nonce.into(),
             last_deadline_block: self.deadline_block,
             last_used_gas_price,
@@ -57,6 +58,8 @@ impl EthereumTxParams {
             encoded_tx_data: self.raw_tx.clone(),
             confirmed: false,
             final_hash: None,
+            sender: None,
+            cancel_tx_hash: None,
         }
     }
 }
@@ -117,6 +120,7 @@ async fn ethereum_storage(mut storage: StorageProcessor<'_>) -> QueryResult<()>
             params.deadline_block as i64,
             params.gas_price.clone(),
             params.raw_tx.clone(),
+            None,
         )
         .await?;
     EthereumSchema(&mut storage)
@@ -157,6 +161,7 @@ async fn ethereum_storage(mut storage: StorageProcessor<'_>) -> QueryResult<()>
             params_2.deadline_block as i64,
             params_2.gas_price.clone(),
             params_2.raw_tx.clone(),
+            None,
         )
         .await?;
     EthereumSchema(&mut storage)
@@ -271,6 +276,7 @@ async fn ethereum_unprocessed(mut storage: StorageProcessor<'_>) -> QueryResult<
             params.deadline_block as i64,
             params.gas_price.clone(),
             params.raw_tx.clone(),
+            None,
         )
         .await?;
     EthereumSchema(&mut storage)
@@ -329,6 +335,7 @@ async fn ethereum_unprocessed(mut storage: StorageProcessor<'_>) -> QueryResult<
             verify_params.deadline_block as i64,
             verify_params.gas_price.clone(),
             verify_params.raw_tx.clone(),
+            None,
         )
         .await?;
     EthereumSchema(&mut storage)
@@ -399,3 +406,368 @@ async fn test_update_eth_parameters(mut storage: StorageProcessor<'_>) -> QueryR
 
     Ok(())
 }
+
+/// Checks that the nonces of the additional operator accounts are assigned independently
+/// from the nonce of the main operator account.
+#[db_test]
+async fn ethereum_operator_nonces(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    storage.ethereum_schema().initialize_eth_data().await?;
+    let operator = Address::repeat_byte(0x11);
+
+    // Nonce of the account must be initialized first.
+    assert!(storage
+        .ethereum_schema()
+        .get_next_operator_nonce(operator)
+        .await
+        .is_err());
+
+    storage
+        .ethereum_schema()
+        .initialize_operator_nonce(operator, 5)
+        .await?;
+    // Repeated initialization does not reset the already used nonces.
+    assert_eq!(
+        storage
+            .ethereum_schema()
+            .get_next_operator_nonce(operator)
+            .await?,
+        5
+    );
+    storage
+        .ethereum_schema()
+        .initialize_operator_nonce(operator, 0)
+        .await?;
+
+    let params = EthereumTxParams::new("CommitBlocks".into(), None);
+    let response = storage
+        .ethereum_schema()
+        .save_new_eth_tx(
+            AggregatedActionType::CommitBlocks,
+            None,
+            params.deadline_block as i64,
+            params.gas_price.clone(),
+            params.raw_tx.clone(),
+            Some(operator),
+        )
+        .await?;
+    assert_eq!(response.nonce, 6.into());
+    storage
+        .ethereum_schema()
+        .add_hash_entry(response.id, &params.hash)
+        .await?;
+
+    // Nonce of the main operator account is not affected.
+    assert_eq!(storage.ethereum_schema().get_next_nonce().await?, 0);
+
+    let unconfirmed_operations = storage
+        .ethereum_schema()
+        .load_unconfirmed_operations()
+        .await?;
+    assert_eq!(unconfirmed_operations.len(), 1);
+    assert_eq!(unconfirmed_operations[0].sender, Some(operator));
+
+    Ok(())
+}
+
+/// Checks that the operations sent within the multicall transactions are loaded
+/// along with their Ethereum operations and confirmed all together.
+#[db_test]
+async fn ethereum_bundled_operations(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    storage.ethereum_schema().initialize_eth_data().await?;
+
+    let mut operations = Vec::new();
+    for block_number in 1..=5 {
+        let block_number = BlockNumber(block_number);
+        OperationsSchema(&mut storage)
+            .store_aggregated_action(gen_unique_aggregated_operation(
+                block_number,
+                AggregatedActionType::CommitBlocks,
+                BLOCK_SIZE_CHUNKS,
+            ))
+            .await?;
+        let op = OperationsSchema(&mut storage)
+            .get_aggregated_op_that_affects_block(AggregatedActionType::CommitBlocks, block_number)
+            .await?
+            .unwrap();
+        operations.push(op);
+    }
+
+    // The first transaction combines three operations, the second one combines two of them
+    // and is replaced once.
+    let mut sent_txs = Vec::new();
+    for bundle in &[&operations[..3], &operations[3..]] {
+        let params = EthereumTxParams::new("CommitBlocks".into(), Some(bundle[0].clone()));
+        let response = storage
+            .ethereum_schema()
+            .save_new_eth_tx(
+                AggregatedActionType::CommitBlocks,
+                params.op.clone(),
+                params.deadline_block as i64,
+                params.gas_price.clone(),
+                params.raw_tx.clone(),
+                None,
+            )
+            .await?;
+        for op in &bundle[1..] {
+            storage
+                .ethereum_schema()
+                .add_bundled_operation(response.id, op.clone())
+                .await?;
+        }
+        storage
+            .ethereum_schema()
+            .add_hash_entry(response.id, &params.hash)
+            .await?;
+        sent_txs.push((response.id, params.hash));
+    }
+    let replacement_hash = H256::repeat_byte(0xff);
+    storage
+        .ethereum_schema()
+        .add_hash_entry(sent_txs[1].0, &replacement_hash)
+        .await?;
+
+    // Every multicall transaction is loaded as a single operation.
+    let unconfirmed_operations = storage
+        .ethereum_schema()
+        .load_unconfirmed_operations()
+        .await?;
+    assert_eq!(unconfirmed_operations.len(), 2);
+    let expected = [
+        (&operations[..3], vec![sent_txs[0].1]),
+        (&operations[3..], vec![sent_txs[1].1, replacement_hash]),
+    ];
+    for (eth_op, (bundle, hashes)) in unconfirmed_operations.iter().zip(&expected) {
+        assert_eq!(eth_op.op.as_ref().map(|(id, _)| *id), Some(bundle[0].0));
+        let bundled_ids: Vec<_> = eth_op.bundled_ops.iter().map(|(id, _)| *id).collect();
+        let expected_ids: Vec<_> = bundle[1..].iter().map(|(id, _)| *id).collect();
+        assert_eq!(bundled_ids, expected_ids);
+        assert_eq!(&eth_op.used_tx_hashes, hashes);
+    }
+
+    // All the operations are confirmed along with the transaction.
+    storage
+        .ethereum_schema()
+        .confirm_eth_tx(&sent_txs[0].1)
+        .await?;
+    let last_confirmed_block = OperationsSchema(&mut storage)
+        .get_last_block_by_aggregated_action(AggregatedActionType::CommitBlocks, Some(true))
+        .await?;
+    assert_eq!(last_confirmed_block, BlockNumber(3));
+    let unconfirmed_operations = storage
+        .ethereum_schema()
+        .load_unconfirmed_operations()
+        .await?;
+    assert_eq!(unconfirmed_operations.len(), 1);
+    assert_eq!(unconfirmed_operations[0].id, sent_txs[1].0);
+    assert_eq!(unconfirmed_operations[0].bundled_ops.len(), 1);
+
+    Ok(())
+}
+
+/// Checks the workflow of the transaction replacement requests and the cancellation
+/// of the Ethereum operation.
+#[db_test]
+async fn ethereum_tx_replacement(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    storage.ethereum_schema().initialize_eth_data().await?;
+
+    let block_number = BlockNumber(1);
+    OperationsSchema(&mut storage)
+        .store_aggregated_action(gen_unique_aggregated_operation(
+            block_number,
+            AggregatedActionType::CommitBlocks,
+            BLOCK_SIZE_CHUNKS,
+        ))
+        .await?;
+    let op = OperationsSchema(&mut storage)
+        .get_aggregated_op_that_affects_block(AggregatedActionType::CommitBlocks, block_number)
+        .await?;
+
+    let params = EthereumTxParams::new("CommitBlocks".into(), op);
+    let response = storage
+        .ethereum_schema()
+        .save_new_eth_tx(
+            AggregatedActionType::CommitBlocks,
+            params.op.clone(),
+            params.deadline_block as i64,
+            params.gas_price.clone(),
+            params.raw_tx.clone(),
+            None,
+        )
+        .await?;
+    storage
+        .ethereum_schema()
+        .add_hash_entry(response.id, &params.hash)
+        .await?;
+
+    // Requests for the unknown operations are rejected, repeated requests override the previous ones.
+    assert!(
+        !storage
+            .ethereum_schema()
+            .request_eth_tx_replacement(response.id + 1, EthTxReplacement::Resend)
+            .await?
+    );
+    assert!(
+        storage
+            .ethereum_schema()
+            .request_eth_tx_replacement(response.id, EthTxReplacement::Resend)
+            .await?
+    );
+    assert!(
+        storage
+            .ethereum_schema()
+            .request_eth_tx_replacement(response.id, EthTxReplacement::Cancel)
+            .await?
+    );
+    assert_eq!(
+        storage
+            .ethereum_schema()
+            .take_eth_tx_replacement_requests()
+            .await?,
+        vec![(response.id, EthTxReplacement::Cancel)]
+    );
+    assert!(storage
+        .ethereum_schema()
+        .take_eth_tx_replacement_requests()
+        .await?
+        .is_empty());
+
+    let cancel_tx_hash = H256::repeat_byte(0xca);
+    storage
+        .ethereum_schema()
+        .set_cancel_tx_hash(response.id, &cancel_tx_hash)
+        .await?;
+    let unconfirmed_operations = storage
+        .ethereum_schema()
+        .load_unconfirmed_operations()
+        .await?;
+    assert_eq!(
+        unconfirmed_operations[0].cancel_tx_hash,
+        Some(cancel_tx_hash)
+    );
+
+    // After the cancellation the operation is considered unprocessed again.
+    storage
+        .ethereum_schema()
+        .cancel_eth_tx(response.id, &cancel_tx_hash)
+        .await?;
+    assert!(storage
+        .ethereum_schema()
+        .load_unconfirmed_operations()
+        .await?
+        .is_empty());
+    let stats = storage.ethereum_schema().load_stats().await?;
+    assert_eq!(stats.last_committed_block, 0);
+
+    Ok(())
+}
+
+/// Checks that the stored nonces of the operator accounts and the nonce of the unconfirmed
+/// operation can be overwritten.
+#[db_test]
+async fn ethereum_nonce_repair(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    storage.ethereum_schema().initialize_eth_data().await?;
+    let operator = Address::repeat_byte(0x11);
+
+    // Nonce of the additional account must be initialized first.
+    assert!(storage
+        .ethereum_schema()
+        .load_next_nonce(Some(operator))
+        .await
+        .is_err());
+    storage
+        .ethereum_schema()
+        .initialize_operator_nonce(operator, 3)
+        .await?;
+
+    assert_eq!(storage.ethereum_schema().load_next_nonce(None).await?, 0);
+    assert_eq!(
+        storage
+            .ethereum_schema()
+            .load_next_nonce(Some(operator))
+            .await?,
+        3
+    );
+
+    // Loading the nonce does not update it.
+    assert_eq!(storage.ethereum_schema().load_next_nonce(None).await?, 0);
+
+    storage.ethereum_schema().set_next_nonce(None, 10).await?;
+    storage
+        .ethereum_schema()
+        .set_next_nonce(Some(operator), 7)
+        .await?;
+    assert_eq!(storage.ethereum_schema().get_next_nonce().await?, 10);
+    assert_eq!(
+        storage
+            .ethereum_schema()
+            .get_next_operator_nonce(operator)
+            .await?,
+        7
+    );
+
+    let params = EthereumTxParams::new("CommitBlocks".into(), None);
+    let response = storage
+        .ethereum_schema()
+        .save_new_eth_tx(
+            AggregatedActionType::CommitBlocks,
+            None,
+            params.deadline_block as i64,
+            params.gas_price.clone(),
+            params.raw_tx.clone(),
+            None,
+        )
+        .await?;
+    assert_eq!(response.nonce, 11.into());
+    storage
+        .ethereum_schema()
+        .add_hash_entry(response.id, &params.hash)
+        .await?;
+
+    storage
+        .ethereum_schema()
+        .update_eth_tx_nonce(response.id, 12)
+        .await?;
+    let unconfirmed_operations = storage
+        .ethereum_schema()
+        .load_unconfirmed_operations()
+        .await?;
+    assert_eq!(unconfirmed_operations[0].nonce, 12.into());
+
+    Ok(())
+}
+
+/// Checks that the gas used by the confirmed transactions is loaded for the similar operations only.
+#[db_test]
+async fn ethereum_gas_usage(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    for (size, gas_used) in [(10, 100_000), (20, 200_000), (30, 300_000)].iter() {
+        storage
+            .ethereum_schema()
+            .save_gas_usage(AggregatedActionType::CommitBlocks, *size, *gas_used)
+            .await?;
+    }
+    storage
+        .ethereum_schema()
+        .save_gas_usage(AggregatedActionType::ExecuteBlocks, 20, 500_000)
+        .await?;
+
+    // The latest transactions go first.
+    let usage = storage
+        .ethereum_schema()
+        .load_gas_usage(AggregatedActionType::CommitBlocks, 15, 30, 10)
+        .await?;
+    assert_eq!(usage, vec![(30, 300_000), (20, 200_000)]);
+
+    let usage = storage
+        .ethereum_schema()
+        .load_gas_usage(AggregatedActionType::CommitBlocks, 0, 100, 1)
+        .await?;
+    assert_eq!(usage, vec![(30, 300_000)]);
+
+    let usage = storage
+        .ethereum_schema()
+        .load_gas_usage(AggregatedActionType::ExecuteBlocks, 15, 25, 10)
+        .await?;
+    assert_eq!(usage, vec![(20, 500_000)]);
+
+    Ok(())
+}
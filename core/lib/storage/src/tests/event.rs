@@ -73,6 +73,7 @@ async fn store_operation(
             100,
             100u32.into(),
             Default::default(),
+            None,
         )
         .await?;
     storage
@@ -331,3 +332,61 @@ async fn test_account_events(mut storage: StorageProcessor<'_>) -> QueryResult<(
             && check_account_event(event, AccountStateChangeStatus::Finalized)));
     Ok(())
 }
+
+/// Checks that the events are fetched in batches and the publisher offsets are stored.
+#[db_test]
+async fn test_publisher_offsets(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    for block_number in 1..=3i64 {
+        sqlx::query(
+            "INSERT INTO events (block_number, event_type, event_data) VALUES ($1, 'Block', '{}')",
+        )
+        .bind(block_number)
+        .execute(storage.conn())
+        .await?;
+    }
+    let events = storage
+        .event_schema()
+        .fetch_events_batch(EventId(0), 2)
+        .await?;
+    assert_eq!(
+        events
+            .iter()
+            .map(|event| event.block_number)
+            .collect::<Vec<_>>(),
+        vec![1, 2]
+    );
+
+    assert_eq!(
+        storage.event_schema().get_publisher_offset("kafka").await?,
+        None
+    );
+    let offset = EventId(events[1].id as u64);
+    storage
+        .event_schema()
+        .store_publisher_offset("kafka", offset)
+        .await?;
+    assert_eq!(
+        storage.event_schema().get_publisher_offset("kafka").await?,
+        Some(offset)
+    );
+    let events = storage.event_schema().fetch_events_batch(offset, 2).await?;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].block_number, 3);
+
+    // The replay from the block starts with its first event.
+    assert_eq!(
+        storage
+            .event_schema()
+            .get_first_event_id_since_block(BlockNumber(3))
+            .await?,
+        Some(EventId(events[0].id as u64))
+    );
+    assert_eq!(
+        storage
+            .event_schema()
+            .get_first_event_id_since_block(BlockNumber(4))
+            .await?,
+        None
+    );
+    Ok(())
+}
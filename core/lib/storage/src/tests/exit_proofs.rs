@@ -0,0 +1,179 @@
+// Built-in imports
+use std::time::Duration;
+// External imports
+use num::BigUint;
+// Workspace imports
+use zksync_api_types::v02::exit_proof::{ExitProofData, ExitProofJobStatus, StoredBlockInfo};
+use zksync_crypto::proof::EncodedSingleProof;
+use zksync_types::{AccountId, Address, BlockNumber, TokenId, H256};
+// Local imports
+use crate::tests::db_test;
+use crate::{QueryResult, StorageProcessor};
+
+fn exit_proof_data(account_id: AccountId, token_id: TokenId) -> ExitProofData {
+    ExitProofData {
+        stored_block_info: StoredBlockInfo {
+            block_number: BlockNumber(5),
+            priority_operations: 1,
+            pending_onchain_operations_hash: H256::random(),
+            timestamp: 1_000,
+            state_hash: H256::random(),
+            commitment: H256::random(),
+        },
+        owner: Address::random(),
+        account_id,
+        token_id,
+        amount: BigUint::from(100u32).into(),
+        nft_creator_id: AccountId(0),
+        nft_creator_address: Address::random(),
+        nft_serial_id: 0,
+        nft_content_hash: H256::zero(),
+        proof: EncodedSingleProof::default(),
+        token_address: Address::zero(),
+    }
+}
+
+/// Checks the exit proof jobs queue.
+#[db_test]
+async fn exit_proof_jobs(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let block = BlockNumber(5);
+    let first = storage
+        .exit_proofs_schema()
+        .request_exit_proof(AccountId(1), TokenId(0), block)
+        .await?;
+    assert_eq!(first.status, ExitProofJobStatus::Queued);
+    assert_eq!(first.queue_position, Some(0));
+    let second = storage
+        .exit_proofs_schema()
+        .request_exit_proof(AccountId(2), TokenId(0), block)
+        .await?;
+    assert_eq!(second.queue_position, Some(1));
+
+    // Requesting the same proof again returns the existing job.
+    let same = storage
+        .exit_proofs_schema()
+        .request_exit_proof(AccountId(1), TokenId(0), block)
+        .await?;
+    assert_eq!(same.id, first.id);
+
+    // Jobs are taken in the order they were requested.
+    let taken = storage
+        .exit_proofs_schema()
+        .take_next_exit_proof_job()
+        .await?
+        .unwrap();
+    assert_eq!(taken.id, first.id);
+    assert_eq!(taken.status, ExitProofJobStatus::InProgress);
+    let second = storage
+        .exit_proofs_schema()
+        .get_exit_proof_job(AccountId(2), TokenId(0), block)
+        .await?
+        .unwrap();
+    assert_eq!(second.queue_position, Some(0));
+
+    let proof = exit_proof_data(AccountId(1), TokenId(0));
+    storage
+        .exit_proofs_schema()
+        .store_exit_proof(first.id, &proof)
+        .await?;
+    let first = storage
+        .exit_proofs_schema()
+        .get_exit_proof_job(AccountId(1), TokenId(0), block)
+        .await?
+        .unwrap();
+    assert_eq!(first.status, ExitProofJobStatus::Done);
+    assert_eq!(first.queue_position, None);
+    assert_eq!(first.proof, Some(proof));
+
+    // Jobs left in progress without heartbeats are returned to the queue.
+    storage
+        .exit_proofs_schema()
+        .take_next_exit_proof_job()
+        .await?
+        .unwrap();
+    assert!(storage
+        .exit_proofs_schema()
+        .take_next_exit_proof_job()
+        .await?
+        .is_none());
+    assert_eq!(
+        storage
+            .exit_proofs_schema()
+            .count_queued_exit_proof_jobs()
+            .await?,
+        0
+    );
+    // The job is not returned while its lease is valid.
+    assert_eq!(
+        storage
+            .exit_proofs_schema()
+            .return_stale_exit_proof_jobs(Duration::from_secs(60))
+            .await?,
+        0
+    );
+    assert_eq!(
+        storage
+            .exit_proofs_schema()
+            .return_stale_exit_proof_jobs(Duration::from_secs(0))
+            .await?,
+        1
+    );
+    assert_eq!(
+        storage
+            .exit_proofs_schema()
+            .count_queued_exit_proof_jobs()
+            .await?,
+        1
+    );
+
+    // Failed jobs are queued again once requested.
+    let taken = storage
+        .exit_proofs_schema()
+        .take_next_exit_proof_job()
+        .await?
+        .unwrap();
+    assert_eq!(taken.id, second.id);
+    storage
+        .exit_proofs_schema()
+        .mark_exit_proof_job_failed(second.id, "Account not found")
+        .await?;
+    let failed = storage
+        .exit_proofs_schema()
+        .get_exit_proof_job(AccountId(2), TokenId(0), block)
+        .await?
+        .unwrap();
+    assert_eq!(failed.status, ExitProofJobStatus::Failed);
+    assert_eq!(failed.error.as_deref(), Some("Account not found"));
+    let requeued = storage
+        .exit_proofs_schema()
+        .request_exit_proof(AccountId(2), TokenId(0), block)
+        .await?;
+    assert_eq!(requeued.id, second.id);
+    assert_eq!(requeued.status, ExitProofJobStatus::Queued);
+    assert_eq!(requeued.error, None);
+
+    // Queued jobs for the previous blocks are superseded by the new request.
+    let superseding = storage
+        .exit_proofs_schema()
+        .request_exit_proof(AccountId(2), TokenId(0), block + 1)
+        .await?;
+    assert_ne!(superseding.id, second.id);
+    assert!(storage
+        .exit_proofs_schema()
+        .get_exit_proof_job(AccountId(2), TokenId(0), block)
+        .await?
+        .is_none());
+    // Generated proofs are kept.
+    assert!(storage
+        .exit_proofs_schema()
+        .request_exit_proof(AccountId(1), TokenId(0), block + 1)
+        .await
+        .is_ok());
+    assert!(storage
+        .exit_proofs_schema()
+        .get_exit_proof_job(AccountId(1), TokenId(0), block)
+        .await?
+        .is_some());
+
+    Ok(())
+}
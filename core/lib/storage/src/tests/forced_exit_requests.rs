@@ -3,16 +3,22 @@ use std::{
     str::FromStr,
 };
 
+use crate::chain::state::StateSchema;
 use crate::forced_exit_requests::ForcedExitRequestsSchema;
+use crate::test_data::gen_sample_block;
 use crate::tests::db_test;
 use crate::QueryResult;
 use crate::StorageProcessor;
 use chrono::{Duration, Timelike, Utc};
-use num::{BigUint, FromPrimitive};
+use num::{rational::Ratio, BigUint, FromPrimitive};
 use zksync_types::{
-    forced_exit_requests::{ForcedExitRequest, SaveForcedExitRequestQuery},
+    forced_exit_requests::{
+        DormantBalance, ForcedExitRequest, ForcedExitRequestProgress, ForcedExitTarget,
+        SaveForcedExitRequestQuery,
+    },
     tx::TxHash,
-    Address,
+    AccountId, AccountUpdate, Address, BlockNumber, Nonce, PubKeyHash, Token, TokenKind,
+    TokenPrice,
 };
 
 use std::ops::Add;
@@ -46,6 +52,7 @@ async fn get_oldest_unfulfilled_request(mut storage: StorageProcessor<'_>) -> Qu
         SaveForcedExitRequestQuery {
             target: Address::from_str("c0f97CC918C9d6fA4E9fc6be61a6a06589D199b2").unwrap(),
             tokens: vec![TokenId(1)],
+            additional_targets: vec![],
             price_in_wei: BigUint::from_i32(212).unwrap(),
             created_at: now,
             valid_until: now,
@@ -53,6 +60,7 @@ async fn get_oldest_unfulfilled_request(mut storage: StorageProcessor<'_>) -> Qu
         SaveForcedExitRequestQuery {
             target: Address::from_str("c0f97CC918C9d6fA4E9fc6be61a6a06589D199b2").unwrap(),
             tokens: vec![TokenId(1)],
+            additional_targets: vec![],
             price_in_wei: BigUint::from_i32(1).unwrap(),
             created_at: now,
             valid_until: now,
@@ -60,6 +68,7 @@ async fn get_oldest_unfulfilled_request(mut storage: StorageProcessor<'_>) -> Qu
         SaveForcedExitRequestQuery {
             target: Address::from_str("c0f97CC918C9d6fA4E9fc6be61a6a06589D199b2").unwrap(),
             tokens: vec![TokenId(20)],
+            additional_targets: vec![],
             price_in_wei: BigUint::from_str("1000000000000000").unwrap(),
             created_at: now,
             valid_until: now,
@@ -130,6 +139,7 @@ async fn delete_old_requests(mut storage: StorageProcessor<'_>) -> QueryResult<(
         SaveForcedExitRequestQuery {
             target: Address::from_str("c0f97CC918C9d6fA4E9fc6be61a6a06589D199b2").unwrap(),
             tokens: vec![TokenId(1)],
+            additional_targets: vec![],
             price_in_wei: BigUint::from_i32(212).unwrap(),
             created_at: now.sub(day.mul(8)),
             // Invalid for 6 days => should be deleted
@@ -138,6 +148,7 @@ async fn delete_old_requests(mut storage: StorageProcessor<'_>) -> QueryResult<(
         SaveForcedExitRequestQuery {
             target: Address::from_str("c0f97CC918C9d6fA4E9fc6be61a6a06589D199b2").unwrap(),
             tokens: vec![TokenId(1)],
+            additional_targets: vec![],
             price_in_wei: BigUint::from_i32(1).unwrap(),
             created_at: now.sub(day.mul(5)).sub(minute),
             // Invalid for 3 days and 1 minutes => should be deleted
@@ -146,6 +157,7 @@ async fn delete_old_requests(mut storage: StorageProcessor<'_>) -> QueryResult<(
         SaveForcedExitRequestQuery {
             target: Address::from_str("c0f97CC918C9d6fA4E9fc6be61a6a06589D199b2").unwrap(),
             tokens: vec![TokenId(20)],
+            additional_targets: vec![],
             price_in_wei: BigUint::from_str("1000000000000000").unwrap(),
             created_at: now.sub(day.mul(5)).add(minute.mul(5)),
             // Invalid for 3 days minus 5 minutes => should not be deleted
@@ -154,6 +166,7 @@ async fn delete_old_requests(mut storage: StorageProcessor<'_>) -> QueryResult<(
         SaveForcedExitRequestQuery {
             target: Address::from_str("c0f97CC918C9d6fA4E9fc6be61a6a06589D199b2").unwrap(),
             tokens: vec![TokenId(20)],
+            additional_targets: vec![],
             price_in_wei: BigUint::from_str("1000000000000000").unwrap(),
             created_at: now.sub(day.mul(5)).add(minute.mul(5)),
             // Is valid => should not be deleted
@@ -201,3 +214,297 @@ async fn delete_old_requests(mut storage: StorageProcessor<'_>) -> QueryResult<(
 
     Ok(())
 }
+
+// Checks that the requests are listed by their main and additional targets
+// and that the payment is reflected in the request progress
+#[db_test]
+async fn requests_by_target(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let now = Utc::now().with_nanosecond(0).unwrap();
+    let target = Address::from_str("c0f97CC918C9d6fA4E9fc6be61a6a06589D199b2").unwrap();
+    let other_target = Address::from_str("2a0a81e257a2f5d6ed4f07b81dbda09f107bd027").unwrap();
+
+    let request = |target| SaveForcedExitRequestQuery {
+        target,
+        tokens: vec![TokenId(1)],
+        additional_targets: vec![],
+        price_in_wei: BigUint::from_i32(212).unwrap(),
+        created_at: now,
+        valid_until: now.add(Duration::days(1)),
+    };
+    // The last request exits the target along with the other one.
+    let batch_request = SaveForcedExitRequestQuery {
+        additional_targets: vec![ForcedExitTarget {
+            target,
+            tokens: vec![TokenId(1), TokenId(2)],
+        }],
+        ..request(other_target)
+    };
+    let stored_requests = store_requests(
+        &mut storage,
+        vec![
+            request(target),
+            request(other_target),
+            request(target),
+            batch_request,
+        ],
+    )
+    .await;
+
+    let requests = ForcedExitRequestsSchema(&mut storage)
+        .get_requests_by_target(target, 10)
+        .await?;
+    let ids: Vec<_> = requests.iter().map(|request| request.id).collect();
+    assert_eq!(
+        ids,
+        vec![
+            stored_requests[3].id,
+            stored_requests[2].id,
+            stored_requests[0].id
+        ]
+    );
+    assert_eq!(requests[0], stored_requests[3]);
+    assert_eq!(
+        requests[0].exits(),
+        vec![
+            (other_target, TokenId(1)),
+            (target, TokenId(1)),
+            (target, TokenId(2))
+        ]
+    );
+    assert_eq!(
+        requests[1].progress(now),
+        ForcedExitRequestProgress::AwaitingPayment
+    );
+    assert_eq!(
+        requests[1].progress(now.add(Duration::days(2))),
+        ForcedExitRequestProgress::Expired
+    );
+
+    let requests = ForcedExitRequestsSchema(&mut storage)
+        .get_requests_by_target(target, 1)
+        .await?;
+    assert_eq!(requests.len(), 1);
+
+    ForcedExitRequestsSchema(&mut storage)
+        .set_payment_received_at(stored_requests[0].id, now)
+        .await?;
+    let request = ForcedExitRequestsSchema(&mut storage)
+        .get_request_by_id(stored_requests[0].id)
+        .await?
+        .unwrap();
+    assert_eq!(request.payment_received_at, Some(now));
+    assert_eq!(
+        request.progress(now),
+        ForcedExitRequestProgress::PaymentReceived
+    );
+
+    Ok(())
+}
+
+// Checks that only the small balances of the idle accounts without a signing key are loaded
+#[db_test]
+async fn dormant_balances(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let now = Utc::now();
+    // The token worth $2 and the one without a known price.
+    for &(id, symbol) in &[(1, "ABC"), (2, "DEF")] {
+        let token = Token::new(TokenId(id), Address::random(), symbol, 6, TokenKind::ERC20);
+        storage.tokens_schema().store_or_update_token(token).await?;
+    }
+    storage
+        .tokens_schema()
+        .update_historical_ticker_price(
+            TokenId(1),
+            TokenPrice {
+                usd_price: Ratio::from_integer(BigUint::from(2u32)),
+                last_updated: now,
+            },
+        )
+        .await?;
+
+    let addresses: Vec<_> = (0..5).map(|_| Address::random()).collect();
+    let create = |id: usize, balances: Vec<(u32, u64)>| {
+        let account_id = AccountId(id as u32 + 1);
+        let mut updates = vec![(
+            account_id,
+            AccountUpdate::Create {
+                address: addresses[id],
+                nonce: Nonce(0),
+            },
+        )];
+        updates.extend(balances.into_iter().map(|(token, balance)| {
+            (
+                account_id,
+                AccountUpdate::UpdateBalance {
+                    old_nonce: Nonce(0),
+                    new_nonce: Nonce(0),
+                    balance_update: (TokenId(token), BigUint::from(0u64), BigUint::from(balance)),
+                },
+            )
+        }));
+        updates
+    };
+    // 0.25 ABC is worth $0.5 and 5 ABC is worth $10.
+    let mut old_updates = create(0, vec![(1, 250_000), (2, 1)]);
+    old_updates.extend(create(1, vec![(1, 5_000_000)]));
+    old_updates.extend(create(2, vec![(1, 250_000)]));
+    old_updates.extend(create(3, vec![(1, 250_000)]));
+    old_updates.push((
+        AccountId(4),
+        AccountUpdate::ChangePubKeyHash {
+            old_pub_key_hash: PubKeyHash::default(),
+            new_pub_key_hash: PubKeyHash { data: [1; 20] },
+            old_nonce: Nonce(0),
+            new_nonce: Nonce(0),
+        },
+    ));
+    let new_updates = create(4, vec![(1, 250_000)]);
+
+    let mut new_block = gen_sample_block(BlockNumber(2), 100, Default::default());
+    new_block.timestamp = now.timestamp() as u64;
+    let blocks = vec![
+        (
+            gen_sample_block(BlockNumber(1), 100, Default::default()),
+            old_updates,
+        ),
+        (new_block, new_updates),
+    ];
+    for (block, updates) in blocks {
+        let block_number = block.block_number;
+        storage
+            .chain()
+            .block_schema()
+            .save_full_block(block)
+            .await?;
+        StateSchema(&mut storage)
+            .commit_state_update(block_number, &updates, 0)
+            .await?;
+        StateSchema(&mut storage)
+            .apply_state_update(block_number)
+            .await?;
+    }
+
+    let dormant_balances = ForcedExitRequestsSchema(&mut storage)
+        .get_dormant_balances(now.sub(Duration::days(1)), 1.0, &[addresses[2]], 10)
+        .await?;
+    assert_eq!(
+        dormant_balances,
+        vec![DormantBalance {
+            account_id: AccountId(1),
+            target: addresses[0],
+            token: TokenId(1),
+            balance: BigUint::from(250_000u64),
+        }]
+    );
+
+    // Without the exclusion the balance of the opted out account is loaded too.
+    let dormant_balances = ForcedExitRequestsSchema(&mut storage)
+        .get_dormant_balances(now.sub(Duration::days(1)), 1.0, &[], 1)
+        .await?;
+    assert_eq!(dormant_balances.len(), 1);
+    let dormant_balances = ForcedExitRequestsSchema(&mut storage)
+        .get_dormant_balances(now.sub(Duration::days(1)), 1.0, &[], 10)
+        .await?;
+    let targets: Vec<_> = dormant_balances
+        .iter()
+        .map(|balance| balance.target)
+        .collect();
+    assert_eq!(targets, vec![addresses[0], addresses[2]]);
+
+    Ok(())
+}
+
+// Checks that the balances of the opted out accounts are never loaded, and the other
+// dormant balances are still loaded up to the limit
+#[db_test]
+async fn dormant_balances_opt_out(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let now = Utc::now();
+    for &(id, symbol) in &[(1, "ABC"), (2, "DEF")] {
+        let token = Token::new(TokenId(id), Address::random(), symbol, 6, TokenKind::ERC20);
+        storage.tokens_schema().store_or_update_token(token).await?;
+        storage
+            .tokens_schema()
+            .update_historical_ticker_price(
+                TokenId(id),
+                TokenPrice {
+                    usd_price: Ratio::from_integer(BigUint::from(1u32)),
+                    last_updated: now,
+                },
+            )
+            .await?;
+    }
+
+    // Every account holds the $0.1 balances of both tokens.
+    let addresses: Vec<_> = (0..4).map(|_| Address::random()).collect();
+    let mut updates = Vec::new();
+    for (id, &address) in addresses.iter().enumerate() {
+        let account_id = AccountId(id as u32 + 1);
+        updates.push((
+            account_id,
+            AccountUpdate::Create {
+                address,
+                nonce: Nonce(0),
+            },
+        ));
+        for token in 1..=2 {
+            updates.push((
+                account_id,
+                AccountUpdate::UpdateBalance {
+                    old_nonce: Nonce(0),
+                    new_nonce: Nonce(0),
+                    balance_update: (
+                        TokenId(token),
+                        BigUint::from(0u64),
+                        BigUint::from(100_000u64),
+                    ),
+                },
+            ));
+        }
+    }
+    storage
+        .chain()
+        .block_schema()
+        .save_full_block(gen_sample_block(BlockNumber(1), 100, Default::default()))
+        .await?;
+    StateSchema(&mut storage)
+        .commit_state_update(BlockNumber(1), &updates, 0)
+        .await?;
+    StateSchema(&mut storage)
+        .apply_state_update(BlockNumber(1))
+        .await?;
+
+    let idle_since = now.sub(Duration::days(1));
+    let opt_out = [addresses[0], addresses[2]];
+    let dormant_balances = ForcedExitRequestsSchema(&mut storage)
+        .get_dormant_balances(idle_since, 1.0, &opt_out, 10)
+        .await?;
+    let balances: Vec<_> = dormant_balances
+        .iter()
+        .map(|balance| (balance.target, balance.token))
+        .collect();
+    assert_eq!(
+        balances,
+        vec![
+            (addresses[1], TokenId(1)),
+            (addresses[1], TokenId(2)),
+            (addresses[3], TokenId(1)),
+            (addresses[3], TokenId(2)),
+        ]
+    );
+
+    // The opted out balances don't take the place of the other ones within the limit.
+    let dormant_balances = ForcedExitRequestsSchema(&mut storage)
+        .get_dormant_balances(idle_since, 1.0, &opt_out, 3)
+        .await?;
+    assert!(dormant_balances
+        .iter()
+        .all(|balance| !opt_out.contains(&balance.target)));
+    assert_eq!(dormant_balances.len(), 3);
+
+    // Every account can opt out.
+    let dormant_balances = ForcedExitRequestsSchema(&mut storage)
+        .get_dormant_balances(idle_since, 1.0, &addresses, 10)
+        .await?;
+    assert!(dormant_balances.is_empty());
+
+    Ok(())
+}
@@ -3,7 +3,7 @@ use zksync_types::TokenId;
 
 use crate::tests::db_test;
 use crate::{misc::records::Subsidy, misc::MiscSchema};
-use crate::{QueryResult, StorageProcessor};
+use crate::{query_log, QueryResult, StorageProcessor};
 
 fn get_subsidy(name: String, value: u64) -> Subsidy {
     // The only fields that matter are `subsidy_type` and `value`
@@ -54,3 +54,63 @@ async fn stored_subsidy(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
 
     Ok(())
 }
+
+/// Checks that the acceptance of the transactions can be paused and resumed.
+#[db_test]
+async fn tx_acceptance_pause(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    assert!(MiscSchema(&mut storage)
+        .load_tx_acceptance_pause()
+        .await?
+        .is_none());
+    assert!(!MiscSchema(&mut storage).resume_tx_acceptance().await?);
+
+    assert!(
+        MiscSchema(&mut storage)
+            .pause_tx_acceptance("maintenance")
+            .await?
+    );
+    // The second pause doesn't override the first one.
+    assert!(
+        !MiscSchema(&mut storage)
+            .pause_tx_acceptance("other")
+            .await?
+    );
+    let pause = MiscSchema(&mut storage)
+        .load_tx_acceptance_pause()
+        .await?
+        .expect("acceptance must be paused");
+    assert_eq!(pause.reason, "maintenance");
+
+    assert!(MiscSchema(&mut storage).resume_tx_acceptance().await?);
+    assert!(MiscSchema(&mut storage)
+        .load_tx_acceptance_pause()
+        .await?
+        .is_none());
+
+    Ok(())
+}
+
+/// Checks that the size of the database is reported.
+#[db_test]
+async fn database_size(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    assert!(MiscSchema(&mut storage).get_database_size().await? > 0);
+    Ok(())
+}
+
+/// Checks that the slow queries of the transactions are reported along with
+/// the component of the processor which started them.
+#[db_test]
+async fn transaction_component(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    assert_eq!(storage.component(), query_log::component());
+
+    storage.set_component("eth_sender");
+    let mut transaction = storage.start_transaction().await?;
+    assert_eq!(transaction.component(), "eth_sender");
+    // Schema methods report the queries through the processor they are called on.
+    MiscSchema(&mut transaction)
+        .get_total_used_subsidy_for_type("subsidy")
+        .await?;
+    transaction.commit().await?;
+
+    Ok(())
+}
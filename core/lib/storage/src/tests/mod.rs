@@ -23,15 +23,20 @@ use tokio::sync::Mutex;
 // Workspace imports
 use zksync_crypto::rand::{SeedableRng, XorShiftRng};
 
+mod archive;
+mod backup;
 pub(crate) mod chain;
 mod config;
+mod connection;
 mod data_restore;
 mod ethereum;
 mod event;
+mod exit_proofs;
 mod forced_exit_requests;
 mod misc;
 mod prover;
 mod tokens;
+mod webhooks;
 
 pub use db_test_macro::test as db_test;
 
@@ -1,10 +1,15 @@
+// Built-in imports
+use std::time::Duration;
 // External imports
 use anyhow::format_err;
 use once_cell::sync::Lazy;
 use tokio::sync::Mutex;
 // Workspace imports
 use zksync_types::{
-    prover::{ProverJob, ProverJobType},
+    prover::{
+        ProverJob, ProverJobPriorityPolicy, ProverJobType, AGGREGATED_PROOF_JOB_PRIORITY,
+        DEADLINE_JOB_PRIORITY, SINGLE_PROOF_JOB_PRIORITY,
+    },
     BlockNumber,
 };
 // Local imports
@@ -14,9 +19,14 @@ use crate::{prover::ProverSchema, QueryResult, StorageProcessor};
 
 static MUTEX: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
 
+const PRIORITY_POLICY: ProverJobPriorityPolicy = ProverJobPriorityPolicy {
+    aggregated_proof_deadline: Duration::from_secs(600),
+    preemption_window: Duration::from_secs(120),
+};
+
 async fn get_idle_job_from_queue(storage: &mut StorageProcessor<'_>) -> QueryResult<ProverJob> {
     let job = ProverSchema(storage)
-        .get_idle_prover_job_from_job_queue()
+        .get_idle_prover_job_from_job_queue(&PRIORITY_POLICY)
         .await?;
 
     job.ok_or_else(|| format_err!("expect idle job from job queue"))
@@ -268,6 +278,21 @@ async fn test_store_witness(mut storage: StorageProcessor<'_>) -> QueryResult<()
     assert_ne!(loaded, Some(not_expected));
     assert_eq!(loaded, Some(expected));
 
+    // Replacing the witness overwrites it.
+    let replaced = String::from("replaced");
+    let witness = serde_json::to_value(replaced.clone()).unwrap();
+    storage
+        .prover_schema()
+        .replace_witness(BLOCK_NUMBER, witness)
+        .await?;
+
+    let loaded = storage
+        .prover_schema()
+        .get_witness(BLOCK_NUMBER)
+        .await?
+        .map(|value| serde_json::from_value(value).unwrap());
+    assert_eq!(loaded, Some(replaced));
+
     Ok(())
 }
 
@@ -402,6 +427,70 @@ async fn test_remove_proofs(mut storage: StorageProcessor<'_>) -> QueryResult<()
     Ok(())
 }
 
+/// Checks that the quarantined aggregated proof is moved out of the loaded proofs
+/// and its job is returned to the queue.
+#[db_test]
+async fn test_quarantine_aggregated_proof(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    // Lock to prevent database deadlock
+    let _lock = MUTEX.lock().await;
+
+    // There is nothing to quarantine yet.
+    assert!(
+        !ProverSchema(&mut storage)
+            .quarantine_aggregated_proof(BlockNumber(1), BlockNumber(2))
+            .await?
+    );
+
+    ProverSchema(&mut storage)
+        .add_prover_job_to_job_queue(
+            BlockNumber(1),
+            BlockNumber(2),
+            serde_json::Value::default(),
+            1,
+            ProverJobType::AggregatedProof,
+        )
+        .await?;
+    let job_id = get_idle_job_from_queue(&mut storage).await?.job_id;
+    ProverSchema(&mut storage)
+        .store_aggregated_proof(
+            job_id,
+            BlockNumber(1),
+            BlockNumber(2),
+            &get_sample_aggregated_proof(),
+        )
+        .await?;
+    assert!(ProverSchema(&mut storage)
+        .get_idle_prover_job_from_job_queue(&PRIORITY_POLICY)
+        .await?
+        .is_none());
+
+    assert!(
+        ProverSchema(&mut storage)
+            .quarantine_aggregated_proof(BlockNumber(1), BlockNumber(2))
+            .await?
+    );
+    assert!(ProverSchema(&mut storage)
+        .load_aggregated_proof(BlockNumber(1), BlockNumber(2))
+        .await?
+        .is_none());
+    let quarantined: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM quarantined_aggregated_proofs WHERE first_block = 1 AND last_block = 2",
+    )
+    .fetch_one(storage.conn())
+    .await?;
+    assert_eq!(quarantined, 1);
+
+    // The same job is picked up again to generate the new proof.
+    let job = get_idle_job_from_queue(&mut storage).await?;
+    assert_eq!(job.job_id, job_id);
+    assert_eq!(
+        (job.first_block, job.last_block),
+        (BlockNumber(1), BlockNumber(2))
+    );
+
+    Ok(())
+}
+
 /// Checks that prover jobs are removed correctly.
 #[db_test]
 async fn test_remove_prover_jobs(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
@@ -441,3 +530,217 @@ async fn test_remove_prover_jobs(mut storage: StorageProcessor<'_>) -> QueryResu
 
     Ok(())
 }
+
+/// Checks that a witness lease can be held by only one worker until it expires.
+#[db_test]
+async fn test_witness_leases(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let timeout = Duration::from_secs(60);
+
+    // The first worker obtains the lease, the second one can't take it over.
+    assert!(
+        ProverSchema(&mut storage)
+            .try_acquire_witness_lease(BlockNumber(1), "first", timeout)
+            .await?
+    );
+    assert!(
+        !ProverSchema(&mut storage)
+            .try_acquire_witness_lease(BlockNumber(1), "second", timeout)
+            .await?
+    );
+    // Re-acquiring the own lease is allowed.
+    assert!(
+        ProverSchema(&mut storage)
+            .try_acquire_witness_lease(BlockNumber(1), "first", timeout)
+            .await?
+    );
+    assert!(
+        ProverSchema(&mut storage)
+            .heartbeat_witness_lease(BlockNumber(1), "first")
+            .await?
+    );
+    assert!(
+        !ProverSchema(&mut storage)
+            .heartbeat_witness_lease(BlockNumber(1), "second")
+            .await?
+    );
+
+    // Expired lease can be taken over, after which the old holder can't prolong it.
+    assert!(
+        ProverSchema(&mut storage)
+            .try_acquire_witness_lease(BlockNumber(1), "second", Duration::from_secs(0))
+            .await?
+    );
+    assert!(
+        !ProverSchema(&mut storage)
+            .heartbeat_witness_lease(BlockNumber(1), "first")
+            .await?
+    );
+
+    // Released lease is available to everyone.
+    ProverSchema(&mut storage)
+        .release_witness_lease(BlockNumber(1), "second")
+        .await?;
+    assert!(
+        ProverSchema(&mut storage)
+            .try_acquire_witness_lease(BlockNumber(1), "first", timeout)
+            .await?
+    );
+
+    Ok(())
+}
+
+/// Checks that jobs without prover heartbeats are returned to the queue.
+#[db_test]
+async fn test_stale_jobs(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    // Lock to prevent database deadlock
+    let _lock = MUTEX.lock().await;
+
+    ProverSchema(&mut storage)
+        .add_prover_job_to_job_queue(
+            BlockNumber(1),
+            BlockNumber(1),
+            serde_json::Value::default(),
+            1,
+            ProverJobType::SingleProof,
+        )
+        .await?;
+    let job = get_idle_job_from_queue(&mut storage).await?;
+    ProverSchema(&mut storage)
+        .record_prover_is_working(job.job_id, "test_prover")
+        .await?;
+
+    let in_progress = ProverSchema(&mut storage).load_in_progress_jobs().await?;
+    assert_eq!(in_progress.len(), 1);
+    assert_eq!(in_progress[0].id, job.job_id);
+    assert_eq!(in_progress[0].updated_by, "test_prover");
+
+    // The job isn't stale yet.
+    ProverSchema(&mut storage)
+        .mark_stale_jobs_as_idle(Duration::from_secs(60))
+        .await?;
+    assert_eq!(
+        ProverSchema(&mut storage)
+            .load_in_progress_jobs()
+            .await?
+            .len(),
+        1
+    );
+
+    // Once the timeout is exceeded, the job can be given to another prover.
+    ProverSchema(&mut storage)
+        .mark_stale_jobs_as_idle(Duration::from_secs(0))
+        .await?;
+    assert!(ProverSchema(&mut storage)
+        .load_in_progress_jobs()
+        .await?
+        .is_empty());
+    let reassigned_job = get_idle_job_from_queue(&mut storage).await?;
+    assert_eq!(reassigned_job.job_id, job.job_id);
+
+    Ok(())
+}
+
+/// Checks that the prover queue statistics are grouped by the job type and size.
+#[db_test]
+async fn test_prover_queue_stats(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    // Lock to prevent database deadlock
+    let _lock = MUTEX.lock().await;
+
+    for (first_block, last_block, job_priority, job_type) in vec![
+        (1, 4, 0, ProverJobType::AggregatedProof),
+        (5, 8, 0, ProverJobType::AggregatedProof),
+        (9, 9, 1, ProverJobType::SingleProof),
+    ] {
+        ProverSchema(&mut storage)
+            .add_prover_job_to_job_queue(
+                BlockNumber(first_block),
+                BlockNumber(last_block),
+                serde_json::Value::default(),
+                job_priority,
+                job_type,
+            )
+            .await?;
+    }
+    get_idle_job_from_queue(&mut storage).await?;
+
+    let stats = ProverSchema(&mut storage)
+        .load_prover_queue_stats(Duration::from_secs(3600))
+        .await?;
+    assert_eq!(stats.len(), 2);
+
+    assert_eq!(
+        stats[0].job_type,
+        ProverJobType::AggregatedProof.to_string()
+    );
+    assert_eq!(stats[0].job_size, 4);
+    assert_eq!(stats[0].pending_jobs, 1);
+    assert_eq!(stats[0].in_progress_jobs, 1);
+    // No jobs were proven yet.
+    assert_eq!(stats[0].average_proving_time, None);
+
+    // There is no block 9 in the database, so the block size is unknown.
+    assert_eq!(stats[1].job_type, ProverJobType::SingleProof.to_string());
+    assert_eq!(stats[1].job_size, 0);
+    assert_eq!(stats[1].pending_jobs, 1);
+    assert_eq!(stats[1].in_progress_jobs, 0);
+
+    Ok(())
+}
+
+/// Checks that the aggregated proof jobs close to their deadline preempt the single block proof jobs.
+#[db_test]
+async fn test_deadline_job_priority(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    // Lock to prevent database deadlock
+    let _lock = MUTEX.lock().await;
+
+    for (first_block, last_block, job_priority, job_type) in vec![
+        (1, 1, SINGLE_PROOF_JOB_PRIORITY, ProverJobType::SingleProof),
+        (2, 2, SINGLE_PROOF_JOB_PRIORITY, ProverJobType::SingleProof),
+        (
+            1,
+            2,
+            AGGREGATED_PROOF_JOB_PRIORITY,
+            ProverJobType::AggregatedProof,
+        ),
+        (3, 3, SINGLE_PROOF_JOB_PRIORITY, ProverJobType::SingleProof),
+    ] {
+        ProverSchema(&mut storage)
+            .add_prover_job_to_job_queue(
+                BlockNumber(first_block),
+                BlockNumber(last_block),
+                serde_json::Value::default(),
+                job_priority,
+                job_type,
+            )
+            .await?;
+    }
+
+    // The fresh aggregated proof job waits in the queue.
+    let job = get_idle_job_from_queue(&mut storage).await?;
+    assert_eq!(
+        (job.first_block, job.last_block),
+        (BlockNumber(1), BlockNumber(1))
+    );
+    assert_eq!(job.job_priority, SINGLE_PROOF_JOB_PRIORITY);
+
+    // Once the aggregated proof job is close to its deadline, it's handed out before
+    // the single block proof jobs added earlier.
+    sqlx::query("UPDATE prover_job_queue SET created_at = now() - interval '500 seconds' WHERE job_type = $1")
+        .bind(ProverJobType::AggregatedProof.to_string())
+        .execute(storage.conn())
+        .await?;
+    let job = get_idle_job_from_queue(&mut storage).await?;
+    assert_eq!(
+        (job.first_block, job.last_block),
+        (BlockNumber(1), BlockNumber(2))
+    );
+    assert_eq!(job.job_priority, DEADLINE_JOB_PRIORITY);
+
+    for block in 2..=3 {
+        let job = get_idle_job_from_queue(&mut storage).await?;
+        assert_eq!(job.first_block, BlockNumber(block));
+        assert_eq!(job.job_priority, SINGLE_PROOF_JOB_PRIORITY);
+    }
+
+    Ok(())
+}
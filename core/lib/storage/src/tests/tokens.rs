@@ -3,17 +3,21 @@ use std::str::FromStr;
 // External imports
 use chrono::Utc;
 use num::{rational::Ratio, BigUint};
+use sqlx::types::BigDecimal;
 // Workspace imports
+use zksync_api_types::v02::pagination::{PaginationDirection, PaginationQuery};
 use zksync_test_account::ZkSyncAccount;
 use zksync_types::{
-    tokens::TokenMarketVolume, AccountId, Address, BlockNumber, ExecutedOperations, ExecutedTx,
-    Token, TokenId, TokenKind, TokenLike, TokenPrice, WithdrawNFTOp, ZkSyncOp, H256,
+    tokens::{TokenMarketVolume, TokenMetadata},
+    AccountId, AccountUpdate, Address, BlockNumber, ExecutedOperations, ExecutedTx, Nonce,
+    RegisterNFTFactoryEvent, Token, TokenId, TokenKind, TokenLike, TokenPrice, WithdrawNFTOp,
+    ZkSyncOp, H256,
 };
 use zksync_utils::{big_decimal_to_ratio, ratio_to_big_decimal};
 // Local imports
 use crate::tests::db_test;
 use crate::{
-    chain::account::records::StorageMintNFTUpdate,
+    chain::{account::records::StorageMintNFTUpdate, state::StateSchema},
     diff::StorageAccountDiff,
     tokens::{TokensSchema, STORED_USD_PRICE_PRECISION},
     QueryResult, StorageProcessor,
@@ -135,6 +139,238 @@ async fn tokens_storage(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
     Ok(())
 }
 
+/// Checks that the rejected token listings are recorded once and loaded the latest first.
+#[db_test]
+async fn test_listing_rejections(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let address = Address::random();
+    let eth_tx_hash = H256::random();
+    storage
+        .tokens_schema()
+        .store_listing_rejection(TokenId(10), address, "listing fee is not paid", None)
+        .await?;
+    storage
+        .tokens_schema()
+        .store_listing_rejection(
+            TokenId(11),
+            Address::random(),
+            "decimals are too large",
+            Some(eth_tx_hash),
+        )
+        .await?;
+    // The token is recorded once.
+    storage
+        .tokens_schema()
+        .store_listing_rejection(TokenId(10), address, "symbol is taken", None)
+        .await?;
+
+    let rejections = storage.tokens_schema().load_listing_rejections(10).await?;
+    assert_eq!(rejections.len(), 2);
+    assert_eq!(rejections[0].token_id, 11);
+    assert_eq!(
+        rejections[0].eth_tx_hash.as_deref(),
+        Some(eth_tx_hash.as_bytes())
+    );
+    assert_eq!(rejections[1].token_id, 10);
+    assert_eq!(rejections[1].reason, "listing fee is not paid");
+    assert_eq!(rejections[1].eth_tx_hash, None);
+
+    let rejections = storage.tokens_schema().load_listing_rejections(1).await?;
+    assert_eq!(rejections.len(), 1);
+
+    Ok(())
+}
+
+/// Checks that the tokens are paused and unpaused.
+#[db_test]
+async fn test_paused_tokens(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let token = Token::new(TokenId(1), Address::random(), "ABC", 18, TokenKind::ERC20);
+    storage.tokens_schema().store_or_update_token(token).await?;
+    assert!(storage
+        .tokens_schema()
+        .load_paused_tokens()
+        .await?
+        .is_empty());
+
+    assert!(storage.tokens_schema().pause_token(TokenId(1)).await?);
+    assert!(!storage.tokens_schema().pause_token(TokenId(1)).await?);
+    let paused_tokens = storage.tokens_schema().load_paused_tokens().await?;
+    assert_eq!(
+        paused_tokens.into_iter().collect::<Vec<_>>(),
+        vec![TokenId(1)]
+    );
+
+    assert!(storage.tokens_schema().unpause_token(TokenId(1)).await?);
+    assert!(!storage.tokens_schema().unpause_token(TokenId(1)).await?);
+    assert!(storage
+        .tokens_schema()
+        .load_paused_tokens()
+        .await?
+        .is_empty());
+
+    Ok(())
+}
+
+/// Checks the store/load routine for the token metadata.
+#[db_test]
+async fn test_token_metadata(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let token = Token::new(TokenId(1), Address::random(), "ABC", 6, TokenKind::ERC20);
+    storage.tokens_schema().store_or_update_token(token).await?;
+    assert!(storage
+        .tokens_schema()
+        .get_token_metadata(TokenId(1))
+        .await?
+        .is_none());
+
+    let mut metadata = TokenMetadata {
+        name: Some("ABC Token".to_string()),
+        onchain_decimals: Some(6),
+        logo_url: None,
+        description: None,
+    };
+    storage
+        .tokens_schema()
+        .store_token_metadata(TokenId(1), &metadata)
+        .await?;
+    let stored = storage
+        .tokens_schema()
+        .get_token_metadata(TokenId(1))
+        .await?
+        .unwrap();
+    assert_eq!(stored.name.as_deref(), Some("ABC Token"));
+    assert_eq!((stored.decimals, stored.onchain_decimals), (6, Some(6)));
+
+    // The metadata is replaced on update, the missing values are kept.
+    metadata.name = Some("ABC".to_string());
+    metadata.onchain_decimals = None;
+    metadata.logo_url = Some("https://example.com/abc.png".to_string());
+    storage
+        .tokens_schema()
+        .store_token_metadata(TokenId(1), &metadata)
+        .await?;
+    let stored = storage
+        .tokens_schema()
+        .get_token_metadata(TokenId(1))
+        .await?
+        .unwrap();
+    assert_eq!(stored.name.as_deref(), Some("ABC"));
+    assert_eq!(stored.onchain_decimals, Some(6));
+    assert_eq!(stored.logo_url, metadata.logo_url);
+
+    Ok(())
+}
+
+/// Checks the calculation of the token holders distribution.
+#[db_test]
+async fn test_token_holders(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let token = Token::new(TokenId(1), Address::random(), "ABC", 6, TokenKind::ERC20);
+    storage.tokens_schema().store_or_update_token(token).await?;
+    assert!(storage
+        .tokens_schema()
+        .get_token_holders(TokenId(1))
+        .await?
+        .is_none());
+
+    // 0.5, 1 and 5000 ABC.
+    let balances = [500_000u64, 1_000_000, 5_000_000_000];
+    let updates: Vec<_> = balances
+        .iter()
+        .enumerate()
+        .flat_map(|(id, balance)| {
+            let account_id = AccountId(id as u32 + 1);
+            vec![
+                (
+                    account_id,
+                    AccountUpdate::Create {
+                        address: Address::random(),
+                        nonce: Nonce(0),
+                    },
+                ),
+                (
+                    account_id,
+                    AccountUpdate::UpdateBalance {
+                        old_nonce: Nonce(0),
+                        new_nonce: Nonce(0),
+                        balance_update: (TokenId(1), BigUint::from(0u64), BigUint::from(*balance)),
+                    },
+                ),
+            ]
+        })
+        .collect();
+    StateSchema(&mut storage)
+        .commit_state_update(BlockNumber(1), &updates, 0)
+        .await?;
+    StateSchema(&mut storage)
+        .apply_state_update(BlockNumber(1))
+        .await?;
+
+    let thresholds: Vec<_> = ["0", "0.01", "1", "100", "10000"]
+        .iter()
+        .map(|threshold| BigDecimal::from_str(threshold).unwrap())
+        .collect();
+    storage
+        .tokens_schema()
+        .update_token_holders(&thresholds)
+        .await?;
+
+    let holders = storage
+        .tokens_schema()
+        .get_token_holders(TokenId(1))
+        .await?
+        .unwrap();
+    assert_eq!(holders.holders, 3);
+    let distribution: Vec<_> = holders
+        .distribution
+        .into_iter()
+        .map(|bucket| (bucket.min_balance, bucket.holders))
+        .collect();
+    assert_eq!(
+        distribution,
+        vec![
+            (thresholds[1].clone(), 1),
+            (thresholds[2].clone(), 1),
+            (thresholds[3].clone(), 1)
+        ]
+    );
+
+    // Tokens without holders are reported as well.
+    let holders = storage
+        .tokens_schema()
+        .get_token_holders(TokenId(0))
+        .await?
+        .unwrap();
+    assert_eq!(holders.holders, 0);
+    assert!(holders.distribution.is_empty());
+
+    Ok(())
+}
+
+/// Checks that the listing fee transfer can be consumed by a single token only.
+#[db_test]
+async fn test_listing_fee_payments(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let eth_tx_hash = H256::random();
+    assert!(
+        storage
+            .tokens_schema()
+            .consume_listing_fee_payment(eth_tx_hash, 3, TokenId(10))
+            .await?
+    );
+    assert!(
+        !storage
+            .tokens_schema()
+            .consume_listing_fee_payment(eth_tx_hash, 3, TokenId(11))
+            .await?
+    );
+    // Another transfer of the same transaction can still be consumed.
+    assert!(
+        storage
+            .tokens_schema()
+            .consume_listing_fee_payment(eth_tx_hash, 4, TokenId(11))
+            .await?
+    );
+
+    Ok(())
+}
+
 /// Checks the store/load routine for `ticker_price` table.
 #[db_test]
 async fn test_ticker_price(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
@@ -145,6 +381,11 @@ async fn test_ticker_price(mut storage: StorageProcessor<'_>) -> QueryResult<()>
         .get_historical_ticker_price(TOKEN_ID)
         .await?;
     assert!(loaded.is_none());
+    assert!(storage
+        .tokens_schema()
+        .get_last_ticker_price_update()
+        .await?
+        .is_none());
     // Store new price.
     // `usd_price` is not a finite decimal, so we expect it to be rounded
     // up to `STORED_USD_PRICE_PRECISION` digits.
@@ -175,6 +416,12 @@ async fn test_ticker_price(mut storage: StorageProcessor<'_>) -> QueryResult<()>
         loaded.last_updated.timestamp(),
         price.last_updated.timestamp()
     );
+    let last_update = storage
+        .tokens_schema()
+        .get_last_ticker_price_update()
+        .await?
+        .expect("couldn't load the last price update");
+    assert_eq!(last_update.timestamp(), price.last_updated.timestamp());
 
     Ok(())
 }
@@ -342,3 +589,240 @@ async fn test_nfts_with_factories(mut storage: StorageProcessor<'_>) -> QueryRes
 
     Ok(())
 }
+
+/// Checks that the pending factories are stored up to the limit and removed.
+#[db_test]
+async fn test_pending_nft_factories(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let factories: Vec<_> = (0..3)
+        .map(|id| RegisterNFTFactoryEvent {
+            factory_address: Address::random(),
+            creator_id: AccountId(id),
+            creator_address: Address::random(),
+            eth_block: 10 - id as u64,
+        })
+        .collect();
+
+    let mut tokens_schema = storage.tokens_schema();
+    assert!(
+        tokens_schema
+            .store_pending_nft_factory(&factories[0], 2)
+            .await?
+    );
+    assert!(
+        tokens_schema
+            .store_pending_nft_factory(&factories[1], 2)
+            .await?
+    );
+    // Storing the same registration again doesn't change anything.
+    tokens_schema
+        .store_pending_nft_factory(&factories[1], 3)
+        .await?;
+    assert!(
+        !tokens_schema
+            .store_pending_nft_factory(&factories[2], 2)
+            .await?
+    );
+
+    let pending = tokens_schema.load_pending_nft_factories().await?;
+    let pending: Vec<_> = pending
+        .iter()
+        .map(|factory| {
+            (
+                factory.creator_id,
+                factory.factory_address,
+                factory.eth_block,
+            )
+        })
+        .collect();
+    assert_eq!(
+        pending,
+        vec![
+            (AccountId(1), factories[1].factory_address, 9),
+            (AccountId(0), factories[0].factory_address, 10),
+        ]
+    );
+
+    // Registration is removed only for the matching creator address.
+    tokens_schema
+        .remove_pending_nft_factory(AccountId(1), Address::random())
+        .await?;
+    tokens_schema
+        .remove_pending_nft_factory(AccountId(0), factories[0].creator_address)
+        .await?;
+    let pending = tokens_schema.load_pending_nft_factories().await?;
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].creator_address, factories[1].creator_address);
+
+    assert!(
+        tokens_schema
+            .store_pending_nft_factory(&factories[2], 2)
+            .await?
+    );
+    Ok(())
+}
+
+/// Checks the life cycle of the NFT factory registrations.
+#[db_test]
+async fn test_nft_factory_registrations(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let newer = |from| PaginationQuery {
+        from,
+        limit: 10,
+        direction: PaginationDirection::Newer,
+    };
+
+    let creator_address = Address::random();
+    let factory_address = Address::random();
+    let signature = vec![1u8; 65];
+    let id = storage
+        .tokens_schema()
+        .store_nft_factory_registration(AccountId(1), creator_address, factory_address, &signature)
+        .await?;
+    storage
+        .tokens_schema()
+        .store_nft_factory_registration(
+            AccountId(2),
+            Address::random(),
+            Address::random(),
+            &signature,
+        )
+        .await?;
+
+    let registrations = storage
+        .tokens_schema()
+        .load_nft_factory_registrations(Some(AccountId(1)), false, &newer(0))
+        .await?;
+    assert_eq!(registrations.len(), 1);
+    assert_eq!(registrations[0].id, id);
+    assert_eq!(registrations[0].signature, signature);
+    assert!(!registrations[0].registered_on_chain);
+
+    // Once the factory is registered on the contract, it's reported along with the registration.
+    storage
+        .tokens_schema()
+        .store_nft_factory(AccountId(1), creator_address, factory_address)
+        .await?;
+    let registration = storage
+        .tokens_schema()
+        .load_nft_factory_registration(id)
+        .await?
+        .unwrap();
+    assert!(registration.registered_on_chain);
+
+    assert!(
+        storage
+            .tokens_schema()
+            .deprecate_nft_factory_registration(id)
+            .await?
+    );
+    assert!(
+        !storage
+            .tokens_schema()
+            .deprecate_nft_factory_registration(id)
+            .await?
+    );
+    assert!(storage
+        .tokens_schema()
+        .load_nft_factory_registrations(Some(AccountId(1)), false, &newer(0))
+        .await?
+        .is_empty());
+    let registrations = storage
+        .tokens_schema()
+        .load_nft_factory_registrations(None, true, &newer(0))
+        .await?;
+    assert_eq!(registrations.len(), 2);
+    assert!(registrations[0].deprecated_at.is_some());
+
+    // Replaying the registration doesn't restore the deprecated factory.
+    let new_id = storage
+        .tokens_schema()
+        .store_nft_factory_registration(AccountId(1), creator_address, factory_address, &signature)
+        .await?;
+    assert_eq!(new_id, id);
+    assert_eq!(
+        storage
+            .tokens_schema()
+            .get_nft_factory_registrations_count(None, false)
+            .await?
+            .0,
+        1
+    );
+    assert!(storage
+        .tokens_schema()
+        .load_nft_factory_registration(id)
+        .await?
+        .unwrap()
+        .deprecated_at
+        .is_some());
+
+    Ok(())
+}
+
+/// Checks the pagination of the NFT factory registrations.
+#[db_test]
+async fn test_nft_factory_registrations_pagination(
+    mut storage: StorageProcessor<'_>,
+) -> QueryResult<()> {
+    let mut ids = Vec::new();
+    for _ in 0..5 {
+        let id = storage
+            .tokens_schema()
+            .store_nft_factory_registration(
+                AccountId(1),
+                Address::random(),
+                Address::random(),
+                &[1u8; 65],
+            )
+            .await?;
+        ids.push(id);
+    }
+
+    let (count, last_id) = storage
+        .tokens_schema()
+        .get_nft_factory_registrations_count(Some(AccountId(1)), false)
+        .await?;
+    assert_eq!(count, 5);
+    assert_eq!(last_id, Some(ids[4]));
+
+    let page = |from, direction| PaginationQuery {
+        from,
+        limit: 2,
+        direction,
+    };
+    let loaded: Vec<i64> = storage
+        .tokens_schema()
+        .load_nft_factory_registrations(
+            Some(AccountId(1)),
+            false,
+            &page(ids[1], PaginationDirection::Newer),
+        )
+        .await?
+        .into_iter()
+        .map(|registration| registration.id)
+        .collect();
+    assert_eq!(loaded, vec![ids[1], ids[2]]);
+
+    let loaded: Vec<i64> = storage
+        .tokens_schema()
+        .load_nft_factory_registrations(
+            Some(AccountId(1)),
+            false,
+            &page(ids[4], PaginationDirection::Older),
+        )
+        .await?
+        .into_iter()
+        .map(|registration| registration.id)
+        .collect();
+    assert_eq!(loaded, vec![ids[4], ids[3]]);
+
+    assert!(storage
+        .tokens_schema()
+        .load_nft_factory_registrations(
+            Some(AccountId(2)),
+            false,
+            &page(0, PaginationDirection::Newer)
+        )
+        .await?
+        .is_empty());
+
+    Ok(())
+}
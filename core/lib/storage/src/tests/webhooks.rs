@@ -0,0 +1,152 @@
+// Built-in uses
+// External uses
+use chrono::{Duration, Utc};
+use serde_json::json;
+// Workspace uses
+use zksync_types::event::EventId;
+// Local uses
+use super::db_test;
+use crate::{QueryResult, StorageProcessor};
+
+/// Checks the life cycle of the webhook deliveries: they are enqueued once, claimed for the sending
+/// when due, moved to the dead letters once out of attempts, retried from there and pruned once delivered.
+#[db_test]
+async fn webhook_deliveries(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let filters = json!({ "block": { "status": "finalized" } });
+    let webhook_id = storage
+        .webhooks_schema()
+        .add_webhook("http://localhost/hook", "secret", &filters)
+        .await?;
+    let webhooks = storage.webhooks_schema().load_active_webhooks().await?;
+    assert_eq!(webhooks.len(), 1);
+    assert_eq!(webhooks[0].filters, filters);
+    let last_event_id = webhooks[0].last_event_id as u64;
+
+    let events: Vec<_> = (1..=3)
+        .map(|id| (EventId(last_event_id + id), json!({ "id": id })))
+        .collect();
+    storage
+        .webhooks_schema()
+        .enqueue_deliveries(webhook_id, &events, EventId(last_event_id + 5))
+        .await?;
+    // Events that are already enqueued are skipped.
+    storage
+        .webhooks_schema()
+        .enqueue_deliveries(webhook_id, &events[..1], EventId(last_event_id + 1))
+        .await?;
+    let webhooks = storage.webhooks_schema().load_active_webhooks().await?;
+    assert_eq!(webhooks[0].last_event_id as u64, last_event_id + 5);
+
+    let claimed_until = Utc::now() + Duration::minutes(1);
+    let deliveries = storage
+        .webhooks_schema()
+        .claim_pending_deliveries(10, claimed_until)
+        .await?;
+    assert_eq!(deliveries.len(), 3);
+    assert_eq!(deliveries[0].url, "http://localhost/hook");
+    assert_eq!(deliveries[0].payload, json!({ "id": 1 }));
+    // Claimed deliveries are not sent twice.
+    assert!(storage
+        .webhooks_schema()
+        .claim_pending_deliveries(10, claimed_until)
+        .await?
+        .is_empty());
+
+    // Delivered and postponed deliveries are not pending.
+    storage
+        .webhooks_schema()
+        .mark_delivered(deliveries[0].id)
+        .await?;
+    storage
+        .webhooks_schema()
+        .record_failed_attempt(
+            deliveries[1].id,
+            "timeout",
+            Some(Utc::now() + Duration::hours(1)),
+        )
+        .await?;
+    storage
+        .webhooks_schema()
+        .record_failed_attempt(deliveries[2].id, "timeout", None)
+        .await?;
+    assert!(storage
+        .webhooks_schema()
+        .claim_pending_deliveries(10, claimed_until)
+        .await?
+        .is_empty());
+
+    let dead_letters = storage
+        .webhooks_schema()
+        .load_dead_letters(webhook_id)
+        .await?;
+    assert_eq!(dead_letters.len(), 1);
+    assert_eq!(dead_letters[0].id, deliveries[2].id);
+    assert_eq!(dead_letters[0].attempts, 1);
+    assert_eq!(dead_letters[0].last_error.as_deref(), Some("timeout"));
+
+    // Dead letters are retried within their webhook only.
+    assert!(
+        !storage
+            .webhooks_schema()
+            .retry_dead_letter(webhook_id + 1, deliveries[2].id)
+            .await?
+    );
+    assert!(
+        storage
+            .webhooks_schema()
+            .retry_dead_letter(webhook_id, deliveries[2].id)
+            .await?
+    );
+    assert!(
+        !storage
+            .webhooks_schema()
+            .retry_dead_letter(webhook_id, deliveries[2].id)
+            .await?
+    );
+    let pending = storage
+        .webhooks_schema()
+        .claim_pending_deliveries(10, claimed_until)
+        .await?;
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].attempts, 0);
+
+    // Only the delivered payloads are pruned.
+    assert_eq!(
+        storage
+            .webhooks_schema()
+            .prune_delivered(Utc::now() + Duration::minutes(1))
+            .await?,
+        1
+    );
+    assert_eq!(
+        storage
+            .webhooks_schema()
+            .prune_delivered(Utc::now() + Duration::minutes(1))
+            .await?,
+        0
+    );
+
+    // Deliveries of the deactivated webhooks are not sent.
+    storage
+        .webhooks_schema()
+        .record_failed_attempt(pending[0].id, "timeout", Some(Utc::now()))
+        .await?;
+    assert!(
+        storage
+            .webhooks_schema()
+            .deactivate_webhook(webhook_id)
+            .await?
+    );
+    assert!(storage
+        .webhooks_schema()
+        .claim_pending_deliveries(10, claimed_until)
+        .await?
+        .is_empty());
+    assert!(storage
+        .webhooks_schema()
+        .load_active_webhooks()
+        .await?
+        .is_empty());
+
+    Ok(())
+}
@@ -1,23 +1,33 @@
 // Built-in deps
 use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
 use std::time::Instant;
 // External imports
+use chrono::{DateTime, Utc};
 use num::{rational::Ratio, BigUint};
+use sqlx::types::BigDecimal;
 
 use thiserror::Error;
 // Workspace imports
 use zksync_api_types::v02::{
     pagination::{PaginationDirection, PaginationQuery},
-    token::ApiNFT,
+    token::{ApiNFT, ApiTokenHolders},
+};
+use zksync_crypto::params::MIN_NFT_TOKEN_ID;
+use zksync_types::{
+    AccountId, Address, RegisterNFTFactoryEvent, Token, TokenId, TokenLike, TokenPrice, H256, NFT,
 };
-use zksync_types::{AccountId, Address, Token, TokenId, TokenLike, TokenPrice, NFT};
 use zksync_utils::ratio_to_big_decimal;
 // Local imports
-use self::records::{DBMarketVolume, DbTickerPrice, DbToken, StorageApiNFT, StorageNFT, TokenKind};
+use self::records::{
+    DBMarketVolume, DbTickerPrice, DbToken, DbTokenHolders, DbTokenListingRejection,
+    DbTokenMetadata, StorageApiNFT, StorageNFT, StorageNFTFactoryRegistration,
+    StoragePendingNFTFactory, TokenKind,
+};
 
 use crate::utils::address_to_stored_string;
 use crate::{QueryResult, StorageProcessor};
-use zksync_types::tokens::TokenMarketVolume;
+use zksync_types::tokens::{TokenMarketVolume, TokenMetadata};
 
 pub mod records;
 
@@ -95,7 +105,7 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
         .await
         .map_err(|err| StoreTokenError::Other(err.into()))?;
 
-        metrics::histogram!("sql.token.store_token", start.elapsed());
+        sql_histogram!(self.0, "sql.token.store_token", start.elapsed());
         Ok(())
     }
 
@@ -121,7 +131,7 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
         .execute(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql.token.store_token", start.elapsed());
+        sql_histogram!(self.0, "sql.token.store_token", start.elapsed());
         Ok(())
     }
 
@@ -148,7 +158,7 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
         .await?;
 
         let result = tokens.into_iter().map(Token::from).collect();
-        metrics::histogram!("sql.token.load_tokens_asc", start.elapsed());
+        sql_histogram!(self.0, "sql.token.load_tokens_asc", start.elapsed());
         Ok(result)
     }
 
@@ -175,7 +185,7 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
         .await?;
 
         let result = tokens.into_iter().map(Token::from).collect();
-        metrics::histogram!("sql.token.load_tokens_desc", start.elapsed());
+        sql_histogram!(self.0, "sql.token.load_tokens_desc", start.elapsed());
         Ok(result)
     }
 
@@ -226,7 +236,7 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
         .map(|nft| (TokenId(nft.token_id as u32), nft.into()))
         .collect();
 
-        metrics::histogram!("sql.token.load_nfts", start.elapsed());
+        sql_histogram!(self.0, "sql.token.load_nfts", start.elapsed());
         Ok(nfts)
     }
 
@@ -261,7 +271,11 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
             })
             .collect());
 
-        metrics::histogram!("sql.token.load_tokens_by_market_volume", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.token.load_tokens_by_market_volume",
+            start.elapsed()
+        );
         result
     }
 
@@ -295,7 +309,8 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
             result.insert(TokenId(0));
         }
 
-        metrics::histogram!(
+        sql_histogram!(
+            self.0,
             "sql.token.load_token_ids_that_enabled_for_fees",
             start.elapsed()
         );
@@ -314,7 +329,7 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
         .await?
         .count;
 
-        metrics::histogram!("sql.token.get_count", start.elapsed());
+        sql_histogram!(self.0, "sql.token.get_count", start.elapsed());
         Ok(count as u32)
     }
 
@@ -331,7 +346,7 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
         .map(|token| token.id)
         .unwrap_or(0);
 
-        metrics::histogram!("sql.token.get_max_erc20_token_id", start.elapsed());
+        sql_histogram!(self.0, "sql.token.get_max_erc20_token_id", start.elapsed());
         Ok(last_token_id as u32)
     }
 
@@ -348,7 +363,7 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
         .map(|token| token.id)
         .unwrap_or(0);
 
-        metrics::histogram!("sql.token.get_max_token_id", start.elapsed());
+        sql_histogram!(self.0, "sql.token.get_max_token_id", start.elapsed());
         Ok(last_token_id as u32)
     }
 
@@ -367,7 +382,7 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
         )
         .fetch_optional(self.0.conn())
         .await?;
-        metrics::histogram!("sql.token.get_nft", start.elapsed());
+        sql_histogram!(self.0, "sql.token.get_nft", start.elapsed());
         Ok(db_token.map(|t| t.into()))
     }
 
@@ -397,7 +412,7 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
         )
         .fetch_optional(self.0.conn())
         .await?;
-        metrics::histogram!("sql.token.get_nft_with_factories", start.elapsed());
+        sql_histogram!(self.0, "sql.token.get_nft_with_factories", start.elapsed());
         Ok(db_token.map(|t| t.into()))
     }
 
@@ -453,7 +468,7 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
             }
         };
 
-        metrics::histogram!("sql.token.get_token", start.elapsed());
+        sql_histogram!(self.0, "sql.token.get_token", start.elapsed());
         Ok(db_token.map(|t| t.into()))
     }
 
@@ -474,7 +489,7 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
         .fetch_optional(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql.token.get_market_volume", start.elapsed());
+        sql_histogram!(self.0, "sql.token.get_market_volume", start.elapsed());
         Ok(db_market_volume.map(|p| p.into()))
     }
 
@@ -501,7 +516,7 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
         .fetch_optional(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql.token.update_market_volume", start.elapsed());
+        sql_histogram!(self.0, "sql.token.update_market_volume", start.elapsed());
         Ok(())
     }
 
@@ -523,10 +538,32 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
         .fetch_optional(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql.token.get_historical_ticker_price", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.token.get_historical_ticker_price",
+            start.elapsed()
+        );
         Ok(db_price.map(|p| p.into()))
     }
 
+    /// Returns the time of the latest update of the token prices,
+    /// `None` if there are no prices stored.
+    pub async fn get_last_ticker_price_update(&mut self) -> QueryResult<Option<DateTime<Utc>>> {
+        let start = Instant::now();
+        let last_updated =
+            sqlx::query!("SELECT MAX(last_updated) as last_updated FROM ticker_price")
+                .fetch_one(self.0.conn())
+                .await?
+                .last_updated;
+
+        sql_histogram!(
+            self.0,
+            "sql.token.get_last_ticker_price_update",
+            start.elapsed()
+        );
+        Ok(last_updated)
+    }
+
     /// Updates price in USD for the given token.
     ///
     /// Note, that the price precision cannot be greater than `STORED_USD_PRICE_PRECISION`,
@@ -553,7 +590,11 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
         .fetch_optional(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql.token.update_historical_ticker_price", start.elapsed());
+        sql_histogram!(
+            self.0,
+            "sql.token.update_historical_ticker_price",
+            start.elapsed()
+        );
         Ok(())
     }
 
@@ -579,7 +620,509 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
         .fetch_optional(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql.token.store_nft_factory", start.elapsed());
+        sql_histogram!(self.0, "sql.token.store_nft_factory", start.elapsed());
         Ok(())
     }
+
+    /// Stores the factory registered on L1 for the creator account that doesn't exist yet.
+    /// Returns `false` if the registration is not stored since there are already `limit`
+    /// pending registrations. Storing the same registration again is a no-op.
+    pub async fn store_pending_nft_factory(
+        &mut self,
+        event: &RegisterNFTFactoryEvent,
+        limit: u32,
+    ) -> QueryResult<bool> {
+        let start = Instant::now();
+        let count = sqlx::query!(r#"SELECT COUNT(*) as "count!" FROM pending_nft_factories"#)
+            .fetch_one(self.0.conn())
+            .await?
+            .count;
+
+        let is_stored = count < limit as i64;
+        if is_stored {
+            sqlx::query!(
+                r#"
+                INSERT INTO pending_nft_factories ( creator_id, creator_address, factory_address, eth_block )
+                VALUES ( $1, $2, $3, $4 )
+                ON CONFLICT ( creator_id, creator_address ) DO NOTHING
+                "#,
+                event.creator_id.0 as i32,
+                address_to_stored_string(&event.creator_address),
+                address_to_stored_string(&event.factory_address),
+                event.eth_block as i64,
+            )
+            .execute(self.0.conn())
+            .await?;
+        }
+
+        sql_histogram!(
+            self.0,
+            "sql.token.store_pending_nft_factory",
+            start.elapsed()
+        );
+        Ok(is_stored)
+    }
+
+    /// Loads the factories registered on L1 for the creator accounts that didn't exist yet,
+    /// the oldest registrations go first.
+    pub async fn load_pending_nft_factories(
+        &mut self,
+    ) -> QueryResult<Vec<RegisterNFTFactoryEvent>> {
+        let start = Instant::now();
+        let factories = sqlx::query_as!(
+            StoragePendingNFTFactory,
+            r#"
+            SELECT creator_id, creator_address, factory_address, eth_block
+            FROM pending_nft_factories
+            ORDER BY eth_block, creator_id
+            "#
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        sql_histogram!(
+            self.0,
+            "sql.token.load_pending_nft_factories",
+            start.elapsed()
+        );
+        Ok(factories.into_iter().map(Into::into).collect())
+    }
+
+    /// Removes the pending factory registration once the creator account is created.
+    pub async fn remove_pending_nft_factory(
+        &mut self,
+        creator_id: AccountId,
+        creator_address: Address,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "DELETE FROM pending_nft_factories WHERE creator_id = $1 AND creator_address = $2",
+            creator_id.0 as i32,
+            address_to_stored_string(&creator_address),
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        sql_histogram!(
+            self.0,
+            "sql.token.remove_pending_nft_factory",
+            start.elapsed()
+        );
+        Ok(())
+    }
+
+    /// Records the token that failed the listing validation, so the operators can review it.
+    /// The token is recorded once, the following rejections are ignored.
+    pub async fn store_listing_rejection(
+        &mut self,
+        token_id: TokenId,
+        address: Address,
+        reason: &str,
+        eth_tx_hash: Option<H256>,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            r#"
+            INSERT INTO token_listing_rejections ( token_id, address, reason, eth_tx_hash )
+            VALUES ( $1, $2, $3, $4 )
+            ON CONFLICT ( token_id ) DO NOTHING
+            "#,
+            *token_id as i32,
+            address_to_stored_string(&address),
+            reason,
+            eth_tx_hash.map(|hash| hash.as_bytes().to_vec()),
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        sql_histogram!(self.0, "sql.token.store_listing_rejection", start.elapsed());
+        Ok(())
+    }
+
+    /// Marks the listing fee transfer (identified by the transaction hash and the log index) as consumed
+    /// by the token. Returns `false` if the transfer is already consumed by another token.
+    pub async fn consume_listing_fee_payment(
+        &mut self,
+        eth_tx_hash: H256,
+        log_index: u64,
+        token_id: TokenId,
+    ) -> QueryResult<bool> {
+        let start = Instant::now();
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO token_listing_fee_payments ( eth_tx_hash, log_index, token_id )
+            VALUES ( $1, $2, $3 )
+            ON CONFLICT ( eth_tx_hash, log_index ) DO NOTHING
+            "#,
+            eth_tx_hash.as_bytes(),
+            log_index as i64,
+            *token_id as i32,
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        sql_histogram!(
+            self.0,
+            "sql.token.consume_listing_fee_payment",
+            start.elapsed()
+        );
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Loads up to `limit` rejected token listings, the latest first.
+    pub async fn load_listing_rejections(
+        &mut self,
+        limit: u32,
+    ) -> QueryResult<Vec<DbTokenListingRejection>> {
+        let start = Instant::now();
+        let rejections = sqlx::query_as!(
+            DbTokenListingRejection,
+            r#"
+            SELECT * FROM token_listing_rejections
+            ORDER BY token_id DESC
+            LIMIT $1
+            "#,
+            i64::from(limit)
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        sql_histogram!(self.0, "sql.token.load_listing_rejections", start.elapsed());
+        Ok(rejections)
+    }
+
+    /// Pauses the token, so it can't be transferred or swapped until it's unpaused.
+    /// Returns `false` if the token is already paused.
+    pub async fn pause_token(&mut self, token_id: TokenId) -> QueryResult<bool> {
+        let start = Instant::now();
+        let result = sqlx::query!(
+            "INSERT INTO paused_tokens (token_id) VALUES ($1) ON CONFLICT (token_id) DO NOTHING",
+            *token_id as i32
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        sql_histogram!(self.0, "sql.token.pause_token", start.elapsed());
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Unpauses the token. Returns `false` if the token isn't paused.
+    pub async fn unpause_token(&mut self, token_id: TokenId) -> QueryResult<bool> {
+        let start = Instant::now();
+        let result = sqlx::query!(
+            "DELETE FROM paused_tokens WHERE token_id = $1",
+            *token_id as i32
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        sql_histogram!(self.0, "sql.token.unpause_token", start.elapsed());
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Loads the ids of the paused tokens.
+    pub async fn load_paused_tokens(&mut self) -> QueryResult<HashSet<TokenId>> {
+        let start = Instant::now();
+        let token_ids = sqlx::query!("SELECT token_id FROM paused_tokens")
+            .fetch_all(self.0.conn())
+            .await?
+            .into_iter()
+            .map(|row| TokenId(row.token_id as u32))
+            .collect();
+
+        sql_histogram!(self.0, "sql.token.load_paused_tokens", start.elapsed());
+        Ok(token_ids)
+    }
+
+    /// Stores the factory registration signed by the creator. If the factory is already registered
+    /// for the creator, the signature is updated. A deprecated registration stays deprecated:
+    /// the registration message is the same every time, so a replayed signature must not restore it.
+    /// Returns the id of the registration.
+    pub async fn store_nft_factory_registration(
+        &mut self,
+        creator_id: AccountId,
+        creator_address: Address,
+        factory_address: Address,
+        signature: &[u8],
+    ) -> QueryResult<i64> {
+        let start = Instant::now();
+        let id = sqlx::query!(
+            r#"
+            INSERT INTO nft_factory_registrations ( creator_id, creator_address, factory_address, signature )
+            VALUES ( $1, $2, $3, $4 )
+            ON CONFLICT ( creator_id, factory_address )
+            DO UPDATE
+            SET creator_address = $2, signature = $4
+            RETURNING id
+            "#,
+            *creator_id as i32,
+            address_to_stored_string(&creator_address),
+            address_to_stored_string(&factory_address),
+            signature,
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .id;
+
+        sql_histogram!(
+            self.0,
+            "sql.token.store_nft_factory_registration",
+            start.elapsed()
+        );
+        Ok(id)
+    }
+
+    /// Loads the factory registration by its id, including the deprecated one.
+    pub async fn load_nft_factory_registration(
+        &mut self,
+        id: i64,
+    ) -> QueryResult<Option<StorageNFTFactoryRegistration>> {
+        let start = Instant::now();
+        let registration = sqlx::query_as!(
+            StorageNFTFactoryRegistration,
+            r#"
+            SELECT nft_factory_registrations.id, nft_factory_registrations.creator_id,
+                nft_factory_registrations.creator_address, nft_factory_registrations.factory_address,
+                nft_factory_registrations.signature, nft_factory_registrations.created_at,
+                nft_factory_registrations.deprecated_at,
+                nft_factory.creator_id IS NOT NULL as "registered_on_chain!"
+            FROM nft_factory_registrations
+            LEFT JOIN nft_factory
+                ON nft_factory.creator_id = nft_factory_registrations.creator_id
+                AND nft_factory.factory_address = nft_factory_registrations.factory_address
+            WHERE nft_factory_registrations.id = $1
+            "#,
+            id
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        sql_histogram!(
+            self.0,
+            "sql.token.load_nft_factory_registration",
+            start.elapsed()
+        );
+        Ok(registration)
+    }
+
+    /// Loads the page of the factory registrations, either of the given creator or all of them,
+    /// starting from the registration with the `query.from` id.
+    /// Deprecated registrations are skipped unless `include_deprecated` is set.
+    pub async fn load_nft_factory_registrations(
+        &mut self,
+        creator_id: Option<AccountId>,
+        include_deprecated: bool,
+        query: &PaginationQuery<i64>,
+    ) -> QueryResult<Vec<StorageNFTFactoryRegistration>> {
+        let start = Instant::now();
+        let registrations = sqlx::query_as!(
+            StorageNFTFactoryRegistration,
+            r#"
+            SELECT nft_factory_registrations.id, nft_factory_registrations.creator_id,
+                nft_factory_registrations.creator_address, nft_factory_registrations.factory_address,
+                nft_factory_registrations.signature, nft_factory_registrations.created_at,
+                nft_factory_registrations.deprecated_at,
+                nft_factory.creator_id IS NOT NULL as "registered_on_chain!"
+            FROM nft_factory_registrations
+            LEFT JOIN nft_factory
+                ON nft_factory.creator_id = nft_factory_registrations.creator_id
+                AND nft_factory.factory_address = nft_factory_registrations.factory_address
+            WHERE ($1::integer IS NULL OR nft_factory_registrations.creator_id = $1)
+                AND ($2 OR nft_factory_registrations.deprecated_at IS NULL)
+                AND (
+                    ($3 AND nft_factory_registrations.id >= $4)
+                    OR (NOT $3 AND nft_factory_registrations.id <= $4)
+                )
+            ORDER BY CASE WHEN $3 THEN nft_factory_registrations.id ELSE -nft_factory_registrations.id END ASC
+            LIMIT $5
+            "#,
+            creator_id.map(|id| *id as i32),
+            include_deprecated,
+            query.direction == PaginationDirection::Newer,
+            query.from,
+            i64::from(query.limit),
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        sql_histogram!(
+            self.0,
+            "sql.token.load_nft_factory_registrations",
+            start.elapsed()
+        );
+        Ok(registrations)
+    }
+
+    /// Returns the number and the last id of the factory registrations,
+    /// either of the given creator or all of them.
+    pub async fn get_nft_factory_registrations_count(
+        &mut self,
+        creator_id: Option<AccountId>,
+        include_deprecated: bool,
+    ) -> QueryResult<(u32, Option<i64>)> {
+        let start = Instant::now();
+        let record = sqlx::query!(
+            r#"
+            SELECT COUNT(*) as "count!", MAX(id) as last_id FROM nft_factory_registrations
+            WHERE ($1::integer IS NULL OR creator_id = $1)
+                AND ($2 OR deprecated_at IS NULL)
+            "#,
+            creator_id.map(|id| *id as i32),
+            include_deprecated,
+        )
+        .fetch_one(self.0.conn())
+        .await?;
+
+        sql_histogram!(
+            self.0,
+            "sql.token.get_nft_factory_registrations_count",
+            start.elapsed()
+        );
+        Ok((record.count as u32, record.last_id))
+    }
+
+    /// Marks the factory registration as deprecated, so it's no longer served to the users.
+    /// Returns `false` if there's no active registration with such id.
+    pub async fn deprecate_nft_factory_registration(&mut self, id: i64) -> QueryResult<bool> {
+        let start = Instant::now();
+        let result = sqlx::query!(
+            "UPDATE nft_factory_registrations SET deprecated_at = now()
+            WHERE id = $1 AND deprecated_at IS NULL",
+            id
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        sql_histogram!(
+            self.0,
+            "sql.token.deprecate_nft_factory_registration",
+            start.elapsed()
+        );
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Stores the metadata of the token, replacing the previous one. The missing values don't
+    /// overwrite the stored ones, so the metadata isn't lost if its source is temporarily unavailable.
+    pub async fn store_token_metadata(
+        &mut self,
+        token_id: TokenId,
+        metadata: &TokenMetadata,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            r#"
+            INSERT INTO token_metadata ( token_id, name, onchain_decimals, logo_url, description )
+            VALUES ( $1, $2, $3, $4, $5 )
+            ON CONFLICT ( token_id )
+            DO UPDATE
+            SET name = COALESCE($2, token_metadata.name),
+                onchain_decimals = COALESCE($3, token_metadata.onchain_decimals),
+                logo_url = COALESCE($4, token_metadata.logo_url),
+                description = COALESCE($5, token_metadata.description),
+                updated_at = now()
+            "#,
+            *token_id as i32,
+            metadata.name,
+            metadata.onchain_decimals.map(i16::from),
+            metadata.logo_url,
+            metadata.description,
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        sql_histogram!(self.0, "sql.token.store_token_metadata", start.elapsed());
+        Ok(())
+    }
+
+    /// Loads the metadata of the token along with the decimals known to the network,
+    /// `None` if the metadata isn't collected yet.
+    pub async fn get_token_metadata(
+        &mut self,
+        token_id: TokenId,
+    ) -> QueryResult<Option<DbTokenMetadata>> {
+        let start = Instant::now();
+        let metadata = sqlx::query_as!(
+            DbTokenMetadata,
+            r#"
+            SELECT token_metadata.token_id, token_metadata.name, tokens.decimals,
+                token_metadata.onchain_decimals, token_metadata.logo_url,
+                token_metadata.description, token_metadata.updated_at
+            FROM token_metadata
+            INNER JOIN tokens ON tokens.id = token_metadata.token_id
+            WHERE token_metadata.token_id = $1
+            "#,
+            *token_id as i32
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        sql_histogram!(self.0, "sql.token.get_token_metadata", start.elapsed());
+        Ok(metadata)
+    }
+
+    /// Recalculates the amount of the holders of every token (except NFTs) in the finalized state
+    /// and the distribution of their balances.
+    ///
+    /// `thresholds` are the ascending min balances of the distribution buckets in the token units,
+    /// the first one should be zero so every holder falls into some bucket.
+    pub async fn update_token_holders(&mut self, thresholds: &[BigDecimal]) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            r#"
+            INSERT INTO token_holders ( token_id, holders, distribution )
+            SELECT tokens.id, COALESCE(SUM(buckets.holders), 0)::bigint,
+                COALESCE(
+                    jsonb_agg(
+                        jsonb_build_object('minBalance', buckets.min_balance::text, 'holders', buckets.holders)
+                        ORDER BY buckets.min_balance
+                    ) FILTER (WHERE buckets.holders IS NOT NULL),
+                    '[]'::jsonb
+                )
+            FROM tokens
+            LEFT JOIN (
+                SELECT balances.coin_id,
+                    ($1::numeric[])[width_bucket(balances.balance / power(10::numeric, tokens.decimals), $1::numeric[])] AS min_balance,
+                    COUNT(*) AS holders
+                FROM balances
+                INNER JOIN tokens ON tokens.id = balances.coin_id
+                WHERE balances.balance > 0 AND balances.coin_id < $2
+                GROUP BY 1, 2
+            ) buckets ON buckets.coin_id = tokens.id
+            WHERE tokens.id < $2
+            GROUP BY tokens.id
+            ON CONFLICT ( token_id )
+            DO UPDATE
+            SET holders = EXCLUDED.holders, distribution = EXCLUDED.distribution, updated_at = now()
+            "#,
+            thresholds,
+            MIN_NFT_TOKEN_ID as i32
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        sql_histogram!(self.0, "sql.token.update_token_holders", start.elapsed());
+        Ok(())
+    }
+
+    /// Loads the holders of the token, `None` if they aren't collected yet.
+    pub async fn get_token_holders(
+        &mut self,
+        token_id: TokenId,
+    ) -> QueryResult<Option<ApiTokenHolders>> {
+        let start = Instant::now();
+        let holders = sqlx::query_as!(
+            DbTokenHolders,
+            "SELECT * FROM token_holders WHERE token_id = $1",
+            *token_id as i32
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+        let holders = match holders {
+            Some(holders) => Some(ApiTokenHolders::try_from(holders)?),
+            None => None,
+        };
+
+        sql_histogram!(self.0, "sql.token.get_token_holders", start.elapsed());
+        Ok(holders)
+    }
 }
@@ -1,5 +1,5 @@
 // Built-in imports
-use std::str::FromStr;
+use std::{convert::TryFrom, str::FromStr};
 // External imports
 use serde::{Deserialize, Serialize};
 use sqlx::{types::BigDecimal, FromRow};
@@ -7,10 +7,14 @@ use sqlx::{types::BigDecimal, FromRow};
 // Local imports
 use crate::utils::{address_to_stored_string, stored_str_address_to_address};
 use chrono::{DateTime, Utc};
-use zksync_api_types::v02::token::ApiNFT;
+use zksync_api_types::v02::token::{
+    ApiNFT, ApiTokenHolders, ApiTokenMetadata, NFTFactoryRegistration, TokenListingRejection,
+};
 use zksync_types::{
+    register_factory::register_factory_message,
     tokens::{TokenMarketVolume, TokenPrice},
-    AccountId, Address, Token, TokenId, H256, NFT,
+    tx::PackedEthSignature,
+    AccountId, Address, RegisterNFTFactoryEvent, Token, TokenId, H256, NFT,
 };
 use zksync_utils::big_decimal_to_ratio;
 
@@ -168,3 +172,124 @@ impl From<DBMarketVolume> for TokenMarketVolume {
         }
     }
 }
+
+#[derive(Debug, Clone, FromRow)]
+pub struct StoragePendingNFTFactory {
+    pub creator_id: i32,
+    pub creator_address: String,
+    pub factory_address: String,
+    pub eth_block: i64,
+}
+
+impl From<StoragePendingNFTFactory> for RegisterNFTFactoryEvent {
+    fn from(val: StoragePendingNFTFactory) -> Self {
+        Self {
+            factory_address: stored_str_address_to_address(&val.factory_address),
+            creator_id: AccountId(val.creator_id as u32),
+            creator_address: stored_str_address_to_address(&val.creator_address),
+            eth_block: val.eth_block as u64,
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct DbTokenListingRejection {
+    pub token_id: i32,
+    pub address: String,
+    pub reason: String,
+    pub eth_tx_hash: Option<Vec<u8>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<DbTokenListingRejection> for TokenListingRejection {
+    fn from(val: DbTokenListingRejection) -> Self {
+        Self {
+            token_id: TokenId(val.token_id as u32),
+            address: stored_str_address_to_address(&val.address),
+            reason: val.reason,
+            eth_tx_hash: val.eth_tx_hash.map(|hash| H256::from_slice(&hash)),
+            created_at: val.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct StorageNFTFactoryRegistration {
+    pub id: i64,
+    pub creator_id: i32,
+    pub creator_address: String,
+    pub factory_address: String,
+    pub signature: Vec<u8>,
+    pub created_at: DateTime<Utc>,
+    pub deprecated_at: Option<DateTime<Utc>>,
+    pub registered_on_chain: bool,
+}
+
+impl From<StorageNFTFactoryRegistration> for NFTFactoryRegistration {
+    fn from(val: StorageNFTFactoryRegistration) -> Self {
+        let creator_id = AccountId(val.creator_id as u32);
+        let creator_address = stored_str_address_to_address(&val.creator_address);
+        let factory_address = stored_str_address_to_address(&val.factory_address);
+        let message = register_factory_message(creator_id, creator_address, factory_address);
+        Self {
+            id: val.id,
+            creator_id,
+            creator_address,
+            factory_address,
+            message: String::from_utf8(message).expect("registration message is ASCII"),
+            signature: PackedEthSignature::deserialize_packed(&val.signature)
+                .expect("failed to deserialize stored signature"),
+            registered_on_chain: val.registered_on_chain,
+            created_at: val.created_at,
+            deprecated_at: val.deprecated_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct DbTokenMetadata {
+    pub token_id: i32,
+    pub name: Option<String>,
+    pub decimals: i16,
+    pub onchain_decimals: Option<i16>,
+    pub logo_url: Option<String>,
+    pub description: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<DbTokenMetadata> for ApiTokenMetadata {
+    fn from(val: DbTokenMetadata) -> Self {
+        Self {
+            token_id: TokenId(val.token_id as u32),
+            name: val.name,
+            decimals: val.decimals as u8,
+            onchain_decimals: val.onchain_decimals.map(|decimals| decimals as u8),
+            decimals_verified: val.onchain_decimals == Some(val.decimals),
+            logo_url: val.logo_url,
+            description: val.description,
+            updated_at: val.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct DbTokenHolders {
+    pub token_id: i32,
+    pub holders: i64,
+    /// JSON encoded list of `TokenHoldersBucket`.
+    pub distribution: serde_json::Value,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TryFrom<DbTokenHolders> for ApiTokenHolders {
+    type Error = serde_json::Error;
+
+    fn try_from(val: DbTokenHolders) -> Result<Self, Self::Error> {
+        Ok(Self {
+            token_id: TokenId(val.token_id as u32),
+            holders: val.holders as u64,
+            distribution: serde_json::from_value(val.distribution)?,
+            updated_at: val.updated_at,
+        })
+    }
+}
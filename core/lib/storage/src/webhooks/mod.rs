@@ -0,0 +1,270 @@
+// Built-in deps
+use std::time::Instant;
+// External imports
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+// Workspace imports
+use zksync_types::event::EventId;
+// Local imports
+use crate::{QueryResult, StorageProcessor};
+use records::{PendingWebhookDelivery, StoredWebhook, WebhookDeadLetter};
+
+pub mod records;
+
+/// Webhooks schema keeps the webhooks registered by the integrators and the queue
+/// of the events to be delivered to them.
+///
+/// Every webhook tracks the last event enqueued for it, so the events emitted while
+/// the dispatcher is down are delivered once it's back. Deliveries that ran out
+/// of attempts remain in the `webhook_dead_letters` view until they are retried.
+#[derive(Debug)]
+pub struct WebhooksSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> WebhooksSchema<'a, 'c> {
+    /// Registers the webhook, it's notified about the events emitted after the registration only.
+    /// Returns the id of the webhook.
+    pub async fn add_webhook(
+        &mut self,
+        url: &str,
+        secret: &str,
+        filters: &Value,
+    ) -> QueryResult<i64> {
+        let start = Instant::now();
+        let id = sqlx::query!(
+            "INSERT INTO webhooks (url, secret, filters, last_event_id)
+            VALUES ($1, $2, $3, COALESCE((SELECT MAX(id) FROM events), 0))
+            RETURNING id",
+            url,
+            secret,
+            filters,
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .id;
+
+        sql_histogram!(self.0, "sql.webhooks.add_webhook", start.elapsed());
+        Ok(id)
+    }
+
+    /// Stops the delivery of the events to the webhook, the pending ones are not sent either.
+    /// Returns `false` if there's no active webhook with such id.
+    pub async fn deactivate_webhook(&mut self, id: i64) -> QueryResult<bool> {
+        let start = Instant::now();
+        let result = sqlx::query!(
+            "UPDATE webhooks SET is_active = false WHERE id = $1 AND is_active",
+            id
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        sql_histogram!(self.0, "sql.webhooks.deactivate_webhook", start.elapsed());
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Loads the active webhooks ordered by id.
+    pub async fn load_active_webhooks(&mut self) -> QueryResult<Vec<StoredWebhook>> {
+        let start = Instant::now();
+        let webhooks = sqlx::query_as!(
+            StoredWebhook,
+            "SELECT * FROM webhooks WHERE is_active ORDER BY id ASC"
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        sql_histogram!(self.0, "sql.webhooks.load_active_webhooks", start.elapsed());
+        Ok(webhooks)
+    }
+
+    /// Enqueues the delivery of the events to the webhook and moves its cursor to the `last_event_id`.
+    /// Events that are already enqueued are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `webhook_id` - Id of the webhook
+    /// * `events` - Ids of the events along with their payloads
+    /// * `last_event_id` - Id of the last processed event, matching the webhook or not
+    ///
+    pub async fn enqueue_deliveries(
+        &mut self,
+        webhook_id: i64,
+        events: &[(EventId, Value)],
+        last_event_id: EventId,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        let (event_ids, payloads): (Vec<i64>, Vec<Value>) = events
+            .iter()
+            .map(|(id, payload)| (**id as i64, payload.clone()))
+            .unzip();
+
+        let mut transaction = self.0.start_transaction().await?;
+        sqlx::query!(
+            "INSERT INTO webhook_deliveries (webhook_id, event_id, payload)
+            SELECT $1, u.event_id, u.payload
+                FROM UNNEST ($2::bigint[], $3::jsonb[])
+                AS u(event_id, payload)
+            ON CONFLICT (webhook_id, event_id) DO NOTHING",
+            webhook_id,
+            &event_ids,
+            &payloads,
+        )
+        .execute(transaction.conn())
+        .await?;
+        sqlx::query!(
+            "UPDATE webhooks SET last_event_id = GREATEST(last_event_id, $2) WHERE id = $1",
+            webhook_id,
+            *last_event_id as i64,
+        )
+        .execute(transaction.conn())
+        .await?;
+        transaction.commit().await?;
+
+        sql_histogram!(self.0, "sql.webhooks.enqueue_deliveries", start.elapsed());
+        Ok(())
+    }
+
+    /// Claims up to `limit` deliveries of the active webhooks due to be sent, the oldest first.
+    ///
+    /// Claimed deliveries are postponed until `claimed_until`, so the concurrent dispatchers don't
+    /// send them twice, and the ones not reported back (e.g. if the dispatcher has crashed) are retried
+    /// after that. Rows locked by the concurrent claims are skipped.
+    pub async fn claim_pending_deliveries(
+        &mut self,
+        limit: i64,
+        claimed_until: DateTime<Utc>,
+    ) -> QueryResult<Vec<PendingWebhookDelivery>> {
+        let start = Instant::now();
+        let deliveries = sqlx::query_as!(
+            PendingWebhookDelivery,
+            r#"
+            WITH claimed AS (
+                UPDATE webhook_deliveries SET next_attempt_at = $2
+                WHERE id IN (
+                    SELECT webhook_deliveries.id FROM webhook_deliveries
+                    INNER JOIN webhooks ON webhooks.id = webhook_deliveries.webhook_id
+                    WHERE delivered_at IS NULL AND NOT is_dead AND next_attempt_at <= now() AND webhooks.is_active
+                    ORDER BY next_attempt_at ASC, webhook_deliveries.id ASC
+                    LIMIT $1
+                    FOR UPDATE OF webhook_deliveries SKIP LOCKED
+                )
+                RETURNING id, webhook_id, event_id, payload, attempts
+            )
+            SELECT
+                claimed.id as "id!",
+                claimed.webhook_id as "webhook_id!",
+                claimed.event_id as "event_id!",
+                claimed.payload as "payload!",
+                claimed.attempts as "attempts!",
+                webhooks.url as "url!",
+                webhooks.secret as "secret!"
+            FROM claimed
+            INNER JOIN webhooks ON webhooks.id = claimed.webhook_id
+            ORDER BY claimed.id ASC
+            "#,
+            limit,
+            claimed_until,
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        sql_histogram!(
+            self.0,
+            "sql.webhooks.claim_pending_deliveries",
+            start.elapsed()
+        );
+        Ok(deliveries)
+    }
+
+    /// Marks the delivery as successfully sent.
+    pub async fn mark_delivered(&mut self, id: i64) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "UPDATE webhook_deliveries SET attempts = attempts + 1, delivered_at = now(), last_error = NULL
+            WHERE id = $1",
+            id
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        sql_histogram!(self.0, "sql.webhooks.mark_delivered", start.elapsed());
+        Ok(())
+    }
+
+    /// Records the failed attempt of the delivery. If there's no next attempt,
+    /// the delivery is moved to the dead letters.
+    pub async fn record_failed_attempt(
+        &mut self,
+        id: i64,
+        error: &str,
+        next_attempt_at: Option<DateTime<Utc>>,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "UPDATE webhook_deliveries
+            SET attempts = attempts + 1, last_error = $2,
+                next_attempt_at = COALESCE($3, next_attempt_at), is_dead = $3 IS NULL
+            WHERE id = $1",
+            id,
+            error,
+            next_attempt_at,
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        sql_histogram!(
+            self.0,
+            "sql.webhooks.record_failed_attempt",
+            start.elapsed()
+        );
+        Ok(())
+    }
+
+    /// Loads the deliveries of the webhook that ran out of attempts, ordered by id.
+    pub async fn load_dead_letters(
+        &mut self,
+        webhook_id: i64,
+    ) -> QueryResult<Vec<WebhookDeadLetter>> {
+        let start = Instant::now();
+        // Nullability of the view columns is unknown to the `sqlx` macros.
+        let dead_letters = sqlx::query_as::<_, WebhookDeadLetter>(
+            "SELECT * FROM webhook_dead_letters WHERE webhook_id = $1 ORDER BY id ASC",
+        )
+        .bind(webhook_id)
+        .fetch_all(self.0.conn())
+        .await?;
+
+        sql_histogram!(self.0, "sql.webhooks.load_dead_letters", start.elapsed());
+        Ok(dead_letters)
+    }
+
+    /// Moves the dead delivery of the webhook back to the queue with the fresh attempts.
+    /// Returns `false` if the webhook has no dead delivery with such id.
+    pub async fn retry_dead_letter(&mut self, webhook_id: i64, id: i64) -> QueryResult<bool> {
+        let start = Instant::now();
+        let result = sqlx::query!(
+            "UPDATE webhook_deliveries SET is_dead = false, attempts = 0, next_attempt_at = now()
+            WHERE id = $1 AND webhook_id = $2 AND is_dead",
+            id,
+            webhook_id
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        sql_histogram!(self.0, "sql.webhooks.retry_dead_letter", start.elapsed());
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Removes the deliveries sent before the given time, the dead ones are kept until retried.
+    /// Returns the amount of the removed deliveries.
+    pub async fn prune_delivered(&mut self, delivered_before: DateTime<Utc>) -> QueryResult<u64> {
+        let start = Instant::now();
+        let result = sqlx::query!(
+            "DELETE FROM webhook_deliveries WHERE delivered_at < $1",
+            delivered_before
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        sql_histogram!(self.0, "sql.webhooks.prune_delivered", start.elapsed());
+        Ok(result.rows_affected())
+    }
+}
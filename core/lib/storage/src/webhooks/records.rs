@@ -0,0 +1,44 @@
+// External imports
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::FromRow;
+// Workspace imports
+// Local imports
+
+#[derive(Debug, Clone, FromRow, PartialEq)]
+pub struct StoredWebhook {
+    pub id: i64,
+    pub url: String,
+    pub secret: String,
+    pub filters: Value,
+    pub last_event_id: i64,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Delivery due to be sent, along with the target of its webhook.
+#[derive(Debug, Clone, FromRow, PartialEq)]
+pub struct PendingWebhookDelivery {
+    pub id: i64,
+    pub webhook_id: i64,
+    pub event_id: i64,
+    pub payload: Value,
+    pub attempts: i32,
+    pub url: String,
+    pub secret: String,
+}
+
+/// Delivery that ran out of attempts.
+#[derive(Debug, Clone, FromRow, PartialEq, Serialize)]
+pub struct WebhookDeadLetter {
+    pub id: i64,
+    pub webhook_id: i64,
+    pub event_id: i64,
+    pub payload: Value,
+    pub attempts: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub url: String,
+}
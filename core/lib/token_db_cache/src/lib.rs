@@ -1,5 +1,8 @@
 use std::time::{Duration, Instant};
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 
 use tokio::sync::RwLock;
 
@@ -7,10 +10,15 @@ use zksync_storage::StorageProcessor;
 use zksync_types::tokens::TokenMarketVolume;
 use zksync_types::{Token, TokenId, TokenLike, NFT};
 
+/// Paused tokens are reloaded much more often than the tokens themselves,
+/// so the pause takes effect soon after it's requested.
+const PAUSED_TOKENS_INVALIDATE_CACHE: Duration = Duration::from_secs(5);
+
 #[derive(Debug, Clone, Default)]
 pub struct TokenDBCache {
     cache: Arc<RwLock<HashMap<TokenLike, (Token, Instant)>>>,
     nft_tokens: Arc<RwLock<HashMap<TokenId, NFT>>>,
+    paused_tokens: Arc<RwLock<Option<(HashSet<TokenId>, Instant)>>>,
     token_invalidate_cache: Duration,
 }
 
@@ -76,6 +84,32 @@ impl TokenDBCache {
         Ok(token)
     }
 
+    /// Version of `get_paused_tokens` that only attempts to find the paused tokens in the cache.
+    pub async fn try_get_paused_tokens_from_cache(&self) -> Option<HashSet<TokenId>> {
+        match self.paused_tokens.read().await.as_ref() {
+            Some((paused_tokens, update_time))
+                if update_time.elapsed() < PAUSED_TOKENS_INVALIDATE_CACHE =>
+            {
+                Some(paused_tokens.clone())
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the ids of the tokens that can't be transferred or swapped.
+    pub async fn get_paused_tokens(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<HashSet<TokenId>> {
+        if let Some(paused_tokens) = self.try_get_paused_tokens_from_cache().await {
+            return Ok(paused_tokens);
+        }
+        let paused_tokens = storage.tokens_schema().load_paused_tokens().await?;
+        *self.paused_tokens.write().await = Some((paused_tokens.clone(), Instant::now()));
+
+        Ok(paused_tokens)
+    }
+
     pub async fn token_symbol(
         &self,
         storage: &mut StorageProcessor<'_>,
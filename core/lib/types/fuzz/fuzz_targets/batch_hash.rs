@@ -0,0 +1,7 @@
+//! Batch hashing and the batch Ethereum signature message, see [`zksync_types_fuzz::batch_hash`].
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| zksync_types_fuzz::batch_hash(data));
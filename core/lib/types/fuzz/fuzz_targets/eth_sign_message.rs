@@ -0,0 +1,7 @@
+//! Ethereum signature messages and recovery of their signers, see [`zksync_types_fuzz::eth_sign_message`].
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| zksync_types_fuzz::eth_sign_message(data));
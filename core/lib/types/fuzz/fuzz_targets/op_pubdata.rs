@@ -0,0 +1,7 @@
+//! Decoding of the operations from the public data, see [`zksync_types_fuzz::op_pubdata`].
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| zksync_types_fuzz::op_pubdata(data));
@@ -0,0 +1,7 @@
+//! Transaction deserialization, hashing and signature checks, see [`zksync_types_fuzz::tx_json`].
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| zksync_types_fuzz::tx_json(data));
@@ -0,0 +1,132 @@
+//! Checks run by the fuzzing targets on the arbitrary input.
+//!
+//! The checks are kept apart from the targets, so the corpus can be replayed against them
+//! by the regular tests on the stable toolchain.
+
+use zksync_crypto::params::CHUNK_BYTES;
+use zksync_types::{
+    tx::{EthBatchSignData, PackedEthSignature, TxHash},
+    Address, Token, TokenId, TokenKind, ZkSyncOp, ZkSyncTx,
+};
+
+const PACKED_SIGNATURE_LEN: usize = 65;
+
+/// Deserializes the transaction from the arbitrary JSON the same way the API server does
+/// for the incoming submissions, and checks that it survives the serialization round-trip.
+pub fn tx_json(data: &[u8]) {
+    let mut tx: ZkSyncTx = match serde_json::from_slice(data) {
+        Ok(tx) => tx,
+        Err(_) => return,
+    };
+
+    let hash = tx.hash();
+    let bytes = tx.get_bytes();
+    tx.get_old_bytes();
+    tx.min_chunks();
+    tx.get_fee_info();
+
+    let serialized = serde_json::to_vec(&tx).expect("Parsed transaction must be serializable");
+    let restored: ZkSyncTx =
+        serde_json::from_slice(&serialized).expect("Serialized transaction must be parsable");
+    assert_eq!(restored.hash(), hash);
+    assert_eq!(restored.get_bytes(), bytes);
+
+    // Malformed transactions must be declined with an error.
+    let _ = tx.check_correctness();
+}
+
+/// Restores the operation from the arbitrary public data the same way the data restore does,
+/// and checks that the public data of the restored operation is decoded back to the same operation.
+pub fn op_pubdata(data: &[u8]) {
+    let op = match ZkSyncOp::from_public_data(data) {
+        Ok(op) => op,
+        Err(_) => return,
+    };
+
+    let public_data = op.public_data();
+    assert_eq!(public_data.len(), op.chunks() * CHUNK_BYTES);
+    op.eth_witness();
+    op.withdrawal_data();
+    let _ = op.try_get_tx();
+
+    // The input may contain the non-canonical packed amounts, so only the encoded data
+    // is required to be stable.
+    let restored =
+        ZkSyncOp::from_public_data(&public_data).expect("Encoded operation must be decodable");
+    assert_eq!(restored.public_data(), public_data);
+}
+
+/// Tokens covering the different decimals and symbols used for the amounts formatting.
+fn tokens(token_id: TokenId) -> Vec<Token> {
+    vec![
+        Token::new(token_id, Address::zero(), "ETH", 18, TokenKind::ERC20),
+        Token::new(
+            token_id,
+            Address::repeat_byte(0x11),
+            "USDC",
+            6,
+            TokenKind::ERC20,
+        ),
+        Token::new(token_id, Address::repeat_byte(0x22), "", 0, TokenKind::None),
+        Token::new_nft(token_id, "NFT-65536"),
+    ]
+}
+
+/// Builds the Ethereum signature messages of the transaction parsed from the arbitrary JSON,
+/// and recovers the signer of each message from the arbitrary signature.
+///
+/// The first 65 bytes of the input are the packed Ethereum signature, the rest is the transaction.
+pub fn eth_sign_message(data: &[u8]) {
+    if data.len() < PACKED_SIGNATURE_LEN {
+        return;
+    }
+    let (signature, tx) = data.split_at(PACKED_SIGNATURE_LEN);
+    let tx: ZkSyncTx = match serde_json::from_slice(tx) {
+        Ok(tx) => tx,
+        Err(_) => return,
+    };
+    let signature = PackedEthSignature::deserialize_packed(signature).ok();
+
+    for token in tokens(tx.token_id()) {
+        tx.get_ethereum_sign_message_part(token.clone());
+        let messages = [
+            tx.get_ethereum_sign_message(token.clone()),
+            tx.get_old_ethereum_sign_message(token.clone()),
+        ];
+        let eip712_message = tx.get_eip712_message(token);
+
+        if let Some(signature) = &signature {
+            for message in messages.iter().flatten() {
+                let _ = signature.signature_recover_signer(message.as_bytes());
+            }
+            if let Some(eip712_message) = &eip712_message {
+                let _ = signature.signature_recover_signer_from_raw(&eip712_message.signing_hash());
+            }
+        }
+    }
+}
+
+/// Calculates the hash and the Ethereum signature message of the batch parsed from the arbitrary JSON,
+/// the same way the API server does for the submitted batches.
+pub fn batch_hash(data: &[u8]) {
+    let txs: Vec<ZkSyncTx> = match serde_json::from_slice(data) {
+        Ok(txs) => txs,
+        Err(_) => return,
+    };
+
+    let tx_hashes: Vec<_> = txs.iter().map(ZkSyncTx::hash).collect();
+    let batch_hash = TxHash::batch_hash(&tx_hashes);
+    assert_eq!(TxHash::batch_hash(&tx_hashes), batch_hash);
+
+    EthBatchSignData::get_old_ethereum_batch_message(txs.iter());
+    let txs: Vec<_> = txs
+        .into_iter()
+        .map(|tx| {
+            let token = Token::new(tx.token_id(), Address::zero(), "ETH", 18, TokenKind::ERC20);
+            let address = tx.account();
+            (tx, token, address)
+        })
+        .collect();
+    let is_empty = txs.is_empty();
+    assert_eq!(EthBatchSignData::new(txs, Vec::new()).is_err(), is_empty);
+}
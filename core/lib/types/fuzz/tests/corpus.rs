@@ -0,0 +1,55 @@
+//! Replays the fuzzing corpus against the checks of the targets, so the inputs that once
+//! crashed them keep being checked without the fuzzer and the nightly toolchain.
+
+use std::{
+    fs,
+    panic::{catch_unwind, AssertUnwindSafe},
+    path::Path,
+};
+
+fn replay_corpus(target: &str, check: fn(&[u8])) {
+    let corpus = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("corpus")
+        .join(target);
+    let mut inputs: Vec<_> = fs::read_dir(&corpus)
+        .unwrap_or_else(|err| panic!("Can't read the corpus {}: {}", corpus.display(), err))
+        .map(|entry| entry.expect("Can't read the corpus entry").path())
+        .collect();
+    inputs.sort();
+    assert!(!inputs.is_empty(), "Corpus of {} is empty", target);
+
+    let failed: Vec<_> = inputs
+        .iter()
+        .filter(|input| {
+            let data = fs::read(input).expect("Can't read the corpus input");
+            catch_unwind(AssertUnwindSafe(|| check(&data))).is_err()
+        })
+        .map(|input| input.display().to_string())
+        .collect();
+    assert!(
+        failed.is_empty(),
+        "Inputs failing the {} checks: {:?}",
+        target,
+        failed
+    );
+}
+
+#[test]
+fn tx_json_corpus() {
+    replay_corpus("tx_json", zksync_types_fuzz::tx_json);
+}
+
+#[test]
+fn op_pubdata_corpus() {
+    replay_corpus("op_pubdata", zksync_types_fuzz::op_pubdata);
+}
+
+#[test]
+fn eth_sign_message_corpus() {
+    replay_corpus("eth_sign_message", zksync_types_fuzz::eth_sign_message);
+}
+
+#[test]
+fn batch_hash_corpus() {
+    replay_corpus("batch_hash", zksync_types_fuzz::batch_hash);
+}
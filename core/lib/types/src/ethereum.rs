@@ -1,10 +1,11 @@
 //! Common primitives for the Ethereum network interaction.
 // Built-in deps
 // External uses
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 // Local uses
 use crate::aggregated_operations::{AggregatedActionType, AggregatedOperation};
-use zksync_basic_types::{H256, U256};
+use zksync_basic_types::{Address, H256, U256};
 
 /// Numerical identifier of the Ethereum operation.
 pub type EthOpId = i64;
@@ -18,6 +19,9 @@ pub struct ETHOperation {
     pub op_type: AggregatedActionType,
     /// Optional ZKSync operation associated with Ethereum operation.
     pub op: Option<(i64, AggregatedOperation)>,
+    /// ZKSync operations sent after `op` within the same multicall transaction.
+    /// Empty if the transaction is sent directly to the zkSync contract.
+    pub bundled_ops: Vec<(i64, AggregatedOperation)>,
     /// Used nonce (fixed for all the sent transactions).
     pub nonce: U256,
     /// Deadline block of the last sent transaction.
@@ -34,6 +38,12 @@ pub struct ETHOperation {
     /// Hash of the accepted Ethereum transaction (if operation
     /// is confirmed).
     pub final_hash: Option<H256>,
+    /// Additional operator account that sends the transactions of the operation.
+    /// `None` stands for the main operator account.
+    pub sender: Option<Address>,
+    /// Hash of the self-send transaction cancelling the operation at the same nonce,
+    /// if the cancellation was requested.
+    pub cancel_tx_hash: Option<H256>,
 }
 
 impl ETHOperation {
@@ -81,3 +91,35 @@ pub struct InsertedOperationResponse {
 #[derive(Debug, Error, PartialEq)]
 #[error("Unknown type of operation: {0}")]
 pub struct UnknownOperationType(pub String);
+
+/// Action requested by the operator for the stuck Ethereum operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EthTxReplacement {
+    /// Resend the transaction with the increased fee without waiting for the deadline block.
+    Resend,
+    /// Replace the transaction with a self-send one at the same nonce, so the operation
+    /// is returned to the queue and sent again later.
+    Cancel,
+}
+
+impl std::string::ToString for EthTxReplacement {
+    fn to_string(&self) -> String {
+        match self {
+            EthTxReplacement::Resend => "resend".to_owned(),
+            EthTxReplacement::Cancel => "cancel".to_owned(),
+        }
+    }
+}
+
+impl std::str::FromStr for EthTxReplacement {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "resend" => Ok(Self::Resend),
+            "cancel" => Ok(Self::Cancel),
+            _ => Err("Incorrect transaction replacement action".to_owned()),
+        }
+    }
+}
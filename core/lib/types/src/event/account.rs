@@ -12,7 +12,7 @@ use crate::{
     AccountId, Nonce, TokenId,
 };
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum AccountStateChangeStatus {
     Committed,
@@ -8,7 +8,7 @@ use zksync_utils::{BytesToHexSerde, OptionBytesToHexSerde, SyncBlockPrefix, Zero
 use super::account::AccountStateChangeStatus;
 use crate::BlockNumber;
 
-#[derive(Debug, Copy, Clone, Serialize, PartialEq, Eq, Deserialize)]
+#[derive(Debug, Copy, Clone, Serialize, PartialEq, Eq, Hash, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum BlockStatus {
     Committed,
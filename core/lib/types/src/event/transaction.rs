@@ -8,7 +8,7 @@ use serde::{Deserialize, Serialize};
 use super::account::AccountStateChangeStatus;
 use crate::{block::ExecutedOperations, AccountId, BlockNumber, TokenId};
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum TransactionStatus {
     Queued,
@@ -1,7 +1,7 @@
 use chrono::{DateTime, Utc};
 use num::BigUint;
 use thiserror::Error;
-use zksync_basic_types::{Address, TokenId};
+use zksync_basic_types::{AccountId, Address, TokenId};
 use zksync_utils::BigUintSerdeAsRadix10Str;
 
 use serde::{Deserialize, Serialize};
@@ -20,24 +20,95 @@ pub struct ForcedExitRequest {
     pub id: ForcedExitRequestId,
     pub target: Address,
     pub tokens: Vec<TokenId>,
+    /// Other accounts exited by the same request, so they are paid for with a single payment.
+    #[serde(default)]
+    pub additional_targets: Vec<ForcedExitTarget>,
     #[serde(with = "BigUintSerdeAsRadix10Str")]
     pub price_in_wei: BigUint,
     pub valid_until: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
     pub fulfilled_by: Option<Vec<TxHash>>,
     pub fulfilled_at: Option<DateTime<Utc>>,
+    pub payment_received_at: Option<DateTime<Utc>>,
+}
+
+/// Account to be exited along with the tokens to withdraw from it.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+pub struct ForcedExitTarget {
+    pub target: Address,
+    pub tokens: Vec<TokenId>,
+}
+
+/// Stage of the forced exit request processing.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ForcedExitRequestProgress {
+    /// The payment for the request was not seen yet.
+    AwaitingPayment,
+    /// The request was not paid before its expiration.
+    Expired,
+    /// The payment was seen, the `ForcedExit` transactions are not sent yet.
+    PaymentReceived,
+    /// The `ForcedExit` transactions are sent to the network.
+    TxSubmitted,
+    /// The `ForcedExit` transactions are committed.
+    Completed,
+}
+
+impl ForcedExitRequest {
+    /// Returns all the `(target, token)` pairs the request exits, starting with the main target.
+    pub fn exits(&self) -> Vec<(Address, TokenId)> {
+        let main_target = ForcedExitTarget {
+            target: self.target,
+            tokens: self.tokens.clone(),
+        };
+        std::iter::once(&main_target)
+            .chain(self.additional_targets.iter())
+            .flat_map(|target| {
+                target
+                    .tokens
+                    .iter()
+                    .map(move |token| (target.target, *token))
+            })
+            .collect()
+    }
+
+    /// Returns the processing stage of the request at the given moment.
+    pub fn progress(&self, now: DateTime<Utc>) -> ForcedExitRequestProgress {
+        if self.fulfilled_at.is_some() {
+            ForcedExitRequestProgress::Completed
+        } else if self.fulfilled_by.is_some() {
+            ForcedExitRequestProgress::TxSubmitted
+        } else if self.payment_received_at.is_some() {
+            ForcedExitRequestProgress::PaymentReceived
+        } else if self.valid_until < now {
+            ForcedExitRequestProgress::Expired
+        } else {
+            ForcedExitRequestProgress::AwaitingPayment
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 pub struct SaveForcedExitRequestQuery {
     pub target: Address,
     pub tokens: Vec<TokenId>,
+    pub additional_targets: Vec<ForcedExitTarget>,
     #[serde(with = "BigUintSerdeAsRadix10Str")]
     pub price_in_wei: BigUint,
     pub created_at: DateTime<Utc>,
     pub valid_until: DateTime<Utc>,
 }
 
+/// Small balance of the dormant account, which is withdrawn by the ForcedExit sender account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DormantBalance {
+    pub account_id: AccountId,
+    pub target: Address,
+    pub token: TokenId,
+    pub balance: BigUint,
+}
+
 #[derive(Debug, Clone)]
 pub struct FundsReceivedEvent {
     pub amount: BigUint,
@@ -1,3 +1,6 @@
+use std::{str::FromStr, time::Duration};
+
+use chrono::{DateTime, Utc};
 use thiserror::Error;
 use zksync_basic_types::BlockNumber;
 
@@ -27,8 +30,56 @@ impl ProverJobStatus {
     }
 }
 
+// Jobs with a lower priority value are handed out to provers first, and jobs of the same
+// priority are handed out in the order they were added. Aggregated proofs close to their
+// deadline are handed out with `DEADLINE_JOB_PRIORITY`, see `ProverJobPriorityPolicy`.
 pub const SINGLE_PROOF_JOB_PRIORITY: i32 = 1;
-pub const AGGREGATED_PROOF_JOB_PRIORITY: i32 = 0;
+pub const AGGREGATED_PROOF_JOB_PRIORITY: i32 = 1;
+pub const DEADLINE_JOB_PRIORITY: i32 = 0;
+
+/// Policy of handing out the prover jobs. Aggregated proofs are the last step before the blocks
+/// can be executed on L1, so the ones close to their deadline preempt the pending single block proofs.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProverJobPriorityPolicy {
+    /// Time since the aggregated proof job is added within which the proof has to be generated.
+    pub aggregated_proof_deadline: Duration,
+    /// Aggregated proof jobs this close to their deadline preempt the single block proof jobs.
+    pub preemption_window: Duration,
+}
+
+impl ProverJobPriorityPolicy {
+    /// Returns the time before which the aggregated proof jobs have to be added to preempt
+    /// the single block proof jobs at `now`, if any job can be added that early.
+    pub fn preemption_threshold(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let preemption_age = self
+            .aggregated_proof_deadline
+            .checked_sub(self.preemption_window)
+            .unwrap_or_default();
+        chrono::Duration::from_std(preemption_age)
+            .ok()
+            .and_then(|preemption_age| now.checked_sub_signed(preemption_age))
+    }
+
+    /// Returns the priority the job added at `created_at` is handed out with at `now`.
+    pub fn job_priority(
+        &self,
+        job_type: &ProverJobType,
+        stored_priority: i32,
+        created_at: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> i32 {
+        match job_type {
+            ProverJobType::AggregatedProof
+                if self
+                    .preemption_threshold(now)
+                    .map_or(false, |threshold| created_at <= threshold) =>
+            {
+                DEADLINE_JOB_PRIORITY
+            }
+            _ => stored_priority,
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct ProverJob {
@@ -36,6 +87,7 @@ pub struct ProverJob {
     pub first_block: BlockNumber,
     pub last_block: BlockNumber,
     pub job_data: serde_json::Value,
+    pub job_priority: i32,
 }
 
 impl ProverJob {
@@ -44,12 +96,14 @@ impl ProverJob {
         first_block: BlockNumber,
         last_block: BlockNumber,
         job_data: serde_json::Value,
+        job_priority: i32,
     ) -> Self {
         Self {
             job_id,
             first_block,
             last_block,
             job_data,
+            job_priority,
         }
     }
 }
@@ -69,6 +123,85 @@ impl ToString for ProverJobType {
     }
 }
 
+impl FromStr for ProverJobType {
+    type Err = IncorrectProverJobType;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "SINGLE_PROOF" => Ok(ProverJobType::SingleProof),
+            "AGGREGATED_PROOF" => Ok(ProverJobType::AggregatedProof),
+            _ => Err(IncorrectProverJobType(s.to_string())),
+        }
+    }
+}
+
 #[derive(Debug, Error, PartialEq)]
 #[error("Incorrect ProverJobStatus number: {0}")]
 pub struct IncorrectProverJobStatus(pub i32);
+
+#[derive(Debug, Error, PartialEq)]
+#[error("Incorrect ProverJobType: {0}")]
+pub struct IncorrectProverJobType(pub String);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deadline_job_priority() {
+        let policy = ProverJobPriorityPolicy {
+            aggregated_proof_deadline: Duration::from_secs(600),
+            preemption_window: Duration::from_secs(120),
+        };
+        let now = Utc::now();
+        let added = |secs: i64| now - chrono::Duration::seconds(secs);
+
+        // Aggregated proofs far from their deadline wait in the queue with the single block proofs.
+        assert_eq!(
+            policy.job_priority(
+                &ProverJobType::AggregatedProof,
+                AGGREGATED_PROOF_JOB_PRIORITY,
+                added(10),
+                now
+            ),
+            AGGREGATED_PROOF_JOB_PRIORITY
+        );
+        for &age in &[480, 600, 6000] {
+            assert_eq!(
+                policy.job_priority(
+                    &ProverJobType::AggregatedProof,
+                    AGGREGATED_PROOF_JOB_PRIORITY,
+                    added(age),
+                    now
+                ),
+                DEADLINE_JOB_PRIORITY
+            );
+        }
+        // Single block proofs don't have a deadline.
+        assert_eq!(
+            policy.job_priority(
+                &ProverJobType::SingleProof,
+                SINGLE_PROOF_JOB_PRIORITY,
+                added(6000),
+                now
+            ),
+            SINGLE_PROOF_JOB_PRIORITY
+        );
+
+        // The window wider than the deadline makes every aggregated proof preempt the single ones.
+        let policy = ProverJobPriorityPolicy {
+            preemption_window: Duration::from_secs(1200),
+            ..policy
+        };
+        assert_eq!(policy.preemption_threshold(now), Some(now));
+        assert_eq!(
+            policy.job_priority(
+                &ProverJobType::AggregatedProof,
+                AGGREGATED_PROOF_JOB_PRIORITY,
+                now,
+                now
+            ),
+            DEADLINE_JOB_PRIORITY
+        );
+    }
+}
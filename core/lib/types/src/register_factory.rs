@@ -3,9 +3,9 @@ use std::convert::TryFrom;
 use ethabi::{decode, ParamType};
 use thiserror::Error;
 
-use zksync_basic_types::Log;
+use zksync_basic_types::{Log, U256};
 
-use crate::Address;
+use crate::{AccountId, Address};
 
 #[derive(Debug, Error)]
 #[allow(clippy::large_enum_variant)]
@@ -19,6 +19,9 @@ pub enum RegisterNFTFactoryEventParseError {
 #[derive(Clone, Debug)]
 pub struct RegisterNFTFactoryEvent {
     pub factory_address: Address,
+    /// Account ID the factory is registered for on L1. Contract uses the factory only for the NFTs
+    /// created by the account with both this ID and `creator_address`.
+    pub creator_id: AccountId,
     pub creator_address: Address,
     pub eth_block: u64,
 }
@@ -39,12 +42,57 @@ impl TryFrom<Log> for RegisterNFTFactoryEvent {
             &event.data.0,
         )
         .map_err(RegisterNFTFactoryEventParseError::ParseError)?;
+        let creator_id = AccountId(U256::from_big_endian(event.topics[1].as_bytes()).as_u32());
         let creator_address = Address::from_slice(&event.topics[2].as_fixed_bytes()[12..]);
         let factory_address = decoded_event.remove(0).into_address().unwrap();
         Ok(Self {
             factory_address,
+            creator_id,
             creator_address,
             eth_block,
         })
     }
 }
+
+/// Returns the message the creator signs to register the factory, the same one the contract
+/// verifies the signature of on the factory registration.
+pub fn register_factory_message(
+    creator_id: AccountId,
+    creator_address: Address,
+    factory_address: Address,
+) -> Vec<u8> {
+    format!(
+        "\nCreator's account ID in zkSync: {}\nCreator: {}\nFactory: {}",
+        hex::encode(creator_id.0.to_be_bytes()),
+        hex::encode(creator_address.as_bytes()),
+        hex::encode(factory_address.as_bytes()),
+    )
+    .into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tx::PackedEthSignature;
+    use zksync_basic_types::H256;
+
+    #[test]
+    fn register_factory_message_signature() {
+        let private_key = H256::repeat_byte(7);
+        let creator_address = PackedEthSignature::address_from_private_key(&private_key).unwrap();
+        let factory_address = Address::repeat_byte(0xab);
+
+        let message = register_factory_message(AccountId(0x1234), creator_address, factory_address);
+        // The contract expects the message of the fixed length.
+        assert_eq!(message.len(), 141);
+        assert!(String::from_utf8(message.clone())
+            .unwrap()
+            .starts_with("\nCreator's account ID in zkSync: 00001234\nCreator: "));
+
+        let signature = PackedEthSignature::sign(&private_key, &message).unwrap();
+        assert_eq!(
+            signature.signature_recover_signer(&message).unwrap(),
+            creator_address
+        );
+    }
+}
@@ -181,10 +181,22 @@ impl TokenInfo {
     }
 }
 
+/// Metadata of the token collected from the token contract and the curated token list.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TokenMetadata {
+    pub name: Option<String>,
+    /// Decimals reported by the token contract, `None` if it doesn't implement `decimals()`.
+    pub onchain_decimals: Option<u8>,
+    pub logo_url: Option<String>,
+    pub description: Option<String>,
+}
+
 /// Tokens that added through a contract.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct NewTokenEvent {
     pub eth_block_number: u64,
+    /// Hash of the Ethereum transaction that added the token.
+    pub eth_tx_hash: H256,
     pub address: Address,
     pub id: TokenId,
 }
@@ -206,8 +218,16 @@ impl TryFrom<Log> for NewTokenEvent {
             }
         };
 
+        let eth_tx_hash = match event.transaction_hash {
+            Some(tx_hash) => tx_hash,
+            None => {
+                return Err(NewTokenEventParseError::ParseError(event));
+            }
+        };
+
         Ok(NewTokenEvent {
             eth_block_number,
+            eth_tx_hash,
             address: Address::from_slice(&event.topics[1].as_fixed_bytes()[12..]),
             id: TokenId(U256::from_big_endian(&event.topics[2].as_fixed_bytes()[..]).as_u32()),
         })
@@ -14,8 +14,8 @@ use zksync_utils::{format_units, BigUintSerdeAsRadix10Str};
 
 use super::{PackedEthSignature, TimeRange, TxSignature, VerifiedSignatureCache};
 use crate::tx::error::{
-    FEE_AMOUNT_IS_NOT_PACKABLE, INVALID_AUTH_DATA, WRONG_ACCOUNT_ID, WRONG_FEE_ERROR,
-    WRONG_SIGNATURE, WRONG_TIME_RANGE, WRONG_TOKEN_FOR_PAYING_FEE,
+    FEE_AMOUNT_IS_NOT_PACKABLE, INVALID_AUTH_DATA, INVALID_CREATE2_DATA, WRONG_ACCOUNT_ID,
+    WRONG_FEE_ERROR, WRONG_SIGNATURE, WRONG_TIME_RANGE, WRONG_TOKEN_FOR_PAYING_FEE,
 };
 use crate::{
     account::PubKeyHash,
@@ -156,7 +156,7 @@ pub struct ChangePubKey {
     #[serde(flatten)]
     pub time_range: Option<TimeRange>,
     #[serde(skip)]
-    cached_signer: VerifiedSignatureCache,
+    pub(crate) cached_signer: VerifiedSignatureCache,
 }
 
 impl ChangePubKey {
@@ -453,11 +453,19 @@ impl ChangePubKey {
     /// Verifies the transaction correctness:
     ///
     /// - Ethereum signature (if set) must correspond to the account address.
+    /// - CREATE2 data (if set) must derive the account address.
     /// - zkSync signature must correspond to the `new_pk_hash` field of the transaction.
     /// - `account_id` field must be within supported range.
     /// - `fee_token` field must be within supported range.
     /// - `fee` field must represent a packable value.
     pub fn check_correctness(&mut self) -> Result<(), TransactionError> {
+        // CREATE2 data is checked separately to give a more precise error, since wallets
+        // usually compute the address themselves and any mismatch in the inputs is not obvious.
+        if let Some(ChangePubKeyEthAuthData::CREATE2(create2_data)) = &self.eth_auth_data {
+            if create2_data.get_address(&self.new_pk_hash) != self.account {
+                return Err(TransactionError::InvalidCREATE2Data);
+            }
+        }
         if !self.is_eth_auth_data_valid() {
             return Err(TransactionError::InvalidAuthData);
         }
@@ -498,6 +506,7 @@ impl ChangePubKey {
 #[derive(Error, Debug, Copy, Clone, Serialize, Deserialize)]
 pub enum TransactionError {
     InvalidAuthData,
+    InvalidCREATE2Data,
     WrongFee,
     FeeNotPackable,
     WrongAccountId,
@@ -516,6 +525,7 @@ impl Display for TransactionError {
             TransactionError::WrongSignature => WRONG_SIGNATURE,
             TransactionError::WrongFeeToken => WRONG_TOKEN_FOR_PAYING_FEE,
             TransactionError::InvalidAuthData => INVALID_AUTH_DATA,
+            TransactionError::InvalidCREATE2Data => INVALID_CREATE2_DATA,
         };
         write!(f, "{}", error)
     }
@@ -4,6 +4,7 @@ use thiserror::Error;
 use crate::tx::{
     change_pubkey, close, forced_exit, mint_nft, swap, transfer, withdraw, withdraw_nft,
 };
+use crate::TokenId;
 #[derive(Debug, Error, PartialEq)]
 pub enum ChangePubkeySignedDataError {
     #[error("Change pubkey signed message does not match in size. Actual: {actual}, expected: {expected}")]
@@ -28,6 +29,9 @@ pub enum TxAddError {
     #[error("Transactions batch summary fee is too low")]
     TxBatchFeeTooLow,
 
+    #[error("Balance of the account paying the fee for the sponsored batch is too low")]
+    SponsorBalanceTooLow,
+
     #[error("EIP1271 signature could not be verified")]
     EIP1271SignatureVerificationFail,
 
@@ -57,6 +61,27 @@ pub enum TxAddError {
 
     #[error("Too many Ethereum signatures provided")]
     EthSignaturesLimitExceeded,
+
+    #[error("Replacement tx fee must be strictly higher than the fee of the queued tx")]
+    ReplacementFeeTooLow,
+
+    #[error("Queued tx with the same nonce cannot be replaced")]
+    ReplacementNotPossible,
+
+    #[error("Mempool is full and the tx fee is too low to replace any of the queued txs")]
+    MempoolIsFull,
+
+    #[error("Token {0} is paused, only withdrawals of it are allowed")]
+    TokenPaused(TokenId),
+
+    #[error("Acceptance of the new transactions is paused by the operator")]
+    TxAcceptancePaused,
+
+    #[error("Tx is not queued in the mempool")]
+    TxNotQueued,
+
+    #[error("Transactions from batches cannot be cancelled")]
+    CancellationNotPossible,
 }
 
 #[derive(Error, Debug, Copy, Clone, Serialize, Deserialize)]
@@ -92,3 +117,6 @@ pub const WRONG_TOKEN_FOR_PAYING_FEE: &str = "Specified token is not supported f
 pub const WRONG_SIGNATURE: &str = "L2 signature is incorrect";
 pub const WRONG_TO_ADDRESS: &str = "Transfer for specified address is not supported";
 pub const INVALID_AUTH_DATA: &str = "Specified auth data is incorrect";
+pub const INVALID_CREATE2_DATA: &str =
+    "Account address doesn't match the CREATE2 address derived from the \
+    creator address, salt argument, code hash and new public key hash";
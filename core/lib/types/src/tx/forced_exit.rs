@@ -51,7 +51,7 @@ pub struct ForcedExit {
     /// Transaction zkSync signature.
     pub signature: TxSignature,
     #[serde(skip)]
-    cached_signer: VerifiedSignatureCache,
+    pub(crate) cached_signer: VerifiedSignatureCache,
     /// Time range when the transaction is valid
     /// This fields must be Option<...> because of backward compatibility with first version of ZkSync
     #[serde(flatten)]
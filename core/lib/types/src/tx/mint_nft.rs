@@ -47,7 +47,7 @@ pub struct MintNFT {
     /// Transaction zkSync signature.
     pub signature: TxSignature,
     #[serde(skip)]
-    cached_signer: VerifiedSignatureCache,
+    pub(crate) cached_signer: VerifiedSignatureCache,
 }
 
 impl MintNFT {
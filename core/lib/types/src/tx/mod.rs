@@ -38,6 +38,7 @@ pub use self::{
 // Re-export primitives associated with transactions.
 pub use self::primitives::{
     eip1271_signature::EIP1271Signature,
+    eip712_message::{EIP712Message, EIP712Value, EIP712_DOMAIN_NAME, EIP712_DOMAIN_VERSION},
     eth_batch_sign_data::EthBatchSignData,
     eth_batch_signature::EthBatchSignatures,
     eth_signature::{TxEthSignature, TxEthSignatureVariant},
@@ -0,0 +1,126 @@
+use parity_crypto::Keccak256;
+use serde_json::{json, Map, Value};
+use zksync_basic_types::{Address, H256};
+
+/// Name of the EIP-712 domain of the zkSync messages.
+pub const EIP712_DOMAIN_NAME: &str = "zkSync";
+/// Version of the EIP-712 domain of the zkSync messages.
+pub const EIP712_DOMAIN_VERSION: &str = "1";
+
+/// Value of the EIP-712 message field.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EIP712Value {
+    Address(Address),
+    String(String),
+    Uint32(u32),
+}
+
+impl EIP712Value {
+    fn type_name(&self) -> &'static str {
+        match self {
+            Self::Address(_) => "address",
+            Self::String(_) => "string",
+            Self::Uint32(_) => "uint32",
+        }
+    }
+
+    /// Encodes the value as a 32-byte word according to the `encodeData` of EIP-712.
+    fn encode(&self) -> [u8; 32] {
+        let mut word = [0u8; 32];
+        match self {
+            Self::Address(address) => word[12..].copy_from_slice(address.as_bytes()),
+            Self::String(string) => word = string.as_bytes().keccak256(),
+            Self::Uint32(value) => word[28..].copy_from_slice(&value.to_be_bytes()),
+        }
+        word
+    }
+
+    fn to_json(&self) -> Value {
+        match self {
+            Self::Address(address) => json!(address),
+            Self::String(string) => json!(string),
+            Self::Uint32(value) => json!(value),
+        }
+    }
+}
+
+/// EIP-712 structured data alternative of the human-readable 2FA message.
+///
+/// The message consists of the flat fields only and is signed within the zkSync domain,
+/// which has the name and the version only. Same as the human-readable message, it's not
+/// bound to the network.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EIP712Message {
+    pub primary_type: &'static str,
+    pub fields: Vec<(&'static str, EIP712Value)>,
+}
+
+impl EIP712Message {
+    /// Returns the type encoding, e.g. `Transfer(address to,uint32 nonce)`.
+    pub fn encode_type(&self) -> String {
+        let members: Vec<_> = self
+            .fields
+            .iter()
+            .map(|(name, value)| format!("{} {}", value.type_name(), name))
+            .collect();
+        format!("{}({})", self.primary_type, members.join(","))
+    }
+
+    /// Returns the `hashStruct` of the message.
+    pub fn struct_hash(&self) -> H256 {
+        let mut bytes = Vec::with_capacity(32 * (self.fields.len() + 1));
+        bytes.extend_from_slice(&self.encode_type().as_bytes().keccak256());
+        for (_, value) in &self.fields {
+            bytes.extend_from_slice(&value.encode());
+        }
+        bytes.keccak256().into()
+    }
+
+    /// Returns the `hashStruct` of the zkSync domain.
+    pub fn domain_separator() -> H256 {
+        let mut bytes = Vec::with_capacity(32 * 3);
+        bytes.extend_from_slice(&b"EIP712Domain(string name,string version)".keccak256());
+        bytes.extend_from_slice(&EIP712_DOMAIN_NAME.as_bytes().keccak256());
+        bytes.extend_from_slice(&EIP712_DOMAIN_VERSION.as_bytes().keccak256());
+        bytes.keccak256().into()
+    }
+
+    /// Returns the hash to be signed, i.e. `keccak256("\x19\x01" ‖ domainSeparator ‖ hashStruct(message))`.
+    pub fn signing_hash(&self) -> H256 {
+        let mut bytes = Vec::with_capacity(2 + 32 * 2);
+        bytes.extend_from_slice(b"\x19\x01");
+        bytes.extend_from_slice(Self::domain_separator().as_bytes());
+        bytes.extend_from_slice(self.struct_hash().as_bytes());
+        bytes.keccak256().into()
+    }
+
+    /// Returns the typed data in the format of the `eth_signTypedData_v4` JSON RPC method.
+    pub fn to_json(&self) -> Value {
+        let members: Vec<_> = self
+            .fields
+            .iter()
+            .map(|(name, value)| json!({ "name": name, "type": value.type_name() }))
+            .collect();
+        let message: Map<_, _> = self
+            .fields
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_json()))
+            .collect();
+
+        json!({
+            "types": {
+                "EIP712Domain": [
+                    { "name": "name", "type": "string" },
+                    { "name": "version", "type": "string" },
+                ],
+                self.primary_type: members,
+            },
+            "primaryType": self.primary_type,
+            "domain": {
+                "name": EIP712_DOMAIN_NAME,
+                "version": EIP712_DOMAIN_VERSION,
+            },
+            "message": message,
+        })
+    }
+}
@@ -1,4 +1,5 @@
 pub mod eip1271_signature;
+pub mod eip712_message;
 pub mod eth_batch_sign_data;
 pub mod eth_batch_signature;
 pub mod eth_signature;
@@ -86,6 +86,26 @@ impl PackedEthSignature {
         Ok(public_to_address(&public_key))
     }
 
+    /// Signs the 32-byte hash as is, without adding the Ethereum message prefix
+    /// (e.g. the EIP-712 typed data hash).
+    pub fn sign_raw(
+        private_key: &H256,
+        signed_bytes: &H256,
+    ) -> Result<PackedEthSignature, PackedETHSignatureError> {
+        let secret_key = (*private_key).into();
+        let signature = sign(&secret_key, signed_bytes)?;
+        Ok(PackedEthSignature(signature))
+    }
+
+    /// Checks signature of the 32-byte hash signed as is and returns ethereum address of the signer.
+    pub fn signature_recover_signer_from_raw(
+        &self,
+        signed_bytes: &H256,
+    ) -> Result<Address, PackedETHSignatureError> {
+        let public_key = recover(&self.0, signed_bytes)?;
+        Ok(public_to_address(&public_key))
+    }
+
     /// Get Ethereum address from private key.
     pub fn address_from_private_key(
         private_key: &H256,
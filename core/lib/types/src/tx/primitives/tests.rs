@@ -1,7 +1,7 @@
 use std::str::FromStr;
 // External uses
 // Workspace uses
-use zksync_basic_types::Address;
+use zksync_basic_types::{Address, H256};
 use zksync_utils::format_units;
 // Local uses
 use crate::{tx::*, AccountId, Nonce, Token, TokenId, TokenKind, Transfer, Withdraw, ZkSyncTx};
@@ -130,3 +130,53 @@ fn test_batch_message() {
     let message = EthBatchSignData::get_batch_sign_message(txs);
     assert_eq!(message, expected.into_bytes());
 }
+
+/// Checks the EIP-712 message of the transfer and the signature of its hash.
+#[test]
+fn test_transfer_eip712_message() {
+    let mut transfer = get_transfer();
+    transfer.to = Address::from_str("2e46cd9538248826ede540012c0e8d13f223d587").unwrap();
+    transfer.amount = 1_500_000_000_000_000_000u64.into();
+    transfer.fee = 1_000_000_000_000_000u64.into();
+    transfer.nonce = Nonce(7);
+
+    let message = ZkSyncTx::from(transfer)
+        .get_eip712_message(Token::new(
+            TokenId(0),
+            Default::default(),
+            "ETH",
+            18,
+            TokenKind::ERC20,
+        ))
+        .unwrap();
+    assert_eq!(
+        message.encode_type(),
+        "Transfer(address to,string token,string amount,string fee,uint32 nonce)"
+    );
+    assert_eq!(
+        message.fields[2],
+        ("amount", EIP712Value::String("1.5".to_string()))
+    );
+    assert_eq!(
+        hex::encode(EIP712Message::domain_separator()),
+        "03f26fcd0c774cb55b6a3537c1036b572cda3fc31e2258621d228e37f9d4c7b2"
+    );
+    assert_eq!(
+        hex::encode(message.struct_hash()),
+        "31c1dd5b8ea42f6b3f83c0454b2c10f715a37cdf491a1c322269398b561a3764"
+    );
+    assert_eq!(
+        hex::encode(message.signing_hash()),
+        "07003a2ef8a6ae49a2c2f7e08ecf43cb5aff6c43a1597491d7ba37783599b2df"
+    );
+    assert_eq!(message.to_json()["message"]["nonce"], 7);
+
+    let private_key = H256::repeat_byte(0x11);
+    let signature = PackedEthSignature::sign_raw(&private_key, &message.signing_hash()).unwrap();
+    assert_eq!(
+        signature
+            .signature_recover_signer_from_raw(&message.signing_hash())
+            .unwrap(),
+        PackedEthSignature::address_from_private_key(&private_key).unwrap()
+    );
+}
@@ -62,7 +62,7 @@ pub struct Swap {
     pub fee_token: TokenId,
     pub signature: TxSignature,
     #[serde(skip)]
-    cached_signer: VerifiedSignatureCache,
+    pub(crate) cached_signer: VerifiedSignatureCache,
 }
 
 impl Order {
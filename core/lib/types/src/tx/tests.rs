@@ -451,6 +451,55 @@ fn test_musig_rescue_signing_verification() {
     }
 }
 
+#[test]
+fn test_verify_signatures_batch() {
+    let mut rng = XorShiftRng::from_seed([5, 6, 7, 8]);
+    let mut txs: Vec<ZkSyncTx> = (0..10u32)
+        .map(|i| {
+            let pk = PrivateKey(rng.gen());
+            let transfer = Transfer::new_signed(
+                AccountId(i),
+                Address::random(),
+                Address::random(),
+                TokenId(1),
+                BigUint::from(100u32),
+                BigUint::from(10u32),
+                Nonce(i),
+                Default::default(),
+                &pk,
+            )
+            .unwrap();
+            // Deserialized transactions don't have the signers cached.
+            serde_json::from_value(serde_json::to_value(ZkSyncTx::from(transfer)).unwrap()).unwrap()
+        })
+        .collect();
+    let expected_signers: Vec<_> = txs
+        .iter()
+        .map(|tx| PubKeyHash::from_pubkey(&tx.signature().pub_key.0))
+        .collect();
+
+    let mut invalid_txs = txs.clone();
+    if let ZkSyncTx::Transfer(tx) = &mut invalid_txs[3] {
+        tx.signature = txs[4].signature();
+    }
+    assert!(!ZkSyncTx::verify_signatures_batch(invalid_txs.iter_mut()));
+    assert!(matches!(
+        invalid_txs[3].check_correctness(),
+        Err(TransactionError::TransferError(
+            transfer::TransactionError::WrongSignature
+        ))
+    ));
+
+    assert!(ZkSyncTx::verify_signatures_batch(txs.iter_mut()));
+    for (tx, expected_signer) in txs.iter().zip(expected_signers) {
+        if let ZkSyncTx::Transfer(tx) = tx {
+            assert!(
+                matches!(tx.cached_signer, VerifiedSignatureCache::Cached(Some((signer, TxVersion::V1))) if signer == expected_signer)
+            );
+        }
+    }
+}
+
 #[test]
 fn test_ethereum_signature_verify_with_serialization() {
     let address: Address = "52312AD6f01657413b2eaE9287f6B9ADaD93D5FE".parse().unwrap();
@@ -585,3 +634,39 @@ fn test_check_signature() {
 
     assert_eq!(hex::encode(signature), "4e3298ac8cc13868dbbc94ad6fb41085ffe05b3c2eee22f88b05e69b7a5126aea723d7a3e7282ef5a32d9479c9c8dde52b3e3c462dd445dcd8158ebb6edb6000");
 }
+
+#[test]
+fn test_change_pub_key_create2_data_check() {
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    let key = gen_pk_and_msg().0;
+    let pk_hash = PubKeyHash::from_privkey(&key);
+    let create2_data = ChangePubKeyCREATE2Data {
+        creator_address: Address::from(rng.gen::<[u8; 20]>()),
+        salt_arg: H256::random(),
+        code_hash: H256::random(),
+    };
+
+    let mut tx = ChangePubKey::new_signed(
+        gen_account_id(&mut rng),
+        create2_data.get_address(&pk_hash),
+        pk_hash,
+        gen_token_id(&mut rng),
+        BigUint::from(56_700_000_000u64),
+        Nonce(rng.gen()),
+        Default::default(),
+        None,
+        &key,
+    )
+    .expect("failed to sign change pubkey");
+    tx.eth_auth_data = Some(ChangePubKeyEthAuthData::CREATE2(create2_data.clone()));
+    assert!(tx.check_correctness().is_ok());
+
+    tx.eth_auth_data = Some(ChangePubKeyEthAuthData::CREATE2(ChangePubKeyCREATE2Data {
+        code_hash: H256::random(),
+        ..create2_data
+    }));
+    assert!(matches!(
+        tx.check_correctness(),
+        Err(change_pubkey::TransactionError::InvalidCREATE2Data)
+    ));
+}
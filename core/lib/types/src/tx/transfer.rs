@@ -29,7 +29,12 @@ use crate::tx::error::{
     WRONG_TO_ADDRESS,
 };
 use crate::tx::version::TxVersion;
-use crate::{account::PubKeyHash, utils::ethereum_sign_message_part, Engine};
+use crate::{
+    account::PubKeyHash,
+    tx::EIP712Message,
+    utils::{eip712_message, ethereum_sign_message_part},
+    Engine,
+};
 
 /// `Transfer` transaction performs a move of funds from one zkSync account to another.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -58,7 +63,7 @@ pub struct Transfer {
     /// Transaction zkSync signature.
     pub signature: TxSignature,
     #[serde(skip)]
-    cached_signer: VerifiedSignatureCache,
+    pub(crate) cached_signer: VerifiedSignatureCache,
 }
 
 impl Transfer {
@@ -211,6 +216,19 @@ impl Transfer {
         message
     }
 
+    /// Gets the EIP-712 alternative of the message returned by `get_ethereum_sign_message`.
+    pub fn get_eip712_message(&self, token_symbol: &str, decimals: u8) -> EIP712Message {
+        eip712_message(
+            "Transfer",
+            token_symbol,
+            decimals,
+            &self.amount,
+            &self.fee,
+            &self.to,
+            self.nonce,
+        )
+    }
+
     /// Returns an old-format message that should be signed by Ethereum account key.
     /// Needed for backwards compatibility.
     pub fn get_old_ethereum_sign_message(&self, token_symbol: &str, decimals: u8) -> String {
@@ -13,7 +13,12 @@ use zksync_crypto::{
 };
 use zksync_utils::{format_units, BigUintSerdeAsRadix10Str};
 
-use crate::{account::PubKeyHash, utils::ethereum_sign_message_part, Engine};
+use crate::{
+    account::PubKeyHash,
+    tx::EIP712Message,
+    utils::{eip712_message, ethereum_sign_message_part},
+    Engine,
+};
 use crate::{
     helpers::{is_fee_amount_packable, pack_fee_amount},
     AccountId, Nonce, TokenId,
@@ -49,7 +54,7 @@ pub struct Withdraw {
     /// Transaction zkSync signature.
     pub signature: TxSignature,
     #[serde(skip)]
-    cached_signer: VerifiedSignatureCache,
+    pub(crate) cached_signer: VerifiedSignatureCache,
     /// Optional setting signalizing state keeper to speed up creation
     /// of the block with provided transaction.
     /// This field is only set by the server. Transaction with this field set manually will be
@@ -212,6 +217,19 @@ impl Withdraw {
         message
     }
 
+    /// Gets the EIP-712 alternative of the message returned by `get_ethereum_sign_message`.
+    pub fn get_eip712_message(&self, token_symbol: &str, decimals: u8) -> EIP712Message {
+        eip712_message(
+            "Withdraw",
+            token_symbol,
+            decimals,
+            &self.amount,
+            &self.fee,
+            &self.to,
+            self.nonce,
+        )
+    }
+
     /// Returns an old-format message that should be signed by Ethereum account key.
     /// Needed for backwards compatibility.
     pub fn get_old_ethereum_sign_message(&self, token_symbol: &str, decimals: u8) -> String {
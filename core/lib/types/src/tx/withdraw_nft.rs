@@ -46,7 +46,7 @@ pub struct WithdrawNFT {
     /// Transaction zkSync signature.
     pub signature: TxSignature,
     #[serde(skip)]
-    cached_signer: VerifiedSignatureCache,
+    pub(crate) cached_signer: VerifiedSignatureCache,
     /// Optional setting signalizing state keeper to speed up creation
     /// of the block with provided transaction.
     /// This field is only set by the server. Transaction with this field set manually will be
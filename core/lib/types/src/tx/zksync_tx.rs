@@ -5,18 +5,19 @@ use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
 use zksync_basic_types::{AccountId, Address};
-use zksync_crypto::params::ETH_TOKEN_ID;
+use zksync_crypto::{batch_verification::batch_verify_musig_rescue, params::ETH_TOKEN_ID};
 
 use crate::{
     operations::{ChangePubKeyOp, MintNFTOp},
     tx::{
         error::{CloseOperationsDisabled, TransactionError},
-        ChangePubKey, Close, ForcedExit, MintNFT, Swap, TimeRange, Transfer, TxEthSignature,
-        TxHash, TxSignature, Withdraw, WithdrawNFT,
+        ChangePubKey, Close, EIP712Message, ForcedExit, MintNFT, Swap, TimeRange, Transfer,
+        TxEthSignature, TxHash, TxSignature, TxVersion, VerifiedSignatureCache, Withdraw,
+        WithdrawNFT,
     },
     utils::deserialize_eth_message,
-    CloseOp, ForcedExitOp, Nonce, SwapOp, Token, TokenId, TokenLike, TransferOp, TxFeeTypes,
-    WithdrawNFTOp, WithdrawOp,
+    CloseOp, ForcedExitOp, Nonce, PubKeyHash, SwapOp, Token, TokenId, TokenLike, TransferOp,
+    TxFeeTypes, WithdrawNFTOp, WithdrawOp,
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -184,6 +185,15 @@ impl ZkSyncTx {
         tokens
     }
 
+    /// Returns the tokens that must not be paused for the transaction to be accepted.
+    /// Only transfers and swaps are restricted, so the paused tokens can still be withdrawn.
+    pub fn pausable_tokens(&self) -> Vec<TokenId> {
+        match self {
+            ZkSyncTx::Transfer(_) | ZkSyncTx::Swap(_) => self.tokens(),
+            _ => Vec::new(),
+        }
+    }
+
     pub fn account_id(&self) -> Result<AccountId, CloseOperationsDisabled> {
         match self {
             ZkSyncTx::Transfer(tx) => Ok(tx.account_id),
@@ -224,6 +234,97 @@ impl ZkSyncTx {
         }
     }
 
+    /// Returns the hash of the public key the zkSync signature of the transaction was made with,
+    /// or `None` if the signature is incorrect.
+    pub fn verify_signature(&self) -> Option<PubKeyHash> {
+        match self {
+            ZkSyncTx::Transfer(tx) => tx.verify_signature().map(|(signer, _)| signer),
+            ZkSyncTx::Withdraw(tx) => tx.verify_signature().map(|(signer, _)| signer),
+            ZkSyncTx::Close(tx) => tx.verify_signature(),
+            ZkSyncTx::ChangePubKey(tx) => tx.verify_signature().map(|(signer, _)| signer),
+            ZkSyncTx::ForcedExit(tx) => tx.verify_signature().map(|(signer, _)| signer),
+            ZkSyncTx::MintNFT(tx) => tx.verify_signature().map(|(signer, _)| signer),
+            ZkSyncTx::Swap(tx) => tx.verify_signature().map(|(signer, _)| signer),
+            ZkSyncTx::WithdrawNFT(tx) => tx.verify_signature().map(|(signer, _)| signer),
+        }
+    }
+
+    /// Verifies the zkSync signatures of the transactions all at once, which is considerably faster
+    /// than verifying them one by one.
+    ///
+    /// If all the signatures are valid, their signers are cached, so the subsequent
+    /// `check_correctness` calls and the transactions execution don't verify the signatures again.
+    /// Otherwise, the transactions are left intact, and the invalid signatures are reported by
+    /// the usual per-transaction checks. Signatures of the legacy transactions messages are not
+    /// supported by the batch verification and make it fail the same way.
+    ///
+    /// Returns `true` if all the signatures are valid.
+    pub fn verify_signatures_batch<'a>(txs: impl IntoIterator<Item = &'a mut ZkSyncTx>) -> bool {
+        let mut txs: Vec<_> = txs
+            .into_iter()
+            .filter(|tx| {
+                matches!(
+                    tx.signature_cache(),
+                    Some(VerifiedSignatureCache::NotCached)
+                )
+            })
+            .collect();
+        let messages: Vec<_> = txs.iter().map(|tx| tx.signature_message()).collect();
+        let signatures: Vec<_> = txs.iter().map(|tx| tx.signature()).collect();
+
+        let batch: Vec<_> = signatures
+            .iter()
+            .zip(&messages)
+            .map(|(signature, msg)| (&signature.pub_key.0, &signature.signature.0, msg.as_slice()))
+            .collect();
+        if !batch_verify_musig_rescue(&batch) {
+            return false;
+        }
+
+        for (tx, signature) in txs.iter_mut().zip(&signatures) {
+            let signer = PubKeyHash::from_pubkey(&signature.pub_key.0);
+            if let Some(cache) = tx.signature_cache_mut() {
+                *cache = VerifiedSignatureCache::Cached(Some((signer, TxVersion::V1)));
+            }
+        }
+        true
+    }
+
+    /// Returns the cache of the signature verification result, if the transaction has one.
+    fn signature_cache(&self) -> Option<&VerifiedSignatureCache> {
+        match self {
+            ZkSyncTx::Transfer(tx) => Some(&tx.cached_signer),
+            ZkSyncTx::Withdraw(tx) => Some(&tx.cached_signer),
+            ZkSyncTx::Close(_) => None,
+            ZkSyncTx::ChangePubKey(tx) => Some(&tx.cached_signer),
+            ZkSyncTx::ForcedExit(tx) => Some(&tx.cached_signer),
+            ZkSyncTx::MintNFT(tx) => Some(&tx.cached_signer),
+            ZkSyncTx::Swap(tx) => Some(&tx.cached_signer),
+            ZkSyncTx::WithdrawNFT(tx) => Some(&tx.cached_signer),
+        }
+    }
+
+    fn signature_cache_mut(&mut self) -> Option<&mut VerifiedSignatureCache> {
+        match self {
+            ZkSyncTx::Transfer(tx) => Some(&mut tx.cached_signer),
+            ZkSyncTx::Withdraw(tx) => Some(&mut tx.cached_signer),
+            ZkSyncTx::Close(_) => None,
+            ZkSyncTx::ChangePubKey(tx) => Some(&mut tx.cached_signer),
+            ZkSyncTx::ForcedExit(tx) => Some(&mut tx.cached_signer),
+            ZkSyncTx::MintNFT(tx) => Some(&mut tx.cached_signer),
+            ZkSyncTx::Swap(tx) => Some(&mut tx.cached_signer),
+            ZkSyncTx::WithdrawNFT(tx) => Some(&mut tx.cached_signer),
+        }
+    }
+
+    /// Returns the message signed by the zkSync signature of the transaction.
+    fn signature_message(&self) -> Vec<u8> {
+        match self {
+            ZkSyncTx::Swap(tx) => tx.get_sign_bytes(),
+            _ => self.get_bytes(),
+        }
+    }
+
     pub fn is_backwards_compatible(&self) -> bool {
         match self {
             ZkSyncTx::Transfer(tx) => tx.is_backwards_compatible(),
@@ -296,6 +397,16 @@ impl ZkSyncTx {
         }
     }
 
+    /// Returns the EIP-712 alternative of the message returned by `get_ethereum_sign_message`.
+    /// If the transaction has no such alternative, returns `None`.
+    pub fn get_eip712_message(&self, token: Token) -> Option<EIP712Message> {
+        match self {
+            ZkSyncTx::Transfer(tx) => Some(tx.get_eip712_message(&token.symbol, token.decimals)),
+            ZkSyncTx::Withdraw(tx) => Some(tx.get_eip712_message(&token.symbol, token.decimals)),
+            _ => None,
+        }
+    }
+
     /// Returns a message that user has to sign to send the transaction in the old format.
     /// If the transaction doesn't need a message signature, returns `None`.
     /// Needed for backwards compatibility.
@@ -382,6 +493,16 @@ impl ZkSyncTx {
         }
     }
 
+    /// Returns `true` if transaction is a withdrawal that requested fast processing,
+    /// i.e. the block containing it should be sealed and executed on L1 as soon as possible.
+    pub fn is_fast_processing(&self) -> bool {
+        match self {
+            ZkSyncTx::Withdraw(tx) => tx.fast,
+            ZkSyncTx::WithdrawNFT(tx) => tx.fast,
+            _ => false,
+        }
+    }
+
     /// Returns `true` if transaction is `ZkSyncTx::Withdraw`.
     pub fn is_withdraw(&self) -> bool {
         matches!(
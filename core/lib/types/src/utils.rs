@@ -11,7 +11,10 @@ use serde::{
 use zksync_utils::format_units;
 
 // Local uses.
-use crate::Address;
+use crate::{
+    tx::{EIP712Message, EIP712Value},
+    Address, Nonce,
+};
 
 /// Deserializes either a `String` or `Vec<u8>` into `Vec<u8>`.
 /// The reason we cannot expect just a vector is backward compatibility: messages
@@ -123,3 +126,30 @@ pub fn ethereum_sign_message_part(
     }
     message
 }
+
+/// Returns the EIP-712 alternative of the 2FA message for the transactions moving funds
+/// to the provided address (transfers and withdrawals). Amounts are formatted in the token
+/// units, same as in the human-readable message.
+pub fn eip712_message(
+    transaction: &'static str,
+    token_symbol: &str,
+    decimals: u8,
+    amount: &BigUint,
+    fee: &BigUint,
+    to: &Address,
+    nonce: Nonce,
+) -> EIP712Message {
+    EIP712Message {
+        primary_type: transaction,
+        fields: vec![
+            ("to", EIP712Value::Address(*to)),
+            ("token", EIP712Value::String(token_symbol.to_string())),
+            (
+                "amount",
+                EIP712Value::String(format_units(amount, decimals)),
+            ),
+            ("fee", EIP712Value::String(format_units(fee, decimals))),
+            ("nonce", EIP712Value::Uint32(*nonce)),
+        ],
+    }
+}
@@ -5,21 +5,48 @@
 //! For warn and error macros we are adding file line and column to tracing variables
 //!
 //! The format of the logs in stdout can be `plain` or` json` and is set by the `MISC_LOG_FORMAT` env variable.
+//! JSON logs include the fields of the spans the event is emitted in, e.g. the `request_id` of the API request
+//! (see `request_span`) and the `tx_hash` of the processed transaction (see `tx_span`).
+//!
+//! The per-module log levels are set by the `RUST_LOG` env variable and can be changed at runtime
+//! via `set_log_filter`.
 //!
 //! Full documentation for the `tracing` crate here https://docs.rs/tracing/
 //!
 //! Integration with sentry for catching errors and react on them immediately
 //! https://docs.sentry.io/platforms/rust/
 //!
+//! If the `MISC_OTLP_ENDPOINT` env variable is set, the spans are exported to the OpenTelemetry collector.
+//! The spans created via `tx_span` for the same transaction belong to the same trace, even if they are
+//! reported by different services. The export requires the Tokio runtime, so it's only enabled if `init`
+//! is called within one.
+//!
+//! The spans created by this crate use the `zksync_spans` target, which is always enabled at the `info`
+//! level regardless of `RUST_LOG`, unless the filter mentions the target explicitly.
+//!
+//! Records of the crates using `log` are forwarded to the same subscriber. The slow SQL statements
+//! logged by `sqlx` under the `sqlx::query` target are always enabled at the `warn` level, the same
+//! way as the spans.
+//!
 
-use std::{borrow::Cow, str::FromStr};
+use std::{borrow::Cow, convert::TryInto, str::FromStr};
 
+use once_cell::sync::OnceCell;
+use opentelemetry::{
+    sdk::{trace, Resource},
+    trace::{SpanContext, SpanId, TraceContextExt, TraceId, TraceState, TRACE_FLAG_SAMPLED},
+    Context, KeyValue,
+};
 pub use sentry;
 use sentry::{types::Dsn, ClientInitGuard};
 
 pub use tracing as __tracing;
-pub use tracing::{debug, info, log, trace};
+use tracing::Subscriber;
+pub use tracing::{debug, info, log, trace, Instrument};
 use tracing_appender::non_blocking::WorkerGuard;
+use tracing_log::LogTracer;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::{fmt, layer::SubscriberExt, reload, EnvFilter, Registry};
 
 #[macro_export]
 macro_rules! warn {
@@ -45,10 +72,28 @@ macro_rules! error {
     };
 }
 
+/// Target of the spans created by `tx_span` and `request_span`.
+const SPAN_TARGET: &str = "zksync_spans";
+/// Directive enabling the slow statements logged by `sqlx`.
+const SLOW_STATEMENTS_DIRECTIVE: &str = "sqlx::query=warn";
+
+/// Handle to replace the log filter of the initialized logger.
+static LOG_FILTER: OnceCell<reload::Handle<EnvFilter, Registry>> = OnceCell::new();
+
 /// When this is dropped sentry and logger stops working
 pub struct VlogGuard {
     _sentry_guard: Option<ClientInitGuard>,
     _logger_guard: WorkerGuard,
+    _otlp_guard: Option<OtlpGuard>,
+}
+
+/// Flushes the spans which are not yet exported to the OpenTelemetry collector when dropped.
+struct OtlpGuard;
+
+impl Drop for OtlpGuard {
+    fn drop(&mut self) {
+        opentelemetry::global::shutdown_tracer_provider();
+    }
 }
 
 fn get_sentry_url() -> Option<Dsn> {
@@ -60,6 +105,127 @@ fn get_sentry_url() -> Option<Dsn> {
     None
 }
 
+fn get_otlp_endpoint() -> Option<String> {
+    std::env::var("MISC_OTLP_ENDPOINT")
+        .ok()
+        .filter(|endpoint| endpoint.starts_with("http"))
+}
+
+/// Name of the service reported to the OpenTelemetry collector, i.e. the name of the binary.
+fn service_name() -> String {
+    std::env::current_exe()
+        .ok()
+        .and_then(|path| path.file_stem()?.to_str().map(String::from))
+        .unwrap_or_else(|| "zksync".to_string())
+}
+
+/// Returns `None` if there is no Tokio runtime to run the exporter in, e.g. in the synchronous binaries.
+fn otlp_tracer(endpoint: String) -> Option<trace::Tracer> {
+    tokio::runtime::Handle::try_current().ok()?;
+    let resource = Resource::new(vec![
+        KeyValue::new("service.name", service_name()),
+        KeyValue::new(
+            "deployment.environment",
+            std::env::var("CHAIN_ETH_NETWORK").unwrap_or_default(),
+        ),
+    ]);
+    opentelemetry_otlp::new_pipeline()
+        .with_endpoint(endpoint)
+        .with_trace_config(trace::config().with_resource(resource))
+        .install_batch(opentelemetry::runtime::Tokio)
+        .map_err(|err| eprintln!("Failed to install the OpenTelemetry tracer: {}", err))
+        .ok()
+}
+
+/// Enables the spans of this crate and the slow statements of `sqlx` unless the filter directives
+/// mention the span target or the `sqlx` crate respectively.
+fn with_default_directives(mut filter: EnvFilter, directives: &str) -> EnvFilter {
+    let span_directive = format!("{}=info", SPAN_TARGET);
+    for (mention, directive) in &[
+        (SPAN_TARGET, span_directive.as_str()),
+        ("sqlx", SLOW_STATEMENTS_DIRECTIVE),
+    ] {
+        if !directives.contains(mention) {
+            filter = filter.add_directive(directive.parse().expect("invalid default directive"));
+        }
+    }
+    filter
+}
+
+/// Sets the global subscriber, forwarding the records of the `log` crate to it.
+fn set_global_subscriber(subscriber: impl Subscriber + Send + Sync + 'static) {
+    LogTracer::init().expect("log records are already forwarded");
+    tracing::subscriber::set_global_default(subscriber).expect("logger is already initialized");
+}
+
+/// Creates the span of the transaction processing stage, e.g. `mempool.add_tx`.
+///
+/// The trace ID of the span is derived from the transaction hash, so all the spans of the transaction
+/// form a single trace without passing the context along with the transaction between the services.
+/// Spans are only exported if the OpenTelemetry collector is configured.
+pub fn tx_span(stage: &str, tx_hash: &[u8]) -> tracing::Span {
+    let span = tracing::info_span!(
+        target: SPAN_TARGET,
+        "tx",
+        otel.name = stage,
+        tx_hash = %tx_hash.iter().map(|byte| format!("{:02x}", byte)).collect::<String>()
+    );
+
+    if tx_hash.len() >= 24 {
+        let trace_id = u128::from_be_bytes(tx_hash[..16].try_into().unwrap());
+        let span_id = u64::from_be_bytes(tx_hash[16..24].try_into().unwrap());
+        let parent = SpanContext::new(
+            TraceId::from_u128(trace_id),
+            SpanId::from_u64(span_id),
+            TRACE_FLAG_SAMPLED,
+            true,
+            TraceState::default(),
+        );
+        span.set_parent(Context::new().with_remote_span_context(parent));
+    }
+    span
+}
+
+/// Reports that the transaction has reached the stage which is not a long-running operation by itself,
+/// e.g. its block was committed on L1. The span is closed right away, marking the moment in the trace.
+pub fn tx_event(stage: &str, tx_hash: &[u8]) {
+    tx_span(stage, tx_hash)
+        .in_scope(|| tracing::debug!(target: SPAN_TARGET, "tx reached the stage"));
+}
+
+/// Creates the span of the API request, so all the events emitted while processing the request
+/// are annotated with its ID.
+pub fn request_span(request_id: &str) -> tracing::Span {
+    tracing::info_span!(target: SPAN_TARGET, "request", request_id)
+}
+
+/// Generates the random ID of the API request, used when the client (or the load balancer)
+/// doesn't provide one.
+pub fn new_request_id() -> String {
+    format!("{:032x}", rand::random::<u128>())
+}
+
+/// Returns the log filter directives currently in use, e.g. `info,zksync_core::state_keeper=debug`.
+/// Returns `None` if the logger is not initialized.
+pub fn log_filter() -> Option<String> {
+    LOG_FILTER
+        .get()?
+        .with_current(|filter| filter.to_string())
+        .ok()
+}
+
+/// Replaces the log filter directives (in the `RUST_LOG` format) at runtime,
+/// so the log level of a particular module can be raised without restarting the service.
+pub fn set_log_filter(directives: &str) -> Result<(), String> {
+    let filter = EnvFilter::try_new(directives).map_err(|err| err.to_string())?;
+    let filter = with_default_directives(filter, directives);
+    LOG_FILTER
+        .get()
+        .ok_or_else(|| "logger is not initialized".to_string())?
+        .reload(filter)
+        .map_err(|err| err.to_string())
+}
+
 /// Initialize logging with non blocking tracing and set up log format
 ///
 /// If the sentry URL is provided via an environment variable, this function will also initialize sentry.
@@ -71,25 +237,43 @@ fn get_sentry_url() -> Option<Dsn> {
 pub fn init() -> VlogGuard {
     let log_format = std::env::var("MISC_LOG_FORMAT").unwrap_or_else(|_| "plain".to_string());
     let (non_blocking, _logger_guard) = tracing_appender::non_blocking(std::io::stdout());
+    let otlp_endpoint = get_otlp_endpoint();
+    let otlp_tracer = otlp_endpoint.clone().and_then(otlp_tracer);
+    let otlp_layer = otlp_tracer.map(|tracer| tracing_opentelemetry::layer().with_tracer(tracer));
+    let _otlp_guard = otlp_layer.as_ref().map(|_| OtlpGuard);
+    let directives = std::env::var(EnvFilter::DEFAULT_ENV).unwrap_or_default();
+    let filter = with_default_directives(EnvFilter::from_default_env(), &directives);
+    let (filter_layer, filter_handle) = reload::Layer::new(filter);
+    LOG_FILTER
+        .set(filter_handle)
+        .expect("logger is already initialized");
+    let registry = tracing_subscriber::registry()
+        .with(filter_layer)
+        .with(otlp_layer);
     match log_format.as_str() {
         "plain" => {
-            tracing_subscriber::fmt::Subscriber::builder()
-                .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
-                .with_writer(non_blocking)
-                .init();
+            set_global_subscriber(registry.with(fmt::layer().with_writer(non_blocking)));
         }
         "json" => {
-            let timer = tracing_subscriber::fmt::time::ChronoUtc::rfc3339();
-            tracing_subscriber::fmt::Subscriber::builder()
-                .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
-                .with_writer(non_blocking)
-                .with_timer(timer)
-                .json()
-                .init();
+            let timer = fmt::time::ChronoUtc::rfc3339();
+            set_global_subscriber(
+                registry.with(
+                    fmt::layer()
+                        .with_writer(non_blocking)
+                        .with_timer(timer)
+                        .json()
+                        .with_current_span(true)
+                        .with_span_list(true),
+                ),
+            );
         }
         _ => panic!("MISC_LOG_FORMAT has an unexpected value {}", log_format),
     };
 
+    if otlp_endpoint.is_some() && _otlp_guard.is_none() {
+        tracing::warn!("The spans are not exported to the OpenTelemetry collector");
+    }
+
     let _sentry_guard = get_sentry_url().map(|sentry_url| {
         sentry::init((
             sentry_url,
@@ -106,5 +290,85 @@ pub fn init() -> VlogGuard {
     VlogGuard {
         _sentry_guard,
         _logger_guard,
+        _otlp_guard,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use tracing::Event;
+    use tracing_subscriber::layer::{Context, Layer};
+
+    /// Records the targets of the events passing the filter.
+    struct TargetsRecorder(Arc<Mutex<Vec<String>>>);
+
+    impl<S: Subscriber> Layer<S> for TargetsRecorder {
+        fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+            let target = event.metadata().target().to_string();
+            self.0.lock().unwrap().push(target);
+        }
+    }
+
+    fn forwarded_log_targets(directives: &str) -> Vec<String> {
+        // Other tests may have installed the bridge already.
+        let _ = LogTracer::init();
+        let targets = Arc::new(Mutex::new(Vec::new()));
+        let filter = with_default_directives(EnvFilter::try_new(directives).unwrap(), directives);
+        let subscriber = tracing_subscriber::registry()
+            .with(filter)
+            .with(TargetsRecorder(targets.clone()));
+        tracing::subscriber::with_default(subscriber, || {
+            log::warn!(target: "sqlx::query", "slow statement");
+            log::info!(target: "sqlx::query", "statement");
+            log::warn!(target: "hyper::client", "unrelated record");
+        });
+        let targets = targets.lock().unwrap();
+        targets.clone()
+    }
+
+    fn is_enabled(directives: &str, span: fn() -> tracing::Span) -> bool {
+        let filter = with_default_directives(EnvFilter::try_new(directives).unwrap(), directives);
+        let subscriber = tracing_subscriber::registry().with(filter);
+        tracing::subscriber::with_default(subscriber, || !span().is_disabled())
+    }
+
+    #[test]
+    fn spans_are_enabled() {
+        let tx = || tx_span("mempool.add_tx", &[1; 32]);
+        let request = || request_span("id");
+
+        // The default filter of the services doesn't mention the span target.
+        let directives = "zksync_api=debug,zksync_core=debug";
+        assert!(is_enabled(directives, tx));
+        assert!(is_enabled("warn", tx));
+        assert!(is_enabled(directives, request));
+
+        // Spans can still be disabled explicitly.
+        assert!(!is_enabled("info,zksync_spans=off", tx));
+        assert!(!is_enabled("info,zksync_spans=off", request));
+    }
+
+    #[test]
+    fn slow_statements_are_logged() {
+        assert_eq!(
+            forwarded_log_targets("zksync_api=debug,zksync_core=debug"),
+            vec!["sqlx::query"]
+        );
+        // The statements can still be configured explicitly.
+        assert_eq!(
+            forwarded_log_targets("zksync_core=debug,sqlx=info"),
+            vec!["sqlx::query", "sqlx::query"]
+        );
+        assert_eq!(
+            forwarded_log_targets("info,sqlx=off"),
+            vec!["hyper::client"]
+        );
+    }
+
+    #[test]
+    fn otlp_requires_runtime() {
+        assert!(otlp_tracer("http://localhost:4317".to_string()).is_none());
     }
 }
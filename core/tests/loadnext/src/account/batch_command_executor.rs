@@ -3,14 +3,14 @@ use zksync::{error::ClientError, operations::SyncTransactionHandle};
 use crate::{
     account::AccountLifespan,
     command::{ExpectedOutcome, IncorrectnessModifier, TxCommand, TxType},
-    report::ReportLabel,
+    report::ActionOutcome,
 };
 
 impl AccountLifespan {
     pub(super) async fn execute_batch_command(
         &mut self,
         batch_command: &[TxCommand],
-    ) -> Result<ReportLabel, ClientError> {
+    ) -> Result<ActionOutcome, ClientError> {
         let mut batch = Vec::with_capacity(batch_command.len());
 
         // Since we're manually building the batch, we have to increment nonce by ourselves.
@@ -58,8 +58,21 @@ impl AccountLifespan {
                     .unwrap_or(IncorrectnessModifier::None)
             });
 
+        // The error code is only predictable if all the incorrect transactions have the same modifier,
+        // otherwise it depends on the order of the server checks.
+        let mut api_failures = batch_command
+            .iter()
+            .map(|cmd| cmd.modifier)
+            .filter(|modifier| modifier.expected_outcome() == ExpectedOutcome::ApiRequestFailed);
+        let expected_error_code = match api_failures.next() {
+            Some(first) if api_failures.all(|modifier| modifier == first) => {
+                first.expected_error_code()
+            }
+            _ => None,
+        };
+
         let provider = self.wallet.provider.clone();
-        self.submit(modifier, || async {
+        self.submit(modifier, expected_error_code, || async {
             self.wallet.provider.send_txs_batch(batch, None).await?;
             Ok(SyncTransactionHandle::new(main_hash, provider))
         })
@@ -13,9 +13,11 @@ use crate::{
     account_pool::{AddressPool, TestWallet},
     command::{Command, ExpectedOutcome, IncorrectnessModifier, TxCommand},
     config::LoadtestConfig,
-    constants::{COMMIT_TIMEOUT, POLLING_INTERVAL},
-    report::{Report, ReportBuilder, ReportLabel},
+    constants::{COMMIT_TIMEOUT, POLLING_INTERVAL, VERIFY_TIMEOUT},
+    market_maker::MarketMaker,
+    report::{ActionOutcome, OperationTimings, Report, ReportBuilder, ReportLabel},
     rng::LoadtestRng,
+    scenario::Scenario,
 };
 
 mod batch_command_executor;
@@ -37,10 +39,13 @@ pub struct AccountLifespan {
     /// Rng unique to the account.
     rng: LoadtestRng,
     config: LoadtestConfig,
+    scenario: Scenario,
     /// Pool of account addresses, used to generate commands.
     addresses: AddressPool,
-    /// ERC-20 token used in the test.
-    main_token: Token,
+    /// ERC-20 tokens used in the test.
+    tokens: Vec<Token>,
+    /// Orders to match the swaps with.
+    market_maker: MarketMaker,
     /// Channel for sending reports about performed operations.
     report_sink: Sender<Report>,
 }
@@ -48,23 +53,33 @@ pub struct AccountLifespan {
 impl AccountLifespan {
     pub fn new(
         config: &LoadtestConfig,
+        scenario: &Scenario,
         addresses: AddressPool,
         test_account: TestWallet,
+        market_maker: MarketMaker,
         report_sink: Sender<Report>,
     ) -> Self {
-        let main_token = test_account
-            .wallet
+        let tokens = scenario
             .tokens
-            .resolve(config.main_token.as_str().into())
-            .unwrap();
+            .iter()
+            .map(|token| {
+                test_account
+                    .wallet
+                    .tokens
+                    .resolve(token.as_str().into())
+                    .unwrap()
+            })
+            .collect();
 
         Self {
             wallet: test_account.wallet,
             eth_pk: test_account.eth_pk,
             rng: test_account.rng,
             config: config.clone(),
+            scenario: scenario.clone(),
             addresses,
-            main_token,
+            tokens,
+            market_maker,
 
             report_sink,
         }
@@ -96,12 +111,48 @@ impl AccountLifespan {
             }
         }
 
-        let command_sequence = self.generate_commands();
-        for command in command_sequence {
+        let started_at = Instant::now();
+        let deadline = self
+            .scenario
+            .duration()
+            .map(|duration| started_at + duration);
+        let command_interval = self.scenario.command_interval();
+
+        // We start with a CPK just to unlock accounts.
+        let mut command = Command::SingleTx(TxCommand::change_pubkey(
+            self.wallet.address(),
+            self.scenario.main_token(),
+        ));
+        let mut operations_performed = 0;
+        loop {
+            let command_started_at = Instant::now();
             self.execute_command(command).await;
+
+            let test_finished = match deadline {
+                Some(deadline) => Instant::now() >= deadline,
+                None => operations_performed >= self.scenario.operations_per_account,
+            };
+            if test_finished {
+                break;
+            }
+
+            if let Some(interval) = command_interval {
+                tokio::time::sleep_until((command_started_at + interval).into()).await;
+            }
+
+            command = self.next_command();
+            operations_performed += 1;
         }
     }
 
+    /// Returns the token used in the test by its symbol.
+    fn token(&self, symbol: &str) -> &Token {
+        self.tokens
+            .iter()
+            .find(|token| token.symbol.eq_ignore_ascii_case(symbol))
+            .expect("Token is not used in the scenario")
+    }
+
     /// Executes a command with support of retries:
     /// If command fails due to the network/API error, it will be retried multiple times
     /// before considering it completely failed. Such an approach makes us a bit more resilient to
@@ -124,8 +175,8 @@ impl AccountLifespan {
                 }
             };
 
-            let label = match result {
-                Ok(label) => label,
+            let outcome = match result {
+                Ok(outcome) => outcome,
                 Err(ClientError::NetworkError(_)) | Err(ClientError::OperationTimeout) => {
                     if attempt < MAX_RETRIES {
                         // Retry operation.
@@ -138,16 +189,18 @@ impl AccountLifespan {
                         "Retries limit reached. Latest error: {}",
                         result.unwrap_err()
                     );
-                    ReportLabel::failed(&error)
+                    ReportLabel::failed(&error).into()
                 }
                 Err(err) => {
                     // Other kinds of errors should not be handled, we will just report them.
-                    ReportLabel::failed(&err.to_string())
+                    ActionOutcome::from(ReportLabel::failed(&err.to_string()))
+                        .with_error_code(api_error_code(&err))
                 }
             };
 
             // We won't continue the loop unless `continue` was manually called.
-            self.report(label, start.elapsed(), attempt, command).await;
+            self.report(outcome, start.elapsed(), attempt, command)
+                .await;
             break;
         }
     }
@@ -155,20 +208,23 @@ impl AccountLifespan {
     /// Builds a report and sends it.
     async fn report(
         &mut self,
-        label: ReportLabel,
+        outcome: ActionOutcome,
         time: Duration,
         retries: usize,
         command: Command,
     ) {
-        if matches!(label, ReportLabel::ActionFailed { .. }) {
+        if matches!(outcome.label, ReportLabel::ActionFailed { .. }) {
             vlog::error!("Command failed: {:#?}", command);
         }
 
         let report = ReportBuilder::new()
-            .label(label)
+            .label(outcome.label)
             .reporter(self.wallet.address())
             .time(time)
+            .timings(outcome.timings)
+            .error_code(outcome.error_code)
             .retries(retries)
+            .valid(command.is_valid())
             .action(command)
             .finish();
 
@@ -186,50 +242,74 @@ impl AccountLifespan {
     async fn submit<F, Fut>(
         &self,
         modifier: IncorrectnessModifier,
+        expected_error_code: Option<i64>,
         send: F,
-    ) -> Result<ReportLabel, ClientError>
+    ) -> Result<ActionOutcome, ClientError>
     where
         F: FnOnce() -> Fut,
         Fut: Future<Output = Result<SyncTransactionHandle<RpcProvider>, ClientError>>,
     {
         let expected_outcome = modifier.expected_outcome();
 
+        let submitted_at = Instant::now();
         let send_result = send().await;
+        let mut timings = OperationTimings {
+            submission: Some(submitted_at.elapsed()),
+            ..Default::default()
+        };
+
         let mut handle = match (expected_outcome, send_result) {
             (ExpectedOutcome::ApiRequestFailed, Ok(_handle)) => {
                 // Transaction got accepted, but should have not been.
                 let error = "Tx/batch was accepted, but should have not been";
-                return Ok(ReportLabel::failed(error));
+                return Ok(ActionOutcome::new(ReportLabel::failed(error), timings));
             }
             (_, Ok(handle)) => {
                 // Transaction should have been accepted by API and it was; now wait for the commitment.
                 handle
             }
-            (ExpectedOutcome::ApiRequestFailed, Err(_error)) => {
+            (ExpectedOutcome::ApiRequestFailed, Err(error)) => {
                 // Transaction was expected to be rejected and it was.
-                return Ok(ReportLabel::done());
+                // If we know the reason of the rejection, it must match the actual one.
+                let error_code = api_error_code(&error);
+                let label = match (expected_error_code, error) {
+                    (Some(expected_code), ClientError::RpcError(failure))
+                        if failure.error.code.code() != expected_code =>
+                    {
+                        let error = format!(
+                            "Tx/batch was rejected with unexpected error: expected code {} because of modifier {:?}, got {:?}",
+                            expected_code, modifier, failure.error
+                        );
+                        ReportLabel::failed(&error)
+                    }
+                    _ => ReportLabel::done(),
+                };
+                return Ok(ActionOutcome::new(label, timings).with_error_code(error_code));
             }
-            (_, Err(_error)) => {
+            (_, Err(error)) => {
                 // Transaction was expected to be accepted, but was rejected.
-                let error = "Tx/batch should have been accepted, but got rejected";
-                return Ok(ReportLabel::failed(error));
+                let error_code = api_error_code(&error);
+                let label =
+                    ReportLabel::failed("Tx/batch should have been accepted, but got rejected");
+                return Ok(ActionOutcome::new(label, timings).with_error_code(error_code));
             }
         };
 
         handle.polling_interval(POLLING_INTERVAL).unwrap();
-        let transaction_receipt = handle
+        let handle = handle
             .commit_timeout(COMMIT_TIMEOUT)
-            .wait_for_commit()
-            .await?;
+            .verify_timeout(VERIFY_TIMEOUT);
+        let transaction_receipt = handle.wait_for_commit().await?;
+        timings.commit = Some(submitted_at.elapsed());
 
-        match expected_outcome {
+        let label = match expected_outcome {
             ExpectedOutcome::TxSucceed if transaction_receipt.fail_reason.is_none() => {
                 // Transaction succeed and it should have.
-                Ok(ReportLabel::done())
+                ReportLabel::done()
             }
             ExpectedOutcome::TxRejected if transaction_receipt.fail_reason.is_some() => {
                 // Transaction failed and it should have.
-                Ok(ReportLabel::done())
+                return Ok(ActionOutcome::new(ReportLabel::done(), timings));
             }
             other => {
                 // Transaction status didn't match expected one.
@@ -237,23 +317,41 @@ impl AccountLifespan {
                     "Unexpected transaction status: expected {:#?} because of modifier {:?}, receipt {:#?}",
                     other, modifier, transaction_receipt
                 );
-                Ok(ReportLabel::failed(&error))
+                return Ok(ActionOutcome::new(ReportLabel::failed(&error), timings));
+            }
+        };
+
+        // Only the successfully executed transactions are awaited to be verified.
+        if self.scenario.wait_for_verify {
+            // The transaction is already committed, so it must not be resent on the verification timeout:
+            // such an error is reported as is.
+            match handle.wait_for_verify().await {
+                Ok(_) => timings.verify = Some(submitted_at.elapsed()),
+                Err(err) => {
+                    let error = format!("Committed tx/batch was not verified: {}", err);
+                    return Ok(ActionOutcome::new(ReportLabel::failed(&error), timings));
+                }
             }
         }
+
+        Ok(ActionOutcome::new(label, timings))
     }
 
-    /// Prepares a list of random operations to be executed by an account.
-    fn generate_commands(&mut self) -> Vec<Command> {
-        // We start with a CPK just to unlock accounts.
-        let mut commands = vec![Command::SingleTx(TxCommand::change_pubkey(
+    /// Generates the next random operation to be executed by an account.
+    fn next_command(&mut self) -> Command {
+        Command::random(
+            &mut self.rng,
             self.wallet.address(),
-        ))];
-
-        for _ in 0..self.config.operations_per_account {
-            let command = Command::random(&mut self.rng, self.wallet.address(), &self.addresses);
-            commands.push(command)
-        }
+            &self.addresses,
+            &self.scenario,
+        )
+    }
+}
 
-        commands
+/// Returns the code of the API error, if the operation was rejected by the server.
+fn api_error_code(error: &ClientError) -> Option<i64> {
+    match error {
+        ClientError::RpcError(failure) => Some(failure.error.code.code()),
+        _ => None,
     }
 }
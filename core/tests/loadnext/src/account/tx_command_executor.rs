@@ -1,25 +1,28 @@
-use std::convert::TryInto;
+use std::{convert::TryInto, time::Instant};
 
 use num::{BigUint, Zero};
 use zksync::{
-    error::ClientError, ethereum::PriorityOpHolder, operations::SyncTransactionHandle,
+    error::ClientError,
+    ethereum::PriorityOpHolder,
+    operations::SyncTransactionHandle,
     provider::Provider,
+    types::{BlockStatus, NFT},
 };
-use zksync_types::{tokens::ETH_TOKEN_ID, tx::PackedEthSignature, Nonce, ZkSyncTx, H256};
+use zksync_types::{tokens::ETH_TOKEN_ID, tx::PackedEthSignature, Nonce, Token, ZkSyncTx, H256};
 
 use crate::{
     account::AccountLifespan,
     command::{IncorrectnessModifier, TxCommand, TxType},
     constants::{COMMIT_TIMEOUT, POLLING_INTERVAL},
     corrupted_tx::Corrupted,
-    report::ReportLabel,
+    report::{ActionOutcome, OperationTimings, ReportLabel},
 };
 
 impl AccountLifespan {
     pub(super) async fn execute_tx_command(
         &mut self,
         command: &TxCommand,
-    ) -> Result<ReportLabel, ClientError> {
+    ) -> Result<ActionOutcome, ClientError> {
         match command.command_type {
             TxType::ChangePubKey => self.execute_change_pubkey(command).await,
             TxType::TransferToExisting | TxType::TransferToNew => {
@@ -30,6 +33,10 @@ impl AccountLifespan {
             }
             TxType::Deposit => self.execute_deposit(command).await,
             TxType::FullExit => self.execute_full_exit().await,
+            TxType::MintNFT => self.execute_mint_nft(command).await,
+            TxType::TransferNFT => self.execute_transfer_nft(command).await,
+            TxType::WithdrawNFT => self.execute_withdraw_nft(command).await,
+            TxType::Swap => self.execute_swap(command).await,
         }
     }
 
@@ -49,24 +56,41 @@ impl AccountLifespan {
         &self,
         tx: ZkSyncTx,
         eth_signature: Option<PackedEthSignature>,
-        modifier: IncorrectnessModifier,
+        command: &TxCommand,
     ) -> (ZkSyncTx, Option<PackedEthSignature>) {
+        let token = self.token(&command.token);
         (tx, eth_signature).apply_modifier(
-            modifier,
+            command.modifier,
             self.eth_pk,
-            self.main_token.symbol.as_ref(),
-            self.main_token.decimals,
+            token.symbol.as_ref(),
+            token.decimals,
         )
     }
 
-    /// Returns the balances for ETH and the main token on the L1.
+    /// Returns the nonce to be set in the transaction: either the provided one, or the already used
+    /// one for the `WrongNonce` modifier.
+    async fn tx_nonce(
+        &self,
+        command: &TxCommand,
+        nonce: Option<Nonce>,
+    ) -> Result<Option<Nonce>, ClientError> {
+        if command.modifier != IncorrectnessModifier::WrongNonce {
+            return Ok(nonce);
+        }
+
+        // Every account starts with `ChangePubKey`, so the committed nonce is positive.
+        let committed_nonce = self.wallet.account_info().await?.committed.nonce;
+        Ok(Some(Nonce(committed_nonce.saturating_sub(1))))
+    }
+
+    /// Returns the balances for ETH and the given token on the L1.
     /// This function is used to check whether the L1 operation can be performed or should be
     /// skipped.
-    async fn l1_balances(&self) -> Result<(BigUint, BigUint), ClientError> {
+    async fn l1_balances(&self, token: &Token) -> Result<(BigUint, BigUint), ClientError> {
         let ethereum = self.wallet.ethereum(&self.config.web3_url).await?;
         let eth_balance = ethereum.balance().await?;
         let erc20_balance = ethereum
-            .erc20_balance(self.wallet.address(), self.main_token.id)
+            .erc20_balance(self.wallet.address(), token.id)
             .await?;
 
         // Casting via `low_u128` is safe here, since we don't use numbers higher than `u128::max_value()`.
@@ -74,32 +98,29 @@ impl AccountLifespan {
         Ok((eth_balance, erc20_balance))
     }
 
-    async fn execute_deposit(&self, command: &TxCommand) -> Result<ReportLabel, ClientError> {
-        let (eth_balance, erc20_balance) = self.l1_balances().await?;
+    async fn execute_deposit(&self, command: &TxCommand) -> Result<ActionOutcome, ClientError> {
+        let token = self.token(&command.token);
+        let (eth_balance, erc20_balance) = self.l1_balances(token).await?;
         if eth_balance.is_zero() || erc20_balance < command.amount {
             // We don't have either funds in L1 to pay for tx or to deposit.
             // It's not a problem with the server, thus we mark this operation as skipped.
-            return Ok(ReportLabel::skipped("No L1 balance"));
+            return Ok(ReportLabel::skipped("No L1 balance").into());
         }
 
         let ethereum = self.wallet.ethereum(&self.config.web3_url).await?;
 
         // We should check whether we've previously approved ERC-20 deposits.
-        let deposits_allowed = ethereum
-            .is_erc20_deposit_approved(self.main_token.id)
-            .await?;
+        let deposits_allowed = ethereum.is_erc20_deposit_approved(token.id).await?;
         if !deposits_allowed {
-            let approve_tx_hash = ethereum
-                .approve_erc20_token_deposits(self.main_token.id)
-                .await?;
+            let approve_tx_hash = ethereum.approve_erc20_token_deposits(token.id).await?;
             // Before submitting the deposit, wait for the approve transaction confirmation.
             match ethereum.wait_for_tx(approve_tx_hash).await {
                 Ok(receipt) => {
                     if receipt.status != Some(1.into()) {
-                        return Ok(ReportLabel::skipped("Approve transaction failed"));
+                        return Ok(ReportLabel::skipped("Approve transaction failed").into());
                     }
                 }
-                Err(reason) => return Ok(ReportLabel::skipped(&reason.to_string())),
+                Err(reason) => return Ok(ReportLabel::skipped(&reason.to_string()).into()),
             }
         }
 
@@ -112,7 +133,7 @@ impl AccountLifespan {
             .unwrap_or_else(|_| u128::max_value())
             .into();
         let eth_tx_hash = match ethereum
-            .deposit(self.main_token.id, amount, self.wallet.address())
+            .deposit(token.id, amount, self.wallet.address())
             .await
         {
             Ok(hash) => hash,
@@ -120,18 +141,20 @@ impl AccountLifespan {
                 // Most likely we don't have enough ETH to perform operations.
                 // Just mark the operations as skipped.
                 let reason = format!("Unable to perform an L1 operation. Reason: {}", err);
-                return Ok(ReportLabel::skipped(&reason));
+                return Ok(ReportLabel::skipped(&reason).into());
             }
         };
 
         self.handle_priority_op(eth_tx_hash).await
     }
 
-    async fn execute_full_exit(&self) -> Result<ReportLabel, ClientError> {
-        let balances = self.l1_balances().await?;
+    async fn execute_full_exit(&self) -> Result<ActionOutcome, ClientError> {
+        let balances = self
+            .l1_balances(self.token(self.scenario.main_token()))
+            .await?;
         if balances.0.is_zero() {
             // We don't have either funds in L1 to pay for tx.
-            return Ok(ReportLabel::skipped("No L1 balance"));
+            return Ok(ReportLabel::skipped("No L1 balance").into());
         }
 
         // We always call full exit for the ETH, since we don't want to leave the wallet without main token.
@@ -140,7 +163,7 @@ impl AccountLifespan {
         let account_id = match self.wallet.account_id() {
             Some(id) => id,
             None => {
-                return Ok(ReportLabel::skipped("L2 account was not initialized yet"));
+                return Ok(ReportLabel::skipped("L2 account was not initialized yet").into());
             }
         };
 
@@ -150,14 +173,14 @@ impl AccountLifespan {
             Err(_err) => {
                 // Most likely we don't have enough ETH to perform operations.
                 // Just mark the operations as skipped.
-                return Ok(ReportLabel::skipped("Unable to perform an L1 operation"));
+                return Ok(ReportLabel::skipped("Unable to perform an L1 operation").into());
             }
         };
 
         self.handle_priority_op(eth_tx_hash).await
     }
 
-    async fn handle_priority_op(&self, eth_tx_hash: H256) -> Result<ReportLabel, ClientError> {
+    async fn handle_priority_op(&self, eth_tx_hash: H256) -> Result<ActionOutcome, ClientError> {
         let ethereum = self.wallet.ethereum(&self.config.web3_url).await?;
         let receipt = ethereum.wait_for_tx(eth_tx_hash).await?;
 
@@ -166,12 +189,13 @@ impl AccountLifespan {
             Some(handle) => handle,
             None => {
                 // Probably we did something wrong, no big deal.
-                return Ok(ReportLabel::skipped(
-                    "Ethereum transaction for deposit failed",
-                ));
+                return Ok(ReportLabel::skipped("Ethereum transaction for deposit failed").into());
             }
         };
 
+        // For priority operations, the commitment time is measured since the operation was included into
+        // the Ethereum block.
+        let included_at = Instant::now();
         priority_op_handle
             .polling_interval(POLLING_INTERVAL)
             .unwrap();
@@ -180,17 +204,28 @@ impl AccountLifespan {
             .wait_for_commit()
             .await?;
 
-        Ok(ReportLabel::done())
+        let timings = OperationTimings {
+            commit: Some(included_at.elapsed()),
+            ..Default::default()
+        };
+        Ok(ActionOutcome::new(ReportLabel::done(), timings))
     }
 
-    async fn execute_change_pubkey(&self, command: &TxCommand) -> Result<ReportLabel, ClientError> {
+    async fn execute_change_pubkey(
+        &self,
+        command: &TxCommand,
+    ) -> Result<ActionOutcome, ClientError> {
         let (tx, eth_signature) = self.build_change_pubkey(command, None).await?;
 
         let provider = self.wallet.provider.clone();
-        self.submit(command.modifier, || async {
-            let tx_hash = provider.send_tx(tx, eth_signature).await?;
-            Ok(SyncTransactionHandle::new(tx_hash, provider))
-        })
+        self.submit(
+            command.modifier,
+            command.modifier.expected_error_code(),
+            || async {
+                let tx_hash = provider.send_tx(tx, eth_signature).await?;
+                Ok(SyncTransactionHandle::new(tx_hash, provider))
+            },
+        )
         .await
     }
 
@@ -202,26 +237,30 @@ impl AccountLifespan {
         let mut builder = self
             .wallet
             .start_change_pubkey()
-            .fee_token(self.config.main_token.as_str())
+            .fee_token(command.token.as_str())
             .unwrap();
 
-        if let Some(nonce) = nonce {
+        if let Some(nonce) = self.tx_nonce(command, nonce).await? {
             builder = builder.nonce(nonce);
         }
 
         let tx = builder.tx().await.map_err(Self::tx_creation_error)?;
 
-        Ok(self.apply_modifier(tx, None, command.modifier))
+        Ok(self.apply_modifier(tx, None, command))
     }
 
-    async fn execute_transfer(&self, command: &TxCommand) -> Result<ReportLabel, ClientError> {
+    async fn execute_transfer(&self, command: &TxCommand) -> Result<ActionOutcome, ClientError> {
         let (tx, eth_signature) = self.build_transfer(command, None).await?;
 
         let provider = self.wallet.provider.clone();
-        self.submit(command.modifier, || async {
-            let tx_hash = provider.send_tx(tx, eth_signature).await?;
-            Ok(SyncTransactionHandle::new(tx_hash, provider))
-        })
+        self.submit(
+            command.modifier,
+            command.modifier.expected_error_code(),
+            || async {
+                let tx_hash = provider.send_tx(tx, eth_signature).await?;
+                Ok(SyncTransactionHandle::new(tx_hash, provider))
+            },
+        )
         .await
     }
 
@@ -235,26 +274,30 @@ impl AccountLifespan {
             .start_transfer()
             .to(command.to)
             .amount(command.amount.clone())
-            .token(self.config.main_token.as_str())
+            .token(command.token.as_str())
             .unwrap();
 
-        if let Some(nonce) = nonce {
+        if let Some(nonce) = self.tx_nonce(command, nonce).await? {
             builder = builder.nonce(nonce);
         }
 
         let (tx, eth_signature) = builder.tx().await.map_err(Self::tx_creation_error)?;
 
-        Ok(self.apply_modifier(tx, eth_signature, command.modifier))
+        Ok(self.apply_modifier(tx, eth_signature, command))
     }
 
-    async fn execute_withdraw(&self, command: &TxCommand) -> Result<ReportLabel, ClientError> {
+    async fn execute_withdraw(&self, command: &TxCommand) -> Result<ActionOutcome, ClientError> {
         let (tx, eth_signature) = self.build_withdraw(command, None).await?;
 
         let provider = self.wallet.provider.clone();
-        self.submit(command.modifier, || async {
-            let tx_hash = provider.send_tx(tx, eth_signature).await?;
-            Ok(SyncTransactionHandle::new(tx_hash, provider))
-        })
+        self.submit(
+            command.modifier,
+            command.modifier.expected_error_code(),
+            || async {
+                let tx_hash = provider.send_tx(tx, eth_signature).await?;
+                Ok(SyncTransactionHandle::new(tx_hash, provider))
+            },
+        )
         .await
     }
 
@@ -268,14 +311,149 @@ impl AccountLifespan {
             .start_withdraw()
             .to(command.to)
             .amount(command.amount.clone())
-            .token(self.config.main_token.as_str())
+            .token(command.token.as_str())
             .unwrap();
-        if let Some(nonce) = nonce {
+        if let Some(nonce) = self.tx_nonce(command, nonce).await? {
             builder = builder.nonce(nonce);
         }
 
         let (tx, eth_signature) = builder.tx().await.map_err(Self::tx_creation_error)?;
 
-        Ok(self.apply_modifier(tx, eth_signature, command.modifier))
+        Ok(self.apply_modifier(tx, eth_signature, command))
+    }
+
+    async fn execute_mint_nft(&self, command: &TxCommand) -> Result<ActionOutcome, ClientError> {
+        let (tx, eth_signature) = self
+            .wallet
+            .start_mint_nft()
+            .recipient(command.to)
+            .content_hash(H256::random())
+            .fee_token(command.token.as_str())
+            .unwrap()
+            .tx()
+            .await
+            .map_err(Self::tx_creation_error)?;
+
+        let provider = self.wallet.provider.clone();
+        self.submit(command.modifier, None, || async {
+            let tx_hash = provider.send_tx(tx, eth_signature).await?;
+            Ok(SyncTransactionHandle::new(tx_hash, provider))
+        })
+        .await
+    }
+
+    /// Returns the NFT owned by the account with the lowest ID, if any.
+    async fn owned_nft(&self) -> Result<Option<NFT>, ClientError> {
+        let nfts = self.wallet.get_nfts(BlockStatus::Committed).await?;
+        Ok(nfts
+            .into_iter()
+            .min_by_key(|(token_id, _)| *token_id)
+            .map(|(_, nft)| nft))
+    }
+
+    async fn execute_transfer_nft(
+        &self,
+        command: &TxCommand,
+    ) -> Result<ActionOutcome, ClientError> {
+        let nft = match self.owned_nft().await? {
+            Some(nft) => nft,
+            None => return Ok(ReportLabel::skipped("No NFTs to transfer").into()),
+        };
+
+        // NFT transfer is a batch of the NFT transfer itself and the transfer paying the fee.
+        let (nft_transfer, fee_transfer) = self
+            .wallet
+            .start_transfer_nft()
+            .nft(nft)
+            .to(command.to)
+            .fee_token(command.token.as_str())
+            .unwrap()
+            .tx()
+            .await
+            .map_err(Self::tx_creation_error)?;
+        let main_hash = nft_transfer.0.hash();
+
+        let provider = self.wallet.provider.clone();
+        self.submit(command.modifier, None, || async {
+            provider
+                .send_txs_batch(vec![nft_transfer, fee_transfer], None)
+                .await?;
+            Ok(SyncTransactionHandle::new(main_hash, provider))
+        })
+        .await
+    }
+
+    async fn execute_withdraw_nft(
+        &self,
+        command: &TxCommand,
+    ) -> Result<ActionOutcome, ClientError> {
+        let nft = match self.owned_nft().await? {
+            Some(nft) => nft,
+            None => return Ok(ReportLabel::skipped("No NFTs to withdraw").into()),
+        };
+
+        let (tx, eth_signature) = self
+            .wallet
+            .start_withdraw_nft()
+            .token(nft.id)
+            .unwrap()
+            .to(command.to)
+            .fee_token(command.token.as_str())
+            .unwrap()
+            .tx()
+            .await
+            .map_err(Self::tx_creation_error)?;
+
+        let provider = self.wallet.provider.clone();
+        self.submit(command.modifier, None, || async {
+            let tx_hash = provider.send_tx(tx, eth_signature).await?;
+            Ok(SyncTransactionHandle::new(tx_hash, provider))
+        })
+        .await
+    }
+
+    async fn execute_swap(&self, command: &TxCommand) -> Result<ActionOutcome, ClientError> {
+        let token_sell = self.token(&command.token);
+        let token_buy = self.token(self.scenario.swap_token_buy(&command.token));
+        let counter_order = match self.market_maker.counter_order(token_sell.id, token_buy.id) {
+            Some(order) => order.clone(),
+            None => return Ok(ReportLabel::skipped("No order to match the swap with").into()),
+        };
+
+        // The order of the account is the exact one (zero amount would make it a limit order), and the counter
+        // order is the limit one with the 1:1 ratio, so the amounts of both orders are the same.
+        let order = self
+            .wallet
+            .start_order()
+            .token_sell(token_sell.id)
+            .unwrap()
+            .token_buy(token_buy.id)
+            .unwrap()
+            .ratio(1u64, 1u64)
+            .amount(&command.amount + 1u64)
+            .sign()
+            .await
+            .map_err(Self::tx_creation_error)?;
+        let amount = order.order.amount.clone();
+
+        let (swap, eth_signature, orders_eth_signatures) = self
+            .wallet
+            .start_swap()
+            .orders(order, counter_order)
+            .amounts(amount.clone(), amount)
+            .fee_token(self.scenario.main_token())
+            .unwrap()
+            .tx()
+            .await
+            .map_err(Self::tx_creation_error)?;
+
+        let provider = self.wallet.provider.clone();
+        self.submit(command.modifier, None, || async {
+            let tx_hash = provider
+                .send_swap(swap, eth_signature, orders_eth_signatures)
+                .await?;
+            Ok(SyncTransactionHandle::new(tx_hash, provider))
+        })
+        .await
     }
 }
@@ -12,6 +12,7 @@ use zksync_types::{tx::PackedEthSignature, Address, H256};
 use crate::{
     config::LoadtestConfig,
     rng::{LoadtestRng, Random},
+    scenario::Scenario,
 };
 
 /// Thread-safe pool of the addresses of accounts used in the loadtest.
@@ -77,11 +78,14 @@ pub struct AccountPool {
     pub accounts: VecDeque<TestWallet>,
     /// Pool of addresses of the test accounts.
     pub addresses: AddressPool,
+    /// Account providing the orders for the swaps, if the scenario has swaps.
+    /// It doesn't belong to the address pool, so the test accounts never send funds to it.
+    pub market_maker: Option<TestWallet>,
 }
 
 impl AccountPool {
     /// Generates all the required test accounts and prepares `Wallet` objects.
-    pub async fn new(config: &LoadtestConfig) -> anyhow::Result<Self> {
+    pub async fn new(config: &LoadtestConfig, scenario: &Scenario) -> anyhow::Result<Self> {
         let provider = RpcProvider::from_addr_and_network(
             &config.zksync_rpc_addr,
             zksync::Network::from_str(&config.eth_network).expect("Invalid network name"),
@@ -116,38 +120,52 @@ impl AccountPool {
                 .expect("Can't create a wallet")
         };
 
-        let mut accounts = VecDeque::with_capacity(config.accounts_amount);
-        let mut addresses = Vec::with_capacity(config.accounts_amount);
+        let mut accounts = VecDeque::with_capacity(scenario.accounts_amount);
+        let mut addresses = Vec::with_capacity(scenario.accounts_amount);
 
-        for _ in 0..config.accounts_amount {
-            let eth_credentials = AccountCredentials::random(&mut rng);
-            let zksync_pk = private_key_from_seed(eth_credentials.eth_pk.as_bytes())
-                .expect("Can't generate the zkSync private key");
-            let wallet_credentials = WalletCredentials::<PrivateKeySigner>::from_pk(
-                eth_credentials.address,
-                zksync_pk,
-                Some(eth_credentials.eth_pk),
-            );
-
-            let wallet = Wallet::new(provider.clone(), wallet_credentials)
-                .await
-                .expect("Can't create a wallet");
-
-            addresses.push(wallet.address());
-            let account = TestWallet {
-                wallet,
-                eth_pk: eth_credentials.eth_pk,
-                rng: rng.derive(eth_credentials.eth_pk),
-            };
+        for _ in 0..scenario.accounts_amount {
+            let account = Self::new_test_wallet(&provider, &mut rng).await;
+            addresses.push(account.wallet.address());
             accounts.push_back(account);
         }
 
+        // The market maker is generated after the test accounts, so that they don't depend on whether
+        // the scenario has swaps.
+        let market_maker = if scenario.has_swaps() {
+            Some(Self::new_test_wallet(&provider, &mut rng).await)
+        } else {
+            None
+        };
+
         Ok(Self {
             master_wallet,
             accounts,
             addresses: AddressPool::new(addresses),
+            market_maker,
         })
     }
+
+    /// Generates a random test wallet.
+    async fn new_test_wallet(provider: &RpcProvider, rng: &mut LoadtestRng) -> TestWallet {
+        let eth_credentials = AccountCredentials::random(rng);
+        let zksync_pk = private_key_from_seed(eth_credentials.eth_pk.as_bytes())
+            .expect("Can't generate the zkSync private key");
+        let wallet_credentials = WalletCredentials::<PrivateKeySigner>::from_pk(
+            eth_credentials.address,
+            zksync_pk,
+            Some(eth_credentials.eth_pk),
+        );
+
+        let wallet = Wallet::new(provider.clone(), wallet_credentials)
+            .await
+            .expect("Can't create a wallet");
+
+        TestWallet {
+            wallet,
+            eth_pk: eth_credentials.eth_pk,
+            rng: rng.derive(eth_credentials.eth_pk),
+        }
+    }
 }
 
 fn pk_to_address(eth_pk: &H256) -> Address {
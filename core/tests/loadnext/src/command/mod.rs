@@ -1,13 +1,8 @@
 use rand::Rng;
-use static_assertions::const_assert;
 
 use zksync_types::Address;
 
-use crate::{
-    account_pool::AddressPool,
-    constants::MAX_BATCH_SIZE,
-    rng::{LoadtestRng, Random},
-};
+use crate::{account_pool::AddressPool, rng::LoadtestRng, scenario::Scenario};
 
 pub use self::{
     api_command::ApiRequestCommand,
@@ -35,25 +30,18 @@ enum CommandType {
     ApiRequest,
 }
 
-impl Random for CommandType {
-    fn random(rng: &mut LoadtestRng) -> Self {
-        // Chances of a certain event generation.
-        // You must maintain the sum of these constants to be equal to 1.0f32.
-        const SINGLE_TX_CHANCE: f32 = 0.7;
-        const BATCH_CHANCE: f32 = 0.3;
+impl CommandType {
+    fn random(rng: &mut LoadtestRng, scenario: &Scenario) -> Self {
         // We don't generate API requests at the moment.
-        const _API_REQUEST_CHANCE: f32 = 0.0;
+        const API_REQUEST_CHANCE: f32 = 0.0;
+
+        let single_tx_chance = 1.0f32 - scenario.batch_chance - API_REQUEST_CHANCE;
 
-        const _CHANCES_SUM: f32 = SINGLE_TX_CHANCE + BATCH_CHANCE + _API_REQUEST_CHANCE;
-        // Unfortunately. f64::abs()` is not yet a `const` function.
-        const_assert!(
-            -f32::EPSILON <= (_CHANCES_SUM - 1.0f32) && (_CHANCES_SUM - 1.0f32) <= f32::EPSILON
-        );
         let chance = rng.gen_range(0.0f32..1.0f32);
 
-        if chance <= SINGLE_TX_CHANCE {
+        if chance <= single_tx_chance {
             Self::SingleTx
-        } else if chance <= (SINGLE_TX_CHANCE + BATCH_CHANCE) {
+        } else if chance <= (single_tx_chance + scenario.batch_chance) {
             Self::Batch
         } else {
             Self::ApiRequest
@@ -62,27 +50,59 @@ impl Random for CommandType {
 }
 
 impl Command {
-    pub fn random(rng: &mut LoadtestRng, own_address: Address, addresses: &AddressPool) -> Self {
-        match CommandType::random(rng) {
-            CommandType::SingleTx => Self::SingleTx(TxCommand::random(rng, own_address, addresses)),
+    /// Checks whether the command has no deliberate errors.
+    pub fn is_valid(&self) -> bool {
+        match self {
+            Self::SingleTx(tx_command) => tx_command.modifier == IncorrectnessModifier::None,
+            Self::Batch(tx_commands) => tx_commands
+                .iter()
+                .all(|tx_command| tx_command.modifier == IncorrectnessModifier::None),
+            Self::ApiRequest(_) => true,
+        }
+    }
+
+    pub fn random(
+        rng: &mut LoadtestRng,
+        own_address: Address,
+        addresses: &AddressPool,
+        scenario: &Scenario,
+    ) -> Self {
+        match CommandType::random(rng, scenario) {
+            CommandType::SingleTx => {
+                Self::SingleTx(TxCommand::random(rng, own_address, addresses, scenario))
+            }
             CommandType::Batch => {
-                // TODO: For some reason, batches of size 1 are being rejected because of nonce mistmatch.
-                // It may be either bug in loadtest or server code, thus it should be investigated.
-                let batch_size = rng.gen_range(2..=MAX_BATCH_SIZE);
+                let chaos = &scenario.chaos;
+                // Don't touch the RNG if oversized batches are disabled, so that seeds of the previous runs
+                // produce the same sequence of operations.
+                let oversized = chaos.oversized_batch > 0.0
+                    && rng.gen_range(0.0f32..1.0f32) < chaos.oversized_batch;
+                let batch_size = if oversized {
+                    chaos.oversized_batch_size
+                } else {
+                    rng.gen_range(scenario.min_batch_size..=scenario.max_batch_size)
+                };
                 let mut batch_command: Vec<_> = (0..batch_size)
-                    .map(|_| TxCommand::random_batchable(rng, own_address, addresses))
+                    .map(|_| TxCommand::random_batchable(rng, own_address, addresses, scenario))
                     .collect();
 
-                if batch_command
+                let fee_modifier = batch_command
                     .iter()
-                    .any(|cmd| cmd.modifier == IncorrectnessModifier::ZeroFee)
-                {
+                    .map(|cmd| cmd.modifier)
+                    .find(|modifier| modifier.affects_fee());
+                if oversized {
+                    // The batch is rejected because of its size regardless of the transactions in it.
+                    for command in batch_command.iter_mut() {
+                        command.modifier = IncorrectnessModifier::OversizedBatch;
+                    }
+                } else if let Some(fee_modifier) = fee_modifier {
                     // Zero fee modifier is kinda weird for batches, since the summary fee may be enough to cover
                     // cost of one tx with zero fee. Thus in that case we set zero fee modifier to all the transactions.
                     // Note that behavior in the statement above is not a bug: to live in the volatile world of Ethereum,
                     // server may accept batches with the fee slightly below that what has been reported to user via API.
+                    // The same goes for the underpriced fee.
                     for command in batch_command.iter_mut() {
-                        command.modifier = IncorrectnessModifier::ZeroFee;
+                        command.modifier = fee_modifier;
                     }
                 }
 
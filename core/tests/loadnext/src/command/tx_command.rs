@@ -1,12 +1,13 @@
 use num::BigUint;
-use rand::Rng;
+use rand::{seq::SliceRandom, Rng};
 
 use zksync_types::Address;
 
 use crate::{
     account_pool::AddressPool,
-    all::{All, AllWeighted},
-    rng::{LoadtestRng, WeightedRandom},
+    all::All,
+    rng::LoadtestRng,
+    scenario::{ChaosConfig, Scenario, TxWeights},
 };
 
 /// Type of transaction. It doesn't copy the zkSync operation list, because
@@ -20,6 +21,10 @@ pub enum TxType {
     WithdrawToOther,
     FullExit,
     ChangePubKey,
+    MintNFT,
+    TransferNFT,
+    WithdrawNFT,
+    Swap,
 }
 
 impl All for TxType {
@@ -32,44 +37,56 @@ impl All for TxType {
             Self::WithdrawToOther,
             Self::FullExit,
             Self::ChangePubKey,
+            Self::MintNFT,
+            Self::TransferNFT,
+            Self::WithdrawNFT,
+            Self::Swap,
         ]
     }
 }
 
-impl AllWeighted for TxType {
-    fn all_weighted() -> &'static [(Self, f32)] {
-        // All available options together with their weight.
-        // `TransferToNew` and `TransferToExisting` the most likely options.
-        const DEFAULT_WEIGHT: f32 = 1.0;
-        const HIGH_WEIGHT: f32 = 3.0;
-        &[
-            (Self::Deposit, DEFAULT_WEIGHT),
-            (Self::TransferToNew, HIGH_WEIGHT),
-            (Self::TransferToExisting, HIGH_WEIGHT),
-            (Self::WithdrawToSelf, DEFAULT_WEIGHT),
-            (Self::WithdrawToOther, DEFAULT_WEIGHT),
-            (Self::FullExit, DEFAULT_WEIGHT),
-            (Self::ChangePubKey, DEFAULT_WEIGHT),
-        ]
+impl TxType {
+    /// Generates a random transaction type according to the scenario weights.
+    pub fn random(rng: &mut LoadtestRng, weights: &TxWeights) -> Self {
+        weights
+            .all_weighted()
+            .choose_weighted(rng, |item| item.1)
+            .expect("Scenario must have positive transaction weights")
+            .0
     }
-}
 
-impl TxType {
     /// Generates a random transaction type that can be a part of the batch.
-    pub fn random_batchable(rng: &mut LoadtestRng) -> Self {
-        loop {
-            let output = Self::random(rng);
-
-            // Priority ops cannot be inserted into the batch.
-            if output.is_batchable() {
-                return output;
-            }
-        }
+    pub fn random_batchable(rng: &mut LoadtestRng, weights: &TxWeights) -> Self {
+        // Priority ops cannot be inserted into the batch.
+        let batchable: Vec<_> = weights
+            .all_weighted()
+            .iter()
+            .copied()
+            .filter(|(tx_type, _)| tx_type.is_batchable())
+            .collect();
+
+        batchable
+            .choose_weighted(rng, |item| item.1)
+            .expect("Scenario must have positive batchable transaction weights")
+            .0
     }
 
     /// Checks whether `TxType` can be used as a part of the batch.
-    fn is_batchable(self) -> bool {
-        !matches!(self, Self::Deposit | Self::FullExit)
+    pub(crate) fn is_batchable(self) -> bool {
+        !self.is_priority() && !self.is_nft() && !self.is_swap()
+    }
+
+    /// Checks whether `TxType` pays the fee in the main token regardless of the command token.
+    fn pays_fee_in_main_token(self) -> bool {
+        self.is_change_pubkey() || self.is_nft()
+    }
+
+    fn is_nft(self) -> bool {
+        matches!(self, Self::MintNFT | Self::TransferNFT | Self::WithdrawNFT)
+    }
+
+    fn is_swap(self) -> bool {
+        matches!(self, Self::Swap)
     }
 
     fn is_withdrawal(self) -> bool {
@@ -85,7 +102,10 @@ impl TxType {
     }
 
     fn is_target_self(self) -> bool {
-        matches!(self, Self::WithdrawToSelf | Self::FullExit)
+        matches!(
+            self,
+            Self::WithdrawToSelf | Self::FullExit | Self::WithdrawNFT | Self::Swap
+        )
     }
 }
 
@@ -101,6 +121,13 @@ pub enum IncorrectnessModifier {
     TooBigAmount,
     NotPackableAmount,
     NotPackableFeeAmount,
+    /// Transaction uses the nonce that was already used by the account.
+    WrongNonce,
+    /// Transaction fee is below the required one, but is not zero.
+    UnderpricedFee,
+    /// Transaction is a part of the batch exceeding the server batch size limit.
+    /// Applied to all the transactions of the batch.
+    OversizedBatch,
 
     // Last option goes for no modifier,
     // since it's more convenient than dealing with `Option<IncorrectnessModifier>`.
@@ -118,6 +145,9 @@ impl IncorrectnessModifier {
             Self::TooBigAmount,
             Self::NotPackableAmount,
             Self::NotPackableFeeAmount,
+            Self::WrongNonce,
+            Self::UnderpricedFee,
+            Self::OversizedBatch,
             Self::None,
         ]
     }
@@ -129,28 +159,47 @@ impl All for IncorrectnessModifier {
     }
 }
 
-impl AllWeighted for IncorrectnessModifier {
-    fn all_weighted() -> &'static [(Self, f32)] {
-        const VARIANT_AMOUNTS: f32 = IncorrectnessModifier::const_all().len() as f32;
-        // No modifier is 9 times probable than all the other variants in sum.
-        // In other words, 90% probability of no modifier.
-        const NONE_PROBABILITY: f32 = (VARIANT_AMOUNTS - 1.0) * 9.0;
-        const DEFAULT_PROBABILITY: f32 = 1.0f32;
+impl IncorrectnessModifier {
+    /// Chance of every modifier that is not configured by the scenario chaos options.
+    /// All the modifiers together have 10% probability by default.
+    pub const DEFAULT_CHANCE: f32 = 1.0 / 70.0;
+
+    /// Generates a random modifier according to the scenario chaos options.
+    pub fn random(rng: &mut LoadtestRng, chaos: &ChaosConfig) -> Self {
+        Self::all_weighted(chaos)
+            .choose_weighted(rng, |item| item.1)
+            .unwrap()
+            .0
+    }
 
-        &[
-            (Self::ZeroFee, DEFAULT_PROBABILITY),
-            (Self::IncorrectZkSyncSignature, DEFAULT_PROBABILITY),
-            (Self::IncorrectEthSignature, DEFAULT_PROBABILITY),
-            (Self::NonExistentToken, DEFAULT_PROBABILITY),
-            (Self::TooBigAmount, DEFAULT_PROBABILITY),
-            (Self::NotPackableAmount, DEFAULT_PROBABILITY),
-            (Self::NotPackableFeeAmount, DEFAULT_PROBABILITY),
-            (Self::None, NONE_PROBABILITY),
-        ]
+    /// Returns all the modifiers applicable to a single transaction together with their chance.
+    pub fn all_weighted(chaos: &ChaosConfig) -> [(Self, f32); 10] {
+        let mut weighted = [
+            (Self::ZeroFee, Self::DEFAULT_CHANCE),
+            // Invalid signatures of both kinds are equally likely.
+            (
+                Self::IncorrectZkSyncSignature,
+                chaos.invalid_signature / 2.0,
+            ),
+            (Self::IncorrectEthSignature, chaos.invalid_signature / 2.0),
+            (Self::NonExistentToken, Self::DEFAULT_CHANCE),
+            (Self::TooBigAmount, Self::DEFAULT_CHANCE),
+            (Self::NotPackableAmount, Self::DEFAULT_CHANCE),
+            (Self::NotPackableFeeAmount, Self::DEFAULT_CHANCE),
+            (Self::WrongNonce, chaos.wrong_nonce),
+            (Self::UnderpricedFee, chaos.underpriced_fee),
+            (Self::None, 0.0),
+        ];
+        let modifiers_chance: f32 = weighted.iter().map(|item| item.1).sum();
+        weighted[weighted.len() - 1].1 = (1.0 - modifiers_chance).max(0.0);
+
+        weighted
+    }
+
+    pub(crate) fn affects_fee(self) -> bool {
+        matches!(self, Self::ZeroFee | Self::UnderpricedFee)
     }
-}
 
-impl IncorrectnessModifier {
     fn affects_amount(self) -> bool {
         matches!(self, Self::TooBigAmount | Self::NotPackableAmount)
     }
@@ -184,11 +233,27 @@ impl IncorrectnessModifier {
             | Self::IncorrectZkSyncSignature
             | Self::NonExistentToken
             | Self::NotPackableAmount
-            | Self::NotPackableFeeAmount => ExpectedOutcome::ApiRequestFailed,
+            | Self::NotPackableFeeAmount
+            | Self::WrongNonce
+            | Self::UnderpricedFee
+            | Self::OversizedBatch => ExpectedOutcome::ApiRequestFailed,
 
             Self::TooBigAmount => ExpectedOutcome::TxRejected,
         }
     }
+
+    /// Returns the code of the API error caused by the modifier, if it can be predicted.
+    /// For example, the incorrect Ethereum signature of `ChangePubKey` is reported differently
+    /// from the one of the other transactions, thus it isn't checked.
+    pub fn expected_error_code(self) -> Option<i64> {
+        match self {
+            Self::WrongNonce => Some(101),
+            Self::IncorrectZkSyncSignature => Some(103),
+            Self::ZeroFee | Self::UnderpricedFee => Some(104),
+            Self::OversizedBatch => Some(300),
+            _ => None,
+        }
+    }
 }
 
 /// Complete description of a transaction that must be executed by a test wallet.
@@ -202,23 +267,33 @@ pub struct TxCommand {
     pub to: Address,
     /// Transaction amount (0 if not applicable).
     pub amount: BigUint,
+    /// Symbol of the transaction token.
+    /// For `ChangePubKey` and NFT transactions it's the fee token, and for `FullExit` it's ignored.
+    /// For `Swap` it's the sold token, and the next scenario token is bought.
+    pub token: String,
 }
 
 impl TxCommand {
-    pub fn change_pubkey(address: Address) -> Self {
+    pub fn change_pubkey(address: Address, fee_token: &str) -> Self {
         Self {
             command_type: TxType::ChangePubKey,
             modifier: IncorrectnessModifier::None,
             to: address,
             amount: 0u64.into(),
+            token: fee_token.to_owned(),
         }
     }
 
     /// Generates a fully random transaction command.
-    pub fn random(rng: &mut LoadtestRng, own_address: Address, addresses: &AddressPool) -> Self {
-        let command_type = TxType::random(rng);
+    pub fn random(
+        rng: &mut LoadtestRng,
+        own_address: Address,
+        addresses: &AddressPool,
+        scenario: &Scenario,
+    ) -> Self {
+        let command_type = TxType::random(rng, &scenario.tx_weights);
 
-        Self::new_with_type(rng, own_address, addresses, command_type)
+        Self::new_with_type(rng, own_address, addresses, scenario, command_type)
     }
 
     /// Generates a random transaction command that can be a part of the batch.
@@ -226,25 +301,33 @@ impl TxCommand {
         rng: &mut LoadtestRng,
         own_address: Address,
         addresses: &AddressPool,
+        scenario: &Scenario,
     ) -> Self {
-        let command_type = TxType::random_batchable(rng);
+        let command_type = TxType::random_batchable(rng, &scenario.tx_weights);
 
-        Self::new_with_type(rng, own_address, addresses, command_type)
+        Self::new_with_type(rng, own_address, addresses, scenario, command_type)
     }
 
     fn new_with_type(
         rng: &mut LoadtestRng,
         own_address: Address,
         addresses: &AddressPool,
+        scenario: &Scenario,
         command_type: TxType,
     ) -> Self {
         let mut command = Self {
             command_type,
-            modifier: IncorrectnessModifier::random(rng),
+            modifier: IncorrectnessModifier::random(rng, &scenario.chaos),
             to: addresses.random_address(rng),
             amount: Self::random_amount(rng),
+            token: scenario.random_token(rng).to_owned(),
         };
 
+        // Fee of `ChangePubKey` and NFT transactions is always paid in the main token.
+        if command.command_type.pays_fee_in_main_token() {
+            command.token = scenario.main_token().to_owned();
+        }
+
         // Check whether we should use a non-existent address.
         if matches!(command.command_type, TxType::TransferToNew) {
             command.to = Address::random();
@@ -260,12 +343,14 @@ impl TxCommand {
             command.command_type.is_change_pubkey() && command.modifier.affects_amount();
         // It doesn't make sense to fail contract-based functions.
         let incorrect_priority_op = command.command_type.is_priority();
+        // NFT transactions and swaps are meant to measure the cost of the valid operations.
+        let incorrect_new_op = command.command_type.is_nft() || command.command_type.is_swap();
         // Amount doesn't have to be packable for withdrawals.
         let unpackable_withdrawal =
             command.command_type.is_withdrawal() && command.modifier.is_not_packable_amount();
 
         // Check whether generator modifier does not make sense.
-        if no_amount_field || incorrect_priority_op || unpackable_withdrawal {
+        if no_amount_field || incorrect_priority_op || incorrect_new_op || unpackable_withdrawal {
             command.modifier = IncorrectnessModifier::None;
         }
 
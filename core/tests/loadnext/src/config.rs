@@ -1,13 +1,16 @@
 use serde::Deserialize;
 
+use crate::scenario::Scenario;
+
 /// Configuration for the loadtest.
 ///
 /// This structure is meant to provide the least possible amount of parameters:
-/// By the ideology of the test, it is OK for it to be opinionated. Thus we don't provide
-/// kinds of operations we want to perform, do not configure fail or pass criteria.
+/// By the ideology of the test, it is OK for it to be opinionated. Thus we don't configure
+/// fail or pass criteria.
 ///
 /// It is expected that the user will provide the basic settings, and the loadtest will
-/// take care of everything else.
+/// take care of everything else. The shape of the load can be tuned via the scenario file,
+/// see `Scenario` for details.
 #[derive(Debug, Clone, Deserialize)]
 pub struct LoadtestConfig {
     /// Address of the zkSync node.
@@ -45,12 +48,27 @@ pub struct LoadtestConfig {
     pub seed: Option<String>,
     /// Allowed percent of failed transactions
     pub allowed_percent: u8,
+
+    /// Optional path to the TOML file with the test scenario.
+    /// If set, the scenario takes precedence over `accounts_amount`, `operations_per_account` and `main_token`.
+    pub scenario_path: Option<String>,
+    /// Optional path to write the JSON summary of the test results to, e.g. to compare the performance
+    /// between the revisions on CI.
+    pub report_path: Option<String>,
 }
 
 impl LoadtestConfig {
     pub fn from_env() -> envy::Result<Self> {
         envy::from_env()
     }
+
+    /// Returns the scenario of the test: either loaded from the file or the default one.
+    pub fn scenario(&self) -> anyhow::Result<Scenario> {
+        match &self.scenario_path {
+            Some(path) => Scenario::load(path),
+            None => Ok(Scenario::from_config(self)),
+        }
+    }
 }
 
 impl Default for LoadtestConfig {
@@ -69,6 +87,8 @@ impl Default for LoadtestConfig {
             main_token: "DAI".into(),
             seed: None,
             allowed_percent: 10,
+            scenario_path: None,
+            report_path: None,
         }
     }
 }
@@ -7,6 +7,8 @@ pub const ETH_CONFIRMATION_TIMEOUT: Duration = Duration::from_secs(300);
 /// but nonetheless we want to provide some buffer in case we'll spam the server with way too many transactions
 /// and some tx will have to wait in the mempool for a while.
 pub const COMMIT_TIMEOUT: Duration = Duration::from_secs(600);
+/// Verification requires the block proof to be generated, which is much slower than the commitment.
+pub const VERIFY_TIMEOUT: Duration = Duration::from_secs(3600);
 /// We don't want to overload the server with too many requests; given the fact that blocks are expected to be created
 /// every couple of seconds, chosen value seems to be adequate to provide the result in one or two calls at average.
 pub const POLLING_INTERVAL: Duration = Duration::from_secs(3);
@@ -1,7 +1,7 @@
 use num::BigUint;
 use zksync::utils::{
-    closest_packable_token_amount, is_fee_amount_packable, is_token_amount_packable,
-    private_key_from_seed,
+    closest_packable_fee_amount, closest_packable_token_amount, is_fee_amount_packable,
+    is_token_amount_packable, private_key_from_seed,
 };
 use zksync_types::{
     tx::{ChangePubKeyECDSAData, ChangePubKeyEthAuthData, PackedEthSignature, TxSignature},
@@ -34,6 +34,8 @@ pub trait Corrupted: Sized {
     fn too_big_amount(self, eth_pk: H256, token_symbol: &str, decimals: u8) -> Self;
     /// Creates a transaction without fee provided.
     fn zero_fee(self, eth_pk: H256, token_symbol: &str, decimals: u8) -> Self;
+    /// Creates a transaction with a third of the fee provided, which is below the fee tolerance of the server.
+    fn underpriced_fee(self, eth_pk: H256, token_symbol: &str, decimals: u8) -> Self;
 
     /// Resigns the transaction after the modification in order to make signatures correct (if applicable).
     fn resign(&mut self, eth_pk: H256, token_symbol: &str, decimals: u8);
@@ -65,6 +67,11 @@ pub trait Corrupted: Sized {
                 self.too_big_amount(eth_pk, token_symbol, decimals)
             }
             IncorrectnessModifier::ZeroFee => self.zero_fee(eth_pk, token_symbol, decimals),
+            IncorrectnessModifier::UnderpricedFee => {
+                self.underpriced_fee(eth_pk, token_symbol, decimals)
+            }
+            // These modifiers are applied during the transaction creation.
+            IncorrectnessModifier::WrongNonce | IncorrectnessModifier::OversizedBatch => self,
         }
     }
 }
@@ -317,6 +324,37 @@ impl Corrupted for (ZkSyncTx, Option<PackedEthSignature>) {
 
         self
     }
+
+    fn underpriced_fee(mut self, eth_pk: H256, token_symbol: &str, decimals: u8) -> Self {
+        let underpriced = |fee: &BigUint| closest_packable_fee_amount(&(fee / 3u64));
+        match &mut self.0 {
+            ZkSyncTx::ChangePubKey(tx) => {
+                tx.fee = underpriced(&tx.fee);
+            }
+            ZkSyncTx::ForcedExit(tx) => {
+                tx.fee = underpriced(&tx.fee);
+            }
+            ZkSyncTx::Transfer(tx) => {
+                tx.fee = underpriced(&tx.fee);
+            }
+            ZkSyncTx::Withdraw(tx) => {
+                tx.fee = underpriced(&tx.fee);
+            }
+            ZkSyncTx::Swap(tx) => {
+                tx.fee = underpriced(&tx.fee);
+            }
+            ZkSyncTx::Close(_tx) => unreachable!(),
+            ZkSyncTx::MintNFT(tx) => {
+                tx.fee = underpriced(&tx.fee);
+            }
+            ZkSyncTx::WithdrawNFT(tx) => {
+                tx.fee = underpriced(&tx.fee);
+            }
+        }
+        self.resign(eth_pk, token_symbol, decimals);
+
+        self
+    }
 }
 
 #[cfg(test)]
@@ -374,6 +412,18 @@ mod tests {
         assert_eq!(unwrap_transfer(modified_transfer).fee, 0u64.into());
     }
 
+    #[test]
+    fn underpriced_fee() {
+        let account = create_account();
+
+        let transfer = create_transfer(&account);
+
+        let (modified_transfer, _eth_signature) =
+            transfer.underpriced_fee(account.eth_account_data.unwrap_eoa_pk(), "ETH", 18);
+
+        assert_eq!(unwrap_transfer(modified_transfer).fee, (FEE / 3).into());
+    }
+
     #[test]
     fn too_big_amount() {
         let account = create_account();
@@ -9,7 +9,7 @@ use zksync_types::{tx::TxHash, TransactionReceipt, TxFeeTypes, U256};
 
 use crate::{
     account::AccountLifespan, account_pool::AccountPool, config::LoadtestConfig,
-    report_collector::LoadtestResult,
+    market_maker::MarketMaker, report_collector::LoadtestResult, scenario::Scenario,
 };
 use crate::{constants::*, report_collector::ReportCollector};
 
@@ -17,8 +17,9 @@ use crate::{constants::*, report_collector::ReportCollector};
 ///
 /// It takes care of the following topics:
 ///
-/// - Minting the tokens on L1 for the main account.
+/// - Minting the scenario tokens on L1 for the main account.
 /// - Depositing tokens to the main account in L2 and unlocking it.
+/// - Preparing the market maker account for the swaps (if the scenario has them).
 /// - Spawning the report collector.
 /// - Distributing the funds among the test wallets.
 /// - Spawning account lifespan futures.
@@ -27,15 +28,20 @@ use crate::{constants::*, report_collector::ReportCollector};
 #[derive(Debug)]
 pub struct Executor {
     config: LoadtestConfig,
+    scenario: Scenario,
     pool: AccountPool,
 }
 
 impl Executor {
     /// Creates a new Executor entity.
-    pub async fn new(config: LoadtestConfig) -> anyhow::Result<Self> {
-        let pool = AccountPool::new(&config).await?;
+    pub async fn new(config: LoadtestConfig, scenario: Scenario) -> anyhow::Result<Self> {
+        let pool = AccountPool::new(&config, &scenario).await?;
 
-        Ok(Self { config, pool })
+        Ok(Self {
+            config,
+            scenario,
+            pool,
+        })
     }
 
     /// Runs the loadtest until the completion.
@@ -54,7 +60,8 @@ impl Executor {
         self.mint().await?;
         self.deposit_to_master().await?;
         self.set_signing_key().await?;
-        let (executor_future, account_futures) = self.send_initial_transfers().await?;
+        let market_maker = self.prepare_market_maker().await?;
+        let (executor_future, account_futures) = self.send_initial_transfers(market_maker).await?;
         self.wait_account_routines(account_futures).await;
 
         let final_resultion = executor_future.await.unwrap_or(LoadtestResult::TestFailed);
@@ -80,17 +87,24 @@ impl Executor {
         Ok(())
     }
 
-    /// Mints the ERC-20 token on the main wallet.
+    /// Mints the ERC-20 tokens on the main wallet.
     async fn mint(&mut self) -> anyhow::Result<()> {
-        vlog::info!("Master Account: Minting ERC20 token...");
+        for token in &self.scenario.tokens {
+            self.mint_token(token).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Mints the ERC-20 token on the main wallet.
+    async fn mint_token(&self, token: &str) -> anyhow::Result<()> {
+        vlog::info!("Master Account: Minting ERC20 token {}...", token);
         let deposit_amount = self.amount_to_deposit();
 
         let master_wallet = &self.pool.master_wallet;
         let mut ethereum = master_wallet.ethereum(&self.config.web3_url).await?;
         ethereum.set_confirmation_timeout(ETH_CONFIRMATION_TIMEOUT);
 
-        let token = self.config.main_token.as_str();
-
         let balance = ethereum
             .erc20_balance(master_wallet.address(), token)
             .await?;
@@ -118,9 +132,30 @@ impl Executor {
         Ok(())
     }
 
-    /// Deposits the ERC-20 token to main wallet in L2.
+    /// Deposits the ERC-20 tokens to main wallet in L2.
     async fn deposit_to_master(&mut self) -> anyhow::Result<()> {
-        vlog::info!("Master Account: Performing a deposit to master");
+        for token in &self.scenario.tokens {
+            self.deposit_token_to_master(token).await?;
+        }
+
+        // After deposits are committed, we have to update the account ID in the wallet
+        // (in case we didn't have one).
+        self.pool.master_wallet.update_account_id().await?;
+        assert!(
+            self.pool.master_wallet.account_id().is_some(),
+            "Account ID for master account was not set",
+        );
+
+        vlog::info!("Master Account: Deposit is OK");
+        Ok(())
+    }
+
+    /// Deposits the ERC-20 token to main wallet in L2.
+    async fn deposit_token_to_master(&self, token: &str) -> anyhow::Result<()> {
+        vlog::info!(
+            "Master Account: Performing a deposit of {} to master",
+            token
+        );
         let deposit_amount = self.amount_to_deposit();
         let mut ethereum = self
             .pool
@@ -130,8 +165,7 @@ impl Executor {
         ethereum.set_confirmation_timeout(ETH_CONFIRMATION_TIMEOUT);
 
         // Approve ERC20 deposits.
-        let main_token = self.config.main_token.as_str();
-        let approve_tx_hash = ethereum.approve_erc20_token_deposits(main_token).await?;
+        let approve_tx_hash = ethereum.approve_erc20_token_deposits(token).await?;
         let receipt = ethereum.wait_for_tx(approve_tx_hash).await?;
         self.assert_eth_tx_success(&receipt).await;
 
@@ -140,7 +174,7 @@ impl Executor {
         // Perform the deposit itself.
         let deposit_tx_hash = ethereum
             .deposit(
-                main_token,
+                token,
                 U256::from(deposit_amount),
                 self.pool.master_wallet.address(),
             )
@@ -166,15 +200,6 @@ impl Executor {
             .wait_for_commit()
             .await?;
 
-        // After deposit is committed, we have to update the account ID in the wallet
-        // (in case we didn't have one).
-        self.pool.master_wallet.update_account_id().await?;
-        assert!(
-            self.pool.master_wallet.account_id().is_some(),
-            "Account ID for master account was not set",
-        );
-
-        vlog::info!("Master Account: Deposit is OK");
         Ok(())
     }
 
@@ -185,7 +210,7 @@ impl Executor {
             .pool
             .master_wallet
             .start_change_pubkey()
-            .fee_token(self.config.main_token.as_str())
+            .fee_token(self.scenario.main_token())
             .unwrap()
             .send()
             .await?;
@@ -204,14 +229,78 @@ impl Executor {
         Ok(())
     }
 
+    /// Funds and unlocks the market maker account, and signs its limit orders for every pair of
+    /// the scenario tokens. Returns no orders if the scenario has no swaps.
+    async fn prepare_market_maker(&mut self) -> anyhow::Result<MarketMaker> {
+        let mut market_maker = match self.pool.market_maker.take() {
+            Some(market_maker) => market_maker.wallet,
+            None => return Ok(MarketMaker::default()),
+        };
+
+        vlog::info!("Market Maker: Receiving the funds");
+        let transfer_amount = self.transfer_amount();
+        for token in &self.scenario.tokens {
+            let handle = self
+                .pool
+                .master_wallet
+                .start_transfer()
+                .to(market_maker.address())
+                .amount(transfer_amount)
+                .token(token.as_str())?
+                .send()
+                .await?;
+            let tx_result = self.wait_for_sync_tx(handle.hash()).await?;
+            anyhow::ensure!(
+                tx_result.fail_reason.is_none(),
+                "Unable to transfer {} to the market maker",
+                token
+            );
+        }
+
+        vlog::info!("Market Maker: Setting the signing key");
+        market_maker.update_account_id().await?;
+        let handle = market_maker
+            .start_change_pubkey()
+            .fee_token(self.scenario.main_token())?
+            .send()
+            .await?;
+        let tx_result = self.wait_for_sync_tx(handle.hash()).await?;
+        anyhow::ensure!(
+            tx_result.fail_reason.is_none(),
+            "Unable to set signing key on the market maker"
+        );
+
+        // Limit orders exchange tokens in the 1:1 ratio, since the actual price doesn't matter for the test.
+        let mut orders = Vec::new();
+        for token_sell in &self.scenario.tokens {
+            for token_buy in self
+                .scenario
+                .tokens
+                .iter()
+                .filter(|token| *token != token_sell)
+            {
+                let order = market_maker
+                    .start_order()
+                    .token_sell(token_sell.as_str())?
+                    .token_buy(token_buy.as_str())?
+                    .ratio(1u64, 1u64)
+                    .sign()
+                    .await?;
+                orders.push(order);
+            }
+        }
+
+        vlog::info!("Market Maker: Signed {} limit orders", orders.len());
+        Ok(MarketMaker::new(orders))
+    }
+
     async fn send_initial_transfers_batch(
         &self,
         accounts_to_process: usize,
     ) -> anyhow::Result<TxHash> {
         let eth_to_distribute = self.eth_amount_to_distribute().await?;
         let master_wallet = &self.pool.master_wallet;
-        let config = &self.config;
-        let token = &config.main_token;
+        let main_token = self.scenario.main_token();
 
         let transfer_amount = self.transfer_amount();
 
@@ -223,8 +312,8 @@ impl Executor {
         // We request nonce each time, so that if one iteration was failed, it will be repeated on the next iteration.
         let mut nonce = master_wallet.account_info().await?.committed.nonce;
 
-        // 1 tx per account + 1 fee tx.
-        let batch_txs_amount = accounts_to_process + 1;
+        // 1 tx per account per token + 1 fee tx.
+        let batch_txs_amount = accounts_to_process * self.scenario.tokens.len() + 1;
         let mut batch = Vec::with_capacity(batch_txs_amount);
         let mut batch_fee_types = Vec::with_capacity(batch_txs_amount);
         let mut batch_addresses = Vec::with_capacity(batch_txs_amount);
@@ -240,23 +329,25 @@ impl Executor {
                 .transfer("ETH", eth_to_distribute, target_address)
                 .await;
 
-            // And then we will prepare an L2 transaction.
-            let (tx, signature) = master_wallet
-                .start_transfer()
-                .to(target_address)
-                .amount(transfer_amount)
-                .token(token.as_str())?
-                .fee(0u64)
-                .nonce(nonce)
-                .tx()
-                .await?;
-
-            let fee_type = tx.get_fee_info().unwrap().0;
-            batch_fee_types.push(fee_type);
-            batch_addresses.push(target_address);
-            batch.push((tx, signature));
-
-            *nonce += 1;
+            // And then we will prepare L2 transactions.
+            for token in &self.scenario.tokens {
+                let (tx, signature) = master_wallet
+                    .start_transfer()
+                    .to(target_address)
+                    .amount(transfer_amount)
+                    .token(token.as_str())?
+                    .fee(0u64)
+                    .nonce(nonce)
+                    .tx()
+                    .await?;
+
+                let fee_type = tx.get_fee_info().unwrap().0;
+                batch_fee_types.push(fee_type);
+                batch_addresses.push(target_address);
+                batch.push((tx, signature));
+
+                *nonce += 1;
+            }
         }
 
         // Add mock transfer that contains the fee.
@@ -266,7 +357,7 @@ impl Executor {
         // Request fee for the batch.
         let batch_fee = master_wallet
             .provider
-            .get_txs_batch_fee(batch_fee_types, batch_addresses, token.as_str())
+            .get_txs_batch_fee(batch_fee_types, batch_addresses, main_token)
             .await?;
 
         // Add the fee transaction to the batch.
@@ -274,7 +365,7 @@ impl Executor {
             .start_transfer()
             .to(master_wallet.address())
             .amount(0u64)
-            .token(token.as_str())?
+            .token(main_token)?
             .fee(batch_fee)
             .nonce(nonce)
             .tx()
@@ -292,7 +383,8 @@ impl Executor {
 
     /// Returns the amount sufficient for wallets to perform many operations.
     fn transfer_amount(&self) -> u128 {
-        let accounts_amount = self.config.accounts_amount;
+        // The market maker (if any) receives the same funds as the test accounts.
+        let accounts_amount = self.scenario.accounts_amount + self.scenario.has_swaps() as usize;
         let account_balance = self.amount_to_deposit();
         let for_fees = u64::max_value() >> 24; // Leave some spare funds on the master account for fees.
         let funds_to_distribute = account_balance - u128::from(for_fees);
@@ -314,12 +406,13 @@ impl Executor {
     /// Initializes the loadtest by doing the following:
     ///
     /// - Spawning the `ReportCollector`.
-    /// - Distributing ERC-20 token in L2 among test wallets via `Transfer` operation.
+    /// - Distributing ERC-20 tokens in L2 among test wallets via `Transfer` operation.
     /// - Distributing ETH in L1 among test wallets in order to make them able to perform priority operations.
     /// - Spawning test account routine futures.
     /// - Collecting all the spawned tasks and returning them to the caller.
     async fn send_initial_transfers(
         &mut self,
+        market_maker: MarketMaker,
     ) -> anyhow::Result<(JoinHandle<LoadtestResult>, Vec<JoinHandle<()>>)> {
         vlog::info!("Master Account: Sending initial transfers");
         // How many times we will resend a batch.
@@ -328,11 +421,20 @@ impl Executor {
         // Prepare channels for the report collector.
         let (report_sender, report_receiver) = mpsc::channel(256);
 
-        let report_collector = ReportCollector::new(report_receiver, self.config.allowed_percent);
+        let report_collector = ReportCollector::new(
+            report_receiver,
+            self.config.allowed_percent,
+            self.scenario.chaos.min_valid_tps,
+            self.config.report_path.clone().map(Into::into),
+            self.scenario.soak.clone(),
+        );
         let report_collector_future = tokio::spawn(report_collector.run());
 
         let config = &self.config;
-        let accounts_amount = config.accounts_amount;
+        let scenario = &self.scenario;
+        let accounts_amount = scenario.accounts_amount;
+        // Every account receives a transfer of each token, and the batch has to fit into the limit.
+        let accounts_per_batch = std::cmp::max(1, MAX_BATCH_SIZE / scenario.tokens.len());
         let addresses = self.pool.addresses.clone();
 
         let mut retry_counter = 0;
@@ -345,7 +447,7 @@ impl Executor {
             }
 
             let accounts_left = accounts_amount - accounts_processed;
-            let accounts_to_process = std::cmp::min(accounts_left, MAX_BATCH_SIZE);
+            let accounts_to_process = std::cmp::min(accounts_left, accounts_per_batch);
 
             let batch_tx_hash = match self.send_initial_transfers_batch(accounts_to_process).await {
                 Ok(hash) => hash,
@@ -395,8 +497,10 @@ impl Executor {
                     .map(|wallet| {
                         let account = AccountLifespan::new(
                             config,
+                            scenario,
                             addresses.clone(),
                             wallet,
+                            market_maker.clone(),
                             report_sender.clone(),
                         );
                         tokio::spawn(account.run())
@@ -432,7 +536,7 @@ impl Executor {
 
         // Amount of priority operations expected to be made by account.
         // We assume that 10% of operations made by account will be priority operations.
-        let priority_ops_per_account = self.config.operations_per_account / 10;
+        let priority_ops_per_account = self.scenario.estimated_operations_per_account() / 10;
 
         Ok(average_gas_price * gas_per_priority_op * priority_ops_per_account)
     }
@@ -19,7 +19,10 @@ async fn main() -> anyhow::Result<()> {
         LoadtestConfig::default()
     });
 
-    let mut executor = Executor::new(config).await?;
+    let scenario = config.scenario()?;
+    vlog::info!("Using the scenario: {:?}", scenario);
+
+    let mut executor = Executor::new(config, scenario).await?;
     let final_resolution = executor.start().await;
 
     match final_resolution {
@@ -0,0 +1,31 @@
+use std::sync::Arc;
+
+use zksync::operations::SignedOrder;
+use zksync_types::TokenId;
+
+/// Limit orders of the market maker account, used as a counterparty for the swaps of the test accounts.
+///
+/// Swap requires two matching orders signed by different accounts, while every test account only knows its
+/// own private key. Thus a dedicated account signs a limit order for every pair of the scenario tokens
+/// before the test, and the test accounts match their own orders against them.
+/// Limit orders don't change the nonce of their owner, and the market maker doesn't send any transactions
+/// during the test, so its orders remain valid for the whole test.
+#[derive(Debug, Clone, Default)]
+pub struct MarketMaker {
+    orders: Arc<Vec<SignedOrder>>,
+}
+
+impl MarketMaker {
+    pub fn new(orders: Vec<SignedOrder>) -> Self {
+        Self {
+            orders: Arc::new(orders),
+        }
+    }
+
+    /// Returns the order that can be matched with an order selling `token_sell` for `token_buy`.
+    pub fn counter_order(&self, token_sell: TokenId, token_buy: TokenId) -> Option<&SignedOrder> {
+        self.orders.iter().find(|signed_order| {
+            signed_order.order.token_sell == token_buy && signed_order.order.token_buy == token_sell
+        })
+    }
+}
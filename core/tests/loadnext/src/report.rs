@@ -27,6 +27,12 @@ pub struct Report {
     pub retries: usize,
     /// Duration of the latest execution attempt.
     pub time: Duration,
+    /// Whether the action had no deliberate errors.
+    pub valid: bool,
+    /// Durations of the separate stages of the latest execution attempt.
+    pub timings: OperationTimings,
+    /// Code of the API error, if the action was rejected by the server.
+    pub error_code: Option<i64>,
 }
 
 /// Builder structure for `Report`.
@@ -50,6 +56,9 @@ impl ReportBuilder {
                 action: ActionType::Tx(TxActionType::Transfer),
                 retries: 0,
                 time: Default::default(),
+                valid: true,
+                timings: Default::default(),
+                error_code: None,
             },
         }
     }
@@ -79,6 +88,21 @@ impl ReportBuilder {
         self
     }
 
+    pub fn valid(mut self, valid: bool) -> Self {
+        self.report.valid = valid;
+        self
+    }
+
+    pub fn timings(mut self, timings: OperationTimings) -> Self {
+        self.report.timings = timings;
+        self
+    }
+
+    pub fn error_code(mut self, error_code: Option<i64>) -> Self {
+        self.report.error_code = error_code;
+        self
+    }
+
     pub fn finish(self) -> Report {
         self.report
     }
@@ -110,6 +134,48 @@ impl ReportLabel {
     }
 }
 
+/// Durations of the separate stages of the operation processing.
+/// Stages that weren't reached (or weren't waited for) are not set.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct OperationTimings {
+    /// Time it took the server to accept the operation.
+    pub submission: Option<Duration>,
+    /// Time between the submission and the commitment of the operation.
+    pub commit: Option<Duration>,
+    /// Time between the submission and the verification of the operation.
+    pub verify: Option<Duration>,
+}
+
+/// Outcome of the performed action along with the details obtained during its execution.
+#[derive(Debug, Clone)]
+pub struct ActionOutcome {
+    pub label: ReportLabel,
+    pub timings: OperationTimings,
+    /// Code of the API error, if the action was rejected by the server.
+    pub error_code: Option<i64>,
+}
+
+impl ActionOutcome {
+    pub fn new(label: ReportLabel, timings: OperationTimings) -> Self {
+        Self {
+            label,
+            timings,
+            error_code: None,
+        }
+    }
+
+    pub fn with_error_code(mut self, error_code: Option<i64>) -> Self {
+        self.error_code = error_code;
+        self
+    }
+}
+
+impl From<ReportLabel> for ActionOutcome {
+    fn from(label: ReportLabel) -> Self {
+        Self::new(label, OperationTimings::default())
+    }
+}
+
 /// Denotes the type of executed transaction.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum TxActionType {
@@ -119,6 +185,10 @@ pub enum TxActionType {
     ChangePubKey,
     FullExit,
     Deposit,
+    MintNFT,
+    TransferNFT,
+    WithdrawNFT,
+    Swap,
 }
 
 impl All for TxActionType {
@@ -130,6 +200,10 @@ impl All for TxActionType {
             TxActionType::ChangePubKey,
             TxActionType::FullExit,
             TxActionType::Deposit,
+            TxActionType::MintNFT,
+            TxActionType::TransferNFT,
+            TxActionType::WithdrawNFT,
+            TxActionType::Swap,
         ];
 
         ALL
@@ -144,6 +218,10 @@ impl From<TxType> for TxActionType {
             TxType::WithdrawToSelf | TxType::WithdrawToOther => Self::Withdraw,
             TxType::FullExit => Self::FullExit,
             TxType::ChangePubKey => Self::ChangePubKey,
+            TxType::MintNFT => Self::MintNFT,
+            TxType::TransferNFT => Self::TransferNFT,
+            TxType::WithdrawNFT => Self::WithdrawNFT,
+            TxType::Swap => Self::Swap,
         }
     }
 }
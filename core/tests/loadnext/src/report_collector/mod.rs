@@ -1,13 +1,26 @@
+use std::path::PathBuf;
+
 use futures::{channel::mpsc::Receiver, StreamExt};
 use operation_results_collector::OperationResultsCollector;
 
 use crate::{
     report::{Report, ReportLabel},
-    report_collector::metrics_collector::MetricsCollector,
+    report_collector::{
+        metrics_collector::MetricsCollector,
+        resources_collector::{ResourcesCollector, ResourcesSampler},
+        summary::{LoadtestSummary, OperationsSummary},
+        throughput_collector::ThroughputCollector,
+        timings_collector::TimingsCollector,
+    },
+    scenario::SoakConfig,
 };
 
 mod metrics_collector;
 mod operation_results_collector;
+mod resources_collector;
+mod summary;
+mod throughput_collector;
+mod timings_collector;
 
 /// Decision on whether loadtest considered passed or failed.
 #[derive(Debug, Clone, Copy)]
@@ -25,8 +38,14 @@ pub enum LoadtestResult {
 /// Currently, only the following collectors are used:
 ///
 /// - MetricsCollector, which builds time distribution histograms for each kind of performed action.
-/// - OperationResultsCollector, a primitive collector that counts the amount of failures and decides whether
-///   test is passed.
+/// - OperationResultsCollector, a primitive collector that counts the amount of failures and API errors, and
+///   decides whether test is passed.
+/// - ThroughputCollector, which measures the throughput of the successful operations (both all and valid ones).
+/// - TimingsCollector, which calculates the percentiles of the submission, commitment and verification times.
+/// - ResourcesCollector, which samples the server metrics in the soak test and checks them for the drift.
+///   Since sampling requires HTTP requests, it's run by the separate actor (`ResourcesSampler`).
+///
+/// If the summary path is set, the results of all the collectors are also written there as a JSON.
 ///
 /// Other possible collectors that can be implemented:
 ///
@@ -44,23 +63,43 @@ pub enum LoadtestResult {
 #[derive(Debug)]
 pub struct ReportCollector {
     allowed_percent: u8,
+    min_valid_tps: Option<f64>,
+    summary_path: Option<PathBuf>,
+    soak: Option<SoakConfig>,
     reports_stream: Receiver<Report>,
     metrics_collector: MetricsCollector,
     operations_results_collector: OperationResultsCollector,
+    throughput_collector: ThroughputCollector,
+    timings_collector: TimingsCollector,
+    resources_collector: Option<ResourcesCollector>,
 }
 
 impl ReportCollector {
-    pub fn new(reports_stream: Receiver<Report>, allowed_percent: u8) -> Self {
+    pub fn new(
+        reports_stream: Receiver<Report>,
+        allowed_percent: u8,
+        min_valid_tps: Option<f64>,
+        summary_path: Option<PathBuf>,
+        soak: Option<SoakConfig>,
+    ) -> Self {
         assert!(allowed_percent < 100, "Allowed percent more than 100");
         Self {
             allowed_percent,
+            min_valid_tps,
+            summary_path,
+            soak,
             reports_stream,
             metrics_collector: MetricsCollector::new(),
             operations_results_collector: OperationResultsCollector::new(),
+            throughput_collector: ThroughputCollector::new(),
+            timings_collector: TimingsCollector::new(),
+            resources_collector: None,
         }
     }
 
     pub async fn run(mut self) -> LoadtestResult {
+        let resources_sampler = self.soak.clone().map(ResourcesSampler::start);
+
         while let Some(report) = self.reports_stream.next().await {
             vlog::trace!("Report: {:?}", &report);
 
@@ -70,7 +109,9 @@ impl ReportCollector {
                     .add_metric(report.action, report.time);
             }
 
-            self.operations_results_collector.add_status(&report.label);
+            self.operations_results_collector.add_report(&report);
+            self.throughput_collector.add_report(&report);
+            self.timings_collector.add_report(&report);
 
             // Report failure, if it exists.
             if let ReportLabel::ActionFailed { error } = &report.label {
@@ -79,11 +120,60 @@ impl ReportCollector {
         }
 
         // All the receivers are gone, it's likely the end of the test.
+        if let Some(sampler) = resources_sampler {
+            match sampler.stop().await {
+                Ok(collector) => self.resources_collector = Some(collector),
+                Err(err) => vlog::error!("Resources sampler failed: {}", err),
+            }
+        }
+
         // Now we can output the statistics.
         self.metrics_collector.report();
         self.operations_results_collector.report();
+        self.throughput_collector.report();
+        self.timings_collector.report();
+        if let Some(collector) = &self.resources_collector {
+            collector.report();
+        }
+
+        let result = self.final_resolution();
+        if let Some(path) = self.summary_path.clone() {
+            let summary = self.summary(result);
+            match summary.write(path) {
+                Ok(()) => vlog::info!("Loadtest summary is written to {:?}", path),
+                Err(err) => vlog::error!(
+                    "Unable to write the loadtest summary to {:?}: {}",
+                    path,
+                    err
+                ),
+            }
+        }
 
-        self.final_resolution()
+        result
+    }
+
+    fn summary(&mut self, result: LoadtestResult) -> LoadtestSummary {
+        let operations = &self.operations_results_collector;
+        LoadtestSummary {
+            passed: matches!(result, LoadtestResult::TestPassed),
+            duration_secs: self.throughput_collector.elapsed().as_secs_f64(),
+            operations: OperationsSummary {
+                successes: operations.successes(),
+                skipped: operations.skipped(),
+                failures: operations.failures(),
+                total: operations.total(),
+            },
+            tps: self.throughput_collector.tps(),
+            valid_tps: self.throughput_collector.valid_tps(),
+            submission_latency: self.timings_collector.submission(),
+            time_to_commit: self.timings_collector.commit(),
+            time_to_verify: self.timings_collector.verify(),
+            errors_by_code: operations.errors_by_code().clone(),
+            resources: self
+                .resources_collector
+                .as_ref()
+                .map(ResourcesCollector::drifts),
+        }
     }
 
     fn final_resolution(&self) -> LoadtestResult {
@@ -91,9 +181,29 @@ impl ReportCollector {
             / self.operations_results_collector.total() as f64)
             * 100.0;
         if failure_percent > self.allowed_percent as f64 {
-            LoadtestResult::TestFailed
-        } else {
-            LoadtestResult::TestPassed
+            return LoadtestResult::TestFailed;
+        }
+
+        if self.soak.is_some() {
+            let passed = self
+                .resources_collector
+                .as_ref()
+                .map_or(false, ResourcesCollector::passed);
+            if !passed {
+                vlog::error!("Drift of the server resources exceeds the configured thresholds");
+                return LoadtestResult::TestFailed;
+            }
+        }
+
+        match self.min_valid_tps {
+            Some(min_valid_tps) if self.throughput_collector.valid_tps() < min_valid_tps => {
+                vlog::error!(
+                    "Throughput of the valid operations is below the expected {:.2} per second",
+                    min_valid_tps
+                );
+                LoadtestResult::TestFailed
+            }
+            _ => LoadtestResult::TestPassed,
         }
     }
 }
@@ -1,12 +1,16 @@
-use crate::report::ReportLabel;
+use std::collections::BTreeMap;
+
+use crate::report::{Report, ReportLabel};
 
 /// Collector that analyzes the outcomes of the performed operations.
-/// Currently it's solely capable of deciding whether test was failed or not.
+/// It's capable of deciding whether test was failed or not, and counts the API errors by their codes.
 #[derive(Debug, Clone, Default)]
 pub struct OperationResultsCollector {
     successes: u64,
     skipped: u64,
     failures: u64,
+    /// Amount of the API errors per error code, including the expected ones.
+    errors_by_code: BTreeMap<i64, u64>,
 }
 
 impl OperationResultsCollector {
@@ -14,8 +18,12 @@ impl OperationResultsCollector {
         Self::default()
     }
 
-    pub fn add_status(&mut self, status: &ReportLabel) {
-        match status {
+    pub fn add_report(&mut self, report: &Report) {
+        if let Some(code) = report.error_code {
+            *self.errors_by_code.entry(code).or_default() += 1;
+        }
+
+        match report.label {
             ReportLabel::ActionDone => self.successes += 1,
             ReportLabel::ActionSkipped { .. } => self.skipped += 1,
             ReportLabel::ActionFailed { .. } => self.failures += 1,
@@ -38,6 +46,10 @@ impl OperationResultsCollector {
         self.successes + self.skipped + self.failures
     }
 
+    pub fn errors_by_code(&self) -> &BTreeMap<i64, u64> {
+        &self.errors_by_code
+    }
+
     pub fn report(&self) {
         vlog::info!(
             "Loadtest status: {} successful operations, {} skipped, {} failures. {} actions total.",
@@ -46,5 +58,8 @@ impl OperationResultsCollector {
             self.failures(),
             self.total()
         );
+        for (code, amount) in &self.errors_by_code {
+            vlog::info!("API error code {}: {} occurrences", code, amount);
+        }
     }
 }
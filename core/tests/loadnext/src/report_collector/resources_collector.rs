@@ -0,0 +1,492 @@
+use std::{
+    collections::BTreeMap,
+    time::{Duration, Instant},
+};
+
+use futures::channel::oneshot;
+use serde::Serialize;
+use tokio::{task::JoinHandle, time};
+
+use crate::scenario::{DriftThreshold, SoakConfig};
+
+/// Timeout of the single metrics endpoint request.
+const SCRAPE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Sample of the metric series parsed from the Prometheus text format.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricSample {
+    pub name: String,
+    pub labels: BTreeMap<String, String>,
+    pub value: f64,
+}
+
+impl MetricSample {
+    /// Returns the identifier of the series, e.g. `mempool_size{component="core"}`.
+    fn series(&self) -> String {
+        if self.labels.is_empty() {
+            return self.name.clone();
+        }
+        let labels: Vec<_> = self
+            .labels
+            .iter()
+            .map(|(name, value)| format!("{}={:?}", name, value))
+            .collect();
+        format!("{}{{{}}}", self.name, labels.join(","))
+    }
+
+    fn matches(&self, threshold: &DriftThreshold) -> bool {
+        self.name == threshold.metric
+            && threshold
+                .labels
+                .iter()
+                .all(|(name, value)| self.labels.get(name) == Some(value))
+    }
+}
+
+/// Parses the metrics exposed in the Prometheus text format. Comments and malformed lines are skipped.
+pub fn parse_metrics(text: &str) -> Vec<MetricSample> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(parse_metric_line)
+        .collect()
+}
+
+fn parse_metric_line(line: &str) -> Option<MetricSample> {
+    let (name, labels, rest) = match line.find('{') {
+        Some(labels_start) => {
+            let labels_end = line.rfind('}')?;
+            let labels = parse_labels(&line[labels_start + 1..labels_end])?;
+            (&line[..labels_start], labels, &line[labels_end + 1..])
+        }
+        None => {
+            let name_end = line.find(char::is_whitespace)?;
+            (&line[..name_end], BTreeMap::new(), &line[name_end..])
+        }
+    };
+    // The value may be followed by the timestamp.
+    let value = rest.split_whitespace().next()?.parse().ok()?;
+
+    Some(MetricSample {
+        name: name.trim().to_string(),
+        labels,
+        value,
+    })
+}
+
+/// Parses the labels of the series, e.g. `component="core",network="localhost"`.
+fn parse_labels(text: &str) -> Option<BTreeMap<String, String>> {
+    let mut labels = BTreeMap::new();
+    let mut chars = text.chars().peekable();
+    loop {
+        while matches!(chars.peek(), Some(',') | Some(' ')) {
+            chars.next();
+        }
+        if chars.peek().is_none() {
+            return Some(labels);
+        }
+
+        let name: String = chars.by_ref().take_while(|c| *c != '=').collect();
+        if chars.next()? != '"' {
+            return None;
+        }
+        let mut value = String::new();
+        loop {
+            match chars.next()? {
+                '"' => break,
+                '\\' => match chars.next()? {
+                    'n' => value.push('\n'),
+                    escaped => value.push(escaped),
+                },
+                c => value.push(c),
+            }
+        }
+        labels.insert(name.trim().to_string(), value);
+    }
+}
+
+/// Values of the series tracked since the end of the warm-up.
+#[derive(Debug, Clone)]
+struct SeriesStats {
+    baseline: f64,
+    baseline_at: Duration,
+    last: f64,
+    last_at: Duration,
+    max: f64,
+    samples: u64,
+}
+
+/// Drift of the metric series during the soak test.
+#[derive(Debug, Clone, Serialize)]
+pub struct SeriesDrift {
+    pub series: String,
+    /// Value at the end of the warm-up.
+    pub baseline: f64,
+    pub last: f64,
+    pub max: f64,
+    /// Growth since the end of the warm-up in percent, unknown if the baseline is zero.
+    pub growth_percent: Option<f64>,
+    pub growth_per_hour: f64,
+    pub samples: u64,
+    /// Descriptions of the exceeded limits.
+    pub violations: Vec<String>,
+}
+
+/// Collector that tracks the resource usage of the server in the soak test.
+///
+/// Metrics are sampled from the Prometheus endpoints of the server components, and every series matching
+/// any of the thresholds is tracked separately. Samples taken during the warm-up are ignored, so the growth
+/// is measured since its end. Test is considered failed if any threshold is exceeded or doesn't match
+/// any of the series, since it's likely a misconfiguration.
+#[derive(Debug, Clone)]
+pub struct ResourcesCollector {
+    thresholds: Vec<DriftThreshold>,
+    warmup: Duration,
+    /// Tracked series along with the indices of the matching thresholds.
+    series: BTreeMap<String, (Vec<usize>, SeriesStats)>,
+    failed_scrapes: u64,
+}
+
+impl ResourcesCollector {
+    pub fn new(config: &SoakConfig) -> Self {
+        Self {
+            thresholds: config.thresholds.clone(),
+            warmup: config.warmup(),
+            series: BTreeMap::new(),
+            failed_scrapes: 0,
+        }
+    }
+
+    /// Adds the metrics sampled at the given time since the start of the test.
+    pub fn add_samples(&mut self, elapsed: Duration, samples: &[MetricSample]) {
+        if elapsed < self.warmup {
+            return;
+        }
+
+        for sample in samples {
+            let thresholds: Vec<_> = self
+                .thresholds
+                .iter()
+                .enumerate()
+                .filter(|(_, threshold)| sample.matches(threshold))
+                .map(|(idx, _)| idx)
+                .collect();
+            if thresholds.is_empty() {
+                continue;
+            }
+
+            let (_, stats) = self.series.entry(sample.series()).or_insert_with(|| {
+                (
+                    thresholds,
+                    SeriesStats {
+                        baseline: sample.value,
+                        baseline_at: elapsed,
+                        last: sample.value,
+                        last_at: elapsed,
+                        max: sample.value,
+                        samples: 0,
+                    },
+                )
+            });
+            stats.last = sample.value;
+            stats.last_at = elapsed;
+            stats.max = stats.max.max(sample.value);
+            stats.samples += 1;
+        }
+    }
+
+    pub fn add_failed_scrape(&mut self) {
+        self.failed_scrapes += 1;
+    }
+
+    /// Returns the drift of every tracked series.
+    pub fn drifts(&self) -> Vec<SeriesDrift> {
+        self.series
+            .iter()
+            .map(|(series, (thresholds, stats))| {
+                let growth = stats.last - stats.baseline;
+                let growth_percent = if stats.baseline > 0.0 {
+                    Some(growth / stats.baseline * 100.0)
+                } else {
+                    None
+                };
+                let hours = (stats.last_at - stats.baseline_at).as_secs_f64() / 3600.0;
+                let growth_per_hour = if hours > 0.0 { growth / hours } else { 0.0 };
+
+                let mut drift = SeriesDrift {
+                    series: series.clone(),
+                    baseline: stats.baseline,
+                    last: stats.last,
+                    max: stats.max,
+                    growth_percent,
+                    growth_per_hour,
+                    samples: stats.samples,
+                    violations: Vec::new(),
+                };
+                for threshold in thresholds.iter().map(|idx| &self.thresholds[*idx]) {
+                    drift.check(threshold);
+                }
+                drift
+            })
+            .collect()
+    }
+
+    /// Returns the metrics of the thresholds that don't match any of the sampled series.
+    pub fn unmatched_thresholds(&self) -> Vec<&str> {
+        (0..self.thresholds.len())
+            .filter(|idx| {
+                !self
+                    .series
+                    .values()
+                    .any(|(thresholds, _)| thresholds.contains(idx))
+            })
+            .map(|idx| self.thresholds[idx].metric.as_str())
+            .collect()
+    }
+
+    /// Checks whether none of the thresholds is exceeded.
+    pub fn passed(&self) -> bool {
+        self.unmatched_thresholds().is_empty()
+            && self
+                .drifts()
+                .iter()
+                .all(|drift| drift.violations.is_empty())
+    }
+
+    pub fn report(&self) {
+        for drift in self.drifts() {
+            vlog::info!(
+                "Resource {}: {} -> {} (max {}), growth {:.2}% ({:.2} per hour) over {} samples",
+                drift.series,
+                drift.baseline,
+                drift.last,
+                drift.max,
+                drift.growth_percent.unwrap_or_default(),
+                drift.growth_per_hour,
+                drift.samples
+            );
+            for violation in &drift.violations {
+                vlog::error!("Resource {} drift is too high: {}", drift.series, violation);
+            }
+        }
+        for metric in self.unmatched_thresholds() {
+            vlog::error!("Metric {} was not found on any of the endpoints", metric);
+        }
+        if self.failed_scrapes > 0 {
+            vlog::warn!("{} requests for the metrics failed", self.failed_scrapes);
+        }
+    }
+}
+
+impl SeriesDrift {
+    fn check(&mut self, threshold: &DriftThreshold) {
+        if let (Some(limit), Some(growth)) = (threshold.max_growth_percent, self.growth_percent) {
+            if growth > limit {
+                self.violations
+                    .push(format!("growth {:.2}% exceeds {}%", growth, limit));
+            }
+        }
+        if let Some(limit) = threshold.max_growth_per_hour {
+            if self.growth_per_hour > limit {
+                self.violations.push(format!(
+                    "growth {:.2} per hour exceeds {}",
+                    self.growth_per_hour, limit
+                ));
+            }
+        }
+        if let Some(limit) = threshold.max_value {
+            if self.max > limit {
+                self.violations
+                    .push(format!("value {} exceeds {}", self.max, limit));
+            }
+        }
+    }
+}
+
+/// Actor sampling the metrics for the `ResourcesCollector` until it's stopped,
+/// so that the HTTP requests don't block the processing of the reports.
+#[derive(Debug)]
+pub struct ResourcesSampler {
+    stop_sender: oneshot::Sender<()>,
+    handle: JoinHandle<ResourcesCollector>,
+}
+
+impl ResourcesSampler {
+    pub fn start(config: SoakConfig) -> Self {
+        let (stop_sender, mut stop_receiver) = oneshot::channel();
+        let handle = tokio::spawn(async move {
+            let mut collector = ResourcesCollector::new(&config);
+            let client = reqwest::Client::builder()
+                .timeout(SCRAPE_TIMEOUT)
+                .build()
+                .expect("Unable to create the HTTP client");
+            let started_at = Instant::now();
+            let mut timer = time::interval(config.sample_interval());
+
+            loop {
+                tokio::select! {
+                    _ = timer.tick() => {}
+                    _ = &mut stop_receiver => break,
+                }
+
+                let elapsed = started_at.elapsed();
+                for endpoint in &config.metrics_endpoints {
+                    match scrape(&client, endpoint).await {
+                        Ok(samples) => collector.add_samples(elapsed, &samples),
+                        Err(err) => {
+                            vlog::warn!("Unable to get the metrics from {}: {}", endpoint, err);
+                            collector.add_failed_scrape();
+                        }
+                    }
+                }
+            }
+            collector
+        });
+
+        Self {
+            stop_sender,
+            handle,
+        }
+    }
+
+    /// Stops the sampling and returns the collected metrics.
+    pub async fn stop(self) -> anyhow::Result<ResourcesCollector> {
+        // The sampler may only be gone if it panicked, which is reported by the handle.
+        let _ = self.stop_sender.send(());
+        Ok(self.handle.await?)
+    }
+}
+
+async fn scrape(client: &reqwest::Client, endpoint: &str) -> anyhow::Result<Vec<MetricSample>> {
+    let text = client
+        .get(endpoint)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    Ok(parse_metrics(&text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn threshold(metric: &str) -> DriftThreshold {
+        DriftThreshold {
+            metric: metric.to_string(),
+            labels: BTreeMap::new(),
+            max_growth_percent: None,
+            max_growth_per_hour: None,
+            max_value: None,
+        }
+    }
+
+    fn sample(name: &str, component: &str, value: f64) -> MetricSample {
+        let mut labels = BTreeMap::new();
+        labels.insert("component".to_string(), component.to_string());
+        MetricSample {
+            name: name.to_string(),
+            labels,
+            value,
+        }
+    }
+
+    #[test]
+    fn metrics_parsing() {
+        let text = r#"
+# HELP mempool_size Amount of the queued transactions.
+# TYPE mempool_size gauge
+mempool_size{component="core",network="localhost"} 15
+database_size_bytes 1.5e9 1634380000000
+process_resident_memory_bytes{component="api,core",path="C:\\zk\"sync\""} 1024
+malformed_line
+"#;
+        let samples = parse_metrics(text);
+        assert_eq!(samples.len(), 3);
+        assert_eq!(
+            samples[0].series(),
+            r#"mempool_size{component="core",network="localhost"}"#
+        );
+        assert_eq!(samples[0].value, 15.0);
+        assert_eq!(samples[1].series(), "database_size_bytes");
+        assert_eq!(samples[1].value, 1.5e9);
+        assert_eq!(samples[2].labels["component"], "api,core");
+        assert_eq!(samples[2].labels["path"], r#"C:\zk"sync""#);
+    }
+
+    #[test]
+    fn drift_thresholds() {
+        let hour = Duration::from_secs(3600);
+        let memory = DriftThreshold {
+            max_growth_percent: Some(20.0),
+            ..threshold("process_resident_memory_bytes")
+        };
+        let mut prover_memory = DriftThreshold {
+            max_value: Some(500.0),
+            ..threshold("process_resident_memory_bytes")
+        };
+        prover_memory
+            .labels
+            .insert("component".to_string(), "prover".to_string());
+        let database = DriftThreshold {
+            max_growth_per_hour: Some(100.0),
+            ..threshold("database_size_bytes")
+        };
+        let config = SoakConfig {
+            warmup_secs: 600,
+            thresholds: vec![memory, prover_memory, database],
+            ..SoakConfig::default()
+        };
+        let mut collector = ResourcesCollector::new(&config);
+
+        // Samples taken during the warm-up are ignored.
+        collector.add_samples(
+            Duration::from_secs(60),
+            &[sample("process_resident_memory_bytes", "core", 1.0)],
+        );
+        assert!(collector.drifts().is_empty());
+        assert!(!collector.passed());
+
+        collector.add_samples(
+            hour,
+            &[
+                sample("process_resident_memory_bytes", "core", 100.0),
+                sample("process_resident_memory_bytes", "prover", 400.0),
+                sample("database_size_bytes", "core", 1000.0),
+                sample("mempool_size", "core", 10.0),
+            ],
+        );
+        collector.add_samples(
+            hour * 3,
+            &[
+                sample("process_resident_memory_bytes", "core", 110.0),
+                sample("process_resident_memory_bytes", "prover", 450.0),
+                sample("database_size_bytes", "core", 1150.0),
+            ],
+        );
+        assert!(collector.unmatched_thresholds().is_empty());
+        assert!(collector.passed());
+
+        collector.add_samples(
+            hour * 4,
+            &[
+                sample("process_resident_memory_bytes", "core", 130.0),
+                sample("process_resident_memory_bytes", "prover", 600.0),
+                sample("database_size_bytes", "core", 1600.0),
+            ],
+        );
+        let drifts = collector.drifts();
+        assert_eq!(drifts.len(), 3);
+        // Database grew by 600 in 3 hours.
+        assert_eq!(drifts[0].growth_per_hour, 200.0);
+        assert_eq!(drifts[0].violations.len(), 1);
+        // Memory of the core grew by 30%.
+        assert!((drifts[1].growth_percent.unwrap() - 30.0).abs() < 1e-9);
+        assert_eq!(drifts[1].violations.len(), 1);
+        // Memory of the prover is checked by both thresholds.
+        assert_eq!(drifts[2].max, 600.0);
+        assert_eq!(drifts[2].violations.len(), 2);
+        assert!(!collector.passed());
+    }
+}
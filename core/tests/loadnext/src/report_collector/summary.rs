@@ -0,0 +1,54 @@
+use std::{collections::BTreeMap, fs, path::Path};
+
+use serde::Serialize;
+
+use crate::report_collector::{
+    resources_collector::SeriesDrift, timings_collector::LatencyPercentiles,
+};
+
+/// Amounts of the operations by their outcome.
+#[derive(Debug, Clone, Serialize)]
+pub struct OperationsSummary {
+    pub successes: u64,
+    pub skipped: u64,
+    pub failures: u64,
+    pub total: u64,
+}
+
+/// Machine-readable summary of the loadtest run.
+///
+/// It's written as a JSON file, so that the performance of the different revisions can be compared
+/// automatically (e.g. on CI). All the durations are represented in milliseconds.
+#[derive(Debug, Clone, Serialize)]
+pub struct LoadtestSummary {
+    /// Whether the test was passed.
+    pub passed: bool,
+    /// Duration of the test, in seconds.
+    pub duration_secs: f64,
+    pub operations: OperationsSummary,
+    /// Amount of the successful operations per second.
+    pub tps: f64,
+    /// Amount of the successful operations without deliberate errors per second.
+    pub valid_tps: f64,
+    /// Time it took the server to accept the transaction or batch.
+    pub submission_latency: Option<LatencyPercentiles>,
+    /// Time from the submission till the commitment.
+    pub time_to_commit: Option<LatencyPercentiles>,
+    /// Time from the submission till the verification.
+    /// Only measured if the scenario requires to wait for the verification.
+    pub time_to_verify: Option<LatencyPercentiles>,
+    /// Amount of the API errors (including the expected ones) per error code.
+    pub errors_by_code: BTreeMap<i64, u64>,
+    /// Drift of the server metrics, only sampled in the soak test.
+    pub resources: Option<Vec<SeriesDrift>>,
+}
+
+impl LoadtestSummary {
+    /// Writes the summary to the file as a JSON.
+    pub fn write(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let contents = serde_json::to_string_pretty(self)?;
+        fs::write(path, contents)?;
+
+        Ok(())
+    }
+}
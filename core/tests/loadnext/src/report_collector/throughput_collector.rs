@@ -0,0 +1,71 @@
+use std::time::{Duration, Instant};
+
+use crate::report::{Report, ReportLabel};
+
+/// Collector that measures the throughput of the successfully performed operations.
+///
+/// Throughput is measured between the first and the last received reports, and is tracked
+/// separately for the valid operations, so that it's possible to check that deliberately incorrect
+/// operations don't affect the valid ones.
+#[derive(Debug, Clone, Default)]
+pub struct ThroughputCollector {
+    first_report_at: Option<Instant>,
+    last_report_at: Option<Instant>,
+    successes: u64,
+    valid_successes: u64,
+}
+
+impl ThroughputCollector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_report(&mut self, report: &Report) {
+        let now = Instant::now();
+        self.first_report_at.get_or_insert(now);
+        self.last_report_at = Some(now);
+
+        if matches!(report.label, ReportLabel::ActionDone) {
+            self.successes += 1;
+            if report.valid {
+                self.valid_successes += 1;
+            }
+        }
+    }
+
+    /// Returns the time between the first and the last received reports.
+    pub fn elapsed(&self) -> Duration {
+        match (self.first_report_at, self.last_report_at) {
+            (Some(first), Some(last)) => last - first,
+            _ => Duration::default(),
+        }
+    }
+
+    fn per_second(&self, amount: u64) -> f64 {
+        let elapsed = self.elapsed().as_secs_f64();
+        if elapsed > 0.0 {
+            amount as f64 / elapsed
+        } else {
+            0.0
+        }
+    }
+
+    /// Returns the amount of successful operations per second.
+    pub fn tps(&self) -> f64 {
+        self.per_second(self.successes)
+    }
+
+    /// Returns the amount of successful valid operations per second.
+    pub fn valid_tps(&self) -> f64 {
+        self.per_second(self.valid_successes)
+    }
+
+    pub fn report(&self) {
+        vlog::info!(
+            "Loadtest throughput: {:.2} successful operations per second, {:.2} of them are valid. Measured for {}s.",
+            self.tps(),
+            self.valid_tps(),
+            self.elapsed().as_secs()
+        );
+    }
+}
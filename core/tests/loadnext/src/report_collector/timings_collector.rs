@@ -0,0 +1,125 @@
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::report::{OperationTimings, Report, ReportLabel};
+
+/// Percentiles of the stage duration, in milliseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct LatencyPercentiles {
+    pub p50: u64,
+    pub p95: u64,
+    pub p99: u64,
+}
+
+impl LatencyPercentiles {
+    /// Calculates the percentiles of the provided durations.
+    /// Returns `None` if there are no durations.
+    fn new(durations: &mut [Duration]) -> Option<Self> {
+        if durations.is_empty() {
+            return None;
+        }
+        durations.sort_unstable();
+
+        Some(Self {
+            p50: percentile(durations, 50),
+            p95: percentile(durations, 95),
+            p99: percentile(durations, 99),
+        })
+    }
+}
+
+/// Returns the percentile of the sorted durations using the nearest-rank method.
+fn percentile(sorted: &[Duration], percent: usize) -> u64 {
+    let rank = (sorted.len() * percent + 99) / 100;
+    sorted[rank.saturating_sub(1)].as_millis() as u64
+}
+
+/// Collector that gathers the durations of the separate stages of the successful operations:
+/// submission, commitment and verification.
+///
+/// Unlike `MetricsCollector`, it stores the exact values, so that the precise percentiles can be
+/// calculated and compared between the loadtest runs.
+#[derive(Debug, Clone, Default)]
+pub struct TimingsCollector {
+    submission: Vec<Duration>,
+    commit: Vec<Duration>,
+    verify: Vec<Duration>,
+}
+
+impl TimingsCollector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_report(&mut self, report: &Report) {
+        if !matches!(report.label, ReportLabel::ActionDone) {
+            return;
+        }
+
+        let OperationTimings {
+            submission,
+            commit,
+            verify,
+        } = report.timings;
+        self.submission.extend(submission);
+        self.commit.extend(commit);
+        self.verify.extend(verify);
+    }
+
+    pub fn submission(&mut self) -> Option<LatencyPercentiles> {
+        LatencyPercentiles::new(&mut self.submission)
+    }
+
+    pub fn commit(&mut self) -> Option<LatencyPercentiles> {
+        LatencyPercentiles::new(&mut self.commit)
+    }
+
+    pub fn verify(&mut self) -> Option<LatencyPercentiles> {
+        LatencyPercentiles::new(&mut self.verify)
+    }
+
+    pub fn report(&mut self) {
+        let stages = [
+            ("Submission", self.submission()),
+            ("Time to commit", self.commit()),
+            ("Time to verify", self.verify()),
+        ];
+        for (stage, percentiles) in &stages {
+            if let Some(percentiles) = percentiles {
+                vlog::info!(
+                    "{}: p50 {}ms, p95 {}ms, p99 {}ms",
+                    stage,
+                    percentiles.p50,
+                    percentiles.p95,
+                    percentiles.p99
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn latency_percentiles() {
+        let mut durations: Vec<_> = (1..=200).rev().map(Duration::from_millis).collect();
+        let percentiles = LatencyPercentiles::new(&mut durations).unwrap();
+        assert_eq!(
+            percentiles,
+            LatencyPercentiles {
+                p50: 100,
+                p95: 190,
+                p99: 198,
+            }
+        );
+
+        let mut single = [Duration::from_millis(42)];
+        let percentiles = LatencyPercentiles::new(&mut single).unwrap();
+        assert_eq!((percentiles.p50, percentiles.p99), (42, 42));
+
+        assert_eq!(LatencyPercentiles::new(&mut []), None);
+    }
+}
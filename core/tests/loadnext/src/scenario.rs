@@ -0,0 +1,543 @@
+use std::{collections::BTreeMap, fs, path::Path, time::Duration};
+
+use rand::seq::SliceRandom;
+use serde::Deserialize;
+
+use crate::{
+    command::{IncorrectnessModifier, TxType},
+    config::LoadtestConfig,
+    constants::MAX_BATCH_SIZE,
+    rng::LoadtestRng,
+};
+
+/// Scenario of the loadtest: description of the load to be generated.
+///
+/// Scenario can be loaded from a TOML file, so that the performance runs can be reproduced and
+/// reviewed. All the fields are optional: omitted fields take the default values, which correspond
+/// to the loadtest behavior without the scenario file.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Scenario {
+    /// Amount of accounts to be used in test.
+    pub accounts_amount: usize,
+    /// Amount of operations per account.
+    /// Ignored if `duration_secs` is set.
+    pub operations_per_account: usize,
+    /// Symbols of the ERC-20 tokens to be used in test.
+    /// Every token must be supported by zkSync and have `mint` operation.
+    /// The first token is the main one: it's used to pay fees.
+    pub tokens: Vec<String>,
+    /// Target amount of commands sent per second by all the accounts together.
+    /// Since every account waits for the result of the command before sending the next one,
+    /// it's the upper bound of the load. If not set, accounts send commands as fast as they can.
+    pub tps_target: Option<f64>,
+    /// Duration of the test. If set, accounts send commands until the duration elapses.
+    pub duration_secs: Option<u64>,
+    /// Chance of sending a batch instead of a single transaction, from 0.0 to 1.0.
+    pub batch_chance: f32,
+    /// Minimal amount of transactions in the batch.
+    pub min_batch_size: usize,
+    /// Maximal amount of transactions in the batch.
+    pub max_batch_size: usize,
+    /// Weights of the transaction types.
+    pub tx_weights: TxWeights,
+    /// Whether accounts should wait for the verification of their successful transactions.
+    /// Required to measure the time to verify, but makes every account idle until the block proof is generated.
+    pub wait_for_verify: bool,
+    /// Rates of the deliberately incorrect transactions.
+    pub chaos: ChaosConfig,
+    /// Soak test options: if set, the server metrics are sampled during the test and checked for the drift.
+    pub soak: Option<SoakConfig>,
+}
+
+impl Default for Scenario {
+    fn default() -> Self {
+        let config = LoadtestConfig::default();
+
+        Self {
+            accounts_amount: config.accounts_amount,
+            operations_per_account: config.operations_per_account,
+            tokens: vec![config.main_token],
+            tps_target: None,
+            duration_secs: None,
+            batch_chance: 0.3,
+            // TODO: For some reason, batches of size 1 are being rejected because of nonce mistmatch.
+            // It may be either bug in loadtest or server code, thus it should be investigated.
+            min_batch_size: 2,
+            max_batch_size: MAX_BATCH_SIZE,
+            tx_weights: TxWeights::default(),
+            wait_for_verify: false,
+            chaos: ChaosConfig::default(),
+            soak: None,
+        }
+    }
+}
+
+impl Scenario {
+    /// Creates the default scenario with the parameters provided via the environment variables.
+    pub fn from_config(config: &LoadtestConfig) -> Self {
+        Self {
+            accounts_amount: config.accounts_amount,
+            operations_per_account: config.operations_per_account,
+            tokens: vec![config.main_token.clone()],
+            ..Self::default()
+        }
+    }
+
+    /// Loads the scenario from the TOML file.
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path)
+            .map_err(|err| anyhow::anyhow!("Unable to read scenario {:?}: {}", path, err))?;
+
+        Self::from_toml(&contents)
+    }
+
+    /// Parses the scenario from the TOML string.
+    pub fn from_toml(contents: &str) -> anyhow::Result<Self> {
+        let scenario: Self = toml::from_str(contents)?;
+        scenario.validate()?;
+
+        Ok(scenario)
+    }
+
+    /// Checks that the scenario can be executed.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        anyhow::ensure!(self.accounts_amount > 0, "Accounts amount must be positive");
+        anyhow::ensure!(!self.tokens.is_empty(), "At least one token must be set");
+        anyhow::ensure!(
+            (0.0..=1.0).contains(&self.batch_chance),
+            "Batch chance must be from 0.0 to 1.0"
+        );
+        anyhow::ensure!(
+            2 <= self.min_batch_size
+                && self.min_batch_size <= self.max_batch_size
+                && self.max_batch_size <= MAX_BATCH_SIZE,
+            "Batch size must be from 2 to {}",
+            MAX_BATCH_SIZE
+        );
+        if let Some(tps_target) = self.tps_target {
+            anyhow::ensure!(tps_target > 0.0, "TPS target must be positive");
+        }
+        if let Some(duration_secs) = self.duration_secs {
+            anyhow::ensure!(duration_secs > 0, "Duration must be positive");
+        }
+
+        let weights = self.tx_weights.all_weighted();
+        anyhow::ensure!(
+            weights.iter().all(|(_, weight)| *weight >= 0.0),
+            "Transaction weights must not be negative"
+        );
+        anyhow::ensure!(
+            self.batch_chance >= 1.0 || weights.iter().any(|(_, weight)| *weight > 0.0),
+            "At least one transaction type must have a positive weight"
+        );
+        anyhow::ensure!(
+            self.batch_chance <= 0.0
+                || weights
+                    .iter()
+                    .any(|(tx_type, weight)| tx_type.is_batchable() && *weight > 0.0),
+            "At least one batchable transaction type must have a positive weight"
+        );
+        anyhow::ensure!(
+            self.tx_weights.swap <= 0.0 || self.tokens.len() >= 2,
+            "Swaps require at least two tokens"
+        );
+
+        if let Some(soak) = &self.soak {
+            anyhow::ensure!(
+                self.tps_target.is_some() && self.duration_secs.is_some(),
+                "Soak test requires both TPS target and duration to be set"
+            );
+            soak.validate()?;
+        }
+
+        self.chaos.validate()
+    }
+
+    /// Returns the symbol of the token used to pay fees.
+    pub fn main_token(&self) -> &str {
+        &self.tokens[0]
+    }
+
+    /// Randomly chooses one of the scenario tokens.
+    pub fn random_token(&self, rng: &mut LoadtestRng) -> &str {
+        // Don't touch the RNG if there is no choice, so that seeds of the previous runs
+        // produce the same sequence of operations.
+        if self.tokens.len() == 1 {
+            return self.main_token();
+        }
+        self.tokens.choose(rng).unwrap()
+    }
+
+    /// Returns the duration of the test, if it's limited.
+    pub fn duration(&self) -> Option<Duration> {
+        self.duration_secs.map(Duration::from_secs)
+    }
+
+    /// Returns the minimal interval between the commands of a single account.
+    pub fn command_interval(&self) -> Option<Duration> {
+        self.tps_target
+            .map(|tps_target| Duration::from_secs_f64(self.accounts_amount as f64 / tps_target))
+    }
+
+    /// Returns the token bought by the swap selling the given one: the next token of the scenario.
+    pub fn swap_token_buy(&self, token_sell: &str) -> &str {
+        let position = self
+            .tokens
+            .iter()
+            .position(|token| token == token_sell)
+            .expect("Token is not used in the scenario");
+        &self.tokens[(position + 1) % self.tokens.len()]
+    }
+
+    /// Checks whether the scenario sends swaps, which require the market maker account.
+    pub fn has_swaps(&self) -> bool {
+        self.tx_weights.swap > 0.0
+    }
+
+    /// Returns the expected amount of operations performed by each account.
+    pub fn estimated_operations_per_account(&self) -> usize {
+        match (self.duration_secs, self.tps_target) {
+            (Some(duration_secs), Some(tps_target)) => {
+                (duration_secs as f64 * tps_target / self.accounts_amount as f64).ceil() as usize
+            }
+            _ => self.operations_per_account,
+        }
+    }
+}
+
+/// Chaos options of the scenario: rates of the transactions that deliberately violate the server rules.
+/// Server is expected to reject such transactions with the corresponding API errors, while the valid
+/// traffic should not be affected.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ChaosConfig {
+    /// Chance of the transaction to have an invalid zkSync or Ethereum signature.
+    pub invalid_signature: f32,
+    /// Chance of the transaction to have an already used nonce.
+    pub wrong_nonce: f32,
+    /// Chance of the transaction to have the fee below the required one.
+    pub underpriced_fee: f32,
+    /// Chance of the batch to exceed the server batch size limit.
+    pub oversized_batch: f32,
+    /// Amount of transactions in the oversized batch.
+    /// Must be greater than `max_number_of_transactions_per_batch` of the server API config.
+    pub oversized_batch_size: usize,
+    /// Minimal throughput of the valid operations (i.e. the ones without deliberate errors) per second.
+    /// If set, the test fails if the actual throughput is lower.
+    pub min_valid_tps: Option<f64>,
+}
+
+impl Default for ChaosConfig {
+    fn default() -> Self {
+        Self {
+            invalid_signature: 2.0 * IncorrectnessModifier::DEFAULT_CHANCE,
+            wrong_nonce: 0.0,
+            underpriced_fee: 0.0,
+            oversized_batch: 0.0,
+            // Default server limit is 200 transactions.
+            oversized_batch_size: 201,
+            min_valid_tps: None,
+        }
+    }
+}
+
+impl ChaosConfig {
+    fn validate(&self) -> anyhow::Result<()> {
+        let rates = [
+            self.invalid_signature,
+            self.wrong_nonce,
+            self.underpriced_fee,
+            self.oversized_batch,
+        ];
+        anyhow::ensure!(
+            rates.iter().all(|rate| (0.0..=1.0).contains(rate)),
+            "Chaos rates must be from 0.0 to 1.0"
+        );
+        // Chance of no modifier is what's left of the other modifier chances.
+        let modifiers_chance: f32 = IncorrectnessModifier::all_weighted(self)
+            .iter()
+            .filter(|(modifier, _)| *modifier != IncorrectnessModifier::None)
+            .map(|(_, chance)| chance)
+            .sum();
+        anyhow::ensure!(
+            modifiers_chance <= 1.0,
+            "Sum of the incorrect transaction chances must not exceed 1.0"
+        );
+        anyhow::ensure!(
+            self.oversized_batch_size > MAX_BATCH_SIZE,
+            "Oversized batch must have more than {} transactions",
+            MAX_BATCH_SIZE
+        );
+        if let Some(min_valid_tps) = self.min_valid_tps {
+            anyhow::ensure!(min_valid_tps > 0.0, "Minimal valid TPS must be positive");
+        }
+
+        Ok(())
+    }
+}
+
+/// Soak test options: the metrics of the server components sampled during the test and the limits of their drift.
+///
+/// Soak test is a long run with the constant load (see `tps_target` and `duration_secs`), which catches
+/// the resource leaks and the unbounded growth of the server state before they hit the production.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SoakConfig {
+    /// Prometheus endpoints of the server components, e.g. `http://127.0.0.1:3312/metrics`.
+    pub metrics_endpoints: Vec<String>,
+    /// Interval between the samples of the metrics.
+    pub sample_interval_secs: u64,
+    /// Samples taken during the warm-up after the start of the test are ignored, so that the caches
+    /// filled once the load starts aren't considered a leak.
+    pub warmup_secs: u64,
+    /// Limits of the sampled metrics. The test fails if any of them is exceeded.
+    pub thresholds: Vec<DriftThreshold>,
+}
+
+impl Default for SoakConfig {
+    fn default() -> Self {
+        Self {
+            metrics_endpoints: Vec::new(),
+            sample_interval_secs: 60,
+            warmup_secs: 600,
+            thresholds: Vec::new(),
+        }
+    }
+}
+
+impl SoakConfig {
+    /// Returns the interval between the samples of the metrics.
+    pub fn sample_interval(&self) -> Duration {
+        Duration::from_secs(self.sample_interval_secs)
+    }
+
+    /// Returns the duration of the warm-up.
+    pub fn warmup(&self) -> Duration {
+        Duration::from_secs(self.warmup_secs)
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            !self.metrics_endpoints.is_empty(),
+            "At least one metrics endpoint must be set for the soak test"
+        );
+        anyhow::ensure!(
+            self.sample_interval_secs > 0,
+            "Sample interval must be positive"
+        );
+        anyhow::ensure!(
+            !self.thresholds.is_empty(),
+            "At least one threshold must be set for the soak test"
+        );
+        for threshold in &self.thresholds {
+            threshold.validate()?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Limits of the metric sampled during the soak test. The growth is measured since the end of the warm-up.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DriftThreshold {
+    /// Name of the metric, e.g. `process_resident_memory_bytes`.
+    pub metric: String,
+    /// Labels the metric series must have, e.g. `{ component = "core" }`.
+    /// Every matching series is checked separately.
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+    /// Maximal growth of the metric, in percent.
+    pub max_growth_percent: Option<f64>,
+    /// Maximal growth of the metric per hour, in the metric units.
+    pub max_growth_per_hour: Option<f64>,
+    /// Maximal value of the metric.
+    pub max_value: Option<f64>,
+}
+
+impl DriftThreshold {
+    fn validate(&self) -> anyhow::Result<()> {
+        let limits = [
+            self.max_growth_percent,
+            self.max_growth_per_hour,
+            self.max_value,
+        ];
+        anyhow::ensure!(
+            limits.iter().any(Option::is_some),
+            "Threshold of {} must set at least one limit",
+            self.metric
+        );
+        anyhow::ensure!(
+            limits.iter().flatten().all(|limit| *limit >= 0.0),
+            "Limits of {} must not be negative",
+            self.metric
+        );
+
+        Ok(())
+    }
+}
+
+/// Weights of the transaction types used in the scenario.
+/// By default, transfers are 3 times more likely than every other transaction type,
+/// and NFT transactions and swaps are not sent.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TxWeights {
+    pub deposit: f32,
+    pub transfer_to_new: f32,
+    pub transfer_to_existing: f32,
+    pub withdraw_to_self: f32,
+    pub withdraw_to_other: f32,
+    pub full_exit: f32,
+    pub change_pubkey: f32,
+    pub mint_nft: f32,
+    pub transfer_nft: f32,
+    pub withdraw_nft: f32,
+    /// Swaps are matched against the orders of the market maker account, and require at least two tokens.
+    pub swap: f32,
+}
+
+impl Default for TxWeights {
+    fn default() -> Self {
+        const DEFAULT_WEIGHT: f32 = 1.0;
+        const HIGH_WEIGHT: f32 = 3.0;
+
+        Self {
+            deposit: DEFAULT_WEIGHT,
+            transfer_to_new: HIGH_WEIGHT,
+            transfer_to_existing: HIGH_WEIGHT,
+            withdraw_to_self: DEFAULT_WEIGHT,
+            withdraw_to_other: DEFAULT_WEIGHT,
+            full_exit: DEFAULT_WEIGHT,
+            change_pubkey: DEFAULT_WEIGHT,
+            mint_nft: 0.0,
+            transfer_nft: 0.0,
+            withdraw_nft: 0.0,
+            swap: 0.0,
+        }
+    }
+}
+
+impl TxWeights {
+    /// Returns all the transaction types together with their weight.
+    pub fn all_weighted(&self) -> [(TxType, f32); 11] {
+        [
+            (TxType::Deposit, self.deposit),
+            (TxType::TransferToNew, self.transfer_to_new),
+            (TxType::TransferToExisting, self.transfer_to_existing),
+            (TxType::WithdrawToSelf, self.withdraw_to_self),
+            (TxType::WithdrawToOther, self.withdraw_to_other),
+            (TxType::FullExit, self.full_exit),
+            (TxType::ChangePubKey, self.change_pubkey),
+            (TxType::MintNFT, self.mint_nft),
+            (TxType::TransferNFT, self.transfer_nft),
+            (TxType::WithdrawNFT, self.withdraw_nft),
+            (TxType::Swap, self.swap),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scenario_example() {
+        let scenario = Scenario::load(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/scenarios/example.toml"
+        ))
+        .unwrap();
+
+        assert_eq!(scenario.accounts_amount, 40);
+        assert_eq!(scenario.tokens, vec!["DAI".to_string(), "wBTC".to_string()]);
+        assert_eq!(scenario.main_token(), "DAI");
+        assert_eq!(scenario.command_interval(), Some(Duration::from_secs(2)));
+        assert_eq!(scenario.duration(), Some(Duration::from_secs(600)));
+        assert_eq!(scenario.estimated_operations_per_account(), 300);
+        assert_eq!(scenario.tx_weights.full_exit, 0.0);
+        assert_eq!(scenario.chaos.wrong_nonce, 0.02);
+        assert_eq!(scenario.chaos.min_valid_tps, Some(5.0));
+        // Omitted fields are taken from the default scenario.
+        assert_eq!(scenario.min_batch_size, Scenario::default().min_batch_size);
+    }
+
+    #[test]
+    fn scenario_nft_and_swaps() {
+        let scenario = Scenario::load(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/scenarios/nft_and_swaps.toml"
+        ))
+        .unwrap();
+
+        assert!(scenario.has_swaps());
+        assert_eq!(scenario.swap_token_buy("DAI"), "wBTC");
+        assert_eq!(scenario.swap_token_buy("wBTC"), "DAI");
+        assert_eq!(scenario.tx_weights.mint_nft, 4.0);
+        assert!(!Scenario::default().has_swaps());
+    }
+
+    #[test]
+    fn scenario_soak() {
+        let scenario =
+            Scenario::load(concat!(env!("CARGO_MANIFEST_DIR"), "/scenarios/soak.toml")).unwrap();
+
+        let soak = scenario.soak.unwrap();
+        assert_eq!(scenario.duration(), Some(Duration::from_secs(6 * 3600)));
+        assert_eq!(soak.metrics_endpoints.len(), 2);
+        assert_eq!(soak.sample_interval(), Duration::from_secs(60));
+        assert_eq!(soak.thresholds[0].metric, "process_resident_memory_bytes");
+        assert!(soak.thresholds[0].labels.is_empty());
+        assert_eq!(soak.thresholds[0].max_growth_percent, Some(25.0));
+        assert_eq!(soak.thresholds[1].labels["component"], "prover");
+        assert_eq!(soak.thresholds[2].max_growth_per_hour, Some(1e9));
+        assert!(Scenario::default().soak.is_none());
+    }
+
+    #[test]
+    fn scenario_defaults() {
+        assert_eq!(Scenario::from_toml("").unwrap(), Scenario::default());
+        Scenario::default().validate().unwrap();
+    }
+
+    #[test]
+    fn default_chaos() {
+        // By default, 90% of the transactions are valid.
+        let weighted = IncorrectnessModifier::all_weighted(&ChaosConfig::default());
+        let (modifier, chance) = weighted[weighted.len() - 1];
+        assert_eq!(modifier, IncorrectnessModifier::None);
+        assert!((chance - 0.9).abs() < 1e-6);
+    }
+
+    #[test]
+    fn invalid_scenarios() {
+        for contents in &[
+            "accounts_amount = 0",
+            "tokens = []",
+            "batch_chance = 1.5",
+            "min_batch_size = 1",
+            "max_batch_size = 100",
+            "tps_target = 0.0",
+            "unknown_field = 1",
+            "[tx_weights]\ndeposit = -1.0",
+            "[chaos]\nwrong_nonce = -0.1",
+            "[chaos]\ninvalid_signature = 0.5\nunderpriced_fee = 0.5",
+            "[chaos]\noversized_batch_size = 10",
+            "[tx_weights]\nswap = 1.0",
+            // Soak test requires the constant load for the fixed time.
+            "[soak]\nmetrics_endpoints = [\"http://127.0.0.1:3312/metrics\"]\n\
+             [[soak.thresholds]]\nmetric = \"mempool_size\"\nmax_value = 1000.0",
+            "tps_target = 10.0\nduration_secs = 600\n[soak]\n[[soak.thresholds]]\n\
+             metric = \"mempool_size\"\nmax_value = 1000.0",
+            "tps_target = 10.0\nduration_secs = 600\n[soak]\n\
+             metrics_endpoints = [\"http://127.0.0.1:3312/metrics\"]\n\
+             [[soak.thresholds]]\nmetric = \"mempool_size\"",
+            "[tx_weights]\ntransfer_to_new = 0.0\ntransfer_to_existing = 0.0\n\
+             withdraw_to_self = 0.0\nwithdraw_to_other = 0.0\nchange_pubkey = 0.0",
+        ] {
+            assert!(Scenario::from_toml(contents).is_err(), "{}", contents);
+        }
+    }
+}
@@ -39,6 +39,22 @@ impl AccountSet {
         }
     }
 
+    /// Deposits the ERC20 token supporting EIP-2612 using the permit instead of the approve transaction.
+    pub async fn deposit_with_permit(
+        &self,
+        from: ETHAccountId,
+        to: ZKSyncAccountId,
+        token: Address,
+        amount: BigUint,
+    ) -> (Vec<TransactionReceipt>, PriorityOp) {
+        let from = &self.eth_accounts[from.0];
+        let to = &self.zksync_accounts[to.0];
+
+        from.deposit_erc20_with_permit(token, amount, &to.address)
+            .await
+            .expect("erc20 deposit with permit should not fail")
+    }
+
     pub async fn deposit_to_random(
         &self,
         from: ETHAccountId,
@@ -1,13 +1,19 @@
+use std::thread::JoinHandle;
+
+use futures::channel::oneshot;
+use tokio::task;
 use web3::transports::Http;
 
 use zksync_core::state_keeper::ZkSyncStateInitParams;
-use zksync_types::block::Block;
+use zksync_types::{block::Block, Address};
 
 use zksync_testkit::zksync_account::ZkSyncETHAccountData;
 use zksync_testkit::*;
 use zksync_testkit::{
     data_restore::verify_restore,
     scenarios::{perform_basic_operations, BlockProcessing},
+    server_storage::ServerStorage,
+    state_keeper_utils::StateKeeperChannels,
 };
 use zksync_types::{BlockNumber, Nonce, TokenId};
 
@@ -73,6 +79,7 @@ fn create_test_setup_state(
 
 async fn execute_blocks(
     test_setup: &mut TestSetup,
+    storage: &ServerStorage,
     start_block_number: BlockNumber,
     number_of_verified_iteration_blocks: u16, // Each operation generate 4 blocks
     number_of_committed_iteration_blocks: u16,
@@ -136,6 +143,12 @@ async fn execute_blocks(
         [(*reverted_state.last_block_number - *start_block_number - 1) as usize]
         .clone();
 
+    storage
+        .send_operations(
+            &executed_blocks,
+            (number_of_verified_iteration_blocks * 4) as usize,
+        )
+        .await;
     test_setup
         .revert_blocks(&executed_blocks_reverse_order)
         .await
@@ -144,11 +157,82 @@ async fn execute_blocks(
     (reverted_state, test_setup_accounts, executed_block)
 }
 
+/// State keeper of the testkit along with the committer storing its blocks.
+struct StateKeeper {
+    handler: JoinHandle<()>,
+    stop_sender: oneshot::Sender<()>,
+    committer: task::JoinHandle<()>,
+}
+
+impl StateKeeper {
+    fn spawn(
+        storage: &ServerStorage,
+        fee_account: &Address,
+        state: ZkSyncStateInitParams,
+    ) -> (Self, StateKeeperChannels) {
+        let (handler, stop_sender, channels) = spawn_state_keeper(fee_account, state);
+        let (channels, committer) = storage.commit_blocks(channels);
+        let state_keeper = Self {
+            handler,
+            stop_sender,
+            committer,
+        };
+        (state_keeper, channels)
+    }
+
+    /// Stops the state keeper and waits for its blocks to be stored.
+    async fn stop(self, storage: &ServerStorage) {
+        self.stop_sender.send(()).expect("sk stop send");
+        self.handler.join().expect("sk thread join");
+        storage.wait_for_committer(self.committer).await;
+    }
+}
+
+/// Checks that the contract and the restarted state keeper agree on the state after the revert
+/// of the unverified blocks: the last kept block is the last committed and verified one.
+async fn check_recovered_state(test_setup: &mut TestSetup, last_block: &Block) {
+    let last_block_number = *last_block.block_number as u64;
+    let committed = test_setup
+        .total_blocks_committed()
+        .await
+        .expect("total_blocks_committed call fails");
+    let verified = test_setup
+        .total_blocks_verified()
+        .await
+        .expect("total_blocks_verified call fails");
+    assert_eq!(
+        committed, last_block_number,
+        "Reverted blocks are still committed on the contract"
+    );
+    assert_eq!(
+        verified, last_block_number,
+        "Verified blocks are affected by the revert"
+    );
+    assert!(
+        !test_setup.is_exodus().await,
+        "Revert triggered the exodus mode"
+    );
+
+    let state = test_setup.get_current_state().await;
+    assert_eq!(state.last_block_number, last_block.block_number);
+    assert_eq!(
+        state.state.root_hash(),
+        last_block.new_root_hash,
+        "State keeper state doesn't match the last kept block"
+    );
+}
+
 async fn revert_blocks_test() {
     let fee_account = ZkSyncAccount::rand();
     let test_config = TestkitConfig::from_env();
 
-    let state = genesis_state(&fee_account.address);
+    let storage = ServerStorage::genesis(fee_account.address).await;
+    let state = storage.restore_state().await;
+    assert_eq!(
+        state.state.root_hash(),
+        genesis_state(&fee_account.address).state.root_hash(),
+        "Genesis state in the database doesn't match the testkit one"
+    );
 
     println!("deploying contracts");
     let contracts = deploy_contracts(false, state.state.root_hash());
@@ -158,7 +242,7 @@ async fn revert_blocks_test() {
         create_test_setup_state(&test_config, &contracts, &fee_account);
 
     let hash = state.state.root_hash();
-    let (handler, sender, channels) = spawn_state_keeper(&fee_account.address, state);
+    let (state_keeper, channels) = StateKeeper::spawn(&storage, &fee_account.address, state);
     let mut test_setup = TestSetup::new(
         channels,
         account_set.clone(),
@@ -177,24 +261,25 @@ async fn revert_blocks_test() {
     iteration += 1;
     println!("Iteration: {}", iteration);
     let (state, account_set, last_block) =
-        execute_blocks(&mut test_setup, BlockNumber(0), 1, 3, 2).await;
+        execute_blocks(&mut test_setup, &storage, BlockNumber(0), 1, 3, 2).await;
     println!("Iteration {} completed, recreating state...", iteration);
 
-    sender.send(()).expect("sk stop send");
-    handler.join().expect("sk thread join");
+    state_keeper.stop(&storage).await;
+    storage.revert_blocks(last_block.block_number).await;
+    let state = storage.check_reverted(&last_block, &state).await;
     let hash = state.state.root_hash();
     let start_block_number = state.last_block_number;
 
-    let (handler, sender, channels) = spawn_state_keeper(&fee_account.address, state);
-
+    let (state_keeper, channels) = StateKeeper::spawn(&storage, &fee_account.address, state);
     let mut test_setup = TestSetup::new(
         channels,
         account_set.clone(),
         &contracts,
         commit_account.clone(),
         hash,
-        Some(last_block),
+        Some(last_block.clone()),
     );
+    check_recovered_state(&mut test_setup, &last_block).await;
 
     // Verify 2
     // Commit 3
@@ -203,36 +288,40 @@ async fn revert_blocks_test() {
     iteration += 1;
     println!("Iteration: {}", iteration);
     let (state, account_set, last_block) =
-        execute_blocks(&mut test_setup, start_block_number, 2, 3, 2).await;
+        execute_blocks(&mut test_setup, &storage, start_block_number, 2, 3, 2).await;
     println!("Iteration {} completed, recreating state...", iteration);
 
-    sender.send(()).expect("sk stop send");
-    handler.join().expect("sk thread join");
-
+    state_keeper.stop(&storage).await;
+    storage.revert_blocks(last_block.block_number).await;
+    let state = storage.check_reverted(&last_block, &state).await;
     let hash = state.state.root_hash();
     let start_block_number = state.last_block_number;
 
-    let (handler, sender, channels) = spawn_state_keeper(&fee_account.address, state);
-
+    let (state_keeper, channels) = StateKeeper::spawn(&storage, &fee_account.address, state);
     let mut test_setup = TestSetup::new(
         channels,
         account_set.clone(),
         &contracts,
         commit_account.clone(),
         hash,
-        Some(last_block),
+        Some(last_block.clone()),
     );
+    check_recovered_state(&mut test_setup, &last_block).await;
+
     // Verify 1
     // Commit 1
     // Revert 0
     // Do not revert blocks for verifying restore
     iteration += 1;
     println!("Iteration: {}", iteration);
-    let (state, _, _) = execute_blocks(&mut test_setup, start_block_number, 1, 1, 0).await;
+    let (state, _, last_block) =
+        execute_blocks(&mut test_setup, &storage, start_block_number, 1, 1, 0).await;
     println!("Iteration {} completed, recreating state...", iteration);
 
-    sender.send(()).expect("sk stop send");
-    handler.join().expect("sk thread join");
+    state_keeper.stop(&storage).await;
+    // Nothing was reverted on the contract, so all the blocks are kept in the database.
+    storage.revert_blocks(last_block.block_number).await;
+    storage.check_reverted(&last_block, &state).await;
 
     println!("Verifying restored state");
     verify_restore(
@@ -0,0 +1,606 @@
+//! In-process end-to-end simulation of the zkSync server.
+//!
+//! Wires the server components together in a single process on the embedded database,
+//! so the protocol changes can be exercised end-to-end in seconds without docker-compose and geth:
+//!
+//! - storage is the embedded Postgres database, see `zksync_storage::embedded`, initialized with the genesis block;
+//! - mempool is the real one, storing the submitted operations in the database and proposing the miniblocks;
+//! - state keeper, root hash calculator, committer and tx event emitter are the real ones, the state keeper
+//!   is run in the testkit mode, so the blocks are sealed by the simulation;
+//! - Ethereum is emulated by the in-memory contract checking the committed, proven and executed blocks;
+//! - witness generator builds the real block witness over the circuit account tree for the stored blocks;
+//! - dummy prover accepts the witness without generating the actual proof.
+//!
+//! The simulation deposits funds to the test accounts, sets their signing keys and then produces
+//! blocks with the transfers and withdrawals, checking the receipts and the final state of every account
+//! stored in the database.
+//!
+//! The chain parameters are loaded from the environment, `DATABASE_URL` must not be set.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use anyhow::{ensure, format_err};
+use futures::{
+    channel::{mpsc, oneshot},
+    SinkExt,
+};
+use num::BigUint;
+use structopt::StructOpt;
+use tokio::time;
+
+use zksync_circuit::witness::utils::build_block_witness;
+use zksync_config::{configs::chain::BlockSealCriteria, ChainConfig};
+use zksync_core::{
+    committer::{run_committer, AggregatedProofSizes},
+    genesis::create_genesis_block,
+    state_keeper::{
+        start_root_hash_calculator, StateKeeperTestkitRequest, ZkSyncStateInitParams,
+        ZkSyncStateKeeper,
+    },
+    tx_event_emitter::run_tx_event_emitter_task,
+    wait_for_tasks,
+};
+use zksync_crypto::{circuit::CircuitAccountTree, params::account_tree_depth, Fr};
+use zksync_mempool::{
+    run_mempool_block_handler, run_mempool_tx_handler, GetBlockRequest, MempoolBlocksRequest,
+    MempoolTransactionRequest,
+};
+use zksync_storage::ConnectionPool;
+use zksync_testkit::zksync_account::ZkSyncAccount;
+use zksync_types::{
+    block::{Block, ExecutedOperations},
+    tx::{ChangePubKeyType, SignedZkSyncTx, TxHash},
+    Account, AccountId, Address, BlockNumber, Deposit, PriorityOp, SerialId, TokenId, ZkSyncOp,
+    ZkSyncPriorityOp, ZkSyncTx, H256,
+};
+
+const ETH_TOKEN: TokenId = TokenId(0);
+/// Capacity of the channels between the components.
+const CHANNEL_CAPACITY: usize = 256;
+/// Amount of the database connections shared by the components.
+const POOL_SIZE: u32 = 10;
+/// Time given to the root hash calculator and the committer to store the sealed block.
+const BLOCK_STORE_TIMEOUT: Duration = Duration::from_secs(60);
+/// Interval of polling the database for the sealed block.
+const BLOCK_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "ZkSync in-process simulation", author = "Matter Labs")]
+struct Opt {
+    /// Amount of the test accounts.
+    #[structopt(long, default_value = "8")]
+    accounts: usize,
+
+    /// Amount of the blocks with transfers and withdrawals.
+    #[structopt(long, default_value = "10")]
+    blocks: usize,
+
+    /// Amount of the transactions in every block.
+    #[structopt(long, default_value = "20")]
+    txs_per_block: usize,
+}
+
+fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Current time is before the epoch")
+        .as_secs()
+}
+
+/// Proof of the dummy prover, it only carries the root hash the witness was built for.
+#[derive(Debug, Clone, Copy)]
+struct DummyProof {
+    block_number: BlockNumber,
+    root_hash: Fr,
+}
+
+/// Witness generator along with the dummy prover: builds the real witness for every block
+/// and checks that it results in the root hash calculated by the state keeper.
+struct WitnessGenerator {
+    account_tree: CircuitAccountTree,
+}
+
+impl WitnessGenerator {
+    fn new(accounts: Vec<(u32, Account)>) -> Self {
+        let mut account_tree = CircuitAccountTree::new(account_tree_depth());
+        for (id, account) in accounts {
+            account_tree.insert(id, account.into());
+        }
+
+        Self { account_tree }
+    }
+
+    fn prove(&mut self, block: &Block) -> anyhow::Result<DummyProof> {
+        let witness = build_block_witness(&mut self.account_tree, block)?;
+        let root_hash = witness.root_after_fees.ok_or_else(|| {
+            anyhow::anyhow!("Witness of block {} has no root hash", *block.block_number)
+        })?;
+        ensure!(
+            root_hash == block.new_root_hash,
+            "Witness root hash of block {} doesn't match the state keeper one",
+            *block.block_number
+        );
+
+        Ok(DummyProof {
+            block_number: block.block_number,
+            root_hash,
+        })
+    }
+}
+
+/// Ethereum emulation: the state of the zkSync contract, checking the blocks the same way it does.
+#[derive(Debug, Default)]
+struct InMemoryContract {
+    committed_blocks: Vec<(BlockNumber, Fr, (u64, u64))>,
+    total_blocks_proven: usize,
+    total_blocks_executed: usize,
+    total_priority_requests: u64,
+    total_committed_priority_requests: u64,
+    pending_balances: HashMap<(Address, TokenId), BigUint>,
+}
+
+impl InMemoryContract {
+    fn add_priority_request(&mut self) {
+        self.total_priority_requests += 1;
+    }
+
+    fn commit_block(&mut self, block: &Block) -> anyhow::Result<()> {
+        let expected_block_number = self.committed_blocks.len() as u32 + 1;
+        ensure!(
+            *block.block_number == expected_block_number,
+            "Committed block {}, expected {}",
+            *block.block_number,
+            expected_block_number
+        );
+        let (first_priority_op, last_priority_op) = block.processed_priority_ops;
+        ensure!(
+            first_priority_op == self.total_committed_priority_requests
+                && last_priority_op <= self.total_priority_requests,
+            "Block {} processes unexpected priority operations {:?}",
+            *block.block_number,
+            block.processed_priority_ops
+        );
+
+        self.total_committed_priority_requests = last_priority_op;
+        self.committed_blocks.push((
+            block.block_number,
+            block.new_root_hash,
+            block.processed_priority_ops,
+        ));
+        Ok(())
+    }
+
+    fn prove_block(&mut self, proof: DummyProof) -> anyhow::Result<()> {
+        let (block_number, root_hash, _) = self
+            .committed_blocks
+            .get(self.total_blocks_proven)
+            .copied()
+            .ok_or_else(|| anyhow::anyhow!("No committed blocks to prove"))?;
+        ensure!(
+            proof.block_number == block_number && proof.root_hash == root_hash,
+            "Proof of block {} doesn't match the committed block {}",
+            *proof.block_number,
+            *block_number
+        );
+
+        self.total_blocks_proven += 1;
+        Ok(())
+    }
+
+    fn execute_block(&mut self, block: &Block) -> anyhow::Result<()> {
+        ensure!(
+            self.total_blocks_executed < self.total_blocks_proven,
+            "Block {} is executed before being proven",
+            *block.block_number
+        );
+
+        for op in block
+            .block_transactions
+            .iter()
+            .filter_map(ExecutedOperations::get_executed_op)
+        {
+            if let ZkSyncOp::Withdraw(op) = op {
+                *self
+                    .pending_balances
+                    .entry((op.tx.to, op.tx.token))
+                    .or_default() += &op.tx.amount;
+            }
+        }
+
+        self.total_blocks_executed += 1;
+        Ok(())
+    }
+
+    fn pending_balance(&self, address: Address, token: TokenId) -> BigUint {
+        self.pending_balances
+            .get(&(address, token))
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+/// Server emulation: the real components running on the embedded database along with the in-memory contract.
+struct Simulation {
+    pool: ConnectionPool,
+    state_keeper: mpsc::Sender<StateKeeperTestkitRequest>,
+    mempool_txs: mpsc::Sender<MempoolTransactionRequest>,
+    mempool_blocks: mpsc::Sender<MempoolBlocksRequest>,
+    contract: InMemoryContract,
+    witness_generator: WitnessGenerator,
+    next_serial_id: SerialId,
+    /// Serial ID of the first priority operation not yet executed by the state keeper.
+    unprocessed_priority_op: u64,
+    last_block_number: BlockNumber,
+}
+
+impl Simulation {
+    /// Adds the confirmed deposit to the mempool, as if it was reported by the Ethereum watcher.
+    async fn deposit(&mut self, to: Address, amount: BigUint) -> anyhow::Result<()> {
+        let serial_id = self.next_serial_id;
+        self.next_serial_id += 1;
+        let op = PriorityOp {
+            serial_id,
+            data: ZkSyncPriorityOp::Deposit(Deposit {
+                from: to,
+                token: ETH_TOKEN,
+                amount,
+                to,
+            }),
+            deadline_block: i64::MAX as u64,
+            eth_hash: H256::from_low_u64_be(serial_id),
+            eth_block: 0,
+            eth_block_index: Some(0),
+        };
+
+        let (sender, receiver) = oneshot::channel();
+        self.mempool_txs
+            .send(MempoolTransactionRequest::NewPriorityOps(
+                vec![op],
+                true,
+                sender,
+            ))
+            .await?;
+        receiver.await??;
+        self.contract.add_priority_request();
+        Ok(())
+    }
+
+    async fn submit_tx(&mut self, tx: ZkSyncTx) -> anyhow::Result<TxHash> {
+        let tx = SignedZkSyncTx::from(tx);
+        let tx_hash = tx.hash();
+
+        let (sender, receiver) = oneshot::channel();
+        self.mempool_txs
+            .send(MempoolTransactionRequest::NewTx(Box::new(tx), sender))
+            .await?;
+        receiver.await??;
+        Ok(tx_hash)
+    }
+
+    /// Executes the miniblocks proposed by the mempool until it's empty and seals the block, then passes
+    /// the blocks stored by the committer through the witness generation, commitment, proving and execution.
+    /// Returns the sealed blocks, there are several of them if the state keeper sealed the full ones.
+    async fn produce_blocks(&mut self) -> anyhow::Result<Vec<Block>> {
+        let mut executed_txs = Vec::new();
+        loop {
+            let (response_sender, response) = oneshot::channel();
+            self.mempool_blocks
+                .send(MempoolBlocksRequest::GetBlock(GetBlockRequest {
+                    last_priority_op_number: self.unprocessed_priority_op,
+                    block_timestamp: unix_timestamp(),
+                    // Executed transactions are removed from the mempool by the committer asynchronously.
+                    executed_txs: executed_txs.clone(),
+                    response_sender,
+                }))
+                .await?;
+            let miniblock = response.await?;
+            if miniblock.is_empty() {
+                break;
+            }
+
+            self.unprocessed_priority_op += miniblock.priority_ops.len() as u64;
+            for tx in &miniblock.txs {
+                executed_txs.extend(tx.hashes());
+            }
+            self.state_keeper
+                .send(StateKeeperTestkitRequest::ExecuteMiniBlock(miniblock))
+                .await?;
+        }
+        self.state_keeper
+            .send(StateKeeperTestkitRequest::SealBlock)
+            .await?;
+
+        let (sender, receiver) = oneshot::channel();
+        self.state_keeper
+            .send(StateKeeperTestkitRequest::GetCurrentState(sender))
+            .await?;
+        let sealed_block_number = receiver.await?.last_block_number;
+
+        let mut blocks = Vec::new();
+        while self.last_block_number < sealed_block_number {
+            let block = self.await_stored_block(self.last_block_number + 1).await?;
+            self.contract.commit_block(&block)?;
+            let proof = self.witness_generator.prove(&block)?;
+            self.contract.prove_block(proof)?;
+            self.contract.execute_block(&block)?;
+
+            self.last_block_number = block.block_number;
+            blocks.push(block);
+        }
+        Ok(blocks)
+    }
+
+    /// Waits for the block to be finished, i.e. stored by the committer along with its root hash.
+    async fn await_stored_block(&self, block_number: BlockNumber) -> anyhow::Result<Block> {
+        let poll = async {
+            loop {
+                let block = self
+                    .pool
+                    .access_storage()
+                    .await?
+                    .chain()
+                    .block_schema()
+                    .get_block(block_number)
+                    .await?;
+                if let Some(block) = block {
+                    return Ok::<_, anyhow::Error>(block);
+                }
+                time::sleep(BLOCK_POLL_INTERVAL).await;
+            }
+        };
+        time::timeout(BLOCK_STORE_TIMEOUT, poll)
+            .await
+            .map_err(|_| {
+                format_err!(
+                    "Block {} wasn't stored in {:?}",
+                    *block_number,
+                    BLOCK_STORE_TIMEOUT
+                )
+            })?
+    }
+
+    async fn get_account(&self, address: Address) -> anyhow::Result<Option<(AccountId, Account)>> {
+        let account = self
+            .pool
+            .access_storage()
+            .await?
+            .chain()
+            .account_schema()
+            .account_state_by_address(address)
+            .await?
+            .committed;
+        Ok(account)
+    }
+
+    /// Checks the receipt of the transaction stored by the committer.
+    async fn check_receipt(&self, tx_hash: &TxHash) -> anyhow::Result<()> {
+        let receipt = self
+            .pool
+            .access_storage()
+            .await?
+            .chain()
+            .operations_ext_schema()
+            .tx_receipt(tx_hash.as_ref())
+            .await?
+            .ok_or_else(|| format_err!("Tx {} wasn't executed", tx_hash.to_string()))?;
+        ensure!(
+            receipt.success,
+            "Tx {} failed in block {}: {}",
+            tx_hash.to_string(),
+            receipt.block_number,
+            receipt.fail_reason.unwrap_or_default()
+        );
+        Ok(())
+    }
+}
+
+/// Creates the genesis block in the database and starts the server components on top of it.
+async fn start_simulation(
+    config: &ChainConfig,
+    fee_account: Address,
+) -> anyhow::Result<(Simulation, Vec<tokio::task::JoinHandle<()>>)> {
+    let pool = ConnectionPool::new(Some(POOL_SIZE));
+    create_genesis_block(pool.clone(), &fee_account).await;
+
+    let chunk_sizes = config.state_keeper.block_chunk_sizes.clone();
+    let initial_state = ZkSyncStateInitParams::restore_from_db(
+        &mut pool.access_storage().await?,
+        fee_account,
+        &chunk_sizes,
+    )
+    .await;
+    let witness_generator = WitnessGenerator::new(initial_state.state.get_accounts());
+    let last_block_number = initial_state.last_block_number;
+
+    let (commit_requests_sender, commit_requests_receiver) = mpsc::channel(CHANNEL_CAPACITY);
+    let (state_keeper_sender, state_keeper_receiver) = mpsc::channel(CHANNEL_CAPACITY);
+    let (mempool_tx_sender, mempool_tx_receiver) = mpsc::channel(CHANNEL_CAPACITY);
+    let (mempool_block_sender, mempool_block_receiver) = mpsc::channel(CHANNEL_CAPACITY);
+    let (processed_tx_events_sender, processed_tx_events_receiver) =
+        mpsc::channel(CHANNEL_CAPACITY);
+    // Deadline monitoring is disabled by the seal criteria, so eth watch requests are never sent.
+    let (eth_watch_req_sender, _eth_watch_req_receiver) = mpsc::channel(CHANNEL_CAPACITY);
+
+    // Blocks are sealed by the simulation, so the state keeper never seals them on its own unless they're full.
+    let (state_keeper, root_hash_calculator) = ZkSyncStateKeeper::new(
+        initial_state,
+        fee_account,
+        commit_requests_sender,
+        mempool_block_sender.clone(),
+        chunk_sizes.clone(),
+        BlockSealCriteria::with_iterations(usize::MAX, usize::MAX),
+        eth_watch_req_sender,
+        processed_tx_events_sender,
+    );
+    let aggregated_proof_sizes = AggregatedProofSizes::new(
+        config.state_keeper.aggregated_proof_sizes.clone(),
+        config.circuit.supported_aggregated_proof_sizes.clone(),
+    );
+
+    let tasks = vec![
+        tokio::spawn(state_keeper.run_for_testkit(state_keeper_receiver)),
+        start_root_hash_calculator(root_hash_calculator),
+        run_committer(
+            commit_requests_receiver,
+            pool.clone(),
+            config.clone(),
+            aggregated_proof_sizes,
+        ),
+        run_tx_event_emitter_task(pool.clone(), processed_tx_events_receiver),
+        run_mempool_tx_handler(
+            pool.clone(),
+            mempool_tx_receiver,
+            chunk_sizes.clone(),
+            config.state_keeper.mempool_capacity,
+        ),
+        run_mempool_block_handler(pool.clone(), mempool_block_receiver, chunk_sizes),
+    ];
+
+    let simulation = Simulation {
+        pool,
+        state_keeper: state_keeper_sender,
+        mempool_txs: mempool_tx_sender,
+        mempool_blocks: mempool_block_sender,
+        contract: InMemoryContract::default(),
+        witness_generator,
+        next_serial_id: 0,
+        unprocessed_priority_op: 0,
+        last_block_number,
+    };
+    Ok((simulation, tasks))
+}
+
+async fn run_simulation(simulation: &mut Simulation, opt: Opt) -> anyhow::Result<()> {
+    let accounts: Vec<_> = (0..opt.accounts as u32)
+        .map(|i| ZkSyncAccount::rand_with_seed([i + 5, 6, 7, 8]))
+        .collect();
+    let deposit_amount = BigUint::from(1_000_000u32);
+    let mut expected_balances = vec![deposit_amount.clone(); accounts.len()];
+    let mut withdrawn = vec![BigUint::from(0u32); accounts.len()];
+    let mut tx_hashes = Vec::new();
+
+    let started_at = Instant::now();
+
+    // Fund the accounts and set their signing keys.
+    for account in &accounts {
+        simulation
+            .deposit(account.address, deposit_amount.clone())
+            .await?;
+    }
+    simulation.produce_blocks().await?;
+    for account in &accounts {
+        let (account_id, _) = simulation
+            .get_account(account.address)
+            .await?
+            .ok_or_else(|| format_err!("Deposit didn't create the account"))?;
+        account.set_account_id(Some(account_id));
+
+        let change_pubkey = account.sign_change_pubkey_tx(
+            None,
+            true,
+            ETH_TOKEN,
+            0u32.into(),
+            ChangePubKeyType::ECDSA,
+            Default::default(),
+        );
+        tx_hashes.push(simulation.submit_tx(change_pubkey.into()).await?);
+    }
+    simulation.produce_blocks().await?;
+
+    // Move funds between the accounts, every account withdraws a bit once in a while.
+    for block_idx in 0..opt.blocks {
+        for tx_idx in 0..opt.txs_per_block {
+            let from = (block_idx + tx_idx) % accounts.len();
+            let amount = BigUint::from((tx_idx + 1) as u32);
+
+            let tx: ZkSyncTx = if tx_idx == 0 {
+                let (withdraw, _) = accounts[from].sign_withdraw(
+                    ETH_TOKEN,
+                    "ETH",
+                    amount.clone(),
+                    0u32.into(),
+                    &accounts[from].address,
+                    None,
+                    true,
+                    Default::default(),
+                );
+                withdrawn[from] += &amount;
+                withdraw.into()
+            } else {
+                let to = (from + 1 + block_idx % (accounts.len() - 1)) % accounts.len();
+                let (transfer, _) = accounts[from].sign_transfer(
+                    ETH_TOKEN,
+                    "ETH",
+                    amount.clone(),
+                    0u32.into(),
+                    &accounts[to].address,
+                    None,
+                    true,
+                    Default::default(),
+                );
+                expected_balances[to] += &amount;
+                transfer.into()
+            };
+            expected_balances[from] -= &amount;
+            tx_hashes.push(simulation.submit_tx(tx).await?);
+        }
+
+        for block in simulation.produce_blocks().await? {
+            vlog::info!(
+                "Block {} is committed, proven and executed ({} operations, {} chunks)",
+                *block.block_number,
+                block.block_transactions.len(),
+                block.block_chunks_size
+            );
+        }
+    }
+
+    // Check that every transaction succeeded and the stored state matches the expected one.
+    for tx_hash in &tx_hashes {
+        simulation.check_receipt(tx_hash).await?;
+    }
+    for (idx, account) in accounts.iter().enumerate() {
+        let (_, state) = simulation
+            .get_account(account.address)
+            .await?
+            .ok_or_else(|| format_err!("Account {} is missing", account.address))?;
+        ensure!(
+            state.get_balance(ETH_TOKEN) == expected_balances[idx],
+            "Unexpected balance of the account {}",
+            account.address
+        );
+        ensure!(
+            simulation
+                .contract
+                .pending_balance(account.address, ETH_TOKEN)
+                == withdrawn[idx],
+            "Unexpected pending balance of the account {}",
+            account.address
+        );
+    }
+
+    vlog::info!(
+        "Simulation completed: {} blocks, {} transactions in {:?}",
+        *simulation.last_block_number,
+        tx_hashes.len(),
+        started_at.elapsed()
+    );
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let _vlog_guard = vlog::init();
+
+    let opt = Opt::from_args();
+    ensure!(opt.accounts >= 2, "At least 2 accounts are required");
+    let config = ChainConfig::from_env();
+    let fee_account = ZkSyncAccount::rand_with_seed([1, 2, 3, 4]);
+
+    let (mut simulation, tasks) = start_simulation(&config, fee_account.address).await?;
+    tokio::select! {
+        result = run_simulation(&mut simulation, opt) => result,
+        _ = wait_for_tasks(tasks) => anyhow::bail!("Server components stopped"),
+    }
+}
@@ -8,10 +8,11 @@ use std::convert::TryFrom;
 use std::str::FromStr;
 use web3::{
     contract::Options,
+    signing::{keccak256, Key, SecretKey, SecretKeyRef},
     transports::Http,
     types::{TransactionReceipt, H256, U128, U256, U64},
 };
-use zksync_contracts::{erc20_contract, zksync_contract};
+use zksync_contracts::{erc20_contract, erc20_permit_contract, zksync_contract};
 use zksync_crypto::proof::EncodedSingleProof;
 use zksync_eth_client::ETHDirectClient;
 use zksync_eth_signer::PrivateKeySigner;
@@ -362,6 +363,94 @@ impl EthereumAccount {
         Ok((vec![approve_receipt, receipt], priority_op))
     }
 
+    /// Deposits the ERC20 token supporting EIP-2612 with a single transaction, the allowance
+    /// is set by the permit signed by the account instead of the separate approve transaction.
+    pub async fn deposit_erc20_with_permit(
+        &self,
+        token_contract: Address,
+        amount: BigUint,
+        to: &Address,
+    ) -> Result<(Vec<TransactionReceipt>, PriorityOp), anyhow::Error> {
+        let amount = big_dec_to_u256(amount);
+        let deadline = U256::max_value();
+        let (v, r, s) = self.sign_permit(token_contract, amount, deadline).await?;
+
+        let data = self.main_contract_eth_client.encode_tx_data(
+            "depositERC20WithPermit",
+            (token_contract, amount, *to, deadline, v, r, s),
+        );
+        let signed_tx = self
+            .main_contract_eth_client
+            .sign_prepared_tx(data, default_tx_options())
+            .await
+            .map_err(|e| format_err!("Deposit erc20 with permit send err: {}", e))?;
+        let receipt =
+            send_raw_tx_wait_confirmation(&self.main_contract_eth_client, signed_tx.raw_tx).await?;
+        let exec_result = ETHExecResult::new(receipt, &self.main_contract_eth_client).await;
+        let receipt = exec_result.success_result()?;
+        let priority_op = priority_op_from_tx_logs(&receipt)
+            .expect("no priority op log in deposit erc20 with permit");
+        Ok((vec![receipt], priority_op))
+    }
+
+    /// Signs the EIP-2612 permit allowing the zkSync contract to spend `value` tokens of the account.
+    /// Returns the `v`, `r` and `s` components of the signature.
+    async fn sign_permit(
+        &self,
+        token_contract: Address,
+        value: U256,
+        deadline: U256,
+    ) -> Result<(u8, H256, H256), anyhow::Error> {
+        const PERMIT_TYPE: &str =
+            "Permit(address owner,address spender,uint256 value,uint256 nonce,uint256 deadline)";
+
+        let nonce: U256 = self
+            .main_contract_eth_client
+            .call_contract_function(
+                "nonces",
+                self.address,
+                None,
+                Options::default(),
+                None,
+                token_contract,
+                erc20_permit_contract(),
+            )
+            .await?;
+        let domain_separator: H256 = self
+            .main_contract_eth_client
+            .call_contract_function(
+                "DOMAIN_SEPARATOR",
+                (),
+                None,
+                Options::default(),
+                None,
+                token_contract,
+                erc20_permit_contract(),
+            )
+            .await?;
+
+        let struct_hash = keccak256(&ethabi::encode(&[
+            Token::FixedBytes(keccak256(PERMIT_TYPE.as_bytes()).to_vec()),
+            Token::Address(self.address),
+            Token::Address(self.main_contract_eth_client.contract_addr()),
+            Token::Uint(value),
+            Token::Uint(nonce),
+            Token::Uint(deadline),
+        ]));
+        let digest = keccak256(
+            &[
+                &[0x19, 0x01][..],
+                domain_separator.as_bytes(),
+                &struct_hash[..],
+            ]
+            .concat(),
+        );
+
+        let secret_key = SecretKey::from_slice(self.private_key.as_bytes())?;
+        let signature = SecretKeyRef::new(&secret_key).sign(&digest, None)?;
+        Ok((signature.v as u8, signature.r, signature.s))
+    }
+
     pub async fn commit_block(
         &self,
         commit_operation: &BlocksCommitOperation,
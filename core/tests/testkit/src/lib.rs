@@ -17,6 +17,7 @@ pub mod data_restore;
 pub mod eth_account;
 pub mod external_commands;
 pub mod scenarios;
+pub mod server_storage;
 pub mod state_keeper_utils;
 pub mod test_setup;
 pub mod types;
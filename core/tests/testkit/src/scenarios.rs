@@ -101,13 +101,32 @@ pub async fn perform_basic_tests() {
     let deposit_amount = parse_ether("1.0").unwrap();
 
     let token = TokenId(1);
-    let executed_blocks = perform_basic_operations(
+    let mut executed_blocks = perform_basic_operations(
         token,
         &mut test_setup,
         deposit_amount.clone(),
         BlockProcessing::CommitAndVerify,
     )
     .await;
+
+    // Deposit with the EIP-2612 permit creates an ordinary deposit priority operation,
+    // which has to be executed and restored the same way.
+    test_setup.start_block();
+    test_setup
+        .deposit_with_permit(
+            ETHAccountId(0),
+            ZKSyncAccountId(2),
+            Token(token),
+            deposit_amount.clone(),
+        )
+        .await;
+    let block = test_setup
+        .execute_commit_and_verify_block()
+        .await
+        .expect("Block execution failed")
+        .block;
+    executed_blocks.push(block);
+    println!("Deposit with permit test success, token_id: {}", *token);
     let tokens = vec![token];
 
     // Verify queued transactions events.
@@ -0,0 +1,347 @@
+//! Server storage mirrored by the testkit.
+//!
+//! The blocks produced by the testkit state keeper are stored in the embedded database by the real committer,
+//! and the operations sent to the contract are recorded the way the Ethereum sender records them,
+//! so the recovery of the server from the database can be checked against the contract state.
+
+use std::time::Duration;
+
+use futures::{channel::mpsc, SinkExt, StreamExt};
+use tokio::{task::JoinHandle, time};
+
+use zksync_core::{
+    committer::run_block_committer, genesis::create_genesis_block,
+    state_keeper::ZkSyncStateInitParams,
+};
+use zksync_crypto::proof::EncodedAggregatedProof;
+use zksync_storage::ConnectionPool;
+use zksync_types::{
+    aggregated_operations::{
+        AggregatedOperation, BlocksCommitOperation, BlocksExecuteOperation, BlocksProofOperation,
+    },
+    block::Block,
+    Address, BlockNumber, H256, U256,
+};
+
+use crate::state_keeper_utils::{block_chunk_sizes, StateKeeperChannels};
+
+/// Capacity of the channels between the state keeper, the committer and the test setup.
+const CHANNEL_CAPACITY: usize = 256;
+/// Amount of the database connections shared by the committer and the testkit.
+const POOL_SIZE: u32 = 4;
+/// Time given to the committer to store the block or to process all the requests after the state keeper stopped.
+const COMMITTER_TIMEOUT: Duration = Duration::from_secs(60);
+/// Interval of polling the database for the stored block.
+const BLOCK_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Server database along with the committer storing the blocks of the testkit state keeper.
+///
+/// The database is the embedded one, so `DATABASE_URL` must not be set.
+pub struct ServerStorage {
+    pool: ConnectionPool,
+    fee_account: Address,
+}
+
+impl ServerStorage {
+    /// Creates the genesis block and initializes the Ethereum sender data in the empty database.
+    pub async fn genesis(fee_account: Address) -> Self {
+        assert!(
+            std::env::var("DATABASE_URL").is_err(),
+            "Server storage of the testkit runs on the embedded database, `DATABASE_URL` must not be set"
+        );
+        let pool = ConnectionPool::new(Some(POOL_SIZE));
+        create_genesis_block(pool.clone(), &fee_account).await;
+        pool.access_storage()
+            .await
+            .expect("db connection failed")
+            .ethereum_schema()
+            .initialize_eth_data()
+            .await
+            .expect("failed to initialize the Ethereum data");
+
+        Self { pool, fee_account }
+    }
+
+    /// Restores the state keeper parameters from the database, the same way the server does on startup.
+    pub async fn restore_state(&self) -> ZkSyncStateInitParams {
+        let mut storage = self
+            .pool
+            .access_storage()
+            .await
+            .expect("db connection failed");
+        ZkSyncStateInitParams::restore_from_db(&mut storage, self.fee_account, &block_chunk_sizes())
+            .await
+    }
+
+    /// Passes the requests of the state keeper to the committer storing the blocks.
+    /// Returns the channels to create the test setup with and the handle of the task
+    /// completing once all the requests are processed after the state keeper is stopped.
+    pub fn commit_blocks(
+        &self,
+        channels: StateKeeperChannels,
+    ) -> (StateKeeperChannels, JoinHandle<()>) {
+        let (mut committer_sender, committer_receiver) = mpsc::channel(CHANNEL_CAPACITY);
+        let (mut new_blocks_sender, new_blocks_receiver) = mpsc::channel(CHANNEL_CAPACITY);
+        let committer = run_block_committer(committer_receiver, self.pool.clone());
+
+        let mut new_blocks = channels.new_blocks;
+        let task = tokio::spawn(async move {
+            while let Some(request) = new_blocks.next().await {
+                committer_sender
+                    .send(request.clone())
+                    .await
+                    .expect("committer receiver dropped");
+                // The test setup may be dropped before the state keeper, the blocks are stored anyway.
+                new_blocks_sender.send(request).await.unwrap_or_default();
+            }
+            drop(committer_sender);
+            committer.await.expect("committer task failed");
+        });
+
+        let channels = StateKeeperChannels {
+            new_blocks: new_blocks_receiver,
+            ..channels
+        };
+        (channels, task)
+    }
+
+    /// Waits for the committer to process all the requests of the stopped state keeper.
+    pub async fn wait_for_committer(&self, task: JoinHandle<()>) {
+        time::timeout(COMMITTER_TIMEOUT, task)
+            .await
+            .expect("committer didn't stop in time")
+            .expect("committer task failed");
+    }
+
+    /// Records the operations sent to the contract for the blocks: every block is committed,
+    /// and the first `verified_blocks` of them are also proven and executed.
+    pub async fn send_operations(&self, blocks: &[Block], verified_blocks: usize) {
+        for (idx, block) in blocks.iter().enumerate() {
+            let last_committed_block = self.await_block(block.block_number - 1).await;
+            self.await_block(block.block_number).await;
+            self.send_operation(
+                BlocksCommitOperation {
+                    last_committed_block,
+                    blocks: vec![block.clone()],
+                }
+                .into(),
+            )
+            .await;
+            if idx >= verified_blocks {
+                continue;
+            }
+
+            let mut proof = EncodedAggregatedProof::default();
+            proof.individual_vk_inputs[0] =
+                U256::from_big_endian(block.block_commitment.as_bytes());
+            self.send_operation(
+                BlocksProofOperation {
+                    blocks: vec![block.clone()],
+                    proof,
+                }
+                .into(),
+            )
+            .await;
+            self.send_operation(
+                BlocksExecuteOperation {
+                    blocks: vec![block.clone()],
+                }
+                .into(),
+            )
+            .await;
+        }
+    }
+
+    /// Stores the aggregated operation and passes it through the Ethereum sender workflow:
+    /// the operation is taken from the unprocessed ones, sent in the new Ethereum transaction and confirmed.
+    async fn send_operation(&self, operation: AggregatedOperation) {
+        let mut storage = self
+            .pool
+            .access_storage()
+            .await
+            .expect("db connection failed");
+        storage
+            .chain()
+            .operations_schema()
+            .store_aggregated_action(operation)
+            .await
+            .expect("failed to store the aggregated operation");
+
+        let new_operations = storage
+            .ethereum_schema()
+            .load_unprocessed_operations()
+            .await
+            .expect("failed to load the unprocessed operations");
+        for (id, operation) in new_operations {
+            let mut transaction = storage
+                .start_transaction()
+                .await
+                .expect("failed to start the db transaction");
+            let response = transaction
+                .ethereum_schema()
+                .save_new_eth_tx(
+                    operation.get_action_type(),
+                    Some((id, operation.clone())),
+                    0,
+                    Default::default(),
+                    Vec::new(),
+                    None,
+                )
+                .await
+                .expect("failed to save the Ethereum transaction");
+            transaction
+                .ethereum_schema()
+                .remove_unprocessed_operations(vec![id])
+                .await
+                .expect("failed to remove the unprocessed operation");
+            // Operation ids are unique, so are the hashes.
+            let hash = H256::from_low_u64_be(response.id as u64);
+            transaction
+                .ethereum_schema()
+                .add_hash_entry(response.id, &hash)
+                .await
+                .expect("failed to save the Ethereum transaction hash");
+
+            // The executed blocks become the verified state, see `confirm_operation` of the Ethereum sender database.
+            if let AggregatedOperation::ExecuteBlocks(operation) = &operation {
+                for block in &operation.blocks {
+                    transaction
+                        .chain()
+                        .state_schema()
+                        .apply_state_update(block.block_number)
+                        .await
+                        .expect("failed to apply the state update");
+                }
+            }
+            transaction
+                .ethereum_schema()
+                .confirm_eth_tx(&hash)
+                .await
+                .expect("failed to confirm the Ethereum transaction");
+            transaction
+                .commit()
+                .await
+                .expect("failed to commit the db transaction");
+        }
+    }
+
+    /// Reverts the blocks following `last_block` in the database, as `block_revert` does
+    /// after the blocks are reverted on the contract.
+    pub async fn revert_blocks(&self, last_block: BlockNumber) {
+        self.pool
+            .access_storage()
+            .await
+            .expect("db connection failed")
+            .chain()
+            .block_schema()
+            .revert_blocks(last_block)
+            .await
+            .expect("failed to revert the blocks in the database");
+    }
+
+    /// Checks that the server restored from the database after the revert continues from `last_block`:
+    /// the reverted blocks and their operations are gone, the Ethereum sender has nothing to resend,
+    /// and the restored state keeper state is `expected_state`. Returns the restored state.
+    pub async fn check_reverted(
+        &self,
+        last_block: &Block,
+        expected_state: &ZkSyncStateInitParams,
+    ) -> ZkSyncStateInitParams {
+        let mut storage = self
+            .pool
+            .access_storage()
+            .await
+            .expect("db connection failed");
+        let mut block_schema = storage.chain().block_schema();
+        assert_eq!(
+            block_schema.get_last_saved_block().await.unwrap(),
+            last_block.block_number,
+            "Reverted blocks are still saved"
+        );
+        assert_eq!(
+            block_schema.get_last_committed_block().await.unwrap(),
+            last_block.block_number,
+            "Reverted blocks are still committed"
+        );
+        assert_eq!(
+            block_schema
+                .get_last_committed_confirmed_block()
+                .await
+                .unwrap(),
+            last_block.block_number,
+            "Commitments of the reverted blocks are still confirmed"
+        );
+        assert!(
+            !block_schema.pending_block_exists().await.unwrap(),
+            "Pending block wasn't reverted"
+        );
+
+        // The Ethereum sender restores the operations that weren't sent on startup.
+        let mut ethereum_schema = storage.ethereum_schema();
+        ethereum_schema
+            .restore_unprocessed_operations()
+            .await
+            .unwrap();
+        assert!(
+            ethereum_schema
+                .load_unprocessed_operations()
+                .await
+                .unwrap()
+                .is_empty(),
+            "Operations of the reverted blocks would be sent again"
+        );
+        assert!(
+            ethereum_schema
+                .load_unconfirmed_operations()
+                .await
+                .unwrap()
+                .is_empty(),
+            "Transactions of the reverted blocks are still unconfirmed"
+        );
+        let stats = ethereum_schema.load_stats().await.unwrap();
+        let last_block_number = i64::from(*last_block.block_number);
+        assert_eq!(stats.last_committed_block, last_block_number);
+        assert!(stats.last_verified_block <= last_block_number);
+        assert!(stats.last_executed_block <= last_block_number);
+        drop(storage);
+
+        let state = self.restore_state().await;
+        assert_eq!(state.last_block_number, expected_state.last_block_number);
+        assert_eq!(
+            state.state.root_hash(),
+            last_block.new_root_hash,
+            "Restored state doesn't match the last kept block"
+        );
+        assert_eq!(state.state.root_hash(), expected_state.state.root_hash());
+        assert_eq!(
+            state.unprocessed_priority_op, expected_state.unprocessed_priority_op,
+            "Restored state has unexpected priority operations"
+        );
+        state
+    }
+
+    /// Waits for the block to be finished, i.e. stored by the committer along with its root hash.
+    async fn await_block(&self, block_number: BlockNumber) -> Block {
+        let poll = async {
+            loop {
+                let block = self
+                    .pool
+                    .access_storage()
+                    .await
+                    .expect("db connection failed")
+                    .chain()
+                    .block_schema()
+                    .get_block(block_number)
+                    .await
+                    .expect("failed to load the block");
+                if let Some(block) = block {
+                    return block;
+                }
+                time::sleep(BLOCK_POLL_INTERVAL).await;
+            }
+        };
+        time::timeout(COMMITTER_TIMEOUT, poll)
+            .await
+            .unwrap_or_else(|_| panic!("Block {} wasn't stored in time", *block_number))
+    }
+}
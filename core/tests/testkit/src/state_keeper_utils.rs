@@ -17,6 +17,7 @@ use zksync_types::{
 };
 
 use itertools::Itertools;
+use zksync_config::configs::chain::BlockSealCriteria;
 use zksync_mempool::MempoolBlocksRequest;
 
 pub async fn state_keeper_get_account(
@@ -38,16 +39,8 @@ pub struct StateKeeperChannels {
     pub queued_txs_events: mpsc::Receiver<ProcessedOperations>,
 }
 
-// Thread join handle and stop channel sender.
-pub fn spawn_state_keeper(
-    fee_account: &Address,
-    initial_state: ZkSyncStateInitParams,
-) -> (JoinHandle<()>, oneshot::Sender<()>, StateKeeperChannels) {
-    let (proposed_blocks_sender, proposed_blocks_receiver) = mpsc::channel(256);
-    let (state_keeper_req_sender, state_keeper_req_receiver) = mpsc::channel(256);
-    let (mempool_req_sender, mempool_req_receiver) = mpsc::channel(256);
-    let (processed_tx_events_sender, processed_tx_events_receiver) = mpsc::channel(256);
-
+/// Block sizes available to the testkit state keeper.
+pub fn block_chunk_sizes() -> Vec<usize> {
     let max_ops_in_block = 1000;
     let ops_chunks = vec![
         TransferToNewOp::CHUNKS,
@@ -62,7 +55,22 @@ pub fn spawn_state_keeper(
         .collect::<Vec<_>>();
     block_chunks_sizes.sort_unstable();
     block_chunks_sizes.dedup();
+    block_chunks_sizes
+}
+
+// Thread join handle and stop channel sender.
+pub fn spawn_state_keeper(
+    fee_account: &Address,
+    initial_state: ZkSyncStateInitParams,
+) -> (JoinHandle<()>, oneshot::Sender<()>, StateKeeperChannels) {
+    let (proposed_blocks_sender, proposed_blocks_receiver) = mpsc::channel(256);
+    let (state_keeper_req_sender, state_keeper_req_receiver) = mpsc::channel(256);
+    let (mempool_req_sender, mempool_req_receiver) = mpsc::channel(256);
+    let (processed_tx_events_sender, processed_tx_events_receiver) = mpsc::channel(256);
+    // Deadline monitoring is disabled in the testkit, so eth watch requests are never sent.
+    let (eth_watch_req_sender, _eth_watch_req_receiver) = mpsc::channel(256);
 
+    let block_chunks_sizes = block_chunk_sizes();
     let max_miniblock_iterations = *block_chunks_sizes.iter().max().unwrap();
     let (state_keeper, root_hash_calculator) = ZkSyncStateKeeper::new(
         initial_state,
@@ -70,8 +78,8 @@ pub fn spawn_state_keeper(
         proposed_blocks_sender,
         mempool_req_sender,
         block_chunks_sizes,
-        max_miniblock_iterations,
-        max_miniblock_iterations,
+        BlockSealCriteria::with_iterations(max_miniblock_iterations, max_miniblock_iterations),
+        eth_watch_req_sender,
         processed_tx_events_sender,
     );
 
@@ -171,6 +171,24 @@ impl TestSetup {
         (receipts, deposit_op)
     }
 
+    /// Deposits the ERC20 token supporting EIP-2612 using the permit instead of the approve transaction.
+    pub async fn deposit_with_permit(
+        &mut self,
+        from: ETHAccountId,
+        to: ZKSyncAccountId,
+        token: Token,
+        amount: BigUint,
+    ) -> (Vec<TransactionReceipt>, PriorityOp) {
+        self.setup_basic_l1_balances(from, token).await;
+        self.setup_basic_l2_balances(to, token).await;
+
+        let (receipts, deposit_op, transfers) = self
+            .create_deposit_impl(from, to, token, amount, true)
+            .await;
+        self.apply_transfers(&transfers);
+        (receipts, deposit_op)
+    }
+
     #[allow(clippy::map_entry)]
     // Due to await function map entry looks really ugly
     pub async fn setup_basic_l1_balances(&mut self, eth_account_id: ETHAccountId, token: Token) {
@@ -269,6 +287,18 @@ impl TestSetup {
         to: ZKSyncAccountId,
         token: Token,
         amount: BigUint,
+    ) -> (Vec<TransactionReceipt>, PriorityOp, Vec<AccountTransfer>) {
+        self.create_deposit_impl(from, to, token, amount, false)
+            .await
+    }
+
+    async fn create_deposit_impl(
+        &mut self,
+        from: ETHAccountId,
+        to: ZKSyncAccountId,
+        token: Token,
+        amount: BigUint,
+        with_permit: bool,
     ) -> (Vec<TransactionReceipt>, PriorityOp, Vec<AccountTransfer>) {
         let mut transfers = vec![
             AccountTransfer::EthAccountTransfer(EthAccountTransfer {
@@ -294,7 +324,14 @@ impl TestSetup {
             )
         };
 
-        let (receipts, deposit_op) = self.accounts.deposit(from, to, token_address, amount).await;
+        let (receipts, deposit_op) = match token_address {
+            Some(token_address) if with_permit => {
+                self.accounts
+                    .deposit_with_permit(from, to, token_address, amount)
+                    .await
+            }
+            _ => self.accounts.deposit(from, to, token_address, amount).await,
+        };
 
         let mut gas_fee = BigUint::from(0u32);
 
@@ -6,7 +6,9 @@ import deepExtend from 'deep-extend';
 import { env } from 'process';
 
 const CONFIG_FILES = [
+    'alerting.toml',
     'api.toml',
+    'archiver.toml',
     'chain.toml',
     'contracts.toml',
     'database.toml',
@@ -14,6 +16,7 @@ const CONFIG_FILES = [
     'eth_sender.toml',
     'eth_watch.toml',
     'event_listener.toml',
+    'event_publisher.toml',
     'gateway_watcher.toml',
     'fee_ticker.toml',
     'misc.toml',
@@ -23,6 +26,9 @@ const CONFIG_FILES = [
     'private.toml',
     'forced_exit_requests.toml',
     'token_handler.toml',
+    'token_holders.toml',
+    'token_metadata.toml',
+    'webhooks.toml',
     'nft_factory.toml'
 ];
 
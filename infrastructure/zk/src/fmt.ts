@@ -24,6 +24,10 @@ export async function rustfmt(check: boolean = false) {
     process.chdir('sdk/zksync-crypto');
     await utils.spawn(command);
     process.chdir(process.env.ZKSYNC_HOME as string);
+
+    process.chdir('sdk/zksync-wasm');
+    await utils.spawn(command);
+    process.chdir(process.env.ZKSYNC_HOME as string);
 }
 
 export const command = new Command('fmt')
@@ -42,6 +42,10 @@ async function clippy() {
     process.chdir('sdk/zksync-crypto');
     await utils.spawn('cargo clippy  --all --tests --benches -- -D warnings -A clippy::upper-case-acronyms');
     process.chdir(process.env.ZKSYNC_HOME as string);
+
+    process.chdir('sdk/zksync-wasm');
+    await utils.spawn('cargo clippy  --all --tests --benches -- -D warnings -A clippy::upper-case-acronyms');
+    process.chdir(process.env.ZKSYNC_HOME as string);
 }
 
 export const command = new Command('lint')
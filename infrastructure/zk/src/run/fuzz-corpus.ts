@@ -0,0 +1,177 @@
+import { Command } from 'commander';
+import fetch from 'node-fetch';
+import { ethers } from 'ethers';
+import fs from 'fs';
+import * as path from 'path';
+
+// Seeds of the `zksync_types` fuzzing targets taken from the real transactions of the zkSync network.
+
+const MAINNET_API_URL = 'https://api.zksync.io/api/v0.2';
+const CORPUS_DIR = 'core/lib/types/fuzz/corpus';
+// Maximal page size of the API.
+const TXS_PER_BLOCK = 100;
+const REQUEST_TIMEOUT_MS = 30000;
+
+const STORED_BLOCK_INFO =
+    '(uint32 blockNumber, uint64 priorityOperations, bytes32 pendingOnchainOperationsHash, uint256 timestamp, ' +
+    'bytes32 stateHash, bytes32 commitment)';
+const COMMIT_BLOCK_INFO =
+    '(bytes32 newStateHash, bytes publicData, uint256 timestamp, ' +
+    '(bytes ethWitness, uint32 publicDataOffset)[] onchainOperations, uint32 blockNumber, uint32 feeAccount)';
+const COMMIT_BLOCKS_ABI = [`function commitBlocks(${STORED_BLOCK_INFO} lastBlock, ${COMMIT_BLOCK_INFO}[] newBlocks)`];
+
+// Amount of the public data chunks by the operation code,
+// see the `CHUNKS` constants of the operations in `zksync_types`.
+const CHUNK_BYTES = 10;
+const OP_CHUNKS: { [opCode: number]: number } = {
+    0x00: 1, // Noop
+    0x01: 6, // Deposit
+    0x02: 6, // TransferToNew
+    0x03: 6, // Withdraw
+    0x05: 2, // Transfer
+    0x06: 11, // FullExit
+    0x07: 6, // ChangePubKey
+    0x08: 6, // ForcedExit
+    0x09: 5, // MintNFT
+    0x0a: 10, // WithdrawNFT
+    0x0b: 5 // Swap
+};
+
+async function get(url: string): Promise<any> {
+    const response = await fetch(url, { timeout: REQUEST_TIMEOUT_MS });
+    const body = await response.json();
+    if (body.status != 'success') {
+        throw new Error(`Request to ${url} failed: ${JSON.stringify(body.error)}`);
+    }
+    return body.result;
+}
+
+async function web3Request(web3Url: string, method: string, params: any[]): Promise<any> {
+    const response = await fetch(web3Url, {
+        method: 'post',
+        body: JSON.stringify({ jsonrpc: '2.0', method, params, id: 1 }),
+        headers: {
+            Accept: 'application/json',
+            'Content-type': 'application/json'
+        },
+        timeout: REQUEST_TIMEOUT_MS
+    });
+    const body = await response.json();
+    if (body.error) {
+        throw new Error(`${method} failed: ${JSON.stringify(body.error)}`);
+    }
+    return body.result;
+}
+
+function writeSeed(target: string, name: string, data: string | Uint8Array) {
+    const dir = path.join(CORPUS_DIR, target);
+    fs.mkdirSync(dir, { recursive: true });
+    fs.writeFileSync(path.join(dir, `mainnet-${name}`), data);
+}
+
+// The API returns the withdrawals along with the hashes of their Ethereum transactions,
+// which are not a part of the submitted transaction.
+function submittedTx(op: any): any {
+    const { ethTxHash, ...tx } = op;
+    return tx;
+}
+
+// Splits the block public data into the operations the same way the data restore does.
+function splitPublicData(publicData: Uint8Array): Uint8Array[] {
+    const ops: Uint8Array[] = [];
+    let offset = 0;
+    while (offset < publicData.length) {
+        const chunks = OP_CHUNKS[publicData[offset]];
+        if (chunks === undefined) {
+            throw new Error(`Unknown operation code ${publicData[offset]} at the offset ${offset}`);
+        }
+        ops.push(publicData.slice(offset, offset + chunks * CHUNK_BYTES));
+        offset += chunks * CHUNK_BYTES;
+    }
+    return ops;
+}
+
+async function blockTransactions(apiUrl: string, blockNumber: number) {
+    const txs = await get(
+        `${apiUrl}/blocks/${blockNumber}/transactions?from=latest&limit=${TXS_PER_BLOCK}&direction=older`
+    );
+    let seeds = 0;
+    const batches: Map<number, any[]> = new Map();
+    for (const tx of txs.list) {
+        // Priority operations are not submitted via the API.
+        if (tx.op.type == 'Deposit' || tx.op.type == 'FullExit') {
+            continue;
+        }
+        const op = submittedTx(tx.op);
+        const hash = tx.txHash.replace(/^0x/, '');
+        writeSeed('tx_json', hash, JSON.stringify(op, null, 2));
+        seeds += 1;
+
+        const data = await get(`${apiUrl}/transactions/${tx.txHash}/data`);
+        if (data.ethSignature) {
+            const signature = ethers.utils.arrayify(data.ethSignature);
+            if (signature.length == 65) {
+                writeSeed('eth_sign_message', hash, ethers.utils.concat([signature, Buffer.from(JSON.stringify(op))]));
+                seeds += 1;
+            }
+        }
+
+        if (tx.batchId !== null && tx.batchId !== undefined) {
+            batches.set(tx.batchId, [...(batches.get(tx.batchId) || []), op]);
+        }
+    }
+    for (const [batchId, batch] of batches) {
+        // The list is ordered from the newest transactions.
+        writeSeed('batch_hash', `batch-${batchId}`, JSON.stringify(batch.reverse(), null, 2));
+        seeds += 1;
+    }
+    return seeds;
+}
+
+async function blockPublicData(web3Url: string, blockNumber: number, commitTxHash: string) {
+    const commitTx = await web3Request(web3Url, 'eth_getTransactionByHash', [commitTxHash]);
+    const zksync = new ethers.utils.Interface(COMMIT_BLOCKS_ABI);
+    const [, newBlocks] = zksync.decodeFunctionData('commitBlocks', commitTx.input);
+    const block = newBlocks.find((block: any) => block.blockNumber == blockNumber);
+    if (!block) {
+        throw new Error(`Block ${blockNumber} is not committed by ${commitTxHash}`);
+    }
+
+    let seeds = 0;
+    const seen = new Set<string>();
+    splitPublicData(ethers.utils.arrayify(block.publicData)).forEach((op, index) => {
+        // Blocks are padded with the noop operations.
+        const key = ethers.utils.hexlify(op);
+        if (seen.has(key)) {
+            return;
+        }
+        seen.add(key);
+        writeSeed('op_pubdata', `block-${blockNumber}-${index}`, op);
+        seeds += 1;
+    });
+    return seeds;
+}
+
+export async function fuzzCorpus(blocks: number, apiUrl: string, web3Url?: string) {
+    const lastBlock = await get(`${apiUrl}/blocks/lastFinalized`);
+    for (let blockNumber = lastBlock.blockNumber; blockNumber > lastBlock.blockNumber - blocks; blockNumber--) {
+        let seeds = await blockTransactions(apiUrl, blockNumber);
+        if (web3Url) {
+            const block = await get(`${apiUrl}/blocks/${blockNumber}`);
+            seeds += await blockPublicData(web3Url, blockNumber, block.commitTxHash);
+        }
+        console.log(`Block ${blockNumber}: ${seeds} seeds`);
+    }
+    if (!web3Url) {
+        console.log('Ethereum node URL is not provided, the public data seeds were not added');
+    }
+}
+
+export const command = new Command('fuzz-corpus')
+    .description('add the transactions of the latest finalized zkSync blocks to the corpus of the fuzzing targets')
+    .option('--blocks <blocks>', 'amount of the blocks to take the transactions from', '10')
+    .option('--api-url <api-url>', 'zkSync REST API v0.2 URL', MAINNET_API_URL)
+    .option('--web3-url <web3-url>', 'Ethereum node URL to load the public data of the blocks from')
+    .action(async (cmd: Command) => {
+        await fuzzCorpus(parseInt(cmd.blocks), cmd.apiUrl, cmd.web3Url);
+    });
@@ -6,6 +6,7 @@ import * as path from 'path';
 import * as verifyKeys from './verify-keys';
 import * as eventListener from './event-listener';
 import * as dataRestore from './data-restore';
+import * as fuzzCorpus from './fuzz-corpus';
 import * as docker from '../docker';
 
 export { verifyKeys, dataRestore };
@@ -148,7 +149,8 @@ export const command = new Command('run')
     .description('run miscellaneous applications')
     .addCommand(verifyKeys.command)
     .addCommand(dataRestore.command)
-    .addCommand(eventListener.command);
+    .addCommand(eventListener.command)
+    .addCommand(fuzzCorpus.command);
 
 command.command('test-accounts').description('print ethereum test accounts').action(testAccounts);
 command.command('explorer').description('run zksync explorer locally').action(explorer);
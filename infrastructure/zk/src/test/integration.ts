@@ -165,6 +165,8 @@ export async function testkit(command: string, timeout: number) {
     });
 
     process.env.CHAIN_ETH_NETWORK = 'test';
+    // The revert test stores the blocks in the embedded database started and migrated by the binary itself.
+    delete process.env.DATABASE_URL;
     await run.verifyKeys.unpack();
     await contract.build();
 
@@ -181,6 +183,12 @@ export async function testkit(command: string, timeout: number) {
     }
 }
 
+export async function simulation() {
+    // The simulation runs on the embedded database started and migrated by the binary itself.
+    delete process.env.DATABASE_URL;
+    await utils.spawn('cargo run --bin simulation --release');
+}
+
 export async function rustSDK() {
     await utils.spawn('cargo test -p zksync --release -- --ignored --test-threads=1');
 }
@@ -243,6 +251,11 @@ command
         cmd.withServer ? await withServer(apiDocs, 240) : await apiDocs();
     });
 
+command
+    .command('simulation')
+    .description('run in-process end-to-end simulation on the embedded database (no docker or geth required)')
+    .action(simulation);
+
 command
     .command('testkit [mode]')
     .description('run testkit tests')
@@ -4,11 +4,17 @@ import * as utils from '../utils';
 import * as integration from './integration';
 export { integration };
 
-async function runOnTestDb(reset: boolean, dir: string, command: string) {
-    const databaseUrl = process.env.DATABASE_URL as string;
-    process.env.DATABASE_URL = databaseUrl.replace(/plasma/g, 'plasma_test');
+async function runOnTestDb(reset: boolean, embedded: boolean, dir: string, command: string) {
+    if (embedded) {
+        // The embedded database is started and migrated by the tests themselves.
+        delete process.env.DATABASE_URL;
+        delete process.env.DATABASE_REPLICA_URL;
+    } else {
+        const databaseUrl = process.env.DATABASE_URL as string;
+        process.env.DATABASE_URL = databaseUrl.replace(/plasma/g, 'plasma_test');
+    }
     process.chdir('core/lib/storage');
-    if (reset) {
+    if (reset && !embedded) {
         console.info('Performing database reset...');
         await utils.exec('diesel database reset');
         await utils.exec('diesel migration run');
@@ -20,24 +26,28 @@ async function runOnTestDb(reset: boolean, dir: string, command: string) {
     process.chdir(process.env.ZKSYNC_HOME as string);
 }
 
-export async function db(reset: boolean, ...args: string[]) {
+export async function db(reset: boolean, embedded: boolean, ...args: string[]) {
     // Running many similar transactions in parallel can cause db deadlocks, so we run
     // them in a single thread. Given that tests are pretty fast, it's not a big problem.
+    const features = embedded ? '--features embedded_db' : '';
     await runOnTestDb(
         reset,
+        embedded,
         'core/lib/storage',
-        `cargo test --release -p zksync_storage --lib -- --ignored --nocapture --test-threads=1
+        `cargo test --release -p zksync_storage --lib ${features} -- --ignored --nocapture --test-threads=1
         ${args.join(' ')}`
     );
 }
 
-export async function rustApi(reset: boolean, ...args: string[]) {
+export async function rustApi(reset: boolean, embedded: boolean, ...args: string[]) {
     // Running many similar transactions in parallel can cause db deadlocks, so we run
     // them in a single thread. Given that tests are pretty fast, it's not a big problem.
+    const features = embedded ? '--features embedded_db' : '';
     await runOnTestDb(
         reset,
+        embedded,
         'core/bin/zksync_api',
-        `cargo test --release -p zksync_api --lib -- --ignored --nocapture --test-threads=1 api_server
+        `cargo test --release -p zksync_api --lib ${features} -- --ignored --nocapture --test-threads=1 api_server
         ${args.join(' ')}`
     );
 }
@@ -69,12 +79,23 @@ async function rustCryptoTests() {
     process.chdir('sdk/zksync-crypto');
     await utils.spawn('cargo test --release');
     process.chdir(process.env.ZKSYNC_HOME as string);
+
+    process.chdir('sdk/zksync-wasm');
+    await utils.spawn('cargo test --release');
+    process.chdir(process.env.ZKSYNC_HOME as string);
+}
+
+export async function fuzzCorpus() {
+    process.chdir('core/lib/types/fuzz');
+    await utils.spawn('cargo test --release');
+    process.chdir(process.env.ZKSYNC_HOME as string);
 }
 
 export async function serverRust() {
     await utils.spawn('cargo test --release');
-    await db(true);
-    await rustApi(true);
+    await fuzzCorpus();
+    await db(true, false);
+    await rustApi(true, false);
     await prover();
 }
 
@@ -94,6 +115,7 @@ command.command('js').description('run unit-tests for javascript packages').acti
 command.command('prover').description('run unit-tests for the prover').action(prover);
 command.command('witness-generator').description('run unit-tests for the witness-generator').action(witness_generator);
 command.command('contracts').description('run unit-tests for the contracts').action(contracts);
+command.command('fuzz-corpus').description('replay the fuzzing corpus of zksync_types').action(fuzzCorpus);
 command.command('rust').description('run unit-tests for all rust binaries and libraries').action(rust);
 command.command('server-rust').description('run unit-tests for server binaries and libraries').action(serverRust);
 command.command('crypto-rust').description('run unit-tests for rust crypto binaries and libraries').action(cryptoRust);
@@ -102,18 +124,20 @@ command
     .command('db')
     .description('run unit-tests for the database')
     .option('--no-reset', 'do not reset the database before test starting')
+    .option('--embedded', 'use the embedded database instead of the one from `DATABASE_URL`')
     .allowUnknownOption()
     .action(async (cmd: Command, options: string[] | undefined) => {
-        await db(cmd.reset, ...(options || []));
+        await db(cmd.reset, !!cmd.embedded, ...(options || []));
     });
 
 command
     .command('rust-api')
     .description('run unit-tests for the REST API')
     .option('--no-reset', 'do not reset the database before test starting')
+    .option('--embedded', 'use the embedded database instead of the one from `DATABASE_URL`')
     .allowUnknownOption()
     .action(async (cmd: Command, options: string[] | undefined) => {
-        await rustApi(cmd.reset, ...(options || []));
+        await rustApi(cmd.reset, !!cmd.embedded, ...(options || []));
     });
 
 command
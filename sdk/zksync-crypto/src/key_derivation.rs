@@ -0,0 +1,48 @@
+//! Deterministic derivation of the zkSync private keys, the same as in the `key_derivation` module
+//! of the server `zksync_crypto` crate, which can't be compiled into wasm.
+//!
+//! The seed of every level of the `m/i_1/.../i_n` path is calculated from the seed of the previous level
+//! as `sha256(KEY_DERIVATION_MESSAGE || parent_seed || index)`, where the index is encoded as 4 big-endian bytes.
+
+use sha2::{Digest, Sha256};
+
+/// Message prepended to the parent seed during the derivation of the child seed.
+const KEY_DERIVATION_MESSAGE: &[u8] = b"zkSync key derivation";
+
+pub fn sha256_bytes(input: &[u8]) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    hasher.input(input);
+    hasher.result().to_vec()
+}
+
+/// Parses the derivation path in the `m/i_1/.../i_n` format, where every index is a decimal `u32` number.
+pub fn parse_derivation_path(path: &str) -> Option<Vec<u32>> {
+    let mut segments = path.split('/');
+    if segments.next() != Some("m") {
+        return None;
+    }
+
+    segments
+        .map(|segment| {
+            // `u32::from_str` accepts the leading `+`, which is not a part of the format.
+            let is_number =
+                !segment.is_empty() && segment.bytes().all(|byte| byte.is_ascii_digit());
+            if is_number {
+                segment.parse().ok()
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Derives the seed of the given path from the seed of the path `m`.
+pub fn derive_seed(seed: &[u8], path: &[u32]) -> Vec<u8> {
+    path.iter().fold(seed.to_vec(), |seed, index| {
+        let mut input = Vec::with_capacity(KEY_DERIVATION_MESSAGE.len() + seed.len() + 4);
+        input.extend_from_slice(KEY_DERIVATION_MESSAGE);
+        input.extend_from_slice(&seed);
+        input.extend_from_slice(&index.to_be_bytes());
+        sha256_bytes(&input)
+    })
+}
@@ -1,6 +1,7 @@
 //! Utils for signing zksync transactions.
 //! This crate is compiled into wasm to be used in `zksync.js`.
 
+mod key_derivation;
 #[cfg(test)]
 mod tests;
 mod utils;
@@ -29,8 +30,10 @@ use franklin_crypto::{
 
 pub type Signature = EddsaSignature<Engine>;
 
-use crate::utils::set_panic_hook;
-use sha2::{Digest, Sha256};
+use crate::{
+    key_derivation::{derive_seed, parse_derivation_path, sha256_bytes},
+    utils::set_panic_hook,
+};
 
 // When the `wee_alloc` feature is enabled, use `wee_alloc` as the global
 // allocator.
@@ -53,12 +56,6 @@ pub fn private_key_from_seed(seed: &[u8]) -> Result<Vec<u8>, JsValue> {
         return Err(JsValue::from_str("Seed is too short"));
     };
 
-    let sha256_bytes = |input: &[u8]| -> Vec<u8> {
-        let mut hasher = Sha256::new();
-        hasher.input(input);
-        hasher.result().to_vec()
-    };
-
     let mut effective_seed = sha256_bytes(seed);
 
     loop {
@@ -75,6 +72,19 @@ pub fn private_key_from_seed(seed: &[u8]) -> Result<Vec<u8>, JsValue> {
     }
 }
 
+/// Derives the private key from seed via the derivation path in the `m/i_1/.../i_n` format,
+/// so a single seed can be used to manage multiple zkSync accounts.
+/// The path `m` corresponds to the key returned by `privateKeyFromSeed`.
+#[wasm_bindgen(js_name = derivePrivateKey)]
+pub fn derive_private_key(seed: &[u8], path: &str) -> Result<Vec<u8>, JsValue> {
+    if seed.len() < 32 {
+        return Err(JsValue::from_str("Seed is too short"));
+    };
+    let path = parse_derivation_path(path)
+        .ok_or_else(|| JsValue::from_str(&format!("Invalid derivation path: {}", path)))?;
+    private_key_from_seed(&derive_seed(seed, &path))
+}
+
 fn read_signing_key(private_key: &[u8]) -> Result<PrivateKey<Engine>, JsValue> {
     let mut fs_repr = FsRepr::default();
     fs_repr
@@ -1,8 +1,11 @@
 //! Compare crypto primitives to those that we use in our `zksync_types` crate;
 
-use super::{private_key_to_pubkey_hash, read_signing_key, sign_musig, verify_musig};
+use super::{
+    derive_private_key, key_derivation::parse_derivation_path, private_key_from_seed,
+    private_key_to_pubkey_hash, read_signing_key, sign_musig, verify_musig,
+};
 
-use crypto_lib::{public_key_from_private, Engine};
+use crypto_lib::{key_derivation, public_key_from_private, Engine};
 use franklin_crypto::bellman::pairing::ff::{self, PrimeField, PrimeFieldRepr};
 use franklin_crypto::eddsa::PrivateKey;
 use rand::{Rng, SeedableRng, XorShiftRng};
@@ -78,3 +81,31 @@ fn test_verify_signature() {
     let valid = verify_musig(&msg, &wasm_signature).unwrap();
     assert!(valid);
 }
+
+#[test]
+fn test_derive_private_key() {
+    let seed = [1u8; 32];
+    for path in &["m", "m/0", "m/0/1", "m/2147483655", "m/4294967295/7/42"] {
+        let wasm_pk = derive_private_key(&seed, path).unwrap();
+        let zksync_types_pk = key_derivation::derive_private_key(
+            &seed,
+            &key_derivation::parse_derivation_path(path).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            ff::to_hex(&read_signing_key(&wasm_pk).unwrap().0),
+            ff::to_hex(&zksync_types_pk.0),
+            "path: {}",
+            path
+        );
+    }
+    assert_eq!(
+        derive_private_key(&seed, "m").unwrap(),
+        private_key_from_seed(&seed).unwrap()
+    );
+
+    for path in &["", "0/1", "m/", "m//1", "m/+1", "m/1'", "m/4294967296"] {
+        assert!(parse_derivation_path(path).is_none(), "path: {}", path);
+        assert!(key_derivation::parse_derivation_path(path).is_err());
+    }
+}
@@ -1,14 +1,22 @@
-use crate::{error::ClientError, utils::private_key_from_seed};
+use crate::{
+    error::ClientError,
+    utils::{derive_private_key, private_key_from_seed},
+};
 
 use web3::types::{Address, H256};
 use zksync_crypto::PrivateKey;
 use zksync_eth_signer::{EthereumSigner, PrivateKeySigner};
-use zksync_types::{network::Network, tx::TxEthSignature};
+use zksync_types::{
+    network::Network,
+    tx::{ChangePubKeyCREATE2Data, TxEthSignature},
+    PubKeyHash,
+};
 
 pub struct WalletCredentials<S: EthereumSigner> {
     pub(crate) eth_signer: Option<S>,
     pub(crate) eth_address: Address,
     pub(crate) zksync_private_key: PrivateKey,
+    pub(crate) create2_data: Option<ChangePubKeyCREATE2Data>,
 }
 
 impl<S: EthereumSigner> std::fmt::Debug for WalletCredentials<S> {
@@ -73,6 +81,7 @@ impl<S: EthereumSigner> WalletCredentials<S> {
             eth_signer: Some(eth_signer),
             eth_address,
             zksync_private_key: zksync_pk,
+            create2_data: None,
         })
     }
 
@@ -92,9 +101,61 @@ impl<S: EthereumSigner> WalletCredentials<S> {
             eth_signer: None,
             eth_address,
             zksync_private_key: zksync_pk,
+            create2_data: None,
         })
     }
 
+    /// Creates wallet credentials from the private key derived from the seed via the derivation path.
+    /// Same as `from_seed`, the Ethereum signer will not be set.
+    ///
+    /// ## Arguments
+    ///
+    /// - `eth_address`: Address of the corresponding Ethereum wallet.
+    /// - `seed`: A random bytearray to derive private key from. Must be at least 32 bytes long.
+    /// - `path`: Derivation path in the `m/i_1/.../i_n` format, see [`derive_private_key`].
+    pub fn from_seed_and_path(
+        eth_address: Address,
+        seed: &[u8],
+        path: &str,
+    ) -> Result<Self, ClientError> {
+        let zksync_pk = derive_private_key(seed, path)?;
+
+        Ok(Self {
+            eth_signer: None,
+            eth_address,
+            zksync_private_key: zksync_pk,
+            create2_data: None,
+        })
+    }
+
+    /// Creates wallet credentials of the smart contract wallet deployed via CREATE2.
+    /// The wallet address is derived from the CREATE2 data and the zkSync public key hash,
+    /// so the signing key is set without the Ethereum signature. The Ethereum signer
+    /// will not be set, so the transactions are sent without the Ethereum signatures.
+    ///
+    /// ## Arguments
+    ///
+    /// - `create2_data`: Creator address, salt argument and code hash of the wallet contract.
+    /// - `private_key`: Private key of a zkSync account.
+    pub fn from_create2_data(
+        create2_data: ChangePubKeyCREATE2Data,
+        private_key: PrivateKey,
+    ) -> Self {
+        let eth_address = create2_data.get_address(&PubKeyHash::from_privkey(&private_key));
+
+        Self {
+            eth_signer: None,
+            eth_address,
+            zksync_private_key: private_key,
+            create2_data: Some(create2_data),
+        }
+    }
+
+    /// Returns the address of the wallet.
+    pub fn eth_address(&self) -> Address {
+        self.eth_address
+    }
+
     /// Creates wallet credentials from the provided keys.
     ///
     /// ## Arguments
@@ -113,6 +174,7 @@ impl<S: EthereumSigner> WalletCredentials<S> {
             eth_address,
             eth_signer,
             zksync_private_key: private_key,
+            create2_data: None,
         }
     }
 }
@@ -1,6 +1,8 @@
 pub use jsonrpc_core::types::response::Failure as RpcFailure;
 use thiserror::Error;
+use zksync_crypto::error::MusigError;
 use zksync_eth_signer::error::SignerError;
+use zksync_types::Nonce;
 
 #[derive(Debug, Error, PartialEq)]
 pub enum ClientError {
@@ -17,8 +19,12 @@ pub enum ClientError {
     IncorrectCredentials,
     #[error("Seed too short, must be at least 32 bytes long")]
     SeedTooShort,
+    #[error("Invalid key derivation path: {0}")]
+    InvalidDerivationPath(String),
     #[error("Token is not supported by zkSync")]
     UnknownToken,
+    #[error("None of the tokens can be used to pay the fee")]
+    NoViableFeeToken,
     #[error("Incorrect address")]
     IncorrectAddress,
 
@@ -26,9 +32,13 @@ pub enum ClientError {
     OperationTimeout,
     #[error("Polling interval is too small")]
     PollingIntervalIsTooSmall,
+    #[error("Transaction failed: {0}")]
+    TransactionFailed(String),
 
     #[error("Signing error: {0}")]
     SigningError(SignerError),
+    #[error("MuSig error: {0}")]
+    MusigError(MusigError),
     #[error("Missing required field for a transaction: {0}")]
     MissingRequiredField(String),
 
@@ -41,6 +51,11 @@ pub enum ClientError {
     #[error("Provided function arguments are incorrect")]
     IncorrectInput,
 
+    #[error("Signed transactions have expired")]
+    TransactionsExpired,
+    #[error("Transactions were signed for nonce {expected}, but the account nonce is {actual}")]
+    NonceMismatch { expected: Nonce, actual: Nonce },
+
     #[error("Other")]
     Other,
 }
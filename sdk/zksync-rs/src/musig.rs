@@ -0,0 +1,95 @@
+//! MuSig signing of the zkSync transactions by several parties.
+//!
+//! The parties owning the account with the aggregated public key (e.g. set via `ChangePubKey`
+//! with the `pub_key_hash` of the [`MusigSigner`]) cooperatively produce the transaction
+//! signature. See [`zksync_crypto::musig`] for the description of the protocol rounds.
+
+use zksync_crypto::{
+    musig::MusigSigner as MusigSessionSigner, rand::thread_rng, Fr, Fs, PrivateKey,
+};
+use zksync_types::{
+    tx::{PackedPublicKey, PackedSignature, TxSignature},
+    PubKeyHash,
+};
+
+pub use zksync_crypto::musig::{aggregate_public_keys, nonce_commitment, MusigNonce};
+
+use crate::error::ClientError;
+
+/// One of the parties of the transaction MuSig signing.
+///
+/// The signer is used for exactly one message, since the nonce is generated on the signer creation.
+#[derive(Debug)]
+pub struct MusigSigner {
+    inner: MusigSessionSigner,
+}
+
+impl MusigSigner {
+    /// Creates a signer of the party with the given private key.
+    /// `position` is the index of the party public key in the list of all the public keys,
+    /// which must be the same for every party.
+    pub fn new(
+        private_key: PrivateKey,
+        pubkeys: Vec<PackedPublicKey>,
+        position: usize,
+    ) -> Result<Self, ClientError> {
+        let pubkeys = pubkeys.into_iter().map(|pubkey| pubkey.0).collect();
+        let inner = MusigSessionSigner::new(private_key, pubkeys, position, &mut thread_rng())
+            .map_err(ClientError::MusigError)?;
+
+        Ok(Self { inner })
+    }
+
+    /// Returns the aggregated public key, which verifies the transaction signatures.
+    pub fn aggregated_pubkey(&self) -> PackedPublicKey {
+        PackedPublicKey(self.inner.aggregated_pubkey().clone())
+    }
+
+    /// Returns the hash of the aggregated public key to be set as the account public key hash.
+    pub fn pub_key_hash(&self) -> PubKeyHash {
+        PubKeyHash::from_pubkey(self.inner.aggregated_pubkey())
+    }
+
+    /// Returns the commitment to the party nonce to be sent to the other parties.
+    pub fn nonce_commitment(&self) -> Fr {
+        self.inner.nonce_commitment()
+    }
+
+    /// Receives the nonce commitments of all the parties in the order of their public keys.
+    /// Returns the party nonce to be sent to the other parties.
+    pub fn receive_commitments(&mut self, commitments: Vec<Fr>) -> Result<MusigNonce, ClientError> {
+        self.inner
+            .receive_commitments(commitments)
+            .map_err(ClientError::MusigError)
+    }
+
+    /// Receives the nonces of all the parties in the order of their public keys.
+    pub fn receive_nonces(&mut self, nonces: Vec<MusigNonce>) -> Result<(), ClientError> {
+        self.inner
+            .receive_nonces(nonces)
+            .map_err(ClientError::MusigError)
+    }
+
+    /// Creates the party partial signature of the transaction message (e.g. `tx.get_bytes()`)
+    /// to be sent to the other parties.
+    pub fn sign(&mut self, msg: &[u8]) -> Result<Fs, ClientError> {
+        self.inner.sign(msg).map_err(ClientError::MusigError)
+    }
+
+    /// Aggregates the partial signatures of all the parties into the transaction signature.
+    pub fn aggregate_signatures(
+        &self,
+        msg: &[u8],
+        partial_signatures: &[Fs],
+    ) -> Result<TxSignature, ClientError> {
+        let signature = self
+            .inner
+            .aggregate_signatures(msg, partial_signatures)
+            .map_err(ClientError::MusigError)?;
+
+        Ok(TxSignature {
+            pub_key: self.aggregated_pubkey(),
+            signature: PackedSignature(signature),
+        })
+    }
+}
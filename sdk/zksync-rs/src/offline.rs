@@ -0,0 +1,399 @@
+//! Offline signing workflow.
+//!
+//! Construction, signing and submission of the transactions are split between two machines:
+//!
+//! 1. The online machine prepares an [`OfflinePayload`] with [`Wallet::prepare_offline`],
+//!    resolving the account id, nonce and fee, and saves it to a file (the payload is serializable).
+//! 2. The air-gapped machine signs the payload with [`Signer::sign_offline`], producing the
+//!    [`SignedTransactions`] with both zkSync and Ethereum signatures.
+//! 3. The online machine submits them later with [`submit_signed`].
+//!
+//! The transactions are valid until the `valid_until` timestamp of the payload, so it has to cover
+//! the delay between the preparation and the broadcast. The submission fails early if the account
+//! nonce was changed in the meantime, since the server would reject such transactions anyway.
+
+// Built-in imports
+use std::time::{SystemTime, UNIX_EPOCH};
+// External uses
+use num::BigUint;
+use serde::{Deserialize, Serialize};
+// Workspace uses
+use zksync_eth_signer::{error::SignerError, EthereumSigner};
+use zksync_types::{
+    tokens::{ChangePubKeyFeeTypeArg, TxFeeTypes},
+    tx::{ChangePubKeyType, PackedEthSignature, TimeRange, TxEthSignature},
+    AccountId, Address, ForcedExit, Nonce, Token, TokenLike, Transfer, Withdraw, ZkSyncTx,
+};
+use zksync_utils::BigUintSerdeAsRadix10Str;
+// Local imports
+use crate::{
+    error::ClientError, operations::SyncTransactionHandle, provider::Provider, signer::Signer,
+    utils::closest_greater_or_eq_packable_fee_amount, wallet::Wallet,
+};
+
+/// Transaction to be signed offline. The amounts are expected to be packable.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum OfflineTx {
+    Transfer {
+        token: Token,
+        to: Address,
+        #[serde(with = "BigUintSerdeAsRadix10Str")]
+        amount: BigUint,
+    },
+    Withdraw {
+        token: Token,
+        to: Address,
+        #[serde(with = "BigUintSerdeAsRadix10Str")]
+        amount: BigUint,
+    },
+    ForcedExit {
+        token: Token,
+        target: Address,
+    },
+    /// `ChangePubKey` authorized with the Ethereum signature.
+    ChangePubKey,
+}
+
+impl OfflineTx {
+    fn fee_type(&self) -> TxFeeTypes {
+        match self {
+            OfflineTx::Transfer { .. } => TxFeeTypes::Transfer,
+            OfflineTx::Withdraw { .. } | OfflineTx::ForcedExit { .. } => TxFeeTypes::Withdraw,
+            OfflineTx::ChangePubKey => TxFeeTypes::ChangePubKey(
+                ChangePubKeyFeeTypeArg::ContractsV4Version(ChangePubKeyType::ECDSA),
+            ),
+        }
+    }
+
+    fn recipient(&self, address: Address) -> Address {
+        match self {
+            OfflineTx::Transfer { to, .. } | OfflineTx::Withdraw { to, .. } => *to,
+            OfflineTx::ForcedExit { target, .. } => *target,
+            OfflineTx::ChangePubKey => address,
+        }
+    }
+
+    /// Whether the transaction can pay the fee in the given token itself.
+    fn pays_fee_in(&self, fee_token: &Token) -> bool {
+        match self {
+            OfflineTx::Transfer { token, .. }
+            | OfflineTx::Withdraw { token, .. }
+            | OfflineTx::ForcedExit { token, .. } => token.id == fee_token.id,
+            OfflineTx::ChangePubKey => true,
+        }
+    }
+}
+
+/// Everything needed to sign the transactions without the access to the network.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OfflinePayload {
+    pub account_id: AccountId,
+    pub address: Address,
+    /// Nonce of the first transaction, the next ones use the subsequent nonces.
+    pub nonce: Nonce,
+    pub valid_from: u64,
+    pub valid_until: u64,
+    pub txs: Vec<OfflineTx>,
+    pub fee_token: Token,
+    /// Total fee of the transactions.
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub fee: BigUint,
+}
+
+impl OfflinePayload {
+    /// Whether the transactions are sent as a batch, paying the fee with
+    /// an additional zero-amount transfer to the account itself.
+    pub fn is_batch(&self) -> bool {
+        !matches!(self.txs.as_slice(), [tx] if tx.pays_fee_in(&self.fee_token))
+    }
+
+    fn fee_types(&self) -> (Vec<TxFeeTypes>, Vec<Address>) {
+        let (mut tx_types, mut addresses): (Vec<_>, Vec<_>) = self
+            .txs
+            .iter()
+            .map(|tx| (tx.fee_type(), tx.recipient(self.address)))
+            .unzip();
+        if self.is_batch() {
+            // The fee transfer.
+            tx_types.push(TxFeeTypes::Transfer);
+            addresses.push(self.address);
+        }
+        (tx_types, addresses)
+    }
+}
+
+/// Transaction signed offline along with its own Ethereum signature, if any.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedTx {
+    pub tx: ZkSyncTx,
+    pub eth_signature: Option<PackedEthSignature>,
+}
+
+/// Transactions signed offline and ready to be submitted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedTransactions {
+    pub address: Address,
+    pub nonce: Nonce,
+    pub valid_until: u64,
+    pub txs: Vec<SignedTx>,
+    /// Ethereum signature of the whole batch.
+    pub batch_signature: Option<PackedEthSignature>,
+}
+
+impl<S, P> Wallet<S, P>
+where
+    S: EthereumSigner,
+    P: Provider + Clone,
+{
+    /// Prepares the payload to sign the transactions offline, the fee is paid in `fee_token`.
+    /// Transactions are valid until the `valid_until` unix timestamp.
+    pub async fn prepare_offline(
+        &self,
+        txs: Vec<OfflineTx>,
+        fee_token: impl Into<TokenLike>,
+        valid_until: u64,
+    ) -> Result<OfflinePayload, ClientError> {
+        if txs.is_empty() {
+            return Err(ClientError::MissingRequiredField("txs".into()));
+        }
+        let fee_token = self
+            .tokens
+            .resolve(fee_token.into())
+            .ok_or(ClientError::UnknownToken)?;
+        let account_id = self
+            .account_id()
+            .ok_or(ClientError::SigningError(SignerError::NoSigningKey))?;
+        let account_info = self.provider.account_info(self.address()).await?;
+
+        let mut payload = OfflinePayload {
+            account_id,
+            address: self.address(),
+            nonce: account_info.committed.nonce,
+            valid_from: 0,
+            valid_until,
+            txs,
+            fee_token,
+            fee: BigUint::from(0u32),
+        };
+
+        let (mut tx_types, mut addresses) = payload.fee_types();
+        let fee = if tx_types.len() == 1 {
+            self.provider
+                .get_tx_fee(
+                    tx_types.remove(0),
+                    addresses.remove(0),
+                    payload.fee_token.id,
+                )
+                .await?
+                .total_fee
+        } else {
+            self.provider
+                .get_txs_batch_fee(tx_types, addresses, payload.fee_token.id)
+                .await?
+        };
+        payload.fee = closest_greater_or_eq_packable_fee_amount(&fee);
+
+        Ok(payload)
+    }
+}
+
+impl<S: EthereumSigner> Signer<S> {
+    /// Signs the prepared payload, does not require the access to the network.
+    pub async fn sign_offline(
+        &self,
+        payload: OfflinePayload,
+    ) -> Result<SignedTransactions, SignerError> {
+        if payload.address != self.address {
+            return Err(SignerError::CustomError(
+                "Payload was prepared for another account".to_string(),
+            ));
+        }
+
+        let is_batch = payload.is_batch();
+        let time_range = TimeRange::new(payload.valid_from, payload.valid_until);
+        let zero = || BigUint::from(0u32);
+        let tx_fee = |fee: &BigUint| if is_batch { zero() } else { fee.clone() };
+
+        let mut nonce = payload.nonce;
+        let mut txs = Vec::with_capacity(payload.txs.len() + 1);
+        for tx in payload.txs {
+            let signed_tx = match tx {
+                OfflineTx::Transfer { token, to, amount } => {
+                    let transfer = Transfer::new_signed(
+                        payload.account_id,
+                        self.address,
+                        to,
+                        token.id,
+                        amount,
+                        tx_fee(&payload.fee),
+                        nonce,
+                        time_range,
+                        &self.private_key,
+                    )
+                    .map_err(signing_failed_error)?;
+                    (ZkSyncTx::from(transfer), token)
+                }
+                OfflineTx::Withdraw { token, to, amount } => {
+                    let withdraw = Withdraw::new_signed(
+                        payload.account_id,
+                        self.address,
+                        to,
+                        token.id,
+                        amount,
+                        tx_fee(&payload.fee),
+                        nonce,
+                        time_range,
+                        &self.private_key,
+                    )
+                    .map_err(signing_failed_error)?;
+                    (ZkSyncTx::from(withdraw), token)
+                }
+                OfflineTx::ForcedExit { token, target } => {
+                    let forced_exit = ForcedExit::new_signed(
+                        payload.account_id,
+                        target,
+                        token.id,
+                        tx_fee(&payload.fee),
+                        nonce,
+                        time_range,
+                        &self.private_key,
+                    )
+                    .map_err(signing_failed_error)?;
+                    (ZkSyncTx::from(forced_exit), token)
+                }
+                OfflineTx::ChangePubKey => {
+                    let change_pubkey = self
+                        .sign_change_pubkey_tx_for_account(
+                            payload.account_id,
+                            nonce,
+                            false,
+                            payload.fee_token.clone(),
+                            tx_fee(&payload.fee),
+                            time_range,
+                        )
+                        .await?;
+                    (ZkSyncTx::from(change_pubkey), payload.fee_token.clone())
+                }
+            };
+            txs.push(signed_tx);
+            nonce = nonce + 1;
+        }
+
+        if !is_batch {
+            let (tx, token) = txs.remove(0);
+            let eth_signature = self.sign_tx_message(&tx, token).await?;
+            return Ok(SignedTransactions {
+                address: self.address,
+                nonce: payload.nonce,
+                valid_until: payload.valid_until,
+                txs: vec![SignedTx { tx, eth_signature }],
+                batch_signature: None,
+            });
+        }
+
+        let fee_transfer = Transfer::new_signed(
+            payload.account_id,
+            self.address,
+            self.address,
+            payload.fee_token.id,
+            zero(),
+            payload.fee,
+            nonce,
+            time_range,
+            &self.private_key,
+        )
+        .map_err(signing_failed_error)?;
+        txs.push((ZkSyncTx::from(fee_transfer), payload.fee_token));
+
+        let batch_signature = self.sign_batch(txs.clone()).await?;
+        Ok(SignedTransactions {
+            address: self.address,
+            nonce: payload.nonce,
+            valid_until: payload.valid_until,
+            txs: txs
+                .into_iter()
+                .map(|(tx, _)| SignedTx {
+                    tx,
+                    eth_signature: None,
+                })
+                .collect(),
+            batch_signature,
+        })
+    }
+
+    /// Signs the Ethereum message of a single transaction, `ChangePubKey` is authorized
+    /// by its own Ethereum signature.
+    async fn sign_tx_message(
+        &self,
+        tx: &ZkSyncTx,
+        token: Token,
+    ) -> Result<Option<PackedEthSignature>, SignerError> {
+        let signer = match &self.eth_signer {
+            Some(signer) => signer,
+            None => return Ok(None),
+        };
+        let typed_message = match self.typed_data_2fa {
+            true => tx.get_eip712_message(token.clone()),
+            false => None,
+        };
+        let signature = match (typed_message, tx.get_ethereum_sign_message(token)) {
+            (Some(typed_message), _) => signer.sign_typed_data(&typed_message).await?,
+            (None, Some(message)) => signer.sign_message(message.as_bytes()).await?,
+            (None, None) => return Ok(None),
+        };
+        match signature {
+            TxEthSignature::EthereumSignature(packed_signature) => Ok(Some(packed_signature)),
+            _ => Err(SignerError::MissingEthSigner),
+        }
+    }
+}
+
+/// Submits the transactions signed offline, returning the handles for them.
+///
+/// Fails without sending the transactions if they have expired or the account
+/// nonce doesn't match the one they were signed for.
+pub async fn submit_signed<P: Provider + Clone>(
+    provider: &P,
+    signed: SignedTransactions,
+) -> Result<Vec<SyncTransactionHandle<P>>, ClientError> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Current time is before the unix epoch")
+        .as_secs();
+    if now > signed.valid_until {
+        return Err(ClientError::TransactionsExpired);
+    }
+
+    let account_info = provider.account_info(signed.address).await?;
+    if account_info.committed.nonce != signed.nonce {
+        return Err(ClientError::NonceMismatch {
+            expected: signed.nonce,
+            actual: account_info.committed.nonce,
+        });
+    }
+
+    let tx_hashes = if signed.txs.len() == 1 && signed.batch_signature.is_none() {
+        let SignedTx { tx, eth_signature } = signed.txs.into_iter().next().unwrap();
+        vec![provider.send_tx(tx, eth_signature).await?]
+    } else {
+        let txs = signed
+            .txs
+            .into_iter()
+            .map(|signed_tx| (signed_tx.tx, signed_tx.eth_signature))
+            .collect();
+        provider.send_txs_batch(txs, signed.batch_signature).await?
+    };
+
+    Ok(tx_hashes
+        .into_iter()
+        .map(|tx_hash| SyncTransactionHandle::new(tx_hash, provider.clone()))
+        .collect())
+}
+
+fn signing_failed_error(err: impl ToString) -> SignerError {
+    SignerError::SigningFailed(err.to_string())
+}
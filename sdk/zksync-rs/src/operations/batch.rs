@@ -0,0 +1,333 @@
+use num::BigUint;
+use zksync_eth_signer::{error::SignerError, EthereumSigner};
+use zksync_types::{
+    helpers::{closest_packable_fee_amount, closest_packable_token_amount, is_fee_amount_packable},
+    tokens::{ChangePubKeyFeeTypeArg, TxFeeTypes},
+    tx::{PackedEthSignature, TimeRange},
+    Address, Nonce, Token, TokenLike, Transfer, Withdraw, ZkSyncTx,
+};
+
+use crate::{
+    error::ClientError, operations::SyncTransactionHandle, provider::Provider, signer::Signer,
+    wallet::Wallet,
+};
+
+/// Transaction added to the batch, signed once the batch is built.
+#[derive(Debug, Clone)]
+enum BatchTx {
+    Transfer {
+        token: Token,
+        amount: BigUint,
+        to: Address,
+    },
+    Withdraw {
+        token: Token,
+        amount: BigUint,
+        to: Address,
+    },
+    ChangePubKey {
+        onchain_auth: bool,
+    },
+}
+
+impl BatchTx {
+    fn fee_type<S: EthereumSigner>(&self, signer: &Signer<S>) -> TxFeeTypes {
+        match self {
+            BatchTx::Transfer { .. } => TxFeeTypes::Transfer,
+            BatchTx::Withdraw { .. } => TxFeeTypes::Withdraw,
+            BatchTx::ChangePubKey { onchain_auth } => {
+                TxFeeTypes::ChangePubKey(ChangePubKeyFeeTypeArg::ContractsV4Version(
+                    signer.change_pubkey_type(*onchain_auth),
+                ))
+            }
+        }
+    }
+}
+
+/// Builder of the transactions batch paying a single fee and signed
+/// with a single Ethereum signature.
+///
+/// The whole batch fee is paid by an additional zero-amount transfer
+/// to the wallet itself, placed at the end of the batch.
+#[derive(Debug)]
+pub struct BatchBuilder<'a, S: EthereumSigner, P: Provider> {
+    wallet: &'a Wallet<S, P>,
+    txs: Vec<BatchTx>,
+    fee_token: Option<Token>,
+    fee: Option<BigUint>,
+    nonce: Option<Nonce>,
+    valid_from: Option<u64>,
+    valid_until: Option<u64>,
+}
+
+impl<'a, S, P> BatchBuilder<'a, S, P>
+where
+    S: EthereumSigner,
+    P: Provider + Clone,
+{
+    /// Initializes a transactions batch building process.
+    pub fn new(wallet: &'a Wallet<S, P>) -> Self {
+        Self {
+            wallet,
+            txs: Vec::new(),
+            fee_token: None,
+            fee: None,
+            nonce: None,
+            valid_from: None,
+            valid_until: None,
+        }
+    }
+
+    /// Directly returns the signed batch transactions along with the batch Ethereum signature
+    /// for the subsequent usage.
+    pub async fn txs(
+        self,
+    ) -> Result<
+        (
+            Vec<(ZkSyncTx, Option<PackedEthSignature>)>,
+            Option<PackedEthSignature>,
+        ),
+        ClientError,
+    > {
+        if self.txs.is_empty() {
+            return Err(ClientError::MissingRequiredField("txs".into()));
+        }
+        let fee_token = self
+            .fee_token
+            .ok_or_else(|| ClientError::MissingRequiredField("fee_token".into()))?;
+        let account_id = self
+            .wallet
+            .account_id()
+            .ok_or(ClientError::SigningError(SignerError::NoSigningKey))?;
+        let address = self.wallet.address();
+        let time_range = TimeRange::new(
+            self.valid_from.unwrap_or(0),
+            self.valid_until.unwrap_or(u64::MAX),
+        );
+
+        let fee = match self.fee {
+            Some(fee) => fee,
+            None => {
+                let (mut tx_types, mut addresses): (Vec<_>, Vec<_>) = self
+                    .txs
+                    .iter()
+                    .map(|tx| {
+                        let to = match tx {
+                            BatchTx::Transfer { to, .. } | BatchTx::Withdraw { to, .. } => *to,
+                            BatchTx::ChangePubKey { .. } => address,
+                        };
+                        (tx.fee_type(&self.wallet.signer), to)
+                    })
+                    .unzip();
+                // The fee transfer.
+                tx_types.push(TxFeeTypes::Transfer);
+                addresses.push(address);
+
+                self.wallet
+                    .provider
+                    .get_txs_batch_fee(tx_types, addresses, fee_token.id)
+                    .await?
+            }
+        };
+
+        let mut nonce = match self.nonce {
+            Some(nonce) => nonce,
+            None => {
+                let account_info = self.wallet.provider.account_info(address).await?;
+                account_info.committed.nonce
+            }
+        };
+
+        let private_key = &self.wallet.signer.private_key;
+        let mut txs = Vec::with_capacity(self.txs.len() + 1);
+        for tx in self.txs {
+            let signed_tx = match tx {
+                BatchTx::Transfer { token, amount, to } => {
+                    let transfer = Transfer::new_signed(
+                        account_id,
+                        address,
+                        to,
+                        token.id,
+                        amount,
+                        BigUint::from(0u32),
+                        nonce,
+                        time_range,
+                        private_key,
+                    )
+                    .map_err(|err| signing_error(err.to_string()))?;
+                    (ZkSyncTx::from(transfer), token)
+                }
+                BatchTx::Withdraw { token, amount, to } => {
+                    let withdraw = Withdraw::new_signed(
+                        account_id,
+                        address,
+                        to,
+                        token.id,
+                        amount,
+                        BigUint::from(0u32),
+                        nonce,
+                        time_range,
+                        private_key,
+                    )
+                    .map_err(|err| signing_error(err.to_string()))?;
+                    (ZkSyncTx::from(withdraw), token)
+                }
+                BatchTx::ChangePubKey { onchain_auth } => {
+                    let change_pubkey = self
+                        .wallet
+                        .signer
+                        .sign_change_pubkey_tx(
+                            nonce,
+                            onchain_auth,
+                            fee_token.clone(),
+                            BigUint::from(0u32),
+                            time_range,
+                        )
+                        .await
+                        .map_err(ClientError::SigningError)?;
+                    (ZkSyncTx::from(change_pubkey), fee_token.clone())
+                }
+            };
+            txs.push(signed_tx);
+            nonce = nonce + 1;
+        }
+
+        let fee_transfer = Transfer::new_signed(
+            account_id,
+            address,
+            address,
+            fee_token.id,
+            BigUint::from(0u32),
+            fee,
+            nonce,
+            time_range,
+            private_key,
+        )
+        .map_err(|err| signing_error(err.to_string()))?;
+        txs.push((ZkSyncTx::from(fee_transfer), fee_token));
+
+        let eth_signature = self
+            .wallet
+            .signer
+            .sign_batch(txs.clone())
+            .await
+            .map_err(ClientError::SigningError)?;
+
+        Ok((
+            txs.into_iter().map(|(tx, _)| (tx, None)).collect(),
+            eth_signature,
+        ))
+    }
+
+    /// Sends the transaction batch, returning the handles for its transactions.
+    pub async fn send(self) -> Result<Vec<SyncTransactionHandle<P>>, ClientError> {
+        let provider = self.wallet.provider.clone();
+
+        let (txs, eth_signature) = self.txs().await?;
+        let tx_hashes = provider.send_txs_batch(txs, eth_signature).await?;
+
+        Ok(tx_hashes
+            .into_iter()
+            .map(|tx_hash| SyncTransactionHandle::new(tx_hash, provider.clone()))
+            .collect())
+    }
+
+    /// Adds a transfer to the batch. If the provided amount is not packable,
+    /// rounds it to the closest packable amount.
+    pub fn add_transfer(
+        mut self,
+        token: impl Into<TokenLike>,
+        amount: impl Into<BigUint>,
+        to: Address,
+    ) -> Result<Self, ClientError> {
+        let token = self.resolve_token(token)?;
+        let amount = closest_packable_token_amount(&amount.into());
+        self.txs.push(BatchTx::Transfer { token, amount, to });
+
+        Ok(self)
+    }
+
+    /// Adds a withdrawal to the given Ethereum address to the batch.
+    pub fn add_withdraw(
+        mut self,
+        token: impl Into<TokenLike>,
+        amount: impl Into<BigUint>,
+        to: Address,
+    ) -> Result<Self, ClientError> {
+        let token = self.resolve_token(token)?;
+        self.txs.push(BatchTx::Withdraw {
+            token,
+            amount: amount.into(),
+            to,
+        });
+
+        Ok(self)
+    }
+
+    /// Adds a change public key transaction to the batch.
+    pub fn add_change_pubkey(mut self, onchain_auth: bool) -> Self {
+        self.txs.push(BatchTx::ChangePubKey { onchain_auth });
+        self
+    }
+
+    /// Sets the token to pay the batch fee in. Returns an error if token is not supported by zkSync.
+    pub fn fee_token(mut self, token: impl Into<TokenLike>) -> Result<Self, ClientError> {
+        self.fee_token = Some(self.resolve_token(token)?);
+
+        Ok(self)
+    }
+
+    /// Set the batch fee amount. If the provided fee is not packable,
+    /// rounds it to the closest packable fee amount.
+    ///
+    /// For more details, see [utils](../utils/index.html) functions.
+    pub fn fee(mut self, fee: impl Into<BigUint>) -> Self {
+        let fee = closest_packable_fee_amount(&fee.into());
+        self.fee = Some(fee);
+
+        self
+    }
+
+    /// Set the batch fee amount. If the provided fee is not packable,
+    /// returns an error.
+    ///
+    /// For more details, see [utils](../utils/index.html) functions.
+    pub fn fee_exact(mut self, fee: impl Into<BigUint>) -> Result<Self, ClientError> {
+        let fee = fee.into();
+        if !is_fee_amount_packable(&fee) {
+            return Err(ClientError::NotPackableValue);
+        }
+        self.fee = Some(fee);
+
+        Ok(self)
+    }
+
+    /// Sets the nonce of the first batch transaction, the next ones use the subsequent nonces.
+    pub fn nonce(mut self, nonce: Nonce) -> Self {
+        self.nonce = Some(nonce);
+        self
+    }
+
+    /// Sets the unix format timestamp of the first moment when the batch execution is valid.
+    pub fn valid_from(mut self, valid_from: u64) -> Self {
+        self.valid_from = Some(valid_from);
+        self
+    }
+
+    /// Sets the unix format timestamp of the last moment when the batch execution is valid.
+    pub fn valid_until(mut self, valid_until: u64) -> Self {
+        self.valid_until = Some(valid_until);
+        self
+    }
+
+    fn resolve_token(&self, token: impl Into<TokenLike>) -> Result<Token, ClientError> {
+        self.wallet
+            .tokens
+            .resolve(token.into())
+            .ok_or(ClientError::UnknownToken)
+    }
+}
+
+fn signing_error(err: String) -> ClientError {
+    ClientError::SigningError(SignerError::SigningFailed(err))
+}
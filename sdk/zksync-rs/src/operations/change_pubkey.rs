@@ -3,7 +3,6 @@ use zksync_eth_signer::EthereumSigner;
 use zksync_types::{
     helpers::{closest_packable_fee_amount, is_fee_amount_packable},
     tokens::TxFeeTypes,
-    tx::ChangePubKeyType,
     Nonce, Token, TokenLike, ZkSyncTx,
 };
 
@@ -54,15 +53,9 @@ where
                     .wallet
                     .provider
                     .get_tx_fee(
-                        if self.onchain_auth {
-                            TxFeeTypes::ChangePubKey(ChangePubKeyFeeTypeArg::ContractsV4Version(
-                                ChangePubKeyType::Onchain,
-                            ))
-                        } else {
-                            TxFeeTypes::ChangePubKey(ChangePubKeyFeeTypeArg::ContractsV4Version(
-                                ChangePubKeyType::ECDSA,
-                            ))
-                        },
+                        TxFeeTypes::ChangePubKey(ChangePubKeyFeeTypeArg::ContractsV4Version(
+                            self.wallet.signer.change_pubkey_type(self.onchain_auth),
+                        )),
                         self.wallet.address(),
                         fee_token.id,
                     )
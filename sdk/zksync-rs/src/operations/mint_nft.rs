@@ -8,7 +8,8 @@ use zksync_types::{
 };
 
 use crate::{
-    error::ClientError, operations::SyncTransactionHandle, provider::Provider, wallet::Wallet,
+    error::ClientError, operations::SyncTransactionHandle, provider::Provider,
+    utils::content_hash_from_ipfs_cid, wallet::Wallet,
 };
 
 #[derive(Debug)]
@@ -162,4 +163,11 @@ where
         self.content_hash = Some(content_hash);
         self
     }
+
+    /// Sets the transaction content hash to the one of the content stored in IPFS
+    /// with the given CIDv0.
+    pub fn ipfs_cid(mut self, cid: &str) -> Result<Self, ClientError> {
+        self.content_hash = Some(content_hash_from_ipfs_cid(cid)?);
+        Ok(self)
+    }
 }
@@ -1,8 +1,15 @@
 //! This file contains representation of not signed transactions and builders for them.
 
-use std::time::{Duration, Instant};
+use std::time::Duration;
 
-use zksync_types::tx::TxHash;
+use futures::StreamExt;
+use serde_json::json;
+use web3::{
+    api::{SubscriptionId, SubscriptionStream},
+    transports::WebSocket,
+    Transport,
+};
+use zksync_types::{tx::TxHash, ActionType};
 
 use crate::{
     error::ClientError,
@@ -11,28 +18,48 @@ use crate::{
 };
 
 pub use self::{
-    change_pubkey::ChangePubKeyBuilder, mint_nft::MintNFTBuilder, transfer::TransferBuilder,
-    transfer_nft::TransferNFTBuilder, withdraw::WithdrawBuilder, withdraw_nft::WithdrawNFTBuilder,
+    batch::BatchBuilder,
+    change_pubkey::ChangePubKeyBuilder,
+    mint_nft::MintNFTBuilder,
+    sponsored_batch::{SponsoredBatch, SponsoredBatchBuilder},
+    swap::{OrderBuilder, SignedOrder, SwapBuilder},
+    transfer::TransferBuilder,
+    transfer_nft::TransferNFTBuilder,
+    withdraw::WithdrawBuilder,
+    withdraw_nft::WithdrawNFTBuilder,
 };
 
+mod batch;
 mod change_pubkey;
 mod mint_nft;
+mod sponsored_batch;
+mod swap;
 mod transfer;
 mod transfer_nft;
 mod withdraw;
 mod withdraw_nft;
 
 /// Handle for transaction, providing an interface to control its execution.
-/// For obtained handle it's possible to set the polling interval, commit timeout
-/// and verify timeout values.
+/// For obtained handle it's possible to set the polling interval and its backoff,
+/// commit timeout and verify timeout values.
 ///
 /// By default, awaiting for transaction may run up to forever, and the polling is
-/// performed once a second.
+/// performed once a second. If the WebSocket URL of the server is set, the transaction
+/// state is received via the subscription instead, falling back to polling if the
+/// subscription is not available.
+///
+/// The failed transaction is awaited the same way as the successful one and its information
+/// contains the fail reason. With `fail_on_rejection` set, awaiting stops with
+/// a `ClientError::TransactionFailed` error once the transaction is executed unsuccessfully.
 #[derive(Debug)]
 pub struct SyncTransactionHandle<P: Provider> {
     hash: TxHash,
     provider: P,
     polling_interval: Duration,
+    max_polling_interval: Duration,
+    backoff_multiplier: f64,
+    ws_url: Option<String>,
+    fail_on_rejection: bool,
     commit_timeout: Option<Duration>,
     verify_timeout: Option<Duration>,
 }
@@ -43,8 +70,12 @@ impl<P: Provider> SyncTransactionHandle<P> {
             hash,
             provider,
             polling_interval: Duration::from_secs(1), // 1 second.
-            commit_timeout: None,                     // Wait until forever
-            verify_timeout: None,                     // Wait until forever
+            max_polling_interval: Duration::from_secs(1),
+            backoff_multiplier: 1.0, // No backoff.
+            ws_url: None,
+            fail_on_rejection: false,
+            commit_timeout: None, // Wait until forever
+            verify_timeout: None, // Wait until forever
         }
     }
 
@@ -54,12 +85,41 @@ impl<P: Provider> SyncTransactionHandle<P> {
     pub fn polling_interval(&mut self, polling_interval: Duration) -> Result<(), ClientError> {
         if polling_interval >= Self::MIN_POLLING_INTERVAL {
             self.polling_interval = polling_interval;
+            self.max_polling_interval = self.max_polling_interval.max(polling_interval);
             Ok(())
         } else {
             Err(ClientError::PollingIntervalIsTooSmall)
         }
     }
 
+    /// Enables the exponential backoff of the polling: each next polling interval is
+    /// `multiplier` times longer than the previous one, up to `max_polling_interval`.
+    pub fn backoff(
+        mut self,
+        multiplier: f64,
+        max_polling_interval: Duration,
+    ) -> Result<Self, ClientError> {
+        if multiplier < 1.0 || max_polling_interval < self.polling_interval {
+            return Err(ClientError::IncorrectInput);
+        }
+        self.backoff_multiplier = multiplier;
+        self.max_polling_interval = max_polling_interval;
+        Ok(self)
+    }
+
+    /// Sets the WebSocket URL of the server to await for the transaction via the subscription.
+    pub fn websocket(mut self, ws_url: impl Into<String>) -> Self {
+        self.ws_url = Some(ws_url.into());
+        self
+    }
+
+    /// Makes awaiting for the failed transaction return the `ClientError::TransactionFailed` error
+    /// with the fail reason instead of the transaction information.
+    pub fn fail_on_rejection(mut self) -> Self {
+        self.fail_on_rejection = true;
+        self
+    }
+
     /// Returns the transaction hash.
     pub fn hash(&self) -> TxHash {
         self.hash
@@ -83,43 +143,137 @@ impl<P: Provider> SyncTransactionHandle<P> {
 
     /// Awaits for the transaction commit and returns the information about its execution.
     pub async fn wait_for_commit(&self) -> Result<TransactionInfo, ClientError> {
-        self.wait_for(|block| block.committed, self.commit_timeout)
-            .await
+        self.wait_for(
+            |block| block.committed,
+            ActionType::COMMIT,
+            self.commit_timeout,
+        )
+        .await
     }
 
     /// Awaits for the transaction verification and returns the information about its execution.
     pub async fn wait_for_verify(&self) -> Result<TransactionInfo, ClientError> {
-        self.wait_for(|block| block.verified, self.verify_timeout)
-            .await
+        self.wait_for(
+            |block| block.verified,
+            ActionType::VERIFY,
+            self.verify_timeout,
+        )
+        .await
     }
 
     /// Awaits for the transaction to reach given state and returns the information about its execution.
     async fn wait_for<WaitPredicate>(
         &self,
         condition: WaitPredicate,
+        action: ActionType,
         timeout: Option<Duration>,
     ) -> Result<TransactionInfo, ClientError>
     where
         WaitPredicate: Fn(&BlockInfo) -> bool,
     {
-        let mut timer = tokio::time::interval(self.polling_interval);
-        let start = Instant::now();
-
-        loop {
-            timer.tick().await;
-
-            if let Some(timeout) = timeout {
-                if start.elapsed() >= timeout {
-                    return Err(ClientError::OperationTimeout);
+        let wait = async {
+            if let Some(ws_url) = &self.ws_url {
+                match self.subscribe(ws_url, &condition, action).await {
+                    Err(ClientError::NetworkError(_)) => {}
+                    result => return result,
                 }
             }
+            self.poll(&condition).await
+        };
 
+        match timeout {
+            Some(timeout) => tokio::time::timeout(timeout, wait)
+                .await
+                .map_err(|_| ClientError::OperationTimeout)?,
+            None => wait.await,
+        }
+    }
+
+    async fn poll<WaitPredicate>(
+        &self,
+        condition: &WaitPredicate,
+    ) -> Result<TransactionInfo, ClientError>
+    where
+        WaitPredicate: Fn(&BlockInfo) -> bool,
+    {
+        let mut interval = self.polling_interval;
+        loop {
             let response = self.provider.tx_info(self.hash).await?;
-            if let Some(block) = &response.block {
-                if condition(block) {
-                    return Ok(response);
-                }
+            if let Some(response) = check_tx_info(response, condition, self.fail_on_rejection)? {
+                return Ok(response);
+            }
+
+            tokio::time::sleep(interval).await;
+            interval = interval
+                .mul_f64(self.backoff_multiplier)
+                .min(self.max_polling_interval);
+        }
+    }
+
+    /// Awaits for the transaction via the `tx_subscribe` subscription.
+    /// Returns a `ClientError::NetworkError` if the subscription is not available.
+    async fn subscribe<WaitPredicate>(
+        &self,
+        ws_url: &str,
+        condition: &WaitPredicate,
+        action: ActionType,
+    ) -> Result<TransactionInfo, ClientError>
+    where
+        WaitPredicate: Fn(&BlockInfo) -> bool,
+    {
+        let network_error = |err: web3::Error| ClientError::NetworkError(err.to_string());
+
+        let transport = WebSocket::new(ws_url).await.map_err(network_error)?;
+        let subscription_id = transport
+            .execute(
+                "tx_subscribe",
+                vec![json!(self.hash), json!(action.to_string())],
+            )
+            .await
+            .map_err(network_error)?;
+        let subscription_id: String = serde_json::from_value(subscription_id)
+            .map_err(|err| ClientError::MalformedResponse(err.to_string()))?;
+        let mut notifications = SubscriptionStream::<_, TransactionInfo>::new(
+            transport,
+            SubscriptionId::from(subscription_id),
+        )
+        .map_err(network_error)?;
+
+        // The transaction may have reached the state before the subscription.
+        let response = self.provider.tx_info(self.hash).await?;
+        if let Some(response) = check_tx_info(response, condition, self.fail_on_rejection)? {
+            return Ok(response);
+        }
+
+        while let Some(response) = notifications.next().await {
+            let response = response.map_err(network_error)?;
+            if let Some(response) = check_tx_info(response, condition, self.fail_on_rejection)? {
+                return Ok(response);
             }
         }
+        Err(ClientError::NetworkError(
+            "Subscription was closed by the server".to_string(),
+        ))
+    }
+}
+
+/// Returns the transaction information if the transaction has reached the awaited state,
+/// or an error if it has failed and `fail_on_rejection` is set.
+fn check_tx_info<WaitPredicate>(
+    response: TransactionInfo,
+    condition: &WaitPredicate,
+    fail_on_rejection: bool,
+) -> Result<Option<TransactionInfo>, ClientError>
+where
+    WaitPredicate: Fn(&BlockInfo) -> bool,
+{
+    if fail_on_rejection && response.executed && response.success == Some(false) {
+        return Err(ClientError::TransactionFailed(
+            response.fail_reason.unwrap_or_default(),
+        ));
+    }
+    match &response.block {
+        Some(block) if condition(block) => Ok(Some(response)),
+        _ => Ok(None),
     }
 }
@@ -0,0 +1,260 @@
+use num::BigUint;
+use zksync_eth_signer::{error::SignerError, EthereumSigner};
+use zksync_types::{
+    helpers::{closest_packable_fee_amount, is_fee_amount_packable},
+    tx::{EthBatchSignData, PackedEthSignature, TimeRange},
+    Address, Nonce, Token, TokenLike, Transfer, TxFeeTypes, ZkSyncTx,
+};
+
+use crate::{
+    error::ClientError, operations::SyncTransactionHandle, provider::Provider, wallet::Wallet,
+};
+
+/// Builder of the transactions batch in which the sponsor wallet pays the fee
+/// for the transactions of other accounts.
+///
+/// The transactions of the other accounts are signed by them with zero fee and added
+/// to the batch, then the sponsor appends the zero-amount transfer to itself paying
+/// the fee of the whole batch. The built batch has to be signed by every its author.
+#[derive(Debug)]
+pub struct SponsoredBatchBuilder<'a, S: EthereumSigner, P: Provider> {
+    sponsor: &'a Wallet<S, P>,
+    txs: Vec<(ZkSyncTx, Token)>,
+    fee_token: Option<Token>,
+    fee: Option<BigUint>,
+    nonce: Option<Nonce>,
+}
+
+impl<'a, S, P> SponsoredBatchBuilder<'a, S, P>
+where
+    S: EthereumSigner,
+    P: Provider + Clone,
+{
+    /// Initializes a sponsored transactions batch building process.
+    pub fn new(sponsor: &'a Wallet<S, P>) -> Self {
+        Self {
+            sponsor,
+            txs: Vec::new(),
+            fee_token: None,
+            fee: None,
+            nonce: None,
+        }
+    }
+
+    /// Returns the batch with the sponsor's fee transfer, signed by the sponsor.
+    pub async fn build(self) -> Result<SponsoredBatch, ClientError> {
+        if self.txs.is_empty() {
+            return Err(ClientError::MissingRequiredField("txs".into()));
+        }
+        let fee_token = self
+            .fee_token
+            .ok_or_else(|| ClientError::MissingRequiredField("fee_token".into()))?;
+        let account_id = self
+            .sponsor
+            .account_id()
+            .ok_or(ClientError::SigningError(SignerError::NoSigningKey))?;
+        let address = self.sponsor.address();
+
+        let fee = match self.fee {
+            Some(fee) => fee,
+            None => {
+                let (mut tx_types, mut addresses): (Vec<_>, Vec<_>) = self
+                    .txs
+                    .iter()
+                    .filter_map(|(tx, _)| tx.get_fee_info())
+                    .map(|(tx_type, _, to, _)| (tx_type, to))
+                    .unzip();
+                // The fee transfer.
+                tx_types.push(TxFeeTypes::Transfer);
+                addresses.push(address);
+
+                self.sponsor
+                    .provider
+                    .get_txs_batch_fee(tx_types, addresses, fee_token.id)
+                    .await?
+            }
+        };
+
+        let nonce = match self.nonce {
+            Some(nonce) => nonce,
+            None => {
+                let account_info = self.sponsor.provider.account_info(address).await?;
+                account_info.committed.nonce
+            }
+        };
+
+        let fee_transfer = Transfer::new_signed(
+            account_id,
+            address,
+            address,
+            fee_token.id,
+            BigUint::from(0u32),
+            fee,
+            nonce,
+            TimeRange::default(),
+            &self.sponsor.signer.private_key,
+        )
+        .map_err(|err| ClientError::SigningError(SignerError::SigningFailed(err.to_string())))?;
+
+        let mut txs = self.txs;
+        txs.push((ZkSyncTx::from(fee_transfer), fee_token));
+        let mut batch = SponsoredBatch {
+            txs,
+            eth_signatures: Vec::new(),
+        };
+        batch.sign(self.sponsor).await?;
+
+        Ok(batch)
+    }
+
+    /// Adds a transaction of another account to the batch. The transaction is expected
+    /// to have zero fee, its Ethereum signature is replaced by the batch one.
+    ///
+    /// Swaps and forced exits can't be sponsored.
+    pub fn add_tx(mut self, tx: ZkSyncTx) -> Result<Self, ClientError> {
+        if matches!(
+            tx,
+            ZkSyncTx::Swap(_) | ZkSyncTx::ForcedExit(_) | ZkSyncTx::Close(_)
+        ) {
+            return Err(ClientError::IncorrectInput);
+        }
+        let token = self.resolve_token(tx.token_id())?;
+        self.txs.push((tx, token));
+
+        Ok(self)
+    }
+
+    /// Sets the token to pay the batch fee in. Returns an error if token is not supported by zkSync.
+    pub fn fee_token(mut self, token: impl Into<TokenLike>) -> Result<Self, ClientError> {
+        self.fee_token = Some(self.resolve_token(token)?);
+
+        Ok(self)
+    }
+
+    /// Set the batch fee amount. If the provided fee is not packable,
+    /// rounds it to the closest packable fee amount.
+    ///
+    /// For more details, see [utils](../utils/index.html) functions.
+    pub fn fee(mut self, fee: impl Into<BigUint>) -> Self {
+        let fee = closest_packable_fee_amount(&fee.into());
+        self.fee = Some(fee);
+
+        self
+    }
+
+    /// Set the batch fee amount. If the provided fee is not packable,
+    /// returns an error.
+    ///
+    /// For more details, see [utils](../utils/index.html) functions.
+    pub fn fee_exact(mut self, fee: impl Into<BigUint>) -> Result<Self, ClientError> {
+        let fee = fee.into();
+        if !is_fee_amount_packable(&fee) {
+            return Err(ClientError::NotPackableValue);
+        }
+        self.fee = Some(fee);
+
+        Ok(self)
+    }
+
+    /// Sets the nonce of the sponsor's fee transfer.
+    pub fn nonce(mut self, nonce: Nonce) -> Self {
+        self.nonce = Some(nonce);
+        self
+    }
+
+    fn resolve_token(&self, token: impl Into<TokenLike>) -> Result<Token, ClientError> {
+        self.sponsor
+            .tokens
+            .resolve(token.into())
+            .ok_or(ClientError::UnknownToken)
+    }
+}
+
+/// Sponsored transactions batch collecting the Ethereum signatures of its authors.
+///
+/// Every author signs the message of the whole batch, either with [`SponsoredBatch::sign`]
+/// or elsewhere, providing the signature via [`SponsoredBatch::add_signature`].
+#[derive(Debug, Clone)]
+pub struct SponsoredBatch {
+    txs: Vec<(ZkSyncTx, Token)>,
+    eth_signatures: Vec<PackedEthSignature>,
+}
+
+impl SponsoredBatch {
+    /// Transactions of the batch, the last one is the sponsor's fee transfer.
+    pub fn txs(&self) -> Vec<ZkSyncTx> {
+        self.txs.iter().map(|(tx, _)| tx.clone()).collect()
+    }
+
+    /// Accounts that have to sign the batch.
+    pub fn authors(&self) -> Vec<Address> {
+        let mut authors = Vec::new();
+        for (tx, _) in &self.txs {
+            if !authors.contains(&tx.account()) {
+                authors.push(tx.account());
+            }
+        }
+        authors
+    }
+
+    /// Ethereum message of the batch signed by every author.
+    pub fn message(&self) -> Vec<u8> {
+        EthBatchSignData::get_batch_sign_message(
+            self.txs
+                .iter()
+                .map(|(tx, token)| (tx.clone(), token.clone(), tx.account()))
+                .collect(),
+        )
+    }
+
+    /// Ethereum signatures of the batch collected so far.
+    pub fn eth_signatures(&self) -> &[PackedEthSignature] {
+        &self.eth_signatures
+    }
+
+    /// Signs the batch with the Ethereum signer of the wallet, which has to be one of the authors.
+    pub async fn sign<S, P>(&mut self, wallet: &Wallet<S, P>) -> Result<(), ClientError>
+    where
+        S: EthereumSigner,
+        P: Provider + Clone,
+    {
+        if !self.authors().contains(&wallet.address()) {
+            return Err(ClientError::IncorrectInput);
+        }
+        let txs = self
+            .txs
+            .iter()
+            .map(|(tx, token)| (tx.clone(), token.clone(), tx.account()))
+            .collect();
+        let signature = wallet
+            .signer
+            .sign_multi_author_batch(txs)
+            .await
+            .map_err(ClientError::SigningError)?
+            .ok_or(ClientError::NoEthereumPrivateKey)?;
+        self.eth_signatures.push(signature);
+
+        Ok(())
+    }
+
+    /// Adds the Ethereum signature of the batch message made by one of the authors.
+    pub fn add_signature(&mut self, signature: PackedEthSignature) {
+        self.eth_signatures.push(signature);
+    }
+
+    /// Sends the batch, returning the handles for its transactions.
+    pub async fn send<P: Provider + Clone>(
+        self,
+        provider: &P,
+    ) -> Result<Vec<SyncTransactionHandle<P>>, ClientError> {
+        let txs = self.txs.into_iter().map(|(tx, _)| (tx, None)).collect();
+        let tx_hashes = provider
+            .send_multi_author_txs_batch(txs, self.eth_signatures)
+            .await?;
+
+        Ok(tx_hashes
+            .into_iter()
+            .map(|tx_hash| SyncTransactionHandle::new(tx_hash, provider.clone()))
+            .collect())
+    }
+}
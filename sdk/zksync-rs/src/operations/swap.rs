@@ -0,0 +1,358 @@
+use num::{BigUint, Zero};
+use serde::{Deserialize, Serialize};
+use zksync_eth_signer::EthereumSigner;
+use zksync_types::{
+    helpers::{
+        closest_packable_fee_amount, closest_packable_token_amount, is_fee_amount_packable,
+        is_token_amount_packable,
+    },
+    tx::{PackedEthSignature, TimeRange},
+    Address, Nonce, Order, Swap, Token, TokenLike, TxFeeTypes,
+};
+
+use crate::{
+    error::ClientError, operations::SyncTransactionHandle, provider::Provider, wallet::Wallet,
+};
+
+/// Swap order signed by its owner, along with the Ethereum signature of the order message
+/// (if the owner has an Ethereum signer).
+///
+/// Signed orders are meant to be passed to the swap submitter, which is not necessarily
+/// the owner of any of the orders.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedOrder {
+    pub order: Order,
+    pub eth_signature: Option<PackedEthSignature>,
+}
+
+#[derive(Debug)]
+pub struct OrderBuilder<'a, S: EthereumSigner, P: Provider> {
+    wallet: &'a Wallet<S, P>,
+    token_sell: Option<Token>,
+    token_buy: Option<Token>,
+    price: Option<(BigUint, BigUint)>,
+    amount: Option<BigUint>,
+    recipient: Option<Address>,
+    nonce: Option<Nonce>,
+    valid_from: Option<u64>,
+    valid_until: Option<u64>,
+}
+
+impl<'a, S, P> OrderBuilder<'a, S, P>
+where
+    S: EthereumSigner,
+    P: Provider + Clone,
+{
+    /// Initializes an order building process.
+    pub fn new(wallet: &'a Wallet<S, P>) -> Self {
+        Self {
+            wallet,
+            token_sell: None,
+            token_buy: None,
+            price: None,
+            amount: None,
+            recipient: None,
+            nonce: None,
+            valid_from: None,
+            valid_until: None,
+        }
+    }
+
+    /// Signs the order. If the amount is not set, the order is a limit one.
+    pub async fn sign(self) -> Result<SignedOrder, ClientError> {
+        let token_sell = self
+            .token_sell
+            .ok_or_else(|| ClientError::MissingRequiredField("token_sell".into()))?;
+        let token_buy = self
+            .token_buy
+            .ok_or_else(|| ClientError::MissingRequiredField("token_buy".into()))?;
+        let price = self
+            .price
+            .ok_or_else(|| ClientError::MissingRequiredField("ratio".into()))?;
+        let amount = self.amount.unwrap_or_else(BigUint::zero);
+        let recipient = self.recipient.unwrap_or_else(|| self.wallet.address());
+        let valid_from = self.valid_from.unwrap_or(0);
+        let valid_until = self.valid_until.unwrap_or(u64::MAX);
+
+        let nonce = match self.nonce {
+            Some(nonce) => nonce,
+            None => {
+                let account_info = self
+                    .wallet
+                    .provider
+                    .account_info(self.wallet.address())
+                    .await?;
+                account_info.committed.nonce
+            }
+        };
+
+        self.wallet
+            .signer
+            .sign_order(
+                recipient,
+                nonce,
+                token_sell,
+                token_buy,
+                price,
+                amount,
+                TimeRange::new(valid_from, valid_until),
+            )
+            .await
+            .map(|(order, eth_signature)| SignedOrder {
+                order,
+                eth_signature,
+            })
+            .map_err(ClientError::SigningError)
+    }
+
+    /// Sets the token to be sold. Returns an error if token is not supported by zkSync.
+    pub fn token_sell(mut self, token: impl Into<TokenLike>) -> Result<Self, ClientError> {
+        let token = self
+            .wallet
+            .tokens
+            .resolve(token.into())
+            .ok_or(ClientError::UnknownToken)?;
+
+        self.token_sell = Some(token);
+
+        Ok(self)
+    }
+
+    /// Sets the token to be bought. Returns an error if token is not supported by zkSync.
+    pub fn token_buy(mut self, token: impl Into<TokenLike>) -> Result<Self, ClientError> {
+        let token = self
+            .wallet
+            .tokens
+            .resolve(token.into())
+            .ok_or(ClientError::UnknownToken)?;
+
+        self.token_buy = Some(token);
+
+        Ok(self)
+    }
+
+    /// Sets the exchange ratio in the minimal units of the tokens: the order allows to get
+    /// `buy` units of the bought token for `sell` units of the sold one.
+    pub fn ratio(mut self, sell: impl Into<BigUint>, buy: impl Into<BigUint>) -> Self {
+        self.price = Some((sell.into(), buy.into()));
+        self
+    }
+
+    /// Set the amount of the sold token. If the provided amount is not packable,
+    /// rounds it to the closest packable amount.
+    ///
+    /// For more details, see [utils](../utils/index.html) functions.
+    pub fn amount(mut self, amount: impl Into<BigUint>) -> Self {
+        let amount = closest_packable_token_amount(&amount.into());
+        self.amount = Some(amount);
+
+        self
+    }
+
+    /// Set the amount of the sold token. If the provided amount is not packable,
+    /// returns an error.
+    ///
+    /// For more details, see [utils](../utils/index.html) functions.
+    pub fn amount_exact(mut self, amount: impl Into<BigUint>) -> Result<Self, ClientError> {
+        let amount = amount.into();
+        if !is_token_amount_packable(&amount) {
+            return Err(ClientError::NotPackableValue);
+        }
+        self.amount = Some(amount);
+
+        Ok(self)
+    }
+
+    /// Sets the recipient of the bought token. By default, it's the wallet address.
+    pub fn recipient(mut self, recipient: Address) -> Self {
+        self.recipient = Some(recipient);
+        self
+    }
+
+    /// Sets the unix format timestamp of the first moment when the order can be filled.
+    pub fn valid_from(mut self, valid_from: u64) -> Self {
+        self.valid_from = Some(valid_from);
+        self
+    }
+
+    /// Sets the unix format timestamp of the last moment when the order can be filled.
+    pub fn valid_until(mut self, valid_until: u64) -> Self {
+        self.valid_until = Some(valid_until);
+        self
+    }
+
+    /// Sets the order nonce.
+    pub fn nonce(mut self, nonce: Nonce) -> Self {
+        self.nonce = Some(nonce);
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct SwapBuilder<'a, S: EthereumSigner, P: Provider> {
+    wallet: &'a Wallet<S, P>,
+    orders: Option<(SignedOrder, SignedOrder)>,
+    amounts: Option<(BigUint, BigUint)>,
+    fee_token: Option<Token>,
+    fee: Option<BigUint>,
+    nonce: Option<Nonce>,
+}
+
+impl<'a, S, P> SwapBuilder<'a, S, P>
+where
+    S: EthereumSigner,
+    P: Provider + Clone,
+{
+    /// Initializes a swap transaction building process.
+    pub fn new(wallet: &'a Wallet<S, P>) -> Self {
+        Self {
+            wallet,
+            orders: None,
+            amounts: None,
+            fee_token: None,
+            fee: None,
+            nonce: None,
+        }
+    }
+
+    /// Directly returns the signed swap transaction along with the Ethereum signatures
+    /// of the swap and of its orders for the subsequent usage.
+    #[allow(clippy::type_complexity)]
+    pub async fn tx(
+        self,
+    ) -> Result<
+        (
+            Swap,
+            Option<PackedEthSignature>,
+            (Option<PackedEthSignature>, Option<PackedEthSignature>),
+        ),
+        ClientError,
+    > {
+        let (first, second) = self
+            .orders
+            .ok_or_else(|| ClientError::MissingRequiredField("orders".into()))?;
+        let fee_token = self
+            .fee_token
+            .ok_or_else(|| ClientError::MissingRequiredField("fee_token".into()))?;
+
+        // Amounts of the orders with the explicit amounts are known in advance,
+        // limit orders require them to be set for the swap.
+        let amounts = match self.amounts {
+            Some(amounts) => amounts,
+            None if !first.order.amount.is_zero() && !second.order.amount.is_zero() => {
+                (first.order.amount.clone(), second.order.amount.clone())
+            }
+            None => return Err(ClientError::MissingRequiredField("amounts".into())),
+        };
+
+        let nonce = match self.nonce {
+            Some(nonce) => nonce,
+            None => {
+                let account_info = self
+                    .wallet
+                    .provider
+                    .account_info(self.wallet.address())
+                    .await?;
+                account_info.committed.nonce
+            }
+        };
+
+        let fee = match self.fee {
+            Some(fee) => fee,
+            None => {
+                let fee = self
+                    .wallet
+                    .provider
+                    .get_tx_fee(TxFeeTypes::Swap, self.wallet.address(), fee_token.id)
+                    .await?;
+                fee.total_fee
+            }
+        };
+
+        let (swap, eth_signature) = self
+            .wallet
+            .signer
+            .sign_swap((first.order, second.order), amounts, fee_token, fee, nonce)
+            .await
+            .map_err(ClientError::SigningError)?;
+
+        Ok((
+            swap,
+            eth_signature,
+            (first.eth_signature, second.eth_signature),
+        ))
+    }
+
+    /// Sends the transaction, returning the handle for its awaiting.
+    pub async fn send(self) -> Result<SyncTransactionHandle<P>, ClientError> {
+        let provider = self.wallet.provider.clone();
+
+        let (swap, eth_signature, orders_eth_signatures) = self.tx().await?;
+        let tx_hash = provider
+            .send_swap(swap, eth_signature, orders_eth_signatures)
+            .await?;
+
+        Ok(SyncTransactionHandle::new(tx_hash, provider))
+    }
+
+    /// Sets the orders to be swapped. The first order sells the token the second one buys.
+    pub fn orders(mut self, first: SignedOrder, second: SignedOrder) -> Self {
+        self.orders = Some((first, second));
+        self
+    }
+
+    /// Sets the amounts sold by the first and the second order respectively.
+    /// Required if any of the orders is a limit one.
+    pub fn amounts(mut self, first: impl Into<BigUint>, second: impl Into<BigUint>) -> Self {
+        self.amounts = Some((
+            closest_packable_token_amount(&first.into()),
+            closest_packable_token_amount(&second.into()),
+        ));
+        self
+    }
+
+    /// Sets the transaction fee token. Returns an error if token is not supported by zkSync.
+    pub fn fee_token(mut self, token: impl Into<TokenLike>) -> Result<Self, ClientError> {
+        let token = self
+            .wallet
+            .tokens
+            .resolve(token.into())
+            .ok_or(ClientError::UnknownToken)?;
+
+        self.fee_token = Some(token);
+
+        Ok(self)
+    }
+
+    /// Set the fee amount. If the provided fee is not packable,
+    /// rounds it to the closest packable fee amount.
+    ///
+    /// For more details, see [utils](../utils/index.html) functions.
+    pub fn fee(mut self, fee: impl Into<BigUint>) -> Self {
+        let fee = closest_packable_fee_amount(&fee.into());
+        self.fee = Some(fee);
+
+        self
+    }
+
+    /// Set the fee amount. If the provided fee is not packable,
+    /// returns an error.
+    ///
+    /// For more details, see [utils](../utils/index.html) functions.
+    pub fn fee_exact(mut self, fee: impl Into<BigUint>) -> Result<Self, ClientError> {
+        let fee = fee.into();
+        if !is_fee_amount_packable(&fee) {
+            return Err(ClientError::NotPackableValue);
+        }
+        self.fee = Some(fee);
+
+        Ok(self)
+    }
+
+    /// Sets the transaction nonce.
+    pub fn nonce(mut self, nonce: Nonce) -> Self {
+        self.nonce = Some(nonce);
+        self
+    }
+}
@@ -2,18 +2,23 @@
 // from: https://github.com/matter-labs/zksync-dev/blob/dev/core/loadtest/src/rpc_client.rs
 
 // Built-in imports
-use std::time::Duration;
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 
 // External uses
 use async_trait::async_trait;
+use bigdecimal::BigDecimal;
 use jsonrpc_core::{types::response::Output, ErrorCode};
 use num::BigUint;
+use rand::Rng;
 
 // Workspace uses
 use zksync_types::{
     network::Network,
     tx::{PackedEthSignature, TxHash, ZkSyncTx},
-    Address, TokenLike, TxFeeTypes,
+    Address, Swap, TokenLike, TxFeeTypes,
 };
 
 // Local uses
@@ -65,6 +70,12 @@ pub trait Provider {
         token: impl Into<TokenLike> + Send + 'async_trait,
     ) -> ResponseResult<BigUint>;
 
+    /// Requests and returns the USD price of one token.
+    async fn get_token_price(
+        &self,
+        token: impl Into<TokenLike> + Send + 'async_trait,
+    ) -> ResponseResult<BigDecimal>;
+
     /// Requests and returns information about an Ethereum operation given its `serial_id`.
     async fn ethop_info(&self, serial_id: u32) -> ResponseResult<EthOpInfo>;
 
@@ -93,34 +104,196 @@ pub trait Provider {
         eth_signature: Option<PackedEthSignature>,
     ) -> ResponseResult<Vec<TxHash>>;
 
+    /// Submits a batch of transactions of several accounts to the zkSync network,
+    /// every account provides its own Ethereum signature of the whole batch.
+    /// Returns the hashes of the created transactions.
+    async fn send_multi_author_txs_batch(
+        &self,
+        txs_signed: Vec<(ZkSyncTx, Option<PackedEthSignature>)>,
+        eth_signatures: Vec<PackedEthSignature>,
+    ) -> ResponseResult<Vec<TxHash>>;
+
+    /// Submits a swap to the zkSync network along with the Ethereum signatures
+    /// of the swap itself and of both of its orders.
+    /// Returns the hash of the created transaction.
+    async fn send_swap(
+        &self,
+        swap: Swap,
+        eth_signature: Option<PackedEthSignature>,
+        orders_eth_signatures: (Option<PackedEthSignature>, Option<PackedEthSignature>),
+    ) -> ResponseResult<TxHash>;
+
     /// Type of network this provider is allowing access to.
     fn network(&self) -> Network;
 }
 
+/// Kind of the provider call, every kind has its own timeout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallType {
+    /// Requests of the accounts, transactions, tokens and other server data.
+    Query,
+    /// Requests of the fees and token prices, which may involve the price providers on the server side.
+    Fee,
+    /// Submissions of the transactions, batches and swaps.
+    Submission,
+}
+
+/// Timeouts of the provider calls by their type.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CallTimeouts {
+    pub query: Duration,
+    pub fee: Duration,
+    pub submission: Duration,
+}
+
+impl Default for CallTimeouts {
+    fn default() -> Self {
+        Self {
+            query: Duration::from_secs(10),
+            fee: Duration::from_secs(10),
+            submission: Duration::from_secs(30),
+        }
+    }
+}
+
+impl CallTimeouts {
+    /// Returns the timeout of the call of the given type.
+    pub fn get(&self, call_type: CallType) -> Duration {
+        match call_type {
+            CallType::Query => self.query,
+            CallType::Fee => self.fee,
+            CallType::Submission => self.submission,
+        }
+    }
+}
+
+/// Policy of retrying the calls failed with the transient errors, i.e. the network errors
+/// and the internal server errors.
+///
+/// Delay before each next retry is twice as long as the previous one, up to `max_backoff`.
+/// If `jitter` is enabled, the delay is randomized between its half and its full value,
+/// so that the clients failed at the same time don't retry simultaneously.
+///
+/// Submissions are only retried if the request didn't reach the server, since otherwise the transaction
+/// may be accepted already and resending it would be rejected.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// Max amount of attempts of the call, including the first one.
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 10,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(30),
+            jitter: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Policy making every call exactly once.
+    pub fn no_retries() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Returns the delay before the retry with the given number (starting from zero).
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let multiplier = 2u32.saturating_pow(retry);
+        let delay = self
+            .initial_backoff
+            .checked_mul(multiplier)
+            .map_or(self.max_backoff, |delay| delay.min(self.max_backoff));
+        if self.jitter {
+            let half = delay / 2;
+            half + half.mul_f64(rand::thread_rng().gen::<f64>())
+        } else {
+            delay
+        }
+    }
+}
+
+/// Availability of the API endpoint according to the last requests to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EndpointStatus {
+    pub addr: String,
+    pub available: bool,
+}
+
+#[derive(Debug)]
+struct Endpoint {
+    addr: String,
+    /// The endpoint is not used until this moment after the transient error (unless all the endpoints are unavailable).
+    unavailable_until: Mutex<Option<Instant>>,
+}
+
+impl Endpoint {
+    fn new(addr: String) -> Self {
+        Self {
+            addr,
+            unavailable_until: Mutex::new(None),
+        }
+    }
+
+    fn unavailable_until(&self) -> Option<Instant> {
+        let mut unavailable_until = self.unavailable_until.lock().unwrap();
+        if matches!(*unavailable_until, Some(until) if until <= Instant::now()) {
+            *unavailable_until = None;
+        }
+        *unavailable_until
+    }
+
+    fn set_available(&self, available: bool, cooldown: Duration) {
+        *self.unavailable_until.lock().unwrap() = if available {
+            None
+        } else {
+            Some(Instant::now() + cooldown)
+        };
+    }
+}
+
 /// `RpcProvider` is capable of interacting with the ZKSync node via its
 /// JSON RPC interface.
+///
+/// Provider may be connected to several API endpoints of the same network, listed in the order of priority.
+/// Every call is sent to the first available endpoint, and if it fails with a transient error, the endpoint
+/// is considered unavailable for the `endpoint_cooldown` and the call is retried with the next one
+/// according to the retry policy. Once all the endpoints are unavailable, the call is retried with the one
+/// that recovers first after the backoff delay. The availability is shared by the clones of the provider
+/// and may be refreshed explicitly via `RpcProvider::check_health`.
 #[derive(Debug, Clone)]
 pub struct RpcProvider {
-    rpc_addr: String,
+    endpoints: Arc<[Endpoint]>,
     client: reqwest::Client,
     network: Network,
+    retry_policy: RetryPolicy,
+    timeouts: CallTimeouts,
+    endpoint_cooldown: Duration,
 }
 
 #[async_trait]
 impl Provider for RpcProvider {
     async fn account_info(&self, address: Address) -> ResponseResult<AccountInfo> {
         let msg = JsonRpcRequest::account_info(address);
-        self.send_and_deserialize(&msg).await
+        self.send_and_deserialize(&msg, CallType::Query).await
     }
 
     async fn tokens(&self) -> ResponseResult<Tokens> {
         let msg = JsonRpcRequest::tokens();
-        self.send_and_deserialize(&msg).await
+        self.send_and_deserialize(&msg, CallType::Query).await
     }
 
     async fn tx_info(&self, tx_hash: TxHash) -> ResponseResult<TransactionInfo> {
         let msg = JsonRpcRequest::tx_info(tx_hash);
-        self.send_and_deserialize(&msg).await
+        self.send_and_deserialize(&msg, CallType::Query).await
     }
 
     async fn get_tx_fee(
@@ -131,7 +304,7 @@ impl Provider for RpcProvider {
     ) -> ResponseResult<Fee> {
         let token = token.into();
         let msg = JsonRpcRequest::get_tx_fee(tx_type, address, token);
-        self.send_and_deserialize(&msg).await
+        self.send_and_deserialize(&msg, CallType::Fee).await
     }
 
     async fn get_txs_batch_fee(
@@ -142,13 +315,21 @@ impl Provider for RpcProvider {
     ) -> ResponseResult<BigUint> {
         let msg = JsonRpcRequest::get_txs_batch_fee_in_wei(tx_types, addresses, token.into());
 
-        let batch_fee: BatchFee = self.send_and_deserialize(&msg).await?;
+        let batch_fee: BatchFee = self.send_and_deserialize(&msg, CallType::Fee).await?;
         Ok(batch_fee.total_fee)
     }
 
+    async fn get_token_price(
+        &self,
+        token: impl Into<TokenLike> + Send + 'async_trait,
+    ) -> ResponseResult<BigDecimal> {
+        let msg = JsonRpcRequest::get_token_price(token.into());
+        self.send_and_deserialize(&msg, CallType::Fee).await
+    }
+
     async fn ethop_info(&self, serial_id: u32) -> ResponseResult<EthOpInfo> {
         let msg = JsonRpcRequest::ethop_info(serial_id);
-        self.send_and_deserialize(&msg).await
+        self.send_and_deserialize(&msg, CallType::Query).await
     }
 
     async fn get_eth_tx_for_withdrawal(
@@ -156,12 +337,12 @@ impl Provider for RpcProvider {
         withdrawal_hash: TxHash,
     ) -> ResponseResult<Option<String>> {
         let msg = JsonRpcRequest::eth_tx_for_withdrawal(withdrawal_hash);
-        self.send_and_deserialize(&msg).await
+        self.send_and_deserialize(&msg, CallType::Query).await
     }
 
     async fn contract_address(&self) -> ResponseResult<ContractAddress> {
         let msg = JsonRpcRequest::contract_address();
-        self.send_and_deserialize(&msg).await
+        self.send_and_deserialize(&msg, CallType::Query).await
     }
 
     async fn send_tx(
@@ -170,7 +351,7 @@ impl Provider for RpcProvider {
         eth_signature: Option<PackedEthSignature>,
     ) -> ResponseResult<TxHash> {
         let msg = JsonRpcRequest::submit_tx(tx, eth_signature);
-        self.send_and_deserialize(&msg).await
+        self.send_and_deserialize(&msg, CallType::Submission).await
     }
 
     async fn send_txs_batch(
@@ -179,7 +360,26 @@ impl Provider for RpcProvider {
         eth_signature: Option<PackedEthSignature>,
     ) -> ResponseResult<Vec<TxHash>> {
         let msg = JsonRpcRequest::submit_tx_batch(txs_signed, eth_signature);
-        self.send_and_deserialize(&msg).await
+        self.send_and_deserialize(&msg, CallType::Submission).await
+    }
+
+    async fn send_multi_author_txs_batch(
+        &self,
+        txs_signed: Vec<(ZkSyncTx, Option<PackedEthSignature>)>,
+        eth_signatures: Vec<PackedEthSignature>,
+    ) -> ResponseResult<Vec<TxHash>> {
+        let msg = JsonRpcRequest::submit_multi_author_tx_batch(txs_signed, eth_signatures);
+        self.send_and_deserialize(&msg, CallType::Submission).await
+    }
+
+    async fn send_swap(
+        &self,
+        swap: Swap,
+        eth_signature: Option<PackedEthSignature>,
+        orders_eth_signatures: (Option<PackedEthSignature>, Option<PackedEthSignature>),
+    ) -> ResponseResult<TxHash> {
+        let msg = JsonRpcRequest::submit_swap(swap, eth_signature, orders_eth_signatures);
+        self.send_and_deserialize(&msg, CallType::Submission).await
     }
 
     fn network(&self) -> Network {
@@ -188,31 +388,118 @@ impl Provider for RpcProvider {
 }
 
 impl RpcProvider {
-    /// Creates a new `RpcProvider` connected to the desired zkSync network.
-    pub fn new(network: Network) -> Self {
+    const DEFAULT_ENDPOINT_COOLDOWN: Duration = Duration::from_secs(30);
+
+    fn with_endpoints(endpoints: Vec<String>, network: Network) -> Self {
         Self {
-            rpc_addr: get_rpc_addr(network).into(),
+            endpoints: endpoints.into_iter().map(Endpoint::new).collect(),
             client: reqwest::Client::new(),
             network,
+            retry_policy: RetryPolicy::default(),
+            timeouts: CallTimeouts::default(),
+            endpoint_cooldown: Self::DEFAULT_ENDPOINT_COOLDOWN,
         }
     }
 
+    /// Creates a new `RpcProvider` connected to the desired zkSync network.
+    pub fn new(network: Network) -> Self {
+        Self::with_endpoints(vec![get_rpc_addr(network).into()], network)
+    }
+
     /// Creates a new `Provider` object connected to a custom address.
     pub fn from_addr(rpc_addr: impl Into<String>) -> Self {
-        Self {
-            rpc_addr: rpc_addr.into(),
-            client: reqwest::Client::new(),
-            network: Network::Unknown,
-        }
+        Self::with_endpoints(vec![rpc_addr.into()], Network::Unknown)
     }
 
     /// Creates a new `Provider` object connected to a custom address and the desired zkSync network.
     pub fn from_addr_and_network(rpc_addr: impl Into<String>, network: Network) -> Self {
-        Self {
-            rpc_addr: rpc_addr.into(),
-            client: reqwest::Client::new(),
-            network,
+        Self::with_endpoints(vec![rpc_addr.into()], network)
+    }
+
+    /// Creates a new `Provider` object connected to several addresses of the desired zkSync network.
+    /// Addresses are used in the order of priority, the rest of them are only used if the first one is unavailable.
+    pub fn from_addrs(
+        rpc_addrs: impl IntoIterator<Item = impl Into<String>>,
+        network: Network,
+    ) -> Result<Self, ClientError> {
+        let endpoints: Vec<String> = rpc_addrs.into_iter().map(Into::into).collect();
+        if endpoints.is_empty() {
+            return Err(ClientError::IncorrectInput);
         }
+        Ok(Self::with_endpoints(endpoints, network))
+    }
+
+    /// Sets the policy of retrying the calls failed with the transient errors.
+    /// At least one attempt must be allowed.
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Result<Self, ClientError> {
+        if retry_policy.max_attempts == 0 || retry_policy.max_backoff < retry_policy.initial_backoff
+        {
+            return Err(ClientError::IncorrectInput);
+        }
+        self.retry_policy = retry_policy;
+        Ok(self)
+    }
+
+    /// Sets the timeout of the calls of the given type.
+    pub fn timeout(mut self, call_type: CallType, timeout: Duration) -> Self {
+        match call_type {
+            CallType::Query => self.timeouts.query = timeout,
+            CallType::Fee => self.timeouts.fee = timeout,
+            CallType::Submission => self.timeouts.submission = timeout,
+        }
+        self
+    }
+
+    /// Sets the time the endpoint is not used for after the transient error.
+    pub fn endpoint_cooldown(mut self, cooldown: Duration) -> Self {
+        self.endpoint_cooldown = cooldown;
+        self
+    }
+
+    /// Returns the availability of the endpoints according to the last requests to them.
+    pub fn endpoints_status(&self) -> Vec<EndpointStatus> {
+        self.endpoints
+            .iter()
+            .map(|endpoint| EndpointStatus {
+                addr: endpoint.addr.clone(),
+                available: endpoint.unavailable_until().is_none(),
+            })
+            .collect()
+    }
+
+    /// Requests every endpoint (without retries) to refresh its availability and returns the result.
+    pub async fn check_health(&self) -> Vec<EndpointStatus> {
+        let msg = &JsonRpcRequest::contract_address();
+        let checks = self.endpoints.iter().map(|endpoint| async move {
+            let result = self
+                .post_raw(endpoint, msg, self.timeouts.get(CallType::Query))
+                .await;
+            let available = !is_transient(&result);
+            endpoint.set_available(available, self.endpoint_cooldown);
+            EndpointStatus {
+                addr: endpoint.addr.clone(),
+                available,
+            }
+        });
+        futures::future::join_all(checks).await
+    }
+
+    /// Chooses the first available endpoint, or the one that recovers first if all of them are unavailable.
+    /// Returns the endpoint along with the flag whether it's available.
+    fn choose_endpoint(&self) -> (&Endpoint, bool) {
+        let mut recovers_first: Option<(&Endpoint, Instant)> = None;
+        for endpoint in self.endpoints.iter() {
+            match endpoint.unavailable_until() {
+                None => return (endpoint, true),
+                Some(until) => {
+                    if recovers_first.map_or(true, |(_, first)| until < first) {
+                        recovers_first = Some((endpoint, until));
+                    }
+                }
+            }
+        }
+        // Provider is always created with at least one endpoint.
+        (recovers_first.unwrap().0, false)
     }
 
     /// Submits a batch transaction to the zkSync network.
@@ -223,13 +510,13 @@ impl RpcProvider {
         eth_signature: Option<PackedEthSignature>,
     ) -> Result<Vec<TxHash>, ClientError> {
         let msg = JsonRpcRequest::submit_tx_batch(txs_signed, eth_signature);
-        self.send_and_deserialize(&msg).await
+        self.send_and_deserialize(&msg, CallType::Submission).await
     }
 
     /// Requests and returns information about an Ethereum operation given its `serial_id`.
     pub async fn ethop_info(&self, serial_id: u32) -> Result<EthOpInfo, ClientError> {
         let msg = JsonRpcRequest::ethop_info(serial_id);
-        self.send_and_deserialize(&msg).await
+        self.send_and_deserialize(&msg, CallType::Query).await
     }
 
     /// Requests and returns eth withdrawal transaction hash for some offchain withdrawal.
@@ -238,7 +525,7 @@ impl RpcProvider {
         withdrawal_hash: TxHash,
     ) -> Result<Option<String>, ClientError> {
         let msg = JsonRpcRequest::eth_tx_for_withdrawal(withdrawal_hash);
-        self.send_and_deserialize(&msg).await
+        self.send_and_deserialize(&msg, CallType::Query).await
     }
 
     /// Performs a POST query to the JSON RPC endpoint,
@@ -246,85 +533,139 @@ impl RpcProvider {
     /// `Ok` is returned only for successful calls, for any kind of error
     /// the `Err` variant is returned (including the failed RPC method
     /// execution response).
-    async fn post(&self, message: impl serde::Serialize) -> ResponseResult<serde_json::Value> {
+    async fn post(
+        &self,
+        message: impl serde::Serialize,
+        call_type: CallType,
+    ) -> ResponseResult<serde_json::Value> {
         // Repeat requests with exponential backoff until an ok response is received to avoid
-        // network and internal errors impact.
-        const MAX_DURATION: Duration = Duration::from_secs(30);
-        let mut delay = Duration::from_millis(50);
+        // network and internal errors impact, switching to the next endpoint on every error.
+        let timeout = self.timeouts.get(call_type);
+        let mut attempt = 1;
+        let mut retry = 0;
         loop {
-            let result = self.post_raw(&message).await;
-
-            /// Determines if the error code is recoverable or not.
-            fn is_recoverable(code: &ErrorCode) -> bool {
-                code == &ErrorCode::InternalError
-                // This is a communication error code, so we can make attempt to retry request.
-                || code == &ErrorCode::ServerError(300)
+            let (endpoint, available) = self.choose_endpoint();
+            if !available && attempt > 1 {
+                // All the endpoints have failed, so wait for them to recover.
+                tokio::time::sleep(self.retry_policy.backoff(retry)).await;
+                retry += 1;
             }
+            let result = self.post_raw(endpoint, &message, timeout).await;
 
-            let should_retry = match result.as_ref() {
-                Err(ClientError::NetworkError(..)) => true,
-                Err(ClientError::RpcError(fail)) => is_recoverable(&fail.error.code),
-                Ok(Output::Failure(fail)) => is_recoverable(&fail.error.code),
-                _ => false,
-            };
+            let transient = is_transient(&result);
+            endpoint.set_available(!transient, self.endpoint_cooldown);
+            // The server may have accepted the submission already, so it's only resent
+            // if the request wasn't delivered at all.
+            let may_resend =
+                call_type != CallType::Submission || matches!(&result, Err(err) if !err.delivered);
 
-            if should_retry && delay < MAX_DURATION {
-                delay *= 2;
-                tokio::time::sleep(delay).await;
+            if transient && may_resend && attempt < self.retry_policy.max_attempts {
+                attempt += 1;
                 continue;
             }
 
-            return match result? {
+            return match result.map_err(|err| err.error)? {
                 Output::Success(success) => Ok(success.result),
                 Output::Failure(failure) => Err(ClientError::RpcError(failure)),
             };
         }
     }
 
-    /// Performs a POST query to the JSON RPC endpoint,
+    /// Performs a POST query to the given JSON RPC endpoint,
     /// and decodes the response, returning the decoded `serde_json::Value`.
     /// `Ok` is returned only for successful calls, for any kind of error
     /// the `Err` variant is returned (including the failed RPC method
     /// execution response).
-    async fn post_raw(&self, message: impl serde::Serialize) -> ResponseResult<Output> {
+    async fn post_raw(
+        &self,
+        endpoint: &Endpoint,
+        message: impl serde::Serialize,
+        timeout: Duration,
+    ) -> Result<Output, RequestError> {
         let res = self
             .client
-            .post(&self.rpc_addr)
+            .post(&endpoint.addr)
+            .timeout(timeout)
             .json(&message)
             .send()
             .await
-            .map_err(|err| ClientError::NetworkError(err.to_string()))?;
+            .map_err(|err| RequestError {
+                delivered: !err.is_connect(),
+                error: ClientError::NetworkError(err.to_string()),
+            })?;
         if res.status() != reqwest::StatusCode::OK {
             let error = format!(
                 "Post query responded with a non-OK response: {}",
                 res.status()
             );
-            return Err(ClientError::NetworkError(error));
+            return Err(RequestError::delivered(ClientError::NetworkError(error)));
         }
-        let reply: Output = res
-            .json()
-            .await
-            .map_err(|err| ClientError::MalformedResponse(err.to_string()))?;
+        let reply: Output = res.json().await.map_err(|err| {
+            RequestError::delivered(ClientError::MalformedResponse(err.to_string()))
+        })?;
 
         Ok(reply)
     }
 
-    async fn send_and_deserialize<R>(&self, msg: &JsonRpcRequest) -> ResponseResult<R>
+    async fn send_and_deserialize<R>(
+        &self,
+        msg: &JsonRpcRequest,
+        call_type: CallType,
+    ) -> ResponseResult<R>
     where
         R: serde::de::DeserializeOwned,
     {
-        let ret = self.post(msg).await?;
+        let ret = self.post(msg, call_type).await?;
         let result = serde_json::from_value(ret)
             .map_err(|err| ClientError::MalformedResponse(err.to_string()))?;
         Ok(result)
     }
 }
 
+/// Determines if the error code is recoverable or not.
+fn is_recoverable(code: &ErrorCode) -> bool {
+    code == &ErrorCode::InternalError
+    // This is a communication error code, so we can make attempt to retry request.
+    || code == &ErrorCode::ServerError(300)
+}
+
+/// Error of the request to the single endpoint.
+#[derive(Debug)]
+struct RequestError {
+    error: ClientError,
+    /// Whether the server may have received the request.
+    delivered: bool,
+}
+
+impl RequestError {
+    fn delivered(error: ClientError) -> Self {
+        Self {
+            error,
+            delivered: true,
+        }
+    }
+}
+
+/// Determines if the call failed with the transient error, so it can be retried.
+fn is_transient(result: &Result<Output, RequestError>) -> bool {
+    match result {
+        Err(RequestError {
+            error: ClientError::NetworkError(..),
+            ..
+        }) => true,
+        Ok(Output::Failure(fail)) => is_recoverable(&fail.error.code),
+        _ => false,
+    }
+}
+
 mod messages {
     use serde::Serialize;
     use zksync_types::{
-        tx::{PackedEthSignature, TxEthSignature, TxHash, ZkSyncTx},
-        Address, TokenLike, TxFeeTypes,
+        tx::{
+            EthBatchSignatures, PackedEthSignature, TxEthSignature, TxEthSignatureVariant, TxHash,
+            ZkSyncTx,
+        },
+        Address, Swap, TokenLike, TxFeeTypes,
     };
 
     #[derive(Debug, Serialize)]
@@ -372,9 +713,48 @@ mod messages {
             Self::create("tx_submit", params)
         }
 
+        pub fn submit_swap(
+            swap: Swap,
+            eth_signature: Option<PackedEthSignature>,
+            orders_eth_signatures: (Option<PackedEthSignature>, Option<PackedEthSignature>),
+        ) -> Self {
+            let signatures = TxEthSignatureVariant::Triple(
+                eth_signature.map(TxEthSignature::EthereumSignature),
+                orders_eth_signatures
+                    .0
+                    .map(TxEthSignature::EthereumSignature),
+                orders_eth_signatures
+                    .1
+                    .map(TxEthSignature::EthereumSignature),
+            );
+            let params = json_values![ZkSyncTx::from(swap), signatures];
+            Self::create("tx_submit", params)
+        }
+
         pub fn submit_tx_batch(
             txs_signed: Vec<(ZkSyncTx, Option<PackedEthSignature>)>,
             eth_signature: Option<PackedEthSignature>,
+        ) -> Self {
+            let eth_signature = eth_signature.map(TxEthSignature::EthereumSignature);
+            Self::submit_tx_batch_with_signatures(txs_signed, to_json_value(eth_signature))
+        }
+
+        pub fn submit_multi_author_tx_batch(
+            txs_signed: Vec<(ZkSyncTx, Option<PackedEthSignature>)>,
+            eth_signatures: Vec<PackedEthSignature>,
+        ) -> Self {
+            let eth_signatures = EthBatchSignatures::Multi(
+                eth_signatures
+                    .into_iter()
+                    .map(TxEthSignature::EthereumSignature)
+                    .collect(),
+            );
+            Self::submit_tx_batch_with_signatures(txs_signed, to_json_value(eth_signatures))
+        }
+
+        fn submit_tx_batch_with_signatures(
+            txs_signed: Vec<(ZkSyncTx, Option<PackedEthSignature>)>,
+            eth_signatures: serde_json::Value,
         ) -> Self {
             let mut params = Vec::with_capacity(2);
 
@@ -385,9 +765,7 @@ mod messages {
                 })
             }).collect();
             params.push(serde_json::Value::Array(txs_signed));
-            params.push(to_json_value(
-                eth_signature.map(TxEthSignature::EthereumSignature),
-            ));
+            params.push(eth_signatures);
 
             Self::create("submit_txs_batch", params)
         }
@@ -425,5 +803,10 @@ mod messages {
             let params = json_values![tx_types, addresses, token_like];
             Self::create("get_txs_batch_fee_in_wei", params)
         }
+
+        pub fn get_token_price(token_like: TokenLike) -> Self {
+            let params = json_values![token_like];
+            Self::create("get_token_price", params)
+        }
     }
 }
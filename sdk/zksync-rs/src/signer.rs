@@ -7,11 +7,11 @@ use zksync_crypto::PrivateKey;
 use zksync_eth_signer::{error::SignerError, EthereumSigner};
 use zksync_types::{
     tx::{
-        ChangePubKey, ChangePubKeyECDSAData, ChangePubKeyEthAuthData, PackedEthSignature,
-        TimeRange, TxEthSignature,
+        ChangePubKey, ChangePubKeyCREATE2Data, ChangePubKeyECDSAData, ChangePubKeyEthAuthData,
+        ChangePubKeyType, EthBatchSignData, PackedEthSignature, TimeRange, TxEthSignature,
     },
-    AccountId, Address, ForcedExit, MintNFT, Nonce, PubKeyHash, Token, TokenId, Transfer, Withdraw,
-    WithdrawNFT, H256,
+    AccountId, Address, ForcedExit, MintNFT, Nonce, Order, PubKeyHash, Swap, Token, TokenId,
+    Transfer, Withdraw, WithdrawNFT, ZkSyncTx, H256,
 };
 // Local imports
 use crate::WalletCredentials;
@@ -26,6 +26,8 @@ pub struct Signer<S: EthereumSigner> {
     pub(crate) private_key: PrivateKey,
     pub(crate) eth_signer: Option<S>,
     pub(crate) account_id: Option<AccountId>,
+    pub(crate) create2_data: Option<ChangePubKeyCREATE2Data>,
+    pub(crate) typed_data_2fa: bool,
 }
 
 impl<S: EthereumSigner> fmt::Debug for Signer<S> {
@@ -44,6 +46,10 @@ impl<S: EthereumSigner> fmt::Debug for Signer<S> {
 impl<S: EthereumSigner> Signer<S> {
     pub fn new(private_key: PrivateKey, address: Address, eth_signer: Option<S>) -> Self {
         let pubkey_hash = PubKeyHash::from_privkey(&private_key);
+        let typed_data_2fa = eth_signer
+            .as_ref()
+            .map(EthereumSigner::prefers_typed_data)
+            .unwrap_or_default();
 
         Self {
             private_key,
@@ -51,16 +57,20 @@ impl<S: EthereumSigner> Signer<S> {
             address,
             eth_signer,
             account_id: None,
+            create2_data: None,
+            typed_data_2fa,
         }
     }
 
     /// Construct a `Signer` with the given credentials
     pub fn with_credentials(credentials: WalletCredentials<S>) -> Self {
-        Self::new(
+        let mut signer = Self::new(
             credentials.zksync_private_key,
             credentials.eth_address,
             credentials.eth_signer,
-        )
+        );
+        signer.create2_data = credentials.create2_data;
+        signer
     }
 
     pub fn pubkey_hash(&self) -> &PubKeyHash {
@@ -75,6 +85,31 @@ impl<S: EthereumSigner> Signer<S> {
         self.account_id
     }
 
+    /// Makes the transfers and the withdrawals to be authorized with the signature of
+    /// the EIP-712 typed data instead of the human-readable message, e.g. for the Ethereum
+    /// signers that only expose the typed data signing. Enabled by default for the signers
+    /// preferring the typed data, e.g. `LedgerSigner`.
+    pub fn set_typed_data_2fa(&mut self, enabled: bool) {
+        self.typed_data_2fa = enabled;
+    }
+
+    /// Returns the CREATE2 data if the signer belongs to the CREATE2 smart contract wallet.
+    pub fn create2_data(&self) -> Option<&ChangePubKeyCREATE2Data> {
+        self.create2_data.as_ref()
+    }
+
+    /// Returns the type of the `ChangePubKey` authorization, CREATE2 wallets are always
+    /// authorized with the CREATE2 data.
+    pub fn change_pubkey_type(&self, auth_onchain: bool) -> ChangePubKeyType {
+        if self.create2_data.is_some() {
+            ChangePubKeyType::CREATE2
+        } else if auth_onchain {
+            ChangePubKeyType::Onchain
+        } else {
+            ChangePubKeyType::ECDSA
+        }
+    }
+
     pub async fn sign_change_pubkey_tx(
         &self,
         nonce: Nonce,
@@ -84,7 +119,26 @@ impl<S: EthereumSigner> Signer<S> {
         time_range: TimeRange,
     ) -> Result<ChangePubKey, SignerError> {
         let account_id = self.account_id.ok_or(SignerError::NoSigningKey)?;
+        self.sign_change_pubkey_tx_for_account(
+            account_id,
+            nonce,
+            auth_onchain,
+            fee_token,
+            fee,
+            time_range,
+        )
+        .await
+    }
 
+    pub(crate) async fn sign_change_pubkey_tx_for_account(
+        &self,
+        account_id: AccountId,
+        nonce: Nonce,
+        auth_onchain: bool,
+        fee_token: Token,
+        fee: BigUint,
+        time_range: TimeRange,
+    ) -> Result<ChangePubKey, SignerError> {
         let mut change_pubkey = ChangePubKey::new_signed(
             account_id,
             self.address,
@@ -98,33 +152,37 @@ impl<S: EthereumSigner> Signer<S> {
         )
         .map_err(signing_failed_error)?;
 
-        let eth_auth_data = if auth_onchain {
-            ChangePubKeyEthAuthData::Onchain
-        } else {
-            let eth_signer = self
-                .eth_signer
-                .as_ref()
-                .ok_or(SignerError::MissingEthSigner)?;
-
-            let sign_bytes = change_pubkey
-                .get_eth_signed_data()
-                .map_err(signing_failed_error)?;
-            let eth_signature = eth_signer
-                .sign_message(&sign_bytes)
-                .await
-                .map_err(signing_failed_error)?;
-
-            let eth_signature = match eth_signature {
-                TxEthSignature::EthereumSignature(packed_signature) => Ok(packed_signature),
-                TxEthSignature::EIP1271Signature(..) => Err(SignerError::CustomError(
-                    "Can't sign ChangePubKey message with EIP1271 signer".to_string(),
-                )),
-            }?;
-
-            ChangePubKeyEthAuthData::ECDSA(ChangePubKeyECDSAData {
-                eth_signature,
-                batch_hash: H256::zero(),
-            })
+        let eth_auth_data = match self.change_pubkey_type(auth_onchain) {
+            ChangePubKeyType::CREATE2 => ChangePubKeyEthAuthData::CREATE2(
+                self.create2_data.clone().expect("CREATE2 data is set"),
+            ),
+            ChangePubKeyType::Onchain => ChangePubKeyEthAuthData::Onchain,
+            ChangePubKeyType::ECDSA => {
+                let eth_signer = self
+                    .eth_signer
+                    .as_ref()
+                    .ok_or(SignerError::MissingEthSigner)?;
+
+                let sign_bytes = change_pubkey
+                    .get_eth_signed_data()
+                    .map_err(signing_failed_error)?;
+                let eth_signature = eth_signer
+                    .sign_message(&sign_bytes)
+                    .await
+                    .map_err(signing_failed_error)?;
+
+                let eth_signature = match eth_signature {
+                    TxEthSignature::EthereumSignature(packed_signature) => Ok(packed_signature),
+                    TxEthSignature::EIP1271Signature(..) => Err(SignerError::CustomError(
+                        "Can't sign ChangePubKey message with EIP1271 signer".to_string(),
+                    )),
+                }?;
+
+                ChangePubKeyEthAuthData::ECDSA(ChangePubKeyECDSAData {
+                    eth_signature,
+                    batch_hash: H256::zero(),
+                })
+            }
         };
         change_pubkey.eth_auth_data = Some(eth_auth_data);
 
@@ -163,8 +221,13 @@ impl<S: EthereumSigner> Signer<S> {
 
         let eth_signature = match &self.eth_signer {
             Some(signer) => {
-                let message = transfer.get_ethereum_sign_message(&token.symbol, token.decimals);
-                let signature = signer.sign_message(message.as_bytes()).await?;
+                let signature = if self.typed_data_2fa {
+                    let message = transfer.get_eip712_message(&token.symbol, token.decimals);
+                    signer.sign_typed_data(&message).await?
+                } else {
+                    let message = transfer.get_ethereum_sign_message(&token.symbol, token.decimals);
+                    signer.sign_message(message.as_bytes()).await?
+                };
 
                 if let TxEthSignature::EthereumSignature(packed_signature) = signature {
                     Some(packed_signature)
@@ -178,6 +241,41 @@ impl<S: EthereumSigner> Signer<S> {
         Ok((transfer, eth_signature))
     }
 
+    /// Signs the combined Ethereum message of the batch, so the batch transactions
+    /// don't need their own Ethereum signatures.
+    /// Each transaction is provided along with the token its message part refers to.
+    pub async fn sign_batch(
+        &self,
+        txs: Vec<(ZkSyncTx, Token)>,
+    ) -> Result<Option<PackedEthSignature>, SignerError> {
+        let txs = txs
+            .into_iter()
+            .map(|(tx, token)| (tx, token, self.address))
+            .collect();
+        self.sign_multi_author_batch(txs).await
+    }
+
+    /// Signs the combined Ethereum message of the batch including the transactions of several accounts.
+    /// Each transaction is provided along with the token its message part refers to and its author.
+    pub async fn sign_multi_author_batch(
+        &self,
+        txs: Vec<(ZkSyncTx, Token, Address)>,
+    ) -> Result<Option<PackedEthSignature>, SignerError> {
+        let signer = match &self.eth_signer {
+            Some(signer) => signer,
+            None => return Ok(None),
+        };
+
+        let message = EthBatchSignData::get_batch_sign_message(txs);
+        let signature = signer.sign_message(&message).await?;
+
+        if let TxEthSignature::EthereumSignature(packed_signature) = signature {
+            Ok(Some(packed_signature))
+        } else {
+            Err(SignerError::MissingEthSigner)
+        }
+    }
+
     pub async fn sign_withdraw(
         &self,
         token: Token,
@@ -204,8 +302,13 @@ impl<S: EthereumSigner> Signer<S> {
 
         let eth_signature = match &self.eth_signer {
             Some(signer) => {
-                let message = withdraw.get_ethereum_sign_message(&token.symbol, token.decimals);
-                let signature = signer.sign_message(message.as_bytes()).await?;
+                let signature = if self.typed_data_2fa {
+                    let message = withdraw.get_eip712_message(&token.symbol, token.decimals);
+                    signer.sign_typed_data(&message).await?
+                } else {
+                    let message = withdraw.get_ethereum_sign_message(&token.symbol, token.decimals);
+                    signer.sign_message(message.as_bytes()).await?
+                };
 
                 if let TxEthSignature::EthereumSignature(packed_signature) = signature {
                     Some(packed_signature)
@@ -338,4 +441,96 @@ impl<S: EthereumSigner> Signer<S> {
 
         Ok((withdraw_nft, eth_signature))
     }
+
+    /// Signs the swap order. Zero `amount` denotes a limit order, which can be filled
+    /// by any number of swaps until the nonce is used or the order expires.
+    ///
+    /// Unlike transactions, orders are signed with the separate message type, so the
+    /// signature can't be reused for any other transaction of the account.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn sign_order(
+        &self,
+        recipient: Address,
+        nonce: Nonce,
+        token_sell: Token,
+        token_buy: Token,
+        price: (BigUint, BigUint),
+        amount: BigUint,
+        time_range: TimeRange,
+    ) -> Result<(Order, Option<PackedEthSignature>), SignerError> {
+        let account_id = self.account_id.ok_or(SignerError::NoSigningKey)?;
+
+        let order = Order::new_signed(
+            account_id,
+            recipient,
+            nonce,
+            token_sell.id,
+            token_buy.id,
+            price,
+            amount,
+            time_range,
+            &self.private_key,
+        )
+        .map_err(signing_failed_error)?;
+
+        let eth_signature = match &self.eth_signer {
+            Some(signer) => {
+                let message = order.get_ethereum_sign_message(
+                    &token_sell.symbol,
+                    &token_buy.symbol,
+                    token_sell.decimals,
+                );
+                let signature = signer.sign_message(message.as_bytes()).await?;
+
+                if let TxEthSignature::EthereumSignature(packed_signature) = signature {
+                    Some(packed_signature)
+                } else {
+                    return Err(SignerError::MissingEthSigner);
+                }
+            }
+            _ => None,
+        };
+
+        Ok((order, eth_signature))
+    }
+
+    /// Signs the swap of the two signed orders submitted by this account.
+    pub async fn sign_swap(
+        &self,
+        orders: (Order, Order),
+        amounts: (BigUint, BigUint),
+        fee_token: Token,
+        fee: BigUint,
+        nonce: Nonce,
+    ) -> Result<(Swap, Option<PackedEthSignature>), SignerError> {
+        let account_id = self.account_id.ok_or(SignerError::NoSigningKey)?;
+
+        let swap = Swap::new_signed(
+            account_id,
+            self.address,
+            nonce,
+            orders,
+            amounts,
+            fee,
+            fee_token.id,
+            &self.private_key,
+        )
+        .map_err(signing_failed_error)?;
+
+        let eth_signature = match &self.eth_signer {
+            Some(signer) => {
+                let message = swap.get_ethereum_sign_message(&fee_token.symbol, fee_token.decimals);
+                let signature = signer.sign_message(message.as_bytes()).await?;
+
+                if let TxEthSignature::EthereumSignature(packed_signature) = signature {
+                    Some(packed_signature)
+                } else {
+                    return Err(SignerError::MissingEthSigner);
+                }
+            }
+            _ => None,
+        };
+
+        Ok((swap, eth_signature))
+    }
 }
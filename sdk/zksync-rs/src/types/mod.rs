@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 
+use bigdecimal::BigDecimal;
 use num::BigUint;
 use serde::{Deserialize, Serialize};
 
@@ -15,13 +16,24 @@ pub struct NFT {
     pub symbol: String,
     pub creator_id: AccountId,
     pub content_hash: H256,
+    #[serde(default)]
+    pub creator_address: Address,
+    #[serde(default)]
+    pub serial_id: u32,
+    /// L2 address of the token.
+    #[serde(default)]
+    pub address: Address,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct AccountState {
     pub balances: HashMap<String, BigUintSerdeWrapper>,
+    /// NFTs owned by the account.
     pub nfts: HashMap<TokenId, NFT>,
+    /// NFTs created by the account, including the ones it doesn't own anymore.
+    #[serde(default)]
+    pub minted_nfts: HashMap<TokenId, NFT>,
     pub nonce: Nonce,
     pub pub_key_hash: PubKeyHash,
 }
@@ -149,3 +161,13 @@ pub(crate) struct BatchFee {
     #[serde(with = "BigUintSerdeAsRadix10Str")]
     pub total_fee: BigUint,
 }
+
+/// Fee of the operation in the token chosen to pay it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeeTokenEstimate {
+    pub token: Token,
+    /// Fee in the token units.
+    pub fee: BigUint,
+    /// Fee in USD.
+    pub fee_usd: BigDecimal,
+}
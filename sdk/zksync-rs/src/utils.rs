@@ -4,11 +4,9 @@ use std::time::Instant;
 use num::BigUint;
 use sha2::{Digest, Sha256};
 
-use zksync_crypto::bellman::{pairing::ff::PrimeField, PrimeFieldRepr};
-use zksync_crypto::franklin_crypto::alt_babyjubjub::fs::FsRepr;
-use zksync_crypto::{priv_key_from_fs, Fs, PrivateKey};
+use zksync_crypto::{error::KeyDerivationError, key_derivation, PrivateKey};
 use zksync_eth_signer::EthereumSigner;
-use zksync_types::{AccountId, U256};
+use zksync_types::{AccountId, H256, U256};
 
 use crate::{error::ClientError, provider::Provider, wallet::Wallet};
 
@@ -24,30 +22,23 @@ pub use zksync_types::helpers::{
 /// If the obtained value doesn't have a correct value to be a `PrivateKey`, hashing operation is applied
 /// repeatedly to the previous output, until the value can be interpreted as a `PrivateKey`.
 pub fn private_key_from_seed(seed: &[u8]) -> Result<PrivateKey, ClientError> {
-    if seed.len() < 32 {
-        return Err(ClientError::SeedTooShort);
-    }
+    key_derivation::private_key_from_seed(seed).map_err(key_derivation_error)
+}
 
-    let sha256_bytes = |input: &[u8]| {
-        let mut hasher = Sha256::new();
-        hasher.input(input);
-        hasher.result()
-    };
-
-    let mut effective_seed = sha256_bytes(seed);
-
-    loop {
-        let raw_priv_key = sha256_bytes(&effective_seed);
-        let mut fs_repr = FsRepr::default();
-        fs_repr
-            .read_be(&raw_priv_key[..])
-            .expect("failed to read raw_priv_key");
-        match Fs::from_repr(fs_repr) {
-            Ok(fs) => return Ok(priv_key_from_fs(fs)),
-            Err(_) => {
-                effective_seed = raw_priv_key;
-            }
-        }
+/// Derives the `PrivateKey` from seed via the derivation path in the `m/i_1/.../i_n` format,
+/// so a single seed can be used to manage multiple zkSync accounts.
+/// The path `m` corresponds to the key returned by `private_key_from_seed`.
+///
+/// For the details of the derivation scheme, see the `zksync_crypto::key_derivation` module.
+pub fn derive_private_key(seed: &[u8], path: &str) -> Result<PrivateKey, ClientError> {
+    let path = key_derivation::parse_derivation_path(path).map_err(key_derivation_error)?;
+    key_derivation::derive_private_key(seed, &path).map_err(key_derivation_error)
+}
+
+fn key_derivation_error(error: KeyDerivationError) -> ClientError {
+    match error {
+        KeyDerivationError::SeedTooShort => ClientError::SeedTooShort,
+        KeyDerivationError::InvalidPath(path) => ClientError::InvalidDerivationPath(path),
     }
 }
 
@@ -101,6 +92,33 @@ pub fn biguint_to_u256(value: BigUint) -> U256 {
     U256::from_little_endian(&bytes)
 }
 
+/// Multihash prefix of the SHA-256 digest: the hash function code and the digest length.
+const SHA256_MULTIHASH_PREFIX: [u8; 2] = [0x12, 0x20];
+
+/// Returns the NFT content hash of the raw content, i.e. its SHA-256 hash.
+pub fn nft_content_hash(content: &[u8]) -> H256 {
+    H256::from_slice(&Sha256::digest(content))
+}
+
+/// Returns the NFT content hash of the content stored in IPFS, i.e. the SHA-256 digest
+/// of its CIDv0 (the `Qm...` one).
+pub fn content_hash_from_ipfs_cid(cid: &str) -> Result<H256, ClientError> {
+    let multihash = bs58::decode(cid)
+        .into_vec()
+        .map_err(|_| ClientError::IncorrectInput)?;
+    if multihash.len() != 34 || multihash[..2] != SHA256_MULTIHASH_PREFIX {
+        return Err(ClientError::IncorrectInput);
+    }
+    Ok(H256::from_slice(&multihash[2..]))
+}
+
+/// Returns the IPFS CIDv0 of the content with the given NFT content hash.
+pub fn ipfs_cid_from_content_hash(content_hash: &H256) -> String {
+    let mut multihash = SHA256_MULTIHASH_PREFIX.to_vec();
+    multihash.extend_from_slice(content_hash.as_bytes());
+    bs58::encode(multihash).into_string()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -140,4 +158,21 @@ mod tests {
         let pk_err = private_key_from_seed(short_seed).map(|_| ()).unwrap_err();
         assert_eq!(pk_err, ClientError::SeedTooShort);
     }
+
+    #[test]
+    fn test_ipfs_cid_content_hash() {
+        let cid = "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG";
+        let content_hash = content_hash_from_ipfs_cid(cid).unwrap();
+        assert_eq!(
+            hex::encode(content_hash.as_bytes()),
+            "9d6c2be50f706953479ab9df2ce3edca90b68053c00b3004b7f0accbe1e8eedf"
+        );
+        assert_eq!(ipfs_cid_from_content_hash(&content_hash), cid);
+
+        // CIDv1 is not supported.
+        assert!(content_hash_from_ipfs_cid(
+            "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi"
+        )
+        .is_err());
+    }
 }
@@ -1,6 +1,9 @@
-use num::BigUint;
+use std::collections::HashMap;
+
+use bigdecimal::BigDecimal;
+use num::{BigInt, BigUint};
 use zksync_eth_signer::EthereumSigner;
-use zksync_types::{AccountId, Address, TokenId, TokenLike};
+use zksync_types::{AccountId, Address, TokenId, TokenLike, TxFeeTypes};
 
 use crate::{
     credentials::WalletCredentials,
@@ -10,7 +13,7 @@ use crate::{
     provider::Provider,
     signer::Signer,
     tokens_cache::TokensCache,
-    types::{AccountInfo, BlockStatus, NFT},
+    types::{AccountInfo, BlockStatus, FeeTokenEstimate, NFT},
 };
 
 #[derive(Debug)]
@@ -84,6 +87,91 @@ where
             .unwrap_or_default())
     }
 
+    /// Chooses the cheapest token to pay the fee of the operation among the `candidates`,
+    /// skipping the unknown ones, the ones that can't be used to pay the fee or have no price,
+    /// and the ones the committed balance of which isn't enough to pay the fee.
+    ///
+    /// The operation is described by the types of its transactions along with their recipients,
+    /// the fee is requested for them as for a batch, and compared by its USD value.
+    pub async fn cheapest_fee_token(
+        &self,
+        txs: Vec<(TxFeeTypes, Address)>,
+        candidates: Vec<TokenLike>,
+    ) -> Result<FeeTokenEstimate, ClientError> {
+        let (tx_types, addresses): (Vec<_>, Vec<_>) = txs.into_iter().unzip();
+        let balances = self.account_info().await?.committed.balances;
+
+        let mut cheapest: Option<FeeTokenEstimate> = None;
+        for token_like in candidates {
+            let token = match self.tokens.resolve(token_like) {
+                Some(token) => token,
+                None => continue,
+            };
+            // The server rejects the requests for the tokens not suitable for paying the fee.
+            let fee = match self
+                .provider
+                .get_txs_batch_fee(tx_types.clone(), addresses.clone(), token.id)
+                .await
+            {
+                Ok(fee) => fee,
+                Err(ClientError::RpcError(_)) => continue,
+                Err(err) => return Err(err),
+            };
+            let balance = balances
+                .get(&token.symbol as &str)
+                .map(|balance| balance.0.clone())
+                .unwrap_or_default();
+            if balance < fee {
+                continue;
+            }
+
+            let price = match self.provider.get_token_price(token.id).await {
+                Ok(price) => price,
+                Err(ClientError::RpcError(_)) => continue,
+                Err(err) => return Err(err),
+            };
+            let fee_usd = BigDecimal::new(BigInt::from(fee.clone()), token.decimals as i64) * price;
+            if cheapest
+                .as_ref()
+                .map_or(true, |cheapest| fee_usd < cheapest.fee_usd)
+            {
+                cheapest = Some(FeeTokenEstimate {
+                    token,
+                    fee,
+                    fee_usd,
+                });
+            }
+        }
+
+        cheapest.ok_or(ClientError::NoViableFeeToken)
+    }
+
+    /// Returns the NFTs owned by the account.
+    pub async fn get_nfts(
+        &self,
+        block_status: BlockStatus,
+    ) -> Result<HashMap<TokenId, NFT>, ClientError> {
+        let account_state = match block_status {
+            BlockStatus::Committed => self.account_info().await?.committed,
+            BlockStatus::Verified => self.account_info().await?.verified,
+        };
+
+        Ok(account_state.nfts)
+    }
+
+    /// Returns the NFTs minted by the account.
+    pub async fn get_minted_nfts(
+        &self,
+        block_status: BlockStatus,
+    ) -> Result<HashMap<TokenId, NFT>, ClientError> {
+        let account_state = match block_status {
+            BlockStatus::Committed => self.account_info().await?.committed,
+            BlockStatus::Verified => self.account_info().await?.verified,
+        };
+
+        Ok(account_state.minted_nfts)
+    }
+
     /// Returns nft in the account.
     pub async fn get_nft(
         &self,
@@ -158,6 +246,26 @@ where
         WithdrawNFTBuilder::new(self)
     }
 
+    /// Initializes the swap order signing.
+    pub fn start_order(&self) -> OrderBuilder<'_, S, P> {
+        OrderBuilder::new(self)
+    }
+
+    /// Initializes `Swap` transaction sending.
+    pub fn start_swap(&self) -> SwapBuilder<'_, S, P> {
+        SwapBuilder::new(self)
+    }
+
+    /// Initializes the transactions batch sending.
+    pub fn start_batch(&self) -> BatchBuilder<'_, S, P> {
+        BatchBuilder::new(self)
+    }
+
+    /// Initializes the batch in which the wallet pays the fee for the transactions of other accounts.
+    pub fn start_sponsored_batch(&self) -> SponsoredBatchBuilder<'_, S, P> {
+        SponsoredBatchBuilder::new(self)
+    }
+
     /// Creates an `EthereumProvider` to interact with the Ethereum network.
     ///
     /// Returns an error if wallet was created without providing an Ethereum private key.
@@ -59,6 +59,67 @@ fn test_tokens_cache() {
     assert!(!tokens_cache.is_eth((&token_dai.symbol as &str).into()));
 }
 
+#[test]
+fn test_musig_signer() {
+    use zksync::musig::MusigSigner;
+    use zksync_crypto::public_key_from_private;
+    use zksync_types::{tx::PackedPublicKey, PubKeyHash};
+
+    let private_keys: Vec<_> = [[1u8; 32], [2u8; 32]]
+        .iter()
+        .map(|seed| private_key_from_seed(seed).unwrap())
+        .collect();
+    let pubkeys: Vec<_> = private_keys
+        .iter()
+        .map(|key| PackedPublicKey(public_key_from_private(key)))
+        .collect();
+    let mut signers: Vec<_> = private_keys
+        .into_iter()
+        .enumerate()
+        .map(|(i, key)| MusigSigner::new(key, pubkeys.clone(), i).unwrap())
+        .collect();
+
+    let commitments: Vec<_> = signers.iter().map(|s| s.nonce_commitment()).collect();
+    let nonces: Vec<_> = signers
+        .iter_mut()
+        .map(|s| s.receive_commitments(commitments.clone()).unwrap())
+        .collect();
+    for signer in &mut signers {
+        signer.receive_nonces(nonces.clone()).unwrap();
+    }
+
+    let msg = b"transaction bytes";
+    let partial_signatures: Vec<_> = signers.iter_mut().map(|s| s.sign(msg).unwrap()).collect();
+    let signature = signers[1]
+        .aggregate_signatures(msg, &partial_signatures)
+        .unwrap();
+
+    let pubkey = signature.verify_musig(msg).unwrap();
+    assert_eq!(PubKeyHash::from_pubkey(&pubkey), signers[0].pub_key_hash());
+    assert!(signature.verify_musig(b"another bytes").is_none());
+}
+
+#[test]
+fn test_derive_private_key() {
+    let seed = [1u8; 32];
+    assert_eq!(
+        derive_private_key(&seed, "m").unwrap().0,
+        private_key_from_seed(&seed).unwrap().0
+    );
+
+    let expected =
+        hex::decode("04e50a78f4dd1a21f1da456036d37a876f3f41d69bc505c2504492b9511209f0").unwrap();
+    assert_eq!(
+        derive_private_key(&seed, "m/0/1").unwrap().0,
+        priv_key_from_raw(&expected).unwrap().0
+    );
+
+    assert_eq!(
+        derive_private_key(&seed, "m/0/x").unwrap_err(),
+        zksync::error::ClientError::InvalidDerivationPath("m/0/x".into())
+    );
+}
+
 fn priv_key_from_raw(raw: &[u8]) -> Option<PrivateKey> {
     use zksync_crypto::{
         bellman::{pairing::ff::PrimeField, PrimeFieldRepr},
@@ -508,22 +569,30 @@ mod signatures_with_vectors {
 #[cfg(test)]
 mod wallet_tests {
     use super::*;
+    use bigdecimal::BigDecimal;
     use num::{BigUint, ToPrimitive};
+    use std::time::Duration;
     use zksync::{
         error::ClientError,
+        offline::{submit_signed, OfflinePayload, OfflineTx, SignedTransactions},
+        operations::SyncTransactionHandle,
         provider::Provider,
         signer::Signer,
         types::{
-            AccountInfo, AccountState, BlockStatus, ContractAddress, EthOpInfo, Fee, Tokens,
-            TransactionInfo,
+            AccountInfo, AccountState, BlockInfo, BlockStatus, ContractAddress, EthOpInfo, Fee,
+            Tokens, TransactionInfo,
         },
+        utils::content_hash_from_ipfs_cid,
         Network, Wallet, WalletCredentials,
     };
     use zksync_eth_signer::PrivateKeySigner;
     use zksync_types::{
         tokens::get_genesis_token_list,
-        tx::{PackedEthSignature, TxHash},
-        Address, PubKeyHash, TokenId, TokenLike, TxFeeTypes, ZkSyncTx, H256,
+        tx::{
+            ChangePubKeyCREATE2Data, ChangePubKeyEthAuthData, ChangePubKeyType, EthBatchSignData,
+            PackedEthSignature, TxHash,
+        },
+        Address, PubKeyHash, Swap, TokenId, TokenLike, TxFeeTypes, ZkSyncTx, H256,
     };
 
     #[derive(Debug, Clone)]
@@ -552,11 +621,12 @@ mod wallet_tests {
         /// Returns the example `AccountInfo` instance:
         ///  - assigns the '42' value to account_id;
         ///  - assigns the PubKeyHash to match the wallet's signer's PubKeyHash
-        ///  - adds single entry of "DAI" token to the committed balances;
+        ///  - adds the entries of "DAI" and "TUSD" tokens to the committed balances;
         ///  - adds single entry of "USDC" token to the verified balances.
         async fn account_info(&self, address: Address) -> Result<AccountInfo, ClientError> {
             let mut committed_balances = HashMap::new();
             committed_balances.insert("DAI".into(), BigUint::from(12345_u32).into());
+            committed_balances.insert("TUSD".into(), BigUint::from(5000_u32).into());
 
             let mut verified_balances = HashMap::new();
             verified_balances.insert("USDC".into(), BigUint::from(98765_u32).into());
@@ -600,8 +670,18 @@ mod wallet_tests {
             Ok(tokens)
         }
 
+        /// Returns the example `TransactionInfo` of the transaction failed in the committed block.
         async fn tx_info(&self, _tx_hash: TxHash) -> Result<TransactionInfo, ClientError> {
-            unreachable!()
+            Ok(TransactionInfo {
+                executed: true,
+                success: Some(false),
+                fail_reason: Some("Not enough balance".to_string()),
+                block: Some(BlockInfo {
+                    block_number: 1,
+                    committed: true,
+                    verified: false,
+                }),
+            })
         }
 
         async fn get_tx_fee(
@@ -613,13 +693,36 @@ mod wallet_tests {
             unreachable!()
         }
 
+        /// Returns the fee of 1000 units in the token with id 1 ("DAI"), 500 in the token
+        /// with id 3 ("TUSD") and 10 in the other ones.
         async fn get_txs_batch_fee(
             &self,
             _tx_types: Vec<TxFeeTypes>,
             _addresses: Vec<Address>,
-            _token: impl Into<TokenLike> + Send + 'async_trait,
+            token: impl Into<TokenLike> + Send + 'async_trait,
         ) -> Result<BigUint, ClientError> {
-            unreachable!()
+            let fee = match token.into() {
+                TokenLike::Id(TokenId(1)) => 1000_u32,
+                TokenLike::Id(TokenId(3)) => 500_u32,
+                _ => 10_u32,
+            };
+            Ok(BigUint::from(fee))
+        }
+
+        /// Returns the same price for all the tokens except the token with id 1 ("DAI"),
+        /// the price of which is not available.
+        async fn get_token_price(
+            &self,
+            token: impl Into<TokenLike> + Send + 'async_trait,
+        ) -> Result<BigDecimal, ClientError> {
+            match token.into() {
+                TokenLike::Id(TokenId(1)) => Err(ClientError::RpcError(jsonrpc_core::Failure {
+                    jsonrpc: Some(jsonrpc_core::Version::V2),
+                    error: jsonrpc_core::Error::internal_error(),
+                    id: jsonrpc_core::Id::Num(1),
+                })),
+                _ => Ok(BigDecimal::from(1)),
+            }
         }
 
         async fn ethop_info(&self, _serial_id: u32) -> Result<EthOpInfo, ClientError> {
@@ -659,6 +762,23 @@ mod wallet_tests {
             unreachable!()
         }
 
+        async fn send_multi_author_txs_batch(
+            &self,
+            _txs_signed: Vec<(ZkSyncTx, Option<PackedEthSignature>)>,
+            _eth_signatures: Vec<PackedEthSignature>,
+        ) -> Result<Vec<TxHash>, ClientError> {
+            unreachable!()
+        }
+
+        async fn send_swap(
+            &self,
+            _swap: Swap,
+            _eth_signature: Option<PackedEthSignature>,
+            _orders_eth_signatures: (Option<PackedEthSignature>, Option<PackedEthSignature>),
+        ) -> Result<TxHash, ClientError> {
+            unreachable!()
+        }
+
         fn network(&self) -> Network {
             self.network
         }
@@ -758,12 +878,459 @@ mod wallet_tests {
         assert_eq!(result.unwrap_err(), ClientError::UnknownToken);
     }
 
+    #[test]
+    fn test_account_state_nfts() {
+        let content_hash =
+            content_hash_from_ipfs_cid("QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG").unwrap();
+        let nft = serde_json::json!({
+            "id": 65536,
+            "symbol": "NFT-65536",
+            "creatorId": 42,
+            "contentHash": content_hash,
+            "creatorAddress": Address::repeat_byte(1),
+            "serialId": 0,
+            "address": Address::repeat_byte(2),
+        });
+        let state: AccountState = serde_json::from_value(serde_json::json!({
+            "balances": {},
+            "nfts": {},
+            "mintedNfts": { "65536": nft },
+            "nonce": 1,
+            "pubKeyHash": PubKeyHash::default(),
+        }))
+        .unwrap();
+
+        assert!(state.nfts.is_empty());
+        let minted = &state.minted_nfts[&TokenId(65536)];
+        assert_eq!(minted.creator_id, AccountId(42));
+        assert_eq!(minted.content_hash, content_hash);
+        assert_eq!(minted.address, Address::repeat_byte(2));
+    }
+
     #[tokio::test]
     async fn test_wallet_is_signing_key_set() {
         let wallet = get_test_wallet(&[50; 32], Network::Mainnet).await;
         assert!(wallet.is_signing_key_set().await.unwrap());
     }
 
+    #[tokio::test]
+    async fn test_wallet_batch() {
+        let wallet = get_test_wallet(&[60; 32], Network::Mainnet).await;
+        let recipient = Address::random();
+
+        let (txs, eth_signature) = wallet
+            .start_batch()
+            .add_transfer("DAI", 1000_u32, recipient)
+            .unwrap()
+            .add_withdraw("DAI", 500_u32, wallet.address())
+            .unwrap()
+            .fee_token("DAI")
+            .unwrap()
+            .fee(200_u32)
+            .nonce(Nonce(5))
+            .txs()
+            .await
+            .unwrap();
+
+        // The fee is paid by the additional transfer at the end of the batch.
+        assert_eq!(txs.len(), 3);
+        let nonces: Vec<_> = txs.iter().map(|(tx, _)| tx.nonce()).collect();
+        assert_eq!(nonces, vec![Nonce(5), Nonce(6), Nonce(7)]);
+        match &txs[2].0 {
+            ZkSyncTx::Transfer(transfer) => {
+                assert_eq!(transfer.to, wallet.address());
+                assert_eq!(transfer.fee, BigUint::from(200_u32));
+            }
+            _ => panic!("The last transaction is not the fee transfer"),
+        }
+
+        // The only Ethereum signature is the batch one.
+        assert!(txs.iter().all(|(_, signature)| signature.is_none()));
+        let dai = wallet.tokens.resolve("DAI".into()).unwrap();
+        let message = EthBatchSignData::get_batch_sign_message(
+            txs.into_iter()
+                .map(|(tx, _)| (tx, dai.clone(), wallet.address()))
+                .collect(),
+        );
+        let signer = eth_signature
+            .unwrap()
+            .signature_recover_signer(&message)
+            .unwrap();
+        assert_eq!(signer, wallet.address());
+
+        let result = wallet.start_batch().fee_token("DAI").unwrap().txs().await;
+        assert_eq!(
+            result.unwrap_err(),
+            ClientError::MissingRequiredField("txs".into())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_wallet_sponsored_batch() {
+        let user = get_test_wallet(&[70; 32], Network::Mainnet).await;
+        let sponsor = get_test_wallet(&[71; 32], Network::Mainnet).await;
+        let recipient = Address::random();
+
+        // The user has no tokens to pay the fee in, so the transfer is signed with zero fee.
+        let (user_tx, _) = user
+            .start_transfer()
+            .token("TUSD")
+            .unwrap()
+            .amount(1000_u32)
+            .fee(0_u32)
+            .to(recipient)
+            .nonce(Nonce(3))
+            .tx()
+            .await
+            .unwrap();
+
+        let mut batch = sponsor
+            .start_sponsored_batch()
+            .add_tx(user_tx.clone())
+            .unwrap()
+            .fee_token("DAI")
+            .unwrap()
+            .nonce(Nonce(7))
+            .build()
+            .await
+            .unwrap();
+
+        // The fee of the whole batch is paid by the sponsor's transfer at the end of the batch.
+        let txs = batch.txs();
+        assert_eq!(txs.len(), 2);
+        assert_eq!(txs[0].hash(), user_tx.hash());
+        match &txs[1] {
+            ZkSyncTx::Transfer(transfer) => {
+                assert_eq!(transfer.from, sponsor.address());
+                assert_eq!(transfer.to, sponsor.address());
+                assert_eq!(transfer.amount, BigUint::from(0_u32));
+                assert_eq!(transfer.fee, BigUint::from(1000_u32));
+                assert_eq!(transfer.nonce, Nonce(7));
+            }
+            _ => panic!("The last transaction is not the fee transfer"),
+        }
+        assert_eq!(batch.authors(), vec![user.address(), sponsor.address()]);
+
+        // The batch is signed by the sponsor once built, and then by the user.
+        batch.sign(&user).await.unwrap();
+        let message = batch.message();
+        let signers: Vec<_> = batch
+            .eth_signatures()
+            .iter()
+            .map(|signature| signature.signature_recover_signer(&message).unwrap())
+            .collect();
+        assert_eq!(signers, vec![sponsor.address(), user.address()]);
+
+        // Only the authors sign the batch.
+        let stranger = get_test_wallet(&[72; 32], Network::Mainnet).await;
+        assert_eq!(
+            batch.sign(&stranger).await.unwrap_err(),
+            ClientError::IncorrectInput
+        );
+
+        let result = sponsor
+            .start_sponsored_batch()
+            .fee_token("DAI")
+            .unwrap()
+            .build()
+            .await;
+        assert_eq!(
+            result.unwrap_err(),
+            ClientError::MissingRequiredField("txs".into())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_wallet_typed_data_2fa() {
+        let mut wallet = get_test_wallet(&[85; 32], Network::Mainnet).await;
+        wallet.signer.set_typed_data_2fa(true);
+
+        let (tx, eth_signature) = wallet
+            .start_transfer()
+            .token("DAI")
+            .unwrap()
+            .amount(100u64)
+            .fee(10u64)
+            .to(Address::repeat_byte(1))
+            .tx()
+            .await
+            .unwrap();
+        let dai = wallet.tokens.resolve("DAI".into()).unwrap();
+        let message = tx.get_eip712_message(dai).unwrap();
+
+        let signer = eth_signature
+            .unwrap()
+            .signature_recover_signer_from_raw(&message.signing_hash())
+            .unwrap();
+        assert_eq!(signer, wallet.address());
+    }
+
+    #[tokio::test]
+    async fn test_wallet_offline_signing() {
+        let wallet = get_test_wallet(&[70; 32], Network::Mainnet).await;
+        let dai = wallet.tokens.resolve("DAI".into()).unwrap();
+        let recipient = Address::random();
+        let mut payload = OfflinePayload {
+            account_id: AccountId(42),
+            address: wallet.address(),
+            nonce: Nonce(0),
+            valid_from: 0,
+            valid_until: u64::MAX,
+            txs: vec![OfflineTx::Transfer {
+                token: dai.clone(),
+                to: recipient,
+                amount: BigUint::from(1000_u32),
+            }],
+            fee_token: dai.clone(),
+            fee: BigUint::from(200_u32),
+        };
+
+        // The payload survives the round trip through the file.
+        let serialized = serde_json::to_string(&payload).unwrap();
+        assert_eq!(
+            serde_json::from_str::<OfflinePayload>(&serialized).unwrap(),
+            payload
+        );
+
+        // A single transaction pays the fee itself and has its own Ethereum signature.
+        let signed = wallet.signer.sign_offline(payload.clone()).await.unwrap();
+        assert_eq!(signed.txs.len(), 1);
+        assert!(signed.batch_signature.is_none());
+        match &signed.txs[0].tx {
+            ZkSyncTx::Transfer(transfer) => {
+                assert_eq!(transfer.fee, BigUint::from(200_u32));
+                assert!(transfer.verify_signature().is_some());
+                let message = transfer.get_ethereum_sign_message(&dai.symbol, dai.decimals);
+                let signer = signed.txs[0]
+                    .eth_signature
+                    .as_ref()
+                    .unwrap()
+                    .signature_recover_signer(message.as_bytes())
+                    .unwrap();
+                assert_eq!(signer, wallet.address());
+            }
+            _ => panic!("Signed transaction is not a transfer"),
+        }
+
+        // Several transactions are signed as a batch with the fee transfer.
+        payload.txs.push(OfflineTx::Withdraw {
+            token: dai,
+            to: wallet.address(),
+            amount: BigUint::from(500_u32),
+        });
+        payload.nonce = Nonce(5);
+        let signed = wallet.signer.sign_offline(payload).await.unwrap();
+        assert_eq!(signed.txs.len(), 3);
+        assert!(signed.batch_signature.is_some());
+        let nonces: Vec<_> = signed.txs.iter().map(|tx| tx.tx.nonce()).collect();
+        assert_eq!(nonces, vec![Nonce(5), Nonce(6), Nonce(7)]);
+
+        // The account nonce is 0, so the transactions are not sent.
+        let result = submit_signed(&wallet.provider, signed.clone()).await;
+        assert_eq!(
+            result.unwrap_err(),
+            ClientError::NonceMismatch {
+                expected: Nonce(5),
+                actual: Nonce(0)
+            }
+        );
+
+        let expired = SignedTransactions {
+            valid_until: 1,
+            ..signed
+        };
+        let result = submit_signed(&wallet.provider, expired).await;
+        assert_eq!(result.unwrap_err(), ClientError::TransactionsExpired);
+    }
+
+    #[tokio::test]
+    async fn test_wallet_swap() {
+        let maker = get_test_wallet(&[90; 32], Network::Mainnet).await;
+        let taker = get_test_wallet(&[91; 32], Network::Mainnet).await;
+
+        let limit_order = maker
+            .start_order()
+            .token_sell("DAI")
+            .unwrap()
+            .token_buy("TUSD")
+            .unwrap()
+            .ratio(1u64, 2u64)
+            .sign()
+            .await
+            .unwrap();
+        let order = taker
+            .start_order()
+            .token_sell("TUSD")
+            .unwrap()
+            .token_buy("DAI")
+            .unwrap()
+            .ratio(2u64, 1u64)
+            .amount(200u64)
+            .sign()
+            .await
+            .unwrap();
+        assert_eq!(limit_order.order.recipient_address, maker.address());
+        assert_eq!(
+            limit_order.order.verify_signature(),
+            Some(maker.signer.pubkey_hash)
+        );
+
+        // The order message is signed with the Ethereum key of its owner.
+        let message = order.order.get_ethereum_sign_message("TUSD", "DAI", 18);
+        let signer = order
+            .eth_signature
+            .clone()
+            .unwrap()
+            .signature_recover_signer(message.as_bytes())
+            .unwrap();
+        assert_eq!(signer, taker.address());
+
+        // Amount of the limit order has to be specified explicitly.
+        let result = taker
+            .start_swap()
+            .orders(limit_order.clone(), order.clone())
+            .fee_token("DAI")
+            .unwrap()
+            .fee(10u64)
+            .tx()
+            .await;
+        assert_eq!(
+            result.unwrap_err(),
+            ClientError::MissingRequiredField("amounts".into())
+        );
+
+        let (swap, eth_signature, orders_eth_signatures) = taker
+            .start_swap()
+            .orders(limit_order.clone(), order.clone())
+            .amounts(100u64, 200u64)
+            .fee_token("DAI")
+            .unwrap()
+            .fee(10u64)
+            .tx()
+            .await
+            .unwrap();
+        assert_eq!(swap.submitter_address, taker.address());
+        assert_eq!(
+            swap.verify_signature()
+                .map(|(pub_key_hash, _)| pub_key_hash),
+            Some(taker.signer.pubkey_hash)
+        );
+        assert!(eth_signature.is_some());
+        assert_eq!(orders_eth_signatures.0, limit_order.eth_signature);
+        assert_eq!(orders_eth_signatures.1, order.eth_signature);
+    }
+
+    #[tokio::test]
+    async fn test_transaction_handle_failed() {
+        let wallet = get_test_wallet(&[80; 32], Network::Mainnet).await;
+        let handle = SyncTransactionHandle::new(TxHash::default(), wallet.provider.clone())
+            .backoff(2.0, Duration::from_secs(10))
+            .unwrap()
+            .commit_timeout(Duration::from_secs(10));
+
+        // By default the failed transaction is returned along with its fail reason.
+        let info = handle.wait_for_commit().await.unwrap();
+        assert_eq!(info.fail_reason, Some("Not enough balance".to_string()));
+
+        // The failed transaction is reported as the error if requested.
+        let handle = handle.fail_on_rejection();
+        assert_eq!(
+            handle.wait_for_commit().await.unwrap_err(),
+            ClientError::TransactionFailed("Not enough balance".to_string())
+        );
+
+        let handle = SyncTransactionHandle::new(TxHash::default(), wallet.provider.clone());
+        assert_eq!(
+            handle.backoff(0.5, Duration::from_secs(10)).unwrap_err(),
+            ClientError::IncorrectInput
+        );
+    }
+
+    #[tokio::test]
+    async fn test_wallet_cheapest_fee_token() {
+        let wallet = get_test_wallet(&[90; 32], Network::Mainnet).await;
+        let txs = vec![(TxFeeTypes::Transfer, Address::random())];
+
+        // "USDC" has the lowest fee, but there is no balance to pay it, and "DAI" has no price.
+        let estimate = wallet
+            .cheapest_fee_token(
+                txs.clone(),
+                vec!["DAI".into(), "USDC".into(), "TUSD".into()],
+            )
+            .await
+            .unwrap();
+        assert_eq!(estimate.token.symbol, "TUSD");
+        assert_eq!(estimate.fee, BigUint::from(500_u32));
+
+        let result = wallet
+            .cheapest_fee_token(txs.clone(), vec!["USDC".into()])
+            .await;
+        assert_eq!(result.unwrap_err(), ClientError::NoViableFeeToken);
+
+        // "DAI" has enough balance, but no price.
+        let result = wallet
+            .cheapest_fee_token(txs.clone(), vec!["DAI".into()])
+            .await;
+        assert_eq!(result.unwrap_err(), ClientError::NoViableFeeToken);
+
+        // Unknown tokens are skipped as well.
+        let estimate = wallet
+            .cheapest_fee_token(txs.clone(), vec!["ETH".into(), "TUSD".into()])
+            .await
+            .unwrap();
+        assert_eq!(estimate.token.symbol, "TUSD");
+
+        let result = wallet.cheapest_fee_token(txs, vec!["ETH".into()]).await;
+        assert_eq!(result.unwrap_err(), ClientError::NoViableFeeToken);
+    }
+
+    #[tokio::test]
+    async fn test_wallet_create2() {
+        let create2_data = ChangePubKeyCREATE2Data {
+            creator_address: Address::repeat_byte(1),
+            salt_arg: H256::repeat_byte(2),
+            code_hash: H256::repeat_byte(3),
+        };
+        let private_key = private_key_from_seed(&[100; 32]).unwrap();
+        let pub_key_hash = PubKeyHash::from_privkey(&private_key);
+
+        let creds: WalletCredentials<PrivateKeySigner> =
+            WalletCredentials::from_create2_data(create2_data.clone(), private_key);
+        assert_eq!(creds.eth_address(), create2_data.get_address(&pub_key_hash));
+
+        let provider = MockProvider {
+            network: Network::Mainnet,
+            eth_private_key: H256::repeat_byte(100),
+        };
+        let wallet = Wallet::new(provider, creds).await.unwrap();
+        assert_eq!(
+            wallet.signer.change_pubkey_type(false),
+            ChangePubKeyType::CREATE2
+        );
+
+        // The signing key is authorized with the CREATE2 data instead of the Ethereum signature.
+        let tx = wallet
+            .start_change_pubkey()
+            .fee_token("DAI")
+            .unwrap()
+            .fee(0_u32)
+            .nonce(Nonce(0))
+            .tx()
+            .await
+            .unwrap();
+        match tx {
+            ZkSyncTx::ChangePubKey(mut change_pubkey) => {
+                assert_eq!(change_pubkey.account, wallet.address());
+                assert!(matches!(
+                    change_pubkey.eth_auth_data,
+                    Some(ChangePubKeyEthAuthData::CREATE2(_))
+                ));
+                assert!(change_pubkey.check_correctness().is_ok());
+            }
+            _ => panic!("Signed transaction is not a ChangePubKey"),
+        }
+    }
+
     #[tokio::test]
     async fn test_wallet_ethereum() {
         let wallet = get_test_wallet(&[50; 32], Network::Mainnet).await;
@@ -772,3 +1339,220 @@ mod wallet_tests {
         assert_eq!(eth_provider.contract_address().as_bytes(), expected_address);
     }
 }
+
+#[cfg(test)]
+mod provider_tests {
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::{TcpListener, TcpStream},
+    };
+    use zksync::{
+        error::ClientError,
+        provider::{CallType, Provider, RetryPolicy, RpcProvider},
+        Network,
+    };
+
+    const CONTRACT_ADDRESS_RESPONSE: &str =
+        r#"{"jsonrpc":"2.0","result":{"mainContract":"0x1","govContract":"0x2"},"id":"1"}"#;
+
+    /// Starts the JSON RPC server responding to every request with the contract addresses after the `delay`.
+    /// The first `failures` requests are responded with the internal error status.
+    /// Returns the server address along with the counter of the received requests.
+    async fn start_mock_server(failures: usize, delay: Duration) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(AtomicUsize::new(0));
+
+        let counter = requests.clone();
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let request_number = counter.fetch_add(1, Ordering::SeqCst);
+                tokio::spawn(async move {
+                    read_request(&mut socket).await;
+                    tokio::time::sleep(delay).await;
+
+                    let response = if request_number < failures {
+                        "HTTP/1.1 500 Internal Server Error\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                            .to_string()
+                    } else {
+                        format!(
+                            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                            CONTRACT_ADDRESS_RESPONSE.len(),
+                            CONTRACT_ADDRESS_RESPONSE
+                        )
+                    };
+                    let _ = socket.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+
+        (addr, requests)
+    }
+
+    /// Reads the whole HTTP request, so that the connection isn't reset once the response is sent.
+    async fn read_request(socket: &mut TcpStream) {
+        let mut request = Vec::new();
+        let mut buffer = [0u8; 1024];
+        loop {
+            match socket.read(&mut buffer).await {
+                Ok(0) | Err(_) => return,
+                Ok(read) => request.extend_from_slice(&buffer[..read]),
+            }
+            let text = String::from_utf8_lossy(&request);
+            if let Some(headers_end) = text.find("\r\n\r\n") {
+                let content_length = text[..headers_end]
+                    .lines()
+                    .filter_map(|line| line.split_once(':'))
+                    .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+                    .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+                    .unwrap_or_default();
+                if request.len() >= headers_end + 4 + content_length {
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Returns the address nobody listens on.
+    async fn unavailable_addr() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        format!("http://{}", listener.local_addr().unwrap())
+    }
+
+    fn fast_retries(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_millis(50),
+            jitter: false,
+        }
+    }
+
+    #[test]
+    fn test_retry_policy_backoff() {
+        let policy = RetryPolicy {
+            max_attempts: 10,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(1),
+            jitter: false,
+        };
+        assert_eq!(policy.backoff(0), Duration::from_millis(100));
+        assert_eq!(policy.backoff(3), Duration::from_millis(800));
+        assert_eq!(policy.backoff(4), Duration::from_secs(1));
+        assert_eq!(policy.backoff(100), Duration::from_secs(1));
+
+        let policy = RetryPolicy {
+            jitter: true,
+            ..policy
+        };
+        for retry in 0..10 {
+            let backoff = policy.backoff(retry);
+            let max = Duration::from_millis(100 << retry).min(Duration::from_secs(1));
+            assert!(backoff >= max / 2 && backoff <= max);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rpc_provider_failover() {
+        let (addr, requests) = start_mock_server(0, Duration::default()).await;
+        let unavailable = unavailable_addr().await;
+        let provider = RpcProvider::from_addrs(vec![unavailable, addr], Network::Localhost)
+            .unwrap()
+            .retry_policy(fast_retries(2))
+            .unwrap();
+
+        let contract = provider.contract_address().await.unwrap();
+        assert_eq!(contract.main_contract, "0x1");
+        let status = provider.endpoints_status();
+        assert!(!status[0].available);
+        assert!(status[1].available);
+
+        // Unavailable endpoint is skipped until it recovers.
+        provider.contract_address().await.unwrap();
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+        let status = provider.check_health().await;
+        assert!(!status[0].available);
+        assert!(status[1].available);
+
+        assert_eq!(
+            RpcProvider::from_addrs(Vec::<String>::new(), Network::Localhost).unwrap_err(),
+            ClientError::IncorrectInput
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rpc_provider_retries() {
+        let (addr, requests) = start_mock_server(2, Duration::default()).await;
+        let provider = RpcProvider::from_addr(&addr)
+            .retry_policy(fast_retries(3))
+            .unwrap();
+        provider.contract_address().await.unwrap();
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+
+        let (addr, requests) = start_mock_server(2, Duration::default()).await;
+        let provider = RpcProvider::from_addr(&addr)
+            .retry_policy(RetryPolicy::no_retries())
+            .unwrap();
+        assert!(matches!(
+            provider.contract_address().await,
+            Err(ClientError::NetworkError(..))
+        ));
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+        assert!(!provider.endpoints_status()[0].available);
+
+        assert!(RpcProvider::from_addr(&addr)
+            .retry_policy(fast_retries(0))
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rpc_provider_timeouts() {
+        let (addr, _) = start_mock_server(0, Duration::from_millis(500)).await;
+        let provider = RpcProvider::from_addr(&addr)
+            .retry_policy(RetryPolicy::no_retries())
+            .unwrap()
+            .timeout(CallType::Query, Duration::from_millis(50));
+        assert!(matches!(
+            provider.contract_address().await,
+            Err(ClientError::NetworkError(..))
+        ));
+
+        let provider = provider.timeout(CallType::Query, Duration::from_secs(5));
+        provider.contract_address().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_rpc_provider_submission_not_resent() {
+        // Submission that timed out may be accepted by the server, so it's not sent again.
+        let (addr, requests) = start_mock_server(0, Duration::from_millis(500)).await;
+        let provider = RpcProvider::from_addr(&addr)
+            .retry_policy(fast_retries(3))
+            .unwrap()
+            .timeout(CallType::Submission, Duration::from_millis(50));
+        assert!(matches!(
+            provider.send_txs_batch(Vec::new(), None).await,
+            Err(ClientError::NetworkError(..))
+        ));
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+        // Submission that wasn't delivered is sent to the next endpoint.
+        let (addr, requests) = start_mock_server(0, Duration::default()).await;
+        let unavailable = unavailable_addr().await;
+        let provider = RpcProvider::from_addrs(vec![unavailable, addr], Network::Localhost)
+            .unwrap()
+            .retry_policy(fast_retries(2))
+            .unwrap();
+        // Mock server responds with the contract addresses, so only the delivery is checked.
+        let _ = provider.send_txs_batch(Vec::new(), None).await;
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,18 @@
+#!/bin/bash
+
+set -e
+
+which wasm-pack || cargo install --version 0.10.1 wasm-pack
+
+# pack for bundler
+wasm-pack build --release --target=bundler --out-name=zksync-wasm-bundler --out-dir=dist
+# pack for browser
+wasm-pack build --release --target=web --out-name=zksync-wasm-web --out-dir=web-dist
+# pack for node.js
+wasm-pack build --release --target=nodejs --out-name=zksync-wasm-node --out-dir=node-dist
+
+# Merge dist folders. wasm-pack removes out-dir before it starts a new build
+mv web-dist/* dist/
+mv node-dist/* dist/
+rm -rf web-dist node-dist
+rm dist/package.json dist/.gitignore
@@ -0,0 +1,286 @@
+//! WebAssembly bindings for the zkSync SDK.
+//! Transactions are encoded the same way the server and the Rust SDK do it and are signed
+//! by the `zksync-crypto` crate used by `zksync.js`, so the browser and Node integrators
+//! don't depend on a separate signing implementation. The server crates can't be compiled
+//! into wasm, so the encoding is ported here and checked against them in `tests.rs`.
+//!
+//! The private key is derived from the seed by `privateKeyFromSeed` or, via the derivation path,
+//! by `derivePrivateKey` of `zksync-crypto`, which are exported by this package as well.
+//!
+//! Transactions and their parameters are passed as JS objects in the format of the zkSync API.
+
+#[cfg(test)]
+mod tests;
+pub mod tx;
+pub mod types;
+pub mod utils;
+
+use num::BigUint;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    tx::{pub_key_hash, ChangePubKey, ForcedExit, Transfer, Withdraw, ZkSyncTx},
+    types::{Address, TimeRange, TokenLike, TxFeeType},
+    utils::biguint_str,
+};
+
+/// Parameters of the `Transfer` and `Withdraw` transactions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferParams {
+    pub account_id: u32,
+    pub from: Address,
+    pub to: Address,
+    pub token: u32,
+    #[serde(with = "biguint_str")]
+    pub amount: BigUint,
+    #[serde(with = "biguint_str")]
+    pub fee: BigUint,
+    pub nonce: u32,
+    #[serde(default)]
+    pub valid_from: Option<u64>,
+    #[serde(default)]
+    pub valid_until: Option<u64>,
+}
+
+/// Parameters of the `ForcedExit` transaction.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForcedExitParams {
+    pub initiator_account_id: u32,
+    pub target: Address,
+    pub token: u32,
+    #[serde(with = "biguint_str")]
+    pub fee: BigUint,
+    pub nonce: u32,
+    #[serde(default)]
+    pub valid_from: Option<u64>,
+    #[serde(default)]
+    pub valid_until: Option<u64>,
+}
+
+/// Parameters of the `ChangePubKey` transaction, the new public key hash
+/// is the one of the signing key.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangePubKeyParams {
+    pub account_id: u32,
+    pub account: Address,
+    pub fee_token: u32,
+    #[serde(with = "biguint_str")]
+    pub fee: BigUint,
+    pub nonce: u32,
+    #[serde(default)]
+    pub valid_from: Option<u64>,
+    #[serde(default)]
+    pub valid_until: Option<u64>,
+}
+
+/// Token the Ethereum message of the transaction refers to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageToken {
+    pub symbol: String,
+    pub decimals: u8,
+}
+
+/// JSON-RPC request to be sent to the zkSync API.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct JsonRpcRequest {
+    pub id: String,
+    pub method: String,
+    pub jsonrpc: String,
+    pub params: Vec<serde_json::Value>,
+}
+
+impl JsonRpcRequest {
+    fn create(method: &str, params: Vec<serde_json::Value>) -> Self {
+        Self {
+            id: "1".to_owned(),
+            jsonrpc: "2.0".to_owned(),
+            method: method.to_owned(),
+            params,
+        }
+    }
+}
+
+fn js_error(err: impl ToString) -> JsValue {
+    JsValue::from_str(&err.to_string())
+}
+
+fn from_js<T: DeserializeOwned>(value: &JsValue) -> Result<T, JsValue> {
+    value.into_serde().map_err(js_error)
+}
+
+fn to_js<T: Serialize>(value: &T) -> Result<JsValue, JsValue> {
+    JsValue::from_serde(value).map_err(js_error)
+}
+
+fn to_json_value<T: Serialize>(value: T) -> serde_json::Value {
+    serde_json::to_value(value).expect("serialization fail")
+}
+
+pub fn sign_transfer_tx(private_key: &[u8], params: TransferParams) -> Result<ZkSyncTx, String> {
+    let mut tx = Transfer {
+        account_id: params.account_id,
+        from: params.from,
+        to: params.to,
+        token: params.token,
+        amount: params.amount,
+        fee: params.fee,
+        nonce: params.nonce,
+        time_range: TimeRange::new(params.valid_from, params.valid_until),
+        signature: None,
+    };
+    tx.sign(private_key)?;
+    Ok(ZkSyncTx::Transfer(Box::new(tx)))
+}
+
+pub fn sign_withdraw_tx(private_key: &[u8], params: TransferParams) -> Result<ZkSyncTx, String> {
+    let mut tx = Withdraw {
+        account_id: params.account_id,
+        from: params.from,
+        to: params.to,
+        token: params.token,
+        amount: params.amount,
+        fee: params.fee,
+        nonce: params.nonce,
+        signature: None,
+        fast: false,
+        time_range: TimeRange::new(params.valid_from, params.valid_until),
+    };
+    tx.sign(private_key)?;
+    Ok(ZkSyncTx::Withdraw(Box::new(tx)))
+}
+
+pub fn sign_forced_exit_tx(
+    private_key: &[u8],
+    params: ForcedExitParams,
+) -> Result<ZkSyncTx, String> {
+    let mut tx = ForcedExit {
+        initiator_account_id: params.initiator_account_id,
+        target: params.target,
+        token: params.token,
+        fee: params.fee,
+        nonce: params.nonce,
+        signature: None,
+        time_range: TimeRange::new(params.valid_from, params.valid_until),
+    };
+    tx.sign(private_key)?;
+    Ok(ZkSyncTx::ForcedExit(Box::new(tx)))
+}
+
+pub fn sign_change_pubkey_tx(
+    private_key: &[u8],
+    params: ChangePubKeyParams,
+) -> Result<ZkSyncTx, String> {
+    let mut tx = ChangePubKey {
+        account_id: params.account_id,
+        account: params.account,
+        new_pk_hash: pub_key_hash(private_key)?,
+        fee_token: params.fee_token,
+        fee: params.fee,
+        nonce: params.nonce,
+        signature: None,
+        eth_signature: None,
+        eth_auth_data: None,
+        time_range: TimeRange::new(params.valid_from, params.valid_until),
+    };
+    tx.sign(private_key)?;
+    Ok(ZkSyncTx::ChangePubKey(Box::new(tx)))
+}
+
+/// Returns the message to be signed by the Ethereum key to authorize the transaction.
+pub fn tx_eth_sign_message(tx: &ZkSyncTx, token: &MessageToken) -> Vec<u8> {
+    match tx {
+        ZkSyncTx::Transfer(tx) => tx
+            .get_ethereum_sign_message(&token.symbol, token.decimals)
+            .into_bytes(),
+        ZkSyncTx::Withdraw(tx) => tx
+            .get_ethereum_sign_message(&token.symbol, token.decimals)
+            .into_bytes(),
+        ZkSyncTx::ForcedExit(tx) => tx
+            .get_ethereum_sign_message(&token.symbol, token.decimals)
+            .into_bytes(),
+        ZkSyncTx::ChangePubKey(tx) => tx.get_eth_signed_data(),
+    }
+}
+
+pub fn tx_fee_request(tx_type: TxFeeType, address: Address, token: TokenLike) -> JsonRpcRequest {
+    JsonRpcRequest::create(
+        "get_tx_fee",
+        vec![
+            to_json_value(tx_type),
+            to_json_value(address),
+            to_json_value(token),
+        ],
+    )
+}
+
+pub fn txs_batch_fee_request(
+    tx_types: Vec<TxFeeType>,
+    addresses: Vec<Address>,
+    token: TokenLike,
+) -> JsonRpcRequest {
+    JsonRpcRequest::create(
+        "get_txs_batch_fee_in_wei",
+        vec![
+            to_json_value(tx_types),
+            to_json_value(addresses),
+            to_json_value(token),
+        ],
+    )
+}
+
+#[wasm_bindgen(js_name = signTransfer)]
+pub fn sign_transfer(private_key: &[u8], params: &JsValue) -> Result<JsValue, JsValue> {
+    to_js(&sign_transfer_tx(private_key, from_js(params)?).map_err(js_error)?)
+}
+
+#[wasm_bindgen(js_name = signWithdraw)]
+pub fn sign_withdraw(private_key: &[u8], params: &JsValue) -> Result<JsValue, JsValue> {
+    to_js(&sign_withdraw_tx(private_key, from_js(params)?).map_err(js_error)?)
+}
+
+#[wasm_bindgen(js_name = signForcedExit)]
+pub fn sign_forced_exit(private_key: &[u8], params: &JsValue) -> Result<JsValue, JsValue> {
+    to_js(&sign_forced_exit_tx(private_key, from_js(params)?).map_err(js_error)?)
+}
+
+/// Signs the `ChangePubKey` transaction with the zkSync key. The Ethereum authorization
+/// data is to be added by the caller, see `txEthSignMessage`.
+#[wasm_bindgen(js_name = signChangePubKey)]
+pub fn sign_change_pubkey(private_key: &[u8], params: &JsValue) -> Result<JsValue, JsValue> {
+    to_js(&sign_change_pubkey_tx(private_key, from_js(params)?).map_err(js_error)?)
+}
+
+#[wasm_bindgen(js_name = txEthSignMessage)]
+pub fn tx_eth_sign_message_js(tx: &JsValue, token: &JsValue) -> Result<Vec<u8>, JsValue> {
+    Ok(tx_eth_sign_message(&from_js(tx)?, &from_js(token)?))
+}
+
+#[wasm_bindgen(js_name = txFeeRequest)]
+pub fn tx_fee_request_js(
+    tx_type: &JsValue,
+    address: &JsValue,
+    token: &JsValue,
+) -> Result<JsValue, JsValue> {
+    to_js(&tx_fee_request(
+        from_js(tx_type)?,
+        from_js(address)?,
+        from_js(token)?,
+    ))
+}
+
+#[wasm_bindgen(js_name = txsBatchFeeRequest)]
+pub fn txs_batch_fee_request_js(
+    tx_types: &JsValue,
+    addresses: &JsValue,
+    token: &JsValue,
+) -> Result<JsValue, JsValue> {
+    to_js(&txs_batch_fee_request(
+        from_js(tx_types)?,
+        from_js(addresses)?,
+        from_js(token)?,
+    ))
+}
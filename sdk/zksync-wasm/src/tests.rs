@@ -0,0 +1,255 @@
+//! Compare the signed transactions to those built by the `zksync_types` crate;
+
+use super::*;
+
+use crypto_lib::{
+    bellman::{pairing::ff::PrimeField, PrimeFieldRepr},
+    franklin_crypto::alt_babyjubjub::fs::FsRepr,
+    priv_key_from_fs, Fs, PrivateKey,
+};
+use zksync_types::{tx::TimeRange as ServerTimeRange, PubKeyHash, Token, TokenId, TokenKind};
+
+const SEED: [u8; 32] = [7u8; 32];
+
+fn private_key() -> (PrivateKey, Vec<u8>) {
+    let bytes = zksync_crypto::private_key_from_seed(&SEED).unwrap();
+    let mut fs_repr = FsRepr::default();
+    fs_repr.read_be(&bytes[..]).unwrap();
+    (priv_key_from_fs(Fs::from_repr(fs_repr).unwrap()), bytes)
+}
+
+fn transfer_params() -> TransferParams {
+    serde_json::from_value(serde_json::json!({
+        "accountId": 42,
+        "from": "0x0000000000000000000000000000000000000001",
+        "to": "0x00000000000000000000000000000000000000ff",
+        "token": 1,
+        "amount": "1234500000000000000",
+        "fee": "1000",
+        "nonce": 3,
+        "validUntil": 1_000_000,
+    }))
+    .unwrap()
+}
+
+fn server_tx(tx: &ZkSyncTx) -> zksync_types::ZkSyncTx {
+    serde_json::from_value(serde_json::to_value(tx).unwrap()).unwrap()
+}
+
+fn assert_same_json(tx: &ZkSyncTx, expected: zksync_types::ZkSyncTx) {
+    assert_eq!(
+        serde_json::to_value(tx).unwrap(),
+        serde_json::to_value(expected).unwrap()
+    );
+}
+
+#[test]
+fn test_packing() {
+    let values = [
+        BigUint::from(0u32),
+        BigUint::from(2047u32),
+        BigUint::from(2048u32),
+        BigUint::from(1234500000000000000u64),
+        BigUint::from(34_359_738_367u64) * BigUint::from(10u32).pow(20),
+        BigUint::from(34_359_738_368u64),
+        BigUint::from(12345u32),
+    ];
+    for value in values.iter() {
+        let packable = zksync_types::helpers::is_token_amount_packable(value);
+        assert_eq!(utils::pack_token_amount(value).is_some(), packable);
+        if packable {
+            assert_eq!(
+                utils::pack_token_amount(value).unwrap(),
+                zksync_types::helpers::pack_token_amount(value)
+            );
+        }
+
+        let packable = zksync_types::helpers::is_fee_amount_packable(value);
+        assert_eq!(utils::pack_fee_amount(value).is_some(), packable);
+        if packable {
+            assert_eq!(
+                utils::pack_fee_amount(value).unwrap(),
+                zksync_types::helpers::pack_fee_amount(value)
+            );
+        }
+    }
+}
+
+#[test]
+fn test_format_units() {
+    for (value, decimals) in [(0u64, 18), (1, 18), (1_000_000, 6), (1_234_500, 3), (10, 0)].iter() {
+        let value = BigUint::from(*value);
+        assert_eq!(
+            utils::format_units(&value, *decimals),
+            zksync_utils::format_units(&value, *decimals)
+        );
+    }
+}
+
+#[test]
+fn test_sign_transfer() {
+    let (private_key, bytes) = private_key();
+    let params = transfer_params();
+
+    let tx = sign_transfer_tx(&bytes, params.clone()).unwrap();
+    let expected = zksync_types::Transfer::new_signed(
+        params.account_id.into(),
+        params.from.0.into(),
+        params.to.0.into(),
+        params.token.into(),
+        params.amount,
+        params.fee,
+        params.nonce.into(),
+        ServerTimeRange::new(0, 1_000_000),
+        &private_key,
+    )
+    .unwrap();
+    assert_same_json(&tx, expected.into());
+}
+
+#[test]
+fn test_sign_withdraw() {
+    let (private_key, bytes) = private_key();
+    let tx = sign_withdraw_tx(&bytes, transfer_params()).unwrap();
+
+    match server_tx(&tx) {
+        zksync_types::ZkSyncTx::Withdraw(tx) => {
+            let (pub_key_hash, _) = tx.verify_signature().unwrap();
+            assert_eq!(pub_key_hash, PubKeyHash::from_privkey(&private_key));
+        }
+        tx => panic!("Unexpected transaction: {:?}", tx),
+    }
+}
+
+#[test]
+fn test_sign_forced_exit() {
+    let (private_key, bytes) = private_key();
+    let params: ForcedExitParams = serde_json::from_value(serde_json::json!({
+        "initiatorAccountId": 42,
+        "target": "0x0000000000000000000000000000000000000002",
+        "token": 0,
+        "fee": "12300000",
+        "nonce": 1,
+    }))
+    .unwrap();
+
+    let tx = sign_forced_exit_tx(&bytes, params.clone()).unwrap();
+    let expected = zksync_types::ForcedExit::new_signed(
+        params.initiator_account_id.into(),
+        params.target.0.into(),
+        params.token.into(),
+        params.fee,
+        params.nonce.into(),
+        Default::default(),
+        &private_key,
+    )
+    .unwrap();
+    assert_same_json(&tx, expected.into());
+}
+
+#[test]
+fn test_sign_change_pubkey() {
+    let (private_key, bytes) = private_key();
+    let params: ChangePubKeyParams = serde_json::from_value(serde_json::json!({
+        "accountId": 42,
+        "account": "0x0000000000000000000000000000000000000001",
+        "feeToken": 0,
+        "fee": "0",
+        "nonce": 0,
+    }))
+    .unwrap();
+
+    let tx = sign_change_pubkey_tx(&bytes, params).unwrap();
+    match server_tx(&tx) {
+        zksync_types::ZkSyncTx::ChangePubKey(server_tx) => {
+            assert_eq!(
+                server_tx.new_pk_hash,
+                PubKeyHash::from_privkey(&private_key)
+            );
+            assert!(server_tx.verify_signature().is_some());
+            assert_eq!(
+                tx_eth_sign_message(&tx, &token()),
+                server_tx.get_eth_signed_data().unwrap()
+            );
+        }
+        tx => panic!("Unexpected transaction: {:?}", tx),
+    }
+}
+
+fn token() -> MessageToken {
+    MessageToken {
+        symbol: "DAI".to_string(),
+        decimals: 18,
+    }
+}
+
+#[test]
+fn test_eth_sign_message() {
+    let (_, bytes) = private_key();
+    let server_token = Token::new(TokenId(1), Default::default(), "DAI", 18, TokenKind::ERC20);
+
+    let txs = vec![
+        sign_transfer_tx(&bytes, transfer_params()).unwrap(),
+        sign_withdraw_tx(&bytes, transfer_params()).unwrap(),
+    ];
+    for tx in txs {
+        let message = tx_eth_sign_message(&tx, &token());
+        assert_eq!(
+            String::from_utf8(message).unwrap(),
+            server_tx(&tx)
+                .get_ethereum_sign_message(server_token.clone())
+                .unwrap()
+        );
+    }
+}
+
+#[test]
+fn test_not_packable_amount() {
+    let (_, bytes) = private_key();
+    let mut params = transfer_params();
+    params.amount = BigUint::from(12345678901234567u64);
+    assert!(sign_transfer_tx(&bytes, params).is_err());
+}
+
+#[test]
+fn test_fee_requests() {
+    let address: Address = "0x0101010101010101010101010101010101010101"
+        .parse()
+        .unwrap();
+    let request = tx_fee_request(
+        TxFeeType::Transfer,
+        address,
+        TokenLike::Symbol("ETH".to_string()),
+    );
+    assert_eq!(request.method, "get_tx_fee");
+    assert_eq!(
+        request.params,
+        vec![
+            serde_json::to_value(zksync_types::TxFeeTypes::Transfer).unwrap(),
+            serde_json::to_value(zksync_types::Address::repeat_byte(1)).unwrap(),
+            serde_json::json!("ETH"),
+        ]
+    );
+
+    let request = txs_batch_fee_request(
+        vec![
+            TxFeeType::Withdraw,
+            TxFeeType::ChangePubKey(types::ChangePubKeyType::ECDSA),
+        ],
+        vec![address, address],
+        TokenLike::Id(0),
+    );
+    assert_eq!(request.method, "get_txs_batch_fee_in_wei");
+    assert_eq!(
+        request.params[0],
+        serde_json::to_value(vec![
+            zksync_types::TxFeeTypes::Withdraw,
+            zksync_types::TxFeeTypes::ChangePubKey(
+                zksync_types::tokens::ChangePubKeyFeeTypeArg::ContractsV4Version(
+                    zksync_types::tx::ChangePubKeyType::ECDSA
+                )
+            ),
+        ])
+        .unwrap()
+    );
+}
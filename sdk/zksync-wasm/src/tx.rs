@@ -0,0 +1,292 @@
+//! zkSync transactions in the format of the zkSync API and their encoding for the signature,
+//! see the `zksync_types::tx` module.
+
+use num::{BigUint, ToPrimitive, Zero};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsValue;
+
+use crate::{
+    types::{Address, PubKeyHash, TimeRange, TxSignature},
+    utils::{
+        biguint_str, ethereum_sign_message_part, format_units, pack_fee_amount, pack_token_amount,
+    },
+};
+
+/// Version of the transactions encoding, the same as `CURRENT_TX_VERSION` of the server.
+const TX_VERSION: u8 = 1;
+const PACKED_POINT_SIZE: usize = 32;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Transfer {
+    pub account_id: u32,
+    pub from: Address,
+    pub to: Address,
+    pub token: u32,
+    #[serde(with = "biguint_str")]
+    pub amount: BigUint,
+    #[serde(with = "biguint_str")]
+    pub fee: BigUint,
+    pub nonce: u32,
+    #[serde(flatten)]
+    pub time_range: TimeRange,
+    #[serde(default)]
+    pub signature: Option<TxSignature>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Withdraw {
+    pub account_id: u32,
+    pub from: Address,
+    pub to: Address,
+    pub token: u32,
+    #[serde(with = "biguint_str")]
+    pub amount: BigUint,
+    #[serde(with = "biguint_str")]
+    pub fee: BigUint,
+    pub nonce: u32,
+    #[serde(default)]
+    pub signature: Option<TxSignature>,
+    #[serde(default)]
+    pub fast: bool,
+    #[serde(flatten)]
+    pub time_range: TimeRange,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForcedExit {
+    pub initiator_account_id: u32,
+    pub target: Address,
+    pub token: u32,
+    #[serde(with = "biguint_str")]
+    pub fee: BigUint,
+    pub nonce: u32,
+    #[serde(default)]
+    pub signature: Option<TxSignature>,
+    #[serde(flatten)]
+    pub time_range: TimeRange,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangePubKey {
+    pub account_id: u32,
+    pub account: Address,
+    pub new_pk_hash: PubKeyHash,
+    pub fee_token: u32,
+    #[serde(with = "biguint_str")]
+    pub fee: BigUint,
+    pub nonce: u32,
+    #[serde(default)]
+    pub signature: Option<TxSignature>,
+    /// Set by the caller after the transaction is authorized by the Ethereum key.
+    #[serde(default)]
+    pub eth_signature: Option<String>,
+    #[serde(default)]
+    pub eth_auth_data: Option<serde_json::Value>,
+    #[serde(flatten)]
+    pub time_range: TimeRange,
+}
+
+/// Transaction signed by the bindings, serialized as it is expected by the zkSync API.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum ZkSyncTx {
+    Transfer(Box<Transfer>),
+    Withdraw(Box<Withdraw>),
+    ChangePubKey(Box<ChangePubKey>),
+    ForcedExit(Box<ForcedExit>),
+}
+
+fn packed_amount(amount: &BigUint) -> Result<Vec<u8>, String> {
+    pack_token_amount(amount).ok_or_else(|| format!("Amount {} is not packable", amount))
+}
+
+fn packed_fee(fee: &BigUint) -> Result<Vec<u8>, String> {
+    pack_fee_amount(fee).ok_or_else(|| format!("Fee {} is not packable", fee))
+}
+
+fn check_time_range(time_range: &TimeRange) -> Result<(), String> {
+    if time_range.valid_from > time_range.valid_until {
+        return Err("validFrom is greater than validUntil".to_string());
+    }
+    Ok(())
+}
+
+fn crypto_error(err: JsValue) -> String {
+    err.as_string()
+        .unwrap_or_else(|| "Failed to sign the transaction".to_string())
+}
+
+/// Signs the transaction bytes with the same MuSig scheme `zksync.js` uses.
+fn sign(private_key: &[u8], bytes: &[u8]) -> Result<TxSignature, String> {
+    let signature = zksync_crypto::sign_musig(private_key, bytes).map_err(crypto_error)?;
+    let (pub_key, signature) = signature.split_at(PACKED_POINT_SIZE);
+    Ok(TxSignature {
+        pub_key: hex::encode(pub_key),
+        signature: hex::encode(signature),
+    })
+}
+
+/// Reads the public key hash of the signing key.
+pub fn pub_key_hash(private_key: &[u8]) -> Result<PubKeyHash, String> {
+    let hash = zksync_crypto::private_key_to_pubkey_hash(private_key).map_err(crypto_error)?;
+    let mut data = [0u8; 20];
+    if hash.len() != data.len() {
+        return Err("Invalid public key hash length".to_string());
+    }
+    data.copy_from_slice(&hash);
+    Ok(PubKeyHash(data))
+}
+
+impl Transfer {
+    pub const TX_TYPE: u8 = 5;
+
+    pub fn get_bytes(&self) -> Result<Vec<u8>, String> {
+        let mut out = vec![255u8 - Self::TX_TYPE, TX_VERSION];
+        out.extend_from_slice(&self.account_id.to_be_bytes());
+        out.extend_from_slice(self.from.as_bytes());
+        out.extend_from_slice(self.to.as_bytes());
+        out.extend_from_slice(&self.token.to_be_bytes());
+        out.extend_from_slice(&packed_amount(&self.amount)?);
+        out.extend_from_slice(&packed_fee(&self.fee)?);
+        out.extend_from_slice(&self.nonce.to_be_bytes());
+        out.extend_from_slice(&self.time_range.as_be_bytes());
+        Ok(out)
+    }
+
+    pub fn sign(&mut self, private_key: &[u8]) -> Result<(), String> {
+        check_time_range(&self.time_range)?;
+        self.signature = Some(sign(private_key, &self.get_bytes()?)?);
+        Ok(())
+    }
+
+    pub fn get_ethereum_sign_message(&self, token_symbol: &str, decimals: u8) -> String {
+        let mut message = ethereum_sign_message_part(
+            "Transfer",
+            token_symbol,
+            decimals,
+            &self.amount,
+            &self.fee,
+            &self.to.to_string(),
+        );
+        if !message.is_empty() {
+            message.push('\n');
+        }
+        message.push_str(&format!("Nonce: {}", self.nonce));
+        message
+    }
+}
+
+impl Withdraw {
+    pub const TX_TYPE: u8 = 3;
+
+    pub fn get_bytes(&self) -> Result<Vec<u8>, String> {
+        let amount = self
+            .amount
+            .to_u128()
+            .ok_or_else(|| format!("Amount {} is too big", self.amount))?;
+
+        let mut out = vec![255u8 - Self::TX_TYPE, TX_VERSION];
+        out.extend_from_slice(&self.account_id.to_be_bytes());
+        out.extend_from_slice(self.from.as_bytes());
+        out.extend_from_slice(self.to.as_bytes());
+        out.extend_from_slice(&self.token.to_be_bytes());
+        out.extend_from_slice(&amount.to_be_bytes());
+        out.extend_from_slice(&packed_fee(&self.fee)?);
+        out.extend_from_slice(&self.nonce.to_be_bytes());
+        out.extend_from_slice(&self.time_range.as_be_bytes());
+        Ok(out)
+    }
+
+    pub fn sign(&mut self, private_key: &[u8]) -> Result<(), String> {
+        check_time_range(&self.time_range)?;
+        self.signature = Some(sign(private_key, &self.get_bytes()?)?);
+        Ok(())
+    }
+
+    pub fn get_ethereum_sign_message(&self, token_symbol: &str, decimals: u8) -> String {
+        let mut message = ethereum_sign_message_part(
+            "Withdraw",
+            token_symbol,
+            decimals,
+            &self.amount,
+            &self.fee,
+            &self.to.to_string(),
+        );
+        if !message.is_empty() {
+            message.push('\n');
+        }
+        message.push_str(&format!("Nonce: {}", self.nonce));
+        message
+    }
+}
+
+impl ForcedExit {
+    pub const TX_TYPE: u8 = 8;
+
+    pub fn get_bytes(&self) -> Result<Vec<u8>, String> {
+        let mut out = vec![255u8 - Self::TX_TYPE, TX_VERSION];
+        out.extend_from_slice(&self.initiator_account_id.to_be_bytes());
+        out.extend_from_slice(self.target.as_bytes());
+        out.extend_from_slice(&self.token.to_be_bytes());
+        out.extend_from_slice(&packed_fee(&self.fee)?);
+        out.extend_from_slice(&self.nonce.to_be_bytes());
+        out.extend_from_slice(&self.time_range.as_be_bytes());
+        Ok(out)
+    }
+
+    pub fn sign(&mut self, private_key: &[u8]) -> Result<(), String> {
+        check_time_range(&self.time_range)?;
+        self.signature = Some(sign(private_key, &self.get_bytes()?)?);
+        Ok(())
+    }
+
+    pub fn get_ethereum_sign_message(&self, token_symbol: &str, decimals: u8) -> String {
+        let mut message = format!("ForcedExit {} to: {}", token_symbol, self.target);
+        if !self.fee.is_zero() {
+            message.push_str(&format!(
+                "\nFee: {} {}",
+                format_units(&self.fee, decimals),
+                token_symbol
+            ));
+        }
+        message.push_str(&format!("\nNonce: {}", self.nonce));
+        message
+    }
+}
+
+impl ChangePubKey {
+    pub const TX_TYPE: u8 = 7;
+
+    pub fn get_bytes(&self) -> Result<Vec<u8>, String> {
+        let mut out = vec![255u8 - Self::TX_TYPE, TX_VERSION];
+        out.extend_from_slice(&self.account_id.to_be_bytes());
+        out.extend_from_slice(self.account.as_bytes());
+        out.extend_from_slice(&self.new_pk_hash.0);
+        out.extend_from_slice(&self.fee_token.to_be_bytes());
+        out.extend_from_slice(&packed_fee(&self.fee)?);
+        out.extend_from_slice(&self.nonce.to_be_bytes());
+        out.extend_from_slice(&self.time_range.as_be_bytes());
+        Ok(out)
+    }
+
+    pub fn sign(&mut self, private_key: &[u8]) -> Result<(), String> {
+        check_time_range(&self.time_range)?;
+        self.signature = Some(sign(private_key, &self.get_bytes()?)?);
+        Ok(())
+    }
+
+    /// Message to be signed by the Ethereum key to authorize the new public key hash (ECDSA),
+    /// the transaction is not a part of a batch.
+    pub fn get_eth_signed_data(&self) -> Vec<u8> {
+        let mut message = Vec::with_capacity(60);
+        message.extend_from_slice(&self.new_pk_hash.0);
+        message.extend_from_slice(&self.nonce.to_be_bytes());
+        message.extend_from_slice(&self.account_id.to_be_bytes());
+        message.extend_from_slice(&[0u8; 32]);
+        message
+    }
+}
@@ -0,0 +1,139 @@
+//! Primitive types of the zkSync API, serialized the same way as their `zksync_types` counterparts.
+
+use std::{fmt, str::FromStr};
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+/// Ethereum address.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Address(pub [u8; 20]);
+
+impl Address {
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x{}", hex::encode(self.0))
+    }
+}
+
+impl fmt::Debug for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl FromStr for Address {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.strip_prefix("0x").unwrap_or(s);
+        let mut address = [0u8; 20];
+        hex::decode_to_slice(s, &mut address)
+            .map_err(|err| format!("invalid address {}: {}", s, err))?;
+        Ok(Self(address))
+    }
+}
+
+impl Serialize for Address {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Address {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+/// Hash of the zkSync public key, serialized with the `sync:` prefix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PubKeyHash(pub [u8; 20]);
+
+impl Serialize for PubKeyHash {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("sync:{}", hex::encode(self.0)))
+    }
+}
+
+impl<'de> Deserialize<'de> for PubKeyHash {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        let hex_hash = value
+            .strip_prefix("sync:")
+            .ok_or_else(|| de::Error::custom("public key hash must start with sync:"))?;
+        let mut hash = [0u8; 20];
+        hex::decode_to_slice(hex_hash, &mut hash).map_err(de::Error::custom)?;
+        Ok(Self(hash))
+    }
+}
+
+/// Time range `[valid_from, valid_until]` in which the transaction is valid.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeRange {
+    pub valid_from: u64,
+    pub valid_until: u64,
+}
+
+impl TimeRange {
+    pub fn new(valid_from: Option<u64>, valid_until: Option<u64>) -> Self {
+        Self {
+            valid_from: valid_from.unwrap_or(0),
+            valid_until: valid_until.unwrap_or(u64::MAX),
+        }
+    }
+
+    pub fn as_be_bytes(&self) -> Vec<u8> {
+        [
+            self.valid_from.to_be_bytes(),
+            self.valid_until.to_be_bytes(),
+        ]
+        .concat()
+    }
+}
+
+/// zkSync transaction signature: the packed public key of the signer
+/// and the packed MuSig signature of the transaction bytes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxSignature {
+    pub pub_key: String,
+    pub signature: String,
+}
+
+/// Type of the `ChangePubKey` authorization.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ChangePubKeyType {
+    Onchain,
+    ECDSA,
+    CREATE2,
+}
+
+/// Type of the transaction to request the fee for.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum TxFeeType {
+    Withdraw,
+    FastWithdraw,
+    Transfer,
+    ChangePubKey(ChangePubKeyType),
+    Swap,
+    MintNFT,
+    WithdrawNFT,
+    FastWithdrawNFT,
+}
+
+/// Token ID, address or symbol.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum TokenLike {
+    Id(u32),
+    Address(Address),
+    Symbol(String),
+}
@@ -0,0 +1,115 @@
+//! Encoding helpers ported from the `zksync_types` and `zksync_utils` crates,
+//! which can't be compiled into wasm. `tests.rs` checks that both implementations agree.
+
+use num::{BigUint, ToPrimitive, Zero};
+use serde::{de, Deserialize, Deserializer, Serializer};
+
+pub const AMOUNT_EXPONENT_BIT_WIDTH: u32 = 5;
+pub const AMOUNT_MANTISSA_BIT_WIDTH: u32 = 35;
+pub const FEE_EXPONENT_BIT_WIDTH: u32 = 5;
+pub const FEE_MANTISSA_BIT_WIDTH: u32 = 11;
+
+/// Packs the value into the floating point form with the base 10 exponent:
+/// `mantissa` bits followed by `exponent` bits, big-endian.
+/// Returns `None` if the value can't be represented in this form without the loss of precision.
+fn pack(value: &BigUint, exponent_len: u32, mantissa_len: u32) -> Option<Vec<u8>> {
+    let mut mantissa = value.to_u128()?;
+    let max_mantissa = (1u128 << mantissa_len) - 1;
+    let max_exponent = (1u128 << exponent_len) - 1;
+
+    let mut exponent = 0u128;
+    while mantissa > max_mantissa {
+        if mantissa % 10 != 0 || exponent == max_exponent {
+            return None;
+        }
+        mantissa /= 10;
+        exponent += 1;
+    }
+
+    let packed = (mantissa << exponent_len) | exponent;
+    let len = ((exponent_len + mantissa_len) / 8) as usize;
+    Some(packed.to_be_bytes()[16 - len..].to_vec())
+}
+
+/// Packs the token amount, returns `None` if the amount is not packable.
+pub fn pack_token_amount(amount: &BigUint) -> Option<Vec<u8>> {
+    pack(amount, AMOUNT_EXPONENT_BIT_WIDTH, AMOUNT_MANTISSA_BIT_WIDTH)
+}
+
+/// Packs the fee amount, returns `None` if the amount is not packable.
+pub fn pack_fee_amount(amount: &BigUint) -> Option<Vec<u8>> {
+    pack(amount, FEE_EXPONENT_BIT_WIDTH, FEE_MANTISSA_BIT_WIDTH)
+}
+
+/// Formats amount in wei to tokens with precision.
+/// Behaves just like ethers.utils.formatUnits
+pub fn format_units(wei: &BigUint, units: u8) -> String {
+    let mut chars: Vec<char> = wei.to_string().chars().collect();
+    let units = units as usize;
+
+    if chars.len() < units {
+        let mut padded = vec!['0'; units - chars.len()];
+        padded.append(&mut chars);
+        chars = padded;
+    }
+    chars.insert(chars.len() - units, '.');
+    if chars[0] == '.' {
+        chars.insert(0, '0');
+    }
+    while chars.last() == Some(&'0') {
+        chars.pop();
+    }
+    if chars.last() == Some(&'.') {
+        chars.push('0');
+    }
+    chars.into_iter().collect()
+}
+
+/// Message to be signed by the Ethereum key of the transactions moving the funds,
+/// see `zksync_types::utils::ethereum_sign_message_part`.
+pub fn ethereum_sign_message_part(
+    transaction: &str,
+    token_symbol: &str,
+    decimals: u8,
+    amount: &BigUint,
+    fee: &BigUint,
+    to: &str,
+) -> String {
+    let mut message = if !amount.is_zero() {
+        format!(
+            "{transaction} {amount} {token} to: {to}",
+            transaction = transaction,
+            amount = format_units(amount, decimals),
+            token = token_symbol,
+            to = to
+        )
+    } else {
+        String::new()
+    };
+    if !fee.is_zero() {
+        if !message.is_empty() {
+            message.push('\n');
+        }
+        message.push_str(&format!(
+            "Fee: {fee} {token}",
+            fee = format_units(fee, decimals),
+            token = token_symbol
+        ));
+    }
+    message
+}
+
+/// Serializes `BigUint` as a decimal string, the format of the zkSync API.
+pub mod biguint_str {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(value: &BigUint, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&value.to_str_radix(10))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<BigUint, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        BigUint::parse_bytes(value.as_bytes(), 10)
+            .ok_or_else(|| de::Error::custom(format!("invalid amount: {}", value)))
+    }
+}
@@ -22,6 +22,18 @@ export async function privateKeyFromSeed(seed: Uint8Array): Promise<Uint8Array>
     return _zks.privateKeyFromSeed(seed);
 }
 
+/**
+ * Derives the private key from the seed via the derivation path in the `m/i_1/.../i_n` format,
+ * so a single seed can be used to manage multiple zkSync accounts.
+ * The path `m` corresponds to the key returned by `privateKeyFromSeed`.
+ */
+export async function derivePrivateKey(seed: Uint8Array, path: string): Promise<Uint8Array> {
+    await loadZkSyncCrypto();
+
+    const _zks = asmJs || zks;
+    return _zks.derivePrivateKey(seed, path);
+}
+
 export async function signTransactionBytes(privKey: Uint8Array, bytes: Uint8Array): Promise<Signature> {
     await loadZkSyncCrypto();
 
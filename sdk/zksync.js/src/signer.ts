@@ -1,4 +1,4 @@
-import { privateKeyFromSeed, signTransactionBytes, privateKeyToPubKeyHash } from './crypto';
+import { derivePrivateKey, privateKeyFromSeed, signTransactionBytes, privateKeyToPubKeyHash } from './crypto';
 import { BigNumber, BigNumberish, ethers } from 'ethers';
 import * as utils from './utils';
 import {
@@ -315,6 +315,14 @@ export class Signer {
         return new Signer(await privateKeyFromSeed(seed));
     }
 
+    /**
+     * Creates the signer with the private key derived from the seed via the derivation path
+     * in the `m/i_1/.../i_n` format. The path `m` corresponds to the signer created by `fromSeed`.
+     */
+    static async fromSeedAndPath(seed: Uint8Array, path: string): Promise<Signer> {
+        return new Signer(await derivePrivateKey(seed, path));
+    }
+
     static async fromETHSignature(ethSigner: ethers.Signer): Promise<{
         signer: Signer;
         ethSignatureType: EthSignerType;
@@ -14,7 +14,7 @@ import {
     getTxHash,
     serializeTx
 } from '../src/utils';
-import { privateKeyFromSeed, signTransactionBytes } from '../src/crypto';
+import { derivePrivateKey, privateKeyFromSeed, signTransactionBytes } from '../src/crypto';
 import { loadTestVectorsConfig } from 'reading-tool';
 import { MintNFT, WithdrawNFT } from '../src/types';
 
@@ -40,6 +40,36 @@ describe('Crypto tests', function () {
     });
 });
 
+describe('Key derivation tests', function () {
+    // The keys derived by the `key_derivation` module of the `zksync_crypto` crate.
+    const seed = new Uint8Array(32).fill(1);
+    const derivedKeys = [
+        ['m', '0x0caa064a83a3e78f8469fca9b0d65b8982389e99d39c1dba2bc85a89863c8123'],
+        ['m/0/1', '0x04e50a78f4dd1a21f1da456036d37a876f3f41d69bc505c2504492b9511209f0'],
+        ['m/2147483655', '0x0c1c2cfe00d11a1bc6af2722630afee644d8a2f67a43cf73eecb4f20b10d63eb']
+    ];
+
+    it('should derive the same keys as the server crypto library', async function () {
+        for (const [path, expected] of derivedKeys) {
+            const privateKey = await derivePrivateKey(seed, path);
+            expect(utils.hexlify(privateKey), `private key of the path '${path}' does not match`).eq(expected);
+        }
+        expect(await derivePrivateKey(seed, 'm')).to.eql(await privateKeyFromSeed(seed));
+    });
+
+    it('should reject the invalid derivation paths', async function () {
+        for (const path of ['', '0/1', 'm/', 'm//1', 'm/+1', "m/1'", 'm/4294967296']) {
+            let error;
+            try {
+                await derivePrivateKey(seed, path);
+            } catch (e) {
+                error = e;
+            }
+            expect(error, `path '${path}' is accepted`).to.exist;
+        }
+    });
+});
+
 const amountPackingVectors = utilsVectors.amountPacking;
 const feePackingVectors = utilsVectors.feePacking;
 const tokenFormattingVectors = utilsVectors.tokenFormatting;